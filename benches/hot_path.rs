@@ -0,0 +1,106 @@
+//! Verify-then-settle hot path benchmarks.
+//!
+//! Run with `cargo bench --bench hot_path`.
+//!
+//! - `evm_verify_then_settle`: EIP-712 signing hash computed by both verify and settle,
+//!   recomputed each time vs memoized in a request scope.
+//! - `algorand_verify_then_settle`: replay-protection lookup by verify and check-and-mark
+//!   by settle, with formatted string keys vs binary [`NonceKey`]s.
+
+use alloy::sol_types::eip712_domain;
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use std::hint::black_box;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use x402_rs::chain::evm::{
+    compute_transfer_authorization_digest, transfer_authorization_digest, EvmChain, ExactEvmPayment,
+};
+use x402_rs::digest_cache;
+use x402_rs::network::Network;
+use x402_rs::nonce_store::{algorand_nonce_key, MemoryNonceStore, NonceKey, NonceStore};
+use x402_rs::timestamp::UnixTimestamp;
+use x402_rs::types::{EvmSignature, HexEncodedNonce, TokenAmount};
+
+fn evm_payment(nonce: u64) -> ExactEvmPayment {
+    let mut nonce_bytes = [0u8; 32];
+    nonce_bytes[24..].copy_from_slice(&nonce.to_be_bytes());
+    ExactEvmPayment {
+        chain: EvmChain::new(Network::BaseSepolia, 84532),
+        from: "0x2222222222222222222222222222222222222222".parse().unwrap(),
+        to: "0x1111111111111111111111111111111111111111".parse().unwrap(),
+        value: TokenAmount::from(1_000_000u64),
+        valid_after: UnixTimestamp(0),
+        valid_before: UnixTimestamp(u64::MAX / 2),
+        nonce: HexEncodedNonce(nonce_bytes),
+        signature: EvmSignature(vec![0u8; 65]),
+    }
+}
+
+fn evm_verify_then_settle(c: &mut Criterion) {
+    let domain = eip712_domain! {
+        name: "USDC",
+        version: "2",
+        chain_id: 84532,
+        verifying_contract: alloy::primitives::address!("0x036CbD53842c5426634e7929541eC2318f3dCF7e"),
+    };
+    let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+    let counter = AtomicU64::new(0);
+    let mut group = c.benchmark_group("evm_verify_then_settle");
+
+    group.bench_function("recomputed", |b| {
+        b.iter(|| {
+            let payment = evm_payment(counter.fetch_add(1, Ordering::Relaxed));
+            black_box(compute_transfer_authorization_digest(&payment, &domain));
+            black_box(compute_transfer_authorization_digest(&payment, &domain));
+        })
+    });
+    group.bench_function("memoized", |b| {
+        b.iter(|| {
+            // Fresh nonce per iteration so verify always misses and settle always hits
+            let payment = evm_payment(counter.fetch_add(1, Ordering::Relaxed));
+            runtime.block_on(digest_cache::request_scope(async {
+                black_box(transfer_authorization_digest(&payment, &domain));
+                black_box(transfer_authorization_digest(&payment, &domain));
+            }))
+        })
+    });
+    group.finish();
+}
+
+fn algorand_verify_then_settle(c: &mut Criterion) {
+    let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+    let group_id = [0xab; 32];
+    let mut group = c.benchmark_group("algorand_verify_then_settle");
+
+    group.bench_function("string_keys", |b| {
+        b.iter_batched(
+            MemoryNonceStore::new,
+            |store| {
+                runtime.block_on(async {
+                    let key = algorand_nonce_key("algorand", &group_id);
+                    black_box(store.is_used(&key).await.unwrap());
+                    let key = algorand_nonce_key("algorand", &group_id);
+                    store.check_and_mark_used(&key, 3600).await.unwrap();
+                })
+            },
+            BatchSize::SmallInput,
+        )
+    });
+    group.bench_function("binary_keys", |b| {
+        b.iter_batched(
+            MemoryNonceStore::new,
+            |store| {
+                runtime.block_on(async {
+                    let key = NonceKey::algorand_group("algorand", &group_id);
+                    black_box(store.is_key_used(&key).await.unwrap());
+                    store.check_and_mark_key(&key, 3600).await.unwrap();
+                })
+            },
+            BatchSize::SmallInput,
+        )
+    });
+    group.finish();
+}
+
+criterion_group!(benches, evm_verify_then_settle, algorand_verify_then_settle);
+criterion_main!(benches);
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * CAIP-2 compliant network identifier.
+ *
+ * Represents a blockchain network using the format `{namespace}:{reference}`.
+ */
+export type Caip2NetworkId = string;
@@ -0,0 +1,32 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ResourceLiveness } from "./ResourceLiveness";
+import type { JsonValue } from "./serde_json/JsonValue";
+
+/**
+ * Metadata for a discoverable resource in the Bazaar registry.
+ */
+export type DiscoveryMetadata = { 
+/**
+ * Category for filtering (e.g., "finance", "ai", "data")
+ */
+category?: string, 
+/**
+ * Provider name or organization
+ */
+provider?: string, 
+/**
+ * Tags for search and discovery
+ */
+tags?: Array<string>, 
+/**
+ * Facilitators listing this resource, when aggregated from more than one
+ */
+sources?: Array<string>, 
+/**
+ * Latest liveness checks, recorded by the discovery health monitor
+ */
+liveness?: ResourceLiveness, 
+/**
+ * Fields of an aggregated listing that have no counterpart here, kept verbatim
+ */
+extra?: { [key in string]: JsonValue }, };
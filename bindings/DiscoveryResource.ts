@@ -0,0 +1,70 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DiscoveryMetadata } from "./DiscoveryMetadata";
+import type { DiscoverySource } from "./DiscoverySource";
+import type { PaymentRequirementsV2 } from "./PaymentRequirementsV2";
+
+/**
+ * A discoverable paid resource in the Bazaar registry.
+ *
+ * Represents an API endpoint or service that accepts x402 payments.
+ *
+ * # Source Tracking (Meta-Bazaar)
+ *
+ * Resources can come from multiple sources:
+ * - `SelfRegistered`: Explicit POST /discovery/register
+ * - `Settlement`: Auto-registered via /settle with discoverable=true
+ * - `Crawled`: Discovered from /.well-known/x402 endpoints
+ * - `Aggregated`: Pulled from another facilitator's Bazaar
+ *
+ * The `source` and `source_facilitator` fields enable filtering and attribution.
+ */
+export type DiscoveryResource = { 
+/**
+ * The URL of the paid resource
+ */
+url: string, 
+/**
+ * Type of resource ("http", "mcp", "a2a")
+ */
+type: string, 
+/**
+ * x402 protocol version this resource supports
+ */
+x402Version: number, 
+/**
+ * Human-readable description of the resource
+ */
+description: string, 
+/**
+ * Accepted payment methods
+ */
+accepts: Array<PaymentRequirementsV2>, 
+/**
+ * Unix timestamp of last registration/update
+ */
+lastUpdated: number, 
+/**
+ * Optional metadata for categorization and search
+ */
+metadata?: DiscoveryMetadata | null, 
+/**
+ * How this resource was discovered/registered
+ */
+source: DiscoverySource, 
+/**
+ * Origin facilitator for aggregated resources (e.g., "coinbase", "ultravioleta")
+ */
+sourceFacilitator?: string | null, 
+/**
+ * Unix timestamp when we first discovered this resource
+ */
+firstSeen?: number | null, 
+/**
+ * Number of settlements observed (for Settlement source)
+ */
+settlementCount?: number | null, 
+/**
+ * Whether the resource answered its latest health checks; unhealthy resources are
+ * hidden from listings until they recover
+ */
+isHealthy: boolean, };
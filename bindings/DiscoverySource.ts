@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * How a resource was discovered and added to the Bazaar registry.
+ *
+ * This enables the "Meta-Bazaar" architecture where resources can come from
+ * multiple sources: self-registration, settlement tracking, crawling, or
+ * aggregation from other facilitators.
+ */
+export type DiscoverySource = "self_registered" | "settlement" | "crawled" | "aggregated";
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A simple error structure returned on unexpected or fatal server errors.
+ * Used when no structured protocol-level response is appropriate.
+ */
+export type ErrorResponse = { error: string, };
@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Represents an EVM address.
+ *
+ * Wrapper around `alloy::primitives::Address`, providing display/serialization support.
+ * Used throughout the protocol for typed Ethereum address handling.
+ */
+export type EvmAddress = string;
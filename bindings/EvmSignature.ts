@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Represents an EVM signature used in EIP-712 typed data.
+ * Serialized as 0x-prefixed hex string.
+ * Used to authorize an ERC-3009 transferWithAuthorization.
+ * Can contain EOA, EIP-1271, and EIP-6492 signatures.
+ */
+export type EvmSignature = string;
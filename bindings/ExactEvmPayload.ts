@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { EvmSignature } from "./EvmSignature";
+import type { ExactEvmPayloadAuthorization } from "./ExactEvmPayloadAuthorization";
+
+/**
+ * Full payload required to authorize an ERC-3009 transfer:
+ * includes the signature and the EIP-712 struct.
+ */
+export type ExactEvmPayload = { signature: EvmSignature, authorization: ExactEvmPayloadAuthorization, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { EvmAddress } from "./EvmAddress";
+import type { HexEncodedNonce } from "./HexEncodedNonce";
+import type { TokenAmount } from "./TokenAmount";
+import type { UnixTimestamp } from "./UnixTimestamp";
+
+/**
+ * EIP-712 structured data for ERC-3009-based authorization.
+ * Defines who can transfer how much USDC and when.
+ */
+export type ExactEvmPayloadAuthorization = { from: EvmAddress, to: EvmAddress, value: TokenAmount, validAfter: UnixTimestamp, validBefore: UnixTimestamp, nonce: HexEncodedNonce, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * NEAR payment payload containing a base64-encoded SignedDelegateAction (NEP-366).
+ */
+export type ExactNearPayload = { 
+/**
+ * Base64-encoded borsh-serialized SignedDelegateAction
+ */
+signedDelegateAction: string, };
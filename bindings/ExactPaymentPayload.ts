@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ExactEvmPayload } from "./ExactEvmPayload";
+import type { ExactNearPayload } from "./ExactNearPayload";
+import type { ExactSolanaPayload } from "./ExactSolanaPayload";
+import type { ExactStellarPayload } from "./ExactStellarPayload";
+
+export type ExactPaymentPayload = ExactEvmPayload | ExactSolanaPayload | ExactNearPayload | ExactStellarPayload;
@@ -0,0 +1,34 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Payload for Stellar/Soroban payments using pre-signed authorization entries.
+ */
+export type ExactStellarPayload = { 
+/**
+ * Stellar account paying (G... address)
+ */
+from: string, 
+/**
+ * Stellar account receiving payment (G... address)
+ */
+to: string, 
+/**
+ * Payment amount in stroops (1 USDC = 10^7 stroops for Stellar)
+ */
+amount: string, 
+/**
+ * Token contract address (C... for USDC SAC contract)
+ */
+tokenContract: string, 
+/**
+ * Pre-authorized invocation signature (base64-encoded XDR)
+ */
+authorizationEntryXdr: string, 
+/**
+ * Client-provided nonce (for replay protection)
+ */
+nonce: number, 
+/**
+ * Signature expiration ledger number
+ */
+signatureExpirationLedger: number, };
@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { FeedbackFileEntry } from "./FeedbackFileEntry";
+
+/**
+ * Off-chain feedback file structure (for feedbackURI)
+ */
+export type FeedbackFile = { 
+/**
+ * Type identifier
+ */
+type: string, 
+/**
+ * Feedback entries
+ */
+feedback: Array<FeedbackFileEntry>, };
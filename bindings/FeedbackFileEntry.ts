@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { PaymentInfo } from "./PaymentInfo";
+
+/**
+ * Individual entry in a feedback file
+ */
+export type FeedbackFileEntry = { timestamp: number, result: string, value: number, valueDecimals: number, paymentInfo?: PaymentInfo, interactions: Array<string>, };
@@ -0,0 +1,44 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ProofOfPayment } from "./ProofOfPayment";
+
+/**
+ * Parameters for submitting reputation feedback (matches official spec).
+ */
+export type FeedbackParams = { 
+/**
+ * The agent's ID (tokenId in Identity Registry)
+ */
+agentId: number, 
+/**
+ * Feedback value (fixed-point)
+ * Examples: 87 with decimals=0 means 87/100, 9977 with decimals=2 means 99.77%
+ */
+value: number, 
+/**
+ * Decimal places for value interpretation (0-18)
+ */
+valueDecimals: number, 
+/**
+ * Primary categorization tag (e.g., "starred", "uptime", "responseTime")
+ */
+tag1: string, 
+/**
+ * Secondary categorization tag
+ */
+tag2: string, 
+/**
+ * Service endpoint that was used (optional)
+ */
+endpoint: string, 
+/**
+ * URI to off-chain feedback file (IPFS, HTTPS)
+ */
+feedbackUri: string, 
+/**
+ * Keccak256 hash of feedback content (for integrity)
+ */
+feedbackHash: string | null, 
+/**
+ * Proof of payment (required for authorized feedback)
+ */
+proof?: ProofOfPayment, };
@@ -0,0 +1,27 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { FeedbackFile } from "./FeedbackFile";
+import type { FeedbackParams } from "./FeedbackParams";
+import type { Network } from "./Network";
+import type { X402Version } from "./X402Version";
+
+/**
+ * Request body for POST /feedback endpoint.
+ */
+export type FeedbackRequest = { 
+/**
+ * x402 protocol version
+ */
+x402Version: X402Version, 
+/**
+ * Network where feedback will be submitted
+ */
+network: Network, 
+/**
+ * Feedback parameters
+ */
+feedback: FeedbackParams, 
+/**
+ * Feedback file for the facilitator to store; its URI and hash become the
+ * feedback's `feedbackUri` and `feedbackHash`
+ */
+feedbackFile?: FeedbackFile, };
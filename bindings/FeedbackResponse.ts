@@ -0,0 +1,28 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Network } from "./Network";
+import type { TransactionHash } from "./TransactionHash";
+
+/**
+ * Response from POST /feedback endpoint.
+ */
+export type FeedbackResponse = { 
+/**
+ * Whether the feedback was successfully submitted
+ */
+success: boolean, 
+/**
+ * Transaction hash of the feedback submission
+ */
+transaction?: TransactionHash, 
+/**
+ * Feedback index assigned (1-indexed)
+ */
+feedbackIndex?: number, 
+/**
+ * Error message (if failed)
+ */
+error?: string, 
+/**
+ * Network where feedback was submitted
+ */
+network: Network, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Represents a 32-byte random nonce, hex-encoded with 0x prefix.
+ * Must be exactly 64 hex characters long.
+ */
+export type HexEncodedNonce = string;
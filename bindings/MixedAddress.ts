@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Represents either an EVM address (0x...), or an off-chain address, Solana address, or NEAR account.
+ * The format is used for routing settlement.
+ */
+export type MixedAddress = string;
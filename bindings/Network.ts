@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Supported Ethereum-compatible networks.
+ *
+ * Used to differentiate between testnet and mainnet environments for the x402 protocol.
+ */
+export type Network = "base-sepolia" | "base" | "xdc" | "avalanche-fuji" | "avalanche" | "xrpl-evm" | "solana" | "solana-devnet" | "polygon-amoy" | "polygon" | "optimism" | "optimism-sepolia" | "celo" | "celo-sepolia" | "hyperevm" | "hyperevm-testnet" | "sei" | "sei-testnet" | "ethereum" | "ethereum-sepolia" | "arbitrum" | "arbitrum-sepolia" | "unichain" | "unichain-sepolia" | "monad" | "bsc" | "near" | "near-testnet" | "stellar" | "stellar-testnet" | "fogo" | "fogo-testnet" | "skale-base" | "skale-base-sepolia" | "scroll" | "scroll-sepolia" | "zksync-era" | "zksync-era-sepolia";
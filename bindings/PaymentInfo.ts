@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Payment info in feedback file
+ */
+export type PaymentInfo = { token: string, amount: string, reason: string, };
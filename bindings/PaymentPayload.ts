@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ExactPaymentPayload } from "./ExactPaymentPayload";
+import type { Network } from "./Network";
+import type { Scheme } from "./Scheme";
+import type { X402Version } from "./X402Version";
+
+/**
+ * Describes a signed request to transfer a specific amount of funds on-chain.
+ * Includes the scheme, network, and signed payload contents.
+ */
+export type PaymentPayload = { x402Version: X402Version, scheme: Scheme, network: Network, payload: ExactPaymentPayload, };
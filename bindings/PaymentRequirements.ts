@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { MixedAddress } from "./MixedAddress";
+import type { Network } from "./Network";
+import type { Scheme } from "./Scheme";
+import type { TokenAmount } from "./TokenAmount";
+import type { JsonValue } from "./serde_json/JsonValue";
+
+/**
+ * Requirements set by the payment-gated endpoint for an acceptable payment.
+ * This includes min/max amounts, recipient, asset, network, and metadata.
+ */
+export type PaymentRequirements = { scheme: Scheme, network: Network, maxAmountRequired: TokenAmount, resource: string, description: string, mimeType: string, outputSchema?: JsonValue, payTo: MixedAddress, maxTimeoutSeconds: number, asset: MixedAddress, extra: JsonValue | null, };
@@ -0,0 +1,46 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Caip2NetworkId } from "./Caip2NetworkId";
+import type { MixedAddress } from "./MixedAddress";
+import type { Scheme } from "./Scheme";
+import type { TokenAmount } from "./TokenAmount";
+import type { JsonValue } from "./serde_json/JsonValue";
+
+/**
+ * Payment requirements for x402 v2.
+ *
+ * Simplified from v1 - resource metadata moved to ResourceInfo at top level.
+ *
+ * # Breaking Changes from v1
+ * - `resource`, `description`, `mime_type`, `output_schema` removed (moved to ResourceInfo)
+ * - `max_amount_required` renamed to `amount`
+ * - `network` now uses CAIP-2 format
+ */
+export type PaymentRequirementsV2 = { 
+/**
+ * Payment scheme (currently only "exact")
+ */
+scheme: Scheme, 
+/**
+ * Network in CAIP-2 format (e.g., "eip155:8453", "solana:5eykt...")
+ */
+network: Caip2NetworkId, 
+/**
+ * Token contract address or account
+ */
+asset: MixedAddress, 
+/**
+ * Exact amount required (renamed from maxAmountRequired)
+ */
+amount: TokenAmount, 
+/**
+ * Recipient address for payment
+ */
+payTo: MixedAddress, 
+/**
+ * Maximum seconds before payment expires
+ */
+maxTimeoutSeconds: number, 
+/**
+ * Optional chain-specific or application-specific data
+ */
+extra?: JsonValue | null, };
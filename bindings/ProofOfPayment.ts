@@ -0,0 +1,56 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { MixedAddress } from "./MixedAddress";
+import type { Network } from "./Network";
+import type { TokenAmount } from "./TokenAmount";
+import type { TransactionHash } from "./TransactionHash";
+
+/**
+ * Cryptographic proof of a settled payment for reputation submission.
+ */
+export type ProofOfPayment = { 
+/**
+ * Transaction hash of the settled payment
+ */
+transactionHash: TransactionHash, 
+/**
+ * Block number where the transaction was included
+ */
+blockNumber: number, 
+/**
+ * Network where the payment was settled
+ */
+network: Network, 
+/**
+ * The payer (consumer/client) address
+ */
+payer: MixedAddress, 
+/**
+ * The payee (agent/resource owner) address
+ */
+payee: MixedAddress, 
+/**
+ * Amount paid in token base units
+ */
+amount: TokenAmount, 
+/**
+ * Token contract address
+ */
+token: MixedAddress, 
+/**
+ * Unix timestamp of the block, when the block could be fetched at settlement
+ */
+timestamp?: number, 
+/**
+ * Version of the `payment_hash` computation; proofs without one use version 0,
+ * which zero-pads non-EVM transaction hashes
+ */
+version: number, 
+/**
+ * Keccak256 hash of the payment data for verification
+ */
+paymentHash: string, 
+/**
+ * EIP-191 signature of `payment_hash` by the settling facilitator, so the proof can
+ * be checked without chain access; not part of `payment_hash`
+ */
+facilitatorSignature?: string, };
@@ -0,0 +1,26 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Outcome of the latest liveness checks of a resource.
+ */
+export type ResourceLiveness = { 
+/**
+ * Whether the resource is listed; false after too many consecutive failed checks
+ */
+healthy: boolean, 
+/**
+ * Unix timestamp of the latest check
+ */
+lastChecked: number, 
+/**
+ * Failed checks since the last successful one
+ */
+consecutiveFailures: number, 
+/**
+ * HTTP status of the latest check, absent when no response was received
+ */
+lastStatus?: number, 
+/**
+ * Milliseconds the latest check took
+ */
+latencyMs: number, };
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Enumerates payment schemes. Only "exact" is settled by this implementation,
+ * meaning the amount to be transferred must match exactly; "upto" is recognized
+ * so that usage-priced resources can be listed in discovery.
+ */
+export type Scheme = "exact" | "upto" | "fhe-transfer";
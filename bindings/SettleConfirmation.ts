@@ -0,0 +1,20 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Where and when a settlement transaction was confirmed, for accounting.
+ *
+ * Block numbers are rounds on Algorand; `gas_used` is only known on EVM chains.
+ */
+export type SettleConfirmation = { 
+/**
+ * Block (or round) including the transaction
+ */
+blockNumber: number, 
+/**
+ * Unix timestamp of that block, when the block could be fetched
+ */
+blockTimestamp?: number, 
+/**
+ * Gas used by the transaction
+ */
+gasUsed?: number, };
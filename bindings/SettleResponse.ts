@@ -0,0 +1,25 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { FacilitatorErrorReason } from "./FacilitatorErrorReason";
+import type { MixedAddress } from "./MixedAddress";
+import type { Network } from "./Network";
+import type { ProofOfPayment } from "./ProofOfPayment";
+import type { SettleConfirmation } from "./SettleConfirmation";
+import type { TransactionHash } from "./TransactionHash";
+
+/**
+ * Returned from a facilitator after attempting to settle a payment on-chain.
+ * Indicates success/failure, transaction hash, and payer identity.
+ *
+ * When the `8004-reputation` extension is active in PaymentRequirements.extra,
+ * the response includes a `proof_of_payment` field containing cryptographic proof
+ * that can be used to submit reputation feedback on-chain.
+ */
+export type SettleResponse = { success: boolean, errorReason?: FacilitatorErrorReason, payer: MixedAddress, transaction?: TransactionHash, network: Network, 
+/**
+ * ERC-8004 proof of payment (included when `8004-reputation` extension is active)
+ */
+proofOfPayment?: ProofOfPayment, 
+/**
+ * On-chain confirmation of `transaction`, on chains that report one
+ */
+confirmation?: SettleConfirmation, };
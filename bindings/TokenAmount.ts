@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A precise on-chain token amount in base units (e.g., USDC with 6 decimals).
+ * Represented as a stringified `U256` in JSON to prevent precision loss.
+ */
+export type TokenAmount = string;
@@ -0,0 +1,13 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A Unix timestamp represented as a `u64`, used in payment authorization windows.
+ *
+ * This type encodes the number of seconds since the Unix epoch (1970-01-01T00:00:00Z).
+ * It is used in time-bounded ERC-3009 `transferWithAuthorization` messages to specify
+ * the validity window (`validAfter` and `validBefore`) of a payment authorization.
+ *
+ * Serialized as a stringified integer to avoid loss of precision in JSON.
+ * For example, `1699999999` becomes `"1699999999"` in the wire format.
+ */
+export type UnixTimestamp = string;
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { PaymentPayload } from "./PaymentPayload";
+import type { PaymentRequirements } from "./PaymentRequirements";
+import type { X402Version } from "./X402Version";
+
+/**
+ * Wrapper for a payment payload and requirements sent by the client to a facilitator
+ * to be verified.
+ */
+export type VerifyRequest = { x402Version: X402Version, paymentPayload: PaymentPayload, paymentRequirements: PaymentRequirements, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { FacilitatorErrorReason } from "./FacilitatorErrorReason";
+import type { MixedAddress } from "./MixedAddress";
+
+/**
+ * Result returned by a facilitator after verifying a [`PaymentPayload`] against the provided [`PaymentRequirements`].
+ *
+ * This response indicates whether the payment authorization is valid and identifies the payer. If invalid,
+ * it includes a reason describing why verification failed (e.g., wrong network, an invalid scheme, insufficient funds).
+ */
+export type VerifyResponse = { isValid: true, payer: MixedAddress, } | { isValid: false, invalidReason: FacilitatorErrorReason, payer?: MixedAddress, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Represents the protocol version. Supports both v1 and v2 of the x402 protocol.
+ *
+ * # Protocol Versions
+ * - **V1**: Original x402 protocol with network enum names (e.g., "base", "solana")
+ * - **V2**: Updated protocol using CAIP-2 network identifiers (e.g., "eip155:8453")
+ */
+export type X402Version = 1 | 2;
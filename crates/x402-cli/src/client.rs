@@ -0,0 +1,118 @@
+//! Thin HTTP client for the facilitator endpoints exercised by the CLI.
+//!
+//! Responses are returned as raw JSON together with their HTTP status, so that
+//! error bodies from the facilitator can be printed verbatim for debugging.
+
+use reqwest::StatusCode;
+use serde::Serialize;
+use serde_json::Value;
+use url::Url;
+use x402_rs::types::{SettleRequest, VerifyRequest};
+
+use crate::error::CliError;
+
+/// A response from the facilitator: HTTP status plus the decoded JSON body.
+#[derive(Debug)]
+pub struct ApiResponse {
+    pub status: StatusCode,
+    pub body: Value,
+}
+
+/// Query string for `GET /discovery/resources`.
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListParams {
+    pub limit: u32,
+    pub offset: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub network: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_facilitator: Option<String>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub include_unhealthy: bool,
+}
+
+/// Query string for `GET /discovery/search`.
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchParams {
+    pub q: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub network: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_price: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_facilitator: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sort: Option<String>,
+    pub page: u32,
+    pub page_size: u32,
+}
+
+/// Client bound to a single facilitator base URL.
+#[derive(Debug, Clone)]
+pub struct FacilitatorApi {
+    base_url: Url,
+    client: reqwest::Client,
+}
+
+impl FacilitatorApi {
+    /// Creates a client for the facilitator at `base_url`.
+    pub fn new(base_url: &str) -> Result<Self, CliError> {
+        let mut base_url =
+            Url::parse(base_url).map_err(|e| CliError::InvalidUrl(base_url.to_string(), e))?;
+        // Without a trailing slash `Url::join` would drop the last path segment
+        if !base_url.path().ends_with('/') {
+            base_url.set_path(&format!("{}/", base_url.path()));
+        }
+        Ok(Self {
+            base_url,
+            client: reqwest::Client::new(),
+        })
+    }
+
+    /// `POST /verify`
+    pub async fn verify(&self, request: &VerifyRequest) -> Result<ApiResponse, CliError> {
+        let url = self.endpoint("verify")?;
+        self.send(self.client.post(url).json(request)).await
+    }
+
+    /// `POST /settle`
+    pub async fn settle(&self, request: &SettleRequest) -> Result<ApiResponse, CliError> {
+        let url = self.endpoint("settle")?;
+        self.send(self.client.post(url).json(request)).await
+    }
+
+    /// `GET /discovery/resources`
+    pub async fn discovery_list(&self, params: &ListParams) -> Result<ApiResponse, CliError> {
+        let url = self.endpoint("discovery/resources")?;
+        self.send(self.client.get(url).query(params)).await
+    }
+
+    /// `GET /discovery/search`
+    pub async fn discovery_search(&self, params: &SearchParams) -> Result<ApiResponse, CliError> {
+        let url = self.endpoint("discovery/search")?;
+        self.send(self.client.get(url).query(params)).await
+    }
+
+    fn endpoint(&self, path: &str) -> Result<Url, CliError> {
+        self.base_url
+            .join(path)
+            .map_err(|e| CliError::InvalidUrl(format!("{}{path}", self.base_url), e))
+    }
+
+    async fn send(&self, request: reqwest::RequestBuilder) -> Result<ApiResponse, CliError> {
+        let response = request.send().await?;
+        let status = response.status();
+        let text = response.text().await?;
+        // Non-JSON bodies (e.g. a proxy error page) are still worth showing
+        let body = serde_json::from_str(&text).unwrap_or(Value::String(text));
+        Ok(ApiResponse { status, body })
+    }
+}
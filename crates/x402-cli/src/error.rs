@@ -0,0 +1,40 @@
+use std::path::PathBuf;
+
+use x402_rs::network::{Network, NetworkParseError};
+use x402_rs::nonce_store::NonceStoreError;
+use x402_rs::types::PaymentPayloadB64DecodingError;
+
+/// Errors reported by the CLI before or while talking to a facilitator.
+#[derive(Debug, thiserror::Error)]
+pub enum CliError {
+    #[error("Invalid facilitator URL {0}: {1}")]
+    InvalidUrl(String, url::ParseError),
+    #[error(transparent)]
+    Network(#[from] NetworkParseError),
+    #[error("Invalid payment header: {0}")]
+    PaymentHeader(#[from] PaymentPayloadB64DecodingError),
+    #[error("Failed to read payment requirements from {path}: {source}")]
+    RequirementsRead {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("Invalid payment requirements in {path}: {source}")]
+    RequirementsParse {
+        path: PathBuf,
+        source: serde_json::Error,
+    },
+    #[error("{what} is for network {actual}, expected {expected}")]
+    NetworkMismatch {
+        what: &'static str,
+        expected: Network,
+        actual: Network,
+    },
+    #[error("Unknown nonce chain {0:?}, expected one of: stellar, stellar-testnet, algorand, algorand-testnet")]
+    UnknownChain(String),
+    #[error("Nonce store error: {0}")]
+    NonceStore(#[from] NonceStoreError),
+    #[error("HTTP request failed: {0}")]
+    Http(#[from] reqwest::Error),
+    #[error("Failed to encode output: {0}")]
+    Output(#[from] serde_json::Error),
+}
@@ -0,0 +1,300 @@
+//! `x402-cli`: a command-line client for manually testing and debugging an x402 facilitator.
+//!
+//! Every subcommand prints the facilitator's JSON response, pretty-printed, to stdout
+//! and exits non-zero when the facilitator answers with a non-success status.
+//!
+//! ```text
+//! x402-cli verify --payment-header <BASE64> --network base-sepolia --requirements req.json
+//! x402-cli settle --payment-header <BASE64> --network base-sepolia --requirements req.json
+//! x402-cli discovery list --facilitator https://facilitator.example
+//! x402-cli discovery search --query weather
+//! x402-cli nonce check --chain stellar --key <ADDRESS>#<NONCE>
+//! x402-cli completions zsh > _x402-cli
+//! ```
+//!
+//! The facilitator URL is taken from `--facilitator` or `X402_FACILITATOR_URL`.
+
+mod client;
+mod error;
+
+use clap::{Args, CommandFactory, Parser, Subcommand};
+use clap_complete::Shell;
+use serde_json::json;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use x402_rs::network::Network;
+use x402_rs::nonce_store::{self, NonceChain};
+use x402_rs::types::{Base64Bytes, PaymentPayload, PaymentRequirements, VerifyRequest};
+
+use crate::client::{ApiResponse, FacilitatorApi, ListParams, SearchParams};
+use crate::error::CliError;
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "x402-cli",
+    version,
+    about = "Manual testing and debugging for x402 facilitators"
+)]
+struct Cli {
+    /// Base URL of the facilitator
+    #[arg(
+        long,
+        global = true,
+        env = "X402_FACILITATOR_URL",
+        default_value = "http://localhost:8080"
+    )]
+    facilitator: String,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Verify a payment with `POST /verify`
+    Verify(PaymentArgs),
+    /// Settle a payment with `POST /settle`
+    Settle(PaymentArgs),
+    /// Query the Bazaar discovery registry
+    #[command(subcommand)]
+    Discovery(DiscoveryCommand),
+    /// Inspect the replay-protection nonce store
+    #[command(subcommand)]
+    Nonce(NonceCommand),
+    /// Print a shell completion script to stdout
+    Completions {
+        /// Shell to generate completions for
+        shell: Shell,
+    },
+}
+
+#[derive(Debug, Args)]
+struct PaymentArgs {
+    /// Base64-encoded payment payload, as sent in the `X-PAYMENT` header
+    #[arg(long)]
+    payment_header: String,
+    /// Network the payment is expected on (e.g. `base-sepolia`)
+    #[arg(long)]
+    network: String,
+    /// JSON file with the payment requirements the payload is checked against
+    #[arg(long)]
+    requirements: PathBuf,
+}
+
+#[derive(Debug, Subcommand)]
+enum DiscoveryCommand {
+    /// List resources with `GET /discovery/resources`
+    List {
+        /// Maximum number of resources to return
+        #[arg(long, default_value_t = 10)]
+        limit: u32,
+        /// Number of resources to skip
+        #[arg(long, default_value_t = 0)]
+        offset: u32,
+        /// Filter by category
+        #[arg(long)]
+        category: Option<String>,
+        /// Filter by network (CAIP-2, e.g. `eip155:8453`)
+        #[arg(long)]
+        network: Option<String>,
+        /// Filter by source facilitator (e.g. `coinbase`)
+        #[arg(long)]
+        source_facilitator: Option<String>,
+        /// Also list resources hidden after failing health checks
+        #[arg(long)]
+        include_unhealthy: bool,
+    },
+    /// Search resources with `GET /discovery/search`
+    Search {
+        /// Free-text query matched against descriptions and URLs
+        #[arg(long)]
+        query: String,
+        /// Filter by network (CAIP-2, e.g. `eip155:8453`)
+        #[arg(long)]
+        network: Option<String>,
+        /// Maximum price in token base units
+        #[arg(long)]
+        max_price: Option<String>,
+        /// Filter by category
+        #[arg(long)]
+        category: Option<String>,
+        /// Comma-separated tags, all of which must be present
+        #[arg(long)]
+        tags: Option<String>,
+        /// Filter by source facilitator (e.g. `coinbase`)
+        #[arg(long)]
+        source_facilitator: Option<String>,
+        /// Result order: `newest` or `relevance`
+        #[arg(long)]
+        sort: Option<String>,
+        /// 1-based page number
+        #[arg(long, default_value_t = 1)]
+        page: u32,
+        /// Results per page
+        #[arg(long, default_value_t = 10)]
+        page_size: u32,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum NonceCommand {
+    /// Check whether a nonce has already been used
+    ///
+    /// Reads the nonce store configured through the facilitator's environment
+    /// (`NONCE_STORE_*`, `REDIS_URL`, `DATABASE_URL`, ...), not over HTTP.
+    Check {
+        /// Chain the nonce belongs to: stellar, stellar-testnet, algorand or algorand-testnet
+        #[arg(long)]
+        chain: String,
+        /// Nonce key without the chain prefix, e.g. `<ADDRESS>#<NONCE>` or `group#<HEX>`
+        #[arg(long)]
+        key: String,
+    },
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+    match run(cli).await {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::from(2)
+        }
+    }
+}
+
+async fn run(cli: Cli) -> Result<ExitCode, CliError> {
+    let response = match cli.command {
+        Command::Verify(args) => {
+            let request = payment_request(&args)?;
+            FacilitatorApi::new(&cli.facilitator)?
+                .verify(&request)
+                .await?
+        }
+        Command::Settle(args) => {
+            let request = payment_request(&args)?;
+            FacilitatorApi::new(&cli.facilitator)?
+                .settle(&request)
+                .await?
+        }
+        Command::Discovery(DiscoveryCommand::List {
+            limit,
+            offset,
+            category,
+            network,
+            source_facilitator,
+            include_unhealthy,
+        }) => {
+            let params = ListParams {
+                limit,
+                offset,
+                category,
+                network,
+                source_facilitator,
+                include_unhealthy,
+            };
+            FacilitatorApi::new(&cli.facilitator)?
+                .discovery_list(&params)
+                .await?
+        }
+        Command::Discovery(DiscoveryCommand::Search {
+            query,
+            network,
+            max_price,
+            category,
+            tags,
+            source_facilitator,
+            sort,
+            page,
+            page_size,
+        }) => {
+            let params = SearchParams {
+                q: query,
+                network,
+                max_price,
+                category,
+                tags,
+                source_facilitator,
+                sort,
+                page,
+                page_size,
+            };
+            FacilitatorApi::new(&cli.facilitator)?
+                .discovery_search(&params)
+                .await?
+        }
+        Command::Nonce(NonceCommand::Check { chain, key }) => {
+            let chain = NonceChain::from_name(&chain).ok_or(CliError::UnknownChain(chain))?;
+            let store = nonce_store::create_nonce_store().await;
+            let used = store.is_used(&format!("{}#{key}", chain.as_str())).await?;
+            print_json(&json!({ "chain": chain.as_str(), "key": key, "used": used }))?;
+            return Ok(ExitCode::SUCCESS);
+        }
+        Command::Completions { shell } => {
+            let mut command = Cli::command();
+            clap_complete::generate(shell, &mut command, "x402-cli", &mut std::io::stdout());
+            return Ok(ExitCode::SUCCESS);
+        }
+    };
+    report(response)
+}
+
+/// Builds a verify/settle request from the header and requirements file,
+/// refusing to send anything if either targets a different network than `--network`.
+fn payment_request(args: &PaymentArgs) -> Result<VerifyRequest, CliError> {
+    let network: Network = args.network.parse()?;
+    let payment_payload =
+        PaymentPayload::try_from(Base64Bytes::from(args.payment_header.trim().as_bytes()))?;
+    let payment_requirements = read_requirements(&args.requirements)?;
+    check_network("Payment payload", network, payment_payload.network)?;
+    check_network(
+        "Payment requirements",
+        network,
+        payment_requirements.network,
+    )?;
+    Ok(VerifyRequest {
+        x402_version: payment_payload.x402_version,
+        payment_payload,
+        payment_requirements,
+    })
+}
+
+fn read_requirements(path: &Path) -> Result<PaymentRequirements, CliError> {
+    let raw = std::fs::read_to_string(path).map_err(|source| CliError::RequirementsRead {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    serde_json::from_str(&raw).map_err(|source| CliError::RequirementsParse {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+fn check_network(what: &'static str, expected: Network, actual: Network) -> Result<(), CliError> {
+    if expected == actual {
+        Ok(())
+    } else {
+        Err(CliError::NetworkMismatch {
+            what,
+            expected,
+            actual,
+        })
+    }
+}
+
+/// Prints the response body and maps a non-success HTTP status to exit code 1.
+fn report(response: ApiResponse) -> Result<ExitCode, CliError> {
+    print_json(&response.body)?;
+    if response.status.is_success() {
+        Ok(ExitCode::SUCCESS)
+    } else {
+        eprintln!("facilitator responded with {}", response.status);
+        Ok(ExitCode::FAILURE)
+    }
+}
+
+fn print_json(value: &serde_json::Value) -> Result<(), CliError> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
@@ -0,0 +1,241 @@
+//! Runs the `x402-cli` binary against an in-process facilitator.
+//!
+//! No RPC providers are configured, so `verify`/`settle` are expected to come back
+//! as facilitator errors; the point is that the request reaches the facilitator and
+//! its JSON answer is printed.
+
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::process::Output;
+use std::sync::Arc;
+
+use axum::Router;
+use serde_json::{json, Value};
+use tokio::net::TcpListener;
+use tokio::process::Command;
+use url::Url;
+use x402_compliance::ComplianceCheckerBuilder;
+use x402_rs::discovery::DiscoveryRegistry;
+use x402_rs::facilitator_local::FacilitatorLocal;
+use x402_rs::handlers;
+use x402_rs::nonce_store::MemoryNonceStore;
+use x402_rs::provider_cache::ProviderCache;
+use x402_rs::types::Base64Bytes;
+use x402_rs::types_v2::DiscoveryResource;
+
+async fn start_facilitator() -> SocketAddr {
+    let nonce_store = Arc::new(MemoryNonceStore::new());
+    let provider_cache = ProviderCache::from_env(nonce_store).await.unwrap();
+    let compliance_checker = ComplianceCheckerBuilder::new()
+        .with_ofac(false)
+        .build()
+        .await
+        .unwrap();
+    let facilitator = FacilitatorLocal::new(provider_cache, Arc::new(compliance_checker));
+
+    let registry = DiscoveryRegistry::new();
+    registry
+        .register(DiscoveryResource::new(
+            Url::parse("https://weather.example.com/").unwrap(),
+            "facilitator".to_string(),
+            "Weather forecast facilitator".to_string(),
+            Vec::new(),
+        ))
+        .await
+        .unwrap();
+
+    let app = Router::new()
+        .merge(handlers::routes().with_state(Arc::new(facilitator)))
+        .merge(handlers::discovery_routes().with_state(Arc::new(registry)));
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    addr
+}
+
+async fn cli(addr: SocketAddr, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_x402-cli"))
+        .env("X402_FACILITATOR_URL", format!("http://{addr}"))
+        .args(args)
+        .output()
+        .await
+        .unwrap()
+}
+
+fn stdout_json(output: &Output) -> Value {
+    serde_json::from_slice(&output.stdout).unwrap_or_else(|e| {
+        panic!(
+            "stdout is not JSON ({e}): {}\nstderr: {}",
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr)
+        )
+    })
+}
+
+fn payment_header() -> String {
+    let payload = json!({
+        "x402Version": 1,
+        "scheme": "exact",
+        "network": "base-sepolia",
+        "payload": {
+            "signature": format!("0x{}", "11".repeat(65)),
+            "authorization": {
+                "from": "0x2222222222222222222222222222222222222222",
+                "to": "0x1111111111111111111111111111111111111111",
+                "value": "1000",
+                "validAfter": "0",
+                "validBefore": "9999999999",
+                "nonce": format!("0x{}", "00".repeat(32))
+            }
+        }
+    });
+    let encoded = Base64Bytes::encode(serde_json::to_vec(&payload).unwrap());
+    String::from_utf8(encoded.0.into_owned()).unwrap()
+}
+
+fn requirements_file(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("x402-cli-{}-{name}.json", std::process::id()));
+    let requirements = json!({
+        "scheme": "exact",
+        "network": "base-sepolia",
+        "maxAmountRequired": "1000",
+        "resource": "https://api.example.com/data",
+        "description": "",
+        "mimeType": "application/json",
+        "payTo": "0x1111111111111111111111111111111111111111",
+        "maxTimeoutSeconds": 60,
+        "asset": "0x036CbD53842c5426634e7929541eC2318f3dCF7e"
+    });
+    std::fs::write(&path, requirements.to_string()).unwrap();
+    path
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_verify_and_settle_reach_facilitator() {
+    let addr = start_facilitator().await;
+    let header = payment_header();
+    let requirements = requirements_file("payment");
+    let requirements = requirements.to_str().unwrap();
+
+    for command in ["verify", "settle"] {
+        let output = cli(
+            addr,
+            &[
+                command,
+                "--payment-header",
+                &header,
+                "--network",
+                "base-sepolia",
+                "--requirements",
+                requirements,
+            ],
+        )
+        .await;
+        assert!(stdout_json(&output).is_object(), "{command}");
+        // Exit code 2 is reserved for errors raised before the facilitator answered
+        assert_ne!(output.status.code(), Some(2), "{command}");
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_verify_rejects_network_mismatch_locally() {
+    let addr = start_facilitator().await;
+    let requirements = requirements_file("mismatch");
+
+    let output = cli(
+        addr,
+        &[
+            "verify",
+            "--payment-header",
+            &payment_header(),
+            "--network",
+            "base",
+            "--requirements",
+            requirements.to_str().unwrap(),
+        ],
+    )
+    .await;
+
+    assert_eq!(output.status.code(), Some(2));
+    assert!(output.stdout.is_empty());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("expected base"));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_discovery_list_and_search() {
+    let addr = start_facilitator().await;
+
+    let output = cli(addr, &["discovery", "list", "--limit", "5"]).await;
+    assert!(output.status.success());
+    let list = stdout_json(&output);
+    assert_eq!(list["pagination"]["total"], 1);
+    assert_eq!(list["items"][0]["url"], "https://weather.example.com/");
+
+    let output = cli(addr, &["discovery", "search", "--query", "forecast"]).await;
+    assert!(output.status.success());
+    assert_eq!(stdout_json(&output)["items"].as_array().unwrap().len(), 1);
+
+    let output = cli(
+        addr,
+        &[
+            "discovery",
+            "search",
+            "--query",
+            "forecast",
+            "--sort",
+            "bogus",
+        ],
+    )
+    .await;
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stdout_json(&output)["error"], "Invalid sort");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_facilitator_flag_overrides_env() {
+    let addr = start_facilitator().await;
+    let output = Command::new(env!("CARGO_BIN_EXE_x402-cli"))
+        .env("X402_FACILITATOR_URL", "http://127.0.0.1:1")
+        .args([
+            "discovery",
+            "list",
+            "--facilitator",
+            &format!("http://{addr}"),
+        ])
+        .output()
+        .await
+        .unwrap();
+    assert!(output.status.success());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_nonce_check() {
+    let addr = start_facilitator().await;
+    let key = format!("{}#42", "G".repeat(56));
+
+    let output = cli(
+        addr,
+        &["nonce", "check", "--chain", "stellar", "--key", &key],
+    )
+    .await;
+    assert!(output.status.success());
+    assert_eq!(
+        stdout_json(&output),
+        json!({ "chain": "stellar", "key": key, "used": false })
+    );
+
+    let output = cli(
+        addr,
+        &["nonce", "check", "--chain", "solana", "--key", &key],
+    )
+    .await;
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_completions() {
+    let addr = start_facilitator().await;
+    let output = cli(addr, &["completions", "bash"]).await;
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("x402-cli"));
+}
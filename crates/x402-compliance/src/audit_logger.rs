@@ -2,6 +2,8 @@ use crate::checker::{AddressType, TransactionContext};
 use crate::config::{AuditLoggingConfig, LogFormat};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc::error::TrySendError;
+use tokio::sync::{broadcast, mpsc, oneshot};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ComplianceEvent {
@@ -19,6 +21,8 @@ pub struct ComplianceEvent {
 pub enum EventType {
     SanctionsHit,
     BlacklistHit,
+    CorrelatedHit,
+    PluginHit,
     CleanTransaction,
     ScreeningError,
 }
@@ -30,13 +34,55 @@ pub enum Decision {
     Clear,
 }
 
+/// Events queued for the writer task before [`AuditLogger::log_event`] writes them itself.
+pub const DEFAULT_CHANNEL_CAPACITY: usize = 1024;
+
+/// Most events the writer task takes off the queue at once.
+const WRITE_BATCH_SIZE: usize = 64;
+
+/// Events buffered per [`AuditLogger::subscribe`] receiver before it lags.
+const SUBSCRIBER_CAPACITY: usize = 1024;
+
+enum AuditMessage {
+    Event(Box<ComplianceEvent>),
+    /// Answered once every event queued before it is written
+    Flush(oneshot::Sender<()>),
+}
+
+/// Writes compliance events to the `compliance_audit` tracing target.
+///
+/// Created inside a Tokio runtime, the logger queues events on a bounded channel that a
+/// background task drains and writes in batches, so logging does not block the caller
+/// on the log sink. When the queue is full the caller writes the event itself: this
+/// slows producers down to the writer's pace instead of dropping events. Outside a
+/// runtime every event is written inline.
+///
+/// Written events are also broadcast to the receivers of [`subscribe`](Self::subscribe),
+/// e.g. for real-time dashboards.
 pub struct AuditLogger {
     config: AuditLoggingConfig,
+    queue: Option<mpsc::Sender<AuditMessage>>,
+    events: broadcast::Sender<ComplianceEvent>,
 }
 
 impl AuditLogger {
     pub fn new(config: AuditLoggingConfig) -> Self {
-        Self { config }
+        Self::with_capacity(config, DEFAULT_CHANNEL_CAPACITY)
+    }
+
+    /// Create a logger queuing up to `capacity` events for the writer task.
+    pub fn with_capacity(config: AuditLoggingConfig, capacity: usize) -> Self {
+        let (events, _) = broadcast::channel(SUBSCRIBER_CAPACITY);
+        let queue = tokio::runtime::Handle::try_current().ok().map(|runtime| {
+            let (queue, receiver) = mpsc::channel(capacity.max(1));
+            runtime.spawn(write_batches(config.format.clone(), receiver, events.clone()));
+            queue
+        });
+        Self {
+            config,
+            queue,
+            events,
+        }
     }
 
     pub fn log_event(&self, event: ComplianceEvent) {
@@ -49,59 +95,118 @@ impl AuditLogger {
             return;
         }
 
-        match self.config.format {
-            LogFormat::Json => self.log_json(event),
-            LogFormat::Text => self.log_text(event),
+        let Some(queue) = &self.queue else {
+            write_event(&self.config.format, &event, &self.events);
+            return;
+        };
+        match queue.try_send(AuditMessage::Event(Box::new(event))) {
+            Ok(()) => {}
+            Err(TrySendError::Full(AuditMessage::Event(event)))
+            | Err(TrySendError::Closed(AuditMessage::Event(event))) => {
+                write_event(&self.config.format, &event, &self.events);
+            }
+            Err(_) => {}
         }
     }
 
-    fn log_json(&self, event: ComplianceEvent) {
-        let json = serde_json::to_string(&event)
-            .unwrap_or_else(|e| format!(r#"{{"error": "Failed to serialize: {}"}}"#, e));
+    /// Receive every event written from now on.
+    ///
+    /// A receiver more than 1024 events behind skips the oldest ones
+    /// ([`broadcast::error::RecvError::Lagged`]); the audit log itself is unaffected.
+    pub fn subscribe(&self) -> broadcast::Receiver<ComplianceEvent> {
+        self.events.subscribe()
+    }
 
-        // Note: tracing macros require compile-time constant targets
-        // Using the configured target as part of the log message instead
-        match event.decision {
-            Decision::Block => {
-                tracing::error!(target: "compliance_audit", "{}", json)
-            }
-            Decision::Review => {
-                tracing::warn!(target: "compliance_audit", "{}", json)
-            }
-            Decision::Clear => {
-                tracing::info!(target: "compliance_audit", "{}", json)
-            }
+    /// Wait until every event logged so far has been written, e.g. before shutdown.
+    pub async fn flush(&self) {
+        let Some(queue) = &self.queue else {
+            return;
+        };
+        let (done, written) = oneshot::channel();
+        if queue.send(AuditMessage::Flush(done)).await.is_ok() {
+            let _ = written.await;
         }
     }
+}
 
-    fn log_text(&self, event: ComplianceEvent) {
-        let message = format!(
-            "[{}] {:?} - {} address: {} | List: {} | Network: {} | Amount: {} {}",
-            event.timestamp.format("%Y-%m-%d %H:%M:%S"),
-            event.decision,
-            event.address_type,
-            event.matched_address,
-            event.list_source,
-            event.transaction_context.network,
-            event.transaction_context.amount,
-            event.transaction_context.currency
-        );
-
-        // Note: tracing macros require compile-time constant targets
-        match event.decision {
-            Decision::Block => {
-                tracing::error!(target: "compliance_audit", "{}", message)
-            }
-            Decision::Review => {
-                tracing::warn!(target: "compliance_audit", "{}", message)
-            }
-            Decision::Clear => {
-                tracing::info!(target: "compliance_audit", "{}", message)
+/// Drain `receiver` until every sender is gone, writing events in batches.
+async fn write_batches(
+    format: LogFormat,
+    mut receiver: mpsc::Receiver<AuditMessage>,
+    events: broadcast::Sender<ComplianceEvent>,
+) {
+    let mut batch = Vec::with_capacity(WRITE_BATCH_SIZE);
+    while receiver.recv_many(&mut batch, WRITE_BATCH_SIZE).await > 0 {
+        for message in batch.drain(..) {
+            match message {
+                AuditMessage::Event(event) => write_event(&format, &event, &events),
+                AuditMessage::Flush(done) => {
+                    let _ = done.send(());
+                }
             }
         }
     }
 }
 
+fn write_event(
+    format: &LogFormat,
+    event: &ComplianceEvent,
+    events: &broadcast::Sender<ComplianceEvent>,
+) {
+    match format {
+        LogFormat::Json => log_json(event),
+        LogFormat::Text => log_text(event),
+    }
+    // No subscribers is not an error
+    let _ = events.send(event.clone());
+}
+
+fn log_json(event: &ComplianceEvent) {
+    let json = serde_json::to_string(event)
+        .unwrap_or_else(|e| format!(r#"{{"error": "Failed to serialize: {}"}}"#, e));
+
+    // Note: tracing macros require compile-time constant targets
+    // Using the configured target as part of the log message instead
+    match event.decision {
+        Decision::Block => {
+            tracing::error!(target: "compliance_audit", "{}", json)
+        }
+        Decision::Review => {
+            tracing::warn!(target: "compliance_audit", "{}", json)
+        }
+        Decision::Clear => {
+            tracing::info!(target: "compliance_audit", "{}", json)
+        }
+    }
+}
+
+fn log_text(event: &ComplianceEvent) {
+    let message = format!(
+        "[{}] {:?} - {} address: {} | List: {} | Network: {} | Amount: {} {}",
+        event.timestamp.format("%Y-%m-%d %H:%M:%S"),
+        event.decision,
+        event.address_type,
+        event.matched_address,
+        event.list_source,
+        event.transaction_context.network,
+        event.transaction_context.amount,
+        event.transaction_context.currency
+    );
+
+    // Note: tracing macros require compile-time constant targets
+    match event.decision {
+        Decision::Block => {
+            tracing::error!(target: "compliance_audit", "{}", message)
+        }
+        Decision::Review => {
+            tracing::warn!(target: "compliance_audit", "{}", message)
+        }
+        Decision::Clear => {
+            tracing::info!(target: "compliance_audit", "{}", message)
+        }
+    }
+}
+
 impl Default for AuditLogger {
     fn default() -> Self {
         Self::new(AuditLoggingConfig {
@@ -112,3 +217,94 @@ impl Default for AuditLogger {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> AuditLoggingConfig {
+        AuditLoggingConfig {
+            enabled: true,
+            target: "compliance_audit".to_string(),
+            format: LogFormat::Json,
+            include_clear_transactions: false,
+        }
+    }
+
+    fn event(address: String, decision: Decision) -> ComplianceEvent {
+        ComplianceEvent {
+            timestamp: Utc::now(),
+            event_type: EventType::SanctionsHit,
+            decision,
+            transaction_context: TransactionContext {
+                amount: "1000000".to_string(),
+                currency: "USDC".to_string(),
+                network: "base".to_string(),
+                transaction_id: None,
+            },
+            matched_address: address,
+            address_type: AddressType::Payer,
+            list_source: "OFAC".to_string(),
+            entity_name: None,
+        }
+    }
+
+    fn drain(receiver: &mut broadcast::Receiver<ComplianceEvent>) -> Vec<String> {
+        let mut addresses = Vec::new();
+        while let Ok(event) = receiver.try_recv() {
+            addresses.push(event.matched_address);
+        }
+        addresses
+    }
+
+    #[tokio::test]
+    async fn test_events_are_written_in_order_and_flushed() {
+        let logger = AuditLogger::new(config());
+        let mut receiver = logger.subscribe();
+
+        for i in 0..200 {
+            logger.log_event(event(format!("0x{i}"), Decision::Block));
+        }
+        // Filtered out: clean transactions are not logged by default
+        logger.log_event(event("0xclean".to_string(), Decision::Clear));
+        logger.flush().await;
+
+        let expected: Vec<String> = (0..200).map(|i| format!("0x{i}")).collect();
+        assert_eq!(drain(&mut receiver), expected);
+    }
+
+    #[tokio::test]
+    async fn test_full_channel_applies_back_pressure_without_losing_events() {
+        // The writer task cannot run until the test yields, so the queue stays full
+        let logger = AuditLogger::with_capacity(config(), 4);
+        let mut receiver = logger.subscribe();
+
+        for i in 0..50 {
+            logger.log_event(event(format!("0x{i}"), Decision::Review));
+        }
+        // Events beyond the queue's capacity were written by the caller
+        assert_eq!(drain(&mut receiver).len(), 46);
+
+        logger.flush().await;
+        let mut written = drain(&mut receiver);
+        assert_eq!(written, ["0x0", "0x1", "0x2", "0x3"]);
+
+        for i in 50..60 {
+            logger.log_event(event(format!("0x{i}"), Decision::Review));
+        }
+        logger.flush().await;
+        written = drain(&mut receiver);
+        assert_eq!(written.len(), 10);
+    }
+
+    #[test]
+    fn test_logger_outside_runtime_writes_inline() {
+        let logger = AuditLogger::new(config());
+        let mut receiver = logger.subscribe();
+        logger.log_event(event("0xabc".to_string(), Decision::Block));
+        assert_eq!(drain(&mut receiver), ["0xabc"]);
+
+        let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+        runtime.block_on(logger.flush());
+    }
+}
@@ -1,7 +1,11 @@
 use crate::audit_logger::{AuditLogger, ComplianceEvent, Decision, EventType};
 use crate::config::Config;
+use crate::correlation::CrossChainCorrelator;
 use crate::error::Result;
 use crate::lists::SanctionsList;
+use crate::plugins::ScreeningPlugin;
+use crate::risk::{RiskContext, RiskScore, RiskScorer};
+use crate::travel_rule::{TravelRuleData, TravelRuleValidator};
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -21,6 +25,21 @@ pub trait ComplianceChecker: Send + Sync {
     /// Screen a single address
     async fn screen_address(&self, address: &str) -> Result<ScreeningDecision>;
 
+    /// Score a single address from 0 to 100 with the configured risk weights
+    async fn score_address(&self, address: &str, context: &RiskContext) -> Result<RiskScore>;
+
+    /// Screen a single address, blocking it when its score is above the configured
+    /// `risk_threshold`
+    async fn screen_address_with_risk(
+        &self,
+        address: &str,
+        context: &RiskContext,
+    ) -> Result<(ScreeningDecision, RiskScore)>;
+
+    /// Require travel rule data on payments worth at least the configured
+    /// `travel_rule_threshold_usd`
+    fn check_travel_rule(&self, amount_usd: f64, data: Option<&TravelRuleData>) -> Result<()>;
+
     /// Check if a specific list is loaded
     fn is_list_enabled(&self, list_name: &str) -> bool;
 
@@ -29,6 +48,9 @@ pub trait ComplianceChecker: Send + Sync {
 
     /// Reload/refresh sanctions lists
     async fn reload_lists(&mut self) -> Result<()>;
+
+    /// Release the resources held by screening plugins
+    async fn shutdown(&self) {}
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -102,6 +124,9 @@ pub struct ComplianceCheckerBuilder {
     blacklist_path: Option<std::path::PathBuf>,
     config_path: Option<std::path::PathBuf>,
     audit_logger: Option<Arc<AuditLogger>>,
+    extra_lists: Vec<Box<dyn SanctionsList>>,
+    correlator: Option<Arc<CrossChainCorrelator>>,
+    plugins: Vec<Arc<dyn ScreeningPlugin>>,
 }
 
 impl ComplianceCheckerBuilder {
@@ -114,6 +139,9 @@ impl ComplianceCheckerBuilder {
             blacklist_path: None,
             config_path: None,
             audit_logger: None,
+            extra_lists: Vec::new(),
+            correlator: None,
+            plugins: Vec::new(),
         }
     }
 
@@ -152,6 +180,28 @@ impl ComplianceCheckerBuilder {
         self
     }
 
+    /// Screen against an additional list, such as a shared [`OfacSdnSource`]
+    ///
+    /// [`OfacSdnSource`]: crate::lists::ofac_sdn::OfacSdnSource
+    pub fn with_sanctions_list(mut self, list: Box<dyn SanctionsList>) -> Self {
+        self.extra_lists.push(list);
+        self
+    }
+
+    /// Send payments to review when an address linked to the payer or payee on another
+    /// chain is denied
+    pub fn with_correlator(mut self, correlator: Arc<CrossChainCorrelator>) -> Self {
+        self.correlator = Some(correlator);
+        self
+    }
+
+    /// Run a custom screening rule after the built-in checks. Plugins run in the order
+    /// they are added and the first one to block a payment wins
+    pub fn add_plugin(mut self, plugin: Arc<dyn ScreeningPlugin>) -> Self {
+        self.plugins.push(plugin);
+        self
+    }
+
     pub async fn build(self) -> Result<Box<dyn ComplianceChecker>> {
         // Load config if provided
         let config = if let Some(path) = self.config_path {
@@ -170,6 +220,8 @@ impl ComplianceCheckerBuilder {
 
         // TODO: Add UN, UK, EU lists in Phase 2
 
+        lists.extend(self.extra_lists);
+
         // Load blacklist if provided
         let blacklist = if let Some(path) = &self.blacklist_path {
             Some(crate::lists::blacklist::Blacklist::from_file(path)?)
@@ -184,11 +236,17 @@ impl ComplianceCheckerBuilder {
             .audit_logger
             .unwrap_or_else(|| Arc::new(AuditLogger::new(config.audit_logging.clone())));
 
+        for plugin in &self.plugins {
+            plugin.on_load().await?;
+        }
+
         Ok(Box::new(MultiListChecker {
             lists,
             blacklist,
             audit_logger,
             config,
+            correlator: self.correlator,
+            plugins: self.plugins,
         }))
     }
 }
@@ -205,6 +263,123 @@ pub struct MultiListChecker {
     blacklist: Option<crate::lists::blacklist::Blacklist>,
     audit_logger: Arc<AuditLogger>,
     config: Config,
+    correlator: Option<Arc<CrossChainCorrelator>>,
+    plugins: Vec<Arc<dyn ScreeningPlugin>>,
+}
+
+impl MultiListChecker {
+    /// Source and entity name of the first list denying `address`
+    fn denied_by(&self, address: &str) -> Option<(String, Option<String>)> {
+        if let Some(blacklist) = &self.blacklist {
+            if blacklist.is_blacklisted(address) {
+                return Some(("blacklist".to_string(), None));
+            }
+        }
+        self.lists
+            .iter()
+            .find(|list| list.is_sanctioned(address))
+            .map(|list| {
+                let entity = list.sanctioned_entity(address).unwrap_or_default();
+                (list.metadata().name, entity.name)
+            })
+    }
+
+    /// Review decision for the first payer or payee linked to a denied address on
+    /// another chain
+    fn screen_correlated(
+        &self,
+        payer: &str,
+        payee: &str,
+        context: &TransactionContext,
+    ) -> Option<(ScreeningDecision, MatchedEntity)> {
+        let correlator = self.correlator.as_ref()?;
+        for (address, address_type) in [(payer, AddressType::Payer), (payee, AddressType::Payee)] {
+            for linked in correlator.correlated_addresses(address) {
+                let Some((list_source, entity_name)) = self.denied_by(&linked.address) else {
+                    continue;
+                };
+
+                self.audit_logger.log_event(ComplianceEvent {
+                    timestamp: chrono::Utc::now(),
+                    event_type: EventType::CorrelatedHit,
+                    decision: Decision::Review,
+                    transaction_context: context.clone(),
+                    matched_address: linked.address.clone(),
+                    address_type: address_type.clone(),
+                    list_source: list_source.clone(),
+                    entity_name: entity_name.clone(),
+                });
+
+                let reason = format!(
+                    "Address is linked to {}, which is on {} list ({})",
+                    linked, list_source, address_type
+                );
+                let matched = MatchedEntity {
+                    address: linked.address,
+                    address_type,
+                    list_source,
+                    entity_name,
+                    entity_id: None,
+                    program: None,
+                };
+                return Some((ScreeningDecision::Review { reason }, matched));
+            }
+        }
+        None
+    }
+
+    /// First blocking plugin decision for the payer or payee, or else the first review
+    async fn screen_plugins(
+        &self,
+        payer: &str,
+        payee: &str,
+        context: &TransactionContext,
+    ) -> Option<(ScreeningDecision, MatchedEntity)> {
+        let mut review = None;
+        for plugin in &self.plugins {
+            for (address, address_type) in
+                [(payer, AddressType::Payer), (payee, AddressType::Payee)]
+            {
+                let decision = match plugin.screen(address, context).await {
+                    Some(ScreeningDecision::Clear) | None => continue,
+                    Some(decision) => decision,
+                };
+                let blocked = matches!(decision, ScreeningDecision::Block { .. });
+                if !blocked && review.is_some() {
+                    continue;
+                }
+
+                self.audit_logger.log_event(ComplianceEvent {
+                    timestamp: chrono::Utc::now(),
+                    event_type: EventType::PluginHit,
+                    decision: if blocked {
+                        Decision::Block
+                    } else {
+                        Decision::Review
+                    },
+                    transaction_context: context.clone(),
+                    matched_address: address.to_string(),
+                    address_type: address_type.clone(),
+                    list_source: plugin.name().to_string(),
+                    entity_name: None,
+                });
+
+                let matched = MatchedEntity {
+                    address: address.to_string(),
+                    address_type,
+                    list_source: plugin.name().to_string(),
+                    entity_name: None,
+                    entity_id: None,
+                    program: None,
+                };
+                if blocked {
+                    return Some((decision, matched));
+                }
+                review = Some((decision, matched));
+            }
+        }
+        review
+    }
 }
 
 #[async_trait]
@@ -267,13 +442,14 @@ impl ComplianceChecker for MultiListChecker {
                         metadata.checksum.clone().unwrap_or_default(),
                     );
 
+                    let entity = list.sanctioned_entity(address).unwrap_or_default();
                     let matched = MatchedEntity {
                         address: address.to_string(),
                         address_type: address_type.clone(),
                         list_source: metadata.name.clone(),
-                        entity_name: None, // TODO: Extract entity name in Phase 2
-                        entity_id: None,
-                        program: None,
+                        entity_name: entity.name.clone(),
+                        entity_id: entity.id,
+                        program: entity.program,
                     };
 
                     matched_entities.push(matched);
@@ -287,14 +463,15 @@ impl ComplianceChecker for MultiListChecker {
                         matched_address: address.to_string(),
                         address_type: address_type.clone(),
                         list_source: metadata.name.clone(),
-                        entity_name: None,
+                        entity_name: entity.name.clone(),
                     });
 
                     return Ok(ScreeningResult {
                         decision: ScreeningDecision::Block {
-                            reason: format!(
-                                "Address is on {} sanctions list ({})",
-                                metadata.name, address_type
+                            reason: sanctions_reason(
+                                &metadata.name,
+                                entity.name.as_deref(),
+                                Some(address_type),
                             ),
                         },
                         payer_address: payer.to_string(),
@@ -306,6 +483,24 @@ impl ComplianceChecker for MultiListChecker {
             }
         }
 
+        // Plugins may still block a payment the correlator would only send to review
+        let review = match self.screen_plugins(payer, payee, context).await {
+            Some(hit @ (ScreeningDecision::Block { .. }, _)) => Some(hit),
+            plugin_review => self
+                .screen_correlated(payer, payee, context)
+                .or(plugin_review),
+        };
+        if let Some((decision, matched)) = review {
+            matched_entities.push(matched);
+            return Ok(ScreeningResult {
+                decision,
+                payer_address: payer.to_string(),
+                payee_address: payee.to_string(),
+                matched_entities,
+                list_versions,
+            });
+        }
+
         // If we get here, transaction is clear
         self.audit_logger.log_event(ComplianceEvent {
             timestamp: chrono::Utc::now(),
@@ -341,8 +536,9 @@ impl ComplianceChecker for MultiListChecker {
         for list in &self.lists {
             if list.is_sanctioned(address) {
                 let metadata = list.metadata();
+                let entity = list.sanctioned_entity(address).unwrap_or_default();
                 return Ok(ScreeningDecision::Block {
-                    reason: format!("Address is on {} sanctions list", metadata.name),
+                    reason: sanctions_reason(&metadata.name, entity.name.as_deref(), None),
                 });
             }
         }
@@ -350,6 +546,59 @@ impl ComplianceChecker for MultiListChecker {
         Ok(ScreeningDecision::Clear)
     }
 
+    async fn score_address(&self, address: &str, context: &RiskContext) -> Result<RiskScore> {
+        let mut list_versions = HashMap::new();
+        let matched_entities = self
+            .lists
+            .iter()
+            .filter(|list| list.is_sanctioned(address))
+            .map(|list| {
+                let metadata = list.metadata();
+                let entity = list.sanctioned_entity(address).unwrap_or_default();
+                list_versions.insert(
+                    metadata.name.clone(),
+                    metadata.checksum.clone().unwrap_or_default(),
+                );
+                MatchedEntity {
+                    address: address.to_string(),
+                    address_type: AddressType::Payer,
+                    list_source: metadata.name,
+                    entity_name: entity.name,
+                    entity_id: entity.id,
+                    program: entity.program,
+                }
+            })
+            .collect();
+        let result = ScreeningResult {
+            decision: self.screen_address(address).await?,
+            payer_address: address.to_string(),
+            payee_address: String::new(),
+            matched_entities,
+            list_versions,
+        };
+        Ok(RiskScorer::new(self.config.risk_weights.clone()).score(&result, context))
+    }
+
+    async fn screen_address_with_risk(
+        &self,
+        address: &str,
+        context: &RiskContext,
+    ) -> Result<(ScreeningDecision, RiskScore)> {
+        let decision = self.screen_address(address).await?;
+        let score = self.score_address(address, context).await?;
+        let decision = match decision {
+            ScreeningDecision::Block { .. } => decision,
+            _ => score
+                .threshold_decision(self.config.risk_threshold)
+                .unwrap_or(decision),
+        };
+        Ok((decision, score))
+    }
+
+    fn check_travel_rule(&self, amount_usd: f64, data: Option<&TravelRuleData>) -> Result<()> {
+        TravelRuleValidator::new(self.config.travel_rule_threshold_usd).validate(amount_usd, data)
+    }
+
     fn is_list_enabled(&self, list_name: &str) -> bool {
         self.lists
             .iter()
@@ -370,4 +619,21 @@ impl ComplianceChecker for MultiListChecker {
         // TODO: Implement list reloading in Phase 2
         Ok(())
     }
+
+    async fn shutdown(&self) {
+        for plugin in &self.plugins {
+            plugin.on_unload().await;
+        }
+    }
+}
+
+fn sanctions_reason(list: &str, entity: Option<&str>, address_type: Option<AddressType>) -> String {
+    let mut reason = format!("Address is on {} sanctions list", list);
+    if let Some(entity) = entity.filter(|e| !e.is_empty()) {
+        reason.push_str(&format!(" as {}", entity));
+    }
+    if let Some(address_type) = address_type {
+        reason.push_str(&format!(" ({})", address_type));
+    }
+    reason
 }
@@ -1,4 +1,7 @@
+use crate::correlation::{default_bridges, BridgeContract};
 use crate::error::{ComplianceError, Result};
+use crate::risk::RiskWeights;
+use crate::travel_rule::DEFAULT_TRAVEL_RULE_THRESHOLD_USD;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
@@ -8,6 +11,23 @@ pub struct Config {
     pub blacklist_path: Option<PathBuf>,
     pub audit_logging: AuditLoggingConfig,
     pub fail_mode: FailMode,
+    /// Addresses scoring above this are blocked (see [`crate::risk::RiskScore`])
+    #[serde(default)]
+    pub risk_threshold: Option<u8>,
+    #[serde(default)]
+    pub risk_weights: RiskWeights,
+    /// Payments worth at least this many USD must carry travel rule data
+    /// (see [`crate::travel_rule::TravelRuleValidator`]); `None` turns the check off
+    #[serde(default = "default_travel_rule_threshold")]
+    pub travel_rule_threshold_usd: Option<f64>,
+    /// Contracts whose transfers link addresses across chains
+    /// (see [`crate::correlation::CrossChainCorrelator`])
+    #[serde(default = "default_bridges")]
+    pub bridges: Vec<BridgeContract>,
+}
+
+fn default_travel_rule_threshold() -> Option<f64> {
+    Some(DEFAULT_TRAVEL_RULE_THRESHOLD_USD)
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -137,6 +157,10 @@ impl Default for Config {
                 on_list_load_error: FailModeType::Open,
                 on_screening_error: FailModeType::Open,
             },
+            risk_threshold: None,
+            risk_weights: RiskWeights::default(),
+            travel_rule_threshold_usd: default_travel_rule_threshold(),
+            bridges: default_bridges(),
         }
     }
 }
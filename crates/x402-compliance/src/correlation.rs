@@ -0,0 +1,390 @@
+//! Cross-chain address correlation.
+//!
+//! The extractors yield one address per chain, so an operator paying from Base with
+//! funds bridged from a sanctioned Ethereum or Solana wallet goes unnoticed. A
+//! [`CrossChainCorrelator`] links addresses believed to belong to the same entity:
+//!
+//! - the sender and recipient of a transfer through a known bridge contract
+//!   (Wormhole, Across, Stargate by default, see [`default_bridges`]);
+//! - the same EVM address on different EVM chains, as one key controls all of them.
+//!
+//! Networks are CAIP-2 identifiers (`eip155:8453`, `solana:5eykt4UsFv8P8NJdTREpY1vzqKqZKvdp`).
+
+use crate::error::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::path::Path;
+use std::sync::RwLock;
+
+const ETHEREUM: &str = "eip155:1";
+const BASE: &str = "eip155:8453";
+const ARBITRUM: &str = "eip155:42161";
+const SOLANA: &str = "solana:5eykt4UsFv8P8NJdTREpY1vzqKqZKvdp";
+
+/// An address on a given network
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct ChainAddress {
+    /// CAIP-2 network identifier
+    pub network: String,
+    pub address: String,
+}
+
+impl ChainAddress {
+    pub fn new(network: impl Into<String>, address: impl Into<String>) -> Self {
+        Self {
+            network: network.into(),
+            address: address.into(),
+        }
+    }
+
+    /// Identity of the address holder: EVM addresses are the same account on every
+    /// EVM chain and compare case-insensitively
+    fn key(&self) -> ChainAddress {
+        if self.network.starts_with("eip155:") || is_evm_address(&self.address) {
+            ChainAddress::new("eip155", self.address.to_lowercase())
+        } else {
+            self.clone()
+        }
+    }
+}
+
+impl<N: Into<String>, A: Into<String>> From<(N, A)> for ChainAddress {
+    fn from((network, address): (N, A)) -> Self {
+        Self::new(network, address)
+    }
+}
+
+impl std::fmt::Display for ChainAddress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} on {}", self.address, self.network)
+    }
+}
+
+/// A bridge contract whose transfers link the sender to the recipient
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BridgeContract {
+    pub name: String,
+    /// CAIP-2 network the contract is deployed on
+    pub network: String,
+    pub address: String,
+}
+
+impl BridgeContract {
+    pub fn new(name: &str, network: &str, address: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            network: network.to_string(),
+            address: address.to_string(),
+        }
+    }
+}
+
+/// Wormhole token bridges, Across spoke pools and Stargate routers on Ethereum,
+/// Base, Arbitrum and Solana
+pub fn default_bridges() -> Vec<BridgeContract> {
+    vec![
+        BridgeContract::new(
+            "Wormhole",
+            ETHEREUM,
+            "0x3ee18B2214AFF97000D974cf647E7C347E8fa585",
+        ),
+        BridgeContract::new(
+            "Wormhole",
+            BASE,
+            "0x8d2de8d2f73F1F4cAB472AC9A881C9b123C79627",
+        ),
+        BridgeContract::new(
+            "Wormhole",
+            SOLANA,
+            "wormDTUJ6AWPNvk59vGQbDvGJmqbDTdgWgAqcLBCgUb",
+        ),
+        BridgeContract::new(
+            "Across",
+            ETHEREUM,
+            "0x5c7BCd6E7De5423a257D81B442095A1a6ced35C5",
+        ),
+        BridgeContract::new("Across", BASE, "0x09aea4b2242abC8bb4BB78D537A67a245A7bEC64"),
+        BridgeContract::new(
+            "Across",
+            ARBITRUM,
+            "0xe35e9842fceaCA96570B734083f4a58e8F7C5f2A",
+        ),
+        BridgeContract::new(
+            "Stargate",
+            ETHEREUM,
+            "0x8731d54E9D02c286767d56ac03e8037C07e01e98",
+        ),
+        BridgeContract::new(
+            "Stargate",
+            BASE,
+            "0x45f1A95A4D3f3836523F5c83673c797f4d4d263B",
+        ),
+        BridgeContract::new(
+            "Stargate",
+            ARBITRUM,
+            "0x53Bf833A5d6c4ddA888F69c22C88C9f356a41614",
+        ),
+    ]
+}
+
+/// A transfer observed on a source chain, sent to `bridge` for `recipient` on the
+/// destination chain
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BridgeTransfer {
+    pub source_network: String,
+    pub sender: String,
+    /// Contract the sender paid into, on the source network
+    pub bridge: String,
+    pub destination_network: String,
+    pub recipient: String,
+}
+
+/// Groups addresses on different chains that belong to the same entity
+#[derive(Debug)]
+pub struct CrossChainCorrelator {
+    /// Bridge names by contract key
+    bridges: HashMap<ChainAddress, String>,
+    /// Linked address keys
+    links: RwLock<HashMap<ChainAddress, HashSet<ChainAddress>>>,
+    /// Addresses seen for each key, to report them with their networks
+    seen: RwLock<HashMap<ChainAddress, BTreeSet<ChainAddress>>>,
+}
+
+impl Default for CrossChainCorrelator {
+    fn default() -> Self {
+        Self::new(default_bridges())
+    }
+}
+
+impl CrossChainCorrelator {
+    /// Correlator using transfers through `bridges` as signals
+    pub fn new(bridges: Vec<BridgeContract>) -> Self {
+        let bridges = bridges
+            .into_iter()
+            .map(|bridge| {
+                let key = ChainAddress::new(&bridge.network, &bridge.address).key();
+                (key, bridge.name)
+            })
+            .collect();
+        Self {
+            bridges,
+            links: RwLock::new(HashMap::new()),
+            seen: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Name of the known bridge deployed at `address` on `network`
+    pub fn bridge_name(&self, network: &str, address: &str) -> Option<&str> {
+        self.bridges
+            .get(&ChainAddress::new(network, address).key())
+            .map(String::as_str)
+    }
+
+    /// Link the sender and recipient of a transfer through a known bridge
+    ///
+    /// Returns `false`, recording nothing, when the transfer went through another contract.
+    pub fn record_transfer(&self, transfer: &BridgeTransfer) -> bool {
+        if self
+            .bridge_name(&transfer.source_network, &transfer.bridge)
+            .is_none()
+        {
+            return false;
+        }
+        let sender = ChainAddress::new(&transfer.source_network, &transfer.sender);
+        let recipient = ChainAddress::new(&transfer.destination_network, &transfer.recipient);
+        {
+            let mut seen = self.seen.write().unwrap();
+            for address in [&sender, &recipient] {
+                seen.entry(address.key())
+                    .or_default()
+                    .insert(address.clone());
+            }
+        }
+        let (sender, recipient) = (sender.key(), recipient.key());
+        let mut links = self.links.write().unwrap();
+        links
+            .entry(sender.clone())
+            .or_default()
+            .insert(recipient.clone());
+        links.entry(recipient).or_default().insert(sender);
+        true
+    }
+
+    /// Record the transfers of a JSON array of [`BridgeTransfer`]s, returning how many
+    /// went through known bridges
+    pub fn load_transfers(&self, path: impl AsRef<Path>) -> Result<usize> {
+        let content = std::fs::read_to_string(path)?;
+        let transfers: Vec<BridgeTransfer> = serde_json::from_str(&content)?;
+        Ok(transfers
+            .iter()
+            .filter(|transfer| self.record_transfer(transfer))
+            .count())
+    }
+
+    /// Groups of `addresses` believed to belong to the same entity
+    ///
+    /// Only groups of two or more addresses are returned, each sorted, with the groups in
+    /// the order of their first address in `addresses`.
+    pub fn correlate(&self, addresses: Vec<ChainAddress>) -> Vec<Vec<ChainAddress>> {
+        let links = self.links.read().unwrap();
+        let mut component_of: HashMap<ChainAddress, usize> = HashMap::new();
+        let mut groups: BTreeMap<usize, BTreeSet<ChainAddress>> = BTreeMap::new();
+        let mut order = Vec::new();
+
+        for address in addresses {
+            let key = address.key();
+            let component = match component_of.get(&key) {
+                Some(component) => *component,
+                None => {
+                    let component = order.len();
+                    for member in connected(&links, &key) {
+                        component_of.insert(member, component);
+                    }
+                    component
+                }
+            };
+            if !groups.contains_key(&component) {
+                order.push(component);
+            }
+            groups.entry(component).or_default().insert(address);
+        }
+
+        order
+            .into_iter()
+            .filter_map(|component| groups.remove(&component))
+            .filter(|group| group.len() > 1)
+            .map(|group| group.into_iter().collect())
+            .collect()
+    }
+
+    /// Addresses, on any network, linked to `address` by recorded bridge transfers
+    ///
+    /// The address itself, on whichever network it was seen, is not included.
+    pub fn correlated_addresses(&self, address: &str) -> Vec<ChainAddress> {
+        let links = self.links.read().unwrap();
+        let seen = self.seen.read().unwrap();
+        let starts: Vec<ChainAddress> = if is_evm_address(address) {
+            vec![ChainAddress::new("eip155", address.to_lowercase())]
+        } else {
+            seen.keys()
+                .filter(|key| key.address == address)
+                .cloned()
+                .collect()
+        };
+
+        let mut correlated = BTreeSet::new();
+        for start in &starts {
+            for key in connected(&links, start) {
+                if starts.contains(&key) {
+                    continue;
+                }
+                if let Some(addresses) = seen.get(&key) {
+                    correlated.extend(addresses.iter().cloned());
+                }
+            }
+        }
+        correlated.into_iter().collect()
+    }
+}
+
+/// Keys reachable from `start`, including it
+fn connected(
+    links: &HashMap<ChainAddress, HashSet<ChainAddress>>,
+    start: &ChainAddress,
+) -> HashSet<ChainAddress> {
+    let mut visited = HashSet::from([start.clone()]);
+    let mut queue = VecDeque::from([start.clone()]);
+    while let Some(key) = queue.pop_front() {
+        for next in links.get(&key).into_iter().flatten() {
+            if visited.insert(next.clone()) {
+                queue.push_back(next.clone());
+            }
+        }
+    }
+    visited
+}
+
+fn is_evm_address(address: &str) -> bool {
+    address.len() == 42
+        && address.starts_with("0x")
+        && address[2..].chars().all(|c| c.is_ascii_hexdigit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALICE: &str = "0x1111111111111111111111111111111111111111";
+    const ALICE_SOLANA: &str = "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM";
+    const BOB: &str = "0x2222222222222222222222222222222222222222";
+
+    fn wormhole_transfer(sender: &str, recipient: &str) -> BridgeTransfer {
+        BridgeTransfer {
+            source_network: ETHEREUM.to_string(),
+            sender: sender.to_string(),
+            bridge: "0x3EE18B2214AFF97000D974CF647E7C347E8FA585".to_string(),
+            destination_network: SOLANA.to_string(),
+            recipient: recipient.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_unknown_bridge_is_ignored() {
+        let correlator = CrossChainCorrelator::default();
+        let transfer = BridgeTransfer {
+            bridge: "0x5555555555555555555555555555555555555555".to_string(),
+            ..wormhole_transfer(ALICE, ALICE_SOLANA)
+        };
+        assert!(!correlator.record_transfer(&transfer));
+        assert!(correlator.correlated_addresses(ALICE).is_empty());
+        assert!(CrossChainCorrelator::new(Vec::new())
+            .correlated_addresses(ALICE)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_bridge_transfer_links_both_ends() {
+        let correlator = CrossChainCorrelator::default();
+        assert!(correlator.record_transfer(&wormhole_transfer(ALICE, ALICE_SOLANA)));
+
+        assert_eq!(
+            correlator.correlated_addresses(ALICE),
+            vec![ChainAddress::new(SOLANA, ALICE_SOLANA)]
+        );
+        assert_eq!(
+            correlator.correlated_addresses(ALICE_SOLANA),
+            vec![ChainAddress::new(ETHEREUM, ALICE)]
+        );
+    }
+
+    #[test]
+    fn test_correlate_groups_inputs() {
+        let correlator = CrossChainCorrelator::default();
+        correlator.record_transfer(&wormhole_transfer(ALICE, ALICE_SOLANA));
+
+        let groups = correlator.correlate(vec![
+            (BASE, BOB).into(),
+            (SOLANA, ALICE_SOLANA).into(),
+            (ARBITRUM, BOB).into(),
+            // Same account as on Ethereum, where it bridged to Solana
+            (BASE, ALICE).into(),
+        ]);
+        assert_eq!(
+            groups,
+            vec![
+                vec![
+                    ChainAddress::new(ARBITRUM, BOB),
+                    ChainAddress::new(BASE, BOB)
+                ],
+                vec![
+                    ChainAddress::new(BASE, ALICE),
+                    ChainAddress::new(SOLANA, ALICE_SOLANA)
+                ],
+            ]
+        );
+        assert!(correlator
+            .correlate(vec![(BASE, BOB).into(), (SOLANA, ALICE_SOLANA).into()])
+            .is_empty());
+    }
+}
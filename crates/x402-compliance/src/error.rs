@@ -23,6 +23,9 @@ pub enum ComplianceError {
     #[error("Invalid checksum")]
     InvalidChecksum,
 
+    #[error("Travel rule data required: {0}")]
+    MissingTravelRuleData(String),
+
     #[cfg(feature = "solana")]
     #[error("Solana transaction parsing error: {0}")]
     SolanaError(String),
@@ -1,9 +1,13 @@
 pub mod audit_logger;
 pub mod checker;
 pub mod config;
+pub mod correlation;
 pub mod error;
 pub mod extractors;
 pub mod lists;
+pub mod plugins;
+pub mod risk;
+pub mod travel_rule;
 
 // Re-export main types for convenience
 pub use audit_logger::{AuditLogger, ComplianceEvent, Decision, EventType};
@@ -12,7 +16,11 @@ pub use checker::{
     ScreeningResult, TransactionContext,
 };
 pub use config::{Config, ListConfig};
+pub use correlation::{BridgeContract, BridgeTransfer, ChainAddress, CrossChainCorrelator};
 pub use error::{ComplianceError, Result};
+pub use plugins::{JsonFilePlugin, RegexPlugin, ScreeningPlugin};
+pub use risk::{RiskContext, RiskFactor, RiskFactorKind, RiskScore, RiskScorer, RiskWeights};
+pub use travel_rule::{TravelRuleData, TravelRuleValidator};
 
 // Re-export extractors
 pub use extractors::evm::EvmExtractor;
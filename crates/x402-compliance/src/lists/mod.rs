@@ -1,16 +1,50 @@
 pub mod blacklist;
 pub mod ofac;
+pub mod ofac_sdn;
 
 use crate::checker::ListMetadata;
+use std::sync::Arc;
+
+/// Details of the party behind a sanctioned address
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SanctionedEntity {
+    pub name: Option<String>,
+    pub id: Option<String>,
+    pub program: Option<String>,
+}
 
 /// Trait that all sanctions lists must implement
 pub trait SanctionsList: Send + Sync {
     /// Check if an address is sanctioned
     fn is_sanctioned(&self, address: &str) -> bool;
 
+    /// Get the party behind a sanctioned address, if the list records it
+    fn sanctioned_entity(&self, _address: &str) -> Option<SanctionedEntity> {
+        None
+    }
+
     /// Get metadata about this list
     fn metadata(&self) -> ListMetadata;
 
     /// Get the total number of addresses in the list
     fn total_addresses(&self) -> usize;
 }
+
+/// Lets a list refreshed in the background be shared with a checker
+impl<T: SanctionsList + ?Sized> SanctionsList for Arc<T> {
+    fn is_sanctioned(&self, address: &str) -> bool {
+        (**self).is_sanctioned(address)
+    }
+
+    fn sanctioned_entity(&self, address: &str) -> Option<SanctionedEntity> {
+        (**self).sanctioned_entity(address)
+    }
+
+    fn metadata(&self) -> ListMetadata {
+        (**self).metadata()
+    }
+
+    fn total_addresses(&self) -> usize {
+        (**self).total_addresses()
+    }
+}
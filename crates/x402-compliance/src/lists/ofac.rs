@@ -1,7 +1,7 @@
 use crate::checker::ListMetadata;
 use crate::config::ListConfig;
 use crate::error::{ComplianceError, Result};
-use crate::lists::SanctionsList;
+use crate::lists::{SanctionedEntity, SanctionsList};
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use std::collections::HashSet;
@@ -141,6 +141,14 @@ impl SanctionsList for OfacList {
         is_sanctioned
     }
 
+    fn sanctioned_entity(&self, address: &str) -> Option<SanctionedEntity> {
+        self.get_entity_info(address).map(|entry| SanctionedEntity {
+            name: Some(entry.entity_name.clone()),
+            id: Some(entry.entity_id.clone()),
+            program: Some(entry.reason.clone()),
+        })
+    }
+
     fn metadata(&self) -> ListMetadata {
         ListMetadata {
             name: "OFAC_SDN".to_string(),
@@ -0,0 +1,416 @@
+//! Live OFAC SDN list, read from the Treasury's `sdn_advanced.xml`.
+//!
+//! The advanced format lists digital currency addresses as profile features whose
+//! feature type is named `Digital Currency Address - <ticker>`. Each address is cached
+//! with the name of the profile's primary alias and the programs of its sanctions entry.
+//! [`OfacSdnSource::spawn_refresh`] re-downloads the list every refresh interval.
+
+use crate::checker::ListMetadata;
+use crate::error::{ComplianceError, Result};
+use crate::lists::{SanctionedEntity, SanctionsList};
+use dashmap::DashMap;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+/// Where the Treasury publishes the SDN list in the advanced XML format.
+pub const SDN_ADVANCED_URL: &str = "https://www.treasury.gov/ofac/downloads/sdn_advanced.xml";
+
+/// Feature type name prefix of digital currency addresses.
+const DIGITAL_CURRENCY_PREFIX: &str = "Digital Currency Address - ";
+
+/// A sanctioned address from the SDN list
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SdnAddress {
+    /// The address as published
+    pub address: String,
+    /// Currency ticker from the feature type (e.g. "ETH", "XBT", "USDC")
+    pub currency: String,
+    /// Primary name of the sanctioned party
+    pub entity_name: String,
+    /// SDN profile ID
+    pub entity_id: String,
+    /// Sanctions programs (e.g. "CYBER2", "DPRK3")
+    pub programs: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+struct LoadState {
+    checksum: String,
+    loaded_at: Instant,
+    last_updated: chrono::DateTime<chrono::Utc>,
+}
+
+/// OFAC SDN list downloaded from the Treasury and kept in memory
+pub struct OfacSdnSource {
+    url: String,
+    refresh_interval: Duration,
+    client: reqwest::Client,
+    /// Sanctioned addresses, keyed by lowercase address
+    addresses: DashMap<String, SdnAddress>,
+    state: RwLock<Option<LoadState>>,
+}
+
+impl OfacSdnSource {
+    /// Source reading `url` and refreshing it every `refresh_interval`
+    pub fn new(url: impl Into<String>, refresh_interval: Duration) -> Self {
+        Self {
+            url: url.into(),
+            refresh_interval,
+            client: reqwest::Client::new(),
+            addresses: DashMap::new(),
+            state: RwLock::new(None),
+        }
+    }
+
+    /// Replace the cached addresses with those of an `sdn_advanced.xml` document
+    pub fn load_xml(&self, xml: &str) -> Result<usize> {
+        let parsed = parse_sdn_advanced(xml)?;
+
+        let mut hasher = Sha256::new();
+        hasher.update(xml.as_bytes());
+        let checksum = format!("{:x}", hasher.finalize());
+
+        let current: HashSet<String> = parsed.iter().map(|a| a.address.to_lowercase()).collect();
+        self.addresses.retain(|key, _| current.contains(key));
+        for address in parsed {
+            self.addresses
+                .insert(address.address.to_lowercase(), address);
+        }
+        *self.state.write().unwrap_or_else(|e| e.into_inner()) = Some(LoadState {
+            checksum,
+            loaded_at: Instant::now(),
+            last_updated: chrono::Utc::now(),
+        });
+
+        tracing::info!(
+            "Loaded OFAC SDN list: {} digital currency addresses",
+            self.addresses.len()
+        );
+        Ok(self.addresses.len())
+    }
+
+    /// Download the list and replace the cached addresses
+    pub async fn refresh(&self) -> Result<usize> {
+        tracing::info!("Downloading OFAC SDN list from: {}", self.url);
+        let response = self
+            .client
+            .get(&self.url)
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| {
+                ComplianceError::ListLoadError(format!("Failed to download OFAC SDN list: {}", e))
+            })?;
+        let xml = response.text().await.map_err(|e| {
+            ComplianceError::ListLoadError(format!("Failed to read OFAC SDN list: {}", e))
+        })?;
+        self.load_xml(&xml)
+    }
+
+    /// Whether the list was never loaded or is older than the refresh interval
+    pub fn needs_refresh(&self) -> bool {
+        match &*self.state.read().unwrap_or_else(|e| e.into_inner()) {
+            Some(state) => state.loaded_at.elapsed() >= self.refresh_interval,
+            None => true,
+        }
+    }
+
+    /// Refresh the list in the background whenever it becomes stale.
+    ///
+    /// A failed download keeps the previous addresses and is retried after a minute.
+    pub fn spawn_refresh(self: Arc<Self>) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            loop {
+                let wait = if !self.needs_refresh() {
+                    self.refresh_interval
+                } else {
+                    match self.refresh().await {
+                        Ok(_) => self.refresh_interval,
+                        Err(e) => {
+                            tracing::error!("OFAC SDN refresh failed: {}", e);
+                            Duration::from_secs(60).min(self.refresh_interval)
+                        }
+                    }
+                };
+                tokio::time::sleep(wait).await;
+            }
+        })
+    }
+
+    /// SDN entry of a sanctioned address
+    pub fn get_entity_info(&self, address: &str) -> Option<SdnAddress> {
+        self.addresses
+            .get(&address.trim().to_lowercase())
+            .map(|entry| entry.clone())
+    }
+}
+
+impl SanctionsList for OfacSdnSource {
+    fn is_sanctioned(&self, address: &str) -> bool {
+        let is_sanctioned = self.addresses.contains_key(&address.trim().to_lowercase());
+
+        if is_sanctioned {
+            tracing::warn!("OFAC SDN ALERT: Sanctioned address detected: {}", address);
+        }
+
+        is_sanctioned
+    }
+
+    fn sanctioned_entity(&self, address: &str) -> Option<SanctionedEntity> {
+        self.get_entity_info(address).map(|entry| SanctionedEntity {
+            name: Some(entry.entity_name),
+            id: Some(entry.entity_id),
+            program: (!entry.programs.is_empty()).then(|| entry.programs.join(", ")),
+        })
+    }
+
+    fn metadata(&self) -> ListMetadata {
+        let state = self.state.read().unwrap_or_else(|e| e.into_inner());
+        ListMetadata {
+            name: "OFAC_SDN".to_string(),
+            enabled: true,
+            record_count: self.addresses.len(),
+            last_updated: state.as_ref().map(|s| s.last_updated),
+            checksum: state.as_ref().map(|s| s.checksum.clone()),
+            source_url: self.url.clone(),
+        }
+    }
+
+    fn total_addresses(&self) -> usize {
+        self.addresses.len()
+    }
+}
+
+impl std::fmt::Debug for OfacSdnSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OfacSdnSource")
+            .field("url", &self.url)
+            .field("refresh_interval", &self.refresh_interval)
+            .field("addresses", &self.addresses.len())
+            .finish()
+    }
+}
+
+/// Where the parser is within the document
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Text {
+    None,
+    FeatureType,
+    NamePart,
+    Address,
+    MeasureComment,
+}
+
+/// Extract digital currency addresses from an `sdn_advanced.xml` document
+pub fn parse_sdn_advanced(xml: &str) -> Result<Vec<SdnAddress>> {
+    let mut reader = Reader::from_str(xml);
+    reader.config_mut().trim_text(true);
+
+    // Feature type ID -> currency ticker
+    let mut currency_types: HashMap<String, String> = HashMap::new();
+    // Profile ID -> primary name
+    let mut names: HashMap<String, String> = HashMap::new();
+    // Profile ID -> programs
+    let mut programs: HashMap<String, Vec<String>> = HashMap::new();
+    // (profile ID, currency, address)
+    let mut found: Vec<(String, String, String)> = Vec::new();
+
+    let mut text = Text::None;
+    let mut feature_type_id: Option<String> = None;
+    let mut profile: Option<String> = None;
+    let mut in_primary_alias = false;
+    let mut name_parts: Vec<String> = Vec::new();
+    let mut feature_currency: Option<String> = None;
+    let mut entry_profile: Option<String> = None;
+
+    loop {
+        let event = reader.read_event().map_err(|e| {
+            ComplianceError::ListLoadError(format!(
+                "Failed to parse OFAC SDN XML at byte {}: {}",
+                reader.buffer_position(),
+                e
+            ))
+        })?;
+        match event {
+            Event::Start(e) => match e.local_name().as_ref() {
+                b"FeatureType" => {
+                    feature_type_id = attribute(&e, b"ID");
+                    text = Text::FeatureType;
+                }
+                b"Profile" => profile = attribute(&e, b"ID"),
+                b"Alias" => in_primary_alias = attribute(&e, b"Primary").as_deref() == Some("true"),
+                b"NamePartValue" if in_primary_alias => text = Text::NamePart,
+                b"Feature" => {
+                    feature_currency = attribute(&e, b"FeatureTypeID")
+                        .and_then(|id| currency_types.get(&id).cloned());
+                }
+                b"VersionDetail" if feature_currency.is_some() => text = Text::Address,
+                b"SanctionsEntry" => entry_profile = attribute(&e, b"ProfileID"),
+                b"Comment" if entry_profile.is_some() => text = Text::MeasureComment,
+                _ => {}
+            },
+            Event::Text(t) => {
+                let value = t
+                    .unescape()
+                    .map_err(|e| {
+                        ComplianceError::ListLoadError(format!(
+                            "Invalid text in OFAC SDN XML: {}",
+                            e
+                        ))
+                    })?
+                    .trim()
+                    .to_string();
+                match text {
+                    Text::FeatureType => {
+                        if let (Some(id), Some(ticker)) = (
+                            feature_type_id.take(),
+                            value.strip_prefix(DIGITAL_CURRENCY_PREFIX),
+                        ) {
+                            currency_types.insert(id, ticker.trim().to_string());
+                        }
+                    }
+                    Text::NamePart => name_parts.push(value),
+                    Text::Address => {
+                        if let (Some(profile), Some(currency)) = (&profile, &feature_currency) {
+                            if !value.is_empty() {
+                                found.push((profile.clone(), currency.clone(), value));
+                            }
+                        }
+                    }
+                    Text::MeasureComment => {
+                        if let Some(profile) = &entry_profile {
+                            if !value.is_empty() {
+                                programs.entry(profile.clone()).or_default().push(value);
+                            }
+                        }
+                    }
+                    Text::None => {}
+                }
+            }
+            Event::End(e) => match e.local_name().as_ref() {
+                b"FeatureType" | b"NamePartValue" | b"VersionDetail" | b"Comment" => {
+                    text = Text::None
+                }
+                b"DocumentedName" if in_primary_alias => {
+                    if let Some(profile) = &profile {
+                        if !name_parts.is_empty() && !names.contains_key(profile) {
+                            names.insert(profile.clone(), name_parts.join(" "));
+                        }
+                    }
+                    name_parts.clear();
+                }
+                b"Alias" => in_primary_alias = false,
+                b"Feature" => feature_currency = None,
+                b"Profile" => profile = None,
+                b"SanctionsEntry" => entry_profile = None,
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    Ok(found
+        .into_iter()
+        .map(|(profile, currency, address)| SdnAddress {
+            address,
+            currency,
+            entity_name: names.get(&profile).cloned().unwrap_or_default(),
+            programs: programs.get(&profile).cloned().unwrap_or_default(),
+            entity_id: profile,
+        })
+        .collect())
+}
+
+fn attribute(element: &BytesStart, name: &[u8]) -> Option<String> {
+    element
+        .attributes()
+        .flatten()
+        .find(|a| a.key.local_name().as_ref() == name)
+        .and_then(|a| a.unescape_value().ok().map(|v| v.into_owned()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SDN: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<Sanctions xmlns="http://www.un.org/sanctions/1.0">
+  <ReferenceValueSets>
+    <FeatureTypeValues>
+      <FeatureType ID="25" FeatureTypeGroupID="1">Place of Birth</FeatureType>
+      <FeatureType ID="345" FeatureTypeGroupID="4">Digital Currency Address - ETH</FeatureType>
+    </FeatureTypeValues>
+  </ReferenceValueSets>
+  <DistinctParties>
+    <DistinctParty FixedRef="100">
+      <Profile ID="100" PartySubTypeID="4">
+        <Identity ID="1" FixedRef="100" Primary="true" False="false">
+          <Alias FixedRef="100" AliasTypeID="1403" Primary="false" LowQuality="false">
+            <DocumentedName ID="2" FixedRef="100" DocNameStatusID="1">
+              <DocumentedNamePart><NamePartValue NamePartGroupID="1">ALIAS NAME</NamePartValue></DocumentedNamePart>
+            </DocumentedName>
+          </Alias>
+          <Alias FixedRef="100" AliasTypeID="1403" Primary="true" LowQuality="false">
+            <DocumentedName ID="3" FixedRef="100" DocNameStatusID="1">
+              <DocumentedNamePart><NamePartValue NamePartGroupID="1">EXAMPLE</NamePartValue></DocumentedNamePart>
+              <DocumentedNamePart><NamePartValue NamePartGroupID="2">MIXER</NamePartValue></DocumentedNamePart>
+            </DocumentedName>
+          </Alias>
+        </Identity>
+        <Feature ID="4" FeatureTypeID="25">
+          <FeatureVersion ID="5"><VersionDetail DetailTypeID="1432">Nowhere</VersionDetail></FeatureVersion>
+        </Feature>
+        <Feature ID="6" FeatureTypeID="345">
+          <FeatureVersion ID="7"><VersionDetail DetailTypeID="1432">0xAbC0000000000000000000000000000000000001</VersionDetail></FeatureVersion>
+        </Feature>
+      </Profile>
+    </DistinctParty>
+  </DistinctParties>
+  <SanctionsEntries>
+    <SanctionsEntry ID="8" ProfileID="100" ListID="1550">
+      <SanctionsMeasure ID="9" SanctionsTypeID="1"><Comment>CYBER2</Comment></SanctionsMeasure>
+    </SanctionsEntry>
+  </SanctionsEntries>
+</Sanctions>"#;
+
+    #[test]
+    fn test_parse_extracts_digital_currency_addresses() {
+        let parsed = parse_sdn_advanced(SDN).unwrap();
+        assert_eq!(
+            parsed,
+            vec![SdnAddress {
+                address: "0xAbC0000000000000000000000000000000000001".to_string(),
+                currency: "ETH".to_string(),
+                entity_name: "EXAMPLE MIXER".to_string(),
+                entity_id: "100".to_string(),
+                programs: vec!["CYBER2".to_string()],
+            }]
+        );
+    }
+
+    #[test]
+    fn test_reload_replaces_addresses() {
+        let source = OfacSdnSource::new(SDN_ADVANCED_URL, Duration::from_secs(3600));
+        assert!(source.needs_refresh());
+        source.load_xml(SDN).unwrap();
+        assert!(!source.needs_refresh());
+        assert!(source.is_sanctioned("0xabc0000000000000000000000000000000000001"));
+
+        let delisted = SDN.replace("0xAbC0000000000000000000000000000000000001", "0x02");
+        source.load_xml(&delisted).unwrap();
+        assert!(!source.is_sanctioned("0xabc0000000000000000000000000000000000001"));
+        assert!(source.is_sanctioned("0x02"));
+        assert_eq!(source.total_addresses(), 1);
+    }
+
+    #[test]
+    fn test_malformed_xml_is_rejected() {
+        let source = OfacSdnSource::new(SDN_ADVANCED_URL, Duration::from_secs(3600));
+        assert!(source.load_xml("<Sanctions><Feature></Sanctions>").is_err());
+    }
+}
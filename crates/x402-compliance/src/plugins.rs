@@ -0,0 +1,223 @@
+//! Custom screening rules run after the built-in lists.
+//!
+//! Plugins cover rules the generic sanctions lists do not, such as internal blocklists.
+//! They are added with [`ComplianceCheckerBuilder::add_plugin`] and run in the order they
+//! were added; the first one to block a payment wins.
+//!
+//! [`ComplianceCheckerBuilder::add_plugin`]: crate::ComplianceCheckerBuilder::add_plugin
+
+use crate::checker::{ScreeningDecision, TransactionContext};
+use crate::error::{ComplianceError, Result};
+use async_trait::async_trait;
+use regex::Regex;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use tokio::sync::RwLock;
+
+/// Screening rule run on the payer and payee of every payment
+#[async_trait]
+pub trait ScreeningPlugin: Send + Sync {
+    /// Name reported as the list source of the plugin's hits
+    fn name(&self) -> &str;
+
+    /// Acquire resources; called once when the checker is built
+    async fn on_load(&self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Release resources; called when the checker shuts down
+    async fn on_unload(&self) {}
+
+    /// Decision for `address`, or `None` to leave it to the other checks
+    async fn screen(
+        &self,
+        address: &str,
+        context: &TransactionContext,
+    ) -> Option<ScreeningDecision>;
+}
+
+/// Blocks addresses matching any of a set of patterns, for testing and development
+pub struct RegexPlugin {
+    patterns: Vec<Regex>,
+}
+
+impl RegexPlugin {
+    pub fn new<I, S>(patterns: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let patterns = patterns
+            .into_iter()
+            .map(|pattern| {
+                Regex::new(pattern.as_ref()).map_err(|e| {
+                    ComplianceError::ConfigError(format!("Invalid plugin pattern: {}", e))
+                })
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { patterns })
+    }
+}
+
+#[async_trait]
+impl ScreeningPlugin for RegexPlugin {
+    fn name(&self) -> &str {
+        "regex"
+    }
+
+    async fn screen(
+        &self,
+        address: &str,
+        _context: &TransactionContext,
+    ) -> Option<ScreeningDecision> {
+        let pattern = self
+            .patterns
+            .iter()
+            .find(|pattern| pattern.is_match(address))?;
+        Some(ScreeningDecision::Block {
+            reason: format!("Address matches pattern {}", pattern),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DenyListEntry {
+    pub address: String,
+    pub reason: String,
+}
+
+/// Blocks the addresses of a static `deny_list.json`:
+///
+/// ```json
+/// [{ "address": "0x...", "reason": "Internal fraud report" }]
+/// ```
+pub struct JsonFilePlugin {
+    path: PathBuf,
+    entries: RwLock<HashMap<String, String>>,
+}
+
+impl JsonFilePlugin {
+    /// Plugin reading `path` when it is loaded
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            entries: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Number of denied addresses currently loaded
+    pub async fn len(&self) -> usize {
+        self.entries.read().await.len()
+    }
+
+    pub async fn is_empty(&self) -> bool {
+        self.entries.read().await.is_empty()
+    }
+}
+
+#[async_trait]
+impl ScreeningPlugin for JsonFilePlugin {
+    fn name(&self) -> &str {
+        "deny_list"
+    }
+
+    async fn on_load(&self) -> Result<()> {
+        let content = std::fs::read_to_string(&self.path).map_err(|e| {
+            ComplianceError::ListLoadError(format!("Failed to read deny list file: {}", e))
+        })?;
+        let entries: Vec<DenyListEntry> = serde_json::from_str(&content).map_err(|e| {
+            ComplianceError::ListLoadError(format!("Failed to parse deny list JSON: {}", e))
+        })?;
+
+        let entries: HashMap<_, _> = entries
+            .into_iter()
+            .map(|entry| (entry.address.trim().to_lowercase(), entry.reason))
+            .filter(|(address, _)| !address.is_empty())
+            .collect();
+        tracing::info!("Loaded deny list: {} addresses", entries.len());
+        *self.entries.write().await = entries;
+        Ok(())
+    }
+
+    async fn on_unload(&self) {
+        self.entries.write().await.clear();
+    }
+
+    async fn screen(
+        &self,
+        address: &str,
+        _context: &TransactionContext,
+    ) -> Option<ScreeningDecision> {
+        let entries = self.entries.read().await;
+        let reason = entries.get(&address.trim().to_lowercase())?;
+        Some(ScreeningDecision::Block {
+            reason: format!("Address is on deny list: {}", reason),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    const DENIED: &str = "0x1111111111111111111111111111111111111111";
+
+    fn context() -> TransactionContext {
+        TransactionContext {
+            amount: "1000000".to_string(),
+            currency: "USDC".to_string(),
+            network: "base".to_string(),
+            transaction_id: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_regex_plugin_blocks_matches() {
+        let plugin = RegexPlugin::new(["^0x0000", "dead$"]).unwrap();
+
+        assert!(matches!(
+            plugin.screen("0x000012345678", &context()).await,
+            Some(ScreeningDecision::Block { .. })
+        ));
+        assert!(plugin.screen("0xbeefdead", &context()).await.is_some());
+        assert!(plugin.screen(DENIED, &context()).await.is_none());
+        assert!(RegexPlugin::new(["(unclosed"]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_json_file_plugin_lifecycle() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        write!(
+            file,
+            r#"[{{"address": "{}", "reason": "Chargeback fraud"}}]"#,
+            DENIED.to_uppercase()
+        )
+        .unwrap();
+        let plugin = JsonFilePlugin::new(file.path());
+
+        assert!(plugin.screen(DENIED, &context()).await.is_none());
+        plugin.on_load().await.unwrap();
+        assert_eq!(plugin.len().await, 1);
+        match plugin.screen(DENIED, &context()).await {
+            Some(ScreeningDecision::Block { reason }) => {
+                assert_eq!(reason, "Address is on deny list: Chargeback fraud")
+            }
+            decision => panic!("expected block, got {:?}", decision),
+        }
+
+        plugin.on_unload().await;
+        assert!(plugin.is_empty().await);
+        assert!(plugin.screen(DENIED, &context()).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_json_file_plugin_missing_file_fails_to_load() {
+        let plugin = JsonFilePlugin::new("/nonexistent/deny_list.json");
+        assert!(matches!(
+            plugin.on_load().await,
+            Err(ComplianceError::ListLoadError(_))
+        ));
+    }
+}
@@ -0,0 +1,237 @@
+use crate::checker::{ScreeningDecision, ScreeningResult};
+use serde::{Deserialize, Serialize};
+
+/// Wallets younger than this many days count as new
+pub const NEW_WALLET_DAYS: u32 = 30;
+
+/// Highest possible risk score
+pub const MAX_RISK_SCORE: u8 = 100;
+
+/// Points added by each risk factor
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RiskWeights {
+    /// Address is on an OFAC sanctions list
+    pub ofac_match: u8,
+    /// Address moved high volumes through mixers
+    pub mixer_exposure: u8,
+    /// Wallet is younger than [`NEW_WALLET_DAYS`]
+    pub new_wallet: u8,
+    /// Per counterparty flagged by risk teams
+    pub flagged_counterparty: u8,
+}
+
+impl Default for RiskWeights {
+    fn default() -> Self {
+        Self {
+            ofac_match: 50,
+            mixer_exposure: 30,
+            new_wallet: 20,
+            flagged_counterparty: 10,
+        }
+    }
+}
+
+/// Signals about an address that screening lists don't carry
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RiskContext {
+    /// Address moved high volumes through mixers
+    pub high_volume_mixer: bool,
+    /// Days since the wallet's first transaction, when known
+    pub wallet_age_days: Option<u32>,
+    /// Number of counterparties flagged by risk teams
+    pub flagged_counterparties: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RiskFactorKind {
+    OfacMatch,
+    MixerExposure,
+    NewWallet,
+    FlaggedCounterparties,
+}
+
+/// One contribution to a risk score
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RiskFactor {
+    pub kind: RiskFactorKind,
+    pub points: u8,
+    pub detail: String,
+}
+
+/// Risk of an address from 0 (none found) to [`MAX_RISK_SCORE`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RiskScore {
+    /// Sum of the breakdown points, capped at [`MAX_RISK_SCORE`]
+    pub score: u8,
+    pub breakdown: Vec<RiskFactor>,
+}
+
+impl RiskScore {
+    /// Block decision when the score is above `threshold`
+    pub fn threshold_decision(&self, threshold: Option<u8>) -> Option<ScreeningDecision> {
+        let threshold = threshold.filter(|threshold| self.score > *threshold)?;
+        Some(ScreeningDecision::Block {
+            reason: format!("Risk score {} is above threshold {}", self.score, threshold),
+        })
+    }
+}
+
+/// Turns screening results and risk signals into a [`RiskScore`]
+#[derive(Debug, Clone, Default)]
+pub struct RiskScorer {
+    weights: RiskWeights,
+}
+
+impl RiskScorer {
+    pub fn new(weights: RiskWeights) -> Self {
+        Self { weights }
+    }
+
+    pub fn score(&self, result: &ScreeningResult, context: &RiskContext) -> RiskScore {
+        let mut breakdown = Vec::new();
+
+        let ofac_lists: Vec<&str> = result
+            .matched_entities
+            .iter()
+            .map(|entity| entity.list_source.as_str())
+            .filter(|source| source.starts_with("OFAC"))
+            .collect();
+        if let Some(list) = ofac_lists.first() {
+            breakdown.push(RiskFactor {
+                kind: RiskFactorKind::OfacMatch,
+                points: self.weights.ofac_match,
+                detail: format!("Address is on the {} list", list),
+            });
+        }
+
+        if context.high_volume_mixer {
+            breakdown.push(RiskFactor {
+                kind: RiskFactorKind::MixerExposure,
+                points: self.weights.mixer_exposure,
+                detail: "High-volume mixer activity".to_string(),
+            });
+        }
+
+        if let Some(age) = context.wallet_age_days.filter(|age| *age < NEW_WALLET_DAYS) {
+            breakdown.push(RiskFactor {
+                kind: RiskFactorKind::NewWallet,
+                points: self.weights.new_wallet,
+                detail: format!("Wallet is {} days old", age),
+            });
+        }
+
+        if context.flagged_counterparties > 0 {
+            let points = u32::from(self.weights.flagged_counterparty)
+                .saturating_mul(context.flagged_counterparties)
+                .min(u32::from(MAX_RISK_SCORE)) as u8;
+            breakdown.push(RiskFactor {
+                kind: RiskFactorKind::FlaggedCounterparties,
+                points,
+                detail: format!("{} flagged counterparties", context.flagged_counterparties),
+            });
+        }
+
+        let total: u32 = breakdown.iter().map(|factor| u32::from(factor.points)).sum();
+        RiskScore {
+            score: total.min(u32::from(MAX_RISK_SCORE)) as u8,
+            breakdown,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checker::{AddressType, MatchedEntity};
+    use proptest::prelude::*;
+    use std::collections::HashMap;
+
+    fn result(list_sources: &[&str]) -> ScreeningResult {
+        ScreeningResult {
+            decision: ScreeningDecision::Clear,
+            payer_address: "0x1234".to_string(),
+            payee_address: String::new(),
+            matched_entities: list_sources
+                .iter()
+                .map(|source| MatchedEntity {
+                    address: "0x1234".to_string(),
+                    address_type: AddressType::Payer,
+                    list_source: source.to_string(),
+                    entity_name: None,
+                    entity_id: None,
+                    program: None,
+                })
+                .collect(),
+            list_versions: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_clean_address_scores_zero() {
+        let score = RiskScorer::default().score(&result(&[]), &RiskContext::default());
+        assert_eq!(score.score, 0);
+        assert!(score.breakdown.is_empty());
+        assert!(score.threshold_decision(Some(0)).is_none());
+    }
+
+    #[test]
+    fn test_factors_add_up() {
+        let context = RiskContext {
+            high_volume_mixer: false,
+            wallet_age_days: Some(3),
+            flagged_counterparties: 2,
+        };
+        let score = RiskScorer::default().score(&result(&[]), &context);
+        assert_eq!(score.score, 40);
+        let kinds: Vec<RiskFactorKind> = score.breakdown.iter().map(|f| f.kind).collect();
+        assert_eq!(kinds, [RiskFactorKind::NewWallet, RiskFactorKind::FlaggedCounterparties]);
+
+        assert!(score.threshold_decision(Some(40)).is_none());
+        assert!(matches!(
+            score.threshold_decision(Some(39)),
+            Some(ScreeningDecision::Block { .. })
+        ));
+        assert!(score.threshold_decision(None).is_none());
+    }
+
+    #[test]
+    fn test_ofac_match_counts_once_and_score_is_capped() {
+        let context = RiskContext {
+            high_volume_mixer: true,
+            wallet_age_days: Some(NEW_WALLET_DAYS),
+            flagged_counterparties: 3,
+        };
+        let score = RiskScorer::default().score(&result(&["OFAC_SDN", "OFAC_SDN", "blacklist"]), &context);
+        // 50 + 30 + 30, the wallet is not new anymore
+        assert_eq!(score.score, MAX_RISK_SCORE);
+        assert_eq!(score.breakdown.len(), 3);
+        assert_eq!(score.breakdown[0].kind, RiskFactorKind::OfacMatch);
+    }
+
+    proptest! {
+        #[test]
+        fn prop_score_is_within_bounds(
+            weights in (any::<u8>(), any::<u8>(), any::<u8>(), any::<u8>()),
+            ofac in any::<bool>(),
+            high_volume_mixer in any::<bool>(),
+            wallet_age_days in proptest::option::of(any::<u32>()),
+            flagged_counterparties in any::<u32>(),
+        ) {
+            let scorer = RiskScorer::new(RiskWeights {
+                ofac_match: weights.0,
+                mixer_exposure: weights.1,
+                new_wallet: weights.2,
+                flagged_counterparty: weights.3,
+            });
+            let context = RiskContext { high_volume_mixer, wallet_age_days, flagged_counterparties };
+            let sources: &[&str] = if ofac { &["OFAC_SDN"] } else { &[] };
+            let score = scorer.score(&result(sources), &context);
+
+            prop_assert!(score.score <= MAX_RISK_SCORE);
+            let total: u32 = score.breakdown.iter().map(|f| u32::from(f.points)).sum();
+            prop_assert_eq!(u32::from(score.score), total.min(u32::from(MAX_RISK_SCORE)));
+        }
+    }
+}
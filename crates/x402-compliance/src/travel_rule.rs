@@ -0,0 +1,175 @@
+use crate::error::{ComplianceError, Result};
+use serde::{Deserialize, Serialize};
+
+/// Payments from this many USD on need travel rule data by default
+pub const DEFAULT_TRAVEL_RULE_THRESHOLD_USD: f64 = 1000.0;
+
+/// Key of [`TravelRuleData`] in the `extra` object of payment requirements
+pub const TRAVEL_RULE_EXTRA_KEY: &str = "travelRule";
+
+/// Originator and beneficiary information required by the FATF Travel Rule
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TravelRuleData {
+    /// Name of the person or entity sending the payment
+    pub originator_name: String,
+    /// Account of the originator at their VASP (wallet address or account number)
+    pub originator_account: String,
+    /// VASP serving the beneficiary
+    pub beneficiary_vasp: String,
+}
+
+impl TravelRuleData {
+    /// Read the data from the `extra` object of payment requirements
+    ///
+    /// Returns `None` when `extra` has no `travelRule` entry or it is malformed.
+    pub fn from_extra(extra: Option<&serde_json::Value>) -> Option<Self> {
+        let data = extra?.get(TRAVEL_RULE_EXTRA_KEY)?;
+        serde_json::from_value(data.clone()).ok()
+    }
+
+    /// Names of the fields left blank
+    fn blank_fields(&self) -> Vec<&'static str> {
+        [
+            ("originator_name", &self.originator_name),
+            ("originator_account", &self.originator_account),
+            ("beneficiary_vasp", &self.beneficiary_vasp),
+        ]
+        .into_iter()
+        .filter(|(_, value)| value.trim().is_empty())
+        .map(|(name, _)| name)
+        .collect()
+    }
+}
+
+/// Requires [`TravelRuleData`] on payments of at least a USD threshold
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TravelRuleValidator {
+    threshold_usd: Option<f64>,
+}
+
+impl Default for TravelRuleValidator {
+    fn default() -> Self {
+        Self::new(Some(DEFAULT_TRAVEL_RULE_THRESHOLD_USD))
+    }
+}
+
+impl TravelRuleValidator {
+    /// Validator for payments from `threshold_usd` on; `None` requires no data
+    pub fn new(threshold_usd: Option<f64>) -> Self {
+        Self { threshold_usd }
+    }
+
+    /// Whether a payment worth `amount_usd` needs travel rule data
+    pub fn requires_data(&self, amount_usd: f64) -> bool {
+        self.threshold_usd
+            .is_some_and(|threshold| amount_usd >= threshold)
+    }
+
+    /// Check that a payment worth `amount_usd` carries complete data when it needs it
+    ///
+    /// Pass `f64::INFINITY` for a payment that cannot be priced, so it needs the data
+    /// whenever a threshold is set.
+    pub fn validate(&self, amount_usd: f64, data: Option<&TravelRuleData>) -> Result<()> {
+        if !self.requires_data(amount_usd) {
+            return Ok(());
+        }
+        let missing = match data {
+            None => vec!["originator_name", "originator_account", "beneficiary_vasp"],
+            Some(data) => data.blank_fields(),
+        };
+        if missing.is_empty() {
+            return Ok(());
+        }
+        let value = if amount_usd.is_finite() {
+            format!("{:.2} USD", amount_usd)
+        } else {
+            "unknown USD value".to_string()
+        };
+        Err(ComplianceError::MissingTravelRuleData(format!(
+            "payment of {} is at or above the {:.2} USD threshold, missing {}",
+            value,
+            self.threshold_usd.unwrap_or_default(),
+            missing.join(", ")
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn data() -> TravelRuleData {
+        TravelRuleData {
+            originator_name: "Alice Example".to_string(),
+            originator_account: "0x1234567890123456789012345678901234567890".to_string(),
+            beneficiary_vasp: "Example Exchange Ltd".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_below_threshold_passes_without_data() {
+        let validator = TravelRuleValidator::default();
+        assert!(validator.validate(0.0, None).is_ok());
+        assert!(validator.validate(999.99, None).is_ok());
+        assert!(TravelRuleValidator::new(None)
+            .validate(1_000_000.0, None)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_above_threshold_fails_without_data() {
+        let validator = TravelRuleValidator::default();
+        for amount in [1000.0, 2500.0, f64::INFINITY] {
+            let err = validator.validate(amount, None).unwrap_err();
+            assert!(matches!(err, ComplianceError::MissingTravelRuleData(_)));
+        }
+        assert!(validator.validate(1000.0, Some(&data())).is_ok());
+
+        let incomplete = TravelRuleData {
+            beneficiary_vasp: "  ".to_string(),
+            ..data()
+        };
+        let err = validator.validate(5000.0, Some(&incomplete)).unwrap_err();
+        assert!(
+            err.to_string().contains("missing beneficiary_vasp"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn test_from_extra() {
+        let extra = serde_json::json!({
+            "name": "USD Coin",
+            "travelRule": {
+                "originatorName": "Alice Example",
+                "originatorAccount": "0x1234567890123456789012345678901234567890",
+                "beneficiaryVasp": "Example Exchange Ltd"
+            }
+        });
+        assert_eq!(TravelRuleData::from_extra(Some(&extra)), Some(data()));
+
+        let malformed = serde_json::json!({"travelRule": {"originatorName": "Alice"}});
+        assert_eq!(TravelRuleData::from_extra(Some(&malformed)), None);
+        assert_eq!(TravelRuleData::from_extra(None), None);
+    }
+
+    #[tokio::test]
+    async fn test_checker_applies_default_threshold() {
+        let checker = crate::ComplianceCheckerBuilder::new()
+            .with_ofac(false)
+            .with_blacklist(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/tests/fixtures/blacklist.json"
+            ))
+            .build()
+            .await
+            .unwrap();
+        assert!(checker.check_travel_rule(10.0, None).is_ok());
+        assert!(matches!(
+            checker.check_travel_rule(DEFAULT_TRAVEL_RULE_THRESHOLD_USD, None),
+            Err(ComplianceError::MissingTravelRuleData(_))
+        ));
+        assert!(checker.check_travel_rule(1500.0, Some(&data())).is_ok());
+    }
+}
@@ -0,0 +1,145 @@
+//! Cross-chain correlation of addresses from a fixture of bridge transfers.
+
+use std::sync::Arc;
+use std::time::Duration;
+use x402_compliance::lists::ofac_sdn::{OfacSdnSource, SDN_ADVANCED_URL};
+use x402_compliance::{
+    ChainAddress, ComplianceChecker, ComplianceCheckerBuilder, CrossChainCorrelator,
+    ScreeningDecision, TransactionContext,
+};
+
+const TRANSFERS: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/tests/fixtures/bridge_transfers.json"
+);
+
+const SOLANA: &str = "solana:5eykt4UsFv8P8NJdTREpY1vzqKqZKvdp";
+const LAZARUS: &str = "0x098B716B8Aaf21512996dC57EB0615e2383E2f96";
+const LAZARUS_SOLANA: &str = "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM";
+/// Received funds bridged from Lazarus through Solana
+const BRIDGED_PAYER: &str = "0x2222222222222222222222222222222222222222";
+const ACROSS_SENDER: &str = "0x3333333333333333333333333333333333333333";
+const ACROSS_RECIPIENT: &str = "0x4444444444444444444444444444444444444444";
+const STARGATE_RECIPIENT: &str = "0x5555555555555555555555555555555555555555";
+/// Sent funds to Lazarus through a contract that is not a known bridge
+const UNKNOWN_BRIDGE_SENDER: &str = "0x6666666666666666666666666666666666666666";
+const PAYEE: &str = "0x1111111111111111111111111111111111111111";
+
+fn correlator() -> Arc<CrossChainCorrelator> {
+    let correlator = CrossChainCorrelator::default();
+    assert_eq!(correlator.load_transfers(TRANSFERS).unwrap(), 4);
+    Arc::new(correlator)
+}
+
+async fn checker(correlator: Option<Arc<CrossChainCorrelator>>) -> Box<dyn ComplianceChecker> {
+    let source = OfacSdnSource::new(SDN_ADVANCED_URL, Duration::from_secs(24 * 3600));
+    source
+        .load_xml(include_str!("fixtures/sdn_advanced.xml"))
+        .unwrap();
+    let mut builder = ComplianceCheckerBuilder::new()
+        .with_ofac(false)
+        .with_blacklist(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/blacklist.json"
+        ))
+        .with_sanctions_list(Box::new(Arc::new(source)));
+    if let Some(correlator) = correlator {
+        builder = builder.with_correlator(correlator);
+    }
+    builder.build().await.unwrap()
+}
+
+fn context() -> TransactionContext {
+    TransactionContext {
+        amount: "1000000".to_string(),
+        currency: "USDC".to_string(),
+        network: "base".to_string(),
+        transaction_id: None,
+    }
+}
+
+#[test]
+fn test_fixture_transfers_are_grouped() {
+    let groups = correlator().correlate(vec![
+        ("eip155:8453", BRIDGED_PAYER).into(),
+        ("eip155:8453", ACROSS_SENDER).into(),
+        (SOLANA, LAZARUS_SOLANA).into(),
+        ("eip155:1", STARGATE_RECIPIENT).into(),
+        ("eip155:8453", UNKNOWN_BRIDGE_SENDER).into(),
+        ("eip155:1", LAZARUS).into(),
+    ]);
+
+    assert_eq!(
+        groups,
+        vec![
+            vec![
+                ChainAddress::new("eip155:1", LAZARUS),
+                ChainAddress::new("eip155:8453", BRIDGED_PAYER),
+                ChainAddress::new(SOLANA, LAZARUS_SOLANA),
+            ],
+            vec![
+                ChainAddress::new("eip155:1", STARGATE_RECIPIENT),
+                ChainAddress::new("eip155:8453", ACROSS_SENDER),
+            ],
+        ]
+    );
+}
+
+#[test]
+fn test_correlated_addresses_follow_chained_bridges() {
+    let correlator = correlator();
+    assert_eq!(
+        correlator.correlated_addresses(ACROSS_SENDER),
+        vec![
+            ChainAddress::new("eip155:1", STARGATE_RECIPIENT),
+            ChainAddress::new("eip155:42161", ACROSS_RECIPIENT),
+        ]
+    );
+    assert!(correlator
+        .correlated_addresses(UNKNOWN_BRIDGE_SENDER)
+        .is_empty());
+}
+
+#[tokio::test]
+async fn test_payer_linked_to_sanctioned_address_is_reviewed() {
+    let checker = checker(Some(correlator())).await;
+
+    let result = checker
+        .screen_payment(BRIDGED_PAYER, PAYEE, &context())
+        .await
+        .unwrap();
+    match &result.decision {
+        ScreeningDecision::Review { reason } => {
+            assert!(reason.contains(LAZARUS), "{reason}");
+            assert!(reason.contains("OFAC_SDN"), "{reason}");
+        }
+        other => panic!("payment should be reviewed, got {:?}", other),
+    }
+    assert_eq!(result.matched_entities.len(), 1);
+    let matched = &result.matched_entities[0];
+    assert_eq!(matched.address, LAZARUS);
+    assert_eq!(matched.entity_name.as_deref(), Some("LAZARUS GROUP"));
+
+    // Links through unknown contracts and clean groups do not flag payments
+    for payer in [UNKNOWN_BRIDGE_SENDER, ACROSS_SENDER] {
+        let result = checker
+            .screen_payment(payer, PAYEE, &context())
+            .await
+            .unwrap();
+        assert!(
+            matches!(result.decision, ScreeningDecision::Clear),
+            "{payer}: {:?}",
+            result.decision
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_correlation_is_optional() {
+    let checker = checker(None).await;
+    let result = checker
+        .screen_payment(BRIDGED_PAYER, PAYEE, &context())
+        .await
+        .unwrap();
+    assert!(matches!(result.decision, ScreeningDecision::Clear));
+}
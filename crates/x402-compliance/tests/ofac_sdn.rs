@@ -0,0 +1,167 @@
+//! Screening against the OFAC SDN list parsed from an `sdn_advanced.xml` fixture.
+
+use std::sync::Arc;
+use std::time::Duration;
+use x402_compliance::lists::ofac_sdn::{OfacSdnSource, SDN_ADVANCED_URL};
+use x402_compliance::lists::SanctionsList;
+use x402_compliance::{
+    ComplianceChecker, ComplianceCheckerBuilder, RiskContext, RiskFactorKind, ScreeningDecision,
+    TransactionContext,
+};
+
+const FIXTURE: &str = include_str!("fixtures/sdn_advanced.xml");
+
+const TORNADO_CASH: &str = "0x8589427373D6D84E98730D7795D8f6f8731FDA16";
+const TORNADO_CASH_USDC: &str = "0xd90e2f925DA726b50C4Ed8D0Fb90Ad053324F31b";
+const LAZARUS: &str = "0x098B716B8Aaf21512996dC57EB0615e2383E2f96";
+const CLEAN: &str = "0x1111111111111111111111111111111111111111";
+
+fn sdn_source() -> Arc<OfacSdnSource> {
+    let source = OfacSdnSource::new(SDN_ADVANCED_URL, Duration::from_secs(24 * 3600));
+    source.load_xml(FIXTURE).unwrap();
+    Arc::new(source)
+}
+
+async fn checker(source: Arc<OfacSdnSource>) -> Box<dyn ComplianceChecker> {
+    ComplianceCheckerBuilder::new()
+        .with_ofac(false)
+        .with_blacklist(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/blacklist.json"
+        ))
+        .with_sanctions_list(Box::new(source))
+        .build()
+        .await
+        .unwrap()
+}
+
+#[test]
+fn test_fixture_addresses_are_loaded() {
+    let source = sdn_source();
+    assert_eq!(source.total_addresses(), 3);
+    assert_eq!(source.metadata().name, "OFAC_SDN");
+    assert!(source.metadata().checksum.is_some());
+
+    let lazarus = source.get_entity_info(LAZARUS).unwrap();
+    assert_eq!(lazarus.entity_name, "LAZARUS GROUP");
+    assert_eq!(lazarus.entity_id, "23311");
+    assert_eq!(lazarus.currency, "ETH");
+    assert_eq!(lazarus.programs, vec!["DPRK3", "CYBER2"]);
+
+    let usdc = source.get_entity_info(TORNADO_CASH_USDC).unwrap();
+    assert_eq!(usdc.currency, "USDC");
+    assert_eq!(usdc.entity_name, "TORNADO CASH");
+}
+
+#[tokio::test]
+async fn test_sanctioned_addresses_are_blocked() {
+    let checker = checker(sdn_source()).await;
+    let lowercase = TORNADO_CASH.to_lowercase();
+
+    for address in [TORNADO_CASH, lowercase.as_str(), TORNADO_CASH_USDC, LAZARUS] {
+        match checker.screen_address(address).await.unwrap() {
+            ScreeningDecision::Block { reason } => {
+                assert!(reason.contains("OFAC_SDN"), "unexpected reason: {}", reason)
+            }
+            other => panic!("{} should be blocked, got {:?}", address, other),
+        }
+    }
+
+    assert!(matches!(
+        checker.screen_address(CLEAN).await.unwrap(),
+        ScreeningDecision::Clear
+    ));
+}
+
+#[tokio::test]
+async fn test_payment_to_sanctioned_payee_reports_entity() {
+    let checker = checker(sdn_source()).await;
+    let context = TransactionContext {
+        amount: "1000000".to_string(),
+        currency: "USDC".to_string(),
+        network: "base".to_string(),
+        transaction_id: None,
+    };
+
+    let result = checker
+        .screen_payment(CLEAN, LAZARUS, &context)
+        .await
+        .unwrap();
+
+    match &result.decision {
+        ScreeningDecision::Block { reason } => assert!(reason.contains("LAZARUS GROUP")),
+        other => panic!("payment should be blocked, got {:?}", other),
+    }
+    assert_eq!(result.matched_entities.len(), 1);
+    let matched = &result.matched_entities[0];
+    assert_eq!(matched.list_source, "OFAC_SDN");
+    assert_eq!(matched.entity_name.as_deref(), Some("LAZARUS GROUP"));
+    assert_eq!(matched.entity_id.as_deref(), Some("23311"));
+    assert_eq!(matched.program.as_deref(), Some("DPRK3, CYBER2"));
+}
+
+#[tokio::test]
+async fn test_risk_score_above_threshold_blocks() {
+    let config = tempfile::NamedTempFile::new().unwrap();
+    std::fs::write(
+        config.path(),
+        r#"
+            risk_threshold = 40
+
+            [lists.ofac]
+            enabled = false
+            path = "unused.json"
+            auto_update = false
+
+            [audit_logging]
+            enabled = false
+            target = "compliance_audit"
+            format = "Json"
+            include_clear_transactions = false
+
+            [fail_mode]
+            on_list_load_error = "Open"
+            on_screening_error = "Open"
+        "#,
+    )
+    .unwrap();
+    let checker = ComplianceCheckerBuilder::new()
+        .with_ofac(false)
+        .with_config_file(config.path())
+        .with_blacklist(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/blacklist.json"
+        ))
+        .with_sanctions_list(Box::new(sdn_source()))
+        .build()
+        .await
+        .unwrap();
+
+    let score = checker
+        .score_address(TORNADO_CASH, &RiskContext::default())
+        .await
+        .unwrap();
+    assert_eq!(score.score, 50);
+    assert_eq!(score.breakdown[0].kind, RiskFactorKind::OfacMatch);
+
+    // A young wallet alone stays under the threshold
+    let young = RiskContext {
+        wallet_age_days: Some(2),
+        ..RiskContext::default()
+    };
+    let (decision, score) = checker.screen_address_with_risk(CLEAN, &young).await.unwrap();
+    assert_eq!(score.score, 20);
+    assert!(matches!(decision, ScreeningDecision::Clear));
+
+    // Adding flagged counterparties pushes it over
+    let risky = RiskContext {
+        flagged_counterparties: 3,
+        ..young
+    };
+    let (decision, score) = checker.screen_address_with_risk(CLEAN, &risky).await.unwrap();
+    assert_eq!(score.score, 50);
+    match decision {
+        ScreeningDecision::Block { reason } => assert!(reason.contains("above threshold 40")),
+        other => panic!("address should be blocked, got {:?}", other),
+    }
+}
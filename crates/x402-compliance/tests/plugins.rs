@@ -0,0 +1,188 @@
+//! Screening plugins chained after the built-in checks.
+
+use async_trait::async_trait;
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use x402_compliance::{
+    ComplianceChecker, ComplianceCheckerBuilder, RegexPlugin, ScreeningDecision, ScreeningPlugin,
+    TransactionContext,
+};
+
+const PAYER: &str = "0x1111111111111111111111111111111111111111";
+const PAYEE: &str = "0x2222222222222222222222222222222222222222";
+const EMPTY_BLACKLIST: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/blacklist.json");
+
+/// Returns the same decision for every address and records its lifecycle
+struct FixedPlugin {
+    name: &'static str,
+    decision: ScreeningDecision,
+    loaded: AtomicBool,
+}
+
+impl FixedPlugin {
+    fn new(name: &'static str, decision: ScreeningDecision) -> Arc<Self> {
+        Arc::new(Self {
+            name,
+            decision,
+            loaded: AtomicBool::new(false),
+        })
+    }
+
+    fn block(name: &'static str) -> Arc<Self> {
+        Self::new(
+            name,
+            ScreeningDecision::Block {
+                reason: format!("blocked by {}", name),
+            },
+        )
+    }
+
+    fn review(name: &'static str) -> Arc<Self> {
+        Self::new(
+            name,
+            ScreeningDecision::Review {
+                reason: format!("reviewed by {}", name),
+            },
+        )
+    }
+}
+
+#[async_trait]
+impl ScreeningPlugin for FixedPlugin {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    async fn on_load(&self) -> x402_compliance::Result<()> {
+        self.loaded.store(true, Ordering::SeqCst);
+        Ok(())
+    }
+
+    async fn on_unload(&self) {
+        self.loaded.store(false, Ordering::SeqCst);
+    }
+
+    async fn screen(
+        &self,
+        _address: &str,
+        _context: &TransactionContext,
+    ) -> Option<ScreeningDecision> {
+        Some(self.decision.clone())
+    }
+}
+
+fn context() -> TransactionContext {
+    TransactionContext {
+        amount: "1000000".to_string(),
+        currency: "USDC".to_string(),
+        network: "base".to_string(),
+        transaction_id: None,
+    }
+}
+
+fn builder(plugins: &[Arc<FixedPlugin>]) -> ComplianceCheckerBuilder {
+    plugins.iter().fold(
+        ComplianceCheckerBuilder::new()
+            .with_ofac(false)
+            .with_blacklist(EMPTY_BLACKLIST),
+        |builder, plugin| builder.add_plugin(plugin.clone()),
+    )
+}
+
+async fn checker(plugins: &[Arc<FixedPlugin>]) -> Box<dyn ComplianceChecker> {
+    builder(plugins).build().await.unwrap()
+}
+
+fn reason(decision: &ScreeningDecision) -> &str {
+    match decision {
+        ScreeningDecision::Block { reason } | ScreeningDecision::Review { reason } => reason,
+        ScreeningDecision::Clear => "",
+    }
+}
+
+#[tokio::test]
+async fn test_first_blocking_plugin_wins() {
+    let checker = checker(&[
+        FixedPlugin::review("first"),
+        FixedPlugin::block("second"),
+        FixedPlugin::block("third"),
+    ])
+    .await;
+
+    let result = checker
+        .screen_payment(PAYER, PAYEE, &context())
+        .await
+        .unwrap();
+
+    assert!(matches!(result.decision, ScreeningDecision::Block { .. }));
+    assert_eq!(reason(&result.decision), "blocked by second");
+    assert_eq!(result.matched_entities.len(), 1);
+    assert_eq!(result.matched_entities[0].list_source, "second");
+    assert_eq!(result.matched_entities[0].address, PAYER);
+}
+
+#[tokio::test]
+async fn test_first_review_is_kept_without_blocks() {
+    let checker = checker(&[FixedPlugin::review("first"), FixedPlugin::review("second")]).await;
+
+    let result = checker
+        .screen_payment(PAYER, PAYEE, &context())
+        .await
+        .unwrap();
+
+    assert!(matches!(result.decision, ScreeningDecision::Review { .. }));
+    assert_eq!(reason(&result.decision), "reviewed by first");
+}
+
+#[tokio::test]
+async fn test_built_in_checks_run_before_plugins() {
+    let mut blacklist = tempfile::NamedTempFile::new().unwrap();
+    write!(
+        blacklist,
+        r#"[{{"account_type": "wallet", "wallet": "{}", "reason": "fraud"}}]"#,
+        PAYER
+    )
+    .unwrap();
+    let checker = builder(&[FixedPlugin::block("plugin")])
+        .with_blacklist(blacklist.path())
+        .build()
+        .await
+        .unwrap();
+
+    let result = checker
+        .screen_payment(PAYER, PAYEE, &context())
+        .await
+        .unwrap();
+
+    assert_eq!(result.matched_entities[0].list_source, "blacklist");
+}
+
+#[tokio::test]
+async fn test_plugins_are_loaded_and_unloaded() {
+    let plugin = FixedPlugin::review("plugin");
+    let checker = checker(std::slice::from_ref(&plugin)).await;
+    assert!(plugin.loaded.load(Ordering::SeqCst));
+
+    checker.shutdown().await;
+    assert!(!plugin.loaded.load(Ordering::SeqCst));
+}
+
+#[tokio::test]
+async fn test_regex_plugin_blocks_payee() {
+    let checker = ComplianceCheckerBuilder::new()
+        .with_ofac(false)
+        .with_blacklist(EMPTY_BLACKLIST)
+        .add_plugin(Arc::new(RegexPlugin::new(["^0x2222"]).unwrap()))
+        .build()
+        .await
+        .unwrap();
+
+    let result = checker
+        .screen_payment(PAYER, PAYEE, &context())
+        .await
+        .unwrap();
+
+    assert!(matches!(result.decision, ScreeningDecision::Block { .. }));
+    assert_eq!(result.matched_entities[0].address, PAYEE);
+}
@@ -0,0 +1,477 @@
+//! WebAssembly bindings for building x402 payment headers.
+//!
+//! Agents running in a browser or an edge runtime (Cloudflare Workers, Deno Deploy) use
+//! this crate to construct the `exact` EVM payment payload, an EIP-3009
+//! `transferWithAuthorization`, and to encode it into the `X-Payment` header, without
+//! a Rust server. Signing is left to the caller's wallet:
+//!
+//! 1. [`build_evm_payment_header`] returns an unsigned payload with a random nonce and
+//!    a validity window starting now.
+//! 2. The caller sets `payload.authorization.from`, signs the authorization as EIP-712
+//!    `TransferWithAuthorization` typed data, and stores the result in `payload.signature`.
+//! 3. [`encode_payment_header`] validates the payload and returns the header value.
+//!
+//! Payloads serialize to the JSON the facilitator expects. The bound functions never
+//! panic or throw: failures are returned as `{ error: { code, message } }`, see
+//! [`WasmError`].
+
+use base64::engine::general_purpose::STANDARD as b64;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+/// Protocol version of the payloads built here.
+pub const X402_VERSION: u8 = 1;
+
+/// How long an authorization stays valid, in seconds.
+pub const VALIDITY_SECONDS: u64 = 300;
+
+/// How far `validAfter` is backdated, in seconds, to tolerate clock skew.
+pub const CLOCK_SKEW_SECONDS: u64 = 600;
+
+/// Placeholder payer, replaced by the caller before signing.
+const UNSET_ADDRESS: &str = "0x0000000000000000000000000000000000000000";
+
+/// EVM networks accepted by the facilitator, by their wire name.
+pub const EVM_NETWORKS: &[&str] = &[
+    "base-sepolia",
+    "base",
+    "xdc",
+    "avalanche-fuji",
+    "avalanche",
+    "xrpl-evm",
+    "polygon-amoy",
+    "polygon",
+    "optimism",
+    "optimism-sepolia",
+    "celo",
+    "celo-sepolia",
+    "hyperevm",
+    "hyperevm-testnet",
+    "sei",
+    "sei-testnet",
+    "ethereum",
+    "ethereum-sepolia",
+    "arbitrum",
+    "arbitrum-sepolia",
+    "unichain",
+    "unichain-sepolia",
+    "monad",
+    "bsc",
+    "skale-base",
+    "skale-base-sepolia",
+    "scroll",
+    "scroll-sepolia",
+    "zksync-era",
+    "zksync-era-sepolia",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum Error {
+    #[error("amount must be a positive integer in token base units, got {0:?}")]
+    InvalidAmount(String),
+    #[error("{field} must be a 0x-prefixed 20-byte hex address, got {value:?}")]
+    InvalidAddress { field: &'static str, value: String },
+    #[error("unsupported EVM network {0:?}")]
+    UnsupportedNetwork(String),
+    #[error("invalid payment payload: {0}")]
+    InvalidPayload(String),
+    #[error("invalid payment header: {0}")]
+    InvalidHeader(String),
+    #[error("no randomness available for the nonce: {0}")]
+    Randomness(String),
+}
+
+impl Error {
+    fn code(&self) -> &'static str {
+        match self {
+            Error::InvalidAmount(_) => "invalid_amount",
+            Error::InvalidAddress { .. } => "invalid_address",
+            Error::UnsupportedNetwork(_) => "unsupported_network",
+            Error::InvalidPayload(_) => "invalid_payload",
+            Error::InvalidHeader(_) => "invalid_header",
+            Error::Randomness(_) => "randomness_unavailable",
+        }
+    }
+}
+
+/// Error object handed to JavaScript: `{ error: { code, message } }`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WasmError {
+    pub error: WasmErrorDetail,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WasmErrorDetail {
+    /// Stable, machine-readable code such as `invalid_amount`
+    pub code: String,
+    pub message: String,
+}
+
+impl From<Error> for WasmError {
+    fn from(error: Error) -> Self {
+        WasmError {
+            error: WasmErrorDetail {
+                code: error.code().to_string(),
+                message: error.to_string(),
+            },
+        }
+    }
+}
+
+/// Payment payload as sent in the `X-Payment` header.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PaymentPayload {
+    pub x402_version: u8,
+    pub scheme: String,
+    pub network: String,
+    pub payload: ExactEvmPayload,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExactEvmPayload {
+    /// 0x-prefixed hex signature over the authorization
+    pub signature: String,
+    pub authorization: Authorization,
+}
+
+/// EIP-3009 `TransferWithAuthorization` message. Numbers are decimal strings.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Authorization {
+    pub from: String,
+    pub to: String,
+    pub value: String,
+    pub valid_after: String,
+    pub valid_before: String,
+    /// 0x-prefixed 32-byte hex nonce
+    pub nonce: String,
+}
+
+/// Build an unsigned payload paying `amount` base units to `pay_to` on `network`.
+///
+/// The authorization is valid from `now - CLOCK_SKEW_SECONDS` to `now + VALIDITY_SECONDS`
+/// and carries a random nonce. `from` and `signature` are left for the caller to fill in.
+pub fn build_evm_payment(
+    amount: &str,
+    pay_to: &str,
+    network: &str,
+    now: u64,
+) -> Result<PaymentPayload, Error> {
+    check_amount(amount)?;
+    check_address("payTo", pay_to)?;
+    check_network(network)?;
+
+    let mut nonce = [0u8; 32];
+    getrandom::getrandom(&mut nonce).map_err(|e| Error::Randomness(e.to_string()))?;
+
+    Ok(PaymentPayload {
+        x402_version: X402_VERSION,
+        scheme: "exact".to_string(),
+        network: network.to_string(),
+        payload: ExactEvmPayload {
+            signature: "0x".to_string(),
+            authorization: Authorization {
+                from: UNSET_ADDRESS.to_string(),
+                to: pay_to.to_string(),
+                value: amount.to_string(),
+                valid_after: now.saturating_sub(CLOCK_SKEW_SECONDS).to_string(),
+                valid_before: now.saturating_add(VALIDITY_SECONDS).to_string(),
+                nonce: to_hex(&nonce),
+            },
+        },
+    })
+}
+
+/// Check that a payload is complete and well-formed, as the facilitator will.
+pub fn validate_payload(payment: &PaymentPayload) -> Result<(), Error> {
+    if payment.x402_version != X402_VERSION {
+        return Err(Error::InvalidPayload(format!(
+            "unsupported x402Version {}",
+            payment.x402_version
+        )));
+    }
+    if payment.scheme != "exact" {
+        return Err(Error::InvalidPayload(format!(
+            "unsupported scheme {:?}",
+            payment.scheme
+        )));
+    }
+    check_network(&payment.network)?;
+
+    let authorization = &payment.payload.authorization;
+    check_address("authorization.from", &authorization.from)?;
+    if authorization.from.eq_ignore_ascii_case(UNSET_ADDRESS) {
+        return Err(Error::InvalidPayload(
+            "authorization.from is not set".to_string(),
+        ));
+    }
+    check_address("authorization.to", &authorization.to)?;
+    check_amount(&authorization.value)?;
+    let valid_after = parse_timestamp("authorization.validAfter", &authorization.valid_after)?;
+    let valid_before = parse_timestamp("authorization.validBefore", &authorization.valid_before)?;
+    if valid_before <= valid_after {
+        return Err(Error::InvalidPayload(
+            "authorization.validBefore must be after validAfter".to_string(),
+        ));
+    }
+    if !is_hex(&authorization.nonce, Some(32)) {
+        return Err(Error::InvalidPayload(
+            "authorization.nonce must be 32 bytes of 0x-prefixed hex".to_string(),
+        ));
+    }
+    if !is_hex(&payment.payload.signature, None) || payment.payload.signature.len() <= 2 {
+        return Err(Error::InvalidPayload(
+            "signature must be non-empty 0x-prefixed hex".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Encode a signed payload as an `X-Payment` header value (base64 of its JSON).
+pub fn encode_header(payment: &PaymentPayload) -> Result<String, Error> {
+    validate_payload(payment)?;
+    let json = serde_json::to_vec(payment).map_err(|e| Error::InvalidPayload(e.to_string()))?;
+    Ok(b64.encode(json))
+}
+
+/// Decode an `X-Payment` header value.
+pub fn decode_header(header: &str) -> Result<PaymentPayload, Error> {
+    let json = b64
+        .decode(header.trim())
+        .map_err(|e| Error::InvalidHeader(e.to_string()))?;
+    let payment: PaymentPayload =
+        serde_json::from_slice(&json).map_err(|e| Error::InvalidHeader(e.to_string()))?;
+    validate_payload(&payment)?;
+    Ok(payment)
+}
+
+// ============================================================================
+// JavaScript bindings
+// ============================================================================
+
+/// Unsigned payment payload for `amount` base units (e.g. `"10000"` = 0.01 USDC),
+/// or an error object.
+#[wasm_bindgen]
+pub fn build_evm_payment_header(amount: &str, pay_to: &str, network: &str) -> JsValue {
+    to_js(build_evm_payment(amount, pay_to, network, now()))
+}
+
+/// The `X-Payment` header value for a signed payload, or an error object.
+#[wasm_bindgen]
+pub fn encode_payment_header(payload: JsValue) -> JsValue {
+    let encoded = serde_wasm_bindgen::from_value::<PaymentPayload>(payload)
+        .map_err(|e| Error::InvalidPayload(e.to_string()))
+        .and_then(|payment| encode_header(&payment));
+    to_js(encoded)
+}
+
+/// The payload in an `X-Payment` header value, or an error object.
+#[wasm_bindgen]
+pub fn decode_payment_header(header: &str) -> JsValue {
+    to_js(decode_header(header))
+}
+
+/// Plain JS objects (not `Map`s) for the value or its error; `undefined` if even that fails.
+fn to_js<T: Serialize>(result: Result<T, Error>) -> JsValue {
+    let serializer = serde_wasm_bindgen::Serializer::json_compatible();
+    match result {
+        Ok(value) => value.serialize(&serializer),
+        Err(error) => WasmError::from(error).serialize(&serializer),
+    }
+    .unwrap_or(JsValue::UNDEFINED)
+}
+
+#[cfg(target_arch = "wasm32")]
+fn now() -> u64 {
+    (js_sys::Date::now() / 1000.0) as u64
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+// ============================================================================
+// Validation helpers
+// ============================================================================
+
+fn check_amount(amount: &str) -> Result<(), Error> {
+    let digits =
+        !amount.is_empty() && amount.len() <= 78 && amount.bytes().all(|b| b.is_ascii_digit());
+    if digits && amount.bytes().any(|b| b != b'0') {
+        Ok(())
+    } else {
+        Err(Error::InvalidAmount(amount.to_string()))
+    }
+}
+
+fn check_address(field: &'static str, value: &str) -> Result<(), Error> {
+    if is_hex(value, Some(20)) {
+        Ok(())
+    } else {
+        Err(Error::InvalidAddress {
+            field,
+            value: value.to_string(),
+        })
+    }
+}
+
+fn check_network(network: &str) -> Result<(), Error> {
+    if EVM_NETWORKS.contains(&network) {
+        Ok(())
+    } else {
+        Err(Error::UnsupportedNetwork(network.to_string()))
+    }
+}
+
+fn parse_timestamp(field: &str, value: &str) -> Result<u64, Error> {
+    value
+        .parse()
+        .map_err(|_| Error::InvalidPayload(format!("{field} must be a Unix timestamp string")))
+}
+
+/// Whether `value` is `0x`-prefixed hex, of exactly `bytes` bytes when given.
+fn is_hex(value: &str, bytes: Option<usize>) -> bool {
+    let Some(digits) = value.strip_prefix("0x") else {
+        return false;
+    };
+    let length_ok = match bytes {
+        Some(bytes) => digits.len() == bytes * 2,
+        None => digits.len() % 2 == 0,
+    };
+    length_ok && digits.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    let mut hex = String::with_capacity(2 + bytes.len() * 2);
+    hex.push_str("0x");
+    for byte in bytes {
+        hex.push_str(&format!("{byte:02x}"));
+    }
+    hex
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+    use x402_rs::network::{Network, NetworkFamily};
+
+    const PAY_TO: &str = "0x1111111111111111111111111111111111111111";
+    const PAYER: &str = "0x2222222222222222222222222222222222222222";
+
+    fn signed(amount: &str) -> PaymentPayload {
+        let mut payment = build_evm_payment(amount, PAY_TO, "base-sepolia", 1_767_730_979).unwrap();
+        payment.payload.authorization.from = PAYER.to_string();
+        payment.payload.signature = format!("0x{}", "11".repeat(65));
+        payment
+    }
+
+    #[test]
+    fn test_build_evm_payment() {
+        let payment = build_evm_payment("10000", PAY_TO, "base-sepolia", 1_767_730_979).unwrap();
+        let authorization = &payment.payload.authorization;
+        assert_eq!(authorization.to, PAY_TO);
+        assert_eq!(authorization.value, "10000");
+        assert_eq!(authorization.valid_after, "1767730379");
+        assert_eq!(authorization.valid_before, "1767731279");
+        assert!(is_hex(&authorization.nonce, Some(32)));
+
+        let other = build_evm_payment("10000", PAY_TO, "base-sepolia", 1_767_730_979).unwrap();
+        assert_ne!(other.payload.authorization.nonce, authorization.nonce);
+
+        // Unsigned payloads cannot be encoded
+        assert!(matches!(
+            encode_header(&payment),
+            Err(Error::InvalidPayload(_))
+        ));
+    }
+
+    #[test]
+    fn test_header_is_what_the_server_expects() {
+        let payment = signed("10000");
+        let header = encode_header(&payment).unwrap();
+
+        let server = x402_rs::types::PaymentPayload::try_from(x402_rs::types::Base64Bytes::from(
+            header.as_bytes(),
+        ))
+        .unwrap();
+        assert_eq!(server.network, Network::BaseSepolia);
+        assert_eq!(
+            serde_json::to_value(&server).unwrap(),
+            serde_json::to_value(&payment).unwrap()
+        );
+
+        assert_eq!(decode_header(&header).unwrap(), payment);
+    }
+
+    #[test]
+    fn test_networks_match_the_server() {
+        let server: Vec<String> = Network::variants()
+            .iter()
+            .filter(|network| matches!(NetworkFamily::from(**network), NetworkFamily::Evm))
+            .map(|network| network.to_string())
+            .collect();
+        for name in EVM_NETWORKS {
+            assert!(
+                server.contains(&name.to_string()),
+                "{name} is not an EVM network"
+            );
+            assert!(Network::from_str(name).is_ok());
+        }
+        assert_eq!(server.len(), EVM_NETWORKS.len());
+    }
+
+    #[test]
+    fn test_malformed_input_is_rejected() {
+        for amount in ["", "0", "-1", "1.5", "0x10", "abc"] {
+            assert!(matches!(
+                build_evm_payment(amount, PAY_TO, "base", 0),
+                Err(Error::InvalidAmount(_))
+            ));
+        }
+        for pay_to in [
+            "",
+            "0x1234",
+            "1111111111111111111111111111111111111111",
+            "0xZZ11111111111111111111111111111111111111",
+        ] {
+            assert!(matches!(
+                build_evm_payment("1", pay_to, "base", 0),
+                Err(Error::InvalidAddress { .. })
+            ));
+        }
+        assert!(matches!(
+            build_evm_payment("1", PAY_TO, "solana", 0),
+            Err(Error::UnsupportedNetwork(_))
+        ));
+
+        for header in ["", "not base64!", "bm90IGpzb24=", "e30="] {
+            assert!(matches!(
+                decode_header(header),
+                Err(Error::InvalidHeader(_))
+            ));
+        }
+
+        let mut tampered = signed("1");
+        tampered.payload.authorization.nonce = "0x00".to_string();
+        assert!(matches!(
+            encode_header(&tampered),
+            Err(Error::InvalidPayload(_))
+        ));
+
+        let error = WasmError::from(Error::UnsupportedNetwork("solana".to_string()));
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            serde_json::json!({
+                "error": { "code": "unsupported_network", "message": "unsupported EVM network \"solana\"" }
+            })
+        );
+    }
+}
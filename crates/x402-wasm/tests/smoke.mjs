@@ -0,0 +1,47 @@
+// Smoke test of the Node.js build:
+//
+//   wasm-pack build crates/x402-wasm --target nodejs
+//   node crates/x402-wasm/tests/smoke.mjs
+import assert from "node:assert/strict";
+import { createRequire } from "node:module";
+
+const require = createRequire(import.meta.url);
+const {
+  build_evm_payment_header,
+  encode_payment_header,
+  decode_payment_header,
+} = require("../pkg/x402_wasm.js");
+
+const payTo = "0x1111111111111111111111111111111111111111";
+
+const payment = build_evm_payment_header("10000", payTo, "base-sepolia");
+assert.equal(payment.error, undefined);
+assert.equal(payment.x402Version, 1);
+assert.equal(payment.scheme, "exact");
+assert.equal(payment.network, "base-sepolia");
+assert.equal(payment.payload.authorization.to, payTo);
+assert.equal(payment.payload.authorization.value, "10000");
+assert.match(payment.payload.authorization.nonce, /^0x[0-9a-f]{64}$/);
+const now = Math.floor(Date.now() / 1000);
+assert.ok(Number(payment.payload.authorization.validAfter) <= now);
+assert.ok(Number(payment.payload.authorization.validBefore) > now);
+
+// Unsigned payloads are refused
+assert.equal(encode_payment_header(payment).error.code, "invalid_payload");
+
+payment.payload.authorization.from = "0x2222222222222222222222222222222222222222";
+payment.payload.signature = "0x" + "11".repeat(65);
+const header = encode_payment_header(payment);
+assert.equal(typeof header, "string");
+assert.deepEqual(JSON.parse(Buffer.from(header, "base64").toString()), payment);
+assert.deepEqual(decode_payment_header(header), payment);
+
+// Malformed input returns error objects instead of throwing
+assert.equal(build_evm_payment_header("1.5", payTo, "base").error.code, "invalid_amount");
+assert.equal(build_evm_payment_header("1", "0x12", "base").error.code, "invalid_address");
+assert.equal(build_evm_payment_header("1", payTo, "solana").error.code, "unsupported_network");
+assert.equal(decode_payment_header("not base64!").error.code, "invalid_header");
+assert.equal(encode_payment_header(42).error.code, "invalid_payload");
+assert.equal(encode_payment_header(undefined).error.code, "invalid_payload");
+
+console.log("x402-wasm smoke test passed");
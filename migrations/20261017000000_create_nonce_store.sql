@@ -0,0 +1,9 @@
+-- Used nonces for replay protection (PostgresNonceStore).
+CREATE TABLE IF NOT EXISTS nonce_store (
+    pk TEXT PRIMARY KEY,
+    chain TEXT NOT NULL,
+    created_at BIGINT NOT NULL,
+    expires_at BIGINT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS nonce_store_expires_at ON nonce_store (expires_at);
@@ -0,0 +1,2 @@
+-- Values of entries stored with NonceStore::claim_value / set_value, such as idempotency records.
+ALTER TABLE nonce_store ADD COLUMN IF NOT EXISTS cached_value TEXT;
@@ -0,0 +1,254 @@
+//! Batch payment verification (`POST /verify/batch`).
+//!
+//! Clients that fan out to many paid resources verify all of their payments in one
+//! round-trip. [`BatchFacilitator`] wraps any [`Facilitator`] and verifies the items of
+//! a [`BatchVerifyRequest`] concurrently. Each item succeeds or fails on its own: an
+//! error verifying one payment never aborts the others.
+
+use futures::future::join_all;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::facilitator::Facilitator;
+use crate::types::{VerifyRequest, VerifyResponse};
+
+/// Default maximum number of items in one batch.
+pub const DEFAULT_MAX_BATCH_ITEMS: usize = 50;
+
+#[derive(Debug, thiserror::Error)]
+pub enum BatchError {
+    #[error("batch of {len} items exceeds the maximum of {max}")]
+    TooLarge { len: usize, max: usize },
+}
+
+/// Payments to verify in one call.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchVerifyRequest {
+    pub items: Vec<VerifyRequest>,
+}
+
+/// Per-item outcomes, in request order.
+///
+/// Each result serializes as `{ "success": true, "response": {..} }` or
+/// `{ "success": false, "error": ".." }`.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchVerifyResponse {
+    #[serde(with = "item_results")]
+    pub results: Vec<Result<VerifyResponse, String>>,
+}
+
+/// Verifies batches of payments with an inner facilitator.
+#[derive(Debug, Clone)]
+pub struct BatchFacilitator<A> {
+    inner: A,
+    max_items: usize,
+}
+
+impl<A: Facilitator + Sync> BatchFacilitator<A> {
+    pub fn new(inner: A) -> Self {
+        Self {
+            inner,
+            max_items: DEFAULT_MAX_BATCH_ITEMS,
+        }
+    }
+
+    pub fn with_max_items(mut self, max_items: usize) -> Self {
+        self.max_items = max_items;
+        self
+    }
+
+    /// Verify every item concurrently.
+    ///
+    /// Fails only when the batch is larger than the configured maximum; verification
+    /// errors are reported per item.
+    pub async fn batch_verify(
+        &self,
+        request: &BatchVerifyRequest,
+    ) -> Result<BatchVerifyResponse, BatchError> {
+        if request.items.len() > self.max_items {
+            return Err(BatchError::TooLarge {
+                len: request.items.len(),
+                max: self.max_items,
+            });
+        }
+        let results =
+            join_all(request.items.iter().map(|item| async move {
+                self.inner.verify(item).await.map_err(|e| e.to_string())
+            }))
+            .await;
+        Ok(BatchVerifyResponse { results })
+    }
+}
+
+/// Wire format of [`BatchVerifyResponse::results`].
+mod item_results {
+    use super::*;
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct ItemResult<R> {
+        success: bool,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        response: Option<R>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        error: Option<String>,
+    }
+
+    pub fn serialize<S: Serializer>(
+        results: &[Result<VerifyResponse, String>],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(results.iter().map(|result| match result {
+            Ok(response) => ItemResult {
+                success: true,
+                response: Some(response),
+                error: None,
+            },
+            Err(error) => ItemResult {
+                success: false,
+                response: None,
+                error: Some(error.clone()),
+            },
+        }))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<Result<VerifyResponse, String>>, D::Error> {
+        let items = Vec::<ItemResult<VerifyResponse>>::deserialize(deserializer)?;
+        items
+            .into_iter()
+            .map(|item| match (item.success, item.response, item.error) {
+                (true, Some(response), _) => Ok(Ok(response)),
+                (false, _, error) => Ok(Err(error.unwrap_or_default())),
+                (true, None, _) => Err(serde::de::Error::missing_field("response")),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{
+        ExactPaymentPayload, MixedAddress, SettleRequest, SettleResponse,
+        SupportedPaymentKindsResponse,
+    };
+
+    /// Accepts payments of at least 100 units, fails on anything else.
+    struct MinimumAmount;
+
+    impl Facilitator for MinimumAmount {
+        type Error = String;
+
+        async fn verify(&self, request: &VerifyRequest) -> Result<VerifyResponse, String> {
+            let payer = MixedAddress::Evm(match &request.payment_payload.payload {
+                ExactPaymentPayload::Evm(payload) => payload.authorization.from,
+                _ => return Err("unsupported payload".to_string()),
+            });
+            if request.payment_requirements.max_amount_required.0 >= 100u64 {
+                Ok(VerifyResponse::valid(payer))
+            } else {
+                Err("amount below minimum".to_string())
+            }
+        }
+
+        async fn settle(&self, _request: &SettleRequest) -> Result<SettleResponse, String> {
+            unimplemented!()
+        }
+
+        async fn supported(&self) -> Result<SupportedPaymentKindsResponse, String> {
+            unimplemented!()
+        }
+    }
+
+    fn verify_request(amount: u64) -> VerifyRequest {
+        serde_json::from_value(serde_json::json!({
+            "x402Version": 1,
+            "paymentPayload": {
+                "x402Version": 1,
+                "scheme": "exact",
+                "network": "base-sepolia",
+                "payload": {
+                    "signature": format!("0x{}", "11".repeat(65)),
+                    "authorization": {
+                        "from": "0x2222222222222222222222222222222222222222",
+                        "to": "0x1111111111111111111111111111111111111111",
+                        "value": amount.to_string(),
+                        "validAfter": "0",
+                        "validBefore": "9999999999",
+                        "nonce": format!("0x{}", "00".repeat(32))
+                    }
+                }
+            },
+            "paymentRequirements": {
+                "scheme": "exact",
+                "network": "base-sepolia",
+                "maxAmountRequired": amount.to_string(),
+                "resource": "https://api.example.com/data",
+                "description": "",
+                "mimeType": "application/json",
+                "payTo": "0x1111111111111111111111111111111111111111",
+                "maxTimeoutSeconds": 60,
+                "asset": "0x036CbD53842c5426634e7929541eC2318f3dCF7e"
+            }
+        }))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_mixed_batch_reports_each_item() {
+        let batch = BatchFacilitator::new(MinimumAmount);
+        let request = BatchVerifyRequest {
+            items: vec![verify_request(100), verify_request(1), verify_request(500)],
+        };
+
+        let response = batch.batch_verify(&request).await.unwrap();
+        assert!(matches!(
+            response.results[0],
+            Ok(VerifyResponse::Valid { .. })
+        ));
+        assert_eq!(
+            response.results[1].as_ref().unwrap_err(),
+            "amount below minimum"
+        );
+        assert!(matches!(
+            response.results[2],
+            Ok(VerifyResponse::Valid { .. })
+        ));
+
+        let json = serde_json::to_value(&response).unwrap();
+        assert_eq!(json["results"][0]["success"], true);
+        assert_eq!(json["results"][0]["response"]["isValid"], true);
+        assert_eq!(json["results"][1]["success"], false);
+        assert_eq!(json["results"][1]["error"], "amount below minimum");
+
+        let parsed: BatchVerifyResponse = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed.results.len(), 3);
+        assert!(parsed.results[1].is_err());
+    }
+
+    #[tokio::test]
+    async fn test_batch_size_is_limited() {
+        let batch = BatchFacilitator::new(MinimumAmount).with_max_items(2);
+        let request = BatchVerifyRequest {
+            items: vec![verify_request(100); 3],
+        };
+        assert!(matches!(
+            batch.batch_verify(&request).await,
+            Err(BatchError::TooLarge { len: 3, max: 2 })
+        ));
+
+        let request = BatchVerifyRequest {
+            items: vec![verify_request(100); 2],
+        };
+        assert_eq!(batch.batch_verify(&request).await.unwrap().results.len(), 2);
+
+        let default = BatchFacilitator::new(MinimumAmount);
+        let request = BatchVerifyRequest {
+            items: vec![verify_request(100); DEFAULT_MAX_BATCH_ITEMS + 1],
+        };
+        assert!(default.batch_verify(&request).await.is_err());
+    }
+}
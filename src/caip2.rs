@@ -10,6 +10,7 @@
 //! - `solana:5eykt4UsFv8P8NJdTREpY1vzqKqZKvdp` (Solana mainnet)
 //! - `near:mainnet` (NEAR mainnet)
 //! - `stellar:pubnet` (Stellar mainnet)
+//! - `algorand:mainnet` (Algorand mainnet, with the `algorand` feature)
 //!
 //! Reference: <https://github.com/ChainAgnostic/CAIPs/blob/main/CAIPs/caip-2.md>
 
@@ -39,6 +40,14 @@ pub enum Namespace {
     /// Reference is the network name ("mainnet" or "testnet").
     #[cfg(feature = "sui")]
     Sui,
+    /// Algorand.
+    /// Reference is the network name ("mainnet" or "testnet").
+    #[cfg(feature = "algorand")]
+    Algorand,
+    /// Hedera Hashgraph.
+    /// Reference is the network name ("mainnet" or "testnet").
+    #[cfg(feature = "hedera")]
+    Hedera,
 }
 
 impl Display for Namespace {
@@ -51,6 +60,10 @@ impl Display for Namespace {
             Namespace::Fogo => write!(f, "fogo"),
             #[cfg(feature = "sui")]
             Namespace::Sui => write!(f, "sui"),
+            #[cfg(feature = "algorand")]
+            Namespace::Algorand => write!(f, "algorand"),
+            #[cfg(feature = "hedera")]
+            Namespace::Hedera => write!(f, "hedera"),
         }
     }
 }
@@ -67,6 +80,10 @@ impl FromStr for Namespace {
             "fogo" => Ok(Namespace::Fogo),
             #[cfg(feature = "sui")]
             "sui" => Ok(Namespace::Sui),
+            #[cfg(feature = "algorand")]
+            "algorand" => Ok(Namespace::Algorand),
+            #[cfg(feature = "hedera")]
+            "hedera" => Ok(Namespace::Hedera),
             _ => Err(Caip2ParseError::UnknownNamespace(s.to_string())),
         }
     }
@@ -76,6 +93,7 @@ impl FromStr for Namespace {
 ///
 /// Represents a blockchain network using the format `{namespace}:{reference}`.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "ts-gen", derive(ts_rs::TS), ts(type = "string"))]
 pub struct Caip2NetworkId {
     namespace: Namespace,
     reference: String,
@@ -139,6 +157,24 @@ impl Caip2NetworkId {
                     });
                 }
             }
+            #[cfg(feature = "algorand")]
+            Namespace::Algorand => {
+                if reference != "mainnet" && reference != "testnet" {
+                    return Err(Caip2ParseError::InvalidNetworkName {
+                        namespace: "algorand".to_string(),
+                        reference,
+                    });
+                }
+            }
+            #[cfg(feature = "hedera")]
+            Namespace::Hedera => {
+                if reference != "mainnet" && reference != "testnet" {
+                    return Err(Caip2ParseError::InvalidNetworkName {
+                        namespace: "hedera".to_string(),
+                        reference,
+                    });
+                }
+            }
         }
 
         Ok(Self {
@@ -226,6 +262,42 @@ impl Caip2NetworkId {
         }
     }
 
+    /// Create a CAIP-2 ID for Algorand mainnet.
+    #[cfg(feature = "algorand")]
+    pub fn algorand_mainnet() -> Self {
+        Self {
+            namespace: Namespace::Algorand,
+            reference: "mainnet".to_string(),
+        }
+    }
+
+    /// Create a CAIP-2 ID for Algorand testnet.
+    #[cfg(feature = "algorand")]
+    pub fn algorand_testnet() -> Self {
+        Self {
+            namespace: Namespace::Algorand,
+            reference: "testnet".to_string(),
+        }
+    }
+
+    /// Create a CAIP-2 ID for Hedera mainnet.
+    #[cfg(feature = "hedera")]
+    pub fn hedera_mainnet() -> Self {
+        Self {
+            namespace: Namespace::Hedera,
+            reference: "mainnet".to_string(),
+        }
+    }
+
+    /// Create a CAIP-2 ID for Hedera testnet.
+    #[cfg(feature = "hedera")]
+    pub fn hedera_testnet() -> Self {
+        Self {
+            namespace: Namespace::Hedera,
+            reference: "testnet".to_string(),
+        }
+    }
+
     /// Get the namespace.
     pub fn namespace(&self) -> Namespace {
         self.namespace
@@ -390,6 +462,28 @@ mod tests {
         assert_eq!(testnet.to_string(), "fogo:testnet");
     }
 
+    #[cfg(feature = "algorand")]
+    #[test]
+    fn test_caip2_algorand() {
+        let mainnet = Caip2NetworkId::algorand_mainnet();
+        assert_eq!(mainnet.to_string(), "algorand:mainnet");
+        assert_eq!("algorand:testnet".parse::<Caip2NetworkId>().unwrap(), Caip2NetworkId::algorand_testnet());
+        assert!("algorand:betanet".parse::<Caip2NetworkId>().is_err());
+    }
+
+    #[cfg(feature = "hedera")]
+    #[test]
+    fn test_caip2_hedera() {
+        let mainnet = Caip2NetworkId::hedera_mainnet();
+        assert_eq!(mainnet.to_string(), "hedera:mainnet");
+        assert_eq!(mainnet.chain_id(), None);
+        assert_eq!(
+            "hedera:testnet".parse::<Caip2NetworkId>().unwrap(),
+            Caip2NetworkId::hedera_testnet()
+        );
+        assert!("hedera:previewnet".parse::<Caip2NetworkId>().is_err());
+    }
+
     #[test]
     fn test_caip2_parse() {
         let id: Caip2NetworkId = "eip155:8453".parse().unwrap();
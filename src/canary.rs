@@ -0,0 +1,900 @@
+//! Canary self-test settlements for continuous settlement assurance.
+//!
+//! Health endpoints only prove that RPC nodes answer. The only way to know that
+//! end-to-end settlement works is to settle something. When enabled, the canary
+//! periodically sends a minimal self-payment (smallest unit of the configured asset,
+//! from the facilitator wallet to itself) through the full [`Facilitator`] pipeline:
+//! verification, compliance screening, nonce store, broadcast, confirmation and receipt.
+//!
+//! # Architecture
+//!
+//! - [`CanaryConfig`]: env-driven configuration, disabled by default
+//! - [`CanaryPayloadSource`]: produces a signed self-payment for a network;
+//!   [`EvmCanarySource`] signs USDC self-transfers with the facilitator's EVM key
+//! - [`CanaryBudget`]: small canary-only spend budget, separate from user spend caps
+//! - [`CanaryMonitor`]: per-network health component with consecutive-failure alerting
+//! - [`run_canary`] / [`start_canary_task`]: one-shot and scheduled execution
+//!
+//! # Tagging
+//!
+//! Canaries are recognised from server-side state, never from anything the client sends:
+//! [`run_canary`] registers the wallet each self-payment comes from, and
+//! [`is_canary_request`] matches self-payments from those wallets. Anything that accounts
+//! for revenue or analytics (e.g. settlement tracking in the discovery registry) must skip
+//! requests for which it returns `true`.
+//!
+//! Each run is recorded in the `x402_canary_*` metrics, and `GET /health/canary` serves
+//! [`CanaryMonitor::health_json`] while canaries are enabled.
+//!
+//! # Environment
+//!
+//! - `CANARY_ENABLED`: `true`/`1` to enable (default: disabled)
+//! - `CANARY_NETWORKS`: comma-separated networks with optional interval override,
+//!   e.g. `base-sepolia,base:600` (default interval from `CANARY_INTERVAL_SECS`)
+//! - `CANARY_INTERVAL_SECS`: default interval between canaries (default: 3600)
+//! - `CANARY_FAILURE_THRESHOLD`: consecutive failures before alerting (default: 3)
+//! - `CANARY_AMOUNT`: atomic units sent per canary (default: 1)
+//! - `CANARY_DAILY_BUDGET`: atomic units the canary may spend per network per day (default: 100)
+
+use alloy::primitives::{FixedBytes, U256};
+use alloy::signers::local::PrivateKeySigner;
+use alloy::signers::Signer;
+use alloy::sol_types::{Eip712Domain, SolStruct};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+use tokio::task::JoinHandle;
+use tracing::{debug, error, info, warn};
+
+use crate::chain::evm::EvmChain;
+use crate::env_registry;
+use crate::facilitator::Facilitator;
+use crate::from_env::SignerType;
+use crate::metrics;
+use crate::network::{Network, USDCDeployment};
+use crate::timestamp::UnixTimestamp;
+use crate::types::{
+    EvmAddress, EvmSignature, ExactEvmPayload, ExactEvmPayloadAuthorization,
+    ExactPaymentPayload, HexEncodedNonce, MixedAddress, PaymentPayload, PaymentRequirements,
+    Scheme, SettleRequest, TokenAmount, TransactionHash, TransferWithAuthorization,
+    VerifyResponse, X402Version,
+};
+
+/// Wallets canary self-payments are sent from, registered by [`run_canary`].
+static CANARY_PAYERS: Lazy<std::sync::RwLock<HashSet<MixedAddress>>> =
+    Lazy::new(Default::default);
+
+/// How long a canary authorization stays valid.
+const AUTHORIZATION_VALIDITY: Duration = Duration::from_secs(600);
+
+/// Length of the canary budget window.
+const BUDGET_WINDOW: Duration = Duration::from_secs(86_400);
+
+/// Errors produced while preparing a canary run.
+#[derive(Debug, thiserror::Error)]
+pub enum CanaryError {
+    /// The payload source cannot build a self-payment for this network
+    #[error("No canary payload source for network {0}")]
+    UnsupportedNetwork(Network),
+
+    /// Building or signing the self-payment failed
+    #[error("Failed to build canary payload: {0}")]
+    PayloadError(String),
+
+    /// Invalid configuration value
+    #[error("Invalid canary configuration: {0}")]
+    ConfigError(String),
+}
+
+// ============================================================================
+// Configuration
+// ============================================================================
+
+/// Canary configuration.
+#[derive(Debug, Clone)]
+pub struct CanaryConfig {
+    /// Whether canaries run at all
+    pub enabled: bool,
+    /// Networks to exercise, with their interval
+    pub schedules: HashMap<Network, Duration>,
+    /// Consecutive failures before an alert is raised
+    pub failure_threshold: u32,
+    /// Atomic units sent per canary
+    pub amount: u128,
+    /// Atomic units the canary may spend per network per day
+    pub daily_budget: u128,
+}
+
+impl Default for CanaryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            schedules: HashMap::new(),
+            failure_threshold: 3,
+            amount: 1,
+            daily_budget: 100,
+        }
+    }
+}
+
+impl CanaryConfig {
+    /// Load configuration from environment variables.
+    pub fn from_env() -> Result<Self, CanaryError> {
+        let enabled = env_registry::flag("CANARY_ENABLED");
+        let default_interval = env_registry::parse::<u64>("CANARY_INTERVAL_SECS").unwrap_or(3600);
+
+        let schedules = parse_schedules(
+            &env_registry::var("CANARY_NETWORKS").unwrap_or_default(),
+            Duration::from_secs(default_interval),
+        )?;
+
+        let defaults = Self::default();
+        Ok(Self {
+            enabled,
+            schedules,
+            failure_threshold: env_registry::parse("CANARY_FAILURE_THRESHOLD")
+                .unwrap_or(defaults.failure_threshold),
+            amount: env_registry::parse("CANARY_AMOUNT").unwrap_or(defaults.amount),
+            daily_budget: env_registry::parse("CANARY_DAILY_BUDGET")
+                .unwrap_or(defaults.daily_budget),
+        })
+    }
+}
+
+/// Parse a `network[:interval_secs]` comma-separated list.
+fn parse_schedules(
+    raw: &str,
+    default_interval: Duration,
+) -> Result<HashMap<Network, Duration>, CanaryError> {
+    let mut schedules = HashMap::new();
+    for entry in raw.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+        let (name, interval) = match entry.split_once(':') {
+            Some((name, secs)) => {
+                let secs = secs.trim().parse::<u64>().map_err(|_| {
+                    CanaryError::ConfigError(format!("invalid interval in '{}'", entry))
+                })?;
+                (name.trim(), Duration::from_secs(secs))
+            }
+            None => (entry, default_interval),
+        };
+        let network = Network::from_str(name)
+            .map_err(|_| CanaryError::ConfigError(format!("unknown network '{}'", name)))?;
+        schedules.insert(network, interval);
+    }
+    Ok(schedules)
+}
+
+// ============================================================================
+// Tagging
+// ============================================================================
+
+/// The wallet paying itself in `request`, if it is a self-payment.
+fn self_payer(request: &SettleRequest) -> Option<MixedAddress> {
+    let from = match &request.payment_payload.payload {
+        ExactPaymentPayload::Evm(payload) => MixedAddress::Evm(payload.authorization.from),
+        _ => return None,
+    };
+    (from == request.payment_requirements.pay_to).then_some(from)
+}
+
+/// Remember `payer` as a canary wallet, so that its self-payments count as canaries.
+pub fn register_canary_payer(payer: MixedAddress) {
+    CANARY_PAYERS
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(payer);
+}
+
+/// Returns `true` if the request is a self-payment from a canary wallet.
+///
+/// Only wallets registered by [`run_canary`] qualify: a request cannot make itself a
+/// canary, and a settled self-payment from such a wallet was signed by the facilitator.
+/// Canary settlements must be excluded from revenue, analytics and discovery tracking.
+pub fn is_canary_request(request: &SettleRequest) -> bool {
+    self_payer(request).is_some_and(|payer| {
+        CANARY_PAYERS
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .contains(&payer)
+    })
+}
+
+// ============================================================================
+// Payload Source
+// ============================================================================
+
+/// Produces a signed self-payment for a network.
+///
+/// Implementations sign a transfer of `amount` atomic units of the network's configured
+/// asset from the facilitator wallet back to itself (or a zero-value pattern where the
+/// chain allows it). [`run_canary`] registers the payer of the returned self-payment as a
+/// canary wallet before it enters the pipeline.
+#[async_trait::async_trait]
+pub trait CanaryPayloadSource: Send + Sync {
+    async fn build(&self, network: Network, amount: u128) -> Result<SettleRequest, CanaryError>;
+}
+
+/// Signs ERC-3009 USDC self-transfers with the facilitator's EVM proof signer.
+#[derive(Debug, Clone, Default)]
+pub struct EvmCanarySource {
+    signers: HashMap<Network, PrivateKeySigner>,
+}
+
+impl EvmCanarySource {
+    /// Load the facilitator's signer for each EVM network in `networks`.
+    ///
+    /// Non-EVM networks are skipped; their canaries fail with
+    /// [`CanaryError::UnsupportedNetwork`].
+    pub fn from_env<'a>(networks: impl IntoIterator<Item = &'a Network>) -> Result<Self, CanaryError> {
+        let signer_type =
+            SignerType::from_env().map_err(|e| CanaryError::ConfigError(e.to_string()))?;
+        let mut signers = HashMap::new();
+        for &network in networks {
+            if EvmChain::try_from(network).is_err() {
+                continue;
+            }
+            let signer = signer_type
+                .make_evm_proof_signer(network)
+                .map_err(|e| CanaryError::ConfigError(e.to_string()))?;
+            signers.insert(network, signer);
+        }
+        Ok(Self { signers })
+    }
+}
+
+#[async_trait::async_trait]
+impl CanaryPayloadSource for EvmCanarySource {
+    async fn build(&self, network: Network, amount: u128) -> Result<SettleRequest, CanaryError> {
+        let signer = self
+            .signers
+            .get(&network)
+            .ok_or(CanaryError::UnsupportedNetwork(network))?;
+        let chain = EvmChain::try_from(network)
+            .map_err(|_| CanaryError::UnsupportedNetwork(network))?;
+        let usdc = USDCDeployment::by_network(network);
+        let (MixedAddress::Evm(asset), Some(eip712)) = (usdc.address(), usdc.eip712.as_ref())
+        else {
+            return Err(CanaryError::UnsupportedNetwork(network));
+        };
+
+        let wallet = EvmAddress(signer.address());
+        let now = UnixTimestamp::try_now()
+            .map_err(|e| CanaryError::PayloadError(e.to_string()))?
+            .seconds_since_epoch();
+        let authorization = ExactEvmPayloadAuthorization {
+            from: wallet,
+            to: wallet,
+            value: TokenAmount::from(amount),
+            valid_after: UnixTimestamp(now.saturating_sub(60)),
+            valid_before: UnixTimestamp(now + AUTHORIZATION_VALIDITY.as_secs()),
+            nonce: HexEncodedNonce(rand::random()),
+        };
+        let domain = Eip712Domain::new(
+            Some(eip712.name.clone().into()),
+            Some(eip712.version.clone().into()),
+            Some(U256::from(chain.chain_id)),
+            Some(asset.0),
+            None,
+        );
+        let message = TransferWithAuthorization {
+            from: wallet.0,
+            to: wallet.0,
+            value: U256::from(amount),
+            validAfter: U256::from(authorization.valid_after.0),
+            validBefore: U256::from(authorization.valid_before.0),
+            nonce: FixedBytes(authorization.nonce.0),
+        };
+        let signature = signer
+            .sign_hash(&message.eip712_signing_hash(&domain))
+            .await
+            .map_err(|e| CanaryError::PayloadError(e.to_string()))?;
+
+        Ok(SettleRequest {
+            x402_version: X402Version::V1,
+            payment_payload: PaymentPayload {
+                x402_version: X402Version::V1,
+                scheme: Scheme::Exact,
+                network,
+                payload: ExactPaymentPayload::Evm(ExactEvmPayload {
+                    signature: EvmSignature(signature.as_bytes().to_vec()),
+                    authorization,
+                }),
+            },
+            payment_requirements: PaymentRequirements {
+                scheme: Scheme::Exact,
+                network,
+                max_amount_required: TokenAmount::from(amount),
+                resource: "https://facilitator.local/canary"
+                    .parse()
+                    .expect("static canary resource URL"),
+                description: "canary".to_string(),
+                mime_type: "application/json".to_string(),
+                output_schema: None,
+                pay_to: MixedAddress::Evm(wallet),
+                max_timeout_seconds: AUTHORIZATION_VALIDITY.as_secs(),
+                asset: MixedAddress::Evm(asset),
+                extra: Some(serde_json::json!({
+                    "name": eip712.name,
+                    "version": eip712.version,
+                })),
+            },
+        })
+    }
+}
+
+// ============================================================================
+// Budget
+// ============================================================================
+
+/// Canary-only spend budget.
+///
+/// Tracked independently from any user-facing spend caps so that canaries can neither
+/// consume a customer's allowance nor be blocked by it.
+#[derive(Debug, Clone)]
+pub struct CanaryBudget {
+    limit: u128,
+    spent: Arc<RwLock<HashMap<Network, (Instant, u128)>>>,
+}
+
+impl CanaryBudget {
+    /// Create a budget allowing `limit` atomic units per network per day.
+    pub fn new(limit: u128) -> Self {
+        Self {
+            limit,
+            spent: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    /// Reserve `amount` for a canary on `network`. Returns `false` if the budget is exhausted.
+    pub async fn try_reserve(&self, network: Network, amount: u128) -> bool {
+        let mut spent = self.spent.write().await;
+        let entry = spent.entry(network).or_insert((Instant::now(), 0));
+        if entry.0.elapsed() >= BUDGET_WINDOW {
+            *entry = (Instant::now(), 0);
+        }
+        match entry.1.checked_add(amount) {
+            Some(total) if total <= self.limit => {
+                entry.1 = total;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Atomic units spent on `network` in the current window.
+    pub async fn spent(&self, network: Network) -> u128 {
+        self.spent
+            .read()
+            .await
+            .get(&network)
+            .filter(|(start, _)| start.elapsed() < BUDGET_WINDOW)
+            .map(|(_, spent)| *spent)
+            .unwrap_or(0)
+    }
+}
+
+// ============================================================================
+// Monitor
+// ============================================================================
+
+/// Pipeline stage at which a canary failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CanaryStage {
+    Build,
+    Verify,
+    Settle,
+}
+
+/// Outcome of a single canary run.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum CanaryOutcome {
+    /// Settlement confirmed on-chain
+    Success {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        transaction: Option<String>,
+    },
+    /// A pipeline stage failed
+    Failure { stage: CanaryStage, reason: String },
+    /// Canary did not run (e.g. budget exhausted)
+    Skipped { reason: String },
+}
+
+impl CanaryOutcome {
+    pub fn is_failure(&self) -> bool {
+        matches!(self, CanaryOutcome::Failure { .. })
+    }
+}
+
+/// Health status of canaries on a single network.
+#[derive(Debug, Clone, Serialize)]
+pub struct CanaryStatus {
+    pub last_outcome: CanaryOutcome,
+    pub last_latency_ms: u64,
+    pub last_run_at: u64,
+    pub consecutive_failures: u32,
+    pub total_runs: u64,
+    pub total_failures: u64,
+    /// `true` once `consecutive_failures` reached the alert threshold
+    pub alerting: bool,
+}
+
+/// Per-network canary health component.
+#[derive(Debug, Clone)]
+pub struct CanaryMonitor {
+    failure_threshold: u32,
+    statuses: Arc<RwLock<HashMap<Network, CanaryStatus>>>,
+}
+
+impl CanaryMonitor {
+    pub fn new(failure_threshold: u32) -> Self {
+        Self {
+            failure_threshold: failure_threshold.max(1),
+            statuses: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    /// Record a canary outcome. Returns `true` if this run raised a new alert.
+    ///
+    /// Skipped runs are recorded but neither count as failures nor reset the streak.
+    pub async fn record(&self, network: Network, outcome: CanaryOutcome, latency: Duration) -> bool {
+        let now = UnixTimestamp::try_now().map(|t| t.seconds_since_epoch()).unwrap_or(0);
+        let mut statuses = self.statuses.write().await;
+        let status = statuses.entry(network).or_insert_with(|| CanaryStatus {
+            last_outcome: outcome.clone(),
+            last_latency_ms: 0,
+            last_run_at: now,
+            consecutive_failures: 0,
+            total_runs: 0,
+            total_failures: 0,
+            alerting: false,
+        });
+
+        status.last_latency_ms = latency.as_millis() as u64;
+        status.last_run_at = now;
+        let mut raised = false;
+        match &outcome {
+            CanaryOutcome::Success { .. } => {
+                status.total_runs += 1;
+                if status.alerting {
+                    info!(network = %network, "Canary recovered");
+                }
+                status.consecutive_failures = 0;
+                status.alerting = false;
+            }
+            CanaryOutcome::Failure { stage, reason } => {
+                status.total_runs += 1;
+                status.total_failures += 1;
+                status.consecutive_failures += 1;
+                if status.consecutive_failures == self.failure_threshold {
+                    status.alerting = true;
+                    raised = true;
+                    error!(
+                        network = %network,
+                        stage = ?stage,
+                        reason = %reason,
+                        consecutive_failures = status.consecutive_failures,
+                        "ALERT: canary settlement failing"
+                    );
+                }
+            }
+            CanaryOutcome::Skipped { reason } => {
+                debug!(network = %network, reason = %reason, "Canary skipped");
+            }
+        }
+        status.last_outcome = outcome;
+        raised
+    }
+
+    /// Current status for a network.
+    pub async fn status(&self, network: Network) -> Option<CanaryStatus> {
+        self.statuses.read().await.get(&network).cloned()
+    }
+
+    /// `true` if no network is currently alerting.
+    pub async fn is_healthy(&self) -> bool {
+        self.statuses.read().await.values().all(|s| !s.alerting)
+    }
+
+    /// JSON health component, keyed by network.
+    pub async fn health_json(&self) -> Value {
+        let statuses = self.statuses.read().await;
+        let networks: serde_json::Map<String, Value> = statuses
+            .iter()
+            .map(|(network, status)| {
+                (
+                    network.to_string(),
+                    serde_json::to_value(status).unwrap_or(Value::Null),
+                )
+            })
+            .collect();
+        serde_json::json!({
+            "healthy": statuses.values().all(|s| !s.alerting),
+            "networks": networks,
+        })
+    }
+}
+
+// ============================================================================
+// Execution
+// ============================================================================
+
+/// Run a single canary on `network` through the full facilitator pipeline.
+pub async fn run_canary<F, S>(
+    facilitator: &F,
+    source: &S,
+    network: Network,
+    config: &CanaryConfig,
+    budget: &CanaryBudget,
+    monitor: &CanaryMonitor,
+) -> CanaryOutcome
+where
+    F: Facilitator,
+    S: CanaryPayloadSource + ?Sized,
+{
+    let started = Instant::now();
+    let outcome = execute_canary(facilitator, source, network, config, budget).await;
+    let latency = started.elapsed();
+
+    match &outcome {
+        CanaryOutcome::Success { transaction } => info!(
+            network = %network,
+            latency_ms = latency.as_millis() as u64,
+            transaction = ?transaction,
+            "Canary settlement succeeded"
+        ),
+        CanaryOutcome::Failure { stage, reason } => warn!(
+            network = %network,
+            latency_ms = latency.as_millis() as u64,
+            stage = ?stage,
+            reason = %reason,
+            "Canary settlement failed"
+        ),
+        CanaryOutcome::Skipped { .. } => {}
+    }
+
+    monitor.record(network, outcome.clone(), latency).await;
+    let alerting = monitor
+        .status(network)
+        .await
+        .is_some_and(|status| status.alerting);
+    metrics::record_canary(network, &outcome, latency, alerting);
+    outcome
+}
+
+async fn execute_canary<F, S>(
+    facilitator: &F,
+    source: &S,
+    network: Network,
+    config: &CanaryConfig,
+    budget: &CanaryBudget,
+) -> CanaryOutcome
+where
+    F: Facilitator,
+    S: CanaryPayloadSource + ?Sized,
+{
+    if !budget.try_reserve(network, config.amount).await {
+        return CanaryOutcome::Skipped {
+            reason: "canary budget exhausted".to_string(),
+        };
+    }
+
+    let request = match source.build(network, config.amount).await {
+        Ok(request) => request,
+        Err(e) => {
+            return CanaryOutcome::Failure {
+                stage: CanaryStage::Build,
+                reason: e.to_string(),
+            }
+        }
+    };
+    match self_payer(&request) {
+        Some(payer) => register_canary_payer(payer),
+        None => {
+            return CanaryOutcome::Failure {
+                stage: CanaryStage::Build,
+                reason: "canary payload is not a self-payment".to_string(),
+            }
+        }
+    }
+
+    match facilitator.verify(&request).await {
+        Ok(VerifyResponse::Valid { .. }) => {}
+        Ok(VerifyResponse::Invalid { reason, .. }) => {
+            return CanaryOutcome::Failure {
+                stage: CanaryStage::Verify,
+                reason: reason.to_string(),
+            }
+        }
+        Err(e) => {
+            return CanaryOutcome::Failure {
+                stage: CanaryStage::Verify,
+                reason: e.to_string(),
+            }
+        }
+    }
+
+    match facilitator.settle(&request).await {
+        Ok(response) if response.success => CanaryOutcome::Success {
+            transaction: response.transaction.as_ref().map(TransactionHash::to_string),
+        },
+        Ok(response) => CanaryOutcome::Failure {
+            stage: CanaryStage::Settle,
+            reason: response
+                .error_reason
+                .map(|r| r.to_string())
+                .unwrap_or_else(|| "settlement unsuccessful".to_string()),
+        },
+        Err(e) => CanaryOutcome::Failure {
+            stage: CanaryStage::Settle,
+            reason: e.to_string(),
+        },
+    }
+}
+
+/// Start one background canary loop per configured network.
+///
+/// Returns an empty list when canaries are disabled.
+pub fn start_canary_task<F>(
+    facilitator: F,
+    source: Arc<dyn CanaryPayloadSource>,
+    config: CanaryConfig,
+    monitor: CanaryMonitor,
+) -> Vec<JoinHandle<()>>
+where
+    F: Facilitator + Clone + Send + Sync + 'static,
+{
+    if !config.enabled {
+        info!("Canary self-tests are disabled (CANARY_ENABLED=false)");
+        return Vec::new();
+    }
+
+    let budget = CanaryBudget::new(config.daily_budget);
+    let config = Arc::new(config);
+    config
+        .schedules
+        .iter()
+        .map(|(&network, &interval)| {
+            let facilitator = facilitator.clone();
+            let source = Arc::clone(&source);
+            let config = Arc::clone(&config);
+            let budget = budget.clone();
+            let monitor = monitor.clone();
+            info!(network = %network, interval_secs = interval.as_secs(), "Starting canary task");
+            tokio::spawn(async move {
+                let mut ticker = tokio::time::interval(interval);
+                loop {
+                    ticker.tick().await;
+                    run_canary(&facilitator, source.as_ref(), network, &config, &budget, &monitor)
+                        .await;
+                }
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{FacilitatorErrorReason, SettleResponse, SupportedPaymentKindsResponse};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn wallet() -> alloy::primitives::Address {
+        alloy::primitives::address!("0x1111111111111111111111111111111111111111")
+    }
+
+    struct SelfPaymentSource;
+
+    #[async_trait::async_trait]
+    impl CanaryPayloadSource for SelfPaymentSource {
+        async fn build(&self, network: Network, amount: u128) -> Result<SettleRequest, CanaryError> {
+            let authorization = ExactEvmPayloadAuthorization {
+                from: EvmAddress(wallet()),
+                to: EvmAddress(wallet()),
+                value: TokenAmount::from(amount),
+                valid_after: UnixTimestamp(0),
+                valid_before: UnixTimestamp(u64::MAX),
+                nonce: HexEncodedNonce([7u8; 32]),
+            };
+            Ok(SettleRequest {
+                x402_version: X402Version::V1,
+                payment_payload: PaymentPayload {
+                    x402_version: X402Version::V1,
+                    scheme: Scheme::Exact,
+                    network,
+                    payload: ExactPaymentPayload::Evm(ExactEvmPayload {
+                        signature: EvmSignature(vec![0u8; 65]),
+                        authorization,
+                    }),
+                },
+                payment_requirements: PaymentRequirements {
+                    scheme: Scheme::Exact,
+                    network,
+                    max_amount_required: TokenAmount::from(amount),
+                    resource: "https://facilitator.local/canary".parse().unwrap(),
+                    description: "canary".to_string(),
+                    mime_type: "application/json".to_string(),
+                    output_schema: None,
+                    pay_to: MixedAddress::from(wallet()),
+                    max_timeout_seconds: 60,
+                    asset: MixedAddress::from(wallet()),
+                    extra: None,
+                },
+            })
+        }
+    }
+
+    /// Facilitator double that records every request it sees.
+    #[derive(Clone, Default)]
+    struct MockFacilitator {
+        fail_settle: bool,
+        verified: Arc<AtomicUsize>,
+        settled: Arc<std::sync::Mutex<Vec<SettleRequest>>>,
+    }
+
+    impl Facilitator for MockFacilitator {
+        type Error = String;
+
+        async fn verify(&self, request: &SettleRequest) -> Result<VerifyResponse, String> {
+            self.verified.fetch_add(1, Ordering::SeqCst);
+            Ok(VerifyResponse::valid(request.payment_requirements.pay_to.clone()))
+        }
+
+        async fn settle(&self, request: &SettleRequest) -> Result<SettleResponse, String> {
+            self.settled.lock().unwrap().push(request.clone());
+            Ok(SettleResponse {
+                success: !self.fail_settle,
+                error_reason: self
+                    .fail_settle
+                    .then_some(FacilitatorErrorReason::UnexpectedSettleError),
+                payer: request.payment_requirements.pay_to.clone(),
+                transaction: (!self.fail_settle).then_some(TransactionHash::Evm([1u8; 32])),
+                network: request.network(),
+                proof_of_payment: None,
+                confirmation: None,
+            })
+        }
+
+        async fn supported(&self) -> Result<SupportedPaymentKindsResponse, String> {
+            Err("unused".to_string())
+        }
+    }
+
+    fn config() -> CanaryConfig {
+        CanaryConfig {
+            enabled: true,
+            ..CanaryConfig::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_pipeline_exercised_end_to_end() {
+        let facilitator = MockFacilitator::default();
+        let monitor = CanaryMonitor::new(3);
+        let budget = CanaryBudget::new(100);
+
+        let outcome = run_canary(
+            &facilitator,
+            &SelfPaymentSource,
+            Network::BaseSepolia,
+            &config(),
+            &budget,
+            &monitor,
+        )
+        .await;
+
+        assert!(matches!(outcome, CanaryOutcome::Success { transaction: Some(_) }));
+        assert_eq!(facilitator.verified.load(Ordering::SeqCst), 1);
+        let settled = facilitator.settled.lock().unwrap();
+        assert_eq!(settled.len(), 1);
+        assert!(is_canary_request(&settled[0]));
+
+        let status = monitor.status(Network::BaseSepolia).await.unwrap();
+        assert_eq!(status.total_runs, 1);
+        assert_eq!(status.consecutive_failures, 0);
+        assert_eq!(budget.spent(Network::BaseSepolia).await, 1);
+    }
+
+    #[tokio::test]
+    async fn test_only_registered_self_payments_are_canaries() {
+        let payer = alloy::primitives::address!("0x2222222222222222222222222222222222222222");
+        let mut request = SelfPaymentSource.build(Network::Base, 1).await.unwrap();
+        if let ExactPaymentPayload::Evm(payload) = &mut request.payment_payload.payload {
+            payload.authorization.from = EvmAddress(payer);
+        }
+        request.payment_requirements.pay_to = MixedAddress::from(payer);
+
+        // Whatever the client puts in extra does not make a request a canary
+        request.payment_requirements.extra = Some(serde_json::json!({ "canary": true }));
+        assert!(!is_canary_request(&request));
+
+        register_canary_payer(MixedAddress::from(payer));
+        assert!(is_canary_request(&request));
+
+        // Payments from a canary wallet to anyone else still count
+        request.payment_requirements.pay_to =
+            MixedAddress::from(alloy::primitives::address!("0x3333333333333333333333333333333333333333"));
+        assert!(!is_canary_request(&request));
+    }
+
+    #[tokio::test]
+    async fn test_alert_after_consecutive_failures() {
+        let facilitator = MockFacilitator {
+            fail_settle: true,
+            ..MockFacilitator::default()
+        };
+        let monitor = CanaryMonitor::new(3);
+        let budget = CanaryBudget::new(100);
+        let cfg = config();
+
+        for _ in 0..2 {
+            run_canary(&facilitator, &SelfPaymentSource, Network::Base, &cfg, &budget, &monitor)
+                .await;
+        }
+        assert!(monitor.is_healthy().await);
+
+        let outcome =
+            run_canary(&facilitator, &SelfPaymentSource, Network::Base, &cfg, &budget, &monitor)
+                .await;
+        assert!(outcome.is_failure());
+        let status = monitor.status(Network::Base).await.unwrap();
+        assert_eq!(status.consecutive_failures, 3);
+        assert!(status.alerting);
+        assert!(!monitor.is_healthy().await);
+
+        // A success resets the streak and clears the alert
+        let raised = monitor
+            .record(
+                Network::Base,
+                CanaryOutcome::Success { transaction: None },
+                Duration::from_millis(5),
+            )
+            .await;
+        assert!(!raised);
+        assert!(monitor.is_healthy().await);
+    }
+
+    #[tokio::test]
+    async fn test_budget_is_separate_and_enforced() {
+        let facilitator = MockFacilitator::default();
+        let monitor = CanaryMonitor::new(3);
+        let budget = CanaryBudget::new(2);
+        let cfg = config();
+
+        for _ in 0..2 {
+            let outcome =
+                run_canary(&facilitator, &SelfPaymentSource, Network::Base, &cfg, &budget, &monitor)
+                    .await;
+            assert!(matches!(outcome, CanaryOutcome::Success { .. }));
+        }
+        let outcome =
+            run_canary(&facilitator, &SelfPaymentSource, Network::Base, &cfg, &budget, &monitor)
+                .await;
+        assert!(matches!(outcome, CanaryOutcome::Skipped { .. }));
+        assert_eq!(facilitator.settled.lock().unwrap().len(), 2);
+
+        // Budget is tracked per network
+        assert_eq!(budget.spent(Network::Base).await, 2);
+        assert!(budget.try_reserve(Network::BaseSepolia, 1).await);
+
+        // Skipped runs do not count as failures
+        let status = monitor.status(Network::Base).await.unwrap();
+        assert_eq!(status.consecutive_failures, 0);
+        assert_eq!(status.total_runs, 2);
+    }
+
+    #[test]
+    fn test_parse_schedules() {
+        let schedules = parse_schedules("base-sepolia, base:600", Duration::from_secs(3600)).unwrap();
+        assert_eq!(schedules[&Network::BaseSepolia], Duration::from_secs(3600));
+        assert_eq!(schedules[&Network::Base], Duration::from_secs(600));
+        assert!(parse_schedules("not-a-network", Duration::from_secs(1)).is_err());
+        assert!(!CanaryConfig::default().enabled);
+    }
+}
@@ -1,15 +1,15 @@
 //! Algorand payment provider implementation.
 //!
 //! This module implements Algorand payments using atomic transaction groups.
-//! Users sign standard ASA (Algorand Standard Asset) transfers, and the
-//! facilitator co-signs a fee-paying transaction to enable gasless payments
-//! via Algorand's fee pooling mechanism.
+//! Users sign standard ASA (Algorand Standard Asset) transfers or native ALGO
+//! payments, and the facilitator co-signs a fee-paying transaction to enable
+//! gasless payments via Algorand's fee pooling mechanism.
 //!
 //! Flow (based on Coinbase/Algorand Foundation x402 specification):
-//! 1. Client creates an atomic transaction group: [fee_tx, asa_transfer]
-//! 2. Client signs only the ASA transfer (standard wallet signature)
+//! 1. Client creates an atomic transaction group: [fee_tx, payment]
+//! 2. Client signs only the payment (standard wallet signature)
 //! 3. Client sends the partially-signed group to facilitator
-//! 4. Facilitator verifies the ASA transfer is valid and authorized
+//! 4. Facilitator verifies the payment is valid and authorized
 //! 5. Facilitator signs the fee transaction and submits the entire group
 //! 6. Algorand network executes both atomically (or neither)
 //!
@@ -18,9 +18,9 @@
 
 #![cfg(feature = "algorand")]
 
+use alloy::primitives::U256;
 use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
-use once_cell::sync::OnceCell;
-use std::fmt::{Debug, Formatter};
+use std::fmt::{Debug, Display, Formatter};
 use std::str::FromStr;
 use std::sync::Arc;
 
@@ -33,11 +33,12 @@ use crate::chain::{FacilitatorLocalError, FromEnvByNetworkBuild, NetworkProvider
 use crate::facilitator::Facilitator;
 use crate::from_env;
 use crate::network::Network;
-use crate::nonce_store::{algorand_nonce_key, algorand_ttl_seconds, NonceStore, NonceStoreError};
+use crate::nonce_store::{algorand_ttl_seconds, NonceKey, NonceStore, NonceStoreError};
 use crate::types::{
-    ExactAlgorandPayload, ExactPaymentPayload, FacilitatorErrorReason, MixedAddress, Scheme,
-    SettleRequest, SettleResponse, SupportedPaymentKind, SupportedPaymentKindExtra,
-    SupportedPaymentKindsResponse, TransactionHash, VerifyRequest, VerifyResponse, X402Version,
+    ExactAlgorandPayload, ExactPaymentPayload, FacilitatorErrorReason, MixedAddress,
+    PaymentRequirements, Scheme, SettleConfirmation, SettleRequest, SettleResponse,
+    SupportedPaymentKind, SupportedPaymentKindExtra, SupportedPaymentKindsResponse,
+    SupportedTokenInfo, TokenType, TransactionHash, VerifyRequest, VerifyResponse, X402Version,
 };
 
 // =============================================================================
@@ -90,8 +91,20 @@ pub enum AlgorandError {
     #[error("Transaction not confirmed after {attempts} attempts")]
     TransactionNotConfirmed { attempts: u32 },
 
-    #[error("ASA ID mismatch: expected {expected}, got {actual}")]
-    AsaIdMismatch { expected: u64, actual: u64 },
+    #[error("Payment asset {0} is not accepted")]
+    UnsupportedAsset(AlgorandPaymentAsset),
+
+    #[error("Invalid payment asset: {0}")]
+    InvalidAsset(String),
+
+    #[error("Payment asset mismatch: expected {expected}, got {actual}")]
+    AssetMismatch {
+        expected: AlgorandPaymentAsset,
+        actual: AlgorandPaymentAsset,
+    },
+
+    #[error("Insufficient payment amount: provided {provided}, required {required}")]
+    InsufficientAmount { provided: u64, required: U256 },
 
     #[error("RPC error: {0}")]
     RpcError(String),
@@ -102,7 +115,7 @@ pub enum AlgorandError {
     #[error("Payment index out of bounds: {index} >= {len}")]
     PaymentIndexOutOfBounds { index: usize, len: usize },
 
-    #[error("Transaction type mismatch: expected asset transfer")]
+    #[error("Transaction type mismatch: expected payment or asset transfer")]
     TransactionTypeMismatch,
 
     #[error("Transaction simulation failed: {0}")]
@@ -121,6 +134,69 @@ impl From<AlgorandError> for FacilitatorLocalError {
     }
 }
 
+// =============================================================================
+// Payment Assets
+// =============================================================================
+
+/// Asset a client pays with on Algorand
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AlgorandPaymentAsset {
+    /// Native ALGO, sent in a payment transaction (amounts in microAlgos)
+    Algo,
+    /// Algorand Standard Asset, sent in an asset transfer transaction
+    Asa(u64),
+}
+
+impl Display for AlgorandPaymentAsset {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AlgorandPaymentAsset::Algo => write!(f, "ALGO"),
+            AlgorandPaymentAsset::Asa(id) => write!(f, "{id}"),
+        }
+    }
+}
+
+impl FromStr for AlgorandPaymentAsset {
+    type Err = AlgorandError;
+
+    /// Parse a payment requirements asset: `ALGO` (or asset ID `0`) for native ALGO,
+    /// otherwise the ASA ID.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.eq_ignore_ascii_case("algo") {
+            return Ok(AlgorandPaymentAsset::Algo);
+        }
+        match s.parse::<u64>() {
+            Ok(0) => Ok(AlgorandPaymentAsset::Algo),
+            Ok(id) => Ok(AlgorandPaymentAsset::Asa(id)),
+            Err(_) => Err(AlgorandError::InvalidAsset(s.to_string())),
+        }
+    }
+}
+
+/// Client payment transaction details, checked against the payment requirements
+#[derive(Clone, Debug)]
+pub struct AlgorandPayment {
+    pub asset: AlgorandPaymentAsset,
+    /// Amount in microAlgos for ALGO, base units of the asset for an ASA
+    pub amount: u64,
+    pub receiver: AlgoAddress,
+    pub sender: AlgoAddress,
+}
+
+/// Amount of the client's payment in `payload`, without checking it against any payment
+/// requirements
+pub fn signed_payment_amount(payload: &ExactAlgorandPayload) -> Option<u64> {
+    let bytes = BASE64
+        .decode(payload.payment_group.get(payload.payment_index)?)
+        .ok()?;
+    let signed: SignedTransaction = rmp_serde::from_slice(&bytes).ok()?;
+    match signed.transaction.txn_type {
+        TransactionType::Payment(payment) => Some(payment.amount.0),
+        TransactionType::AssetTransferTransaction(xfer) => Some(xfer.amount),
+        _ => None,
+    }
+}
+
 // =============================================================================
 // Chain Configuration
 // =============================================================================
@@ -130,9 +206,96 @@ impl From<AlgorandError> for FacilitatorLocalError {
 pub struct AlgorandChain {
     pub network: Network,
     pub usdc_asa_id: u64,
+    /// Assets accepted as payment, in the order `/supported` lists them
+    pub payment_assets: Vec<AlgorandPaymentAsset>,
 }
 
 impl AlgorandChain {
+    fn new(network: Network, usdc_asa_id: u64) -> Self {
+        Self {
+            network,
+            usdc_asa_id,
+            payment_assets: vec![
+                AlgorandPaymentAsset::Asa(usdc_asa_id),
+                AlgorandPaymentAsset::Algo,
+            ],
+        }
+    }
+
+    /// Whether `asset` is accepted as payment on this network
+    pub fn accepts(&self, asset: AlgorandPaymentAsset) -> bool {
+        self.payment_assets.contains(&asset)
+    }
+
+    /// Accepted payment assets as listed by `/supported`
+    ///
+    /// The ASA is listed by its ID and native ALGO as `ALGO`.
+    pub fn supported_tokens(&self) -> Vec<SupportedTokenInfo> {
+        self.payment_assets
+            .iter()
+            .filter_map(|asset| {
+                let token = match asset {
+                    AlgorandPaymentAsset::Algo => TokenType::Algo,
+                    AlgorandPaymentAsset::Asa(id) if *id == self.usdc_asa_id => TokenType::Usdc,
+                    AlgorandPaymentAsset::Asa(_) => return None,
+                };
+                Some(SupportedTokenInfo {
+                    token,
+                    address: MixedAddress::Algorand(asset.to_string()),
+                    decimals: token.decimals(),
+                })
+            })
+            .collect()
+    }
+
+    /// Extract the client's payment and check it against `requirements`
+    ///
+    /// The payment must be a native ALGO payment or an asset transfer of an accepted
+    /// asset, pay the asset named in `requirements`, and cover its maximum amount.
+    pub fn check_payment(
+        &self,
+        txn_type: &TransactionType,
+        requirements: &PaymentRequirements,
+    ) -> Result<AlgorandPayment, AlgorandError> {
+        let payment = match txn_type {
+            TransactionType::Payment(payment) => AlgorandPayment {
+                asset: AlgorandPaymentAsset::Algo,
+                amount: payment.amount.0,
+                receiver: payment.receiver,
+                sender: payment.sender,
+            },
+            TransactionType::AssetTransferTransaction(xfer) => AlgorandPayment {
+                asset: AlgorandPaymentAsset::Asa(xfer.xfer),
+                amount: xfer.amount,
+                receiver: xfer.receiver,
+                sender: xfer.sender,
+            },
+            _ => return Err(AlgorandError::TransactionTypeMismatch),
+        };
+
+        if !self.accepts(payment.asset) {
+            return Err(AlgorandError::UnsupportedAsset(payment.asset));
+        }
+
+        let expected: AlgorandPaymentAsset = requirements.asset.to_string().parse()?;
+        if payment.asset != expected {
+            return Err(AlgorandError::AssetMismatch {
+                expected,
+                actual: payment.asset,
+            });
+        }
+
+        let required = requirements.max_amount_required.0;
+        if U256::from(payment.amount) < required {
+            return Err(AlgorandError::InsufficientAmount {
+                provided: payment.amount,
+                required,
+            });
+        }
+
+        Ok(payment)
+    }
+
     /// Get the default algod API URL for this network
     pub fn default_algod_url(&self) -> &'static str {
         match self.network {
@@ -148,14 +311,8 @@ impl TryFrom<Network> for AlgorandChain {
 
     fn try_from(value: Network) -> Result<Self, Self::Error> {
         match value {
-            Network::Algorand => Ok(Self {
-                network: value,
-                usdc_asa_id: USDC_ASA_ID_MAINNET,
-            }),
-            Network::AlgorandTestnet => Ok(Self {
-                network: value,
-                usdc_asa_id: USDC_ASA_ID_TESTNET,
-            }),
+            Network::Algorand => Ok(Self::new(value, USDC_ASA_ID_MAINNET)),
+            Network::AlgorandTestnet => Ok(Self::new(value, USDC_ASA_ID_TESTNET)),
             _ => Err(FacilitatorLocalError::UnsupportedNetwork(None)),
         }
     }
@@ -238,24 +395,6 @@ impl From<AlgorandAddress> for MixedAddress {
 ///
 /// Implements USDC payments on Algorand using atomic transaction groups.
 /// The facilitator receives partially-signed atomic groups, verifies them,
-/// Global nonce store for replay protection across all Algorand providers.
-/// Initialized lazily on first use.
-static GLOBAL_NONCE_STORE: OnceCell<Arc<dyn NonceStore>> = OnceCell::new();
-
-/// Get or initialize the global nonce store.
-/// Uses DynamoDB if NONCE_STORE_TABLE_NAME is configured, otherwise falls back to memory.
-async fn get_global_nonce_store() -> Arc<dyn NonceStore> {
-    // If already initialized, return it
-    if let Some(store) = GLOBAL_NONCE_STORE.get() {
-        return store.clone();
-    }
-
-    // Initialize and store
-    let store = crate::nonce_store::create_nonce_store().await;
-    let _ = GLOBAL_NONCE_STORE.set(store.clone());
-    store
-}
-
 /// signs the fee transaction, and submits the complete group.
 #[derive(Clone)]
 pub struct AlgorandProvider {
@@ -271,6 +410,8 @@ pub struct AlgorandProvider {
     http_client: reqwest::Client,
     /// Network configuration
     chain: AlgorandChain,
+    /// Replay protection for transaction group IDs
+    nonce_store: Arc<dyn NonceStore>,
 }
 
 impl Debug for AlgorandProvider {
@@ -300,26 +441,32 @@ impl AlgorandProvider {
         current_round: u64,
         last_valid_round: u64,
     ) -> Result<(), AlgorandError> {
-        let store = get_global_nonce_store().await;
-        let key = algorand_nonce_key(self.chain_name(), group_id);
+        let key = NonceKey::algorand_group(self.chain_name(), group_id);
         let ttl = algorand_ttl_seconds(current_round, last_valid_round);
 
-        store
-            .check_and_mark_used(&key, ttl)
+        self.nonce_store
+            .check_and_mark_key(&key, ttl)
             .await
             .map_err(|e| match e {
-                NonceStoreError::NonceAlreadyUsed(_) => AlgorandError::InvalidAtomicGroup(
-                    "Transaction group already processed (replay attempt)".to_string(),
-                ),
+                NonceStoreError::NonceAlreadyUsed(_) => {
+                    crate::metrics::record_nonce_replay(self.chain_name());
+                    AlgorandError::InvalidAtomicGroup(
+                        "Transaction group already processed (replay attempt)".to_string(),
+                    )
+                }
                 other => AlgorandError::RpcError(format!("Nonce store error: {}", other)),
             })
     }
 
     /// Create a new Algorand provider
+    ///
+    /// Group IDs are tracked in `nonce_store`, so replays stay rejected across
+    /// restarts when the store is persistent.
     pub fn try_new(
         mnemonic: String,
         algod_url: Option<String>,
         network: Network,
+        nonce_store: Arc<dyn NonceStore>,
     ) -> Result<Self, FacilitatorLocalError> {
         let chain = AlgorandChain::try_from(network)?;
 
@@ -358,6 +505,7 @@ impl AlgorandProvider {
             algod_url: effective_url.to_string(),
             http_client,
             chain,
+            nonce_store,
         })
     }
 
@@ -366,6 +514,16 @@ impl AlgorandProvider {
         MixedAddress::Algorand(self.public_address.clone())
     }
 
+    /// Get the last round committed by the algod node
+    pub async fn current_round(&self) -> Result<u64, AlgorandError> {
+        let status = self
+            .algod
+            .status()
+            .await
+            .map_err(|e| AlgorandError::RpcError(e.to_string()))?;
+        Ok(status.last_round)
+    }
+
     /// Decode a base64 msgpack transaction
     fn decode_transaction(&self, base64_tx: &str) -> Result<AlgoTransaction, AlgorandError> {
         let bytes = BASE64
@@ -434,6 +592,7 @@ impl AlgorandProvider {
     async fn verify_payment_group(
         &self,
         payload: &ExactAlgorandPayload,
+        requirements: &PaymentRequirements,
     ) -> Result<VerifyGroupResult, AlgorandError> {
         if payload.payment_group.len() < 2 {
             return Err(AlgorandError::InvalidAtomicGroup(
@@ -492,25 +651,11 @@ impl AlgorandProvider {
             }
         }
 
-        // Verify the payment is an asset transfer and get details
-        let (asset_id, amount, receiver, sender) = match &payment_signed.transaction.txn_type {
-            TransactionType::AssetTransferTransaction(xfer) => {
-                (xfer.xfer, xfer.amount, xfer.receiver.clone(), xfer.sender.clone())
-            }
-            _ => {
-                return Err(AlgorandError::InvalidAtomicGroup(
-                    "Payment must be an asset transfer".to_string(),
-                ));
-            }
-        };
-
-        // Verify it's USDC
-        if asset_id != self.chain.usdc_asa_id {
-            return Err(AlgorandError::AsaIdMismatch {
-                expected: self.chain.usdc_asa_id,
-                actual: asset_id,
-            });
-        }
+        // Verify the payment is a native ALGO payment or an ASA transfer of the required
+        // asset and amount
+        let payment = self
+            .chain
+            .check_payment(&payment_signed.transaction.txn_type, requirements)?;
 
         // Get current round for validity checks
         let status = self
@@ -531,26 +676,29 @@ impl AlgorandProvider {
         }
 
         // Extract payer address
-        let payer_address = sender.to_string();
+        let payer_address = payment.sender.to_string();
 
         Ok(VerifyGroupResult {
             payer: AlgorandAddress::new(payer_address),
             fee_tx,
             payment_signed,
             group_id: fee_group_id.0,
-            amount,
-            recipient: receiver.to_string(),
+            asset: payment.asset,
+            amount: payment.amount,
+            recipient: payment.receiver.to_string(),
             current_round,
             last_valid_round,
         })
     }
 
-    /// Sign the fee transaction and submit the group
+    /// Sign the fee transaction and submit the group.
+    ///
+    /// Returns the transaction id and the round that confirmed it.
     async fn submit_group(
         &self,
         verification: &VerifyGroupResult,
         payload: &ExactAlgorandPayload,
-    ) -> Result<String, AlgorandError> {
+    ) -> Result<(String, u64), AlgorandError> {
         // CRITICAL: Atomically check and mark group_id as used BEFORE submitting to blockchain
         // This prevents replay attacks even if the facilitator crashes after submission
         self.check_and_mark_group_used(
@@ -606,16 +754,16 @@ impl AlgorandProvider {
         );
 
         // Wait for confirmation
-        self.wait_for_confirmation(&tx_id).await?;
+        let confirmed_round = self.wait_for_confirmation(&tx_id).await?;
 
         // Note: group_id was already marked as used at the start of submit_group()
         // via check_and_mark_group_used(), stored in persistent DynamoDB
 
-        Ok(tx_id)
+        Ok((tx_id, confirmed_round))
     }
 
-    /// Wait for transaction confirmation
-    async fn wait_for_confirmation(&self, tx_id: &str) -> Result<(), AlgorandError> {
+    /// Wait for transaction confirmation, returning the confirmed round
+    async fn wait_for_confirmation(&self, tx_id: &str) -> Result<u64, AlgorandError> {
         const MAX_ATTEMPTS: u32 = 20;
         const POLL_INTERVAL_MS: u64 = 500;
 
@@ -624,13 +772,13 @@ impl AlgorandProvider {
 
             match self.algod.pending_transaction_with_id(tx_id).await {
                 Ok(info) => {
-                    if info.confirmed_round.is_some() {
+                    if let Some(confirmed_round) = info.confirmed_round {
                         tracing::info!(
                             tx_id = %tx_id,
-                            confirmed_round = ?info.confirmed_round,
+                            confirmed_round = confirmed_round,
                             "Algorand transaction confirmed"
                         );
-                        return Ok(());
+                        return Ok(confirmed_round);
                     }
                     tracing::debug!(
                         tx_id = %tx_id,
@@ -654,6 +802,44 @@ impl AlgorandProvider {
         })
     }
 
+    /// Unix timestamp of `round`, read from the algod `/v2/blocks/{round}` endpoint.
+    ///
+    /// Only used to report settlement details, so failures are logged and yield `None`.
+    async fn round_timestamp(&self, round: u64) -> Option<u64> {
+        let block_url = format!("{}/v2/blocks/{}?format=json", self.algod_url, round);
+        let block: serde_json::Value = match self
+            .http_client
+            .get(&block_url)
+            .timeout(std::time::Duration::from_secs(10))
+            .send()
+            .await
+        {
+            Ok(response) if response.status().is_success() => match response.json().await {
+                Ok(block) => block,
+                Err(e) => {
+                    tracing::warn!(round, error = %e, "Failed to parse Algorand block");
+                    return None;
+                }
+            },
+            Ok(response) => {
+                tracing::warn!(round, status = %response.status(), "Failed to fetch Algorand block");
+                return None;
+            }
+            Err(e) => {
+                tracing::warn!(round, error = %e, "Failed to fetch Algorand block");
+                return None;
+            }
+        };
+        let timestamp = block
+            .get("block")
+            .and_then(|b| b.get("ts"))
+            .and_then(|ts| ts.as_u64());
+        if timestamp.is_none() {
+            tracing::warn!(round, "Algorand block has no timestamp");
+        }
+        timestamp
+    }
+
     /// Simulate the transaction group before submission
     ///
     /// This calls the algod /v2/transactions/simulate endpoint to verify the
@@ -766,6 +952,7 @@ pub struct VerifyGroupResult {
     #[allow(dead_code)]
     pub payment_signed: SignedTransaction,
     pub group_id: [u8; 32],
+    pub asset: AlgorandPaymentAsset,
     pub amount: u64,
     pub recipient: String,
     pub current_round: u64,
@@ -778,8 +965,11 @@ pub struct VerifyGroupResult {
 // =============================================================================
 
 impl FromEnvByNetworkBuild for AlgorandProvider {
-    async fn from_env(network: Network) -> Result<Option<Self>, Box<dyn std::error::Error>> {
-        let algod_url = std::env::var(from_env::rpc_env_name_from_network(network)).ok();
+    async fn from_env(
+        network: Network,
+        nonce_store: Arc<dyn NonceStore>,
+    ) -> Result<Option<Self>, Box<dyn std::error::Error>> {
+        let algod_url = crate::env_registry::var(from_env::rpc_env_name_from_network(network));
 
         // Get mnemonic from environment
         let mnemonic = match from_env::SignerType::from_env()?.get_algorand_mnemonic(network) {
@@ -790,7 +980,7 @@ impl FromEnvByNetworkBuild for AlgorandProvider {
             }
         };
 
-        let provider = AlgorandProvider::try_new(mnemonic, algod_url, network)?;
+        let provider = AlgorandProvider::try_new(mnemonic, algod_url, network, nonce_store)?;
         Ok(Some(provider))
     }
 }
@@ -824,7 +1014,7 @@ impl Facilitator for AlgorandProvider {
         match &payload.payload {
             ExactPaymentPayload::Algorand(p) => {
                 let verification = self
-                    .verify_payment_group(p)
+                    .verify_payment_group(p, &request.payment_requirements)
                     .await
                     .map_err(FacilitatorLocalError::from)?;
                 Ok(VerifyResponse::valid(verification.payer.into()))
@@ -850,41 +1040,50 @@ impl Facilitator for AlgorandProvider {
             ExactPaymentPayload::Algorand(algorand_payload) => {
                 tracing::info!("Algorand settle: Verifying payment group");
                 let verification = self
-                    .verify_payment_group(algorand_payload)
+                    .verify_payment_group(algorand_payload, &request.payment_requirements)
                     .await
                     .map_err(FacilitatorLocalError::from)?;
 
                 tracing::info!(
                     payer = %verification.payer.address,
+                    asset = %verification.asset,
                     amount = verification.amount,
                     recipient = %verification.recipient,
                     "Algorand settle: Verification successful, submitting group"
                 );
 
                 // Submit the transaction group
-                let tx_id = match self.submit_group(&verification, algorand_payload).await {
-                    Ok(id) => {
-                        tracing::info!(
-                            tx_id = %id,
-                            "Algorand settle: Transaction submitted successfully"
-                        );
-                        id
-                    }
-                    Err(e) => {
-                        tracing::error!(
-                            error = %e,
-                            "Algorand settle: Failed to submit transaction"
-                        );
-                        return Ok(SettleResponse {
-                            success: false,
-                            error_reason: Some(FacilitatorErrorReason::UnexpectedSettleError),
-                            payer: verification.payer.into(),
-                            transaction: None,
-                            network: self.network(),
-                            proof_of_payment: None,
-                        });
-                    }
-                };
+                let (tx_id, confirmed_round) =
+                    match self.submit_group(&verification, algorand_payload).await {
+                        Ok((id, round)) => {
+                            tracing::info!(
+                                tx_id = %id,
+                                confirmed_round = round,
+                                "Algorand settle: Transaction submitted successfully"
+                            );
+                            (id, round)
+                        }
+                        Err(e) => {
+                            tracing::error!(
+                                error = %e,
+                                "Algorand settle: Failed to submit transaction"
+                            );
+                            return Ok(SettleResponse {
+                                success: false,
+                                error_reason: Some(FacilitatorErrorReason::UnexpectedSettleError),
+                                payer: verification.payer.into(),
+                                transaction: None,
+                                network: self.network(),
+                                proof_of_payment: None,
+                                confirmation: None,
+                            });
+                        }
+                    };
+                let confirmation = Some(SettleConfirmation {
+                    block_number: confirmed_round,
+                    block_timestamp: self.round_timestamp(confirmed_round).await,
+                    gas_used: None,
+                });
 
                 Ok(SettleResponse {
                     success: true,
@@ -893,6 +1092,7 @@ impl Facilitator for AlgorandProvider {
                     transaction: Some(TransactionHash::Algorand(tx_id)),
                     network: self.network(),
                     proof_of_payment: None, // ERC-8004 not supported on Algorand
+                    confirmation,
                 })
             }
             _ => Err(FacilitatorLocalError::UnsupportedNetwork(None)),
@@ -906,7 +1106,8 @@ impl Facilitator for AlgorandProvider {
             x402_version: X402Version::V1,
             extra: Some(SupportedPaymentKindExtra {
                 fee_payer: Some(self.signer_address()),
-                tokens: None,
+                tokens: Some(self.chain.supported_tokens()),
+                accepted_tokens: None,
             }),
         }];
         Ok(SupportedPaymentKindsResponse { kinds })
@@ -920,6 +1121,10 @@ impl Facilitator for AlgorandProvider {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::nonce_store::MemoryNonceStore;
+    use crate::types::TokenAmount;
+    use algonaut::core::MicroAlgos;
+    use algonaut::transaction::transaction::{AssetTransferTransaction, Payment};
 
     #[test]
     fn test_algorand_address_validation() {
@@ -947,5 +1152,171 @@ mod tests {
 
         let testnet = AlgorandChain::try_from(Network::AlgorandTestnet).unwrap();
         assert_eq!(testnet.usdc_asa_id, USDC_ASA_ID_TESTNET);
+        assert_eq!(
+            testnet.payment_assets,
+            vec![
+                AlgorandPaymentAsset::Asa(USDC_ASA_ID_TESTNET),
+                AlgorandPaymentAsset::Algo
+            ]
+        );
+    }
+
+    #[test]
+    fn test_payment_asset_parse() {
+        for algo in ["ALGO", "algo", "0"] {
+            assert_eq!(
+                algo.parse::<AlgorandPaymentAsset>().unwrap(),
+                AlgorandPaymentAsset::Algo
+            );
+        }
+        assert_eq!(
+            "31566704".parse::<AlgorandPaymentAsset>().unwrap(),
+            AlgorandPaymentAsset::Asa(USDC_ASA_ID_MAINNET)
+        );
+        assert!("USDC".parse::<AlgorandPaymentAsset>().is_err());
+        assert_eq!(AlgorandPaymentAsset::Algo.to_string(), "ALGO");
+        assert_eq!(
+            AlgorandPaymentAsset::Asa(USDC_ASA_ID_MAINNET).to_string(),
+            "31566704"
+        );
+    }
+
+    fn requirements(asset: &str, amount: u64, pay_to: AlgoAddress) -> PaymentRequirements {
+        PaymentRequirements {
+            scheme: Scheme::Exact,
+            network: Network::AlgorandTestnet,
+            max_amount_required: TokenAmount(U256::from(amount)),
+            resource: "https://example.com/resource".parse().unwrap(),
+            description: String::new(),
+            mime_type: "application/json".to_string(),
+            output_schema: None,
+            pay_to: MixedAddress::Algorand(pay_to.to_string()),
+            max_timeout_seconds: 60,
+            asset: serde_json::from_value(serde_json::json!(asset)).unwrap(),
+            extra: None,
+        }
+    }
+
+    #[test]
+    fn test_check_native_algo_payment() {
+        let chain = AlgorandChain::try_from(Network::AlgorandTestnet).unwrap();
+        let (sender, receiver) = (Account::generate().address(), Account::generate().address());
+        let txn_type = TransactionType::Payment(Payment {
+            sender,
+            receiver,
+            amount: MicroAlgos(1_000_000),
+            close_remainder_to: None,
+        });
+
+        let payment = chain
+            .check_payment(&txn_type, &requirements("ALGO", 1_000_000, receiver))
+            .unwrap();
+        assert_eq!(payment.asset, AlgorandPaymentAsset::Algo);
+        assert_eq!(payment.amount, 1_000_000);
+        assert_eq!(payment.sender, sender);
+
+        let short = chain.check_payment(&txn_type, &requirements("ALGO", 1_000_001, receiver));
+        assert!(matches!(
+            short,
+            Err(AlgorandError::InsufficientAmount {
+                provided: 1_000_000,
+                ..
+            })
+        ));
+        let usdc = USDC_ASA_ID_TESTNET.to_string();
+        let wrong_asset = chain.check_payment(&txn_type, &requirements(&usdc, 1_000, receiver));
+        assert!(matches!(
+            wrong_asset,
+            Err(AlgorandError::AssetMismatch {
+                expected: AlgorandPaymentAsset::Asa(USDC_ASA_ID_TESTNET),
+                actual: AlgorandPaymentAsset::Algo,
+            })
+        ));
+    }
+
+    #[test]
+    fn test_check_usdc_asset_transfer() {
+        let chain = AlgorandChain::try_from(Network::AlgorandTestnet).unwrap();
+        let (sender, receiver) = (Account::generate().address(), Account::generate().address());
+        let transfer = |xfer| {
+            TransactionType::AssetTransferTransaction(AssetTransferTransaction {
+                sender,
+                xfer,
+                amount: 10_000,
+                receiver,
+                close_to: None,
+            })
+        };
+        let usdc = USDC_ASA_ID_TESTNET.to_string();
+
+        let payment = chain
+            .check_payment(
+                &transfer(USDC_ASA_ID_TESTNET),
+                &requirements(&usdc, 10_000, receiver),
+            )
+            .unwrap();
+        assert_eq!(
+            payment.asset,
+            AlgorandPaymentAsset::Asa(USDC_ASA_ID_TESTNET)
+        );
+        assert_eq!(payment.amount, 10_000);
+        assert_eq!(payment.receiver, receiver);
+
+        let unlisted = AlgorandPaymentAsset::Asa(12345);
+        let other_asa =
+            chain.check_payment(&transfer(12345), &requirements("12345", 10_000, receiver));
+        assert!(
+            matches!(other_asa, Err(AlgorandError::UnsupportedAsset(asset)) if asset == unlisted)
+        );
+        let wrong_asset = chain.check_payment(
+            &transfer(USDC_ASA_ID_TESTNET),
+            &requirements("ALGO", 10_000, receiver),
+        );
+        assert!(matches!(
+            wrong_asset,
+            Err(AlgorandError::AssetMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_supported_tokens_list_usdc_and_algo() {
+        let chain = AlgorandChain::try_from(Network::Algorand).unwrap();
+        let tokens = chain.supported_tokens();
+
+        assert_eq!(tokens.len(), 2);
+        assert_eq!(tokens[0].token, TokenType::Usdc);
+        assert_eq!(tokens[0].address.to_string(), "31566704");
+        assert_eq!(tokens[1].token, TokenType::Algo);
+        assert_eq!(tokens[1].address.to_string(), "ALGO");
+        assert!(tokens.iter().all(|token| token.decimals == 6));
+    }
+
+    fn test_provider(nonce_store: Arc<dyn NonceStore>) -> AlgorandProvider {
+        let mnemonic = Account::generate().mnemonic();
+        AlgorandProvider::try_new(mnemonic, None, Network::AlgorandTestnet, nonce_store).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_replayed_group_rejected_after_restart() {
+        let store: Arc<dyn NonceStore> = Arc::new(MemoryNonceStore::new());
+        let group_id = [7u8; 32];
+
+        let provider = test_provider(store.clone());
+        provider
+            .check_and_mark_group_used(&group_id, 1_000, 1_100)
+            .await
+            .unwrap();
+        drop(provider);
+
+        // A new provider sharing the store stands in for a restarted facilitator
+        let restarted = test_provider(store);
+        let replay = restarted
+            .check_and_mark_group_used(&group_id, 1_010, 1_100)
+            .await;
+        assert!(matches!(replay, Err(AlgorandError::InvalidAtomicGroup(_))));
+        restarted
+            .check_and_mark_group_used(&[8u8; 32], 1_010, 1_100)
+            .await
+            .unwrap();
     }
 }
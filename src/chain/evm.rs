@@ -2,7 +2,8 @@
 //!
 //! - **Verify**: simulate signature validity and transfer atomically in a single `eth_call`.
 //!   For 6492 signatures, we call the universal validator which may *prepare* (deploy) the
-//!   counterfactual wallet inside the same simulation.
+//!   counterfactual wallet inside the same simulation. Payers delegated with EIP-7702
+//!   (code `0xef0100 ‖ delegate`) must also pass their delegate's ERC-1271 `isValidSignature`.
 //! - **Settle**: if the signer wallet is not yet deployed, we deploy it (via the 6492
 //!   factory+calldata) and then call ERC-3009 `transferWithAuthorization` in a real tx.
 //!
@@ -14,8 +15,12 @@
 //! - Settlement is atomic: deploy (if needed) + transfer happen in a single user flow.
 //! - Verification does not persist state.
 
+pub mod honeypot;
+pub mod whitelist;
+
 use alloy::contract::SolCallBuilder;
 use alloy::dyn_abi::SolType;
+use alloy::eips::{BlockNumberOrTag, Encodable2718};
 use alloy::network::{
     Ethereum as AlloyEthereum, EthereumWallet, NetworkWallet, TransactionBuilder,
 };
@@ -27,14 +32,18 @@ use alloy::providers::fillers::{
 };
 use alloy::providers::ProviderBuilder;
 use alloy::providers::{
-    Identity, MulticallItem, Provider, RootProvider, WalletProvider, MULTICALL3_ADDRESS,
+    Identity, MulticallItem, PendingTransactionBuilder, Provider, RootProvider, WalletProvider,
+    MULTICALL3_ADDRESS,
 };
 use alloy::rpc::client::RpcClient;
 use alloy::rpc::types::{TransactionReceipt, TransactionRequest};
-use alloy::sol_types::{eip712_domain, Eip712Domain, SolCall, SolStruct};
+use alloy::signers::local::PrivateKeySigner;
+use alloy::sol_types::{eip712_domain, Eip712Domain, SolCall, SolError, SolStruct};
+use alloy::transports::{TransportErrorKind, TransportResult};
 use alloy::{hex, sol};
 use async_trait::async_trait;
 use dashmap::DashMap;
+use once_cell::sync::Lazy;
 use std::future::{Future, IntoFuture};
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
@@ -42,22 +51,37 @@ use tokio::sync::Mutex;
 use tracing::{instrument, Instrument};
 use tracing_core::Level;
 
-use crate::chain::{FacilitatorLocalError, FromEnvByNetworkBuild, NetworkProviderOps};
+use crate::chain::gas_oracle::{
+    ExternalOracle, FeeEstimate, GasOracleKind, GasPriceOracle, RpcGasPriceOracle,
+    DEFAULT_GAS_PRICE_MULTIPLIER,
+};
+use crate::chain::mev::{self, FlashbotsRelay, MevProtection, DEFAULT_FLASHBOTS_TIMEOUT_SECS};
+use crate::chain::rpc_router::RpcRouter;
+use crate::chain::{FacilitatorLocalError, FromEnvByNetworkBuild, NetworkProviderOps, RevertReason};
+use crate::digest_cache::DigestCache;
 use crate::erc8004::{Erc8004Extension, ProofOfPayment};
 use crate::facilitator::Facilitator;
+use crate::env_registry;
 use crate::from_env;
 use crate::network::{
     get_token_deployment, supported_tokens_for_network, AUSDDeployment, EURCDeployment, Network,
     PYUSDDeployment, USDCDeployment, USDTDeployment,
 };
+use crate::nonce_store::NonceStore;
+use crate::streaming::{
+    AllowanceCharger, Eip2612Permit, StreamingError, StreamingPaymentPayload,
+    StreamingPaymentRequirement,
+};
 use crate::timestamp::UnixTimestamp;
 use crate::types::{
     EvmAddress, EvmSignature, ExactPaymentPayload, FacilitatorErrorReason, HexEncodedNonce,
-    MixedAddress, PaymentPayload, PaymentRequirements, Scheme, SettleRequest, SettleResponse,
-    SupportedPaymentKind, SupportedPaymentKindExtra, SupportedPaymentKindsResponse,
+    MixedAddress, PaymentPayload, PaymentRequirements, Scheme, SettleConfirmation, SettleRequest,
+    SettleResponse, SupportedPaymentKind, SupportedPaymentKindExtra, SupportedPaymentKindsResponse,
     SupportedTokenInfo, TokenAmount, TransactionHash, TransferWithAuthorization, VerifyRequest,
     VerifyResponse, X402Version,
 };
+use honeypot::HoneypotDetector;
+use whitelist::TokenWhitelist;
 
 sol!(
     #[allow(missing_docs)]
@@ -77,6 +101,30 @@ sol! {
     "abi/Validator6492.json"
 }
 
+sol! {
+    /// ERC-6093 token errors, raised by OpenZeppelin v5 ERC-20 tokens.
+    #[derive(Debug)]
+    error ERC20InsufficientBalance(address sender, uint256 balance, uint256 needed);
+    #[derive(Debug)]
+    error ERC20InsufficientAllowance(address spender, uint256 allowance, uint256 needed);
+}
+
+sol! {
+    /// [ERC-1271](https://eips.ethereum.org/EIPS/eip-1271) signature validation by contract accounts.
+    #[derive(Debug)]
+    #[sol(rpc)]
+    interface IERC1271 {
+        function isValidSignature(bytes32 hash, bytes signature) external view returns (bytes4 magicValue);
+    }
+}
+
+/// Value returned by `isValidSignature` for a valid signature (its own selector).
+const ERC1271_MAGIC_VALUE: FixedBytes<4> = FixedBytes(hex!("1626ba7e"));
+
+/// Code of an EOA delegated with [EIP-7702](https://eips.ethereum.org/EIPS/eip-7702):
+/// this prefix followed by the 20-byte delegate address.
+const EIP7702_DELEGATION_PREFIX: [u8; 3] = hex!("ef0100");
+
 /// Signature verifier for EIP-6492, EIP-1271, EOA, universally deployed on the supported EVM chains
 /// If absent on a target chain, verification will fail; you should deploy the validator there.
 const VALIDATOR_ADDRESS: alloy::primitives::Address =
@@ -158,6 +206,9 @@ impl TryFrom<Network> for EvmChain {
             Network::SkaleBase => Ok(EvmChain::new(value, 1187947933)),
             Network::SkaleBaseSepolia => Ok(EvmChain::new(value, 324705682)),
             Network::Scroll => Ok(EvmChain::new(value, 534352)),
+            Network::ScrollSepolia => Ok(EvmChain::new(value, 534351)),
+            Network::ZkSyncEra => Ok(EvmChain::new(value, 324)),
+            Network::ZkSyncEraSepolia => Ok(EvmChain::new(value, 300)),
             Network::Near => Err(FacilitatorLocalError::UnsupportedNetwork(None)),
             Network::NearTestnet => Err(FacilitatorLocalError::UnsupportedNetwork(None)),
             Network::Stellar => Err(FacilitatorLocalError::UnsupportedNetwork(None)),
@@ -172,6 +223,11 @@ impl TryFrom<Network> for EvmChain {
             Network::Sui => Err(FacilitatorLocalError::UnsupportedNetwork(None)),
             #[cfg(feature = "sui")]
             Network::SuiTestnet => Err(FacilitatorLocalError::UnsupportedNetwork(None)),
+            // Served by the Hedera provider, which relays payer-signed transfers
+            #[cfg(feature = "hedera")]
+            Network::Hedera => Err(FacilitatorLocalError::UnsupportedNetwork(None)),
+            #[cfg(feature = "hedera")]
+            Network::HederaTestnet => Err(FacilitatorLocalError::UnsupportedNetwork(None)),
         }
     }
 }
@@ -215,6 +271,22 @@ pub struct EvmProvider {
     signer_cursor: Arc<AtomicUsize>,
     /// Nonce manager for resetting nonces on transaction failures.
     nonce_manager: PendingNonceManager,
+    /// Whether transactions are dry-run with `eth_call` before being submitted.
+    simulate_before_settle: bool,
+    /// EIP-1559 fee source; without one, fees are left to the provider's fillers.
+    gas_oracle: Option<Arc<dyn GasPriceOracle>>,
+    /// Base fee multiplier applied to `maxFeePerGas`.
+    gas_price_multiplier: f64,
+    /// Whether payment tokens are screened for honeypots before being accepted.
+    verify_token_legitimacy: bool,
+    /// Honeypot screening with its per-token verdict cache.
+    honeypot_detector: HoneypotDetector,
+    /// Payment tokens accepted per network; every token when unset.
+    token_whitelist: Option<Arc<TokenWhitelist>>,
+    /// Signs ERC-8004 proofs of payment; proofs are unsigned when unset.
+    proof_signer: Option<PrivateKeySigner>,
+    /// Private submission relay; transactions go to the public mempool when unset.
+    flashbots: Option<FlashbotsRelay>,
 }
 
 impl EvmProvider {
@@ -231,12 +303,18 @@ impl EvmProvider {
         if signer_addresses.is_empty() {
             return Err("wallet must contain at least one signer".into());
         }
+        let honeypot_detector = HoneypotDetector::new(signer_addresses[0]);
         let signer_addresses = Arc::new(signer_addresses);
         let signer_cursor = Arc::new(AtomicUsize::new(0));
-        let client = RpcClient::builder()
-            .connect(rpc_url)
-            .await
-            .map_err(|e| format!("Failed to connect to {network}: {e}"))?;
+        // Several comma-separated endpoints fail over to one another
+        let client = if rpc_url.contains(',') {
+            RpcClient::new(RpcRouter::from_list(rpc_url)?, false)
+        } else {
+            RpcClient::builder()
+                .connect(rpc_url)
+                .await
+                .map_err(|e| format!("Failed to connect to {network}: {e}"))?
+        };
 
         // Create nonce manager explicitly so we can store a reference for error handling
         let nonce_manager = PendingNonceManager::default();
@@ -268,9 +346,139 @@ impl EvmProvider {
             signer_addresses,
             signer_cursor,
             nonce_manager,
+            simulate_before_settle: false,
+            gas_oracle: None,
+            gas_price_multiplier: DEFAULT_GAS_PRICE_MULTIPLIER,
+            verify_token_legitimacy: network.is_mainnet(),
+            honeypot_detector,
+            token_whitelist: None,
+            proof_signer: None,
+            flashbots: None,
         })
     }
 
+    /// Price EIP-1559 settlement transactions with `oracle`.
+    ///
+    /// `maxFeePerGas` is the oracle's base fee times `gas_price_multiplier` plus its
+    /// priority fee; see [`FeeEstimate::max_fee_per_gas`].
+    pub fn with_gas_oracle(
+        mut self,
+        oracle: Arc<dyn GasPriceOracle>,
+        gas_price_multiplier: f64,
+    ) -> Self {
+        self.gas_oracle = Some(oracle);
+        self.gas_price_multiplier = gas_price_multiplier;
+        self
+    }
+
+    /// Dry-run every settlement transaction with `eth_call` before submitting it.
+    ///
+    /// A transaction that would revert is then rejected with
+    /// [`FacilitatorLocalError::SimulationFailed`] instead of being mined and burning gas.
+    pub fn with_simulate_before_settle(mut self, enabled: bool) -> Self {
+        self.simulate_before_settle = enabled;
+        self
+    }
+
+    /// Screen payment tokens for honeypots before accepting them; see [`HoneypotDetector`].
+    ///
+    /// Enabled by default on mainnets and disabled on testnets.
+    pub fn with_verify_token_legitimacy(mut self, enabled: bool) -> Self {
+        self.verify_token_legitimacy = enabled;
+        self
+    }
+
+    /// Vouch for a token that passed honeypot screening for `ttl` before simulating again.
+    pub fn with_honeypot_cache_ttl(mut self, ttl: std::time::Duration) -> Self {
+        self.honeypot_detector = self.honeypot_detector.with_ttl(ttl);
+        self
+    }
+
+    /// Accept only the payment tokens `whitelist` lists for this network.
+    pub fn with_token_whitelist(mut self, whitelist: Arc<TokenWhitelist>) -> Self {
+        self.token_whitelist = Some(whitelist);
+        self
+    }
+
+    /// Sign ERC-8004 proofs of payment with `signer`; see [`ProofOfPayment::verify_signature`].
+    pub fn with_proof_signer(mut self, signer: PrivateKeySigner) -> Self {
+        self.proof_signer = Some(signer);
+        self
+    }
+
+    /// Submit settlement transactions according to `protection`, authenticating relay
+    /// requests with `auth_signer`; see [`FlashbotsRelay::send_protected`].
+    ///
+    /// Transactions not included privately within `timeout` are broadcast publicly.
+    /// Flashbots only runs on [`Network::Ethereum`]; other networks ignore `protection`.
+    pub fn with_mev_protection(
+        mut self,
+        protection: MevProtection,
+        auth_signer: PrivateKeySigner,
+        timeout: std::time::Duration,
+    ) -> Self {
+        self.flashbots = match protection.for_network(self.chain.network) {
+            MevProtection::None => None,
+            MevProtection::Flashbots {
+                relay_url,
+                refund_percent,
+            } => Some(FlashbotsRelay::new(
+                relay_url,
+                refund_percent,
+                auth_signer,
+                timeout,
+            )),
+        };
+        self
+    }
+
+    /// Sign `txr` and submit it through `relay`, falling back to the public mempool.
+    async fn send_private_transaction(
+        &self,
+        relay: &FlashbotsRelay,
+        txr: TransactionRequest,
+        from_address: Address,
+    ) -> TransportResult<PendingTransactionBuilder<AlloyEthereum>> {
+        let envelope = self
+            .inner
+            .fill(txr)
+            .await?
+            .try_into_envelope()
+            .map_err(|e| TransportErrorKind::custom_str(&e.to_string()))?;
+        relay
+            .send_protected(
+                &self.inner,
+                &envelope.encoded_2718(),
+                *envelope.tx_hash(),
+                from_address,
+            )
+            .await
+    }
+
+    /// Dry-run a transaction with `eth_call` against the latest block.
+    ///
+    /// # Errors
+    /// Returns [`FacilitatorLocalError::SimulationFailed`] if the call reverts, and
+    /// [`FacilitatorLocalError::ContractCall`] if the node could not run it.
+    async fn simulate(&self, txr: &TransactionRequest) -> Result<(), FacilitatorLocalError> {
+        let result = self
+            .inner
+            .call(txr.clone())
+            .into_future()
+            .instrument(tracing::info_span!("simulate_transaction", otel.kind = "client"))
+            .await;
+        match result {
+            Ok(_) => Ok(()),
+            Err(e) => match revert_reason(&e) {
+                Some(reason) => {
+                    tracing::warn!(reason = %reason, "Transaction reverted in simulation, not submitting");
+                    Err(FacilitatorLocalError::SimulationFailed(reason))
+                }
+                None => Err(FacilitatorLocalError::ContractCall(format!("{e:?}"))),
+            },
+        }
+    }
+
     /// Round-robin selection of next signer from wallet.
     fn next_signer_address(&self) -> Address {
         debug_assert!(!self.signer_addresses.is_empty());
@@ -295,6 +503,18 @@ pub trait MetaEvmProvider {
     fn inner(&self) -> &Self::Inner;
     /// Returns reference to chain descriptor.
     fn chain(&self) -> &EvmChain;
+    /// Returns the honeypot detector payment tokens are screened with, if screening is enabled.
+    fn honeypot_detector(&self) -> Option<&HoneypotDetector> {
+        None
+    }
+    /// Returns the whitelist payment tokens are checked against, if one is configured.
+    fn token_whitelist(&self) -> Option<&TokenWhitelist> {
+        None
+    }
+    /// Returns the signer of ERC-8004 proofs of payment, if proofs are signed.
+    fn proof_signer(&self) -> Option<&PrivateKeySigner> {
+        None
+    }
 
     /// Sends a meta-transaction to the network.
     fn send_transaction(
@@ -325,6 +545,18 @@ impl MetaEvmProvider for EvmProvider {
         &self.chain
     }
 
+    fn honeypot_detector(&self) -> Option<&HoneypotDetector> {
+        self.verify_token_legitimacy.then_some(&self.honeypot_detector)
+    }
+
+    fn token_whitelist(&self) -> Option<&TokenWhitelist> {
+        self.token_whitelist.as_deref()
+    }
+
+    fn proof_signer(&self) -> Option<&PrivateKeySigner> {
+        self.proof_signer.as_ref()
+    }
+
     /// Send a meta-transaction with provided `to`, `calldata`, and automatically selected signer.
     ///
     /// This method constructs a transaction from the provided [`MetaTransaction`], automatically
@@ -338,9 +570,16 @@ impl MetaEvmProvider for EvmProvider {
     ///
     /// # Gas Pricing Strategy
     ///
-    /// - **EIP-1559 networks**: Uses automatic gas pricing via the provider's fillers.
+    /// - **EIP-1559 networks**: With a gas oracle (see [`EvmProvider::with_gas_oracle`]), sets
+    ///   `maxFeePerGas`/`maxPriorityFeePerGas` from its estimate; otherwise uses automatic
+    ///   gas pricing via the provider's fillers.
     /// - **Legacy networks**: Fetches the current gas price using `get_gas_price()` and sets it explicitly.
     ///
+    /// # Simulation
+    ///
+    /// With [`EvmProvider::with_simulate_before_settle`] enabled, the transaction is first
+    /// dry-run with `eth_call`; if it reverts, nothing is submitted.
+    ///
     /// # Timeout Configuration
     ///
     /// Receipt fetching is subject to a configurable timeout:
@@ -358,6 +597,8 @@ impl MetaEvmProvider for EvmProvider {
     ///
     /// # Errors
     ///
+    /// Returns [`FacilitatorLocalError::SimulationFailed`] if the dry run reverts.
+    ///
     /// Returns [`FacilitatorLocalError::ContractCall`] if:
     /// - Gas price fetching fails (on legacy networks)
     /// - Transaction sending fails
@@ -366,7 +607,19 @@ impl MetaEvmProvider for EvmProvider {
         &self,
         tx: MetaTransaction,
     ) -> Result<TransactionReceipt, Self::Error> {
-        let from_address = self.next_signer_address();
+        self.send_transaction_from(tx, self.next_signer_address())
+            .await
+    }
+}
+
+impl EvmProvider {
+    /// [`MetaEvmProvider::send_transaction`] from `from_address`, which must be one of this
+    /// provider's signers. Streaming charges are sent by the spender their permit names.
+    async fn send_transaction_from(
+        &self,
+        tx: MetaTransaction,
+        from_address: Address,
+    ) -> Result<TransactionReceipt, FacilitatorLocalError> {
         let mut txr = TransactionRequest::default()
             .with_to(tx.to)
             .with_from(from_address)
@@ -379,10 +632,39 @@ impl MetaEvmProvider for EvmProvider {
                 .await
                 .map_err(|e| FacilitatorLocalError::ContractCall(format!("{e:?}")))?;
             txr.set_gas_price(gas);
+        } else if let Some(oracle) = &self.gas_oracle {
+            match oracle
+                .estimate_max_fee()
+                .instrument(tracing::info_span!("estimate_max_fee"))
+                .await
+            {
+                Ok((base_fee, priority_fee)) => {
+                    let fees = FeeEstimate::max_fee_per_gas(
+                        base_fee,
+                        priority_fee,
+                        self.gas_price_multiplier,
+                    );
+                    txr.set_max_fee_per_gas(fees.max_fee_per_gas);
+                    txr.set_max_priority_fee_per_gas(fees.max_priority_fee_per_gas);
+                }
+                // The fillers still price the transaction
+                Err(e) => tracing::warn!(error = %e, "Gas oracle failed, using provider estimate"),
+            }
+        }
+
+        if self.simulate_before_settle {
+            self.simulate(&txr).await?;
         }
 
         // Send transaction with error handling for nonce reset
-        let pending_tx = match self.inner.send_transaction(txr).await {
+        let sent = match &self.flashbots {
+            Some(relay) => {
+                self.send_private_transaction(relay, txr, from_address)
+                    .await
+            }
+            None => self.inner.send_transaction(txr).await,
+        };
+        let pending_tx = match sent {
             Ok(pending) => pending,
             Err(e) => {
                 // Transaction submission failed - reset nonce to force requery
@@ -399,10 +681,7 @@ impl MetaEvmProvider for EvmProvider {
             _ => 30,
         };
         let timeout = std::time::Duration::from_secs(
-            std::env::var("TX_RECEIPT_TIMEOUT_SECS")
-                .ok()
-                .and_then(|s| s.parse().ok())
-.unwrap_or(default_timeout),
+            env_registry::parse("TX_RECEIPT_TIMEOUT_SECS").unwrap_or(default_timeout),
         );
 
         let watcher = pending_tx
@@ -433,16 +712,20 @@ impl NetworkProviderOps for EvmProvider {
 }
 
 impl FromEnvByNetworkBuild for EvmProvider {
-    async fn from_env(network: Network) -> Result<Option<Self>, Box<dyn std::error::Error>> {
+    async fn from_env(
+        network: Network,
+        _nonce_store: Arc<dyn NonceStore>,
+    ) -> Result<Option<Self>, Box<dyn std::error::Error>> {
         let env_var = from_env::rpc_env_name_from_network(network);
-        let rpc_url = match std::env::var(env_var).ok() {
+        let rpc_url = match env_registry::var(env_var) {
             Some(rpc_url) => rpc_url,
             None => {
                 tracing::warn!(network=%network, "no RPC URL configured, skipping");
                 return Ok(None);
             }
         };
-        let wallet = from_env::SignerType::from_env()?.make_evm_wallet(network)?;
+        let signer_type = from_env::SignerType::from_env()?;
+        let wallet = signer_type.make_evm_wallet(network)?;
         let is_eip1559 = match network {
             Network::BaseSepolia => true,
             Network::Base => true,
@@ -473,6 +756,9 @@ impl FromEnvByNetworkBuild for EvmProvider {
             Network::SkaleBase => false, // SKALE does NOT support EIP-1559, uses legacy tx
             Network::SkaleBaseSepolia => false, // SKALE does NOT support EIP-1559, uses legacy tx
             Network::Scroll => true, // Scroll zkEVM supports EIP-1559
+            Network::ScrollSepolia => true,
+            Network::ZkSyncEra => true, // zkSync Era accepts EIP-1559 (type 2) transactions
+            Network::ZkSyncEraSepolia => true,
             Network::Near => false, // NEAR is not an EVM chain
             Network::NearTestnet => false, // NEAR is not an EVM chain
             Network::Stellar => false, // Stellar is not an EVM chain
@@ -487,8 +773,63 @@ impl FromEnvByNetworkBuild for EvmProvider {
             Network::Sui => false, // Sui is not an EVM chain
             #[cfg(feature = "sui")]
             Network::SuiTestnet => false, // Sui is not an EVM chain
+            #[cfg(feature = "hedera")]
+            Network::Hedera => false, // Hedera has its own provider
+            #[cfg(feature = "hedera")]
+            Network::HederaTestnet => false, // Hedera has its own provider
         };
-        let provider = EvmProvider::try_new(wallet, &rpc_url, is_eip1559, network).await?;
+        let proof_signer = signer_type.make_evm_proof_signer(network)?;
+        let mut provider = EvmProvider::try_new(wallet, &rpc_url, is_eip1559, network)
+            .await?
+            .with_simulate_before_settle(env_registry::flag("EVM_SIMULATE_BEFORE_SETTLE"))
+            .with_proof_signer(proof_signer.clone());
+        if network == Network::Ethereum {
+            let protection = MevProtection::from_env()?;
+            if protection != MevProtection::None {
+                let auth_signer = mev::auth_signer_from_env()?;
+                if auth_signer.address() == proof_signer.address() {
+                    return Err("FLASHBOTS_AUTH_KEY must not be the proof signing key".into());
+                }
+                let timeout = env_registry::parse("FLASHBOTS_TIMEOUT_SECS")
+                    .unwrap_or(DEFAULT_FLASHBOTS_TIMEOUT_SECS);
+                provider = provider.with_mev_protection(
+                    protection,
+                    auth_signer,
+                    std::time::Duration::from_secs(timeout),
+                );
+            }
+        }
+        if let Some(enabled) = env_registry::parse("EVM_VERIFY_TOKEN_LEGITIMACY") {
+            provider = provider.with_verify_token_legitimacy(enabled);
+        }
+        if let Some(secs) = env_registry::parse("EVM_HONEYPOT_CACHE_TTL_SECS") {
+            provider = provider.with_honeypot_cache_ttl(std::time::Duration::from_secs(secs));
+        }
+        let whitelist = TokenWhitelist::from_env()?;
+        if whitelist.accepted_tokens(network).is_some() {
+            provider = provider.with_token_whitelist(Arc::new(whitelist));
+        }
+        if is_eip1559 {
+            let kind: GasOracleKind = env_registry::var("EVM_GAS_ORACLE")
+                .unwrap_or_default()
+                .parse()?;
+            let oracle: Option<Arc<dyn GasPriceOracle>> = match kind {
+                GasOracleKind::None => None,
+                GasOracleKind::Rpc => {
+                    Some(Arc::new(RpcGasPriceOracle::new(provider.inner.clone())))
+                }
+                GasOracleKind::External(api) => Some(Arc::new(ExternalOracle::new(
+                    api,
+                    provider.chain.chain_id,
+                    env_registry::var("EVM_GAS_ORACLE_API_KEY"),
+                ))),
+            };
+            if let Some(oracle) = oracle {
+                let multiplier = env_registry::parse("EVM_GAS_PRICE_MULTIPLIER")
+                    .unwrap_or(DEFAULT_GAS_PRICE_MULTIPLIER);
+                provider = provider.with_gas_oracle(oracle, multiplier);
+            }
+        }
         Ok(Some(provider))
     }
 }
@@ -511,12 +852,20 @@ where
     /// - [`FacilitatorLocalError::NetworkMismatch`], [`FacilitatorLocalError::SchemeMismatch`], [`FacilitatorLocalError::ReceiverMismatch`] if inputs are inconsistent.
     /// - [`FacilitatorLocalError::InvalidTiming`] if outside `validAfter/validBefore`.
     /// - [`FacilitatorLocalError::InsufficientFunds`] / `FacilitatorLocalError::InsufficientValue` on balance/value checks.
+    /// - [`FacilitatorLocalError::TokenNotWhitelisted`] if a token whitelist is configured and the asset is not on it.
+    /// - [`FacilitatorLocalError::HoneypotDetected`] if token screening is enabled and the token can't be transferred on.
     /// - [`FacilitatorLocalError::ContractCall`] if on-chain calls revert.
     async fn verify(&self, request: &VerifyRequest) -> Result<VerifyResponse, Self::Error> {
         let payload = &request.payment_payload;
         let requirements = &request.payment_requirements;
-        let (contract, payment, eip712_domain) =
-            assert_valid_payment(self.inner(), self.chain(), payload, requirements).await?;
+        let (contract, payment, eip712_domain) = assert_valid_payment(
+            self.inner(),
+            self.chain(),
+            self.token_whitelist(),
+            payload,
+            requirements,
+        )
+        .await?;
 
         let signed_message = SignedMessage::extract(&payment, &eip712_domain)?;
         let payer = signed_message.address;
@@ -602,6 +951,17 @@ where
                 }
             }
             StructuredSignature::EIP1271(signature) => {
+                // EOAs delegated with EIP-7702 validate signatures through their delegate's
+                // ERC-1271 implementation rather than plain ECDSA
+                if let Some(delegate) = eip7702_delegate_of(self.inner(), &payer).await? {
+                    tracing::debug!(payer = %payer, delegate = %delegate, "Payer is an EIP-7702 delegated account");
+                    if !verify_erc1271_signature(self.inner(), payer, hash, signature.clone()).await? {
+                        return Err(FacilitatorLocalError::InvalidSignature(
+                            payer.into(),
+                            "Incorrect ERC-1271 signature".to_string(),
+                        ));
+                    }
+                }
                 // It is EOA or EIP-1271 signature, which we can pass to the transfer simulation
                 // Check if the token requires v,r,s signature variant (e.g., PYUSD)
                 if requires_vrs_signature(*contract.address()) {
@@ -650,6 +1010,13 @@ where
             }
         }
 
+        // Only screen the token once the payer is known to have authorized the transfer
+        if let Some(detector) = self.honeypot_detector() {
+            detector
+                .check(self.inner(), *contract.address(), payer)
+                .await?;
+        }
+
         Ok(VerifyResponse::valid(payer.into()))
     }
 
@@ -674,11 +1041,29 @@ where
     async fn settle(&self, request: &SettleRequest) -> Result<SettleResponse, Self::Error> {
         let payload = &request.payment_payload;
         let requirements = &request.payment_requirements;
-        let (contract, payment, eip712_domain) =
-            assert_valid_payment(self.inner(), self.chain(), payload, requirements).await?;
+        let (contract, payment, eip712_domain) = assert_valid_payment(
+            self.inner(),
+            self.chain(),
+            self.token_whitelist(),
+            payload,
+            requirements,
+        )
+        .await?;
 
         let signed_message = SignedMessage::extract(&payment, &eip712_domain)?;
         let payer = signed_message.address;
+        if let Some(detector) = self.honeypot_detector() {
+            // Screen the token only for payers that actually signed the authorization
+            if !is_signed_by_payer(self.inner(), &signed_message).await? {
+                return Err(FacilitatorLocalError::InvalidSignature(
+                    payer.into(),
+                    "Incorrect signature".to_string(),
+                ));
+            }
+            detector
+                .check(self.inner(), *contract.address(), payer)
+                .await?;
+        }
         let transaction_receipt_fut = match signed_message.signature {
             StructuredSignature::EIP6492 {
                 factory,
@@ -872,16 +1257,24 @@ where
                 "transferWithAuthorization_0 succeeded"
             );
 
+            let confirmation = settle_confirmation(self.inner(), &receipt).await;
+
             // Check if ERC-8004 extension is present and create ProofOfPayment
-            let proof_of_payment = create_proof_of_payment(
-                &receipt,
-                requirements,
-                payload.network,
-                payment.from.into(),
-                requirements.pay_to.clone(),
-                TokenAmount::from(payment.value),
-                requirements.asset.clone(),
-            );
+            let proof_of_payment = confirmation
+                .and_then(|confirmation| {
+                    create_proof_of_payment(
+                        &receipt,
+                        confirmation,
+                        requirements,
+                        payload.network,
+                        payment.from.into(),
+                        TokenAmount::from(payment.value),
+                    )
+                })
+                .map(|proof| match self.proof_signer() {
+                    Some(signer) => sign_proof_of_payment(proof, signer),
+                    None => proof,
+                });
 
             Ok(SettleResponse {
                 success: true,
@@ -890,6 +1283,7 @@ where
                 transaction: Some(TransactionHash::Evm(receipt.transaction_hash.0)),
                 network: payload.network,
                 proof_of_payment,
+                confirmation,
             })
         } else {
             tracing::event!(
@@ -905,6 +1299,7 @@ where
                 transaction: Some(TransactionHash::Evm(receipt.transaction_hash.0)),
                 network: payload.network,
                 proof_of_payment: None,
+                confirmation: None,
             })
         }
     }
@@ -924,13 +1319,18 @@ where
                 })
             })
             .collect();
+        let accepted_tokens: Option<Vec<MixedAddress>> = self
+            .token_whitelist()
+            .and_then(|whitelist| whitelist.accepted_tokens(network))
+            .map(|tokens| tokens.iter().map(|&token| token.into()).collect());
 
-        let extra = if tokens.is_empty() {
+        let extra = if tokens.is_empty() && accepted_tokens.is_none() {
             None
         } else {
             Some(SupportedPaymentKindExtra {
                 fee_payer: None, // Set at FacilitatorLocal level
-                tokens: Some(tokens),
+                tokens: (!tokens.is_empty()).then_some(tokens),
+                accepted_tokens,
             })
         };
 
@@ -944,20 +1344,198 @@ where
     }
 }
 
+/// Streaming allowances are EIP-2612 permits naming one of this provider's signers as
+/// spender; that signer submits the permit and every `transferFrom` charging it.
+impl AllowanceCharger for EvmProvider {
+    /// Simulates the permit from its spender and checks the owner holds the deposit.
+    async fn check_allowance(
+        &self,
+        payload: &StreamingPaymentPayload,
+        requirement: &StreamingPaymentRequirement,
+    ) -> Result<(), StreamingError> {
+        let permit = &payload.permit;
+        if !self.signer_addresses.contains(&permit.spender.0) {
+            return Err(StreamingError::InvalidAllowance(
+                "streaming_permit_wrong_spender".to_string(),
+            ));
+        }
+        let token = streaming_address(&requirement.asset)?;
+        if let Some(whitelist) = self.token_whitelist() {
+            whitelist
+                .check(self.chain.network, token)
+                .map_err(|e| StreamingError::InvalidAllowance(e.to_string()))?;
+        }
+
+        let txr = TransactionRequest::default()
+            .with_to(token)
+            .with_from(permit.spender.0)
+            .with_input(permit_calldata(token, permit)?);
+        self.simulate(&txr).await.map_err(|e| match e {
+            FacilitatorLocalError::SimulationFailed(reason) => {
+                StreamingError::InvalidAllowance(format!("invalid_permit: {reason}"))
+            }
+            e => StreamingError::Charge(e.to_string()),
+        })?;
+
+        let balance = USDC::new(token, self.inner())
+            .balanceOf(permit.owner.0)
+            .call()
+            .into_future()
+            .instrument(tracing::info_span!(
+                "fetch_token_balance",
+                token_contract = %token,
+                sender = %permit.owner,
+                otel.kind = "client"
+            ))
+            .await
+            .map_err(|e| StreamingError::Charge(format!("{e:?}")))?;
+        if balance < permit.value.0 {
+            return Err(StreamingError::InvalidAllowance(
+                "insufficient_funds".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    async fn activate(
+        &self,
+        payload: &StreamingPaymentPayload,
+        requirement: &StreamingPaymentRequirement,
+    ) -> Result<Option<TransactionHash>, StreamingError> {
+        let permit = &payload.permit;
+        let token = streaming_address(&requirement.asset)?;
+        let receipt = self
+            .send_transaction_from(
+                MetaTransaction {
+                    to: token,
+                    calldata: permit_calldata(token, permit)?,
+                    confirmations: 1,
+                },
+                permit.spender.0,
+            )
+            .instrument(tracing::info_span!("call_permit",
+                owner = %permit.owner,
+                spender = %permit.spender,
+                value = %permit.value,
+                token_contract = %token,
+                otel.kind = "client",
+            ))
+            .await
+            .map_err(|e| StreamingError::Charge(e.to_string()))?;
+        if !receipt.status() {
+            return Err(StreamingError::Charge(format!(
+                "permit transaction {} reverted",
+                receipt.transaction_hash
+            )));
+        }
+        Ok(Some(TransactionHash::Evm(receipt.transaction_hash.0)))
+    }
+
+    async fn charge(
+        &self,
+        payload: &StreamingPaymentPayload,
+        requirement: &StreamingPaymentRequirement,
+        amount: TokenAmount,
+    ) -> Result<TransactionHash, StreamingError> {
+        let permit = &payload.permit;
+        let token = streaming_address(&requirement.asset)?;
+        let pay_to = streaming_address(&requirement.pay_to)?;
+        let calldata = USDC::transferFromCall {
+            from: permit.owner.0,
+            to: pay_to,
+            value: amount.0,
+        }
+        .abi_encode();
+        let receipt = self
+            .send_transaction_from(
+                MetaTransaction {
+                    to: token,
+                    calldata: calldata.into(),
+                    confirmations: 1,
+                },
+                permit.spender.0,
+            )
+            .instrument(tracing::info_span!("call_transferFrom",
+                from = %permit.owner,
+                to = %pay_to,
+                value = %amount,
+                token_contract = %token,
+                otel.kind = "client",
+            ))
+            .await
+            .map_err(|e| StreamingError::Charge(e.to_string()))?;
+        if !receipt.status() {
+            return Err(StreamingError::Charge(format!(
+                "transferFrom transaction {} reverted",
+                receipt.transaction_hash
+            )));
+        }
+        Ok(TransactionHash::Evm(receipt.transaction_hash.0))
+    }
+}
+
+/// Block, block timestamp and gas used of a settlement `receipt`.
+///
+/// The block timestamp is fetched from `provider`; if the block cannot be fetched the
+/// confirmation is reported without a timestamp rather than with a wrong one.
+pub async fn settle_confirmation<P: Provider>(
+    provider: &P,
+    receipt: &TransactionReceipt,
+) -> Option<SettleConfirmation> {
+    let Some(block_number) = receipt.block_number else {
+        tracing::warn!(
+            tx = %receipt.transaction_hash,
+            "Receipt has no block number; settling without confirmation details"
+        );
+        return None;
+    };
+    let block_timestamp = match provider
+        .get_block_by_number(BlockNumberOrTag::Number(block_number))
+        .await
+    {
+        Ok(Some(block)) => Some(block.header.timestamp),
+        Ok(None) => {
+            tracing::warn!(
+                tx = %receipt.transaction_hash,
+                block = block_number,
+                "Settlement block not found; settling without its timestamp"
+            );
+            None
+        }
+        Err(e) => {
+            tracing::warn!(
+                tx = %receipt.transaction_hash,
+                block = block_number,
+                error = %e,
+                "Failed to fetch settlement block; settling without its timestamp"
+            );
+            None
+        }
+    };
+    Some(SettleConfirmation {
+        block_number,
+        block_timestamp,
+        gas_used: Some(receipt.gas_used),
+    })
+}
+
 /// Create ProofOfPayment if ERC-8004 extension is active.
 ///
 /// Returns Some(ProofOfPayment) if:
 /// - The `8004-reputation` extension is present in payment requirements
 /// - The network supports ERC-8004 contracts
 /// - The include_proof flag is true (default)
-fn create_proof_of_payment(
+///
+/// Payee and token come from `requirements`, which verification has already matched
+/// against the payload. Block and timestamp come from the settlement's `confirmation`
+/// (see [`settle_confirmation`]), so that the proof passes [`ProofOfPayment::verify`].
+pub fn create_proof_of_payment(
     receipt: &TransactionReceipt,
+    confirmation: SettleConfirmation,
     requirements: &PaymentRequirements,
     network: Network,
     payer: MixedAddress,
-    payee: MixedAddress,
     amount: TokenAmount,
-    token: MixedAddress,
 ) -> Option<ProofOfPayment> {
     // Check if ERC-8004 extension is present
     let extension = Erc8004Extension::from_extra(&requirements.extra)?;
@@ -976,34 +1554,41 @@ fn create_proof_of_payment(
         return None;
     }
 
-    // Extract block number and timestamp from receipt
-    let block_number = receipt.block_number.unwrap_or(0);
-    // Use current timestamp as fallback (block timestamp requires additional RPC call)
-    let timestamp = std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .map(|d| d.as_secs())
-        .unwrap_or(0);
-
     let proof = ProofOfPayment::new(
         TransactionHash::Evm(receipt.transaction_hash.0),
-        block_number,
+        confirmation,
         network,
         payer,
-        payee,
+        requirements.pay_to.clone(),
         amount,
-        token,
-        timestamp,
+        requirements.asset.clone(),
     );
 
     tracing::info!(
         tx = %receipt.transaction_hash,
-        block = block_number,
+        block = confirmation.block_number,
         "Created ERC-8004 ProofOfPayment"
     );
 
     Some(proof)
 }
 
+/// Sign `proof` with the facilitator's proof `signer`.
+///
+/// A proof that cannot be signed is returned unsigned: it still verifies on-chain.
+fn sign_proof_of_payment(proof: ProofOfPayment, signer: &PrivateKeySigner) -> ProofOfPayment {
+    match proof.clone().signed_by(signer) {
+        Ok(signed) => signed,
+        Err(e) => {
+            tracing::warn!(
+                error = %e,
+                "Failed to sign ERC-8004 ProofOfPayment; returning it unsigned"
+            );
+            proof
+        }
+    }
+}
+
 /// A prepared call to `transferWithAuthorization` (ERC-3009) including all derived fields.
 ///
 /// This struct wraps the assembled call builder, making it reusable across verification
@@ -1097,6 +1682,47 @@ async fn assert_enough_balance<P: Provider>(
     }
 }
 
+/// Revert reason of a failed `eth_call`, or `None` if the node failed without reverting.
+fn revert_reason(error: &alloy::transports::TransportError) -> Option<RevertReason> {
+    let payload = error.as_error_resp()?;
+    match payload.as_revert_data() {
+        Some(data) => Some(decode_revert_reason(&data)),
+        None if payload.message.to_lowercase().contains("revert") => {
+            Some(classify_revert_message(payload.message.to_string()))
+        }
+        None => None,
+    }
+}
+
+/// Decodes revert data into a typed reason.
+///
+/// Recognizes the ERC-6093 errors of OpenZeppelin v5 tokens and the revert strings of
+/// older OpenZeppelin and FiatToken (USDC, EURC) contracts.
+fn decode_revert_reason(data: &[u8]) -> RevertReason {
+    if ERC20InsufficientBalance::abi_decode(data).is_ok() {
+        return RevertReason::InsufficientBalance;
+    }
+    if ERC20InsufficientAllowance::abi_decode(data).is_ok() {
+        return RevertReason::InsufficientAllowance;
+    }
+    match alloy::sol_types::decode_revert_reason(data) {
+        Some(message) => classify_revert_message(message),
+        None => RevertReason::Other(hex::encode_prefixed(data)),
+    }
+}
+
+/// Maps well-known ERC-20 revert strings (e.g. "ERC20: transfer amount exceeds balance").
+fn classify_revert_message(message: String) -> RevertReason {
+    let lower = message.to_lowercase();
+    if lower.contains("exceeds balance") || lower.contains("insufficient balance") {
+        RevertReason::InsufficientBalance
+    } else if lower.contains("exceeds allowance") || lower.contains("insufficient allowance") {
+        RevertReason::InsufficientAllowance
+    } else {
+        RevertReason::Other(message)
+    }
+}
+
 /// Verifies that the declared `value` in the payload is sufficient for the required amount.
 ///
 /// This is a static check (not on-chain) that compares two numbers.
@@ -1143,6 +1769,104 @@ async fn is_contract_deployed<P: Provider>(
     Ok(!bytes.is_empty())
 }
 
+/// Delegate of `address` if it is an EOA delegated with EIP-7702.
+///
+/// # Errors
+/// Return [`FacilitatorLocalError::ContractCall`] if the `eth_getCode` call fails.
+async fn eip7702_delegate_of<P: Provider>(
+    provider: P,
+    address: &Address,
+) -> Result<Option<Address>, FacilitatorLocalError> {
+    let code = provider
+        .get_code_at(*address)
+        .into_future()
+        .instrument(tracing::info_span!("get_code_at",
+            address = %address,
+            otel.kind = "client",
+        ))
+        .await
+        .map_err(|e| FacilitatorLocalError::ContractCall(format!("{e:?}")))?;
+    Ok(eip7702_delegate(&code))
+}
+
+/// Decodes an EIP-7702 delegation designator (`0xef0100 ‖ delegate`) from account code.
+fn eip7702_delegate(code: &[u8]) -> Option<Address> {
+    code.strip_prefix(&EIP7702_DELEGATION_PREFIX)
+        .filter(|delegate| delegate.len() == 20)
+        .map(Address::from_slice)
+}
+
+/// Check that the payer of `signed_message` signed it, whatever its kind of account.
+///
+/// Goes through the universal EIP-6492 validator, which handles EOAs, deployed ERC-1271
+/// wallets and counterfactual wallets alike. A reverting validation rejects the signature.
+///
+/// # Errors
+/// Return [`FacilitatorLocalError::ContractCall`] if the node cannot be reached.
+async fn is_signed_by_payer<P: Provider>(
+    provider: P,
+    signed_message: &SignedMessage,
+) -> Result<bool, FacilitatorLocalError> {
+    let signature = match &signed_message.signature {
+        StructuredSignature::EIP6492 { original, .. } => original.clone(),
+        StructuredSignature::EIP1271(signature) => signature.clone(),
+    };
+    let result = Validator6492::new(VALIDATOR_ADDRESS, provider)
+        .isValidSig(signed_message.address, signed_message.hash, signature)
+        .call()
+        .into_future()
+        .instrument(tracing::info_span!("call_isValidSig",
+            address = %signed_message.address,
+            otel.kind = "client",
+        ))
+        .await;
+    match result {
+        Ok(is_valid) => Ok(is_valid),
+        Err(alloy::contract::Error::TransportError(e)) if e.as_error_resp().is_none() => {
+            Err(FacilitatorLocalError::ContractCall(format!("{e:?}")))
+        }
+        Err(e) => {
+            tracing::debug!(error = %e, "isValidSig call failed, rejecting the signature");
+            Ok(false)
+        }
+    }
+}
+
+/// Ask the account at `address` whether `signature` is valid for `hash` (ERC-1271).
+///
+/// An account that reverts or returns anything but the magic value rejects the signature.
+///
+/// # Errors
+/// Return [`FacilitatorLocalError::ContractCall`] if the node cannot be reached.
+#[instrument(skip_all, err, fields(address = %address, hash = %hash))]
+pub async fn verify_erc1271_signature<P: Provider>(
+    provider: P,
+    address: Address,
+    hash: FixedBytes<32>,
+    signature: Bytes,
+) -> Result<bool, FacilitatorLocalError> {
+    let result = IERC1271::new(address, provider)
+        .isValidSignature(hash, signature)
+        .call()
+        .into_future()
+        .instrument(tracing::info_span!("call_isValidSignature",
+            address = %address,
+            otel.kind = "client",
+        ))
+        .await;
+    match result {
+        Ok(magic_value) => Ok(magic_value == ERC1271_MAGIC_VALUE),
+        // Reverts come back as error responses; anything else means the node is unreachable
+        Err(alloy::contract::Error::TransportError(e)) if e.as_error_resp().is_none() => {
+            Err(FacilitatorLocalError::ContractCall(format!("{e:?}")))
+        }
+        Err(e) => {
+            tracing::debug!(error = %e, "isValidSignature call failed, rejecting the signature");
+            Ok(false)
+        }
+    }
+}
+
 /// Constructs the correct EIP-712 domain for signature verification.
 ///
 /// Resolves the `name` and `version` based on:
@@ -1278,6 +2002,7 @@ fn find_known_eip712_metadata(
 async fn assert_valid_payment<P: Provider>(
     provider: P,
     chain: &EvmChain,
+    whitelist: Option<&TokenWhitelist>,
     payload: &PaymentPayload,
     requirements: &PaymentRequirements,
 ) -> Result<(USDC::USDCInstance<P>, ExactEvmPayment, Eip712Domain), FacilitatorLocalError> {
@@ -1300,6 +2025,10 @@ async fn assert_valid_payment<P: Provider>(
         ExactPaymentPayload::Sui(_) => {
             return Err(FacilitatorLocalError::UnsupportedNetwork(None));
         }
+        #[cfg(feature = "hedera")]
+        ExactPaymentPayload::Hedera(_) => {
+            return Err(FacilitatorLocalError::UnsupportedNetwork(None));
+        }
     };
     let payer = payment_payload.authorization.from;
     if payload.network != chain.network {
@@ -1344,6 +2073,9 @@ async fn assert_valid_payment<P: Provider>(
         .clone()
         .try_into()
         .map_err(|e| FacilitatorLocalError::InvalidAddress(format!("{e:?}")))?;
+    if let Some(whitelist) = whitelist {
+        whitelist.check(chain.network, asset_address)?;
+    }
     let contract = USDC::new(asset_address, provider);
 
     let domain = assert_domain(chain, &contract, payload, &asset_address, requirements).await?;
@@ -1428,6 +2160,51 @@ fn requires_vrs_signature(contract_address: Address) -> bool {
 
 /// Split a 65-byte signature into its v, r, s components.
 ///
+/// EVM address of a streaming requirement's `asset` or `payTo`.
+fn streaming_address(address: &MixedAddress) -> Result<Address, StreamingError> {
+    match address {
+        MixedAddress::Evm(address) => Ok(address.0),
+        _ => Err(StreamingError::InvalidAllowance(
+            "invalid_streaming_address".to_string(),
+        )),
+    }
+}
+
+/// Calldata submitting `permit` to `token`, with a split v,r,s signature for tokens that
+/// need one and the raw signature (which also covers ERC-1271 owners) otherwise.
+fn permit_calldata(token: Address, permit: &Eip2612Permit) -> Result<Bytes, StreamingError> {
+    let owner = permit.owner.0;
+    let spender = permit.spender.0;
+    let value = permit.value.0;
+    let deadline = U256::from(permit.deadline.0);
+    let signature = Bytes::from(permit.signature.0.clone());
+    let calldata = if requires_vrs_signature(token) {
+        let (v, r, s) = split_signature(&signature).map_err(|_| {
+            StreamingError::InvalidAllowance("invalid_permit_signature".to_string())
+        })?;
+        USDC::permit_1Call {
+            owner,
+            spender,
+            value,
+            deadline,
+            v,
+            r,
+            s,
+        }
+        .abi_encode()
+    } else {
+        USDC::permit_0Call {
+            owner,
+            spender,
+            value,
+            deadline,
+            signature,
+        }
+        .abi_encode()
+    };
+    Ok(calldata.into())
+}
+
 /// Standard Ethereum signatures are 65 bytes: r (32 bytes) + s (32 bytes) + v (1 byte).
 ///
 /// # Errors
@@ -1585,15 +2362,7 @@ impl SignedMessage {
         payment: &ExactEvmPayment,
         domain: &Eip712Domain,
     ) -> Result<Self, FacilitatorLocalError> {
-        let transfer_with_authorization = TransferWithAuthorization {
-            from: payment.from.0,
-            to: payment.to.0,
-            value: payment.value.into(),
-            validAfter: payment.valid_after.into(),
-            validBefore: payment.valid_before.into(),
-            nonce: FixedBytes(payment.nonce.0),
-        };
-        let eip712_hash = transfer_with_authorization.eip712_signing_hash(domain);
+        let eip712_hash = transfer_authorization_digest(payment, domain);
         let expected_address = payment.from;
         let structured_signature: StructuredSignature = payment.signature.clone().try_into()?;
         let signed_message = Self {
@@ -1605,6 +2374,56 @@ impl SignedMessage {
     }
 }
 
+/// Every input of the ERC-3009 `TransferWithAuthorization` EIP-712 signing hash.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct TransferDigestKey {
+    domain: Eip712Domain,
+    from: Address,
+    to: Address,
+    value: U256,
+    valid_after: u64,
+    valid_before: u64,
+    nonce: [u8; 32],
+}
+
+/// Signing hashes computed during verification, reused by the matching settlement.
+static TRANSFER_DIGESTS: Lazy<DigestCache<TransferDigestKey>> =
+    Lazy::new(|| DigestCache::new(10_000, std::time::Duration::from_secs(600)));
+
+/// Compute the EIP-712 signing hash of an ERC-3009 authorization.
+pub fn compute_transfer_authorization_digest(
+    payment: &ExactEvmPayment,
+    domain: &Eip712Domain,
+) -> FixedBytes<32> {
+    let transfer_with_authorization = TransferWithAuthorization {
+        from: payment.from.0,
+        to: payment.to.0,
+        value: payment.value.into(),
+        validAfter: payment.valid_after.into(),
+        validBefore: payment.valid_before.into(),
+        nonce: FixedBytes(payment.nonce.0),
+    };
+    transfer_with_authorization.eip712_signing_hash(domain)
+}
+
+/// [`compute_transfer_authorization_digest`], memoized across verify and settle.
+///
+/// See [`crate::digest_cache`] for the request-scoped and cross-request layers.
+pub fn transfer_authorization_digest(payment: &ExactEvmPayment, domain: &Eip712Domain) -> FixedBytes<32> {
+    let key = TransferDigestKey {
+        domain: domain.clone(),
+        from: payment.from.0,
+        to: payment.to.0,
+        value: payment.value.into(),
+        valid_after: payment.valid_after.0,
+        valid_before: payment.valid_before.0,
+        nonce: payment.nonce.0,
+    };
+    FixedBytes(TRANSFER_DIGESTS.get_or_compute(&key, || {
+        compute_transfer_authorization_digest(payment, domain).0
+    }))
+}
+
 /// The fixed 32-byte magic suffix defined by [EIP-6492](https://eips.ethereum.org/EIPS/eip-6492).
 ///
 /// Any signature ending with this constant is treated as a 6492-wrapped
@@ -1751,6 +2570,108 @@ mod tests {
     use super::*;
     use alloy::primitives::address;
 
+    #[tokio::test]
+    async fn test_memoized_transfer_digest_matches_and_tracks_payload() {
+        let domain = eip712_domain! {
+            name: "USDC",
+            version: "2",
+            chain_id: 84532,
+            verifying_contract: address!("0x036CbD53842c5426634e7929541eC2318f3dCF7e"),
+        };
+        let mut payment = ExactEvmPayment {
+            chain: EvmChain::new(Network::BaseSepolia, 84532),
+            from: address!("0x2222222222222222222222222222222222222222").into(),
+            to: address!("0x1111111111111111111111111111111111111111").into(),
+            value: TokenAmount::from(1_000u64),
+            valid_after: UnixTimestamp(0),
+            valid_before: UnixTimestamp(1_000_000),
+            nonce: HexEncodedNonce([7u8; 32]),
+            signature: EvmSignature(vec![0u8; 65]),
+        };
+
+        crate::digest_cache::request_scope(async {
+            let verified = transfer_authorization_digest(&payment, &domain);
+            assert_eq!(verified, compute_transfer_authorization_digest(&payment, &domain));
+            assert_eq!(transfer_authorization_digest(&payment, &domain), verified);
+
+            // A modified payload is never served the memoized digest
+            payment.value = TokenAmount::from(2_000u64);
+            let modified = transfer_authorization_digest(&payment, &domain);
+            assert_ne!(modified, verified);
+            assert_eq!(modified, compute_transfer_authorization_digest(&payment, &domain));
+        })
+        .await;
+    }
+
+    #[test]
+    fn test_decode_erc6093_revert_errors() {
+        let account = address!("0x2222222222222222222222222222222222222222");
+        let balance = ERC20InsufficientBalance {
+            sender: account,
+            balance: U256::from(10),
+            needed: U256::from(1_000),
+        };
+        assert_eq!(
+            decode_revert_reason(&balance.abi_encode()),
+            RevertReason::InsufficientBalance
+        );
+
+        let allowance = ERC20InsufficientAllowance {
+            spender: account,
+            allowance: U256::ZERO,
+            needed: U256::from(1_000),
+        };
+        assert_eq!(
+            decode_revert_reason(&allowance.abi_encode()),
+            RevertReason::InsufficientAllowance
+        );
+    }
+
+    #[test]
+    fn test_decode_revert_strings() {
+        let revert = |reason: &str| {
+            alloy::sol_types::Revert {
+                reason: reason.to_string(),
+            }
+            .abi_encode()
+        };
+        assert_eq!(
+            decode_revert_reason(&revert("ERC20: transfer amount exceeds balance")),
+            RevertReason::InsufficientBalance
+        );
+        assert_eq!(
+            decode_revert_reason(&revert("ERC20: transfer amount exceeds allowance")),
+            RevertReason::InsufficientAllowance
+        );
+        assert_eq!(
+            decode_revert_reason(&revert("ERC20: insufficient allowance")),
+            RevertReason::InsufficientAllowance
+        );
+        match decode_revert_reason(&revert("FiatTokenV2: authorization is used or canceled")) {
+            RevertReason::Other(message) => assert!(message.contains("authorization is used")),
+            other => panic!("unexpected reason: {other:?}"),
+        }
+        assert_eq!(
+            decode_revert_reason(&[0xde, 0xad, 0xbe, 0xef]),
+            RevertReason::Other("0xdeadbeef".to_string())
+        );
+    }
+
+    #[test]
+    fn test_eip7702_delegate_designator() {
+        let delegate = address!("0x63c0c19a282a1B52b07dD5a65b58948A07DAE32B");
+        let mut code = EIP7702_DELEGATION_PREFIX.to_vec();
+        code.extend_from_slice(delegate.as_slice());
+        assert_eq!(eip7702_delegate(&code), Some(delegate));
+
+        // Plain EOAs, contracts and truncated designators are not delegations
+        assert_eq!(eip7702_delegate(&[]), None);
+        assert_eq!(eip7702_delegate(&hex!("6080604052")), None);
+        assert_eq!(eip7702_delegate(&code[..22]), None);
+        code.push(0x00);
+        assert_eq!(eip7702_delegate(&code), None);
+    }
+
     #[tokio::test]
     async fn test_reset_nonce_clears_cache() {
         let manager = PendingNonceManager::default();
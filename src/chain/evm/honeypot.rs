@@ -0,0 +1,126 @@
+//! Honeypot token detection.
+//!
+//! A honeypot token lets anyone receive it but reverts when holders try to move it on,
+//! so a payment made in it can never be spent. Before a token is accepted as a payment
+//! asset, [`HoneypotDetector`] dry-runs `transfer(facilitator, 1)` on it with `eth_call`
+//! and rejects the token if the transfer reverts.
+//!
+//! The transfer is simulated from the payer, so callers screen a token only once the
+//! payer's signature and balance have been verified. A revert may come from the holder
+//! rather than the token (a blacklisted or empty account), so it only rejects the
+//! payment at hand; only successful transfers are cached, for a limited time.
+
+use alloy::network::TransactionBuilder;
+use alloy::primitives::{Address, U256};
+use alloy::providers::Provider;
+use alloy::rpc::types::TransactionRequest;
+use alloy::sol_types::SolCall;
+use dashmap::DashMap;
+use std::future::IntoFuture;
+use std::time::{Duration, Instant};
+use tracing::Instrument;
+
+use super::{revert_reason, USDC};
+use crate::chain::FacilitatorLocalError;
+
+/// Default time a token that transferred fine is not simulated again.
+pub const DEFAULT_VERDICT_TTL: Duration = Duration::from_secs(3600);
+
+/// Simulates a token transfer to tell honeypot tokens from legitimate ones.
+///
+/// Tokens whose simulated transfer succeeded are remembered for the detector's TTL.
+/// Reverts are never cached: they may depend on the holder, and caching them would let
+/// one payer get a legitimate token rejected for everyone.
+#[derive(Debug)]
+pub struct HoneypotDetector {
+    /// Recipient of the simulated transfer.
+    facilitator: Address,
+    /// How long a successful simulation vouches for a token.
+    ttl: Duration,
+    /// Token address to when its simulated transfer last succeeded.
+    legitimate: DashMap<Address, Instant>,
+}
+
+impl HoneypotDetector {
+    /// Create a detector that simulates transfers to `facilitator`.
+    pub fn new(facilitator: Address) -> Self {
+        Self {
+            facilitator,
+            ttl: DEFAULT_VERDICT_TTL,
+            legitimate: DashMap::new(),
+        }
+    }
+
+    /// Remember tokens that transferred fine for `ttl` instead of [`DEFAULT_VERDICT_TTL`].
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Check that `token` can be transferred on, simulating the transfer from `holder`.
+    ///
+    /// `holder` must own at least one unit of the token and have authorized the
+    /// payment, otherwise a legitimate token may revert as well: pass the payer once
+    /// its signature and balance have been verified.
+    ///
+    /// # Errors
+    /// Returns [`FacilitatorLocalError::HoneypotDetected`] if the transfer reverts, and
+    /// [`FacilitatorLocalError::ContractCall`] if the node could not run it. Neither is
+    /// cached.
+    pub async fn check<P: Provider>(
+        &self,
+        provider: P,
+        token: Address,
+        holder: Address,
+    ) -> Result<(), FacilitatorLocalError> {
+        let fresh = self
+            .legitimate
+            .get(&token)
+            .is_some_and(|checked_at| checked_at.elapsed() < self.ttl);
+        if fresh {
+            return Ok(());
+        }
+        if self.simulate_transfer(provider, token, holder).await? {
+            self.legitimate.insert(token, Instant::now());
+            Ok(())
+        } else {
+            Err(FacilitatorLocalError::HoneypotDetected(token.into()))
+        }
+    }
+
+    /// Dry-run `transfer(facilitator, 1)` from `holder`, returning whether it succeeded.
+    async fn simulate_transfer<P: Provider>(
+        &self,
+        provider: P,
+        token: Address,
+        holder: Address,
+    ) -> Result<bool, FacilitatorLocalError> {
+        let calldata = USDC::transferCall {
+            to: self.facilitator,
+            value: U256::from(1),
+        }
+        .abi_encode();
+        let tx = TransactionRequest::default()
+            .with_from(holder)
+            .with_to(token)
+            .with_input(calldata);
+        let result = provider
+            .call(tx)
+            .into_future()
+            .instrument(tracing::info_span!(
+                "simulate_token_transfer",
+                otel.kind = "client"
+            ))
+            .await;
+        match result {
+            Ok(_) => Ok(true),
+            Err(e) => match revert_reason(&e) {
+                Some(reason) => {
+                    tracing::warn!(token = %token, reason = %reason, "Token transfer reverted in simulation, rejecting as honeypot");
+                    Ok(false)
+                }
+                None => Err(FacilitatorLocalError::ContractCall(format!("{e:?}"))),
+            },
+        }
+    }
+}
@@ -0,0 +1,183 @@
+//! Per-network payment token whitelist.
+//!
+//! Operators can restrict the assets a network accepts, e.g. to native USDC only and
+//! none of its bridged variants. The whitelist maps networks to token addresses and is
+//! read from `TOKEN_WHITELIST_JSON`:
+//!
+//! ```text
+//! TOKEN_WHITELIST_JSON={"base":["0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913"],"base-sepolia":[]}
+//! ```
+//!
+//! or from the TOML file named by `TOKEN_WHITELIST_FILE`:
+//!
+//! ```toml
+//! base = ["0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913"]
+//! base-sepolia = []
+//! ```
+//!
+//! A network that is not listed, or is listed with an empty list, accepts any token.
+
+use alloy::primitives::Address;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+use crate::chain::FacilitatorLocalError;
+use crate::env_registry;
+use crate::network::{Network, NetworkFamily};
+
+/// Token addresses each network accepts as payment assets.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(transparent)]
+pub struct TokenWhitelist(HashMap<Network, Vec<Address>>);
+
+impl TokenWhitelist {
+    /// Read the whitelist from `TOKEN_WHITELIST_JSON` or `TOKEN_WHITELIST_FILE`.
+    ///
+    /// Neither being set yields an empty whitelist, which accepts every token.
+    pub fn from_env() -> Result<Self, Box<dyn std::error::Error>> {
+        let json = env_registry::var("TOKEN_WHITELIST_JSON").filter(|json| !json.is_empty());
+        let file = env_registry::var("TOKEN_WHITELIST_FILE").filter(|path| !path.is_empty());
+        match (json, file) {
+            (Some(_), Some(_)) => {
+                Err("set only one of TOKEN_WHITELIST_JSON and TOKEN_WHITELIST_FILE".into())
+            }
+            (Some(json), None) => {
+                Ok(Self::from_json(&json).map_err(|e| format!("TOKEN_WHITELIST_JSON: {e}"))?)
+            }
+            (None, Some(path)) => {
+                let content = std::fs::read_to_string(&path).map_err(|e| format!("{path}: {e}"))?;
+                Ok(Self::from_toml(&content).map_err(|e| format!("{path}: {e}"))?)
+            }
+            (None, None) => Ok(Self::default()),
+        }
+    }
+
+    /// Parse a JSON object of network names to token addresses.
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        serde_json::from_str::<Self>(json)
+            .map_err(|e| e.to_string())?
+            .validated()
+    }
+
+    /// Parse a TOML table of network names to token addresses.
+    pub fn from_toml(toml: &str) -> Result<Self, String> {
+        toml::from_str::<Self>(toml)
+            .map_err(|e| e.to_string())?
+            .validated()
+    }
+
+    /// Reject networks whose payment assets are not EVM tokens.
+    fn validated(self) -> Result<Self, String> {
+        match self
+            .0
+            .keys()
+            .find(|network| !matches!(NetworkFamily::from(**network), NetworkFamily::Evm))
+        {
+            Some(network) => Err(format!(
+                "token whitelists only apply to EVM networks, not {network}"
+            )),
+            None => Ok(self),
+        }
+    }
+
+    /// Restrict `network` to `tokens`; an empty list accepts every token.
+    pub fn with_tokens(mut self, network: Network, tokens: Vec<Address>) -> Self {
+        self.0.insert(network, tokens);
+        self
+    }
+
+    /// Tokens `network` is restricted to, or `None` if it accepts every token.
+    pub fn accepted_tokens(&self, network: Network) -> Option<&[Address]> {
+        self.0
+            .get(&network)
+            .map(Vec::as_slice)
+            .filter(|tokens| !tokens.is_empty())
+    }
+
+    /// Whether `asset` may be used as a payment asset on `network`.
+    pub fn accepts(&self, network: Network, asset: Address) -> bool {
+        self.accepted_tokens(network)
+            .map(|tokens| tokens.contains(&asset))
+            .unwrap_or(true)
+    }
+
+    /// Check that `asset` may be used as a payment asset on `network`.
+    ///
+    /// # Errors
+    /// Returns [`FacilitatorLocalError::TokenNotWhitelisted`] if `network` is restricted
+    /// to other tokens.
+    pub fn check(&self, network: Network, asset: Address) -> Result<(), FacilitatorLocalError> {
+        if self.accepts(network, asset) {
+            Ok(())
+        } else {
+            Err(FacilitatorLocalError::TokenNotWhitelisted(asset.into()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::address;
+
+    use crate::types::MixedAddress;
+
+    const USDC_BASE: Address = address!("833589fCD6eDb6E08f4c7C32D4f71b54bdA02913");
+    const BRIDGED_USDC_BASE: Address = address!("d9aAEc86B65D86f6A7B5B1b0c42FFA531710b6CA");
+
+    #[test]
+    fn test_whitelist_miss_is_rejected() {
+        let whitelist = TokenWhitelist::default().with_tokens(Network::Base, vec![USDC_BASE]);
+
+        assert!(whitelist.check(Network::Base, USDC_BASE).is_ok());
+        let error = whitelist
+            .check(Network::Base, BRIDGED_USDC_BASE)
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            FacilitatorLocalError::TokenNotWhitelisted(MixedAddress::Evm(token)) if token.0 == BRIDGED_USDC_BASE
+        ));
+    }
+
+    #[test]
+    fn test_empty_or_missing_list_accepts_all() {
+        let whitelist = TokenWhitelist::default()
+            .with_tokens(Network::Base, vec![USDC_BASE])
+            .with_tokens(Network::BaseSepolia, vec![]);
+
+        assert!(whitelist.accepts(Network::BaseSepolia, BRIDGED_USDC_BASE));
+        assert!(whitelist.accepts(Network::Ethereum, BRIDGED_USDC_BASE));
+        assert_eq!(whitelist.accepted_tokens(Network::BaseSepolia), None);
+        assert_eq!(
+            whitelist.accepted_tokens(Network::Base),
+            Some(&[USDC_BASE][..])
+        );
+    }
+
+    #[test]
+    fn test_parse_json_and_toml() {
+        let json = TokenWhitelist::from_json(&format!(
+            r#"{{"base": ["{USDC_BASE}"], "base-sepolia": []}}"#
+        ))
+        .unwrap();
+        let toml =
+            TokenWhitelist::from_toml(&format!("base = [\"{USDC_BASE}\"]\nbase-sepolia = []\n"))
+                .unwrap();
+
+        for whitelist in [json, toml] {
+            assert_eq!(
+                whitelist.accepted_tokens(Network::Base),
+                Some(&[USDC_BASE][..])
+            );
+            assert_eq!(whitelist.accepted_tokens(Network::BaseSepolia), None);
+        }
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid_entries() {
+        assert!(TokenWhitelist::from_json(r#"{"base": ["0x1234"]}"#).is_err());
+        assert!(TokenWhitelist::from_json(r#"{"base-goerli": []}"#).is_err());
+        let error = TokenWhitelist::from_toml("solana = []").unwrap_err();
+        assert!(error.contains("EVM networks"), "{error}");
+    }
+}
@@ -0,0 +1,447 @@
+//! EIP-1559 fee estimation for EVM settlement transactions.
+//!
+//! A [`GasPriceOracle`] returns the current base fee and a priority fee; the settle path
+//! turns them into `maxFeePerGas`/`maxPriorityFeePerGas` with [`FeeEstimate::max_fee_per_gas`],
+//! where the base fee is scaled by `gas_price_multiplier` so a spike in a few blocks does
+//! not strand the transaction, and a runaway estimate is not paid blindly.
+//!
+//! - [`RpcGasPriceOracle`] reads `eth_feeHistory` from the chain's own RPC.
+//! - [`ExternalOracle`] queries the Etherscan or Blocknative gas API.
+//!
+//! # Configuration
+//!
+//! - `EVM_GAS_ORACLE`: `rpc` (default), `etherscan`, `blocknative`, or `none` to leave
+//!   fees to the provider's fillers
+//! - `EVM_GAS_ORACLE_API_KEY`: API key of the external oracle
+//! - `EVM_GAS_PRICE_MULTIPLIER`: base fee multiplier for `maxFeePerGas` (default: 2.0)
+
+use alloy::providers::Provider;
+use alloy::rpc::types::BlockNumberOrTag;
+use async_trait::async_trait;
+use serde::Deserialize;
+use std::fmt::Debug;
+use std::time::Duration;
+
+/// Default multiplier of the base fee in `maxFeePerGas`: the fee survives six full blocks.
+pub const DEFAULT_GAS_PRICE_MULTIPLIER: f64 = 2.0;
+
+/// Largest accepted `gas_price_multiplier`.
+pub const MAX_GAS_PRICE_MULTIPLIER: f64 = 10.0;
+
+/// Blocks of fee history sampled by [`RpcGasPriceOracle`].
+const FEE_HISTORY_BLOCKS: u64 = 10;
+
+/// Reward percentile sampled by [`RpcGasPriceOracle`].
+const PRIORITY_FEE_PERCENTILE: f64 = 50.0;
+
+/// Wei per gwei, the unit of the external gas APIs.
+const WEI_PER_GWEI: f64 = 1e9;
+
+#[derive(Debug, thiserror::Error)]
+pub enum GasOracleError {
+    #[error("Fee history request failed: {0}")]
+    Rpc(String),
+    #[error("Gas API request failed: {0}")]
+    Http(#[from] reqwest::Error),
+    #[error("Invalid gas estimate: {0}")]
+    InvalidResponse(String),
+    #[error("Invalid gas oracle config: {0}")]
+    Config(String),
+}
+
+/// Source of EIP-1559 fee estimates.
+#[async_trait]
+pub trait GasPriceOracle: Debug + Send + Sync {
+    /// Base fee of the next block and a suggested priority fee, in wei.
+    async fn estimate_max_fee(&self) -> Result<(u128, u128), GasOracleError>;
+}
+
+/// EIP-1559 fee fields of a transaction, in wei.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeeEstimate {
+    pub max_fee_per_gas: u128,
+    pub max_priority_fee_per_gas: u128,
+}
+
+impl FeeEstimate {
+    /// `maxFeePerGas = base_fee × multiplier + priority_fee`.
+    ///
+    /// The multiplier is clamped to `1.0..=`[`MAX_GAS_PRICE_MULTIPLIER`].
+    pub fn max_fee_per_gas(base_fee: u128, priority_fee: u128, multiplier: f64) -> Self {
+        let multiplier = if multiplier.is_finite() {
+            multiplier.clamp(1.0, MAX_GAS_PRICE_MULTIPLIER)
+        } else {
+            DEFAULT_GAS_PRICE_MULTIPLIER
+        };
+        let scaled_base = (base_fee as f64 * multiplier) as u128;
+        Self {
+            max_fee_per_gas: scaled_base.saturating_add(priority_fee),
+            max_priority_fee_per_gas: priority_fee,
+        }
+    }
+}
+
+/// Estimates fees from `eth_feeHistory` over the last blocks.
+///
+/// The base fee is the one announced for the next block; the priority fee is the median
+/// of the blocks' 50th percentile rewards.
+#[derive(Debug, Clone)]
+pub struct RpcGasPriceOracle<P> {
+    provider: P,
+}
+
+impl<P> RpcGasPriceOracle<P> {
+    pub fn new(provider: P) -> Self {
+        Self { provider }
+    }
+}
+
+#[async_trait]
+impl<P> GasPriceOracle for RpcGasPriceOracle<P>
+where
+    P: Provider + Debug + Send + Sync,
+{
+    async fn estimate_max_fee(&self) -> Result<(u128, u128), GasOracleError> {
+        let history = self
+            .provider
+            .get_fee_history(
+                FEE_HISTORY_BLOCKS,
+                BlockNumberOrTag::Latest,
+                &[PRIORITY_FEE_PERCENTILE],
+            )
+            .await
+            .map_err(|e| GasOracleError::Rpc(e.to_string()))?;
+        let base_fee = history
+            .base_fee_per_gas
+            .last()
+            .copied()
+            .ok_or_else(|| GasOracleError::InvalidResponse("empty fee history".to_string()))?;
+        let mut rewards: Vec<u128> = history
+            .reward
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|block| block.first().copied())
+            .collect();
+        rewards.sort_unstable();
+        let priority_fee = rewards.get(rewards.len() / 2).copied().unwrap_or_default();
+        Ok((base_fee, priority_fee))
+    }
+}
+
+/// Third-party gas APIs understood by [`ExternalOracle`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExternalGasApi {
+    /// Etherscan `gastracker` module (multichain V2 API)
+    Etherscan,
+    /// Blocknative block prices
+    Blocknative,
+}
+
+impl ExternalGasApi {
+    fn default_url(self, chain_id: u64) -> String {
+        match self {
+            ExternalGasApi::Etherscan => format!(
+                "https://api.etherscan.io/v2/api?chainid={chain_id}&module=gastracker&action=gasoracle"
+            ),
+            ExternalGasApi::Blocknative => {
+                format!("https://api.blocknative.com/gasprices/blockprices?chainid={chain_id}")
+            }
+        }
+    }
+}
+
+/// Fetches fee estimates from the Etherscan or Blocknative API.
+///
+/// Etherscan's proposed gas price minus its suggested base fee is taken as the priority
+/// fee; Blocknative's 99% confidence estimate is used.
+#[derive(Debug, Clone)]
+pub struct ExternalOracle {
+    client: reqwest::Client,
+    api: ExternalGasApi,
+    url: String,
+    api_key: Option<String>,
+}
+
+impl ExternalOracle {
+    /// Query `api` for the chain with id `chain_id`.
+    pub fn new(api: ExternalGasApi, chain_id: u64, api_key: Option<String>) -> Self {
+        Self::with_url(api, api.default_url(chain_id), api_key)
+    }
+
+    /// Query `api` at a custom `url`.
+    pub fn with_url(api: ExternalGasApi, url: String, api_key: Option<String>) -> Self {
+        Self {
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(5))
+                .build()
+                .expect("Failed to create HTTP client"),
+            api,
+            url,
+            api_key,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct EtherscanResponse {
+    result: EtherscanGasOracle,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EtherscanGasOracle {
+    #[serde(rename = "ProposeGasPrice")]
+    propose_gas_price: String,
+    suggest_base_fee: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BlocknativeResponse {
+    block_prices: Vec<BlocknativeBlockPrices>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BlocknativeBlockPrices {
+    base_fee_per_gas: f64,
+    estimated_prices: Vec<BlocknativeEstimate>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BlocknativeEstimate {
+    confidence: u8,
+    max_priority_fee_per_gas: f64,
+}
+
+/// Wei from a non-negative amount of gwei.
+fn gwei_to_wei(gwei: f64) -> Result<u128, GasOracleError> {
+    if gwei.is_finite() && gwei >= 0.0 {
+        Ok((gwei * WEI_PER_GWEI).round() as u128)
+    } else {
+        Err(GasOracleError::InvalidResponse(format!("{gwei} gwei")))
+    }
+}
+
+fn parse_gwei(value: &str) -> Result<u128, GasOracleError> {
+    let gwei = value
+        .trim()
+        .parse::<f64>()
+        .map_err(|_| GasOracleError::InvalidResponse(format!("{value:?} is not a gwei amount")))?;
+    gwei_to_wei(gwei)
+}
+
+#[async_trait]
+impl GasPriceOracle for ExternalOracle {
+    async fn estimate_max_fee(&self) -> Result<(u128, u128), GasOracleError> {
+        match self.api {
+            ExternalGasApi::Etherscan => {
+                let mut request = self.client.get(&self.url);
+                if let Some(key) = &self.api_key {
+                    request = request.query(&[("apikey", key)]);
+                }
+                let response: EtherscanResponse =
+                    request.send().await?.error_for_status()?.json().await?;
+                let base_fee = parse_gwei(&response.result.suggest_base_fee)?;
+                let proposed = parse_gwei(&response.result.propose_gas_price)?;
+                Ok((base_fee, proposed.saturating_sub(base_fee)))
+            }
+            ExternalGasApi::Blocknative => {
+                let mut request = self.client.get(&self.url);
+                if let Some(key) = &self.api_key {
+                    request = request.header(reqwest::header::AUTHORIZATION, key);
+                }
+                let response: BlocknativeResponse =
+                    request.send().await?.error_for_status()?.json().await?;
+                let block = response.block_prices.first().ok_or_else(|| {
+                    GasOracleError::InvalidResponse("no block prices".to_string())
+                })?;
+                let estimate = block
+                    .estimated_prices
+                    .iter()
+                    .max_by_key(|estimate| estimate.confidence)
+                    .ok_or_else(|| GasOracleError::InvalidResponse("no estimates".to_string()))?;
+                Ok((
+                    gwei_to_wei(block.base_fee_per_gas)?,
+                    gwei_to_wei(estimate.max_priority_fee_per_gas)?,
+                ))
+            }
+        }
+    }
+}
+
+/// Which oracle `EVM_GAS_ORACLE` selects.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GasOracleKind {
+    /// Leave fees to the provider's fillers
+    None,
+    Rpc,
+    External(ExternalGasApi),
+}
+
+impl std::str::FromStr for GasOracleKind {
+    type Err = GasOracleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "none" => Ok(GasOracleKind::None),
+            "" | "rpc" => Ok(GasOracleKind::Rpc),
+            "etherscan" => Ok(GasOracleKind::External(ExternalGasApi::Etherscan)),
+            "blocknative" => Ok(GasOracleKind::External(ExternalGasApi::Blocknative)),
+            other => Err(GasOracleError::Config(format!(
+                "unknown gas oracle {other:?}"
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::network::TransactionBuilder;
+    use alloy::providers::ProviderBuilder;
+    use alloy::rpc::types::TransactionRequest;
+    use wiremock::matchers::{header, method, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const GWEI: u128 = 1_000_000_000;
+
+    /// Serve `eth_feeHistory` with base fees of 10..=20 gwei and 1..=5 gwei rewards.
+    async fn serve_fee_history() -> MockServer {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(|request: &wiremock::Request| {
+                let body: serde_json::Value = request.body_json().unwrap();
+                assert_eq!(body["method"], "eth_feeHistory");
+                let gwei = |n: u128| format!("{:#x}", n * GWEI);
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "id": body["id"],
+                    "result": {
+                        "oldestBlock": "0x100",
+                        "baseFeePerGas": (10..=20).map(gwei).collect::<Vec<_>>(),
+                        "gasUsedRatio": vec![0.5; 10],
+                        "reward": [1, 5, 2, 2, 3, 1, 4, 2, 5, 3].map(|n| vec![gwei(n)]),
+                    }
+                }))
+            })
+            .mount(&server)
+            .await;
+        server
+    }
+
+    #[tokio::test]
+    async fn test_rpc_oracle_reads_fee_history() {
+        let server = serve_fee_history().await;
+        let provider = ProviderBuilder::new().connect_http(server.uri().parse().unwrap());
+        let oracle = RpcGasPriceOracle::new(provider);
+
+        let (base_fee, priority_fee) = oracle.estimate_max_fee().await.unwrap();
+        assert_eq!(base_fee, 20 * GWEI);
+        assert_eq!(priority_fee, 3 * GWEI);
+
+        let fees =
+            FeeEstimate::max_fee_per_gas(base_fee, priority_fee, DEFAULT_GAS_PRICE_MULTIPLIER);
+        let mut txr = TransactionRequest::default();
+        txr.set_max_fee_per_gas(fees.max_fee_per_gas);
+        txr.set_max_priority_fee_per_gas(fees.max_priority_fee_per_gas);
+        let max_fee = txr.max_fee_per_gas.unwrap();
+        // Above the next base fee plus tip, at most twice the base fee plus tip
+        assert!(max_fee >= base_fee + priority_fee, "{max_fee}");
+        assert!(max_fee <= 2 * base_fee + priority_fee, "{max_fee}");
+        assert_eq!(txr.max_priority_fee_per_gas, Some(3 * GWEI));
+    }
+
+    #[test]
+    fn test_multiplier_caps_max_fee() {
+        let base_fee = 100 * GWEI;
+        let fees = FeeEstimate::max_fee_per_gas(base_fee, GWEI, 1.25);
+        assert_eq!(fees.max_fee_per_gas, 126 * GWEI);
+        // Out-of-range multipliers are clamped
+        assert_eq!(
+            FeeEstimate::max_fee_per_gas(base_fee, 0, 0.1).max_fee_per_gas,
+            base_fee
+        );
+        assert_eq!(
+            FeeEstimate::max_fee_per_gas(base_fee, 0, 1e6).max_fee_per_gas,
+            10 * base_fee
+        );
+        assert_eq!(
+            FeeEstimate::max_fee_per_gas(base_fee, 0, f64::NAN).max_fee_per_gas,
+            2 * base_fee
+        );
+    }
+
+    #[tokio::test]
+    async fn test_etherscan_oracle() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(query_param("apikey", "key"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "status": "1",
+                "message": "OK",
+                "result": {
+                    "SafeGasPrice": "11",
+                    "ProposeGasPrice": "12.5",
+                    "FastGasPrice": "14",
+                    "suggestBaseFee": "10.25",
+                    "gasUsedRatio": "0.5,0.4"
+                }
+            })))
+            .mount(&server)
+            .await;
+
+        let oracle = ExternalOracle::with_url(
+            ExternalGasApi::Etherscan,
+            format!("{}/v2/api?chainid=1", server.uri()),
+            Some("key".to_string()),
+        );
+        let (base_fee, priority_fee) = oracle.estimate_max_fee().await.unwrap();
+        assert_eq!(base_fee, 10_250_000_000);
+        assert_eq!(priority_fee, 2_250_000_000);
+    }
+
+    #[tokio::test]
+    async fn test_blocknative_oracle() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(header("authorization", "key"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "blockPrices": [{
+                    "blockNumber": 100,
+                    "baseFeePerGas": 8.5,
+                    "estimatedPrices": [
+                        { "confidence": 99, "price": 10, "maxPriorityFeePerGas": 1.5, "maxFeePerGas": 18.5 },
+                        { "confidence": 70, "price": 9, "maxPriorityFeePerGas": 0.5, "maxFeePerGas": 17.5 }
+                    ]
+                }]
+            })))
+            .mount(&server)
+            .await;
+
+        let oracle = ExternalOracle::with_url(
+            ExternalGasApi::Blocknative,
+            server.uri(),
+            Some("key".to_string()),
+        );
+        assert_eq!(
+            oracle.estimate_max_fee().await.unwrap(),
+            (8_500_000_000, 1_500_000_000)
+        );
+    }
+
+    #[test]
+    fn test_oracle_kind_from_str() {
+        assert_eq!("rpc".parse::<GasOracleKind>().unwrap(), GasOracleKind::Rpc);
+        assert_eq!(
+            "None".parse::<GasOracleKind>().unwrap(),
+            GasOracleKind::None
+        );
+        assert_eq!(
+            "etherscan".parse::<GasOracleKind>().unwrap(),
+            GasOracleKind::External(ExternalGasApi::Etherscan)
+        );
+        assert!("oracle".parse::<GasOracleKind>().is_err());
+    }
+}
@@ -0,0 +1,835 @@
+//! Hedera payment provider implementation.
+//!
+//! USDC on Hedera is a Hedera Token Service (HTS) token. HTS tokens are reachable from
+//! the EVM at their "long-zero" address, but do not implement EIP-3009, so there is no
+//! authorization for the facilitator to execute. The payer signs the transfer itself:
+//!
+//! 1. Client signs an EVM transaction calling `transfer(payTo, amount)` on the USDC
+//!    long-zero address, with chain ID 295 (mainnet) or 296 (testnet)
+//! 2. Facilitator checks the signer, token, recipient and amount against the payment
+//!    requirements, and simulates the transfer with `eth_call`
+//! 3. Facilitator submits the signed transaction through the Hedera JSON-RPC Relay and
+//!    waits for its receipt
+//!
+//! The payer covers the HBAR fee and the transaction nonce makes each payload single-use,
+//! so no nonce store is involved. Hedera accounts are usually referred to by entity ID
+//! (`0.0.x`); the mirror node resolves those to the EVM address of the account.
+//!
+//! # Configuration
+//!
+//! - `RPC_URL_HEDERA` / `RPC_URL_HEDERA_TESTNET`: JSON-RPC Relay, hashio.io by default
+//! - `HEDERA_MIRROR_NODE_URL` / `HEDERA_MIRROR_NODE_URL_TESTNET`: mirror node REST API
+//! - The EVM signer (`EVM_PRIVATE_KEY*`) identifies the facilitator on Hedera; it never
+//!   signs or pays for Hedera transactions
+
+use alloy::consensus::{Transaction, TxEnvelope};
+use alloy::eips::eip2718::Decodable2718;
+use alloy::hex;
+use alloy::primitives::{Address, Bytes, B256, U256};
+use alloy::providers::{Provider, RootProvider};
+use alloy::rpc::types::TransactionRequest;
+use alloy::sol;
+use alloy::sol_types::SolCall;
+use serde::Deserialize;
+use std::sync::Arc;
+use std::time::Duration;
+use url::Url;
+
+use crate::chain::{FacilitatorLocalError, FromEnvByNetworkBuild, NetworkProviderOps};
+use crate::env_registry;
+use crate::facilitator::Facilitator;
+use crate::from_env;
+use crate::network::{Network, USDCDeployment};
+use crate::nonce_store::NonceStore;
+use crate::types::{
+    ExactHederaPayload, ExactPaymentPayload, FacilitatorErrorReason, MixedAddress,
+    PaymentRequirements, Scheme, SettleRequest, SettleResponse, SupportedPaymentKind,
+    SupportedPaymentKindExtra, SupportedPaymentKindsResponse, SupportedTokenInfo, TokenType,
+    TransactionHash, VerifyRequest, VerifyResponse, X402Version,
+};
+
+// =============================================================================
+// Constants
+// =============================================================================
+
+/// EVM chain ID of Hedera mainnet
+pub const HEDERA_MAINNET_CHAIN_ID: u64 = 295;
+
+/// EVM chain ID of Hedera testnet
+pub const HEDERA_TESTNET_CHAIN_ID: u64 = 296;
+
+/// Default Hedera mainnet JSON-RPC Relay
+pub const HEDERA_MAINNET_RELAY: &str = "https://mainnet.hashio.io/api";
+
+/// Default Hedera testnet JSON-RPC Relay
+pub const HEDERA_TESTNET_RELAY: &str = "https://testnet.hashio.io/api";
+
+/// Default Hedera mainnet mirror node
+pub const HEDERA_MAINNET_MIRROR_NODE: &str = "https://mainnet.mirrornode.hedera.com";
+
+/// Default Hedera testnet mirror node
+pub const HEDERA_TESTNET_MIRROR_NODE: &str = "https://testnet.mirrornode.hedera.com";
+
+/// How long to wait for the receipt of a relayed transfer
+const RECEIPT_TIMEOUT: Duration = Duration::from_secs(30);
+
+sol! {
+    /// ERC-20 facade of HTS fungible tokens.
+    interface IHederaToken {
+        function transfer(address to, uint256 amount) external returns (bool);
+    }
+}
+
+// =============================================================================
+// Error Types
+// =============================================================================
+
+/// Hedera-specific errors
+#[derive(Debug, thiserror::Error)]
+pub enum HederaError {
+    #[error("Invalid transaction encoding: {0}")]
+    InvalidEncoding(String),
+
+    #[error("Transaction chain ID mismatch: expected {expected}, got {actual:?}")]
+    ChainIdMismatch { expected: u64, actual: Option<u64> },
+
+    #[error("Invalid transaction signature: {0}")]
+    InvalidSignature(String),
+
+    #[error("Invalid Hedera address: {0}")]
+    InvalidAddress(String),
+
+    #[error("Transaction signed by {signer}, not by payer {payer}")]
+    PayerMismatch { payer: String, signer: Address },
+
+    #[error("Payment asset {0} is not accepted")]
+    UnsupportedAsset(String),
+
+    #[error("Transaction calls {actual:?} instead of token {expected}")]
+    TokenMismatch {
+        expected: Address,
+        actual: Option<Address>,
+    },
+
+    #[error("Transaction must not carry HBAR value")]
+    UnexpectedValue,
+
+    #[error("Transaction is not a token transfer")]
+    NotATransfer,
+
+    #[error("Transfer recipient mismatch: expected {expected}, got {actual}")]
+    RecipientMismatch { expected: String, actual: Address },
+
+    #[error("Insufficient payment amount: provided {provided}, required {required}")]
+    InsufficientAmount { provided: U256, required: U256 },
+
+    #[error("Transaction simulation failed: {0}")]
+    SimulationFailed(String),
+
+    #[error("Hedera account {0} not found")]
+    AccountNotFound(String),
+
+    #[error("Mirror node error: {0}")]
+    MirrorNode(String),
+
+    #[error("Transaction submission failed: {0}")]
+    SubmissionFailed(String),
+
+    #[error("Transaction {0} reverted")]
+    Reverted(B256),
+}
+
+impl From<HederaError> for FacilitatorLocalError {
+    fn from(e: HederaError) -> Self {
+        FacilitatorLocalError::Other(e.to_string())
+    }
+}
+
+// =============================================================================
+// Addresses
+// =============================================================================
+
+/// Long-zero EVM address of Hedera entity `entity_id` (`shard.realm.num`): the shard in
+/// 4 bytes followed by the realm and number in 8 bytes each, big-endian.
+pub fn entity_id_to_evm_address(entity_id: &str) -> Result<Address, HederaError> {
+    let invalid = || HederaError::InvalidAddress(entity_id.to_string());
+    let parse = |part: Option<&str>| {
+        part.filter(|p| p.bytes().all(|b| b.is_ascii_digit()))
+            .and_then(|p| p.parse::<u64>().ok())
+    };
+
+    let mut parts = entity_id.split('.');
+    let shard = parse(parts.next()).and_then(|s| u32::try_from(s).ok());
+    let realm = parse(parts.next());
+    let num = parse(parts.next());
+    let (Some(shard), Some(realm), Some(num), None) = (shard, realm, num, parts.next()) else {
+        return Err(invalid());
+    };
+
+    let mut bytes = [0u8; 20];
+    bytes[..4].copy_from_slice(&shard.to_be_bytes());
+    bytes[4..12].copy_from_slice(&realm.to_be_bytes());
+    bytes[12..].copy_from_slice(&num.to_be_bytes());
+    Ok(Address::from(bytes))
+}
+
+/// Amount of the signed `transfer` in `payload`, without checking it against any
+/// payment requirements.
+pub fn signed_transfer_amount(payload: &ExactHederaPayload) -> Option<U256> {
+    let raw = hex::decode(payload.signed_transaction.trim()).ok()?;
+    let envelope = TxEnvelope::decode_2718(&mut raw.as_slice()).ok()?;
+    let transfer = IHederaToken::transferCall::abi_decode(envelope.input()).ok()?;
+    Some(transfer.amount)
+}
+
+/// Account details returned by the mirror node
+#[derive(Debug, Deserialize)]
+struct MirrorAccount {
+    /// EVM address of the account's ECDSA key alias, absent for ED25519 accounts
+    evm_address: Option<Address>,
+}
+
+/// Client for the Hedera mirror node REST API
+#[derive(Clone, Debug)]
+pub struct MirrorNodeClient {
+    client: reqwest::Client,
+    base_url: String,
+}
+
+impl MirrorNodeClient {
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+        }
+    }
+
+    /// EVM address of account `entity_id`: the alias of its ECDSA key when it has one,
+    /// its long-zero address otherwise.
+    pub async fn account_evm_address(&self, entity_id: &str) -> Result<Address, HederaError> {
+        let long_zero = entity_id_to_evm_address(entity_id)?;
+        let url = format!("{}/api/v1/accounts/{}", self.base_url, entity_id);
+        let response = self
+            .client
+            .get(&url)
+            .send()
+            .await
+            .map_err(|e| HederaError::MirrorNode(e.to_string()))?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Err(HederaError::AccountNotFound(entity_id.to_string()));
+        }
+        let account: MirrorAccount = response
+            .error_for_status()
+            .map_err(|e| HederaError::MirrorNode(e.to_string()))?
+            .json()
+            .await
+            .map_err(|e| HederaError::MirrorNode(e.to_string()))?;
+        Ok(account.evm_address.unwrap_or(long_zero))
+    }
+}
+
+// =============================================================================
+// Chain Configuration
+// =============================================================================
+
+/// Hedera network chain configuration
+#[derive(Clone, Debug)]
+pub struct HederaChain {
+    pub network: Network,
+    pub chain_id: u64,
+    /// USDC entity ID
+    pub usdc_id: String,
+    /// USDC long-zero address
+    pub usdc_address: Address,
+}
+
+impl TryFrom<Network> for HederaChain {
+    type Error = FacilitatorLocalError;
+
+    fn try_from(network: Network) -> Result<Self, Self::Error> {
+        let chain_id = match network {
+            Network::Hedera => HEDERA_MAINNET_CHAIN_ID,
+            Network::HederaTestnet => HEDERA_TESTNET_CHAIN_ID,
+            _ => return Err(FacilitatorLocalError::UnsupportedNetwork(None)),
+        };
+        let usdc_id = USDCDeployment::by_network(network).address().to_string();
+        let usdc_address = entity_id_to_evm_address(&usdc_id)?;
+        Ok(Self {
+            network,
+            chain_id,
+            usdc_id,
+            usdc_address,
+        })
+    }
+}
+
+impl HederaChain {
+    fn default_relay_url(&self) -> &'static str {
+        match self.network {
+            Network::HederaTestnet => HEDERA_TESTNET_RELAY,
+            _ => HEDERA_MAINNET_RELAY,
+        }
+    }
+
+    fn default_mirror_node_url(&self) -> &'static str {
+        match self.network {
+            Network::HederaTestnet => HEDERA_TESTNET_MIRROR_NODE,
+            _ => HEDERA_MAINNET_MIRROR_NODE,
+        }
+    }
+}
+
+/// Signed transfer checked against the payment requirements
+#[derive(Clone, Debug)]
+pub struct HederaTransfer {
+    /// Payer as given in the payload
+    pub payer: MixedAddress,
+    pub signer: Address,
+    pub recipient: Address,
+    pub amount: U256,
+    raw: Bytes,
+}
+
+// =============================================================================
+// Provider
+// =============================================================================
+
+/// Hedera provider relaying payer-signed HTS transfers
+#[derive(Clone, Debug)]
+pub struct HederaProvider {
+    chain: HederaChain,
+    relay: RootProvider,
+    mirror_node: MirrorNodeClient,
+    facilitator: Address,
+}
+
+impl HederaProvider {
+    /// Create a provider for `network`, using the default relay and mirror node when
+    /// their URLs are not given.
+    pub fn try_new(
+        relay_url: Option<String>,
+        mirror_node_url: Option<String>,
+        network: Network,
+        facilitator: Address,
+    ) -> Result<Self, FacilitatorLocalError> {
+        let chain = HederaChain::try_from(network)?;
+        let relay_url = relay_url.unwrap_or_else(|| chain.default_relay_url().to_string());
+        let relay_url = Url::parse(&relay_url).map_err(|e| {
+            FacilitatorLocalError::Other(format!("Invalid Hedera relay URL {relay_url}: {e}"))
+        })?;
+        let mirror_node = MirrorNodeClient::new(
+            mirror_node_url.unwrap_or_else(|| chain.default_mirror_node_url().to_string()),
+        );
+
+        tracing::info!(
+            network = %network,
+            relay = %relay_url,
+            usdc = %chain.usdc_id,
+            "Initialized Hedera provider"
+        );
+
+        Ok(Self {
+            chain,
+            relay: RootProvider::new_http(relay_url),
+            mirror_node,
+            facilitator,
+        })
+    }
+
+    /// EVM address of `address`, given as an entity ID or a 0x address.
+    async fn resolve(&self, address: &str) -> Result<Address, HederaError> {
+        match address.parse::<Address>() {
+            Ok(evm) => Ok(evm),
+            Err(_) => self.mirror_node.account_evm_address(address).await,
+        }
+    }
+
+    /// Long-zero address of the payment asset, which must be the chain's USDC.
+    fn token_address(&self, asset: &MixedAddress) -> Result<Address, HederaError> {
+        let token = match asset {
+            MixedAddress::Hedera(id) => entity_id_to_evm_address(id)?,
+            MixedAddress::Evm(address) => (*address).into(),
+            other => return Err(HederaError::UnsupportedAsset(other.to_string())),
+        };
+        if token != self.chain.usdc_address {
+            return Err(HederaError::UnsupportedAsset(asset.to_string()));
+        }
+        Ok(token)
+    }
+
+    /// Whether `recipient` is `pay_to`, by long-zero address or ECDSA key alias.
+    async fn is_pay_to(
+        &self,
+        pay_to: &MixedAddress,
+        recipient: Address,
+    ) -> Result<bool, HederaError> {
+        match pay_to {
+            MixedAddress::Evm(address) => Ok(Address::from(*address) == recipient),
+            MixedAddress::Hedera(id) => Ok(entity_id_to_evm_address(id)? == recipient
+                || self.mirror_node.account_evm_address(id).await? == recipient),
+            other => Err(HederaError::InvalidAddress(other.to_string())),
+        }
+    }
+
+    /// Check the signed transfer against `requirements` and simulate it.
+    pub async fn verify_transfer(
+        &self,
+        payload: &ExactHederaPayload,
+        requirements: &PaymentRequirements,
+    ) -> Result<HederaTransfer, HederaError> {
+        let raw = hex::decode(payload.signed_transaction.trim())
+            .map_err(|e| HederaError::InvalidEncoding(e.to_string()))?;
+        let envelope = TxEnvelope::decode_2718(&mut raw.as_slice())
+            .map_err(|e| HederaError::InvalidEncoding(e.to_string()))?;
+        if envelope.chain_id() != Some(self.chain.chain_id) {
+            return Err(HederaError::ChainIdMismatch {
+                expected: self.chain.chain_id,
+                actual: envelope.chain_id(),
+            });
+        }
+
+        // Payer
+        let signer = envelope
+            .signature()
+            .recover_address_from_prehash(&envelope.signature_hash())
+            .map_err(|e| HederaError::InvalidSignature(e.to_string()))?;
+        if self.resolve(&payload.from).await? != signer {
+            return Err(HederaError::PayerMismatch {
+                payer: payload.from.clone(),
+                signer,
+            });
+        }
+
+        // Token
+        let token = self.token_address(&requirements.asset)?;
+        if envelope.to() != Some(token) {
+            return Err(HederaError::TokenMismatch {
+                expected: token,
+                actual: envelope.to(),
+            });
+        }
+        if !envelope.value().is_zero() {
+            return Err(HederaError::UnexpectedValue);
+        }
+
+        // Recipient and amount
+        let transfer = IHederaToken::transferCall::abi_decode(envelope.input())
+            .map_err(|_| HederaError::NotATransfer)?;
+        if !self.is_pay_to(&requirements.pay_to, transfer.to).await? {
+            return Err(HederaError::RecipientMismatch {
+                expected: requirements.pay_to.to_string(),
+                actual: transfer.to,
+            });
+        }
+        let required = requirements.max_amount_required.0;
+        if transfer.amount < required {
+            return Err(HederaError::InsufficientAmount {
+                provided: transfer.amount,
+                required,
+            });
+        }
+
+        // Simulation: fails when the recipient is not associated with the token or the
+        // payer's balance is short
+        let call = TransactionRequest::default()
+            .from(signer)
+            .to(token)
+            .input(envelope.input().clone().into());
+        let output = self
+            .relay
+            .call(call)
+            .await
+            .map_err(|e| HederaError::SimulationFailed(e.to_string()))?;
+        let success = IHederaToken::transferCall::abi_decode_returns(&output)
+            .map_err(|e| HederaError::SimulationFailed(e.to_string()))?;
+        if !success {
+            return Err(HederaError::SimulationFailed(
+                "transfer returned false".to_string(),
+            ));
+        }
+
+        let payer = match payload.from.parse::<Address>() {
+            Ok(address) => MixedAddress::Evm(address.into()),
+            Err(_) => MixedAddress::Hedera(payload.from.clone()),
+        };
+        Ok(HederaTransfer {
+            payer,
+            signer,
+            recipient: transfer.to,
+            amount: transfer.amount,
+            raw: raw.into(),
+        })
+    }
+
+    /// Relay a verified transfer and wait for its receipt.
+    pub async fn submit(&self, transfer: &HederaTransfer) -> Result<B256, HederaError> {
+        let receipt = self
+            .relay
+            .send_raw_transaction(&transfer.raw)
+            .await
+            .map_err(|e| HederaError::SubmissionFailed(e.to_string()))?
+            .with_timeout(Some(RECEIPT_TIMEOUT))
+            .get_receipt()
+            .await
+            .map_err(|e| HederaError::SubmissionFailed(e.to_string()))?;
+        if !receipt.status() {
+            return Err(HederaError::Reverted(receipt.transaction_hash));
+        }
+        Ok(receipt.transaction_hash)
+    }
+}
+
+impl FromEnvByNetworkBuild for HederaProvider {
+    async fn from_env(
+        network: Network,
+        _nonce_store: Arc<dyn NonceStore>,
+    ) -> Result<Option<Self>, Box<dyn std::error::Error>> {
+        let relay_url = env_registry::var(from_env::rpc_env_name_from_network(network));
+        let mirror_node_url = match network {
+            Network::HederaTestnet => env_registry::var("HEDERA_MIRROR_NODE_URL_TESTNET"),
+            _ => env_registry::var("HEDERA_MIRROR_NODE_URL"),
+        };
+
+        let facilitator = match from_env::SignerType::from_env()?.make_evm_proof_signer(network) {
+            Ok(signer) => signer.address(),
+            Err(e) => {
+                tracing::warn!(network=%network, error=%e, "no EVM signer configured for Hedera, skipping");
+                return Ok(None);
+            }
+        };
+
+        let provider = HederaProvider::try_new(relay_url, mirror_node_url, network, facilitator)?;
+        Ok(Some(provider))
+    }
+}
+
+impl NetworkProviderOps for HederaProvider {
+    fn signer_address(&self) -> MixedAddress {
+        MixedAddress::Evm(self.facilitator.into())
+    }
+
+    fn network(&self) -> Network {
+        self.chain.network
+    }
+}
+
+impl Facilitator for HederaProvider {
+    type Error = FacilitatorLocalError;
+
+    async fn verify(&self, request: &VerifyRequest) -> Result<VerifyResponse, Self::Error> {
+        let payload = &request.payment_payload;
+
+        // Verify network matches
+        if payload.network != self.network() {
+            return Err(FacilitatorLocalError::NetworkMismatch(
+                None,
+                self.network(),
+                payload.network,
+            ));
+        }
+
+        match &payload.payload {
+            ExactPaymentPayload::Hedera(p) => {
+                let transfer = self
+                    .verify_transfer(p, &request.payment_requirements)
+                    .await?;
+                Ok(VerifyResponse::valid(transfer.payer))
+            }
+            _ => Err(FacilitatorLocalError::UnsupportedNetwork(None)),
+        }
+    }
+
+    async fn settle(&self, request: &SettleRequest) -> Result<SettleResponse, Self::Error> {
+        let payload = &request.payment_payload;
+
+        // Verify network matches
+        if payload.network != self.network() {
+            return Err(FacilitatorLocalError::NetworkMismatch(
+                None,
+                self.network(),
+                payload.network,
+            ));
+        }
+
+        match &payload.payload {
+            ExactPaymentPayload::Hedera(p) => {
+                let transfer = self
+                    .verify_transfer(p, &request.payment_requirements)
+                    .await?;
+
+                tracing::info!(
+                    payer = %transfer.payer,
+                    amount = %transfer.amount,
+                    recipient = %transfer.recipient,
+                    "Hedera settle: Verification successful, relaying transfer"
+                );
+
+                let tx_hash = match self.submit(&transfer).await {
+                    Ok(hash) => hash,
+                    Err(e) => {
+                        tracing::error!(error = %e, "Hedera settle: Failed to relay transfer");
+                        return Ok(SettleResponse {
+                            success: false,
+                            error_reason: Some(FacilitatorErrorReason::UnexpectedSettleError),
+                            payer: transfer.payer,
+                            transaction: None,
+                            network: self.network(),
+                            proof_of_payment: None,
+                            confirmation: None,
+                        });
+                    }
+                };
+
+                Ok(SettleResponse {
+                    success: true,
+                    error_reason: None,
+                    payer: transfer.payer,
+                    transaction: Some(TransactionHash::Evm(tx_hash.0)),
+                    network: self.network(),
+                    proof_of_payment: None, // ERC-8004 not supported on Hedera
+                    confirmation: None,
+                })
+            }
+            _ => Err(FacilitatorLocalError::UnsupportedNetwork(None)),
+        }
+    }
+
+    async fn supported(&self) -> Result<SupportedPaymentKindsResponse, Self::Error> {
+        let kinds = vec![SupportedPaymentKind {
+            network: self.network().to_string(),
+            scheme: Scheme::Exact,
+            x402_version: X402Version::V1,
+            extra: Some(SupportedPaymentKindExtra {
+                // Payers pay their own HBAR fees
+                fee_payer: None,
+                tokens: Some(vec![SupportedTokenInfo {
+                    token: TokenType::Usdc,
+                    address: MixedAddress::Hedera(self.chain.usdc_id.clone()),
+                    decimals: 6,
+                }]),
+                accepted_tokens: None,
+            }),
+        }];
+        Ok(SupportedPaymentKindsResponse { kinds })
+    }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::consensus::{SignableTransaction, TxLegacy};
+    use alloy::eips::eip2718::Encodable2718;
+    use alloy::network::TxSignerSync;
+    use alloy::primitives::{address, TxKind};
+    use alloy::signers::local::PrivateKeySigner;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    /// First Anvil development key, address 0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266
+    const PAYER_KEY: &str = "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+    const PAYER_ID: &str = "0.0.5001";
+    const PAY_TO_ID: &str = "0.0.5002";
+    const TESTNET_USDC: Address = address!("0000000000000000000000000000000000068cda");
+
+    fn signed_transfer(chain_id: u64, to: Address, recipient: Address, amount: u64) -> String {
+        let signer: PrivateKeySigner = PAYER_KEY.parse().unwrap();
+        let input = IHederaToken::transferCall {
+            to: recipient,
+            amount: U256::from(amount),
+        }
+        .abi_encode();
+        let mut tx = TxLegacy {
+            chain_id: Some(chain_id),
+            nonce: 0,
+            gas_price: 1_000_000_000_000,
+            gas_limit: 100_000,
+            to: TxKind::Call(to),
+            value: U256::ZERO,
+            input: input.into(),
+        };
+        let signature = signer.sign_transaction_sync(&mut tx).unwrap();
+        let envelope = TxEnvelope::from(tx.into_signed(signature));
+        hex::encode_prefixed(envelope.encoded_2718())
+    }
+
+    fn requirements(amount: u64) -> PaymentRequirements {
+        serde_json::from_value(serde_json::json!({
+            "scheme": "exact",
+            "network": "hedera-testnet",
+            "maxAmountRequired": amount.to_string(),
+            "resource": "https://example.com/weather",
+            "description": "Weather report",
+            "mimeType": "application/json",
+            "payTo": PAY_TO_ID,
+            "maxTimeoutSeconds": 60,
+            "asset": "0.0.429274",
+        }))
+        .unwrap()
+    }
+
+    /// Mirror node knowing the payer by its ECDSA alias and the recipient by its
+    /// long-zero address, and a relay whose `eth_call` succeeds.
+    async fn setup() -> (MockServer, HederaProvider) {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path(format!("/api/v1/accounts/{PAYER_ID}")))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "account": PAYER_ID,
+                "evm_address": "0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266",
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(format!("/api/v1/accounts/{PAY_TO_ID}")))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "account": PAY_TO_ID,
+                "evm_address": null,
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/api"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 0,
+                "result": format!("0x{:064x}", 1),
+            })))
+            .mount(&server)
+            .await;
+
+        let provider = HederaProvider::try_new(
+            Some(format!("{}/api", server.uri())),
+            Some(server.uri()),
+            Network::HederaTestnet,
+            Address::repeat_byte(0xfa),
+        )
+        .unwrap();
+        (server, provider)
+    }
+
+    fn payload(signed_transaction: String) -> ExactHederaPayload {
+        ExactHederaPayload {
+            signed_transaction,
+            from: PAYER_ID.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_entity_id_to_evm_address() {
+        assert_eq!(
+            entity_id_to_evm_address("0.0.429274").unwrap(),
+            TESTNET_USDC
+        );
+        assert_eq!(
+            entity_id_to_evm_address("0.0.456858").unwrap(),
+            address!("000000000000000000000000000000000006f89a")
+        );
+        assert_eq!(
+            entity_id_to_evm_address("1.2.3").unwrap(),
+            address!("0000000100000000000000020000000000000003")
+        );
+        for invalid in [
+            "0.0",
+            "0.0.1.2",
+            "0.0.x",
+            "0..1",
+            "4294967296.0.1",
+            "0.0.-1",
+        ] {
+            assert!(entity_id_to_evm_address(invalid).is_err(), "{invalid}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_verify_transfer() {
+        let (_server, provider) = setup().await;
+        let pay_to = entity_id_to_evm_address(PAY_TO_ID).unwrap();
+        let tx = signed_transfer(HEDERA_TESTNET_CHAIN_ID, TESTNET_USDC, pay_to, 10_000);
+
+        let transfer = provider
+            .verify_transfer(&payload(tx), &requirements(10_000))
+            .await
+            .unwrap();
+
+        assert_eq!(transfer.payer, MixedAddress::Hedera(PAYER_ID.to_string()));
+        assert_eq!(
+            transfer.signer,
+            address!("f39Fd6e51aad88F6F4ce6aB8827279cffFb92266")
+        );
+        assert_eq!(transfer.recipient, pay_to);
+        assert_eq!(transfer.amount, U256::from(10_000));
+    }
+
+    #[tokio::test]
+    async fn test_verify_transfer_rejects_mismatches() {
+        let (_server, provider) = setup().await;
+        let pay_to = entity_id_to_evm_address(PAY_TO_ID).unwrap();
+
+        let wrong_chain = signed_transfer(HEDERA_MAINNET_CHAIN_ID, TESTNET_USDC, pay_to, 10_000);
+        let wrong_token = signed_transfer(HEDERA_TESTNET_CHAIN_ID, pay_to, pay_to, 10_000);
+        let wrong_recipient =
+            signed_transfer(HEDERA_TESTNET_CHAIN_ID, TESTNET_USDC, Address::ZERO, 10_000);
+        let too_little = signed_transfer(HEDERA_TESTNET_CHAIN_ID, TESTNET_USDC, pay_to, 9_999);
+
+        let error = |tx| async {
+            provider
+                .verify_transfer(&payload(tx), &requirements(10_000))
+                .await
+                .unwrap_err()
+        };
+        assert!(matches!(
+            error(wrong_chain).await,
+            HederaError::ChainIdMismatch { .. }
+        ));
+        assert!(matches!(
+            error(wrong_token).await,
+            HederaError::TokenMismatch { .. }
+        ));
+        assert!(matches!(
+            error(wrong_recipient).await,
+            HederaError::RecipientMismatch { .. }
+        ));
+        assert!(matches!(
+            error(too_little).await,
+            HederaError::InsufficientAmount { .. }
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_verify_transfer_rejects_other_payer() {
+        let (_server, provider) = setup().await;
+        let pay_to = entity_id_to_evm_address(PAY_TO_ID).unwrap();
+        let tx = signed_transfer(HEDERA_TESTNET_CHAIN_ID, TESTNET_USDC, pay_to, 10_000);
+        let payload = ExactHederaPayload {
+            signed_transaction: tx,
+            from: PAY_TO_ID.to_string(),
+        };
+
+        let error = provider
+            .verify_transfer(&payload, &requirements(10_000))
+            .await
+            .unwrap_err();
+
+        assert!(
+            matches!(error, HederaError::PayerMismatch { .. }),
+            "{error}"
+        );
+    }
+
+    #[tokio::test]
+    #[ignore = "queries the public Hedera testnet mirror node"]
+    async fn test_testnet_mirror_node() {
+        let mirror_node = MirrorNodeClient::new(HEDERA_TESTNET_MIRROR_NODE);
+
+        let address = mirror_node.account_evm_address("0.0.2").await.unwrap();
+
+        assert_eq!(
+            address,
+            address!("0000000000000000000000000000000000000002")
+        );
+    }
+}
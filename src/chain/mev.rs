@@ -0,0 +1,460 @@
+//! MEV protection for EVM settlement transactions on Ethereum mainnet.
+//!
+//! A settlement broadcast to the public mempool can be front-run or sandwiched by bots
+//! watching it. With [`MevProtection::Flashbots`], the signed transaction is instead sent
+//! to the Flashbots relay with `eth_sendPrivateTransaction`, which only hands it to block
+//! builders. If it is not included within the relay timeout, the same signed transaction
+//! is broadcast publicly, so a settlement is delayed at worst, never lost.
+//!
+//! Relay requests are authenticated with an `X-Flashbots-Signature` header of the form
+//! `<address>:<signature>`, where the signature is an EIP-191 personal signature of the
+//! hex-encoded keccak256 hash of the request body. The key only identifies the searcher
+//! to the relay, so it is configured on its own and should hold no funds.
+//!
+//! Flashbots only operates on Ethereum mainnet; every other network ignores the setting.
+//!
+//! # Configuration
+//!
+//! - `EVM_MEV_PROTECTION`: `none` (default) or `flashbots`
+//! - `FLASHBOTS_AUTH_KEY`: private key signing relay requests, required with `flashbots`
+//! - `FLASHBOTS_RELAY_URL`: relay endpoint (default: `https://relay.flashbots.net`)
+//! - `FLASHBOTS_REFUND_PERCENT`: share of the MEV-Share refund paid back to the signer, 0-100 (default: 0)
+//! - `FLASHBOTS_TIMEOUT_SECS`: seconds to wait for inclusion before broadcasting publicly (default: 120)
+
+use alloy::hex;
+use alloy::network::Ethereum;
+use alloy::primitives::{keccak256, Address, B256};
+use alloy::providers::{PendingTransactionBuilder, Provider};
+use alloy::signers::local::PrivateKeySigner;
+use alloy::signers::SignerSync;
+use alloy::transports::TransportResult;
+use serde::Deserialize;
+use std::time::Duration;
+
+use crate::env_registry;
+use crate::network::Network;
+
+/// Flashbots Protect relay.
+pub const DEFAULT_FLASHBOTS_RELAY_URL: &str = "https://relay.flashbots.net";
+
+/// Seconds a private transaction has to be included before it is broadcast publicly.
+pub const DEFAULT_FLASHBOTS_TIMEOUT_SECS: u64 = 120;
+
+/// Header carrying the signature of a relay request.
+pub const FLASHBOTS_SIGNATURE_HEADER: &str = "X-Flashbots-Signature";
+
+/// Longest wait between two inclusion checks of a private transaction.
+const INCLUSION_POLL_INTERVAL: Duration = Duration::from_secs(3);
+
+#[derive(Debug, thiserror::Error)]
+pub enum MevError {
+    #[error("Relay request failed: {0}")]
+    Http(#[from] reqwest::Error),
+    #[error("Relay rejected the transaction: {0}")]
+    Relay(String),
+    #[error("Failed to sign relay request: {0}")]
+    Signing(String),
+    #[error("Invalid MEV protection config: {0}")]
+    Config(String),
+}
+
+/// How settlement transactions reach block builders.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum MevProtection {
+    /// Broadcast to the public mempool
+    #[default]
+    None,
+    /// Send privately through a Flashbots relay
+    Flashbots {
+        relay_url: String,
+        /// Share of the MEV-Share refund paid back to the signer, in percent
+        refund_percent: u8,
+    },
+}
+
+impl MevProtection {
+    /// Protection selected by `EVM_MEV_PROTECTION`.
+    pub fn from_env() -> Result<Self, MevError> {
+        let kind = env_registry::var("EVM_MEV_PROTECTION").unwrap_or_default();
+        match kind.trim().to_ascii_lowercase().as_str() {
+            "" | "none" => Ok(MevProtection::None),
+            "flashbots" => {
+                let relay_url = env_registry::var("FLASHBOTS_RELAY_URL")
+                    .filter(|url| !url.trim().is_empty())
+                    .unwrap_or_else(|| DEFAULT_FLASHBOTS_RELAY_URL.to_string());
+                let refund_percent = match env_registry::var("FLASHBOTS_REFUND_PERCENT") {
+                    Some(percent) => percent
+                        .trim()
+                        .parse::<u8>()
+                        .ok()
+                        .filter(|percent| *percent <= 100)
+                        .ok_or_else(|| {
+                            MevError::Config(format!(
+                                "FLASHBOTS_REFUND_PERCENT must be 0-100, got {percent:?}"
+                            ))
+                        })?,
+                    None => 0,
+                };
+                Ok(MevProtection::Flashbots {
+                    relay_url,
+                    refund_percent,
+                })
+            }
+            other => Err(MevError::Config(format!(
+                "unknown MEV protection {other:?}"
+            ))),
+        }
+    }
+
+    /// The protection that applies on `network`: Flashbots only runs on Ethereum mainnet.
+    pub fn for_network(self, network: Network) -> Self {
+        match self {
+            MevProtection::Flashbots { .. } if network != Network::Ethereum => {
+                tracing::debug!(network = %network, "Flashbots is mainnet-only, using the public mempool");
+                MevProtection::None
+            }
+            protection => protection,
+        }
+    }
+}
+
+/// Key authenticating relay requests, from `FLASHBOTS_AUTH_KEY`.
+pub fn auth_signer_from_env() -> Result<PrivateKeySigner, MevError> {
+    let key = env_registry::var("FLASHBOTS_AUTH_KEY")
+        .filter(|key| !key.trim().is_empty())
+        .ok_or_else(|| {
+            MevError::Config("FLASHBOTS_AUTH_KEY is required with Flashbots".to_string())
+        })?;
+    key.trim()
+        .parse::<PrivateKeySigner>()
+        .map_err(|e| MevError::Config(format!("invalid FLASHBOTS_AUTH_KEY: {e}")))
+}
+
+/// `X-Flashbots-Signature` value of a relay request with `body`.
+pub fn flashbots_signature(signer: &PrivateKeySigner, body: &[u8]) -> Result<String, MevError> {
+    let digest = format!("{:#x}", keccak256(body));
+    let signature = signer
+        .sign_message_sync(digest.as_bytes())
+        .map_err(|e| MevError::Signing(e.to_string()))?;
+    Ok(format!(
+        "{}:{}",
+        signer.address(),
+        hex::encode_prefixed(signature.as_bytes())
+    ))
+}
+
+#[derive(Deserialize)]
+struct RelayResponse {
+    result: Option<B256>,
+    error: Option<RelayError>,
+}
+
+#[derive(Deserialize)]
+struct RelayError {
+    message: String,
+}
+
+/// Client of a Flashbots relay's `eth_sendPrivateTransaction`.
+#[derive(Debug, Clone)]
+pub struct FlashbotsRelay {
+    client: reqwest::Client,
+    relay_url: String,
+    refund_percent: u8,
+    /// Signs the `X-Flashbots-Signature` header; identifies the searcher, holds no funds
+    auth_signer: PrivateKeySigner,
+    /// How long a private transaction has to be included
+    timeout: Duration,
+}
+
+impl FlashbotsRelay {
+    pub fn new(
+        relay_url: String,
+        refund_percent: u8,
+        auth_signer: PrivateKeySigner,
+        timeout: Duration,
+    ) -> Self {
+        Self {
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(10))
+                .build()
+                .expect("Failed to create HTTP client"),
+            relay_url,
+            refund_percent,
+            auth_signer,
+            timeout,
+        }
+    }
+
+    /// Send the signed transaction `raw_tx` to the relay, refunding `refund_address`.
+    ///
+    /// # Returns
+    ///
+    /// The transaction hash acknowledged by the relay
+    pub async fn send_private_transaction(
+        &self,
+        raw_tx: &[u8],
+        refund_address: Address,
+    ) -> Result<B256, MevError> {
+        let mut preferences = serde_json::json!({ "fast": true });
+        if self.refund_percent > 0 {
+            preferences["validity"] = serde_json::json!({
+                "refund": [{ "address": refund_address, "percent": self.refund_percent }]
+            });
+        }
+        let body = serde_json::to_vec(&serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "eth_sendPrivateTransaction",
+            "params": [{
+                "tx": hex::encode_prefixed(raw_tx),
+                "preferences": preferences,
+            }],
+        }))
+        .map_err(|e| MevError::Relay(e.to_string()))?;
+        let signature = flashbots_signature(&self.auth_signer, &body)?;
+
+        let response: RelayResponse = self
+            .client
+            .post(&self.relay_url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(FLASHBOTS_SIGNATURE_HEADER, signature)
+            .body(body)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        match (response.result, response.error) {
+            (_, Some(error)) => Err(MevError::Relay(error.message)),
+            (Some(hash), None) => Ok(hash),
+            (None, None) => Err(MevError::Relay("empty response".to_string())),
+        }
+    }
+
+    /// Send `raw_tx` (hashing to `tx_hash`) privately, and broadcast it through
+    /// `provider` if the relay refuses it or it is not included within the timeout.
+    pub async fn send_protected<P: Provider<Ethereum>>(
+        &self,
+        provider: &P,
+        raw_tx: &[u8],
+        tx_hash: B256,
+        refund_address: Address,
+    ) -> TransportResult<PendingTransactionBuilder<Ethereum>> {
+        match self.send_private_transaction(raw_tx, refund_address).await {
+            Ok(_) => {
+                tracing::info!(tx = %tx_hash, relay = %self.relay_url, "Sent private transaction");
+                if self.wait_for_inclusion(provider, tx_hash).await {
+                    return Ok(PendingTransactionBuilder::new(
+                        provider.root().clone(),
+                        tx_hash,
+                    ));
+                }
+                tracing::warn!(
+                    tx = %tx_hash,
+                    timeout_secs = self.timeout.as_secs(),
+                    "Private transaction not included, broadcasting publicly"
+                );
+            }
+            Err(e) => {
+                tracing::warn!(tx = %tx_hash, error = %e, "Flashbots relay failed, broadcasting publicly")
+            }
+        }
+
+        match provider.send_raw_transaction(raw_tx).await {
+            Ok(pending) => Ok(pending),
+            // The private transaction may have landed since the last check
+            Err(e) if self.is_included(provider, tx_hash).await => {
+                tracing::debug!(tx = %tx_hash, error = %e, "Public broadcast of an included transaction failed");
+                Ok(PendingTransactionBuilder::new(
+                    provider.root().clone(),
+                    tx_hash,
+                ))
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Poll for the receipt of `tx_hash` until the timeout; whether it was found.
+    async fn wait_for_inclusion<P: Provider<Ethereum>>(&self, provider: &P, tx_hash: B256) -> bool {
+        let deadline = tokio::time::Instant::now() + self.timeout;
+        let interval = INCLUSION_POLL_INTERVAL.min(self.timeout);
+        loop {
+            tokio::time::sleep(interval).await;
+            if self.is_included(provider, tx_hash).await {
+                return true;
+            }
+            if tokio::time::Instant::now() >= deadline {
+                return false;
+            }
+        }
+    }
+
+    async fn is_included<P: Provider<Ethereum>>(&self, provider: &P, tx_hash: B256) -> bool {
+        matches!(provider.get_transaction_receipt(tx_hash).await, Ok(Some(_)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::Signature;
+    use alloy::providers::ProviderBuilder;
+    use std::str::FromStr;
+    use wiremock::matchers::{header_exists, method};
+    use wiremock::{Mock, MockServer, Request, ResponseTemplate};
+
+    const TX_HASH: B256 = B256::repeat_byte(0xab);
+    const RAW_TX: &[u8] = &[0x02, 0xf8, 0x70, 0x01];
+
+    fn relay(server: &MockServer, refund_percent: u8, timeout: Duration) -> FlashbotsRelay {
+        FlashbotsRelay::new(
+            server.uri(),
+            refund_percent,
+            PrivateKeySigner::random(),
+            timeout,
+        )
+    }
+
+    /// Address recovered from an `X-Flashbots-Signature` header of a request with `body`.
+    fn recover_signer(header: &str, body: &[u8]) -> Address {
+        let (address, signature) = header.split_once(':').unwrap();
+        let signature = Signature::from_str(signature).unwrap();
+        let digest = format!("{:#x}", keccak256(body));
+        let recovered = signature
+            .recover_address_from_msg(digest.as_bytes())
+            .unwrap();
+        assert_eq!(address.parse::<Address>().unwrap(), recovered);
+        recovered
+    }
+
+    #[test]
+    fn test_signature_header_format() {
+        let signer = PrivateKeySigner::random();
+        let body = br#"{"jsonrpc":"2.0","id":1,"method":"eth_sendPrivateTransaction","params":[]}"#;
+
+        let header = flashbots_signature(&signer, body).unwrap();
+
+        let (address, signature) = header.split_once(':').unwrap();
+        assert_eq!(address, signer.address().to_string());
+        assert!(signature.starts_with("0x"));
+        assert_eq!(signature.len(), 2 + 65 * 2);
+        assert_eq!(recover_signer(&header, body), signer.address());
+    }
+
+    #[tokio::test]
+    async fn test_relay_receives_signed_private_transaction() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(header_exists(FLASHBOTS_SIGNATURE_HEADER))
+            .respond_with(|request: &Request| {
+                let header = request.headers[FLASHBOTS_SIGNATURE_HEADER]
+                    .to_str()
+                    .unwrap();
+                recover_signer(header, &request.body);
+                let body: serde_json::Value = request.body_json().unwrap();
+                assert_eq!(body["method"], "eth_sendPrivateTransaction");
+                assert_eq!(body["params"][0]["tx"], "0x02f87001");
+                assert_eq!(
+                    body["params"][0]["preferences"]["validity"]["refund"][0]["percent"],
+                    50
+                );
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "id": body["id"],
+                    "result": TX_HASH,
+                }))
+            })
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let hash = relay(&server, 50, Duration::from_secs(1))
+            .send_private_transaction(RAW_TX, Address::repeat_byte(0x11))
+            .await
+            .unwrap();
+        assert_eq!(hash, TX_HASH);
+    }
+
+    #[tokio::test]
+    async fn test_relay_error_is_reported() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "error": { "code": -32000, "message": "nonce too low" },
+            })))
+            .mount(&server)
+            .await;
+
+        let error = relay(&server, 0, Duration::from_secs(1))
+            .send_private_transaction(RAW_TX, Address::ZERO)
+            .await
+            .unwrap_err();
+        assert!(matches!(error, MevError::Relay(message) if message == "nonce too low"));
+    }
+
+    #[tokio::test]
+    async fn test_falls_back_to_public_mempool_without_inclusion() {
+        let relay_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "result": TX_HASH,
+            })))
+            .expect(1)
+            .mount(&relay_server)
+            .await;
+        // Never included privately; accepts the public broadcast
+        let rpc_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(|request: &Request| {
+                let body: serde_json::Value = request.body_json().unwrap();
+                let result = match body["method"].as_str().unwrap() {
+                    "eth_getTransactionReceipt" => serde_json::Value::Null,
+                    "eth_sendRawTransaction" => {
+                        assert_eq!(body["params"][0], "0x02f87001");
+                        serde_json::json!(TX_HASH)
+                    }
+                    other => panic!("unexpected {other}"),
+                };
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "id": body["id"],
+                    "result": result,
+                }))
+            })
+            .mount(&rpc_server)
+            .await;
+        let provider = ProviderBuilder::new().connect_http(rpc_server.uri().parse().unwrap());
+
+        let pending = relay(&relay_server, 0, Duration::from_millis(200))
+            .send_protected(&provider, RAW_TX, TX_HASH, Address::ZERO)
+            .await
+            .unwrap();
+
+        assert_eq!(*pending.tx_hash(), TX_HASH);
+        let requests = rpc_server.received_requests().await.unwrap();
+        let methods: Vec<String> = requests
+            .iter()
+            .map(|r| r.body_json::<serde_json::Value>().unwrap()["method"].to_string())
+            .collect();
+        assert_eq!(methods.last().unwrap(), "\"eth_sendRawTransaction\"");
+    }
+
+    #[test]
+    fn test_flashbots_is_mainnet_only() {
+        let flashbots = MevProtection::Flashbots {
+            relay_url: DEFAULT_FLASHBOTS_RELAY_URL.to_string(),
+            refund_percent: 0,
+        };
+
+        assert_eq!(flashbots.clone().for_network(Network::Ethereum), flashbots);
+        assert_eq!(
+            flashbots.clone().for_network(Network::EthereumSepolia),
+            MevProtection::None
+        );
+        assert_eq!(flashbots.for_network(Network::Base), MevProtection::None);
+    }
+}
@@ -1,5 +1,6 @@
 use std::future::Future;
-use std::time::SystemTimeError;
+use std::sync::Arc;
+use std::time::{Duration, SystemTimeError};
 
 use crate::chain::evm::EvmProvider;
 use crate::chain::near::NearProvider;
@@ -9,17 +10,28 @@ use crate::chain::stellar::StellarProvider;
 use crate::chain::algorand::AlgorandProvider;
 #[cfg(feature = "sui")]
 use crate::chain::sui::SuiProvider;
+#[cfg(feature = "hedera")]
+use crate::chain::hedera::HederaProvider;
 use crate::facilitator::Facilitator;
 use crate::network::{Network, NetworkFamily};
+use crate::nonce_store::NonceStore;
+use crate::streaming::{
+    AllowanceCharger, StreamingError, StreamingPaymentPayload, StreamingPaymentRequirement,
+};
 use crate::types::{
-    MixedAddress, Scheme, SettleRequest, SettleResponse, SupportedPaymentKindsResponse,
-    VerifyRequest, VerifyResponse,
+    ExactPaymentPayload, MixedAddress, Scheme, SettleRequest, SettleResponse,
+    SupportedPaymentKindsResponse, TokenAmount, TransactionHash, VerifyRequest, VerifyResponse,
 };
 
 #[cfg(feature = "algorand")]
 pub mod algorand;
 pub mod evm;
+pub mod gas_oracle;
+#[cfg(feature = "hedera")]
+pub mod hedera;
+pub mod mev;
 pub mod near;
+pub mod rpc_router;
 pub mod solana;
 pub mod stellar;
 #[cfg(feature = "sui")]
@@ -34,44 +46,59 @@ pub enum NetworkProvider {
     Algorand(AlgorandProvider),
     #[cfg(feature = "sui")]
     Sui(SuiProvider),
+    #[cfg(feature = "hedera")]
+    Hedera(HederaProvider),
 }
 
 pub trait FromEnvByNetworkBuild: Sized {
+    /// Builds the provider for `network`, or `None` when it is not configured.
+    ///
+    /// Providers tracking used nonces off-chain keep them in `nonce_store`, the store the
+    /// whole facilitator shares.
     fn from_env(
         network: Network,
+        nonce_store: Arc<dyn NonceStore>,
     ) -> impl Future<Output = Result<Option<Self>, Box<dyn std::error::Error>>> + Send;
 }
 
 impl FromEnvByNetworkBuild for NetworkProvider {
-    async fn from_env(network: Network) -> Result<Option<Self>, Box<dyn std::error::Error>> {
+    async fn from_env(
+        network: Network,
+        nonce_store: Arc<dyn NonceStore>,
+    ) -> Result<Option<Self>, Box<dyn std::error::Error>> {
         let family: NetworkFamily = network.into();
         let provider = match family {
             NetworkFamily::Evm => {
-                let provider = EvmProvider::from_env(network).await?;
+                let provider = EvmProvider::from_env(network, nonce_store).await?;
                 provider.map(NetworkProvider::Evm)
             }
             NetworkFamily::Solana => {
-                let provider = SolanaProvider::from_env(network).await?;
+                let provider = SolanaProvider::from_env(network, nonce_store).await?;
                 provider.map(NetworkProvider::Solana)
             }
             NetworkFamily::Near => {
-                let provider = NearProvider::from_env(network).await?;
+                let provider = NearProvider::from_env(network, nonce_store).await?;
                 provider.map(NetworkProvider::Near)
             }
             NetworkFamily::Stellar => {
-                let provider = StellarProvider::from_env(network).await?;
+                let provider = StellarProvider::from_env(network, nonce_store).await?;
                 provider.map(NetworkProvider::Stellar)
             }
             #[cfg(feature = "algorand")]
             NetworkFamily::Algorand => {
-                let provider = AlgorandProvider::from_env(network).await?;
+                let provider = AlgorandProvider::from_env(network, nonce_store).await?;
                 provider.map(NetworkProvider::Algorand)
             }
             #[cfg(feature = "sui")]
             NetworkFamily::Sui => {
-                let provider = SuiProvider::from_env(network).await?;
+                let provider = SuiProvider::from_env(network, nonce_store).await?;
                 provider.map(NetworkProvider::Sui)
             }
+            #[cfg(feature = "hedera")]
+            NetworkFamily::Hedera => {
+                let provider = HederaProvider::from_env(network, nonce_store).await?;
+                provider.map(NetworkProvider::Hedera)
+            }
         };
         Ok(provider)
     }
@@ -93,6 +120,8 @@ impl NetworkProviderOps for NetworkProvider {
             NetworkProvider::Algorand(provider) => provider.signer_address(),
             #[cfg(feature = "sui")]
             NetworkProvider::Sui(provider) => provider.signer_address(),
+            #[cfg(feature = "hedera")]
+            NetworkProvider::Hedera(provider) => provider.signer_address(),
         }
     }
 
@@ -106,6 +135,8 @@ impl NetworkProviderOps for NetworkProvider {
             NetworkProvider::Algorand(provider) => provider.network(),
             #[cfg(feature = "sui")]
             NetworkProvider::Sui(provider) => provider.network(),
+            #[cfg(feature = "hedera")]
+            NetworkProvider::Hedera(provider) => provider.network(),
         }
     }
 }
@@ -123,6 +154,8 @@ impl Facilitator for NetworkProvider {
             NetworkProvider::Algorand(provider) => provider.verify(request).await,
             #[cfg(feature = "sui")]
             NetworkProvider::Sui(provider) => provider.verify(request).await,
+            #[cfg(feature = "hedera")]
+            NetworkProvider::Hedera(provider) => provider.verify(request).await,
         }
     }
 
@@ -136,6 +169,8 @@ impl Facilitator for NetworkProvider {
             NetworkProvider::Algorand(provider) => provider.settle(request).await,
             #[cfg(feature = "sui")]
             NetworkProvider::Sui(provider) => provider.settle(request).await,
+            #[cfg(feature = "hedera")]
+            NetworkProvider::Hedera(provider) => provider.settle(request).await,
         }
     }
 
@@ -149,6 +184,80 @@ impl Facilitator for NetworkProvider {
             NetworkProvider::Algorand(provider) => provider.supported().await,
             #[cfg(feature = "sui")]
             NetworkProvider::Sui(provider) => provider.supported().await,
+            #[cfg(feature = "hedera")]
+            NetworkProvider::Hedera(provider) => provider.supported().await,
+        }
+    }
+}
+
+/// Streaming allowances are EIP-2612 permits, so only EVM networks can open streams.
+impl AllowanceCharger for NetworkProvider {
+    async fn check_allowance(
+        &self,
+        payload: &StreamingPaymentPayload,
+        requirement: &StreamingPaymentRequirement,
+    ) -> Result<(), StreamingError> {
+        match self {
+            NetworkProvider::Evm(provider) => provider.check_allowance(payload, requirement).await,
+            _ => Err(StreamingError::InvalidAllowance(
+                "unsupported_network".to_string(),
+            )),
+        }
+    }
+
+    async fn activate(
+        &self,
+        payload: &StreamingPaymentPayload,
+        requirement: &StreamingPaymentRequirement,
+    ) -> Result<Option<TransactionHash>, StreamingError> {
+        match self {
+            NetworkProvider::Evm(provider) => provider.activate(payload, requirement).await,
+            _ => Err(StreamingError::InvalidAllowance(
+                "unsupported_network".to_string(),
+            )),
+        }
+    }
+
+    async fn charge(
+        &self,
+        payload: &StreamingPaymentPayload,
+        requirement: &StreamingPaymentRequirement,
+        amount: TokenAmount,
+    ) -> Result<TransactionHash, StreamingError> {
+        match self {
+            NetworkProvider::Evm(provider) => provider.charge(payload, requirement, amount).await,
+            _ => Err(StreamingError::InvalidAllowance(
+                "unsupported_network".to_string(),
+            )),
+        }
+    }
+}
+
+/// Amount of the asset the payer signed over in the payload of `request`.
+///
+/// `maxAmountRequired` is declared by the caller and only bounds the payment from below,
+/// so amount-based decisions must use this instead. Solana payloads must transfer exactly
+/// the required amount, so they count at the declared one. `None` when the payload cannot
+/// be decoded.
+pub fn signed_amount(request: &VerifyRequest) -> Option<TokenAmount> {
+    match &request.payment_payload.payload {
+        ExactPaymentPayload::Evm(payload) => Some(payload.authorization.value),
+        ExactPaymentPayload::Solana(_) => Some(request.payment_requirements.max_amount_required),
+        ExactPaymentPayload::Near(payload) => {
+            near::signed_transfer_amount(&payload.signed_delegate_action).map(TokenAmount::from)
+        }
+        ExactPaymentPayload::Stellar(payload) => {
+            payload.amount.parse::<u128>().ok().map(TokenAmount::from)
+        }
+        #[cfg(feature = "algorand")]
+        ExactPaymentPayload::Algorand(payload) => {
+            algorand::signed_payment_amount(payload).map(TokenAmount::from)
+        }
+        #[cfg(feature = "sui")]
+        ExactPaymentPayload::Sui(payload) => payload.amount.parse::<u64>().ok().map(TokenAmount::from),
+        #[cfg(feature = "hedera")]
+        ExactPaymentPayload::Hedera(payload) => {
+            hedera::signed_transfer_amount(payload).map(TokenAmount::from)
         }
     }
 }
@@ -194,7 +303,36 @@ pub enum FacilitatorLocalError {
     /// Address is blocked by blacklist.
     #[error("Blocked address: {1}")]
     BlockedAddress(MixedAddress, String),
+    /// The RPC node is considered down; retry after the given delay.
+    #[error("Circuit breaker open, retry in {0:?}")]
+    CircuitOpen(Duration),
+    /// The settlement transaction reverted when dry-run, so it was not submitted.
+    #[error("Transaction simulation reverted: {0}")]
+    SimulationFailed(RevertReason),
+    /// The payment token reverted a simulated transfer, so funds paid in it could not be moved on.
+    #[error("Honeypot token detected: {0}")]
+    HoneypotDetected(MixedAddress),
+    /// The payment token is not on the network's token whitelist.
+    #[error("Token not whitelisted: {0}")]
+    TokenNotWhitelisted(MixedAddress),
+    /// A compliance check rejected the payment, e.g. it lacks required travel rule data.
+    #[error("Compliance check failed: {0}")]
+    Compliance(x402_compliance::ComplianceError),
     /// Other errors.
     #[error("{0}")]
     Other(String),
 }
+
+/// Why a dry-run transaction reverted.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum RevertReason {
+    /// The token sender's balance is below the transfer amount.
+    #[error("insufficient token balance")]
+    InsufficientBalance,
+    /// The spender's token allowance is below the transfer amount.
+    #[error("insufficient token allowance")]
+    InsufficientAllowance,
+    /// Any other revert: the decoded revert string, or the raw revert data.
+    #[error("{0}")]
+    Other(String),
+}
@@ -31,6 +31,7 @@ use crate::chain::{FacilitatorLocalError, FromEnvByNetworkBuild, NetworkProvider
 use crate::facilitator::Facilitator;
 use crate::from_env;
 use crate::network::Network;
+use crate::nonce_store::NonceStore;
 use crate::types::{
     ExactPaymentPayload, FacilitatorErrorReason, MixedAddress, Scheme, SettleRequest,
     SettleResponse, SupportedPaymentKind, SupportedPaymentKindExtra, SupportedPaymentKindsResponse,
@@ -119,6 +120,24 @@ pub struct FtTransferArgs {
     pub memo: Option<String>,
 }
 
+/// Amount of the `ft_transfer` in a base64 SignedDelegateAction, without checking it
+/// against any payment requirements.
+pub fn signed_transfer_amount(encoded: &str) -> Option<u128> {
+    let bytes = base64::Engine::decode(&base64::engine::general_purpose::STANDARD, encoded).ok()?;
+    let signed_delegate_action: SignedDelegateAction = borsh::from_slice(&bytes).ok()?;
+    signed_delegate_action
+        .delegate_action
+        .actions
+        .iter()
+        .find_map(|non_delegate_action| match Action::from(non_delegate_action.clone()) {
+            Action::FunctionCall(func_call) if func_call.method_name == "ft_transfer" => {
+                let args: FtTransferArgs = serde_json::from_slice(&func_call.args).ok()?;
+                args.amount.parse().ok()
+            }
+            _ => None,
+        })
+}
+
 /// NEP-141 storage_deposit arguments
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StorageDepositArgs {
@@ -616,9 +635,12 @@ pub struct VerifyPaymentResult {
 }
 
 impl FromEnvByNetworkBuild for NearProvider {
-    async fn from_env(network: Network) -> Result<Option<Self>, Box<dyn std::error::Error>> {
+    async fn from_env(
+        network: Network,
+        _nonce_store: Arc<dyn NonceStore>,
+    ) -> Result<Option<Self>, Box<dyn std::error::Error>> {
         let env_var = from_env::rpc_env_name_from_network(network);
-        let rpc_url = match std::env::var(env_var).ok() {
+        let rpc_url = match crate::env_registry::var(env_var) {
             Some(rpc_url) => rpc_url,
             None => {
                 tracing::warn!(network=%network, "no RPC URL configured, skipping");
@@ -669,6 +691,7 @@ impl Facilitator for NearProvider {
                 transaction: None,
                 network: self.network(),
                 proof_of_payment: None,
+                confirmation: None,
             });
         }
 
@@ -687,6 +710,7 @@ impl Facilitator for NearProvider {
                     transaction: None,
                     network: self.network(),
                     proof_of_payment: None,
+                    confirmation: None,
                 });
             }
         };
@@ -701,6 +725,7 @@ impl Facilitator for NearProvider {
             transaction: Some(TransactionHash::Near(tx_hash_bytes)),
             network: self.network(),
             proof_of_payment: None, // ERC-8004 not supported on NEAR
+            confirmation: None,
         })
     }
 
@@ -712,6 +737,7 @@ impl Facilitator for NearProvider {
             extra: Some(SupportedPaymentKindExtra {
                 fee_payer: Some(self.signer_address()),
                 tokens: None, // TODO: Add NEAR token support
+                accepted_tokens: None,
             }),
         }];
         Ok(SupportedPaymentKindsResponse { kinds })
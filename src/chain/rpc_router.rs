@@ -0,0 +1,274 @@
+//! JSON-RPC transport with health-aware failover across several endpoints.
+//!
+//! Public RPC nodes go down or start rate-limiting without warning. An [`RpcRouter`]
+//! holds every endpoint configured for a chain and sends each request to the
+//! [`primary`](RpcRouter::primary) one first. When that fails with a connection-level
+//! error (unreachable node, timeout, HTTP 5xx or 429) the request is retried on the next
+//! candidate, and the failure is recorded in the endpoint's [`EndpointHealth`].
+//!
+//! JSON-RPC error responses (reverts, nonce errors) are returned as-is: another node
+//! would answer the same way.
+//!
+//! The router implements alloy's `Transport`, so a provider built on it fails over on
+//! every call, including those made while verifying and settling. EVM providers use it
+//! when their `RPC_URL_*` variable holds a comma-separated list of endpoints.
+
+use alloy::rpc::json_rpc::{RequestPacket, ResponsePacket};
+use alloy::transports::http::{reqwest, Http};
+use alloy::transports::{TransportError, TransportErrorKind, TransportFut, TransportResult};
+use std::sync::{Arc, RwLock};
+use std::task::{Context, Poll};
+use std::time::Instant;
+use tower::Service;
+use url::Url;
+
+#[derive(Debug, thiserror::Error)]
+pub enum RpcRouterError {
+    #[error("no RPC endpoints configured")]
+    NoEndpoints,
+    #[error("invalid RPC endpoint {0}")]
+    InvalidUrl(String),
+}
+
+/// Observed health of one endpoint.
+#[derive(Debug, Clone)]
+pub struct EndpointHealth {
+    pub url: Url,
+    /// Failures since the last success
+    pub consecutive_failures: u32,
+    /// Failures since creation or the last [`RpcRouter::reset_health`]
+    pub total_failures: u64,
+    pub last_failure: Option<Instant>,
+    pub last_success: Option<Instant>,
+}
+
+impl EndpointHealth {
+    fn new(url: Url) -> Self {
+        Self {
+            url,
+            consecutive_failures: 0,
+            total_failures: 0,
+            last_failure: None,
+            last_success: None,
+        }
+    }
+
+    /// An endpoint is live until it fails, and again once it succeeds.
+    pub fn is_live(&self) -> bool {
+        self.consecutive_failures == 0
+    }
+}
+
+/// Routes JSON-RPC requests to the healthiest of several endpoints.
+#[derive(Debug, Clone)]
+pub struct RpcRouter {
+    transports: Arc<Vec<Http<reqwest::Client>>>,
+    health: Arc<RwLock<Vec<EndpointHealth>>>,
+}
+
+impl RpcRouter {
+    /// Router over `urls`, tried in the given order while they are all live.
+    pub fn new(urls: Vec<Url>) -> Result<Self, RpcRouterError> {
+        if urls.is_empty() {
+            return Err(RpcRouterError::NoEndpoints);
+        }
+        let client = reqwest::Client::new();
+        let transports = urls
+            .iter()
+            .map(|url| Http::with_client(client.clone(), url.clone()))
+            .collect();
+        let health = urls.into_iter().map(EndpointHealth::new).collect();
+        Ok(Self {
+            transports: Arc::new(transports),
+            health: Arc::new(RwLock::new(health)),
+        })
+    }
+
+    /// Router over a comma-separated list of endpoints.
+    pub fn from_list(list: &str) -> Result<Self, RpcRouterError> {
+        let urls = list
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(|s| {
+                // Never echo the URL itself: it usually embeds an API key
+                Url::parse(s).map_err(|e| RpcRouterError::InvalidUrl(e.to_string()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Self::new(urls)
+    }
+
+    /// The endpoint requests currently go to first.
+    pub fn primary(&self) -> Url {
+        let index = self.candidates()[0];
+        self.health.read().unwrap()[index].url.clone()
+    }
+
+    /// Snapshot of every endpoint's health, in configuration order.
+    pub fn health(&self) -> Vec<EndpointHealth> {
+        self.health.read().unwrap().clone()
+    }
+
+    /// Forget all recorded failures and successes.
+    pub fn reset_health(&self) {
+        for endpoint in self.health.write().unwrap().iter_mut() {
+            *endpoint = EndpointHealth::new(endpoint.url.clone());
+        }
+    }
+
+    /// Endpoint indices in the order they should be tried: live endpoints in
+    /// configuration order, then failed ones from least to most recently failed.
+    fn candidates(&self) -> Vec<usize> {
+        let health = self.health.read().unwrap();
+        let mut indices: Vec<usize> = (0..health.len()).collect();
+        indices.sort_by_key(|&i| (!health[i].is_live(), health[i].last_failure));
+        indices
+    }
+
+    fn record(&self, index: usize, success: bool) {
+        let mut health = self.health.write().unwrap();
+        let endpoint = &mut health[index];
+        if success {
+            endpoint.consecutive_failures = 0;
+            endpoint.last_success = Some(Instant::now());
+        } else {
+            endpoint.consecutive_failures += 1;
+            endpoint.total_failures += 1;
+            endpoint.last_failure = Some(Instant::now());
+        }
+    }
+
+    async fn send(self, request: RequestPacket) -> TransportResult<ResponsePacket> {
+        let mut last_error = None;
+        for index in self.candidates() {
+            let mut transport = self.transports[index].clone();
+            match transport.call(request.clone()).await {
+                Err(e) if is_connection_error(&e) => {
+                    self.record(index, false);
+                    let host = self.health.read().unwrap()[index]
+                        .url
+                        .host_str()
+                        .map(str::to_string);
+                    tracing::warn!(endpoint = index, host = ?host, error = %e, "RPC endpoint failed, trying next");
+                    last_error = Some(e);
+                }
+                result => {
+                    // The node answered, even if with a JSON-RPC error
+                    self.record(index, true);
+                    return result;
+                }
+            }
+        }
+        Err(last_error.unwrap_or_else(|| TransportErrorKind::custom_str("no RPC endpoints")))
+    }
+}
+
+/// Errors another endpoint might not have.
+fn is_connection_error(error: &TransportError) -> bool {
+    match error {
+        TransportError::Transport(TransportErrorKind::HttpError(e)) => {
+            e.status >= 500 || e.status == 429
+        }
+        TransportError::Transport(TransportErrorKind::Custom(_))
+        | TransportError::Transport(TransportErrorKind::BackendGone) => true,
+        _ => false,
+    }
+}
+
+impl Service<RequestPacket> for RpcRouter {
+    type Response = ResponsePacket;
+    type Error = TransportError;
+    type Future = TransportFut<'static>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: RequestPacket) -> Self::Future {
+        Box::pin(self.clone().send(request))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::providers::{Provider, ProviderBuilder};
+    use alloy::rpc::client::RpcClient;
+    use axum::http::StatusCode;
+    use axum::response::IntoResponse;
+
+    /// Serve `eth_chainId` answering 8453, or HTTP 500 when `fail` is set.
+    async fn serve_rpc(fail: bool) -> Url {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let app = axum::Router::new().route(
+            "/",
+            axum::routing::post(
+                move |axum::Json(request): axum::Json<serde_json::Value>| async move {
+                    if fail {
+                        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+                    }
+                    axum::Json(serde_json::json!({
+                        "jsonrpc": "2.0",
+                        "id": request["id"],
+                        "result": "0x2105"
+                    }))
+                    .into_response()
+                },
+            ),
+        );
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+        Url::parse(&format!("http://127.0.0.1:{}/", port)).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_fails_over_to_next_endpoint() {
+        let broken = serve_rpc(true).await;
+        let healthy = serve_rpc(false).await;
+        let router = RpcRouter::new(vec![broken.clone(), healthy.clone()]).unwrap();
+        assert_eq!(router.primary(), broken);
+
+        let provider = ProviderBuilder::new().connect_client(RpcClient::new(router.clone(), true));
+        assert_eq!(provider.get_chain_id().await.unwrap(), 8453);
+
+        let health = router.health();
+        assert_eq!(health[0].consecutive_failures, 1);
+        assert!(health[1].is_live());
+        assert!(health[1].last_success.is_some());
+        // The failed endpoint is no longer tried first
+        assert_eq!(router.primary(), healthy);
+
+        router.reset_health();
+        assert_eq!(router.primary(), broken);
+        assert_eq!(router.health()[0].total_failures, 0);
+    }
+
+    #[tokio::test]
+    async fn test_least_recently_failed_endpoint_is_retried_first() {
+        let a = serve_rpc(true).await;
+        let b = serve_rpc(true).await;
+        let router = RpcRouter::new(vec![a.clone(), b.clone()]).unwrap();
+        let provider = ProviderBuilder::new().connect_client(RpcClient::new(router.clone(), true));
+
+        // Both fail: a first, then b
+        assert!(provider.get_chain_id().await.is_err());
+        assert!(router.health().iter().all(|endpoint| !endpoint.is_live()));
+        assert_eq!(router.primary(), a);
+    }
+
+    #[test]
+    fn test_from_list() {
+        let router = RpcRouter::from_list("https://a.example.com, https://b.example.com,").unwrap();
+        assert_eq!(router.health().len(), 2);
+        assert!(matches!(
+            RpcRouter::from_list(" , "),
+            Err(RpcRouterError::NoEndpoints)
+        ));
+        assert!(matches!(
+            RpcRouter::from_list("https://a.example.com,not a url"),
+            Err(RpcRouterError::InvalidUrl(_))
+        ));
+    }
+}
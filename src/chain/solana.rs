@@ -13,9 +13,11 @@ use std::time::Duration;
 use tracing_core::Level;
 
 use crate::chain::{FacilitatorLocalError, FromEnvByNetworkBuild, NetworkProviderOps};
+use crate::env_registry;
 use crate::facilitator::Facilitator;
 use crate::from_env;
 use crate::network::Network;
+use crate::nonce_store::NonceStore;
 use crate::types::{
     Base64Bytes, ExactPaymentPayload, FacilitatorErrorReason, MixedAddress, PaymentRequirements,
     SettleRequest, SettleResponse, SupportedPaymentKind, SupportedPaymentKindExtra,
@@ -65,6 +67,9 @@ impl TryFrom<Network> for SolanaChain {
             Network::SkaleBase => Err(FacilitatorLocalError::UnsupportedNetwork(None)),
             Network::SkaleBaseSepolia => Err(FacilitatorLocalError::UnsupportedNetwork(None)),
             Network::Scroll => Err(FacilitatorLocalError::UnsupportedNetwork(None)),
+            Network::ScrollSepolia => Err(FacilitatorLocalError::UnsupportedNetwork(None)),
+            Network::ZkSyncEra => Err(FacilitatorLocalError::UnsupportedNetwork(None)),
+            Network::ZkSyncEraSepolia => Err(FacilitatorLocalError::UnsupportedNetwork(None)),
             Network::Near => Err(FacilitatorLocalError::UnsupportedNetwork(None)),
             Network::NearTestnet => Err(FacilitatorLocalError::UnsupportedNetwork(None)),
             Network::Stellar => Err(FacilitatorLocalError::UnsupportedNetwork(None)),
@@ -79,6 +84,10 @@ impl TryFrom<Network> for SolanaChain {
             Network::Sui => Err(FacilitatorLocalError::UnsupportedNetwork(None)),
             #[cfg(feature = "sui")]
             Network::SuiTestnet => Err(FacilitatorLocalError::UnsupportedNetwork(None)),
+            #[cfg(feature = "hedera")]
+            Network::Hedera => Err(FacilitatorLocalError::UnsupportedNetwork(None)),
+            #[cfg(feature = "hedera")]
+            Network::HederaTestnet => Err(FacilitatorLocalError::UnsupportedNetwork(None)),
         }
     }
 }
@@ -116,6 +125,10 @@ impl TryFrom<MixedAddress> for SolanaAddress {
             MixedAddress::Sui(_) => Err(FacilitatorLocalError::InvalidAddress(
                 "expected Solana address".to_string(),
             )),
+            #[cfg(feature = "hedera")]
+            MixedAddress::Hedera(_) => Err(FacilitatorLocalError::InvalidAddress(
+                "expected Solana address".to_string(),
+            )),
             MixedAddress::Solana(pubkey) => Ok(Self { pubkey }),
         }
     }
@@ -157,9 +170,7 @@ impl SolanaProvider {
         };
 
         let limit_var = format!("X402_SOLANA_MAX_COMPUTE_UNIT_LIMIT_{}", suffix);
-        std::env::var(&limit_var)
-            .ok()
-            .and_then(|v| v.parse().ok())
+        env_registry::parse(&limit_var)
             .unwrap_or(match network {
                 Network::Solana => 400_000,
                 Network::SolanaDevnet => 200_000,
@@ -179,9 +190,7 @@ impl SolanaProvider {
         };
 
         let price_var = format!("X402_SOLANA_MAX_COMPUTE_UNIT_PRICE_{}", suffix);
-        std::env::var(&price_var)
-            .ok()
-            .and_then(|v| v.parse().ok())
+        env_registry::parse(&price_var)
             .unwrap_or(match network {
                 Network::Solana => 1_000_000,
                 Network::SolanaDevnet => 100_000,
@@ -676,6 +685,30 @@ impl SolanaProvider {
         Ok(())
     }
 
+    /// Reject a transaction whose recent blockhash has expired.
+    ///
+    /// A blockhash stays valid for 150 slots (about a minute), which is what bounds how long
+    /// a signed payment can be held back. The chain does not report a blockhash's age, so a
+    /// `max_timeout_seconds` shorter than that window cannot be enforced more tightly.
+    async fn verify_recent_blockhash(
+        &self,
+        transaction: &VersionedTransaction,
+    ) -> Result<(), FacilitatorLocalError> {
+        let blockhash = transaction.message.recent_blockhash();
+        let is_valid = self
+            .rpc_client
+            .is_blockhash_valid(blockhash, CommitmentConfig::processed())
+            .await
+            .map_err(|e| FacilitatorLocalError::ContractCall(format!("{e}")))?;
+        if !is_valid {
+            tracing::debug!(%blockhash, "Recent blockhash has expired");
+            return Err(FacilitatorLocalError::DecodingError(
+                "invalid_exact_svm_payload_transaction_blockhash_expired".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
     async fn verify_transfer(
         &self,
         request: &VerifyRequest,
@@ -702,6 +735,10 @@ impl SolanaProvider {
             ExactPaymentPayload::Sui(..) => {
                 return Err(FacilitatorLocalError::UnsupportedNetwork(None));
             }
+            #[cfg(feature = "hedera")]
+            ExactPaymentPayload::Hedera(..) => {
+                return Err(FacilitatorLocalError::UnsupportedNetwork(None));
+            }
             ExactPaymentPayload::Solana(payload) => payload,
         };
         if payload.network != self.network() {
@@ -741,6 +778,16 @@ impl SolanaProvider {
             "Decoded user-signed transaction"
         );
 
+        // 0. The client signatures must cover this exact message, and its blockhash must be live.
+        // Simulation below skips signature checks since our fee payer signature is not in yet.
+        if !TransactionInt::new(transaction.clone()).verify_signatures_except(&self.keypair.pubkey())
+        {
+            return Err(FacilitatorLocalError::DecodingError(
+                "invalid_exact_svm_payload_transaction_signature".to_string(),
+            ));
+        }
+        self.verify_recent_blockhash(&transaction).await?;
+
         // Flexible verification: find instructions by program ID, not fixed positions
         // This allows Phantom to add extra instructions while we still validate the critical ones
 
@@ -812,9 +859,12 @@ impl SolanaProvider {
 }
 
 impl FromEnvByNetworkBuild for SolanaProvider {
-    async fn from_env(network: Network) -> Result<Option<Self>, Box<dyn std::error::Error>> {
+    async fn from_env(
+        network: Network,
+        _nonce_store: Arc<dyn NonceStore>,
+    ) -> Result<Option<Self>, Box<dyn std::error::Error>> {
         let env_var = from_env::rpc_env_name_from_network(network);
-        let rpc_url = match std::env::var(env_var).ok() {
+        let rpc_url = match env_registry::var(env_var) {
             Some(rpc_url) => rpc_url,
             None => {
                 tracing::warn!(network=%network, "no RPC URL configured, skipping");
@@ -901,6 +951,7 @@ impl Facilitator for SolanaProvider {
                 transaction: None,
                 network: self.network(),
                 proof_of_payment: None,
+                confirmation: None,
             });
         }
 
@@ -922,6 +973,7 @@ impl Facilitator for SolanaProvider {
             transaction: Some(TransactionHash::Solana(*tx_sig.as_array())),
             network: self.network(),
             proof_of_payment: None, // ERC-8004 not supported on Solana yet
+            confirmation: None,
         };
         Ok(settle_response)
     }
@@ -934,6 +986,7 @@ impl Facilitator for SolanaProvider {
             extra: Some(SupportedPaymentKindExtra {
                 fee_payer: Some(self.signer_address()),
                 tokens: None, // TODO: Add Solana token support
+                accepted_tokens: None,
             }),
         }];
         Ok(SupportedPaymentKindsResponse { kinds })
@@ -1032,6 +1085,22 @@ impl TransactionInt {
         true
     }
 
+    /// Check that every required signature, other than the one of `fee_payer`, is a valid
+    /// signature of the message by the matching account key.
+    pub fn verify_signatures_except(&self, fee_payer: &Pubkey) -> bool {
+        let num_required = self.inner.message.header().num_required_signatures as usize;
+        let static_keys = self.inner.message.static_account_keys();
+        if self.inner.signatures.len() < num_required || static_keys.len() < num_required {
+            return false;
+        }
+        let msg_bytes = self.inner.message.serialize();
+        static_keys[..num_required]
+            .iter()
+            .zip(self.inner.signatures.iter())
+            .filter(|(key, _)| *key != fee_payer)
+            .all(|(key, signature)| signature.verify(key.as_ref(), &msg_bytes))
+    }
+
     /// Sign the transaction as the fee payer.
     /// The user's signature should already be in place at the appropriate index.
     /// This function adds the facilitator signature at the fee payer's position (typically index 0).
@@ -1114,10 +1183,7 @@ impl TransactionInt {
 
         // Timeout for confirmation - configurable via environment variable
         // Default: 30 seconds (Solana blocks are ~400ms, 30s = ~75 blocks)
-        let timeout_secs = std::env::var("SOLANA_CONFIRM_TIMEOUT_SECS")
-            .ok()
-            .and_then(|s| s.parse::<u64>().ok())
-            .unwrap_or(30);
+        let timeout_secs = env_registry::parse::<u64>("SOLANA_CONFIRM_TIMEOUT_SECS").unwrap_or(30);
         let timeout_duration = Duration::from_secs(timeout_secs);
 
         let confirmation_future = async {
@@ -1157,4 +1223,56 @@ impl TransactionInt {
             .map_err(|e| FacilitatorLocalError::DecodingError(format!("{e}")))?;
         Ok(string)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::hash::Hash;
+    use solana_sdk::instruction::{AccountMeta, Instruction};
+    use solana_sdk::message::Message;
+    use solana_sdk::transaction::Transaction;
+
+    /// A transaction paid for by `fee_payer` that also needs `client`'s signature,
+    /// signed by the client only.
+    fn client_signed(fee_payer: &Keypair, client: &Keypair) -> VersionedTransaction {
+        let instruction = Instruction::new_with_bytes(
+            spl_token::ID,
+            &[12],
+            vec![AccountMeta::new_readonly(client.pubkey(), true)],
+        );
+        let message = Message::new_with_blockhash(
+            &[instruction],
+            Some(&fee_payer.pubkey()),
+            &Hash::new_unique(),
+        );
+        let mut tx = Transaction::new_unsigned(message);
+        tx.partial_sign(&[client], tx.message.recent_blockhash);
+        VersionedTransaction::from(tx)
+    }
+
+    #[test]
+    fn test_verify_signatures_except_fee_payer() {
+        let fee_payer = Keypair::new();
+        let client = Keypair::new();
+        let tx = client_signed(&fee_payer, &client);
+
+        assert!(TransactionInt::new(tx.clone()).verify_signatures_except(&fee_payer.pubkey()));
+        // The fee payer slot is still empty
+        assert!(!TransactionInt::new(tx.clone()).verify_signatures_except(&client.pubkey()));
+
+        let signed = TransactionInt::new(tx.clone()).sign(&fee_payer).unwrap();
+        assert!(signed.is_fully_signed());
+        assert!(signed.verify_signatures_except(&Pubkey::new_unique()));
+
+        // Signed by someone else
+        let mut forged = tx.clone();
+        forged.signatures[1] = Keypair::new().sign_message(&forged.message.serialize());
+        assert!(!TransactionInt::new(forged).verify_signatures_except(&fee_payer.pubkey()));
+
+        // Message changed after signing
+        let mut tampered = tx;
+        tampered.message.set_recent_blockhash(Hash::new_unique());
+        assert!(!TransactionInt::new(tampered).verify_signatures_except(&fee_payer.pubkey()));
+    }
+}
@@ -14,7 +14,6 @@
 use alloy::hex;
 use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
 use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
-use once_cell::sync::OnceCell;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use std::fmt::{Debug, Formatter};
@@ -32,7 +31,7 @@ use stellar_xdr::curr::{
 };
 
 use crate::chain::{FacilitatorLocalError, FromEnvByNetworkBuild, NetworkProviderOps};
-use crate::nonce_store::{stellar_nonce_key, stellar_ttl_seconds, NonceStore, NonceStoreError};
+use crate::nonce_store::{stellar_ttl_seconds, NonceKey, NonceStore, NonceStoreError};
 use crate::facilitator::Facilitator;
 use crate::from_env;
 use crate::network::Network;
@@ -352,24 +351,6 @@ struct HorizonAccount {
 /// Stellar payment provider
 ///
 /// Implements USDC payments on Stellar using Soroban smart contract
-/// Global nonce store for replay protection across all Stellar providers.
-/// Initialized lazily on first use.
-static GLOBAL_NONCE_STORE: OnceCell<Arc<dyn NonceStore>> = OnceCell::new();
-
-/// Get or initialize the global nonce store.
-/// Uses DynamoDB if NONCE_STORE_TABLE_NAME is configured, otherwise falls back to memory.
-async fn get_global_nonce_store() -> Arc<dyn NonceStore> {
-    // If already initialized, return it
-    if let Some(store) = GLOBAL_NONCE_STORE.get() {
-        return store.clone();
-    }
-
-    // Initialize and store
-    let store = crate::nonce_store::create_nonce_store().await;
-    let _ = GLOBAL_NONCE_STORE.set(store.clone());
-    store
-}
-
 /// authorization entries. The facilitator receives pre-signed authorization
 /// entries and wraps them in transactions, paying the fees.
 #[derive(Clone)]
@@ -384,6 +365,8 @@ pub struct StellarProvider {
     chain: StellarChain,
     /// Custom RPC URL (from environment) or None to use defaults
     rpc_url: Option<String>,
+    /// Replay protection for authorization nonces
+    nonce_store: Arc<dyn NonceStore>,
 }
 
 impl Debug for StellarProvider {
@@ -397,10 +380,14 @@ impl Debug for StellarProvider {
 
 impl StellarProvider {
     /// Create a new Stellar provider
+    ///
+    /// Authorization nonces are tracked in `nonce_store`, so replays stay rejected across
+    /// restarts when the store is persistent.
     pub fn try_new(
         secret_key: String,
         rpc_url: Option<String>,
         network: Network,
+        nonce_store: Arc<dyn NonceStore>,
     ) -> Result<Self, FacilitatorLocalError> {
         let chain = StellarChain::try_from(network)?;
 
@@ -427,6 +414,7 @@ impl StellarProvider {
             http_client: Arc::new(reqwest::Client::new()),
             chain,
             rpc_url,
+            nonce_store,
         })
     }
 
@@ -811,10 +799,9 @@ impl StellarProvider {
 
     /// Check if a nonce has been used (read-only, for verification)
     async fn check_nonce_unused(&self, from: &str, nonce: u64) -> Result<(), StellarError> {
-        let store = get_global_nonce_store().await;
-        let key = stellar_nonce_key(self.chain_name(), from, nonce);
+        let key = NonceKey::stellar(self.chain_name(), from, nonce);
 
-        match store.is_used(&key).await {
+        match self.nonce_store.is_key_used(&key).await {
             Ok(true) => Err(StellarError::NonceReused {
                 from: from.to_string(),
                 nonce,
@@ -836,13 +823,13 @@ impl StellarProvider {
         current_ledger: u32,
         expiry_ledger: u32,
     ) -> Result<(), StellarError> {
-        let store = get_global_nonce_store().await;
-        let key = stellar_nonce_key(self.chain_name(), from, nonce);
+        let key = NonceKey::stellar(self.chain_name(), from, nonce);
         let ttl = stellar_ttl_seconds(current_ledger, expiry_ledger);
 
-        match store.check_and_mark_used(&key, ttl).await {
+        match self.nonce_store.check_and_mark_key(&key, ttl).await {
             Ok(()) => Ok(()),
             Err(NonceStoreError::NonceAlreadyUsed(_)) => {
+                crate::metrics::record_nonce_replay(self.chain_name());
                 Err(StellarError::NonceReused {
                     from: from.to_string(),
                     nonce,
@@ -1444,8 +1431,11 @@ pub struct VerifyPaymentResult {
 // =============================================================================
 
 impl FromEnvByNetworkBuild for StellarProvider {
-    async fn from_env(network: Network) -> Result<Option<Self>, Box<dyn std::error::Error>> {
-        let rpc_url = std::env::var(from_env::rpc_env_name_from_network(network)).ok();
+    async fn from_env(
+        network: Network,
+        nonce_store: Arc<dyn NonceStore>,
+    ) -> Result<Option<Self>, Box<dyn std::error::Error>> {
+        let rpc_url = crate::env_registry::var(from_env::rpc_env_name_from_network(network));
 
         // Get secret key from environment
         let secret_key = match from_env::SignerType::from_env()?.get_stellar_secret_key(network) {
@@ -1456,7 +1446,7 @@ impl FromEnvByNetworkBuild for StellarProvider {
             }
         };
 
-        let provider = StellarProvider::try_new(secret_key, rpc_url, network)?;
+        let provider = StellarProvider::try_new(secret_key, rpc_url, network, nonce_store)?;
         Ok(Some(provider))
     }
 }
@@ -1510,6 +1500,7 @@ impl Facilitator for StellarProvider {
                     transaction: None,
                     network: self.network(),
                     proof_of_payment: None,
+                    confirmation: None,
                 };
                 tracing::info!(
                     success = response.success,
@@ -1527,6 +1518,7 @@ impl Facilitator for StellarProvider {
             transaction: Some(TransactionHash::Stellar(tx_hash)),
             network: self.network(),
             proof_of_payment: None, // ERC-8004 not supported on Stellar
+            confirmation: None,
         };
         tracing::info!(
             success = response.success,
@@ -1544,6 +1536,7 @@ impl Facilitator for StellarProvider {
             extra: Some(SupportedPaymentKindExtra {
                 fee_payer: Some(self.signer_address()),
                 tokens: None, // TODO: Add Stellar token support
+                accepted_tokens: None,
             }),
         }];
         Ok(SupportedPaymentKindsResponse { kinds })
@@ -1575,6 +1568,124 @@ mod tests {
         assert!(!invalid.is_valid());
     }
 
+    fn test_provider() -> StellarProvider {
+        let secret = StellarPrivateKey([7u8; 32]).to_string();
+        let nonce_store = Arc::new(crate::nonce_store::MemoryNonceStore::new());
+        StellarProvider::try_new(secret, None, Network::StellarTestnet, nonce_store).unwrap()
+    }
+
+    /// A USDC `transfer` authorization for `signer`, signed for the provider's network.
+    fn signed_auth_entry(
+        provider: &StellarProvider,
+        signer: &SigningKey,
+        nonce: i64,
+    ) -> SorobanAuthorizationEntry {
+        use stellar_xdr::curr::{
+            AccountId, InvokeContractArgs, PublicKey, ScAddress, ScBytes, ScSymbol, ScVal,
+        };
+
+        let mut credentials = SorobanAddressCredentials {
+            address: ScAddress::Account(AccountId(PublicKey::PublicKeyTypeEd25519(Uint256(
+                signer.verifying_key().to_bytes(),
+            )))),
+            nonce,
+            signature_expiration_ledger: 1_000,
+            signature: ScVal::Void,
+        };
+        let root_invocation = SorobanAuthorizedInvocation {
+            function: SorobanAuthorizedFunction::ContractFn(InvokeContractArgs {
+                contract_address: ScAddress::Contract(Hash([1u8; 32])),
+                function_name: ScSymbol("transfer".try_into().unwrap()),
+                args: VecM::default(),
+            }),
+            sub_invocations: VecM::default(),
+        };
+        let preimage = provider
+            .compute_auth_entry_preimage(&credentials, &root_invocation)
+            .unwrap();
+        let signature = signer.sign(&preimage).to_bytes().to_vec();
+        credentials.signature = ScVal::Bytes(ScBytes(signature.try_into().unwrap()));
+        SorobanAuthorizationEntry {
+            credentials: SorobanCredentials::Address(credentials),
+            root_invocation,
+        }
+    }
+
+    fn address_of(key: &SigningKey) -> String {
+        StellarPublicKey(key.verifying_key().to_bytes()).to_string()
+    }
+
+    #[test]
+    fn test_authorization_signature_validation() {
+        let provider = test_provider();
+        let payer = SigningKey::from_bytes(&[9u8; 32]);
+        let entry = signed_auth_entry(&provider, &payer, 42);
+
+        provider
+            .verify_authorization_signature(&entry, &address_of(&payer))
+            .unwrap();
+
+        // Signed by someone else
+        let other = SigningKey::from_bytes(&[10u8; 32]);
+        assert!(matches!(
+            provider.verify_authorization_signature(&entry, &address_of(&other)),
+            Err(StellarError::InvalidSignature { .. })
+        ));
+
+        // Nonce changed after signing
+        let mut tampered = entry.clone();
+        if let SorobanCredentials::Address(credentials) = &mut tampered.credentials {
+            credentials.nonce += 1;
+        }
+        assert!(provider
+            .verify_authorization_signature(&tampered, &address_of(&payer))
+            .is_err());
+
+        // Signed for another network
+        let mainnet = StellarProvider {
+            chain: StellarChain::try_from(Network::Stellar).unwrap(),
+            ..provider.clone()
+        };
+        assert!(mainnet
+            .verify_authorization_signature(&entry, &address_of(&payer))
+            .is_err());
+
+        // Truncated signature
+        let mut truncated = entry;
+        if let SorobanCredentials::Address(credentials) = &mut truncated.credentials {
+            credentials.signature =
+                stellar_xdr::curr::ScVal::Bytes(stellar_xdr::curr::ScBytes(vec![0u8; 32].try_into().unwrap()));
+        }
+        assert!(provider
+            .verify_authorization_signature(&truncated, &address_of(&payer))
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_reused_nonce_is_rejected() {
+        let provider = test_provider();
+        let from = address_of(&SigningKey::from_bytes(&[11u8; 32]));
+        // The nonce store is process-global; keep this test's nonces unique
+        let nonce = rand::random::<u64>() >> 1;
+
+        provider.check_nonce_unused(&from, nonce).await.unwrap();
+        provider
+            .check_and_mark_nonce_used(&from, nonce, 100, 200)
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            provider.check_nonce_unused(&from, nonce).await,
+            Err(StellarError::NonceReused { .. })
+        ));
+        assert!(matches!(
+            provider.check_and_mark_nonce_used(&from, nonce, 100, 200).await,
+            Err(StellarError::NonceReused { .. })
+        ));
+        // Other nonces of the same account are unaffected
+        provider.check_nonce_unused(&from, nonce + 1).await.unwrap();
+    }
+
     #[test]
     fn test_network_passphrase() {
         let mainnet = StellarChain::try_from(Network::Stellar).unwrap();
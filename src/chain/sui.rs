@@ -21,6 +21,7 @@
 //! - Testnet: `0xa1ec7fc00a6f40db9693ad1415d0c193ad3906494428cf252621037bd7117e29::usdc::USDC`
 
 use std::str::FromStr;
+use std::sync::Arc;
 
 use sui_sdk::SuiClientBuilder;
 use sui_sdk::rpc_types::SuiTransactionBlockResponseOptions;
@@ -37,6 +38,7 @@ use crate::from_env::{
     ENV_SUI_PRIVATE_KEY_TESTNET,
 };
 use crate::network::Network;
+use crate::nonce_store::NonceStore;
 use crate::types::{
     ExactPaymentPayload, ExactSuiPayload, MixedAddress, Scheme, SettleRequest, SettleResponse,
     SupportedPaymentKind, SupportedPaymentKindExtra, SupportedPaymentKindsResponse, VerifyRequest,
@@ -406,7 +408,10 @@ impl NetworkProviderOps for SuiProvider {
 }
 
 impl FromEnvByNetworkBuild for SuiProvider {
-    async fn from_env(network: Network) -> Result<Option<Self>, Box<dyn std::error::Error>> {
+    async fn from_env(
+        network: Network,
+        _nonce_store: Arc<dyn NonceStore>,
+    ) -> Result<Option<Self>, Box<dyn std::error::Error>> {
         // Determine RPC URL based on network
         let rpc_env = match network {
             Network::Sui => ENV_RPC_SUI,
@@ -416,9 +421,9 @@ impl FromEnvByNetworkBuild for SuiProvider {
             }
         };
 
-        let rpc_url = match std::env::var(rpc_env) {
-            Ok(url) => url,
-            Err(_) => {
+        let rpc_url = match crate::env_registry::var(rpc_env) {
+            Some(url) => url,
+            None => {
                 // Use public RPC endpoints as fallback
                 match network {
                     Network::Sui => "https://fullnode.mainnet.sui.io:443".to_string(),
@@ -437,11 +442,11 @@ impl FromEnvByNetworkBuild for SuiProvider {
         };
 
         // Try network-specific key first, then fall back to generic key
-        let private_key_str = match std::env::var(private_key_env)
-            .or_else(|_| std::env::var(ENV_SUI_PRIVATE_KEY))
+        let private_key_str = match crate::env_registry::var(private_key_env)
+            .or_else(|| crate::env_registry::var(ENV_SUI_PRIVATE_KEY))
         {
-            Ok(key) => key,
-            Err(_) => {
+            Some(key) => key,
+            None => {
                 warn!(
                     network = %network,
                     "No Sui private key found for network, skipping provider initialization"
@@ -539,6 +544,7 @@ impl Facilitator for SuiProvider {
                     transaction: Some(crate::types::TransactionHash::Sui(digest)),
                     network: self.network,
                     proof_of_payment: None, // ERC-8004 not supported on Sui yet
+                    confirmation: None,
                 })
             }
             Err(e) => {
@@ -558,6 +564,7 @@ impl Facilitator for SuiProvider {
                     transaction: None,
                     network: self.network,
                     proof_of_payment: None,
+                    confirmation: None,
                 })
             }
         }
@@ -578,6 +585,7 @@ impl Facilitator for SuiProvider {
             extra: Some(SupportedPaymentKindExtra {
                 fee_payer: Some(self.signer_address()),
                 tokens: None, // TODO: Add supported tokens list
+                accepted_tokens: None,
             }),
         }];
 
@@ -0,0 +1,566 @@
+//! Circuit breaker around a [`Facilitator`] for RPC fault tolerance.
+//!
+//! When an RPC node goes down, every verify and settle call would otherwise wait for its
+//! own timeout before failing. [`CircuitBreaker`] counts infrastructure errors and, after
+//! `failure_threshold` consecutive ones within `window`, rejects calls immediately with
+//! [`FacilitatorLocalError::CircuitOpen`] for `reset_timeout`. After that a single probe
+//! call is let through: its success closes the circuit, its failure opens it again.
+//!
+//! Only errors that point at the node ([`FacilitatorLocalError::ContractCall`] and
+//! [`FacilitatorLocalError::Other`]) count as failures. Invalid payments are answered by
+//! a healthy node, so they never trip the breaker.
+//!
+//! Each network has its own circuit, so an outage of one chain's RPC node does not
+//! reject payments on the others.
+//!
+//! # Configuration
+//!
+//! - `CIRCUIT_BREAKER_FAILURE_THRESHOLD`: consecutive failures that open a circuit (0 disables)
+//! - `CIRCUIT_BREAKER_WINDOW_SECS`: failures further apart are not consecutive
+//! - `CIRCUIT_BREAKER_RESET_SECS`: how long a circuit stays open before a probe
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+use crate::chain::FacilitatorLocalError;
+use crate::facilitator::Facilitator;
+use crate::network::Network;
+use crate::provider_cache::HasProviderMap;
+use crate::types::{
+    SettleRequest, SettleResponse, SupportedPaymentKindsResponse, VerifyRequest, VerifyResponse,
+};
+
+/// Thresholds of a [`CircuitBreaker`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CircuitBreakerConfig {
+    /// Consecutive failures that open the circuit; 0 never opens it
+    pub failure_threshold: u32,
+    /// Failures further apart than this are not consecutive
+    pub window: Duration,
+    /// How long the circuit stays open before a probe is allowed
+    pub reset_timeout: Duration,
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        Self {
+            failure_threshold: 5,
+            window: Duration::from_secs(30),
+            reset_timeout: Duration::from_secs(30),
+        }
+    }
+}
+
+impl CircuitBreakerConfig {
+    /// Read the thresholds from the `CIRCUIT_BREAKER_*` variables.
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            failure_threshold: crate::env_registry::parse("CIRCUIT_BREAKER_FAILURE_THRESHOLD")
+                .unwrap_or(defaults.failure_threshold),
+            window: crate::env_registry::parse("CIRCUIT_BREAKER_WINDOW_SECS")
+                .map(Duration::from_secs)
+                .unwrap_or(defaults.window),
+            reset_timeout: crate::env_registry::parse("CIRCUIT_BREAKER_RESET_SECS")
+                .map(Duration::from_secs)
+                .unwrap_or(defaults.reset_timeout),
+        }
+    }
+}
+
+/// State of a [`CircuitBreaker`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    /// Calls go through
+    Closed,
+    /// Calls are rejected until the given instant
+    Open(Instant),
+    /// One probe call decides whether to close or reopen
+    HalfOpen,
+}
+
+/// Whether a call was admitted as a regular call or as the half-open probe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Permit {
+    Regular,
+    Probe,
+}
+
+/// The state machine, with time passed in so it can be tested without sleeping.
+#[derive(Debug)]
+struct Breaker {
+    config: CircuitBreakerConfig,
+    state: CircuitState,
+    failures: u32,
+    first_failure: Option<Instant>,
+    /// When the running probe was admitted; a probe that never reports back (its future
+    /// was dropped) is replaced after `reset_timeout`
+    probe_started: Option<Instant>,
+}
+
+impl Breaker {
+    fn new(config: CircuitBreakerConfig) -> Self {
+        Self {
+            config,
+            state: CircuitState::Closed,
+            failures: 0,
+            first_failure: None,
+            probe_started: None,
+        }
+    }
+
+    /// Admit a call, or return how long until the circuit may let one through.
+    fn acquire(&mut self, now: Instant) -> Result<Permit, Duration> {
+        match self.state {
+            CircuitState::Closed => Ok(Permit::Regular),
+            CircuitState::Open(until) if now < until => Err(until - now),
+            CircuitState::Open(_) => {
+                self.state = CircuitState::HalfOpen;
+                self.probe_started = Some(now);
+                Ok(Permit::Probe)
+            }
+            CircuitState::HalfOpen => match self.probe_started {
+                Some(started)
+                    if now.saturating_duration_since(started) < self.config.reset_timeout =>
+                {
+                    Err(self.config.reset_timeout - now.saturating_duration_since(started))
+                }
+                _ => {
+                    self.probe_started = Some(now);
+                    Ok(Permit::Probe)
+                }
+            },
+        }
+    }
+
+    /// Record the outcome of an admitted call.
+    fn record(&mut self, permit: Permit, failed: bool, now: Instant) {
+        match (permit, self.state) {
+            (Permit::Probe, CircuitState::HalfOpen) => {
+                self.probe_started = None;
+                if failed {
+                    self.open(now);
+                } else {
+                    self.close();
+                }
+            }
+            (Permit::Regular, CircuitState::Closed) if failed => {
+                let in_window = self.first_failure.is_some_and(|first| {
+                    now.saturating_duration_since(first) <= self.config.window
+                });
+                if in_window {
+                    self.failures += 1;
+                } else {
+                    self.first_failure = Some(now);
+                    self.failures = 1;
+                }
+                if self.config.failure_threshold > 0
+                    && self.failures >= self.config.failure_threshold
+                {
+                    self.open(now);
+                }
+            }
+            (Permit::Regular, CircuitState::Closed) => {
+                self.failures = 0;
+                self.first_failure = None;
+            }
+            // Calls admitted before the circuit opened finish late; the probe decides
+            _ => {}
+        }
+    }
+
+    fn open(&mut self, now: Instant) {
+        self.state = CircuitState::Open(now + self.config.reset_timeout);
+        self.failures = 0;
+        self.first_failure = None;
+    }
+
+    fn close(&mut self) {
+        self.state = CircuitState::Closed;
+        self.failures = 0;
+        self.first_failure = None;
+    }
+}
+
+/// A [`Facilitator`] that stops calling `F` for a network while its RPC node appears to be down.
+pub struct CircuitBreaker<F> {
+    inner: F,
+    config: CircuitBreakerConfig,
+    breakers: Mutex<HashMap<Network, Breaker>>,
+}
+
+impl<F> CircuitBreaker<F> {
+    pub fn new(inner: F, config: CircuitBreakerConfig) -> Self {
+        Self {
+            inner,
+            config,
+            breakers: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The wrapped facilitator.
+    pub fn inner(&self) -> &F {
+        &self.inner
+    }
+
+    /// State of the circuit of `network`.
+    pub fn state(&self, network: Network) -> CircuitState {
+        self.lock()
+            .get(&network)
+            .map_or(CircuitState::Closed, |breaker| breaker.state)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<Network, Breaker>> {
+        self.breakers.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn acquire(&self, network: Network) -> Result<Permit, FacilitatorLocalError> {
+        self.lock()
+            .entry(network)
+            .or_insert_with(|| Breaker::new(self.config))
+            .acquire(Instant::now())
+            .map_err(FacilitatorLocalError::CircuitOpen)
+    }
+
+    fn record<T>(&self, network: Network, permit: Permit, result: &Result<T, FacilitatorLocalError>) {
+        let failed = matches!(
+            result,
+            Err(FacilitatorLocalError::ContractCall(_) | FacilitatorLocalError::Other(_))
+        );
+        let mut breakers = self.lock();
+        let breaker = breakers
+            .entry(network)
+            .or_insert_with(|| Breaker::new(self.config));
+        let before = breaker.state;
+        breaker.record(permit, failed, Instant::now());
+        match (before, breaker.state) {
+            (CircuitState::Open(_), _) => {}
+            (_, CircuitState::Open(_)) => warn!(
+                network = %network,
+                reset_timeout = ?breaker.config.reset_timeout,
+                "Circuit breaker opened after repeated RPC failures"
+            ),
+            (CircuitState::HalfOpen, CircuitState::Closed) => {
+                info!(network = %network, "Circuit breaker closed, probe succeeded")
+            }
+            _ => {}
+        }
+    }
+}
+
+impl<F: HasProviderMap> HasProviderMap for CircuitBreaker<F> {
+    type Map = F::Map;
+
+    fn provider_map(&self) -> &Self::Map {
+        self.inner.provider_map()
+    }
+}
+
+impl<F> Facilitator for CircuitBreaker<F>
+where
+    F: Facilitator<Error = FacilitatorLocalError> + Sync,
+{
+    type Error = FacilitatorLocalError;
+
+    async fn verify(&self, request: &VerifyRequest) -> Result<VerifyResponse, Self::Error> {
+        let network = request.network();
+        let permit = self.acquire(network)?;
+        let result = self.inner.verify(request).await;
+        self.record(network, permit, &result);
+        result
+    }
+
+    async fn settle(&self, request: &SettleRequest) -> Result<SettleResponse, Self::Error> {
+        let network = request.network();
+        let permit = self.acquire(network)?;
+        let result = self.inner.settle(request).await;
+        self.record(network, permit, &result);
+        result
+    }
+
+    async fn supported(&self) -> Result<SupportedPaymentKindsResponse, Self::Error> {
+        self.inner.supported().await
+    }
+
+    async fn blacklist_info(&self) -> Result<serde_json::Value, Self::Error> {
+        self.inner.blacklist_info().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    const SECOND: Duration = Duration::from_secs(1);
+
+    fn config() -> CircuitBreakerConfig {
+        CircuitBreakerConfig {
+            failure_threshold: 3,
+            window: Duration::from_secs(10),
+            reset_timeout: Duration::from_secs(30),
+        }
+    }
+
+    #[derive(Debug, Clone)]
+    enum Step {
+        /// Advance the clock by this many seconds
+        Wait(u64),
+        /// Start a call, finishing it at once with this outcome if admitted
+        Call { failed: bool },
+    }
+
+    fn step() -> impl Strategy<Value = Step> {
+        prop_oneof![
+            (0u64..45).prop_map(Step::Wait),
+            any::<bool>().prop_map(|failed| Step::Call { failed }),
+        ]
+    }
+
+    proptest! {
+        #[test]
+        fn prop_state_transitions_are_consistent(steps in prop::collection::vec(step(), 0..200)) {
+            let config = config();
+            let mut breaker = Breaker::new(config);
+            let mut now = Instant::now();
+            // Consecutive failures since the circuit last closed or saw a success
+            let mut streak = 0u32;
+
+            for step in steps {
+                match step {
+                    Step::Wait(secs) => now += Duration::from_secs(secs),
+                    Step::Call { failed } => {
+                        let before = breaker.state;
+                        match breaker.acquire(now) {
+                            Err(retry_after) => {
+                                // Rejected only while open, and never past the reset deadline
+                                let CircuitState::Open(until) = before else {
+                                    return Err(TestCaseError::fail(format!("rejected in {before:?}")));
+                                };
+                                prop_assert!(now < until);
+                                prop_assert_eq!(retry_after, until - now);
+                                prop_assert_eq!(breaker.state, before);
+                            }
+                            Ok(Permit::Regular) => {
+                                prop_assert_eq!(before, CircuitState::Closed);
+                                breaker.record(Permit::Regular, failed, now);
+                                if failed {
+                                    streak += 1;
+                                } else {
+                                    streak = 0;
+                                    prop_assert_eq!(breaker.state, CircuitState::Closed);
+                                }
+                                if let CircuitState::Open(until) = breaker.state {
+                                    prop_assert!(failed);
+                                    prop_assert!(streak >= config.failure_threshold);
+                                    prop_assert_eq!(until, now + config.reset_timeout);
+                                    streak = 0;
+                                } else {
+                                    prop_assert!(breaker.failures < config.failure_threshold);
+                                    prop_assert!(breaker.failures <= streak);
+                                }
+                            }
+                            Ok(Permit::Probe) => {
+                                let CircuitState::Open(until) = before else {
+                                    return Err(TestCaseError::fail(format!("probe from {before:?}")));
+                                };
+                                prop_assert!(now >= until);
+                                prop_assert_eq!(breaker.state, CircuitState::HalfOpen);
+                                // A second caller is held back while the probe runs
+                                prop_assert_eq!(breaker.acquire(now), Err(config.reset_timeout));
+                                breaker.record(Permit::Probe, failed, now);
+                                let expected = if failed {
+                                    CircuitState::Open(now + config.reset_timeout)
+                                } else {
+                                    CircuitState::Closed
+                                };
+                                prop_assert_eq!(breaker.state, expected);
+                                streak = 0;
+                            }
+                        }
+                    }
+                }
+                prop_assert!(breaker.state != CircuitState::HalfOpen, "probes finish at once");
+            }
+        }
+
+        #[test]
+        fn prop_threshold_failures_in_window_open(gaps in prop::collection::vec(0u64..=3, 2)) {
+            // Failures spaced at most 3s apart, 6s in total: inside the 10s window
+            let mut breaker = Breaker::new(config());
+            let mut now = Instant::now();
+            breaker.record(Permit::Regular, true, now);
+            for gap in gaps {
+                prop_assert_eq!(breaker.state, CircuitState::Closed);
+                now += Duration::from_secs(gap);
+                breaker.record(Permit::Regular, true, now);
+            }
+            prop_assert_eq!(breaker.state, CircuitState::Open(now + config().reset_timeout));
+        }
+    }
+
+    #[test]
+    fn test_failures_outside_window_do_not_accumulate() {
+        let mut breaker = Breaker::new(config());
+        let mut now = Instant::now();
+        for _ in 0..10 {
+            breaker.record(Permit::Regular, true, now);
+            now += 6 * SECOND;
+            breaker.record(Permit::Regular, true, now);
+            now += 11 * SECOND;
+        }
+        assert_eq!(breaker.state, CircuitState::Closed);
+    }
+
+    #[test]
+    fn test_late_results_do_not_affect_open_or_half_open_circuit() {
+        let mut breaker = Breaker::new(config());
+        let now = Instant::now();
+        for _ in 0..3 {
+            breaker.record(Permit::Regular, true, now);
+        }
+        let open = breaker.state;
+        breaker.record(Permit::Regular, false, now);
+        assert_eq!(breaker.state, open);
+
+        let later = now + 30 * SECOND;
+        assert_eq!(breaker.acquire(later), Ok(Permit::Probe));
+        breaker.record(Permit::Regular, false, later);
+        assert_eq!(breaker.state, CircuitState::HalfOpen);
+        breaker.record(Permit::Probe, false, later);
+        assert_eq!(breaker.state, CircuitState::Closed);
+    }
+
+    #[test]
+    fn test_abandoned_probe_is_replaced() {
+        let mut breaker = Breaker::new(config());
+        let now = Instant::now();
+        for _ in 0..3 {
+            breaker.record(Permit::Regular, true, now);
+        }
+        let reopened = now + 30 * SECOND;
+        assert_eq!(breaker.acquire(reopened), Ok(Permit::Probe));
+        // The probe never reports back
+        assert_eq!(breaker.acquire(reopened + 10 * SECOND), Err(20 * SECOND));
+        assert_eq!(breaker.acquire(reopened + 30 * SECOND), Ok(Permit::Probe));
+        breaker.record(Permit::Probe, false, reopened + 31 * SECOND);
+        assert_eq!(breaker.state, CircuitState::Closed);
+    }
+
+    /// Fails with a contract call error while `down` is set.
+    struct FlakyFacilitator {
+        down: AtomicBool,
+    }
+
+    impl Facilitator for FlakyFacilitator {
+        type Error = FacilitatorLocalError;
+
+        async fn verify(&self, _request: &VerifyRequest) -> Result<VerifyResponse, Self::Error> {
+            if self.down.load(Ordering::SeqCst) {
+                Err(FacilitatorLocalError::ContractCall(
+                    "connection refused".to_string(),
+                ))
+            } else {
+                Err(FacilitatorLocalError::DecodingError(
+                    "bad payload".to_string(),
+                ))
+            }
+        }
+
+        async fn settle(&self, request: &SettleRequest) -> Result<SettleResponse, Self::Error> {
+            self.verify(request).await.map(|_| unreachable!())
+        }
+
+        async fn supported(&self) -> Result<SupportedPaymentKindsResponse, Self::Error> {
+            Ok(SupportedPaymentKindsResponse { kinds: Vec::new() })
+        }
+    }
+
+    fn request() -> VerifyRequest {
+        serde_json::from_value(serde_json::json!({
+            "x402Version": 1,
+            "paymentPayload": {
+                "x402Version": 1,
+                "scheme": "exact",
+                "network": "base-sepolia",
+                "payload": {
+                    "signature": format!("0x{}", "11".repeat(65)),
+                    "authorization": {
+                        "from": "0x2222222222222222222222222222222222222222",
+                        "to": "0x1111111111111111111111111111111111111111",
+                        "value": "1000",
+                        "validAfter": "0",
+                        "validBefore": "9999999999",
+                        "nonce": format!("0x{}", "00".repeat(32))
+                    }
+                }
+            },
+            "paymentRequirements": {
+                "scheme": "exact",
+                "network": "base-sepolia",
+                "maxAmountRequired": "1000",
+                "resource": "https://api.example.com/data",
+                "description": "",
+                "mimeType": "application/json",
+                "payTo": "0x1111111111111111111111111111111111111111",
+                "maxTimeoutSeconds": 60,
+                "asset": "0x036CbD53842c5426634e7929541eC2318f3dCF7e"
+            }
+        }))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_open_circuit_fails_fast_and_probe_closes_it() {
+        let facilitator = CircuitBreaker::new(
+            FlakyFacilitator {
+                down: AtomicBool::new(false),
+            },
+            CircuitBreakerConfig {
+                reset_timeout: Duration::from_millis(50),
+                ..config()
+            },
+        );
+        let request = request();
+
+        // Invalid payments never trip the breaker
+        for _ in 0..5 {
+            let result = facilitator.verify(&request).await;
+            assert!(matches!(
+                result,
+                Err(FacilitatorLocalError::DecodingError(_))
+            ));
+        }
+        let network = request.network();
+        assert_eq!(facilitator.state(network), CircuitState::Closed);
+
+        facilitator.inner().down.store(true, Ordering::SeqCst);
+        for _ in 0..3 {
+            let result = facilitator.settle(&request).await;
+            assert!(matches!(
+                result,
+                Err(FacilitatorLocalError::ContractCall(_))
+            ));
+        }
+        assert!(matches!(facilitator.state(network), CircuitState::Open(_)));
+        // Other networks keep their own, closed circuit
+        assert_eq!(facilitator.state(Network::Base), CircuitState::Closed);
+        let result = facilitator.verify(&request).await;
+        assert!(
+            matches!(result, Err(FacilitatorLocalError::CircuitOpen(retry)) if retry > Duration::ZERO)
+        );
+        assert!(facilitator.supported().await.is_ok());
+
+        facilitator.inner().down.store(false, Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        let result = facilitator.verify(&request).await;
+        assert!(matches!(
+            result,
+            Err(FacilitatorLocalError::DecodingError(_))
+        ));
+        assert_eq!(facilitator.state(network), CircuitState::Closed);
+    }
+}
@@ -5,16 +5,13 @@
 
 use alloy::primitives::U256;
 use chrono::{DateTime, NaiveDateTime, Utc};
-use std::env;
 
 /// Check if enhanced debug logging is enabled.
 ///
 /// Returns `true` if the `FACILITATOR_ENHANCED_DEBUG` environment variable is set to "true" (case-insensitive).
 /// Returns `false` otherwise.
 pub fn is_enhanced_debug_enabled() -> bool {
-    env::var("FACILITATOR_ENHANCED_DEBUG")
-        .unwrap_or_else(|_| "true".to_string()) // Default to true for now
-        .eq_ignore_ascii_case("true")
+    crate::env_registry::flag("FACILITATOR_ENHANCED_DEBUG") // Defaults to true for now
 }
 
 /// Format USDC amount from micro-units (6 decimals) to human-readable dollar amount.
@@ -0,0 +1,193 @@
+//! Memoized payload digests shared between verify and settle.
+//!
+//! Settlement re-runs the checks performed by verification, including hashing the
+//! signed payload. The digest is a pure function of the payload and its signing
+//! domain, so it is computed once and reused:
+//!
+//! - Within one request ([`request_scope`]), e.g. a handler that verifies and then
+//!   settles the same payload, digests are kept in a task-local map without locking.
+//! - Across requests (the usual `/verify` followed by `/settle`), a bounded
+//!   [`DigestCache`] keyed by the full hash input serves the second computation.
+//!   It is a sharded [`DashMap`], so concurrent requests never wait on one global lock.
+//!
+//! Keys must contain every input of the digest, never a derived fingerprint, so a
+//! modified payload can never be served a digest computed for another one.
+
+use dashmap::DashMap;
+use std::any::{Any, TypeId};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::future::Future;
+use std::hash::Hash;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// A 32-byte digest.
+pub type Digest = [u8; 32];
+
+tokio::task_local! {
+    static REQUEST_DIGESTS: RefCell<HashMap<TypeId, Box<dyn Any + Send>>>;
+}
+
+/// Run `fut` with a request-scoped digest memo.
+///
+/// Digests computed inside the scope are reused by later lookups in the same task.
+pub async fn request_scope<F: Future>(fut: F) -> F::Output {
+    REQUEST_DIGESTS.scope(RefCell::new(HashMap::new()), fut).await
+}
+
+fn scoped_get<K: Hash + Eq + 'static>(key: &K) -> Option<Digest> {
+    REQUEST_DIGESTS
+        .try_with(|memo| {
+            memo.borrow()
+                .get(&TypeId::of::<K>())
+                .and_then(|map| map.downcast_ref::<HashMap<K, Digest>>())
+                .and_then(|map| map.get(key).copied())
+        })
+        .ok()
+        .flatten()
+}
+
+fn scoped_insert<K: Hash + Eq + Send + 'static>(key: K, digest: Digest) {
+    let _ = REQUEST_DIGESTS.try_with(|memo| {
+        let mut memo = memo.borrow_mut();
+        let map = memo
+            .entry(TypeId::of::<K>())
+            .or_insert_with(|| Box::new(HashMap::<K, Digest>::new()));
+        if let Some(map) = map.downcast_mut::<HashMap<K, Digest>>() {
+            map.insert(key, digest);
+        }
+    });
+}
+
+/// Bounded, time-limited cache of digests keyed by their full hash input.
+///
+/// The bound is approximate under concurrent inserts; it only keeps memory in check.
+#[derive(Debug)]
+pub struct DigestCache<K: Hash + Eq> {
+    entries: DashMap<K, (Digest, Instant)>,
+    capacity: usize,
+    ttl: Duration,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl<K: Hash + Eq + Clone + Send + 'static> DigestCache<K> {
+    /// A `capacity` of zero disables the cross-request layer.
+    pub fn new(capacity: usize, ttl: Duration) -> Self {
+        Self {
+            entries: DashMap::new(),
+            capacity,
+            ttl,
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// Return the digest for `key`, computing and caching it on a miss.
+    pub fn get_or_compute(&self, key: &K, compute: impl FnOnce() -> Digest) -> Digest {
+        if let Some(digest) = scoped_get(key) {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return digest;
+        }
+        let now = Instant::now();
+        // The shard guard is released at the end of the statement, before any insert
+        let cached = self.entries.get(key).and_then(|entry| {
+            let (digest, expires_at) = *entry;
+            (expires_at > now).then_some(digest)
+        });
+        let digest = match cached {
+            Some(digest) => {
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                digest
+            }
+            None => {
+                self.misses.fetch_add(1, Ordering::Relaxed);
+                let digest = compute();
+                self.insert(key.clone(), digest, now);
+                digest
+            }
+        };
+        scoped_insert(key.clone(), digest);
+        digest
+    }
+
+    fn insert(&self, key: K, digest: Digest, now: Instant) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.entries.len() >= self.capacity {
+            self.entries.retain(|_, (_, expires_at)| *expires_at > now);
+            // Still full of live entries: start over rather than track recency
+            if self.entries.len() >= self.capacity {
+                self.entries.clear();
+            }
+        }
+        self.entries.insert(key, (digest, now + self.ttl));
+    }
+
+    /// `(hits, misses)` since creation.
+    pub fn stats(&self) -> (u64, u64) {
+        (self.hits.load(Ordering::Relaxed), self.misses.load(Ordering::Relaxed))
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+
+    fn digest(byte: u8) -> Digest {
+        [byte; 32]
+    }
+
+    #[tokio::test]
+    async fn test_request_scope_reuses_digest_without_shared_cache() {
+        // A zero-capacity cache never retains entries, so hits come from the scope
+        let cache = DigestCache::<u32>::new(0, Duration::from_secs(60));
+        let computed = AtomicUsize::new(0);
+        let compute = || {
+            computed.fetch_add(1, Ordering::SeqCst);
+            digest(1)
+        };
+
+        request_scope(async {
+            assert_eq!(cache.get_or_compute(&7, compute), digest(1));
+            assert_eq!(cache.get_or_compute(&7, compute), digest(1));
+        })
+        .await;
+        assert_eq!(computed.load(Ordering::SeqCst), 1);
+
+        // Outside the scope there is nothing to reuse
+        cache.get_or_compute(&7, compute);
+        assert_eq!(computed.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_cache_is_keyed_by_full_input_and_bounded() {
+        let cache = DigestCache::<(u8, u8)>::new(2, Duration::from_secs(60));
+        assert_eq!(cache.get_or_compute(&(1, 1), || digest(1)), digest(1));
+        assert_eq!(cache.get_or_compute(&(1, 1), || unreachable!()), digest(1));
+        // Differing in any part of the input is a different digest
+        assert_eq!(cache.get_or_compute(&(1, 2), || digest(2)), digest(2));
+        assert_eq!(cache.stats(), (1, 2));
+
+        cache.get_or_compute(&(3, 3), || digest(3));
+        assert!(cache.len() <= 2);
+    }
+
+    #[test]
+    fn test_expired_entries_are_recomputed() {
+        let cache = DigestCache::<u8>::new(10, Duration::ZERO);
+        cache.get_or_compute(&1, || digest(1));
+        assert_eq!(cache.get_or_compute(&1, || digest(9)), digest(9));
+    }
+}
@@ -30,6 +30,47 @@
 //! - Writes: Update cache immediately, persist to store asynchronously
 //! - Startup: Load all resources from store into cache
 //!
+//! # Health
+//!
+//! The [`HealthMonitor`](crate::discovery_health::HealthMonitor) reports each check
+//! through [`DiscoveryRegistry::record_health_check`]. A resource failing
+//! `failure_threshold` consecutive checks is marked unhealthy and left out of `list` and
+//! `search` until a check succeeds again; `list` still returns it when the filters set
+//! `include_unhealthy`. Every check is annotated on the resource's
+//! [`DiscoveryMetadata::liveness`]. Health survives updates of the resource.
+//!
+//! # Snapshots
+//!
+//! With a [`RegistryStore`] attached, the whole registry state (resources, tombstones,
+//! missed-cycle counts and health) is snapshotted after every bulk import and every
+//! successful aggregation cycle, and restored by
+//! [`DiscoveryRegistry::with_snapshot_store`], so aggregated resources survive a
+//! restart without waiting for the next cycle.
+//!
+//! [`DiscoveryRegistry::save_checkpoint`] and [`DiscoveryRegistry::load_checkpoint`]
+//! write and read the same state as a gzip-compressed snapshot, the format selected
+//! by `DISCOVERY_CHECKPOINT_PATH`.
+//!
+//! # Relevance
+//!
+//! Every resource is also kept in a [`TfIdfIndex`], updated on registration, update,
+//! bulk import and removal. [`DiscoveryRegistry::search_ranked`] orders matches by
+//! TF-IDF score instead of recency.
+//!
+//! # Tombstones
+//!
+//! Unregistering a resource leaves an in-memory tombstone holding its `last_updated`.
+//! Aggregated imports of that URL at or before the tombstone are ignored, so an
+//! aggregation cycle that fetched before the removal cannot bring it back; a newer
+//! upstream version lifts the tombstone. Explicit `register`/`update` always clear it.
+//!
+//! # Ownership
+//!
+//! Resources registered with a signature of their payTo address (see [`ownership`])
+//! record that address as their `owner`. [`DiscoveryRegistry::register_signed`] and
+//! [`DiscoveryRegistry::unregister_signed`] only replace or remove them for the same
+//! owner, and bulk imports never overwrite them.
+//!
 //! # Example
 //!
 //! ```rust,ignore
@@ -51,13 +92,232 @@
 //! let response = registry.list(10, 0, None).await;
 //! ```
 
-use std::collections::HashMap;
+pub mod index;
+pub mod ownership;
+pub mod taxonomy;
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use tracing::{debug, error, info, warn};
 
-use crate::discovery_store::{DiscoveryStore, NoOpStore, StoreError};
-use crate::types_v2::{DiscoveryFilters, DiscoveryResource, DiscoveryResponse, Pagination};
+pub use index::TfIdfIndex;
+pub use taxonomy::Taxonomy;
+
+use crate::discovery_store::{
+    DiscoveryStore, FileSnapshotStore, NoOpStore, RegistrySnapshot, RegistryStore, StoreError,
+    SNAPSHOT_VERSION,
+};
+use crate::tenant::{TenantError, TenantId, TenantScope};
+use crate::types::MixedAddress;
+use crate::types_v2::{
+    DiscoveryFilters, DiscoveryMetadata, DiscoveryResource, DiscoveryResponse, DiscoverySource,
+    ListQuery, ListSort, Pagination, PaymentRequirementsV2, QueryResult, ResourceLiveness,
+    ResourceReputation, SearchFilters,
+};
+
+/// Health check history of a single resource.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceHealth {
+    pub is_healthy: bool,
+    /// Failed checks since the last successful one
+    pub consecutive_failures: u32,
+    /// Unix timestamp of the latest check, `None` until the resource is first checked
+    pub last_checked: Option<u64>,
+    /// Why the latest check failed
+    pub last_error: Option<String>,
+    /// HTTP status of the latest check, `None` if no response was received
+    pub last_status: Option<u16>,
+    /// Milliseconds the latest check took
+    pub latency_ms: Option<u64>,
+}
+
+impl Default for ResourceHealth {
+    fn default() -> Self {
+        Self {
+            is_healthy: true,
+            consecutive_failures: 0,
+            last_checked: None,
+            last_error: None,
+            last_status: None,
+            latency_ms: None,
+        }
+    }
+}
+
+/// Outcome of a single health check.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HealthCheck {
+    /// HTTP status of the response, `None` if none was received
+    pub status: Option<u16>,
+    /// Milliseconds until the response or the failure
+    pub latency_ms: u64,
+    /// Why the check failed, `None` if it passed
+    pub error: Option<String>,
+}
+
+impl HealthCheck {
+    /// A check answered with `status` that counts as healthy.
+    pub fn passed(status: u16, latency_ms: u64) -> Self {
+        Self {
+            status: Some(status),
+            latency_ms,
+            error: None,
+        }
+    }
+
+    /// A failed check, with the response status if one was received.
+    pub fn failed(status: Option<u16>, latency_ms: u64, error: impl Into<String>) -> Self {
+        Self {
+            status,
+            latency_ms,
+            error: Some(error.into()),
+        }
+    }
+}
+
+/// How [`DiscoveryRegistry::bulk_import`] resolves an imported resource whose URL is
+/// already registered.
+///
+/// Strategies combine, as `[MergeAccepts, KeepLocalMetadata]` for aggregation. The
+/// imported version replaces the registered one as with `PreferNewer`, unless
+/// `ReplaceAll` is given; the other strategies shape what it is replaced with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictStrategy {
+    /// Always replace the registered resource, whatever its `last_updated`
+    ReplaceAll,
+    /// Replace the registered resource when the import is newer, or the same version
+    /// with more payment options or listing facilitators
+    PreferNewer,
+    /// Keep the registered payment options missing from the import, deduplicated by
+    /// network, asset and recipient
+    MergeAccepts,
+    /// Keep the registered metadata when the imported one has no category, provider,
+    /// tags or extra fields
+    KeepLocalMetadata,
+}
+
+/// `resource` resolved against the registered `existing` with `strategies`, or `None`
+/// when `existing` stays as it is. Signed registrations are left to their owner,
+/// whatever the strategies.
+fn resolve_conflict(
+    mut resource: DiscoveryResource,
+    existing: &DiscoveryResource,
+    strategies: &[ConflictStrategy],
+) -> Option<DiscoveryResource> {
+    if existing.owner.is_some() {
+        return None;
+    }
+    if strategies.contains(&ConflictStrategy::MergeAccepts) {
+        merge_accepts(&mut resource, existing);
+    }
+    if strategies.contains(&ConflictStrategy::KeepLocalMetadata) {
+        keep_local_metadata(&mut resource, existing);
+    }
+    let replace = strategies.contains(&ConflictStrategy::ReplaceAll)
+        || resource.last_updated > existing.last_updated
+        || extends_listing(&resource, existing);
+    if !replace {
+        return None;
+    }
+    keep_annotations(&mut resource, existing);
+    Some(resource)
+}
+
+/// Union the payment options of `resource` and `existing`, the imported ones first, with
+/// one option per network, asset and recipient.
+fn merge_accepts(resource: &mut DiscoveryResource, existing: &DiscoveryResource) {
+    let mut merged: Vec<PaymentRequirementsV2> = Vec::with_capacity(resource.accepts.len());
+    for accept in resource
+        .accepts
+        .drain(..)
+        .chain(existing.accepts.iter().cloned())
+    {
+        let listed = merged.iter().any(|m| {
+            m.network == accept.network
+                && same_address(&m.asset, &accept.asset)
+                && same_address(&m.pay_to, &accept.pay_to)
+        });
+        if !listed {
+            merged.push(accept);
+        }
+    }
+    resource.accepts = merged;
+}
+
+/// Keep the metadata of `existing` when `resource` brings none, adding the facilitators
+/// listing `resource` to its sources.
+fn keep_local_metadata(resource: &mut DiscoveryResource, existing: &DiscoveryResource) {
+    let Some(local) = &existing.metadata else {
+        return;
+    };
+    let describes = |m: &DiscoveryMetadata| {
+        m.category.is_some() || m.provider.is_some() || !m.tags.is_empty() || !m.extra.is_empty()
+    };
+    if resource.metadata.as_ref().is_some_and(describes) {
+        return;
+    }
+    let mut metadata = local.clone();
+    for source in resource
+        .metadata
+        .take()
+        .map(|m| m.sources)
+        .unwrap_or_default()
+    {
+        if !metadata.sources.contains(&source) {
+            metadata.sources.push(source);
+        }
+    }
+    resource.metadata = Some(metadata);
+}
+
+/// Keep the health state and reputation of `existing` on `resource`, which replaces it.
+fn keep_annotations(resource: &mut DiscoveryResource, existing: &DiscoveryResource) {
+    resource.is_healthy = existing.is_healthy;
+    let liveness = existing.metadata.as_ref().and_then(|m| m.liveness.clone());
+    if liveness.is_some() {
+        resource
+            .metadata
+            .get_or_insert_with(DiscoveryMetadata::default)
+            .liveness = liveness;
+    }
+    let reputation = existing
+        .metadata
+        .as_ref()
+        .and_then(|m| m.reputation.clone());
+    if reputation.is_some() {
+        resource
+            .metadata
+            .get_or_insert_with(DiscoveryMetadata::default)
+            .reputation = reputation;
+    }
+}
+
+/// ERC-8004 reputation score of `resource`, if it has been read.
+fn reputation_score(resource: &DiscoveryResource) -> Option<u8> {
+    resource
+        .metadata
+        .as_ref()
+        .and_then(|m| m.reputation.as_ref())
+        .map(|r| r.score)
+}
+
+/// Whether `resource` is the version of `existing` with more payment options or listing
+/// facilitators, as when another facilitator's listing is merged into it.
+fn extends_listing(resource: &DiscoveryResource, existing: &DiscoveryResource) -> bool {
+    let sources = |r: &DiscoveryResource| r.metadata.as_ref().map_or(0, |m| m.sources.len());
+    resource.last_updated == existing.last_updated
+        && existing.accepts.iter().all(|accept| resource.accepts.contains(accept))
+        && (resource.accepts.len() > existing.accepts.len() || sources(resource) > sources(existing))
+}
+
+/// Whether two addresses are the same, also when parsed as different variants
+/// (an Algorand asset id read back as an off-chain address, say).
+fn same_address(a: &MixedAddress, b: &MixedAddress) -> bool {
+    a == b || a.to_string() == b.to_string()
+}
 
 // ============================================================================
 // Error Types
@@ -86,9 +346,17 @@ pub enum DiscoveryError {
     #[error("At least one payment method must be specified in 'accepts'")]
     NoPaymentMethods,
 
+    /// Resource is registered without an owner or by a different one
+    #[error("Resource is owned by another registrant: {0}")]
+    OwnedByOther(String),
+
     /// Storage error
     #[error("Storage error: {0}")]
     StorageError(#[from] StoreError),
+
+    /// Tenant scope does not allow the operation
+    #[error(transparent)]
+    Tenant(#[from] TenantError),
 }
 
 // ============================================================================
@@ -106,15 +374,33 @@ pub enum DiscoveryError {
 pub struct DiscoveryRegistry {
     /// In-memory cache: Map of URL -> DiscoveryResource
     resources: Arc<RwLock<HashMap<String, DiscoveryResource>>>,
+    /// Removed resources: Map of URL -> `last_updated` at removal
+    tombstones: Arc<RwLock<HashMap<String, u64>>>,
+    /// Aggregated resources missing from their source: Map of URL -> consecutive cycles missed
+    missed: Arc<RwLock<HashMap<String, u32>>>,
+    /// Health check history: Map of URL -> ResourceHealth
+    health: Arc<RwLock<HashMap<String, ResourceHealth>>>,
+    /// Relevance index of `resources`, updated with it (lock `resources` first)
+    index: Arc<RwLock<TfIdfIndex>>,
     /// Persistent storage backend
     store: Arc<dyn DiscoveryStore>,
+    /// Whole-registry snapshots, refreshed after each bulk import and aggregation cycle
+    snapshots: Option<Arc<dyn RegistryStore>>,
+    /// Local namespaces of tenants: Map of tenant id -> that tenant's own registry
+    namespaces: Arc<RwLock<HashMap<TenantId, DiscoveryRegistry>>>,
 }
 
 impl Clone for DiscoveryRegistry {
     fn clone(&self) -> Self {
         Self {
             resources: Arc::clone(&self.resources),
+            tombstones: Arc::clone(&self.tombstones),
+            missed: Arc::clone(&self.missed),
+            health: Arc::clone(&self.health),
+            index: Arc::clone(&self.index),
             store: Arc::clone(&self.store),
+            snapshots: self.snapshots.clone(),
+            namespaces: Arc::clone(&self.namespaces),
         }
     }
 }
@@ -133,7 +419,13 @@ impl DiscoveryRegistry {
         info!("Initializing Bazaar discovery registry (no persistence)");
         Self {
             resources: Arc::new(RwLock::new(HashMap::new())),
+            tombstones: Arc::new(RwLock::new(HashMap::new())),
+            missed: Arc::new(RwLock::new(HashMap::new())),
+            health: Arc::new(RwLock::new(HashMap::new())),
+            index: Arc::new(RwLock::new(TfIdfIndex::new())),
             store: Arc::new(NoOpStore::new()),
+            snapshots: None,
+            namespaces: Arc::new(RwLock::new(HashMap::new())),
         }
     }
 
@@ -161,13 +453,132 @@ impl DiscoveryRegistry {
             loaded_count = count,
             "Loaded discovery resources from persistent storage"
         );
+        let index = TfIdfIndex::from_resources(cache.values());
 
         Ok(Self {
             resources: Arc::new(RwLock::new(cache)),
+            tombstones: Arc::new(RwLock::new(HashMap::new())),
+            missed: Arc::new(RwLock::new(HashMap::new())),
+            health: Arc::new(RwLock::new(HashMap::new())),
+            index: Arc::new(RwLock::new(index)),
             store: Arc::new(store),
+            snapshots: None,
+            namespaces: Arc::new(RwLock::new(HashMap::new())),
         })
     }
 
+    /// Attach a snapshot store and restore its snapshot into the registry.
+    ///
+    /// Restored resources are merged like a bulk import: a resource already in the
+    /// registry is only replaced by a newer snapshot entry. A missing, corrupt or
+    /// unreadable snapshot leaves the registry as is.
+    pub async fn with_snapshot_store<S: RegistryStore + 'static>(mut self, snapshots: S) -> Self {
+        match snapshots.load_snapshot().await {
+            Ok(Some(snapshot)) => self.restore_snapshot(snapshot).await,
+            Ok(None) => {}
+            Err(e) => warn!(error = %e, "Failed to load discovery snapshot, starting without it"),
+        }
+        self.snapshots = Some(Arc::new(snapshots));
+        self
+    }
+
+    /// Snapshot the whole registry, if a snapshot store is attached.
+    ///
+    /// Called after every bulk import and every successful aggregation cycle.
+    /// Failures are logged; the in-memory registry stays authoritative.
+    pub async fn save_snapshot(&self) {
+        let Some(snapshots) = &self.snapshots else {
+            return;
+        };
+        if let Err(e) = snapshots.save_snapshot(&self.snapshot().await).await {
+            error!(error = %e, "Failed to save discovery snapshot");
+        }
+    }
+
+    /// Save the whole registry to `path` as gzip-compressed JSON.
+    ///
+    /// Same format as a [`FileSnapshotStore::gzip`] snapshot, which is what
+    /// `DISCOVERY_CHECKPOINT_PATH` configures.
+    pub async fn save_checkpoint(&self, path: &Path) -> Result<(), DiscoveryError> {
+        FileSnapshotStore::gzip(path)
+            .save_snapshot(&self.snapshot().await)
+            .await?;
+        Ok(())
+    }
+
+    /// Load an in-memory registry from the checkpoint at `path`.
+    pub async fn load_checkpoint(path: &Path) -> Result<Self, DiscoveryError> {
+        let snapshot = FileSnapshotStore::gzip(path)
+            .load_snapshot()
+            .await?
+            .ok_or_else(|| {
+                StoreError::ReadError(format!("no usable checkpoint at {}", path.display()))
+            })?;
+        let registry = Self::new();
+        registry.restore_snapshot(snapshot).await;
+        Ok(registry)
+    }
+
+    /// Current state of the whole registry.
+    async fn snapshot(&self) -> RegistrySnapshot {
+        let cache = self.resources.read().await;
+        RegistrySnapshot {
+            version: SNAPSHOT_VERSION,
+            saved_at: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+            resources: cache.values().cloned().collect(),
+            tombstones: self.tombstones.read().await.clone(),
+            missed: self.missed.read().await.clone(),
+            health: self.health.read().await.clone(),
+        }
+    }
+
+    /// Merge a snapshot into the registry, keeping the newer version of each resource.
+    async fn restore_snapshot(&self, snapshot: RegistrySnapshot) {
+        let total = snapshot.resources.len();
+        let mut cache = self.resources.write().await;
+        let mut index = self.index.write().await;
+        let mut restored = 0;
+        for resource in snapshot.resources {
+            let url_key = resource.url.to_string();
+            let newer = cache
+                .get(&url_key)
+                .is_none_or(|existing| resource.last_updated > existing.last_updated);
+            if newer {
+                index.insert(&resource);
+                cache.insert(url_key, resource);
+                restored += 1;
+            }
+        }
+        drop(index);
+        drop(cache);
+
+        let mut tombstones = self.tombstones.write().await;
+        for (url, removed_at) in snapshot.tombstones {
+            let entry = tombstones.entry(url).or_default();
+            *entry = (*entry).max(removed_at);
+        }
+        drop(tombstones);
+        let mut missed = self.missed.write().await;
+        for (url, count) in snapshot.missed {
+            missed.entry(url).or_insert(count);
+        }
+        drop(missed);
+        let mut health = self.health.write().await;
+        for (url, resource_health) in snapshot.health {
+            health.entry(url).or_insert(resource_health);
+        }
+
+        info!(
+            restored = restored,
+            total = total,
+            saved_at = snapshot.saved_at,
+            "Restored discovery resources from snapshot"
+        );
+    }
+
     /// Get the store type for diagnostics.
     pub fn store_type(&self) -> &'static str {
         self.store.store_type()
@@ -231,6 +642,8 @@ impl DiscoveryRegistry {
 
         // Clone for persistence before moving into cache
         let resource_for_store = resource.clone();
+        self.tombstones.write().await.remove(&url_key);
+        self.index.write().await.insert(&resource);
         resources.insert(url_key, resource);
 
         // Release lock before async persistence
@@ -246,16 +659,24 @@ impl DiscoveryRegistry {
     ///
     /// If the resource doesn't exist, it will be created (upsert behavior).
     /// The update is immediately applied to cache and persisted asynchronously.
-    pub async fn update(&self, resource: DiscoveryResource) -> Result<(), DiscoveryError> {
+    pub async fn update(&self, mut resource: DiscoveryResource) -> Result<(), DiscoveryError> {
         self.validate_resource(&resource)?;
 
         let url_key = resource.url.to_string();
 
         let mut resources = self.resources.write().await;
-        let existed = resources.contains_key(&url_key);
+        let existed = match resources.get(&url_key) {
+            Some(existing) => {
+                keep_annotations(&mut resource, existing);
+                true
+            }
+            None => false,
+        };
 
         // Clone for persistence
         let resource_for_store = resource.clone();
+        self.tombstones.write().await.remove(&url_key);
+        self.index.write().await.insert(&resource);
         resources.insert(url_key.clone(), resource);
 
         if existed {
@@ -287,6 +708,11 @@ impl DiscoveryRegistry {
         match resources.remove(url) {
             Some(resource) => {
                 info!(url = %url, "Unregistered resource from discovery registry");
+                self.index.write().await.remove(url);
+                self.tombstones
+                    .write()
+                    .await
+                    .insert(url.to_string(), resource.last_updated);
 
                 // Release lock before async deletion
                 drop(resources);
@@ -303,6 +729,89 @@ impl DiscoveryRegistry {
         }
     }
 
+    /// Register or replace a resource whose registration was signed by its `owner`.
+    ///
+    /// Returns whether a previous registration of the same owner was replaced.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DiscoveryError::OwnedByOther` if the URL is already registered without
+    /// an owner or by a different one.
+    pub async fn register_signed(
+        &self,
+        resource: DiscoveryResource,
+        owner: MixedAddress,
+    ) -> Result<bool, DiscoveryError> {
+        self.validate_resource(&resource)?;
+        let mut resource = resource.with_owner(owner);
+        let url_key = resource.url.to_string();
+
+        let mut resources = self.resources.write().await;
+        let replaced = match resources.get(&url_key) {
+            Some(existing) if existing.owner != resource.owner => {
+                warn!(url = %url_key, "Attempted to register a resource owned by another registrant");
+                return Err(DiscoveryError::OwnedByOther(url_key));
+            }
+            Some(existing) => {
+                keep_health(&mut resource, existing);
+                true
+            }
+            None => false,
+        };
+
+        info!(
+            url = %url_key,
+            owner = ?resource.owner,
+            replaced,
+            "Registered signed resource in discovery registry"
+        );
+
+        let resource_for_store = resource.clone();
+        self.tombstones.write().await.remove(&url_key);
+        self.index.write().await.insert(&resource);
+        resources.insert(url_key, resource);
+        drop(resources);
+
+        self.persist_async(resource_for_store);
+
+        Ok(replaced)
+    }
+
+    /// Remove a resource on behalf of `owner`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DiscoveryError::NotFound` if no resource with the given URL exists, and
+    /// `DiscoveryError::OwnedByOther` if it is not owned by `owner`.
+    pub async fn unregister_signed(
+        &self,
+        url: &str,
+        owner: &MixedAddress,
+    ) -> Result<DiscoveryResource, DiscoveryError> {
+        let mut resources = self.resources.write().await;
+        match resources.get(url) {
+            None => return Err(DiscoveryError::NotFound(url.to_string())),
+            Some(existing) if existing.owner.as_ref() != Some(owner) => {
+                warn!(url = %url, "Attempted to remove a resource owned by another registrant");
+                return Err(DiscoveryError::OwnedByOther(url.to_string()));
+            }
+            Some(_) => {}
+        }
+
+        let resource = resources.remove(url).expect("checked above");
+        info!(url = %url, owner = %owner, "Owner unregistered resource from discovery registry");
+        self.index.write().await.remove(url);
+        self.tombstones
+            .write()
+            .await
+            .insert(url.to_string(), resource.last_updated);
+        drop(resources);
+
+        self.delete_from_store_async(url.to_string());
+
+        Ok(resource)
+    }
+
     /// Get a specific resource by URL.
     pub async fn get(&self, url: &str) -> Option<DiscoveryResource> {
         let resources = self.resources.read().await;
@@ -315,7 +824,8 @@ impl DiscoveryRegistry {
     ///
     /// * `limit` - Maximum number of resources to return (capped at 100)
     /// * `offset` - Number of resources to skip
-    /// * `filters` - Optional filters for category, network, provider, or tag
+    /// * `filters` - Optional filters for category, network, provider, or tag; unhealthy
+    ///   resources are only listed with `include_unhealthy`
     pub async fn list(
         &self,
         limit: u32,
@@ -326,10 +836,12 @@ impl DiscoveryRegistry {
 
         // Cap limit at 100 to prevent abuse
         let limit = limit.min(100);
+        let include_unhealthy = filters.as_ref().is_some_and(|f| f.include_unhealthy);
 
         // Collect and filter resources
         let mut filtered: Vec<&DiscoveryResource> = resources
             .values()
+            .filter(|r| r.is_healthy || include_unhealthy)
             .filter(|r| self.matches_filters(r, &filters))
             .collect();
 
@@ -357,6 +869,148 @@ impl DiscoveryRegistry {
         DiscoveryResponse::new(items, Pagination::new(limit, offset, total))
     }
 
+    /// Search resources by free text and filters.
+    ///
+    /// `query` is matched case-insensitively as a plain substring of the description
+    /// or URL; an empty query matches everything. When both `network` and `max_price`
+    /// are set, a single payment method must satisfy both. Results are sorted newest
+    /// first and are not paginated.
+    pub async fn search(&self, query: &str, filters: SearchFilters) -> Vec<DiscoveryResource> {
+        let resources = self.resources.read().await;
+        let query = query.trim().to_lowercase();
+
+        let mut matched: Vec<DiscoveryResource> = resources
+            .values()
+            .filter(|r| r.is_healthy)
+            .filter(|r| {
+                query.is_empty()
+                    || r.description.to_lowercase().contains(&query)
+                    || r.url.as_str().to_lowercase().contains(&query)
+            })
+            .filter(|r| Self::matches_search_filters(r, &filters))
+            .cloned()
+            .collect();
+
+        matched.sort_by_key(|r| std::cmp::Reverse(r.last_updated));
+
+        debug!(
+            query = %query,
+            matched = matched.len(),
+            "Searched discovery resources"
+        );
+
+        matched
+    }
+
+    /// Search resources by relevance to `query`.
+    ///
+    /// Resources are scored by the [`TfIdfIndex`] and narrowed by `filters` like
+    /// [`search`](Self::search); those matching no query term are left out. Results are
+    /// sorted by score, then newest first. An empty query matches everything with a
+    /// score of zero.
+    pub async fn search_ranked(
+        &self,
+        query: &str,
+        filters: SearchFilters,
+    ) -> Vec<(DiscoveryResource, f32)> {
+        let resources = self.resources.read().await;
+        let visible = |r: &&DiscoveryResource| {
+            r.is_healthy && Self::matches_search_filters(r, &filters)
+        };
+
+        let mut ranked: Vec<(DiscoveryResource, f32)> = if query.trim().is_empty() {
+            resources
+                .values()
+                .filter(visible)
+                .map(|r| (r.clone(), 0.0))
+                .collect()
+        } else {
+            let index = self.index.read().await;
+            index
+                .search(query)
+                .into_iter()
+                .filter_map(|(url, score)| {
+                    resources
+                        .get(&url)
+                        .filter(visible)
+                        .map(|r| (r.clone(), score))
+                })
+                .collect()
+        };
+        ranked.sort_by(|(a, a_score), (b, b_score)| {
+            b_score
+                .total_cmp(a_score)
+                .then_with(|| b.last_updated.cmp(&a.last_updated))
+        });
+
+        debug!(
+            query = %query,
+            matched = ranked.len(),
+            "Ranked discovery resources"
+        );
+
+        ranked
+    }
+
+    /// List resources matching `query`, one page at a time.
+    ///
+    /// Results are ordered by `query.sort`, newest first by default. Unhealthy resources
+    /// are only listed with `include_unhealthy`. The page size is capped at 100, as in
+    /// [`list`](Self::list).
+    pub async fn query(&self, query: &ListQuery) -> QueryResult {
+        let resources = self.resources.read().await;
+        let limit = query.limit.min(100);
+        let text = query
+            .q
+            .as_deref()
+            .map(|q| q.trim().to_lowercase())
+            .filter(|q| !q.is_empty());
+
+        let mut matched: Vec<&DiscoveryResource> = resources
+            .values()
+            .filter(|r| r.is_healthy || query.include_unhealthy)
+            .filter(|r| {
+                text.as_ref().is_none_or(|text| {
+                    r.description.to_lowercase().contains(text)
+                        || r.url.as_str().to_lowercase().contains(text)
+                })
+            })
+            .filter(|r| Self::matches_list_query(r, query))
+            .collect();
+        match query.sort {
+            ListSort::Newest => matched.sort_by_key(|r| std::cmp::Reverse(r.last_updated)),
+            ListSort::Reputation => matched.sort_by_key(|r| {
+                (
+                    std::cmp::Reverse(reputation_score(r)),
+                    std::cmp::Reverse(r.last_updated),
+                )
+            }),
+        }
+
+        let total = matched.len() as u32;
+        let items: Vec<DiscoveryResource> = matched
+            .into_iter()
+            .skip(query.offset as usize)
+            .take(limit as usize)
+            .cloned()
+            .collect();
+
+        debug!(
+            total = total,
+            returned = items.len(),
+            limit = limit,
+            offset = query.offset,
+            "Queried discovery resources"
+        );
+
+        QueryResult {
+            items,
+            total,
+            limit,
+            offset: query.offset,
+        }
+    }
+
     /// Get the total count of registered resources.
     pub async fn count(&self) -> usize {
         self.resources.read().await.len()
@@ -365,12 +1019,16 @@ impl DiscoveryRegistry {
     /// Bulk import resources from an external source (aggregation).
     ///
     /// This performs an upsert: existing resources are updated, new ones are added.
-    /// Only updates resources if they have a newer `last_updated` timestamp.
+    /// Conflicts with registered resources are resolved with `strategies`, see
+    /// [`ConflictStrategy`]; by default a resource is only updated when it has a newer
+    /// `last_updated` timestamp, or the same one with more payment options or listing
+    /// facilitators merged in.
     ///
     /// # Arguments
     ///
     /// * `resources` - The resources to import
     /// * `skip_validation` - Skip URL/type validation (useful for aggregated resources)
+    /// * `strategies` - How to resolve conflicts with registered resources
     ///
     /// # Returns
     ///
@@ -379,12 +1037,15 @@ impl DiscoveryRegistry {
         &self,
         resources: Vec<DiscoveryResource>,
         skip_validation: bool,
+        strategies: &[ConflictStrategy],
     ) -> Result<(usize, usize, usize), DiscoveryError> {
         let mut added = 0;
         let mut updated = 0;
         let mut skipped = 0;
 
         let mut cache = self.resources.write().await;
+        let mut tombstones = self.tombstones.write().await;
+        let mut index = self.index.write().await;
         let mut to_persist = Vec::new();
 
         for resource in resources {
@@ -399,9 +1060,18 @@ impl DiscoveryRegistry {
 
             let url_key = resource.url.to_string();
 
+            // Removed resources only come back with a newer upstream version
+            if let Some(&removed_at) = tombstones.get(&url_key) {
+                if resource.last_updated <= removed_at {
+                    skipped += 1;
+                    continue;
+                }
+                tombstones.remove(&url_key);
+            }
+
             if let Some(existing) = cache.get(&url_key) {
-                // Only update if newer
-                if resource.last_updated > existing.last_updated {
+                if let Some(resource) = resolve_conflict(resource, existing, strategies) {
+                    index.insert(&resource);
                     cache.insert(url_key.clone(), resource.clone());
                     to_persist.push(resource);
                     updated += 1;
@@ -410,13 +1080,16 @@ impl DiscoveryRegistry {
                 }
             } else {
                 // New resource
+                index.insert(&resource);
                 cache.insert(url_key.clone(), resource.clone());
                 to_persist.push(resource);
                 added += 1;
             }
         }
 
-        // Release lock before async persistence
+        // Release locks before async persistence
+        drop(index);
+        drop(tombstones);
         drop(cache);
 
         // Persist all changes
@@ -440,70 +1113,265 @@ impl DiscoveryRegistry {
             "Bulk import completed"
         );
 
+        self.save_snapshot().await;
+
         Ok((added, updated, skipped))
     }
 
-    /// Check if a resource matches the given filters.
-    fn matches_filters(&self, resource: &DiscoveryResource, filters: &Option<DiscoveryFilters>) -> bool {
-        let Some(f) = filters else {
-            return true;
-        };
+    /// Evict aggregated resources that their source facilitator no longer lists.
+    ///
+    /// `seen_by_source` maps each facilitator fetched successfully this cycle to the URLs
+    /// it listed. An aggregated resource whose `source_facilitator` is in the map but that
+    /// no facilitator listed is marked stale; once it has been missing for `max_missed`
+    /// consecutive cycles it is removed. Listing it again clears the mark, and a removed
+    /// resource comes back with the next import that lists it. Resources of facilitators
+    /// that failed, and resources not from aggregation, are never touched.
+    ///
+    /// Missed-cycle counts are kept in memory only.
+    ///
+    /// # Returns
+    ///
+    /// The evicted resources
+    pub async fn evict_stale(
+        &self,
+        seen_by_source: &HashMap<String, HashSet<String>>,
+        max_missed: u32,
+    ) -> Vec<DiscoveryResource> {
+        let seen = |url: &str| seen_by_source.values().any(|urls| urls.contains(url));
 
-        // Filter by category
-        if let Some(ref category) = f.category {
-            let matches = resource
-                .metadata
-                .as_ref()
-                .and_then(|m| m.category.as_ref())
-                .map(|c| c.eq_ignore_ascii_case(category))
-                .unwrap_or(false);
-            if !matches {
-                return false;
+        let mut cache = self.resources.write().await;
+        let mut missed = self.missed.write().await;
+        let mut evict = Vec::new();
+        for (url, resource) in cache.iter() {
+            if resource.source != DiscoverySource::Aggregated {
+                continue;
             }
-        }
-
-        // Filter by network
-        if let Some(ref network) = f.network {
-            let matches = resource
-                .accepts
-                .iter()
-                .any(|req| req.network.to_string() == *network);
-            if !matches {
-                return false;
+            let Some(source) = resource.source_facilitator.as_ref() else {
+                continue;
+            };
+            if !seen_by_source.contains_key(source) {
+                continue;
             }
-        }
-
-        // Filter by provider
-        if let Some(ref provider) = f.provider {
-            let matches = resource
-                .metadata
-                .as_ref()
-                .and_then(|m| m.provider.as_ref())
-                .map(|p| p.eq_ignore_ascii_case(provider))
-                .unwrap_or(false);
-            if !matches {
-                return false;
+            if seen(url) {
+                missed.remove(url);
+                continue;
+            }
+            let count = missed.entry(url.clone()).or_default();
+            *count += 1;
+            debug!(url = %url, source = %source, missed = *count, "Aggregated resource missing from its source");
+            if *count >= max_missed.max(1) {
+                evict.push(url.clone());
             }
         }
 
-        // Filter by tag
-        if let Some(ref tag) = f.tag {
-            let matches = resource
-                .metadata
-                .as_ref()
-                .map(|m| m.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)))
-                .unwrap_or(false);
-            if !matches {
-                return false;
+        let mut evicted = Vec::with_capacity(evict.len());
+        let mut index = self.index.write().await;
+        for url in evict {
+            missed.remove(&url);
+            index.remove(&url);
+            if let Some(resource) = cache.remove(&url) {
+                evicted.push(resource);
             }
         }
+        drop(index);
 
-        // Filter by source (Meta-Bazaar)
-        if let Some(ref source) = f.source {
-            let matches = resource.source.to_string().eq_ignore_ascii_case(source);
-            if !matches {
-                return false;
-            }
+        // Release locks before async deletion
+        drop(missed);
+        drop(cache);
+
+        for resource in &evicted {
+            info!(
+                url = %resource.url,
+                source = ?resource.source_facilitator,
+                "Evicted aggregated resource delisted by its source"
+            );
+            self.delete_from_store_async(resource.url.to_string());
+        }
+        evicted
+    }
+
+    /// URLs of every resource in the registry.
+    pub async fn urls(&self) -> Vec<String> {
+        self.resources.read().await.keys().cloned().collect()
+    }
+
+    /// Record the outcome of a health check of `url`.
+    ///
+    /// After `failure_threshold` consecutive failures the resource is marked unhealthy;
+    /// a successful check marks it healthy again. The check is annotated on the resource's
+    /// [`DiscoveryMetadata::liveness`]; only changes of `is_healthy` are persisted.
+    ///
+    /// # Returns
+    ///
+    /// The updated health, or `None` if the resource is no longer registered
+    pub async fn record_health_check(
+        &self,
+        url: &str,
+        check: HealthCheck,
+        failure_threshold: u32,
+    ) -> Option<ResourceHealth> {
+        let mut cache = self.resources.write().await;
+        let mut health = self.health.write().await;
+        let Some(resource) = cache.get_mut(url) else {
+            health.remove(url);
+            return None;
+        };
+
+        let entry = health.entry(url.to_string()).or_default();
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        entry.last_checked = Some(now);
+        entry.last_status = check.status;
+        entry.latency_ms = Some(check.latency_ms);
+        match check.error {
+            None => {
+                entry.consecutive_failures = 0;
+                entry.last_error = None;
+                entry.is_healthy = true;
+            }
+            Some(e) => {
+                entry.consecutive_failures += 1;
+                entry.last_error = Some(e);
+                if entry.consecutive_failures >= failure_threshold.max(1) {
+                    entry.is_healthy = false;
+                }
+            }
+        }
+        let entry = entry.clone();
+        resource
+            .metadata
+            .get_or_insert_with(DiscoveryMetadata::default)
+            .liveness = Some(ResourceLiveness {
+            healthy: entry.is_healthy,
+            last_checked: now,
+            consecutive_failures: entry.consecutive_failures,
+            last_status: entry.last_status,
+            latency_ms: check.latency_ms,
+        });
+
+        if resource.is_healthy == entry.is_healthy {
+            return Some(entry);
+        }
+        resource.is_healthy = entry.is_healthy;
+        let resource_for_store = resource.clone();
+
+        // Release locks before async persistence
+        drop(health);
+        drop(cache);
+
+        if entry.is_healthy {
+            info!(url = %url, "Discovery resource recovered, listing it again");
+        } else {
+            warn!(
+                url = %url,
+                failures = entry.consecutive_failures,
+                error = ?entry.last_error,
+                "Discovery resource failed health checks, hiding it from listings"
+            );
+        }
+        self.persist_async(resource_for_store);
+        Some(entry)
+    }
+
+    /// Health of a registered resource, or `None` if it is not registered.
+    pub async fn health(&self, url: &str) -> Option<ResourceHealth> {
+        let cache = self.resources.read().await;
+        let resource = cache.get(url)?;
+        let health = self.health.read().await;
+        Some(health.get(url).cloned().unwrap_or_else(|| ResourceHealth {
+            is_healthy: resource.is_healthy,
+            ..ResourceHealth::default()
+        }))
+    }
+
+    /// Annotate a resource with the ERC-8004 reputation of its agent.
+    ///
+    /// The reputation survives updates of the resource. It is refreshed periodically by
+    /// [`crate::discovery_reputation`], so recording it does not persist the resource.
+    ///
+    /// # Returns
+    ///
+    /// Whether the resource is registered
+    pub async fn record_reputation(&self, url: &str, reputation: ResourceReputation) -> bool {
+        let mut cache = self.resources.write().await;
+        let Some(resource) = cache.get_mut(url) else {
+            return false;
+        };
+        resource
+            .metadata
+            .get_or_insert_with(DiscoveryMetadata::default)
+            .reputation = Some(reputation);
+        true
+    }
+
+    /// Consecutive aggregation cycles a resource has been missing from its source.
+    pub async fn missed_cycles(&self, url: &str) -> u32 {
+        self.missed.read().await.get(url).copied().unwrap_or(0)
+    }
+
+    /// Check if a resource matches the given filters.
+    fn matches_filters(&self, resource: &DiscoveryResource, filters: &Option<DiscoveryFilters>) -> bool {
+        let Some(f) = filters else {
+            return true;
+        };
+
+        // Filter by category
+        if let Some(ref category) = f.category {
+            let matches = resource
+                .metadata
+                .as_ref()
+                .and_then(|m| m.category.as_ref())
+                .map(|c| c.eq_ignore_ascii_case(category))
+                .unwrap_or(false);
+            if !matches {
+                return false;
+            }
+        }
+
+        // Filter by network
+        if let Some(ref network) = f.network {
+            let matches = resource
+                .accepts
+                .iter()
+                .any(|req| req.network.to_string() == *network);
+            if !matches {
+                return false;
+            }
+        }
+
+        // Filter by provider
+        if let Some(ref provider) = f.provider {
+            let matches = resource
+                .metadata
+                .as_ref()
+                .and_then(|m| m.provider.as_ref())
+                .map(|p| p.eq_ignore_ascii_case(provider))
+                .unwrap_or(false);
+            if !matches {
+                return false;
+            }
+        }
+
+        // Filter by tag
+        if let Some(ref tag) = f.tag {
+            let matches = resource
+                .metadata
+                .as_ref()
+                .map(|m| m.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)))
+                .unwrap_or(false);
+            if !matches {
+                return false;
+            }
+        }
+
+        // Filter by source (Meta-Bazaar)
+        if let Some(ref source) = f.source {
+            let matches = resource.source.to_string().eq_ignore_ascii_case(source);
+            if !matches {
+                return false;
+            }
         }
 
         // Filter by source facilitator (Meta-Bazaar)
@@ -521,6 +1389,104 @@ impl DiscoveryRegistry {
         true
     }
 
+    /// Check if a resource matches the given search filters.
+    fn matches_search_filters(resource: &DiscoveryResource, filters: &SearchFilters) -> bool {
+        if let Some(ref category) = filters.category {
+            let matches = resource
+                .metadata
+                .as_ref()
+                .and_then(|m| m.category.as_ref())
+                .map(|c| c.eq_ignore_ascii_case(category))
+                .unwrap_or(false);
+            if !matches {
+                return false;
+            }
+        }
+
+        if !filters.tags.is_empty() {
+            let tags = resource.metadata.as_ref().map(|m| m.tags.as_slice()).unwrap_or(&[]);
+            let matches = filters
+                .tags
+                .iter()
+                .all(|wanted| tags.iter().any(|t| t.eq_ignore_ascii_case(wanted)));
+            if !matches {
+                return false;
+            }
+        }
+
+        if let Some(ref facilitator) = filters.source_facilitator {
+            let matches = resource
+                .source_facilitator
+                .as_ref()
+                .map(|sf| sf.eq_ignore_ascii_case(facilitator))
+                .unwrap_or(false);
+            if !matches {
+                return false;
+            }
+        }
+
+        if filters.network.is_some() || filters.max_price.is_some() {
+            let matches = resource.accepts.iter().any(|req| {
+                filters.network.as_ref().is_none_or(|n| req.network == *n)
+                    && filters.max_price.is_none_or(|max| req.amount <= max)
+            });
+            if !matches {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// Check if a resource matches the filters of a list query, `q` aside.
+    fn matches_list_query(resource: &DiscoveryResource, query: &ListQuery) -> bool {
+        let metadata = resource.metadata.as_ref();
+        let matches_label = |label: Option<&String>, wanted: &Option<String>| {
+            wanted
+                .as_ref()
+                .is_none_or(|wanted| label.is_some_and(|l| l.eq_ignore_ascii_case(wanted)))
+        };
+
+        if !matches_label(metadata.and_then(|m| m.category.as_ref()), &query.category)
+            || !matches_label(metadata.and_then(|m| m.provider.as_ref()), &query.provider)
+            || !matches_label(resource.source_facilitator.as_ref(), &query.source_facilitator)
+        {
+            return false;
+        }
+
+        if let Some(ref tag) = query.tag {
+            let matches = metadata.is_some_and(|m| m.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)));
+            if !matches {
+                return false;
+            }
+        }
+
+        if let Some(ref source) = query.source {
+            if !resource.source.to_string().eq_ignore_ascii_case(source) {
+                return false;
+            }
+        }
+
+        if let Some(min) = query.min_reputation {
+            if reputation_score(resource).is_none_or(|score| score < min) {
+                return false;
+            }
+        }
+
+        if query.network.is_some() || query.asset.is_some() || query.max_amount.is_some() {
+            let matches = resource.accepts.iter().any(|req| {
+                query.network.as_ref().is_none_or(|n| req.network == *n)
+                    && query.asset.as_ref().is_none_or(|a| same_address(&req.asset, a))
+                    && query.max_amount.is_none_or(|max| req.amount <= max)
+            });
+            if !matches {
+                return false;
+            }
+        }
+
+        true
+    }
+
     /// Track a settlement by either registering a new resource or incrementing the count.
     ///
     /// This is called after successful /settle when the resource has `discoverable=true`
@@ -570,6 +1536,7 @@ impl DiscoveryRegistry {
             );
 
             let resource_for_store = resource.clone();
+            self.index.write().await.insert(&resource);
             resources.insert(url_key, resource);
 
             // Release lock before async persistence
@@ -582,6 +1549,89 @@ impl DiscoveryRegistry {
         }
     }
 
+    /// Local namespace of `tenant`, created on first use.
+    ///
+    /// A namespace is a registry of its own: its resources are never listed by this
+    /// registry or by another tenant's namespace.
+    async fn namespace(&self, tenant: &TenantId) -> DiscoveryRegistry {
+        if let Some(namespace) = self.namespaces.read().await.get(tenant) {
+            return namespace.clone();
+        }
+        self.namespaces
+            .write()
+            .await
+            .entry(tenant.clone())
+            .or_insert_with(Self::new)
+            .clone()
+    }
+
+    /// Register a resource in the local namespace of the scope's tenant.
+    ///
+    /// Requires a scope authenticated by one of the tenant's API keys and respects the
+    /// tenant's `maxLocalResources` quota.
+    pub async fn register_scoped(
+        &self,
+        scope: &TenantScope,
+        resource: DiscoveryResource,
+    ) -> Result<(), DiscoveryError> {
+        scope.require_authenticated()?;
+        let namespace = self.namespace(scope.tenant()).await;
+        if let Some(limit) = scope.quotas().max_local_resources {
+            if namespace.count().await >= limit {
+                return Err(scope.quota_exceeded("local resource").into());
+            }
+        }
+        namespace.register(resource).await
+    }
+
+    /// Track a settlement in the local namespace of the scope's tenant, like
+    /// [`track_settlement`](Self::track_settlement).
+    pub async fn track_settlement_scoped(
+        &self,
+        scope: &TenantScope,
+        resource: DiscoveryResource,
+    ) -> Result<bool, DiscoveryError> {
+        self.namespace(scope.tenant())
+            .await
+            .track_settlement(resource)
+            .await
+    }
+
+    /// The scope's local resources followed by the shared aggregated ones.
+    ///
+    /// Resources self-registered on this registry are public but not part of any
+    /// tenant's view; only aggregated and crawled resources are shared.
+    pub async fn list_scoped(
+        &self,
+        scope: &TenantScope,
+    ) -> Result<Vec<DiscoveryResource>, TenantError> {
+        scope.require_authenticated()?;
+        let mut resources = self.namespace(scope.tenant()).await.list_all().await;
+        let local: HashSet<String> = resources.iter().map(|r| r.url.to_string()).collect();
+        resources.extend(self.list_all().await.into_iter().filter(|r| {
+            matches!(
+                r.source,
+                DiscoverySource::Aggregated | DiscoverySource::Crawled
+            ) && !local.contains(r.url.as_str())
+        }));
+        Ok(resources)
+    }
+
+    /// Every listed resource, newest first.
+    async fn list_all(&self) -> Vec<DiscoveryResource> {
+        let mut out = Vec::new();
+        let mut offset = 0;
+        loop {
+            let page = self.list(100, offset, None).await;
+            let returned = page.items.len() as u32;
+            out.extend(page.items);
+            offset += returned;
+            if returned == 0 || offset >= page.pagination.total {
+                return out;
+            }
+        }
+    }
+
     /// Validate a resource before registration.
     fn validate_resource(&self, resource: &DiscoveryResource) -> Result<(), DiscoveryError> {
         // Validate URL scheme
@@ -655,6 +1705,10 @@ mod tests {
                 category: Some(cat.to_string()),
                 provider: Some("Test Provider".to_string()),
                 tags: vec!["test".to_string()],
+                sources: Vec::new(),
+                liveness: None,
+                reputation: None,
+                extra: serde_json::Map::new(),
             });
         }
 
@@ -717,6 +1771,482 @@ mod tests {
         assert_eq!(registry.count().await, 0);
     }
 
+    #[tokio::test]
+    async fn test_unregister_tombstones_stale_imports() {
+        let registry = DiscoveryRegistry::new();
+        let mut resource = create_test_resource("https://api.example.com/data", None);
+        resource.last_updated = 100;
+
+        registry
+            .bulk_import(
+                vec![resource.clone()],
+                true,
+                &[ConflictStrategy::PreferNewer],
+            )
+            .await
+            .unwrap();
+        registry
+            .unregister("https://api.example.com/data")
+            .await
+            .unwrap();
+
+        // A cycle that fetched before the removal does not bring it back
+        assert_eq!(
+            registry
+                .bulk_import(
+                    vec![resource.clone()],
+                    true,
+                    &[ConflictStrategy::PreferNewer]
+                )
+                .await
+                .unwrap(),
+            (0, 0, 1)
+        );
+        assert_eq!(registry.count().await, 0);
+
+        // A newer upstream version resurrects it
+        resource.last_updated = 101;
+        assert_eq!(
+            registry
+                .bulk_import(vec![resource], true, &[ConflictStrategy::PreferNewer])
+                .await
+                .unwrap(),
+            (1, 0, 0)
+        );
+        assert!(registry.get("https://api.example.com/data").await.is_some());
+    }
+
+    /// A USDC payment option of `amount` on `chain_id`, paid to `pay_to`.
+    fn usdc_accept(chain_id: u64, pay_to: &str, amount: u64) -> PaymentRequirementsV2 {
+        let mut accept = create_test_resource("https://api.example.com/", None).accepts[0].clone();
+        accept.network = Caip2NetworkId::eip155(chain_id);
+        accept.pay_to = MixedAddress::Evm(pay_to.parse().unwrap());
+        accept.amount = TokenAmount::from(amount);
+        accept
+    }
+
+    const PAY_TO: &str = "0x1234567890123456789012345678901234567890";
+
+    /// A registered resource with a curated category and an aggregated listing of it.
+    fn conflicting() -> (DiscoveryResource, DiscoveryResource) {
+        let mut local = create_test_resource("https://api.example.com/data", Some("finance"));
+        local.last_updated = 100;
+        local.accepts = vec![usdc_accept(8453, PAY_TO, 1_000_000)];
+
+        let mut incoming = aggregated("https://api.example.com/data", "coinbase");
+        incoming.last_updated = 200;
+        incoming.accepts = vec![
+            usdc_accept(8453, PAY_TO, 2_000_000),
+            usdc_accept(42161, PAY_TO, 1_000_000),
+        ];
+        incoming.metadata = Some(DiscoveryMetadata {
+            sources: vec!["coinbase".to_string()],
+            ..DiscoveryMetadata::default()
+        });
+        (local, incoming)
+    }
+
+    async fn resolve(
+        local: DiscoveryResource,
+        incoming: DiscoveryResource,
+        strategies: &[ConflictStrategy],
+    ) -> ((usize, usize, usize), DiscoveryResource) {
+        let registry = DiscoveryRegistry::new();
+        let url = local.url.to_string();
+        registry
+            .bulk_import(vec![local], true, strategies)
+            .await
+            .unwrap();
+        let counts = registry
+            .bulk_import(vec![incoming], true, strategies)
+            .await
+            .unwrap();
+        (counts, registry.get(&url).await.unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_conflict_prefer_newer() {
+        let (local, incoming) = conflicting();
+        let (counts, resource) = resolve(
+            local.clone(),
+            incoming.clone(),
+            &[ConflictStrategy::PreferNewer],
+        )
+        .await;
+        assert_eq!(counts, (0, 1, 0));
+        assert_eq!(resource.accepts, incoming.accepts);
+        assert_eq!(resource.metadata, incoming.metadata);
+
+        // An older listing leaves the registered resource alone
+        let mut stale = incoming;
+        stale.last_updated = 50;
+        let (counts, resource) =
+            resolve(local.clone(), stale, &[ConflictStrategy::PreferNewer]).await;
+        assert_eq!(counts, (0, 0, 1));
+        assert_eq!(resource.accepts, local.accepts);
+        assert_eq!(resource.last_updated, local.last_updated);
+    }
+
+    #[tokio::test]
+    async fn test_conflict_replace_all() {
+        let (local, mut incoming) = conflicting();
+        incoming.last_updated = 50;
+        let (counts, resource) =
+            resolve(local, incoming.clone(), &[ConflictStrategy::ReplaceAll]).await;
+        assert_eq!(counts, (0, 1, 0));
+        assert_eq!(resource.accepts, incoming.accepts);
+        assert_eq!(resource.last_updated, 50);
+    }
+
+    #[tokio::test]
+    async fn test_conflict_merge_accepts() {
+        let (mut local, incoming) = conflicting();
+        local.accepts.push(usdc_accept(
+            10,
+            "0x0000000000000000000000000000000000000001",
+            5,
+        ));
+        let (counts, resource) = resolve(local, incoming, &[ConflictStrategy::MergeAccepts]).await;
+        assert_eq!(counts, (0, 1, 0));
+        // Base is offered by both, deduplicated to the imported amount
+        assert_eq!(
+            resource.accepts,
+            vec![
+                usdc_accept(8453, PAY_TO, 2_000_000),
+                usdc_accept(42161, PAY_TO, 1_000_000),
+                usdc_accept(10, "0x0000000000000000000000000000000000000001", 5),
+            ]
+        );
+        assert_eq!(resource.metadata.unwrap().category, None);
+    }
+
+    #[tokio::test]
+    async fn test_conflict_keep_local_metadata() {
+        let (local, incoming) = conflicting();
+        let (counts, resource) = resolve(
+            local.clone(),
+            incoming.clone(),
+            &[ConflictStrategy::KeepLocalMetadata],
+        )
+        .await;
+        assert_eq!(counts, (0, 1, 0));
+        assert_eq!(resource.accepts, incoming.accepts);
+        let metadata = resource.metadata.unwrap();
+        assert_eq!(metadata.category.as_deref(), Some("finance"));
+        assert_eq!(metadata.tags, vec!["test"]);
+        assert_eq!(metadata.sources, vec!["coinbase"]);
+
+        // Listings that describe the resource replace the local metadata
+        let mut described = incoming;
+        described.metadata.as_mut().unwrap().category = Some("data".to_string());
+        let (_, resource) = resolve(local, described, &[ConflictStrategy::KeepLocalMetadata]).await;
+        assert_eq!(resource.metadata.unwrap().category.as_deref(), Some("data"));
+    }
+
+    #[tokio::test]
+    async fn test_conflict_aggregation_strategies_combine() {
+        let (local, mut incoming) = conflicting();
+        // Same version, one more payment option
+        incoming.last_updated = local.last_updated;
+        incoming.accepts = vec![usdc_accept(42161, PAY_TO, 1_000_000)];
+        let strategies = [
+            ConflictStrategy::MergeAccepts,
+            ConflictStrategy::KeepLocalMetadata,
+        ];
+        let (counts, resource) = resolve(local.clone(), incoming.clone(), &strategies).await;
+        assert_eq!(counts, (0, 1, 0));
+        assert_eq!(
+            resource.accepts,
+            vec![
+                usdc_accept(42161, PAY_TO, 1_000_000),
+                usdc_accept(8453, PAY_TO, 1_000_000),
+            ]
+        );
+        assert_eq!(
+            resource.metadata.unwrap().category.as_deref(),
+            Some("finance")
+        );
+
+        // Without merging, the same version with fewer options is skipped
+        let (counts, resource) =
+            resolve(local.clone(), incoming, &[ConflictStrategy::PreferNewer]).await;
+        assert_eq!(counts, (0, 0, 1));
+        assert_eq!(resource.accepts, local.accepts);
+        assert_eq!(resource.last_updated, local.last_updated);
+    }
+
+    /// A resource imported from `facilitator` by aggregation.
+    fn aggregated(url: &str, facilitator: &str) -> DiscoveryResource {
+        let mut resource = create_test_resource(url, None);
+        resource.source = DiscoverySource::Aggregated;
+        resource.source_facilitator = Some(facilitator.to_string());
+        resource
+    }
+
+    fn seen(cycle: &[(&str, &[&str])]) -> HashMap<String, HashSet<String>> {
+        cycle
+            .iter()
+            .map(|(source, urls)| {
+                (source.to_string(), urls.iter().map(|u| u.to_string()).collect())
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_delisted_aggregated_resource_is_evicted() {
+        let registry = DiscoveryRegistry::new();
+        let gone = "https://gone.example.com/";
+        let kept = "https://kept.example.com/";
+        registry
+            .bulk_import(
+                vec![aggregated(gone, "coinbase"), aggregated(kept, "coinbase")],
+                true,
+                &[ConflictStrategy::PreferNewer],
+            )
+            .await
+            .unwrap();
+        registry
+            .register(create_test_resource("https://local.example.com/", None))
+            .await
+            .unwrap();
+
+        let cycle = seen(&[("coinbase", &[kept])]);
+        for missed in 1..3 {
+            assert!(registry.evict_stale(&cycle, 3).await.is_empty());
+            assert_eq!(registry.missed_cycles(gone).await, missed);
+        }
+        // A failed fetch of the source neither counts nor resets
+        assert!(registry.evict_stale(&seen(&[]), 3).await.is_empty());
+        assert_eq!(registry.missed_cycles(gone).await, 2);
+
+        let evicted = registry.evict_stale(&cycle, 3).await;
+        assert_eq!(evicted.len(), 1);
+        assert_eq!(evicted[0].url.as_str(), gone);
+        assert!(registry.get(gone).await.is_none());
+        assert_eq!(registry.missed_cycles(gone).await, 0);
+        assert!(registry.get(kept).await.is_some());
+        // Locally registered resources are never evicted
+        assert!(registry.get("https://local.example.com/").await.is_some());
+
+        // Not tombstoned: the next listing brings it back
+        assert_eq!(
+            registry
+                .bulk_import(
+                    vec![aggregated(gone, "coinbase")],
+                    true,
+                    &[ConflictStrategy::PreferNewer]
+                )
+                .await
+                .unwrap(),
+            (1, 0, 0)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_restores_registry_after_restart() {
+        let dir = std::env::temp_dir().join(format!("x402-registry-{:x}", rand::random::<u64>()));
+        let path = dir.join("registry.json");
+
+        let resources: Vec<DiscoveryResource> = (0..250)
+            .map(|i| {
+                let url = format!("https://api{}.example.com/", i);
+                let mut resource = aggregated(&url, "coinbase");
+                let mut extra = serde_json::Map::new();
+                extra.insert("outputSchema".to_string(), serde_json::json!({ "index": i }));
+                resource.metadata = Some(DiscoveryMetadata {
+                    category: Some(["finance", "ai", "data"][i % 3].to_string()),
+                    provider: Some(format!("Provider {}", i)),
+                    tags: vec!["test".to_string(), format!("tag-{}", i)],
+                    sources: vec!["coinbase".to_string(), "ultravioleta".to_string()],
+                    liveness: None,
+                    reputation: None,
+                    extra,
+                });
+                resource
+            })
+            .collect();
+
+        let registry = DiscoveryRegistry::new()
+            .with_snapshot_store(FileSnapshotStore::new(&path))
+            .await;
+        assert_eq!(registry.count().await, 0);
+        registry
+            .bulk_import(resources.clone(), true, &[ConflictStrategy::PreferNewer])
+            .await
+            .unwrap();
+
+        let restarted = DiscoveryRegistry::new()
+            .with_snapshot_store(FileSnapshotStore::new(&path))
+            .await;
+        assert_eq!(restarted.count().await, 250);
+        for resource in &resources {
+            let restored = restarted.get(resource.url.as_str()).await.unwrap();
+            assert_eq!(
+                serde_json::to_value(&restored).unwrap(),
+                serde_json::to_value(resource).unwrap()
+            );
+        }
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_checkpoint_round_trip() {
+        let dir = std::env::temp_dir().join(format!("x402-checkpoint-{:x}", rand::random::<u64>()));
+        let path = dir.join("registry.json.gz");
+
+        let registry = DiscoveryRegistry::new();
+        for i in 0..120 {
+            let url = format!("https://api{}.example.com/", i);
+            registry
+                .register(create_test_resource(&url, Some("data")))
+                .await
+                .unwrap();
+        }
+        registry
+            .unregister("https://api0.example.com/")
+            .await
+            .unwrap();
+        registry
+            .record_health_check(
+                "https://api1.example.com/",
+                HealthCheck::failed(None, 10, "down"),
+                1,
+            )
+            .await;
+        registry.save_checkpoint(&path).await.unwrap();
+        assert!(!dir.join("registry.json.gz.tmp").exists());
+        // gzip magic number
+        assert_eq!(std::fs::read(&path).unwrap()[..2], [0x1f, 0x8b]);
+
+        let loaded = DiscoveryRegistry::load_checkpoint(&path).await.unwrap();
+        assert_eq!(loaded.count().await, registry.count().await);
+        assert_eq!(loaded.count().await, 119);
+        assert_eq!(
+            loaded.health("https://api1.example.com/").await,
+            registry.health("https://api1.example.com/").await
+        );
+        assert!(loaded
+            .tombstones
+            .read()
+            .await
+            .contains_key("https://api0.example.com/"));
+        // The relevance index is rebuilt from the restored resources
+        let before = registry
+            .search_ranked("test resource", SearchFilters::default())
+            .await;
+        let after = loaded
+            .search_ranked("test resource", SearchFilters::default())
+            .await;
+        assert_eq!(after.len(), before.len());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_gzip_snapshot_store_is_saved_and_restored() {
+        let dir = std::env::temp_dir().join(format!("x402-checkpoint-{:x}", rand::random::<u64>()));
+        let path = dir.join("registry.json.gz");
+        assert!(DiscoveryRegistry::load_checkpoint(&path).await.is_err());
+
+        let registry = DiscoveryRegistry::new()
+            .with_snapshot_store(FileSnapshotStore::gzip(&path))
+            .await;
+        assert_eq!(registry.count().await, 0);
+        registry
+            .bulk_import(
+                vec![aggregated("https://a.example.com/", "coinbase")],
+                true,
+                &[ConflictStrategy::PreferNewer],
+            )
+            .await
+            .unwrap();
+        registry
+            .record_health_check(
+                "https://a.example.com/",
+                HealthCheck::failed(None, 10, "down"),
+                3,
+            )
+            .await;
+        registry.save_snapshot().await;
+
+        let restarted = DiscoveryRegistry::new()
+            .with_snapshot_store(FileSnapshotStore::gzip(&path))
+            .await;
+        assert_eq!(restarted.count().await, 1);
+        assert!(restarted.get("https://a.example.com/").await.is_some());
+        assert_eq!(
+            restarted.health("https://a.example.com/").await,
+            registry.health("https://a.example.com/").await
+        );
+
+        std::fs::write(&path, b"not gzip").unwrap();
+        assert!(DiscoveryRegistry::load_checkpoint(&path).await.is_err());
+        let corrupt = DiscoveryRegistry::new()
+            .with_snapshot_store(FileSnapshotStore::gzip(&path))
+            .await;
+        assert_eq!(corrupt.count().await, 0);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_reappearing_resource_resets_stale_count() {
+        let registry = DiscoveryRegistry::new();
+        let url = "https://flaky.example.com/";
+        registry
+            .bulk_import(
+                vec![aggregated(url, "payai")],
+                true,
+                &[ConflictStrategy::PreferNewer],
+            )
+            .await
+            .unwrap();
+
+        let missing = seen(&[("payai", &[])]);
+        registry.evict_stale(&missing, 3).await;
+        registry.evict_stale(&missing, 3).await;
+        assert_eq!(registry.missed_cycles(url).await, 2);
+
+        // Listed again, by its source or by another facilitator
+        registry.evict_stale(&seen(&[("payai", &[]), ("coinbase", &[url])]), 3).await;
+        assert_eq!(registry.missed_cycles(url).await, 0);
+
+        registry.evict_stale(&missing, 3).await;
+        registry.evict_stale(&missing, 3).await;
+        assert!(registry.get(url).await.is_some());
+        assert_eq!(registry.evict_stale(&missing, 3).await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_imports_never_replace_signed_resources() {
+        let registry = DiscoveryRegistry::new();
+        let url = "https://owned.example.com/";
+        let owner = MixedAddress::Evm(
+            "0x1234567890123456789012345678901234567890"
+                .parse()
+                .unwrap(),
+        );
+        registry
+            .register_signed(create_test_resource(url, None), owner.clone())
+            .await
+            .unwrap();
+
+        let mut newer = aggregated(url, "coinbase");
+        newer.last_updated += 60;
+        assert_eq!(
+            registry
+                .bulk_import(vec![newer], true, &[ConflictStrategy::ReplaceAll])
+                .await
+                .unwrap(),
+            (0, 0, 1)
+        );
+
+        let resource = registry.get(url).await.unwrap();
+        assert_eq!(resource.source, DiscoverySource::SelfRegistered);
+        assert_eq!(resource.owner, Some(owner));
+    }
+
     #[tokio::test]
     async fn test_unregister_not_found() {
         let registry = DiscoveryRegistry::new();
@@ -902,4 +2432,293 @@ mod tests {
         assert_eq!(response.items.len(), 1);
         assert_eq!(response.items[0].resource_type, "facilitator");
     }
+
+    async fn search_fixture() -> DiscoveryRegistry {
+        let registry = DiscoveryRegistry::new();
+
+        let mut weather = create_test_resource("https://weather.example.com/forecast", Some("data"));
+        weather.description = "Hourly Weather forecast".to_string();
+        weather.metadata.as_mut().unwrap().tags = vec!["weather".to_string(), "api".to_string()];
+        weather.source_facilitator = Some("coinbase".to_string());
+        weather.last_updated = 100;
+
+        let mut cheap = create_test_resource("https://cheap.example.com/weather", Some("data"));
+        cheap.description = "Budget forecasts".to_string();
+        cheap.accepts[0].amount = TokenAmount::from(10u64);
+        cheap.accepts[0].network = Caip2NetworkId::eip155(137);
+        cheap.last_updated = 200;
+
+        let mut llm = create_test_resource("https://llm.example.com/chat", Some("ai"));
+        llm.description = "Chat completions".to_string();
+        llm.last_updated = 300;
+
+        for resource in [weather, cheap, llm] {
+            registry.register(resource).await.unwrap();
+        }
+        registry
+    }
+
+    fn urls(resources: &[DiscoveryResource]) -> Vec<&str> {
+        resources.iter().map(|r| r.url.as_str()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_search_matches_description_and_url() {
+        let registry = search_fixture().await;
+
+        let results = registry.search("WEATHER", SearchFilters::default()).await;
+        assert_eq!(
+            urls(&results),
+            vec!["https://cheap.example.com/weather", "https://weather.example.com/forecast"]
+        );
+
+        let all = registry.search("", SearchFilters::default()).await;
+        assert_eq!(all.len(), 3);
+        assert_eq!(all[0].url.as_str(), "https://llm.example.com/chat");
+    }
+
+    #[tokio::test]
+    async fn test_search_filter_combinations() {
+        let registry = search_fixture().await;
+
+        let base = SearchFilters {
+            network: Some(Caip2NetworkId::eip155(8453)),
+            ..Default::default()
+        };
+        let results = registry.search("weather", base.clone()).await;
+        assert_eq!(urls(&results), vec!["https://weather.example.com/forecast"]);
+
+        let cheap = SearchFilters {
+            max_price: Some(TokenAmount::from(100u64)),
+            ..Default::default()
+        };
+        let results = registry.search("", cheap.clone()).await;
+        assert_eq!(urls(&results), vec!["https://cheap.example.com/weather"]);
+
+        // Network and price must hold for the same payment method
+        let results = registry
+            .search("", SearchFilters { max_price: cheap.max_price, ..base })
+            .await;
+        assert!(results.is_empty());
+
+        let tagged = SearchFilters {
+            category: Some("DATA".to_string()),
+            tags: vec!["weather".to_string(), "api".to_string()],
+            source_facilitator: Some("coinbase".to_string()),
+            ..Default::default()
+        };
+        let results = registry.search("forecast", tagged).await;
+        assert_eq!(urls(&results), vec!["https://weather.example.com/forecast"]);
+
+        let missing_tag = SearchFilters {
+            tags: vec!["weather".to_string(), "premium".to_string()],
+            ..Default::default()
+        };
+        assert!(registry.search("", missing_tag).await.is_empty());
+
+        let ai = SearchFilters {
+            category: Some("ai".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(registry.search("", ai).await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_search_ranked_orders_by_relevance() {
+        let registry = search_fixture().await;
+        let ranked = registry.search_ranked("weather forecast", SearchFilters::default()).await;
+        let order: Vec<&str> = ranked.iter().map(|(r, _)| r.url.as_str()).collect();
+        // Newest first would put the cheap resource ahead
+        assert_eq!(
+            order,
+            vec!["https://weather.example.com/forecast", "https://cheap.example.com/weather"]
+        );
+        assert!(ranked[0].1 > ranked[1].1);
+
+        let polygon = SearchFilters {
+            network: Some(Caip2NetworkId::eip155(137)),
+            ..Default::default()
+        };
+        let ranked = registry.search_ranked("weather", polygon).await;
+        assert_eq!(ranked.len(), 1);
+        assert_eq!(ranked[0].0.url.as_str(), "https://cheap.example.com/weather");
+
+        let all = registry.search_ranked("", SearchFilters::default()).await;
+        assert_eq!(all.len(), 3);
+        assert!(all.iter().all(|(_, score)| *score == 0.0));
+    }
+
+    #[tokio::test]
+    async fn test_index_follows_registry_changes() {
+        let registry = search_fixture().await;
+        let mut storm = create_test_resource("https://storm.example.com/alerts", Some("data"));
+        storm.description = "Severe storm alerts".to_string();
+        registry
+            .bulk_import(vec![storm], false, &[ConflictStrategy::PreferNewer])
+            .await
+            .unwrap();
+
+        let ranked = registry.search_ranked("storm", SearchFilters::default()).await;
+        assert_eq!(ranked.len(), 1);
+
+        let mut llm = create_test_resource("https://llm.example.com/chat", Some("ai"));
+        llm.description = "Storm chasing assistant".to_string();
+        registry.update(llm).await.unwrap();
+        assert_eq!(registry.search_ranked("storm", SearchFilters::default()).await.len(), 2);
+        assert!(registry.search_ranked("completions", SearchFilters::default()).await.is_empty());
+
+        registry.unregister("https://storm.example.com/alerts").await.unwrap();
+        let ranked = registry.search_ranked("storm", SearchFilters::default()).await;
+        assert_eq!(ranked.len(), 1);
+        assert_eq!(ranked[0].0.url.as_str(), "https://llm.example.com/chat");
+        assert_eq!(registry.index.read().await.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_search_empty_results() {
+        let registry = search_fixture().await;
+        assert!(registry.search("nonexistent", SearchFilters::default()).await.is_empty());
+
+        let empty = DiscoveryRegistry::new();
+        assert!(empty.search("", SearchFilters::default()).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_search_injection_like_queries_are_plain_text() {
+        let registry = search_fixture().await;
+
+        for query in [
+            "' OR 1=1 --",
+            "\"; DROP TABLE resources; --",
+            ".*",
+            "%",
+            "weather' OR 'a'='a",
+            "\0",
+        ] {
+            let results = registry.search(query, SearchFilters::default()).await;
+            assert!(results.is_empty(), "query {query:?} matched {:?}", urls(&results));
+        }
+        assert_eq!(registry.count().await, 3);
+    }
+
+    #[tokio::test]
+    async fn test_query_combined_filters() {
+        let registry = search_fixture().await;
+        let usdc: MixedAddress = MixedAddress::Evm(
+            "0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913"
+                .parse()
+                .unwrap(),
+        );
+
+        let result = registry.query(&ListQuery::default()).await;
+        assert_eq!(result.total, 3);
+        assert_eq!(result.items[0].url.as_str(), "https://llm.example.com/chat");
+
+        let on_base = ListQuery {
+            network: Some(Caip2NetworkId::eip155(8453)),
+            asset: Some(usdc.clone()),
+            category: Some("Data".to_string()),
+            ..Default::default()
+        };
+        let result = registry.query(&on_base).await;
+        assert_eq!(urls(&result.items), vec!["https://weather.example.com/forecast"]);
+        assert_eq!(result.total, 1);
+
+        let tagged = ListQuery {
+            tag: Some("WEATHER".to_string()),
+            q: Some("forecast".to_string()),
+            source_facilitator: Some("coinbase".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            urls(&registry.query(&tagged).await.items),
+            vec!["https://weather.example.com/forecast"]
+        );
+
+        let cheap = ListQuery {
+            max_amount: Some(TokenAmount::from(100u64)),
+            q: Some("WEATHER".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            urls(&registry.query(&cheap).await.items),
+            vec!["https://cheap.example.com/weather"]
+        );
+
+        // Network, asset and price must hold for the same payment method
+        let cheap_on_base = ListQuery {
+            max_amount: cheap.max_amount,
+            ..on_base.clone()
+        };
+        assert_eq!(registry.query(&cheap_on_base).await.total, 0);
+        let other_asset = ListQuery {
+            asset: Some(MixedAddress::Evm(
+                "0x1234567890123456789012345678901234567890"
+                    .parse()
+                    .unwrap(),
+            )),
+            ..Default::default()
+        };
+        assert_eq!(registry.query(&other_asset).await.total, 0);
+
+        // Hidden resources need include_unhealthy
+        registry
+            .record_health_check("https://llm.example.com/chat", HealthCheck::failed(None, 10, "down"), 1)
+            .await;
+        let ai = ListQuery {
+            category: Some("ai".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(registry.query(&ai).await.total, 0);
+        let ai = ListQuery {
+            include_unhealthy: true,
+            ..ai
+        };
+        assert_eq!(registry.query(&ai).await.total, 1);
+    }
+
+    #[tokio::test]
+    async fn test_query_pagination_boundaries() {
+        let registry = DiscoveryRegistry::new();
+        for i in 0..5u64 {
+            let mut resource =
+                create_test_resource(&format!("https://api{i}.example.com/data"), Some("finance"));
+            resource.last_updated = i;
+            registry.register(resource).await.unwrap();
+        }
+        let page = |limit, offset| ListQuery {
+            limit,
+            offset,
+            ..Default::default()
+        };
+
+        let first = registry.query(&page(2, 0)).await;
+        assert_eq!(
+            urls(&first.items),
+            vec!["https://api4.example.com/data", "https://api3.example.com/data"]
+        );
+        assert_eq!((first.total, first.limit, first.offset), (5, 2, 0));
+
+        let last = registry.query(&page(2, 4)).await;
+        assert_eq!(urls(&last.items), vec!["https://api0.example.com/data"]);
+        assert_eq!(last.total, 5);
+
+        // Past the end: no items, same total
+        let beyond = registry.query(&page(2, 5)).await;
+        assert!(beyond.items.is_empty());
+        assert_eq!(beyond.total, 5);
+        assert!(registry.query(&page(10, u32::MAX)).await.items.is_empty());
+
+        // A zero limit still counts; large limits are capped
+        let count_only = registry.query(&page(0, 0)).await;
+        assert!(count_only.items.is_empty());
+        assert_eq!(count_only.total, 5);
+        let capped = registry.query(&page(1000, 0)).await;
+        assert_eq!((capped.items.len(), capped.limit), (5, 100));
+
+        let response = DiscoveryResponse::from(registry.query(&page(2, 2)).await);
+        assert_eq!(response.items.len(), 2);
+        assert_eq!(response.pagination.total, 5);
+        assert_eq!(response.pagination.offset, 2);
+    }
 }
@@ -0,0 +1,319 @@
+//! TF-IDF relevance index over discovery resources.
+//!
+//! Each resource is indexed under the terms of its URL, description, category and tags;
+//! category and tag terms count [`LABEL_BOOST`] times, as they are chosen to describe the
+//! resource. A query term matches an indexed term exactly, or as a prefix of it at
+//! [`PREFIX_MATCH_WEIGHT`] (so `weath` finds `weather`), and a resource scores
+//!
+//! ```text
+//! sum over matches of  weight × (count of term in resource / terms in resource) × ln(1 + N / df)
+//! ```
+//!
+//! where `N` is the number of indexed resources and `df` the number containing the term.
+//! The index is keyed like the registry, by `resource.url.to_string()`, and is updated
+//! in place as resources are added, replaced and removed.
+
+use std::collections::{BTreeMap, HashMap};
+use std::ops::Bound;
+
+use crate::types_v2::DiscoveryResource;
+
+/// Times a category or tag term is counted.
+pub const LABEL_BOOST: u32 = 2;
+
+/// Weight of a query term matching only the start of an indexed term.
+pub const PREFIX_MATCH_WEIGHT: f32 = 0.5;
+
+/// Shortest query term matched as a prefix.
+const MIN_PREFIX_LEN: usize = 3;
+
+/// Terms too common in URLs and descriptions to tell resources apart.
+const STOP_WORDS: &[&str] = &[
+    "a", "an", "and", "com", "for", "http", "https", "in", "of", "on", "the", "to", "with", "www",
+];
+
+/// Terms of an indexed resource.
+#[derive(Debug, Clone, Default)]
+struct IndexedDocument {
+    /// Distinct terms, to clean up postings on removal
+    terms: Vec<String>,
+    /// Term occurrences, boosts included
+    length: u32,
+}
+
+/// Inverted index ranking resources by TF-IDF relevance to a query.
+#[derive(Debug, Clone, Default)]
+pub struct TfIdfIndex {
+    /// Term -> (resource URL -> occurrences of the term)
+    postings: BTreeMap<String, HashMap<String, u32>>,
+    /// Resource URL -> its indexed terms
+    documents: HashMap<String, IndexedDocument>,
+}
+
+impl TfIdfIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Build an index of `resources`.
+    pub fn from_resources<'a>(resources: impl IntoIterator<Item = &'a DiscoveryResource>) -> Self {
+        let mut index = Self::new();
+        for resource in resources {
+            index.insert(resource);
+        }
+        index
+    }
+
+    /// Number of indexed resources.
+    pub fn len(&self) -> usize {
+        self.documents.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.documents.is_empty()
+    }
+
+    /// Index `resource`, replacing an earlier version with the same URL.
+    pub fn insert(&mut self, resource: &DiscoveryResource) {
+        let url = resource.url.to_string();
+        self.remove(&url);
+
+        let mut counts: HashMap<String, u32> = HashMap::new();
+        for term in tokenize(resource.url.as_str()).chain(tokenize(&resource.description)) {
+            *counts.entry(term).or_default() += 1;
+        }
+        if let Some(metadata) = &resource.metadata {
+            let labels = metadata.category.iter().chain(&metadata.tags);
+            for term in labels.flat_map(|label| tokenize(label)) {
+                *counts.entry(term).or_default() += LABEL_BOOST;
+            }
+        }
+
+        let document = IndexedDocument {
+            terms: counts.keys().cloned().collect(),
+            length: counts.values().sum(),
+        };
+        for (term, count) in counts {
+            self.postings
+                .entry(term)
+                .or_default()
+                .insert(url.clone(), count);
+        }
+        self.documents.insert(url, document);
+    }
+
+    /// Remove the resource with `url`, if it is indexed.
+    pub fn remove(&mut self, url: &str) {
+        let Some(document) = self.documents.remove(url) else {
+            return;
+        };
+        for term in document.terms {
+            if let Some(posting) = self.postings.get_mut(&term) {
+                posting.remove(url);
+                if posting.is_empty() {
+                    self.postings.remove(&term);
+                }
+            }
+        }
+    }
+
+    /// URLs of the resources matching `query`, with their scores, most relevant first.
+    ///
+    /// Resources matching no query term are left out; ties are ordered by URL.
+    pub fn search(&self, query: &str) -> Vec<(String, f32)> {
+        let total = self.documents.len() as f32;
+        let mut scores: HashMap<&str, f32> = HashMap::new();
+
+        for query_term in tokenize(query) {
+            for (term, weight) in self.matching_terms(&query_term) {
+                let posting = &self.postings[term];
+                let idf = (1.0 + total / posting.len() as f32).ln();
+                for (url, count) in posting {
+                    let length = self.documents[url].length.max(1) as f32;
+                    *scores.entry(url).or_default() += weight * (*count as f32 / length) * idf;
+                }
+            }
+        }
+
+        let mut ranked: Vec<(String, f32)> = scores
+            .into_iter()
+            .map(|(url, score)| (url.to_string(), score))
+            .collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        ranked
+    }
+
+    /// Indexed terms matching `query_term`, with the weight of the match.
+    fn matching_terms<'a>(&'a self, query_term: &'a str) -> Vec<(&'a str, f32)> {
+        if query_term.chars().count() < MIN_PREFIX_LEN {
+            return self
+                .postings
+                .get_key_value(query_term)
+                .map(|(term, _)| (term.as_str(), 1.0))
+                .into_iter()
+                .collect();
+        }
+        self.postings
+            .range::<str, _>((Bound::Included(query_term), Bound::Unbounded))
+            .map(|(term, _)| term.as_str())
+            .take_while(|term| term.starts_with(query_term))
+            .map(|term| {
+                let weight = if term == query_term {
+                    1.0
+                } else {
+                    PREFIX_MATCH_WEIGHT
+                };
+                (term, weight)
+            })
+            .collect()
+    }
+}
+
+/// Lowercase alphanumeric terms of `text`, without stop words.
+pub fn tokenize(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .filter(|word| !STOP_WORDS.contains(&word.as_str()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types_v2::DiscoveryMetadata;
+    use url::Url;
+
+    fn resource(
+        url: &str,
+        description: &str,
+        category: Option<&str>,
+        tags: &[&str],
+    ) -> DiscoveryResource {
+        DiscoveryResource::new(
+            Url::parse(url).unwrap(),
+            "http".to_string(),
+            description.to_string(),
+            Vec::new(),
+        )
+        .with_metadata(DiscoveryMetadata {
+            category: category.map(String::from),
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            ..Default::default()
+        })
+    }
+
+    fn fixture() -> TfIdfIndex {
+        TfIdfIndex::from_resources(&[
+            resource(
+                "https://weather.example.com/forecast",
+                "Hourly weather forecast for any city",
+                Some("data"),
+                &["weather", "forecast"],
+            ),
+            resource(
+                "https://maps.example.com/geocode",
+                "Geocoding with optional weather overlay",
+                Some("data"),
+                &["maps"],
+            ),
+            resource(
+                "https://llm.example.com/chat",
+                "Chat completions from a large language model",
+                Some("ai"),
+                &["llm", "chat"],
+            ),
+            resource(
+                "https://images.example.com/generate",
+                "Image generation model",
+                Some("ai"),
+                &["images"],
+            ),
+        ])
+    }
+
+    fn ranked_urls(index: &TfIdfIndex, query: &str) -> Vec<String> {
+        index
+            .search(query)
+            .into_iter()
+            .map(|(url, _)| url)
+            .collect()
+    }
+
+    #[test]
+    fn test_rank_order_matches_fixture() {
+        let index = fixture();
+        assert_eq!(
+            ranked_urls(&index, "weather"),
+            [
+                "https://weather.example.com/forecast",
+                "https://maps.example.com/geocode"
+            ]
+        );
+        assert_eq!(
+            ranked_urls(&index, "language model"),
+            [
+                "https://llm.example.com/chat",
+                "https://images.example.com/generate"
+            ]
+        );
+        // Rare terms outweigh common ones
+        assert_eq!(
+            ranked_urls(&index, "data chat")[0],
+            "https://llm.example.com/chat"
+        );
+
+        let scores = index.search("weather");
+        assert!(scores[0].1 > scores[1].1 && scores[1].1 > 0.0);
+    }
+
+    #[test]
+    fn test_prefix_matches_rank_below_exact_matches() {
+        let index = fixture();
+        assert_eq!(ranked_urls(&index, "WEATH"), ranked_urls(&index, "weather"));
+        assert_eq!(
+            ranked_urls(&index, "gen"),
+            ["https://images.example.com/generate"]
+        );
+
+        let mut index = TfIdfIndex::new();
+        index.insert(&resource("https://a.example.com/", "chat", None, &[]));
+        index.insert(&resource("https://b.example.com/", "chatbot", None, &[]));
+        assert_eq!(
+            ranked_urls(&index, "chat"),
+            ["https://a.example.com/", "https://b.example.com/"]
+        );
+        // Short terms only match exactly
+        assert!(index.search("ch").is_empty());
+    }
+
+    #[test]
+    fn test_insert_replaces_and_remove_cleans_up() {
+        let mut index = fixture();
+        index.insert(&resource(
+            "https://weather.example.com/forecast",
+            "Tide tables",
+            None,
+            &[],
+        ));
+        assert_eq!(index.len(), 4);
+        assert!(index.search("hourly").is_empty());
+        assert_eq!(
+            ranked_urls(&index, "tide"),
+            ["https://weather.example.com/forecast"]
+        );
+
+        index.remove("https://maps.example.com/geocode");
+        index.remove("https://unknown.example.com/");
+        assert_eq!(index.len(), 3);
+        assert!(index.search("overlay").is_empty());
+        assert!(!index.postings.contains_key("geocoding"));
+    }
+
+    #[test]
+    fn test_stop_words_and_punctuation_are_ignored() {
+        let index = fixture();
+        assert!(index.search("the https:// www").is_empty());
+        assert!(index.search("' OR 1=1 --").is_empty());
+        assert!(index.search("").is_empty());
+    }
+}
@@ -0,0 +1,366 @@
+//! Proof of ownership for resources registered through `POST /discovery/resources`.
+//!
+//! Operators register their own resources in the Bazaar by signing a canonical
+//! message with the key of the address their resource pays to:
+//!
+//! ```text
+//! x402 discovery registration
+//! url: https://api.example.com/weather
+//! payTo: 0x209693Bc6afc0C5328bA36FaF03C514EF312287C
+//! bodyHash: 0x5c4a0b9e0c9f0d6c3f3b1f0d2a8f7e6b4c9d2e1f0a3b5c7d9e1f2a4b6c8d0e2f
+//! timestamp: 1767225600
+//! ```
+//!
+//! `bodyHash` is the [`body_hash`] of the request body, so the signature covers the
+//! whole resource and not only its URL. The message is signed with EIP-191
+//! (`personal_sign`). Removing the resource through `DELETE /discovery/resources` signs
+//! the same message with a `x402 discovery deletion` first line, so a registration
+//! signature cannot be replayed as a deletion.
+//!
+//! Signatures are accepted while their `timestamp` is within `DISCOVERY_SIGNATURE_MAX_AGE`
+//! seconds (default: 300) of the facilitator's clock, in either direction, and only once:
+//! a signed message seen before is rejected until it goes stale.
+//!
+//! All payment methods of a signed registration must pay to the same EVM address; it
+//! becomes the resource's [`owner`](crate::types_v2::DiscoveryResource::owner), and only
+//! that address may replace or remove the resource afterwards.
+
+use alloy::primitives::{eip191_hash_message, keccak256, Address, Signature, B256};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use url::Url;
+
+use crate::types::MixedAddress;
+use crate::types_v2::{PaymentRequirementsV2, RegisterResourceRequest};
+
+/// Default seconds a signature's timestamp may be away from the facilitator's clock.
+pub const DEFAULT_SIGNATURE_MAX_AGE_SECS: u64 = 300;
+
+/// Seconds a signature's timestamp may be away from the facilitator's clock.
+pub static SIGNATURE_MAX_AGE: Lazy<u64> = Lazy::new(|| {
+    crate::env_registry::parse("DISCOVERY_SIGNATURE_MAX_AGE")
+        .unwrap_or(DEFAULT_SIGNATURE_MAX_AGE_SECS)
+});
+
+/// Signed messages accepted by this facilitator.
+static ACCEPTED_MESSAGES: Lazy<ReplayGuard> = Lazy::new(ReplayGuard::default);
+
+/// Why a signed registration or deletion was rejected.
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum OwnershipError {
+    #[error("A signed registration needs at least one payment method")]
+    NoPayTo,
+    #[error(
+        "Payment methods pay to different addresses; a signed registration needs a single payTo"
+    )]
+    MultiplePayTo,
+    #[error("payTo must be an EVM address to sign a registration, got {0}")]
+    NonEvmPayTo(String),
+    #[error("Signature timestamp {timestamp} is outside the {max_age}s window around {now}")]
+    Stale {
+        timestamp: u64,
+        now: u64,
+        max_age: u64,
+    },
+    #[error("Malformed signature: {0}")]
+    MalformedSignature(String),
+    #[error("Signature was not made by {expected}")]
+    InvalidSignature { expected: Address },
+    #[error("Signature was already used")]
+    Replayed,
+}
+
+/// What a signature authorizes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignedAction {
+    Register,
+    Delete,
+}
+
+impl SignedAction {
+    fn header(self) -> &'static str {
+        match self {
+            SignedAction::Register => "x402 discovery registration",
+            SignedAction::Delete => "x402 discovery deletion",
+        }
+    }
+}
+
+/// Body of `POST /discovery/resources`: a resource and its owner's signature.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignedRegistration {
+    #[serde(flatten)]
+    pub resource: RegisterResourceRequest,
+    /// Unix timestamp the signature was made at
+    pub timestamp: u64,
+    /// Hex EIP-191 signature of [`canonical_message`] by the payTo address
+    pub signature: String,
+}
+
+/// Body of `DELETE /discovery/resources`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignedDeletion {
+    pub url: Url,
+    /// Unix timestamp the signature was made at
+    pub timestamp: u64,
+    /// Hex EIP-191 signature of [`canonical_message`] by the resource's owner
+    pub signature: String,
+}
+
+/// Message the owner signs to authorize `action` on `url` with a body hashing to
+/// `body_hash`.
+pub fn canonical_message(
+    action: SignedAction,
+    url: &Url,
+    pay_to: Address,
+    body_hash: B256,
+    timestamp: u64,
+) -> String {
+    format!(
+        "{}\nurl: {url}\npayTo: {pay_to}\nbodyHash: {body_hash}\ntimestamp: {timestamp}",
+        action.header()
+    )
+}
+
+/// Keccak-256 of a signed request body without its `timestamp` and `signature`
+/// members, serialized with sorted object keys and no whitespace.
+pub fn body_hash(body: &Value) -> B256 {
+    let mut body = body.clone();
+    if let Some(object) = body.as_object_mut() {
+        object.remove("timestamp");
+        object.remove("signature");
+    }
+    sort_keys(&mut body);
+    keccak256(body.to_string())
+}
+
+fn sort_keys(value: &mut Value) {
+    match value {
+        Value::Object(object) => {
+            object.sort_keys();
+            object.values_mut().for_each(sort_keys);
+        }
+        Value::Array(items) => items.iter_mut().for_each(sort_keys),
+        _ => {}
+    }
+}
+
+/// The single EVM address every payment method of `accepts` pays to.
+pub fn registrant(accepts: &[PaymentRequirementsV2]) -> Result<Address, OwnershipError> {
+    let mut pay_to = None;
+    for method in accepts {
+        let address = match &method.pay_to {
+            MixedAddress::Evm(address) => address.0,
+            other => return Err(OwnershipError::NonEvmPayTo(other.to_string())),
+        };
+        match pay_to {
+            Some(previous) if previous != address => return Err(OwnershipError::MultiplePayTo),
+            _ => pay_to = Some(address),
+        }
+    }
+    pay_to.ok_or(OwnershipError::NoPayTo)
+}
+
+/// Check that `timestamp` is within `max_age` seconds of `now`.
+pub fn check_freshness(timestamp: u64, now: u64, max_age: u64) -> Result<(), OwnershipError> {
+    if timestamp.abs_diff(now) > max_age {
+        return Err(OwnershipError::Stale {
+            timestamp,
+            now,
+            max_age,
+        });
+    }
+    Ok(())
+}
+
+/// Check that `signature` is `expected`'s EIP-191 signature of `message`.
+pub fn verify_signature(
+    message: &str,
+    signature: &str,
+    expected: Address,
+) -> Result<(), OwnershipError> {
+    let signature = Signature::from_str(signature)
+        .map_err(|e| OwnershipError::MalformedSignature(e.to_string()))?;
+    match signature.recover_address_from_msg(message) {
+        Ok(signer) if signer == expected => Ok(()),
+        _ => Err(OwnershipError::InvalidSignature { expected }),
+    }
+}
+
+/// Signed messages seen within the freshness window, by EIP-191 hash.
+#[derive(Debug, Default)]
+pub struct ReplayGuard {
+    /// Message hash to the time its timestamp goes stale
+    seen: Mutex<HashMap<B256, u64>>,
+}
+
+impl ReplayGuard {
+    /// Record `message`, signed at `timestamp`, failing if it was recorded before.
+    ///
+    /// Messages are forgotten once `timestamp` is more than `max_age` seconds behind
+    /// `now`, when [`check_freshness`] rejects them anyway.
+    pub fn accept(
+        &self,
+        message: &str,
+        timestamp: u64,
+        now: u64,
+        max_age: u64,
+    ) -> Result<(), OwnershipError> {
+        let mut seen = self.seen.lock().unwrap();
+        seen.retain(|_, stale_after| *stale_after >= now);
+        match seen.entry(eip191_hash_message(message)) {
+            Entry::Occupied(_) => Err(OwnershipError::Replayed),
+            Entry::Vacant(entry) => {
+                entry.insert(timestamp.saturating_add(max_age));
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Check a signature of `action` on `url` by `owner` over a body hashing to
+/// `body_hash`, made at `timestamp`, and that it was not used before.
+pub fn verify(
+    action: SignedAction,
+    url: &Url,
+    owner: Address,
+    body_hash: B256,
+    timestamp: u64,
+    signature: &str,
+) -> Result<(), OwnershipError> {
+    let now = unix_now();
+    check_freshness(timestamp, now, *SIGNATURE_MAX_AGE)?;
+    let message = canonical_message(action, url, owner, body_hash, timestamp);
+    verify_signature(&message, signature, owner)?;
+    ACCEPTED_MESSAGES.accept(&message, timestamp, now, *SIGNATURE_MAX_AGE)
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::address;
+    use alloy::signers::local::PrivateKeySigner;
+    use alloy::signers::SignerSync;
+
+    fn accepts(pay_to: &[MixedAddress]) -> Vec<PaymentRequirementsV2> {
+        pay_to
+            .iter()
+            .map(|pay_to| {
+                serde_json::from_value(serde_json::json!({
+                    "scheme": "exact",
+                    "network": "eip155:8453",
+                    "asset": "0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913",
+                    "amount": "10000",
+                    "payTo": pay_to,
+                    "maxTimeoutSeconds": 60
+                }))
+                .unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_registrant_needs_a_single_evm_pay_to() {
+        let alice = MixedAddress::Evm(address!("209693Bc6afc0C5328bA36FaF03C514EF312287C").into());
+        let bob = MixedAddress::Evm(address!("0000000000000000000000000000000000000b0b").into());
+
+        assert_eq!(
+            registrant(&accepts(&[alice.clone(), alice.clone()])),
+            Ok(address!("209693Bc6afc0C5328bA36FaF03C514EF312287C"))
+        );
+        assert_eq!(
+            registrant(&accepts(&[alice, bob])),
+            Err(OwnershipError::MultiplePayTo)
+        );
+        let near = MixedAddress::Near("alice.near".to_string());
+        assert!(matches!(
+            registrant(&accepts(&[near])),
+            Err(OwnershipError::NonEvmPayTo(_))
+        ));
+        assert_eq!(registrant(&[]), Err(OwnershipError::NoPayTo));
+    }
+
+    #[test]
+    fn test_signature_binds_action_url_body_and_timestamp() {
+        let signer = PrivateKeySigner::random();
+        let url = Url::parse("https://api.example.com/weather").unwrap();
+        let body = body_hash(&serde_json::json!({ "url": url, "description": "Weather" }));
+        let message = canonical_message(SignedAction::Register, &url, signer.address(), body, 100);
+        let signature = signer
+            .sign_message_sync(message.as_bytes())
+            .unwrap()
+            .to_string();
+
+        assert_eq!(
+            verify_signature(&message, &signature, signer.address()),
+            Ok(())
+        );
+        let deletion = canonical_message(SignedAction::Delete, &url, signer.address(), body, 100);
+        assert!(verify_signature(&deletion, &signature, signer.address()).is_err());
+        let later = canonical_message(SignedAction::Register, &url, signer.address(), body, 101);
+        assert!(verify_signature(&later, &signature, signer.address()).is_err());
+        let edited = body_hash(&serde_json::json!({ "url": url, "description": "Scam" }));
+        let edited = canonical_message(SignedAction::Register, &url, signer.address(), edited, 100);
+        assert!(verify_signature(&edited, &signature, signer.address()).is_err());
+        assert!(matches!(
+            verify_signature(&message, "0x1234", signer.address()),
+            Err(OwnershipError::MalformedSignature(_))
+        ));
+    }
+
+    #[test]
+    fn test_body_hash_ignores_key_order_and_signature_fields() {
+        let body = serde_json::json!({
+            "url": "https://api.example.com/weather",
+            "accepts": [{ "scheme": "exact", "amount": "10000" }],
+        });
+        let signed = serde_json::json!({
+            "timestamp": 100,
+            "accepts": [{ "amount": "10000", "scheme": "exact" }],
+            "signature": "0x1234",
+            "url": "https://api.example.com/weather",
+        });
+        assert_eq!(body_hash(&body), body_hash(&signed));
+        let cheaper = serde_json::json!({
+            "url": "https://api.example.com/weather",
+            "accepts": [{ "scheme": "exact", "amount": "1" }],
+        });
+        assert_ne!(body_hash(&body), body_hash(&cheaper));
+    }
+
+    #[test]
+    fn test_messages_are_accepted_once_while_fresh() {
+        let guard = ReplayGuard::default();
+        assert_eq!(guard.accept("message", 1_000, 1_000, 300), Ok(()));
+        assert_eq!(
+            guard.accept("message", 1_000, 1_200, 300),
+            Err(OwnershipError::Replayed)
+        );
+        assert_eq!(guard.accept("other", 1_000, 1_200, 300), Ok(()));
+        // Forgotten once stale, when the freshness check rejects it first
+        assert_eq!(guard.accept("message", 1_000, 1_301, 300), Ok(()));
+    }
+
+    #[test]
+    fn test_freshness_window_applies_both_ways() {
+        assert!(check_freshness(1_000, 1_300, 300).is_ok());
+        assert!(check_freshness(1_300, 1_000, 300).is_ok());
+        assert!(check_freshness(1_000, 1_301, 300).is_err());
+        assert!(check_freshness(1_301, 1_000, 300).is_err());
+    }
+}
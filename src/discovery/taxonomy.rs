@@ -0,0 +1,347 @@
+//! Canonical categories and tags for aggregated resources.
+//!
+//! Facilitators label the same kind of service in many ways ("AI", "ai-agents",
+//! "Artificial Intelligence", "llm"), which splits category filters and facets. A
+//! [`Taxonomy`] maps such labels to one slug: a label is first slugified (lowercased,
+//! runs of anything but letters and digits turned into a single `-`) and then looked up
+//! in a curated alias table. Labels missing from the table keep their slug.
+//!
+//! The built-in table can be extended with a JSON file, set with
+//! `DISCOVERY_TAXONOMY_FILE`, mapping aliases to canonical slugs:
+//!
+//! ```json
+//! {
+//!   "categories": { "Text to Speech": "media" },
+//!   "tags": { "base-mainnet": "base" }
+//! }
+//! ```
+//!
+//! Entries of the file take precedence over built-in ones.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use serde::Deserialize;
+
+/// Built-in category aliases, by canonical slug.
+const CATEGORY_ALIASES: &[(&str, &[&str])] = &[
+    (
+        "ai",
+        &[
+            "artificial-intelligence",
+            "ai-agents",
+            "ai-agent",
+            "agents",
+            "agent",
+            "llm",
+            "llms",
+            "genai",
+            "generative-ai",
+            "machine-learning",
+            "ml",
+            "ai-ml",
+            "inference",
+        ],
+    ),
+    (
+        "data",
+        &[
+            "datasets",
+            "dataset",
+            "data-feeds",
+            "data-feed",
+            "analytics",
+            "data-api",
+        ],
+    ),
+    (
+        "finance",
+        &[
+            "defi",
+            "trading",
+            "markets",
+            "market-data",
+            "financial",
+            "fintech",
+            "crypto",
+            "payments",
+        ],
+    ),
+    (
+        "media",
+        &[
+            "images",
+            "image",
+            "image-generation",
+            "video",
+            "audio",
+            "music",
+            "multimedia",
+        ],
+    ),
+    ("search", &["web-search", "search-engine"]),
+    ("social", &["social-media", "socialfi", "community"]),
+    ("news", &["headlines", "journalism"]),
+    ("weather", &["climate", "forecast", "forecasts"]),
+    (
+        "developer-tools",
+        &[
+            "dev-tools",
+            "devtools",
+            "developer",
+            "developers",
+            "tools",
+            "utilities",
+        ],
+    ),
+    (
+        "infrastructure",
+        &["infra", "rpc", "nodes", "compute", "hosting"],
+    ),
+    ("storage", &["file-storage", "files", "ipfs"]),
+    ("gaming", &["games", "game", "gamefi"]),
+    ("security", &["cybersecurity", "auditing", "audit"]),
+];
+
+/// Built-in tag aliases, by canonical slug.
+const TAG_ALIASES: &[(&str, &[&str])] = &[
+    (
+        "llm",
+        &["llms", "large-language-model", "large-language-models"],
+    ),
+    ("ai", &["artificial-intelligence", "genai"]),
+    ("defi", &["decentralized-finance"]),
+    ("nft", &["nfts", "non-fungible-tokens"]),
+    ("usdc", &["usd-coin"]),
+    ("api", &["apis", "rest-api"]),
+];
+
+/// Taxonomy file could not be loaded.
+#[derive(Debug, thiserror::Error)]
+pub enum TaxonomyError {
+    /// The file could not be read
+    #[error("Cannot read taxonomy file {0}: {1}")]
+    Io(String, std::io::Error),
+
+    /// The file is not a valid taxonomy
+    #[error("Invalid taxonomy file {0}: {1}")]
+    Parse(String, serde_json::Error),
+}
+
+/// Contents of a taxonomy file: aliases mapped to canonical labels.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TaxonomyFile {
+    #[serde(default)]
+    categories: HashMap<String, String>,
+    #[serde(default)]
+    tags: HashMap<String, String>,
+}
+
+/// Alias tables mapping category and tag slugs to canonical slugs.
+#[derive(Debug, Clone)]
+pub struct Taxonomy {
+    categories: HashMap<String, String>,
+    tags: HashMap<String, String>,
+}
+
+impl Default for Taxonomy {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
+
+impl Taxonomy {
+    /// The curated built-in tables.
+    pub fn builtin() -> Self {
+        Self {
+            categories: alias_table(CATEGORY_ALIASES),
+            tags: alias_table(TAG_ALIASES),
+        }
+    }
+
+    /// The built-in tables extended with the aliases of a JSON taxonomy file.
+    pub fn from_file(path: &Path) -> Result<Self, TaxonomyError> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| TaxonomyError::Io(path.display().to_string(), e))?;
+        let file: TaxonomyFile = serde_json::from_str(&content)
+            .map_err(|e| TaxonomyError::Parse(path.display().to_string(), e))?;
+
+        let mut taxonomy = Self::builtin();
+        extend_table(&mut taxonomy.categories, file.categories);
+        extend_table(&mut taxonomy.tags, file.tags);
+        Ok(taxonomy)
+    }
+
+    /// Load the taxonomy from `DISCOVERY_TAXONOMY_FILE`, or the built-in one when unset.
+    pub fn from_env() -> Result<Self, TaxonomyError> {
+        match crate::env_registry::var("DISCOVERY_TAXONOMY_FILE") {
+            Some(path) => Self::from_file(Path::new(&path)),
+            None => Ok(Self::builtin()),
+        }
+    }
+
+    /// Canonical slug of a category; `None` when it has no letters or digits.
+    pub fn normalize_category(&self, category: &str) -> Option<String> {
+        canonical(&self.categories, category)
+    }
+
+    /// Canonical slugs of tags, in order, without empty labels and duplicates.
+    pub fn normalize_tags(&self, tags: Vec<String>) -> Vec<String> {
+        let mut normalized: Vec<String> = Vec::with_capacity(tags.len());
+        for tag in tags {
+            if let Some(tag) = canonical(&self.tags, &tag) {
+                if !normalized.contains(&tag) {
+                    normalized.push(tag);
+                }
+            }
+        }
+        normalized
+    }
+}
+
+/// [`Taxonomy::normalize_category`] with the built-in taxonomy.
+pub fn normalize_category(category: &str) -> Option<String> {
+    builtin().normalize_category(category)
+}
+
+/// [`Taxonomy::normalize_tags`] with the built-in taxonomy.
+pub fn normalize_tags(tags: Vec<String>) -> Vec<String> {
+    builtin().normalize_tags(tags)
+}
+
+fn builtin() -> &'static Taxonomy {
+    static BUILTIN: OnceLock<Taxonomy> = OnceLock::new();
+    BUILTIN.get_or_init(Taxonomy::builtin)
+}
+
+/// Lowercase `label`, turning every run of characters other than letters and digits
+/// into a single `-`, without leading or trailing ones.
+pub fn slugify(label: &str) -> String {
+    let mut slug = String::with_capacity(label.len());
+    for c in label.chars().flat_map(char::to_lowercase) {
+        if c.is_alphanumeric() {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    if slug.ends_with('-') {
+        slug.pop();
+    }
+    slug
+}
+
+fn canonical(table: &HashMap<String, String>, label: &str) -> Option<String> {
+    let slug = slugify(label);
+    if slug.is_empty() {
+        return None;
+    }
+    Some(table.get(&slug).cloned().unwrap_or(slug))
+}
+
+fn alias_table(entries: &[(&str, &[&str])]) -> HashMap<String, String> {
+    entries
+        .iter()
+        .flat_map(|(canonical, aliases)| {
+            aliases
+                .iter()
+                .map(move |alias| (alias.to_string(), canonical.to_string()))
+        })
+        .collect()
+}
+
+fn extend_table(table: &mut HashMap<String, String>, aliases: HashMap<String, String>) {
+    for (alias, canonical) in aliases {
+        let (alias, canonical) = (slugify(&alias), slugify(&canonical));
+        if !alias.is_empty() && !canonical.is_empty() && alias != canonical {
+            table.insert(alias, canonical);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slugify() {
+        assert_eq!(
+            slugify("Artificial Intelligence"),
+            "artificial-intelligence"
+        );
+        assert_eq!(slugify("  AI / ML  "), "ai-ml");
+        assert_eq!(slugify("Dev_Tools!!"), "dev-tools");
+        assert_eq!(slugify("---"), "");
+    }
+
+    #[test]
+    fn test_ai_variants_collapse_to_one_category() {
+        for label in [
+            "AI",
+            "ai-agents",
+            "Artificial Intelligence",
+            "llm",
+            "Machine Learning",
+        ] {
+            assert_eq!(normalize_category(label).as_deref(), Some("ai"), "{label}");
+        }
+    }
+
+    #[test]
+    fn test_unknown_category_is_kept_as_slug() {
+        assert_eq!(
+            normalize_category("Real Estate").as_deref(),
+            Some("real-estate")
+        );
+        assert_eq!(normalize_category("  "), None);
+    }
+
+    #[test]
+    fn test_normalize_tags_dedups_and_drops_empty() {
+        let tags = vec![
+            "LLM".to_string(),
+            "Large Language Models".to_string(),
+            "".to_string(),
+            "Base".to_string(),
+            "llms".to_string(),
+        ];
+        assert_eq!(normalize_tags(tags), vec!["llm", "base"]);
+    }
+
+    #[test]
+    fn test_file_extends_builtin_table() {
+        let path =
+            std::env::temp_dir().join(format!("x402-taxonomy-{:x}.json", rand::random::<u64>()));
+        std::fs::write(
+            &path,
+            r#"{"categories": {"Text to Speech": "Media", "llm": "language-models"}, "tags": {"base-mainnet": "base"}}"#,
+        )
+        .unwrap();
+        let taxonomy = Taxonomy::from_file(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(
+            taxonomy.normalize_category("text-to-speech").as_deref(),
+            Some("media")
+        );
+        assert_eq!(
+            taxonomy.normalize_category("LLM").as_deref(),
+            Some("language-models")
+        );
+        assert_eq!(
+            taxonomy.normalize_category("AI Agents").as_deref(),
+            Some("ai")
+        );
+        assert_eq!(
+            taxonomy.normalize_tags(vec!["Base Mainnet".to_string()]),
+            vec!["base"]
+        );
+
+        assert!(matches!(
+            Taxonomy::from_file(Path::new("/nonexistent/taxonomy.json")),
+            Err(TaxonomyError::Io(..))
+        ));
+    }
+}
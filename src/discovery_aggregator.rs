@@ -38,27 +38,93 @@
 //! # Usage
 //!
 //! ```rust,ignore
-//! use x402_rs::discovery_aggregator::{DiscoveryAggregator, FacilitatorConfig};
+//! use x402_rs::discovery_aggregator::{
+//!     DiscoveryAggregator, FacilitatorConfig, AGGREGATION_CONFLICT_STRATEGIES,
+//! };
 //!
 //! let aggregator = DiscoveryAggregator::new();
 //! let resources = aggregator.fetch_all().await?;
-//! registry.bulk_import(resources, true).await?;
+//! registry.bulk_import(resources, true, AGGREGATION_CONFLICT_STRATEGIES).await?;
 //! ```
-
-use reqwest::Client;
+//!
+//! The background task aggregates from [`FacilitatorConfig::from_env`]: the built-in
+//! list, merged with the overrides in `DISCOVERY_SOURCES_FILE` when set. Facilitators
+//! listed in `EXTRA_FACILITATOR_URLS` are then probed with [`FacilitatorConfig::probe`]
+//! and added to it.
+//!
+//! An [`AggregatorFilter`] drops payment methods on networks or assets outside
+//! `DISCOVERY_ALLOWED_NETWORKS`/`DISCOVERY_ALLOWED_ASSETS`; resources left without
+//! any payment method are not imported. Categories and tags are mapped to canonical
+//! slugs by a [`Taxonomy`], extended with `DISCOVERY_TAXONOMY_FILE` when set.
+//!
+//! [`fetch_all_with_report`](DiscoveryAggregator::fetch_all_with_report) also returns
+//! an [`AggregationReport`] with per-facilitator counts, timings and errors.
+//! [`fetch_each`](DiscoveryAggregator::fetch_each) hands each facilitator's resources to
+//! a callback as soon as its fetch completes instead of collecting the whole catalog;
+//! the background task imports that way, so the first facilitator's resources are
+//! served while the others are still being fetched. A listing of a resource that an
+//! earlier facilitator of the cycle already imported is merged into the registered
+//! copy, as [`dedup_resources`] would have, and conflicts with registered resources are
+//! resolved with [`AGGREGATION_CONFLICT_STRATEGIES`]. The
+//! background task keeps the latest report in a [`SharedAggregationReport`], served
+//! at `GET /discovery/sources/status`. [`AggregationRunner::try_run`] runs a cycle on
+//! demand (`POST /admin/discovery/aggregate`), never alongside the background one.
+//!
+//! Page requests failing with a timeout, connection error, `429` or 5xx are retried up
+//! to `DISCOVERY_AGGREGATION_MAX_ATTEMPTS` times with exponential backoff, within the
+//! facilitator's `timeout_secs`; other client errors fail right away. A `429` or `503`
+//! carrying `Retry-After` (seconds or HTTP-date) or `X-RateLimit-Reset` waits as long as
+//! the facilitator asks instead, up to `DISCOVERY_AGGREGATION_RATE_LIMIT_BUDGET` seconds
+//! per fetch, and then requests the same page again. A facilitator's
+//! `min_request_interval_ms` spaces its page requests. A facilitator's whole fetch,
+//! every page included, is cut off after its `timeout_secs` plus the rate limit budget,
+//! so one slow or endlessly paginating facilitator cannot hold back the cycle; it is
+//! reported as failed and the others are unaffected.
+//!
+//! A facilitator that fails `DISCOVERY_AGGREGATION_BREAKER_THRESHOLD` times in a row is
+//! skipped for an exponentially growing cool-down (see [`SourceBreaker`]); its breaker
+//! state is part of the report.
+//!
+//! Live per-facilitator statistics (last fetch and success, resources contributed, last
+//! error, breaker state and next scheduled fetch) are kept in [`SharedSourceStats`] and
+//! served at `GET /discovery/sources`.
+//!
+//! The `ETag` and `Last-Modified` of a facilitator's first page are kept between cycles
+//! and sent back as `If-None-Match`/`If-Modified-Since`. A `304 Not Modified` on the
+//! first page means the whole catalog is unchanged: the resources of the previous cycle
+//! are reused without refetching or converting them.
+
+use dashmap::DashMap;
+use futures::stream::{self, StreamExt};
+use reqwest::header::{HeaderMap, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED, RETRY_AFTER};
+use reqwest::{Client, StatusCode};
 use serde::{Deserialize, Deserializer, Serialize};
-use std::time::Duration;
+use serde_json::{Map, Value};
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
 use tracing::{debug, error, info, warn};
+use sha2::{Digest, Sha256};
 use url::Url;
 
 use alloy::primitives::U256;
-use std::str::FromStr;
+
+use crate::discovery::taxonomy::Taxonomy;
+use crate::discovery::ConflictStrategy;
+use crate::peer_net::{self, ConnectionStats, SrvResolver, SrvSource};
 
 // ============================================================================
-// Timestamp Parsing (handles both u64 and ISO8601 string)
+// Timestamp Parsing (handles both u64 and date strings)
 // ============================================================================
 
-/// Deserialize a timestamp that can be either a u64 (Unix seconds) or an ISO8601 string.
+/// Deserialize a timestamp given as Unix seconds or as a date string.
+///
+/// Facilitators disagree on the format, so this is lenient (see [`parse_timestamp`]).
+/// A value that cannot be understood becomes `None` instead of rejecting the resource.
 fn deserialize_flexible_timestamp<'de, D>(deserializer: D) -> Result<Option<u64>, D::Error>
 where
     D: Deserializer<'de>,
@@ -71,7 +137,7 @@ where
         type Value = Option<u64>;
 
         fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
-            formatter.write_str("a u64 timestamp or an ISO8601 date string")
+            formatter.write_str("a Unix timestamp or a date string")
         }
 
         fn visit_none<E>(self) -> Result<Self::Value, E>
@@ -81,133 +147,148 @@ where
             Ok(None)
         }
 
-        fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
-        where
-            D: Deserializer<'de>,
-        {
-            deserializer.deserialize_any(FlexibleTimestampInnerVisitor)
-        }
-
-        fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E>
+        fn visit_unit<E>(self) -> Result<Self::Value, E>
         where
             E: de::Error,
         {
-            Ok(Some(value))
+            Ok(None)
         }
 
-        fn visit_i64<E>(self, value: i64) -> Result<Self::Value, E>
+        fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
         where
-            E: de::Error,
+            D: Deserializer<'de>,
         {
-            Ok(Some(value as u64))
+            deserializer.deserialize_any(self)
         }
 
-        fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+        fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E>
         where
             E: de::Error,
         {
-            parse_iso8601_to_unix(value)
-                .map(Some)
-                .map_err(de::Error::custom)
+            Ok(Some(epoch_seconds(value)))
         }
-    }
-
-    struct FlexibleTimestampInnerVisitor;
-
-    impl<'de> Visitor<'de> for FlexibleTimestampInnerVisitor {
-        type Value = Option<u64>;
 
-        fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
-            formatter.write_str("a u64 timestamp or an ISO8601 date string")
-        }
-
-        fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E>
+        fn visit_i64<E>(self, value: i64) -> Result<Self::Value, E>
         where
             E: de::Error,
         {
-            Ok(Some(value))
+            match u64::try_from(value) {
+                Ok(value) => Ok(Some(epoch_seconds(value))),
+                Err(_) => {
+                    debug!(value, "Ignoring timestamp before the Unix epoch");
+                    Ok(None)
+                }
+            }
         }
 
-        fn visit_i64<E>(self, value: i64) -> Result<Self::Value, E>
+        fn visit_f64<E>(self, value: f64) -> Result<Self::Value, E>
         where
             E: de::Error,
         {
-            Ok(Some(value as u64))
+            if value.is_finite() && value >= 0.0 && value < u64::MAX as f64 {
+                Ok(Some(epoch_seconds(value as u64)))
+            } else {
+                debug!(value, "Ignoring out-of-range timestamp");
+                Ok(None)
+            }
         }
 
         fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
         where
             E: de::Error,
         {
-            parse_iso8601_to_unix(value)
-                .map(Some)
-                .map_err(de::Error::custom)
+            let parsed = parse_timestamp(value);
+            if parsed.is_none() {
+                debug!(value, "Ignoring unparseable timestamp");
+            }
+            Ok(parsed)
         }
     }
 
     deserializer.deserialize_option(FlexibleTimestampVisitor)
 }
 
-/// Parse an ISO8601 date string to Unix timestamp.
-/// Handles formats like "2026-01-06T20:22:59.724Z" or "2026-01-06T20:22:59Z"
-fn parse_iso8601_to_unix(s: &str) -> Result<u64, String> {
-    // Try to parse ISO8601 format manually
-    // Format: YYYY-MM-DDTHH:MM:SS.sssZ or YYYY-MM-DDTHH:MM:SSZ
-    let s = s.trim_end_matches('Z');
-    let (date_time, _millis) = if let Some(dot_pos) = s.find('.') {
-        (&s[..dot_pos], &s[dot_pos + 1..])
+/// Epoch numbers above this are taken to be in milliseconds (it is year 5138 in seconds).
+const MILLIS_THRESHOLD: u64 = 100_000_000_000;
+/// Epoch numbers above this are taken to be in microseconds.
+const MICROS_THRESHOLD: u64 = 100_000_000_000_000;
+
+/// Unix seconds from an epoch number that may be in seconds, milliseconds or microseconds.
+fn epoch_seconds(value: u64) -> u64 {
+    if value >= MICROS_THRESHOLD {
+        value / 1_000_000
+    } else if value >= MILLIS_THRESHOLD {
+        value / 1_000
     } else {
-        (s, "")
-    };
-
-    // Parse date and time parts
-    let parts: Vec<&str> = date_time.split('T').collect();
-    if parts.len() != 2 {
-        return Err(format!("Invalid ISO8601 format: {}", s));
+        value
     }
+}
 
-    let date_parts: Vec<u32> = parts[0]
-        .split('-')
-        .filter_map(|p| p.parse().ok())
-        .collect();
-    let time_parts: Vec<u32> = parts[1]
-        .split(':')
-        .filter_map(|p| p.parse().ok())
-        .collect();
-
-    if date_parts.len() != 3 || time_parts.len() != 3 {
-        return Err(format!("Invalid date/time components: {}", s));
+/// Parse a timestamp string to Unix seconds.
+///
+/// Accepts RFC 3339 with any offset and fractional precision, RFC 2822, a space instead
+/// of `T`, times without an offset or with a `UTC` suffix (taken as UTC), bare dates
+/// (midnight UTC), and numeric epoch strings. Dates before 1970 yield `None`.
+fn parse_timestamp(s: &str) -> Option<u64> {
+    use chrono::{DateTime, NaiveDate, NaiveDateTime};
+
+    let s = s.trim();
+    if s.is_empty() {
+        return None;
+    }
+    if s.bytes().all(|b| b.is_ascii_digit()) {
+        return s.parse().ok().map(epoch_seconds);
     }
 
-    let year = date_parts[0];
-    let month = date_parts[1];
-    let day = date_parts[2];
-    let hour = time_parts[0];
-    let minute = time_parts[1];
-    let second = time_parts[2];
-
-    // Calculate Unix timestamp (simplified - doesn't handle leap seconds)
-    // Days since Unix epoch (1970-01-01)
-    let days = days_since_epoch(year, month, day);
-    let seconds = days as u64 * 86400 + hour as u64 * 3600 + minute as u64 * 60 + second as u64;
-
-    Ok(seconds)
-}
-
-/// Calculate days since Unix epoch (1970-01-01).
-fn days_since_epoch(year: u32, month: u32, day: u32) -> i64 {
-    // Algorithm from Howard Hinnant's date library
-    let y = year as i64 - if month <= 2 { 1 } else { 0 };
-    let era = if y >= 0 { y } else { y - 399 } / 400;
-    let yoe = (y - era * 400) as u32;
-    let doy = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day - 1;
-    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
-    era * 146097 + doe as i64 - 719468
+    const OFFSET_FORMATS: &[&str] = &[
+        "%Y-%m-%d %H:%M:%S%.f%:z",
+        "%Y-%m-%dT%H:%M:%S%.f%z",
+        "%Y-%m-%d %H:%M:%S%.f%z",
+    ];
+    const NAIVE_FORMATS: &[&str] = &[
+        "%Y-%m-%dT%H:%M:%S%.f",
+        "%Y-%m-%d %H:%M:%S%.f",
+        "%Y-%m-%dT%H:%M",
+        "%Y-%m-%d %H:%M",
+    ];
+
+    let seconds = if let Ok(dt) = DateTime::parse_from_rfc3339(s) {
+        dt.timestamp()
+    } else if let Some(dt) = OFFSET_FORMATS
+        .iter()
+        .find_map(|format| DateTime::parse_from_str(s, format).ok())
+    {
+        dt.timestamp()
+    } else if let Ok(dt) = DateTime::parse_from_rfc2822(s) {
+        dt.timestamp()
+    } else {
+        let naive = s
+            .strip_suffix("UTC")
+            .or_else(|| s.strip_suffix('Z'))
+            .unwrap_or(s)
+            .trim_end();
+        if let Some(dt) = NAIVE_FORMATS
+            .iter()
+            .find_map(|format| NaiveDateTime::parse_from_str(naive, format).ok())
+        {
+            dt.and_utc().timestamp()
+        } else {
+            NaiveDate::parse_from_str(naive, "%Y-%m-%d")
+                .ok()?
+                .and_hms_opt(0, 0, 0)?
+                .and_utc()
+                .timestamp()
+        }
+    };
+    u64::try_from(seconds).ok()
 }
 
-use crate::caip2::Caip2NetworkId;
+use crate::caip2::{Caip2NetworkId, SOLANA_DEVNET_GENESIS, SOLANA_MAINNET_GENESIS};
+use crate::network::{find_token_deployment, Network};
 use crate::types::{MixedAddress, Scheme, TokenAmount};
-use crate::types_v2::{DiscoveryMetadata, DiscoveryResource, PaymentRequirementsV2};
+use crate::types_v2::{
+    DiscoveryMetadata, DiscoveryResource, PaymentRequirementsV2, SupportedPaymentKindV2,
+};
 
 // ============================================================================
 // Error Types
@@ -231,6 +312,34 @@ pub enum AggregatorError {
     /// Facilitator returned error
     #[error("Facilitator error: {0}")]
     FacilitatorError(String),
+
+    /// SRV resolution of the discovery source failed
+    #[error("SRV resolution failed: {0}")]
+    SrvError(#[from] peer_net::PeerNetError),
+
+    /// The facilitator's API key variable is not set
+    #[error("API key variable {0} is not set")]
+    MissingApiKey(String),
+
+    /// Discovery sources file is unreadable or invalid
+    #[error("Invalid discovery sources config: {0}")]
+    ConfigError(String),
+
+    /// No enabled facilitator has this id
+    #[error("Unknown discovery source: {0}")]
+    UnknownSource(String),
+
+    /// Another aggregation cycle is running
+    #[error("An aggregation cycle is already in progress")]
+    CycleInProgress,
+
+    /// None of the resource's payment requirements uses a scheme we can list
+    #[error("No supported payment scheme: {0}")]
+    UnsupportedScheme(String),
+
+    /// The facilitator's whole fetch exceeded its deadline
+    #[error("Fetch timed out after {0}s")]
+    Timeout(u64),
 }
 
 // ============================================================================
@@ -238,7 +347,10 @@ pub enum AggregatorError {
 // ============================================================================
 
 /// Configuration for an external facilitator to aggregate from.
-#[derive(Debug, Clone)]
+///
+/// The `Debug` output redacts header values and the API key, so configs can be logged
+/// safely.
+#[derive(Clone)]
 pub struct FacilitatorConfig {
     /// Unique identifier for this facilitator
     pub id: String,
@@ -250,6 +362,36 @@ pub struct FacilitatorConfig {
     pub enabled: bool,
     /// Request timeout in seconds
     pub timeout_secs: u64,
+    /// Extra headers sent with every discovery request
+    pub headers: Vec<(String, String)>,
+    /// Environment variable holding an API key, read on every fetch so keys never
+    /// live in config files. The facilitator is skipped while it is unset.
+    pub api_key_env: Option<String>,
+    /// API key supplied directly, used instead of `api_key_env`
+    pub api_key: Option<String>,
+    /// Header carrying the API key; `Authorization: Bearer <key>` when unset
+    pub api_key_header: Option<String>,
+    /// Minimum milliseconds between the starts of two page requests, for facilitators
+    /// with strict rate limits
+    pub min_request_interval_ms: Option<u64>,
+}
+
+impl std::fmt::Debug for FacilitatorConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let header_names: Vec<&str> = self.headers.iter().map(|(name, _)| name.as_str()).collect();
+        f.debug_struct("FacilitatorConfig")
+            .field("id", &self.id)
+            .field("name", &self.name)
+            .field("discovery_url", &self.discovery_url)
+            .field("enabled", &self.enabled)
+            .field("timeout_secs", &self.timeout_secs)
+            .field("headers", &header_names)
+            .field("api_key_env", &self.api_key_env)
+            .field("api_key", &self.api_key.as_ref().map(|_| "<redacted>"))
+            .field("api_key_header", &self.api_key_header)
+            .field("min_request_interval_ms", &self.min_request_interval_ms)
+            .finish()
+    }
 }
 
 impl FacilitatorConfig {
@@ -261,6 +403,11 @@ impl FacilitatorConfig {
             discovery_url: "https://api.cdp.coinbase.com/platform/v2/x402/discovery/resources".to_string(),
             enabled: true,
             timeout_secs: 30,
+            headers: Vec::new(),
+            api_key_env: None,
+            api_key: None,
+            api_key_header: None,
+            min_request_interval_ms: None,
         }
     }
 
@@ -272,6 +419,11 @@ impl FacilitatorConfig {
             discovery_url: "https://facilitator.payai.network/discovery/resources".to_string(),
             enabled: true,
             timeout_secs: 30,
+            headers: Vec::new(),
+            api_key_env: None,
+            api_key: None,
+            api_key_header: None,
+            min_request_interval_ms: None,
         }
     }
 
@@ -283,6 +435,11 @@ impl FacilitatorConfig {
             discovery_url: "https://api.thirdweb.com/v1/payments/x402/discovery/resources".to_string(),
             enabled: true,
             timeout_secs: 30,
+            headers: Vec::new(),
+            api_key_env: None,
+            api_key: None,
+            api_key_header: None,
+            min_request_interval_ms: None,
         }
     }
 
@@ -294,6 +451,11 @@ impl FacilitatorConfig {
             discovery_url: "https://facilitator.questflow.ai/discovery/resources".to_string(),
             enabled: true,
             timeout_secs: 30,
+            headers: Vec::new(),
+            api_key_env: None,
+            api_key: None,
+            api_key_header: None,
+            min_request_interval_ms: None,
         }
     }
 
@@ -305,6 +467,11 @@ impl FacilitatorConfig {
             discovery_url: "https://x402-facilitator.aurracloud.com/discovery/resources".to_string(),
             enabled: true,
             timeout_secs: 30,
+            headers: Vec::new(),
+            api_key_env: None,
+            api_key: None,
+            api_key_header: None,
+            min_request_interval_ms: None,
         }
     }
 
@@ -316,6 +483,11 @@ impl FacilitatorConfig {
             discovery_url: "https://mainnet.anyspend.com/x402/discovery/resources".to_string(),
             enabled: true,
             timeout_secs: 30,
+            headers: Vec::new(),
+            api_key_env: None,
+            api_key: None,
+            api_key_header: None,
+            min_request_interval_ms: None,
         }
     }
 
@@ -327,6 +499,11 @@ impl FacilitatorConfig {
             discovery_url: "https://open.x402.host/discovery/resources".to_string(),
             enabled: true,
             timeout_secs: 30,
+            headers: Vec::new(),
+            api_key_env: None,
+            api_key: None,
+            api_key_header: None,
+            min_request_interval_ms: None,
         }
     }
 
@@ -338,6 +515,11 @@ impl FacilitatorConfig {
             discovery_url: "https://facilitator.x402.rs/discovery/resources".to_string(),
             enabled: true,
             timeout_secs: 30,
+            headers: Vec::new(),
+            api_key_env: None,
+            api_key: None,
+            api_key_header: None,
+            min_request_interval_ms: None,
         }
     }
 
@@ -349,6 +531,11 @@ impl FacilitatorConfig {
             discovery_url: "https://facilitator.heurist.xyz/discovery/resources".to_string(),
             enabled: true,
             timeout_secs: 30,
+            headers: Vec::new(),
+            api_key_env: None,
+            api_key: None,
+            api_key_header: None,
+            min_request_interval_ms: None,
         }
     }
 
@@ -360,6 +547,11 @@ impl FacilitatorConfig {
             discovery_url: "https://api.polymer.zone/x402/v1/discovery/resources".to_string(),
             enabled: true,
             timeout_secs: 30,
+            headers: Vec::new(),
+            api_key_env: None,
+            api_key: None,
+            api_key_header: None,
+            min_request_interval_ms: None,
         }
     }
 
@@ -371,6 +563,11 @@ impl FacilitatorConfig {
             discovery_url: "https://api.mrdn.finance/discovery/resources".to_string(),
             enabled: true,
             timeout_secs: 30,
+            headers: Vec::new(),
+            api_key_env: None,
+            api_key: None,
+            api_key_header: None,
+            min_request_interval_ms: None,
         }
     }
 
@@ -382,6 +579,11 @@ impl FacilitatorConfig {
             discovery_url: "https://acpx.virtuals.io/discovery/resources".to_string(),
             enabled: true,
             timeout_secs: 30,
+            headers: Vec::new(),
+            api_key_env: None,
+            api_key: None,
+            api_key_header: None,
+            min_request_interval_ms: None,
         }
     }
 
@@ -402,6 +604,319 @@ impl FacilitatorConfig {
             Self::virtuals(),
         ]
     }
+
+    /// Config for a facilitator that is not built in, by its base `url`.
+    ///
+    /// Resources are fetched from `{url}/discovery/resources`. Use [`probe`](Self::probe)
+    /// to check that the facilitator answers before adding it.
+    pub fn custom(id: &str, url: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            name: id.to_string(),
+            discovery_url: format!("{}/discovery/resources", url.trim().trim_end_matches('/')),
+            enabled: true,
+            timeout_secs: 30,
+            headers: Vec::new(),
+            api_key_env: None,
+            api_key: None,
+            api_key_header: None,
+            min_request_interval_ms: None,
+        }
+    }
+
+    /// Probe the facilitator at base `url` and build its [`custom`](Self::custom) config.
+    ///
+    /// Calls `GET {url}/supported`, which must answer with a `kinds` array holding at
+    /// least one payment kind we understand; kinds with unknown schemes are ignored.
+    /// The id is the `facilitatorId` the response advertises, when it has a usable one,
+    /// and otherwise `custom-` followed by a hash of the URL, so it is stable across
+    /// restarts.
+    pub async fn probe(url: &str) -> Result<FacilitatorConfig, AggregatorError> {
+        let parsed = Url::parse(url.trim())
+            .map_err(|e| AggregatorError::InvalidUrl(format!("{}: {}", url, e)))?;
+        if !matches!(parsed.scheme(), "http" | "https") {
+            return Err(AggregatorError::InvalidUrl(format!(
+                "{}: not an HTTP URL",
+                url
+            )));
+        }
+        let base = parsed.as_str().trim_end_matches('/').to_string();
+
+        let client = Client::builder()
+            .timeout(Duration::from_secs(PROBE_TIMEOUT_SECS))
+            .user_agent("x402-rs-aggregator/1.0")
+            .build()?;
+        let response = client.get(format!("{}/supported", base)).send().await?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(AggregatorError::FacilitatorError(format!(
+                "GET {}/supported returned {}",
+                base, status
+            )));
+        }
+        let body: Value = response
+            .json()
+            .await
+            .map_err(|e| AggregatorError::ParseError(format!("{}/supported: {}", base, e)))?;
+        let kinds = supported_kinds(&body)
+            .map_err(|e| AggregatorError::ParseError(format!("{}/supported: {}", base, e)))?;
+        if kinds.is_empty() {
+            return Err(AggregatorError::FacilitatorError(format!(
+                "{} supports no payment kind we understand",
+                base
+            )));
+        }
+
+        let id = advertised_id(&body).unwrap_or_else(|| url_id(&base));
+        info!(id = %id, url = %base, kinds = kinds.len(), "Probed custom facilitator");
+        Ok(Self::custom(&id, &base))
+    }
+
+    /// Probe every `EXTRA_FACILITATOR_URLS` entry and add it to `facilitators`.
+    ///
+    /// A URL that fails its probe, or whose id is already taken, is logged and skipped.
+    pub async fn extend_from_env(facilitators: &mut Vec<Self>) {
+        if let Some(urls) = env_list("EXTRA_FACILITATOR_URLS") {
+            extend_with_probes(facilitators, &urls).await;
+        }
+    }
+
+    /// Load facilitators from a sources file, merged over the built-in list.
+    ///
+    /// Files ending in `.toml` are parsed as TOML, anything else as JSON. See
+    /// [`FacilitatorSources`] for the format and merge rules.
+    pub fn from_file(path: &Path) -> Result<Vec<Self>, AggregatorError> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| AggregatorError::ConfigError(format!("{}: {}", path.display(), e)))?;
+        let sources: FacilitatorSources = if path.extension().is_some_and(|ext| ext == "toml") {
+            toml::from_str(&content)
+                .map_err(|e| AggregatorError::ConfigError(format!("{}: {}", path.display(), e)))?
+        } else {
+            serde_json::from_str(&content)
+                .map_err(|e| AggregatorError::ConfigError(format!("{}: {}", path.display(), e)))?
+        };
+        sources.resolve()
+    }
+
+    /// Headers for a discovery request: the configured ones plus the API key, if any.
+    ///
+    /// Fails with [`AggregatorError::MissingApiKey`] when `api_key_env` is set but the
+    /// variable is not, rather than sending an empty credential.
+    pub fn request_headers(&self) -> Result<Vec<(String, String)>, AggregatorError> {
+        let mut headers = self.headers.clone();
+        let key = match (&self.api_key, &self.api_key_env) {
+            (Some(key), _) => Some(key.clone()),
+            (None, Some(env)) => Some(
+                crate::env_registry::configured_var(env)
+                    .filter(|key| !key.trim().is_empty())
+                    .ok_or_else(|| AggregatorError::MissingApiKey(env.clone()))?,
+            ),
+            (None, None) => None,
+        };
+        if let Some(key) = key {
+            match &self.api_key_header {
+                Some(header) => headers.push((header.clone(), key)),
+                None => headers.push(("authorization".to_string(), format!("Bearer {}", key))),
+            }
+        }
+        Ok(headers)
+    }
+
+    /// Load facilitators from `DISCOVERY_SOURCES_FILE`, or the built-in list when unset.
+    pub fn from_env() -> Result<Vec<Self>, AggregatorError> {
+        match crate::env_registry::var("DISCOVERY_SOURCES_FILE") {
+            Some(path) => Self::from_file(Path::new(&path)),
+            None => Ok(Self::all()),
+        }
+    }
+}
+
+/// Timeout of a [`FacilitatorConfig::probe`] request, in seconds.
+const PROBE_TIMEOUT_SECS: u64 = 10;
+
+/// Probe `urls` concurrently and add the facilitators that answer to `facilitators`.
+async fn extend_with_probes(facilitators: &mut Vec<FacilitatorConfig>, urls: &[String]) {
+    let probes =
+        futures::future::join_all(urls.iter().map(|url| FacilitatorConfig::probe(url))).await;
+    for (url, probe) in urls.iter().zip(probes) {
+        match probe {
+            Ok(config) if facilitators.iter().any(|existing| existing.id == config.id) => {
+                warn!(url = %url, id = %config.id, "Skipping extra facilitator with a duplicate id");
+            }
+            Ok(config) => facilitators.push(config),
+            Err(e) => {
+                warn!(url = %url, error = %e, "Skipping extra facilitator that failed its probe")
+            }
+        }
+    }
+}
+
+/// Payment kinds of a `GET /supported` response, without the ones we cannot parse.
+fn supported_kinds(body: &Value) -> Result<Vec<SupportedPaymentKindV2>, String> {
+    let kinds = body
+        .get("kinds")
+        .and_then(Value::as_array)
+        .ok_or_else(|| "no kinds array".to_string())?;
+    Ok(kinds
+        .iter()
+        .filter_map(|kind| serde_json::from_value(kind.clone()).ok())
+        .collect())
+}
+
+/// The `facilitatorId` a `GET /supported` response advertises, if it makes a usable id.
+fn advertised_id(body: &Value) -> Option<String> {
+    let id = body.get("facilitatorId")?.as_str()?.trim();
+    let usable = !id.is_empty()
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    usable.then(|| id.to_ascii_lowercase())
+}
+
+/// Id of a facilitator that advertises none, derived from its base URL.
+fn url_id(base: &str) -> String {
+    let digest = Sha256::digest(base.as_bytes());
+    format!("custom-{}", hex::encode(&digest[..4]))
+}
+
+/// Contents of a discovery sources file.
+///
+/// ```toml
+/// # Keep the built-in facilitators (default), disable one and add another
+/// [[facilitators]]
+/// id = "coinbase"
+/// enabled = false
+///
+/// [[facilitators]]
+/// id = "partner"
+/// name = "Partner Bazaar"
+/// discovery_url = "https://partner.example.com/discovery/resources"
+/// headers = { "x-client" = "ultravioleta" }
+/// api_key_env = "PARTNER_DISCOVERY_KEY"
+/// api_key_header = "x-secret-key"
+/// ```
+///
+/// An entry whose id matches a built-in facilitator overrides only the fields it
+/// sets; other entries add a facilitator and must set `discovery_url`. Unknown
+/// fields are ignored and duplicate ids are rejected.
+#[derive(Debug, Clone, Deserialize)]
+pub struct FacilitatorSources {
+    /// Start from [`FacilitatorConfig::all`] (default) rather than an empty list
+    #[serde(default = "default_include_builtin")]
+    pub include_builtin: bool,
+    #[serde(default)]
+    pub facilitators: Vec<FacilitatorEntry>,
+}
+
+fn default_include_builtin() -> bool {
+    true
+}
+
+/// One facilitator in a [`FacilitatorSources`] file.
+#[derive(Debug, Clone, Deserialize)]
+pub struct FacilitatorEntry {
+    pub id: String,
+    pub name: Option<String>,
+    pub discovery_url: Option<String>,
+    pub enabled: Option<bool>,
+    pub timeout_secs: Option<u64>,
+    pub headers: Option<BTreeMap<String, String>>,
+    pub api_key_env: Option<String>,
+    pub api_key_header: Option<String>,
+    pub min_request_interval_ms: Option<u64>,
+}
+
+impl FacilitatorSources {
+    /// Merge the entries over the built-in list (when included).
+    pub fn resolve(self) -> Result<Vec<FacilitatorConfig>, AggregatorError> {
+        let mut seen = HashSet::new();
+        if let Some(entry) = self
+            .facilitators
+            .iter()
+            .find(|entry| !seen.insert(entry.id.as_str()))
+        {
+            return Err(AggregatorError::ConfigError(format!(
+                "duplicate facilitator id {}",
+                entry.id
+            )));
+        }
+
+        let mut configs = if self.include_builtin {
+            FacilitatorConfig::all()
+        } else {
+            Vec::new()
+        };
+        for entry in self.facilitators {
+            match configs.iter_mut().find(|config| config.id == entry.id) {
+                Some(config) => entry.apply(config),
+                None => {
+                    let discovery_url = entry.discovery_url.clone().ok_or_else(|| {
+                        AggregatorError::ConfigError(format!(
+                            "facilitator {} has no discovery_url",
+                            entry.id
+                        ))
+                    })?;
+                    let mut config = FacilitatorConfig {
+                        id: entry.id.clone(),
+                        name: entry.id.clone(),
+                        discovery_url,
+                        enabled: true,
+                        timeout_secs: 30,
+                        headers: Vec::new(),
+                        api_key_env: None,
+                        api_key: None,
+                        api_key_header: None,
+                        min_request_interval_ms: None,
+                    };
+                    entry.apply(&mut config);
+                    configs.push(config);
+                }
+            }
+        }
+
+        for config in &configs {
+            let valid = match SrvSource::parse(&config.discovery_url) {
+                Some(source) => source.is_ok(),
+                None => Url::parse(&config.discovery_url).is_ok(),
+            };
+            if !valid {
+                return Err(AggregatorError::ConfigError(format!(
+                    "facilitator {} has an invalid discovery_url {}",
+                    config.id, config.discovery_url
+                )));
+            }
+        }
+        Ok(configs)
+    }
+}
+
+impl FacilitatorEntry {
+    fn apply(self, config: &mut FacilitatorConfig) {
+        if let Some(name) = self.name {
+            config.name = name;
+        }
+        if let Some(discovery_url) = self.discovery_url {
+            config.discovery_url = discovery_url;
+        }
+        if let Some(enabled) = self.enabled {
+            config.enabled = enabled;
+        }
+        if let Some(timeout_secs) = self.timeout_secs {
+            config.timeout_secs = timeout_secs;
+        }
+        if let Some(headers) = self.headers {
+            config.headers = headers.into_iter().collect();
+        }
+        if let Some(api_key_env) = self.api_key_env {
+            config.api_key_env = Some(api_key_env);
+        }
+        if let Some(api_key_header) = self.api_key_header {
+            config.api_key_header = Some(api_key_header);
+        }
+        if let Some(interval) = self.min_request_interval_ms {
+            config.min_request_interval_ms = Some(interval);
+        }
+    }
 }
 
 // ============================================================================
@@ -429,6 +944,12 @@ pub struct CoinbaseResource {
     /// Metadata
     #[serde(default)]
     pub metadata: Option<CoinbaseMetadata>,
+    /// Protocol version of the listing; aggregated resources are republished as v2
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub x402_version: Option<u8>,
+    /// Any other fields, carried into the resource metadata
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
 }
 
 /// Coinbase payment requirement (v1-style network names).
@@ -441,17 +962,21 @@ pub struct CoinbasePaymentRequirement {
     pub network: Option<String>,
     /// Token asset address
     pub asset: Option<String>,
-    /// Amount required
-    #[serde(alias = "maxAmountRequired")]
+    /// Amount required: base units, or human units when decimal or `$`-prefixed
     pub amount: Option<String>,
+    /// v1 name of the amount, always in base units
+    pub max_amount_required: Option<String>,
     /// Pay-to address
     pub pay_to: Option<String>,
     /// Max timeout
     pub max_timeout_seconds: Option<u64>,
+    /// Any other fields (`extra`, `outputSchema`, `description`, ...), see [`carry_accept_extra`]
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
 }
 
 /// Coinbase metadata format.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CoinbaseMetadata {
     pub category: Option<String>,
@@ -475,6 +1000,9 @@ pub struct CoinbasePagination {
     pub limit: Option<u32>,
     pub offset: Option<u32>,
     pub total: Option<u32>,
+    /// Cursor of the next page, for facilitators using cursor pagination
+    #[serde(default, alias = "next_cursor", alias = "cursor", skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
 }
 
 /// Wrapped discovery response (some facilitators wrap in "data" object).
@@ -493,15 +1021,327 @@ pub struct AlternativeDiscoveryResponse {
     pub pagination: Option<CoinbasePagination>,
 }
 
+// ============================================================================
+// Aggregation Report
+// ============================================================================
+
+/// Outcome of fetching a single facilitator during an aggregation cycle.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SourceResult {
+    /// Facilitator id (see [`FacilitatorConfig::id`])
+    pub facilitator_id: String,
+    /// Items returned by the facilitator, across all pages
+    pub fetched: usize,
+    /// Items converted to v2 resources
+    pub converted: usize,
+    /// Items dropped because they could not be converted
+    pub skipped: usize,
+    /// Time spent on this facilitator, in milliseconds
+    pub duration_ms: u64,
+    /// Why the fetch failed, if it did
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Circuit breaker state of the facilitator after this cycle
+    #[serde(default, skip_serializing_if = "SourceBreaker::is_healthy")]
+    pub breaker: SourceBreaker,
+    /// The facilitator answered `304 Not Modified`: counts are zero and the resources
+    /// of its last full fetch were reused
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub unchanged: bool,
+}
+
+/// Default consecutive failures after which a facilitator is skipped.
+pub const DEFAULT_BREAKER_THRESHOLD: u32 = 3;
+
+/// Cool-down after a facilitator first trips its breaker; doubles on every re-trip.
+const BREAKER_BASE_COOLDOWN: Duration = Duration::from_secs(300);
+
+/// Longest a facilitator is skipped for.
+const BREAKER_MAX_COOLDOWN: Duration = Duration::from_secs(6 * 3600);
+
+/// Circuit breaker of one facilitator, so that a failing source is not fetched every cycle.
+///
+/// After `threshold` consecutive failures the source is skipped until `open_until`. The
+/// first fetch after the cool-down is a probe: a failure re-opens the breaker for twice as
+/// long (capped at 6 hours), a success resets it.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SourceBreaker {
+    /// Failed fetches since the last success
+    pub consecutive_failures: u32,
+    /// Times the breaker opened since the last success
+    pub trips: u32,
+    /// Unix timestamp until which the facilitator is skipped
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub open_until: Option<u64>,
+}
+
+impl SourceBreaker {
+    /// No failures since the last success.
+    pub fn is_healthy(&self) -> bool {
+        self.consecutive_failures == 0
+    }
+
+    /// Whether the facilitator should be skipped at `now` (Unix seconds).
+    pub fn is_open(&self, now: u64) -> bool {
+        self.open_until.is_some_and(|until| now < until)
+    }
+
+    /// Record a failed fetch, returning the cool-down if this opened the breaker.
+    ///
+    /// A `threshold` of 0 never opens it.
+    pub fn record_failure(&mut self, now: u64, threshold: u32) -> Option<Duration> {
+        self.consecutive_failures = self.consecutive_failures.saturating_add(1);
+        if threshold == 0 || self.consecutive_failures < threshold {
+            return None;
+        }
+        let cooldown = BREAKER_BASE_COOLDOWN
+            .saturating_mul(1 << self.trips.min(16))
+            .min(BREAKER_MAX_COOLDOWN);
+        self.trips = self.trips.saturating_add(1);
+        self.open_until = Some(now + cooldown.as_secs());
+        Some(cooldown)
+    }
+
+    /// Record a successful fetch, returning whether the breaker had tripped.
+    pub fn record_success(&mut self) -> bool {
+        let tripped = self.trips > 0;
+        *self = Self::default();
+        tripped
+    }
+}
+
+/// Outcome of fetching one facilitator's catalog.
+#[derive(Debug)]
+enum CatalogFetch {
+    /// Converted resources and the number of items fetched before conversion
+    Fetched(Vec<DiscoveryResource>, usize),
+    /// The first page answered `304 Not Modified`
+    Unchanged,
+}
+
+/// Last full catalog of a facilitator that sent cache validators.
+#[derive(Debug, Clone)]
+struct CachedCatalog {
+    etag: Option<String>,
+    last_modified: Option<String>,
+    resources: Vec<DiscoveryResource>,
+}
+
+/// Per-facilitator results of one aggregation cycle.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AggregationReport {
+    /// Unix timestamp at which the cycle finished fetching
+    pub completed_at: u64,
+    /// One entry per enabled facilitator, in configuration order
+    pub per_source: Vec<SourceResult>,
+}
+
+impl AggregationReport {
+    /// Sources whose fetch failed.
+    pub fn failed(&self) -> impl Iterator<Item = &SourceResult> {
+        self.per_source.iter().filter(|source| source.error.is_some())
+    }
+}
+
+/// Live statistics of one facilitator, kept across aggregation cycles.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SourceStats {
+    /// Facilitator id (see [`FacilitatorConfig::id`])
+    pub id: String,
+    pub name: String,
+    pub enabled: bool,
+    /// Unix timestamp of the last fetch attempt; fetches skipped by the breaker don't count
+    pub last_fetch_at: Option<u64>,
+    /// Unix timestamp of the last successful fetch
+    pub last_success_at: Option<u64>,
+    /// Resources the facilitator listed in its last successful fetch
+    pub resources: usize,
+    /// Why the last fetch failed; cleared by a success
+    pub last_error: Option<String>,
+    pub breaker: SourceBreaker,
+    /// Unix timestamp of the next fetch, once the background task has scheduled one
+    pub next_fetch_at: Option<u64>,
+}
+
+impl SourceStats {
+    fn new(config: &FacilitatorConfig) -> Self {
+        Self {
+            id: config.id.clone(),
+            name: config.name.clone(),
+            enabled: config.enabled,
+            last_fetch_at: None,
+            last_success_at: None,
+            resources: 0,
+            last_error: None,
+            breaker: SourceBreaker::default(),
+            next_fetch_at: None,
+        }
+    }
+}
+
+/// Live statistics per facilitator id, shared between the aggregator and HTTP handlers.
+pub type SharedSourceStats = Arc<DashMap<String, SourceStats>>;
+
+/// Latest aggregation report, shared between the background task and HTTP handlers.
+///
+/// `None` until the first cycle has run.
+pub type SharedAggregationReport = Arc<tokio::sync::RwLock<Option<AggregationReport>>>;
+
 // ============================================================================
 // Discovery Aggregator
 // ============================================================================
 
+/// Default number of facilitators fetched concurrently.
+pub const DEFAULT_FETCH_CONCURRENCY: usize = 4;
+
+/// Default maximum number of pages fetched from one facilitator per cycle.
+pub const DEFAULT_MAX_PAGES: usize = 100;
+
+/// Default consecutive successful cycles a resource may be missing from its source
+/// before it is evicted.
+pub const DEFAULT_STALE_CYCLES: u32 = 3;
+
+/// Default attempts per page request, the first one included.
+pub const DEFAULT_FETCH_ATTEMPTS: u32 = 3;
+
+/// Default delay before the first retry of a page request; it doubles on every retry.
+pub const DEFAULT_RETRY_BACKOFF: Duration = Duration::from_millis(500);
+
+/// How the background task resolves imports of registered resources: payment options
+/// are merged, and locally curated metadata survives listings that carry none.
+pub const AGGREGATION_CONFLICT_STRATEGIES: &[ConflictStrategy] = &[
+    ConflictStrategy::MergeAccepts,
+    ConflictStrategy::KeepLocalMetadata,
+];
+
+/// Default total time a facilitator's fetch may spend waiting on its rate limits.
+pub const DEFAULT_RATE_LIMIT_BUDGET: Duration = Duration::from_secs(60);
+
+/// Restricts which aggregated payment methods are imported.
+///
+/// Accepts entries failing the filter are dropped, and a resource left without any
+/// accepts is skipped. The default filter lets everything through.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AggregatorFilter {
+    /// Networks payment methods must use; `None` allows every network
+    pub allowed_networks: Option<Vec<Caip2NetworkId>>,
+    /// Assets payment methods must be priced in; `None` allows every asset
+    pub allowed_assets: Option<Vec<MixedAddress>>,
+    /// Drop payment methods with a zero amount
+    pub require_nonzero_amount: bool,
+}
+
+impl AggregatorFilter {
+    /// Read the filter from `DISCOVERY_ALLOWED_NETWORKS` (CAIP-2 ids),
+    /// `DISCOVERY_ALLOWED_ASSETS` (token addresses) and `DISCOVERY_REQUIRE_NONZERO_AMOUNT`.
+    ///
+    /// Lists are comma-separated; unset or empty lists allow everything.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AggregatorError::ConfigError` if a network or asset cannot be parsed.
+    pub fn from_env() -> Result<Self, AggregatorError> {
+        let allowed_networks = env_list("DISCOVERY_ALLOWED_NETWORKS")
+            .map(|networks| {
+                networks
+                    .iter()
+                    .map(|network| {
+                        network.parse::<Caip2NetworkId>().map_err(|e| {
+                            AggregatorError::ConfigError(format!(
+                                "DISCOVERY_ALLOWED_NETWORKS: {network}: {e}"
+                            ))
+                        })
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .transpose()?;
+        let allowed_assets = env_list("DISCOVERY_ALLOWED_ASSETS")
+            .map(|assets| {
+                assets
+                    .iter()
+                    .map(|asset| {
+                        parse_address(asset).ok_or_else(|| {
+                            AggregatorError::ConfigError(format!(
+                                "DISCOVERY_ALLOWED_ASSETS: invalid address {asset}"
+                            ))
+                        })
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .transpose()?;
+        Ok(Self {
+            allowed_networks,
+            allowed_assets,
+            require_nonzero_amount: crate::env_registry::flag("DISCOVERY_REQUIRE_NONZERO_AMOUNT"),
+        })
+    }
+
+    /// Whether the filter restricts anything.
+    pub fn is_active(&self) -> bool {
+        self.allowed_networks.is_some() || self.allowed_assets.is_some() || self.require_nonzero_amount
+    }
+
+    /// Whether a payment method passes the filter.
+    pub fn allows(&self, requirement: &PaymentRequirementsV2) -> bool {
+        self.allowed_networks
+            .as_ref()
+            .is_none_or(|networks| networks.contains(&requirement.network))
+            && self
+                .allowed_assets
+                .as_ref()
+                .is_none_or(|assets| assets.contains(&requirement.asset))
+            && !(self.require_nonzero_amount && requirement.amount.0.is_zero())
+    }
+}
+
+/// Comma-separated entries of a list variable, or `None` if it is unset or empty.
+fn env_list(name: &str) -> Option<Vec<String>> {
+    let entries: Vec<String> = crate::env_registry::var(name)?
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(str::to_string)
+        .collect();
+    (!entries.is_empty()).then_some(entries)
+}
+
 /// Aggregates discoverable resources from external facilitators.
 #[derive(Debug, Clone)]
 pub struct DiscoveryAggregator {
     client: Client,
     facilitators: Vec<FacilitatorConfig>,
+    /// Maximum number of facilitators fetched at once
+    concurrency: usize,
+    /// Maximum number of pages fetched from one facilitator
+    max_pages: usize,
+    /// Resolver for `srv://` discovery sources
+    srv_resolver: Option<Arc<dyn SrvResolver>>,
+    /// Connection failures per facilitator id
+    connection_stats: Arc<DashMap<String, ConnectionStats>>,
+    /// Consecutive failures that open a facilitator's breaker
+    breaker_threshold: u32,
+    /// Circuit breakers per facilitator id
+    breakers: Arc<DashMap<String, SourceBreaker>>,
+    /// Catalogs with `ETag`/`Last-Modified` per facilitator id, for conditional requests
+    catalogs: Arc<DashMap<String, CachedCatalog>>,
+    /// Successful cycles a resource may be missing from its source before eviction
+    stale_cycles: u32,
+    /// Live statistics per facilitator id
+    stats: SharedSourceStats,
+    /// Payment methods imported
+    filter: AggregatorFilter,
+    /// Canonical categories and tags of imported resources
+    taxonomy: Arc<Taxonomy>,
+    /// Attempts per page request, the first one included
+    max_attempts: u32,
+    /// Delay before the first retry of a page request
+    retry_backoff: Duration,
+    /// Total wait a facilitator's fetch may spend on `Retry-After`/`X-RateLimit-Reset`
+    rate_limit_budget: Duration,
 }
 
 impl Default for DiscoveryAggregator {
@@ -522,10 +1362,35 @@ impl DiscoveryAggregator {
         Self {
             client,
             facilitators: FacilitatorConfig::all(),
+            concurrency: DEFAULT_FETCH_CONCURRENCY,
+            max_pages: DEFAULT_MAX_PAGES,
+            srv_resolver: default_srv_resolver(),
+            connection_stats: Arc::new(DashMap::new()),
+            breaker_threshold: DEFAULT_BREAKER_THRESHOLD,
+            breakers: Arc::new(DashMap::new()),
+            catalogs: Arc::new(DashMap::new()),
+            stale_cycles: DEFAULT_STALE_CYCLES,
+            stats: SharedSourceStats::default(),
+            filter: AggregatorFilter::default(),
+            taxonomy: Arc::new(Taxonomy::builtin()),
+            max_attempts: DEFAULT_FETCH_ATTEMPTS,
+            retry_backoff: DEFAULT_RETRY_BACKOFF,
+            rate_limit_budget: DEFAULT_RATE_LIMIT_BUDGET,
         }
+        .with_stats(SharedSourceStats::default())
     }
 
     /// Create an aggregator with custom facilitator configs.
+    ///
+    /// Facilitators that are not built in can be added by URL, probing them first:
+    ///
+    /// ```rust,ignore
+    /// let mut facilitators = FacilitatorConfig::all();
+    /// facilitators.push(FacilitatorConfig::probe("https://facilitator.example.com").await?);
+    /// // Or without the round trip, trusting the URL
+    /// facilitators.push(FacilitatorConfig::custom("partner", "https://partner.example.com"));
+    /// let aggregator = DiscoveryAggregator::with_facilitators(facilitators);
+    /// ```
     pub fn with_facilitators(facilitators: Vec<FacilitatorConfig>) -> Self {
         let client = Client::builder()
             .timeout(Duration::from_secs(60))
@@ -533,64 +1398,500 @@ impl DiscoveryAggregator {
             .build()
             .expect("Failed to create HTTP client");
 
-        Self { client, facilitators }
+        Self {
+            client,
+            facilitators,
+            concurrency: DEFAULT_FETCH_CONCURRENCY,
+            max_pages: DEFAULT_MAX_PAGES,
+            srv_resolver: default_srv_resolver(),
+            connection_stats: Arc::new(DashMap::new()),
+            breaker_threshold: DEFAULT_BREAKER_THRESHOLD,
+            breakers: Arc::new(DashMap::new()),
+            catalogs: Arc::new(DashMap::new()),
+            stale_cycles: DEFAULT_STALE_CYCLES,
+            stats: SharedSourceStats::default(),
+            filter: AggregatorFilter::default(),
+            taxonomy: Arc::new(Taxonomy::builtin()),
+            max_attempts: DEFAULT_FETCH_ATTEMPTS,
+            retry_backoff: DEFAULT_RETRY_BACKOFF,
+            rate_limit_budget: DEFAULT_RATE_LIMIT_BUDGET,
+        }
+        .with_stats(SharedSourceStats::default())
     }
 
-    /// Fetch resources from all enabled facilitators.
-    pub async fn fetch_all(&self) -> Vec<DiscoveryResource> {
-        let mut all_resources = Vec::new();
+    /// Fetch up to `concurrency` facilitators at once (at least one).
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
 
-        for config in &self.facilitators {
-            if !config.enabled {
-                debug!(facilitator = %config.id, "Skipping disabled facilitator");
-                continue;
-            }
+    /// Stop following a facilitator's pagination after `max_pages` pages (at least one).
+    pub fn with_max_pages(mut self, max_pages: usize) -> Self {
+        self.max_pages = max_pages.max(1);
+        self
+    }
 
-            match self.fetch_from_facilitator(config).await {
-                Ok(resources) => {
-                    info!(
-                        facilitator = %config.id,
-                        count = resources.len(),
-                        "Fetched resources from facilitator"
-                    );
-                    all_resources.extend(resources);
-                }
-                Err(e) => {
-                    error!(
-                        facilitator = %config.id,
-                        error = %e,
-                        "Failed to fetch from facilitator"
-                    );
-                }
-            }
-        }
+    /// Use a custom resolver for `srv://` discovery sources.
+    pub fn with_srv_resolver(mut self, resolver: Arc<dyn SrvResolver>) -> Self {
+        self.srv_resolver = Some(resolver);
+        self
+    }
 
-        info!(total = all_resources.len(), "Total resources aggregated");
-        all_resources
+    /// Skip a facilitator after `threshold` consecutive failed fetches; 0 disables the breaker.
+    pub fn with_breaker_threshold(mut self, threshold: u32) -> Self {
+        self.breaker_threshold = threshold;
+        self
     }
 
-    /// Fetch resources from a specific facilitator.
-    async fn fetch_from_facilitator(
-        &self,
-        config: &FacilitatorConfig,
-    ) -> Result<Vec<DiscoveryResource>, AggregatorError> {
-        info!(facilitator = %config.id, url = %config.discovery_url, "Fetching from facilitator");
+    /// Evict aggregated resources after their source has omitted them from
+    /// `cycles` consecutive successful fetches (at least one).
+    pub fn with_stale_cycles(mut self, cycles: u32) -> Self {
+        self.stale_cycles = cycles.max(1);
+        self
+    }
 
-        // Fetch with pagination - try to get all resources
-        let mut all_resources = Vec::new();
-        let mut offset = 0;
+    /// Make up to `attempts` attempts (at least one) per page request.
+    ///
+    /// Only timeouts, connection errors, `429` and 5xx responses are retried.
+    pub fn with_max_attempts(mut self, attempts: u32) -> Self {
+        self.max_attempts = attempts.max(1);
+        self
+    }
+
+    /// Wait `backoff` before the first retry of a page request, doubling on every retry.
+    pub fn with_retry_backoff(mut self, backoff: Duration) -> Self {
+        self.retry_backoff = backoff;
+        self
+    }
+
+    /// Wait at most `budget` in total on the rate limits of a facilitator's fetch.
+    ///
+    /// A `Retry-After` or `X-RateLimit-Reset` asking for more than what is left fails
+    /// the fetch instead.
+    pub fn with_rate_limit_budget(mut self, budget: Duration) -> Self {
+        self.rate_limit_budget = budget;
+        self
+    }
+
+    /// Only import payment methods passing `filter`.
+    pub fn with_filter(mut self, filter: AggregatorFilter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// Normalize the categories and tags of imported resources with `taxonomy`.
+    pub fn with_taxonomy(mut self, taxonomy: Taxonomy) -> Self {
+        self.taxonomy = Arc::new(taxonomy);
+        self
+    }
+
+    /// Keep live per-facilitator statistics in `stats`, e.g. to serve them over HTTP.
+    ///
+    /// Every configured facilitator gets an entry right away.
+    pub fn with_stats(mut self, stats: SharedSourceStats) -> Self {
+        for config in &self.facilitators {
+            stats
+                .entry(config.id.clone())
+                .and_modify(|entry| {
+                    entry.name = config.name.clone();
+                    entry.enabled = config.enabled;
+                })
+                .or_insert_with(|| SourceStats::new(config));
+        }
+        self.stats = stats;
+        self
+    }
+
+    /// Live statistics of every configured facilitator.
+    pub fn stats(&self) -> SharedSourceStats {
+        Arc::clone(&self.stats)
+    }
+
+    /// Circuit breaker state of a facilitator.
+    pub fn breaker(&self, facilitator_id: &str) -> SourceBreaker {
+        self.breakers
+            .get(facilitator_id)
+            .map(|b| b.clone())
+            .unwrap_or_default()
+    }
+
+    /// Connection failure counters for a facilitator.
+    pub fn connection_stats(&self, facilitator_id: &str) -> ConnectionStats {
+        self.connection_stats
+            .get(facilitator_id)
+            .map(|s| s.clone())
+            .unwrap_or_default()
+    }
+
+    /// Fetch resources from all enabled facilitators.
+    ///
+    /// Thin wrapper over [`fetch_all_with_report`](Self::fetch_all_with_report) for
+    /// callers that only need the resources.
+    pub async fn fetch_all(&self) -> Vec<DiscoveryResource> {
+        self.fetch_all_with_report().await.0
+    }
+
+    /// Fetch resources from all enabled facilitators, with a per-source report.
+    ///
+    /// Facilitators are fetched concurrently (see [`with_concurrency`](Self::with_concurrency)),
+    /// so a slow source only delays its own results. A failed facilitator is logged,
+    /// recorded in the report and skipped. Resources and report entries are returned in
+    /// configuration order regardless of completion order.
+    ///
+    /// Resources listed by several facilitators are merged (see [`dedup_resources`]).
+    pub async fn fetch_all_with_report(&self) -> (Vec<DiscoveryResource>, AggregationReport) {
+        let mut fetched: Vec<(usize, Vec<DiscoveryResource>)> = Vec::new();
+        let report = self
+            .fetch_sources(None, |index, _, resources| {
+                fetched.push((index, resources));
+                std::future::ready(())
+            })
+            .await;
+        fetched.sort_by_key(|(index, _)| *index);
+
+        let all_resources: Vec<DiscoveryResource> =
+            fetched.into_iter().flat_map(|(_, resources)| resources).collect();
+        let listed = all_resources.len();
+        let all_resources = dedup_resources(all_resources);
+        info!(total = all_resources.len(), listed = listed, "Total resources aggregated");
+        (all_resources, report)
+    }
+
+    /// Fetch all enabled facilitators, calling `on_source` with each one's result and
+    /// resources as soon as its fetch completes.
+    ///
+    /// Callbacks run one at a time, in completion order. Resources are not merged across
+    /// facilitators. The returned report lists sources in configuration order.
+    pub async fn fetch_each<F, Fut>(&self, mut on_source: F) -> AggregationReport
+    where
+        F: FnMut(SourceResult, Vec<DiscoveryResource>) -> Fut,
+        Fut: std::future::Future<Output = ()>,
+    {
+        self.fetch_sources(None, |_, result, resources| on_source(result, resources))
+            .await
+    }
+
+    /// [`fetch_each`](Self::fetch_each), restricted to the facilitator with id `source`
+    /// when given, passing each facilitator's configuration index to `on_source`.
+    async fn fetch_sources<F, Fut>(&self, source: Option<&str>, mut on_source: F) -> AggregationReport
+    where
+        F: FnMut(usize, SourceResult, Vec<DiscoveryResource>) -> Fut,
+        Fut: std::future::Future<Output = ()>,
+    {
+        let enabled: Vec<usize> = self
+            .facilitators
+            .iter()
+            .enumerate()
+            .filter(|(_, config)| source.is_none() || source == Some(config.id.as_str()))
+            .filter(|(_, config)| {
+                if !config.enabled {
+                    debug!(facilitator = %config.id, "Skipping disabled facilitator");
+                }
+                config.enabled
+            })
+            .map(|(index, _)| index)
+            .collect();
+
+        let mut fetches = stream::iter(enabled)
+            .map(|index| self.fetch_source(index))
+            .buffer_unordered(self.concurrency);
+        let mut per_source: Vec<(usize, SourceResult)> = Vec::new();
+        while let Some((index, resources, result)) = fetches.next().await {
+            on_source(index, result.clone(), resources).await;
+            per_source.push((index, result));
+        }
+        per_source.sort_by_key(|(index, _)| *index);
+
+        AggregationReport {
+            completed_at: unix_now(),
+            per_source: per_source.into_iter().map(|(_, result)| result).collect(),
+        }
+    }
+
+    /// Fetch one facilitator for [`fetch_each`](Self::fetch_each), logging and timing
+    /// the outcome.
+    ///
+    /// Facilitators whose [`SourceBreaker`] is open are not contacted. The fetch is
+    /// abandoned after the facilitator's `timeout_secs` plus the rate limit budget, and
+    /// counts as a failure.
+    async fn fetch_source(&self, index: usize) -> (usize, Vec<DiscoveryResource>, SourceResult) {
+        let config = &self.facilitators[index];
+        let mut result = SourceResult {
+            facilitator_id: config.id.clone(),
+            fetched: 0,
+            converted: 0,
+            skipped: 0,
+            duration_ms: 0,
+            error: None,
+            breaker: self.breaker(&config.id),
+            unchanged: false,
+        };
+        if let Some(until) = result.breaker.open_until.filter(|&until| unix_now() < until) {
+            debug!(facilitator = %config.id, open_until = until, "Skipping facilitator while its circuit breaker is open");
+            result.error = Some(format!("circuit breaker open until {until}"));
+            self.record_stats(&result, None);
+            return (index, Vec::new(), result);
+        }
+
+        let started = Instant::now();
+        let deadline = Duration::from_secs(config.timeout_secs) + self.rate_limit_budget;
+        let outcome = tokio::time::timeout(deadline, self.fetch_from_facilitator(config))
+            .await
+            .unwrap_or(Err(AggregatorError::Timeout(deadline.as_secs())));
+        result.duration_ms = started.elapsed().as_millis() as u64;
+        if !matches!(outcome, Err(AggregatorError::MissingApiKey(_))) {
+            result.breaker = self.record_outcome(&config.id, outcome.is_ok(), unix_now());
+        }
+
+        let (index, resources, result) = match outcome {
+            Ok(CatalogFetch::Unchanged) => {
+                let resources = self
+                    .catalogs
+                    .get(&config.id)
+                    .map(|cached| cached.resources.clone())
+                    .unwrap_or_default();
+                result.unchanged = true;
+                info!(
+                    facilitator = %config.id,
+                    count = resources.len(),
+                    duration_ms = result.duration_ms,
+                    "Facilitator catalog unchanged"
+                );
+                (index, resources, result)
+            }
+            Ok(CatalogFetch::Fetched(resources, fetched)) => {
+                result.fetched = fetched;
+                result.converted = resources.len();
+                result.skipped = fetched.saturating_sub(resources.len());
+                info!(
+                    facilitator = %config.id,
+                    count = resources.len(),
+                    skipped = result.skipped,
+                    duration_ms = result.duration_ms,
+                    "Fetched resources from facilitator"
+                );
+                (index, resources, result)
+            }
+            Err(e @ AggregatorError::MissingApiKey(_)) => {
+                warn!(facilitator = %config.id, error = %e, "Facilitator disabled until its API key is set");
+                result.error = Some(e.to_string());
+                (index, Vec::new(), result)
+            }
+            Err(e) => {
+                error!(
+                    facilitator = %config.id,
+                    error = %e,
+                    "Failed to fetch from facilitator"
+                );
+                result.error = Some(e.to_string());
+                (index, Vec::new(), result)
+            }
+        };
+        crate::metrics::record_discovery_fetch(&result);
+        self.record_stats(&result, Some(resources.len()));
+        (index, resources, result)
+    }
+
+    /// Update a facilitator's [`SourceStats`] after a fetch.
+    ///
+    /// `resources` is `None` when the facilitator was skipped without being contacted.
+    fn record_stats(&self, result: &SourceResult, resources: Option<usize>) {
+        let Some(mut stats) = self.stats.get_mut(&result.facilitator_id) else {
+            return;
+        };
+        stats.breaker = result.breaker.clone();
+        let Some(resources) = resources else {
+            return;
+        };
+        let now = unix_now();
+        stats.last_fetch_at = Some(now);
+        match &result.error {
+            None => {
+                stats.last_success_at = Some(now);
+                stats.resources = resources;
+                stats.last_error = None;
+            }
+            Some(error) => stats.last_error = Some(error.clone()),
+        }
+    }
+
+    /// Record when each enabled facilitator is fetched next, given the next cycle's time.
+    ///
+    /// A facilitator whose breaker is open waits for the first cycle after its cool-down.
+    fn schedule_next_fetch(&self, next_cycle: u64) {
+        for mut stats in self.stats.iter_mut() {
+            stats.next_fetch_at = stats.enabled.then(|| {
+                let skipped_until = stats.breaker.open_until.unwrap_or(0);
+                next_cycle.max(skipped_until)
+            });
+        }
+    }
+
+    /// Fetch resources from a specific facilitator, with the number of items it returned.
+    ///
+    /// `srv://` sources are resolved on every call; when a target fails to connect,
+    /// the next target in priority/weight order is tried.
+    async fn fetch_from_facilitator(
+        &self,
+        config: &FacilitatorConfig,
+    ) -> Result<CatalogFetch, AggregatorError> {
+        let headers = config.request_headers()?;
+        info!(facilitator = %config.id, url = %config.discovery_url, "Fetching from facilitator");
+
+        let candidates = match self.resolve_discovery_urls(config).await {
+            Ok(candidates) => candidates,
+            Err(e) => {
+                if matches!(e, AggregatorError::SrvError(_)) {
+                    self.record_failure(&config.id, peer_net::ConnectFailure::Resolution);
+                }
+                return Err(e);
+            }
+        };
+
+        let mut last_error = None;
+        for (attempt, base_url) in candidates.iter().enumerate() {
+            if attempt > 0 {
+                self.connection_stats
+                    .entry(config.id.clone())
+                    .or_default()
+                    .srv_fallbacks += 1;
+                debug!(facilitator = %config.id, url = %base_url, "Trying next SRV target");
+            }
+            match self.fetch_pages(config, base_url, &headers).await {
+                Err(AggregatorError::HttpError(e)) => match peer_net::classify_error(&e) {
+                    Some(failure) => {
+                        self.record_failure(&config.id, failure);
+                        warn!(facilitator = %config.id, url = %base_url, failure = ?failure, "Connection failed");
+                        last_error = Some(AggregatorError::HttpError(e));
+                    }
+                    None => return Err(AggregatorError::HttpError(e)),
+                },
+                result => return result,
+            }
+        }
+        Err(last_error.unwrap_or_else(|| AggregatorError::InvalidUrl(config.discovery_url.clone())))
+    }
+
+    /// Candidate base URLs for a facilitator, in the order they should be tried.
+    async fn resolve_discovery_urls(
+        &self,
+        config: &FacilitatorConfig,
+    ) -> Result<Vec<Url>, AggregatorError> {
+        match SrvSource::parse(&config.discovery_url) {
+            Some(source) => {
+                let source = source?;
+                let resolver = self.srv_resolver.as_ref().ok_or_else(|| {
+                    AggregatorError::InvalidUrl(format!(
+                        "{}: SRV sources require the `srv` feature",
+                        config.discovery_url
+                    ))
+                })?;
+                Ok(source.resolve(resolver.as_ref()).await?)
+            }
+            None => Url::parse(&config.discovery_url)
+                .map(|url| vec![url])
+                .map_err(|e| AggregatorError::InvalidUrl(format!("{}: {}", config.discovery_url, e))),
+        }
+    }
+
+    /// Update a facilitator's breaker after a fetch, logging transitions.
+    fn record_outcome(&self, facilitator_id: &str, success: bool, now: u64) -> SourceBreaker {
+        let mut breaker = self.breakers.entry(facilitator_id.to_string()).or_default();
+        if success {
+            if breaker.record_success() {
+                info!(facilitator = %facilitator_id, "Facilitator recovered, circuit breaker closed");
+            }
+        } else if let Some(cooldown) = breaker.record_failure(now, self.breaker_threshold) {
+            warn!(
+                facilitator = %facilitator_id,
+                consecutive_failures = breaker.consecutive_failures,
+                cooldown_secs = cooldown.as_secs(),
+                "Circuit breaker opened, skipping facilitator"
+            );
+        }
+        breaker.clone()
+    }
+
+    fn record_failure(&self, facilitator_id: &str, failure: peer_net::ConnectFailure) {
+        self.connection_stats
+            .entry(facilitator_id.to_string())
+            .or_default()
+            .record(failure);
+    }
+
+    /// Fetch all pages from a single discovery endpoint.
+    ///
+    /// Follows `nextCursor` while the facilitator returns one, and `limit`/`offset`
+    /// against `total` otherwise. At most [`with_max_pages`](Self::with_max_pages) pages
+    /// are fetched, so a misbehaving server cannot keep the task looping.
+    ///
+    /// The first page is requested conditionally when the last catalog came with
+    /// validators; a `304` there ends the fetch with [`CatalogFetch::Unchanged`].
+    async fn fetch_pages(
+        &self,
+        config: &FacilitatorConfig,
+        base_url: &Url,
+        headers: &[(String, String)],
+    ) -> Result<CatalogFetch, AggregatorError> {
+        let cached = self
+            .catalogs
+            .get(&config.id)
+            .map(|cached| (cached.etag.clone(), cached.last_modified.clone()));
+        let mut validators = (None, None);
+
+        // Fetch with pagination - try to get all resources
+        let mut all_resources = Vec::new();
+        let mut fetched = 0;
+        let mut offset = 0;
+        let mut cursor: Option<String> = None;
         let limit = 100;
+        let mut last_request: Option<Instant> = None;
+        let mut rate_limit_budget = self.rate_limit_budget;
+
+        for page in 1..=self.max_pages {
+            if let (Some(interval), Some(last)) = (config.min_request_interval_ms, last_request) {
+                let wait = Duration::from_millis(interval).saturating_sub(last.elapsed());
+                if !wait.is_zero() {
+                    tokio::time::sleep(wait).await;
+                }
+            }
+            last_request = Some(Instant::now());
 
-        loop {
-            let url = format!("{}?limit={}&offset={}", config.discovery_url, limit, offset);
+            let url = match &cursor {
+                Some(cursor) => cursor_page_url(base_url, limit, cursor),
+                None => page_url(base_url, limit, offset),
+            };
 
+            let mut request = self.client.get(url);
+            for (name, value) in headers {
+                request = request.header(name, value);
+            }
+            if let (1, Some((etag, last_modified))) = (page, &cached) {
+                if let Some(etag) = etag {
+                    request = request.header(IF_NONE_MATCH, etag);
+                }
+                if let Some(last_modified) = last_modified {
+                    request = request.header(IF_MODIFIED_SINCE, last_modified);
+                }
+            }
             let response = self
-                .client
-                .get(&url)
-                .timeout(Duration::from_secs(config.timeout_secs))
-                .send()
+                .send_with_retry(config, request, &mut rate_limit_budget)
                 .await?;
 
+            if page == 1 {
+                if cached.is_some() && response.status() == StatusCode::NOT_MODIFIED {
+                    return Ok(CatalogFetch::Unchanged);
+                }
+                let header = |name| {
+                    response
+                        .headers()
+                        .get(name)
+                        .and_then(|value| value.to_str().ok())
+                        .map(str::to_string)
+                };
+                validators = (header(ETAG), header(LAST_MODIFIED));
+            }
+
             if !response.status().is_success() {
                 return Err(AggregatorError::FacilitatorError(format!(
                     "HTTP {}: {}",
@@ -605,23 +1906,148 @@ impl DiscoveryAggregator {
             let (items, pagination) = self.parse_discovery_response(&body, &config.id)?;
 
             let batch_count = items.len();
+            fetched += batch_count;
 
             // Convert to our format
             let resources = self.convert_coinbase_resources(items, &config.id);
             all_resources.extend(resources);
 
-            // Check if we need to fetch more
-            let total = pagination.as_ref().and_then(|p| p.total).unwrap_or(0);
-            offset += batch_count as u32;
+            // Cursor pagination: follow the cursor until the facilitator stops returning one
+            let next_cursor = pagination
+                .as_ref()
+                .and_then(|p| p.next_cursor.clone())
+                .filter(|c| !c.is_empty());
+            if let Some(next_cursor) = next_cursor {
+                if cursor.as_ref() == Some(&next_cursor) {
+                    warn!(facilitator = %config.id, "Facilitator returned the same cursor twice, stopping");
+                    break;
+                }
+                cursor = Some(next_cursor);
+            } else if cursor.is_some() {
+                break;
+            } else {
+                // Check if we need to fetch more
+                let total = pagination.as_ref().and_then(|p| p.total).unwrap_or(0);
+                offset += batch_count as u32;
+
+                if batch_count < limit as usize || offset >= total {
+                    break;
+                }
+            }
 
-            if batch_count < limit as usize || offset >= total {
+            if page == self.max_pages {
+                warn!(facilitator = %config.id, max_pages = self.max_pages, "Page limit reached, results truncated");
                 break;
             }
+            debug!(page = page, offset = offset, "Fetching next page");
+        }
 
-            debug!(offset = offset, total = total, "Fetching next page");
+        match validators {
+            (None, None) => {
+                self.catalogs.remove(&config.id);
+            }
+            (etag, last_modified) => {
+                self.catalogs.insert(
+                    config.id.clone(),
+                    CachedCatalog {
+                        etag,
+                        last_modified,
+                        resources: all_resources.clone(),
+                    },
+                );
+            }
         }
+        Ok(CatalogFetch::Fetched(all_resources, fetched))
+    }
 
-        Ok(all_resources)
+    /// Send a page request, retrying timeouts, connection errors, `429` and 5xx responses.
+    ///
+    /// Attempts are spaced by an exponential backoff with jitter. All attempts share the
+    /// facilitator's `timeout_secs`: no retry is made once the next one could not start
+    /// within it. The last response is returned as is when retries run out, so the caller
+    /// reports its status.
+    ///
+    /// A `429` or `503` with a [`rate_limit_delay`] waits that long instead of the backoff.
+    /// Those waits don't count against `timeout_secs` but are taken from
+    /// `rate_limit_budget`, shared by all pages of the fetch; no retry is made when the
+    /// wait exceeds what is left of it.
+    async fn send_with_retry(
+        &self,
+        config: &FacilitatorConfig,
+        request: reqwest::RequestBuilder,
+        rate_limit_budget: &mut Duration,
+    ) -> Result<reqwest::Response, reqwest::Error> {
+        let budget = Duration::from_secs(config.timeout_secs);
+        let started = Instant::now();
+        let mut rate_limited = Duration::ZERO;
+        let mut attempt = 1;
+        loop {
+            let spent = started.elapsed().saturating_sub(rate_limited);
+            let remaining = budget.saturating_sub(spent);
+            let outcome = request
+                .try_clone()
+                .expect("GET requests have no streaming body")
+                .timeout(remaining)
+                .send()
+                .await;
+            let retryable = match &outcome {
+                Ok(response) => is_retryable_status(response.status()),
+                Err(e) => is_retryable_error(e),
+            };
+            if !retryable || attempt >= self.max_attempts {
+                return outcome;
+            }
+            let requested = match &outcome {
+                Ok(response)
+                    if matches!(
+                        response.status(),
+                        StatusCode::TOO_MANY_REQUESTS | StatusCode::SERVICE_UNAVAILABLE
+                    ) =>
+                {
+                    rate_limit_delay(response.headers(), unix_now())
+                }
+                _ => None,
+            };
+            if let Some(delay) = requested {
+                if delay > *rate_limit_budget {
+                    warn!(
+                        facilitator = %config.id,
+                        delay_secs = delay.as_secs(),
+                        "Rate limit wait exceeds the budget, not retrying"
+                    );
+                    return outcome;
+                }
+                info!(
+                    facilitator = %config.id,
+                    attempt = attempt,
+                    delay_secs = delay.as_secs(),
+                    "Discovery request rate limited, waiting"
+                );
+                *rate_limit_budget -= delay;
+                rate_limited += delay;
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+                continue;
+            }
+            let delay = retry_delay(self.retry_backoff, attempt);
+            if started.elapsed().saturating_sub(rate_limited) + delay >= budget {
+                debug!(facilitator = %config.id, attempt = attempt, "Timeout reached, not retrying");
+                return outcome;
+            }
+            let reason = match &outcome {
+                Ok(response) => response.status().to_string(),
+                Err(e) => e.to_string(),
+            };
+            warn!(
+                facilitator = %config.id,
+                attempt = attempt,
+                reason = %reason,
+                delay_ms = delay.as_millis() as u64,
+                "Discovery request failed, retrying"
+            );
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
     }
 
     /// Parse discovery response, trying multiple formats.
@@ -660,6 +2086,7 @@ impl DiscoveryAggregator {
         }
 
         // All formats failed
+        crate::metrics::record_discovery_parse_error(facilitator_id);
         let preview = &body[..500.min(body.len())];
         Err(AggregatorError::ParseError(format!(
             "Unknown response format from {}: {}",
@@ -677,6 +2104,14 @@ impl DiscoveryAggregator {
 
         for cb_resource in resources {
             match self.convert_single_resource(cb_resource, facilitator_id) {
+                Ok(mut resource) if self.filter.is_active() => {
+                    resource.accepts.retain(|req| self.filter.allows(req));
+                    if resource.accepts.is_empty() {
+                        debug!(url = %resource.url, "Skipping resource without allowed payment methods");
+                    } else {
+                        converted.push(resource);
+                    }
+                }
                 Ok(resource) => converted.push(resource),
                 Err(e) => {
                     debug!(error = %e, "Skipping resource due to conversion error");
@@ -697,6 +2132,12 @@ impl DiscoveryAggregator {
         let url = Url::parse(&cb.url)
             .map_err(|e| AggregatorError::InvalidUrl(format!("{}: {}", cb.url, e)))?;
 
+        // Skip resources payable only with schemes we cannot represent
+        let any_supported = cb.accepts.iter().any(|req| parse_scheme(req.scheme.as_deref()).is_some());
+        if !cb.accepts.is_empty() && !any_supported {
+            return Err(AggregatorError::UnsupportedScheme(cb.url));
+        }
+
         // Convert payment requirements
         let accepts: Vec<PaymentRequirementsV2> = cb
             .accepts
@@ -705,12 +2146,7 @@ impl DiscoveryAggregator {
             .collect();
 
         // Use default timestamp if not provided
-        let last_updated = cb.last_updated.unwrap_or_else(|| {
-            std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap_or_default()
-                .as_secs()
-        });
+        let last_updated = cb.last_updated.unwrap_or_else(unix_now);
 
         // Create resource with aggregation source
         let mut resource = DiscoveryResource::from_aggregation(
@@ -722,12 +2158,20 @@ impl DiscoveryAggregator {
             last_updated,
         );
 
-        // Convert metadata if present
-        if let Some(meta) = cb.metadata {
+        // Convert metadata if present, keeping fields we do not model
+        if cb.metadata.is_some() || !cb.extra.is_empty() {
+            let meta = cb.metadata.unwrap_or_default();
             resource.metadata = Some(DiscoveryMetadata {
-                category: meta.category,
+                category: meta
+                    .category
+                    .as_deref()
+                    .and_then(|category| self.taxonomy.normalize_category(category)),
                 provider: meta.provider,
-                tags: meta.tags,
+                tags: self.taxonomy.normalize_tags(meta.tags),
+                sources: Vec::new(),
+                liveness: None,
+                reputation: None,
+                extra: cb.extra,
             });
         }
 
@@ -736,32 +2180,50 @@ impl DiscoveryAggregator {
 
     /// Convert a Coinbase payment requirement to v2 format.
     fn convert_payment_requirement(&self, req: CoinbasePaymentRequirement) -> Option<PaymentRequirementsV2> {
+        let Some(scheme) = parse_scheme(req.scheme.as_deref()) else {
+            debug!(scheme = ?req.scheme, "Skipping payment requirement with an unsupported scheme");
+            return None;
+        };
+
         // Parse network - Coinbase uses v1 names like "base", "base-mainnet"
         let network_str = req.network.as_deref()?;
         let network = self.parse_network_to_caip2(network_str)?;
 
         // Parse asset address
         let asset_str = req.asset.as_deref()?;
-        let asset = self.parse_address(asset_str)?;
+        let asset = parse_address(asset_str)?;
 
         // Parse pay_to address
         let pay_to_str = req.pay_to.as_deref()?;
-        let pay_to = self.parse_address(pay_to_str)?;
+        let pay_to = parse_address(pay_to_str)?;
 
-        // Parse amount (assumed to be in smallest units, e.g., 1000000 = 1 USDC)
-        let amount_str = req.amount.as_deref().unwrap_or("0");
-        let amount = U256::from_str(amount_str)
-            .map(TokenAmount::from)
-            .unwrap_or_else(|_| TokenAmount::from(0u64));
+        // Prefer whichever field is already in base units (e.g. 1000000 = 1 USDC)
+        let amount_str = [&req.amount, &req.max_amount_required]
+            .into_iter()
+            .flatten()
+            .find(|a| is_base_units(a))
+            .or(req.amount.as_ref())
+            .or(req.max_amount_required.as_ref())
+            .map_or("0", String::as_str);
+        let decimals = known_token_decimals(&network, &asset);
+        let Some(amount) = parse_token_amount(amount_str, decimals) else {
+            debug!(
+                amount = amount_str,
+                network = %network,
+                asset = asset_str,
+                "Skipping payment requirement with an amount that cannot be converted to base units"
+            );
+            return None;
+        };
 
         Some(PaymentRequirementsV2 {
-            scheme: Scheme::Exact,
+            scheme,
             network,
             asset,
             amount,
             pay_to,
             max_timeout_seconds: req.max_timeout_seconds.unwrap_or(300),
-            extra: None,
+            extra: carry_accept_extra(req.extra),
         })
     }
 
@@ -769,6 +2231,8 @@ impl DiscoveryAggregator {
     fn parse_network_to_caip2(&self, network: &str) -> Option<Caip2NetworkId> {
         // Handle common v1 network names
         let chain_id = match network.to_lowercase().as_str() {
+            "solana" | "solana-mainnet" => return Caip2NetworkId::solana(SOLANA_MAINNET_GENESIS).ok(),
+            "solana-devnet" => return Caip2NetworkId::solana(SOLANA_DEVNET_GENESIS).ok(),
             "base" | "base-mainnet" => 8453,
             "base-sepolia" => 84532,
             "ethereum" | "mainnet" | "ethereum-mainnet" => 1,
@@ -783,9 +2247,13 @@ impl DiscoveryAggregator {
             "avalanche-fuji" | "fuji" => 43113,
             "celo" | "celo-mainnet" => 42220,
             "celo-alfajores" | "alfajores" => 44787,
+            "zksync" | "zksync-era" | "zksync-mainnet" => 324,
+            "zksync-sepolia" | "zksync-era-sepolia" => 300,
+            "scroll" | "scroll-mainnet" => 534352,
+            "scroll-sepolia" => 534351,
             _ => {
                 // Try to parse as CAIP-2 directly
-                if network.starts_with("eip155:") {
+                if network.starts_with("eip155:") || network.starts_with("solana:") {
                     return Caip2NetworkId::parse(network).ok();
                 }
                 // Try to parse as number
@@ -796,18 +2264,281 @@ impl DiscoveryAggregator {
         Some(Caip2NetworkId::eip155(chain_id))
     }
 
-    /// Parse an address string to MixedAddress.
-    fn parse_address(&self, addr: &str) -> Option<MixedAddress> {
-        // Try EVM address first
-        if addr.starts_with("0x") && addr.len() == 42 {
-            addr.parse().ok().map(MixedAddress::Evm)
+}
+
+/// Whether a response status is worth retrying: `429 Too Many Requests` and server errors.
+fn is_retryable_status(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+/// `X-RateLimit-Reset` values from this one on are Unix times, smaller ones are seconds
+/// to wait.
+const RATE_LIMIT_RESET_EPOCH_THRESHOLD: u64 = 1_000_000_000;
+
+/// How long a rate-limited response asks to wait, from `Retry-After` (seconds or an
+/// HTTP-date) or else `X-RateLimit-Reset` (seconds or a Unix time). Times in the past
+/// yield zero.
+fn rate_limit_delay(headers: &HeaderMap, now: u64) -> Option<Duration> {
+    let header = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(str::trim)
+    };
+    let retry_after = header(RETRY_AFTER.as_str()).and_then(|value| match value.parse::<u64>() {
+        Ok(seconds) => Some(seconds),
+        Err(_) => parse_timestamp(value).map(|at| at.saturating_sub(now)),
+    });
+    let seconds = retry_after.or_else(|| {
+        let reset = header("x-ratelimit-reset")?.parse::<u64>().ok()?;
+        if reset >= RATE_LIMIT_RESET_EPOCH_THRESHOLD {
+            Some(epoch_seconds(reset).saturating_sub(now))
         } else {
-            // Could be Solana or other - for now just skip non-EVM
+            Some(reset)
+        }
+    })?;
+    Some(Duration::from_secs(seconds))
+}
+
+/// Whether a failed request is worth retrying: timeouts and connection errors.
+fn is_retryable_error(error: &reqwest::Error) -> bool {
+    error.is_timeout() || error.is_connect()
+}
+
+/// Delay before retry number `attempt` (1-based): `backoff` doubled on every retry, plus
+/// up to 50% jitter so sources failing together don't retry in lockstep.
+fn retry_delay(backoff: Duration, attempt: u32) -> Duration {
+    let delay = backoff.saturating_mul(1 << (attempt - 1).min(16));
+    delay + delay.mul_f64(rand::random::<f64>() / 2.0)
+}
+
+/// Map a v1 scheme name to [`Scheme`]; requirements without one are `exact`.
+///
+/// Returns `None` for schemes we do not know, so they are skipped rather than listed
+/// under the wrong pricing model.
+fn parse_scheme(scheme: Option<&str>) -> Option<Scheme> {
+    let Some(scheme) = scheme else {
+        return Some(Scheme::Exact);
+    };
+    match scheme.trim().to_ascii_lowercase().as_str() {
+        "exact" => Some(Scheme::Exact),
+        "upto" | "up-to" => Some(Scheme::Upto),
+        _ => None,
+    }
+}
+
+/// Parse an address string to MixedAddress.
+///
+/// EVM, Algorand and Solana addresses are recognized (see [`MixedAddress::detect`]);
+/// other chains are not aggregated yet.
+fn parse_address(addr: &str) -> Option<MixedAddress> {
+    match MixedAddress::detect(addr) {
+        Ok(address) => Some(address),
+        Err(e) => {
+            debug!(error = %e, "Skipping unrecognized address");
             None
         }
     }
 }
 
+/// Build the v2 `extra` of an aggregated accept from its unmodeled fields.
+///
+/// The origin's own `extra` object is kept as is, and fields v2 moved out of payment
+/// requirements (`outputSchema`, `description`, `mimeType`, ...) are added next to its
+/// entries, so Bazaar clients can still render input forms. The origin's entries win on
+/// a name clash.
+fn carry_accept_extra(mut fields: Map<String, Value>) -> Option<Value> {
+    let origin = fields.remove("extra").filter(|extra| !extra.is_null());
+    if fields.is_empty() {
+        return origin;
+    }
+    match origin {
+        Some(Value::Object(mut extra)) => {
+            for (key, value) in fields {
+                extra.entry(key).or_insert(value);
+            }
+            Some(Value::Object(extra))
+        }
+        Some(other) => {
+            fields.insert("extra".to_string(), other);
+            Some(Value::Object(fields))
+        }
+        None => Some(Value::Object(fields)),
+    }
+}
+
+/// Whether `amount` is a plain integer of base units.
+fn is_base_units(amount: &str) -> bool {
+    let amount = amount.trim();
+    !amount.is_empty() && amount.bytes().all(|b| b.is_ascii_digit())
+}
+
+/// Decimals of a known token deployment (USDC, EURC, ...) on `network`.
+fn known_token_decimals(network: &Caip2NetworkId, asset: &MixedAddress) -> Option<u8> {
+    let network = Network::from_caip2(&network.to_string())?;
+    find_token_deployment(network, asset).map(|deployment| deployment.decimals)
+}
+
+/// Parse an amount to base units.
+///
+/// Plain integers are already base units. Amounts with a decimal point or a `$` prefix
+/// (`"0.01"`, `"$0.001"`) are human units and are scaled by `decimals`; without known
+/// decimals, or with more fractional digits than the token has, they are rejected.
+fn parse_token_amount(raw: &str, decimals: Option<u8>) -> Option<TokenAmount> {
+    let raw = raw.trim();
+    let (human, amount) = match raw.strip_prefix('$') {
+        Some(amount) => (true, amount.trim_start()),
+        None => (raw.contains('.'), raw),
+    };
+    if !human {
+        return is_base_units(amount)
+            .then(|| U256::from_str_radix(amount, 10).ok())
+            .flatten()
+            .map(TokenAmount::from);
+    }
+
+    let decimals = usize::from(decimals?);
+    let (whole, fraction) = amount.split_once('.').unwrap_or((amount, ""));
+    let digits_only = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+    if (whole.is_empty() && fraction.is_empty())
+        || !digits_only(whole)
+        || !digits_only(fraction)
+        || fraction.len() > decimals
+    {
+        return None;
+    }
+    let base_units = format!("{}{}{}", whole, fraction, "0".repeat(decimals - fraction.len()));
+    U256::from_str_radix(&base_units, 10).ok().map(TokenAmount::from)
+}
+
+/// Build a paginated discovery URL.
+///
+/// Uses proper query handling so existing query parameters and IPv6 literal hosts
+/// (`http://[::1]:8080/...`) survive unchanged.
+fn page_url(base: &Url, limit: u32, offset: u32) -> Url {
+    let mut url = base.clone();
+    url.query_pairs_mut()
+        .append_pair("limit", &limit.to_string())
+        .append_pair("offset", &offset.to_string());
+    url
+}
+
+/// Build a cursor-paginated discovery URL.
+fn cursor_page_url(base: &Url, limit: u32, cursor: &str) -> Url {
+    let mut url = base.clone();
+    url.query_pairs_mut()
+        .append_pair("limit", &limit.to_string())
+        .append_pair("cursor", cursor);
+    url
+}
+
+/// Normalize a resource URL so that equivalent spellings compare equal.
+///
+/// Lowercases the scheme and host, drops default ports, credentials, the fragment and
+/// trailing slashes, and sorts query parameters: `https://API.example.com:443/data/?b=2&a=1`
+/// and `https://api.example.com/data?a=1&b=2` both normalize to the latter.
+pub fn normalize_resource_url(url: &Url) -> String {
+    let mut normalized = format!("{}://", url.scheme().to_ascii_lowercase());
+    if let Some(host) = url.host_str() {
+        normalized.push_str(&host.to_ascii_lowercase());
+    }
+    // `Url` already omits the default port of special schemes
+    if let Some(port) = url.port() {
+        normalized.push_str(&format!(":{}", port));
+    }
+    normalized.push_str(url.path().trim_end_matches('/'));
+
+    let mut pairs: Vec<(String, String)> = url.query_pairs().into_owned().collect();
+    if !pairs.is_empty() {
+        pairs.sort();
+        let query = url::form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(pairs)
+            .finish();
+        normalized.push('?');
+        normalized.push_str(&query);
+    }
+    normalized
+}
+
+/// Merge resources that share a [normalized URL](normalize_resource_url).
+///
+/// The listing with the newest `last_updated` wins (the earliest one on ties), gains
+/// the payment options of the others, and records every listing facilitator in
+/// `metadata.sources`. Resources keep the position of their first listing.
+pub fn dedup_resources(resources: Vec<DiscoveryResource>) -> Vec<DiscoveryResource> {
+    let mut positions: HashMap<String, usize> = HashMap::new();
+    let mut groups: Vec<Vec<DiscoveryResource>> = Vec::new();
+    for resource in resources {
+        match positions.entry(normalize_resource_url(&resource.url)) {
+            Entry::Occupied(entry) => groups[*entry.get()].push(resource),
+            Entry::Vacant(entry) => {
+                entry.insert(groups.len());
+                groups.push(vec![resource]);
+            }
+        }
+    }
+    groups.into_iter().map(merge_listings).collect()
+}
+
+fn merge_listings(mut listings: Vec<DiscoveryResource>) -> DiscoveryResource {
+    if listings.len() == 1 {
+        return listings.remove(0);
+    }
+    // Listings merged earlier in a cycle carry their facilitators in `metadata.sources`
+    let mut sources: Vec<String> = Vec::new();
+    let ids = listings.iter().flat_map(|l| {
+        let merged = l.metadata.as_ref().map(|m| m.sources.as_slice()).unwrap_or(&[]);
+        merged.iter().chain(l.source_facilitator.iter())
+    });
+    for id in ids {
+        if !sources.contains(id) {
+            sources.push(id.clone());
+        }
+    }
+
+    let newest = (1..listings.len()).fold(0, |newest, i| {
+        if listings[i].last_updated > listings[newest].last_updated {
+            i
+        } else {
+            newest
+        }
+    });
+    let mut merged = listings.remove(newest);
+    for other in listings {
+        for accept in other.accepts {
+            if !merged.accepts.contains(&accept) {
+                merged.accepts.push(accept);
+            }
+        }
+        if merged.metadata.is_none() {
+            merged.metadata = other.metadata;
+        }
+    }
+    if sources.len() > 1 {
+        merged.metadata.get_or_insert_with(Default::default).sources = sources;
+    }
+    merged
+}
+
+fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// SRV resolver used by default, when the `srv` feature is enabled.
+fn default_srv_resolver() -> Option<Arc<dyn SrvResolver>> {
+    #[cfg(feature = "srv")]
+    {
+        match peer_net::HickorySrvResolver::from_system_conf() {
+            Ok(resolver) => return Some(Arc::new(resolver)),
+            Err(e) => warn!(error = %e, "Failed to initialize SRV resolver"),
+        }
+    }
+    None
+}
+
 // ============================================================================
 // Background Aggregation Task
 // ============================================================================
@@ -818,65 +2549,1234 @@ impl DiscoveryAggregator {
 ///
 /// * `registry` - The discovery registry to import into
 /// * `interval_secs` - How often to run aggregation (in seconds)
+/// * `report` - Updated with the report of each completed cycle
+/// * `stats` - Updated with live per-facilitator statistics
 ///
-/// Returns a handle that can be used to abort the task.
-pub fn start_aggregation_task(
+/// Returns a handle to trigger cycles and shut the task down, once the facilitators in
+/// `EXTRA_FACILITATOR_URLS` have been probed.
+pub async fn start_aggregation_task(
     registry: crate::discovery::DiscoveryRegistry,
     interval_secs: u64,
-) -> tokio::task::JoinHandle<()> {
+    report: SharedAggregationReport,
+    stats: SharedSourceStats,
+) -> AggregationTaskHandle {
     info!(interval_secs = interval_secs, "Starting discovery aggregation background task");
 
-    tokio::spawn(async move {
-        let aggregator = DiscoveryAggregator::new();
-        let interval = Duration::from_secs(interval_secs);
-
-        // Run immediately on startup
-        run_aggregation(&aggregator, &registry).await;
-
-        // Then run periodically
-        loop {
-            tokio::time::sleep(interval).await;
-            run_aggregation(&aggregator, &registry).await;
+    let concurrency = crate::env_registry::parse::<usize>("DISCOVERY_AGGREGATION_CONCURRENCY")
+        .unwrap_or(DEFAULT_FETCH_CONCURRENCY);
+    let mut facilitators = match FacilitatorConfig::from_env() {
+        Ok(facilitators) => facilitators,
+        Err(e) => {
+            error!(error = %e, "Discovery aggregation disabled");
+            return AggregationTaskHandle::stopped();
+        }
+    };
+    FacilitatorConfig::extend_from_env(&mut facilitators).await;
+    let max_pages = crate::env_registry::parse::<usize>("DISCOVERY_AGGREGATION_MAX_PAGES")
+        .unwrap_or(DEFAULT_MAX_PAGES);
+    let breaker_threshold =
+        crate::env_registry::parse::<u32>("DISCOVERY_AGGREGATION_BREAKER_THRESHOLD")
+            .unwrap_or(DEFAULT_BREAKER_THRESHOLD);
+    let stale_cycles = crate::env_registry::parse::<u32>("DISCOVERY_AGGREGATION_STALE_CYCLES")
+        .unwrap_or(DEFAULT_STALE_CYCLES);
+    let max_attempts = crate::env_registry::parse::<u32>("DISCOVERY_AGGREGATION_MAX_ATTEMPTS")
+        .unwrap_or(DEFAULT_FETCH_ATTEMPTS);
+    let rate_limit_budget =
+        crate::env_registry::parse::<u64>("DISCOVERY_AGGREGATION_RATE_LIMIT_BUDGET")
+            .map(Duration::from_secs)
+            .unwrap_or(DEFAULT_RATE_LIMIT_BUDGET);
+    let filter = match AggregatorFilter::from_env() {
+        Ok(filter) => filter,
+        Err(e) => {
+            error!(error = %e, "Discovery aggregation disabled");
+            return AggregationTaskHandle::stopped();
+        }
+    };
+    let taxonomy = match Taxonomy::from_env() {
+        Ok(taxonomy) => taxonomy,
+        Err(e) => {
+            error!(error = %e, "Discovery aggregation disabled");
+            return AggregationTaskHandle::stopped();
         }
-    })
+    };
+    let aggregator = DiscoveryAggregator::with_facilitators(facilitators)
+        .with_concurrency(concurrency)
+        .with_max_pages(max_pages)
+        .with_breaker_threshold(breaker_threshold)
+        .with_stale_cycles(stale_cycles)
+        .with_max_attempts(max_attempts)
+        .with_rate_limit_budget(rate_limit_budget)
+        .with_filter(filter)
+        .with_taxonomy(taxonomy)
+        .with_stats(stats);
+
+    AggregationTaskHandle::spawn(aggregator, registry, Duration::from_secs(interval_secs), report)
 }
 
-/// Run a single aggregation cycle.
-async fn run_aggregation(
-    aggregator: &DiscoveryAggregator,
-    registry: &crate::discovery::DiscoveryRegistry,
-) {
-    info!("Running discovery aggregation cycle");
+/// Runs aggregation cycles for the background task and on demand, one at a time.
+///
+/// Cycles started with [`try_run`](Self::try_run) run in their own task, so they finish
+/// their import even if the caller goes away.
+#[derive(Clone)]
+pub struct AggregationRunner {
+    aggregator: Arc<DiscoveryAggregator>,
+    registry: crate::discovery::DiscoveryRegistry,
+    report: SharedAggregationReport,
+    /// Held for the duration of a cycle
+    cycle: Arc<tokio::sync::Mutex<()>>,
+}
 
-    let resources = aggregator.fetch_all().await;
+impl AggregationRunner {
+    pub fn new(
+        aggregator: DiscoveryAggregator,
+        registry: crate::discovery::DiscoveryRegistry,
+        report: SharedAggregationReport,
+    ) -> Self {
+        Self {
+            aggregator: Arc::new(aggregator),
+            registry,
+            report,
+            cycle: Arc::new(tokio::sync::Mutex::new(())),
+        }
+    }
 
-    if resources.is_empty() {
-        warn!("No resources fetched from external facilitators");
-        return;
+    /// Run a cycle over every facilitator, after the cycle in progress if any.
+    async fn run(&self) -> AggregationReport {
+        let _cycle = self.cycle.lock().await;
+        run_aggregation(&self.aggregator, &self.registry, &self.report, None).await
     }
 
-    match registry.bulk_import(resources, true).await {
-        Ok((added, updated, skipped)) => {
-            info!(
-                added = added,
-                updated = updated,
-                skipped = skipped,
-                "Discovery aggregation cycle completed"
-            );
-        }
-        Err(e) => {
-            error!(error = %e, "Failed to import aggregated resources");
+    /// Run a cycle now, over every facilitator or only the enabled one with id `source`.
+    ///
+    /// Fails with [`AggregatorError::CycleInProgress`] instead of waiting when a cycle is
+    /// already running, and with [`AggregatorError::UnknownSource`] for an unknown id.
+    pub async fn try_run(&self, source: Option<&str>) -> Result<AggregationReport, AggregatorError> {
+        if let Some(id) = source {
+            let known = self
+                .aggregator
+                .facilitators
+                .iter()
+                .any(|config| config.enabled && config.id == id);
+            if !known {
+                return Err(AggregatorError::UnknownSource(id.to_string()));
+            }
         }
+        let cycle = Arc::clone(&self.cycle)
+            .try_lock_owned()
+            .map_err(|_| AggregatorError::CycleInProgress)?;
+
+        let runner = self.clone();
+        let source = source.map(str::to_string);
+        let task = tokio::spawn(async move {
+            let _cycle = cycle;
+            run_aggregation(&runner.aggregator, &runner.registry, &runner.report, source.as_deref())
+                .await
+        });
+        Ok(task
+            .await
+            .unwrap_or_else(|e| std::panic::resume_unwind(e.into_panic())))
     }
 }
 
-// ============================================================================
-// Tests
-// ============================================================================
+/// Handle to the aggregation background task.
+///
+/// Dropping the handle leaves the task running; [`shutdown`](Self::shutdown) stops it
+/// between cycles, so an import is never cut off mid-write.
+#[derive(Debug)]
+pub struct AggregationTaskHandle {
+    cancel: CancellationToken,
+    trigger: mpsc::Sender<()>,
+    task: tokio::task::JoinHandle<()>,
+    runner: Option<AggregationRunner>,
+}
+
+impl AggregationTaskHandle {
+    /// Run a cycle right away, then every `interval` until shut down.
+    fn spawn(
+        aggregator: DiscoveryAggregator,
+        registry: crate::discovery::DiscoveryRegistry,
+        interval: Duration,
+        report: SharedAggregationReport,
+    ) -> Self {
+        let cancel = CancellationToken::new();
+        // One pending trigger is enough: it runs a cycle that sees every change so far
+        let (trigger, mut triggered) = mpsc::channel(1);
+        let token = cancel.clone();
+        let runner = AggregationRunner::new(aggregator, registry, report);
+        let task_runner = runner.clone();
+        let task = tokio::spawn(async move {
+            loop {
+                task_runner.run().await;
+                task_runner
+                    .aggregator
+                    .schedule_next_fetch(unix_now() + interval.as_secs());
+                tokio::select! {
+                    biased;
+                    _ = token.cancelled() => break,
+                    Some(()) = triggered.recv() => {
+                        info!("Discovery aggregation triggered");
+                    }
+                    _ = tokio::time::sleep(interval) => {}
+                }
+            }
+            info!("Discovery aggregation task stopped");
+        });
+        Self {
+            cancel,
+            trigger,
+            task,
+            runner: Some(runner),
+        }
+    }
+
+    /// A handle whose task has already exited.
+    fn stopped() -> Self {
+        let (trigger, _) = mpsc::channel(1);
+        Self {
+            cancel: CancellationToken::new(),
+            trigger,
+            task: tokio::spawn(async {}),
+            runner: None,
+        }
+    }
+
+    /// Runner for on-demand cycles, sharing the task's in-progress guard.
+    ///
+    /// `None` when aggregation could not start.
+    pub fn runner(&self) -> Option<AggregationRunner> {
+        self.runner.clone()
+    }
+
+    /// Start a cycle now instead of waiting for the interval.
+    ///
+    /// A cycle in progress finishes first. Returns `false` if the task has stopped.
+    pub async fn trigger_now(&self) -> bool {
+        match self.trigger.try_send(()) {
+            Ok(()) | Err(mpsc::error::TrySendError::Full(())) => true,
+            Err(mpsc::error::TrySendError::Closed(())) => false,
+        }
+    }
+
+    /// Stop the task, waiting for a cycle in progress to finish.
+    pub async fn shutdown(self) {
+        self.cancel.cancel();
+        if let Err(e) = self.task.await {
+            error!(error = %e, "Discovery aggregation task failed");
+        }
+    }
+
+    /// Whether the task has exited.
+    pub fn is_finished(&self) -> bool {
+        self.task.is_finished()
+    }
+}
+
+/// Run a single aggregation cycle, over every facilitator or only `source`.
+///
+/// Each facilitator's resources are imported as soon as its fetch completes. A
+/// single-source cycle updates that facilitator's entry in the shared report and
+/// leaves the others as they are.
+async fn run_aggregation(
+    aggregator: &DiscoveryAggregator,
+    registry: &crate::discovery::DiscoveryRegistry,
+    report: &SharedAggregationReport,
+    source: Option<&str>,
+) -> AggregationReport {
+    info!(source = ?source, "Running discovery aggregation cycle");
+
+    let import = tokio::sync::Mutex::new(CycleImport::default());
+    let importer = &import;
+    let cycle_report = aggregator
+        .fetch_sources(source, move |_, result, resources| async move {
+            importer.lock().await.import_source(registry, &result, resources).await
+        })
+        .await;
+    let import = import.into_inner();
+
+    let failed: Vec<&str> = cycle_report
+        .failed()
+        .map(|source| source.facilitator_id.as_str())
+        .collect();
+    info!(
+        sources = cycle_report.per_source.len(),
+        failed = failed.len(),
+        failed_sources = ?failed,
+        "Discovery aggregation report"
+    );
+    {
+        let mut latest = report.write().await;
+        match (source, latest.as_mut()) {
+            (Some(_), Some(latest)) => {
+                latest.completed_at = cycle_report.completed_at;
+                for result in &cycle_report.per_source {
+                    match latest
+                        .per_source
+                        .iter_mut()
+                        .find(|entry| entry.facilitator_id == result.facilitator_id)
+                    {
+                        Some(entry) => *entry = result.clone(),
+                        None => latest.per_source.push(result.clone()),
+                    }
+                }
+            }
+            _ => *latest = Some(cycle_report.clone()),
+        }
+    }
+
+    if import.failed {
+        return cycle_report;
+    }
+    if import.listed == 0 {
+        warn!("No resources fetched from external facilitators");
+    } else {
+        crate::metrics::record_discovery_import(import.added, import.updated, import.skipped);
+        info!(
+            added = import.added,
+            updated = import.updated,
+            skipped = import.skipped,
+            "Discovery aggregation cycle completed"
+        );
+    }
+
+    let evicted = registry.evict_stale(&import.seen, aggregator.stale_cycles).await;
+    if !evicted.is_empty() {
+        info!(evicted = evicted.len(), "Evicted resources delisted by their source");
+    }
+    if failed.len() < cycle_report.per_source.len() {
+        crate::metrics::record_discovery_cycle_success(cycle_report.completed_at);
+        registry.save_snapshot().await;
+    }
+    cycle_report
+}
+
+/// Imports of one aggregation cycle, one facilitator at a time.
+#[derive(Debug, Default)]
+struct CycleImport {
+    /// Registry URL of each resource imported this cycle, by normalized URL
+    imported: HashMap<String, String>,
+    /// URLs listed by each facilitator fetched successfully
+    seen: HashMap<String, HashSet<String>>,
+    /// Resources handed to the registry
+    listed: usize,
+    added: usize,
+    updated: usize,
+    skipped: usize,
+    /// A registry import failed
+    failed: bool,
+}
+
+impl CycleImport {
+    /// Import the resources of a facilitator fetched successfully.
+    async fn import_source(
+        &mut self,
+        registry: &crate::discovery::DiscoveryRegistry,
+        result: &SourceResult,
+        resources: Vec<DiscoveryResource>,
+    ) {
+        if result.error.is_some() {
+            return;
+        }
+        let batch = self.merge_with_imported(registry, resources).await;
+        self.seen
+            .entry(result.facilitator_id.clone())
+            .or_default()
+            .extend(batch.iter().map(|resource| resource.url.to_string()));
+        if batch.is_empty() {
+            return;
+        }
+        self.listed += batch.len();
+        match registry
+            .bulk_import(batch, true, AGGREGATION_CONFLICT_STRATEGIES)
+            .await
+        {
+            Ok((added, updated, skipped)) => {
+                debug!(
+                    facilitator = %result.facilitator_id,
+                    added = added,
+                    updated = updated,
+                    skipped = skipped,
+                    "Imported facilitator resources"
+                );
+                self.added += added;
+                self.updated += updated;
+                self.skipped += skipped;
+            }
+            Err(e) => {
+                error!(facilitator = %result.facilitator_id, error = %e, "Failed to import aggregated resources");
+                self.failed = true;
+            }
+        }
+    }
+
+    /// Merge a facilitator's listings with each other and with the registered copies of
+    /// resources that an earlier facilitator of the cycle imported.
+    ///
+    /// A merged resource keeps the URL it is registered under.
+    async fn merge_with_imported(
+        &mut self,
+        registry: &crate::discovery::DiscoveryRegistry,
+        resources: Vec<DiscoveryResource>,
+    ) -> Vec<DiscoveryResource> {
+        let mut batch = Vec::with_capacity(resources.len());
+        for resource in dedup_resources(resources) {
+            let key = normalize_resource_url(&resource.url);
+            let registered = match self.imported.get(&key) {
+                Some(url) => registry.get(url).await,
+                None => None,
+            };
+            match registered {
+                Some(registered) => {
+                    let url = registered.url.clone();
+                    let mut merged = merge_listings(vec![registered, resource]);
+                    merged.url = url;
+                    batch.push(merged);
+                }
+                None => {
+                    self.imported.insert(key, resource.url.to_string());
+                    batch.push(resource);
+                }
+            }
+        }
+        batch
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::peer_net::{PeerNetError, SrvRecord};
+
+    #[derive(Debug)]
+    struct StaticSrvResolver(Vec<SrvRecord>);
+
+    #[async_trait::async_trait]
+    impl SrvResolver for StaticSrvResolver {
+        async fn resolve_srv(&self, _name: &str) -> Result<Vec<SrvRecord>, PeerNetError> {
+            Ok(self.0.clone())
+        }
+    }
+
+    fn test_config(id: &str, discovery_url: String) -> FacilitatorConfig {
+        FacilitatorConfig {
+            id: id.to_string(),
+            name: id.to_string(),
+            discovery_url,
+            enabled: true,
+            timeout_secs: 5,
+            headers: Vec::new(),
+            api_key_env: None,
+            api_key: None,
+            api_key_header: None,
+            min_request_interval_ms: None,
+        }
+    }
+
+    /// Fetch a facilitator's full catalog, failing the test on a `304`.
+    async fn fetch_catalog(
+        aggregator: &DiscoveryAggregator,
+        config: &FacilitatorConfig,
+    ) -> (Vec<DiscoveryResource>, usize) {
+        match aggregator.fetch_from_facilitator(config).await.unwrap() {
+            CatalogFetch::Fetched(resources, fetched) => (resources, fetched),
+            CatalogFetch::Unchanged => panic!("{} reported an unchanged catalog", config.id),
+        }
+    }
+
+    /// Serve an empty discovery page on an ephemeral port.
+    async fn serve_empty_discovery(listener: tokio::net::TcpListener) {
+        let app = axum::Router::new().route(
+            "/discovery/resources",
+            axum::routing::get(|| async {
+                axum::Json(serde_json::json!({ "items": [], "pagination": { "total": 0 } }))
+            }),
+        );
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+    }
+
+    /// Serve one resource after `delay`, or fail with 500 when `fail` is set.
+    async fn serve_delayed_discovery(resource: &'static str, delay: Duration, fail: bool) -> String {
+        use axum::response::IntoResponse;
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let app = axum::Router::new().route(
+            "/discovery/resources",
+            axum::routing::get(move || async move {
+                tokio::time::sleep(delay).await;
+                if fail {
+                    return axum::http::StatusCode::INTERNAL_SERVER_ERROR.into_response();
+                }
+                axum::Json(serde_json::json!({
+                    "items": [{ "url": resource, "lastUpdated": 1 }],
+                    "pagination": { "total": 1 }
+                }))
+                .into_response()
+            }),
+        );
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+        format!("http://127.0.0.1:{}/discovery/resources", port)
+    }
+
+    #[tokio::test]
+    async fn test_fetch_all_runs_facilitators_concurrently() {
+        let slow = Duration::from_secs(2);
+        let facilitators = vec![
+            test_config("slow-a", serve_delayed_discovery("https://a.example.com/", slow, false).await),
+            test_config("fast", serve_delayed_discovery("https://b.example.com/", Duration::ZERO, false).await),
+            test_config("slow-c", serve_delayed_discovery("https://c.example.com/", slow, false).await),
+            test_config("broken", serve_delayed_discovery("https://d.example.com/", Duration::ZERO, true).await),
+        ];
+        let aggregator = DiscoveryAggregator::with_facilitators(facilitators).with_concurrency(4);
+
+        let started = Instant::now();
+        let resources = aggregator.fetch_all().await;
+        let elapsed = started.elapsed();
+
+        // Bounded by the slowest source, not the sum of both slow ones
+        assert!(elapsed >= slow);
+        assert!(elapsed < slow * 2, "fetch_all took {:?}", elapsed);
+        // The failing source is skipped; the rest keep configuration order
+        let urls: Vec<&str> = resources.iter().map(|r| r.url.as_str()).collect();
+        assert_eq!(
+            urls,
+            vec!["https://a.example.com/", "https://b.example.com/", "https://c.example.com/"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_endless_facilitator_is_cut_off_at_its_deadline() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let pages = Arc::new(AtomicUsize::new(0));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let app = axum::Router::new().route(
+            "/discovery/resources",
+            axum::routing::get(move || async move {
+                let page = pages.fetch_add(1, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(200)).await;
+                axum::Json(serde_json::json!({
+                    "items": [{ "url": format!("https://endless.example.com/{page}"), "lastUpdated": 1 }],
+                    "pagination": { "nextCursor": format!("c{page}") }
+                }))
+            }),
+        );
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+
+        // Every page answers within the timeout, but the catalog never ends
+        let mut endless = test_config("endless", format!("http://127.0.0.1:{}/discovery/resources", port));
+        endless.timeout_secs = 1;
+        let facilitators = vec![
+            endless,
+            test_config("ok", serve_delayed_discovery("https://a.example.com/", Duration::ZERO, false).await),
+        ];
+        let aggregator = DiscoveryAggregator::with_facilitators(facilitators)
+            .with_rate_limit_budget(Duration::ZERO);
+
+        let started = Instant::now();
+        let (resources, report) = aggregator.fetch_all_with_report().await;
+        assert!(started.elapsed() < Duration::from_secs(2), "took {:?}", started.elapsed());
+
+        let urls: Vec<&str> = resources.iter().map(|r| r.url.as_str()).collect();
+        assert_eq!(urls, vec!["https://a.example.com/"]);
+        let failed: Vec<(&str, Option<&str>)> = report
+            .failed()
+            .map(|s| (s.facilitator_id.as_str(), s.error.as_deref()))
+            .collect();
+        assert_eq!(failed, vec![("endless", Some("Fetch timed out after 1s"))]);
+        assert!(report.per_source[0].duration_ms >= 1000);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_all_with_report_counts_per_source() {
+        let mut disabled = test_config("disabled", "http://127.0.0.1:1/discovery/resources".to_string());
+        disabled.enabled = false;
+        let facilitators = vec![
+            test_config("ok", serve_delayed_discovery("https://a.example.com/", Duration::ZERO, false).await),
+            disabled,
+            test_config("invalid", serve_delayed_discovery("not a url", Duration::ZERO, false).await),
+            test_config("broken", serve_delayed_discovery("https://b.example.com/", Duration::ZERO, true).await),
+        ];
+        let aggregator = DiscoveryAggregator::with_facilitators(facilitators);
+
+        let (resources, report) = aggregator.fetch_all_with_report().await;
+        assert_eq!(resources.len(), 1);
+
+        let summary: Vec<(&str, usize, usize, usize, bool)> = report
+            .per_source
+            .iter()
+            .map(|s| (s.facilitator_id.as_str(), s.fetched, s.converted, s.skipped, s.error.is_some()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("ok", 1, 1, 0, false),
+                ("invalid", 1, 0, 1, false),
+                ("broken", 0, 0, 0, true),
+            ]
+        );
+        let failed: Vec<&str> = report.failed().map(|s| s.facilitator_id.as_str()).collect();
+        assert_eq!(failed, vec!["broken"]);
+        assert!(report.completed_at > 0);
+    }
+
+    #[tokio::test]
+    async fn test_api_key_header_is_attached_or_facilitator_skipped() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let hits = Arc::new(AtomicUsize::new(0));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let app = axum::Router::new().route(
+            "/discovery/resources",
+            axum::routing::get({
+                let hits = Arc::clone(&hits);
+                move |headers: axum::http::HeaderMap| async move {
+                    hits.fetch_add(1, Ordering::SeqCst);
+                    let authorized = headers.get("x-secret-key").is_some_and(|v| v == "s3cret")
+                        && headers.get("x-client").is_some_and(|v| v == "ultravioleta");
+                    let items = if authorized {
+                        serde_json::json!([{ "url": "https://a.example.com/", "lastUpdated": 1 }])
+                    } else {
+                        serde_json::json!([])
+                    };
+                    axum::Json(serde_json::json!({ "items": items, "pagination": { "total": 1 } }))
+                }
+            }),
+        );
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+        let url = format!("http://127.0.0.1:{}/discovery/resources", port);
+
+        let mut keyed = test_config("keyed", url.clone());
+        keyed.headers = vec![("x-client".to_string(), "ultravioleta".to_string())];
+        keyed.api_key = Some("s3cret".to_string());
+        keyed.api_key_header = Some("x-secret-key".to_string());
+        let mut unkeyed = test_config("unkeyed", url);
+        unkeyed.api_key_env = Some("X402_TEST_AGGREGATOR_KEY_UNSET".to_string());
+
+        assert!(!format!("{:?}", keyed).contains("ultravioleta"));
+        assert!(!format!("{:?}", keyed).contains("s3cret"));
+
+        let aggregator = DiscoveryAggregator::with_facilitators(vec![keyed, unkeyed]);
+        let (resources, report) = aggregator.fetch_all_with_report().await;
+        assert_eq!(resources.len(), 1);
+        assert!(report.per_source[0].error.is_none());
+        assert_eq!(
+            report.per_source[1].error.as_deref(),
+            Some("API key variable X402_TEST_AGGREGATOR_KEY_UNSET is not set")
+        );
+        // The facilitator without its key was never contacted
+        assert_eq!(hits.load(Ordering::SeqCst), 1);
+    }
+
+    #[derive(Clone, Copy)]
+    enum Paging {
+        Offset,
+        Cursor,
+        EndlessCursor,
+    }
+
+    /// Serve 250 resources, 100 per page, paginated in the given style.
+    async fn serve_paginated(paging: Paging) -> String {
+        use std::collections::HashMap;
+
+        const TOTAL: usize = 250;
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let app = axum::Router::new().route(
+            "/discovery/resources",
+            axum::routing::get(move |axum::extract::Query(query): axum::extract::Query<HashMap<String, String>>| async move {
+                let start: usize = match paging {
+                    Paging::Offset => query.get("offset").map_or(0, |o| o.parse().unwrap()),
+                    _ => query.get("cursor").map_or(0, |c| c[1..].parse().unwrap()),
+                };
+                let end = match paging {
+                    Paging::EndlessCursor => start + 100,
+                    _ => (start + 100).min(TOTAL),
+                };
+                let items: Vec<_> = (start..end)
+                    .map(|i| serde_json::json!({ "url": format!("https://r{}.example.com/", i), "lastUpdated": 1 }))
+                    .collect();
+                let pagination = match paging {
+                    Paging::Offset => serde_json::json!({ "total": TOTAL }),
+                    _ if end < TOTAL || matches!(paging, Paging::EndlessCursor) => {
+                        serde_json::json!({ "next_cursor": format!("c{}", end) })
+                    }
+                    _ => serde_json::json!({ "next_cursor": null }),
+                };
+                axum::Json(serde_json::json!({ "items": items, "pagination": pagination }))
+            }),
+        );
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+        format!("http://127.0.0.1:{}/discovery/resources", port)
+    }
+
+    #[tokio::test]
+    async fn test_fetch_follows_offset_and_cursor_pagination() {
+        let aggregator = DiscoveryAggregator::with_facilitators(vec![]);
+        for paging in [Paging::Offset, Paging::Cursor] {
+            let config = test_config("paged", serve_paginated(paging).await);
+            let (resources, fetched) = fetch_catalog(&aggregator, &config).await;
+            assert_eq!(fetched, 250);
+            let urls: std::collections::HashSet<_> = resources.iter().map(|r| r.url.to_string()).collect();
+            assert_eq!(urls.len(), 250);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fetch_stops_at_page_limit() {
+        let aggregator = DiscoveryAggregator::with_facilitators(vec![]).with_max_pages(3);
+        let config = test_config("endless", serve_paginated(Paging::EndlessCursor).await);
+        let (resources, fetched) = fetch_catalog(&aggregator, &config).await;
+        assert_eq!(fetched, 300);
+        assert_eq!(resources.len(), 300);
+    }
+
+    /// One-item catalog served with cache validators.
+    fn validated_catalog() -> wiremock::ResponseTemplate {
+        wiremock::ResponseTemplate::new(200)
+            .insert_header("ETag", "\"v1\"")
+            .insert_header("Last-Modified", "Wed, 01 Jul 2026 00:00:00 GMT")
+            .set_body_json(serde_json::json!({
+                "items": [{ "url": "https://a.example.com/", "lastUpdated": 1 }],
+                "pagination": { "total": 1 }
+            }))
+    }
+
+    #[tokio::test]
+    async fn test_not_modified_catalog_is_reused() {
+        use wiremock::matchers::{header, header_exists, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/discovery/resources"))
+            .and(header("if-none-match", "\"v1\""))
+            .and(header_exists("if-modified-since"))
+            .respond_with(ResponseTemplate::new(304))
+            .with_priority(1)
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/discovery/resources"))
+            .respond_with(validated_catalog())
+            .expect(1)
+            .mount(&server)
+            .await;
+        let config = test_config("cached", format!("{}/discovery/resources", server.uri()));
+        let aggregator = DiscoveryAggregator::with_facilitators(vec![config]);
+
+        let (first, report) = aggregator.fetch_all_with_report().await;
+        assert_eq!(first.len(), 1);
+        assert!(!report.per_source[0].unchanged);
+
+        let (second, report) = aggregator.fetch_all_with_report().await;
+        let urls: Vec<&str> = second.iter().map(|r| r.url.as_str()).collect();
+        assert_eq!(urls, vec!["https://a.example.com/"]);
+        let source = &report.per_source[0];
+        assert!(source.unchanged);
+        assert_eq!((source.fetched, source.converted, source.skipped), (0, 0, 0));
+        assert_eq!(source.error, None);
+        assert_eq!(serde_json::to_value(source).unwrap()["unchanged"], true);
+
+        let requests = server.received_requests().await.unwrap();
+        assert_eq!(
+            requests[1].headers.get("if-modified-since").unwrap(),
+            "Wed, 01 Jul 2026 00:00:00 GMT"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_server_ignoring_conditionals_is_refetched() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/discovery/resources"))
+            .respond_with(validated_catalog())
+            .expect(2)
+            .mount(&server)
+            .await;
+        let config = test_config("uncached", format!("{}/discovery/resources", server.uri()));
+        let aggregator = DiscoveryAggregator::with_facilitators(vec![config]);
+
+        aggregator.fetch_all_with_report().await;
+        let (resources, report) = aggregator.fetch_all_with_report().await;
+        assert_eq!(resources.len(), 1);
+        let source = &report.per_source[0];
+        assert!(!source.unchanged);
+        assert_eq!((source.fetched, source.converted), (1, 1));
+        assert!(serde_json::to_value(source).unwrap().get("unchanged").is_none());
+
+        // Only the second request was conditional
+        let requests = server.received_requests().await.unwrap();
+        assert!(requests[0].headers.get("if-none-match").is_none());
+        assert_eq!(requests[1].headers.get("if-none-match").unwrap(), "\"v1\"");
+    }
+
+    #[tokio::test]
+    async fn test_triggered_cycle_runs_promptly() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let hits = Arc::new(AtomicUsize::new(0));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let counter = Arc::clone(&hits);
+        let app = axum::Router::new().route(
+            "/discovery/resources",
+            axum::routing::get(move || async move {
+                counter.fetch_add(1, Ordering::SeqCst);
+                axum::Json(serde_json::json!({ "items": [], "pagination": { "total": 0 } }))
+            }),
+        );
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+        let wait_for = |n: usize| {
+            let hits = Arc::clone(&hits);
+            async move {
+                tokio::time::timeout(Duration::from_secs(2), async {
+                    while hits.load(Ordering::SeqCst) < n {
+                        tokio::time::sleep(Duration::from_millis(10)).await;
+                    }
+                })
+                .await
+                .is_ok()
+            }
+        };
+
+        let config = test_config("counted", format!("http://127.0.0.1:{}/discovery/resources", port));
+        let handle = AggregationTaskHandle::spawn(
+            DiscoveryAggregator::with_facilitators(vec![config]),
+            crate::discovery::DiscoveryRegistry::new(),
+            Duration::from_secs(3600),
+            SharedAggregationReport::default(),
+        );
+        assert!(wait_for(1).await, "startup cycle did not run");
+        assert!(handle.trigger_now().await);
+        assert!(wait_for(2).await, "triggered cycle did not run");
+
+        handle.shutdown().await;
+        assert_eq!(hits.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_cycle_evicts_resources_delisted_by_source() {
+        let registry = crate::discovery::DiscoveryRegistry::new();
+        let mut stale = DiscoveryResource::from_aggregation(
+            Url::parse("https://gone.example.com/").unwrap(),
+            "http".to_string(),
+            String::new(),
+            Vec::new(),
+            "up".to_string(),
+            1,
+        );
+        registry
+            .bulk_import(vec![stale.clone()], true, &[ConflictStrategy::PreferNewer])
+            .await
+            .unwrap();
+        stale.source_facilitator = Some("broken".to_string());
+        stale.url = Url::parse("https://unreachable.example.com/").unwrap();
+        registry
+            .bulk_import(vec![stale], true, &[ConflictStrategy::PreferNewer])
+            .await
+            .unwrap();
+
+        let aggregator = DiscoveryAggregator::with_facilitators(vec![
+            test_config("up", serve_delayed_discovery("https://a.example.com/", Duration::ZERO, false).await),
+            test_config("broken", serve_delayed_discovery("https://b.example.com/", Duration::ZERO, true).await),
+        ])
+        .with_stale_cycles(2);
+        let report = SharedAggregationReport::default();
+
+        run_aggregation(&aggregator, &registry, &report, None).await;
+        assert_eq!(registry.missed_cycles("https://gone.example.com/").await, 1);
+        run_aggregation(&aggregator, &registry, &report, None).await;
+        assert!(registry.get("https://gone.example.com/").await.is_none());
+        assert!(registry.get("https://a.example.com/").await.is_some());
+        // Its source failed, so it is not known to be delisted
+        assert!(registry.get("https://unreachable.example.com/").await.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_single_source_cycle_updates_its_report_entry() {
+        let registry = crate::discovery::DiscoveryRegistry::new();
+        let report = SharedAggregationReport::default();
+        let runner = AggregationRunner::new(
+            DiscoveryAggregator::with_facilitators(vec![
+                test_config("up", serve_delayed_discovery("https://a.example.com/", Duration::ZERO, false).await),
+                test_config("broken", serve_delayed_discovery("https://b.example.com/", Duration::ZERO, true).await),
+            ]),
+            registry.clone(),
+            Arc::clone(&report),
+        );
+
+        runner.run().await;
+        let full = report.read().await.clone().unwrap();
+        assert_eq!(full.per_source.len(), 2);
+
+        let cycle = runner.try_run(Some("up")).await.unwrap();
+        assert_eq!(cycle.per_source.len(), 1);
+        let latest = report.read().await.clone().unwrap();
+        assert_eq!(latest.per_source.len(), 2);
+        assert_eq!(latest.per_source[0], cycle.per_source[0]);
+        assert_eq!(latest.per_source[1], full.per_source[1]);
+
+        assert!(matches!(
+            runner.try_run(Some("missing")).await,
+            Err(AggregatorError::UnknownSource(id)) if id == "missing"
+        ));
+        let _cycle = runner.cycle.lock().await;
+        assert!(matches!(runner.try_run(None).await, Err(AggregatorError::CycleInProgress)));
+    }
+
+    /// Serve `body` as every discovery page on an ephemeral port.
+    async fn serve_discovery_body(body: &'static str) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let app = axum::Router::new().route(
+            "/discovery/resources",
+            axum::routing::get(move || async move { body }),
+        );
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+        format!("http://127.0.0.1:{}/discovery/resources", port)
+    }
+
+    #[tokio::test]
+    async fn test_cycle_imports_each_source_as_it_completes() {
+        let registry = crate::discovery::DiscoveryRegistry::new();
+        let aggregator = DiscoveryAggregator::with_facilitators(vec![
+            test_config("slow", serve_delayed_discovery("https://slow.example.com/", Duration::from_secs(2), false).await),
+            test_config("fast", serve_delayed_discovery("https://fast.example.com/", Duration::ZERO, false).await),
+        ]);
+        let report = SharedAggregationReport::default();
+
+        let cycle = {
+            let registry = registry.clone();
+            let report = Arc::clone(&report);
+            tokio::spawn(async move { run_aggregation(&aggregator, &registry, &report, None).await })
+        };
+        let deadline = Instant::now() + Duration::from_secs(1);
+        while registry.get("https://fast.example.com/").await.is_none() {
+            assert!(Instant::now() < deadline, "fast source was not imported before the slow one");
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert!(registry.get("https://slow.example.com/").await.is_none());
+        assert!(report.read().await.is_none());
+
+        let cycle = cycle.await.unwrap();
+        assert!(registry.get("https://slow.example.com/").await.is_some());
+        let ids: Vec<&str> = cycle.per_source.iter().map(|s| s.facilitator_id.as_str()).collect();
+        assert_eq!(ids, ["slow", "fast"]);
+        assert!(cycle.per_source.iter().all(|s| s.converted == 1 && s.error.is_none()));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_each_hands_over_sources_in_completion_order() {
+        let aggregator = DiscoveryAggregator::with_facilitators(vec![
+            test_config("slow", serve_delayed_discovery("https://slow.example.com/", Duration::from_millis(300), false).await),
+            test_config("broken", serve_delayed_discovery("https://broken.example.com/", Duration::ZERO, true).await),
+            test_config("fast", serve_delayed_discovery("https://fast.example.com/", Duration::ZERO, false).await),
+        ]);
+
+        let mut calls: Vec<(String, usize)> = Vec::new();
+        let report = aggregator
+            .fetch_each(|result, resources| {
+                calls.push((result.facilitator_id, resources.len()));
+                std::future::ready(())
+            })
+            .await;
+
+        assert_eq!(calls.len(), 3);
+        let position = |id: &str| calls.iter().position(|(source, _)| source == id).unwrap();
+        assert!(position("fast") < position("slow"));
+        assert_eq!(calls[position("slow")].1, 1);
+        assert_eq!(calls[position("broken")].1, 0);
+        let ids: Vec<&str> = report.per_source.iter().map(|s| s.facilitator_id.as_str()).collect();
+        assert_eq!(ids, ["slow", "broken", "fast"]);
+        assert!(report.per_source[1].error.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_cycle_merges_listings_across_sources() {
+        const BASE: &str = r#"{"items": [{"resource": "https://api.example.com/data", "lastUpdated": 5,
+            "accepts": [{"network": "base", "maxAmountRequired": "10000",
+                "payTo": "0x1234567890123456789012345678901234567890",
+                "asset": "0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913"}]}]}"#;
+        const POLYGON: &str = r#"{"items": [{"resource": "https://API.example.com/data/", "lastUpdated": 5,
+            "accepts": [{"network": "polygon", "maxAmountRequired": "10000",
+                "payTo": "0x1234567890123456789012345678901234567890",
+                "asset": "0x3c499c542cEF5E3811e1192ce70d8cC03d5c3359"}]}]}"#;
+        let registry = crate::discovery::DiscoveryRegistry::new();
+        let aggregator = DiscoveryAggregator::with_facilitators(vec![
+            test_config("coinbase", serve_discovery_body(BASE).await),
+            test_config("payai", serve_discovery_body(POLYGON).await),
+        ]);
+        let report = SharedAggregationReport::default();
+
+        for _ in 0..2 {
+            run_aggregation(&aggregator, &registry, &report, None).await;
+            assert_eq!(registry.count().await, 1);
+            let url = registry.urls().await.remove(0);
+            let merged = registry.get(&url).await.unwrap();
+            assert_eq!(merged.accepts.len(), 2);
+            let mut sources = merged.metadata.unwrap().sources;
+            sources.sort();
+            assert_eq!(sources, ["coinbase", "payai"]);
+        }
+    }
+
+    #[test]
+    fn test_cycle_metrics_are_recorded() {
+        let recorder = crate::metrics::builder().unwrap().build_recorder();
+        let handle = recorder.handle();
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+
+        let report = ::metrics::with_local_recorder(&recorder, || {
+            runtime.block_on(async {
+                let mixed = serve_discovery_body(
+                    r#"{"items": [
+                        { "url": "https://a.example.com/", "lastUpdated": 1 },
+                        { "url": "not a url", "lastUpdated": 1 }
+                    ], "pagination": { "total": 2 }}"#,
+                )
+                .await;
+                let garbage = serve_discovery_body("<html>maintenance</html>").await;
+                let aggregator = DiscoveryAggregator::with_facilitators(vec![
+                    test_config("mixed", mixed),
+                    test_config("garbage", garbage),
+                ]);
+                let registry = crate::discovery::DiscoveryRegistry::new();
+                run_aggregation(&aggregator, &registry, &SharedAggregationReport::default(), None)
+                    .await
+            })
+        });
+
+        let rendered = handle.render();
+        for line in [
+            r#"x402_discovery_resources_fetched_total{facilitator="mixed"} 2"#,
+            r#"x402_discovery_resources_converted_total{facilitator="mixed"} 1"#,
+            r#"x402_discovery_resources_skipped_total{facilitator="mixed"} 1"#,
+            r#"x402_discovery_resources_fetched_total{facilitator="garbage"} 0"#,
+            r#"x402_discovery_parse_errors_total{facilitator="garbage"} 1"#,
+            r#"x402_discovery_fetch_duration_seconds_count{facilitator="mixed"} 1"#,
+            r#"x402_discovery_import_resources{result="added"} 1"#,
+            r#"x402_discovery_import_resources{result="updated"} 0"#,
+        ] {
+            assert!(rendered.contains(line), "missing {line} in:\n{rendered}");
+        }
+        assert!(rendered.contains(&format!(
+            "x402_discovery_last_success_timestamp_seconds {}",
+            report.completed_at
+        )));
+        assert!(!rendered.contains(r#"x402_discovery_parse_errors_total{facilitator="mixed"}"#));
+    }
+
+    #[tokio::test]
+    async fn test_source_stats_track_fetches() {
+        let mut disabled = test_config("off", "http://127.0.0.1:9/discovery/resources".to_string());
+        disabled.enabled = false;
+        let aggregator = DiscoveryAggregator::with_facilitators(vec![
+            test_config("up", serve_delayed_discovery("https://a.example.com/", Duration::ZERO, false).await),
+            test_config("broken", serve_delayed_discovery("https://b.example.com/", Duration::ZERO, true).await),
+            disabled,
+        ])
+        .with_breaker_threshold(1);
+        let stats = aggregator.stats();
+        assert_eq!(stats.len(), 3);
+        assert_eq!(stats.get("up").unwrap().last_fetch_at, None);
+
+        aggregator.fetch_all_with_report().await;
+        aggregator.schedule_next_fetch(unix_now() + 60);
+
+        let up = stats.get("up").unwrap().clone();
+        assert_eq!(up.resources, 1);
+        assert!(up.last_success_at.is_some());
+        assert_eq!(up.last_error, None);
+        assert!(up.next_fetch_at.unwrap() >= unix_now() + 59);
+
+        let broken = stats.get("broken").unwrap().clone();
+        assert!(broken.last_fetch_at.is_some());
+        assert_eq!(broken.last_success_at, None);
+        assert!(broken.last_error.is_some());
+        // Its breaker opened, so it is skipped until the cool-down ends
+        assert_eq!(broken.next_fetch_at, broken.breaker.open_until);
+        assert!(broken.next_fetch_at.unwrap() > up.next_fetch_at.unwrap());
+
+        let off = stats.get("off").unwrap().clone();
+        assert!(!off.enabled);
+        assert_eq!((off.last_fetch_at, off.next_fetch_at), (None, None));
+
+        // Skipped while the breaker is open: no new fetch, error kept
+        let last_fetch = broken.last_fetch_at;
+        aggregator.fetch_all_with_report().await;
+        let broken = stats.get("broken").unwrap().clone();
+        assert_eq!(broken.last_fetch_at, last_fetch);
+        assert!(broken.last_error.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_waits_for_cycle_in_progress() {
+        let delay = Duration::from_millis(500);
+        let url = serve_delayed_discovery("https://slow.example.com/", delay, false).await;
+        let registry = crate::discovery::DiscoveryRegistry::new();
+        let report = SharedAggregationReport::default();
+        let handle = AggregationTaskHandle::spawn(
+            DiscoveryAggregator::with_facilitators(vec![test_config("slow", url)]),
+            registry.clone(),
+            Duration::from_secs(3600),
+            Arc::clone(&report),
+        );
+
+        // Shut down while the startup cycle is still fetching
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        let started = Instant::now();
+        handle.shutdown().await;
+        assert!(started.elapsed() >= delay - Duration::from_millis(150));
+
+        // The cycle completed its import before the task exited
+        assert!(report.read().await.is_some());
+        assert!(registry.get("https://slow.example.com/").await.is_some());
+    }
+
+    #[test]
+    fn test_normalize_resource_url() {
+        let normalize = |url: &str| normalize_resource_url(&Url::parse(url).unwrap());
+
+        assert_eq!(normalize("https://api.example.com/"), normalize("https://API.example.com"));
+        assert_eq!(normalize("https://api.example.com/"), "https://api.example.com");
+        assert_eq!(
+            normalize("HTTPS://Api.Example.com:443/data/?b=2&a=1#section"),
+            "https://api.example.com/data?a=1&b=2"
+        );
+        assert_eq!(normalize("http://api.example.com:80/x"), normalize("http://api.example.com/x"));
+        assert_eq!(normalize("http://[::1]:8080/x/"), "http://[::1]:8080/x");
+
+        // Differences that matter are kept
+        assert_ne!(normalize("https://api.example.com:8443/"), normalize("https://api.example.com/"));
+        assert_ne!(normalize("http://api.example.com/"), normalize("https://api.example.com/"));
+        assert_ne!(normalize("https://api.example.com/Data"), normalize("https://api.example.com/data"));
+        assert_ne!(normalize("https://api.example.com/?a=1"), normalize("https://api.example.com/?a=2"));
+    }
+
+    #[test]
+    fn test_dedup_merges_listings_of_the_same_resource() {
+        let listing = |url: &str, facilitator: &str, amount: u64, last_updated: u64| {
+            DiscoveryResource::from_aggregation(
+                Url::parse(url).unwrap(),
+                "http".to_string(),
+                facilitator.to_string(),
+                vec![PaymentRequirementsV2 {
+                    scheme: Scheme::Exact,
+                    network: Caip2NetworkId::eip155(8453),
+                    asset: MixedAddress::Evm("0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913".parse().unwrap()),
+                    amount: TokenAmount::from(amount),
+                    pay_to: MixedAddress::Evm("0x1234567890123456789012345678901234567890".parse().unwrap()),
+                    max_timeout_seconds: 300,
+                    extra: None,
+                }],
+                facilitator.to_string(),
+                last_updated,
+            )
+        };
+
+        let merged = dedup_resources(vec![
+            listing("https://api.example.com/data", "coinbase", 1000, 10),
+            listing("https://other.example.com/", "coinbase", 1000, 10),
+            listing("https://API.example.com/data/", "x402rs", 2000, 20),
+            listing("https://api.example.com/data", "payai", 1000, 20),
+        ]);
+
+        assert_eq!(merged.len(), 2);
+        let data = &merged[0];
+        // Newest listing wins, the earliest of the two newest on ties
+        assert_eq!(data.source_facilitator.as_deref(), Some("x402rs"));
+        assert_eq!(data.url.as_str(), "https://api.example.com/data/");
+        assert_eq!(data.last_updated, 20);
+        let amounts: Vec<TokenAmount> = data.accepts.iter().map(|a| a.amount).collect();
+        assert_eq!(amounts, vec![TokenAmount::from(2000u64), TokenAmount::from(1000u64)]);
+        assert_eq!(data.metadata.as_ref().unwrap().sources, vec!["coinbase", "x402rs", "payai"]);
+
+        // Single listings are left untouched
+        assert_eq!(merged[1].url.as_str(), "https://other.example.com/");
+        assert!(merged[1].metadata.is_none());
+    }
+
+    #[test]
+    fn test_page_url_ipv6_literal() {
+        let base = Url::parse("http://[2001:db8::1]:8080/discovery/resources?network=base").unwrap();
+        let url = page_url(&base, 100, 200);
+        assert_eq!(
+            url.as_str(),
+            "http://[2001:db8::1]:8080/discovery/resources?network=base&limit=100&offset=200"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fetch_from_ipv6_loopback() {
+        let Ok(listener) = tokio::net::TcpListener::bind("[::1]:0").await else {
+            return; // No IPv6 loopback in this environment
+        };
+        let port = listener.local_addr().unwrap().port();
+        serve_empty_discovery(listener).await;
+
+        let aggregator = DiscoveryAggregator::with_facilitators(vec![]);
+        let config = test_config("v6", format!("http://[::1]:{}/discovery/resources", port));
+        let (resources, fetched) = fetch_catalog(&aggregator, &config).await;
+        assert!(resources.is_empty());
+        assert_eq!(fetched, 0);
+    }
+
+    #[tokio::test]
+    async fn test_srv_fallback_to_next_target() {
+        // Reserve a port and close it so connecting fails
+        let dead_port = {
+            let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            listener.local_addr().unwrap().port()
+        };
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let live_port = listener.local_addr().unwrap().port();
+        serve_empty_discovery(listener).await;
+
+        let resolver = StaticSrvResolver(vec![
+            SrvRecord { priority: 10, weight: 1, port: dead_port, target: "127.0.0.1".into() },
+            SrvRecord { priority: 20, weight: 1, port: live_port, target: "127.0.0.1".into() },
+        ]);
+        let aggregator =
+            DiscoveryAggregator::with_facilitators(vec![]).with_srv_resolver(Arc::new(resolver));
+        let config = test_config("srv-peer", "srv+http://_x402._tcp.peer.internal".to_string());
+
+        let (resources, fetched) = fetch_catalog(&aggregator, &config).await;
+        assert!(resources.is_empty());
+        assert_eq!(fetched, 0);
+
+        let stats = aggregator.connection_stats("srv-peer");
+        assert_eq!(stats.srv_fallbacks, 1);
+        assert_eq!(stats.connect_failures_ipv4, 1);
+        assert_eq!(stats.resolution_failures, 0);
+    }
+
 
     #[test]
     fn test_parse_network_to_caip2() {
@@ -905,20 +3805,772 @@ mod tests {
             aggregator.parse_network_to_caip2("eip155:8453").unwrap().to_string(),
             "eip155:8453"
         );
+
+        // Solana
+        for (name, expected) in [
+            ("solana", "solana:5eykt4UsFv8P8NJdTREpY1vzqKqZKvdp"),
+            ("Solana-Mainnet", "solana:5eykt4UsFv8P8NJdTREpY1vzqKqZKvdp"),
+            ("solana-devnet", "solana:EtWTRABZaYq6iMfeYKouRu166VU2xqa1"),
+            ("solana:EtWTRABZaYq6iMfeYKouRu166VU2xqa1", "solana:EtWTRABZaYq6iMfeYKouRu166VU2xqa1"),
+        ] {
+            assert_eq!(
+                aggregator.parse_network_to_caip2(name).unwrap().to_string(),
+                expected,
+                "parsing {name}"
+            );
+        }
+        assert!(aggregator.parse_network_to_caip2("solana-testnet").is_none());
+
+        // zkSync Era and Scroll
+        for (name, network) in [
+            ("zksync", Network::ZkSyncEra),
+            ("zkSync-Era", Network::ZkSyncEra),
+            ("zksync-era-sepolia", Network::ZkSyncEraSepolia),
+            ("scroll", Network::Scroll),
+            ("scroll-sepolia", Network::ScrollSepolia),
+        ] {
+            let caip2 = aggregator.parse_network_to_caip2(name).unwrap();
+            assert_eq!(Network::from_caip2(&caip2.to_string()), Some(network), "parsing {name}");
+        }
     }
 
     #[test]
-    fn test_parse_address() {
+    fn test_parse_token_amount() {
+        let usdc = Some(6);
+        let parse = |raw: &str, decimals: Option<u8>| parse_token_amount(raw, decimals).map(|a| a.0);
+
+        assert_eq!(parse("0.01", usdc), Some(U256::from(10_000u64)));
+        assert_eq!(parse("$0.001", usdc), Some(U256::from(1_000u64)));
+        assert_eq!(parse("$1", usdc), Some(U256::from(1_000_000u64)));
+        assert_eq!(parse(".5", usdc), Some(U256::from(500_000u64)));
+        assert_eq!(parse("2.", usdc), Some(U256::from(2_000_000u64)));
+        assert_eq!(parse("1000000", usdc), Some(U256::from(1_000_000u64)));
+        // Base units need no decimals
+        assert_eq!(parse("1000000", None), Some(U256::from(1_000_000u64)));
+
+        // Human units of unknown tokens cannot be scaled
+        assert_eq!(parse("0.01", None), None);
+        assert_eq!(parse("$5", None), None);
+        // Malformed
+        for raw in ["", "$", ".", "abc", "1.2.3", "-1", "0x10", "1e6", "$$1", "0.0000001", "1,000"] {
+            assert_eq!(parse(raw, usdc), None, "parsing {raw:?}");
+        }
+    }
+
+    #[test]
+    fn test_convert_payment_requirement_amounts() {
         let aggregator = DiscoveryAggregator::new();
+        let convert = |amount: serde_json::Value, asset: &str| {
+            let mut req = serde_json::json!({
+                "scheme": "exact",
+                "network": "base",
+                "asset": asset,
+                "payTo": "0x1234567890123456789012345678901234567890",
+            });
+            req.as_object_mut()
+                .unwrap()
+                .extend(amount.as_object().unwrap().clone());
+            aggregator
+                .convert_payment_requirement(serde_json::from_value(req).unwrap())
+                .map(|r| r.amount.0)
+        };
+        let usdc = "0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913";
+        let unknown = "0x0000000000000000000000000000000000000001";
+
+        assert_eq!(convert(serde_json::json!({"amount": "0.01"}), usdc), Some(U256::from(10_000u64)));
+        assert_eq!(convert(serde_json::json!({"amount": "$0.001"}), usdc), Some(U256::from(1_000u64)));
+        assert_eq!(
+            convert(serde_json::json!({"maxAmountRequired": "1000000"}), unknown),
+            Some(U256::from(1_000_000u64))
+        );
+        // The base-unit field wins when both are present, whichever name it has
+        assert_eq!(
+            convert(serde_json::json!({"amount": "0.01", "maxAmountRequired": "20000"}), usdc),
+            Some(U256::from(20_000u64))
+        );
+        assert_eq!(
+            convert(serde_json::json!({"amount": "30000", "maxAmountRequired": "$0.01"}), usdc),
+            Some(U256::from(30_000u64))
+        );
+        // Skipped rather than published as free
+        assert_eq!(convert(serde_json::json!({"amount": "0.01"}), unknown), None);
+        assert_eq!(convert(serde_json::json!({"amount": "ten cents"}), usdc), None);
+    }
 
+    #[test]
+    fn test_parse_address() {
         // Valid EVM address
-        let addr = aggregator.parse_address("0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913");
+        let addr = parse_address("0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913");
         assert!(addr.is_some());
         assert!(matches!(addr.unwrap(), MixedAddress::Evm(_)));
 
+        // Valid Solana addresses (USDC mint, system program)
+        for solana in [
+            "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v",
+            "11111111111111111111111111111111",
+        ] {
+            let addr = parse_address(solana);
+            assert!(matches!(addr, Some(MixedAddress::Solana(_))), "parsing {solana}");
+            assert_eq!(addr.unwrap().to_string(), solana);
+        }
+
         // Invalid address
-        assert!(aggregator.parse_address("invalid").is_none());
-        assert!(aggregator.parse_address("0x123").is_none()); // Too short
+        assert!(parse_address("invalid").is_none());
+        assert!(parse_address("0x123").is_none()); // Too short
+        // Not base58 (contains 0 and l), or decodes to the wrong length
+        assert!(parse_address("0PjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1l").is_none());
+        assert!(parse_address("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1vEPjF").is_none());
+        assert!(parse_address("GBRPYHIL2CI3FNQ4BXLFMNDLFJUNPU2HY3ZMFSHONUCEOASW7QC7OX2H").is_none());
+
+        // Algorand addresses are kept when their checksum is valid
+        let algorand = "7ZUECA7HFLZTXENRV24SHLU4AVPUTMTTDUFUBNBD64C73F3UHRTHAIOF6Q";
+        assert_eq!(
+            parse_address(algorand),
+            Some(MixedAddress::Algorand(algorand.to_string()))
+        );
+        assert!(parse_address(&format!("6{}", &algorand[1..])).is_none());
+    }
+
+    #[test]
+    fn test_convert_payai_resources_keeps_solana_accepts() {
+        // Shape of a PayAI bazaar listing: v1 network names, Solana mints and pubkeys
+        let body = r#"{
+            "x402Version": 1,
+            "items": [
+                {
+                    "resource": "https://x402.payai.network/api/solana/paid-content",
+                    "type": "http",
+                    "x402Version": 1,
+                    "lastUpdated": "2025-10-22T17:05:31.223Z",
+                    "accepts": [
+                        {
+                            "scheme": "exact",
+                            "network": "solana",
+                            "maxAmountRequired": "10000",
+                            "resource": "https://x402.payai.network/api/solana/paid-content",
+                            "description": "Access to premium content",
+                            "mimeType": "",
+                            "payTo": "2wKupLR9q6wXYppw8Gr2NvWxKBUqm4PPJKkQfoxHDBg4",
+                            "maxTimeoutSeconds": 60,
+                            "asset": "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v",
+                            "outputSchema": {"input": {"type": "http", "method": "GET"}},
+                            "extra": {"feePayer": "2wKupLR9q6wXYppw8Gr2NvWxKBUqm4PPJKkQfoxHDBg4"}
+                        },
+                        {
+                            "scheme": "exact",
+                            "network": "base",
+                            "maxAmountRequired": "10000",
+                            "payTo": "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+                            "maxTimeoutSeconds": 60,
+                            "asset": "0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913",
+                            "extra": {"name": "USD Coin", "version": "2"}
+                        }
+                    ]
+                },
+                {
+                    "resource": "https://x402.payai.network/api/solana-devnet/paid-content",
+                    "type": "http",
+                    "x402Version": 1,
+                    "accepts": [
+                        {
+                            "scheme": "exact",
+                            "network": "solana-devnet",
+                            "amount": "$0.01",
+                            "payTo": "2wKupLR9q6wXYppw8Gr2NvWxKBUqm4PPJKkQfoxHDBg4",
+                            "maxTimeoutSeconds": 60,
+                            "asset": "4zMMC9srt5Ri5X14GAgXhaHii3GnPAEERYPJgZJDncDU"
+                        }
+                    ]
+                }
+            ],
+            "pagination": {"limit": 100, "offset": 0, "total": 2}
+        }"#;
+
+        let aggregator = DiscoveryAggregator::new();
+        let (items, _) = aggregator.parse_discovery_response(body, "payai").unwrap();
+        let resources = aggregator.convert_coinbase_resources(items, "payai");
+        assert_eq!(resources.len(), 2);
+
+        let mainnet = &resources[0];
+        assert_eq!(mainnet.accepts.len(), 2);
+        let solana = &mainnet.accepts[0];
+        assert_eq!(solana.network.to_string(), "solana:5eykt4UsFv8P8NJdTREpY1vzqKqZKvdp");
+        assert!(matches!(solana.asset, MixedAddress::Solana(_)));
+        assert_eq!(solana.pay_to.to_string(), "2wKupLR9q6wXYppw8Gr2NvWxKBUqm4PPJKkQfoxHDBg4");
+        assert_eq!(solana.amount.0, U256::from(10_000u64));
+        assert_eq!(solana.max_timeout_seconds, 60);
+        let base = &mainnet.accepts[1];
+        assert_eq!(base.network.to_string(), "eip155:8453");
+        assert!(matches!(base.asset, MixedAddress::Evm(_)));
+
+        // Dollar amounts scale with the devnet USDC decimals
+        let devnet = &resources[1];
+        assert_eq!(devnet.accepts.len(), 1);
+        assert_eq!(devnet.accepts[0].network.to_string(), "solana:EtWTRABZaYq6iMfeYKouRu166VU2xqa1");
+        assert_eq!(devnet.accepts[0].amount.0, U256::from(10_000u64));
+    }
+
+    #[test]
+    fn test_convert_keeps_upto_and_skips_unknown_schemes() {
+        let accept = |scheme: &str| {
+            format!(
+                r#"{{"scheme": "{scheme}", "network": "base", "maxAmountRequired": "10000",
+                    "payTo": "0x1234567890123456789012345678901234567890",
+                    "asset": "0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913"}}"#
+            )
+        };
+        let body = format!(
+            r#"{{"items": [
+                {{"resource": "https://api.example.com/metered", "accepts": [{}, {}]}},
+                {{"resource": "https://api.example.com/streamed", "accepts": [{}]}},
+                {{"resource": "https://api.example.com/mixed", "accepts": [{}, {}]}}
+            ]}}"#,
+            accept("upto"),
+            accept("Up-To"),
+            accept("stream"),
+            accept("stream"),
+            accept("exact"),
+        );
+
+        let aggregator = DiscoveryAggregator::new();
+        let (items, _) = aggregator.parse_discovery_response(&body, "coinbase").unwrap();
+        let resources = aggregator.convert_coinbase_resources(items, "coinbase");
+
+        // Resources payable only with unknown schemes are skipped, not listed as exact
+        let urls: Vec<&str> = resources.iter().map(|r| r.url.as_str()).collect();
+        assert_eq!(urls, ["https://api.example.com/metered", "https://api.example.com/mixed"]);
+        assert!(resources[0].accepts.iter().all(|req| req.scheme == Scheme::Upto));
+        assert_eq!(resources[0].accepts.len(), 2);
+        assert_eq!(resources[1].accepts.len(), 1);
+        assert_eq!(resources[1].accepts[0].scheme, Scheme::Exact);
+
+        let json = serde_json::to_value(&resources[0]).unwrap();
+        assert_eq!(json["accepts"][0]["scheme"], "upto");
+    }
+
+    /// A listing priced on Base, Polygon and Avalanche, one on Polygon only, and a
+    /// free one on Base.
+    const MULTI_NETWORK_LISTING: &str = r#"{
+        "x402Version": 1,
+        "items": [
+            {
+                "resource": "https://api.example.com/multi",
+                "type": "http",
+                "accepts": [
+                    {"network": "base", "maxAmountRequired": "10000", "payTo": "0x1234567890123456789012345678901234567890",
+                     "asset": "0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913"},
+                    {"network": "polygon", "maxAmountRequired": "10000", "payTo": "0x1234567890123456789012345678901234567890",
+                     "asset": "0x3c499c542cEF5E3811e1192ce70d8cC03d5c3359"},
+                    {"network": "avalanche", "maxAmountRequired": "10000", "payTo": "0x1234567890123456789012345678901234567890",
+                     "asset": "0xB97EF9Ef8734C71904D8002F8b6Bc66Dd9c48a6E"}
+                ]
+            },
+            {
+                "resource": "https://api.example.com/polygon-only",
+                "type": "http",
+                "accepts": [
+                    {"network": "polygon", "maxAmountRequired": "10000", "payTo": "0x1234567890123456789012345678901234567890",
+                     "asset": "0x3c499c542cEF5E3811e1192ce70d8cC03d5c3359"}
+                ]
+            },
+            {
+                "resource": "https://api.example.com/free",
+                "type": "http",
+                "accepts": [
+                    {"network": "base", "maxAmountRequired": "0", "payTo": "0x1234567890123456789012345678901234567890",
+                     "asset": "0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913"}
+                ]
+            }
+        ]
+    }"#;
+
+    #[test]
+    fn test_filter_keeps_only_allowed_accepts() {
+        let aggregator = DiscoveryAggregator::with_facilitators(vec![]).with_filter(AggregatorFilter {
+            allowed_networks: Some(vec![Caip2NetworkId::eip155(8453), Caip2NetworkId::eip155(43114)]),
+            allowed_assets: None,
+            require_nonzero_amount: true,
+        });
+        let (items, _) = aggregator.parse_discovery_response(MULTI_NETWORK_LISTING, "coinbase").unwrap();
+        let resources = aggregator.convert_coinbase_resources(items, "coinbase");
+
+        // The Polygon-only and free listings are skipped entirely
+        assert_eq!(resources.len(), 1);
+        assert_eq!(resources[0].url.as_str(), "https://api.example.com/multi");
+        let networks: Vec<String> = resources[0].accepts.iter().map(|a| a.network.to_string()).collect();
+        assert_eq!(networks, ["eip155:8453", "eip155:43114"]);
+
+        let usdc_on_base = parse_address("0x833589fcd6edb6e08f4c7c32d4f71b54bda02913").unwrap();
+        let aggregator = DiscoveryAggregator::with_facilitators(vec![]).with_filter(AggregatorFilter {
+            allowed_assets: Some(vec![usdc_on_base.clone()]),
+            ..AggregatorFilter::default()
+        });
+        let (items, _) = aggregator.parse_discovery_response(MULTI_NETWORK_LISTING, "coinbase").unwrap();
+        let resources = aggregator.convert_coinbase_resources(items, "coinbase");
+        let urls: Vec<&str> = resources.iter().map(|r| r.url.as_str()).collect();
+        assert_eq!(urls, ["https://api.example.com/multi", "https://api.example.com/free"]);
+        assert!(resources.iter().all(|r| r.accepts.len() == 1 && r.accepts[0].asset == usdc_on_base));
+    }
+
+    #[test]
+    fn test_filter_rejects_resources_without_allowed_accepts() {
+        let aggregator = DiscoveryAggregator::with_facilitators(vec![]).with_filter(AggregatorFilter {
+            allowed_networks: Some(vec![Caip2NetworkId::eip155(1)]),
+            ..AggregatorFilter::default()
+        });
+        let (items, _) = aggregator.parse_discovery_response(MULTI_NETWORK_LISTING, "coinbase").unwrap();
+        assert!(aggregator.convert_coinbase_resources(items, "coinbase").is_empty());
+
+        // Without a filter every listing is imported as is
+        let aggregator = DiscoveryAggregator::with_facilitators(vec![]);
+        let (items, _) = aggregator.parse_discovery_response(MULTI_NETWORK_LISTING, "coinbase").unwrap();
+        let resources = aggregator.convert_coinbase_resources(items, "coinbase");
+        assert_eq!(resources.iter().map(|r| r.accepts.len()).collect::<Vec<_>>(), [3, 1, 1]);
+    }
+
+    #[tokio::test]
+    async fn test_unmodeled_fields_survive_aggregation_golden() {
+        let golden = include_str!("../tests/fixtures/coinbase_bazaar_item.json");
+        let origin: Value = serde_json::from_str(golden).unwrap();
+        let origin_accept = &origin["items"][0]["accepts"][0];
+
+        let aggregator = DiscoveryAggregator::new();
+        let (items, _) = aggregator.parse_discovery_response(golden, "coinbase").unwrap();
+        let resources = aggregator.convert_coinbase_resources(items, "coinbase");
+
+        // Republish and read back as clients of GET /discovery/resources would
+        let registry = crate::discovery::DiscoveryRegistry::new();
+        registry
+            .bulk_import(resources, false, &[ConflictStrategy::PreferNewer])
+            .await
+            .unwrap();
+        let listed = serde_json::to_value(registry.list(10, 0, None).await).unwrap();
+        let accept = &listed["items"][0]["accepts"][0];
+
+        assert_eq!(accept["network"], "eip155:8453");
+        assert_eq!(accept["amount"], "1000");
+        let extra = accept["extra"].as_object().unwrap();
+        for (key, value) in origin_accept["extra"].as_object().unwrap() {
+            assert_eq!(&extra[key], value, "extra.{key}");
+        }
+        for key in ["outputSchema", "description", "mimeType", "resource"] {
+            assert_eq!(extra[key], origin_accept[key], "{key}");
+        }
+        // Modeled fields are not duplicated into extra
+        for key in ["network", "asset", "payTo", "maxAmountRequired", "scheme"] {
+            assert!(!extra.contains_key(key), "{key} leaked into extra");
+        }
+        assert!(listed["items"][0]["metadata"].get("extra").is_none());
+    }
+
+    #[test]
+    fn test_carry_accept_extra() {
+        let fields = |value: Value| value.as_object().unwrap().clone();
+
+        assert_eq!(carry_accept_extra(Map::new()), None);
+        assert_eq!(carry_accept_extra(fields(serde_json::json!({"extra": null}))), None);
+        assert_eq!(
+            carry_accept_extra(fields(serde_json::json!({"extra": {"name": "USDC"}}))),
+            Some(serde_json::json!({"name": "USDC"}))
+        );
+        // The origin's entries win over promoted fields
+        assert_eq!(
+            carry_accept_extra(fields(serde_json::json!({
+                "extra": {"description": "kept"},
+                "description": "dropped",
+                "outputSchema": {"input": {"type": "http"}}
+            }))),
+            Some(serde_json::json!({
+                "description": "kept",
+                "outputSchema": {"input": {"type": "http"}}
+            }))
+        );
+        assert_eq!(
+            carry_accept_extra(fields(serde_json::json!({"extra": "opaque", "mimeType": "text/csv"}))),
+            Some(serde_json::json!({"extra": "opaque", "mimeType": "text/csv"}))
+        );
+    }
+
+    #[test]
+    fn test_unmodeled_resource_fields_go_to_metadata() {
+        let aggregator = DiscoveryAggregator::new();
+        let item: CoinbaseResource = serde_json::from_value(serde_json::json!({
+            "resource": "https://api.example.com/mcp",
+            "type": "mcp",
+            "x402Version": 1,
+            "accepts": [],
+            "inputSchema": {"tools": ["search"]},
+            "icon": "https://api.example.com/icon.png"
+        }))
+        .unwrap();
+
+        let resource = aggregator.convert_single_resource(item, "coinbase").unwrap();
+        let metadata = resource.metadata.unwrap();
+        assert_eq!(metadata.extra.len(), 2);
+        assert_eq!(metadata.extra["inputSchema"], serde_json::json!({"tools": ["search"]}));
+        assert_eq!(metadata.category, None);
+    }
+
+    #[test]
+    fn test_convert_normalizes_category_and_tags() {
+        let aggregator = DiscoveryAggregator::new();
+        let item: CoinbaseResource = serde_json::from_value(serde_json::json!({
+            "resource": "https://api.example.com/chat",
+            "type": "http",
+            "x402Version": 1,
+            "accepts": [],
+            "metadata": {
+                "category": "Artificial Intelligence",
+                "tags": ["LLM", "large language models", " ", "Chat Bots"]
+            }
+        }))
+        .unwrap();
+
+        let resource = aggregator.convert_single_resource(item, "coinbase").unwrap();
+        let metadata = resource.metadata.unwrap();
+        assert_eq!(metadata.category.as_deref(), Some("ai"));
+        assert_eq!(metadata.tags, vec!["llm", "chat-bots"]);
+    }
+
+    #[test]
+    fn test_source_breaker_state_machine() {
+        let mut breaker = SourceBreaker::default();
+        let now = 1_700_000_000;
+
+        assert_eq!(breaker.record_failure(now, 3), None);
+        assert_eq!(breaker.record_failure(now, 3), None);
+        assert!(!breaker.is_open(now));
+        // A success in between resets the count
+        assert!(!breaker.record_success());
+        assert!(breaker.is_healthy());
+
+        for _ in 0..2 {
+            assert_eq!(breaker.record_failure(now, 3), None);
+        }
+        assert_eq!(breaker.record_failure(now, 3), Some(Duration::from_secs(300)));
+        assert!(breaker.is_open(now));
+        assert!(breaker.is_open(now + 299));
+        assert_eq!(SourceBreaker::default().record_failure(now, 0), None);
+        assert!(!breaker.is_open(now + 300));
+
+        // Each failed probe doubles the cool-down, up to 6 hours
+        let mut cooldowns = Vec::new();
+        let mut probe_at = now + 300;
+        for _ in 0..8 {
+            let cooldown = breaker.record_failure(probe_at, 3).unwrap();
+            cooldowns.push(cooldown.as_secs());
+            probe_at += cooldown.as_secs();
+        }
+        assert_eq!(cooldowns, vec![600, 1200, 2400, 4800, 9600, 19200, 21600, 21600]);
+        assert_eq!(breaker.trips, 9);
+
+        // One success closes it completely
+        assert!(breaker.record_success());
+        assert_eq!(breaker, SourceBreaker::default());
+        assert!(!breaker.is_open(probe_at));
+    }
+
+    #[tokio::test]
+    async fn test_retry_classification() {
+        for status in [429, 500, 502, 503, 504] {
+            assert!(is_retryable_status(StatusCode::from_u16(status).unwrap()), "{status}");
+        }
+        for status in [200, 304, 400, 401, 403, 404, 410] {
+            assert!(!is_retryable_status(StatusCode::from_u16(status).unwrap()), "{status}");
+        }
+
+        // Nothing listens on port 1
+        let refused = Client::new().get("http://127.0.0.1:1/").send().await.unwrap_err();
+        assert!(is_retryable_error(&refused));
+        let invalid = Client::new().get("http://").send().await.unwrap_err();
+        assert!(!is_retryable_error(&invalid));
+
+        let backoff = Duration::from_millis(100);
+        for (attempt, base) in [(1, 100), (2, 200), (3, 400)] {
+            let delay = retry_delay(backoff, attempt);
+            assert!(delay >= Duration::from_millis(base), "attempt {attempt}: {delay:?}");
+            assert!(delay <= Duration::from_millis(base * 3 / 2), "attempt {attempt}: {delay:?}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_transient_failure_is_retried() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/discovery/resources"))
+            .respond_with(ResponseTemplate::new(502))
+            .up_to_n_times(1)
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/discovery/resources"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "items": [{ "url": "https://a.example.com/", "lastUpdated": 1 }],
+                "pagination": { "total": 1 }
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let config = test_config("flaky", format!("{}/discovery/resources", server.uri()));
+        let aggregator = DiscoveryAggregator::with_facilitators(vec![config.clone()])
+            .with_retry_backoff(Duration::from_millis(10));
+        let (resources, fetched) = fetch_catalog(&aggregator, &config).await;
+        assert_eq!(fetched, 1);
+        assert_eq!(resources[0].url.as_str(), "https://a.example.com/");
+    }
+
+    #[tokio::test]
+    async fn test_client_errors_and_exhausted_retries_fail() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/missing"))
+            .respond_with(ResponseTemplate::new(404))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/down"))
+            .respond_with(ResponseTemplate::new(503))
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let missing = test_config("missing", format!("{}/missing", server.uri()));
+        let down = test_config("down", format!("{}/down", server.uri()));
+        let aggregator = DiscoveryAggregator::with_facilitators(vec![missing.clone(), down.clone()])
+            .with_max_attempts(2)
+            .with_retry_backoff(Duration::from_millis(10));
+
+        let error = aggregator.fetch_from_facilitator(&missing).await.err().unwrap();
+        assert!(error.to_string().contains("404"), "{error}");
+        let error = aggregator.fetch_from_facilitator(&down).await.err().unwrap();
+        assert!(error.to_string().contains("503"), "{error}");
+    }
+
+    #[tokio::test]
+    async fn test_retries_stop_at_facilitator_timeout() {
+        let mut config = test_config("down", "http://127.0.0.1:1/discovery/resources".to_string());
+        config.timeout_secs = 1;
+        let aggregator = DiscoveryAggregator::with_facilitators(vec![config.clone()])
+            .with_max_attempts(10)
+            .with_retry_backoff(Duration::from_millis(400));
+
+        let started = Instant::now();
+        assert!(aggregator.fetch_from_facilitator(&config).await.is_err());
+        // 400ms then 800ms+ of backoff would exceed the 1s budget, so only one retry runs
+        assert!(started.elapsed() < Duration::from_secs(1), "took {:?}", started.elapsed());
+    }
+
+    #[test]
+    fn test_rate_limit_delay() {
+        let now = 1_700_000_000;
+        let headers = |pairs: &[(&'static str, &str)]| {
+            let mut headers = HeaderMap::new();
+            for (name, value) in pairs {
+                headers.insert(*name, value.parse().unwrap());
+            }
+            headers
+        };
+        let delay = |pairs: &[(&'static str, &str)]| rate_limit_delay(&headers(pairs), now);
+
+        assert_eq!(delay(&[("retry-after", "7")]), Some(Duration::from_secs(7)));
+        // 2023-11-14T22:13:20Z is `now`
+        assert_eq!(
+            delay(&[("retry-after", "Tue, 14 Nov 2023 22:13:50 GMT")]),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(
+            delay(&[("retry-after", "Tue, 14 Nov 2023 22:00:00 GMT")]),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(delay(&[("x-ratelimit-reset", "12")]), Some(Duration::from_secs(12)));
+        assert_eq!(
+            delay(&[("x-ratelimit-reset", "1700000045")]),
+            Some(Duration::from_secs(45))
+        );
+        assert_eq!(
+            delay(&[("x-ratelimit-reset", "1700000045000")]),
+            Some(Duration::from_secs(45))
+        );
+        // Retry-After wins, and an unreadable one falls back to X-RateLimit-Reset
+        assert_eq!(
+            delay(&[("retry-after", "3"), ("x-ratelimit-reset", "12")]),
+            Some(Duration::from_secs(3))
+        );
+        assert_eq!(
+            delay(&[("retry-after", "soon"), ("x-ratelimit-reset", "12")]),
+            Some(Duration::from_secs(12))
+        );
+        assert_eq!(delay(&[]), None);
+        assert_eq!(delay(&[("retry-after", "soon")]), None);
+    }
+
+    #[tokio::test]
+    async fn test_rate_limited_page_is_requested_again() {
+        use wiremock::matchers::{method, query_param};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(query_param("cursor", "c1"))
+            .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "1"))
+            .up_to_n_times(1)
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(query_param("cursor", "c1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "items": [{ "url": "https://b.example.com/", "lastUpdated": 1 }]
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "items": [{ "url": "https://a.example.com/", "lastUpdated": 1 }],
+                "pagination": { "nextCursor": "c1" }
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let mut config = test_config("limited", format!("{}/discovery/resources", server.uri()));
+        // The wait doesn't count against the timeout
+        config.timeout_secs = 1;
+        let aggregator = DiscoveryAggregator::with_facilitators(vec![config.clone()])
+            .with_retry_backoff(Duration::from_millis(10));
+
+        let started = Instant::now();
+        let (resources, fetched) = fetch_catalog(&aggregator, &config).await;
+        assert!(started.elapsed() >= Duration::from_secs(1), "took {:?}", started.elapsed());
+        assert_eq!(fetched, 2);
+        let urls: Vec<&str> = resources.iter().map(|r| r.url.as_str()).collect();
+        assert_eq!(urls, ["https://a.example.com/", "https://b.example.com/"]);
+    }
+
+    #[tokio::test]
+    async fn test_unavailable_with_http_date_is_retried() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let retry_at = chrono::DateTime::from_timestamp(unix_now() as i64 + 1, 0).unwrap();
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(503).insert_header(
+                "Retry-After",
+                retry_at.format("%a, %d %b %Y %H:%M:%S GMT").to_string(),
+            ))
+            .up_to_n_times(1)
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "items": [{ "url": "https://a.example.com/", "lastUpdated": 1 }]
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let config = test_config("maintenance", format!("{}/discovery/resources", server.uri()));
+        let aggregator = DiscoveryAggregator::with_facilitators(vec![config.clone()]);
+        let (_, fetched) = fetch_catalog(&aggregator, &config).await;
+        assert_eq!(fetched, 1);
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_beyond_budget_fails() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(429).insert_header(
+                "X-RateLimit-Reset",
+                (unix_now() + 3600).to_string(),
+            ))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let config = test_config("limited", format!("{}/discovery/resources", server.uri()));
+        let aggregator = DiscoveryAggregator::with_facilitators(vec![config.clone()])
+            .with_rate_limit_budget(Duration::from_secs(30));
+
+        let started = Instant::now();
+        let error = aggregator.fetch_from_facilitator(&config).await.err().unwrap();
+        assert!(error.to_string().contains("429"), "{error}");
+        assert!(started.elapsed() < Duration::from_secs(1), "took {:?}", started.elapsed());
+    }
+
+    #[tokio::test]
+    async fn test_min_request_interval_spaces_pages() {
+        use wiremock::matchers::{method, query_param};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(query_param("cursor", "c1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "items": [{ "url": "https://b.example.com/", "lastUpdated": 1 }]
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "items": [{ "url": "https://a.example.com/", "lastUpdated": 1 }],
+                "pagination": { "nextCursor": "c1" }
+            })))
+            .mount(&server)
+            .await;
+
+        let mut config = test_config("slow", format!("{}/discovery/resources", server.uri()));
+        config.min_request_interval_ms = Some(300);
+        let aggregator = DiscoveryAggregator::with_facilitators(vec![config.clone()]);
+
+        let started = Instant::now();
+        let (_, fetched) = fetch_catalog(&aggregator, &config).await;
+        assert_eq!(fetched, 2);
+        assert!(started.elapsed() >= Duration::from_millis(300), "took {:?}", started.elapsed());
+    }
+
+    #[tokio::test]
+    async fn test_open_breaker_skips_facilitator() {
+        // Nothing listens on port 1: every fetch fails without a server
+        let mut config = FacilitatorConfig::coinbase();
+        config.id = "down".to_string();
+        config.discovery_url = "http://127.0.0.1:1/discovery/resources".to_string();
+        config.api_key_env = None;
+        let aggregator = DiscoveryAggregator::with_facilitators(vec![config]).with_breaker_threshold(2);
+
+        let (_, report) = aggregator.fetch_all_with_report().await;
+        assert_eq!(report.per_source[0].breaker.consecutive_failures, 1);
+        assert_eq!(report.per_source[0].breaker.open_until, None);
+
+        let (_, report) = aggregator.fetch_all_with_report().await;
+        let until = report.per_source[0].breaker.open_until.unwrap();
+        assert!(until >= unix_now() + 299);
+
+        let (_, report) = aggregator.fetch_all_with_report().await;
+        let source = &report.per_source[0];
+        assert_eq!(source.error.as_deref(), Some(format!("circuit breaker open until {until}").as_str()));
+        assert_eq!(source.duration_ms, 0);
+        assert_eq!(aggregator.breaker("down").consecutive_failures, 2);
+        let status = serde_json::to_value(source).unwrap();
+        assert_eq!(status["breaker"]["openUntil"], until);
+
+        // Once recovered, one success resets the breaker
+        assert!(aggregator.record_outcome("down", true, until).is_healthy());
+        assert_eq!(aggregator.breaker("down"), SourceBreaker::default());
     }
 
     #[test]
@@ -929,6 +4581,206 @@ mod tests {
         assert!(config.discovery_url.contains("coinbase"));
     }
 
+    fn write_sources_file(extension: &str, content: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "x402-sources-{:x}.{}",
+            rand::random::<u64>(),
+            extension
+        ));
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_sources_file_merges_over_builtin() {
+        let path = write_sources_file(
+            "toml",
+            r#"
+            [[facilitators]]
+            id = "coinbase"
+            enabled = false
+            comment = "unknown fields are ignored"
+
+            [[facilitators]]
+            id = "payai"
+            timeout_secs = 5
+
+            [[facilitators]]
+            id = "partner"
+            name = "Partner Bazaar"
+            discovery_url = "https://partner.example.com/discovery/resources"
+            headers = { "x-client" = "ultravioleta" }
+            "#,
+        );
+        let configs = FacilitatorConfig::from_file(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(configs.len(), 13);
+        let coinbase = configs.iter().find(|c| c.id == "coinbase").unwrap();
+        assert!(!coinbase.enabled);
+        assert_eq!(coinbase.discovery_url, FacilitatorConfig::coinbase().discovery_url);
+        let payai = configs.iter().find(|c| c.id == "payai").unwrap();
+        assert_eq!(payai.timeout_secs, 5);
+        assert!(payai.enabled);
+
+        let partner = configs.last().unwrap();
+        assert_eq!(partner.id, "partner");
+        assert_eq!(partner.name, "Partner Bazaar");
+        assert!(partner.enabled);
+        assert_eq!(partner.timeout_secs, 30);
+        assert_eq!(partner.headers, vec![("x-client".to_string(), "ultravioleta".to_string())]);
+    }
+
+    #[test]
+    fn test_sources_file_json_without_builtin() {
+        let path = write_sources_file(
+            "json",
+            r#"{
+                "include_builtin": false,
+                "facilitators": [
+                    { "id": "peer", "discovery_url": "srv+https://_x402._tcp.peer.example.com" }
+                ]
+            }"#,
+        );
+        let configs = FacilitatorConfig::from_file(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(configs.len(), 1);
+        assert_eq!(configs[0].id, "peer");
+        assert_eq!(configs[0].name, "peer");
+    }
+
+    #[test]
+    fn test_sources_file_rejects_invalid_entries() {
+        let resolve = |json: &str| {
+            serde_json::from_str::<FacilitatorSources>(json)
+                .unwrap()
+                .resolve()
+                .unwrap_err()
+                .to_string()
+        };
+
+        let err = resolve(r#"{ "facilitators": [{ "id": "coinbase", "enabled": false }, { "id": "coinbase" }] }"#);
+        assert!(err.contains("duplicate facilitator id coinbase"), "{}", err);
+
+        let err = resolve(r#"{ "facilitators": [{ "id": "partner" }] }"#);
+        assert!(err.contains("partner has no discovery_url"), "{}", err);
+
+        let err = resolve(r#"{ "facilitators": [{ "id": "payai", "discovery_url": "not a url" }] }"#);
+        assert!(err.contains("payai has an invalid discovery_url"), "{}", err);
+    }
+
+    async fn supported_server(body: Value) -> wiremock::MockServer {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/supported"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(body))
+            .mount(&server)
+            .await;
+        server
+    }
+
+    #[tokio::test]
+    async fn test_probe_builds_custom_config() {
+        let server = supported_server(serde_json::json!({
+            "facilitatorId": "Partner",
+            "kinds": [
+                { "x402Version": 2, "scheme": "exact", "network": "eip155:8453" },
+                { "x402Version": 2, "scheme": "future-scheme", "network": "eip155:8453" }
+            ],
+            "extensions": ["bazaar"]
+        }))
+        .await;
+
+        let config = FacilitatorConfig::probe(&format!("{}/", server.uri()))
+            .await
+            .unwrap();
+        assert_eq!(config.id, "partner");
+        assert_eq!(
+            config.discovery_url,
+            format!("{}/discovery/resources", server.uri())
+        );
+        assert!(config.enabled);
+
+        // Without an advertised id, the id is a stable hash of the URL
+        let server = supported_server(serde_json::json!({
+            "kinds": [{ "x402Version": 1, "scheme": "exact", "network": "base" }]
+        }))
+        .await;
+        let first = FacilitatorConfig::probe(&server.uri()).await.unwrap();
+        let second = FacilitatorConfig::probe(&server.uri()).await.unwrap();
+        assert!(first.id.starts_with("custom-"), "{}", first.id);
+        assert_eq!(first.id, second.id);
+        assert_eq!(first.id, url_id(&server.uri()));
+    }
+
+    #[tokio::test]
+    async fn test_probe_rejects_invalid_facilitators() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let err = FacilitatorConfig::probe("ftp://facilitator.example.com")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AggregatorError::InvalidUrl(_)), "{}", err);
+
+        let down = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/supported"))
+            .respond_with(ResponseTemplate::new(503))
+            .mount(&down)
+            .await;
+        let err = FacilitatorConfig::probe(&down.uri()).await.unwrap_err();
+        assert!(err.to_string().contains("503"), "{}", err);
+
+        let not_supported = supported_server(serde_json::json!({ "status": "ok" })).await;
+        let err = FacilitatorConfig::probe(&not_supported.uri())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AggregatorError::ParseError(_)), "{}", err);
+
+        let unknown_kinds = supported_server(serde_json::json!({
+            "kinds": [{ "x402Version": 2, "scheme": "future-scheme", "network": "eip155:8453" }]
+        }))
+        .await;
+        let err = FacilitatorConfig::probe(&unknown_kinds.uri())
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(err, AggregatorError::FacilitatorError(_)),
+            "{}",
+            err
+        );
+    }
+
+    #[tokio::test]
+    async fn test_extra_facilitators_skip_failures_and_duplicates() {
+        let kinds =
+            serde_json::json!([{ "x402Version": 2, "scheme": "exact", "network": "eip155:8453" }]);
+        let partner =
+            supported_server(serde_json::json!({ "facilitatorId": "partner", "kinds": kinds }))
+                .await;
+        let clash =
+            supported_server(serde_json::json!({ "facilitatorId": "coinbase", "kinds": kinds }))
+                .await;
+        let anonymous = supported_server(serde_json::json!({ "kinds": kinds })).await;
+        let empty = supported_server(serde_json::json!({ "kinds": [] })).await;
+
+        let mut facilitators = FacilitatorConfig::all();
+        let urls = [&partner, &clash, &anonymous, &empty].map(|server| server.uri());
+        extend_with_probes(&mut facilitators, &urls).await;
+
+        let added: Vec<&str> = facilitators[12..].iter().map(|f| f.id.as_str()).collect();
+        assert_eq!(added, vec!["partner".to_string(), url_id(&anonymous.uri())]);
+        assert_eq!(
+            facilitators.iter().filter(|f| f.id == "coinbase").count(),
+            1
+        );
+    }
+
     #[test]
     fn test_all_facilitators() {
         let all = FacilitatorConfig::all();
@@ -951,24 +4803,60 @@ mod tests {
     }
 
     #[test]
-    fn test_parse_iso8601_to_unix() {
-        // Test a known date (Unix epoch)
-        let epoch = parse_iso8601_to_unix("1970-01-01T00:00:00Z").unwrap();
-        assert_eq!(epoch, 0);
-
-        // Test Y2K
-        let y2k = parse_iso8601_to_unix("2000-01-01T00:00:00Z").unwrap();
-        assert_eq!(y2k, 946684800);
-
-        // Test full ISO8601 with milliseconds
-        let ts = parse_iso8601_to_unix("2026-01-06T20:22:59.724Z").unwrap();
-        // Just verify it parses and returns a reasonable value (around 2026)
-        assert!(ts > 1700000000); // After 2023
-        assert!(ts < 1800000000); // Before 2027
+    fn test_parse_timestamp_shapes() {
+        // 2026-01-06T20:22:59Z
+        const T: u64 = 1767730979;
+        let cases: &[(&str, Option<u64>)] = &[
+            ("1970-01-01T00:00:00Z", Some(0)),
+            ("2000-01-01T00:00:00Z", Some(946684800)),
+            ("2026-01-06T20:22:59Z", Some(T)),
+            ("2026-01-06T20:22:59.724Z", Some(T)),
+            ("2026-01-06T20:22:59.724123Z", Some(T)),
+            ("2026-01-06T20:22:59.724123456z", Some(T)),
+            ("2026-01-06T22:22:59+02:00", Some(T)),
+            ("2026-01-06T15:22:59.5-05:00", Some(T)),
+            ("2026-01-06T22:22:59+0200", Some(T)),
+            ("2026-01-06 20:22:59", Some(T)),
+            ("2026-01-06 20:22:59.724", Some(T)),
+            ("2026-01-06 20:22:59 UTC", Some(T)),
+            ("2026-01-06 20:22:59+00:00", Some(T)),
+            ("2026-01-06T20:22:59", Some(T)),
+            ("2026-01-06T20:22", Some(T - 59)),
+            ("2026-01-06", Some(1767657600)),
+            ("Tue, 06 Jan 2026 20:22:59 GMT", Some(T)),
+            ("1767730979", Some(T)),
+            ("1767730979724", Some(T)),
+            ("1767730979724123", Some(T)),
+            (" 2026-01-06T20:22:59Z ", Some(T)),
+            ("1969-12-31T23:59:59Z", None),
+            ("2026-13-01T00:00:00Z", None),
+            ("2026-01-06T25:00:00Z", None),
+            ("yesterday", None),
+            ("", None),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(parse_timestamp(input), *expected, "parsing {input:?}");
+        }
+    }
 
-        // Test ISO8601 without milliseconds should give same result
-        let ts2 = parse_iso8601_to_unix("2026-01-06T20:22:59Z").unwrap();
-        assert_eq!(ts, ts2);
+    #[test]
+    fn test_deserialize_flexible_timestamp_lenient() {
+        #[derive(Deserialize)]
+        struct TestStruct {
+            #[serde(default, deserialize_with = "deserialize_flexible_timestamp")]
+            last_updated: Option<u64>,
+        }
+        let parse = |value: serde_json::Value| {
+            serde_json::from_value::<TestStruct>(serde_json::json!({ "last_updated": value }))
+                .unwrap()
+                .last_updated
+        };
+        assert_eq!(parse(serde_json::json!("2026-01-06T22:22:59+02:00")), Some(1767730979));
+        assert_eq!(parse(serde_json::json!(1767730979724u64)), Some(1767730979));
+        assert_eq!(parse(serde_json::json!(1767730979.5)), Some(1767730979));
+        // Unusable values are dropped rather than failing the resource
+        assert_eq!(parse(serde_json::json!("not a date")), None);
+        assert_eq!(parse(serde_json::json!(-5)), None);
     }
 
     #[test]
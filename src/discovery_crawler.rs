@@ -53,7 +53,7 @@ use std::time::Duration;
 use tracing::{debug, error, info, warn};
 use url::Url;
 
-use crate::discovery::DiscoveryRegistry;
+use crate::discovery::{ConflictStrategy, DiscoveryRegistry};
 use crate::types_v2::{DiscoveryResource, DiscoverySource, PaymentRequirementsV2};
 
 // ============================================================================
@@ -239,6 +239,10 @@ impl DiscoveryCrawler {
             category: m.category,
             provider: m.provider,
             tags: m.tags,
+            sources: Vec::new(),
+            liveness: None,
+            reputation: None,
+            extra: serde_json::Map::new(),
         });
 
         DiscoveryResource {
@@ -253,6 +257,8 @@ impl DiscoveryCrawler {
             source_facilitator: Some(source_domain.to_string()),
             first_seen: Some(now),
             settlement_count: None,
+            is_healthy: true,
+            owner: None,
         }
     }
 
@@ -277,7 +283,10 @@ impl DiscoveryCrawler {
 
                     let resource_count = resources.len();
 
-                    match registry.bulk_import(resources, true).await {
+                    match registry
+                        .bulk_import(resources, true, &[ConflictStrategy::PreferNewer])
+                        .await
+                    {
                         Ok((added, updated, skipped)) => {
                             summary.targets_crawled += 1;
                             summary.resources_added += added;
@@ -0,0 +1,495 @@
+//! Health monitoring of discovery resources.
+//!
+//! Resources listed in the Bazaar can go offline while staying registered. The
+//! [`HealthMonitor`] periodically sends a `HEAD` request to every resource URL and
+//! reports the outcome to the [`DiscoveryRegistry`], which hides a resource after
+//! `failure_threshold` consecutive failures and lists it again once it answers.
+//! Status and latency of each check are recorded on the resource's metadata.
+//!
+//! A check fails on a connection error or timeout, a 5xx status, or `404`/`410`.
+//! Any other status counts as healthy: paid endpoints commonly answer an unpaid
+//! request with `402`. Endpoints rejecting `HEAD` with `405`/`501` are checked with
+//! `GET` instead.
+//!
+//! At most [`CHECK_CONCURRENCY`] checks run at once, and at most [`PER_HOST_CONCURRENCY`]
+//! against the same host, so that a host serving many resources is not flooded.
+//!
+//! # Configuration
+//!
+//! - `DISCOVERY_ENABLE_HEALTH_CHECKS`: run the monitor (default: false)
+//! - `DISCOVERY_HEALTH_CHECK_INTERVAL`: seconds between check rounds (default: 300)
+//! - `DISCOVERY_HEALTH_FAILURE_THRESHOLD`: consecutive failures before deactivation (default: 3)
+//! - `DISCOVERY_HEALTH_REQUEST_TIMEOUT`: seconds before a check times out (default: 10)
+
+use futures::stream::{self, StreamExt};
+use reqwest::{Client, StatusCode};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Semaphore;
+use tracing::{debug, info};
+
+use crate::discovery::{DiscoveryRegistry, HealthCheck};
+
+/// Default seconds between check rounds.
+pub const DEFAULT_HEALTH_CHECK_INTERVAL_SECS: u64 = 300;
+
+/// Default consecutive failures before a resource is marked unhealthy.
+pub const DEFAULT_FAILURE_THRESHOLD: u32 = 3;
+
+/// Default seconds before a single check times out.
+pub const DEFAULT_REQUEST_TIMEOUT_SECS: u64 = 10;
+
+/// Resources checked concurrently.
+pub const CHECK_CONCURRENCY: usize = 16;
+
+/// Resources of the same host checked concurrently.
+pub const PER_HOST_CONCURRENCY: usize = 2;
+
+/// Health monitor settings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HealthMonitorConfig {
+    /// Seconds between check rounds
+    pub health_check_interval_secs: u64,
+    /// Consecutive failures before a resource is marked unhealthy
+    pub failure_threshold: u32,
+    /// Seconds before a single check times out
+    pub request_timeout_secs: u64,
+}
+
+impl Default for HealthMonitorConfig {
+    fn default() -> Self {
+        Self {
+            health_check_interval_secs: DEFAULT_HEALTH_CHECK_INTERVAL_SECS,
+            failure_threshold: DEFAULT_FAILURE_THRESHOLD,
+            request_timeout_secs: DEFAULT_REQUEST_TIMEOUT_SECS,
+        }
+    }
+}
+
+impl HealthMonitorConfig {
+    /// Read the settings from the environment, falling back to the defaults.
+    pub fn from_env() -> Self {
+        Self {
+            health_check_interval_secs: crate::env_registry::parse("DISCOVERY_HEALTH_CHECK_INTERVAL")
+                .unwrap_or(DEFAULT_HEALTH_CHECK_INTERVAL_SECS),
+            failure_threshold: crate::env_registry::parse("DISCOVERY_HEALTH_FAILURE_THRESHOLD")
+                .unwrap_or(DEFAULT_FAILURE_THRESHOLD),
+            request_timeout_secs: crate::env_registry::parse("DISCOVERY_HEALTH_REQUEST_TIMEOUT")
+                .unwrap_or(DEFAULT_REQUEST_TIMEOUT_SECS),
+        }
+    }
+}
+
+/// Summary of one round of health checks.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HealthCheckSummary {
+    /// Resources checked
+    pub checked: usize,
+    /// Resources healthy after the round
+    pub healthy: usize,
+    /// Resources hidden from listings after the round
+    pub unhealthy: usize,
+}
+
+/// Checks that discovery resources are reachable.
+#[derive(Debug, Clone)]
+pub struct HealthMonitor {
+    client: Client,
+    config: HealthMonitorConfig,
+}
+
+impl HealthMonitor {
+    /// Create a monitor with the given settings.
+    pub fn new(config: HealthMonitorConfig) -> Self {
+        Self {
+            client: Client::builder()
+                .timeout(Duration::from_secs(config.request_timeout_secs.max(1)))
+                .user_agent("x402-facilitator/1.0 (discovery-health)")
+                .build()
+                .expect("Failed to create HTTP client"),
+            config,
+        }
+    }
+
+    /// Check every registered resource once and record the outcomes.
+    pub async fn check_all(&self, registry: &DiscoveryRegistry) -> HealthCheckSummary {
+        let urls = interleave_by_host(registry.urls().await);
+        let mut host_limits: HashMap<String, Arc<Semaphore>> = HashMap::new();
+        let checks: Vec<_> = urls
+            .into_iter()
+            .map(|url| {
+                let limit = host_limits
+                    .entry(host_of(&url))
+                    .or_insert_with(|| Arc::new(Semaphore::new(PER_HOST_CONCURRENCY)))
+                    .clone();
+                (url, limit)
+            })
+            .collect();
+
+        let outcomes: Vec<_> = stream::iter(checks)
+            .map(|(url, limit)| async move {
+                let check = {
+                    let _permit = limit.acquire_owned().await;
+                    self.check(&url).await
+                };
+                if let Some(e) = &check.error {
+                    debug!(url = %url, error = %e, "Discovery resource health check failed");
+                }
+                registry
+                    .record_health_check(&url, check, self.config.failure_threshold)
+                    .await
+            })
+            .buffer_unordered(CHECK_CONCURRENCY)
+            .collect()
+            .await;
+
+        let mut summary = HealthCheckSummary::default();
+        // Resources removed while being checked are not counted
+        for health in outcomes.into_iter().flatten() {
+            summary.checked += 1;
+            if health.is_healthy {
+                summary.healthy += 1;
+            } else {
+                summary.unhealthy += 1;
+            }
+        }
+        summary
+    }
+
+    /// Send a `HEAD` request to `url`, or a `GET` if `HEAD` is not supported.
+    async fn check(&self, url: &str) -> HealthCheck {
+        let started = Instant::now();
+        let mut response = self.client.head(url).send().await;
+        if let Ok(head) = &response {
+            if matches!(
+                head.status(),
+                StatusCode::METHOD_NOT_ALLOWED | StatusCode::NOT_IMPLEMENTED
+            ) {
+                response = self.client.get(url).send().await;
+            }
+        }
+        let latency_ms = started.elapsed().as_millis() as u64;
+
+        match response {
+            Err(e) => HealthCheck::failed(None, latency_ms, e.to_string()),
+            Ok(response) => {
+                let status = response.status();
+                if status.is_server_error()
+                    || status == StatusCode::NOT_FOUND
+                    || status == StatusCode::GONE
+                {
+                    HealthCheck::failed(
+                        Some(status.as_u16()),
+                        latency_ms,
+                        format!("HTTP {}", status.as_u16()),
+                    )
+                } else {
+                    HealthCheck::passed(status.as_u16(), latency_ms)
+                }
+            }
+        }
+    }
+}
+
+/// Host (with port) of `url`, or the whole URL if it has none.
+fn host_of(url: &str) -> String {
+    url::Url::parse(url)
+        .ok()
+        .and_then(|u| {
+            let host = u.host_str()?.to_string();
+            Some(match u.port() {
+                Some(port) => format!("{host}:{port}"),
+                None => host,
+            })
+        })
+        .unwrap_or_else(|| url.to_string())
+}
+
+/// Order `urls` round-robin across hosts, so that the resources of one host don't
+/// occupy every concurrent check while waiting for its per-host limit.
+fn interleave_by_host(urls: Vec<String>) -> Vec<String> {
+    let total = urls.len();
+    let mut by_host: BTreeMap<String, VecDeque<String>> = BTreeMap::new();
+    for url in urls {
+        by_host.entry(host_of(&url)).or_default().push_back(url);
+    }
+    let mut ordered = Vec::with_capacity(total);
+    while ordered.len() < total {
+        for queue in by_host.values_mut() {
+            ordered.extend(queue.pop_front());
+        }
+    }
+    ordered
+}
+
+/// Start a background task that checks every resource each interval.
+///
+/// The first round runs right away.
+///
+/// # Returns
+///
+/// A JoinHandle for the background task.
+pub fn start_health_monitor_task(
+    registry: DiscoveryRegistry,
+    config: HealthMonitorConfig,
+) -> tokio::task::JoinHandle<()> {
+    info!(
+        interval_secs = config.health_check_interval_secs,
+        failure_threshold = config.failure_threshold,
+        "Starting discovery health monitor background task"
+    );
+    tokio::spawn(async move {
+        let monitor = HealthMonitor::new(config);
+        let mut interval =
+            tokio::time::interval(Duration::from_secs(config.health_check_interval_secs.max(1)));
+
+        loop {
+            interval.tick().await;
+            let summary = monitor.check_all(&registry).await;
+            info!(
+                checked = summary.checked,
+                healthy = summary.healthy,
+                unhealthy = summary.unhealthy,
+                "Discovery health check round completed"
+            );
+        }
+    })
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::caip2::Caip2NetworkId;
+    use crate::types::{MixedAddress, Scheme, TokenAmount};
+    use crate::types_v2::{DiscoveryFilters, DiscoveryResource, PaymentRequirementsV2};
+    use url::Url;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn resource(url: &str) -> DiscoveryResource {
+        DiscoveryResource::new(
+            Url::parse(url).unwrap(),
+            "http".to_string(),
+            "Flaky resource".to_string(),
+            vec![PaymentRequirementsV2 {
+                scheme: Scheme::Exact,
+                network: Caip2NetworkId::eip155(8453),
+                asset: MixedAddress::Evm(
+                    "0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913"
+                        .parse()
+                        .unwrap(),
+                ),
+                amount: TokenAmount::from(1000u64),
+                pay_to: MixedAddress::Evm(
+                    "0x1234567890123456789012345678901234567890"
+                        .parse()
+                        .unwrap(),
+                ),
+                max_timeout_seconds: 300,
+                extra: None,
+            }],
+        )
+    }
+
+    fn monitor(failure_threshold: u32) -> HealthMonitor {
+        HealthMonitor::new(HealthMonitorConfig {
+            health_check_interval_secs: 60,
+            failure_threshold,
+            request_timeout_secs: 2,
+        })
+    }
+
+    #[tokio::test]
+    async fn test_flaky_resource_is_deactivated_and_recovers() {
+        let server = MockServer::start().await;
+        Mock::given(method("HEAD"))
+            .and(path("/flaky"))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(3)
+            .mount(&server)
+            .await;
+        // Unpaid requests are answered with 402 once the resource is back
+        Mock::given(method("HEAD"))
+            .and(path("/flaky"))
+            .respond_with(ResponseTemplate::new(402))
+            .mount(&server)
+            .await;
+
+        let url = format!("{}/flaky", server.uri());
+        let registry = DiscoveryRegistry::new();
+        registry.register(resource(&url)).await.unwrap();
+        let monitor = monitor(3);
+
+        for failures in 1..3 {
+            monitor.check_all(&registry).await;
+            let health = registry.health(&url).await.unwrap();
+            assert!(health.is_healthy);
+            assert_eq!(health.consecutive_failures, failures);
+            assert_eq!(registry.list(10, 0, None).await.items.len(), 1);
+        }
+
+        let summary = monitor.check_all(&registry).await;
+        assert_eq!(summary.unhealthy, 1);
+        let health = registry.health(&url).await.unwrap();
+        assert!(!health.is_healthy);
+        assert_eq!(health.last_error.as_deref(), Some("HTTP 503"));
+        assert!(health.last_checked.is_some());
+        assert!(!registry.get(&url).await.unwrap().is_healthy);
+        assert!(registry.list(10, 0, None).await.items.is_empty());
+        let all = DiscoveryFilters {
+            include_unhealthy: true,
+            ..Default::default()
+        };
+        let items = registry.list(10, 0, Some(all)).await.items;
+        assert_eq!(items.len(), 1);
+        let liveness = items[0].metadata.clone().unwrap().liveness.unwrap();
+        assert!(!liveness.healthy);
+        assert_eq!(liveness.consecutive_failures, 3);
+        assert_eq!(liveness.last_status, Some(503));
+
+        monitor.check_all(&registry).await;
+        let health = registry.health(&url).await.unwrap();
+        assert!(health.is_healthy);
+        assert_eq!(health.consecutive_failures, 0);
+        assert_eq!(registry.list(10, 0, None).await.items.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_missing_resource_counts_as_failure() {
+        let server = MockServer::start().await;
+        Mock::given(method("HEAD"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+
+        let url = format!("{}/gone", server.uri());
+        let registry = DiscoveryRegistry::new();
+        registry.register(resource(&url)).await.unwrap();
+
+        let summary = monitor(1).check_all(&registry).await;
+        assert_eq!(summary, HealthCheckSummary { checked: 1, healthy: 0, unhealthy: 1 });
+        assert_eq!(registry.health(&url).await.unwrap().last_status, Some(404));
+    }
+
+    #[tokio::test]
+    async fn test_healthy_resource_records_status_and_latency() {
+        let server = MockServer::start().await;
+        Mock::given(method("HEAD"))
+            .respond_with(ResponseTemplate::new(402).set_delay(Duration::from_millis(50)))
+            .mount(&server)
+            .await;
+
+        let url = format!("{}/paid", server.uri());
+        let registry = DiscoveryRegistry::new();
+        registry.register(resource(&url)).await.unwrap();
+
+        let summary = monitor(1).check_all(&registry).await;
+        assert_eq!(summary, HealthCheckSummary { checked: 1, healthy: 1, unhealthy: 0 });
+        let health = registry.health(&url).await.unwrap();
+        assert_eq!(health.last_status, Some(402));
+        assert!(health.latency_ms.unwrap() >= 50);
+
+        let resource = registry.get(&url).await.unwrap();
+        let liveness = resource.metadata.unwrap().liveness.unwrap();
+        assert!(liveness.healthy);
+        assert_eq!(liveness.consecutive_failures, 0);
+        assert_eq!(liveness.last_checked, health.last_checked.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_timeout_counts_as_failure() {
+        let server = MockServer::start().await;
+        Mock::given(method("HEAD"))
+            .respond_with(ResponseTemplate::new(200).set_delay(Duration::from_millis(1500)))
+            .mount(&server)
+            .await;
+
+        let url = format!("{}/slow", server.uri());
+        let registry = DiscoveryRegistry::new();
+        registry.register(resource(&url)).await.unwrap();
+
+        let monitor = HealthMonitor::new(HealthMonitorConfig {
+            health_check_interval_secs: 60,
+            failure_threshold: 1,
+            request_timeout_secs: 1,
+        });
+        let summary = monitor.check_all(&registry).await;
+        assert_eq!(summary.unhealthy, 1);
+        let health = registry.health(&url).await.unwrap();
+        assert_eq!(health.last_status, None);
+        assert!(health.last_error.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_head_not_allowed_falls_back_to_get() {
+        let server = MockServer::start().await;
+        Mock::given(method("HEAD"))
+            .respond_with(ResponseTemplate::new(405))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/gone"))
+            .respond_with(ResponseTemplate::new(410))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(402))
+            .mount(&server)
+            .await;
+
+        let registry = DiscoveryRegistry::new();
+        let paid = format!("{}/paid", server.uri());
+        let gone = format!("{}/gone", server.uri());
+        registry.register(resource(&paid)).await.unwrap();
+        registry.register(resource(&gone)).await.unwrap();
+
+        monitor(1).check_all(&registry).await;
+        assert_eq!(registry.health(&paid).await.unwrap().last_status, Some(402));
+        let health = registry.health(&gone).await.unwrap();
+        assert!(!health.is_healthy);
+        assert_eq!(health.last_status, Some(410));
+    }
+
+    #[test]
+    fn test_urls_are_interleaved_by_host() {
+        let urls = [
+            "https://a.example.com/1",
+            "https://a.example.com/2",
+            "https://a.example.com/3",
+            "https://b.example.com/1",
+            "https://a.example.com:8443/1",
+        ]
+        .map(String::from)
+        .to_vec();
+        assert_eq!(
+            interleave_by_host(urls),
+            [
+                "https://a.example.com/1",
+                "https://a.example.com:8443/1",
+                "https://b.example.com/1",
+                "https://a.example.com/2",
+                "https://a.example.com/3",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_health_survives_updates() {
+        let url = "https://api.example.com/data";
+        let registry = DiscoveryRegistry::new();
+        registry.register(resource(url)).await.unwrap();
+        registry
+            .record_health_check(url, HealthCheck::failed(None, 10_000, "timed out"), 1)
+            .await
+            .unwrap();
+
+        registry.update(resource(url)).await.unwrap();
+        assert!(!registry.get(url).await.unwrap().is_healthy);
+        assert!(registry.health("https://unknown.example.com/").await.is_none());
+    }
+}
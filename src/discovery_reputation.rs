@@ -0,0 +1,694 @@
+//! ERC-8004 reputation of discovery resources.
+//!
+//! Resources whose provider is a registered ERC-8004 agent can be ranked by that
+//! agent's reputation. The [`ReputationRefresher`] periodically reads `getSummary` from
+//! the Reputation Registry for the agent of every resource and records a normalized
+//! score on the resource's metadata, which [`DiscoveryRegistry::query`] can sort and
+//! filter by (`sort=reputation`, `min_reputation=`).
+//!
+//! The agent of a resource is taken from its metadata:
+//!
+//! ```json
+//! { "metadata": { "agentId": 42, "agentNetwork": "ethereum" } }
+//! ```
+//!
+//! `agentNetwork` defaults to Ethereum mainnet. A resource without an `agentId` is
+//! attributed to an agent whose Identity Registry wallet (`getAgentWallet`) is one of its
+//! `payTo` addresses, among the agents referenced by other resources.
+//!
+//! Listings never wait on the chain: reads happen in the background, at most
+//! [`READ_CONCURRENCY`] at once, and each summary and wallet is cached for
+//! `cache_ttl_secs`, so a round only reads agents whose entries expired.
+//!
+//! # Configuration
+//!
+//! - `DISCOVERY_ENABLE_REPUTATION`: run the refresher (default: false)
+//! - `DISCOVERY_REPUTATION_INTERVAL`: seconds between refresh rounds (default: 600)
+//! - `DISCOVERY_REPUTATION_CACHE_TTL`: seconds a read is reused (default: 3600)
+//!
+//! [`DiscoveryRegistry::query`]: crate::discovery::DiscoveryRegistry::query
+
+use alloy::primitives::{Address, U256};
+use async_trait::async_trait;
+use futures::stream::{self, StreamExt};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio::sync::RwLock;
+use tracing::{debug, info};
+
+use crate::chain::evm::InnerProvider;
+use crate::discovery::DiscoveryRegistry;
+use crate::erc8004::{Erc8004Contracts, IIdentityRegistry, IReputationRegistry, ReputationSummary};
+use crate::network::Network;
+use crate::types::MixedAddress;
+use crate::types_v2::{DiscoveryResource, ResourceReputation};
+
+/// Default seconds between refresh rounds.
+pub const DEFAULT_REFRESH_INTERVAL_SECS: u64 = 600;
+
+/// Default seconds a summary or wallet read is reused.
+pub const DEFAULT_CACHE_TTL_SECS: u64 = 3600;
+
+/// Registry reads in flight at once.
+pub const READ_CONCURRENCY: usize = 8;
+
+/// Metadata key of the resource's agent id.
+pub const AGENT_ID_KEY: &str = "agentId";
+
+/// Metadata key of the network of the resource's agent.
+pub const AGENT_NETWORK_KEY: &str = "agentNetwork";
+
+/// Network of agents whose resources don't name one.
+pub const DEFAULT_AGENT_NETWORK: Network = Network::Ethereum;
+
+/// Reputation refresher settings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReputationRefresherConfig {
+    /// Seconds between refresh rounds
+    pub refresh_interval_secs: u64,
+    /// Seconds a summary or wallet read is reused
+    pub cache_ttl_secs: u64,
+}
+
+impl Default for ReputationRefresherConfig {
+    fn default() -> Self {
+        Self {
+            refresh_interval_secs: DEFAULT_REFRESH_INTERVAL_SECS,
+            cache_ttl_secs: DEFAULT_CACHE_TTL_SECS,
+        }
+    }
+}
+
+impl ReputationRefresherConfig {
+    /// Read the settings from the environment, falling back to the defaults.
+    pub fn from_env() -> Self {
+        Self {
+            refresh_interval_secs: crate::env_registry::parse("DISCOVERY_REPUTATION_INTERVAL")
+                .unwrap_or(DEFAULT_REFRESH_INTERVAL_SECS),
+            cache_ttl_secs: crate::env_registry::parse("DISCOVERY_REPUTATION_CACHE_TTL")
+                .unwrap_or(DEFAULT_CACHE_TTL_SECS),
+        }
+    }
+}
+
+/// Reads of the ERC-8004 registries.
+#[async_trait]
+pub trait ReputationReader: Send + Sync {
+    /// `getSummary` of the Reputation Registry, over all clients and tags.
+    async fn summary(&self, network: Network, agent_id: u64) -> Result<ReputationSummary, String>;
+
+    /// `getAgentWallet` of the Identity Registry, `None` when unset.
+    async fn agent_wallet(
+        &self,
+        network: Network,
+        agent_id: u64,
+    ) -> Result<Option<Address>, String>;
+}
+
+/// Reads the registries through EVM providers.
+#[derive(Default)]
+pub struct EvmReputationReader {
+    registries: HashMap<Network, (InnerProvider, Erc8004Contracts)>,
+}
+
+impl EvmReputationReader {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Read the registries of `network` through `provider`.
+    pub fn with_network(
+        mut self,
+        network: Network,
+        provider: InnerProvider,
+        contracts: Erc8004Contracts,
+    ) -> Self {
+        self.registries.insert(network, (provider, contracts));
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.registries.is_empty()
+    }
+
+    fn registry(&self, network: Network) -> Result<&(InnerProvider, Erc8004Contracts), String> {
+        self.registries
+            .get(&network)
+            .ok_or_else(|| format!("No ERC-8004 registries for network {}", network))
+    }
+}
+
+#[async_trait]
+impl ReputationReader for EvmReputationReader {
+    async fn summary(&self, network: Network, agent_id: u64) -> Result<ReputationSummary, String> {
+        let (provider, contracts) = self.registry(network)?;
+        let result = IReputationRegistry::new(contracts.reputation_registry, provider)
+            .getSummary(U256::from(agent_id), vec![], String::new(), String::new())
+            .call()
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(ReputationSummary {
+            agent_id,
+            count: result.count,
+            summary_value: result.summaryValue,
+            summary_value_decimals: result.summaryValueDecimals,
+            network,
+        })
+    }
+
+    async fn agent_wallet(
+        &self,
+        network: Network,
+        agent_id: u64,
+    ) -> Result<Option<Address>, String> {
+        let (provider, contracts) = self.registry(network)?;
+        let wallet = IIdentityRegistry::new(contracts.identity_registry, provider)
+            .getAgentWallet(U256::from(agent_id))
+            .call()
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok((wallet != Address::ZERO).then_some(wallet))
+    }
+}
+
+/// Summary value on the 0-100 scale of ERC-8004 feedback, 0 without feedback.
+pub fn normalized_score(summary: &ReputationSummary) -> u8 {
+    if summary.count == 0 {
+        return 0;
+    }
+    let value =
+        summary.summary_value as f64 / 10f64.powi(i32::from(summary.summary_value_decimals));
+    value.clamp(0.0, 100.0).round() as u8
+}
+
+/// An agent in the registries of a network.
+type AgentRef = (Network, u64);
+
+/// Agent named by the metadata of `resource`.
+fn declared_agent(resource: &DiscoveryResource) -> Option<AgentRef> {
+    let extra = &resource.metadata.as_ref()?.extra;
+    let agent_id = match extra.get(AGENT_ID_KEY)? {
+        serde_json::Value::Number(id) => id.as_u64()?,
+        serde_json::Value::String(id) => id.trim().parse().ok()?,
+        _ => return None,
+    };
+    let network = match extra.get(AGENT_NETWORK_KEY).and_then(|n| n.as_str()) {
+        Some(network) => Network::from_caip2(network).or_else(|| network.parse().ok())?,
+        None => DEFAULT_AGENT_NETWORK,
+    };
+    Some((network, agent_id))
+}
+
+/// EVM `payTo` addresses of `resource`.
+fn pay_to_wallets(resource: &DiscoveryResource) -> impl Iterator<Item = Address> + '_ {
+    resource
+        .accepts
+        .iter()
+        .filter_map(|accept| match &accept.pay_to {
+            MixedAddress::Evm(address) => Some(address.0),
+            _ => None,
+        })
+}
+
+/// A registry read and when it was made.
+struct Cached<T> {
+    value: T,
+    read_at: Instant,
+}
+
+/// Summary of one refresh round.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReputationRefreshSummary {
+    /// Resources attributed to an agent
+    pub attributed: usize,
+    /// Resources annotated with a score
+    pub scored: usize,
+    /// Registry reads made, excluding cached ones
+    pub reads: usize,
+    /// Registry reads that failed
+    pub failed_reads: usize,
+}
+
+/// Annotates discovery resources with the reputation of their agents.
+pub struct ReputationRefresher {
+    reader: Arc<dyn ReputationReader>,
+    config: ReputationRefresherConfig,
+    summaries: RwLock<HashMap<AgentRef, Cached<ReputationSummary>>>,
+    wallets: RwLock<HashMap<AgentRef, Cached<Option<Address>>>>,
+}
+
+impl ReputationRefresher {
+    pub fn new(reader: Arc<dyn ReputationReader>, config: ReputationRefresherConfig) -> Self {
+        Self {
+            reader,
+            config,
+            summaries: RwLock::new(HashMap::new()),
+            wallets: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Read the reputation of the agents of every resource and record it.
+    pub async fn refresh_all(&self, registry: &DiscoveryRegistry) -> ReputationRefreshSummary {
+        let mut resources = Vec::new();
+        for url in registry.urls().await {
+            resources.extend(registry.get(&url).await);
+        }
+        let mut summary = ReputationRefreshSummary::default();
+
+        let declared: Vec<(&DiscoveryResource, Option<AgentRef>)> =
+            resources.iter().map(|r| (r, declared_agent(r))).collect();
+        let known: HashSet<AgentRef> = declared.iter().filter_map(|(_, agent)| *agent).collect();
+
+        // Wallets of known agents, the lowest agent id winning a shared wallet
+        let wallets = self
+            .read_all(
+                &self.wallets,
+                known.into_iter().collect(),
+                &mut summary,
+                |reader, (network, id)| async move { reader.agent_wallet(network, id).await },
+            )
+            .await;
+        let mut by_wallet: BTreeMap<Address, AgentRef> = BTreeMap::new();
+        for (agent, wallet) in wallets {
+            if let Some(wallet) = wallet {
+                let entry = by_wallet.entry(wallet).or_insert(agent);
+                if agent.1 < entry.1 {
+                    *entry = agent;
+                }
+            }
+        }
+
+        let attributed: Vec<(&DiscoveryResource, AgentRef)> = declared
+            .into_iter()
+            .filter_map(|(resource, agent)| {
+                let agent = agent.or_else(|| {
+                    pay_to_wallets(resource).find_map(|w| by_wallet.get(&w).copied())
+                })?;
+                Some((resource, agent))
+            })
+            .collect();
+        summary.attributed = attributed.len();
+
+        let agents: HashSet<AgentRef> = attributed.iter().map(|(_, agent)| *agent).collect();
+        let summaries = self
+            .read_all(
+                &self.summaries,
+                agents.into_iter().collect(),
+                &mut summary,
+                |reader, (network, id)| async move { reader.summary(network, id).await },
+            )
+            .await;
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        for (resource, agent) in attributed {
+            let Some(agent_summary) = summaries.get(&agent) else {
+                continue;
+            };
+            let reputation = ResourceReputation {
+                agent_id: agent.1,
+                network: agent
+                    .0
+                    .to_caip2()
+                    .parse()
+                    .expect("Network::to_caip2() is valid CAIP-2"),
+                feedback_count: agent_summary.count,
+                score: normalized_score(agent_summary),
+                last_updated: now,
+            };
+            if registry
+                .record_reputation(resource.url.as_str(), reputation)
+                .await
+            {
+                summary.scored += 1;
+            }
+        }
+        summary
+    }
+
+    /// Values of `agents` from `cache`, reading the missing and expired ones with `read`.
+    ///
+    /// Agents whose read failed are left out.
+    async fn read_all<T, F, Fut>(
+        &self,
+        cache: &RwLock<HashMap<AgentRef, Cached<T>>>,
+        agents: Vec<AgentRef>,
+        summary: &mut ReputationRefreshSummary,
+        read: F,
+    ) -> HashMap<AgentRef, T>
+    where
+        T: Clone,
+        F: Fn(Arc<dyn ReputationReader>, AgentRef) -> Fut,
+        Fut: std::future::Future<Output = Result<T, String>>,
+    {
+        let ttl = Duration::from_secs(self.config.cache_ttl_secs);
+        let mut values = HashMap::new();
+        let mut stale = Vec::new();
+        {
+            let cache = cache.read().await;
+            for agent in agents {
+                match cache.get(&agent) {
+                    Some(cached) if cached.read_at.elapsed() < ttl => {
+                        values.insert(agent, cached.value.clone());
+                    }
+                    _ => stale.push(agent),
+                }
+            }
+        }
+
+        let reads: Vec<_> = stream::iter(stale)
+            .map(|agent| {
+                let pending = read(Arc::clone(&self.reader), agent);
+                async move { (agent, pending.await) }
+            })
+            .buffer_unordered(READ_CONCURRENCY)
+            .collect()
+            .await;
+
+        let mut cache = cache.write().await;
+        for (agent, result) in reads {
+            summary.reads += 1;
+            match result {
+                Ok(value) => {
+                    cache.insert(
+                        agent,
+                        Cached {
+                            value: value.clone(),
+                            read_at: Instant::now(),
+                        },
+                    );
+                    values.insert(agent, value);
+                }
+                Err(e) => {
+                    summary.failed_reads += 1;
+                    debug!(network = %agent.0, agent_id = agent.1, error = %e, "ERC-8004 registry read failed");
+                }
+            }
+        }
+        values
+    }
+}
+
+/// Start a background task that refreshes reputations each interval.
+///
+/// The first round runs right away.
+///
+/// # Returns
+///
+/// A JoinHandle for the background task.
+pub fn start_reputation_refresh_task(
+    registry: DiscoveryRegistry,
+    refresher: ReputationRefresher,
+) -> tokio::task::JoinHandle<()> {
+    info!(
+        interval_secs = refresher.config.refresh_interval_secs,
+        cache_ttl_secs = refresher.config.cache_ttl_secs,
+        "Starting discovery reputation refresh background task"
+    );
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(
+            refresher.config.refresh_interval_secs.max(1),
+        ));
+
+        loop {
+            interval.tick().await;
+            let summary = refresher.refresh_all(&registry).await;
+            info!(
+                attributed = summary.attributed,
+                scored = summary.scored,
+                reads = summary.reads,
+                failed_reads = summary.failed_reads,
+                "Discovery reputation refresh round completed"
+            );
+        }
+    })
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::caip2::Caip2NetworkId;
+    use crate::types::{EvmAddress, Scheme, TokenAmount};
+    use crate::types_v2::{DiscoveryMetadata, ListQuery, ListSort, PaymentRequirementsV2};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use url::Url;
+
+    /// Registry contents served from memory, counting reads.
+    #[derive(Default)]
+    struct MockReader {
+        /// Agent id -> (feedback count, summary value with 2 decimals)
+        summaries: HashMap<u64, (u64, i128)>,
+        wallets: HashMap<u64, Address>,
+        summary_reads: AtomicUsize,
+        wallet_reads: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl ReputationReader for MockReader {
+        async fn summary(
+            &self,
+            network: Network,
+            agent_id: u64,
+        ) -> Result<ReputationSummary, String> {
+            self.summary_reads.fetch_add(1, Ordering::SeqCst);
+            let (count, summary_value) = *self
+                .summaries
+                .get(&agent_id)
+                .ok_or_else(|| format!("agent {} not found", agent_id))?;
+            Ok(ReputationSummary {
+                agent_id,
+                count,
+                summary_value,
+                summary_value_decimals: 2,
+                network,
+            })
+        }
+
+        async fn agent_wallet(
+            &self,
+            _network: Network,
+            agent_id: u64,
+        ) -> Result<Option<Address>, String> {
+            self.wallet_reads.fetch_add(1, Ordering::SeqCst);
+            Ok(self.wallets.get(&agent_id).copied())
+        }
+    }
+
+    const AGENT_WALLET: Address = Address::repeat_byte(0x42);
+
+    fn resource(
+        url: &str,
+        pay_to: Address,
+        agent: Option<serde_json::Value>,
+        last_updated: u64,
+    ) -> DiscoveryResource {
+        let mut resource = DiscoveryResource::new(
+            Url::parse(url).unwrap(),
+            "http".to_string(),
+            "Agent resource".to_string(),
+            vec![PaymentRequirementsV2 {
+                scheme: Scheme::Exact,
+                network: Caip2NetworkId::eip155(8453),
+                asset: MixedAddress::Evm(
+                    "0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913"
+                        .parse()
+                        .unwrap(),
+                ),
+                amount: TokenAmount::from(1000u64),
+                pay_to: MixedAddress::Evm(EvmAddress(pay_to)),
+                max_timeout_seconds: 300,
+                extra: None,
+            }],
+        );
+        resource.last_updated = last_updated;
+        if let Some(agent) = agent {
+            let mut metadata = DiscoveryMetadata::default();
+            metadata.extra.insert(AGENT_ID_KEY.to_string(), agent);
+            resource.metadata = Some(metadata);
+        }
+        resource
+    }
+
+    async fn registry() -> DiscoveryRegistry {
+        let registry = DiscoveryRegistry::new();
+        for resource in [
+            resource(
+                "https://trusted.example.com/api",
+                Address::repeat_byte(1),
+                Some(42.into()),
+                100,
+            ),
+            resource(
+                "https://shady.example.com/api",
+                Address::repeat_byte(2),
+                Some("7".into()),
+                300,
+            ),
+            // Attributed to agent 42 through its wallet
+            resource("https://wallet.example.com/api", AGENT_WALLET, None, 200),
+            resource(
+                "https://anonymous.example.com/api",
+                Address::repeat_byte(3),
+                None,
+                400,
+            ),
+        ] {
+            registry.register(resource).await.unwrap();
+        }
+        registry
+    }
+
+    fn reader() -> Arc<MockReader> {
+        Arc::new(MockReader {
+            summaries: HashMap::from([(42, (12, 9150)), (7, (3, 2000))]),
+            wallets: HashMap::from([(42, AGENT_WALLET)]),
+            ..Default::default()
+        })
+    }
+
+    fn urls(items: &[DiscoveryResource]) -> Vec<&str> {
+        items.iter().map(|r| r.url.as_str()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_resources_are_sorted_and_filtered_by_reputation() {
+        let registry = registry().await;
+        let refresher = ReputationRefresher::new(reader(), ReputationRefresherConfig::default());
+
+        let summary = refresher.refresh_all(&registry).await;
+        assert_eq!(summary.attributed, 3);
+        assert_eq!(summary.scored, 3);
+        assert_eq!(summary.failed_reads, 0);
+
+        let reputation = registry
+            .get("https://wallet.example.com/api")
+            .await
+            .unwrap()
+            .metadata
+            .unwrap()
+            .reputation
+            .unwrap();
+        assert_eq!(reputation.agent_id, 42);
+        assert_eq!(reputation.network, Caip2NetworkId::eip155(1));
+        assert_eq!(reputation.feedback_count, 12);
+        assert_eq!(reputation.score, 92);
+
+        let by_reputation = ListQuery {
+            sort: ListSort::Reputation,
+            ..Default::default()
+        };
+        let result = registry.query(&by_reputation).await;
+        assert_eq!(
+            urls(&result.items),
+            [
+                "https://wallet.example.com/api",
+                "https://trusted.example.com/api",
+                "https://shady.example.com/api",
+                "https://anonymous.example.com/api",
+            ]
+        );
+
+        let reputable = ListQuery {
+            min_reputation: Some(50),
+            ..Default::default()
+        };
+        let result = registry.query(&reputable).await;
+        assert_eq!(result.total, 2);
+        assert_eq!(
+            urls(&result.items),
+            [
+                "https://wallet.example.com/api",
+                "https://trusted.example.com/api"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_reads_are_cached_until_they_expire() {
+        let registry = registry().await;
+        let reader = reader();
+        let refresher =
+            ReputationRefresher::new(reader.clone(), ReputationRefresherConfig::default());
+
+        let first = refresher.refresh_all(&registry).await;
+        assert_eq!(first.reads, 4);
+        let second = refresher.refresh_all(&registry).await;
+        assert_eq!(second.reads, 0);
+        assert_eq!(second.scored, 3);
+        assert_eq!(reader.summary_reads.load(Ordering::SeqCst), 2);
+        assert_eq!(reader.wallet_reads.load(Ordering::SeqCst), 2);
+
+        let uncached = ReputationRefresher::new(
+            reader.clone(),
+            ReputationRefresherConfig {
+                refresh_interval_secs: 60,
+                cache_ttl_secs: 0,
+            },
+        );
+        uncached.refresh_all(&registry).await;
+        uncached.refresh_all(&registry).await;
+        assert_eq!(reader.summary_reads.load(Ordering::SeqCst), 6);
+    }
+
+    #[tokio::test]
+    async fn test_failed_reads_keep_previous_reputation() {
+        let registry = registry().await;
+        ReputationRefresher::new(reader(), ReputationRefresherConfig::default())
+            .refresh_all(&registry)
+            .await;
+
+        // The registries become unreachable
+        let summary = ReputationRefresher::new(
+            Arc::new(MockReader::default()),
+            ReputationRefresherConfig::default(),
+        )
+        .refresh_all(&registry)
+        .await;
+        assert_eq!(summary.scored, 0);
+        assert_eq!(summary.failed_reads, 2);
+        let trusted = registry
+            .get("https://trusted.example.com/api")
+            .await
+            .unwrap();
+        assert_eq!(trusted.metadata.unwrap().reputation.unwrap().score, 92);
+
+        // Updates of the resource keep it too
+        registry
+            .update(resource(
+                "https://trusted.example.com/api",
+                Address::repeat_byte(1),
+                Some(42.into()),
+                500,
+            ))
+            .await
+            .unwrap();
+        let trusted = registry
+            .get("https://trusted.example.com/api")
+            .await
+            .unwrap();
+        assert_eq!(trusted.metadata.unwrap().reputation.unwrap().score, 92);
+    }
+
+    #[test]
+    fn test_normalized_score() {
+        let summary = |count, summary_value, summary_value_decimals| ReputationSummary {
+            agent_id: 1,
+            count,
+            summary_value,
+            summary_value_decimals,
+            network: Network::Ethereum,
+        };
+        assert_eq!(normalized_score(&summary(3, 8749, 2)), 87);
+        assert_eq!(normalized_score(&summary(3, 95, 0)), 95);
+        assert_eq!(normalized_score(&summary(3, -20, 0)), 0);
+        assert_eq!(normalized_score(&summary(3, 250, 0)), 100);
+        assert_eq!(normalized_score(&summary(0, 0, 0)), 0);
+    }
+}
@@ -22,13 +22,22 @@
 //! The registry maintains an in-memory cache for fast reads, while the store
 //! handles persistence. On startup, the registry loads all resources from the
 //! store. On writes, the registry updates both memory and store.
+//!
+//! Independently of the store, a [`RegistryStore`] keeps a snapshot of the whole
+//! registry state (aggregated resources, tombstones, missed cycles and health) so a
+//! restart serves the previous catalog instead of waiting for the first aggregation
+//! cycle. [`FileSnapshotStore`] writes it as plain or gzip-compressed JSON.
 
 use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::path::PathBuf;
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use tokio::sync::{Mutex, RwLock};
 use tracing::{debug, error, info, warn};
 
+use crate::discovery::ResourceHealth;
 use crate::types_v2::DiscoveryResource;
 
 // ============================================================================
@@ -194,12 +203,11 @@ impl S3Store {
     /// - `DISCOVERY_S3_BUCKET` (required): S3 bucket name
     /// - `DISCOVERY_S3_KEY` (optional): Object key, defaults to "bazaar/resources.json"
     pub async fn from_env() -> Result<Self, StoreError> {
-        let bucket = std::env::var("DISCOVERY_S3_BUCKET").map_err(|_| {
+        let bucket = crate::env_registry::var("DISCOVERY_S3_BUCKET").ok_or_else(|| {
             StoreError::NotConfigured("DISCOVERY_S3_BUCKET environment variable not set".into())
         })?;
 
-        let key = std::env::var("DISCOVERY_S3_KEY")
-            .unwrap_or_else(|_| "bazaar/resources.json".to_string());
+        let key = crate::env_registry::var("DISCOVERY_S3_KEY").unwrap_or_default();
 
         let config = aws_config::load_defaults(aws_config::BehaviorVersion::latest()).await;
         let client = aws_sdk_s3::Client::new(&config);
@@ -322,6 +330,234 @@ impl DiscoveryStore for S3Store {
     }
 }
 
+// ============================================================================
+// Registry Snapshots
+// ============================================================================
+
+/// Version of the snapshot format written by this build.
+///
+/// Snapshots of any other version are discarded on load.
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+/// Full registry state as written to disk.
+///
+/// Snapshots written before tombstones, missed cycles and health were included
+/// load with those maps empty.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegistrySnapshot {
+    pub version: u32,
+    /// Unix timestamp of when the snapshot was taken
+    pub saved_at: u64,
+    pub resources: Vec<DiscoveryResource>,
+    /// Removed resources: Map of URL -> `last_updated` at removal
+    #[serde(default)]
+    pub tombstones: HashMap<String, u64>,
+    /// Aggregated resources missing from their source: Map of URL -> cycles missed
+    #[serde(default)]
+    pub missed: HashMap<String, u32>,
+    /// Health check history: Map of URL -> ResourceHealth
+    #[serde(default)]
+    pub health: HashMap<String, ResourceHealth>,
+}
+
+/// Trait for point-in-time snapshots of the whole registry.
+///
+/// Unlike [`DiscoveryStore`], which persists individual writes, a snapshot is
+/// replaced wholesale after every bulk import and every successful aggregation
+/// cycle.
+#[async_trait]
+pub trait RegistryStore: Send + Sync + std::fmt::Debug {
+    /// Replace the stored snapshot with `snapshot`.
+    async fn save_snapshot(&self, snapshot: &RegistrySnapshot) -> Result<(), StoreError>;
+
+    /// Load the stored snapshot.
+    ///
+    /// Returns `None` when there is no usable snapshot, including when the stored
+    /// one is corrupt or was written by an incompatible version.
+    async fn load_snapshot(&self) -> Result<Option<RegistrySnapshot>, StoreError>;
+}
+
+/// On-disk encoding of a [`FileSnapshotStore`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnapshotFormat {
+    /// Plain JSON
+    Json,
+    /// Gzip-compressed JSON
+    Gzip,
+}
+
+/// File holding the latest registry snapshot.
+///
+/// Snapshots are written to a temporary file next to the target and renamed
+/// over it, so a crash mid-write leaves the previous snapshot intact.
+///
+/// # Configuration
+///
+/// At most one of these may be set; snapshots are disabled when neither is:
+/// - `DISCOVERY_SNAPSHOT_PATH`: plain JSON snapshot file
+/// - `DISCOVERY_CHECKPOINT_PATH`: gzip-compressed JSON snapshot file
+#[derive(Debug)]
+pub struct FileSnapshotStore {
+    path: PathBuf,
+    format: SnapshotFormat,
+    /// Serializes writers so an older snapshot never replaces a newer one
+    write_lock: Mutex<()>,
+}
+
+impl FileSnapshotStore {
+    /// Create a plain JSON snapshot store writing to `path`.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self::with_format(path, SnapshotFormat::Json)
+    }
+
+    /// Create a gzip-compressed snapshot store writing to `path`.
+    pub fn gzip(path: impl Into<PathBuf>) -> Self {
+        Self::with_format(path, SnapshotFormat::Gzip)
+    }
+
+    /// Create a snapshot store writing to `path` in `format`.
+    pub fn with_format(path: impl Into<PathBuf>, format: SnapshotFormat) -> Self {
+        let path = path.into();
+        info!(path = %path.display(), format = ?format, "Initialized discovery snapshot store");
+        Self {
+            path,
+            format,
+            write_lock: Mutex::new(()),
+        }
+    }
+
+    /// Create a snapshot store from `DISCOVERY_SNAPSHOT_PATH` (plain JSON) or
+    /// `DISCOVERY_CHECKPOINT_PATH` (gzip), if either is set.
+    ///
+    /// Setting both is a configuration error, so there is only ever one snapshot
+    /// to restore.
+    pub fn from_env() -> Result<Option<Self>, StoreError> {
+        let configured = |name: &str| {
+            crate::env_registry::var(name).filter(|path| !path.trim().is_empty())
+        };
+        match (
+            configured("DISCOVERY_SNAPSHOT_PATH"),
+            configured("DISCOVERY_CHECKPOINT_PATH"),
+        ) {
+            (Some(_), Some(_)) => Err(StoreError::NotConfigured(
+                "set only one of DISCOVERY_SNAPSHOT_PATH and DISCOVERY_CHECKPOINT_PATH".to_string(),
+            )),
+            (Some(path), None) => Ok(Some(Self::new(path))),
+            (None, Some(path)) => Ok(Some(Self::gzip(path))),
+            (None, None) => Ok(None),
+        }
+    }
+
+    fn temp_path(&self) -> PathBuf {
+        let mut name = self.path.file_name().unwrap_or_default().to_os_string();
+        name.push(".tmp");
+        self.path.with_file_name(name)
+    }
+}
+
+#[async_trait]
+impl RegistryStore for FileSnapshotStore {
+    async fn save_snapshot(&self, snapshot: &RegistrySnapshot) -> Result<(), StoreError> {
+        let json = serde_json::to_vec(snapshot)
+            .map_err(|e| StoreError::SerializationError(e.to_string()))?;
+        let body = match self.format {
+            SnapshotFormat::Json => json,
+            SnapshotFormat::Gzip => {
+                let mut encoder =
+                    flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder
+                    .write_all(&json)
+                    .map_err(|e| StoreError::WriteError(e.to_string()))?;
+                encoder
+                    .finish()
+                    .map_err(|e| StoreError::WriteError(e.to_string()))?
+            }
+        };
+
+        let _guard = self.write_lock.lock().await;
+        if let Some(parent) = self.path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(|e| StoreError::WriteError(e.to_string()))?;
+        }
+        let temp = self.temp_path();
+        tokio::fs::write(&temp, &body)
+            .await
+            .map_err(|e| StoreError::WriteError(e.to_string()))?;
+        tokio::fs::rename(&temp, &self.path)
+            .await
+            .map_err(|e| StoreError::WriteError(e.to_string()))?;
+
+        debug!(
+            path = %self.path.display(),
+            count = snapshot.resources.len(),
+            "Saved discovery registry snapshot"
+        );
+        Ok(())
+    }
+
+    async fn load_snapshot(&self) -> Result<Option<RegistrySnapshot>, StoreError> {
+        let raw = match tokio::fs::read(&self.path).await {
+            Ok(raw) => raw,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                info!(path = %self.path.display(), "No discovery snapshot found, starting fresh");
+                return Ok(None);
+            }
+            Err(e) => return Err(StoreError::ReadError(e.to_string())),
+        };
+        let body = match self.format {
+            SnapshotFormat::Json => raw,
+            SnapshotFormat::Gzip => {
+                let mut json = Vec::new();
+                if let Err(e) = flate2::read::GzDecoder::new(raw.as_slice()).read_to_end(&mut json) {
+                    warn!(path = %self.path.display(), error = %e, "Discarding corrupt discovery snapshot");
+                    return Ok(None);
+                }
+                json
+            }
+        };
+
+        // Check the version before the resources, whose shape may have changed
+        #[derive(Deserialize)]
+        struct Header {
+            version: u32,
+        }
+        let version = match serde_json::from_slice::<Header>(&body) {
+            Ok(header) => header.version,
+            Err(e) => {
+                warn!(path = %self.path.display(), error = %e, "Discarding corrupt discovery snapshot");
+                return Ok(None);
+            }
+        };
+        if version != SNAPSHOT_VERSION {
+            warn!(
+                path = %self.path.display(),
+                version = version,
+                expected = SNAPSHOT_VERSION,
+                "Discarding discovery snapshot with unsupported version"
+            );
+            return Ok(None);
+        }
+
+        match serde_json::from_slice::<RegistrySnapshot>(&body) {
+            Ok(snapshot) => {
+                info!(
+                    path = %self.path.display(),
+                    count = snapshot.resources.len(),
+                    saved_at = snapshot.saved_at,
+                    "Loaded discovery registry snapshot"
+                );
+                Ok(Some(snapshot))
+            }
+            Err(e) => {
+                warn!(path = %self.path.display(), error = %e, "Discarding corrupt discovery snapshot");
+                Ok(None)
+            }
+        }
+    }
+}
+
 // ============================================================================
 // No-Op Store (for when persistence is disabled)
 // ============================================================================
@@ -464,4 +700,108 @@ mod tests {
         let noop_store = NoOpStore::new();
         assert!(noop_store.health_check().await.is_ok());
     }
+
+    fn snapshot_path() -> PathBuf {
+        std::env::temp_dir()
+            .join(format!("x402-snapshot-{:x}", rand::random::<u64>()))
+            .join("registry.json")
+    }
+
+    fn snapshot_of(resources: Vec<DiscoveryResource>) -> RegistrySnapshot {
+        RegistrySnapshot {
+            version: SNAPSHOT_VERSION,
+            resources,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_file_snapshot_round_trip() {
+        let path = snapshot_path();
+        let store = FileSnapshotStore::new(&path);
+        assert!(store.load_snapshot().await.unwrap().is_none());
+
+        let resources: Vec<DiscoveryResource> = (0..300)
+            .map(|i| create_test_resource(&format!("https://api{}.example.com/data", i)))
+            .collect();
+        store.save_snapshot(&snapshot_of(resources)).await.unwrap();
+        assert!(!store.temp_path().exists());
+
+        let loaded = FileSnapshotStore::new(&path)
+            .load_snapshot()
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(loaded.resources.len(), 300);
+        assert_eq!(
+            loaded.resources[299].url.as_str(),
+            "https://api299.example.com/data"
+        );
+
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_gzip_snapshot_round_trip() {
+        let path = snapshot_path().with_extension("json.gz");
+        let store = FileSnapshotStore::gzip(&path);
+
+        let mut snapshot = snapshot_of(vec![create_test_resource("https://api.example.com/data")]);
+        snapshot
+            .tombstones
+            .insert("https://gone.example.com/".to_string(), 42);
+        store.save_snapshot(&snapshot).await.unwrap();
+        // gzip magic number
+        assert_eq!(std::fs::read(&path).unwrap()[..2], [0x1f, 0x8b]);
+
+        let loaded = store.load_snapshot().await.unwrap().unwrap();
+        assert_eq!(loaded.resources.len(), 1);
+        assert_eq!(loaded.tombstones.get("https://gone.example.com/"), Some(&42));
+
+        // A plain JSON store can't read it, and vice versa
+        assert!(FileSnapshotStore::new(&path)
+            .load_snapshot()
+            .await
+            .unwrap()
+            .is_none());
+        std::fs::write(&path, b"{}").unwrap();
+        assert!(store.load_snapshot().await.unwrap().is_none());
+
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_corrupt_snapshot_is_discarded() {
+        let path = snapshot_path();
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        let store = FileSnapshotStore::new(&path);
+
+        std::fs::write(&path, b"{\"version\": 1, \"resources\": [").unwrap();
+        assert!(store.load_snapshot().await.unwrap().is_none());
+
+        std::fs::write(&path, b"{\"version\": 1, \"savedAt\": 0, \"resources\": [{}]}").unwrap();
+        assert!(store.load_snapshot().await.unwrap().is_none());
+
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_version_mismatched_snapshot_is_discarded() {
+        let path = snapshot_path();
+        let store = FileSnapshotStore::new(&path);
+        store
+            .save_snapshot(&snapshot_of(vec![create_test_resource(
+                "https://api.example.com/data",
+            )]))
+            .await
+            .unwrap();
+
+        let body = std::fs::read_to_string(&path).unwrap();
+        let mut snapshot: serde_json::Value = serde_json::from_str(&body).unwrap();
+        snapshot["version"] = serde_json::json!(SNAPSHOT_VERSION + 1);
+        std::fs::write(&path, snapshot.to_string()).unwrap();
+        assert!(store.load_snapshot().await.unwrap().is_none());
+
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
 }
@@ -0,0 +1,641 @@
+//! Central registry of every environment variable the facilitator reads.
+//!
+//! Configuration is self-describing: each variable is declared once in [`ENV_VARS`]
+//! with its type, default, required-ness, owning subsystem and whether it holds a
+//! secret. Config loaders read through [`var`], [`parse`] and [`flag`], which apply
+//! the registered default, and the registry is rendered for operators by the
+//! `config vars` CLI subcommand and the `GET /admin/config/vars` endpoint (mounted
+//! when `CONFIG_ADMIN_KEY` is set).
+//!
+//! A unit test scans the crate sources and fails if a loader reads a name that is
+//! not declared here, or reads the environment without going through this module.
+//!
+//! # Usage
+//!
+//! ```ignore
+//! let port: u16 = env_registry::parse("PORT").unwrap_or(8080);
+//! let enabled = env_registry::flag("ENABLE_ESCROW");
+//! ```
+
+use serde::Serialize;
+use std::fmt::Write;
+use std::str::FromStr;
+
+use crate::from_env::*;
+
+/// Value type of an environment variable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EnvVarType {
+    #[serde(rename = "string")]
+    Text,
+    Bool,
+    Integer,
+    Url,
+    /// Comma-separated list
+    List,
+}
+
+impl EnvVarType {
+    fn as_str(&self) -> &'static str {
+        match self {
+            EnvVarType::Text => "string",
+            EnvVarType::Bool => "bool",
+            EnvVarType::Integer => "integer",
+            EnvVarType::Url => "url",
+            EnvVarType::List => "list",
+        }
+    }
+}
+
+/// Declaration of a single environment variable.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct EnvVar {
+    pub name: &'static str,
+    #[serde(rename = "type")]
+    pub ty: EnvVarType,
+    pub default: Option<&'static str>,
+    pub required: bool,
+    /// Feature or subsystem that consumes the variable
+    pub subsystem: &'static str,
+    /// Secret values are never displayed
+    pub secret: bool,
+    pub description: &'static str,
+}
+
+impl EnvVar {
+    const fn new(
+        name: &'static str,
+        ty: EnvVarType,
+        subsystem: &'static str,
+        description: &'static str,
+    ) -> Self {
+        Self {
+            name,
+            ty,
+            default: None,
+            required: false,
+            subsystem,
+            secret: false,
+            description,
+        }
+    }
+
+    const fn default(mut self, default: &'static str) -> Self {
+        self.default = Some(default);
+        self
+    }
+
+    const fn required(mut self) -> Self {
+        self.required = true;
+        self
+    }
+
+    const fn secret(mut self) -> Self {
+        self.secret = true;
+        self
+    }
+}
+
+const fn rpc(name: &'static str, subsystem: &'static str) -> EnvVar {
+    // RPC URLs frequently embed provider API keys
+    EnvVar::new(name, EnvVarType::Url, subsystem, "JSON-RPC endpoint; the network is disabled when unset. EVM networks accept a comma-separated list for failover").secret()
+}
+
+const fn contracts(name: &'static str) -> EnvVar {
+    EnvVar::new(name, EnvVarType::List, "erc8004", "ERC-8004 deployment override, e.g. `identity:0x..,reputation:0x..,validation:0x..`; read for every EVM network")
+}
+
+const fn key(name: &'static str, subsystem: &'static str, description: &'static str) -> EnvVar {
+    EnvVar::new(name, EnvVarType::Text, subsystem, description).secret()
+}
+
+use EnvVarType::*;
+
+/// Every environment variable consumed by the crate.
+pub static ENV_VARS: &[EnvVar] = &[
+    // ------------------------------------------------------------------------
+    // Server
+    // ------------------------------------------------------------------------
+    EnvVar::new("HOST", List, "server", "Bind addresses, IPv6 optionally bracketed").default("0.0.0.0"),
+    EnvVar::new("PORT", Integer, "server", "Bind port").default("8080"),
+    EnvVar::new("LISTEN_DUAL_STACK", Bool, "server", "Accept IPv4 on IPv6 wildcard sockets").default("true"),
+    EnvVar::new("FACILITATOR_URL", Url, "server", "Public URL; enables self-registration in discovery"),
+    EnvVar::new("FACILITATOR_ENHANCED_DEBUG", Bool, "server", "Verbose request debugging").default("true"),
+    EnvVar::new("VERIFY_BATCH_MAX_ITEMS", Integer, "server", "Maximum payments per POST /verify/batch").default("50"),
+    EnvVar::new("METRICS_PORT", Integer, "server", "Serve Prometheus metrics at /metrics on this port"),
+    EnvVar::new("CONFIG_ADMIN_KEY", Text, "server", "Enables GET /admin/config/vars; the `X-API-Key` value required to call it").secret(),
+    EnvVar::new("CIRCUIT_BREAKER_FAILURE_THRESHOLD", Integer, "server", "Consecutive RPC failures on a network before its verify and settle calls fail fast with 503 (0 disables)").default("5"),
+    EnvVar::new("CIRCUIT_BREAKER_WINDOW_SECS", Integer, "server", "Seconds within which RPC failures count as consecutive").default("30"),
+    EnvVar::new("CIRCUIT_BREAKER_RESET_SECS", Integer, "server", "Seconds an open circuit rejects calls before letting a probe through").default("30"),
+    // ------------------------------------------------------------------------
+    // Signers
+    // ------------------------------------------------------------------------
+    EnvVar::new(ENV_SIGNER_TYPE, Text, "signer", "Signer backend (only `private-key` is supported)").required(),
+    key(ENV_EVM_PRIVATE_KEY, "evm", "EVM private key used when no network-specific key is set"),
+    key(ENV_EVM_PRIVATE_KEY_MAINNET, "evm", "EVM private key for mainnets"),
+    key(ENV_EVM_PRIVATE_KEY_TESTNET, "evm", "EVM private key for testnets"),
+    key(ENV_SOLANA_PRIVATE_KEY, "solana", "Solana keypair used when no network-specific key is set"),
+    key(ENV_SOLANA_PRIVATE_KEY_MAINNET, "solana", "Solana keypair for mainnet"),
+    key(ENV_SOLANA_PRIVATE_KEY_TESTNET, "solana", "Solana keypair for devnet"),
+    key(ENV_NEAR_PRIVATE_KEY, "near", "NEAR private key used when no network-specific key is set"),
+    key(ENV_NEAR_PRIVATE_KEY_MAINNET, "near", "NEAR private key for mainnet"),
+    key(ENV_NEAR_PRIVATE_KEY_TESTNET, "near", "NEAR private key for testnet"),
+    EnvVar::new(ENV_NEAR_ACCOUNT_ID, Text, "near", "NEAR relayer account id"),
+    EnvVar::new(ENV_NEAR_ACCOUNT_ID_MAINNET, Text, "near", "NEAR relayer account id for mainnet"),
+    EnvVar::new(ENV_NEAR_ACCOUNT_ID_TESTNET, Text, "near", "NEAR relayer account id for testnet"),
+    key(ENV_STELLAR_PRIVATE_KEY, "stellar", "Stellar secret key used when no network-specific key is set"),
+    key(ENV_STELLAR_PRIVATE_KEY_MAINNET, "stellar", "Stellar secret key for mainnet"),
+    key(ENV_STELLAR_PRIVATE_KEY_TESTNET, "stellar", "Stellar secret key for testnet"),
+    key(ENV_ALGORAND_MNEMONIC, "algorand", "Algorand 25-word mnemonic used when no network-specific one is set"),
+    key(ENV_ALGORAND_MNEMONIC_MAINNET, "algorand", "Algorand mnemonic for mainnet"),
+    key(ENV_ALGORAND_MNEMONIC_TESTNET, "algorand", "Algorand mnemonic for testnet"),
+    key("SUI_PRIVATE_KEY", "sui", "Sui private key used when no network-specific key is set"),
+    key("SUI_PRIVATE_KEY_MAINNET", "sui", "Sui private key for mainnet"),
+    key("SUI_PRIVATE_KEY_TESTNET", "sui", "Sui private key for testnet"),
+    // ------------------------------------------------------------------------
+    // RPC endpoints
+    // ------------------------------------------------------------------------
+    rpc(ENV_RPC_BASE, "evm"),
+    rpc(ENV_RPC_BASE_SEPOLIA, "evm"),
+    rpc(ENV_RPC_XDC, "evm"),
+    rpc(ENV_RPC_AVALANCHE_FUJI, "evm"),
+    rpc(ENV_RPC_AVALANCHE, "evm"),
+    rpc(ENV_RPC_XRPL_EVM, "evm"),
+    rpc(ENV_RPC_POLYGON_AMOY, "evm"),
+    rpc(ENV_RPC_POLYGON, "evm"),
+    rpc(ENV_RPC_SEI, "evm"),
+    rpc(ENV_RPC_SEI_TESTNET, "evm"),
+    rpc(ENV_RPC_CELO, "evm"),
+    rpc(ENV_RPC_CELO_SEPOLIA, "evm"),
+    rpc(ENV_RPC_HYPEREVM, "evm"),
+    rpc(ENV_RPC_HYPEREVM_TESTNET, "evm"),
+    rpc(ENV_RPC_OPTIMISM, "evm"),
+    rpc(ENV_RPC_OPTIMISM_SEPOLIA, "evm"),
+    rpc(ENV_RPC_ETHEREUM, "evm"),
+    rpc(ENV_RPC_ETHEREUM_SEPOLIA, "evm"),
+    rpc(ENV_RPC_ARBITRUM, "evm"),
+    rpc(ENV_RPC_ARBITRUM_SEPOLIA, "evm"),
+    rpc(ENV_RPC_UNICHAIN, "evm"),
+    rpc(ENV_RPC_UNICHAIN_SEPOLIA, "evm"),
+    rpc(ENV_RPC_MONAD, "evm"),
+    rpc(ENV_RPC_BSC, "evm"),
+    rpc(ENV_RPC_SKALE_BASE, "evm"),
+    rpc(ENV_RPC_SKALE_BASE_SEPOLIA, "evm"),
+    rpc(ENV_RPC_SCROLL, "evm"),
+    rpc(ENV_RPC_SCROLL_SEPOLIA, "evm"),
+    rpc(ENV_RPC_ZKSYNC_ERA, "evm"),
+    rpc(ENV_RPC_ZKSYNC_ERA_SEPOLIA, "evm"),
+    rpc(ENV_RPC_SOLANA, "solana"),
+    rpc(ENV_RPC_SOLANA_DEVNET, "solana"),
+    rpc(ENV_RPC_FOGO, "solana"),
+    rpc(ENV_RPC_FOGO_TESTNET, "solana"),
+    rpc(ENV_RPC_NEAR, "near"),
+    rpc(ENV_RPC_NEAR_TESTNET, "near"),
+    rpc(ENV_RPC_STELLAR, "stellar"),
+    rpc(ENV_RPC_STELLAR_TESTNET, "stellar"),
+    rpc(ENV_RPC_ALGORAND, "algorand"),
+    rpc(ENV_RPC_ALGORAND_TESTNET, "algorand"),
+    rpc("RPC_URL_SUI", "sui"),
+    rpc("RPC_URL_SUI_TESTNET", "sui"),
+    rpc("RPC_URL_HEDERA", "hedera"),
+    rpc("RPC_URL_HEDERA_TESTNET", "hedera"),
+    // ------------------------------------------------------------------------
+    // Chain tuning
+    // ------------------------------------------------------------------------
+    EnvVar::new("TX_RECEIPT_TIMEOUT_SECS", Integer, "evm", "Seconds to wait for a transaction receipt"),
+    EnvVar::new("EVM_SIMULATE_BEFORE_SETTLE", Bool, "evm", "Dry-run settlement transactions with eth_call and reject those that would revert").default("true"),
+    EnvVar::new("EVM_VERIFY_TOKEN_LEGITIMACY", Bool, "evm", "Reject payment tokens whose simulated transfer reverts (honeypots); defaults to true on mainnets, false on testnets"),
+    EnvVar::new("EVM_HONEYPOT_CACHE_TTL_SECS", Integer, "evm", "Seconds a token that passed honeypot screening is not simulated again; reverts are never cached").default("3600"),
+    EnvVar::new("TOKEN_WHITELIST_JSON", Text, "evm", "JSON object of network names to accepted payment token addresses; unlisted networks and empty lists accept any token"),
+    EnvVar::new("TOKEN_WHITELIST_FILE", Text, "evm", "TOML file with the same network-to-token mapping as TOKEN_WHITELIST_JSON"),
+    EnvVar::new("EVM_GAS_ORACLE", Text, "evm", "EIP-1559 fee source: rpc (eth_feeHistory), etherscan, blocknative or none").default("rpc"),
+    EnvVar::new("EVM_GAS_ORACLE_API_KEY", Text, "evm", "API key of the etherscan or blocknative gas oracle").secret(),
+    EnvVar::new("EVM_GAS_PRICE_MULTIPLIER", Text, "evm", "Base fee multiplier in maxFeePerGas, clamped to 1-10").default("2.0"),
+    EnvVar::new("EVM_MEV_PROTECTION", Text, "evm", "Private submission of Ethereum mainnet settlements: none or flashbots").default("none"),
+    EnvVar::new("FLASHBOTS_AUTH_KEY", Text, "evm", "Private key signing Flashbots relay requests, required with EVM_MEV_PROTECTION=flashbots; use a key holding no funds").secret(),
+    EnvVar::new("FLASHBOTS_RELAY_URL", Text, "evm", "Flashbots relay receiving eth_sendPrivateTransaction").default("https://relay.flashbots.net"),
+    EnvVar::new("FLASHBOTS_REFUND_PERCENT", Integer, "evm", "Share of the MEV-Share refund paid back to the signer, 0-100").default("0"),
+    EnvVar::new("FLASHBOTS_TIMEOUT_SECS", Integer, "evm", "Seconds to wait for private inclusion before broadcasting publicly").default("120"),
+    EnvVar::new("SOLANA_CONFIRM_TIMEOUT_SECS", Integer, "solana", "Seconds to wait for transaction confirmation").default("30"),
+    EnvVar::new("X402_SOLANA_MAX_COMPUTE_UNIT_LIMIT_SOLANA", Integer, "solana", "Max compute unit limit accepted on Solana").default("400000"),
+    EnvVar::new("X402_SOLANA_MAX_COMPUTE_UNIT_LIMIT_SOLANA_DEVNET", Integer, "solana", "Max compute unit limit accepted on Solana devnet").default("200000"),
+    EnvVar::new("X402_SOLANA_MAX_COMPUTE_UNIT_LIMIT_FOGO", Integer, "solana", "Max compute unit limit accepted on Fogo").default("400000"),
+    EnvVar::new("X402_SOLANA_MAX_COMPUTE_UNIT_LIMIT_FOGO_TESTNET", Integer, "solana", "Max compute unit limit accepted on Fogo testnet").default("200000"),
+    EnvVar::new("X402_SOLANA_MAX_COMPUTE_UNIT_PRICE_SOLANA", Integer, "solana", "Max compute unit price accepted on Solana").default("1000000"),
+    EnvVar::new("X402_SOLANA_MAX_COMPUTE_UNIT_PRICE_SOLANA_DEVNET", Integer, "solana", "Max compute unit price accepted on Solana devnet").default("100000"),
+    EnvVar::new("X402_SOLANA_MAX_COMPUTE_UNIT_PRICE_FOGO", Integer, "solana", "Max compute unit price accepted on Fogo").default("1000000"),
+    EnvVar::new("X402_SOLANA_MAX_COMPUTE_UNIT_PRICE_FOGO_TESTNET", Integer, "solana", "Max compute unit price accepted on Fogo testnet").default("100000"),
+    EnvVar::new("HEDERA_MIRROR_NODE_URL", Url, "hedera", "Hedera mainnet mirror node resolving account IDs to EVM addresses").default("https://mainnet.mirrornode.hedera.com"),
+    EnvVar::new("HEDERA_MIRROR_NODE_URL_TESTNET", Url, "hedera", "Hedera testnet mirror node resolving account IDs to EVM addresses").default("https://testnet.mirrornode.hedera.com"),
+    // ------------------------------------------------------------------------
+    // Nonce store
+    // ------------------------------------------------------------------------
+    EnvVar::new("REDIS_URL", Url, "nonce_store", "Redis URL (comma-separated nodes for Redis Cluster) for replay protection; takes precedence over DynamoDB").secret(),
+    EnvVar::new("DATABASE_URL", Url, "nonce_store", "PostgreSQL URL for replay protection; used when REDIS_URL is unset").secret(),
+    EnvVar::new("SQLITE_PATH", Text, "nonce_store", "SQLite database file for replay protection (requires the `sqlite` feature); used when REDIS_URL and DATABASE_URL are unset"),
+    EnvVar::new("NONCE_STORE_TABLE_NAME", Text, "nonce_store", "DynamoDB table for replay protection; in-memory when unset"),
+    EnvVar::new("NONCE_STORE_PREFIX", Text, "nonce_store", "Key prefix for the Redis nonce store").default("x402:nonce:"),
+    // ------------------------------------------------------------------------
+    // Discovery
+    // ------------------------------------------------------------------------
+    EnvVar::new("DISCOVERY_S3_BUCKET", Text, "discovery", "S3 bucket for registry persistence; in-memory when unset"),
+    EnvVar::new("DISCOVERY_S3_KEY", Text, "discovery", "S3 object key for the registry").default("bazaar/resources.json"),
+    EnvVar::new("DISCOVERY_SNAPSHOT_PATH", Text, "discovery", "JSON file snapshotting the registry state after each aggregation import and cycle, restored at startup; exclusive with DISCOVERY_CHECKPOINT_PATH, disabled when both are unset"),
+    EnvVar::new("DISCOVERY_CHECKPOINT_PATH", Text, "discovery", "Gzip-compressed variant of DISCOVERY_SNAPSHOT_PATH; exclusive with it"),
+    EnvVar::new("DISCOVERY_ENABLE_AGGREGATION", Bool, "discovery", "Aggregate resources from external facilitators").default("true"),
+    EnvVar::new("DISCOVERY_AGGREGATION_INTERVAL", Integer, "discovery", "Seconds between aggregation runs").default("3600"),
+    EnvVar::new("DISCOVERY_AGGREGATION_CONCURRENCY", Integer, "discovery", "Facilitators fetched concurrently per aggregation run").default("4"),
+    EnvVar::new("DISCOVERY_AGGREGATION_MAX_PAGES", Integer, "discovery", "Maximum pages fetched from one facilitator per aggregation run").default("100"),
+    EnvVar::new("DISCOVERY_AGGREGATION_BREAKER_THRESHOLD", Integer, "discovery", "Consecutive failures before a facilitator is skipped with an exponential cool-down (0 disables)").default("3"),
+    EnvVar::new("DISCOVERY_AGGREGATION_MAX_ATTEMPTS", Integer, "discovery", "Attempts per discovery page request; timeouts, connection errors, 429 and 5xx are retried with backoff").default("3"),
+    EnvVar::new("DISCOVERY_AGGREGATION_RATE_LIMIT_BUDGET", Integer, "discovery", "Seconds a facilitator's fetch may spend waiting on Retry-After/X-RateLimit-Reset").default("60"),
+    EnvVar::new("DISCOVERY_AGGREGATION_STALE_CYCLES", Integer, "discovery", "Successful cycles an aggregated resource may be missing from its source before it is evicted").default("3"),
+    EnvVar::new("DISCOVERY_ALLOWED_NETWORKS", List, "discovery", "CAIP-2 networks aggregated payment methods must use (all when unset)"),
+    EnvVar::new("DISCOVERY_ALLOWED_ASSETS", List, "discovery", "Token addresses aggregated payment methods must be priced in (all when unset)"),
+    EnvVar::new("DISCOVERY_REQUIRE_NONZERO_AMOUNT", Bool, "discovery", "Drop aggregated payment methods with a zero amount").default("false"),
+    EnvVar::new("DISCOVERY_ADMIN_KEY", Text, "discovery", "Enables POST /admin/discovery/aggregate; the `X-API-Key` required to call it").secret(),
+    EnvVar::new("DISCOVERY_SIGNATURE_MAX_AGE", Integer, "discovery", "Seconds a signed POST/DELETE /discovery/resources timestamp may be away from the facilitator's clock").default("300"),
+    EnvVar::new("DISCOVERY_SOURCES_FILE", Text, "discovery", "TOML or JSON file overriding the built-in aggregation sources"),
+    EnvVar::new("EXTRA_FACILITATOR_URLS", List, "discovery", "Facilitator base URLs probed at startup and added to the aggregation sources"),
+    EnvVar::new("DISCOVERY_TAXONOMY_FILE", Text, "discovery", "JSON file extending the built-in category and tag aliases"),
+    EnvVar::new("DISCOVERY_ENABLE_CRAWLER", Bool, "discovery", "Crawl /.well-known/x402 endpoints").default("false"),
+    EnvVar::new("DISCOVERY_CRAWL_INTERVAL", Integer, "discovery", "Seconds between crawl runs").default("86400"),
+    EnvVar::new("DISCOVERY_CRAWL_URLS", List, "discovery", "Seed URLs for the crawler"),
+    EnvVar::new("DISCOVERY_ENABLE_HEALTH_CHECKS", Bool, "discovery", "Periodically check resources and hide unreachable ones").default("false"),
+    EnvVar::new("DISCOVERY_HEALTH_CHECK_INTERVAL", Integer, "discovery", "Seconds between health check rounds").default("300"),
+    EnvVar::new("DISCOVERY_HEALTH_FAILURE_THRESHOLD", Integer, "discovery", "Consecutive failed checks before a resource is hidden").default("3"),
+    EnvVar::new("DISCOVERY_HEALTH_REQUEST_TIMEOUT", Integer, "discovery", "Seconds before a health check times out").default("10"),
+    EnvVar::new("DISCOVERY_ENABLE_REPUTATION", Bool, "discovery", "Periodically read the ERC-8004 reputation of resource agents for sort=reputation").default("false"),
+    EnvVar::new("DISCOVERY_REPUTATION_INTERVAL", Integer, "discovery", "Seconds between reputation refresh rounds").default("600"),
+    EnvVar::new("DISCOVERY_REPUTATION_CACHE_TTL", Integer, "discovery", "Seconds an ERC-8004 summary or agent wallet read is reused").default("3600"),
+    // ------------------------------------------------------------------------
+    // ERC-8004
+    // ------------------------------------------------------------------------
+    EnvVar::new("ERC8004_IDENTITY_REGISTRY", Text, "erc8004", "Identity Registry address override"),
+    EnvVar::new("ERC8004_REPUTATION_REGISTRY", Text, "erc8004", "Reputation Registry address override"),
+    EnvVar::new("ERC8004_VALIDATION_REGISTRY", Text, "erc8004", "Validation Registry address override"),
+    EnvVar::new("ERC8004_REGISTRY_FILE", Text, "erc8004", "TOML file of ERC-8004 deployments merged over the canonical ones"),
+    contracts("ERC8004_CONTRACTS_ETHEREUM"),
+    contracts("ERC8004_CONTRACTS_ETHEREUM_SEPOLIA"),
+    contracts("ERC8004_CONTRACTS_BASE"),
+    contracts("ERC8004_CONTRACTS_BASE_SEPOLIA"),
+    EnvVar::new("FEEDBACK_SAGA_DIR", Text, "erc8004", "Directory for persisted feedback saga state, proof claims and the feedback journal (in-memory when unset)"),
+    EnvVar::new("FEEDBACK_SAGA_EXPIRY_SECS", Integer, "erc8004", "Seconds after submission a still unconfirmed feedback transaction is given up and its saga compensated").default("86400"),
+    EnvVar::new("FEEDBACK_SAGA_RETRY_INTERVAL_SECS", Integer, "erc8004", "Seconds between confirmation retries of unconfirmed feedback sagas").default("60"),
+    EnvVar::new("FEEDBACK_FILES_DIR", Text, "erc8004", "Directory where POST /feedback stores `feedbackFile` documents, served at GET /feedback-files/{id}"),
+    EnvVar::new("FEEDBACK_FILES_BASE_URL", Url, "erc8004", "Public URL of this facilitator, prefixed to the URIs of stored feedback files"),
+    EnvVar::new("FEEDBACK_IPFS_PINNING_URL", Url, "erc8004", "Upload endpoint of the IPFS pinning service for feedback files").default("https://api.pinata.cloud/pinning/pinFileToIPFS"),
+    EnvVar::new("FEEDBACK_IPFS_PINNING_TOKEN", Text, "erc8004", "Bearer token of the IPFS pinning service; when set, `feedbackFile` documents are pinned instead of stored on disk").secret(),
+    EnvVar::new("ERC8004_REPUTATION_CACHE_TTL", Integer, "erc8004", "Seconds a summary read by GET /reputation or GET /reputation/batch is reused").default("60"),
+    EnvVar::new("ERC8004_IDENTITY_CACHE_TTL", Integer, "erc8004", "Seconds an Identity Registry read by GET /identity or GET /reputation is reused").default("600"),
+    EnvVar::new("ERC8004_READ_CACHE_SIZE", Integer, "erc8004", "Number of ERC-8004 registry reads kept in memory").default("10000"),
+    EnvVar::new("ERC8004_CACHE_ADMIN_KEY", Text, "erc8004", "Enables DELETE /cache/identity/{agentId}; the `X-API-Key` value required to call it").secret(),
+    EnvVar::new("ERC8004_MAX_FEEDBACK_PAGE", Integer, "erc8004", "Most feedback entries returned per GET /reputation request").default("100"),
+    EnvVar::new("ERC8004_INDEXER_NETWORKS", List, "erc8004", "Networks whose feedback events are indexed, with optional start block, e.g. `ethereum:24339871` (disabled when unset)"),
+    EnvVar::new("ERC8004_INDEXER_INTERVAL", Integer, "erc8004", "Seconds between feedback indexer polls").default("12"),
+    EnvVar::new("ERC8004_INDEXER_BLOCK_RANGE", Integer, "erc8004", "Most blocks per eth_getLogs request of the feedback indexer").default("2000"),
+    EnvVar::new("ERC8004_INDEXER_REORG_DEPTH", Integer, "erc8004", "Blocks the feedback indexer re-scans on every poll").default("12"),
+    EnvVar::new("ERC8004_INDEXER_STATE_FILE", Text, "erc8004", "File the feedback index is saved to and resumed from (in-memory when unset)"),
+    EnvVar::new("ERC8004_IPFS_GATEWAY", Text, "erc8004", "Gateway used to fetch ipfs:// agent registration files").default("https://ipfs.io/ipfs/"),
+    EnvVar::new("ERC8004_REGISTRATION_CACHE_TTL", Integer, "erc8004", "Seconds a resolved agent registration file is reused").default("300"),
+    EnvVar::new("ERC8004_REGISTRATION_CACHE_SIZE", Integer, "erc8004", "Number of resolved agent registration files kept in memory").default("1000"),
+    EnvVar::new("ERC8004_ARWEAVE_GATEWAY", Text, "erc8004", "Gateway used to fetch ar:// agent registration files").default("https://arweave.net/"),
+    EnvVar::new("IDENTITY_REGISTER_API_KEYS", List, "erc8004", "Enables POST /identity/register; the `X-API-Key` values allowed to call it").secret(),
+    EnvVar::new("IDENTITY_REGISTER_DAILY_QUOTA", Integer, "erc8004", "Agents each identity registration key may register per UTC day").default("10"),
+    key("ERC8004_VALIDATOR_PRIVATE_KEY", "erc8004", "Validator key publishing responses through POST /validation/response"),
+    EnvVar::new("ERC8004_VALIDATOR_API_KEY", Text, "erc8004", "Enables POST /validation/response with ERC8004_VALIDATOR_PRIVATE_KEY; the `X-API-Key` value required to call it").secret(),
+    EnvVar::new("ERC8004_PROOF_MAX_AGE", Integer, "erc8004", "Oldest payment, in seconds, a proof backing feedback may point at").default("2592000"),
+    // ------------------------------------------------------------------------
+    // Escrow / FHE
+    // ------------------------------------------------------------------------
+    EnvVar::new("ENABLE_ESCROW", Bool, "escrow", "Enable x402r escrow settlement").default("false"),
+    EnvVar::new("FHE_FACILITATOR_URL", Url, "fhe", "FHE facilitator endpoint").default("https://zama-facilitator.ultravioletadao.xyz"),
+    // ------------------------------------------------------------------------
+    // Canary
+    // ------------------------------------------------------------------------
+    EnvVar::new("CANARY_ENABLED", Bool, "canary", "Enable canary self-payments").default("false"),
+    EnvVar::new("CANARY_NETWORKS", List, "canary", "Networks with optional interval, e.g. `base:600`"),
+    EnvVar::new("CANARY_INTERVAL_SECS", Integer, "canary", "Default seconds between canaries").default("3600"),
+    EnvVar::new("CANARY_FAILURE_THRESHOLD", Integer, "canary", "Consecutive failures before alerting").default("3"),
+    EnvVar::new("CANARY_AMOUNT", Integer, "canary", "Atomic units sent per canary").default("1"),
+    EnvVar::new("CANARY_DAILY_BUDGET", Integer, "canary", "Atomic units the canary may spend per network per day").default("100"),
+    // ------------------------------------------------------------------------
+    // Settlement finality
+    // ------------------------------------------------------------------------
+    EnvVar::new("FINALITY_POLICIES_FILE", Text, "finality", "JSON file with per-asset finality policies"),
+    EnvVar::new("FINALITY_POLL_INTERVAL_SECS", Integer, "finality", "Seconds between checks of settlements waiting for finality").default("12"),
+    EnvVar::new("FINALITY_PENDING_TTL_SECS", Integer, "finality", "Seconds a settlement may wait for finality before it expires").default("86400"),
+    // ------------------------------------------------------------------------
+    // Streaming
+    // ------------------------------------------------------------------------
+    EnvVar::new("STREAMING_ENABLED", Bool, "streaming", "Enable the /streaming routes opening per-second payment streams").default("false"),
+    EnvVar::new("STREAMING_DRAIN_INTERVAL_SECS", Integer, "streaming", "Seconds between charges of what open streams have accrued").default("60"),
+    // ------------------------------------------------------------------------
+    // Paywall
+    // ------------------------------------------------------------------------
+    EnvVar::new("PAYWALL_CONFIG_FILE", Text, "paywall", "JSON file mapping premium routes to x402 prices; gating disabled when unset"),
+    EnvVar::new("PAYWALL_RELOAD_SECS", Integer, "paywall", "Seconds between checks of the paywall config for changes").default("30"),
+    EnvVar::new("PAYWALL_API_KEYS", List, "paywall", "`key=scope1|scope2` pairs that bypass payment (`*` for all scopes)").secret(),
+    // ------------------------------------------------------------------------
+    // Tenants
+    // ------------------------------------------------------------------------
+    EnvVar::new("TENANTS_CONFIG_FILE", Text, "tenant", "JSON file defining tenants for multi-tenant mode; single-tenant when unset"),
+    EnvVar::new("TENANT_SUPER_ADMIN_KEYS", List, "tenant", "API keys allowed to query data across tenants").secret(),
+    // ------------------------------------------------------------------------
+    // Webhooks
+    // ------------------------------------------------------------------------
+    EnvVar::new("WEBHOOK_ADMIN_KEY", Text, "webhook", "Enables settlement webhooks; the `X-API-Key` required to register or remove them").secret(),
+    // ------------------------------------------------------------------------
+    // Rate limiting
+    // ------------------------------------------------------------------------
+    EnvVar::new("RATE_LIMIT_VERIFY", Integer, "rate_limit", "POST /verify requests per second and client IP; 0 disables").default("100"),
+    EnvVar::new("RATE_LIMIT_SETTLE", Integer, "rate_limit", "POST /settle requests per second and client IP; 0 disables").default("50"),
+    EnvVar::new("RATE_LIMIT_DISCOVERY", Integer, "rate_limit", "GET /discovery/resources requests per second and client IP; 0 disables").default("20"),
+    EnvVar::new("RATE_LIMIT_TRUSTED_PROXIES", Integer, "rate_limit", "Reverse proxies in front of the facilitator; above 0 the client IP is read from X-Forwarded-For").default("0"),
+    // ------------------------------------------------------------------------
+    // Telemetry
+    // ------------------------------------------------------------------------
+    EnvVar::new("OTEL_EXPORTER_OTLP_ENDPOINT", Url, "telemetry", "OTLP collector endpoint"),
+    EnvVar::new("OTEL_EXPORTER_OTLP_HEADERS", Text, "telemetry", "OTLP headers (usually carries an API key)").secret(),
+    EnvVar::new("OTEL_EXPORTER_OTLP_PROTOCOL", Text, "telemetry", "OTLP protocol (`grpc` or `http/protobuf`)"),
+    EnvVar::new("OTEL_SERVICE_NAME", Text, "telemetry", "Service name reported to telemetry"),
+    EnvVar::new("OTEL_SERVICE_VERSION", Text, "telemetry", "Service version reported to telemetry"),
+    EnvVar::new("OTEL_SERVICE_DEPLOYMENT", Text, "telemetry", "Deployment environment reported to telemetry"),
+];
+
+// ============================================================================
+// Typed access
+// ============================================================================
+
+/// Look up the declaration of a variable.
+pub fn lookup(name: &str) -> Option<&'static EnvVar> {
+    ENV_VARS.iter().find(|v| v.name == name)
+}
+
+/// Read a registered variable, falling back to its registered default.
+pub fn var(name: &str) -> Option<String> {
+    let decl = lookup(name);
+    debug_assert!(decl.is_some(), "environment variable {name} is not registered");
+    std::env::var(name)
+        .ok()
+        .or_else(|| decl.and_then(|d| d.default).map(str::to_string))
+}
+
+/// Parse a registered variable. Values that fail to parse fall back to the registered default.
+pub fn parse<T: FromStr>(name: &str) -> Option<T> {
+    let decl = lookup(name);
+    debug_assert!(decl.is_some(), "environment variable {name} is not registered");
+    std::env::var(name)
+        .ok()
+        .and_then(|v| v.trim().parse().ok())
+        .or_else(|| decl.and_then(|d| d.default).and_then(|d| d.parse().ok()))
+}
+
+/// Read a variable named by configuration rather than registered here, such as a
+/// discovery source's `api_key_env`.
+pub fn configured_var(name: &str) -> Option<String> {
+    std::env::var(name).ok()
+}
+
+/// Read a registered boolean flag (`true`/`1`, case-insensitive).
+pub fn flag(name: &str) -> bool {
+    var(name)
+        .map(|v| v.eq_ignore_ascii_case("true") || v == "1")
+        .unwrap_or(false)
+}
+
+// ============================================================================
+// Listing
+// ============================================================================
+
+/// A declared variable together with its current state.
+#[derive(Debug, Clone, Serialize)]
+pub struct EnvVarListing {
+    #[serde(flatten)]
+    pub var: EnvVar,
+    pub is_set: bool,
+    /// Current value; always `None` for secrets
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+}
+
+/// All declared variables with whether they are set. Secret values are never included.
+pub fn listing() -> Vec<EnvVarListing> {
+    listing_from(|name| std::env::var(name).ok())
+}
+
+fn listing_from(lookup: impl Fn(&str) -> Option<String>) -> Vec<EnvVarListing> {
+    ENV_VARS
+        .iter()
+        .map(|var| {
+            let current = lookup(var.name);
+            EnvVarListing {
+                var: *var,
+                is_set: current.is_some(),
+                value: if var.secret { None } else { current },
+            }
+        })
+        .collect()
+}
+
+/// Plain-text table used by the `config vars` CLI subcommand.
+pub fn render_table() -> String {
+    render_listing(&listing())
+}
+
+fn render_listing(listing: &[EnvVarListing]) -> String {
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "{:<50} {:<8} {:<12} {:<4} {:<40} DESCRIPTION",
+        "NAME", "TYPE", "SUBSYSTEM", "SET", "VALUE"
+    );
+    for entry in listing {
+        let value = match (&entry.value, entry.var.secret, entry.is_set) {
+            (_, true, true) => "<secret>".to_string(),
+            (Some(v), _, _) => v.clone(),
+            (None, _, _) => entry
+                .var
+                .default
+                .map(|d| format!("(default: {d})"))
+                .unwrap_or_else(|| if entry.var.required { "(required)".into() } else { "-".into() }),
+        };
+        let _ = writeln!(
+            out,
+            "{:<50} {:<8} {:<12} {:<4} {:<40} {}",
+            entry.var.name,
+            entry.var.ty.as_str(),
+            entry.var.subsystem,
+            if entry.is_set { "yes" } else { "no" },
+            value,
+            entry.var.description
+        );
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::path::Path;
+
+    fn collect_sources(dir: &Path, out: &mut Vec<String>) {
+        let mut named = Vec::new();
+        collect_named_sources(dir, &mut named);
+        out.extend(named.into_iter().map(|(_, source)| source));
+    }
+
+    fn collect_named_sources(dir: &Path, out: &mut Vec<(String, String)>) {
+        for entry in std::fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                collect_named_sources(&path, out);
+            } else if path.extension().is_some_and(|e| e == "rs") {
+                let name = path.file_name().unwrap().to_string_lossy().into_owned();
+                out.push((name, std::fs::read_to_string(&path).unwrap()));
+            }
+        }
+    }
+
+    /// Source up to its test module, which may mutate and read the environment freely.
+    fn non_test_code(source: &str) -> &str {
+        source
+            .find("#[cfg(test)]\nmod tests")
+            .or_else(|| source.find("#[cfg(test)]\r\nmod tests"))
+            .map_or(source, |end| &source[..end])
+    }
+
+    /// First argument of every call to `env::var` or to a typed accessor of this module.
+    fn read_arguments(source: &str) -> Vec<&str> {
+        let mut args = Vec::new();
+        for pattern in ["env::var(", "env_registry::var(", "env_registry::flag(", "env_registry::parse"] {
+            for (i, _) in source.match_indices(pattern) {
+                let rest = &source[i + pattern.len()..];
+                // Skip a turbofish, e.g. `parse::<u64>(`
+                let rest = match rest.find('(') {
+                    Some(open) if !pattern.ends_with('(') => &rest[open + 1..],
+                    _ => rest,
+                };
+                args.push(rest.trim_start());
+            }
+        }
+        args
+    }
+
+    /// `ENV_*` constants declared in from_env.rs, name -> value.
+    fn env_constants(sources: &[String]) -> HashMap<String, String> {
+        let mut consts = HashMap::new();
+        for source in sources {
+            for line in source.lines() {
+                let line = line.trim();
+                if let Some(rest) = line.strip_prefix("pub const ENV_") {
+                    if let Some((ident, value)) = rest.split_once(": &str = \"") {
+                        let value = value.trim_end_matches("\";");
+                        consts.insert(format!("ENV_{ident}"), value.to_string());
+                    }
+                }
+            }
+        }
+        consts
+    }
+
+    #[test]
+    fn test_registry_is_complete() {
+        let mut sources = Vec::new();
+        collect_sources(&Path::new(env!("CARGO_MANIFEST_DIR")).join("src"), &mut sources);
+        let consts = env_constants(&sources);
+        assert!(!consts.is_empty());
+
+        let mut missing = Vec::new();
+        for source in &sources {
+            for arg in read_arguments(source) {
+                let name = if let Some(literal) = arg.strip_prefix('"') {
+                    literal
+                        .split('"')
+                        .next()
+                        .filter(|n| n.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_'))
+                        .map(str::to_string)
+                } else {
+                    let ident: String = arg
+                        .chars()
+                        .take_while(|c| c.is_ascii_alphanumeric() || *c == '_')
+                        .collect();
+                    // Dynamic names (e.g. `env_var`) are covered by the constant check below
+                    consts.get(&ident).cloned()
+                };
+                if let Some(name) = name {
+                    if lookup(&name).is_none() {
+                        missing.push(name);
+                    }
+                }
+            }
+        }
+        for name in consts.values() {
+            if lookup(name).is_none() {
+                missing.push(name.clone());
+            }
+        }
+        missing.sort();
+        missing.dedup();
+        assert!(missing.is_empty(), "unregistered environment variables: {missing:?}");
+    }
+
+    #[test]
+    fn test_loaders_read_through_registry() {
+        let mut sources = Vec::new();
+        collect_named_sources(&Path::new(env!("CARGO_MANIFEST_DIR")).join("src"), &mut sources);
+
+        let bypassing: Vec<_> = sources
+            .iter()
+            .filter(|(name, _)| name != "env_registry.rs")
+            .filter(|(_, source)| non_test_code(source).contains("env::var("))
+            .map(|(name, _)| name.clone())
+            .collect();
+        assert!(bypassing.is_empty(), "read the environment without env_registry: {bypassing:?}");
+    }
+
+    #[test]
+    fn test_registry_has_no_duplicates() {
+        let mut names: Vec<_> = ENV_VARS.iter().map(|v| v.name).collect();
+        names.sort();
+        let len = names.len();
+        names.dedup();
+        assert_eq!(names.len(), len);
+    }
+
+    #[test]
+    fn test_listing_content_and_secret_suppression() {
+        // A fake environment, so the test never races others reading the real one
+        let env: HashMap<&str, &str> = HashMap::from([
+            ("DISCOVERY_S3_KEY", "custom/key.json"),
+            ("OTEL_EXPORTER_OTLP_HEADERS", "x-api-key=super-secret"),
+        ]);
+        let listing = listing_from(|name| env.get(name).map(|v| v.to_string()));
+        let s3_key = listing.iter().find(|e| e.var.name == "DISCOVERY_S3_KEY").unwrap();
+        assert!(s3_key.is_set);
+        assert_eq!(s3_key.value.as_deref(), Some("custom/key.json"));
+
+        let headers = listing
+            .iter()
+            .find(|e| e.var.name == "OTEL_EXPORTER_OTLP_HEADERS")
+            .unwrap();
+        assert!(headers.is_set);
+        assert!(headers.value.is_none());
+
+        let json = serde_json::to_string(&listing).unwrap();
+        assert!(!json.contains("super-secret"));
+        let table = render_listing(&listing);
+        assert!(!table.contains("super-secret"));
+        assert!(table.contains("OTEL_EXPORTER_OTLP_HEADERS"));
+        assert!(table.contains("<secret>"));
+    }
+
+    #[test]
+    fn test_typed_access_uses_defaults() {
+        assert_eq!(parse::<u64>("DISCOVERY_CRAWL_INTERVAL"), Some(86400));
+        assert!(!flag("CANARY_ENABLED"));
+        assert_eq!(lookup("SIGNER_TYPE").map(|v| v.required), Some(true));
+    }
+}
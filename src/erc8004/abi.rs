@@ -115,11 +115,19 @@ sol!(
         /// Get the owner of an agent
         function ownerOf(uint256 agentId) external view returns (address);
 
+        /// Transfer an agent to a new owner
+        function transferFrom(address from, address to, uint256 tokenId) external;
+
         /// Get the total number of registered agents
         function totalSupply() external view returns (uint256);
 
         /// Check if an agent ID exists
         function exists(uint256 agentId) external view returns (bool);
+
+        // ============ Errors ============
+
+        /// OpenZeppelin v5 revert for `ownerOf`/`tokenURI` on an unminted or burned agent ID
+        error ERC721NonexistentToken(uint256 tokenId);
     }
 
     /// Metadata entry for registration
@@ -0,0 +1,177 @@
+//! Cross-network reputation for `GET /reputation/aggregate/:agentUri`.
+//!
+//! An agent registered on several networks lists every registration in the
+//! `registrations` array of its registration file, each as an `agentRegistry`
+//! (`{namespace}:{chainId}:{identityRegistry}`) and the `agentId` it holds there.
+//! [`aggregate_reputation`] reads the unfiltered `getSummary` of each registration on a
+//! supported network concurrently and combines them into one count-weighted score.
+//!
+//! A registration that cannot be read is reported in its own entry instead of failing
+//! the whole aggregate. Summaries share the [`ReputationCache`] of
+//! `GET /reputation/batch`.
+
+use alloy::primitives::Address;
+use alloy::providers::Provider;
+use futures::future::join_all;
+use std::collections::HashMap;
+
+use super::batch::ReputationCache;
+use super::{
+    supported_networks, AgentRegistration, AggregatedReputationResponse, Erc8004Client,
+    Erc8004Error, GetReputationRequest, NetworkReputation, ReputationSummary,
+};
+use crate::network::Network;
+
+/// How [`AggregatedReputationResponse::weighted_score`] is computed, as reported in it.
+pub const WEIGHTING: &str = "weightedScore = sum(count * summaryValue / 10^summaryValueDecimals) / sum(count), over the registries that answered; registries without feedback carry no weight";
+
+/// Reputation of the agent registered as `registrations`, read through `clients`.
+pub async fn aggregate_reputation<P: Provider>(
+    cache: &ReputationCache,
+    agent_uri: &str,
+    registrations: &[AgentRegistration],
+    clients: &HashMap<Network, Erc8004Client<P>>,
+) -> AggregatedReputationResponse {
+    let networks = join_all(
+        registrations
+            .iter()
+            .map(|registration| network_reputation(cache, registration, clients)),
+    )
+    .await;
+    let summaries: Vec<&ReputationSummary> = networks
+        .iter()
+        .filter_map(|network| network.summary.as_ref())
+        .collect();
+    AggregatedReputationResponse {
+        agent_uri: agent_uri.to_string(),
+        total_count: summaries.iter().map(|summary| summary.count).sum(),
+        weighted_score: weighted_score(&summaries),
+        weighting: WEIGHTING.to_string(),
+        networks,
+    }
+}
+
+/// Count-weighted mean of the summary values, `None` without feedback.
+fn weighted_score(summaries: &[&ReputationSummary]) -> Option<f64> {
+    let count: u64 = summaries.iter().map(|summary| summary.count).sum();
+    if count == 0 {
+        return None;
+    }
+    let weighted: f64 = summaries
+        .iter()
+        .map(|summary| {
+            let value = summary.summary_value as f64
+                / 10f64.powi(i32::from(summary.summary_value_decimals));
+            summary.count as f64 * value
+        })
+        .sum();
+    Some(weighted / count as f64)
+}
+
+async fn network_reputation<P: Provider>(
+    cache: &ReputationCache,
+    registration: &AgentRegistration,
+    clients: &HashMap<Network, Erc8004Client<P>>,
+) -> NetworkReputation {
+    let agent_id = registration.agent_id;
+    let mut entry = NetworkReputation {
+        agent_registry: registration.agent_registry.clone(),
+        network: None,
+        agent_id,
+        summary: None,
+        cached: false,
+        error: None,
+    };
+    let (network, registry) = match parse_agent_registry(&registration.agent_registry) {
+        Some(parsed) => parsed,
+        None => {
+            entry.error = Some("Unrecognized agent registry".to_string());
+            return entry;
+        }
+    };
+    entry.network = Some(network);
+    if !supported_networks().contains(&network) {
+        entry.error = Some(format!("ERC-8004 is not supported on network {}", network));
+        return entry;
+    }
+    let Some(client) = clients.get(&network) else {
+        entry.error = Some(format!("No EVM provider available for network {}", network));
+        return entry;
+    };
+    if registry != client.contracts().identity_registry {
+        entry.error = Some(format!(
+            "{} is not the Identity Registry of network {}",
+            registry, network
+        ));
+        return entry;
+    }
+
+    let read = async {
+        if !client.agent_exists(agent_id).await? {
+            return Err(Erc8004Error::AgentNotFound(agent_id));
+        }
+        client
+            .get_summary(agent_id, &GetReputationRequest::default())
+            .await
+    };
+    match cache.get_or_read(network, agent_id, read).await {
+        Ok((summary, cached)) => {
+            entry.summary = Some(summary);
+            entry.cached = cached;
+        }
+        Err(e) => {
+            tracing::warn!(network = %network, agent_id, error = %e, "Failed to read reputation for aggregate");
+            entry.error = Some(e.to_string());
+        }
+    }
+    entry
+}
+
+/// Network and Identity Registry address of an `eip155:{chainId}:{address}` registry.
+fn parse_agent_registry(agent_registry: &str) -> Option<(Network, Address)> {
+    let (caip2, address) = agent_registry.trim().rsplit_once(':')?;
+    Some((Network::from_caip2(caip2)?, address.parse().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::erc8004::ETHEREUM_SEPOLIA_CONTRACTS;
+
+    fn summary(count: u64, summary_value: i128, summary_value_decimals: u8) -> ReputationSummary {
+        ReputationSummary {
+            agent_id: 1,
+            count,
+            summary_value,
+            summary_value_decimals,
+            network: Network::Ethereum,
+        }
+    }
+
+    #[test]
+    fn test_parse_agent_registry() {
+        let (network, address) =
+            parse_agent_registry("eip155:11155111:0x8004A818BFB912233c491871b3d84c89A494BD9e")
+                .unwrap();
+        assert_eq!(network, Network::EthereumSepolia);
+        assert_eq!(address, ETHEREUM_SEPOLIA_CONTRACTS.identity_registry);
+        assert!(parse_agent_registry("eip155:1").is_none());
+        assert!(parse_agent_registry(
+            "eip155:999999999:0x8004A818BFB912233c491871b3d84c89A494BD9e"
+        )
+        .is_none());
+        assert!(parse_agent_registry("eip155:1:not-an-address").is_none());
+    }
+
+    #[test]
+    fn test_weighted_score() {
+        assert_eq!(weighted_score(&[]), None);
+        assert_eq!(weighted_score(&[&summary(0, 0, 0)]), None);
+        // 3 entries averaging 90 and 1 averaging 50.00
+        let score = weighted_score(&[&summary(3, 90, 0), &summary(1, 5000, 2)]).unwrap();
+        assert!((score - 80.0).abs() < 1e-9);
+        // Registries without feedback carry no weight
+        let score = weighted_score(&[&summary(2, 70, 0), &summary(0, 0, 0)]).unwrap();
+        assert!((score - 70.0).abs() < 1e-9);
+    }
+}
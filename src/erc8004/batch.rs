@@ -0,0 +1,335 @@
+//! Batch reputation reads for `GET /reputation/batch`.
+//!
+//! Clients comparing several agents would otherwise make one request, and one
+//! `getSummary` RPC call, per agent. A batch reads every summary concurrently and
+//! keeps each one in an in-memory cache for `ERC8004_REPUTATION_CACHE_TTL` seconds
+//! (default: 60), so repeated comparisons of the same agents do not touch the chain.
+
+use futures::future::join_all;
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use super::{ReputationBatchResponse, ReputationResponse, ReputationSummary};
+use crate::discovery_reputation::ReputationReader;
+use crate::network::Network;
+
+/// Most agents a single batch may query.
+pub const MAX_BATCH_SIZE: usize = 50;
+
+/// Default seconds a summary is served from the cache.
+pub const DEFAULT_CACHE_TTL_SECS: u64 = 60;
+
+#[derive(Debug, thiserror::Error)]
+pub enum BatchError {
+    #[error("agentIds must list between 1 and {MAX_BATCH_SIZE} agent IDs")]
+    Size,
+    #[error("Invalid agent ID: {0:?}")]
+    InvalidAgentId(String),
+    #[error("Failed to query reputation of agent {agent_id}: {error}")]
+    Read { agent_id: u64, error: String },
+}
+
+/// Parse a comma-separated `agentIds` list, dropping repeated IDs.
+pub fn parse_agent_ids(raw: &str) -> Result<Vec<u64>, BatchError> {
+    let mut agent_ids = Vec::new();
+    for id in raw.split(',').map(str::trim).filter(|id| !id.is_empty()) {
+        let id = id
+            .parse()
+            .map_err(|_| BatchError::InvalidAgentId(id.to_string()))?;
+        if !agent_ids.contains(&id) {
+            agent_ids.push(id);
+        }
+    }
+    if agent_ids.is_empty() || agent_ids.len() > MAX_BATCH_SIZE {
+        return Err(BatchError::Size);
+    }
+    Ok(agent_ids)
+}
+
+/// Reputation summaries by network and agent, each kept for a fixed TTL.
+#[derive(Debug)]
+pub struct ReputationCache {
+    ttl: Duration,
+    entries: Mutex<HashMap<(Network, u64), (ReputationSummary, Instant)>>,
+}
+
+impl ReputationCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn from_env() -> Self {
+        let ttl = crate::env_registry::parse("ERC8004_REPUTATION_CACHE_TTL")
+            .unwrap_or(DEFAULT_CACHE_TTL_SECS);
+        Self::new(Duration::from_secs(ttl))
+    }
+
+    fn get(&self, network: Network, agent_id: u64) -> Option<ReputationSummary> {
+        let entries = self.entries.lock().unwrap();
+        entries
+            .get(&(network, agent_id))
+            .filter(|(_, read_at)| read_at.elapsed() < self.ttl)
+            .map(|(summary, _)| summary.clone())
+    }
+
+    fn insert(&self, summary: ReputationSummary) {
+        let mut entries = self.entries.lock().unwrap();
+        entries.retain(|_, (_, read_at)| read_at.elapsed() < self.ttl);
+        entries.insert(
+            (summary.network, summary.agent_id),
+            (summary, Instant::now()),
+        );
+    }
+
+    /// Summary of `agent_id` on `network` from the cache when fresh, otherwise from
+    /// `read`, which is then cached. Also returns whether the cache answered.
+    pub async fn get_or_read<E>(
+        &self,
+        network: Network,
+        agent_id: u64,
+        read: impl Future<Output = Result<ReputationSummary, E>>,
+    ) -> Result<(ReputationSummary, bool), E> {
+        if let Some(summary) = self.get(network, agent_id) {
+            return Ok((summary, true));
+        }
+        let summary = read.await?;
+        self.insert(summary.clone());
+        Ok((summary, false))
+    }
+
+    /// Summaries of `agent_ids` on `network`, reading the ones not cached concurrently.
+    ///
+    /// Fails as a whole if any read fails; the successful reads are still cached.
+    pub async fn fetch_batch(
+        &self,
+        reader: &dyn ReputationReader,
+        network: Network,
+        agent_ids: &[u64],
+    ) -> Result<ReputationBatchResponse, BatchError> {
+        let mut summaries: HashMap<u64, ReputationSummary> = agent_ids
+            .iter()
+            .filter_map(|&agent_id| Some((agent_id, self.get(network, agent_id)?)))
+            .collect();
+        let cached_count = summaries.len();
+
+        let missing: Vec<u64> = agent_ids
+            .iter()
+            .copied()
+            .filter(|agent_id| !summaries.contains_key(agent_id))
+            .collect();
+        let reads =
+            join_all(missing.iter().map(|&agent_id| async move {
+                (agent_id, reader.summary(network, agent_id).await)
+            }))
+            .await;
+        let mut failure = None;
+        for (agent_id, read) in reads {
+            match read {
+                Ok(summary) => {
+                    self.insert(summary.clone());
+                    summaries.insert(agent_id, summary);
+                }
+                Err(error) => {
+                    failure.get_or_insert(BatchError::Read { agent_id, error });
+                }
+            }
+        }
+        if let Some(failure) = failure {
+            return Err(failure);
+        }
+
+        let results = agent_ids
+            .iter()
+            .filter_map(|agent_id| summaries.remove(agent_id))
+            .map(|summary| ReputationResponse {
+                agent_id: summary.agent_id,
+                network: summary.network,
+                summary,
+                feedback: None,
+                feedback_total: None,
+                source: None,
+            })
+            .collect();
+        Ok(ReputationBatchResponse {
+            results,
+            cached_count,
+        })
+    }
+}
+
+/// Cache shared by the `GET /reputation/batch` and `GET /reputation/aggregate` handlers.
+pub static REPUTATION_CACHE: Lazy<ReputationCache> = Lazy::new(ReputationCache::from_env);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::Address;
+    use async_trait::async_trait;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Reader that answers after a delay, tracking calls and reads in flight.
+    #[derive(Default)]
+    struct CountingReader {
+        calls: AtomicUsize,
+        in_flight: AtomicUsize,
+        max_in_flight: AtomicUsize,
+        failing: Option<u64>,
+    }
+
+    #[async_trait]
+    impl ReputationReader for CountingReader {
+        async fn summary(
+            &self,
+            network: Network,
+            agent_id: u64,
+        ) -> Result<ReputationSummary, String> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            let in_flight = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max_in_flight.fetch_max(in_flight, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            self.in_flight.fetch_sub(1, Ordering::SeqCst);
+            if self.failing == Some(agent_id) {
+                return Err("execution reverted".to_string());
+            }
+            Ok(ReputationSummary {
+                agent_id,
+                count: agent_id,
+                summary_value: i128::from(agent_id) * 10,
+                summary_value_decimals: 0,
+                network,
+            })
+        }
+
+        async fn agent_wallet(&self, _: Network, _: u64) -> Result<Option<Address>, String> {
+            Ok(None)
+        }
+    }
+
+    #[test]
+    fn test_parse_agent_ids() {
+        assert_eq!(parse_agent_ids("1, 2,3,2,").unwrap(), vec![1, 2, 3]);
+        assert!(matches!(
+            parse_agent_ids("1,x"),
+            Err(BatchError::InvalidAgentId(id)) if id == "x"
+        ));
+        assert!(matches!(parse_agent_ids(" , "), Err(BatchError::Size)));
+        let too_many = (0..=MAX_BATCH_SIZE as u64)
+            .map(|id| id.to_string())
+            .collect::<Vec<_>>()
+            .join(",");
+        assert!(matches!(parse_agent_ids(&too_many), Err(BatchError::Size)));
+    }
+
+    #[tokio::test]
+    async fn test_batch_reads_concurrently() {
+        let cache = ReputationCache::new(Duration::from_secs(60));
+        let reader = CountingReader::default();
+
+        let batch = cache
+            .fetch_batch(&reader, Network::Ethereum, &[3, 1, 2])
+            .await
+            .unwrap();
+
+        assert_eq!(reader.calls.load(Ordering::SeqCst), 3);
+        assert_eq!(reader.max_in_flight.load(Ordering::SeqCst), 3);
+        assert_eq!(batch.cached_count, 0);
+        let ids: Vec<u64> = batch.results.iter().map(|r| r.agent_id).collect();
+        assert_eq!(ids, vec![3, 1, 2]);
+        assert_eq!(batch.results[0].summary.summary_value, 30);
+        assert_eq!(batch.results[0].network, Network::Ethereum);
+    }
+
+    #[tokio::test]
+    async fn test_cached_summaries_skip_rpc() {
+        let cache = ReputationCache::new(Duration::from_secs(60));
+        let reader = CountingReader::default();
+        cache
+            .fetch_batch(&reader, Network::Ethereum, &[1, 2])
+            .await
+            .unwrap();
+
+        let batch = cache
+            .fetch_batch(&reader, Network::Ethereum, &[1, 2, 3])
+            .await
+            .unwrap();
+        assert_eq!(reader.calls.load(Ordering::SeqCst), 3);
+        assert_eq!(batch.cached_count, 2);
+        assert_eq!(batch.results.len(), 3);
+
+        // Entries are per network
+        cache
+            .fetch_batch(&reader, Network::EthereumSepolia, &[1])
+            .await
+            .unwrap();
+        assert_eq!(reader.calls.load(Ordering::SeqCst), 4);
+    }
+
+    #[tokio::test]
+    async fn test_expired_summaries_are_read_again() {
+        let cache = ReputationCache::new(Duration::from_millis(10));
+        let reader = CountingReader::default();
+        cache
+            .fetch_batch(&reader, Network::Ethereum, &[1])
+            .await
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(20)).await;
+
+        let batch = cache
+            .fetch_batch(&reader, Network::Ethereum, &[1])
+            .await
+            .unwrap();
+        assert_eq!(batch.cached_count, 0);
+        assert_eq!(reader.calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_get_or_read_caches_successful_reads() {
+        let cache = ReputationCache::new(Duration::from_secs(60));
+        let reader = CountingReader {
+            failing: Some(2),
+            ..Default::default()
+        };
+
+        let (_, cached) = cache
+            .get_or_read(Network::Ethereum, 1, reader.summary(Network::Ethereum, 1))
+            .await
+            .unwrap();
+        assert!(!cached);
+        let (summary, cached) = cache
+            .get_or_read(Network::Ethereum, 1, reader.summary(Network::Ethereum, 1))
+            .await
+            .unwrap();
+        assert!(cached);
+        assert_eq!(summary.summary_value, 10);
+        assert_eq!(reader.calls.load(Ordering::SeqCst), 1);
+
+        assert!(cache
+            .get_or_read(Network::Ethereum, 2, reader.summary(Network::Ethereum, 2))
+            .await
+            .is_err());
+        assert!(cache.get(Network::Ethereum, 2).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_failed_read_fails_batch_but_caches_others() {
+        let cache = ReputationCache::new(Duration::from_secs(60));
+        let reader = CountingReader {
+            failing: Some(2),
+            ..Default::default()
+        };
+
+        let error = cache
+            .fetch_batch(&reader, Network::Ethereum, &[1, 2])
+            .await
+            .unwrap_err();
+        assert!(matches!(error, BatchError::Read { agent_id: 2, .. }));
+        assert!(cache.get(Network::Ethereum, 1).is_some());
+        assert!(cache.get(Network::Ethereum, 2).is_none());
+    }
+}
@@ -0,0 +1,454 @@
+//! Read-only client for the ERC-8004 Identity and Reputation Registries.
+//!
+//! [`Erc8004Client`] wraps any alloy [`Provider`] together with the [`Erc8004Contracts`]
+//! of a network, so the same code reads mainnet and Sepolia registries:
+//!
+//! ```ignore
+//! let contracts = get_contracts(&Network::EthereumSepolia).unwrap();
+//! let client = Erc8004Client::new(provider, contracts).with_network(Network::EthereumSepolia);
+//! let identity = client.get_identity(42).await?;
+//! ```
+//!
+//! Reverts of `ownerOf` for an unminted or burned agent ID surface as
+//! [`Erc8004Error::AgentNotFound`] rather than as an opaque contract error.
+
+use alloy::primitives::{Address, U256};
+use alloy::providers::Provider;
+use alloy::sol_types::Revert;
+
+use super::{
+    get_contracts, supported_networks, AgentIdentity, Erc8004Contracts, FeedbackEntry,
+    GetReputationRequest, IIdentityRegistry, IReputationRegistry, ReputationSummary,
+};
+use crate::network::Network;
+use crate::types::{EvmAddress, MixedAddress};
+
+/// Revert reasons of pre-v5 OpenZeppelin ERC-721 `ownerOf` for a missing token.
+const NONEXISTENT_TOKEN_REASONS: [&str; 2] = ["invalid token ID", "nonexistent token"];
+
+/// Errors from ERC-8004 registry reads.
+#[derive(Debug, thiserror::Error)]
+pub enum Erc8004Error {
+    /// No agent with this ID is registered
+    #[error("Agent {0} not found in Identity Registry")]
+    AgentNotFound(u64),
+
+    /// The registry call failed for another reason (RPC error, unexpected revert, bad return data)
+    #[error("Identity Registry call failed: {0}")]
+    Contract(#[from] alloy::contract::Error),
+
+    /// A Reputation Registry read failed
+    #[error("Reputation Registry call failed: {0}")]
+    Reputation(alloy::contract::Error),
+
+    /// A reputation filter names a client that cannot be on an EVM registry
+    #[error("Client {0} is not an EVM address")]
+    NonEvmClient(MixedAddress),
+}
+
+/// Client for the ERC-8004 Identity Registry of one network.
+#[derive(Debug, Clone)]
+pub struct Erc8004Client<P> {
+    identity_registry: IIdentityRegistry::IIdentityRegistryInstance<P>,
+    contracts: Erc8004Contracts,
+    network: Network,
+}
+
+impl<P: Provider> Erc8004Client<P> {
+    /// Read the registries at `contracts` through `provider`.
+    ///
+    /// The network reported in [`AgentIdentity`] is the supported network whose
+    /// canonical deployment matches `contracts` (Ethereum mainnet otherwise);
+    /// use [`Erc8004Client::with_network`] for custom deployments.
+    pub fn new(provider: P, contracts: Erc8004Contracts) -> Self {
+        let network = supported_networks()
+            .into_iter()
+            .find(|network| {
+                get_contracts(network)
+                    .is_some_and(|c| c.identity_registry == contracts.identity_registry)
+            })
+            .unwrap_or(Network::Ethereum);
+        Self {
+            identity_registry: IIdentityRegistry::new(contracts.identity_registry, provider),
+            contracts,
+            network,
+        }
+    }
+
+    /// Report identities as registered on `network`.
+    pub fn with_network(mut self, network: Network) -> Self {
+        self.network = network;
+        self
+    }
+
+    pub fn contracts(&self) -> &Erc8004Contracts {
+        &self.contracts
+    }
+
+    pub fn network(&self) -> Network {
+        self.network
+    }
+
+    /// Owner, registration URI and payment wallet of an agent.
+    ///
+    /// A wallet that is unset (the zero address) or that the registry cannot
+    /// report, as on deployments predating `getAgentWallet`, is `None`.
+    pub async fn get_identity(&self, agent_id: u64) -> Result<AgentIdentity, Erc8004Error> {
+        let owner = self.owner_of(agent_id).await?;
+        let id = U256::from(agent_id);
+        let uri_call = self.identity_registry.tokenURI(id);
+        let wallet_call = self.identity_registry.getAgentWallet(id);
+        let (agent_uri, wallet) = tokio::join!(uri_call.call(), wallet_call.call());
+        let agent_uri = agent_uri.map_err(|e| not_found_or(agent_id, e))?;
+        let agent_wallet = match wallet {
+            Ok(wallet) if wallet != Address::ZERO => Some(MixedAddress::Evm(EvmAddress(wallet))),
+            Ok(_) => None,
+            Err(e) => {
+                tracing::warn!(agent_id, error = %e, "Failed to get agent wallet");
+                None
+            }
+        };
+        Ok(AgentIdentity {
+            agent_id,
+            owner: MixedAddress::Evm(EvmAddress(owner)),
+            agent_uri,
+            agent_wallet,
+            network: self.network,
+        })
+    }
+
+    /// Registration URI of an agent (`tokenURI`).
+    pub async fn agent_uri(&self, agent_id: u64) -> Result<String, Erc8004Error> {
+        self.identity_registry
+            .tokenURI(U256::from(agent_id))
+            .call()
+            .await
+            .map_err(|e| not_found_or(agent_id, e))
+    }
+
+    /// Whether `agent_id` is registered, i.e. `ownerOf` does not revert.
+    pub async fn agent_exists(&self, agent_id: u64) -> Result<bool, Erc8004Error> {
+        match self.owner_of(agent_id).await {
+            Ok(_) => Ok(true),
+            Err(Erc8004Error::AgentNotFound(_)) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Number of registered agents (`totalSupply`).
+    pub async fn total_agents(&self) -> Result<u64, Erc8004Error> {
+        let total = self.identity_registry.totalSupply().call().await?;
+        Ok(total.saturating_to())
+    }
+
+    /// Aggregated feedback of an agent (`getSummary`), restricted to `filter`.
+    pub async fn get_summary(
+        &self,
+        agent_id: u64,
+        filter: &GetReputationRequest,
+    ) -> Result<ReputationSummary, Erc8004Error> {
+        let summary = self
+            .reputation_registry()
+            .getSummary(
+                U256::from(agent_id),
+                client_addresses(filter)?,
+                filter.tag1.clone(),
+                filter.tag2.clone(),
+            )
+            .call()
+            .await
+            .map_err(Erc8004Error::Reputation)?;
+        Ok(ReputationSummary {
+            agent_id,
+            count: summary.count,
+            summary_value: summary.summaryValue,
+            summary_value_decimals: summary.summaryValueDecimals,
+            network: self.network,
+        })
+    }
+
+    /// Individual feedback entries of an agent (`readAllFeedback`), restricted to `filter`.
+    pub async fn read_all_feedback(
+        &self,
+        agent_id: u64,
+        filter: &GetReputationRequest,
+        include_revoked: bool,
+    ) -> Result<Vec<FeedbackEntry>, Erc8004Error> {
+        let feedback = self
+            .reputation_registry()
+            .readAllFeedback(
+                U256::from(agent_id),
+                client_addresses(filter)?,
+                filter.tag1.clone(),
+                filter.tag2.clone(),
+                include_revoked,
+            )
+            .call()
+            .await
+            .map_err(Erc8004Error::Reputation)?;
+        let entries = feedback
+            .clients
+            .into_iter()
+            .zip(feedback.feedbackIndexes)
+            .zip(feedback.values)
+            .zip(feedback.valueDecimals)
+            .zip(feedback.tag1s)
+            .zip(feedback.tag2s)
+            .zip(feedback.revokedStatuses)
+            .map(
+                |((((((client, index), value), decimals), tag1), tag2), revoked)| FeedbackEntry {
+                    client: MixedAddress::Evm(EvmAddress(client)),
+                    feedback_index: index,
+                    value,
+                    value_decimals: decimals,
+                    tag1,
+                    tag2,
+                    is_revoked: revoked,
+                },
+            )
+            .collect();
+        Ok(entries)
+    }
+
+    fn reputation_registry(&self) -> IReputationRegistry::IReputationRegistryInstance<&P> {
+        IReputationRegistry::new(
+            self.contracts.reputation_registry,
+            self.identity_registry.provider(),
+        )
+    }
+
+    async fn owner_of(&self, agent_id: u64) -> Result<Address, Erc8004Error> {
+        self.identity_registry
+            .ownerOf(U256::from(agent_id))
+            .call()
+            .await
+            .map_err(|e| not_found_or(agent_id, e))
+    }
+}
+
+/// EVM addresses of the clients `filter` is restricted to.
+fn client_addresses(filter: &GetReputationRequest) -> Result<Vec<Address>, Erc8004Error> {
+    filter
+        .client_addresses
+        .iter()
+        .map(|client| match client {
+            MixedAddress::Evm(address) => Ok(address.0),
+            other => Err(Erc8004Error::NonEvmClient(other.clone())),
+        })
+        .collect()
+}
+
+/// [`Erc8004Error::AgentNotFound`] if `error` is the registry rejecting an unknown token.
+fn not_found_or(agent_id: u64, error: alloy::contract::Error) -> Erc8004Error {
+    let missing = error
+        .as_decoded_error::<IIdentityRegistry::ERC721NonexistentToken>()
+        .is_some()
+        || error.as_decoded_error::<Revert>().is_some_and(|revert| {
+            NONEXISTENT_TOKEN_REASONS
+                .iter()
+                .any(|reason| revert.reason.contains(reason))
+        });
+    if missing {
+        Erc8004Error::AgentNotFound(agent_id)
+    } else {
+        Erc8004Error::Contract(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::erc8004::{ETHEREUM_MAINNET_CONTRACTS, ETHEREUM_SEPOLIA_CONTRACTS};
+    use alloy::primitives::{address, Bytes};
+    use alloy::providers::ProviderBuilder;
+    use alloy::sol_types::{SolCall, SolError, SolValue};
+    use serde_json::{json, Value};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const OWNER: Address = address!("1111111111111111111111111111111111111111");
+    const WALLET: Address = address!("2222222222222222222222222222222222222222");
+
+    /// Answer `eth_call`s to `registry` from `reply`, keyed by calldata;
+    /// calldata without a reply fails the test.
+    async fn serve_registry(
+        registry: Address,
+        reply: impl Fn(&[u8]) -> Result<Vec<u8>, Vec<u8>> + Send + Sync + 'static,
+    ) -> MockServer {
+        let server = MockServer::start().await;
+        Mock::given(wiremock::matchers::method("POST"))
+            .respond_with(move |request: &wiremock::Request| {
+                let body: Value = request.body_json().unwrap();
+                assert_eq!(body["method"], "eth_call");
+                let call = &body["params"][0];
+                let to: Address = serde_json::from_value(call["to"].clone()).unwrap();
+                assert_eq!(to, registry);
+                let input = call.get("input").or_else(|| call.get("data")).unwrap();
+                let input: Bytes = serde_json::from_value(input.clone()).unwrap();
+                let response = match reply(&input) {
+                    Ok(output) => json!({ "jsonrpc": "2.0", "id": body["id"], "result": Bytes::from(output) }),
+                    Err(revert) => json!({
+                        "jsonrpc": "2.0",
+                        "id": body["id"],
+                        "error": { "code": 3, "message": "execution reverted", "data": Bytes::from(revert) }
+                    }),
+                };
+                ResponseTemplate::new(200).set_body_json(response)
+            })
+            .mount(&server)
+            .await;
+        server
+    }
+
+    fn client(server: &MockServer, contracts: Erc8004Contracts) -> Erc8004Client<impl Provider> {
+        let provider = ProviderBuilder::new().connect_http(server.uri().parse().unwrap());
+        Erc8004Client::new(provider, contracts)
+    }
+
+    fn nonexistent(agent_id: u64) -> Vec<u8> {
+        IIdentityRegistry::ERC721NonexistentToken {
+            tokenId: U256::from(agent_id),
+        }
+        .abi_encode()
+    }
+
+    fn revert(reason: &str) -> Vec<u8> {
+        Revert {
+            reason: reason.to_string(),
+        }
+        .abi_encode()
+    }
+
+    #[tokio::test]
+    async fn test_get_identity_decodes_registry_reads() {
+        let contracts = ETHEREUM_SEPOLIA_CONTRACTS;
+        let id = U256::from(42);
+        let owner_of = IIdentityRegistry::ownerOfCall { agentId: id }.abi_encode();
+        let token_uri = IIdentityRegistry::tokenURICall { agentId: id }.abi_encode();
+        let wallet = IIdentityRegistry::getAgentWalletCall { agentId: id }.abi_encode();
+        let server = serve_registry(contracts.identity_registry, move |input| {
+            if input == owner_of.as_slice() {
+                Ok(OWNER.abi_encode())
+            } else if input == token_uri.as_slice() {
+                Ok("ipfs://agent-42".to_string().abi_encode())
+            } else if input == wallet.as_slice() {
+                Ok(WALLET.abi_encode())
+            } else {
+                panic!("unexpected calldata {}", Bytes::copy_from_slice(input))
+            }
+        })
+        .await;
+
+        let identity = client(&server, contracts).get_identity(42).await.unwrap();
+        assert_eq!(identity.agent_id, 42);
+        assert_eq!(identity.owner, MixedAddress::Evm(EvmAddress(OWNER)));
+        assert_eq!(identity.agent_uri, "ipfs://agent-42");
+        assert_eq!(
+            identity.agent_wallet,
+            Some(MixedAddress::Evm(EvmAddress(WALLET)))
+        );
+        assert_eq!(identity.network, Network::EthereumSepolia);
+    }
+
+    #[tokio::test]
+    async fn test_unset_wallet_is_none() {
+        let contracts = ETHEREUM_MAINNET_CONTRACTS;
+        let wallet_selector = IIdentityRegistry::getAgentWalletCall::SELECTOR;
+        let server = serve_registry(contracts.identity_registry, move |input| {
+            if input.starts_with(&wallet_selector) {
+                Ok(Address::ZERO.abi_encode())
+            } else if input.starts_with(&IIdentityRegistry::tokenURICall::SELECTOR) {
+                Ok(String::new().abi_encode())
+            } else {
+                Ok(OWNER.abi_encode())
+            }
+        })
+        .await;
+
+        let identity = client(&server, contracts).get_identity(1).await.unwrap();
+        assert_eq!(identity.agent_wallet, None);
+        assert_eq!(identity.network, Network::Ethereum);
+    }
+
+    #[tokio::test]
+    async fn test_nonexistent_agent_is_typed_error() {
+        let contracts = ETHEREUM_SEPOLIA_CONTRACTS;
+        let server = serve_registry(contracts.identity_registry, |input| {
+            assert!(input.starts_with(&IIdentityRegistry::ownerOfCall::SELECTOR));
+            Err(nonexistent(7))
+        })
+        .await;
+        let client = client(&server, contracts);
+
+        assert!(matches!(
+            client.get_identity(7).await,
+            Err(Erc8004Error::AgentNotFound(7))
+        ));
+        assert!(!client.agent_exists(7).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_agent_uri() {
+        let contracts = ETHEREUM_SEPOLIA_CONTRACTS;
+        let server = serve_registry(contracts.identity_registry, |input| {
+            let call = IIdentityRegistry::tokenURICall::abi_decode(input).unwrap();
+            if call.agentId == U256::from(42) {
+                Ok("ar://agent-42".to_string().abi_encode())
+            } else {
+                Err(nonexistent(7))
+            }
+        })
+        .await;
+        let client = client(&server, contracts);
+
+        assert_eq!(client.agent_uri(42).await.unwrap(), "ar://agent-42");
+        assert!(matches!(
+            client.agent_uri(7).await,
+            Err(Erc8004Error::AgentNotFound(7))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_agent_exists_and_legacy_revert_string() {
+        let contracts = ETHEREUM_SEPOLIA_CONTRACTS;
+        let server = serve_registry(contracts.identity_registry, |input| {
+            let call = IIdentityRegistry::ownerOfCall::abi_decode(input).unwrap();
+            if call.agentId == U256::from(1) {
+                Ok(OWNER.abi_encode())
+            } else {
+                Err(revert("ERC721: invalid token ID"))
+            }
+        })
+        .await;
+        let client = client(&server, contracts);
+
+        assert!(client.agent_exists(1).await.unwrap());
+        assert!(!client.agent_exists(2).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_other_reverts_are_not_masked() {
+        let contracts = ETHEREUM_SEPOLIA_CONTRACTS;
+        let server = serve_registry(contracts.identity_registry, |_| Err(revert("paused"))).await;
+
+        assert!(matches!(
+            client(&server, contracts).agent_exists(1).await,
+            Err(Erc8004Error::Contract(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_total_agents() {
+        let contracts = ETHEREUM_MAINNET_CONTRACTS;
+        let total_supply = IIdentityRegistry::totalSupplyCall {}.abi_encode();
+        let server = serve_registry(contracts.identity_registry, move |input| {
+            assert_eq!(input, total_supply.as_slice());
+            Ok(U256::from(1234).abi_encode())
+        })
+        .await;
+
+        assert_eq!(
+            client(&server, contracts).total_agents().await.unwrap(),
+            1234
+        );
+    }
+}
@@ -0,0 +1,183 @@
+//! Integrity of off-chain feedback files.
+//!
+//! Feedback carries a `feedbackURI` pointing at an off-chain file and a `feedbackHash`
+//! committing to it on-chain. `POST /feedback` fetches the file and hashes its raw bytes
+//! with keccak256, without any canonicalization:
+//!
+//! - with both a URI and a hash, feedback whose hash does not match the file is rejected
+//! - with only a URI, the hash is filled in before the feedback is submitted
+//!
+//! URIs are fetched like agent registration files (see [`registration`](super::registration)),
+//! plain `http://` included. Files larger than [`MAX_FEEDBACK_FILE_BYTES`] are rejected,
+//! and fetches time out after [`FETCH_TIMEOUT`](super::registration::FETCH_TIMEOUT).
+
+use alloy::primitives::{keccak256, B256};
+use once_cell::sync::Lazy;
+
+use super::registration::{AgentUriResolver, ResolverError};
+use super::FeedbackParams;
+
+/// Largest feedback file accepted, in bytes.
+pub const MAX_FEEDBACK_FILE_BYTES: usize = 256 * 1024;
+
+#[derive(Debug, thiserror::Error)]
+pub enum FeedbackFileError {
+    #[error("Unsupported feedbackURI: {0}")]
+    Unsupported(String),
+    #[error("Failed to fetch feedbackURI: {0}")]
+    Fetch(String),
+    #[error("feedbackURI request failed with status {0}")]
+    Status(u16),
+    #[error("Feedback file exceeds {0} bytes")]
+    TooLarge(usize),
+    #[error(
+        "feedbackHash {declared} does not match the keccak256 of the feedbackURI content, {actual}"
+    )]
+    Mismatch { declared: B256, actual: B256 },
+}
+
+impl From<ResolverError> for FeedbackFileError {
+    fn from(e: ResolverError) -> Self {
+        match e {
+            ResolverError::EmptyUri => Self::Unsupported("empty URI".to_string()),
+            ResolverError::UnsupportedScheme(scheme) => {
+                Self::Unsupported(format!("unsupported scheme {scheme}"))
+            }
+            ResolverError::InvalidUri(uri) => Self::Unsupported(uri),
+            ResolverError::Status(status) => Self::Status(status),
+            ResolverError::TooLarge(max_bytes) => Self::TooLarge(max_bytes),
+            ResolverError::Fetch(e) => Self::Fetch(e.to_string()),
+            ResolverError::InvalidJson(e) => Self::Fetch(e.to_string()),
+        }
+    }
+}
+
+/// Check `feedback.feedback_hash` against the file at `feedback.feedback_uri`, or fill it
+/// in when absent. Feedback without a URI is left untouched.
+pub async fn check_feedback_hash(
+    resolver: &AgentUriResolver,
+    feedback: &mut FeedbackParams,
+) -> Result<(), FeedbackFileError> {
+    if feedback.feedback_uri.trim().is_empty() {
+        return Ok(());
+    }
+    let content = resolver
+        .fetch_bytes(&feedback.feedback_uri, MAX_FEEDBACK_FILE_BYTES)
+        .await?;
+    let actual = keccak256(&content);
+    match feedback.feedback_hash {
+        Some(declared) if declared != actual => {
+            Err(FeedbackFileError::Mismatch { declared, actual })
+        }
+        _ => {
+            feedback.feedback_hash = Some(actual);
+            Ok(())
+        }
+    }
+}
+
+/// Resolver shared by `POST /feedback`.
+pub static FEEDBACK_FILE_RESOLVER: Lazy<AgentUriResolver> =
+    Lazy::new(|| AgentUriResolver::from_env().allow_http(true));
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::num::NonZeroUsize;
+    use std::time::Duration;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const CONTENT: &[u8] = br#"{"agentRegistry":"eip155:11155111:0x8004","agentId":42,"value":87}"#;
+
+    fn resolver() -> AgentUriResolver {
+        AgentUriResolver::new(NonZeroUsize::new(1).unwrap(), Duration::ZERO).allow_http(true)
+    }
+
+    fn feedback(feedback_uri: String, feedback_hash: Option<B256>) -> FeedbackParams {
+        FeedbackParams {
+            agent_id: 42,
+            value: 87,
+            value_decimals: 0,
+            tag1: "starred".to_string(),
+            tag2: String::new(),
+            endpoint: String::new(),
+            feedback_uri,
+            feedback_hash,
+            proof: None,
+        }
+    }
+
+    async fn file_server(body: Vec<u8>) -> MockServer {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/feedback.json"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(body))
+            .mount(&server)
+            .await;
+        server
+    }
+
+    #[tokio::test]
+    async fn test_matching_hash_is_accepted() {
+        let server = file_server(CONTENT.to_vec()).await;
+        let uri = format!("{}/feedback.json", server.uri());
+        let mut params = feedback(uri, Some(keccak256(CONTENT)));
+
+        check_feedback_hash(&resolver(), &mut params).await.unwrap();
+
+        assert_eq!(params.feedback_hash, Some(keccak256(CONTENT)));
+    }
+
+    #[tokio::test]
+    async fn test_missing_hash_is_filled_in() {
+        let server = file_server(CONTENT.to_vec()).await;
+        let mut params = feedback(format!("{}/feedback.json", server.uri()), None);
+
+        check_feedback_hash(&resolver(), &mut params).await.unwrap();
+
+        assert_eq!(params.feedback_hash, Some(keccak256(CONTENT)));
+    }
+
+    #[tokio::test]
+    async fn test_mismatched_hash_is_rejected() {
+        let server = file_server(CONTENT.to_vec()).await;
+        let declared = B256::repeat_byte(0x11);
+        let mut params = feedback(format!("{}/feedback.json", server.uri()), Some(declared));
+
+        let result = check_feedback_hash(&resolver(), &mut params).await;
+
+        assert!(
+            matches!(result, Err(FeedbackFileError::Mismatch { declared: d, actual })
+                if d == declared && actual == keccak256(CONTENT)),
+            "{result:?}"
+        );
+        assert_eq!(params.feedback_hash, Some(declared));
+    }
+
+    #[tokio::test]
+    async fn test_oversized_file_is_rejected() {
+        let server = file_server(vec![b' '; MAX_FEEDBACK_FILE_BYTES + 1]).await;
+        let mut params = feedback(format!("{}/feedback.json", server.uri()), None);
+
+        let result = check_feedback_hash(&resolver(), &mut params).await;
+
+        assert!(
+            matches!(
+                result,
+                Err(FeedbackFileError::TooLarge(MAX_FEEDBACK_FILE_BYTES))
+            ),
+            "{result:?}"
+        );
+        assert_eq!(params.feedback_hash, None);
+    }
+
+    #[tokio::test]
+    async fn test_feedback_without_uri_is_untouched() {
+        let mut params = feedback(String::new(), None);
+
+        check_feedback_hash(&resolver(), &mut params).await.unwrap();
+
+        assert_eq!(params.feedback_hash, None);
+    }
+}
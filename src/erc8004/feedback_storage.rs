@@ -0,0 +1,361 @@
+//! Storage of facilitator-generated feedback files.
+//!
+//! Clients that want richer feedback than the on-chain value can send a [`FeedbackFile`]
+//! with `POST /feedback` instead of hosting one themselves. The file is serialized to
+//! JSON, stored by a [`FeedbackStorage`], and the resulting URI and the keccak256 of the
+//! stored bytes become the feedback's `feedbackURI` and `feedbackHash`.
+//!
+//! Two stores are available:
+//!
+//! - [`IpfsPinningStorage`] uploads the file to a Pinata-compatible pinning service
+//!   (`pinFileToIPFS`, or web3.storage's `upload`) and returns an `ipfs://` URI
+//! - [`LocalFeedbackStorage`] writes the file to disk, served at
+//!   `GET /feedback-files/{id}`
+//!
+//! # Environment
+//!
+//! - `FEEDBACK_IPFS_PINNING_TOKEN` - Bearer token of the pinning service; selects IPFS
+//! - `FEEDBACK_IPFS_PINNING_URL` - Upload endpoint of the pinning service
+//! - `FEEDBACK_FILES_DIR` - Directory of the local store, used without a pinning token
+//! - `FEEDBACK_FILES_BASE_URL` - Public URL of this facilitator, prefixed to local URIs
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use alloy::primitives::{keccak256, B256};
+use async_trait::async_trait;
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+use tracing::{info, warn};
+use url::Url;
+
+use super::FeedbackFile;
+
+/// How long an upload to the pinning service may take.
+const PINNING_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Errors from storing a feedback file.
+#[derive(Debug, thiserror::Error)]
+pub enum FeedbackStorageError {
+    #[error("Failed to serialize feedback file: {0}")]
+    Serialize(#[from] serde_json::Error),
+    #[error("Failed to write feedback file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Failed to pin feedback file: {0}")]
+    Pinning(String),
+}
+
+/// Where feedback files are kept.
+#[async_trait]
+pub trait FeedbackStorage: Send + Sync {
+    /// Store `file`, returning its URI and the keccak256 of the stored bytes.
+    async fn store(&self, file: &FeedbackFile) -> Result<(String, B256), FeedbackStorageError>;
+}
+
+/// Feedback file bytes as stored, with their keccak256.
+fn serialize(file: &FeedbackFile) -> Result<(Vec<u8>, B256), FeedbackStorageError> {
+    let bytes = serde_json::to_vec(file)?;
+    let hash = keccak256(&bytes);
+    Ok((bytes, hash))
+}
+
+// ============================================================================
+// Local Disk
+// ============================================================================
+
+/// Store writing one JSON file per feedback file, named after its hash.
+#[derive(Debug, Clone)]
+pub struct LocalFeedbackStorage {
+    dir: PathBuf,
+    base_url: Url,
+}
+
+impl LocalFeedbackStorage {
+    /// Store in `dir`, with URIs under `{base_url}/feedback-files/`.
+    pub fn new(dir: impl Into<PathBuf>, base_url: Url) -> Self {
+        Self {
+            dir: dir.into(),
+            base_url,
+        }
+    }
+
+    /// Store from `FEEDBACK_FILES_DIR` and `FEEDBACK_FILES_BASE_URL`, if both are set.
+    pub fn from_env() -> Option<Self> {
+        let dir =
+            crate::env_registry::var("FEEDBACK_FILES_DIR").filter(|d| !d.trim().is_empty())?;
+        let Some(base_url) = crate::env_registry::var("FEEDBACK_FILES_BASE_URL")
+            .filter(|url| !url.trim().is_empty())
+        else {
+            warn!("FEEDBACK_FILES_DIR is set without FEEDBACK_FILES_BASE_URL, not storing feedback files");
+            return None;
+        };
+        match Url::parse(&base_url) {
+            Ok(base_url) => Some(Self::new(dir, base_url)),
+            Err(e) => {
+                warn!(error = %e, "Invalid FEEDBACK_FILES_BASE_URL, not storing feedback files");
+                None
+            }
+        }
+    }
+
+    /// Bytes of a stored feedback file, `None` if `id` is unknown or malformed.
+    pub async fn load(&self, id: &str) -> Option<Vec<u8>> {
+        // Ids are hex hashes, which also keeps paths inside the directory
+        if id.len() != 64 || !id.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return None;
+        }
+        tokio::fs::read(self.path(id)).await.ok()
+    }
+
+    fn path(&self, id: &str) -> PathBuf {
+        self.dir.join(format!("{id}.json"))
+    }
+}
+
+#[async_trait]
+impl FeedbackStorage for LocalFeedbackStorage {
+    async fn store(&self, file: &FeedbackFile) -> Result<(String, B256), FeedbackStorageError> {
+        let (bytes, hash) = serialize(file)?;
+        let id = alloy::hex::encode(hash);
+        tokio::fs::create_dir_all(&self.dir).await?;
+        // Write then rename so a crash never leaves a truncated file
+        let tmp = self.dir.join(format!("{id}.json.tmp"));
+        tokio::fs::write(&tmp, &bytes).await?;
+        tokio::fs::rename(&tmp, self.path(&id)).await?;
+        let uri = format!(
+            "{}/feedback-files/{id}",
+            self.base_url.as_str().trim_end_matches('/')
+        );
+        Ok((uri, hash))
+    }
+}
+
+// ============================================================================
+// IPFS Pinning Service
+// ============================================================================
+
+/// Default upload endpoint of the pinning service.
+pub const DEFAULT_PINNING_URL: &str = "https://api.pinata.cloud/pinning/pinFileToIPFS";
+
+/// Store uploading feedback files to an IPFS pinning service.
+///
+/// The file is sent as the `file` part of a multipart upload with a bearer token. The
+/// CID is read from Pinata's `IpfsHash` or web3.storage's `cid`.
+#[derive(Clone)]
+pub struct IpfsPinningStorage {
+    client: reqwest::Client,
+    endpoint: Url,
+    token: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct PinResponse {
+    #[serde(rename = "IpfsHash", alias = "cid")]
+    cid: String,
+}
+
+impl IpfsPinningStorage {
+    pub fn new(endpoint: Url, token: impl Into<String>) -> Self {
+        Self {
+            client: reqwest::Client::builder()
+                .timeout(PINNING_TIMEOUT)
+                .build()
+                .expect("Failed to build pinning client"),
+            endpoint,
+            token: token.into(),
+        }
+    }
+
+    /// Store from `FEEDBACK_IPFS_PINNING_TOKEN` and `FEEDBACK_IPFS_PINNING_URL`, if a
+    /// token is set.
+    pub fn from_env() -> Option<Self> {
+        let token = crate::env_registry::var("FEEDBACK_IPFS_PINNING_TOKEN")
+            .filter(|token| !token.trim().is_empty())?;
+        let endpoint = crate::env_registry::var("FEEDBACK_IPFS_PINNING_URL")
+            .unwrap_or_else(|| DEFAULT_PINNING_URL.to_string());
+        match Url::parse(&endpoint) {
+            Ok(endpoint) => Some(Self::new(endpoint, token)),
+            Err(e) => {
+                warn!(error = %e, "Invalid FEEDBACK_IPFS_PINNING_URL, not pinning feedback files");
+                None
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl FeedbackStorage for IpfsPinningStorage {
+    async fn store(&self, file: &FeedbackFile) -> Result<(String, B256), FeedbackStorageError> {
+        let (bytes, hash) = serialize(file)?;
+        let part = reqwest::multipart::Part::bytes(bytes)
+            .file_name(format!("{}.json", alloy::hex::encode(hash)))
+            .mime_str("application/json")
+            .map_err(|e| FeedbackStorageError::Pinning(e.to_string()))?;
+        let response = self
+            .client
+            .post(self.endpoint.clone())
+            .bearer_auth(&self.token)
+            .multipart(reqwest::multipart::Form::new().part("file", part))
+            .send()
+            .await
+            .map_err(|e| FeedbackStorageError::Pinning(e.to_string()))?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(FeedbackStorageError::Pinning(format!(
+                "pinning service answered {status}"
+            )));
+        }
+        let pinned: PinResponse = response
+            .json()
+            .await
+            .map_err(|e| FeedbackStorageError::Pinning(e.to_string()))?;
+        Ok((format!("ipfs://{}", pinned.cid), hash))
+    }
+}
+
+/// Local store served at `GET /feedback-files/{id}`, when configured.
+pub static LOCAL_FEEDBACK_STORAGE: Lazy<Option<LocalFeedbackStorage>> =
+    Lazy::new(LocalFeedbackStorage::from_env);
+
+/// Store used by `POST /feedback`: the pinning service when it has a token, otherwise
+/// the local store. `None` when neither is configured.
+pub static FEEDBACK_STORAGE: Lazy<Option<Box<dyn FeedbackStorage>>> = Lazy::new(|| {
+    if let Some(pinning) = IpfsPinningStorage::from_env() {
+        info!(endpoint = %pinning.endpoint, "Pinning feedback files to IPFS");
+        return Some(Box::new(pinning));
+    }
+    let local = LOCAL_FEEDBACK_STORAGE.clone()?;
+    info!(dir = %local.dir.display(), "Storing feedback files on disk");
+    Some(Box::new(local))
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::erc8004::FeedbackFileEntry;
+    use wiremock::matchers::{header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn feedback_file() -> FeedbackFile {
+        FeedbackFile {
+            type_: "https://eips.ethereum.org/EIPS/eip-8004#feedback-v1".to_string(),
+            feedback: vec![FeedbackFileEntry {
+                timestamp: 1_760_000_000,
+                result: "success".to_string(),
+                value: 87,
+                value_decimals: 0,
+                payment_info: None,
+                interactions: vec!["GET /weather".to_string()],
+            }],
+        }
+    }
+
+    /// Bytes of the `file` part of a multipart body.
+    fn file_part(body: &[u8]) -> &[u8] {
+        let start = body
+            .windows(4)
+            .position(|w| w == b"\r\n\r\n")
+            .expect("part headers")
+            + 4;
+        let end = start
+            + body[start..]
+                .windows(4)
+                .position(|w| w == b"\r\n--")
+                .expect("closing boundary");
+        &body[start..end]
+    }
+
+    #[tokio::test]
+    async fn test_pinned_file_hash_matches_uploaded_bytes() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/pinning/pinFileToIPFS"))
+            .and(header("authorization", "Bearer pin-token"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "IpfsHash": "bafkreidvbhs33ighmljlvr7zbv2ywwzcmp5adtf4kqvlly67cy56bdtmve",
+                "PinSize": 120,
+                "Timestamp": "2026-10-17T00:00:00Z"
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let endpoint = Url::parse(&format!("{}/pinning/pinFileToIPFS", server.uri())).unwrap();
+        let storage = IpfsPinningStorage::new(endpoint, "pin-token");
+
+        let (uri, hash) = storage.store(&feedback_file()).await.unwrap();
+
+        assert_eq!(
+            uri,
+            "ipfs://bafkreidvbhs33ighmljlvr7zbv2ywwzcmp5adtf4kqvlly67cy56bdtmve"
+        );
+        let requests = server.received_requests().await.unwrap();
+        let uploaded = file_part(&requests[0].body);
+        assert_eq!(hash, keccak256(uploaded));
+        let parsed: FeedbackFile = serde_json::from_slice(uploaded).unwrap();
+        assert_eq!(parsed.feedback[0].value, 87);
+    }
+
+    #[tokio::test]
+    async fn test_web3_storage_cid_is_accepted() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(
+                serde_json::json!({ "cid": "bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi" }),
+            ))
+            .mount(&server)
+            .await;
+        let endpoint = Url::parse(&format!("{}/upload", server.uri())).unwrap();
+
+        let (uri, _) = IpfsPinningStorage::new(endpoint, "token")
+            .store(&feedback_file())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            uri,
+            "ipfs://bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_pinning_failure_is_reported() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(401))
+            .mount(&server)
+            .await;
+        let endpoint = Url::parse(&format!("{}/pinning/pinFileToIPFS", server.uri())).unwrap();
+
+        let result = IpfsPinningStorage::new(endpoint, "expired")
+            .store(&feedback_file())
+            .await;
+
+        assert!(
+            matches!(&result, Err(FeedbackStorageError::Pinning(e)) if e.contains("401")),
+            "{result:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_local_file_hash_matches_stored_bytes() {
+        let dir =
+            std::env::temp_dir().join(format!("x402-feedback-files-{:x}", rand::random::<u64>()));
+        let storage = LocalFeedbackStorage::new(
+            &dir,
+            Url::parse("https://facilitator.example.com/").unwrap(),
+        );
+
+        let (uri, hash) = storage.store(&feedback_file()).await.unwrap();
+
+        let id = alloy::hex::encode(hash);
+        assert_eq!(
+            uri,
+            format!("https://facilitator.example.com/feedback-files/{id}")
+        );
+        let stored = storage.load(&id).await.unwrap();
+        assert_eq!(keccak256(&stored), hash);
+        assert!(storage.load("../secret").await.is_none());
+        assert!(storage.load(&"0".repeat(64)).await.is_none());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
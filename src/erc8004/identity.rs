@@ -0,0 +1,302 @@
+//! Agent registration paid for by the facilitator.
+//!
+//! `POST /identity/register` mints an ERC-8004 identity with the Identity Registry's
+//! `register(agentURI, metadata)`, sent by the facilitator signer, and reads the new
+//! `agentId` from the `Registered` event. When the request names an `owner`, a second
+//! transaction transfers the agent NFT to it:
+//!
+//! ```text
+//! register(agentURI, metadata) ─▶ Registered(agentId, owner = facilitator) ─▶ transferFrom(facilitator, owner, agentId)
+//! ```
+//!
+//! Registration spends facilitator gas, so the endpoint is only mounted when API keys
+//! are configured, and every key has a daily registration quota. Requests refused
+//! before a transaction is sent do not count against it.
+//!
+//! # Environment
+//!
+//! - `IDENTITY_REGISTER_API_KEYS` - `X-API-Key` values allowed to register agents (endpoint disabled when unset)
+//! - `IDENTITY_REGISTER_DAILY_QUOTA` - Registrations per key and UTC day (default: 10)
+
+use alloy::primitives::{Address, Bytes, B256, U256};
+use alloy::providers::Provider;
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tracing::info;
+
+use super::{IIdentityRegistry, MetadataEntry, RegisterAgentRequest};
+
+/// How long to wait for a registration or transfer receipt.
+const CONFIRMATION_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Registrations per API key and day when `IDENTITY_REGISTER_DAILY_QUOTA` is unset.
+pub const DEFAULT_DAILY_QUOTA: u32 = 10;
+
+/// Errors from registering an agent.
+#[derive(Debug, thiserror::Error)]
+pub enum RegistrationError {
+    /// The `register` transaction could not be sent or confirmed
+    #[error("register transaction failed: {0}")]
+    Register(String),
+
+    /// The `register` transaction reverted
+    #[error("register transaction {0} reverted")]
+    Reverted(B256),
+
+    /// The `register` transaction was mined without a `Registered` event
+    #[error("register transaction {0} emitted no Registered event")]
+    MissingEvent(B256),
+
+    /// The agent was registered but could not be handed to the requested owner
+    #[error("agent {agent_id} was registered in {transaction} but the transfer to {owner} failed: {reason}")]
+    Transfer {
+        agent_id: u64,
+        transaction: B256,
+        owner: Address,
+        reason: String,
+    },
+}
+
+/// A registered agent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AgentRegistration {
+    pub agent_id: u64,
+    /// Owner of the agent NFT: the requested owner, or else the facilitator signer
+    pub owner: Address,
+    /// `register` transaction
+    pub transaction: B256,
+    /// Transfer to the requested owner
+    pub transfer_transaction: Option<B256>,
+}
+
+/// Register the agent described by `request` in the Identity Registry at
+/// `identity_registry`, then transfer it to `request.owner` if set.
+pub async fn register_agent<P: Provider>(
+    provider: P,
+    identity_registry: Address,
+    request: &RegisterAgentRequest,
+) -> Result<AgentRegistration, RegistrationError> {
+    let registry = IIdentityRegistry::new(identity_registry, provider);
+    let metadata = request
+        .metadata
+        .iter()
+        .map(|entry| MetadataEntry {
+            metadataKey: entry.key.clone(),
+            metadataValue: Bytes::copy_from_slice(entry.value.as_bytes()),
+        })
+        .collect();
+
+    let receipt = registry
+        .register_0(request.agent_uri.clone(), metadata)
+        .send()
+        .await
+        .map_err(|e| RegistrationError::Register(e.to_string()))?
+        .with_timeout(Some(CONFIRMATION_TIMEOUT))
+        .get_receipt()
+        .await
+        .map_err(|e| RegistrationError::Register(e.to_string()))?;
+    let transaction = receipt.transaction_hash;
+    if !receipt.status() {
+        return Err(RegistrationError::Reverted(transaction));
+    }
+    let registered = receipt
+        .inner
+        .logs()
+        .iter()
+        .filter(|log| log.address() == identity_registry)
+        .find_map(|log| log.log_decode::<IIdentityRegistry::Registered>().ok())
+        .ok_or(RegistrationError::MissingEvent(transaction))?
+        .inner
+        .data;
+    let agent_id = u64::try_from(registered.agentId)
+        .map_err(|_| RegistrationError::MissingEvent(transaction))?;
+    info!(agent_id, tx = %transaction, owner = %registered.owner, "Registered ERC-8004 agent");
+
+    let Some(owner) = request.owner.filter(|owner| *owner != registered.owner) else {
+        return Ok(AgentRegistration {
+            agent_id,
+            owner: registered.owner,
+            transaction,
+            transfer_transaction: None,
+        });
+    };
+
+    let transfer_failed = |reason: String| RegistrationError::Transfer {
+        agent_id,
+        transaction,
+        owner,
+        reason,
+    };
+    // Sent by the signer the registry minted the agent to
+    let receipt = registry
+        .transferFrom(registered.owner, owner, U256::from(agent_id))
+        .from(registered.owner)
+        .send()
+        .await
+        .map_err(|e| transfer_failed(e.to_string()))?
+        .with_timeout(Some(CONFIRMATION_TIMEOUT))
+        .get_receipt()
+        .await
+        .map_err(|e| transfer_failed(e.to_string()))?;
+    if !receipt.status() {
+        return Err(transfer_failed(format!(
+            "transaction {} reverted",
+            receipt.transaction_hash
+        )));
+    }
+    info!(agent_id, tx = %receipt.transaction_hash, owner = %owner, "Transferred ERC-8004 agent");
+
+    Ok(AgentRegistration {
+        agent_id,
+        owner,
+        transaction,
+        transfer_transaction: Some(receipt.transaction_hash),
+    })
+}
+
+/// Why a registration request was refused before reaching the chain.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum RegistrarError {
+    #[error("Missing or invalid API key")]
+    Unauthorized,
+
+    #[error("Daily registration quota of {0} exhausted")]
+    QuotaExceeded(u32),
+}
+
+/// Registrations made per API key on the current UTC day.
+#[derive(Debug)]
+pub struct RegistrationQuota {
+    daily_limit: u32,
+    /// Key to (day, registrations that day)
+    used: Mutex<HashMap<String, (u64, u32)>>,
+}
+
+impl RegistrationQuota {
+    pub fn new(daily_limit: u32) -> Self {
+        Self {
+            daily_limit,
+            used: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Count a registration by `key` on `day`, unless its quota for that day is used up.
+    pub fn try_acquire(&self, key: &str, day: u64) -> Result<(), RegistrarError> {
+        let mut used = self.used.lock().unwrap();
+        let entry = used.entry(key.to_string()).or_insert((day, 0));
+        if entry.0 != day {
+            *entry = (day, 0);
+        }
+        if entry.1 >= self.daily_limit {
+            return Err(RegistrarError::QuotaExceeded(self.daily_limit));
+        }
+        entry.1 += 1;
+        Ok(())
+    }
+
+    /// Registrations `key` has left on `day`.
+    pub fn remaining(&self, key: &str, day: u64) -> u32 {
+        let used = self.used.lock().unwrap();
+        match used.get(key) {
+            Some((used_day, count)) if *used_day == day => self.daily_limit.saturating_sub(*count),
+            _ => self.daily_limit,
+        }
+    }
+}
+
+/// API keys allowed to register agents and their daily quota.
+#[derive(Debug)]
+pub struct IdentityRegistrar {
+    api_keys: HashSet<String>,
+    quota: RegistrationQuota,
+}
+
+impl IdentityRegistrar {
+    pub fn new(api_keys: impl IntoIterator<Item = String>, daily_quota: u32) -> Self {
+        Self {
+            api_keys: api_keys.into_iter().collect(),
+            quota: RegistrationQuota::new(daily_quota),
+        }
+    }
+
+    /// Registrar for `IDENTITY_REGISTER_API_KEYS`, or `None` when no key is set.
+    pub fn from_env() -> Option<Self> {
+        let api_keys: HashSet<String> = crate::env_registry::var("IDENTITY_REGISTER_API_KEYS")?
+            .split(',')
+            .map(|k| k.trim().to_string())
+            .filter(|k| !k.is_empty())
+            .collect();
+        if api_keys.is_empty() {
+            return None;
+        }
+        let daily_quota = crate::env_registry::parse("IDENTITY_REGISTER_DAILY_QUOTA")
+            .unwrap_or(DEFAULT_DAILY_QUOTA);
+        info!(
+            keys = api_keys.len(),
+            daily_quota, "Agent registration endpoint enabled"
+        );
+        Some(Self::new(api_keys, daily_quota))
+    }
+
+    /// The configured key matching `api_key`.
+    pub fn authenticate<'a>(&self, api_key: Option<&'a str>) -> Result<&'a str, RegistrarError> {
+        api_key
+            .filter(|key| self.api_keys.contains(*key))
+            .ok_or(RegistrarError::Unauthorized)
+    }
+
+    /// Count a registration by `api_key` against its quota for today.
+    pub fn acquire(&self, api_key: &str) -> Result<(), RegistrarError> {
+        self.quota.try_acquire(api_key, today())
+    }
+
+    pub fn quota(&self) -> &RegistrationQuota {
+        &self.quota
+    }
+}
+
+/// Days since the Unix epoch, in UTC.
+pub fn today() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+        / 86_400
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unknown_keys_are_rejected() {
+        let registrar = IdentityRegistrar::new(["key-a".to_string()], 1);
+
+        assert_eq!(
+            registrar.authenticate(None),
+            Err(RegistrarError::Unauthorized)
+        );
+        assert_eq!(
+            registrar.authenticate(Some("key-b")),
+            Err(RegistrarError::Unauthorized)
+        );
+        assert_eq!(registrar.authenticate(Some("key-a")), Ok("key-a"));
+    }
+
+    #[test]
+    fn test_quota_is_per_key_and_day() {
+        let quota = RegistrationQuota::new(2);
+
+        assert_eq!(quota.try_acquire("key-a", 7), Ok(()));
+        assert_eq!(quota.try_acquire("key-a", 7), Ok(()));
+        assert_eq!(
+            quota.try_acquire("key-a", 7),
+            Err(RegistrarError::QuotaExceeded(2))
+        );
+        assert_eq!(quota.remaining("key-a", 7), 0);
+        assert_eq!(quota.try_acquire("key-b", 7), Ok(()));
+        assert_eq!(quota.try_acquire("key-a", 8), Ok(()));
+        assert_eq!(quota.remaining("key-a", 8), 1);
+    }
+}
@@ -0,0 +1,792 @@
+//! Local index of ERC-8004 feedback, built from Reputation Registry events.
+//!
+//! `readAllFeedback` returns every entry of an agent in one call, which gets slow and
+//! expensive for popular agents. The indexer instead polls `eth_getLogs` in block ranges
+//! for `NewFeedback`, `FeedbackRevoked` and `ResponseAppended` on each configured network
+//! and keeps a per-agent feedback list in a [`FeedbackIndex`]. Once a network's index has
+//! caught up with the chain head, `GET /reputation` serves feedback entries from it and
+//! reports `"source": "cache"`; until then it reads the registry (`"source": "chain"`).
+//!
+//! # Reorgs
+//!
+//! Every poll rolls back what the last `ERC8004_INDEXER_REORG_DEPTH` blocks contributed
+//! and scans them again together with the new blocks. A reorg shallower than that depth
+//! therefore replaces the orphaned events with the canonical ones.
+//!
+//! # Environment
+//!
+//! - `ERC8004_INDEXER_NETWORKS` - Networks to index, each with an optional start block
+//!   (the registry deployment), e.g. `ethereum:24339871,ethereum-sepolia` (disabled when
+//!   unset)
+//! - `ERC8004_INDEXER_INTERVAL` - Seconds between polls (default: 12)
+//! - `ERC8004_INDEXER_BLOCK_RANGE` - Most blocks per `eth_getLogs` request (default: 2000)
+//! - `ERC8004_INDEXER_REORG_DEPTH` - Blocks re-scanned on every poll (default: 12)
+//! - `ERC8004_INDEXER_STATE_FILE` - File the index is saved to after every poll and
+//!   resumed from on startup (in-memory when unset)
+
+use alloy::primitives::{Address, U256};
+use alloy::providers::Provider;
+use alloy::rpc::types::{Filter, Log};
+use alloy::sol_types::{SolEvent, SolEventInterface};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::RwLock;
+use std::time::Duration;
+use tracing::{debug, info, warn};
+
+use super::{
+    FeedbackEntry, GetReputationRequest, IReputationRegistry,
+    IReputationRegistry::IReputationRegistryEvents,
+};
+use crate::network::Network;
+use crate::types::{EvmAddress, MixedAddress};
+
+/// Default seconds between polls.
+pub const DEFAULT_POLL_INTERVAL_SECS: u64 = 12;
+
+/// Default most blocks per `eth_getLogs` request.
+pub const DEFAULT_BLOCK_RANGE: u64 = 2_000;
+
+/// Default blocks re-scanned on every poll.
+pub const DEFAULT_REORG_DEPTH: u64 = 12;
+
+/// Errors from the feedback indexer.
+#[derive(Debug, thiserror::Error)]
+pub enum IndexerError {
+    /// `ERC8004_INDEXER_NETWORKS` or another setting is invalid
+    #[error("Invalid indexer config: {0}")]
+    Config(String),
+
+    /// Reading the chain head or the registry logs failed
+    #[error("RPC request failed: {0}")]
+    Rpc(String),
+
+    /// The state file could not be read or written
+    #[error("Feedback index state file: {0}")]
+    State(String),
+}
+
+// ============================================================================
+// Configuration
+// ============================================================================
+
+/// Settings of the indexer task.
+#[derive(Debug, Clone)]
+pub struct IndexerConfig {
+    /// Networks to index, with the block to start from
+    pub networks: HashMap<Network, u64>,
+    /// Time between polls
+    pub poll_interval: Duration,
+    /// Most blocks per `eth_getLogs` request
+    pub block_range: u64,
+    /// Blocks re-scanned on every poll
+    pub reorg_depth: u64,
+    /// File the index is saved to and resumed from
+    pub state_file: Option<PathBuf>,
+}
+
+impl Default for IndexerConfig {
+    fn default() -> Self {
+        Self {
+            networks: HashMap::new(),
+            poll_interval: Duration::from_secs(DEFAULT_POLL_INTERVAL_SECS),
+            block_range: DEFAULT_BLOCK_RANGE,
+            reorg_depth: DEFAULT_REORG_DEPTH,
+            state_file: None,
+        }
+    }
+}
+
+impl IndexerConfig {
+    /// Read the settings from the environment, falling back to the defaults.
+    pub fn from_env() -> Result<Self, IndexerError> {
+        let defaults = Self::default();
+        Ok(Self {
+            networks: parse_networks(
+                &crate::env_registry::var("ERC8004_INDEXER_NETWORKS").unwrap_or_default(),
+            )?,
+            poll_interval: crate::env_registry::parse("ERC8004_INDEXER_INTERVAL")
+                .map(Duration::from_secs)
+                .unwrap_or(defaults.poll_interval),
+            block_range: crate::env_registry::parse::<u64>("ERC8004_INDEXER_BLOCK_RANGE")
+                .unwrap_or(defaults.block_range)
+                .max(1),
+            reorg_depth: crate::env_registry::parse("ERC8004_INDEXER_REORG_DEPTH")
+                .unwrap_or(defaults.reorg_depth),
+            state_file: crate::env_registry::var("ERC8004_INDEXER_STATE_FILE").map(PathBuf::from),
+        })
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.networks.is_empty()
+    }
+}
+
+/// Parse a `network[:start_block]` comma-separated list.
+fn parse_networks(raw: &str) -> Result<HashMap<Network, u64>, IndexerError> {
+    let mut networks = HashMap::new();
+    for entry in raw.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+        let (name, start_block) = match entry.split_once(':') {
+            Some((name, block)) => {
+                let block = block.trim().parse::<u64>().map_err(|_| {
+                    IndexerError::Config(format!("invalid start block in '{}'", entry))
+                })?;
+                (name.trim(), block)
+            }
+            None => (entry, 0),
+        };
+        let network = Network::from_str(name)
+            .map_err(|_| IndexerError::Config(format!("unknown network '{}'", name)))?;
+        if !super::is_erc8004_supported(&network) {
+            return Err(IndexerError::Config(format!(
+                "ERC-8004 is not supported on network {}",
+                network
+            )));
+        }
+        networks.insert(network, start_block);
+    }
+    Ok(networks)
+}
+
+// ============================================================================
+// Index
+// ============================================================================
+
+/// A feedback entry as indexed, with the blocks of the events that shaped it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexedFeedback {
+    pub client: Address,
+    pub feedback_index: u64,
+    pub value: i128,
+    pub value_decimals: u8,
+    pub tag1: String,
+    pub tag2: String,
+    /// Block of the `NewFeedback` event
+    pub block: u64,
+    /// Block of the `FeedbackRevoked` event, if revoked
+    pub revoked_at: Option<u64>,
+    /// Responses appended to the entry, in event order
+    pub responses: Vec<IndexedResponse>,
+}
+
+/// A response appended to a feedback entry (`ResponseAppended`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexedResponse {
+    pub responder: Address,
+    pub response_uri: String,
+    pub block: u64,
+}
+
+/// Indexed feedback of one network's Reputation Registry.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkIndex {
+    /// Reputation Registry the events were read from
+    pub registry: Address,
+    /// Last block whose events have been applied, `None` before the first scan
+    pub last_block: Option<u64>,
+    /// Whether the index has caught up with the chain head since it was loaded
+    #[serde(skip)]
+    pub warm: bool,
+    /// Feedback entries by agent, in event order
+    pub agents: HashMap<u64, Vec<IndexedFeedback>>,
+}
+
+impl NetworkIndex {
+    fn new(registry: Address) -> Self {
+        Self {
+            registry,
+            ..Self::default()
+        }
+    }
+
+    /// Forget everything that blocks from `from_block` on contributed.
+    fn rollback(&mut self, from_block: u64) {
+        for entries in self.agents.values_mut() {
+            entries.retain(|entry| entry.block < from_block);
+            for entry in entries.iter_mut() {
+                if entry.revoked_at.is_some_and(|block| block >= from_block) {
+                    entry.revoked_at = None;
+                }
+                entry
+                    .responses
+                    .retain(|response| response.block < from_block);
+            }
+        }
+        self.agents.retain(|_, entries| !entries.is_empty());
+    }
+
+    /// Apply one registry event. Logs without a block number or flagged as removed,
+    /// and events of other contracts, are ignored.
+    fn apply(&mut self, log: &Log) {
+        let Some(block) = log.block_number.filter(|_| !log.removed) else {
+            return;
+        };
+        if log.address() != self.registry {
+            return;
+        }
+        let event = match IReputationRegistryEvents::decode_log(&log.inner) {
+            Ok(event) => event.data,
+            Err(e) => {
+                debug!(block, error = %e, "Skipping undecodable Reputation Registry log");
+                return;
+            }
+        };
+        match event {
+            IReputationRegistryEvents::NewFeedback(event) => {
+                let Some(agent_id) = agent_id(event.agentId) else {
+                    return;
+                };
+                let entries = self.agents.entry(agent_id).or_default();
+                if find(entries, event.clientAddress, event.feedbackIndex).is_none() {
+                    entries.push(IndexedFeedback {
+                        client: event.clientAddress,
+                        feedback_index: event.feedbackIndex,
+                        value: event.value,
+                        value_decimals: event.valueDecimals,
+                        tag1: event.tag1,
+                        tag2: event.tag2,
+                        block,
+                        revoked_at: None,
+                        responses: Vec::new(),
+                    });
+                }
+            }
+            IReputationRegistryEvents::FeedbackRevoked(event) => {
+                if let Some(entry) =
+                    self.entry_mut(event.agentId, event.clientAddress, event.feedbackIndex)
+                {
+                    entry.revoked_at.get_or_insert(block);
+                }
+            }
+            IReputationRegistryEvents::ResponseAppended(event) => {
+                if let Some(entry) =
+                    self.entry_mut(event.agentId, event.clientAddress, event.feedbackIndex)
+                {
+                    entry.responses.push(IndexedResponse {
+                        responder: event.responder,
+                        response_uri: event.responseURI,
+                        block,
+                    });
+                }
+            }
+        }
+    }
+
+    fn entry_mut(
+        &mut self,
+        agent_id: U256,
+        client: Address,
+        feedback_index: u64,
+    ) -> Option<&mut IndexedFeedback> {
+        let entries = self.agents.get_mut(&self::agent_id(agent_id)?)?;
+        let position = find(entries, client, feedback_index)?;
+        Some(&mut entries[position])
+    }
+
+    /// Unrevoked entries of `agent_id` matching `filter`, ordered like `readAllFeedback`:
+    /// by client in order of their first feedback, then by feedback index.
+    fn feedback(&self, agent_id: u64, filter: &GetReputationRequest) -> Vec<FeedbackEntry> {
+        let Some(entries) = self.agents.get(&agent_id) else {
+            return Vec::new();
+        };
+        let mut clients: Vec<Address> = Vec::new();
+        for entry in entries {
+            if !clients.contains(&entry.client) {
+                clients.push(entry.client);
+            }
+        }
+        let mut matching: Vec<&IndexedFeedback> = entries
+            .iter()
+            .filter(|entry| entry.revoked_at.is_none())
+            .filter(|entry| {
+                filter.client_addresses.is_empty()
+                    || filter
+                        .client_addresses
+                        .contains(&MixedAddress::Evm(EvmAddress(entry.client)))
+            })
+            .filter(|entry| filter.tag1.is_empty() || entry.tag1 == filter.tag1)
+            .filter(|entry| filter.tag2.is_empty() || entry.tag2 == filter.tag2)
+            .collect();
+        matching.sort_by_key(|entry| {
+            let client = clients.iter().position(|client| *client == entry.client);
+            (client, entry.feedback_index)
+        });
+        matching
+            .into_iter()
+            .map(|entry| FeedbackEntry {
+                client: MixedAddress::Evm(EvmAddress(entry.client)),
+                feedback_index: entry.feedback_index,
+                value: entry.value,
+                value_decimals: entry.value_decimals,
+                tag1: entry.tag1.clone(),
+                tag2: entry.tag2.clone(),
+                is_revoked: false,
+            })
+            .collect()
+    }
+}
+
+fn agent_id(agent_id: U256) -> Option<u64> {
+    agent_id.try_into().ok()
+}
+
+fn find(entries: &[IndexedFeedback], client: Address, feedback_index: u64) -> Option<usize> {
+    entries
+        .iter()
+        .position(|entry| entry.client == client && entry.feedback_index == feedback_index)
+}
+
+/// Indexed feedback of every indexed network.
+#[derive(Debug, Default)]
+pub struct FeedbackIndex {
+    networks: RwLock<HashMap<Network, NetworkIndex>>,
+}
+
+impl FeedbackIndex {
+    /// Whether feedback on `network` can be served from the index.
+    pub fn is_warm(&self, network: Network) -> bool {
+        let networks = self.networks.read().unwrap();
+        networks.get(&network).is_some_and(|index| index.warm)
+    }
+
+    /// Unrevoked feedback of `agent_id` on `registry` matching `filter`, or `None` while
+    /// that registry's index is not warm.
+    pub fn feedback(
+        &self,
+        network: Network,
+        registry: Address,
+        agent_id: u64,
+        filter: &GetReputationRequest,
+    ) -> Option<Vec<FeedbackEntry>> {
+        let networks = self.networks.read().unwrap();
+        let index = networks
+            .get(&network)
+            .filter(|index| index.warm && index.registry == registry)?;
+        Some(index.feedback(agent_id, filter))
+    }
+
+    /// Last block applied for `network`.
+    pub fn last_block(&self, network: Network) -> Option<u64> {
+        let networks = self.networks.read().unwrap();
+        networks.get(&network).and_then(|index| index.last_block)
+    }
+
+    /// Replace what blocks `from_block` on contributed with `logs`, read from
+    /// `from_block` to `to_block`, in one step.
+    ///
+    /// Starts the network's index over if it was built from another registry.
+    pub fn apply_logs(
+        &self,
+        network: Network,
+        registry: Address,
+        from_block: u64,
+        to_block: u64,
+        logs: &[Log],
+    ) {
+        let mut networks = self.networks.write().unwrap();
+        let index = networks
+            .entry(network)
+            .or_insert_with(|| NetworkIndex::new(registry));
+        if index.registry != registry {
+            *index = NetworkIndex::new(registry);
+        }
+        index.rollback(from_block);
+        for log in logs {
+            index.apply(log);
+        }
+        index.last_block = Some(to_block);
+    }
+
+    /// Mark `network` as caught up with the chain head.
+    pub fn mark_warm(&self, network: Network) {
+        let mut networks = self.networks.write().unwrap();
+        if let Some(index) = networks.get_mut(&network) {
+            if !index.warm {
+                info!(network = %network, "ERC-8004 feedback index is warm");
+            }
+            index.warm = true;
+        }
+    }
+
+    /// Save the index to `path`, replacing the file atomically.
+    pub async fn save(&self, path: &Path) -> Result<(), IndexerError> {
+        let json = {
+            let networks = self.networks.read().unwrap();
+            serde_json::to_vec(&*networks).map_err(|e| IndexerError::State(e.to_string()))?
+        };
+        // Write then rename so a crash never leaves a truncated file
+        let tmp = path.with_extension("tmp");
+        tokio::fs::write(&tmp, json)
+            .await
+            .map_err(|e| IndexerError::State(format!("{}: {}", tmp.display(), e)))?;
+        tokio::fs::rename(&tmp, path)
+            .await
+            .map_err(|e| IndexerError::State(format!("{}: {}", path.display(), e)))
+    }
+
+    /// Resume from an index saved to `path`; a missing file leaves the index empty.
+    ///
+    /// Loaded networks are not warm until the indexer catches up with the chain again.
+    pub async fn load(&self, path: &Path) -> Result<(), IndexerError> {
+        let bytes = match tokio::fs::read(path).await {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(IndexerError::State(format!("{}: {}", path.display(), e))),
+        };
+        let loaded: HashMap<Network, NetworkIndex> = serde_json::from_slice(&bytes)
+            .map_err(|e| IndexerError::State(format!("{}: {}", path.display(), e)))?;
+        *self.networks.write().unwrap() = loaded;
+        Ok(())
+    }
+}
+
+/// Index shared by the indexer task and the `GET /reputation` handlers.
+pub static FEEDBACK_INDEX: Lazy<FeedbackIndex> = Lazy::new(FeedbackIndex::default);
+
+// ============================================================================
+// Indexer
+// ============================================================================
+
+/// Keeps one network's part of a [`FeedbackIndex`] in sync with its Reputation Registry.
+#[derive(Debug, Clone)]
+pub struct FeedbackIndexer<P> {
+    provider: P,
+    network: Network,
+    registry: Address,
+    start_block: u64,
+    block_range: u64,
+    reorg_depth: u64,
+}
+
+impl<P: Provider> FeedbackIndexer<P> {
+    /// Index the events of `registry` on `network` from `start_block` on.
+    pub fn new(provider: P, network: Network, registry: Address, start_block: u64) -> Self {
+        Self {
+            provider,
+            network,
+            registry,
+            start_block,
+            block_range: DEFAULT_BLOCK_RANGE,
+            reorg_depth: DEFAULT_REORG_DEPTH,
+        }
+    }
+
+    /// Request at most `block_range` blocks of logs at once (at least one).
+    pub fn with_block_range(mut self, block_range: u64) -> Self {
+        self.block_range = block_range.max(1);
+        self
+    }
+
+    /// Re-scan the last `reorg_depth` blocks on every sync.
+    pub fn with_reorg_depth(mut self, reorg_depth: u64) -> Self {
+        self.reorg_depth = reorg_depth;
+        self
+    }
+
+    pub fn network(&self) -> Network {
+        self.network
+    }
+
+    /// Apply the events up to the current chain head and mark the network warm.
+    ///
+    /// Resumes `reorg_depth` blocks before the last block applied. Returns the head.
+    pub async fn sync(&self, index: &FeedbackIndex) -> Result<u64, IndexerError> {
+        let head = self
+            .provider
+            .get_block_number()
+            .await
+            .map_err(|e| IndexerError::Rpc(e.to_string()))?;
+        let mut from = match index.last_block(self.network) {
+            Some(last) => (last + 1).saturating_sub(self.reorg_depth),
+            None => self.start_block,
+        }
+        .max(self.start_block);
+
+        while from <= head {
+            let to = head.min(from.saturating_add(self.block_range - 1));
+            let filter = Filter::new()
+                .address(self.registry)
+                .event_signature(vec![
+                    IReputationRegistry::NewFeedback::SIGNATURE_HASH,
+                    IReputationRegistry::FeedbackRevoked::SIGNATURE_HASH,
+                    IReputationRegistry::ResponseAppended::SIGNATURE_HASH,
+                ])
+                .from_block(from)
+                .to_block(to);
+            let logs = self
+                .provider
+                .get_logs(&filter)
+                .await
+                .map_err(|e| IndexerError::Rpc(e.to_string()))?;
+            debug!(network = %self.network, from, to, logs = logs.len(), "Indexed feedback events");
+            index.apply_logs(self.network, self.registry, from, to, &logs);
+            from = to + 1;
+        }
+        index.mark_warm(self.network);
+        Ok(head)
+    }
+}
+
+/// Start the background task syncing `indexers` into [`FEEDBACK_INDEX`] every
+/// `config.poll_interval`, resuming from and saving to `config.state_file` when set.
+pub fn start_indexer_task<P>(
+    indexers: Vec<FeedbackIndexer<P>>,
+    config: IndexerConfig,
+) -> tokio::task::JoinHandle<()>
+where
+    P: Provider + Send + Sync + 'static,
+{
+    info!(
+        networks = indexers.len(),
+        interval_secs = config.poll_interval.as_secs(),
+        "Starting ERC-8004 feedback indexer background task"
+    );
+    tokio::spawn(async move {
+        let index = &*FEEDBACK_INDEX;
+        if let Some(path) = &config.state_file {
+            if let Err(e) = index.load(path).await {
+                warn!(error = %e, "Failed to resume feedback index, starting over");
+            }
+        }
+        let mut interval = tokio::time::interval(config.poll_interval.max(Duration::from_secs(1)));
+        loop {
+            interval.tick().await;
+            for indexer in &indexers {
+                if let Err(e) = indexer.sync(index).await {
+                    warn!(network = %indexer.network(), error = %e, "Feedback index sync failed");
+                }
+            }
+            if let Some(path) = &config.state_file {
+                if let Err(e) = index.save(path).await {
+                    warn!(error = %e, "Failed to save feedback index");
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::B256;
+
+    const REGISTRY: Address = Address::repeat_byte(0x80);
+    const ALICE: Address = Address::repeat_byte(0xaa);
+    const BOB: Address = Address::repeat_byte(0xbb);
+
+    fn log(event: &impl SolEvent, block: u64) -> Log {
+        Log {
+            inner: alloy::primitives::Log {
+                address: REGISTRY,
+                data: event.encode_log_data(),
+            },
+            block_number: Some(block),
+            ..Log::default()
+        }
+    }
+
+    fn new_feedback(
+        agent_id: u64,
+        client: Address,
+        index: u64,
+        value: i128,
+        tag1: &str,
+    ) -> IReputationRegistry::NewFeedback {
+        IReputationRegistry::NewFeedback {
+            agentId: U256::from(agent_id),
+            clientAddress: client,
+            feedbackIndex: index,
+            value,
+            valueDecimals: 0,
+            indexedTag1: alloy::primitives::keccak256(tag1),
+            tag1: tag1.to_string(),
+            tag2: String::new(),
+            endpoint: String::new(),
+            feedbackURI: String::new(),
+            feedbackHash: B256::ZERO,
+        }
+    }
+
+    fn revoked(agent_id: u64, client: Address, index: u64) -> IReputationRegistry::FeedbackRevoked {
+        IReputationRegistry::FeedbackRevoked {
+            agentId: U256::from(agent_id),
+            clientAddress: client,
+            feedbackIndex: index,
+        }
+    }
+
+    fn indexes(entries: &[FeedbackEntry]) -> Vec<(MixedAddress, u64)> {
+        entries
+            .iter()
+            .map(|entry| (entry.client.clone(), entry.feedback_index))
+            .collect()
+    }
+
+    fn evm(address: Address) -> MixedAddress {
+        MixedAddress::Evm(EvmAddress(address))
+    }
+
+    /// Alice and Bob rate agent 7 twice each, Alice revokes her first entry and the
+    /// agent responds to Bob's second one.
+    fn stream() -> Vec<Log> {
+        vec![
+            log(&new_feedback(7, ALICE, 1, 100, "uptime"), 10),
+            log(&new_feedback(7, BOB, 1, 90, "uptime"), 11),
+            log(&new_feedback(9, BOB, 1, 10, "uptime"), 11),
+            log(&new_feedback(7, ALICE, 2, 80, "latency"), 12),
+            log(&new_feedback(7, BOB, 2, 70, "uptime"), 13),
+            log(&revoked(7, ALICE, 1), 14),
+            log(
+                &IReputationRegistry::ResponseAppended {
+                    agentId: U256::from(7),
+                    clientAddress: BOB,
+                    feedbackIndex: 2,
+                    responder: Address::repeat_byte(0x07),
+                    responseURI: "ipfs://response".to_string(),
+                    responseHash: B256::ZERO,
+                },
+                15,
+            ),
+        ]
+    }
+
+    fn warm_index(logs: &[Log]) -> FeedbackIndex {
+        let index = FeedbackIndex::default();
+        index.apply_logs(Network::EthereumSepolia, REGISTRY, 0, 20, logs);
+        index.mark_warm(Network::EthereumSepolia);
+        index
+    }
+
+    #[test]
+    fn test_replayed_stream_skips_revoked_feedback() {
+        let index = warm_index(&stream());
+        let all = GetReputationRequest::default();
+
+        let feedback = index
+            .feedback(Network::EthereumSepolia, REGISTRY, 7, &all)
+            .unwrap();
+        assert_eq!(
+            indexes(&feedback),
+            vec![(evm(ALICE), 2), (evm(BOB), 1), (evm(BOB), 2)]
+        );
+        assert!(feedback.iter().all(|entry| !entry.is_revoked));
+
+        let filter = GetReputationRequest {
+            client_addresses: vec![evm(BOB)],
+            tag1: "uptime".to_string(),
+            tag2: String::new(),
+        };
+        let feedback = index
+            .feedback(Network::EthereumSepolia, REGISTRY, 7, &filter)
+            .unwrap();
+        assert_eq!(indexes(&feedback), vec![(evm(BOB), 1), (evm(BOB), 2)]);
+
+        let networks = index.networks.read().unwrap();
+        let entries = &networks[&Network::EthereumSepolia].agents[&7];
+        assert_eq!(entries[0].revoked_at, Some(14));
+        assert_eq!(entries[3].responses[0].response_uri, "ipfs://response");
+    }
+
+    #[test]
+    fn test_cold_or_foreign_index_is_not_served() {
+        let index = FeedbackIndex::default();
+        let all = GetReputationRequest::default();
+        index.apply_logs(Network::EthereumSepolia, REGISTRY, 0, 20, &stream());
+        assert!(index
+            .feedback(Network::EthereumSepolia, REGISTRY, 7, &all)
+            .is_none());
+
+        index.mark_warm(Network::EthereumSepolia);
+        assert!(index
+            .feedback(Network::EthereumSepolia, Address::ZERO, 7, &all)
+            .is_none());
+        assert!(index
+            .feedback(Network::Ethereum, REGISTRY, 7, &all)
+            .is_none());
+        assert!(index
+            .feedback(Network::EthereumSepolia, REGISTRY, 8, &all)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_rescan_replaces_reorged_blocks() {
+        let index = warm_index(&stream());
+        let all = GetReputationRequest::default();
+
+        // Blocks 13 on were reorged: Bob's second entry and Alice's revocation are gone,
+        // Alice's first entry is revoked later and a new entry lands instead
+        let rescan = vec![
+            log(&new_feedback(7, BOB, 2, 60, "uptime"), 16),
+            log(&revoked(7, ALICE, 1), 17),
+        ];
+        index.apply_logs(Network::EthereumSepolia, REGISTRY, 13, 20, &rescan);
+
+        let feedback = index
+            .feedback(Network::EthereumSepolia, REGISTRY, 7, &all)
+            .unwrap();
+        assert_eq!(
+            indexes(&feedback),
+            vec![(evm(ALICE), 2), (evm(BOB), 1), (evm(BOB), 2)]
+        );
+        assert_eq!(feedback[2].value, 60);
+        let networks = index.networks.read().unwrap();
+        let entries = &networks[&Network::EthereumSepolia].agents[&7];
+        assert_eq!(entries[0].revoked_at, Some(17));
+        assert!(entries.iter().all(|entry| entry.responses.is_empty()));
+    }
+
+    #[test]
+    fn test_replaying_a_window_is_idempotent() {
+        let index = warm_index(&stream());
+        index.apply_logs(Network::EthereumSepolia, REGISTRY, 0, 20, &stream());
+        let networks = index.networks.read().unwrap();
+        let network = &networks[&Network::EthereumSepolia];
+        assert_eq!(network.agents[&7].len(), 4);
+        assert_eq!(network.agents[&9].len(), 1);
+        assert_eq!(network.last_block, Some(20));
+    }
+
+    #[tokio::test]
+    async fn test_state_file_round_trip() {
+        let path = std::env::temp_dir().join(format!("feedback-index-{}.json", std::process::id()));
+        let index = warm_index(&stream());
+        index.save(&path).await.unwrap();
+
+        let resumed = FeedbackIndex::default();
+        resumed.load(&path).await.unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(resumed.last_block(Network::EthereumSepolia), Some(20));
+        assert!(!resumed.is_warm(Network::EthereumSepolia));
+        resumed.mark_warm(Network::EthereumSepolia);
+        let all = GetReputationRequest::default();
+        let feedback = |index: &FeedbackIndex| {
+            indexes(
+                &index
+                    .feedback(Network::EthereumSepolia, REGISTRY, 7, &all)
+                    .unwrap(),
+            )
+        };
+        assert_eq!(feedback(&resumed), feedback(&index));
+
+        let missing = FeedbackIndex::default();
+        missing.load(&path).await.unwrap();
+        assert_eq!(missing.last_block(Network::EthereumSepolia), None);
+    }
+
+    #[test]
+    fn test_parse_networks() {
+        let networks = parse_networks("ethereum:24339871, ethereum-sepolia").unwrap();
+        assert_eq!(networks[&Network::Ethereum], 24339871);
+        assert_eq!(networks[&Network::EthereumSepolia], 0);
+        assert!(parse_networks("").unwrap().is_empty());
+        assert!(parse_networks("ethereum:latest").is_err());
+        assert!(parse_networks("base").is_err());
+    }
+}
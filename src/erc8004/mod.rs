@@ -17,6 +17,9 @@
 //! - Base Mainnet (when contracts are deployed)
 //! - Base Sepolia (when contracts are deployed)
 //!
+//! Any other EVM network, or a custom deployment on one of the above, can be
+//! configured at startup; see [`registry`].
+//!
 //! # x402 Integration
 //!
 //! The `8004-reputation` extension enables:
@@ -24,6 +27,8 @@
 //! 2. **Feedback Endpoint**: POST /feedback to submit reputation on-chain
 //! 3. **Reputation Query**: GET /reputation/:agentId to read reputation
 //! 4. **Identity Query**: GET /identity/:agentId to read agent info
+//! 5. **Agent Registration**: POST /identity/register to mint an agent identity
+//! 6. **Agent Wallet**: POST /identity/:agentId/wallet to set an agent's payment wallet
 //!
 //! # Reference
 //!
@@ -32,9 +37,25 @@
 //! - x402 Extension: `8004-reputation`
 
 mod abi;
+pub mod aggregate;
+pub mod batch;
+pub mod client;
+pub mod feedback_file;
+pub mod feedback_storage;
+pub mod identity;
+pub mod indexer;
+pub mod proof;
+pub mod read_cache;
+pub mod registration;
+pub mod registry;
+pub mod saga;
+pub mod validation;
+pub mod wallet;
 mod types;
 
 pub use abi::*;
+pub use client::{Erc8004Client, Erc8004Error};
+pub use registry::{install_registry, registry, Erc8004Registry, RegistryError};
 pub use types::*;
 
 use alloy::primitives::Address;
@@ -77,15 +98,9 @@ pub const BASE_MAINNET_CONTRACTS: Option<Erc8004Contracts> = None;
 // Reference implementation exists but not canonical addresses
 pub const BASE_SEPOLIA_CONTRACTS: Option<Erc8004Contracts> = None;
 
-/// Get ERC-8004 contract addresses for a network
+/// Get ERC-8004 contract addresses for a network from the installed [`Erc8004Registry`]
 pub fn get_contracts(network: &Network) -> Option<Erc8004Contracts> {
-    match network {
-        Network::Ethereum => Some(ETHEREUM_MAINNET_CONTRACTS),
-        Network::EthereumSepolia => Some(ETHEREUM_SEPOLIA_CONTRACTS),
-        Network::Base => BASE_MAINNET_CONTRACTS,
-        Network::BaseSepolia => BASE_SEPOLIA_CONTRACTS,
-        _ => None,
-    }
+    registry().get(network)
 }
 
 /// Check if ERC-8004 is supported on a network
@@ -95,20 +110,24 @@ pub fn is_erc8004_supported(network: &Network) -> bool {
 
 /// Get list of all networks with ERC-8004 support
 pub fn supported_networks() -> Vec<Network> {
-    vec![
-        Network::Ethereum,
-        Network::EthereumSepolia,
-        // Add more networks here as contracts are deployed
-    ]
+    registry().networks()
 }
 
 /// Get list of supported network names for API responses
-pub fn supported_network_names() -> Vec<&'static str> {
-    vec![
-        "ethereum",
-        "ethereum-sepolia",
-        // Add more as deployed
-    ]
+pub fn supported_network_names() -> Vec<String> {
+    supported_networks()
+        .iter()
+        .map(Network::to_string)
+        .collect()
+}
+
+/// Names of the networks with a deployed Validation Registry
+pub fn validation_network_names() -> Vec<String> {
+    supported_networks()
+        .into_iter()
+        .filter(|network| get_contracts(network).is_some_and(|c| c.validation_registry.is_some()))
+        .map(|network| network.to_string())
+        .collect()
 }
 
 // ============================================================================
@@ -116,7 +135,6 @@ pub fn supported_network_names() -> Vec<&'static str> {
 // ============================================================================
 
 use once_cell::sync::Lazy;
-use std::str::FromStr;
 
 /// Placeholder address used when contracts are not configured
 const PLACEHOLDER_ADDRESS: Address = alloy::primitives::address!("0000000000000000000000000000000000000000");
@@ -132,19 +150,13 @@ pub struct Erc8004Config {
 impl Erc8004Config {
     fn from_env() -> Self {
         // Try environment variables first (for custom deployments)
-        let identity_registry = std::env::var("ERC8004_IDENTITY_REGISTRY")
-            .ok()
-            .and_then(|s| Address::from_str(&s).ok())
+        let identity_registry = crate::env_registry::parse::<Address>("ERC8004_IDENTITY_REGISTRY")
             .unwrap_or(ETHEREUM_MAINNET_CONTRACTS.identity_registry);
 
-        let reputation_registry = std::env::var("ERC8004_REPUTATION_REGISTRY")
-            .ok()
-            .and_then(|s| Address::from_str(&s).ok())
+        let reputation_registry = crate::env_registry::parse::<Address>("ERC8004_REPUTATION_REGISTRY")
             .unwrap_or(ETHEREUM_MAINNET_CONTRACTS.reputation_registry);
 
-        let validation_registry = std::env::var("ERC8004_VALIDATION_REGISTRY")
-            .ok()
-            .and_then(|s| Address::from_str(&s).ok())
+        let validation_registry = crate::env_registry::parse::<Address>("ERC8004_VALIDATION_REGISTRY")
             .unwrap_or(PLACEHOLDER_ADDRESS);
 
         let is_configured = reputation_registry != PLACEHOLDER_ADDRESS;
@@ -219,6 +231,8 @@ mod tests {
     fn test_unsupported_network() {
         assert!(!is_erc8004_supported(&Network::Avalanche));
         assert!(get_contracts(&Network::Avalanche).is_none());
+        assert!(get_contracts(&Network::ZkSyncEra).is_none());
+        assert!(get_contracts(&Network::ScrollSepolia).is_none());
     }
 
     #[test]
@@ -226,5 +240,9 @@ mod tests {
         let networks = supported_networks();
         assert!(networks.contains(&Network::Ethereum));
         assert!(networks.contains(&Network::EthereumSepolia));
+        assert_eq!(
+            supported_network_names(),
+            vec!["ethereum", "ethereum-sepolia"]
+        );
     }
 }
@@ -0,0 +1,388 @@
+//! On-chain verification of [`ProofOfPayment`].
+//!
+//! A proof attached to feedback is supplied by the client, so anyone could fabricate
+//! one. Before a proof backs feedback, [`ProofOfPayment::verify`] checks it against
+//! the chain it names:
+//!
+//! - `paymentHash` matches the proof's fields
+//! - the transaction exists, succeeded and was included in `blockNumber`
+//! - it emitted a `Transfer` of `token` from `payer` to `payee` for `amount`, as both
+//!   plain transfers and ERC-3009 `transferWithAuthorization` do
+//! - the block timestamp equals `timestamp`, when the proof carries one, and is at most
+//!   `ERC8004_PROOF_MAX_AGE` seconds old (default: 30 days)
+
+use alloy::eips::BlockNumberOrTag;
+use alloy::primitives::{Address, B256, U256};
+use alloy::providers::Provider;
+use once_cell::sync::Lazy;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use super::ProofOfPayment;
+use crate::chain::evm::USDC;
+use crate::network::Network;
+use crate::types::{MixedAddress, TransactionHash};
+
+/// Default maximum age of a payment backing feedback, in seconds.
+pub const DEFAULT_MAX_PROOF_AGE_SECS: u64 = 30 * 24 * 60 * 60;
+
+static MAX_PROOF_AGE: Lazy<Duration> = Lazy::new(|| {
+    let secs =
+        crate::env_registry::parse("ERC8004_PROOF_MAX_AGE").unwrap_or(DEFAULT_MAX_PROOF_AGE_SECS);
+    Duration::from_secs(secs)
+});
+
+/// A failed proof of payment check.
+#[derive(Debug, thiserror::Error)]
+pub enum ProofError {
+    #[error("Payments on {0} cannot be verified")]
+    UnsupportedNetwork(Network),
+    #[error("Proof must reference an EVM transaction, payer, payee and token")]
+    NotEvm,
+    #[error("paymentHash does not match the proof fields")]
+    PaymentHash,
+    #[error("Transaction {0} not found")]
+    TransactionNotFound(B256),
+    #[error("Transaction {0} reverted")]
+    TransactionFailed(B256),
+    #[error("Transaction was included in block {actual}, not {claimed}")]
+    BlockNumber { claimed: u64, actual: u64 },
+    #[error("No Transfer of token {0} in the transaction")]
+    NoTransfer(Address),
+    #[error("No Transfer from payer {0} in the transaction")]
+    Payer(Address),
+    #[error("No Transfer to payee {0} in the transaction")]
+    Payee(Address),
+    #[error("Transferred amount {actual} does not match {claimed}")]
+    Amount { claimed: U256, actual: U256 },
+    #[error("Block timestamp is {actual}, not {claimed}")]
+    Timestamp { claimed: u64, actual: u64 },
+    #[error("Payment is {age} seconds old, more than the {max_age} seconds accepted")]
+    Stale { age: u64, max_age: u64 },
+    #[error("Failed to query the chain: {0}")]
+    Rpc(String),
+}
+
+impl ProofError {
+    /// Name of the failed check, for API responses.
+    pub fn check(&self) -> &'static str {
+        match self {
+            ProofError::UnsupportedNetwork(_) => "network",
+            ProofError::NotEvm => "format",
+            ProofError::PaymentHash => "paymentHash",
+            ProofError::TransactionNotFound(_) | ProofError::TransactionFailed(_) => "transaction",
+            ProofError::BlockNumber { .. } => "blockNumber",
+            ProofError::NoTransfer(_) => "token",
+            ProofError::Payer(_) => "payer",
+            ProofError::Payee(_) => "payee",
+            ProofError::Amount { .. } => "amount",
+            ProofError::Timestamp { .. } => "timestamp",
+            ProofError::Stale { .. } => "age",
+            ProofError::Rpc(_) => "rpc",
+        }
+    }
+}
+
+fn rpc(error: impl std::fmt::Display) -> ProofError {
+    ProofError::Rpc(error.to_string())
+}
+
+impl ProofOfPayment {
+    /// Check the proof against the chain through `provider`, which must serve `self.network`.
+    pub async fn verify<P: Provider>(&self, provider: &P) -> Result<(), ProofError> {
+        self.verify_with_max_age(provider, *MAX_PROOF_AGE).await
+    }
+
+    /// [`verify`](Self::verify) with an explicit maximum payment age.
+    pub async fn verify_with_max_age<P: Provider>(
+        &self,
+        provider: &P,
+        max_age: Duration,
+    ) -> Result<(), ProofError> {
+        let (transaction, payer, payee, token) = match (
+            &self.transaction_hash,
+            &self.payer,
+            &self.payee,
+            &self.token,
+        ) {
+            (
+                TransactionHash::Evm(transaction),
+                MixedAddress::Evm(payer),
+                MixedAddress::Evm(payee),
+                MixedAddress::Evm(token),
+            ) => (B256::from(*transaction), payer.0, payee.0, token.0),
+            _ => return Err(ProofError::NotEvm),
+        };
+        if !self.is_hash_consistent() {
+            return Err(ProofError::PaymentHash);
+        }
+
+        let receipt = provider
+            .get_transaction_receipt(transaction)
+            .await
+            .map_err(rpc)?
+            .ok_or(ProofError::TransactionNotFound(transaction))?;
+        if !receipt.status() {
+            return Err(ProofError::TransactionFailed(transaction));
+        }
+        let block_number = receipt
+            .block_number
+            .ok_or(ProofError::TransactionNotFound(transaction))?;
+        if block_number != self.block_number {
+            return Err(ProofError::BlockNumber {
+                claimed: self.block_number,
+                actual: block_number,
+            });
+        }
+
+        let transfers: Vec<USDC::Transfer> = receipt
+            .inner
+            .logs()
+            .iter()
+            .filter(|log| log.address() == token)
+            .filter_map(|log| log.log_decode::<USDC::Transfer>().ok())
+            .map(|log| log.inner.data)
+            .collect();
+        self.check_transfers(&transfers, token, payer, payee)?;
+
+        let block = provider
+            .get_block_by_number(BlockNumberOrTag::Number(block_number))
+            .await
+            .map_err(rpc)?
+            .ok_or_else(|| ProofError::Rpc(format!("block {block_number} not found")))?;
+        let timestamp = block.header.timestamp;
+        if let Some(claimed) = self.timestamp.filter(|&claimed| claimed != timestamp) {
+            return Err(ProofError::Timestamp {
+                claimed,
+                actual: timestamp,
+            });
+        }
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let age = now.saturating_sub(timestamp);
+        if age > max_age.as_secs() {
+            return Err(ProofError::Stale {
+                age,
+                max_age: max_age.as_secs(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Find the transfer the proof claims, naming the first field no transfer matches.
+    fn check_transfers(
+        &self,
+        transfers: &[USDC::Transfer],
+        token: Address,
+        payer: Address,
+        payee: Address,
+    ) -> Result<(), ProofError> {
+        let claimed: U256 = self.amount.into();
+        if transfers.is_empty() {
+            return Err(ProofError::NoTransfer(token));
+        }
+        let from_payer: Vec<&USDC::Transfer> =
+            transfers.iter().filter(|t| t.from == payer).collect();
+        if from_payer.is_empty() {
+            return Err(ProofError::Payer(payer));
+        }
+        let to_payee: Vec<&USDC::Transfer> =
+            from_payer.into_iter().filter(|t| t.to == payee).collect();
+        match to_payee.first() {
+            None => Err(ProofError::Payee(payee)),
+            Some(_) if to_payee.iter().any(|t| t.value == claimed) => Ok(()),
+            Some(transfer) => Err(ProofError::Amount {
+                claimed,
+                actual: transfer.value,
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{EvmAddress, SettleConfirmation, TokenAmount};
+    use alloy::primitives::address;
+    use alloy::providers::ProviderBuilder;
+    use serde_json::{json, Value};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const RECEIPT: &str = include_str!("../../tests/fixtures/erc8004_payment_receipt.json");
+    const BLOCK: &str = include_str!("../../tests/fixtures/erc8004_payment_block.json");
+
+    const TRANSACTION: B256 = alloy::primitives::b256!(
+        "5f2c8e7a1d3b4f60a9e8c7d6b5a4938271605f4e3d2c1b0a99887766554433aa"
+    );
+    const BLOCK_NUMBER: u64 = 27_439_044;
+    const TIMESTAMP: u64 = 1_755_427_523;
+    const TOKEN: Address = address!("036CbD53842c5426634e7929541eC2318f3dCF7e");
+    const PAYER: Address = address!("6c8f2a50c7f8e6b2d3a1f9e0b4c5d6a7e8f90123");
+    const PAYEE: Address = address!("209693bc6afc0c5328ba36faf03c514ef312287c");
+    const AMOUNT: u64 = 10_000;
+
+    /// No limit on the age of the recorded payment.
+    const ANY_AGE: Duration = Duration::from_secs(100 * 365 * 24 * 60 * 60);
+
+    /// JSON-RPC node answering from the recorded fixtures; unknown transactions are `null`.
+    async fn serve_fixtures() -> MockServer {
+        let receipt: Value = serde_json::from_str(RECEIPT).unwrap();
+        let block: Value = serde_json::from_str(BLOCK).unwrap();
+        let server = MockServer::start().await;
+        Mock::given(wiremock::matchers::method("POST"))
+            .respond_with(move |request: &wiremock::Request| {
+                let body: Value = request.body_json().unwrap();
+                let result = match body["method"].as_str().unwrap() {
+                    "eth_getTransactionReceipt"
+                        if body["params"][0] == receipt["transactionHash"] =>
+                    {
+                        receipt.clone()
+                    }
+                    "eth_getTransactionReceipt" => Value::Null,
+                    "eth_getBlockByNumber" if body["params"][0] == block["number"] => block.clone(),
+                    "eth_getBlockByNumber" => Value::Null,
+                    method => panic!("unexpected RPC method {method}"),
+                };
+                ResponseTemplate::new(200)
+                    .set_body_json(json!({ "jsonrpc": "2.0", "id": body["id"], "result": result }))
+            })
+            .mount(&server)
+            .await;
+        server
+    }
+
+    fn provider(server: &MockServer) -> impl Provider {
+        ProviderBuilder::new().connect_http(server.uri().parse().unwrap())
+    }
+
+    fn proof(payee: Address, amount: u64, block_number: u64) -> ProofOfPayment {
+        ProofOfPayment::new(
+            TransactionHash::Evm(TRANSACTION.0),
+            SettleConfirmation {
+                block_number,
+                block_timestamp: Some(TIMESTAMP),
+                gas_used: None,
+            },
+            Network::BaseSepolia,
+            MixedAddress::Evm(EvmAddress(PAYER)),
+            MixedAddress::Evm(EvmAddress(payee)),
+            TokenAmount::from(amount),
+            MixedAddress::Evm(EvmAddress(TOKEN)),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_recorded_payment_verifies() {
+        let server = serve_fixtures().await;
+        proof(PAYEE, AMOUNT, BLOCK_NUMBER)
+            .verify_with_max_age(&provider(&server), ANY_AGE)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_tampered_amount_is_rejected() {
+        let server = serve_fixtures().await;
+        let provider = provider(&server);
+
+        // Recomputing the hash does not help: the chain disagrees
+        let error = proof(PAYEE, 1_000_000, BLOCK_NUMBER)
+            .verify_with_max_age(&provider, ANY_AGE)
+            .await
+            .unwrap_err();
+        assert!(matches!(error, ProofError::Amount { .. }), "{error:?}");
+        assert_eq!(error.check(), "amount");
+
+        // Editing the amount alone breaks the hash
+        let mut tampered = proof(PAYEE, AMOUNT, BLOCK_NUMBER);
+        tampered.amount = TokenAmount::from(1_000_000u64);
+        let error = tampered
+            .verify_with_max_age(&provider, ANY_AGE)
+            .await
+            .unwrap_err();
+        assert!(matches!(error, ProofError::PaymentHash), "{error:?}");
+    }
+
+    #[tokio::test]
+    async fn test_wrong_payee_is_rejected() {
+        let server = serve_fixtures().await;
+        let payee = address!("1111111111111111111111111111111111111111");
+
+        let error = proof(payee, AMOUNT, BLOCK_NUMBER)
+            .verify_with_max_age(&provider(&server), ANY_AGE)
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(error, ProofError::Payee(p) if p == payee),
+            "{error:?}"
+        );
+        assert_eq!(error.check(), "payee");
+    }
+
+    #[tokio::test]
+    async fn test_stale_proof_is_rejected() {
+        let server = serve_fixtures().await;
+
+        let error = proof(PAYEE, AMOUNT, BLOCK_NUMBER)
+            .verify_with_max_age(&provider(&server), Duration::from_secs(3600))
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(error, ProofError::Stale { max_age: 3600, .. }),
+            "{error:?}"
+        );
+        assert_eq!(error.check(), "age");
+    }
+
+    #[tokio::test]
+    async fn test_block_and_transaction_mismatches() {
+        let server = serve_fixtures().await;
+        let provider = provider(&server);
+
+        let error = proof(PAYEE, AMOUNT, BLOCK_NUMBER + 1)
+            .verify_with_max_age(&provider, ANY_AGE)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            ProofError::BlockNumber { claimed, actual }
+                if claimed == BLOCK_NUMBER + 1 && actual == BLOCK_NUMBER
+        ));
+
+        let unknown = ProofOfPayment::new(
+            TransactionHash::Evm([0xab; 32]),
+            SettleConfirmation {
+                block_number: BLOCK_NUMBER,
+                block_timestamp: Some(TIMESTAMP),
+                gas_used: None,
+            },
+            Network::BaseSepolia,
+            MixedAddress::Evm(EvmAddress(PAYER)),
+            MixedAddress::Evm(EvmAddress(PAYEE)),
+            TokenAmount::from(AMOUNT),
+            MixedAddress::Evm(EvmAddress(TOKEN)),
+        );
+        let error = unknown
+            .verify_with_max_age(&provider, ANY_AGE)
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(error, ProofError::TransactionNotFound(_)),
+            "{error:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_non_evm_proof_is_rejected() {
+        let server = serve_fixtures().await;
+        let mut proof = proof(PAYEE, AMOUNT, BLOCK_NUMBER);
+        proof.payer = MixedAddress::Offchain("payer".into());
+
+        let error = proof
+            .verify_with_max_age(&provider(&server), ANY_AGE)
+            .await
+            .unwrap_err();
+        assert!(matches!(error, ProofError::NotEvm));
+    }
+}
@@ -0,0 +1,417 @@
+//! Cache of the registry reads behind `GET /identity` and `GET /reputation`.
+//!
+//! Every lookup otherwise costs fresh `eth_call`s, 300-800ms each on public RPC
+//! endpoints, and the Bazaar UI renders whole lists of agents. Reads are cached by
+//! network, contract, method and arguments, for a TTL depending on how often the data
+//! changes:
+//!
+//! - identity reads (`getIdentity`, `tokenURI`, `ownerOf`) for
+//!   `ERC8004_IDENTITY_CACHE_TTL` seconds (default: 600)
+//! - reputation summaries (`getSummary`) for `ERC8004_REPUTATION_CACHE_TTL` seconds
+//!   (default: 60)
+//!
+//! At most `ERC8004_READ_CACHE_SIZE` reads are kept; the least recently used is evicted
+//! first. Failed reads are not cached.
+//!
+//! Concurrent misses of the same read are coalesced: the first caller reads the chain
+//! while the others wait for its result instead of sending the same call. If that read
+//! fails, the next waiter reads again.
+//!
+//! `DELETE /cache/identity/{agentId}` drops the identity reads of an agent, and the
+//! facilitator drops them itself when it registers an agent or sets its wallet. Hits
+//! and misses are counted in `x402_erc8004_cache_requests_total`.
+
+use alloy::primitives::Address;
+use alloy::providers::Provider;
+use lru::LruCache;
+use once_cell::sync::Lazy;
+use std::any::Any;
+use std::collections::HashMap;
+use std::future::Future;
+use std::num::NonZeroUsize;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use super::{AgentIdentity, Erc8004Client, Erc8004Error, GetReputationRequest, ReputationSummary};
+use crate::network::Network;
+
+/// Default seconds an identity read is served from the cache.
+pub const DEFAULT_IDENTITY_TTL_SECS: u64 = 600;
+
+/// Default seconds a reputation summary is served from the cache.
+pub const DEFAULT_SUMMARY_TTL_SECS: u64 = super::batch::DEFAULT_CACHE_TTL_SECS;
+
+/// Default number of reads kept.
+pub const DEFAULT_CACHE_SIZE: usize = 10_000;
+
+/// Registry read being cached.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ReadMethod {
+    /// Owner, URI and wallet of an agent
+    Identity,
+    /// `tokenURI`
+    AgentUri,
+    /// `ownerOf`, as a registration check
+    AgentExists,
+    /// `getSummary`
+    Summary,
+}
+
+impl ReadMethod {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ReadMethod::Identity => "getIdentity",
+            ReadMethod::AgentUri => "tokenURI",
+            ReadMethod::AgentExists => "ownerOf",
+            ReadMethod::Summary => "getSummary",
+        }
+    }
+
+    /// Whether this reads the Identity Registry.
+    pub fn is_identity(self) -> bool {
+        !matches!(self, ReadMethod::Summary)
+    }
+}
+
+/// Cache key of one registry read.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ReadKey {
+    pub network: Network,
+    pub contract: Address,
+    pub method: ReadMethod,
+    pub agent_id: u64,
+    /// Arguments besides the agent, e.g. a serialized reputation filter
+    pub args: String,
+}
+
+type CachedRead = (Arc<dyn Any + Send + Sync>, Instant);
+
+/// Registry reads kept for a per-method TTL, with concurrent misses coalesced.
+pub struct ReadCache {
+    identity_ttl: Duration,
+    summary_ttl: Duration,
+    entries: Mutex<LruCache<ReadKey, CachedRead>>,
+    /// One lock per read in progress; its holder is the only caller reading the chain
+    in_flight: Mutex<HashMap<ReadKey, Arc<tokio::sync::Mutex<()>>>>,
+}
+
+impl ReadCache {
+    pub fn new(capacity: NonZeroUsize, identity_ttl: Duration, summary_ttl: Duration) -> Self {
+        Self {
+            identity_ttl,
+            summary_ttl,
+            entries: Mutex::new(LruCache::new(capacity)),
+            in_flight: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn from_env() -> Self {
+        let capacity = crate::env_registry::parse("ERC8004_READ_CACHE_SIZE")
+            .and_then(NonZeroUsize::new)
+            .unwrap_or(NonZeroUsize::new(DEFAULT_CACHE_SIZE).expect("non-zero default"));
+        let identity_ttl = crate::env_registry::parse("ERC8004_IDENTITY_CACHE_TTL")
+            .unwrap_or(DEFAULT_IDENTITY_TTL_SECS);
+        let summary_ttl = crate::env_registry::parse("ERC8004_REPUTATION_CACHE_TTL")
+            .unwrap_or(DEFAULT_SUMMARY_TTL_SECS);
+        Self::new(
+            capacity,
+            Duration::from_secs(identity_ttl),
+            Duration::from_secs(summary_ttl),
+        )
+    }
+
+    fn ttl(&self, method: ReadMethod) -> Duration {
+        if method.is_identity() {
+            self.identity_ttl
+        } else {
+            self.summary_ttl
+        }
+    }
+
+    fn get<T: Clone + 'static>(&self, key: &ReadKey) -> Option<T> {
+        let mut entries = self.entries.lock().unwrap();
+        let (value, read_at) = entries.get(key)?;
+        if read_at.elapsed() >= self.ttl(key.method) {
+            entries.pop(key);
+            return None;
+        }
+        value.downcast_ref::<T>().cloned()
+    }
+
+    /// Value of the read `key` from the cache when fresh, otherwise from `read`, which
+    /// is then cached. While a read of `key` is in progress, callers wait for its result.
+    pub async fn get_or_read<T, E>(
+        &self,
+        key: ReadKey,
+        read: impl Future<Output = Result<T, E>>,
+    ) -> Result<T, E>
+    where
+        T: Clone + Send + Sync + 'static,
+    {
+        if let Some(value) = self.get(&key) {
+            crate::metrics::record_erc8004_cache(key.method.as_str(), true);
+            return Ok(value);
+        }
+        let flight = Arc::clone(
+            self.in_flight
+                .lock()
+                .unwrap()
+                .entry(key.clone())
+                .or_default(),
+        );
+        let result = {
+            let _reading = flight.lock().await;
+            match self.get(&key) {
+                Some(value) => {
+                    crate::metrics::record_erc8004_cache(key.method.as_str(), true);
+                    Ok(value)
+                }
+                None => {
+                    crate::metrics::record_erc8004_cache(key.method.as_str(), false);
+                    let result = read.await;
+                    if let Ok(value) = &result {
+                        let value: Arc<dyn Any + Send + Sync> = Arc::new(value.clone());
+                        self.entries
+                            .lock()
+                            .unwrap()
+                            .put(key.clone(), (value, Instant::now()));
+                    }
+                    result
+                }
+            }
+        };
+        let mut in_flight = self.in_flight.lock().unwrap();
+        // Only the map and this call still hold the lock when no one else waits
+        if Arc::strong_count(&flight) == 2 {
+            in_flight.remove(&key);
+        }
+        result
+    }
+
+    /// Drop the cached reads of `agent_id` matching `method`, on `network` or on every
+    /// network. Returns how many were dropped.
+    fn invalidate(
+        &self,
+        agent_id: u64,
+        network: Option<Network>,
+        method: impl Fn(ReadMethod) -> bool,
+    ) -> usize {
+        let mut entries = self.entries.lock().unwrap();
+        let stale: Vec<ReadKey> = entries
+            .iter()
+            .map(|(key, _)| key)
+            .filter(|key| {
+                key.agent_id == agent_id
+                    && method(key.method)
+                    && network.is_none_or(|network| network == key.network)
+            })
+            .cloned()
+            .collect();
+        for key in &stale {
+            entries.pop(key);
+        }
+        stale.len()
+    }
+
+    /// Drop the cached identity reads of `agent_id`, on `network` or on every network.
+    /// Returns how many were dropped.
+    pub fn invalidate_identity(&self, agent_id: u64, network: Option<Network>) -> usize {
+        self.invalidate(agent_id, network, ReadMethod::is_identity)
+    }
+
+    /// Drop the cached reputation summaries of `agent_id` on `network`.
+    pub fn invalidate_summaries(&self, agent_id: u64, network: Network) -> usize {
+        self.invalidate(agent_id, Some(network), |method| {
+            method == ReadMethod::Summary
+        })
+    }
+
+    /// [`Erc8004Client::get_identity`] through the cache.
+    pub async fn identity<P: Provider>(
+        &self,
+        client: &Erc8004Client<P>,
+        agent_id: u64,
+    ) -> Result<AgentIdentity, Erc8004Error> {
+        let key = identity_key(client, ReadMethod::Identity, agent_id);
+        self.get_or_read(key, client.get_identity(agent_id)).await
+    }
+
+    /// [`Erc8004Client::agent_uri`] through the cache.
+    pub async fn agent_uri<P: Provider>(
+        &self,
+        client: &Erc8004Client<P>,
+        agent_id: u64,
+    ) -> Result<String, Erc8004Error> {
+        let key = identity_key(client, ReadMethod::AgentUri, agent_id);
+        self.get_or_read(key, client.agent_uri(agent_id)).await
+    }
+
+    /// [`Erc8004Client::agent_exists`] through the cache.
+    pub async fn agent_exists<P: Provider>(
+        &self,
+        client: &Erc8004Client<P>,
+        agent_id: u64,
+    ) -> Result<bool, Erc8004Error> {
+        let key = identity_key(client, ReadMethod::AgentExists, agent_id);
+        self.get_or_read(key, client.agent_exists(agent_id)).await
+    }
+
+    /// [`Erc8004Client::get_summary`] through the cache.
+    pub async fn summary<P: Provider>(
+        &self,
+        client: &Erc8004Client<P>,
+        agent_id: u64,
+        filter: &GetReputationRequest,
+    ) -> Result<ReputationSummary, Erc8004Error> {
+        let key = ReadKey {
+            network: client.network(),
+            contract: client.contracts().reputation_registry,
+            method: ReadMethod::Summary,
+            agent_id,
+            args: serde_json::to_string(filter).expect("filters serialize"),
+        };
+        self.get_or_read(key, client.get_summary(agent_id, filter))
+            .await
+    }
+}
+
+fn identity_key<P: Provider>(
+    client: &Erc8004Client<P>,
+    method: ReadMethod,
+    agent_id: u64,
+) -> ReadKey {
+    ReadKey {
+        network: client.network(),
+        contract: client.contracts().identity_registry,
+        method,
+        agent_id,
+        args: String::new(),
+    }
+}
+
+/// Cache shared by the `GET /identity` and `GET /reputation` handlers.
+pub static READ_CACHE: Lazy<ReadCache> = Lazy::new(ReadCache::from_env);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn key(method: ReadMethod, agent_id: u64) -> ReadKey {
+        ReadKey {
+            network: Network::EthereumSepolia,
+            contract: Address::repeat_byte(0x80),
+            method,
+            agent_id,
+            args: String::new(),
+        }
+    }
+
+    fn cache(identity_ttl: Duration, summary_ttl: Duration) -> ReadCache {
+        ReadCache::new(NonZeroUsize::new(16).unwrap(), identity_ttl, summary_ttl)
+    }
+
+    /// A read counting its calls, answering after a delay.
+    async fn read(calls: &AtomicUsize, value: u64) -> Result<u64, String> {
+        calls.fetch_add(1, Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        Ok(value)
+    }
+
+    #[tokio::test]
+    async fn test_reads_expire_after_their_method_ttl() {
+        let cache = cache(Duration::from_secs(600), Duration::from_millis(100));
+        let calls = AtomicUsize::new(0);
+
+        for _ in 0..2 {
+            let identity = cache.get_or_read(key(ReadMethod::Identity, 1), read(&calls, 1));
+            assert_eq!(identity.await, Ok(1));
+            let summary = cache.get_or_read(key(ReadMethod::Summary, 1), read(&calls, 2));
+            assert_eq!(summary.await, Ok(2));
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        let identity = cache.get_or_read(key(ReadMethod::Identity, 1), read(&calls, 3));
+        assert_eq!(identity.await, Ok(1));
+        let summary = cache.get_or_read(key(ReadMethod::Summary, 1), read(&calls, 4));
+        assert_eq!(summary.await, Ok(4));
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_misses_share_one_read() {
+        let cache = cache(Duration::from_secs(600), Duration::from_secs(60));
+        let calls = AtomicUsize::new(0);
+
+        let reads = futures::future::join_all(
+            (0..10).map(|_| cache.get_or_read(key(ReadMethod::Identity, 1), read(&calls, 7))),
+        )
+        .await;
+
+        assert!(reads.iter().all(|value| *value == Ok(7)));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert!(cache.in_flight.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_failed_read_is_retried_by_the_next_waiter() {
+        let cache = cache(Duration::from_secs(600), Duration::from_secs(60));
+        let calls = AtomicUsize::new(0);
+        let failing = async {
+            calls.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            Err::<u64, _>("execution reverted".to_string())
+        };
+
+        let (first, second) = tokio::join!(
+            cache.get_or_read(key(ReadMethod::Identity, 1), failing),
+            cache.get_or_read(key(ReadMethod::Identity, 1), read(&calls, 7)),
+        );
+
+        assert!(first.is_err());
+        assert_eq!(second, Ok(7));
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_invalidate_identity_keeps_summaries_and_other_agents() {
+        let cache = cache(Duration::from_secs(600), Duration::from_secs(60));
+        let calls = AtomicUsize::new(0);
+        for key in [
+            key(ReadMethod::Identity, 1),
+            key(ReadMethod::AgentUri, 1),
+            key(ReadMethod::Summary, 1),
+            key(ReadMethod::Identity, 2),
+        ] {
+            cache.get_or_read(key, read(&calls, 0)).await.unwrap();
+        }
+
+        assert_eq!(cache.invalidate_identity(1, Some(Network::Ethereum)), 0);
+        assert_eq!(cache.invalidate_identity(1, None), 2);
+
+        assert!(cache.get::<u64>(&key(ReadMethod::Identity, 1)).is_none());
+        assert!(cache.get::<u64>(&key(ReadMethod::Summary, 1)).is_some());
+        assert!(cache.get::<u64>(&key(ReadMethod::Identity, 2)).is_some());
+    }
+
+    #[test]
+    fn test_entries_are_bounded() {
+        let cache = ReadCache::new(
+            NonZeroUsize::new(2).unwrap(),
+            Duration::from_secs(600),
+            Duration::from_secs(60),
+        );
+        for agent_id in 1..=3 {
+            let value: Arc<dyn Any + Send + Sync> = Arc::new(agent_id);
+            cache
+                .entries
+                .lock()
+                .unwrap()
+                .put(key(ReadMethod::Identity, agent_id), (value, Instant::now()));
+        }
+
+        assert!(cache.get::<u64>(&key(ReadMethod::Identity, 1)).is_none());
+        assert_eq!(cache.get::<u64>(&key(ReadMethod::Identity, 3)), Some(3));
+    }
+}
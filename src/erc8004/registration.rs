@@ -0,0 +1,500 @@
+//! Resolution of ERC-8004 agent registration files.
+//!
+//! The Identity Registry stores an `agentURI` per agent, pointing at an
+//! [`AgentRegistrationFile`]. [`AgentUriResolver`] fetches and parses it for
+//! `GET /identity/:network/:agentId` and `GET /identity/:network/:agentId/registration`.
+//! Supported URIs:
+//!
+//! - `https://...`
+//! - `ipfs://<cid>[/path]` (or `ipfs://ipfs/<cid>`), through `ERC8004_IPFS_GATEWAY`
+//!   (default: `https://ipfs.io/ipfs/`)
+//! - `ar://<transaction>[/path]`, through `ERC8004_ARWEAVE_GATEWAY`
+//!   (default: `https://arweave.net/`)
+//! - `data:application/json;base64,...` inline files
+//!
+//! Files larger than [`MAX_REGISTRATION_FILE_BYTES`] are rejected, and fetches time out
+//! after [`FETCH_TIMEOUT`]. Parsed files are kept in an LRU cache of
+//! `ERC8004_REGISTRATION_CACHE_SIZE` URIs (default: 1000), each for
+//! `ERC8004_REGISTRATION_CACHE_TTL` seconds (default: 300); failures are not cached.
+
+use base64::engine::general_purpose::STANDARD as b64;
+use base64::Engine;
+use lru::LruCache;
+use once_cell::sync::Lazy;
+use reqwest::{Client, Url};
+use std::num::NonZeroUsize;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use super::AgentRegistrationFile;
+
+/// Default gateway for `ipfs://` URIs.
+pub const DEFAULT_IPFS_GATEWAY: &str = "https://ipfs.io/ipfs/";
+
+/// Default gateway for `ar://` URIs.
+pub const DEFAULT_ARWEAVE_GATEWAY: &str = "https://arweave.net/";
+
+/// Largest registration file accepted, in bytes.
+pub const MAX_REGISTRATION_FILE_BYTES: usize = 256 * 1024;
+
+/// Timeout of a registration file fetch.
+pub const FETCH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Default seconds a resolved file is cached.
+pub const DEFAULT_CACHE_TTL_SECS: u64 = 300;
+
+/// Default number of resolved files cached.
+pub const DEFAULT_CACHE_SIZE: usize = 1000;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ResolverError {
+    #[error("Agent has no registration URI")]
+    EmptyUri,
+    #[error("Unsupported registration URI scheme: {0}")]
+    UnsupportedScheme(String),
+    #[error("Invalid registration URI: {0}")]
+    InvalidUri(String),
+    #[error("Failed to fetch registration file: {0}")]
+    Fetch(#[from] reqwest::Error),
+    #[error("Registration file request failed with status {0}")]
+    Status(u16),
+    #[error("Registration file exceeds {0} bytes")]
+    TooLarge(usize),
+    #[error("Invalid registration file: {0}")]
+    InvalidJson(#[from] serde_json::Error),
+}
+
+/// Fetches, parses and caches agent registration files.
+#[derive(Debug)]
+pub struct AgentUriResolver {
+    client: Client,
+    ipfs_gateway: Url,
+    arweave_gateway: Url,
+    allow_http: bool,
+    cache_ttl: Duration,
+    cache: Mutex<LruCache<String, (AgentRegistrationFile, Instant)>>,
+}
+
+impl AgentUriResolver {
+    /// Create a resolver with the default gateways, caching up to `cache_size` files
+    /// for `cache_ttl` each.
+    pub fn new(cache_size: NonZeroUsize, cache_ttl: Duration) -> Self {
+        Self {
+            client: Client::builder()
+                .timeout(FETCH_TIMEOUT)
+                .user_agent("x402-facilitator/1.0 (erc8004-registration)")
+                .build()
+                .expect("Failed to create HTTP client"),
+            ipfs_gateway: Url::parse(DEFAULT_IPFS_GATEWAY).expect("valid default gateway"),
+            arweave_gateway: Url::parse(DEFAULT_ARWEAVE_GATEWAY).expect("valid default gateway"),
+            allow_http: false,
+            cache_ttl,
+            cache: Mutex::new(LruCache::new(cache_size)),
+        }
+    }
+
+    /// Read the gateways and cache settings from the environment, falling back to the
+    /// defaults.
+    pub fn from_env() -> Self {
+        let cache_size = crate::env_registry::parse("ERC8004_REGISTRATION_CACHE_SIZE")
+            .and_then(NonZeroUsize::new)
+            .unwrap_or(NonZeroUsize::new(DEFAULT_CACHE_SIZE).expect("non-zero default"));
+        let cache_ttl = crate::env_registry::parse("ERC8004_REGISTRATION_CACHE_TTL")
+            .unwrap_or(DEFAULT_CACHE_TTL_SECS);
+        let mut resolver = Self::new(cache_size, Duration::from_secs(cache_ttl));
+        if let Some(gateway) = gateway_from_env("ERC8004_IPFS_GATEWAY") {
+            resolver = resolver.with_ipfs_gateway(gateway);
+        }
+        if let Some(gateway) = gateway_from_env("ERC8004_ARWEAVE_GATEWAY") {
+            resolver = resolver.with_arweave_gateway(gateway);
+        }
+        resolver
+    }
+
+    /// Gateway used for `ipfs://` URIs.
+    pub fn with_ipfs_gateway(mut self, gateway: Url) -> Self {
+        self.ipfs_gateway = with_trailing_slash(gateway);
+        self
+    }
+
+    /// Gateway used for `ar://` URIs.
+    pub fn with_arweave_gateway(mut self, gateway: Url) -> Self {
+        self.arweave_gateway = with_trailing_slash(gateway);
+        self
+    }
+
+    /// Also accept plain `http://` agent URIs, for local development.
+    pub fn allow_http(mut self, allow: bool) -> Self {
+        self.allow_http = allow;
+        self
+    }
+
+    /// The registration file at `uri`, from the cache when fresh.
+    pub async fn resolve(&self, uri: &str) -> Result<AgentRegistrationFile, ResolverError> {
+        let uri = uri.trim();
+        if uri.is_empty() {
+            return Err(ResolverError::EmptyUri);
+        }
+        if let Some(file) = self.cached(uri) {
+            return Ok(file);
+        }
+        let bytes = self.load(uri, MAX_REGISTRATION_FILE_BYTES).await?;
+        let file: AgentRegistrationFile = serde_json::from_slice(&bytes)?;
+        let mut cache = self.cache.lock().unwrap();
+        cache.put(uri.to_string(), (file.clone(), Instant::now()));
+        Ok(file)
+    }
+
+    fn cached(&self, uri: &str) -> Option<AgentRegistrationFile> {
+        let mut cache = self.cache.lock().unwrap();
+        match cache.get(uri) {
+            Some((file, resolved_at)) if resolved_at.elapsed() < self.cache_ttl => {
+                Some(file.clone())
+            }
+            Some(_) => {
+                cache.pop(uri);
+                None
+            }
+            None => None,
+        }
+    }
+
+    /// Raw bytes of the file at `uri`, uncached, rejecting files over `max_bytes`.
+    ///
+    /// Takes the same URIs as [`resolve`](Self::resolve), whatever the file holds.
+    pub async fn fetch_bytes(&self, uri: &str, max_bytes: usize) -> Result<Vec<u8>, ResolverError> {
+        let uri = uri.trim();
+        if uri.is_empty() {
+            return Err(ResolverError::EmptyUri);
+        }
+        self.load(uri, max_bytes).await
+    }
+
+    /// Raw bytes of the file at `uri`, at most `max_bytes`.
+    async fn load(&self, uri: &str, max_bytes: usize) -> Result<Vec<u8>, ResolverError> {
+        let (scheme, rest) = uri
+            .split_once(':')
+            .ok_or_else(|| ResolverError::InvalidUri(uri.to_string()))?;
+        match scheme.to_ascii_lowercase().as_str() {
+            "data" => decode_data_uri(rest, max_bytes),
+            "ipfs" => {
+                let path = rest.trim_start_matches('/');
+                let path = path.strip_prefix("ipfs/").unwrap_or(path);
+                self.fetch(gateway_url(&self.ipfs_gateway, path, uri)?, max_bytes)
+                    .await
+            }
+            "ar" => {
+                let path = rest.trim_start_matches('/');
+                self.fetch(gateway_url(&self.arweave_gateway, path, uri)?, max_bytes)
+                    .await
+            }
+            "https" => self.fetch(parse_url(uri)?, max_bytes).await,
+            "http" if self.allow_http => self.fetch(parse_url(uri)?, max_bytes).await,
+            other => Err(ResolverError::UnsupportedScheme(other.to_string())),
+        }
+    }
+
+    /// GET `url`, reading at most `max_bytes`.
+    async fn fetch(&self, url: Url, max_bytes: usize) -> Result<Vec<u8>, ResolverError> {
+        let mut response = self.client.get(url).send().await?;
+        if !response.status().is_success() {
+            return Err(ResolverError::Status(response.status().as_u16()));
+        }
+        if response
+            .content_length()
+            .is_some_and(|length| length > max_bytes as u64)
+        {
+            return Err(ResolverError::TooLarge(max_bytes));
+        }
+        // The declared length may be missing or wrong, so the body is capped as it streams
+        let mut body = Vec::new();
+        while let Some(chunk) = response.chunk().await? {
+            if body.len() + chunk.len() > max_bytes {
+                return Err(ResolverError::TooLarge(max_bytes));
+            }
+            body.extend_from_slice(&chunk);
+        }
+        Ok(body)
+    }
+}
+
+fn gateway_from_env(name: &str) -> Option<Url> {
+    let gateway = crate::env_registry::var(name)?;
+    Url::parse(&gateway)
+        .inspect_err(
+            |e| tracing::warn!(name, gateway, error = %e, "Invalid gateway URL, using default"),
+        )
+        .ok()
+}
+
+fn parse_url(uri: &str) -> Result<Url, ResolverError> {
+    Url::parse(uri).map_err(|_| ResolverError::InvalidUri(uri.to_string()))
+}
+
+/// `path` of a content-addressed `uri` under `gateway`.
+fn gateway_url(gateway: &Url, path: &str, uri: &str) -> Result<Url, ResolverError> {
+    if path.is_empty() {
+        return Err(ResolverError::InvalidUri(uri.to_string()));
+    }
+    gateway
+        .join(path)
+        .map_err(|_| ResolverError::InvalidUri(uri.to_string()))
+}
+
+/// `Url::join` replaces the last segment of a base without a trailing slash.
+fn with_trailing_slash(mut url: Url) -> Url {
+    if !url.path().ends_with('/') {
+        url.set_path(&format!("{}/", url.path()));
+    }
+    url
+}
+
+/// Payload of a `data:application/json[;charset=...];base64,...` URI (without `data:`).
+fn decode_data_uri(rest: &str, max_bytes: usize) -> Result<Vec<u8>, ResolverError> {
+    let (meta, payload) = rest
+        .split_once(',')
+        .ok_or_else(|| ResolverError::InvalidUri("data URI without payload".to_string()))?;
+    let mut params = meta.split(';').map(str::trim);
+    let media_type = params.next().unwrap_or_default();
+    let is_base64 = params.any(|param| param.eq_ignore_ascii_case("base64"));
+    if !media_type.eq_ignore_ascii_case("application/json") || !is_base64 {
+        return Err(ResolverError::UnsupportedScheme(format!("data:{meta}")));
+    }
+    // base64 inflates by 4/3, so oversized payloads are rejected before decoding
+    if payload.len() / 4 * 3 > max_bytes {
+        return Err(ResolverError::TooLarge(max_bytes));
+    }
+    b64.decode(payload.trim())
+        .map_err(|e| ResolverError::InvalidUri(format!("data:{meta}: {e}")))
+}
+
+/// Resolver shared by the `GET /identity` handlers.
+pub static AGENT_URI_RESOLVER: Lazy<AgentUriResolver> = Lazy::new(AgentUriResolver::from_env);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn registration_json(name: &str) -> serde_json::Value {
+        json!({
+            "type": "https://eips.ethereum.org/EIPS/eip-8004#registration-v1",
+            "name": name,
+            "description": "Weather forecasts",
+            "services": [{ "name": "A2A", "endpoint": "https://agent.example.com/a2a" }],
+            "x402Support": true
+        })
+    }
+
+    fn resolver() -> AgentUriResolver {
+        AgentUriResolver::new(NonZeroUsize::new(10).unwrap(), Duration::from_secs(60))
+            .allow_http(true)
+    }
+
+    #[tokio::test]
+    async fn test_resolves_http_uri_and_caches() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/agent.json"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(registration_json("forecaster")))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let resolver = resolver();
+        let uri = format!("{}/agent.json", server.uri());
+
+        let file = resolver.resolve(&uri).await.unwrap();
+        assert_eq!(file.name, "forecaster");
+        assert!(file.x402_support);
+        assert_eq!(file.services[0].endpoint, "https://agent.example.com/a2a");
+        // Served from the cache; the mock expects a single request
+        assert_eq!(resolver.resolve(&uri).await.unwrap().name, "forecaster");
+    }
+
+    #[tokio::test]
+    async fn test_plain_http_needs_opt_in() {
+        let resolver = AgentUriResolver::new(NonZeroUsize::new(10).unwrap(), Duration::ZERO);
+        assert!(matches!(
+            resolver.resolve("http://agent.example.com/agent.json").await,
+            Err(ResolverError::UnsupportedScheme(scheme)) if scheme == "http"
+        ));
+        assert!(matches!(
+            resolver.resolve("ftp://agent.example.com/agent.json").await,
+            Err(ResolverError::UnsupportedScheme(_))
+        ));
+        assert!(matches!(
+            resolver.resolve(" ").await,
+            Err(ResolverError::EmptyUri)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_resolves_ipfs_through_gateway() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/ipfs/QmAgent/registration.json"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(registration_json("ipfs-agent")))
+            .mount(&server)
+            .await;
+        // Gateway without a trailing slash
+        let resolver =
+            resolver().with_ipfs_gateway(Url::parse(&format!("{}/ipfs", server.uri())).unwrap());
+
+        let file = resolver
+            .resolve("ipfs://QmAgent/registration.json")
+            .await
+            .unwrap();
+        assert_eq!(file.name, "ipfs-agent");
+        let file = resolver
+            .resolve("ipfs://ipfs/QmAgent/registration.json")
+            .await
+            .unwrap();
+        assert_eq!(file.name, "ipfs-agent");
+    }
+
+    #[tokio::test]
+    async fn test_resolves_arweave_through_gateway() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/arweave/bNbA3TEQVL60xlgCcqdz4ZPHFZ711cZ3hmkpGttDt_U"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(registration_json("ar-agent")))
+            .mount(&server)
+            .await;
+        let resolver = resolver()
+            .with_arweave_gateway(Url::parse(&format!("{}/arweave/", server.uri())).unwrap());
+
+        let file = resolver
+            .resolve("ar://bNbA3TEQVL60xlgCcqdz4ZPHFZ711cZ3hmkpGttDt_U")
+            .await
+            .unwrap();
+        assert_eq!(file.name, "ar-agent");
+        assert!(matches!(
+            resolver.resolve("ar://").await,
+            Err(ResolverError::InvalidUri(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_cache_evicts_least_recently_used() {
+        let server = MockServer::start().await;
+        for name in ["a", "b", "c"] {
+            Mock::given(method("GET"))
+                .and(path(format!("/{name}.json")))
+                .respond_with(ResponseTemplate::new(200).set_body_json(registration_json(name)))
+                .mount(&server)
+                .await;
+        }
+        let resolver =
+            AgentUriResolver::new(NonZeroUsize::new(2).unwrap(), Duration::from_secs(60))
+                .allow_http(true);
+        let uri = |name: &str| format!("{}/{name}.json", server.uri());
+
+        resolver.resolve(&uri("a")).await.unwrap();
+        resolver.resolve(&uri("b")).await.unwrap();
+        // Touch "a" so that "b" is the least recently used when "c" arrives
+        resolver.resolve(&uri("a")).await.unwrap();
+        resolver.resolve(&uri("c")).await.unwrap();
+
+        assert!(resolver.cached(&uri("a")).is_some());
+        assert!(resolver.cached(&uri("b")).is_none());
+        assert!(resolver.cached(&uri("c")).is_some());
+        assert_eq!(server.received_requests().await.unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_resolves_base64_data_uri() {
+        let resolver = resolver();
+        let encoded = b64.encode(registration_json("inline").to_string());
+
+        let file = resolver
+            .resolve(&format!("data:application/json;base64,{encoded}"))
+            .await
+            .unwrap();
+        assert_eq!(file.name, "inline");
+        let file = resolver
+            .resolve(&format!(
+                "data:application/json;charset=utf-8;base64,{encoded}"
+            ))
+            .await
+            .unwrap();
+        assert_eq!(file.name, "inline");
+
+        assert!(matches!(
+            resolver.resolve("data:text/plain;base64,e30=").await,
+            Err(ResolverError::UnsupportedScheme(_))
+        ));
+        assert!(matches!(
+            resolver.resolve("data:application/json;base64,!!!").await,
+            Err(ResolverError::InvalidUri(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_rejects_oversized_file() {
+        let server = MockServer::start().await;
+        let description = "x".repeat(MAX_REGISTRATION_FILE_BYTES);
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "type": "agent",
+                "name": "big",
+                "description": description
+            })))
+            .mount(&server)
+            .await;
+        let resolver = resolver();
+
+        let result = resolver
+            .resolve(&format!("{}/agent.json", server.uri()))
+            .await;
+        assert!(
+            matches!(
+                result,
+                Err(ResolverError::TooLarge(MAX_REGISTRATION_FILE_BYTES))
+            ),
+            "{result:?}"
+        );
+
+        let encoded = b64.encode(vec![b' '; MAX_REGISTRATION_FILE_BYTES + 3]);
+        let result = resolver
+            .resolve(&format!("data:application/json;base64,{encoded}"))
+            .await;
+        assert!(
+            matches!(
+                result,
+                Err(ResolverError::TooLarge(MAX_REGISTRATION_FILE_BYTES))
+            ),
+            "{result:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_invalid_json_and_status_errors() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/broken.json"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("{\"name\": "))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/missing.json"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+        let resolver = resolver();
+
+        assert!(matches!(
+            resolver
+                .resolve(&format!("{}/broken.json", server.uri()))
+                .await,
+            Err(ResolverError::InvalidJson(_))
+        ));
+        assert!(matches!(
+            resolver
+                .resolve(&format!("{}/missing.json", server.uri()))
+                .await,
+            Err(ResolverError::Status(404))
+        ));
+    }
+}
@@ -0,0 +1,463 @@
+//! Runtime registry of ERC-8004 deployments.
+//!
+//! [`Erc8004Registry`] starts from the canonical deployments and merges, in order:
+//!
+//! 1. The TOML file named by `ERC8004_REGISTRY_FILE`:
+//!
+//!    ```toml
+//!    [networks.base-sepolia]
+//!    identity = "0x8004A818BFB912233c491871b3d84c89A494BD9e"
+//!    reputation = "0x8004B663056A597Dffe9eCcC1965A193B7388713"
+//!    validation = "0x8004Cb1BF31DAf7788923b405b754f57acEB4272"
+//!    ```
+//!
+//! 2. `ERC8004_CONTRACTS_<NETWORK>` variables, e.g.
+//!    `ERC8004_CONTRACTS_BASE_SEPOLIA=identity:0x..,reputation:0x..,validation:0x..`
+//!
+//! A source overrides only the registries it sets, so a network without a canonical
+//! deployment needs at least `identity` and `reputation`. Unknown or non-EVM networks,
+//! unknown registry names and unparseable or zero addresses are errors, and the
+//! facilitator refuses to start on them.
+//!
+//! `main` installs the registry with [`install_registry`] before anything reads it;
+//! until then [`registry`] serves the canonical deployments.
+
+use alloy::primitives::Address;
+use once_cell::sync::OnceCell;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::str::FromStr;
+
+use super::{
+    Erc8004Contracts, BASE_MAINNET_CONTRACTS, BASE_SEPOLIA_CONTRACTS, ETHEREUM_MAINNET_CONTRACTS,
+    ETHEREUM_SEPOLIA_CONTRACTS,
+};
+use crate::network::{Network, NetworkFamily};
+
+/// Prefix of the per-network override variables.
+const ENV_PREFIX: &str = "ERC8004_CONTRACTS_";
+
+/// Errors building an [`Erc8004Registry`]; `origin` names the variable or file at fault.
+#[derive(Debug, thiserror::Error)]
+pub enum RegistryError {
+    #[error("{origin}: unknown network {network:?}")]
+    UnknownNetwork { origin: String, network: String },
+
+    #[error("{origin}: ERC-8004 is only supported on EVM networks, not {network}")]
+    NotEvm { origin: String, network: Network },
+
+    #[error(
+        "{origin}: unknown registry {registry:?} (expected identity, reputation or validation)"
+    )]
+    UnknownRegistry { origin: String, registry: String },
+
+    #[error("{origin}: invalid {registry} registry address {value:?}")]
+    InvalidAddress {
+        origin: String,
+        registry: &'static str,
+        value: String,
+    },
+
+    #[error(
+        "{origin}: {network} has no canonical deployment, so the {registry} registry must be set"
+    )]
+    Incomplete {
+        origin: String,
+        network: Network,
+        registry: &'static str,
+    },
+
+    #[error("{origin}: {message}")]
+    File { origin: String, message: String },
+}
+
+/// Contents of an `ERC8004_REGISTRY_FILE`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RegistryFile {
+    #[serde(default)]
+    networks: BTreeMap<String, ContractsOverride>,
+}
+
+/// Registry addresses set by one source; unset ones keep their previous value.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ContractsOverride {
+    identity: Option<String>,
+    reputation: Option<String>,
+    validation: Option<String>,
+}
+
+impl ContractsOverride {
+    /// Parse `identity:0x..,reputation:0x..,validation:0x..`.
+    fn parse(spec: &str, origin: &str) -> Result<Self, RegistryError> {
+        let mut parsed = Self::default();
+        for pair in spec
+            .split(',')
+            .map(str::trim)
+            .filter(|pair| !pair.is_empty())
+        {
+            let (registry, address) = pair.split_once(':').unwrap_or((pair, ""));
+            let slot = match registry.trim() {
+                "identity" => &mut parsed.identity,
+                "reputation" => &mut parsed.reputation,
+                "validation" => &mut parsed.validation,
+                registry => {
+                    return Err(RegistryError::UnknownRegistry {
+                        origin: origin.to_string(),
+                        registry: registry.to_string(),
+                    })
+                }
+            };
+            *slot = Some(address.trim().to_string());
+        }
+        Ok(parsed)
+    }
+
+    /// Apply the override on top of `base`, the network's current deployment.
+    fn apply(
+        &self,
+        base: Option<Erc8004Contracts>,
+        network: Network,
+        origin: &str,
+    ) -> Result<Erc8004Contracts, RegistryError> {
+        let address = |value: &Option<String>, registry: &'static str| {
+            value
+                .as_deref()
+                .map(|value| parse_address(value, registry, origin))
+                .transpose()
+        };
+        let required = |address: Option<Address>, current: Option<Address>, registry| {
+            address
+                .or(current)
+                .ok_or_else(|| RegistryError::Incomplete {
+                    origin: origin.to_string(),
+                    network,
+                    registry,
+                })
+        };
+        Ok(Erc8004Contracts {
+            identity_registry: required(
+                address(&self.identity, "identity")?,
+                base.map(|c| c.identity_registry),
+                "identity",
+            )?,
+            reputation_registry: required(
+                address(&self.reputation, "reputation")?,
+                base.map(|c| c.reputation_registry),
+                "reputation",
+            )?,
+            validation_registry: address(&self.validation, "validation")?
+                .or(base.and_then(|c| c.validation_registry)),
+        })
+    }
+}
+
+fn parse_address(
+    value: &str,
+    registry: &'static str,
+    origin: &str,
+) -> Result<Address, RegistryError> {
+    Address::from_str(value)
+        .ok()
+        .filter(|address| !address.is_zero())
+        .ok_or_else(|| RegistryError::InvalidAddress {
+            origin: origin.to_string(),
+            registry,
+            value: value.to_string(),
+        })
+}
+
+/// ERC-8004 contract addresses of every network the facilitator serves.
+#[derive(Debug, Clone)]
+pub struct Erc8004Registry {
+    /// Canonical deployments first, then configured networks in the order they were added
+    networks: Vec<(Network, Erc8004Contracts)>,
+}
+
+impl Erc8004Registry {
+    /// The canonical deployments only.
+    pub fn builtin() -> Self {
+        let networks = [
+            (Network::Ethereum, Some(ETHEREUM_MAINNET_CONTRACTS)),
+            (Network::EthereumSepolia, Some(ETHEREUM_SEPOLIA_CONTRACTS)),
+            (Network::Base, BASE_MAINNET_CONTRACTS),
+            (Network::BaseSepolia, BASE_SEPOLIA_CONTRACTS),
+        ]
+        .into_iter()
+        .filter_map(|(network, contracts)| Some((network, contracts?)))
+        .collect();
+        Self { networks }
+    }
+
+    /// The canonical deployments merged with `ERC8004_REGISTRY_FILE` and the
+    /// `ERC8004_CONTRACTS_<NETWORK>` variables.
+    pub fn from_env() -> Result<Self, RegistryError> {
+        let file =
+            crate::env_registry::var("ERC8004_REGISTRY_FILE").filter(|path| !path.is_empty());
+        let registry = match file {
+            Some(path) => Self::builtin().with_file(Path::new(&path))?,
+            None => Self::builtin(),
+        };
+        registry.with_env_vars(std::env::vars())
+    }
+
+    /// Merge the deployments listed in a TOML file.
+    pub fn with_file(self, path: &Path) -> Result<Self, RegistryError> {
+        let content = std::fs::read_to_string(path).map_err(|e| RegistryError::File {
+            origin: path.display().to_string(),
+            message: e.to_string(),
+        })?;
+        self.with_toml(&content, &path.display().to_string())
+    }
+
+    fn with_toml(mut self, content: &str, origin: &str) -> Result<Self, RegistryError> {
+        let file: RegistryFile = toml::from_str(content).map_err(|e| RegistryError::File {
+            origin: origin.to_string(),
+            message: e.to_string(),
+        })?;
+        for (network, contracts) in &file.networks {
+            self.merge(network, contracts, origin)?;
+        }
+        Ok(self)
+    }
+
+    /// Merge the non-empty `ERC8004_CONTRACTS_<NETWORK>` entries of `vars`, ignoring all others.
+    fn with_env_vars(
+        mut self,
+        vars: impl IntoIterator<Item = (String, String)>,
+    ) -> Result<Self, RegistryError> {
+        let mut overrides: Vec<_> = vars
+            .into_iter()
+            .filter(|(name, spec)| name.starts_with(ENV_PREFIX) && !spec.trim().is_empty())
+            .collect();
+        overrides.sort();
+        for (name, spec) in overrides {
+            let network = name[ENV_PREFIX.len()..].to_lowercase().replace('_', "-");
+            let contracts = ContractsOverride::parse(&spec, &name)?;
+            self.merge(&network, &contracts, &name)?;
+        }
+        Ok(self)
+    }
+
+    fn merge(
+        &mut self,
+        network: &str,
+        contracts: &ContractsOverride,
+        origin: &str,
+    ) -> Result<(), RegistryError> {
+        let network = Network::from_str(network).map_err(|_| RegistryError::UnknownNetwork {
+            origin: origin.to_string(),
+            network: network.to_string(),
+        })?;
+        if !matches!(NetworkFamily::from(network), NetworkFamily::Evm) {
+            return Err(RegistryError::NotEvm {
+                origin: origin.to_string(),
+                network,
+            });
+        }
+        let merged = contracts.apply(self.get(&network), network, origin)?;
+        match self.networks.iter_mut().find(|(n, _)| *n == network) {
+            Some((_, existing)) => *existing = merged,
+            None => self.networks.push((network, merged)),
+        }
+        Ok(())
+    }
+
+    /// Contract addresses on `network`, if ERC-8004 is available there.
+    pub fn get(&self, network: &Network) -> Option<Erc8004Contracts> {
+        self.networks
+            .iter()
+            .find(|(n, _)| n == network)
+            .map(|(_, contracts)| *contracts)
+    }
+
+    /// Networks with a deployment, canonical ones first.
+    pub fn networks(&self) -> Vec<Network> {
+        self.networks.iter().map(|(network, _)| *network).collect()
+    }
+}
+
+static REGISTRY: OnceCell<Erc8004Registry> = OnceCell::new();
+
+/// Make `registry` the one [`registry`] returns. Only the first call takes effect.
+pub fn install_registry(registry: Erc8004Registry) {
+    if REGISTRY.set(registry).is_err() {
+        tracing::warn!("ERC-8004 registry was already in use; ignoring the new configuration");
+    }
+}
+
+/// The installed registry, or the canonical deployments if none was installed.
+pub fn registry() -> &'static Erc8004Registry {
+    REGISTRY.get_or_init(Erc8004Registry::builtin)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::address;
+
+    const IDENTITY: Address = address!("1111111111111111111111111111111111111111");
+    const REPUTATION: Address = address!("2222222222222222222222222222222222222222");
+    const VALIDATION: Address = address!("3333333333333333333333333333333333333333");
+
+    fn env(vars: &[(&str, &str)]) -> Result<Erc8004Registry, RegistryError> {
+        Erc8004Registry::builtin().with_env_vars(
+            vars.iter()
+                .map(|(name, value)| (name.to_string(), value.to_string())),
+        )
+    }
+
+    #[test]
+    fn test_env_var_adds_network() {
+        let registry = env(&[
+            (
+                "ERC8004_CONTRACTS_BASE_SEPOLIA",
+                &format!("identity:{IDENTITY}, reputation:{REPUTATION},validation:{VALIDATION}"),
+            ),
+            ("ERC8004_CONTRACTS_BASE", ""),
+            ("ERC8004_REGISTRY_FILE", "ignored.toml"),
+            ("RPC_URL_BASE_SEPOLIA", "https://sepolia.base.org"),
+        ])
+        .unwrap();
+
+        let contracts = registry.get(&Network::BaseSepolia).unwrap();
+        assert_eq!(contracts.identity_registry, IDENTITY);
+        assert_eq!(contracts.reputation_registry, REPUTATION);
+        assert_eq!(contracts.validation_registry, Some(VALIDATION));
+        assert_eq!(
+            registry.networks(),
+            vec![
+                Network::Ethereum,
+                Network::EthereumSepolia,
+                Network::BaseSepolia
+            ]
+        );
+    }
+
+    #[test]
+    fn test_override_keeps_unset_registries() {
+        let registry = env(&[(
+            "ERC8004_CONTRACTS_ETHEREUM_SEPOLIA",
+            &format!("reputation:{REPUTATION}"),
+        )])
+        .unwrap();
+
+        let contracts = registry.get(&Network::EthereumSepolia).unwrap();
+        assert_eq!(
+            contracts.identity_registry,
+            ETHEREUM_SEPOLIA_CONTRACTS.identity_registry
+        );
+        assert_eq!(contracts.reputation_registry, REPUTATION);
+        assert_eq!(
+            contracts.validation_registry,
+            ETHEREUM_SEPOLIA_CONTRACTS.validation_registry
+        );
+        assert_eq!(registry.networks().len(), 2);
+    }
+
+    #[test]
+    fn test_env_var_overrides_file() {
+        let file = format!(
+            r#"
+            [networks.base-sepolia]
+            identity = "{IDENTITY}"
+            reputation = "{REPUTATION}"
+
+            [networks.base]
+            identity = "{IDENTITY}"
+            reputation = "{REPUTATION}"
+            validation = "{VALIDATION}"
+            "#
+        );
+        let registry = Erc8004Registry::builtin()
+            .with_toml(&file, "erc8004.toml")
+            .unwrap()
+            .with_env_vars([(
+                "ERC8004_CONTRACTS_BASE_SEPOLIA".to_string(),
+                format!("validation:{VALIDATION},reputation:{IDENTITY}"),
+            )])
+            .unwrap();
+
+        let base_sepolia = registry.get(&Network::BaseSepolia).unwrap();
+        assert_eq!(base_sepolia.identity_registry, IDENTITY);
+        assert_eq!(base_sepolia.reputation_registry, IDENTITY);
+        assert_eq!(base_sepolia.validation_registry, Some(VALIDATION));
+        assert_eq!(
+            registry.get(&Network::Base).unwrap().validation_registry,
+            Some(VALIDATION)
+        );
+    }
+
+    #[test]
+    fn test_invalid_configuration_is_rejected() {
+        let cases = [
+            (
+                format!("identity:0x1234,reputation:{REPUTATION}"),
+                "invalid identity registry address \"0x1234\"",
+            ),
+            (
+                format!("identity:{IDENTITY},reputation:{}", Address::ZERO),
+                "invalid reputation registry address",
+            ),
+            (
+                format!("identity:{IDENTITY},validation:{VALIDATION}"),
+                "the reputation registry must be set",
+            ),
+            (
+                format!("identity:{IDENTITY},reputaton:{REPUTATION}"),
+                "unknown registry \"reputaton\"",
+            ),
+        ];
+        for (spec, message) in cases {
+            let error = env(&[("ERC8004_CONTRACTS_BASE_SEPOLIA", &spec)])
+                .unwrap_err()
+                .to_string();
+            assert!(
+                error.starts_with("ERC8004_CONTRACTS_BASE_SEPOLIA: "),
+                "{error}"
+            );
+            assert!(error.contains(message), "{error}");
+        }
+
+        let spec = format!("identity:{IDENTITY},reputation:{REPUTATION}");
+        assert!(matches!(
+            env(&[("ERC8004_CONTRACTS_BASE_GOERLI", &spec)]),
+            Err(RegistryError::UnknownNetwork { .. })
+        ));
+        assert!(matches!(
+            env(&[("ERC8004_CONTRACTS_SOLANA", &spec)]),
+            Err(RegistryError::NotEvm {
+                network: Network::Solana,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_invalid_file_is_rejected() {
+        let unknown_field =
+            format!("[networks.base]\nidentity = \"{IDENTITY}\"\nreputaton = \"{REPUTATION}\"\n");
+        let error = Erc8004Registry::builtin()
+            .with_toml(&unknown_field, "erc8004.toml")
+            .unwrap_err();
+        assert!(matches!(error, RegistryError::File { .. }), "{error}");
+
+        let bad_address = format!(
+            "[networks.base]\nidentity = \"{IDENTITY}\"\nreputation = \"not-an-address\"\n"
+        );
+        let error = Erc8004Registry::builtin()
+            .with_toml(&bad_address, "erc8004.toml")
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "erc8004.toml: invalid reputation registry address \"not-an-address\""
+        );
+
+        let error = Erc8004Registry::builtin()
+            .with_file(Path::new("/nonexistent/erc8004.toml"))
+            .unwrap_err();
+        assert!(error.to_string().starts_with("/nonexistent/erc8004.toml: "));
+    }
+}
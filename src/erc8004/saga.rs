@@ -0,0 +1,1459 @@
+//! Compensating saga for ERC-8004 feedback submission.
+//!
+//! Submitting feedback backed by a [`ProofOfPayment`] touches several systems, any of
+//! which can fail. Claiming the proof up front and giving up halfway would burn the
+//! payer's feedback right even though nothing reached the chain. The saga makes every
+//! step before the on-chain submission reversible:
+//!
+//! ```text
+//! verify proof ─▶ claim proof ─▶ journal (pending) ─▶ submit tx ─▶ NewFeedback event ─▶ commit ─▶ notify
+//!                   │ release       │ cancel            │                 ▲ pivot
+//!                   ◀───────────────◀───────────────────┘ on failure      │
+//! ```
+//!
+//! Only a confirmed `NewFeedback` event consumes the proof permanently. A failure before
+//! that point compensates completed steps in reverse order, so the request can simply be
+//! retried. Notification happens after the pivot and never triggers compensation.
+//!
+//! # Persistence and Recovery
+//!
+//! Each transition is written to a [`SagaStore`] before the next step runs. On startup
+//! [`FeedbackSaga::recover`] walks incomplete sagas: those that never submitted a
+//! transaction are compensated, those with a recorded transaction are confirmed on-chain
+//! and then committed or compensated, and committed sagas finish their notification.
+//!
+//! A transaction whose outcome is still unknown leaves its saga at
+//! [`SagaStep::Submitted`]. [`start_retry_task`] keeps re-confirming such sagas and
+//! compensates those still unconfirmed once they expire, so a dropped transaction does
+//! not hold the proof of payment forever.
+//!
+//! # Concurrency
+//!
+//! Two sagas for the same proof serialize through [`ProofClaims::try_claim`], which is
+//! atomic: the second saga fails with [`SagaError::ProofInUse`] until the first either
+//! commits (then [`SagaError::ProofAlreadyUsed`]) or compensates (then it can proceed).
+//!
+//! # Environment
+//!
+//! - `FEEDBACK_SAGA_DIR` - Directory for persisted saga state, proof claims and the
+//!   feedback journal (in-memory when unset)
+//! - `FEEDBACK_SAGA_EXPIRY_SECS` - Seconds after submission an unconfirmed saga is
+//!   compensated (default: 86400)
+//! - `FEEDBACK_SAGA_RETRY_INTERVAL_SECS` - Seconds between confirmation retries of
+//!   unconfirmed sagas (default: 60)
+
+use alloy::primitives::{Address, FixedBytes, B256};
+use alloy::providers::{PendingTransactionBuilder, Provider};
+use async_trait::async_trait;
+use once_cell::sync::Lazy;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+use tracing::{debug, error, info, warn};
+
+use super::{FeedbackRequest, IReputationRegistry};
+use crate::chain::evm::InnerProvider;
+use crate::network::Network;
+
+/// How long to wait for a feedback transaction receipt.
+const CONFIRMATION_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Default seconds after submission an unconfirmed saga is compensated.
+pub const DEFAULT_EXPIRY_SECS: u64 = 86_400;
+
+/// Default seconds between confirmation retries of unconfirmed sagas.
+pub const DEFAULT_RETRY_INTERVAL_SECS: u64 = 60;
+
+/// Errors from the feedback saga.
+#[derive(Debug, thiserror::Error)]
+pub enum SagaError {
+    /// Proof of payment failed verification
+    #[error("Invalid proof of payment: {0}")]
+    InvalidProof(String),
+
+    /// Another saga currently holds the proof claim
+    #[error("Proof of payment is being used by another feedback submission")]
+    ProofInUse,
+
+    /// Proof was already consumed by confirmed feedback
+    #[error("Proof of payment was already used for feedback")]
+    ProofAlreadyUsed,
+
+    /// Journal entry could not be recorded
+    #[error("Failed to record feedback journal entry: {0}")]
+    Journal(String),
+
+    /// Transaction could not be submitted
+    #[error("Failed to submit feedback transaction: {0}")]
+    Submission(String),
+
+    /// Transaction mined without a NewFeedback event
+    #[error("Feedback transaction {transaction} did not emit NewFeedback")]
+    Rejected { transaction: B256 },
+
+    /// Transaction outcome unknown; the saga stays pending for recovery
+    #[error("Feedback transaction {transaction} not yet confirmed: {reason}")]
+    Unconfirmed { transaction: B256, reason: String },
+
+    /// Transaction stayed unconfirmed past the saga expiry; the saga was compensated
+    #[error("Feedback transaction {transaction} expired unconfirmed")]
+    Expired { transaction: B256 },
+
+    /// Saga state could not be persisted
+    #[error("Failed to persist saga state: {0}")]
+    Store(String),
+}
+
+// ============================================================================
+// Saga State
+// ============================================================================
+
+/// Last completed step of a saga.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SagaStep {
+    /// Proof verified, nothing claimed yet
+    Started,
+    /// Proof claim held by this saga
+    ProofClaimed,
+    /// Pending journal entry recorded
+    Journaled,
+    /// Transaction sent, outcome unknown
+    Submitted,
+    /// NewFeedback confirmed, proof consumed
+    Committed,
+}
+
+/// Persisted state of a single feedback saga.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SagaRecord {
+    pub id: String,
+    pub request: FeedbackRequest,
+    pub step: SagaStep,
+    /// Payment hash of the claimed proof, if the feedback carries one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proof_key: Option<FixedBytes<32>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub journal_entry: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transaction: Option<B256>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub feedback_index: Option<u64>,
+    pub updated_at: u64,
+}
+
+/// Outcome of a committed saga.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeedbackReceipt {
+    pub saga_id: String,
+    pub network: Network,
+    pub agent_id: u64,
+    pub transaction: B256,
+    pub feedback_index: u64,
+}
+
+// ============================================================================
+// Saga Participants
+// ============================================================================
+
+/// Atomic claim over proofs of payment, keyed by payment hash.
+#[async_trait]
+pub trait ProofClaims: Send + Sync + std::fmt::Debug {
+    /// Claim the proof for `saga_id`. Fails if another saga holds it or it was consumed.
+    async fn try_claim(&self, key: FixedBytes<32>, saga_id: &str) -> Result<(), SagaError>;
+
+    /// Release a claim held by `saga_id` (compensation).
+    async fn release(&self, key: FixedBytes<32>, saga_id: &str);
+
+    /// Permanently consume a claim held by `saga_id`.
+    async fn consume(&self, key: FixedBytes<32>, saga_id: &str);
+}
+
+/// Journal of feedback submissions.
+#[async_trait]
+pub trait FeedbackJournal: Send + Sync + std::fmt::Debug {
+    /// Record a pending entry, returning its id.
+    async fn record(&self, saga_id: &str, request: &FeedbackRequest) -> Result<String, String>;
+
+    /// Mark a pending entry as confirmed on-chain.
+    async fn commit(&self, entry: &str, transaction: B256, feedback_index: u64) -> Result<(), String>;
+
+    /// Cancel a pending entry (compensation).
+    async fn cancel(&self, entry: &str);
+}
+
+/// Chain access for feedback submission.
+#[async_trait]
+pub trait FeedbackChain: Send + Sync {
+    /// Send the giveFeedback transaction, returning its hash.
+    async fn submit(&self, request: &FeedbackRequest) -> Result<B256, String>;
+
+    /// Wait for the transaction outcome.
+    ///
+    /// Returns the feedback index from the `NewFeedback` event, `Ok(None)` if the
+    /// transaction was mined without the event (reverted), or `Err` if the outcome is
+    /// still unknown.
+    async fn confirm(&self, transaction: B256) -> Result<Option<u64>, String>;
+}
+
+/// Post-commit notification of confirmed feedback.
+#[async_trait]
+pub trait FeedbackNotifier: Send + Sync + std::fmt::Debug {
+    async fn notify(&self, receipt: &FeedbackReceipt) -> Result<(), String>;
+}
+
+/// Persistence for in-flight sagas.
+#[async_trait]
+pub trait SagaStore: Send + Sync + std::fmt::Debug {
+    async fn save(&self, record: &SagaRecord) -> Result<(), String>;
+    async fn remove(&self, id: &str) -> Result<(), String>;
+    async fn load_all(&self) -> Result<Vec<SagaRecord>, String>;
+}
+
+// ============================================================================
+// In-Memory and File Implementations
+// ============================================================================
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum ClaimState {
+    Claimed(String),
+    Consumed,
+}
+
+type ClaimTable = HashMap<FixedBytes<32>, ClaimState>;
+
+fn claim(claims: &mut ClaimTable, key: FixedBytes<32>, saga_id: &str) -> Result<(), SagaError> {
+    match claims.get(&key) {
+        Some(ClaimState::Consumed) => Err(SagaError::ProofAlreadyUsed),
+        Some(ClaimState::Claimed(owner)) if owner != saga_id => Err(SagaError::ProofInUse),
+        _ => {
+            claims.insert(key, ClaimState::Claimed(saga_id.to_string()));
+            Ok(())
+        }
+    }
+}
+
+/// Returns whether the claim was held by `saga_id` and has been released.
+fn release(claims: &mut ClaimTable, key: FixedBytes<32>, saga_id: &str) -> bool {
+    if claims.get(&key) == Some(&ClaimState::Claimed(saga_id.to_string())) {
+        claims.remove(&key);
+        return true;
+    }
+    false
+}
+
+fn consume(claims: &mut ClaimTable, key: FixedBytes<32>, saga_id: &str) {
+    match claims.get(&key) {
+        Some(ClaimState::Claimed(owner)) if owner != saga_id => {
+            warn!(saga = saga_id, owner = %owner, "Consuming proof claimed by another saga");
+        }
+        _ => {}
+    }
+    claims.insert(key, ClaimState::Consumed);
+}
+
+/// Process-local proof claims.
+#[derive(Debug, Default)]
+pub struct InMemoryProofClaims {
+    claims: Mutex<ClaimTable>,
+}
+
+#[async_trait]
+impl ProofClaims for InMemoryProofClaims {
+    async fn try_claim(&self, key: FixedBytes<32>, saga_id: &str) -> Result<(), SagaError> {
+        claim(&mut *self.claims.lock().await, key, saga_id)
+    }
+
+    async fn release(&self, key: FixedBytes<32>, saga_id: &str) {
+        release(&mut *self.claims.lock().await, key, saga_id);
+    }
+
+    async fn consume(&self, key: FixedBytes<32>, saga_id: &str) {
+        consume(&mut *self.claims.lock().await, key, saga_id);
+    }
+}
+
+/// File name of the proof claims kept next to persisted sagas. Not `.json`, so
+/// [`FileSagaStore`] never mistakes it for a saga record.
+const PROOF_CLAIMS_FILE: &str = "proof-claims.state";
+
+/// File name of the feedback journal kept next to persisted sagas, for the same reason.
+const FEEDBACK_JOURNAL_FILE: &str = "feedback-journal.state";
+
+/// Load JSON state from `path`, starting empty if the file does not exist yet.
+fn read_state<T: DeserializeOwned + Default>(path: &Path) -> Result<T, String> {
+    match std::fs::read(path) {
+        Ok(bytes) => serde_json::from_slice(&bytes).map_err(|e| e.to_string()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(T::default()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Rewrite the JSON state at `path`, through a temporary file so a crash never
+/// leaves it truncated.
+async fn write_state<T: Serialize>(path: &Path, state: &T) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        tokio::fs::create_dir_all(dir).await.map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_vec(state).map_err(|e| e.to_string())?;
+    let tmp = path.with_extension("tmp");
+    tokio::fs::write(&tmp, json).await.map_err(|e| e.to_string())?;
+    tokio::fs::rename(&tmp, path).await.map_err(|e| e.to_string())
+}
+
+/// Proof claims persisted to a single JSON file, rewritten on every change.
+///
+/// Consumed proofs stay consumed across restarts, so a proof cannot back a second
+/// feedback after the facilitator comes back up.
+#[derive(Debug)]
+pub struct FileProofClaims {
+    path: PathBuf,
+    claims: Mutex<ClaimTable>,
+}
+
+impl FileProofClaims {
+    /// Load claims from `path`, starting empty if the file does not exist yet.
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self, String> {
+        let path = path.into();
+        let claims = read_state(&path)?;
+        Ok(Self {
+            path,
+            claims: Mutex::new(claims),
+        })
+    }
+
+    async fn persist(&self, claims: &ClaimTable) -> Result<(), String> {
+        write_state(&self.path, claims).await
+    }
+}
+
+#[async_trait]
+impl ProofClaims for FileProofClaims {
+    async fn try_claim(&self, key: FixedBytes<32>, saga_id: &str) -> Result<(), SagaError> {
+        let mut claims = self.claims.lock().await;
+        let previous = claims.get(&key).cloned();
+        claim(&mut claims, key, saga_id)?;
+        if let Err(e) = self.persist(&claims).await {
+            // Not durable, so not claimed
+            match previous {
+                Some(state) => claims.insert(key, state),
+                None => claims.remove(&key),
+            };
+            return Err(SagaError::Store(e));
+        }
+        Ok(())
+    }
+
+    async fn release(&self, key: FixedBytes<32>, saga_id: &str) {
+        let mut claims = self.claims.lock().await;
+        if release(&mut claims, key, saga_id) {
+            if let Err(e) = self.persist(&claims).await {
+                warn!(saga = saga_id, error = %e, "Failed to persist released proof claim");
+            }
+        }
+    }
+
+    async fn consume(&self, key: FixedBytes<32>, saga_id: &str) {
+        let mut claims = self.claims.lock().await;
+        consume(&mut claims, key, saga_id);
+        if let Err(e) = self.persist(&claims).await {
+            error!(saga = saga_id, error = %e, "Failed to persist consumed proof claim");
+        }
+    }
+}
+
+/// Status of a journal entry.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JournalStatus {
+    Pending,
+    Committed { transaction: B256, feedback_index: u64 },
+    Cancelled,
+}
+
+type JournalTable = HashMap<String, (FeedbackRequest, JournalStatus)>;
+
+fn journal_entry(saga_id: &str) -> String {
+    format!("journal-{saga_id}")
+}
+
+fn commit_entry(
+    entries: &mut JournalTable,
+    entry: &str,
+    transaction: B256,
+    feedback_index: u64,
+) -> Result<(), String> {
+    match entries.get_mut(entry) {
+        Some((_, status)) => {
+            *status = JournalStatus::Committed { transaction, feedback_index };
+            Ok(())
+        }
+        None => Err(format!("unknown journal entry {entry}")),
+    }
+}
+
+/// Returns whether the entry exists and has been cancelled.
+fn cancel_entry(entries: &mut JournalTable, entry: &str) -> bool {
+    match entries.get_mut(entry) {
+        Some((_, status)) => {
+            *status = JournalStatus::Cancelled;
+            true
+        }
+        None => false,
+    }
+}
+
+/// Process-local feedback journal.
+#[derive(Debug, Default)]
+pub struct InMemoryFeedbackJournal {
+    entries: Mutex<JournalTable>,
+}
+
+impl InMemoryFeedbackJournal {
+    /// Status of a journal entry.
+    pub async fn status(&self, entry: &str) -> Option<JournalStatus> {
+        self.entries.lock().await.get(entry).map(|(_, status)| status.clone())
+    }
+}
+
+#[async_trait]
+impl FeedbackJournal for InMemoryFeedbackJournal {
+    async fn record(&self, saga_id: &str, request: &FeedbackRequest) -> Result<String, String> {
+        let entry = journal_entry(saga_id);
+        self.entries
+            .lock()
+            .await
+            .insert(entry.clone(), (request.clone(), JournalStatus::Pending));
+        Ok(entry)
+    }
+
+    async fn commit(&self, entry: &str, transaction: B256, feedback_index: u64) -> Result<(), String> {
+        commit_entry(&mut *self.entries.lock().await, entry, transaction, feedback_index)
+    }
+
+    async fn cancel(&self, entry: &str) {
+        cancel_entry(&mut *self.entries.lock().await, entry);
+    }
+}
+
+/// Feedback journal persisted to a single JSON file, rewritten on every change.
+///
+/// Entries of sagas interrupted by a restart are still there for recovery to commit
+/// or cancel.
+#[derive(Debug)]
+pub struct FileFeedbackJournal {
+    path: PathBuf,
+    entries: Mutex<JournalTable>,
+}
+
+impl FileFeedbackJournal {
+    /// Load the journal from `path`, starting empty if the file does not exist yet.
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self, String> {
+        let path = path.into();
+        let entries = read_state(&path)?;
+        Ok(Self {
+            path,
+            entries: Mutex::new(entries),
+        })
+    }
+
+    /// Status of a journal entry.
+    pub async fn status(&self, entry: &str) -> Option<JournalStatus> {
+        self.entries.lock().await.get(entry).map(|(_, status)| status.clone())
+    }
+}
+
+#[async_trait]
+impl FeedbackJournal for FileFeedbackJournal {
+    async fn record(&self, saga_id: &str, request: &FeedbackRequest) -> Result<String, String> {
+        let entry = journal_entry(saga_id);
+        let mut entries = self.entries.lock().await;
+        let previous = entries.insert(entry.clone(), (request.clone(), JournalStatus::Pending));
+        if let Err(e) = write_state(&self.path, &*entries).await {
+            // Not durable, so not recorded
+            match previous {
+                Some(state) => entries.insert(entry, state),
+                None => entries.remove(&entry),
+            };
+            return Err(e);
+        }
+        Ok(entry)
+    }
+
+    async fn commit(&self, entry: &str, transaction: B256, feedback_index: u64) -> Result<(), String> {
+        let mut entries = self.entries.lock().await;
+        commit_entry(&mut entries, entry, transaction, feedback_index)?;
+        write_state(&self.path, &*entries).await
+    }
+
+    async fn cancel(&self, entry: &str) {
+        let mut entries = self.entries.lock().await;
+        if cancel_entry(&mut entries, entry) {
+            if let Err(e) = write_state(&self.path, &*entries).await {
+                warn!(entry = entry, error = %e, "Failed to persist cancelled journal entry");
+            }
+        }
+    }
+}
+
+/// Notifier that logs confirmed feedback.
+#[derive(Debug, Default)]
+pub struct LogNotifier;
+
+#[async_trait]
+impl FeedbackNotifier for LogNotifier {
+    async fn notify(&self, receipt: &FeedbackReceipt) -> Result<(), String> {
+        info!(
+            network = %receipt.network,
+            agent_id = receipt.agent_id,
+            tx = %receipt.transaction,
+            feedback_index = receipt.feedback_index,
+            "ERC-8004 feedback confirmed"
+        );
+        Ok(())
+    }
+}
+
+/// Process-local saga store (no crash recovery).
+#[derive(Debug, Default)]
+pub struct InMemorySagaStore {
+    records: Mutex<HashMap<String, SagaRecord>>,
+}
+
+#[async_trait]
+impl SagaStore for InMemorySagaStore {
+    async fn save(&self, record: &SagaRecord) -> Result<(), String> {
+        self.records.lock().await.insert(record.id.clone(), record.clone());
+        Ok(())
+    }
+
+    async fn remove(&self, id: &str) -> Result<(), String> {
+        self.records.lock().await.remove(id);
+        Ok(())
+    }
+
+    async fn load_all(&self) -> Result<Vec<SagaRecord>, String> {
+        Ok(self.records.lock().await.values().cloned().collect())
+    }
+}
+
+/// Saga store writing one JSON file per in-flight saga.
+#[derive(Debug, Clone)]
+pub struct FileSagaStore {
+    dir: PathBuf,
+}
+
+impl FileSagaStore {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    fn path(&self, id: &str) -> PathBuf {
+        self.dir.join(format!("{id}.json"))
+    }
+}
+
+#[async_trait]
+impl SagaStore for FileSagaStore {
+    async fn save(&self, record: &SagaRecord) -> Result<(), String> {
+        tokio::fs::create_dir_all(&self.dir).await.map_err(|e| e.to_string())?;
+        let json = serde_json::to_vec_pretty(record).map_err(|e| e.to_string())?;
+        // Write then rename so a crash never leaves a truncated record
+        let tmp = self.dir.join(format!("{}.json.tmp", record.id));
+        tokio::fs::write(&tmp, json).await.map_err(|e| e.to_string())?;
+        tokio::fs::rename(&tmp, self.path(&record.id))
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    async fn remove(&self, id: &str) -> Result<(), String> {
+        match tokio::fs::remove_file(self.path(id)).await {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.to_string()),
+            _ => Ok(()),
+        }
+    }
+
+    async fn load_all(&self) -> Result<Vec<SagaRecord>, String> {
+        let mut entries = match tokio::fs::read_dir(&self.dir).await {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.to_string()),
+        };
+        let mut records = Vec::new();
+        while let Some(entry) = entries.next_entry().await.map_err(|e| e.to_string())? {
+            let path = entry.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                continue;
+            }
+            let bytes = tokio::fs::read(&path).await.map_err(|e| e.to_string())?;
+            match serde_json::from_slice(&bytes) {
+                Ok(record) => records.push(record),
+                Err(e) => warn!(path = %path.display(), error = %e, "Skipping unreadable saga record"),
+            }
+        }
+        Ok(records)
+    }
+}
+
+// ============================================================================
+// EVM Chain Adapter
+// ============================================================================
+
+/// Submits feedback to the ERC-8004 Reputation Registry through an EVM provider.
+pub struct EvmFeedbackChain {
+    provider: InnerProvider,
+    reputation_registry: Address,
+}
+
+impl EvmFeedbackChain {
+    pub fn new(provider: InnerProvider, reputation_registry: Address) -> Self {
+        Self {
+            provider,
+            reputation_registry,
+        }
+    }
+}
+
+#[async_trait]
+impl FeedbackChain for EvmFeedbackChain {
+    async fn submit(&self, request: &FeedbackRequest) -> Result<B256, String> {
+        let feedback = &request.feedback;
+        let registry = IReputationRegistry::new(self.reputation_registry, &self.provider);
+        let pending = registry
+            .giveFeedback(
+                alloy::primitives::U256::from(feedback.agent_id),
+                feedback.value,
+                feedback.value_decimals,
+                feedback.tag1.clone(),
+                feedback.tag2.clone(),
+                feedback.endpoint.clone(),
+                feedback.feedback_uri.clone(),
+                feedback.feedback_hash.unwrap_or_default(),
+            )
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(*pending.tx_hash())
+    }
+
+    async fn confirm(&self, transaction: B256) -> Result<Option<u64>, String> {
+        let receipt = PendingTransactionBuilder::new(self.provider.root().clone(), transaction)
+            .with_timeout(Some(CONFIRMATION_TIMEOUT))
+            .get_receipt()
+            .await
+            .map_err(|e| e.to_string())?;
+        if !receipt.status() {
+            return Ok(None);
+        }
+        Ok(receipt.inner.logs().iter().find_map(|log| {
+            log.log_decode::<IReputationRegistry::NewFeedback>()
+                .ok()
+                .map(|event| event.inner.data.feedbackIndex)
+        }))
+    }
+}
+
+// ============================================================================
+// Saga Orchestrator
+// ============================================================================
+
+/// Global saga instance used by the HTTP handlers.
+pub static FEEDBACK_SAGA: Lazy<FeedbackSaga> = Lazy::new(FeedbackSaga::from_env);
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// Runs feedback submissions as compensating sagas.
+#[derive(Debug, Clone)]
+pub struct FeedbackSaga {
+    claims: Arc<dyn ProofClaims>,
+    journal: Arc<dyn FeedbackJournal>,
+    notifier: Arc<dyn FeedbackNotifier>,
+    store: Arc<dyn SagaStore>,
+    expiry_secs: u64,
+}
+
+impl FeedbackSaga {
+    pub fn new(
+        claims: Arc<dyn ProofClaims>,
+        journal: Arc<dyn FeedbackJournal>,
+        notifier: Arc<dyn FeedbackNotifier>,
+        store: Arc<dyn SagaStore>,
+    ) -> Self {
+        Self {
+            claims,
+            journal,
+            notifier,
+            store,
+            expiry_secs: DEFAULT_EXPIRY_SECS,
+        }
+    }
+
+    /// Compensate sagas still unconfirmed `secs` after submission.
+    pub fn with_expiry(mut self, secs: u64) -> Self {
+        self.expiry_secs = secs;
+        self
+    }
+
+    /// In-memory participants, with sagas, proof claims and the journal persisted to
+    /// disk when `FEEDBACK_SAGA_DIR` is set.
+    pub fn from_env() -> Self {
+        let (store, claims, journal): (
+            Arc<dyn SagaStore>,
+            Arc<dyn ProofClaims>,
+            Arc<dyn FeedbackJournal>,
+        ) = match crate::env_registry::var("FEEDBACK_SAGA_DIR") {
+            Some(dir) => {
+                info!(dir = %dir, "Persisting feedback sagas to disk");
+                let path = PathBuf::from(&dir).join(PROOF_CLAIMS_FILE);
+                let claims: Arc<dyn ProofClaims> = match FileProofClaims::open(&path) {
+                    Ok(claims) => Arc::new(claims),
+                    Err(e) => {
+                        // Leave the file alone rather than overwrite claims we could not read
+                        error!(path = %path.display(), error = %e, "Unreadable proof claims, keeping them in memory");
+                        Arc::new(InMemoryProofClaims::default())
+                    }
+                };
+                let path = PathBuf::from(&dir).join(FEEDBACK_JOURNAL_FILE);
+                let journal: Arc<dyn FeedbackJournal> = match FileFeedbackJournal::open(&path) {
+                    Ok(journal) => Arc::new(journal),
+                    Err(e) => {
+                        error!(path = %path.display(), error = %e, "Unreadable feedback journal, keeping it in memory");
+                        Arc::new(InMemoryFeedbackJournal::default())
+                    }
+                };
+                (Arc::new(FileSagaStore::new(dir)), claims, journal)
+            }
+            None => (
+                Arc::new(InMemorySagaStore::default()),
+                Arc::new(InMemoryProofClaims::default()),
+                Arc::new(InMemoryFeedbackJournal::default()),
+            ),
+        };
+        let expiry = crate::env_registry::parse::<u64>("FEEDBACK_SAGA_EXPIRY_SECS")
+            .unwrap_or(DEFAULT_EXPIRY_SECS);
+        Self::new(claims, journal, Arc::new(LogNotifier), store).with_expiry(expiry)
+    }
+
+    async fn save(&self, record: &mut SagaRecord, step: SagaStep) -> Result<(), SagaError> {
+        record.step = step;
+        record.updated_at = now_secs();
+        self.store.save(record).await.map_err(SagaError::Store)
+    }
+
+    /// Run a feedback submission to completion or full compensation.
+    pub async fn run(
+        &self,
+        chain: &dyn FeedbackChain,
+        request: FeedbackRequest,
+    ) -> Result<FeedbackReceipt, SagaError> {
+        let proof_key = match &request.feedback.proof {
+            Some(proof) if !proof.is_hash_consistent() => {
+                return Err(SagaError::InvalidProof("payment hash mismatch".into()));
+            }
+            Some(proof) => Some(proof.payment_hash),
+            None => None,
+        };
+
+        let mut record = SagaRecord {
+            id: format!("{:032x}", rand::random::<u128>()),
+            request,
+            step: SagaStep::Started,
+            proof_key,
+            journal_entry: None,
+            transaction: None,
+            feedback_index: None,
+            updated_at: now_secs(),
+        };
+
+        if let Some(key) = record.proof_key {
+            self.claims.try_claim(key, &record.id).await?;
+        }
+        if let Err(e) = self.save(&mut record, SagaStep::ProofClaimed).await {
+            self.compensate(&record).await;
+            return Err(e);
+        }
+
+        match self.journal.record(&record.id, &record.request).await {
+            Ok(entry) => record.journal_entry = Some(entry),
+            Err(e) => {
+                self.compensate(&record).await;
+                return Err(SagaError::Journal(e));
+            }
+        }
+        if let Err(e) = self.save(&mut record, SagaStep::Journaled).await {
+            self.compensate(&record).await;
+            return Err(e);
+        }
+
+        let transaction = match chain.submit(&record.request).await {
+            Ok(tx) => tx,
+            Err(e) => {
+                self.compensate(&record).await;
+                return Err(SagaError::Submission(e));
+            }
+        };
+        record.transaction = Some(transaction);
+        // The transaction is out; from here on only its on-chain outcome decides
+        if let Err(e) = self.save(&mut record, SagaStep::Submitted).await {
+            error!(saga = %record.id, tx = %transaction, error = %e, "Failed to persist submitted saga");
+        }
+
+        self.resolve_submitted(chain, record).await
+    }
+
+    /// Confirm a submitted transaction, then commit or compensate.
+    async fn resolve_submitted(
+        &self,
+        chain: &dyn FeedbackChain,
+        mut record: SagaRecord,
+    ) -> Result<FeedbackReceipt, SagaError> {
+        let transaction = record.transaction.expect("submitted saga has a transaction");
+        match chain.confirm(transaction).await {
+            Ok(Some(feedback_index)) => {
+                record.feedback_index = Some(feedback_index);
+                self.commit(record).await
+            }
+            Ok(None) => {
+                warn!(saga = %record.id, tx = %transaction, "Feedback transaction reverted, compensating");
+                self.compensate(&record).await;
+                Err(SagaError::Rejected { transaction })
+            }
+            Err(reason) => {
+                warn!(saga = %record.id, tx = %transaction, reason = %reason, "Feedback outcome unknown, leaving saga for recovery");
+                Err(SagaError::Unconfirmed { transaction, reason })
+            }
+        }
+    }
+
+    /// Pivot: make proof consumption permanent and finish forward steps.
+    async fn commit(&self, mut record: SagaRecord) -> Result<FeedbackReceipt, SagaError> {
+        let transaction = record.transaction.expect("committed saga has a transaction");
+        let feedback_index = record.feedback_index.expect("committed saga has a feedback index");
+
+        if let Some(key) = record.proof_key {
+            self.claims.consume(key, &record.id).await;
+        }
+        if let Some(entry) = &record.journal_entry {
+            if let Err(e) = self.journal.commit(entry, transaction, feedback_index).await {
+                error!(saga = %record.id, error = %e, "Failed to commit journal entry for confirmed feedback");
+            }
+        }
+        if let Err(e) = self.save(&mut record, SagaStep::Committed).await {
+            error!(saga = %record.id, error = %e, "Failed to persist committed saga");
+        }
+        self.finish(&record).await
+    }
+
+    /// Notify and drop a committed saga.
+    async fn finish(&self, record: &SagaRecord) -> Result<FeedbackReceipt, SagaError> {
+        let receipt = FeedbackReceipt {
+            saga_id: record.id.clone(),
+            network: record.request.network,
+            agent_id: record.request.feedback.agent_id,
+            transaction: record.transaction.expect("committed saga has a transaction"),
+            feedback_index: record.feedback_index.expect("committed saga has a feedback index"),
+        };
+        if let Err(e) = self.notifier.notify(&receipt).await {
+            warn!(saga = %record.id, error = %e, "Feedback notification failed");
+        }
+        if let Err(e) = self.store.remove(&record.id).await {
+            warn!(saga = %record.id, error = %e, "Failed to remove completed saga");
+        }
+        Ok(receipt)
+    }
+
+    /// Undo completed steps in reverse order.
+    async fn compensate(&self, record: &SagaRecord) {
+        debug!(saga = %record.id, step = ?record.step, "Compensating feedback saga");
+        if let Some(entry) = &record.journal_entry {
+            self.journal.cancel(entry).await;
+        }
+        if let Some(key) = record.proof_key {
+            self.claims.release(key, &record.id).await;
+        }
+        if let Err(e) = self.store.remove(&record.id).await {
+            warn!(saga = %record.id, error = %e, "Failed to remove compensated saga");
+        }
+    }
+
+    /// Resume or compensate sagas interrupted by a crash.
+    ///
+    /// `chain_for` resolves the chain adapter for a saga's network; sagas whose network
+    /// has no adapter are left in the store.
+    pub async fn recover<F>(&self, chain_for: F) -> Vec<(String, Result<FeedbackReceipt, SagaError>)>
+    where
+        F: Fn(Network) -> Option<Box<dyn FeedbackChain>>,
+    {
+        let records = match self.store.load_all().await {
+            Ok(records) => records,
+            Err(e) => {
+                error!(error = %e, "Failed to load feedback sagas for recovery");
+                return Vec::new();
+            }
+        };
+
+        let mut results = Vec::new();
+        for record in records {
+            let id = record.id.clone();
+            // Claims may not survive a restart; re-acquire before deciding
+            if let Some(key) = record.proof_key {
+                if record.step != SagaStep::Committed {
+                    if let Err(e) = self.claims.try_claim(key, &id).await {
+                        warn!(saga = %id, error = %e, "Proof claimed elsewhere during recovery");
+                    }
+                }
+            }
+            let result = match record.step {
+                SagaStep::Started | SagaStep::ProofClaimed | SagaStep::Journaled => {
+                    info!(saga = %id, step = ?record.step, "Compensating interrupted feedback saga");
+                    self.compensate(&record).await;
+                    continue;
+                }
+                SagaStep::Submitted => match chain_for(record.request.network) {
+                    Some(chain) => self.resolve_submitted(chain.as_ref(), record).await,
+                    None => {
+                        warn!(saga = %id, network = %record.request.network, "No chain adapter to recover saga");
+                        continue;
+                    }
+                },
+                SagaStep::Committed => self.finish(&record).await,
+            };
+            results.push((id, result));
+        }
+        results
+    }
+
+    /// Re-confirm sagas whose transaction outcome was unknown at `now`.
+    ///
+    /// Sagas submitted within the confirmation timeout are skipped, as [`Self::run`] may
+    /// still be waiting on them. Sagas still unconfirmed once the expiry has passed since
+    /// submission are taken as dropped and compensated with [`SagaError::Expired`].
+    pub async fn retry_unconfirmed<F>(
+        &self,
+        chain_for: F,
+        now: u64,
+    ) -> Vec<(String, Result<FeedbackReceipt, SagaError>)>
+    where
+        F: Fn(Network) -> Option<Box<dyn FeedbackChain>>,
+    {
+        let records = match self.store.load_all().await {
+            Ok(records) => records,
+            Err(e) => {
+                error!(error = %e, "Failed to load feedback sagas for retry");
+                return Vec::new();
+            }
+        };
+
+        let mut results = Vec::new();
+        for record in records {
+            if record.step != SagaStep::Submitted
+                || now < record.updated_at + CONFIRMATION_TIMEOUT.as_secs()
+            {
+                continue;
+            }
+            let id = record.id.clone();
+            let transaction = record.transaction.expect("submitted saga has a transaction");
+            let result = match chain_for(record.request.network) {
+                Some(chain) => match self.resolve_submitted(chain.as_ref(), record.clone()).await {
+                    Err(SagaError::Unconfirmed { .. })
+                        if now >= record.updated_at.saturating_add(self.expiry_secs) =>
+                    {
+                        warn!(saga = %id, tx = %transaction, "Feedback transaction expired unconfirmed, compensating");
+                        self.compensate(&record).await;
+                        Err(SagaError::Expired { transaction })
+                    }
+                    result => result,
+                },
+                None => {
+                    warn!(saga = %id, network = %record.request.network, "No chain adapter to retry saga");
+                    continue;
+                }
+            };
+            results.push((id, result));
+        }
+        results
+    }
+}
+
+/// Retry unconfirmed sagas of `saga` every `interval`.
+pub fn start_retry_task<F>(saga: FeedbackSaga, chain_for: F, interval: Duration) -> JoinHandle<()>
+where
+    F: Fn(Network) -> Option<Box<dyn FeedbackChain>> + Send + Sync + 'static,
+{
+    info!(interval_secs = interval.as_secs(), "Starting feedback saga retry task");
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            for (id, result) in saga.retry_unconfirmed(&chain_for, now_secs()).await {
+                if let Ok(receipt) = result {
+                    info!(saga = %id, tx = %receipt.transaction, "Confirmed feedback saga on retry");
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::erc8004::{FeedbackParams, ProofOfPayment};
+    use crate::types::{
+        MixedAddress, SettleConfirmation, TokenAmount, TransactionHash, X402Version,
+    };
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn proof() -> ProofOfPayment {
+        ProofOfPayment::new(
+            TransactionHash::Evm([7u8; 32]),
+            SettleConfirmation {
+                block_number: 100,
+                block_timestamp: Some(1_700_000_000),
+                gas_used: None,
+            },
+            Network::EthereumSepolia,
+            MixedAddress::Offchain("payer".into()),
+            MixedAddress::Offchain("payee".into()),
+            TokenAmount::from(1_000u64),
+            MixedAddress::Offchain("usdc".into()),
+        )
+    }
+
+    fn request(proof: ProofOfPayment) -> FeedbackRequest {
+        FeedbackRequest {
+            x402_version: X402Version::V1,
+            network: Network::EthereumSepolia,
+            feedback: FeedbackParams {
+                agent_id: 42,
+                value: 90,
+                value_decimals: 0,
+                tag1: "starred".into(),
+                tag2: String::new(),
+                endpoint: String::new(),
+                feedback_uri: String::new(),
+                feedback_hash: None,
+                proof: Some(proof),
+            },
+            feedback_file: None,
+        }
+    }
+
+    /// Journal that fails the first `fail_records` recordings.
+    #[derive(Debug, Default)]
+    struct FlakyJournal {
+        inner: InMemoryFeedbackJournal,
+        fail_records: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl FeedbackJournal for FlakyJournal {
+        async fn record(&self, saga_id: &str, request: &FeedbackRequest) -> Result<String, String> {
+            if self.fail_records.load(Ordering::SeqCst) > 0 {
+                self.fail_records.fetch_sub(1, Ordering::SeqCst);
+                return Err("journal unavailable".into());
+            }
+            self.inner.record(saga_id, request).await
+        }
+        async fn commit(&self, entry: &str, tx: B256, index: u64) -> Result<(), String> {
+            self.inner.commit(entry, tx, index).await
+        }
+        async fn cancel(&self, entry: &str) {
+            self.inner.cancel(entry).await
+        }
+    }
+
+    #[derive(Debug, Default, Clone, Copy, PartialEq)]
+    enum Confirm {
+        #[default]
+        Event,
+        Reverted,
+        Unknown,
+    }
+
+    #[derive(Default)]
+    struct MockChain {
+        fail_submits: AtomicUsize,
+        confirm: std::sync::Mutex<Vec<Confirm>>,
+        submissions: AtomicUsize,
+        delay: Option<Duration>,
+    }
+
+    #[async_trait]
+    impl FeedbackChain for MockChain {
+        async fn submit(&self, _request: &FeedbackRequest) -> Result<B256, String> {
+            if let Some(delay) = self.delay {
+                tokio::time::sleep(delay).await;
+            }
+            if self.fail_submits.load(Ordering::SeqCst) > 0 {
+                self.fail_submits.fetch_sub(1, Ordering::SeqCst);
+                return Err("rpc down".into());
+            }
+            let n = self.submissions.fetch_add(1, Ordering::SeqCst);
+            Ok(B256::with_last_byte(n as u8 + 1))
+        }
+
+        async fn confirm(&self, _transaction: B256) -> Result<Option<u64>, String> {
+            let outcome = self.confirm.lock().unwrap().pop().unwrap_or_default();
+            match outcome {
+                Confirm::Event => Ok(Some(self.submissions.load(Ordering::SeqCst) as u64)),
+                Confirm::Reverted => Ok(None),
+                Confirm::Unknown => Err("timeout".into()),
+            }
+        }
+    }
+
+    #[derive(Debug, Default)]
+    struct CountingClaims {
+        inner: InMemoryProofClaims,
+        consumed: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl ProofClaims for CountingClaims {
+        async fn try_claim(&self, key: FixedBytes<32>, saga_id: &str) -> Result<(), SagaError> {
+            self.inner.try_claim(key, saga_id).await
+        }
+        async fn release(&self, key: FixedBytes<32>, saga_id: &str) {
+            self.inner.release(key, saga_id).await
+        }
+        async fn consume(&self, key: FixedBytes<32>, saga_id: &str) {
+            self.consumed.fetch_add(1, Ordering::SeqCst);
+            self.inner.consume(key, saga_id).await
+        }
+    }
+
+    struct Harness {
+        saga: FeedbackSaga,
+        claims: Arc<CountingClaims>,
+        journal: Arc<FlakyJournal>,
+        store: Arc<InMemorySagaStore>,
+    }
+
+    fn harness() -> Harness {
+        let claims = Arc::new(CountingClaims::default());
+        let journal = Arc::new(FlakyJournal::default());
+        let store = Arc::new(InMemorySagaStore::default());
+        let saga = FeedbackSaga::new(
+            claims.clone(),
+            journal.clone(),
+            Arc::new(LogNotifier),
+            store.clone(),
+        );
+        Harness {
+            saga,
+            claims,
+            journal,
+            store,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_success_consumes_proof_exactly_once() {
+        let h = harness();
+        let chain = MockChain::default();
+        let receipt = h.saga.run(&chain, request(proof())).await.unwrap();
+        assert_eq!(receipt.feedback_index, 1);
+        assert_eq!(h.claims.consumed.load(Ordering::SeqCst), 1);
+        assert_eq!(
+            h.journal.inner.status(&format!("journal-{}", receipt.saga_id)).await,
+            Some(JournalStatus::Committed {
+                transaction: receipt.transaction,
+                feedback_index: 1
+            })
+        );
+        assert!(h.store.load_all().await.unwrap().is_empty());
+
+        // Reusing the proof is rejected without touching the chain
+        let err = h.saga.run(&chain, request(proof())).await.unwrap_err();
+        assert!(matches!(err, SagaError::ProofAlreadyUsed));
+        assert_eq!(chain.submissions.load(Ordering::SeqCst), 1);
+        assert_eq!(h.claims.consumed.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_failure_at_each_step_allows_retry() {
+        // Journal failure
+        let h = harness();
+        h.journal.fail_records.store(1, Ordering::SeqCst);
+        let chain = MockChain::default();
+        assert!(matches!(
+            h.saga.run(&chain, request(proof())).await,
+            Err(SagaError::Journal(_))
+        ));
+        assert!(h.saga.run(&chain, request(proof())).await.is_ok());
+
+        // Submission failure
+        let h = harness();
+        let chain = MockChain {
+            fail_submits: AtomicUsize::new(1),
+            ..Default::default()
+        };
+        assert!(matches!(
+            h.saga.run(&chain, request(proof())).await,
+            Err(SagaError::Submission(_))
+        ));
+        assert!(h.store.load_all().await.unwrap().is_empty());
+        assert!(h.saga.run(&chain, request(proof())).await.is_ok());
+
+        // Mined without NewFeedback
+        let h = harness();
+        let chain = MockChain {
+            confirm: std::sync::Mutex::new(vec![Confirm::Reverted]),
+            ..Default::default()
+        };
+        assert!(matches!(
+            h.saga.run(&chain, request(proof())).await,
+            Err(SagaError::Rejected { .. })
+        ));
+        assert_eq!(h.claims.consumed.load(Ordering::SeqCst), 0);
+        assert!(h.saga.run(&chain, request(proof())).await.is_ok());
+        assert_eq!(h.claims.consumed.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_tampered_proof_is_rejected() {
+        let h = harness();
+        let mut bad = proof();
+        bad.amount = TokenAmount::from(1u64);
+        let err = h.saga.run(&MockChain::default(), request(bad)).await.unwrap_err();
+        assert!(matches!(err, SagaError::InvalidProof(_)));
+    }
+
+    #[tokio::test]
+    async fn test_crash_resume_and_compensation() {
+        let dir = std::env::temp_dir().join(format!("x402-saga-{:x}", rand::random::<u64>()));
+        let store = Arc::new(FileSagaStore::new(&dir));
+
+        // Saga interrupted after journaling: compensated on restart
+        let journaled = SagaRecord {
+            id: "crashed-early".into(),
+            request: request(proof()),
+            step: SagaStep::Journaled,
+            proof_key: Some(proof().payment_hash),
+            journal_entry: Some("journal-crashed-early".into()),
+            transaction: None,
+            feedback_index: None,
+            updated_at: 0,
+        };
+        store.save(&journaled).await.unwrap();
+
+        let saga = FeedbackSaga::new(
+            Arc::new(InMemoryProofClaims::default()),
+            Arc::new(InMemoryFeedbackJournal::default()),
+            Arc::new(LogNotifier),
+            store.clone(),
+        );
+        let results = saga.recover(|_| Some(Box::new(MockChain::default()) as Box<dyn FeedbackChain>)).await;
+        assert!(results.is_empty());
+        assert!(store.load_all().await.unwrap().is_empty());
+        // Proof is free again
+        assert!(saga.run(&MockChain::default(), request(proof())).await.is_ok());
+
+        // Saga interrupted after submission: confirmed and committed on restart
+        let other = ProofOfPayment::new(
+            TransactionHash::Evm([9u8; 32]),
+            SettleConfirmation {
+                block_number: 1,
+                block_timestamp: Some(0),
+                gas_used: None,
+            },
+            Network::EthereumSepolia,
+            MixedAddress::Offchain("payer".into()),
+            MixedAddress::Offchain("payee".into()),
+            TokenAmount::from(5u64),
+            MixedAddress::Offchain("usdc".into()),
+        );
+        let submitted = SagaRecord {
+            id: "crashed-late".into(),
+            request: request(other.clone()),
+            step: SagaStep::Submitted,
+            proof_key: Some(other.payment_hash),
+            journal_entry: None,
+            transaction: Some(B256::with_last_byte(9)),
+            feedback_index: None,
+            updated_at: 0,
+        };
+        store.save(&submitted).await.unwrap();
+        let results = saga.recover(|_| Some(Box::new(MockChain::default()) as Box<dyn FeedbackChain>)).await;
+        assert_eq!(results.len(), 1);
+        assert!(results[0].1.is_ok());
+        assert!(matches!(
+            saga.run(&MockChain::default(), request(other)).await,
+            Err(SagaError::ProofAlreadyUsed)
+        ));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_file_claims_survive_restart() {
+        let dir = std::env::temp_dir().join(format!("x402-claims-{:x}", rand::random::<u64>()));
+        let path = dir.join(PROOF_CLAIMS_FILE);
+        let (used, held, released) = (
+            FixedBytes::repeat_byte(1),
+            FixedBytes::repeat_byte(2),
+            FixedBytes::repeat_byte(3),
+        );
+
+        let claims = FileProofClaims::open(&path).unwrap();
+        claims.try_claim(used, "a").await.unwrap();
+        claims.consume(used, "a").await;
+        claims.try_claim(held, "b").await.unwrap();
+        claims.try_claim(released, "c").await.unwrap();
+        claims.release(released, "c").await;
+        drop(claims);
+
+        let reopened = FileProofClaims::open(&path).unwrap();
+        assert!(matches!(
+            reopened.try_claim(used, "d").await,
+            Err(SagaError::ProofAlreadyUsed)
+        ));
+        assert!(matches!(
+            reopened.try_claim(held, "d").await,
+            Err(SagaError::ProofInUse)
+        ));
+        assert!(reopened.try_claim(released, "d").await.is_ok());
+        // The claims file is not picked up as a saga record
+        assert!(FileSagaStore::new(&dir).load_all().await.unwrap().is_empty());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_file_journal_survives_restart() {
+        let dir = std::env::temp_dir().join(format!("x402-journal-{:x}", rand::random::<u64>()));
+        let path = dir.join(FEEDBACK_JOURNAL_FILE);
+        let transaction = B256::with_last_byte(1);
+
+        let journal = FileFeedbackJournal::open(&path).unwrap();
+        let committed = journal.record("a", &request(proof())).await.unwrap();
+        journal.commit(&committed, transaction, 3).await.unwrap();
+        let cancelled = journal.record("b", &request(proof())).await.unwrap();
+        journal.cancel(&cancelled).await;
+        let pending = journal.record("c", &request(proof())).await.unwrap();
+        drop(journal);
+
+        let reopened = FileFeedbackJournal::open(&path).unwrap();
+        assert_eq!(
+            reopened.status(&committed).await,
+            Some(JournalStatus::Committed {
+                transaction,
+                feedback_index: 3
+            })
+        );
+        assert_eq!(reopened.status(&cancelled).await, Some(JournalStatus::Cancelled));
+        assert_eq!(reopened.status(&pending).await, Some(JournalStatus::Pending));
+        // The journal file is not picked up as a saga record
+        assert!(FileSagaStore::new(&dir).load_all().await.unwrap().is_empty());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_unconfirmed_saga_is_retried_then_expired() {
+        fn chain(outcome: Confirm) -> Option<Box<dyn FeedbackChain>> {
+            Some(Box::new(MockChain {
+                confirm: std::sync::Mutex::new(vec![outcome]),
+                ..Default::default()
+            }) as Box<dyn FeedbackChain>)
+        }
+        let h = harness();
+        let unknown = MockChain {
+            confirm: std::sync::Mutex::new(vec![Confirm::Unknown]),
+            ..Default::default()
+        };
+        let transaction = match h.saga.run(&unknown, request(proof())).await {
+            Err(SagaError::Unconfirmed { transaction, .. }) => transaction,
+            other => panic!("expected an unconfirmed saga, got {other:?}"),
+        };
+        let submitted_at = h.store.load_all().await.unwrap()[0].updated_at;
+
+        // Left alone while the submitting request may still be waiting on it
+        assert!(h
+            .saga
+            .retry_unconfirmed(|_| chain(Confirm::Event), submitted_at)
+            .await
+            .is_empty());
+
+        // Still unknown after the confirmation timeout: kept for the next retry
+        let retry_at = submitted_at + CONFIRMATION_TIMEOUT.as_secs();
+        let results = h.saga.retry_unconfirmed(|_| chain(Confirm::Unknown), retry_at).await;
+        assert!(matches!(results[0].1, Err(SagaError::Unconfirmed { .. })));
+        assert_eq!(h.store.load_all().await.unwrap().len(), 1);
+
+        // Still unknown once expired: compensated, so the proof can back feedback again
+        let expire_at = submitted_at + DEFAULT_EXPIRY_SECS;
+        let results = h.saga.retry_unconfirmed(|_| chain(Confirm::Unknown), expire_at).await;
+        assert!(matches!(
+            results[0].1,
+            Err(SagaError::Expired { transaction: expired }) if expired == transaction
+        ));
+        assert!(h.store.load_all().await.unwrap().is_empty());
+        assert_eq!(h.claims.consumed.load(Ordering::SeqCst), 0);
+
+        // A retry that finds the NewFeedback event commits the saga
+        let unknown = MockChain {
+            confirm: std::sync::Mutex::new(vec![Confirm::Unknown]),
+            ..Default::default()
+        };
+        assert!(h.saga.run(&unknown, request(proof())).await.is_err());
+        let resubmitted_at = h.store.load_all().await.unwrap()[0].updated_at;
+        let retry_at = resubmitted_at + CONFIRMATION_TIMEOUT.as_secs();
+        let results = h.saga.retry_unconfirmed(|_| chain(Confirm::Event), retry_at).await;
+        assert!(results[0].1.is_ok());
+        assert!(h.store.load_all().await.unwrap().is_empty());
+        assert_eq!(h.claims.consumed.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_unknown_outcome_stays_pending() {
+        let h = harness();
+        let chain = MockChain {
+            confirm: std::sync::Mutex::new(vec![Confirm::Unknown]),
+            ..Default::default()
+        };
+        assert!(matches!(
+            h.saga.run(&chain, request(proof())).await,
+            Err(SagaError::Unconfirmed { .. })
+        ));
+        let pending = h.store.load_all().await.unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].step, SagaStep::Submitted);
+        // Still claimed, so a concurrent retry cannot double-submit
+        assert!(matches!(
+            h.saga.run(&chain, request(proof())).await,
+            Err(SagaError::ProofInUse)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_sagas_serialize_on_proof_claim() {
+        let h = harness();
+        let chain = MockChain {
+            delay: Some(Duration::from_millis(50)),
+            ..Default::default()
+        };
+        let (a, b) = tokio::join!(
+            h.saga.run(&chain, request(proof())),
+            h.saga.run(&chain, request(proof()))
+        );
+        let (ok, err) = if a.is_ok() { (a, b) } else { (b, a) };
+        assert!(ok.is_ok());
+        assert!(matches!(err, Err(SagaError::ProofInUse)));
+        assert_eq!(chain.submissions.load(Ordering::SeqCst), 1);
+        assert_eq!(h.claims.consumed.load(Ordering::SeqCst), 1);
+    }
+}
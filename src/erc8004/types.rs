@@ -3,11 +3,12 @@
 //! These types represent the data structures used in the `8004-reputation` extension
 //! and match the official ERC-8004 specification.
 
-use alloy::primitives::{FixedBytes, U256};
+use alloy::primitives::{Address, Bytes, FixedBytes, Signature, U256};
+use alloy::signers::SignerSync;
 use serde::{Deserialize, Serialize};
 
 use crate::network::Network;
-use crate::types::{MixedAddress, TokenAmount, TransactionHash};
+use crate::types::{MixedAddress, SettleConfirmation, TokenAmount, TransactionHash};
 
 // ============================================================================
 // Identity Registry Types
@@ -85,6 +86,7 @@ pub struct AgentRegistration {
 
 /// Parameters for submitting reputation feedback (matches official spec).
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-gen", derive(ts_rs::TS))]
 #[serde(rename_all = "camelCase")]
 pub struct FeedbackParams {
     /// The agent's ID (tokenId in Identity Registry)
@@ -116,15 +118,47 @@ pub struct FeedbackParams {
 
     /// Keccak256 hash of feedback content (for integrity)
     #[serde(default)]
+    #[cfg_attr(feature = "ts-gen", ts(as = "Option<String>"))]
     pub feedback_hash: Option<FixedBytes<32>>,
 
     /// Proof of payment (required for authorized feedback)
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "ts-gen", ts(optional))]
     pub proof: Option<ProofOfPayment>,
 }
 
+/// Largest `valueDecimals` accepted for feedback.
+pub const MAX_VALUE_DECIMALS: u8 = 18;
+
+/// Longest `tag1`/`tag2` accepted for feedback, in bytes.
+pub const MAX_TAG_LENGTH: usize = 64;
+
+impl FeedbackParams {
+    /// Check the parameters before anything is sent on-chain.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.value_decimals > MAX_VALUE_DECIMALS {
+            return Err(format!(
+                "valueDecimals must be at most {}, got {}",
+                MAX_VALUE_DECIMALS, self.value_decimals
+            ));
+        }
+        for (name, tag) in [("tag1", &self.tag1), ("tag2", &self.tag2)] {
+            if tag.len() > MAX_TAG_LENGTH {
+                return Err(format!(
+                    "{} must be at most {} bytes, got {}",
+                    name,
+                    MAX_TAG_LENGTH,
+                    tag.len()
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
 /// Request body for POST /feedback endpoint.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-gen", derive(ts_rs::TS))]
 #[serde(rename_all = "camelCase")]
 pub struct FeedbackRequest {
     /// x402 protocol version
@@ -133,22 +167,31 @@ pub struct FeedbackRequest {
     pub network: Network,
     /// Feedback parameters
     pub feedback: FeedbackParams,
+    /// Feedback file for the facilitator to store; its URI and hash become the
+    /// feedback's `feedbackUri` and `feedbackHash`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "ts-gen", ts(optional))]
+    pub feedback_file: Option<FeedbackFile>,
 }
 
 /// Response from POST /feedback endpoint.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-gen", derive(ts_rs::TS))]
 #[serde(rename_all = "camelCase")]
 pub struct FeedbackResponse {
     /// Whether the feedback was successfully submitted
     pub success: bool,
     /// Transaction hash of the feedback submission
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "ts-gen", ts(optional))]
     pub transaction: Option<TransactionHash>,
     /// Feedback index assigned (1-indexed)
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "ts-gen", ts(optional))]
     pub feedback_index: Option<u64>,
     /// Error message (if failed)
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "ts-gen", ts(optional))]
     pub error: Option<String>,
     /// Network where feedback was submitted
     pub network: Network,
@@ -178,6 +221,78 @@ pub struct AppendResponseRequest {
     pub response_hash: Option<FixedBytes<32>>,
 }
 
+/// Metadata entry set on an agent at registration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentMetadataParam {
+    pub key: String,
+    /// Stored on-chain as its UTF-8 bytes
+    pub value: String,
+}
+
+/// Request to register an agent (`POST /identity/register`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegisterAgentRequest {
+    pub network: Network,
+    /// URI of the agent registration file
+    pub agent_uri: String,
+    #[serde(default)]
+    pub metadata: Vec<AgentMetadataParam>,
+    /// Address the agent NFT is transferred to; it stays with the facilitator when unset
+    #[serde(default)]
+    pub owner: Option<Address>,
+}
+
+/// Response from `POST /identity/register`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegisterAgentResponse {
+    pub success: bool,
+    /// ID assigned to the new agent
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub agent_id: Option<u64>,
+    /// Owner of the agent NFT once the request completed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub owner: Option<Address>,
+    /// Hash of the `register` transaction
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transaction: Option<TransactionHash>,
+    /// Hash of the transaction handing the NFT to the requested owner
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transfer_transaction: Option<TransactionHash>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub network: Network,
+}
+
+/// Request to set an agent's payment wallet (`POST /identity/{agent_id}/wallet`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetAgentWalletRequest {
+    pub network: Network,
+    pub new_wallet: Address,
+    /// Unix timestamp the signature expires at
+    pub deadline: u64,
+    /// EIP-712 `AgentWalletSet` signature by `new_wallet`, see
+    /// [`build_set_agent_wallet_payload`](crate::erc8004::wallet::build_set_agent_wallet_payload)
+    pub signature: Bytes,
+}
+
+/// Response from `POST /identity/{agent_id}/wallet`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetAgentWalletResponse {
+    pub success: bool,
+    pub agent_id: u64,
+    pub wallet: Address,
+    /// Hash of the `setAgentWallet` transaction
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transaction: Option<TransactionHash>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub network: Network,
+}
+
 /// Reputation summary for an agent
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -215,7 +330,7 @@ pub struct FeedbackEntry {
 }
 
 /// Request to get reputation summary
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GetReputationRequest {
     /// Filter by specific clients (empty = all)
@@ -237,15 +352,125 @@ pub struct ReputationResponse {
     pub summary: ReputationSummary,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub feedback: Option<Vec<FeedbackEntry>>,
+    /// Feedback entries matching the filters, before `offset`/`limit` were applied
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub feedback_total: Option<usize>,
+    /// Where `feedback` was read from, when included
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<FeedbackSource>,
     pub network: Network,
 }
 
+/// Where the feedback entries of a [`ReputationResponse`] were read from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FeedbackSource {
+    /// The local index of Reputation Registry events (see [`super::indexer`])
+    Cache,
+    /// `readAllFeedback` on the Reputation Registry
+    Chain,
+}
+
+/// Default cap on the feedback entries returned by one reputation query.
+pub const DEFAULT_MAX_FEEDBACK_PAGE: usize = 100;
+
+/// Window of feedback entries returned by a reputation query.
+///
+/// `readAllFeedback` has no pagination of its own, so the page is cut from the full
+/// list after the contract read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeedbackPage {
+    pub offset: usize,
+    pub limit: usize,
+}
+
+impl FeedbackPage {
+    /// Page at `offset` (default: 0) of `limit` entries (default and cap: `max`).
+    pub fn new(offset: Option<usize>, limit: Option<usize>, max: usize) -> Self {
+        Self {
+            offset: offset.unwrap_or(0),
+            limit: limit.map_or(max, |limit| limit.min(max)),
+        }
+    }
+
+    /// Cut this page from `entries`.
+    pub fn apply<T>(&self, entries: Vec<T>) -> Vec<T> {
+        entries
+            .into_iter()
+            .skip(self.offset)
+            .take(self.limit)
+            .collect()
+    }
+}
+
+/// Response for a batch reputation query (`GET /reputation/batch`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReputationBatchResponse {
+    /// One entry per requested agent, in request order
+    pub results: Vec<ReputationResponse>,
+    /// How many results were served from the cache instead of the chain
+    pub cached_count: usize,
+}
+
+/// Reputation of an agent in one of the registries listed in its registration file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkReputation {
+    /// Registry as listed in the registration file: `{namespace}:{chainId}:{address}`
+    pub agent_registry: String,
+    /// Network of the registry, when recognized
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub network: Option<Network>,
+    pub agent_id: u64,
+    /// Unfiltered summary, absent when the registry could not be read
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<ReputationSummary>,
+    /// Whether the summary was served from the cache instead of the chain
+    #[serde(default)]
+    pub cached: bool,
+    /// Why there is no summary for this registry
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Response for a cross-network reputation query (`GET /reputation/aggregate/:agentUri`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AggregatedReputationResponse {
+    pub agent_uri: String,
+    /// One entry per registration, in registration file order
+    pub networks: Vec<NetworkReputation>,
+    /// Feedback entries across the registries that answered
+    pub total_count: u64,
+    /// Combined score, absent when no registry has feedback
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub weighted_score: Option<f64>,
+    /// How `weighted_score` is computed
+    pub weighting: String,
+}
+
+/// Response for an identity lookup (`GET /identity/:network/:agentId`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentIdentityResponse {
+    /// On-chain identity
+    #[serde(flatten)]
+    pub identity: AgentIdentity,
+    /// Registration file resolved from `agentUri`, if it could be fetched and parsed
+    pub registration: Option<AgentRegistrationFile>,
+    /// Why the registration file could not be resolved
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<String>,
+}
+
 // ============================================================================
 // Proof of Payment
 // ============================================================================
 
 /// Cryptographic proof of a settled payment for reputation submission.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-gen", derive(ts_rs::TS))]
 #[serde(rename_all = "camelCase")]
 pub struct ProofOfPayment {
     /// Transaction hash of the settled payment
@@ -262,25 +487,46 @@ pub struct ProofOfPayment {
     pub amount: TokenAmount,
     /// Token contract address
     pub token: MixedAddress,
-    /// Unix timestamp of the block
-    pub timestamp: u64,
+    /// Unix timestamp of the block, when the block could be fetched at settlement
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "ts-gen", ts(optional))]
+    pub timestamp: Option<u64>,
+    /// Version of the `payment_hash` computation; proofs without one use version 0,
+    /// which zero-pads non-EVM transaction hashes
+    #[serde(default)]
+    pub version: u8,
     /// Keccak256 hash of the payment data for verification
+    #[cfg_attr(feature = "ts-gen", ts(type = "string"))]
     pub payment_hash: FixedBytes<32>,
+    /// EIP-191 signature of `payment_hash` by the settling facilitator, so the proof can
+    /// be checked without chain access; not part of `payment_hash`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "ts-gen", ts(optional, type = "string"))]
+    pub facilitator_signature: Option<Bytes>,
 }
 
 impl ProofOfPayment {
-    /// Create a new ProofOfPayment from settlement data.
+    /// Version of the `payment_hash` computation new proofs use.
+    pub const VERSION: u8 = 1;
+
+    /// Create a new ProofOfPayment from settlement data, for `transaction_hash` as
+    /// confirmed by `confirmation`.
     pub fn new(
         transaction_hash: TransactionHash,
-        block_number: u64,
+        confirmation: SettleConfirmation,
         network: Network,
         payer: MixedAddress,
         payee: MixedAddress,
         amount: TokenAmount,
         token: MixedAddress,
-        timestamp: u64,
     ) -> Self {
+        let SettleConfirmation {
+            block_number,
+            block_timestamp: timestamp,
+            ..
+        } = confirmation;
         let payment_hash = Self::compute_payment_hash(
+            Self::VERSION,
             &transaction_hash,
             block_number,
             &payer,
@@ -297,12 +543,56 @@ impl ProofOfPayment {
             amount,
             token,
             timestamp,
+            version: Self::VERSION,
             payment_hash,
+            facilitator_signature: None,
         }
     }
 
+    /// Sign `payment_hash` with the facilitator's `signer` (EIP-191).
+    pub fn signed_by(mut self, signer: &impl SignerSync) -> Result<Self, alloy::signers::Error> {
+        let signature = signer.sign_message_sync(self.payment_hash.as_slice())?;
+        self.facilitator_signature = Some(Bytes::from(signature.as_bytes()));
+        Ok(self)
+    }
+
+    /// Whether the proof carries a signature by `facilitator_address` over a `payment_hash`
+    /// that matches the proof's core fields.
+    ///
+    /// This checks the proof offline against a facilitator the caller trusts, whose
+    /// address `GET /proof-signer` lists; [`verify`](Self::verify) checks the chain.
+    pub fn verify_signature(&self, facilitator_address: Address) -> bool {
+        let Some(signature) = &self.facilitator_signature else {
+            return false;
+        };
+        let Ok(signature) = Signature::try_from(signature.as_ref()) else {
+            return false;
+        };
+        self.is_hash_consistent()
+            && signature
+                .recover_address_from_msg(self.payment_hash.as_slice())
+                .is_ok_and(|signer| signer == facilitator_address)
+    }
+
+    /// Whether `payment_hash` matches the proof's core fields under its `version`.
+    pub fn is_hash_consistent(&self) -> bool {
+        self.version <= Self::VERSION
+            && Self::compute_payment_hash(
+                self.version,
+                &self.transaction_hash,
+                self.block_number,
+                &self.payer,
+                &self.payee,
+                &self.amount,
+            ) == self.payment_hash
+    }
+
     /// Compute the payment hash from core fields.
+    ///
+    /// Version 1 prefixes the transaction hash bytes with a chain discriminator byte;
+    /// version 0 hashes EVM transactions only and zero-pads every other chain's.
     fn compute_payment_hash(
+        version: u8,
         transaction_hash: &TransactionHash,
         block_number: u64,
         payer: &MixedAddress,
@@ -313,9 +603,14 @@ impl ProofOfPayment {
 
         let mut data = Vec::new();
 
-        match transaction_hash {
-            TransactionHash::Evm(bytes) => data.extend_from_slice(bytes),
-            _ => data.extend_from_slice(&[0u8; 32]),
+        match (version, transaction_hash) {
+            (0, TransactionHash::Evm(bytes)) => data.extend_from_slice(bytes),
+            (0, _) => data.extend_from_slice(&[0u8; 32]),
+            (_, transaction_hash) => {
+                let (discriminator, bytes) = transaction_hash_bytes(transaction_hash);
+                data.push(discriminator);
+                data.extend_from_slice(&bytes);
+            }
         }
 
         data.extend_from_slice(&block_number.to_be_bytes());
@@ -330,6 +625,30 @@ impl ProofOfPayment {
     }
 }
 
+/// Chain discriminator and raw bytes of a transaction hash, for the version 1 payment hash.
+///
+/// Algorand transaction IDs are base32-decoded and Sui digests base58-decoded; an ID that
+/// does not decode is hashed as its string bytes.
+fn transaction_hash_bytes(transaction_hash: &TransactionHash) -> (u8, Vec<u8>) {
+    match transaction_hash {
+        TransactionHash::Evm(bytes) => (0x01, bytes.to_vec()),
+        TransactionHash::Solana(signature) => (0x02, signature.to_vec()),
+        TransactionHash::Near(bytes) => (0x03, bytes.to_vec()),
+        TransactionHash::Stellar(bytes) => (0x04, bytes.to_vec()),
+        TransactionHash::Algorand(tx_id) => (
+            0x05,
+            crate::types::decode_base32(tx_id).unwrap_or_else(|| tx_id.as_bytes().to_vec()),
+        ),
+        #[cfg(feature = "sui")]
+        TransactionHash::Sui(digest) => (
+            0x06,
+            bs58::decode(digest)
+                .into_vec()
+                .unwrap_or_else(|_| digest.as_bytes().to_vec()),
+        ),
+    }
+}
+
 // ============================================================================
 // Extension Types
 // ============================================================================
@@ -363,6 +682,7 @@ impl Erc8004Extension {
 
 /// Off-chain feedback file structure (for feedbackURI)
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-gen", derive(ts_rs::TS))]
 #[serde(rename_all = "camelCase")]
 pub struct FeedbackFile {
     /// Type identifier
@@ -378,6 +698,7 @@ fn default_feedback_type() -> String {
 
 /// Individual entry in a feedback file
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-gen", derive(ts_rs::TS))]
 #[serde(rename_all = "camelCase")]
 pub struct FeedbackFileEntry {
     pub timestamp: u64,
@@ -387,6 +708,7 @@ pub struct FeedbackFileEntry {
     #[serde(default)]
     pub value_decimals: u8,
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "ts-gen", ts(optional))]
     pub payment_info: Option<PaymentInfo>,
     #[serde(default)]
     pub interactions: Vec<String>,
@@ -394,6 +716,7 @@ pub struct FeedbackFileEntry {
 
 /// Payment info in feedback file
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-gen", derive(ts_rs::TS))]
 #[serde(rename_all = "camelCase")]
 pub struct PaymentInfo {
     pub token: String,
@@ -464,11 +787,56 @@ pub struct ValidationStatus {
     pub last_update: u64,
 }
 
+/// Result of `POST /validation/request`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidationRequestResponse {
+    pub success: bool,
+    /// Hash identifying the request in the Validation Registry
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_hash: Option<FixedBytes<32>>,
+    /// Hash of the `validationRequest` transaction
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transaction: Option<TransactionHash>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub network: Network,
+}
+
+/// Result of `POST /validation/response`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidationResponseResult {
+    pub success: bool,
+    pub request_hash: FixedBytes<32>,
+    /// Hash of the `validationResponse` transaction
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transaction: Option<TransactionHash>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub network: Network,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::types::X402Version;
 
+    #[test]
+    fn test_feedback_page() {
+        let page = FeedbackPage::new(None, None, 3);
+        assert_eq!(page, FeedbackPage { offset: 0, limit: 3 });
+        assert_eq!(page.apply((0..10).collect()), vec![0, 1, 2]);
+
+        // The limit is capped, the offset may run past the end
+        let page = FeedbackPage::new(Some(8), Some(1_000), 3);
+        assert_eq!(page.limit, 3);
+        assert_eq!(page.apply((0..10).collect()), vec![8, 9]);
+        assert!(FeedbackPage::new(Some(20), None, 3)
+            .apply((0..10).collect::<Vec<_>>())
+            .is_empty());
+    }
+
     #[test]
     fn test_erc8004_extension_parsing() {
         let extra = serde_json::json!({
@@ -500,6 +868,29 @@ mod tests {
         assert!(json.contains("\"tag1\":\"starred\""));
     }
 
+    #[test]
+    fn test_feedback_params_validation() {
+        let mut params = FeedbackParams {
+            agent_id: 42,
+            value: 9977,
+            value_decimals: MAX_VALUE_DECIMALS,
+            tag1: "x".repeat(MAX_TAG_LENGTH),
+            tag2: String::new(),
+            endpoint: String::new(),
+            feedback_uri: String::new(),
+            feedback_hash: None,
+            proof: None,
+        };
+        assert!(params.validate().is_ok());
+
+        params.value_decimals = MAX_VALUE_DECIMALS + 1;
+        assert!(params.validate().unwrap_err().starts_with("valueDecimals"));
+
+        params.value_decimals = 2;
+        params.tag2 = "x".repeat(MAX_TAG_LENGTH + 1);
+        assert!(params.validate().unwrap_err().starts_with("tag2"));
+    }
+
     #[test]
     fn test_feedback_request_serialization() {
         let request = FeedbackRequest {
@@ -516,9 +907,152 @@ mod tests {
                 feedback_hash: None,
                 proof: None,
             },
+            feedback_file: None,
         };
 
         let json = serde_json::to_string_pretty(&request).unwrap();
         assert!(json.contains("ethereum-sepolia"));
     }
+
+    /// Bytes 0..32 in base32: a well-formed Algorand transaction ID.
+    const ALGORAND_TX_ID: &str = "AAAQEAYEAUDAOCAJBIFQYDIOB4IBCEQTCQKRMFYYDENBWHA5DYPQ";
+
+    fn proof(transaction_hash: TransactionHash) -> ProofOfPayment {
+        ProofOfPayment::new(
+            transaction_hash,
+            SettleConfirmation {
+                block_number: 1_000,
+                block_timestamp: Some(1_700_000_000),
+                gas_used: None,
+            },
+            Network::EthereumSepolia,
+            MixedAddress::Offchain("payer".to_string()),
+            MixedAddress::Offchain("payee".to_string()),
+            TokenAmount::from(10_000u128),
+            MixedAddress::Offchain("token".to_string()),
+        )
+    }
+
+    #[test]
+    fn test_payment_hash_unique_across_chains() {
+        let proofs = [
+            proof(TransactionHash::Evm([1; 32])),
+            proof(TransactionHash::Near([1; 32])),
+            proof(TransactionHash::Stellar([1; 32])),
+            proof(TransactionHash::Solana([1; 64])),
+            proof(TransactionHash::Solana([2; 64])),
+            proof(TransactionHash::Algorand(ALGORAND_TX_ID.to_string())),
+            proof(TransactionHash::Algorand("A".repeat(52))),
+        ];
+
+        let hashes: std::collections::HashSet<_> =
+            proofs.iter().map(|proof| proof.payment_hash).collect();
+        assert_eq!(hashes.len(), proofs.len());
+        for proof in &proofs {
+            assert_eq!(proof.version, ProofOfPayment::VERSION);
+            assert!(proof.is_hash_consistent(), "{:?}", proof.transaction_hash);
+        }
+        assert_eq!(
+            transaction_hash_bytes(&TransactionHash::Algorand(ALGORAND_TX_ID.to_string())),
+            (0x05, (0..32).collect())
+        );
+    }
+
+    #[test]
+    fn test_payment_hash_round_trips_through_json() {
+        let proof = proof(TransactionHash::Solana([7; 64]));
+        let json = serde_json::to_value(&proof).unwrap();
+        assert_eq!(json["version"], 1);
+
+        let decoded: ProofOfPayment = serde_json::from_value(json).unwrap();
+        assert!(decoded.is_hash_consistent());
+    }
+
+    #[test]
+    fn test_legacy_payment_hash_still_verifies() {
+        let mut legacy = proof(TransactionHash::Solana([1; 64]));
+        legacy.version = 0;
+        legacy.payment_hash = ProofOfPayment::compute_payment_hash(
+            0,
+            &legacy.transaction_hash,
+            legacy.block_number,
+            &legacy.payer,
+            &legacy.payee,
+            &legacy.amount,
+        );
+
+        // Proofs issued before the version field deserialize as version 0
+        let mut json = serde_json::to_value(&legacy).unwrap();
+        json.as_object_mut().unwrap().remove("version");
+        let decoded: ProofOfPayment = serde_json::from_value(json).unwrap();
+        assert_eq!(decoded.version, 0);
+        assert!(decoded.is_hash_consistent());
+
+        // Version 0 cannot tell Solana payments apart, hence version 1
+        let mut other = decoded.clone();
+        other.transaction_hash = TransactionHash::Solana([2; 64]);
+        assert!(other.is_hash_consistent());
+
+        let mut unknown = proof(TransactionHash::Solana([1; 64]));
+        unknown.version = ProofOfPayment::VERSION + 1;
+        assert!(!unknown.is_hash_consistent());
+    }
+
+    fn facilitator() -> alloy::signers::local::PrivateKeySigner {
+        "0xcafe000000000000000000000000000000000000000000000000000000000001"
+            .parse()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_facilitator_signature_round_trip() {
+        let facilitator = facilitator();
+        let unsigned = proof(TransactionHash::Evm([1; 32]));
+        let signed = unsigned.clone().signed_by(&facilitator).unwrap();
+
+        // The signature is not part of the payment hash
+        assert_eq!(signed.payment_hash, unsigned.payment_hash);
+        assert!(signed.verify_signature(facilitator.address()));
+        assert!(!signed.verify_signature(Address::repeat_byte(0x11)));
+        assert!(!unsigned.verify_signature(facilitator.address()));
+
+        let json = serde_json::to_value(&signed).unwrap();
+        assert!(json["facilitatorSignature"].is_string());
+        assert!(serde_json::to_value(&unsigned)
+            .unwrap()
+            .get("facilitatorSignature")
+            .is_none());
+        let decoded: ProofOfPayment = serde_json::from_value(json).unwrap();
+        assert!(decoded.verify_signature(facilitator.address()));
+    }
+
+    #[test]
+    fn test_facilitator_signature_rejects_tampered_proof() {
+        let facilitator = facilitator();
+        let signed = proof(TransactionHash::Evm([1; 32]))
+            .signed_by(&facilitator)
+            .unwrap();
+
+        let mut tampered = signed.clone();
+        tampered.amount = TokenAmount::from(1_000_000u128);
+        assert!(!tampered.verify_signature(facilitator.address()));
+
+        // Recomputing the hash does not help: the signature covers the original one
+        let mut rehashed = signed.clone();
+        rehashed.payee = MixedAddress::Offchain("attacker".to_string());
+        rehashed.payment_hash = ProofOfPayment::compute_payment_hash(
+            rehashed.version,
+            &rehashed.transaction_hash,
+            rehashed.block_number,
+            &rehashed.payer,
+            &rehashed.payee,
+            &rehashed.amount,
+        );
+        assert!(rehashed.is_hash_consistent());
+        assert!(!rehashed.verify_signature(facilitator.address()));
+
+        let mut garbled = signed;
+        garbled.facilitator_signature = Some(Bytes::from_static(&[0xab; 12]));
+        assert!(!garbled.verify_signature(facilitator.address()));
+    }
 }
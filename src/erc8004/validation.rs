@@ -0,0 +1,311 @@
+//! Validation Registry requests and responses.
+//!
+//! An agent asks a validator to check its work with `validationRequest`, and the
+//! validator publishes a score from 0 (failed) to 100 (passed) with `validationResponse`.
+//! Both are keyed by a `requestHash`:
+//!
+//! ```text
+//! validationRequest(validator, agentId, requestURI, requestHash) ─▶ validationResponse(requestHash, response, ...) ─▶ getValidationStatus(requestHash)
+//! ```
+//!
+//! The registry takes requests from the owner or an operator of the agent, so
+//! `POST /validation/request`, sent by the facilitator signer, works for agents the
+//! facilitator holds. Responses are only accepted from the validator named in the
+//! request; `POST /validation/response` sends them with a dedicated validator key, and is
+//! only mounted when that key and an API key guarding it are configured.
+//!
+//! The Validation Registry is not deployed on every ERC-8004 network (not on Ethereum
+//! mainnet yet); the endpoints answer 501 there.
+//!
+//! # Environment
+//!
+//! - `ERC8004_VALIDATOR_PRIVATE_KEY` - Key answering validation requests (`POST /validation/response` disabled when unset)
+//! - `ERC8004_VALIDATOR_API_KEY` - `X-API-Key` value required by `POST /validation/response`
+
+use alloy::network::EthereumWallet;
+use alloy::primitives::{keccak256, Address, B256, U256};
+use alloy::providers::Provider;
+use alloy::signers::local::PrivateKeySigner;
+use alloy::sol_types::SolValue;
+use std::time::Duration;
+use tracing::{info, warn};
+
+use super::{
+    IValidationRegistry, ValidationRequestParams, ValidationResponseParams, ValidationStatus,
+};
+use crate::types::{EvmAddress, MixedAddress};
+
+/// How long to wait for a request or response receipt.
+const CONFIRMATION_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Errors from Validation Registry calls.
+#[derive(Debug, thiserror::Error)]
+pub enum ValidationError {
+    /// The validator of a request is not an EVM address
+    #[error("Validator {0} is not an EVM address")]
+    NonEvmValidator(MixedAddress),
+
+    /// The registry refused the call when simulated, e.g. for a sender that does not
+    /// control the agent or is not the requested validator
+    #[error("Validation Registry rejected the call: {0}")]
+    Rejected(String),
+
+    /// The transaction could not be sent or confirmed
+    #[error("Validation Registry transaction failed: {0}")]
+    Send(String),
+
+    /// The transaction reverted
+    #[error("Validation Registry transaction {0} reverted")]
+    Reverted(B256),
+
+    /// `getValidationStatus` failed
+    #[error("Validation Registry call failed: {0}")]
+    Read(alloy::contract::Error),
+}
+
+/// Hash identifying `request` in the registry: the one it gives, or else
+/// `keccak256(abi.encode(validatorAddress, agentId, requestURI))`.
+pub fn request_hash(request: &ValidationRequestParams) -> Result<B256, ValidationError> {
+    let validator = validator_address(request)?;
+    Ok(request.request_hash.unwrap_or_else(|| {
+        keccak256(
+            (
+                validator,
+                U256::from(request.agent_id),
+                request.request_uri.clone(),
+            )
+                .abi_encode_params(),
+        )
+    }))
+}
+
+fn validator_address(request: &ValidationRequestParams) -> Result<Address, ValidationError> {
+    match &request.validator_address {
+        MixedAddress::Evm(address) => Ok(address.0),
+        other => Err(ValidationError::NonEvmValidator(other.clone())),
+    }
+}
+
+/// Simulate `request`, so one the registry would refuse is caught before any gas is
+/// spent. Returns its request hash.
+pub async fn check_validation_request<P: Provider>(
+    provider: P,
+    validation_registry: Address,
+    request: &ValidationRequestParams,
+) -> Result<B256, ValidationError> {
+    let hash = request_hash(request)?;
+    IValidationRegistry::new(validation_registry, provider)
+        .validationRequest(
+            validator_address(request)?,
+            U256::from(request.agent_id),
+            request.request_uri.clone(),
+            hash,
+        )
+        .call()
+        .await
+        .map_err(|e| ValidationError::Rejected(e.to_string()))?;
+    Ok(hash)
+}
+
+/// Ask the validator of `request` to validate its agent. Returns the request hash and
+/// the `validationRequest` transaction.
+pub async fn request_validation<P: Provider>(
+    provider: P,
+    validation_registry: Address,
+    request: &ValidationRequestParams,
+) -> Result<(B256, B256), ValidationError> {
+    let hash = request_hash(request)?;
+    let receipt = IValidationRegistry::new(validation_registry, provider)
+        .validationRequest(
+            validator_address(request)?,
+            U256::from(request.agent_id),
+            request.request_uri.clone(),
+            hash,
+        )
+        .send()
+        .await
+        .map_err(|e| ValidationError::Send(e.to_string()))?
+        .with_timeout(Some(CONFIRMATION_TIMEOUT))
+        .get_receipt()
+        .await
+        .map_err(|e| ValidationError::Send(e.to_string()))?;
+    if !receipt.status() {
+        return Err(ValidationError::Reverted(receipt.transaction_hash));
+    }
+    info!(agent_id = request.agent_id, request_hash = %hash, tx = %receipt.transaction_hash, "Requested ERC-8004 validation");
+    Ok((hash, receipt.transaction_hash))
+}
+
+/// Simulate `response` sent by `validator`, so one the registry would refuse is caught
+/// before any gas is spent.
+pub async fn check_validation_response<P: Provider>(
+    provider: P,
+    validation_registry: Address,
+    validator: Address,
+    response: &ValidationResponseParams,
+) -> Result<(), ValidationError> {
+    IValidationRegistry::new(validation_registry, provider)
+        .validationResponse(
+            response.request_hash,
+            response.response,
+            response.response_uri.clone(),
+            response.response_hash.unwrap_or_default(),
+            response.tag.clone(),
+        )
+        .from(validator)
+        .call()
+        .await
+        .map_err(|e| ValidationError::Rejected(e.to_string()))?;
+    Ok(())
+}
+
+/// Publish `response`. `provider` must send as the requested validator.
+pub async fn respond_to_validation<P: Provider>(
+    provider: P,
+    validation_registry: Address,
+    response: &ValidationResponseParams,
+) -> Result<B256, ValidationError> {
+    let receipt = IValidationRegistry::new(validation_registry, provider)
+        .validationResponse(
+            response.request_hash,
+            response.response,
+            response.response_uri.clone(),
+            response.response_hash.unwrap_or_default(),
+            response.tag.clone(),
+        )
+        .send()
+        .await
+        .map_err(|e| ValidationError::Send(e.to_string()))?
+        .with_timeout(Some(CONFIRMATION_TIMEOUT))
+        .get_receipt()
+        .await
+        .map_err(|e| ValidationError::Send(e.to_string()))?;
+    if !receipt.status() {
+        return Err(ValidationError::Reverted(receipt.transaction_hash));
+    }
+    info!(request_hash = %response.request_hash, response = response.response, tx = %receipt.transaction_hash, "Published ERC-8004 validation response");
+    Ok(receipt.transaction_hash)
+}
+
+/// Status of the request `request_hash`, or `None` for an unknown request.
+///
+/// Registries answer an unknown request either with a zero validator address or with a
+/// revert; both are `None`.
+pub async fn validation_status<P: Provider>(
+    provider: P,
+    validation_registry: Address,
+    request_hash: B256,
+) -> Result<Option<ValidationStatus>, ValidationError> {
+    let status = match IValidationRegistry::new(validation_registry, provider)
+        .getValidationStatus(request_hash)
+        .call()
+        .await
+    {
+        Ok(status) => status,
+        Err(e) if e.as_revert_data().is_some() => return Ok(None),
+        Err(e) => return Err(ValidationError::Read(e)),
+    };
+    if status.validatorAddress.is_zero() {
+        return Ok(None);
+    }
+    Ok(Some(ValidationStatus {
+        validator_address: MixedAddress::Evm(EvmAddress(status.validatorAddress)),
+        agent_id: status.agentId.saturating_to(),
+        response: status.response,
+        response_hash: status.responseHash,
+        tag: status.tag,
+        last_update: status.lastUpdate.saturating_to(),
+    }))
+}
+
+/// Key answering validation requests addressed to it, with the API key callers of
+/// `POST /validation/response` must present.
+#[derive(Debug, Clone)]
+pub struct ValidatorKey {
+    signer: PrivateKeySigner,
+    api_key: String,
+}
+
+impl ValidatorKey {
+    pub fn new(signer: PrivateKeySigner, api_key: impl Into<String>) -> Self {
+        Self {
+            signer,
+            api_key: api_key.into(),
+        }
+    }
+
+    /// Key of `ERC8004_VALIDATOR_PRIVATE_KEY` and `ERC8004_VALIDATOR_API_KEY`, or `None`
+    /// unless both are set and valid.
+    pub fn from_env() -> Option<Self> {
+        let key = crate::env_registry::var("ERC8004_VALIDATOR_PRIVATE_KEY")?;
+        let Some(api_key) = crate::env_registry::var("ERC8004_VALIDATOR_API_KEY") else {
+            warn!("ERC8004_VALIDATOR_PRIVATE_KEY is set without ERC8004_VALIDATOR_API_KEY, validation responses disabled");
+            return None;
+        };
+        match key.trim().parse::<PrivateKeySigner>() {
+            Ok(signer) => {
+                info!(validator = %signer.address(), "Validation response endpoint enabled");
+                Some(Self::new(signer, api_key))
+            }
+            Err(e) => {
+                warn!(error = %e, "Invalid ERC8004_VALIDATOR_PRIVATE_KEY, validation responses disabled");
+                None
+            }
+        }
+    }
+
+    /// Whether `api_key` is the configured API key.
+    pub fn authenticate(&self, api_key: Option<&str>) -> bool {
+        api_key == Some(self.api_key.as_str())
+    }
+
+    pub fn address(&self) -> Address {
+        self.signer.address()
+    }
+
+    pub fn wallet(&self) -> EthereumWallet {
+        EthereumWallet::from(self.signer.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network::Network;
+    use crate::types::X402Version;
+    use alloy::primitives::{address, b256};
+
+    fn request(request_hash: Option<B256>) -> ValidationRequestParams {
+        ValidationRequestParams {
+            x402_version: X402Version::V1,
+            network: Network::EthereumSepolia,
+            validator_address: MixedAddress::Evm(EvmAddress(address!(
+                "70997970C51812dc3A010C7d01b50e0d17dc79C8"
+            ))),
+            agent_id: 42,
+            request_uri: "ipfs://bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi"
+                .to_string(),
+            request_hash,
+        }
+    }
+
+    #[test]
+    fn test_request_hash() {
+        let given = b256!("1111111111111111111111111111111111111111111111111111111111111111");
+        assert_eq!(request_hash(&request(Some(given))).unwrap(), given);
+
+        let derived = request_hash(&request(None)).unwrap();
+        assert_ne!(derived, B256::ZERO);
+        assert_eq!(request_hash(&request(None)).unwrap(), derived);
+        let mut other = request(None);
+        other.agent_id = 43;
+        assert_ne!(request_hash(&other).unwrap(), derived);
+
+        let mut non_evm = request(None);
+        non_evm.validator_address = MixedAddress::Near("validator.near".to_string());
+        assert!(matches!(
+            request_hash(&non_evm),
+            Err(ValidationError::NonEvmValidator(_))
+        ));
+    }
+}
@@ -0,0 +1,232 @@
+//! EIP-712 signatures for `setAgentWallet`.
+//!
+//! The Identity Registry only accepts a new agent wallet along with a signature by that
+//! wallet over an `AgentWalletSet` struct, in the registry's EIP-712 domain:
+//!
+//! ```text
+//! EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)
+//!   name = "ERC8004IdentityRegistry", version = "1", verifyingContract = Identity Registry
+//! AgentWalletSet(uint256 agentId,address newWallet,address owner,uint256 deadline)
+//! ```
+//!
+//! `owner` is the owner of the agent NFT when `setAgentWallet` is executed, so a
+//! signature is invalidated by a transfer of the agent. The transaction itself must be
+//! sent by the owner or an approved operator; `POST /identity/{agent_id}/wallet` sends
+//! it with the facilitator signer, which works for agents the facilitator still holds.
+
+use alloy::dyn_abi::TypedData;
+use alloy::primitives::{Address, Bytes, B256, U256};
+use alloy::providers::Provider;
+use alloy::signers::Signer;
+use alloy::sol;
+use alloy::sol_types::{eip712_domain, Eip712Domain, SolStruct};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tracing::info;
+
+use super::{IIdentityRegistry, SetAgentWalletRequest};
+
+/// EIP-712 domain name of the Identity Registry.
+pub const DOMAIN_NAME: &str = "ERC8004IdentityRegistry";
+
+/// EIP-712 domain version of the Identity Registry.
+pub const DOMAIN_VERSION: &str = "1";
+
+/// How long to wait for a `setAgentWallet` receipt.
+const CONFIRMATION_TIMEOUT: Duration = Duration::from_secs(120);
+
+sol! {
+    /// Message signed by the new wallet of an agent.
+    #[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+    struct AgentWalletSet {
+        uint256 agentId;
+        address newWallet;
+        address owner;
+        uint256 deadline;
+    }
+}
+
+/// EIP-712 domain of the Identity Registry at `registry_address` on `chain_id`.
+pub fn set_agent_wallet_domain(chain_id: u64, registry_address: Address) -> Eip712Domain {
+    eip712_domain! {
+        name: DOMAIN_NAME,
+        version: DOMAIN_VERSION,
+        chain_id: chain_id,
+        verifying_contract: registry_address,
+    }
+}
+
+fn agent_wallet_set(
+    agent_id: u64,
+    new_wallet: Address,
+    owner: Address,
+    deadline: u64,
+) -> AgentWalletSet {
+    AgentWalletSet {
+        agentId: U256::from(agent_id),
+        newWallet: new_wallet,
+        owner,
+        deadline: U256::from(deadline),
+    }
+}
+
+/// Typed data `new_wallet` signs to become the wallet of agent `agent_id`, ready for
+/// `eth_signTypedData_v4`.
+///
+/// `owner` is the current owner of the agent NFT and `deadline` the Unix timestamp the
+/// signature expires at.
+pub fn build_set_agent_wallet_payload(
+    agent_id: u64,
+    new_wallet: Address,
+    owner: Address,
+    deadline: u64,
+    chain_id: u64,
+    registry_address: Address,
+) -> TypedData {
+    TypedData::from_struct(
+        &agent_wallet_set(agent_id, new_wallet, owner, deadline),
+        Some(set_agent_wallet_domain(chain_id, registry_address)),
+    )
+}
+
+/// Sign the [`build_set_agent_wallet_payload`] message with `signer`, which must be the
+/// new wallet. Returns the 65-byte `r || s || v` signature `setAgentWallet` expects.
+pub async fn sign_set_agent_wallet<S: Signer + ?Sized>(
+    signer: &S,
+    agent_id: u64,
+    owner: Address,
+    deadline: u64,
+    chain_id: u64,
+    registry_address: Address,
+) -> Result<Bytes, alloy::signers::Error> {
+    let message = agent_wallet_set(agent_id, signer.address(), owner, deadline);
+    let hash = message.eip712_signing_hash(&set_agent_wallet_domain(chain_id, registry_address));
+    let signature = signer.sign_hash(&hash).await?;
+    Ok(Bytes::copy_from_slice(&signature.as_bytes()))
+}
+
+/// Errors from submitting a `setAgentWallet` transaction.
+#[derive(Debug, thiserror::Error)]
+pub enum WalletError {
+    /// The registry refused the update when simulated, e.g. for a bad signature, an
+    /// expired deadline or a sender that does not control the agent
+    #[error("setAgentWallet rejected: {0}")]
+    Rejected(String),
+
+    /// The transaction could not be sent or confirmed
+    #[error("setAgentWallet transaction failed: {0}")]
+    Send(String),
+
+    /// The transaction reverted
+    #[error("setAgentWallet transaction {0} reverted")]
+    Reverted(B256),
+}
+
+/// Simulate setting the wallet of agent `agent_id` with the signature of `request`,
+/// so an update the registry would refuse is caught before any gas is spent.
+pub async fn check_agent_wallet<P: Provider>(
+    provider: P,
+    identity_registry: Address,
+    agent_id: u64,
+    request: &SetAgentWalletRequest,
+) -> Result<(), WalletError> {
+    IIdentityRegistry::new(identity_registry, provider)
+        .setAgentWallet(
+            U256::from(agent_id),
+            request.new_wallet,
+            U256::from(request.deadline),
+            request.signature.clone(),
+        )
+        .call()
+        .await
+        .map_err(|e| WalletError::Rejected(e.to_string()))?;
+    Ok(())
+}
+
+/// Set the wallet of agent `agent_id` with the pre-computed signature of `request`.
+pub async fn set_agent_wallet<P: Provider>(
+    provider: P,
+    identity_registry: Address,
+    agent_id: u64,
+    request: &SetAgentWalletRequest,
+) -> Result<B256, WalletError> {
+    let receipt = IIdentityRegistry::new(identity_registry, provider)
+        .setAgentWallet(
+            U256::from(agent_id),
+            request.new_wallet,
+            U256::from(request.deadline),
+            request.signature.clone(),
+        )
+        .send()
+        .await
+        .map_err(|e| WalletError::Send(e.to_string()))?
+        .with_timeout(Some(CONFIRMATION_TIMEOUT))
+        .get_receipt()
+        .await
+        .map_err(|e| WalletError::Send(e.to_string()))?;
+    if !receipt.status() {
+        return Err(WalletError::Reverted(receipt.transaction_hash));
+    }
+    info!(agent_id, tx = %receipt.transaction_hash, wallet = %request.new_wallet, "Set ERC-8004 agent wallet");
+    Ok(receipt.transaction_hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::{address, b256, bytes};
+    use alloy::signers::local::PrivateKeySigner;
+
+    const REGISTRY: Address = address!("8004A818BFB912233c491871b3d84c89A494BD9e");
+    const OWNER: Address = address!("70997970C51812dc3A010C7d01b50e0d17dc79C8");
+    const CHAIN_ID: u64 = 84532;
+    const AGENT_ID: u64 = 42;
+    const DEADLINE: u64 = 1_767_225_600;
+
+    /// First Anvil development key, address 0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266
+    const NEW_WALLET_KEY: &str = "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+
+    #[test]
+    fn test_struct_matches_contract_typehash() {
+        assert_eq!(
+            AgentWalletSet::eip712_encode_type(),
+            "AgentWalletSet(uint256 agentId,address newWallet,address owner,uint256 deadline)"
+        );
+        assert_eq!(
+            agent_wallet_set(AGENT_ID, Address::ZERO, OWNER, DEADLINE).eip712_type_hash(),
+            b256!("678b53cd718d595370ab070ebf48edfdcd834beac116bf23e625fc7f4d5b7d32")
+        );
+        assert_eq!(
+            set_agent_wallet_domain(CHAIN_ID, REGISTRY).separator(),
+            b256!("d7e6057e6aa5ec8e6d96d7194b9f4326106e0f233ab470f9c1b1f19726fcc6c0")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_signature_matches_fixture() {
+        let signer: PrivateKeySigner = NEW_WALLET_KEY.parse().unwrap();
+
+        let payload = build_set_agent_wallet_payload(
+            AGENT_ID,
+            signer.address(),
+            OWNER,
+            DEADLINE,
+            CHAIN_ID,
+            REGISTRY,
+        );
+        assert_eq!(payload.primary_type, "AgentWalletSet");
+        assert_eq!(
+            payload.eip712_signing_hash().unwrap(),
+            b256!("41491a6f7f3853ad147d0f3ef8e9b77c1a438347c8c16d1caf3f62b83f21269c")
+        );
+
+        let signature =
+            sign_set_agent_wallet(&signer, AGENT_ID, OWNER, DEADLINE, CHAIN_ID, REGISTRY)
+                .await
+                .unwrap();
+        assert_eq!(
+            signature,
+            bytes!("6c8e4acc13e2456cb55d76b4c1334a7f78177d61164264024c77a359cf0a7be0638ad2e697393b8a1e9b9a93f45cd7cc8cd5a94a1a3a80f88d6b5427f5f1f60d1b")
+        );
+    }
+}
@@ -30,7 +30,6 @@ use alloy::sol;
 use alloy::sol_types::SolCall;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::env;
 use thiserror::Error;
 use tracing::{debug, info, instrument, warn};
 
@@ -265,9 +264,7 @@ impl From<EscrowError> for FacilitatorLocalError {
 
 /// Check if escrow feature is enabled via environment variable
 pub fn is_escrow_enabled() -> bool {
-    env::var("ENABLE_ESCROW")
-        .map(|v| v.eq_ignore_ascii_case("true") || v == "1")
-        .unwrap_or(false)
+    crate::env_registry::flag("ENABLE_ESCROW")
 }
 
 // ============================================================================
@@ -786,6 +783,7 @@ where
         transaction: Some(TransactionHash::Evm(tx_hash_bytes)),
         network: request.network,
         proof_of_payment: None, // Escrow settlements don't generate proof yet
+        confirmation: None,
     })
 }
 
@@ -841,6 +839,7 @@ async fn execute_escrow_deposit(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::env;
 
     #[test]
     fn test_compute_proxy_address_deterministic() {
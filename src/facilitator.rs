@@ -3,6 +3,7 @@
 //! Implementors of this trait are responsible for validating incoming payment payloads
 //! against specified requirements [`Facilitator::verify`] and executing on-chain transfers [`Facilitator::settle`].
 
+use crate::metrics::OperationLabels;
 use crate::types::{
     SettleRequest, SettleResponse, SupportedPaymentKindsResponse, VerifyRequest, VerifyResponse,
 };
@@ -82,6 +83,7 @@ pub trait Facilitator {
     }
 }
 
+/// Shared facilitators record [`metrics`](crate::metrics) for every verify and settle call.
 impl<T: Facilitator> Facilitator for Arc<T> {
     type Error = T::Error;
 
@@ -89,14 +91,26 @@ impl<T: Facilitator> Facilitator for Arc<T> {
         &self,
         request: &VerifyRequest,
     ) -> impl Future<Output = Result<VerifyResponse, Self::Error>> + Send {
-        self.as_ref().verify(request)
+        let labels = OperationLabels::start(request);
+        let verify = self.as_ref().verify(request);
+        async move {
+            let result = verify.await;
+            labels.record_verify(&result);
+            result
+        }
     }
 
     fn settle(
         &self,
         request: &SettleRequest,
     ) -> impl Future<Output = Result<SettleResponse, Self::Error>> + Send {
-        self.as_ref().settle(request)
+        let labels = OperationLabels::start(request);
+        let settle = self.as_ref().settle(request);
+        async move {
+            let result = settle.await;
+            labels.record_settle(&result);
+            result
+        }
     }
 
     fn supported(
@@ -9,13 +9,16 @@
 //! - Contract interaction using Alloy
 //! - Network-specific configuration via [`ProviderCache`] and [`USDCDeployment`]
 
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
 use std::str::FromStr;
 use std::sync::Arc;
 use tracing::instrument;
 
-use crate::chain::FacilitatorLocalError;
+use crate::chain::{signed_amount, FacilitatorLocalError};
 use crate::facilitator::Facilitator;
-use crate::network::Network;
+use crate::finality::{PriceOracle, StablecoinPriceOracle};
+use crate::network::{find_token_deployment, Network};
 use crate::provider_cache::{HasProviderMap, ProviderMap};
 use crate::types::{
     Scheme, SettleRequest, SettleResponse, SupportedPaymentKind, SupportedPaymentKindExtra,
@@ -25,7 +28,9 @@ use crate::types::{
 // Compliance module
 #[cfg(feature = "solana")]
 use x402_compliance::SolanaExtractor;
-use x402_compliance::{ComplianceChecker, EvmExtractor, ScreeningDecision, TransactionContext};
+use x402_compliance::{
+    ComplianceChecker, EvmExtractor, ScreeningDecision, TransactionContext, TravelRuleData,
+};
 
 /// A concrete [`Facilitator`] implementation that verifies and settles x402 payments
 /// using a network-aware provider cache.
@@ -76,7 +81,8 @@ where
 {
     type Error = FacilitatorLocalError;
 
-    /// Verifies a proposed x402 payment payload against a passed [`PaymentRequirements`].
+    /// Verifies a proposed x402 payment payload against the passed
+    /// [`PaymentRequirements`](crate::types::PaymentRequirements).
     ///
     /// This function validates the signature, timing, receiver match, network, scheme, and on-chain
     /// balance sufficiency for the token. If all checks pass, return a [`VerifyResponse::Valid`].
@@ -100,6 +106,7 @@ where
         );
 
         let network = request.network();
+        ensure_settleable_scheme(request.payment_payload.scheme)?;
 
         // Perform compliance screening before verification
         tracing::debug!("Performing compliance screening for verification");
@@ -146,11 +153,13 @@ where
 
         let network = request.network();
         tracing::debug!("Settlement request received for network={}", network);
+        ensure_settleable_scheme(request.payment_payload.scheme)?;
 
         // CRITICAL: Re-screen compliance before settlement (don't trust prior verify call)
         tracing::debug!("Performing compliance screening before settlement");
         self.perform_compliance_screening(&request.payment_payload.payload, network)
             .await?;
+        self.check_travel_rule(request)?;
         tracing::debug!("Compliance screening passed for settlement");
 
         tracing::debug!("Resolving provider for settlement on network={}", network);
@@ -436,6 +445,148 @@ where
                 );
                 Ok(())
             }
+            #[cfg(feature = "hedera")]
+            ExactPaymentPayload::Hedera(_hedera_payload) => {
+                // For now, allow Hedera transactions through (compliance will be added later)
+                tracing::debug!(
+                    "Hedera payment compliance check: allowing transaction (TODO: implement Hedera compliance)"
+                );
+                Ok(())
+            }
+        }
+    }
+}
+
+impl<A> FacilitatorLocal<A> {
+    /// Require FATF travel rule data on payments at or above the compliance threshold.
+    ///
+    /// The payment is valued at the amount the payer signed, not the declared
+    /// `maxAmountRequired`, which only bounds it from below. A payment that cannot be
+    /// priced, because the payload does not decode or the asset's decimals or USD price
+    /// are unknown, needs the data whenever a threshold is set. The data is read from
+    /// `extra.travelRule` of the requirements.
+    fn check_travel_rule(&self, request: &SettleRequest) -> Result<(), FacilitatorLocalError> {
+        let amount_usd = signed_usd_value(request, &StablecoinPriceOracle)
+            .and_then(|usd| usd.to_f64())
+            .unwrap_or(f64::INFINITY);
+
+        let requirements = &request.payment_requirements;
+        let data = TravelRuleData::from_extra(requirements.extra.as_ref());
+        self.compliance_checker
+            .check_travel_rule(amount_usd, data.as_ref())
+            .map_err(|e| {
+                tracing::warn!(amount_usd, "Settlement rejected by travel rule check: {}", e);
+                FacilitatorLocalError::Compliance(e)
+            })
+    }
+}
+
+/// USD value of the amount signed in `request`, if the payload decodes and the asset's
+/// decimals and price are known.
+fn signed_usd_value(request: &SettleRequest, oracle: &dyn PriceOracle) -> Option<Decimal> {
+    let requirements = &request.payment_requirements;
+    let decimals = find_token_deployment(requirements.network, &requirements.asset)?.decimals;
+    let price = oracle.usd_price(requirements.network, &requirements.asset.to_string())?;
+    let atomic = Decimal::from_str(&signed_amount(request)?.to_string()).ok()?;
+    let scale = Decimal::from_i128_with_scale(10i128.checked_pow(u32::from(decimals))?, 0);
+    atomic.checked_div(scale)?.checked_mul(price)
+}
+
+/// Reject schemes this facilitator can list in discovery but not verify or settle.
+///
+/// `upto` payments authorize a maximum rather than the amount to transfer, so handling
+/// them as `exact` would charge the payer the full maximum.
+fn ensure_settleable_scheme(scheme: Scheme) -> Result<(), FacilitatorLocalError> {
+    match scheme {
+        Scheme::Exact | Scheme::FheTransfer => Ok(()),
+        Scheme::Upto => Err(FacilitatorLocalError::SchemeMismatch(
+            None,
+            Scheme::Exact,
+            scheme,
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chain::NetworkProvider;
+    use std::borrow::Borrow;
+    use x402_compliance::{ComplianceCheckerBuilder, ComplianceError};
+
+    /// No network configured, so a settlement that clears compliance stops at the
+    /// provider lookup.
+    struct NoProviders;
+
+    impl ProviderMap for NoProviders {
+        type Value = NetworkProvider;
+
+        fn by_network<N: Borrow<Network>>(&self, _network: N) -> Option<&NetworkProvider> {
+            None
+        }
+
+        fn values(&self) -> impl Iterator<Item = &NetworkProvider> + Send {
+            std::iter::empty()
         }
     }
+
+    /// USDC settlement on Base Sepolia declaring `declared` and signing over `signed`.
+    fn settle_request(declared: u64, signed: u64) -> SettleRequest {
+        serde_json::from_value(serde_json::json!({
+            "x402Version": 1,
+            "paymentPayload": {
+                "x402Version": 1,
+                "scheme": "exact",
+                "network": "base-sepolia",
+                "payload": {
+                    "signature": format!("0x{}", "11".repeat(65)),
+                    "authorization": {
+                        "from": "0x2222222222222222222222222222222222222222",
+                        "to": "0x1111111111111111111111111111111111111111",
+                        "value": signed.to_string(),
+                        "validAfter": "0",
+                        "validBefore": "9999999999",
+                        "nonce": format!("0x{}", "00".repeat(32))
+                    }
+                }
+            },
+            "paymentRequirements": {
+                "scheme": "exact",
+                "network": "base-sepolia",
+                "maxAmountRequired": declared.to_string(),
+                "resource": "https://api.example.com/data",
+                "description": "",
+                "mimeType": "application/json",
+                "payTo": "0x1111111111111111111111111111111111111111",
+                "maxTimeoutSeconds": 60,
+                "asset": "0x036CbD53842c5426634e7929541eC2318f3dCF7e"
+            }
+        }))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_travel_rule_values_the_signed_amount() {
+        let checker = ComplianceCheckerBuilder::new()
+            .with_ofac(false)
+            .build()
+            .await
+            .unwrap();
+        let facilitator = FacilitatorLocal::new(NoProviders, Arc::new(checker));
+
+        // $1 signed: below the default $1000 threshold
+        let small = facilitator.settle(&settle_request(1_000_000, 1_000_000)).await;
+        assert!(matches!(small, Err(FacilitatorLocalError::UnsupportedNetwork(_))));
+
+        // $1 declared but $5000 signed
+        let large = facilitator
+            .settle(&settle_request(1_000_000, 5_000_000_000))
+            .await;
+        assert!(matches!(
+            large,
+            Err(FacilitatorLocalError::Compliance(
+                ComplianceError::MissingTravelRuleData(_)
+            ))
+        ));
+    }
 }
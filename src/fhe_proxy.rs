@@ -28,8 +28,7 @@ pub struct FheProxyConfig {
 impl Default for FheProxyConfig {
     fn default() -> Self {
         Self {
-            endpoint: std::env::var("FHE_FACILITATOR_URL")
-                .unwrap_or_else(|_| "https://zama-facilitator.ultravioletadao.xyz".to_string()),
+            endpoint: crate::env_registry::var("FHE_FACILITATOR_URL").unwrap_or_default(),
             // FHE decryption via Zama relayer can take longer than typical requests
             // Lambda has 60s timeout, we add buffer for cold starts
             timeout_secs: 90,
@@ -0,0 +1,885 @@
+//! Per-asset settlement finality policies.
+//!
+//! A $0.01 USDC payment on Base and a $5,000 payment on Ethereum deserve different
+//! finality bars before a merchant is told the payment is final. Finality policies map
+//! `(network, asset, amount range)` to a required confirmation depth (blocks or rounds)
+//! and an optional timelock. Payments not matched by any policy finalize immediately
+//! on first confirmation, as before.
+//!
+//! # Architecture
+//!
+//! ```text
+//! settle ──confirmed──▶ FinalityTracker::on_confirmed
+//!                          │
+//!                          ├─ no policy / depth 0 ──▶ Finalized (immediately)
+//!                          │
+//!                          └─ policy bar ──▶ Confirmed ──poll(BlockHeightSource)──▶ Finalized
+//!                                                 │
+//!                                                 └──── pending too long ────▶ Expired
+//! ```
+//!
+//! Amount ranges can be expressed in atomic units or in USD. USD ranges convert the
+//! atomic amount with the token's decimals and a [`PriceOracle`].
+//!
+//! `POST /settle` hands every confirmed settlement to the tracker, [`start_finality_task`]
+//! polls chain heights until deferred settlements are final, and
+//! `GET /settle/{transaction}/finality` serves a settlement's [`FinalityReceipt`]. Heights
+//! come from the EVM and Algorand providers ([`ChainHeights`]); policies with only a
+//! timelock finalize without one. Settlements resolved by a poll are published to
+//! [`FinalityTracker::subscribe`], which webhooks use to notify only after finality.
+//!
+//! # Configuration
+//!
+//! `FINALITY_POLICIES_FILE` points to a JSON array of [`FinalityPolicy`]; policies are
+//! evaluated in order and the first match wins. `FINALITY_POLL_INTERVAL_SECS` sets how
+//! often pending settlements are checked (default: 12). Settlements still pending after
+//! `FINALITY_PENDING_TTL_SECS` (default: 86400) expire.
+//!
+//! ```json
+//! [
+//!   { "id": "eth-large", "network": "ethereum", "minUsd": "1000", "confirmations": 12 },
+//!   { "id": "base-default", "network": "base", "confirmations": 2, "timelockSecs": 30 }
+//! ]
+//! ```
+
+use alloy::primitives::U256;
+use alloy::providers::Provider;
+use lru::LruCache;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::num::NonZeroUsize;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::{broadcast, RwLock};
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+#[cfg(feature = "algorand")]
+use crate::chain::algorand::AlgorandProvider;
+use crate::chain::evm::InnerProvider;
+use crate::network::{Network, USDCDeployment};
+use crate::timestamp::UnixTimestamp;
+use crate::types::{TokenAmount, VerifyRequest};
+
+/// Policy id recorded when a payment finalizes on first confirmation.
+pub const IMMEDIATE_POLICY_ID: &str = "immediate";
+
+/// Finalized receipts kept for `GET /settle/{transaction}/finality`.
+const FINALIZED_RECEIPTS: usize = 10_000;
+
+/// Default seconds between two polls of pending settlements.
+pub const DEFAULT_POLL_INTERVAL_SECS: u64 = 12;
+
+/// Default seconds a settlement may wait for its finality bar before it expires.
+pub const DEFAULT_PENDING_TTL_SECS: u64 = 86_400;
+
+/// Settlements waiting for finality at once; the oldest expires to make room.
+const MAX_PENDING: usize = 10_000;
+
+/// Resolved receipts buffered for [`FinalityTracker::subscribe`] receivers.
+const EVENT_CAPACITY: usize = 1_024;
+
+/// Errors from finality policy loading and evaluation.
+#[derive(Debug, thiserror::Error)]
+pub enum FinalityError {
+    /// Policy file could not be read
+    #[error("Failed to read finality policies: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// Policy file is not valid JSON
+    #[error("Failed to parse finality policies: {0}")]
+    Parse(#[from] serde_json::Error),
+
+    /// Block height lookup failed
+    #[error("Failed to fetch block height for {network}: {reason}")]
+    BlockHeight { network: Network, reason: String },
+}
+
+// ============================================================================
+// Policies
+// ============================================================================
+
+/// A single finality rule.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FinalityPolicy {
+    /// Identifier recorded in receipts
+    pub id: String,
+    /// Network this policy applies to (any when absent)
+    #[serde(default)]
+    pub network: Option<Network>,
+    /// Asset address this policy applies to, case-insensitive (any when absent)
+    #[serde(default)]
+    pub asset: Option<String>,
+    /// Inclusive lower bound in atomic units
+    #[serde(default)]
+    pub min_amount: Option<TokenAmount>,
+    /// Exclusive upper bound in atomic units
+    #[serde(default)]
+    pub max_amount: Option<TokenAmount>,
+    /// Inclusive lower bound in USD
+    #[serde(default)]
+    pub min_usd: Option<Decimal>,
+    /// Exclusive upper bound in USD
+    #[serde(default)]
+    pub max_usd: Option<Decimal>,
+    /// Confirmations (blocks or rounds) required after inclusion
+    pub confirmations: u64,
+    /// Minimum seconds between confirmation and finalization
+    #[serde(default)]
+    pub timelock_secs: u64,
+}
+
+/// The payment being evaluated against finality policies.
+#[derive(Debug, Clone)]
+pub struct PaymentContext {
+    pub network: Network,
+    pub asset: String,
+    pub amount: TokenAmount,
+    /// Token decimals, used for USD conversion
+    pub decimals: u8,
+}
+
+impl PaymentContext {
+    /// The payment settled by `request`, for the amount the payer signed.
+    ///
+    /// The declared `maxAmountRequired` may understate the payment, so it is not used; a
+    /// payload whose amount cannot be read counts as the largest possible payment.
+    ///
+    /// Decimals are only known for the network's USDC deployment, the one asset the
+    /// default [`StablecoinPriceOracle`] prices; other assets never match USD rules anyway.
+    pub fn from_request(request: &VerifyRequest) -> Self {
+        let requirements = &request.payment_requirements;
+        let usdc = USDCDeployment::by_network(requirements.network);
+        let asset = requirements.asset.to_string();
+        let decimals = if usdc.asset.address.to_string().eq_ignore_ascii_case(&asset) {
+            usdc.decimals
+        } else {
+            0
+        };
+        Self {
+            network: requirements.network,
+            asset,
+            amount: crate::chain::signed_amount(request).unwrap_or(TokenAmount(U256::MAX)),
+            decimals,
+        }
+    }
+
+    /// Amount in whole tokens, honoring `decimals`.
+    pub fn decimal_amount(&self) -> Option<Decimal> {
+        let atomic: U256 = self.amount.into();
+        let atomic = Decimal::from_str(&atomic.to_string()).ok()?;
+        let scale = Decimal::from_i128_with_scale(10i128.checked_pow(self.decimals as u32)?, 0);
+        atomic.checked_div(scale)
+    }
+}
+
+/// USD price source for USD-range policies.
+pub trait PriceOracle: Send + Sync {
+    /// USD price of one whole token, or `None` if unknown.
+    fn usd_price(&self, network: Network, asset: &str) -> Option<Decimal>;
+}
+
+/// Prices the network's USDC deployment at exactly one dollar.
+#[derive(Debug, Clone, Default)]
+pub struct StablecoinPriceOracle;
+
+impl PriceOracle for StablecoinPriceOracle {
+    fn usd_price(&self, network: Network, asset: &str) -> Option<Decimal> {
+        let usdc = USDCDeployment::by_network(network);
+        usdc.asset
+            .address
+            .to_string()
+            .eq_ignore_ascii_case(asset)
+            .then_some(Decimal::ONE)
+    }
+}
+
+impl FinalityPolicy {
+    fn matches(&self, payment: &PaymentContext, usd_value: Option<Decimal>) -> bool {
+        if self.network.is_some_and(|n| n != payment.network) {
+            return false;
+        }
+        if self
+            .asset
+            .as_ref()
+            .is_some_and(|a| !a.eq_ignore_ascii_case(&payment.asset))
+        {
+            return false;
+        }
+        if self.min_amount.is_some_and(|min| payment.amount < min)
+            || self.max_amount.is_some_and(|max| payment.amount >= max)
+        {
+            return false;
+        }
+        if self.min_usd.is_some() || self.max_usd.is_some() {
+            // USD rules never match payments that cannot be priced
+            let Some(usd) = usd_value else {
+                return false;
+            };
+            if self.min_usd.is_some_and(|min| usd < min) || self.max_usd.is_some_and(|max| usd >= max) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// Ordered set of finality policies; first match wins.
+#[derive(Clone, Default)]
+pub struct FinalityPolicies {
+    policies: Vec<FinalityPolicy>,
+    oracle: Option<Arc<dyn PriceOracle>>,
+}
+
+impl std::fmt::Debug for FinalityPolicies {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FinalityPolicies")
+            .field("policies", &self.policies)
+            .finish()
+    }
+}
+
+impl FinalityPolicies {
+    pub fn new(policies: Vec<FinalityPolicy>) -> Self {
+        Self {
+            policies,
+            oracle: Some(Arc::new(StablecoinPriceOracle)),
+        }
+    }
+
+    /// Use a custom price oracle for USD-range rules.
+    pub fn with_oracle(mut self, oracle: Arc<dyn PriceOracle>) -> Self {
+        self.oracle = Some(oracle);
+        self
+    }
+
+    /// Load policies from `FINALITY_POLICIES_FILE`, or an empty set when unset.
+    pub fn from_env() -> Result<Self, FinalityError> {
+        match crate::env_registry::var("FINALITY_POLICIES_FILE") {
+            Some(path) => {
+                let policies: Vec<FinalityPolicy> =
+                    serde_json::from_str(&std::fs::read_to_string(&path)?)?;
+                info!(path = %path, count = policies.len(), "Loaded finality policies");
+                Ok(Self::new(policies))
+            }
+            None => Ok(Self::new(Vec::new())),
+        }
+    }
+
+    /// `true` when no policy is configured, so every payment finalizes immediately.
+    pub fn is_empty(&self) -> bool {
+        self.policies.is_empty()
+    }
+
+    /// USD value of the payment, if it can be priced.
+    pub fn usd_value(&self, payment: &PaymentContext) -> Option<Decimal> {
+        let price = self.oracle.as_ref()?.usd_price(payment.network, &payment.asset)?;
+        payment.decimal_amount()?.checked_mul(price)
+    }
+
+    /// First policy matching the payment, if any.
+    pub fn select(&self, payment: &PaymentContext) -> Option<&FinalityPolicy> {
+        let usd_value = self.usd_value(payment);
+        self.policies.iter().find(|p| p.matches(payment, usd_value))
+    }
+}
+
+// ============================================================================
+// Finality Tracking
+// ============================================================================
+
+/// Settlement lifecycle state relevant to finality.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SettlementState {
+    /// Included on-chain, waiting for the policy's finality bar
+    Confirmed,
+    /// Final; safe to report to the merchant as irreversible
+    Finalized,
+    /// Gave up waiting for the finality bar; never reported as final
+    Expired,
+}
+
+/// Finality information attached to a settlement receipt.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FinalityReceipt {
+    pub transaction: String,
+    pub network: Network,
+    pub state: SettlementState,
+    /// Id of the policy that was applied ([`IMMEDIATE_POLICY_ID`] when none matched)
+    pub policy_id: String,
+    pub required_confirmations: u64,
+    /// Block (or round) in which the transaction was included
+    pub confirmation_block: u64,
+    /// Unix seconds of first confirmation
+    pub confirmed_at: u64,
+    /// Unix seconds of finalization
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub finalized_at: Option<u64>,
+    #[serde(skip)]
+    timelock_secs: u64,
+}
+
+impl FinalityReceipt {
+    pub fn is_final(&self) -> bool {
+        self.state == SettlementState::Finalized
+    }
+}
+
+/// Current chain height per network.
+#[async_trait::async_trait]
+pub trait BlockHeightSource: Send + Sync {
+    async fn current_height(&self, network: Network) -> Result<u64, FinalityError>;
+}
+
+/// Provider reading the height of one network.
+#[derive(Clone)]
+enum HeightProvider {
+    /// Block number
+    Evm(InnerProvider),
+    /// Last committed round
+    #[cfg(feature = "algorand")]
+    Algorand(AlgorandProvider),
+}
+
+/// Reads chain heights from the facilitator's EVM and Algorand providers.
+#[derive(Clone, Default)]
+pub struct ChainHeights {
+    providers: HashMap<Network, HeightProvider>,
+}
+
+impl ChainHeights {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Read the block number of `network` from `provider`.
+    pub fn with_evm(mut self, network: Network, provider: InnerProvider) -> Self {
+        self.providers.insert(network, HeightProvider::Evm(provider));
+        self
+    }
+
+    /// Read the round of `network` from `provider`.
+    #[cfg(feature = "algorand")]
+    pub fn with_algorand(mut self, network: Network, provider: AlgorandProvider) -> Self {
+        self.providers
+            .insert(network, HeightProvider::Algorand(provider));
+        self
+    }
+}
+
+#[async_trait::async_trait]
+impl BlockHeightSource for ChainHeights {
+    async fn current_height(&self, network: Network) -> Result<u64, FinalityError> {
+        let failed = |reason: String| FinalityError::BlockHeight { network, reason };
+        match self.providers.get(&network) {
+            Some(HeightProvider::Evm(provider)) => provider
+                .get_block_number()
+                .await
+                .map_err(|e| failed(e.to_string())),
+            #[cfg(feature = "algorand")]
+            Some(HeightProvider::Algorand(provider)) => provider
+                .current_round()
+                .await
+                .map_err(|e| failed(e.to_string())),
+            None => Err(failed("no height provider".to_string())),
+        }
+    }
+}
+
+/// Tracks confirmed settlements until their finality policy is satisfied.
+#[derive(Debug, Clone)]
+pub struct FinalityTracker {
+    policies: FinalityPolicies,
+    pending: Arc<RwLock<HashMap<String, FinalityReceipt>>>,
+    /// Seconds a settlement may stay pending before it expires
+    pending_ttl_secs: u64,
+    /// Most recently finalized or expired receipts, by transaction
+    finalized: Arc<Mutex<LruCache<String, FinalityReceipt>>>,
+    /// Receipts resolved after being deferred
+    events: broadcast::Sender<FinalityReceipt>,
+}
+
+impl FinalityTracker {
+    pub fn new(policies: FinalityPolicies) -> Self {
+        Self {
+            policies,
+            pending: Arc::new(RwLock::new(HashMap::new())),
+            pending_ttl_secs: DEFAULT_PENDING_TTL_SECS,
+            finalized: Arc::new(Mutex::new(LruCache::new(
+                NonZeroUsize::new(FINALIZED_RECEIPTS).expect("non-zero capacity"),
+            ))),
+            events: broadcast::channel(EVENT_CAPACITY).0,
+        }
+    }
+
+    /// Expire settlements still pending `secs` after their first confirmation.
+    pub fn with_pending_ttl(mut self, secs: u64) -> Self {
+        self.pending_ttl_secs = secs;
+        self
+    }
+
+    /// Receive every deferred settlement once it is finalized or expires.
+    ///
+    /// Settlements finalized on first confirmation are not published.
+    pub fn subscribe(&self) -> broadcast::Receiver<FinalityReceipt> {
+        self.events.subscribe()
+    }
+
+    fn remember_finalized(&self, receipt: &FinalityReceipt) {
+        self.finalized
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .put(receipt.transaction.clone(), receipt.clone());
+    }
+
+    /// Move a pending receipt to `state`, remember it and publish it.
+    fn resolve(
+        &self,
+        mut receipt: FinalityReceipt,
+        state: SettlementState,
+        now: u64,
+    ) -> FinalityReceipt {
+        receipt.state = state;
+        match state {
+            SettlementState::Finalized => {
+                receipt.finalized_at = Some(now);
+                info!(
+                    transaction = %receipt.transaction,
+                    policy = %receipt.policy_id,
+                    "Settlement finalized"
+                );
+            }
+            _ => warn!(
+                transaction = %receipt.transaction,
+                policy = %receipt.policy_id,
+                confirmed_at = receipt.confirmed_at,
+                "Settlement expired before reaching finality"
+            ),
+        }
+        self.remember_finalized(&receipt);
+        // No receivers is fine: webhooks may be disabled
+        let _ = self.events.send(receipt.clone());
+        receipt
+    }
+
+    /// Record the first confirmation of a settlement.
+    ///
+    /// Returns a finalized receipt immediately when no policy applies (or the policy
+    /// requires no additional depth nor timelock); otherwise the receipt is held as
+    /// `Confirmed` until [`Self::poll`] observes the required depth. When
+    /// too many settlements are already waiting, the oldest one expires.
+    pub async fn on_confirmed(
+        &self,
+        transaction: String,
+        payment: &PaymentContext,
+        confirmation_block: u64,
+        now: u64,
+    ) -> FinalityReceipt {
+        let (policy_id, confirmations, timelock_secs) = match self.policies.select(payment) {
+            Some(policy) => (policy.id.clone(), policy.confirmations, policy.timelock_secs),
+            None => (IMMEDIATE_POLICY_ID.to_string(), 0, 0),
+        };
+
+        let mut receipt = FinalityReceipt {
+            transaction,
+            network: payment.network,
+            state: SettlementState::Confirmed,
+            policy_id,
+            required_confirmations: confirmations,
+            confirmation_block,
+            confirmed_at: now,
+            finalized_at: None,
+            timelock_secs,
+        };
+
+        if confirmations <= 1 && timelock_secs == 0 {
+            receipt.state = SettlementState::Finalized;
+            receipt.finalized_at = Some(now);
+            self.remember_finalized(&receipt);
+        } else {
+            debug!(
+                transaction = %receipt.transaction,
+                policy = %receipt.policy_id,
+                confirmations,
+                timelock_secs,
+                "Deferring finalization"
+            );
+            let mut pending = self.pending.write().await;
+            if pending.len() >= MAX_PENDING && !pending.contains_key(&receipt.transaction) {
+                let oldest = pending
+                    .values()
+                    .min_by_key(|r| r.confirmed_at)
+                    .map(|r| r.transaction.clone());
+                if let Some(oldest) = oldest.and_then(|tx| pending.remove(&tx)) {
+                    self.resolve(oldest, SettlementState::Expired, now);
+                }
+            }
+            pending.insert(receipt.transaction.clone(), receipt.clone());
+        }
+        receipt
+    }
+
+    /// Finalize every pending settlement whose policy bar is met, and expire those
+    /// pending for longer than the TTL. Returns the resolved receipts.
+    ///
+    /// A transaction included in block `N` has `height - N + 1` confirmations; policies
+    /// requiring at most one only wait for their timelock, without a height. Settlements
+    /// on networks whose height cannot be fetched stay pending until a later poll.
+    pub async fn poll(&self, heights: &dyn BlockHeightSource, now: u64) -> Vec<FinalityReceipt> {
+        let networks: Vec<Network> = {
+            let pending = self.pending.read().await;
+            pending
+                .values()
+                .filter(|r| r.required_confirmations > 1)
+                .map(|r| r.network)
+                .collect()
+        };
+
+        let mut current = HashMap::new();
+        for network in networks {
+            if let std::collections::hash_map::Entry::Vacant(e) = current.entry(network) {
+                match heights.current_height(network).await {
+                    Ok(height) => {
+                        e.insert(Some(height));
+                    }
+                    Err(error) => {
+                        warn!(network = %network, error = %error, "Skipping finality checks");
+                        e.insert(None);
+                    }
+                }
+            }
+        }
+
+        let mut pending = self.pending.write().await;
+        let resolved: Vec<(String, SettlementState)> = pending
+            .values()
+            .filter_map(|r| {
+                let deep_enough = r.required_confirmations <= 1
+                    || current
+                        .get(&r.network)
+                        .copied()
+                        .flatten()
+                        .is_some_and(|height| {
+                            (height + 1).saturating_sub(r.confirmation_block)
+                                >= r.required_confirmations
+                        });
+                let state = if deep_enough && now >= r.confirmed_at + r.timelock_secs {
+                    SettlementState::Finalized
+                } else if now >= r.confirmed_at.saturating_add(self.pending_ttl_secs) {
+                    SettlementState::Expired
+                } else {
+                    return None;
+                };
+                Some((r.transaction.clone(), state))
+            })
+            .collect();
+
+        resolved
+            .into_iter()
+            .filter_map(|(tx, state)| pending.remove(&tx).map(|r| (r, state)))
+            .map(|(receipt, state)| self.resolve(receipt, state, now))
+            .collect()
+    }
+
+    /// Receipt of a settlement still waiting for finality.
+    pub async fn pending(&self, transaction: &str) -> Option<FinalityReceipt> {
+        self.pending.read().await.get(transaction).cloned()
+    }
+
+    /// Receipt of a settlement, pending or recently finalized or expired.
+    pub async fn receipt(&self, transaction: &str) -> Option<FinalityReceipt> {
+        if let Some(receipt) = self.pending(transaction).await {
+            return Some(receipt);
+        }
+        self.finalized
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(transaction)
+            .cloned()
+    }
+}
+
+/// Poll `heights` every `interval` and finalize the settlements whose bar is met.
+pub fn start_finality_task(
+    tracker: Arc<FinalityTracker>,
+    heights: Arc<dyn BlockHeightSource>,
+    interval: Duration,
+) -> JoinHandle<()> {
+    info!(interval_secs = interval.as_secs(), "Starting settlement finality task");
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            let now = UnixTimestamp::try_now()
+                .map(|t| t.seconds_since_epoch())
+                .unwrap_or(0);
+            tracker.poll(heights.as_ref(), now).await;
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    const USDC_ETHEREUM: &str = "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48";
+
+    fn payment(network: Network, asset: &str, amount: u64) -> PaymentContext {
+        PaymentContext {
+            network,
+            asset: asset.to_string(),
+            amount: TokenAmount::from(amount),
+            decimals: 6,
+        }
+    }
+
+    fn policies() -> FinalityPolicies {
+        FinalityPolicies::new(vec![
+            FinalityPolicy {
+                id: "eth-large".into(),
+                network: Some(Network::Ethereum),
+                asset: None,
+                min_amount: None,
+                max_amount: None,
+                min_usd: Some(Decimal::from(1000)),
+                max_usd: None,
+                confirmations: 12,
+                timelock_secs: 0,
+            },
+            FinalityPolicy {
+                id: "eth-medium".into(),
+                network: Some(Network::Ethereum),
+                asset: None,
+                min_amount: Some(TokenAmount::from(10_000_000u64)),
+                max_amount: None,
+                min_usd: None,
+                max_usd: None,
+                confirmations: 3,
+                timelock_secs: 0,
+            },
+        ])
+    }
+
+    struct MockHeights(AtomicU64);
+
+    #[async_trait::async_trait]
+    impl BlockHeightSource for MockHeights {
+        async fn current_height(&self, _network: Network) -> Result<u64, FinalityError> {
+            Ok(self.0.load(Ordering::SeqCst))
+        }
+    }
+
+    #[test]
+    fn test_range_based_policy_selection() {
+        let policies = policies();
+        let usdc = USDCDeployment::by_network(Network::Ethereum).asset.address.to_string();
+        assert!(usdc.eq_ignore_ascii_case(USDC_ETHEREUM));
+
+        // $5,000 USDC -> USD range rule
+        let large = payment(Network::Ethereum, &usdc, 5_000_000_000);
+        assert_eq!(policies.usd_value(&large), Some(Decimal::from(5000)));
+        assert_eq!(policies.select(&large).unwrap().id, "eth-large");
+
+        // $50 -> atomic range rule
+        let medium = payment(Network::Ethereum, &usdc, 50_000_000);
+        assert_eq!(policies.select(&medium).unwrap().id, "eth-medium");
+
+        // $0.01 -> no rule
+        assert!(policies.select(&payment(Network::Ethereum, &usdc, 10_000)).is_none());
+        // Other network -> no rule
+        assert!(policies.select(&payment(Network::Base, &usdc, 5_000_000_000)).is_none());
+
+        // Unpriceable asset never matches USD rules, falls through to atomic rule
+        let unknown = payment(Network::Ethereum, "0x0000000000000000000000000000000000000001", 5_000_000_000);
+        assert_eq!(policies.select(&unknown).unwrap().id, "eth-medium");
+    }
+
+    #[test]
+    fn test_context_uses_the_signed_amount() {
+        let from = "0x2222222222222222222222222222222222222222";
+        let to = "0x1111111111111111111111111111111111111111";
+        let request: VerifyRequest = serde_json::from_value(serde_json::json!({
+            "x402Version": 1,
+            "paymentPayload": {
+                "x402Version": 1,
+                "scheme": "exact",
+                "network": "ethereum",
+                "payload": {
+                    "signature": format!("0x{}", "00".repeat(65)),
+                    "authorization": {
+                        "from": from, "to": to, "value": "5000000000",
+                        "validAfter": "0", "validBefore": "9999999999",
+                        "nonce": format!("0x{}", "11".repeat(32))
+                    }
+                }
+            },
+            "paymentRequirements": {
+                "scheme": "exact",
+                "network": "ethereum",
+                "maxAmountRequired": "10000",
+                "resource": "https://api.example/data",
+                "description": "",
+                "mimeType": "application/json",
+                "payTo": to,
+                "maxTimeoutSeconds": 60,
+                "asset": USDC_ETHEREUM
+            }
+        }))
+        .unwrap();
+
+        // Declared as $0.01, which no rule matches, but $5,000 was signed
+        let payment = PaymentContext::from_request(&request);
+        assert_eq!(payment.amount, TokenAmount::from(5_000_000_000u64));
+        assert_eq!(policies().select(&payment).unwrap().id, "eth-large");
+    }
+
+    #[tokio::test]
+    async fn test_deferred_finalization_after_depth() {
+        let tracker = FinalityTracker::new(policies());
+        let usdc = USDCDeployment::by_network(Network::Ethereum).asset.address.to_string();
+        let receipt = tracker
+            .on_confirmed("0xabc".into(), &payment(Network::Ethereum, &usdc, 50_000_000), 100, 1_000)
+            .await;
+        assert_eq!(receipt.state, SettlementState::Confirmed);
+        assert_eq!(receipt.required_confirmations, 3);
+        assert!(receipt.finalized_at.is_none());
+
+        let heights = MockHeights(AtomicU64::new(101)); // 2 confirmations
+        assert!(tracker.poll(&heights, 1_010).await.is_empty());
+        assert!(tracker.pending("0xabc").await.is_some());
+
+        heights.0.store(102, Ordering::SeqCst); // 3 confirmations
+        let finalized = tracker.poll(&heights, 1_020).await;
+        assert_eq!(finalized.len(), 1);
+        assert_eq!(finalized[0].state, SettlementState::Finalized);
+        assert_eq!(finalized[0].confirmed_at, 1_000);
+        assert_eq!(finalized[0].finalized_at, Some(1_020));
+        assert!(tracker.pending("0xabc").await.is_none());
+        // The finalized receipt is still served
+        assert!(tracker.receipt("0xabc").await.unwrap().is_final());
+    }
+
+    #[tokio::test]
+    async fn test_timelock_defers_finalization() {
+        let tracker = FinalityTracker::new(FinalityPolicies::new(vec![FinalityPolicy {
+            id: "base-timelock".into(),
+            network: Some(Network::Base),
+            asset: None,
+            min_amount: None,
+            max_amount: None,
+            min_usd: None,
+            max_usd: None,
+            confirmations: 1,
+            timelock_secs: 30,
+        }]));
+        tracker
+            .on_confirmed("0xdef".into(), &payment(Network::Base, "0x1", 1), 10, 1_000)
+            .await;
+        let heights = MockHeights(AtomicU64::new(50));
+        assert!(tracker.poll(&heights, 1_029).await.is_empty());
+        assert_eq!(tracker.poll(&heights, 1_030).await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_immediate_finalization_and_policy_id_in_receipt() {
+        let tracker = FinalityTracker::new(policies());
+        let usdc = USDCDeployment::by_network(Network::Base).asset.address.to_string();
+        let receipt = tracker
+            .on_confirmed("0x123".into(), &payment(Network::Base, &usdc, 10_000), 5, 2_000)
+            .await;
+        assert!(receipt.is_final());
+        assert_eq!(receipt.policy_id, IMMEDIATE_POLICY_ID);
+        assert_eq!(receipt.finalized_at, Some(2_000));
+
+        let json = serde_json::to_value(&receipt).unwrap();
+        assert_eq!(json["policyId"], "immediate");
+        assert_eq!(json["confirmedAt"], 2_000);
+        assert_eq!(json["finalizedAt"], 2_000);
+        assert_eq!(tracker.receipt("0x123").await, Some(receipt));
+    }
+
+    struct NoHeights;
+
+    #[async_trait::async_trait]
+    impl BlockHeightSource for NoHeights {
+        async fn current_height(&self, network: Network) -> Result<u64, FinalityError> {
+            Err(FinalityError::BlockHeight {
+                network,
+                reason: "unreachable".to_string(),
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_unknown_height_keeps_settlement_pending() {
+        let tracker = FinalityTracker::new(policies());
+        let usdc = USDCDeployment::by_network(Network::Ethereum).asset.address.to_string();
+        tracker
+            .on_confirmed("0xabc".into(), &payment(Network::Ethereum, &usdc, 50_000_000), 100, 1_000)
+            .await;
+        assert!(tracker.poll(&NoHeights, 2_000).await.is_empty());
+        let receipt = tracker.receipt("0xabc").await.unwrap();
+        assert_eq!(receipt.state, SettlementState::Confirmed);
+    }
+
+    #[tokio::test]
+    async fn test_timelock_only_policy_finalizes_without_height() {
+        let tracker = FinalityTracker::new(FinalityPolicies::new(vec![FinalityPolicy {
+            id: "algorand-timelock".into(),
+            network: Some(Network::Algorand),
+            asset: None,
+            min_amount: None,
+            max_amount: None,
+            min_usd: None,
+            max_usd: None,
+            confirmations: 0,
+            timelock_secs: 60,
+        }]));
+        let mut events = tracker.subscribe();
+        tracker
+            .on_confirmed("TXID".into(), &payment(Network::Algorand, "31566704", 1), 40, 1_000)
+            .await;
+        assert!(tracker.poll(&NoHeights, 1_059).await.is_empty());
+        let finalized = tracker.poll(&NoHeights, 1_060).await;
+        assert_eq!(finalized.len(), 1);
+        assert!(finalized[0].is_final());
+        assert_eq!(events.try_recv().unwrap(), finalized[0]);
+    }
+
+    #[tokio::test]
+    async fn test_pending_settlements_expire() {
+        let tracker = FinalityTracker::new(policies()).with_pending_ttl(600);
+        let mut events = tracker.subscribe();
+        let usdc = USDCDeployment::by_network(Network::Ethereum).asset.address.to_string();
+        tracker
+            .on_confirmed("0xabc".into(), &payment(Network::Ethereum, &usdc, 50_000_000), 100, 1_000)
+            .await;
+        assert!(tracker.poll(&NoHeights, 1_599).await.is_empty());
+
+        let expired = tracker.poll(&NoHeights, 1_600).await;
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expired[0].state, SettlementState::Expired);
+        assert!(expired[0].finalized_at.is_none());
+        assert!(tracker.pending("0xabc").await.is_none());
+        // Still answered, but never as final
+        assert!(!tracker.receipt("0xabc").await.unwrap().is_final());
+        assert_eq!(events.try_recv().unwrap().state, SettlementState::Expired);
+    }
+
+    #[test]
+    fn test_policy_config_deserialization() {
+        let policies: Vec<FinalityPolicy> = serde_json::from_str(
+            r#"[{ "id": "eth-large", "network": "ethereum", "minUsd": "1000", "confirmations": 12 }]"#,
+        )
+        .unwrap();
+        assert_eq!(policies[0].network, Some(Network::Ethereum));
+        assert_eq!(policies[0].min_usd, Some(Decimal::from(1000)));
+        assert_eq!(policies[0].timelock_secs, 0);
+    }
+}
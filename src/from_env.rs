@@ -1,10 +1,10 @@
+use crate::env_registry;
 use crate::network::Network;
 use alloy::network::EthereumWallet;
 use alloy::signers::local::PrivateKeySigner;
 use serde::Deserialize;
 use serde::Serialize;
 use solana_sdk::signature::Keypair;
-use std::env;
 use std::str::FromStr;
 
 pub const ENV_SIGNER_TYPE: &str = "SIGNER_TYPE";
@@ -76,12 +76,23 @@ pub const ENV_RPC_SUI: &str = "RPC_URL_SUI";
 #[cfg(feature = "sui")]
 pub const ENV_RPC_SUI_TESTNET: &str = "RPC_URL_SUI_TESTNET";
 
+// Hedera JSON-RPC Relay URLs
+#[cfg(feature = "hedera")]
+pub const ENV_RPC_HEDERA: &str = "RPC_URL_HEDERA";
+#[cfg(feature = "hedera")]
+pub const ENV_RPC_HEDERA_TESTNET: &str = "RPC_URL_HEDERA_TESTNET";
+
 // SKALE RPC URLs (L3 on Base with gasless transactions)
 pub const ENV_RPC_SKALE_BASE: &str = "RPC_URL_SKALE_BASE";
 pub const ENV_RPC_SKALE_BASE_SEPOLIA: &str = "RPC_URL_SKALE_BASE_SEPOLIA";
 
-// Scroll RPC URL (zkEVM L2 on Ethereum)
+// Scroll RPC URLs (zkEVM L2 on Ethereum)
 pub const ENV_RPC_SCROLL: &str = "RPC_URL_SCROLL";
+pub const ENV_RPC_SCROLL_SEPOLIA: &str = "RPC_URL_SCROLL_SEPOLIA";
+
+// zkSync Era RPC URLs (ZK rollup L2 on Ethereum)
+pub const ENV_RPC_ZKSYNC_ERA: &str = "RPC_URL_ZKSYNC_ERA";
+pub const ENV_RPC_ZKSYNC_ERA_SEPOLIA: &str = "RPC_URL_ZKSYNC_ERA_SEPOLIA";
 
 // Sui wallet private key environment variables
 #[cfg(feature = "sui")]
@@ -133,9 +144,16 @@ pub fn rpc_env_name_from_network(network: Network) -> &'static str {
         Network::Sui => ENV_RPC_SUI,
         #[cfg(feature = "sui")]
         Network::SuiTestnet => ENV_RPC_SUI_TESTNET,
+        #[cfg(feature = "hedera")]
+        Network::Hedera => ENV_RPC_HEDERA,
+        #[cfg(feature = "hedera")]
+        Network::HederaTestnet => ENV_RPC_HEDERA_TESTNET,
         Network::SkaleBase => ENV_RPC_SKALE_BASE,
         Network::SkaleBaseSepolia => ENV_RPC_SKALE_BASE_SEPOLIA,
         Network::Scroll => ENV_RPC_SCROLL,
+        Network::ScrollSepolia => ENV_RPC_SCROLL_SEPOLIA,
+        Network::ZkSyncEra => ENV_RPC_ZKSYNC_ERA,
+        Network::ZkSyncEraSepolia => ENV_RPC_ZKSYNC_ERA_SEPOLIA,
     }
 }
 
@@ -151,7 +169,7 @@ impl SignerType {
     /// Parse the signer type from the `SIGNER_TYPE` environment variable.
     pub fn from_env() -> Result<Self, Box<dyn std::error::Error>> {
         let signer_type_string =
-            env::var(ENV_SIGNER_TYPE).map_err(|_| format!("env {ENV_SIGNER_TYPE} not set"))?;
+            env_registry::var(ENV_SIGNER_TYPE).ok_or_else(|| format!("env {ENV_SIGNER_TYPE} not set"))?;
         match signer_type_string.as_str() {
             "private-key" => Ok(SignerType::PrivateKey),
             _ => Err(format!("Unknown signer type {signer_type_string}").into()),
@@ -169,22 +187,55 @@ impl SignerType {
         &self,
         network: Network,
     ) -> Result<EthereumWallet, Box<dyn std::error::Error>> {
+        let mut iter = self.evm_signers(network)?.into_iter();
+        let first_signer = iter
+            .next()
+            .expect("iterator contains at least one element by construction");
+        let mut wallet = EthereumWallet::from(first_signer);
+
+        for signer in iter {
+            wallet.register_signer(signer);
+        }
+
+        Ok(wallet)
+    }
+
+    /// Constructs the signer of ERC-8004 proofs of payment on `network`.
+    ///
+    /// This is the default (first) signer of [`make_evm_wallet`](Self::make_evm_wallet).
+    pub fn make_evm_proof_signer(
+        &self,
+        network: Network,
+    ) -> Result<PrivateKeySigner, Box<dyn std::error::Error>> {
+        let first_signer = self
+            .evm_signers(network)?
+            .into_iter()
+            .next()
+            .expect("iterator contains at least one element by construction");
+        Ok(first_signer)
+    }
+
+    /// The non-empty list of EVM signers configured for `network`.
+    fn evm_signers(
+        &self,
+        network: Network,
+    ) -> Result<Vec<PrivateKeySigner>, Box<dyn std::error::Error>> {
         match self {
             SignerType::PrivateKey => {
                 // Try network-specific key first, then fall back to generic EVM_PRIVATE_KEY
                 let raw_keys = if network.is_testnet() {
-                    env::var(ENV_EVM_PRIVATE_KEY_TESTNET)
-                        .or_else(|_| env::var(ENV_EVM_PRIVATE_KEY))
-                        .map_err(|_| {
+                    env_registry::var(ENV_EVM_PRIVATE_KEY_TESTNET)
+                        .or_else(|| env_registry::var(ENV_EVM_PRIVATE_KEY))
+                        .ok_or_else(|| {
                             format!(
                                 "env {} or {} not set",
                                 ENV_EVM_PRIVATE_KEY_TESTNET, ENV_EVM_PRIVATE_KEY
                             )
                         })?
                 } else {
-                    env::var(ENV_EVM_PRIVATE_KEY_MAINNET)
-                        .or_else(|_| env::var(ENV_EVM_PRIVATE_KEY))
-                        .map_err(|_| {
+                    env_registry::var(ENV_EVM_PRIVATE_KEY_MAINNET)
+                        .or_else(|| env_registry::var(ENV_EVM_PRIVATE_KEY))
+                        .ok_or_else(|| {
                             format!(
                                 "env {} or {} not set",
                                 ENV_EVM_PRIVATE_KEY_MAINNET, ENV_EVM_PRIVATE_KEY
@@ -201,18 +252,7 @@ impl SignerType {
                 if signers.is_empty() {
                     return Err("env EVM_PRIVATE_KEY did not contain any private keys".into());
                 }
-
-                let mut iter = signers.into_iter();
-                let first_signer = iter
-                    .next()
-                    .expect("iterator contains at least one element by construction");
-                let mut wallet = EthereumWallet::from(first_signer);
-
-                for signer in iter {
-                    wallet.register_signer(signer);
-                }
-
-                Ok(wallet)
+                Ok(signers)
             }
         }
     }
@@ -230,18 +270,18 @@ impl SignerType {
         match self {
             SignerType::PrivateKey => {
                 let private_key = if network.is_testnet() {
-                    env::var(ENV_SOLANA_PRIVATE_KEY_TESTNET)
-                        .or_else(|_| env::var(ENV_SOLANA_PRIVATE_KEY))
-                        .map_err(|_| {
+                    env_registry::var(ENV_SOLANA_PRIVATE_KEY_TESTNET)
+                        .or_else(|| env_registry::var(ENV_SOLANA_PRIVATE_KEY))
+                        .ok_or_else(|| {
                             format!(
                                 "env {} or {} not set",
                                 ENV_SOLANA_PRIVATE_KEY_TESTNET, ENV_SOLANA_PRIVATE_KEY
                             )
                         })?
                 } else {
-                    env::var(ENV_SOLANA_PRIVATE_KEY_MAINNET)
-                        .or_else(|_| env::var(ENV_SOLANA_PRIVATE_KEY))
-                        .map_err(|_| {
+                    env_registry::var(ENV_SOLANA_PRIVATE_KEY_MAINNET)
+                        .or_else(|| env_registry::var(ENV_SOLANA_PRIVATE_KEY))
+                        .ok_or_else(|| {
                             format!(
                                 "env {} or {} not set",
                                 ENV_SOLANA_PRIVATE_KEY_MAINNET, ENV_SOLANA_PRIVATE_KEY
@@ -271,18 +311,18 @@ impl SignerType {
             SignerType::PrivateKey => {
                 // Get private key based on network type
                 let private_key_str = if network.is_testnet() {
-                    env::var(ENV_NEAR_PRIVATE_KEY_TESTNET)
-                        .or_else(|_| env::var(ENV_NEAR_PRIVATE_KEY))
-                        .map_err(|_| {
+                    env_registry::var(ENV_NEAR_PRIVATE_KEY_TESTNET)
+                        .or_else(|| env_registry::var(ENV_NEAR_PRIVATE_KEY))
+                        .ok_or_else(|| {
                             format!(
                                 "env {} or {} not set",
                                 ENV_NEAR_PRIVATE_KEY_TESTNET, ENV_NEAR_PRIVATE_KEY
                             )
                         })?
                 } else {
-                    env::var(ENV_NEAR_PRIVATE_KEY_MAINNET)
-                        .or_else(|_| env::var(ENV_NEAR_PRIVATE_KEY))
-                        .map_err(|_| {
+                    env_registry::var(ENV_NEAR_PRIVATE_KEY_MAINNET)
+                        .or_else(|| env_registry::var(ENV_NEAR_PRIVATE_KEY))
+                        .ok_or_else(|| {
                             format!(
                                 "env {} or {} not set",
                                 ENV_NEAR_PRIVATE_KEY_MAINNET, ENV_NEAR_PRIVATE_KEY
@@ -292,18 +332,18 @@ impl SignerType {
 
                 // Get account ID based on network type
                 let account_id = if network.is_testnet() {
-                    env::var(ENV_NEAR_ACCOUNT_ID_TESTNET)
-                        .or_else(|_| env::var(ENV_NEAR_ACCOUNT_ID))
-                        .map_err(|_| {
+                    env_registry::var(ENV_NEAR_ACCOUNT_ID_TESTNET)
+                        .or_else(|| env_registry::var(ENV_NEAR_ACCOUNT_ID))
+                        .ok_or_else(|| {
                             format!(
                                 "env {} or {} not set",
                                 ENV_NEAR_ACCOUNT_ID_TESTNET, ENV_NEAR_ACCOUNT_ID
                             )
                         })?
                 } else {
-                    env::var(ENV_NEAR_ACCOUNT_ID_MAINNET)
-                        .or_else(|_| env::var(ENV_NEAR_ACCOUNT_ID))
-                        .map_err(|_| {
+                    env_registry::var(ENV_NEAR_ACCOUNT_ID_MAINNET)
+                        .or_else(|| env_registry::var(ENV_NEAR_ACCOUNT_ID))
+                        .ok_or_else(|| {
                             format!(
                                 "env {} or {} not set",
                                 ENV_NEAR_ACCOUNT_ID_MAINNET, ENV_NEAR_ACCOUNT_ID
@@ -336,18 +376,18 @@ impl SignerType {
         match self {
             SignerType::PrivateKey => {
                 let secret_key = if network.is_testnet() {
-                    env::var(ENV_STELLAR_PRIVATE_KEY_TESTNET)
-                        .or_else(|_| env::var(ENV_STELLAR_PRIVATE_KEY))
-                        .map_err(|_| {
+                    env_registry::var(ENV_STELLAR_PRIVATE_KEY_TESTNET)
+                        .or_else(|| env_registry::var(ENV_STELLAR_PRIVATE_KEY))
+                        .ok_or_else(|| {
                             format!(
                                 "env {} or {} not set",
                                 ENV_STELLAR_PRIVATE_KEY_TESTNET, ENV_STELLAR_PRIVATE_KEY
                             )
                         })?
                 } else {
-                    env::var(ENV_STELLAR_PRIVATE_KEY_MAINNET)
-                        .or_else(|_| env::var(ENV_STELLAR_PRIVATE_KEY))
-                        .map_err(|_| {
+                    env_registry::var(ENV_STELLAR_PRIVATE_KEY_MAINNET)
+                        .or_else(|| env_registry::var(ENV_STELLAR_PRIVATE_KEY))
+                        .ok_or_else(|| {
                             format!(
                                 "env {} or {} not set",
                                 ENV_STELLAR_PRIVATE_KEY_MAINNET, ENV_STELLAR_PRIVATE_KEY
@@ -384,18 +424,18 @@ impl SignerType {
         match self {
             SignerType::PrivateKey => {
                 let mnemonic = if network.is_testnet() {
-                    env::var(ENV_ALGORAND_MNEMONIC_TESTNET)
-                        .or_else(|_| env::var(ENV_ALGORAND_MNEMONIC))
-                        .map_err(|_| {
+                    env_registry::var(ENV_ALGORAND_MNEMONIC_TESTNET)
+                        .or_else(|| env_registry::var(ENV_ALGORAND_MNEMONIC))
+                        .ok_or_else(|| {
                             format!(
                                 "env {} or {} not set",
                                 ENV_ALGORAND_MNEMONIC_TESTNET, ENV_ALGORAND_MNEMONIC
                             )
                         })?
                 } else {
-                    env::var(ENV_ALGORAND_MNEMONIC_MAINNET)
-                        .or_else(|_| env::var(ENV_ALGORAND_MNEMONIC))
-                        .map_err(|_| {
+                    env_registry::var(ENV_ALGORAND_MNEMONIC_MAINNET)
+                        .or_else(|| env_registry::var(ENV_ALGORAND_MNEMONIC))
+                        .ok_or_else(|| {
                             format!(
                                 "env {} or {} not set",
                                 ENV_ALGORAND_MNEMONIC_MAINNET, ENV_ALGORAND_MNEMONIC
@@ -424,6 +464,7 @@ mod tests {
     use super::*;
     use alloy::network::{Ethereum as AlloyEthereum, NetworkWallet};
     use alloy::signers::local::PrivateKeySigner;
+    use std::env;
     use std::str::FromStr;
     use std::sync::Mutex;
 
@@ -11,41 +11,89 @@
 
 use axum::body::Bytes;
 use axum::extract::{Extension, Path, Query, State};
-use axum::http::{HeaderMap, StatusCode};
+use axum::http::{header, HeaderMap, StatusCode};
 use axum::response::Response;
-use axum::routing::{get, post};
+use axum::routing::{delete, get, post};
 use axum::{response::IntoResponse, Json, Router};
 use base64::Engine;
 use serde_json::json;
+use subtle::ConstantTimeEq;
 use tracing::{debug, error, info, instrument, warn};
 
 use std::collections::HashMap;
 use std::sync::Arc;
 
-use crate::chain::{FacilitatorLocalError, NetworkProvider};
-use crate::chain::evm::MetaEvmProvider;
+use crate::batch::{BatchFacilitator, BatchVerifyRequest, DEFAULT_MAX_BATCH_ITEMS};
+use crate::caip2::Caip2NetworkId;
+use crate::canary::CanaryMonitor;
+use crate::chain::{FacilitatorLocalError, NetworkProvider, RevertReason};
+use crate::chain::evm::{InnerProvider, MetaEvmProvider};
+use crate::discovery::ownership::{
+    self, OwnershipError, SignedAction, SignedDeletion, SignedRegistration,
+};
 use crate::discovery::{DiscoveryError, DiscoveryRegistry};
+use crate::discovery_reputation::EvmReputationReader;
+use crate::discovery_aggregator::{
+    AggregationRunner, AggregatorError, SharedAggregationReport, SharedSourceStats, SourceStats,
+};
 use crate::fhe_proxy::FheProxy;
+use crate::finality::{FinalityTracker, PaymentContext};
+use crate::hex_fmt::Hex32;
 use crate::facilitator::Facilitator;
 use crate::provider_cache::{HasProviderMap, ProviderMap};
+use crate::streaming::{
+    AllowanceCharger, StreamId, StreamManager, StreamingError, StreamingFacilitator,
+    StreamingSettleRequest, StreamingVerifyRequest,
+};
+use crate::tenant::Access;
 use crate::types::{
-    ErrorResponse, FacilitatorErrorReason, MixedAddress, SettleRequest, VerifyRequest,
-    VerifyResponse,
+    ErrorResponse, EvmAddress, FacilitatorErrorReason, MixedAddress, SettleRequest, TokenAmount,
+    VerifyRequest, VerifyResponse,
 };
 use crate::erc8004::{
-    FeedbackRequest, FeedbackResponse, IReputationRegistry, IIdentityRegistry,
-    get_contracts, is_erc8004_supported, supported_network_names,
-    ReputationSummary, FeedbackEntry, AgentIdentity,
-    RevokeFeedbackRequest, AppendResponseRequest, ReputationResponse,
+    FeedbackRequest, FeedbackResponse, IReputationRegistry, Erc8004Client, Erc8004Error,
+    get_contracts, is_erc8004_supported, supported_network_names, supported_networks,
+    GetReputationRequest, FeedbackPage, FeedbackSource, DEFAULT_MAX_FEEDBACK_PAGE,
+    RevokeFeedbackRequest, AppendResponseRequest, ReputationResponse, AgentIdentityResponse,
+    RegisterAgentRequest, RegisterAgentResponse, SetAgentWalletRequest, SetAgentWalletResponse,
+    ValidationRequestParams, ValidationRequestResponse, ValidationResponseParams,
+    ValidationResponseResult, validation_network_names,
+};
+use crate::erc8004::aggregate::aggregate_reputation;
+use crate::erc8004::batch::{parse_agent_ids, BatchError, REPUTATION_CACHE};
+use crate::erc8004::feedback_file::{
+    check_feedback_hash, FeedbackFileError, FEEDBACK_FILE_RESOLVER,
 };
+use crate::erc8004::feedback_storage::{FEEDBACK_STORAGE, LOCAL_FEEDBACK_STORAGE};
+use crate::erc8004::identity::{
+    register_agent, IdentityRegistrar, RegistrarError, RegistrationError,
+};
+use crate::erc8004::indexer::{FeedbackIndex, FEEDBACK_INDEX};
+use crate::erc8004::proof::ProofError;
+use crate::erc8004::read_cache::{ReadCache, READ_CACHE};
+use crate::erc8004::registration::{ResolverError, AGENT_URI_RESOLVER};
+use crate::erc8004::saga::{EvmFeedbackChain, SagaError, FEEDBACK_SAGA};
+use crate::erc8004::validation::{
+    self, check_validation_request, check_validation_response, request_validation,
+    respond_to_validation, validation_status, ValidatorKey,
+};
+use crate::erc8004::wallet::{check_agent_wallet, set_agent_wallet};
+use crate::timestamp::UnixTimestamp;
 use crate::types_v2::{
-    DiscoveryFilters, DiscoveryResource, RegisterResourceRequest, SettleRequestEnvelope,
-    SupportedPaymentKindsResponseV1ToV2, VerifyRequestEnvelope,
+    DiscoveryResource, DiscoveryResponse, ListQuery, ListSort, Pagination, RegisterResourceRequest,
+    SearchFilters, SettleRequestEnvelope, SupportedPaymentKindsResponseV1ToV2,
+    VerifyRequestEnvelope,
 };
 
 // Global FHE proxy instance (lazy initialized)
 use once_cell::sync::Lazy;
 static FHE_PROXY: Lazy<FheProxy> = Lazy::new(FheProxy::new);
+static MAX_BATCH_ITEMS: Lazy<usize> = Lazy::new(|| {
+    crate::env_registry::parse("VERIFY_BATCH_MAX_ITEMS").unwrap_or(DEFAULT_MAX_BATCH_ITEMS)
+});
+static MAX_FEEDBACK_PAGE: Lazy<usize> = Lazy::new(|| {
+    crate::env_registry::parse("ERC8004_MAX_FEEDBACK_PAGE").unwrap_or(DEFAULT_MAX_FEEDBACK_PAGE)
+});
 
 /// `GET /verify`: Returns a machine-readable description of the `/verify` endpoint.
 ///
@@ -91,6 +139,7 @@ where
         .route("/", get(get_root))
         .route("/verify", get(get_verify_info))
         .route("/verify", post(post_verify::<A>))
+        .route("/verify/batch", post(post_verify_batch::<A>))
         .route("/settle", get(get_settle_info))
         .route("/settle", post(post_settle::<A>))
         // ERC-8004 Reputation endpoints
@@ -98,9 +147,26 @@ where
         .route("/feedback", post(post_feedback::<A>))
         .route("/feedback/revoke", post(post_revoke_feedback::<A>))
         .route("/feedback/response", post(post_append_response::<A>))
+        .route("/feedback-files/{id}", get(get_feedback_file))
+        .route("/reputation/batch", get(get_reputation_batch::<A>))
+        .route(
+            "/reputation/aggregate/{*agent_uri}",
+            get(get_aggregated_reputation::<A>),
+        )
+        .route("/reputation/{agent_id}", get(get_agent_reputation::<A>))
         .route("/reputation/{network}/{agent_id}", get(get_reputation::<A>))
         // ERC-8004 Identity endpoints
         .route("/identity/{network}/{agent_id}", get(get_identity::<A>))
+        .route(
+            "/identity/{network}/{agent_id}/registration",
+            get(get_identity_registration::<A>),
+        )
+        // ERC-8004 Validation endpoints
+        .route(
+            "/validation/{request_hash}",
+            get(get_validation_status::<A>),
+        )
+        .route("/proof-signer", get(get_proof_signer::<A>))
         .route("/health", get(get_health))
         .route("/version", get(get_version))
         .route("/supported", get(get_supported::<A>))
@@ -135,8 +201,126 @@ where
 /// a different state type (DiscoveryRegistry).
 pub fn discovery_routes() -> Router<Arc<DiscoveryRegistry>> {
     Router::new()
-        .route("/discovery/resources", get(get_discovery_resources))
+        .route(
+            "/discovery/resources",
+            get(get_discovery_resources)
+                .post(post_discovery_resource)
+                .delete(delete_discovery_resource),
+        )
         .route("/discovery/register", post(post_discovery_register))
+        .route("/discovery/search", get(get_discovery_search))
+        .route("/discovery/resources/{id}/health", get(get_discovery_resource_health))
+        .route("/discovery/sources", get(get_discovery_sources))
+        .route("/discovery/sources/status", get(get_discovery_sources_status))
+}
+
+/// Header carrying the key of the admin routes.
+pub const ADMIN_KEY_HEADER: &str = "X-API-Key";
+
+/// Key guarding admin routes, sent in [`ADMIN_KEY_HEADER`] and compared in constant time.
+#[derive(Clone)]
+pub struct AdminKey(String);
+
+impl AdminKey {
+    /// A key configured as `key`.
+    pub fn new(key: impl Into<String>) -> Self {
+        Self(key.into())
+    }
+
+    /// The key in the environment variable `name`; `None` when it is unset or empty.
+    pub fn from_env(name: &str) -> Option<Self> {
+        crate::env_registry::var(name)
+            .filter(|key| !key.is_empty())
+            .map(Self::new)
+    }
+
+    /// Whether `headers` carry this key.
+    pub fn matches(&self, headers: &HeaderMap) -> bool {
+        headers
+            .get(ADMIN_KEY_HEADER)
+            .is_some_and(|value| bool::from(value.as_bytes().ct_eq(self.0.as_bytes())))
+    }
+
+    /// A `401` response unless `headers` carry this key.
+    pub fn require(&self, headers: &HeaderMap) -> Result<(), Response> {
+        if self.matches(headers) {
+            return Ok(());
+        }
+        Err((
+            StatusCode::UNAUTHORIZED,
+            Json(json!({ "error": "Missing or invalid API key" })),
+        )
+            .into_response())
+    }
+}
+
+impl std::fmt::Debug for AdminKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("AdminKey(..)")
+    }
+}
+
+/// State of the discovery admin routes.
+#[derive(Clone)]
+pub struct DiscoveryAdminState {
+    /// Runs aggregation cycles, sharing the background task's in-progress guard
+    pub runner: AggregationRunner,
+    pub admin_key: AdminKey,
+}
+
+/// Discovery admin routes, mounted when aggregation runs and `DISCOVERY_ADMIN_KEY` is set.
+pub fn discovery_admin_routes() -> Router<Arc<DiscoveryAdminState>> {
+    Router::new().route("/admin/discovery/aggregate", post(post_admin_discovery_aggregate))
+}
+
+/// Settlement finality routes, mounted when `FINALITY_POLICIES_FILE` is set.
+pub fn finality_routes() -> Router<Arc<FinalityTracker>> {
+    Router::new().route("/settle/{transaction}/finality", get(get_settle_finality))
+}
+
+/// Streaming payment routes, mounted when `STREAMING_ENABLED` is set.
+pub fn streaming_routes<C>() -> Router<Arc<StreamManager<C>>>
+where
+    C: AllowanceCharger + Send + Sync + 'static,
+{
+    Router::new()
+        .route("/streaming/verify", post(post_streaming_verify::<C>))
+        .route("/streaming/settle", post(post_streaming_settle::<C>))
+        .route("/streaming/{stream_id}", get(get_streaming_stream::<C>))
+}
+
+/// Canary health routes, mounted when `CANARY_ENABLED` is set.
+pub fn canary_routes() -> Router<CanaryMonitor> {
+    Router::new().route("/health/canary", get(get_canary_health))
+}
+
+/// Configuration admin routes, mounted when `CONFIG_ADMIN_KEY` is set.
+pub fn config_admin_routes() -> Router<AdminKey> {
+    Router::new().route("/admin/config/vars", get(get_config_vars))
+}
+
+/// ERC-8004 read cache admin routes, mounted when `ERC8004_CACHE_ADMIN_KEY` is set.
+pub fn cache_admin_routes() -> Router<AdminKey> {
+    Router::new().route("/cache/identity/{agent_id}", delete(delete_identity_cache))
+}
+
+/// Agent registration routes, mounted when `IDENTITY_REGISTER_API_KEYS` is set. They
+/// include `POST /validation/request`, which spends facilitator gas the same way.
+///
+/// Handlers read the [`IdentityRegistrar`] from an [`Extension`] layered by the caller.
+pub fn identity_registration_routes<A>() -> Router<A>
+where
+    A: Facilitator + HasProviderMap + Clone + Send + Sync + 'static,
+    A::Error: IntoResponse,
+    A::Map: ProviderMap<Value = NetworkProvider>,
+{
+    Router::new()
+        .route("/identity/register", post(post_identity_register::<A>))
+        .route(
+            "/identity/{agent_id}/wallet",
+            post(post_identity_wallet::<A>),
+        )
+        .route("/validation/request", post(post_validation_request::<A>))
 }
 
 // ============================================================================
@@ -167,48 +351,55 @@ pub struct DiscoveryQueryParams {
     /// Filter by tag
     pub tag: Option<String>,
 
+    /// Filter by payment token address
+    pub asset: Option<String>,
+
+    /// Maximum amount in token base units
+    pub max_amount: Option<String>,
+
+    /// Free-text query matched against description and URL
+    pub q: Option<String>,
+
     /// Filter by discovery source (self_registered, settlement, crawled, aggregated)
     pub source: Option<String>,
 
     /// Filter by source facilitator (e.g., "coinbase", "ultravioleta")
     pub source_facilitator: Option<String>,
+
+    /// Also list resources hidden after failing health checks
+    #[serde(default, alias = "include_unhealthy")]
+    pub include_unhealthy: bool,
+
+    /// Minimum ERC-8004 reputation score (0-100)
+    #[serde(alias = "min_reputation")]
+    pub min_reputation: Option<u8>,
+
+    /// Result order: `newest` (default) or `reputation`
+    #[serde(default)]
+    pub sort: ListSort,
 }
 
 fn default_limit() -> u32 {
     10
 }
 
-impl From<DiscoveryQueryParams> for Option<DiscoveryFilters> {
-    fn from(params: DiscoveryQueryParams) -> Self {
-        if params.category.is_none()
-            && params.network.is_none()
-            && params.provider.is_none()
-            && params.tag.is_none()
-            && params.source.is_none()
-            && params.source_facilitator.is_none()
-        {
-            None
-        } else {
-            Some(DiscoveryFilters {
-                category: params.category,
-                network: params.network,
-                provider: params.provider,
-                tag: params.tag,
-                source: params.source,
-                source_facilitator: params.source_facilitator,
-            })
-        }
-    }
-}
-
 /// `GET /discovery/resources`: List discoverable paid resources.
 ///
-/// Supports pagination via `limit` and `offset` query parameters.
-/// Supports filtering by `category`, `network`, `provider`, and `tag`.
+/// Supports pagination via `limit` and `offset` query parameters; `pagination.total`
+/// counts every match. Supports filtering by `network`, `asset` and `maxAmount` (held by
+/// the same payment method), `category`, `tag`, `provider`, `source`,
+/// `sourceFacilitator`, and free text `q` over descriptions and URLs. Resources hidden
+/// after failing health checks are listed with `include_unhealthy=true`.
+///
+/// `sort=reputation` lists resources by the ERC-8004 reputation of their agent, and
+/// `min_reputation` keeps those scoring at least that much (see
+/// [`crate::discovery_reputation`]).
 ///
 /// # Example
 /// ```text
 /// GET /discovery/resources?limit=10&offset=0&category=finance&network=eip155:8453
+/// GET /discovery/resources?asset=0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913&maxAmount=10000&q=weather
+/// GET /discovery/resources?sort=reputation&min_reputation=80
 /// ```
 #[instrument(skip_all, fields(limit, offset, category, network))]
 pub async fn get_discovery_resources(
@@ -220,11 +411,79 @@ pub async fn get_discovery_resources(
         offset = params.offset,
         category = ?params.category,
         network = ?params.network,
+        q = ?params.q,
         "Discovery resources query"
     );
 
-    let filters: Option<DiscoveryFilters> = params.clone().into();
-    let response = registry.list(params.limit, params.offset, filters).await;
+    let network = match params.network.as_deref().map(str::parse::<Caip2NetworkId>).transpose() {
+        Ok(network) => network,
+        Err(e) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(json!({
+                    "error": "Invalid network",
+                    "details": e.to_string(),
+                    "hint": "Use CAIP-2 format, e.g. eip155:8453"
+                })),
+            )
+                .into_response();
+        }
+    };
+    let asset = match params
+        .asset
+        .as_deref()
+        .map(|a| serde_json::from_value::<MixedAddress>(json!(a)))
+        .transpose()
+    {
+        Ok(asset) => asset,
+        Err(e) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(json!({
+                    "error": "Invalid asset",
+                    "details": e.to_string(),
+                    "hint": "Use the token address, e.g. 0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913"
+                })),
+            )
+                .into_response();
+        }
+    };
+    let max_amount = match params
+        .max_amount
+        .as_deref()
+        .map(|a| serde_json::from_value::<TokenAmount>(json!(a)))
+        .transpose()
+    {
+        Ok(max_amount) => max_amount,
+        Err(_) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(json!({
+                    "error": "Invalid maxAmount",
+                    "hint": "Use an integer amount in token base units"
+                })),
+            )
+                .into_response();
+        }
+    };
+
+    let query = ListQuery {
+        network,
+        asset,
+        category: params.category,
+        tag: params.tag,
+        q: params.q,
+        max_amount,
+        provider: params.provider,
+        source: params.source,
+        source_facilitator: params.source_facilitator,
+        include_unhealthy: params.include_unhealthy,
+        min_reputation: params.min_reputation,
+        sort: params.sort,
+        limit: params.limit,
+        offset: params.offset,
+    };
+    let response = DiscoveryResponse::from(registry.query(&query).await);
 
     info!(
         total = response.pagination.total,
@@ -232,7 +491,160 @@ pub async fn get_discovery_resources(
         "Discovery query completed"
     );
 
-    (StatusCode::OK, Json(response))
+    (StatusCode::OK, Json(response)).into_response()
+}
+
+/// Query parameters for GET /discovery/search
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchQueryParams {
+    /// Free-text query matched against description and URL
+    #[serde(default)]
+    pub q: String,
+
+    /// Filter by network (CAIP-2 format, e.g., "eip155:8453")
+    pub network: Option<String>,
+
+    /// Maximum price in token base units
+    pub max_price: Option<String>,
+
+    /// Filter by category
+    pub category: Option<String>,
+
+    /// Comma-separated tags, all of which must be present
+    pub tags: Option<String>,
+
+    /// Filter by source facilitator (e.g., "coinbase", "ultravioleta")
+    pub source_facilitator: Option<String>,
+
+    /// Result order: `newest` (default) or `relevance`
+    pub sort: Option<String>,
+
+    /// 1-based page number (default: 1)
+    #[serde(default = "default_page")]
+    pub page: u32,
+
+    /// Results per page (default: 10, max: 100)
+    #[serde(default = "default_limit", alias = "page_size")]
+    pub page_size: u32,
+}
+
+fn default_page() -> u32 {
+    1
+}
+
+/// `GET /discovery/search`: Full-text search over discoverable resources.
+///
+/// Matches `q` against descriptions and URLs, narrowed by `network`, `maxPrice`,
+/// `category`, `tags` and `sourceFacilitator`. Paginated with `page` and `pageSize`.
+/// Results are newest first; with `sort=relevance` they are ranked by TF-IDF score
+/// over URLs, descriptions, categories and tags instead (see [`TfIdfIndex`]).
+///
+/// # Example
+/// ```text
+/// GET /discovery/search?q=weather&network=eip155:8453&maxPrice=10000&page=1&pageSize=20
+/// GET /discovery/search?q=weather+forecast&sort=relevance
+/// ```
+///
+/// [`TfIdfIndex`]: crate::discovery::TfIdfIndex
+#[instrument(skip_all, fields(q = %params.q))]
+pub async fn get_discovery_search(
+    State(registry): State<Arc<DiscoveryRegistry>>,
+    Query(params): Query<SearchQueryParams>,
+) -> impl IntoResponse {
+    let by_relevance = match params.sort.as_deref() {
+        None | Some("newest") => false,
+        Some("relevance") => true,
+        Some(other) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(json!({
+                    "error": "Invalid sort",
+                    "details": format!("unknown order {other:?}"),
+                    "hint": "Use newest or relevance"
+                })),
+            )
+                .into_response();
+        }
+    };
+    let network = match params.network.as_deref().map(str::parse::<Caip2NetworkId>).transpose() {
+        Ok(network) => network,
+        Err(e) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(json!({
+                    "error": "Invalid network",
+                    "details": e.to_string(),
+                    "hint": "Use CAIP-2 format, e.g. eip155:8453"
+                })),
+            )
+                .into_response();
+        }
+    };
+    let max_price = match params
+        .max_price
+        .as_deref()
+        .map(|p| serde_json::from_value::<TokenAmount>(json!(p)))
+        .transpose()
+    {
+        Ok(max_price) => max_price,
+        Err(_) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(json!({
+                    "error": "Invalid maxPrice",
+                    "hint": "Use an integer amount in token base units"
+                })),
+            )
+                .into_response();
+        }
+    };
+    let tags = params
+        .tags
+        .as_deref()
+        .map(|tags| {
+            tags.split(',')
+                .map(str::trim)
+                .filter(|t| !t.is_empty())
+                .map(String::from)
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let filters = SearchFilters {
+        network,
+        max_price,
+        category: params.category,
+        tags,
+        source_facilitator: params.source_facilitator,
+    };
+    let matched = if by_relevance {
+        registry
+            .search_ranked(&params.q, filters)
+            .await
+            .into_iter()
+            .map(|(resource, _)| resource)
+            .collect()
+    } else {
+        registry.search(&params.q, filters).await
+    };
+
+    let page_size = params.page_size.clamp(1, 100);
+    let offset = params.page.max(1).saturating_sub(1).saturating_mul(page_size);
+    let total = matched.len() as u32;
+    let items: Vec<DiscoveryResource> = matched
+        .into_iter()
+        .skip(offset as usize)
+        .take(page_size as usize)
+        .collect();
+
+    info!(total, returned = items.len(), "Discovery search completed");
+
+    (
+        StatusCode::OK,
+        Json(DiscoveryResponse::new(items, Pagination::new(page_size, offset, total))),
+    )
+        .into_response()
 }
 
 /// `POST /discovery/register`: Register a new paid resource.
@@ -261,9 +673,13 @@ pub async fn get_discovery_resources(
 ///   }
 /// }
 /// ```
+///
+/// A request attributed to a tenant registers the resource in that tenant's own
+/// namespace instead of the public registry.
 #[instrument(skip_all, fields(url))]
 pub async fn post_discovery_register(
     State(registry): State<Arc<DiscoveryRegistry>>,
+    access: Option<Extension<Access>>,
     Json(request): Json<RegisterResourceRequest>,
 ) -> impl IntoResponse {
     let url = request.url.to_string();
@@ -271,7 +687,11 @@ pub async fn post_discovery_register(
 
     let resource = request.into_resource();
 
-    match registry.register(resource).await {
+    let registered = match access {
+        Some(Extension(Access::Tenant(scope))) => registry.register_scoped(&scope, resource).await,
+        _ => registry.register(resource).await,
+    };
+    match registered {
         Ok(()) => {
             info!(url = %url, "Resource registered successfully");
             (
@@ -291,35 +711,275 @@ pub async fn post_discovery_register(
     }
 }
 
-/// Convert a DiscoveryError to an HTTP response.
-fn discovery_error_response(error: DiscoveryError) -> Response {
-    match error {
-        DiscoveryError::AlreadyExists(url) => (
-            StatusCode::CONFLICT,
+/// `POST /discovery/resources`: Register a resource signed by the address it pays to.
+///
+/// Unlike `POST /discovery/register`, the body carries an EIP-191 signature by the
+/// `payTo` address of every payment method over the resource URL, that address, a hash
+/// of the body and a timestamp (see [`ownership`]). Each signature is accepted once. The signer becomes the resource's owner: registering
+/// the URL again with its signature replaces the resource, while other registrants get
+/// 409. Signatures older than `DISCOVERY_SIGNATURE_MAX_AGE` seconds are rejected.
+///
+/// # Request Body
+/// The body of `POST /discovery/register`, plus:
+/// ```json
+/// {
+///   "timestamp": 1767225600,
+///   "signature": "0x..."
+/// }
+/// ```
+#[instrument(skip_all)]
+pub async fn post_discovery_resource(
+    State(registry): State<Arc<DiscoveryRegistry>>,
+    raw_body: Bytes,
+) -> impl IntoResponse {
+    let (request, body_hash) = match parse_signed_body::<SignedRegistration>(&raw_body) {
+        Ok(parsed) => parsed,
+        Err(response) => return response,
+    };
+    let url = request.resource.url.to_string();
+    let owner = match ownership::registrant(&request.resource.accepts).and_then(|owner| {
+        ownership::verify(
+            SignedAction::Register,
+            &request.resource.url,
+            owner,
+            body_hash,
+            request.timestamp,
+            &request.signature,
+        )
+        .map(|()| owner)
+    }) {
+        Ok(owner) => MixedAddress::Evm(owner.into()),
+        Err(e) => {
+            warn!(url = %url, error = %e, "Rejected signed resource registration");
+            return ownership_error_response(e);
+        }
+    };
+
+    match registry
+        .register_signed(request.resource.into_resource(), owner.clone())
+        .await
+    {
+        Ok(replaced) => {
+            info!(url = %url, owner = %owner, replaced, "Signed resource registered");
+            let status = if replaced {
+                StatusCode::OK
+            } else {
+                StatusCode::CREATED
+            };
+            (
+                status,
+                Json(json!({
+                    "success": true,
+                    "url": url,
+                    "owner": owner,
+                    "replaced": replaced
+                })),
+            )
+                .into_response()
+        }
+        Err(e) => {
+            warn!(url = %url, error = %e, "Failed to register signed resource");
+            discovery_error_response(e)
+        }
+    }
+}
+
+/// `DELETE /discovery/resources`: Remove a resource registered with a signature.
+///
+/// Authorized like `POST /discovery/resources`: the body carries the resource URL, a
+/// timestamp and the owner's signature of them and the body hash. Resources registered without a
+/// signature cannot be removed this way (409).
+///
+/// # Request Body
+/// ```json
+/// {
+///   "url": "https://api.example.com/premium-data",
+///   "timestamp": 1767225600,
+///   "signature": "0x..."
+/// }
+/// ```
+#[instrument(skip_all)]
+pub async fn delete_discovery_resource(
+    State(registry): State<Arc<DiscoveryRegistry>>,
+    raw_body: Bytes,
+) -> impl IntoResponse {
+    let (request, body_hash) = match parse_signed_body::<SignedDeletion>(&raw_body) {
+        Ok(parsed) => parsed,
+        Err(response) => return response,
+    };
+    let url = request.url.to_string();
+    let owner = match registry.get(&url).await.map(|resource| resource.owner) {
+        None => return discovery_error_response(DiscoveryError::NotFound(url)),
+        Some(Some(MixedAddress::Evm(owner))) => owner,
+        Some(_) => return discovery_error_response(DiscoveryError::OwnedByOther(url)),
+    };
+    if let Err(e) = ownership::verify(
+        SignedAction::Delete,
+        &request.url,
+        owner.0,
+        body_hash,
+        request.timestamp,
+        &request.signature,
+    ) {
+        warn!(url = %url, error = %e, "Rejected signed resource removal");
+        return ownership_error_response(e);
+    }
+
+    match registry
+        .unregister_signed(&url, &MixedAddress::Evm(owner))
+        .await
+    {
+        Ok(_) => (
+            StatusCode::OK,
             Json(json!({
-                "error": "Resource already registered",
-                "url": url,
-                "hint": "Use PUT /discovery/resources/{url} to update an existing resource"
+                "success": true,
+                "url": url
             })),
         )
             .into_response(),
-        DiscoveryError::NotFound(url) => (
-            StatusCode::NOT_FOUND,
+        Err(e) => discovery_error_response(e),
+    }
+}
+
+/// `GET /discovery/resources/{id}/health`: Health of a registered resource.
+///
+/// `id` is the percent-encoded resource URL. Returns whether the resource is listed,
+/// its consecutive failed checks, the time, status and latency of the last check and
+/// the last error, or 404 if the resource is not registered.
+///
+/// # Example
+/// ```text
+/// GET /discovery/resources/https%3A%2F%2Fapi.example.com%2Fweather/health
+/// ```
+#[instrument(skip_all, fields(id = %id))]
+pub async fn get_discovery_resource_health(
+    State(registry): State<Arc<DiscoveryRegistry>>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    // Registry keys are normalized URLs (e.g. a trailing slash on bare hosts)
+    let url = url::Url::parse(&id).map(|u| u.to_string()).unwrap_or(id);
+    match registry.health(&url).await {
+        Some(health) => (
+            StatusCode::OK,
             Json(json!({
-                "error": "Resource not found",
-                "url": url
+                "url": url,
+                "isHealthy": health.is_healthy,
+                "consecutiveFailures": health.consecutive_failures,
+                "lastChecked": health.last_checked,
+                "lastStatus": health.last_status,
+                "latencyMs": health.latency_ms,
+                "lastError": health.last_error,
             })),
         )
             .into_response(),
-        DiscoveryError::InvalidUrl(msg) => (
-            StatusCode::BAD_REQUEST,
+        None => discovery_error_response(DiscoveryError::NotFound(url)),
+    }
+}
+
+/// `GET /discovery/sources`: Live statistics of every configured aggregation source.
+///
+/// Lists each facilitator with its last fetch and success, resources contributed, last
+/// error, circuit breaker state and next scheduled fetch, sorted by id. Empty when
+/// aggregation is disabled.
+#[instrument(skip_all)]
+pub async fn get_discovery_sources(
+    Extension(stats): Extension<SharedSourceStats>,
+) -> impl IntoResponse {
+    let mut sources: Vec<SourceStats> = stats.iter().map(|entry| entry.value().clone()).collect();
+    sources.sort_by(|a, b| a.id.cmp(&b.id));
+    Json(json!({ "sources": sources }))
+}
+
+/// `GET /discovery/sources/status`: Report of the latest aggregation cycle.
+///
+/// Returns per-facilitator fetched/converted/skipped counts, durations and errors,
+/// or 404 until a cycle has completed (or when aggregation is disabled).
+#[instrument(skip_all)]
+pub async fn get_discovery_sources_status(
+    Extension(report): Extension<SharedAggregationReport>,
+) -> impl IntoResponse {
+    match report.read().await.as_ref() {
+        Some(report) => (StatusCode::OK, Json(report.clone())).into_response(),
+        None => (
+            StatusCode::NOT_FOUND,
             Json(json!({
-                "error": "Invalid URL",
-                "details": msg
+                "error": "No aggregation cycle has completed",
+                "hint": "Aggregation runs in the background when DISCOVERY_ENABLE_AGGREGATION is set"
             })),
         )
             .into_response(),
-        DiscoveryError::InvalidResourceType(t) => (
+    }
+}
+
+/// Query parameters for POST /admin/discovery/aggregate
+#[derive(Debug, Default, serde::Deserialize)]
+pub struct AggregateQuery {
+    /// Only fetch the facilitator with this id
+    pub source: Option<String>,
+}
+
+/// `POST /admin/discovery/aggregate`: Run an aggregation cycle now.
+///
+/// Requires the `DISCOVERY_ADMIN_KEY` in `X-API-Key`. Fetches every facilitator, or
+/// only `source`, imports the results and returns the cycle's report. Returns 409 while
+/// another cycle (background or on demand) is running and 404 for an unknown source.
+///
+/// # Example
+/// ```text
+/// POST /admin/discovery/aggregate?source=coinbase
+/// ```
+#[instrument(skip_all, fields(source = ?query.source))]
+pub async fn post_admin_discovery_aggregate(
+    State(admin): State<Arc<DiscoveryAdminState>>,
+    headers: HeaderMap,
+    Query(query): Query<AggregateQuery>,
+) -> impl IntoResponse {
+    if let Err(rejection) = admin.admin_key.require(&headers) {
+        return rejection;
+    }
+
+    match admin.runner.try_run(query.source.as_deref()).await {
+        Ok(report) => (StatusCode::OK, Json(report)).into_response(),
+        Err(e) => {
+            let status = match e {
+                AggregatorError::CycleInProgress => StatusCode::CONFLICT,
+                AggregatorError::UnknownSource(_) => StatusCode::NOT_FOUND,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            };
+            (status, Json(json!({ "error": e.to_string() }))).into_response()
+        }
+    }
+}
+
+/// Convert a DiscoveryError to an HTTP response.
+fn discovery_error_response(error: DiscoveryError) -> Response {
+    match error {
+        DiscoveryError::AlreadyExists(url) => (
+            StatusCode::CONFLICT,
+            Json(json!({
+                "error": "Resource already registered",
+                "url": url,
+                "hint": "Use PUT /discovery/resources/{url} to update an existing resource"
+            })),
+        )
+            .into_response(),
+        DiscoveryError::NotFound(url) => (
+            StatusCode::NOT_FOUND,
+            Json(json!({
+                "error": "Resource not found",
+                "url": url
+            })),
+        )
+            .into_response(),
+        DiscoveryError::InvalidUrl(msg) => (
+            StatusCode::BAD_REQUEST,
+            Json(json!({
+                "error": "Invalid URL",
+                "details": msg
+            })),
+        )
+            .into_response(),
+        DiscoveryError::InvalidResourceType(t) => (
             StatusCode::BAD_REQUEST,
             Json(json!({
                 "error": "Invalid resource type",
@@ -336,6 +996,14 @@ fn discovery_error_response(error: DiscoveryError) -> Response {
             })),
         )
             .into_response(),
+        DiscoveryError::OwnedByOther(url) => (
+            StatusCode::CONFLICT,
+            Json(json!({
+                "error": "Resource is owned by another registrant",
+                "url": url
+            })),
+        )
+            .into_response(),
         DiscoveryError::StorageError(e) => (
             StatusCode::INTERNAL_SERVER_ERROR,
             Json(json!({
@@ -344,9 +1012,42 @@ fn discovery_error_response(error: DiscoveryError) -> Response {
             })),
         )
             .into_response(),
+        DiscoveryError::Tenant(e) => e.into_response(),
     }
 }
 
+/// Convert an OwnershipError to an HTTP response.
+/// Parse a signed discovery request body along with its [`ownership::body_hash`].
+fn parse_signed_body<T: serde::de::DeserializeOwned>(
+    raw_body: &[u8],
+) -> Result<(T, alloy::primitives::B256), Response> {
+    serde_json::from_slice::<serde_json::Value>(raw_body)
+        .and_then(|body| {
+            let body_hash = ownership::body_hash(&body);
+            serde_json::from_value(body).map(|request| (request, body_hash))
+        })
+        .map_err(|e| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(json!({ "error": format!("Invalid request body: {e}") })),
+            )
+                .into_response()
+        })
+}
+
+fn ownership_error_response(error: OwnershipError) -> Response {
+    let status = match error {
+        OwnershipError::Stale { .. }
+        | OwnershipError::InvalidSignature { .. }
+        | OwnershipError::Replayed => StatusCode::UNAUTHORIZED,
+        OwnershipError::NoPayTo
+        | OwnershipError::MultiplePayTo
+        | OwnershipError::NonEvmPayTo(_)
+        | OwnershipError::MalformedSignature(_) => StatusCode::BAD_REQUEST,
+    };
+    (status, Json(json!({ "error": error.to_string() }))).into_response()
+}
+
 /// `GET /`: Returns the Ultravioleta DAO branded landing page.
 #[instrument(skip_all)]
 pub async fn get_root() -> impl IntoResponse {
@@ -633,6 +1334,57 @@ pub async fn get_health() -> impl IntoResponse {
     }))
 }
 
+/// `GET /settle/{transaction}/finality`: Finality receipt of a settlement.
+///
+/// Reports the policy applied to the settlement, when it was confirmed and, once its
+/// confirmation depth and timelock are met, when it was finalized.
+#[instrument(skip_all, fields(transaction = %transaction))]
+pub async fn get_settle_finality(
+    State(tracker): State<Arc<FinalityTracker>>,
+    Path(transaction): Path<String>,
+) -> impl IntoResponse {
+    match tracker.receipt(&transaction).await {
+        Some(receipt) => (StatusCode::OK, Json(json!(receipt))),
+        None => (
+            StatusCode::NOT_FOUND,
+            Json(json!({ "error": "Unknown or expired settlement" })),
+        ),
+    }
+}
+
+/// `GET /health/canary`: Health of the canary self-payments, by network.
+///
+/// Responds `503 Service Unavailable` while any network's canaries are alerting.
+#[instrument(skip_all)]
+pub async fn get_canary_health(State(monitor): State<CanaryMonitor>) -> impl IntoResponse {
+    let status = if monitor.is_healthy().await {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (status, Json(monitor.health_json().await))
+}
+
+/// `GET /admin/config/vars`: Lists every declared environment variable.
+///
+/// Reports whether each variable is currently set. Values are included only for
+/// non-secret variables; see [`crate::env_registry`]. Requires the configuration
+/// admin key in [`ADMIN_KEY_HEADER`].
+#[instrument(skip_all)]
+pub async fn get_config_vars(
+    State(admin_key): State<AdminKey>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    if let Err(rejection) = admin_key.require(&headers) {
+        return rejection;
+    }
+
+    Json(json!({
+        "variables": crate::env_registry::listing()
+    }))
+    .into_response()
+}
+
 /// `GET /version`: Returns the current version of the facilitator.
 ///
 /// This endpoint returns the version from Cargo.toml for operational visibility.
@@ -884,6 +1636,37 @@ where
     }
 }
 
+/// `POST /verify/batch`: Verify several payments in one call.
+///
+/// Items are verified concurrently and reported individually: the response is 200
+/// even when some (or all) items fail. Batches larger than `VERIFY_BATCH_MAX_ITEMS`
+/// are rejected with 413.
+#[instrument(skip_all, fields(items = request.items.len()))]
+pub async fn post_verify_batch<A>(
+    State(facilitator): State<A>,
+    Json(request): Json<BatchVerifyRequest>,
+) -> impl IntoResponse
+where
+    A: Facilitator + Sync,
+{
+    let batch = BatchFacilitator::new(facilitator).with_max_items(*MAX_BATCH_ITEMS);
+    match batch.batch_verify(&request).await {
+        Ok(response) => {
+            let failed = response.results.iter().filter(|r| r.is_err()).count();
+            info!(items = response.results.len(), failed, "Batch verification complete");
+            (StatusCode::OK, Json(response)).into_response()
+        }
+        Err(e) => {
+            warn!(error = %e, "Rejected batch verification");
+            (
+                StatusCode::PAYLOAD_TOO_LARGE,
+                Json(json!({ "error": e.to_string() })),
+            )
+                .into_response()
+        }
+    }
+}
+
 /// Helper function to log detailed deserialization errors for settle requests.
 /// This extracts field-level information from the raw JSON to help debug malformed requests.
 fn log_settle_deserialization_error(body_str: &str, e: &serde_json::Error) {
@@ -1000,6 +1783,8 @@ fn log_settle_deserialization_error(body_str: &str, e: &serde_json::Error) {
 pub async fn post_settle<A>(
     State(facilitator): State<A>,
     Extension(discovery_registry): Extension<Arc<DiscoveryRegistry>>,
+    finality: Option<Extension<Arc<FinalityTracker>>>,
+    access: Option<Extension<Access>>,
     headers: HeaderMap,
     raw_body: Bytes,
 ) -> impl IntoResponse
@@ -1317,6 +2102,16 @@ where
                 sui_payload.coin_object_id
             );
         }
+        #[cfg(feature = "hedera")]
+        crate::types::ExactPaymentPayload::Hedera(hedera_payload) => {
+            debug!("  - payload type: Hedera (signed EVM transaction)");
+            debug!("  - from: {}", hedera_payload.from);
+            debug!(
+                "  - signed_transaction: {} (truncated)",
+                &hedera_payload.signed_transaction
+                    [..hedera_payload.signed_transaction.len().min(100)]
+            );
+        }
     }
 
     debug!("=== END SETTLE REQUEST DEBUG ===");
@@ -1357,7 +2152,8 @@ where
                     .and_then(|v| v.as_bool())
                     .unwrap_or(false);
 
-                if is_discoverable {
+                // Canary self-payments never count towards settlement statistics
+                if is_discoverable && !crate::canary::is_canary_request(&body) {
                     // Convert v1 PaymentRequirements to v2 for the accepts array
                     use crate::types_v2::PaymentRequirementsV1ToV2;
                     let (_resource_info, requirements_v2) = body.payment_requirements.to_v2();
@@ -1370,11 +2166,24 @@ where
                         vec![requirements_v2],
                     );
 
-                    // Track the settlement (register or increment count)
+                    // Track the settlement (register or increment count), in the
+                    // tenant's namespace when the request is attributed to one
                     let registry = discovery_registry.clone();
                     let resource_url = discovery_resource.url.to_string();
+                    let tenant = match access {
+                        Some(Extension(Access::Tenant(scope))) => Some(scope),
+                        _ => None,
+                    };
                     tokio::spawn(async move {
-                        match registry.track_settlement(discovery_resource).await {
+                        let tracked = match &tenant {
+                            Some(scope) => {
+                                registry
+                                    .track_settlement_scoped(scope, discovery_resource)
+                                    .await
+                            }
+                            None => registry.track_settlement(discovery_resource).await,
+                        };
+                        match tracked {
                             Ok(is_new) => {
                                 if is_new {
                                     info!(
@@ -1398,6 +2207,25 @@ where
                         }
                     });
                 }
+
+                // Hold the settlement as confirmed until its finality policy is met
+                if let (Some(Extension(tracker)), Some(transaction), Some(confirmation)) = (
+                    finality,
+                    valid_response.transaction.as_ref(),
+                    valid_response.confirmation.as_ref(),
+                ) {
+                    let now = UnixTimestamp::try_now()
+                        .map(|t| t.seconds_since_epoch())
+                        .unwrap_or(0);
+                    tracker
+                        .on_confirmed(
+                            transaction.to_string(),
+                            &PaymentContext::from_request(&body),
+                            confirmation.block_number,
+                            now,
+                        )
+                        .await;
+                }
             } else {
                 error!(
                     "[FAIL] SETTLEMENT FAILED (success=false) - network={:?}, payer={:?}, error_reason={:?}",
@@ -1514,6 +2342,73 @@ impl IntoResponse for FacilitatorLocalError {
                 )
                     .into_response()
             }
+            FacilitatorLocalError::SimulationFailed(reason) => {
+                tracing::warn!(reason = %reason, "Settlement reverted in simulation");
+                let error_reason = match reason {
+                    RevertReason::InsufficientBalance => FacilitatorErrorReason::InsufficientFunds,
+                    other => FacilitatorErrorReason::FreeForm(format!(
+                        "simulation_reverted: {}",
+                        other
+                    )),
+                };
+                (StatusCode::OK, Json(VerifyResponse::invalid(None, error_reason))).into_response()
+            }
+            FacilitatorLocalError::HoneypotDetected(token) => {
+                tracing::warn!(token = %token, "Rejected honeypot payment token");
+                (
+                    StatusCode::OK,
+                    Json(VerifyResponse::invalid(
+                        None,
+                        FacilitatorErrorReason::FreeForm(format!("honeypot_token: {}", token)),
+                    )),
+                )
+                    .into_response()
+            }
+            FacilitatorLocalError::TokenNotWhitelisted(token) => {
+                tracing::warn!(token = %token, "Rejected payment token not on the whitelist");
+                (
+                    StatusCode::OK,
+                    Json(VerifyResponse::invalid(
+                        None,
+                        FacilitatorErrorReason::FreeForm(format!("token_not_whitelisted: {}", token)),
+                    )),
+                )
+                    .into_response()
+            }
+            FacilitatorLocalError::Compliance(
+                x402_compliance::ComplianceError::MissingTravelRuleData(reason),
+            ) => {
+                tracing::warn!(reason = %reason, "Rejected payment without travel rule data");
+                (
+                    StatusCode::OK,
+                    Json(VerifyResponse::invalid(
+                        None,
+                        FacilitatorErrorReason::FreeForm(format!("missing_travel_rule_data: {}", reason)),
+                    )),
+                )
+                    .into_response()
+            }
+            FacilitatorLocalError::Compliance(ref e) => {
+                tracing::error!(error = %e, "Compliance check error");
+                (
+                    StatusCode::BAD_REQUEST,
+                    Json(ErrorResponse {
+                        error: e.to_string(),
+                    }),
+                )
+                    .into_response()
+            }
+            FacilitatorLocalError::CircuitOpen(retry_after) => {
+                tracing::warn!(retry_after = ?retry_after, "Circuit breaker open, failing fast");
+                (
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    [(header::RETRY_AFTER, retry_after.as_secs_f64().ceil().max(1.0).to_string())],
+                    Json(ErrorResponse {
+                        error: "RPC node unavailable, retry later".to_string(),
+                    }),
+                )
+                    .into_response()
+            }
             FacilitatorLocalError::Other(ref e) => {
                 tracing::error!(error = %e, "Other facilitator error");
                 (
@@ -1528,6 +2423,96 @@ impl IntoResponse for FacilitatorLocalError {
     }
 }
 
+// ============================================================================
+// Streaming Payment Handlers
+// ============================================================================
+
+impl IntoResponse for StreamingError {
+    fn into_response(self) -> Response {
+        let status = match &self {
+            StreamingError::InvalidAllowance(_) => StatusCode::BAD_REQUEST,
+            StreamingError::StreamExists(_) => StatusCode::CONFLICT,
+            StreamingError::Clock(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            StreamingError::Charge(_) => StatusCode::BAD_GATEWAY,
+        };
+        (status, Json(json!({ "error": self.to_string() }))).into_response()
+    }
+}
+
+/// `POST /streaming/verify`: Checks a streaming payment without opening the stream.
+///
+/// Responds with a [`VerifyResponse`], as `POST /verify` does.
+#[instrument(skip_all, fields(network = %request.payload.network))]
+pub async fn post_streaming_verify<C>(
+    State(manager): State<Arc<StreamManager<C>>>,
+    Json(request): Json<StreamingVerifyRequest>,
+) -> Response
+where
+    C: AllowanceCharger + Sync,
+{
+    match manager.verify_streaming(&request).await {
+        Ok(response) => (StatusCode::OK, Json(response)).into_response(),
+        Err(e) => {
+            warn!(error = %e, "Streaming verification failed");
+            e.into_response()
+        }
+    }
+}
+
+/// `POST /streaming/settle`: Submits the permit and opens the stream.
+///
+/// Responds with a [`StreamingSettleResponse`](crate::streaming::StreamingSettleResponse)
+/// carrying the stream id the resource server checks access with.
+#[instrument(skip_all, fields(network = %request.payload.network))]
+pub async fn post_streaming_settle<C>(
+    State(manager): State<Arc<StreamManager<C>>>,
+    Json(request): Json<StreamingSettleRequest>,
+) -> Response
+where
+    C: AllowanceCharger + Sync,
+{
+    match manager.settle_streaming(&request).await {
+        Ok(response) => (StatusCode::OK, Json(response)).into_response(),
+        Err(e) => {
+            warn!(error = %e, "Failed to open stream");
+            e.into_response()
+        }
+    }
+}
+
+/// `GET /streaming/{stream_id}`: Whether a stream still grants access, and what it has
+/// been charged so far.
+#[instrument(skip_all, fields(stream_id = %stream_id))]
+pub async fn get_streaming_stream<C>(
+    State(manager): State<Arc<StreamManager<C>>>,
+    Path(stream_id): Path<StreamId>,
+) -> Response
+where
+    C: AllowanceCharger + Sync,
+{
+    let now = match UnixTimestamp::try_now() {
+        Ok(now) => now.0,
+        Err(e) => return StreamingError::from(e).into_response(),
+    };
+    let Some(stream) = manager.stream(&stream_id).await else {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(json!({ "error": "Unknown or closed stream" })),
+        )
+            .into_response();
+    };
+    Json(json!({
+        "streamId": stream_id,
+        "payer": stream.payload.payer(),
+        "network": stream.payload.network,
+        "active": manager.is_active(&stream_id, now).await,
+        "deposit": stream.payload.permit.value,
+        "charged": stream.charged,
+        "expiresAt": UnixTimestamp(stream.expires_at()),
+    }))
+    .into_response()
+}
+
 // ============================================================================
 // ERC-8004 Feedback Handlers
 // ============================================================================
@@ -1547,16 +2532,16 @@ pub async fn get_feedback_info() -> impl IntoResponse {
         "specification": "https://eips.ethereum.org/EIPS/eip-8004",
         "body": {
             "x402Version": "number (1 or 2)",
-            "network": format!("string (e.g., '{}' or 'eip155:1')", networks.first().unwrap_or(&"ethereum")),
+            "network": format!("string (e.g., '{}' or 'eip155:1')", networks.first().map(String::as_str).unwrap_or("ethereum")),
             "feedback": {
                 "agentId": "number - Agent's token ID in the Identity Registry",
                 "value": "number - Feedback value (fixed-point, e.g., 87 means 87/100)",
                 "valueDecimals": "number (0-18) - Decimal places for value interpretation",
-                "tag1": "string - Primary categorization tag (e.g., 'starred', 'uptime', 'responseTime')",
-                "tag2": "string - Secondary categorization tag",
+                "tag1": "string (max 64 bytes) - Primary categorization tag (e.g., 'starred', 'uptime', 'responseTime')",
+                "tag2": "string (max 64 bytes) - Secondary categorization tag",
                 "endpoint": "string (optional) - Service endpoint that was used",
                 "feedbackUri": "string (optional) - URI to off-chain feedback file (IPFS, HTTPS)",
-                "feedbackHash": "string (optional) - Keccak256 hash of feedback content (32 bytes hex)",
+                "feedbackHash": "string (optional) - Keccak256 hash of the feedbackUri content (32 bytes hex), verified against it and computed when omitted",
                 "proof": {
                     "transactionHash": "string - Settlement transaction hash",
                     "blockNumber": "number - Block number of settlement",
@@ -1568,14 +2553,20 @@ pub async fn get_feedback_info() -> impl IntoResponse {
                     "timestamp": "number - Unix timestamp",
                     "paymentHash": "string - Keccak256 hash of payment data"
                 }
-            }
+            },
+            "feedbackFile": "object (optional) - Feedback file ({ type, feedback: [{ timestamp, result, value, valueDecimals, paymentInfo, interactions }] }) stored by the facilitator and submitted as feedbackUri and feedbackHash"
         },
         "endpoints": {
             "POST /feedback": "Submit new feedback",
+            "GET /feedback-files/:id": "Get a feedback file stored by the facilitator",
             "POST /feedback/revoke": "Revoke previously submitted feedback",
             "POST /feedback/response": "Append response to feedback (agent only)",
+            "GET /reputation/:agentId?network=ethereum&tag1=uptime&clients=0xabc,0xdef&includeFeedback=true&limit=20&offset=0": "Get reputation summary and paginated feedback for an agent",
             "GET /reputation/:network/:agentId": "Get reputation summary for an agent",
-            "GET /identity/:network/:agentId": "Get agent identity from Identity Registry"
+            "GET /reputation/batch?agentIds=1,2,3&network=ethereum": "Get reputation summaries for up to 50 agents",
+            "GET /reputation/aggregate/:agentUri": "Get an agent's reputation on every network of its registration file, with a combined weighted score",
+            "GET /identity/:network/:agentId": "Get agent identity from Identity Registry",
+            "GET /identity/:network/:agentId/registration": "Get the agent's resolved registration file"
         },
         "supportedNetworks": networks
     }))
@@ -1595,11 +2586,32 @@ pub async fn get_feedback_info() -> impl IntoResponse {
 /// - feedbackURI: URI to off-chain feedback file (IPFS, HTTPS) (optional)
 /// - feedbackHash: Keccak256 hash of feedback content (optional)
 ///
+/// A `feedbackURI` is fetched and its raw bytes hashed with keccak256: a `feedbackHash`
+/// must match it, and a missing one is filled in before submission.
+///
+/// A `feedbackFile` is stored instead (see [`feedback_storage`](crate::erc8004::feedback_storage)),
+/// pinned to IPFS or served at `GET /feedback-files/{id}`, and its URI and hash are
+/// submitted as `feedbackURI` and `feedbackHash`.
+///
 /// # Errors
 ///
+/// Every failure still answers with a [`FeedbackResponse`] whose `error` names the
+/// stage that failed:
+///
 /// - Returns 400 if the network doesn't support ERC-8004
-/// - Returns 400 if required fields are missing
-/// - Returns 500 if the on-chain submission fails
+/// - Returns 400 if required fields are missing, `valueDecimals` exceeds 18 or a tag
+///   is longer than 64 bytes
+/// - Returns 400 if a `feedbackFile` comes with a `feedbackURI` or no storage is
+///   configured, and 502 if it could not be stored
+/// - Returns 422 if `feedbackHash` does not match the `feedbackURI` content, or the
+///   URI is unsupported, answers with an error status or serves a file over 256 KiB;
+///   502 if it could not be fetched
+/// - Returns 422 naming the failed check if the proof of payment does not match the
+///   chain (see [`ProofOfPayment::verify`](crate::erc8004::ProofOfPayment::verify)), and
+///   502 if the chain could not be queried
+/// - Returns 409 if the proof of payment is already in use
+/// - Returns 500 if the transaction could not be submitted or emitted no `NewFeedback`
+/// - Returns 202 with the transaction hash if the receipt did not arrive in time
 #[instrument(skip_all)]
 pub async fn post_feedback<A>(
     State(facilitator): State<A>,
@@ -1611,7 +2623,7 @@ where
     A::Map: ProviderMap<Value = NetworkProvider>,
 {
     // Parse the request body
-    let request: FeedbackRequest = match serde_json::from_slice(&raw_body) {
+    let mut request: FeedbackRequest = match serde_json::from_slice(&raw_body) {
         Ok(req) => req,
         Err(e) => {
             error!("Failed to parse feedback request: {}", e);
@@ -1674,6 +2686,115 @@ where
 
     let feedback = &request.feedback;
 
+    if let Err(reason) = feedback.validate() {
+        warn!(network = %network, agent_id = feedback.agent_id, reason = %reason, "Rejected invalid feedback");
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(FeedbackResponse {
+                success: false,
+                transaction: None,
+                feedback_index: None,
+                error: Some(format!("Invalid feedback: {}", reason)),
+                network,
+            }),
+        )
+            .into_response();
+    }
+
+    // A feedback file sent along is stored here and its URI and hash are submitted;
+    // otherwise the on-chain feedbackHash must commit to the off-chain file
+    if let Some(file) = request.feedback_file.take() {
+        let stored = match (
+            FEEDBACK_STORAGE.as_deref(),
+            request.feedback.feedback_uri.is_empty(),
+        ) {
+            (_, false) => Err((
+                StatusCode::BAD_REQUEST,
+                "feedbackFile and feedbackUri cannot both be set".to_string(),
+            )),
+            (None, true) => Err((
+                StatusCode::BAD_REQUEST,
+                "Feedback file storage is not configured".to_string(),
+            )),
+            (Some(storage), true) => storage
+                .store(&file)
+                .await
+                .map_err(|e| (StatusCode::BAD_GATEWAY, e.to_string())),
+        };
+        match stored {
+            Ok((uri, hash)) => {
+                info!(network = %network, agent_id = request.feedback.agent_id, uri = %uri, "Stored feedback file");
+                request.feedback.feedback_uri = uri;
+                request.feedback.feedback_hash = Some(hash);
+            }
+            Err((status, error)) => {
+                warn!(network = %network, agent_id = request.feedback.agent_id, error = %error, "Failed to store feedback file");
+                return (
+                    status,
+                    Json(FeedbackResponse {
+                        success: false,
+                        transaction: None,
+                        feedback_index: None,
+                        error: Some(error),
+                        network,
+                    }),
+                )
+                    .into_response();
+            }
+        }
+    } else if let Err(e) = check_feedback_hash(&FEEDBACK_FILE_RESOLVER, &mut request.feedback).await
+    {
+        warn!(network = %network, agent_id = request.feedback.agent_id, error = %e, "Rejected feedback file");
+        let status = match e {
+            FeedbackFileError::Fetch(_) => StatusCode::BAD_GATEWAY,
+            _ => StatusCode::UNPROCESSABLE_ENTITY,
+        };
+        return (
+            status,
+            Json(FeedbackResponse {
+                success: false,
+                transaction: None,
+                feedback_index: None,
+                error: Some(e.to_string()),
+                network,
+            }),
+        )
+            .into_response();
+    }
+    let feedback = &request.feedback;
+
+    // A proof only counts if the payment it describes is on-chain
+    if let Some(proof) = &feedback.proof {
+        let verified = match facilitator.provider_map().by_network(&proof.network) {
+            Some(NetworkProvider::Evm(p)) => proof.verify(p.inner()).await,
+            _ => Err(ProofError::UnsupportedNetwork(proof.network)),
+        };
+        if let Err(e) = verified {
+            warn!(
+                network = %network,
+                agent_id = feedback.agent_id,
+                check = e.check(),
+                error = %e,
+                "Rejected proof of payment"
+            );
+            let status = match e {
+                ProofError::Rpc(_) => StatusCode::BAD_GATEWAY,
+                _ => StatusCode::UNPROCESSABLE_ENTITY,
+            };
+            return (
+                status,
+                Json(FeedbackResponse {
+                    success: false,
+                    transaction: None,
+                    feedback_index: None,
+                    error: Some(format!("Proof of payment failed the {} check: {}", e.check(), e)),
+                    network,
+                }),
+            )
+                .into_response();
+        }
+    }
+
     info!(
         network = %network,
         agent_id = feedback.agent_id,
@@ -1703,91 +2824,67 @@ where
         }
     };
 
-    // Create the contract instance
-    let reputation_registry =
-        IReputationRegistry::new(contracts.reputation_registry, provider.inner().clone());
-
-    // Convert feedbackHash to bytes32 (default to zero if not provided)
-    let feedback_hash = feedback.feedback_hash.unwrap_or_default();
-
-    // Build and send the transaction using official ERC-8004 giveFeedback function
-    let call = reputation_registry.giveFeedback(
-        alloy::primitives::U256::from(feedback.agent_id),
-        feedback.value,
-        feedback.value_decimals,
-        feedback.tag1.clone(),
-        feedback.tag2.clone(),
-        feedback.endpoint.clone(),
-        feedback.feedback_uri.clone(),
-        feedback_hash,
-    );
-
-    match call.send().await {
-        Ok(pending_tx) => {
-            // Wait for the transaction to be mined
-            match pending_tx.get_receipt().await {
-                Ok(receipt) => {
-                    let tx_hash = receipt.transaction_hash;
-                    info!(
-                        network = %network,
-                        tx = %tx_hash,
-                        agent_id = feedback.agent_id,
-                        "ERC-8004 feedback submitted successfully"
-                    );
+    // Submit through the compensating saga so a failure never burns the proof of payment
+    let agent_id = feedback.agent_id;
+    let chain = EvmFeedbackChain::new(provider.inner().clone(), contracts.reputation_registry);
+    let (status, transaction, feedback_index, error) = match FEEDBACK_SAGA.run(&chain, request).await {
+        Ok(receipt) => {
+            READ_CACHE.invalidate_summaries(agent_id, network);
+            (
+                StatusCode::OK,
+                Some(receipt.transaction),
+                Some(receipt.feedback_index),
+                None,
+            )
+        }
+        Err(e) => {
+            error!(network = %network, error = %e, "ERC-8004 feedback saga failed");
+            let (status, transaction) = match &e {
+                SagaError::InvalidProof(_) => (StatusCode::BAD_REQUEST, None),
+                SagaError::ProofInUse | SagaError::ProofAlreadyUsed => (StatusCode::CONFLICT, None),
+                SagaError::Unconfirmed { transaction, .. } => (StatusCode::ACCEPTED, Some(*transaction)),
+                SagaError::Rejected { transaction } => (StatusCode::INTERNAL_SERVER_ERROR, Some(*transaction)),
+                _ => (StatusCode::INTERNAL_SERVER_ERROR, None),
+            };
+            (status, transaction, None, Some(e.to_string()))
+        }
+    };
 
-                    // TODO: Parse logs to extract feedbackIndex from NewFeedback event
-                    let feedback_index = None; // Would need log parsing
+    (
+        status,
+        Json(FeedbackResponse {
+            success: status == StatusCode::OK,
+            transaction: transaction.map(|tx| crate::types::TransactionHash::Evm(tx.0)),
+            feedback_index,
+            error,
+            network,
+        }),
+    )
+        .into_response()
+}
 
-                    (
-                        StatusCode::OK,
-                        Json(FeedbackResponse {
-                            success: true,
-                            transaction: Some(crate::types::TransactionHash::Evm(tx_hash.0)),
-                            feedback_index,
-                            error: None,
-                            network,
-                        }),
-                    )
-                        .into_response()
-                }
-                Err(e) => {
-                    error!(
-                        network = %network,
-                        error = %e,
-                        "Failed to get transaction receipt for feedback"
-                    );
-                    (
-                        StatusCode::INTERNAL_SERVER_ERROR,
-                        Json(FeedbackResponse {
-                            success: false,
-                            transaction: None,
-                            feedback_index: None,
-                            error: Some(format!("Transaction failed: {}", e)),
-                            network,
-                        }),
-                    )
-                        .into_response()
-                }
-            }
-        }
-        Err(e) => {
-            error!(
-                network = %network,
-                error = %e,
-                "Failed to submit feedback transaction"
-            );
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(FeedbackResponse {
-                    success: false,
-                    transaction: None,
-                    feedback_index: None,
-                    error: Some(format!("Failed to submit transaction: {}", e)),
-                    network,
-                }),
-            )
-                .into_response()
-        }
+/// `GET /feedback-files/{id}`: Serve a feedback file stored by `POST /feedback`.
+///
+/// `id` is the hex keccak256 of the file. Returns 404 for unknown ids, or when feedback
+/// files are not stored on disk.
+#[instrument(skip_all, fields(id = %id))]
+pub async fn get_feedback_file(Path(id): Path<String>) -> impl IntoResponse {
+    let file = match LOCAL_FEEDBACK_STORAGE.as_ref() {
+        Some(storage) => storage.load(&id).await,
+        None => None,
+    };
+    match file {
+        Some(bytes) => (
+            StatusCode::OK,
+            [("content-type", "application/json")],
+            bytes,
+        )
+            .into_response(),
+        None => (
+            StatusCode::NOT_FOUND,
+            Json(json!({ "error": format!("Unknown feedback file: {}", id) })),
+        )
+            .into_response(),
     }
 }
 
@@ -1902,12 +2999,13 @@ where
                         tx = %tx_hash,
                         "ERC-8004 feedback revoked successfully"
                     );
+                    READ_CACHE.invalidate_summaries(request.agent_id, network);
 
                     (
                         StatusCode::OK,
                         Json(json!({
                             "success": true,
-                            "transaction": format!("0x{}", hex::encode(tx_hash.0)),
+                            "transaction": format!("0x{}", Hex32::new(&tx_hash.0)),
                             "network": network.to_string()
                         })),
                     )
@@ -2078,7 +3176,7 @@ where
                         StatusCode::OK,
                         Json(json!({
                             "success": true,
-                            "transaction": format!("0x{}", hex::encode(tx_hash.0)),
+                            "transaction": format!("0x{}", Hex32::new(&tx_hash.0)),
                             "network": network.to_string()
                         })),
                     )
@@ -2122,25 +3220,55 @@ pub struct ReputationPathParams {
 #[derive(Debug, Clone, serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ReputationQueryParams {
+    /// Network of the agent (`GET /reputation/:agent_id` only)
+    #[serde(default)]
+    pub network: Option<String>,
     /// Filter by tag1
     #[serde(default)]
     pub tag1: String,
     /// Filter by tag2
     #[serde(default)]
     pub tag2: String,
-    /// Include individual feedback entries
+    /// Comma-separated client addresses to restrict to (empty = all clients)
     #[serde(default)]
+    pub clients: String,
+    /// Include individual feedback entries
+    #[serde(default, alias = "include_feedback")]
     pub include_feedback: bool,
+    /// Feedback entries to skip
+    #[serde(default)]
+    pub offset: Option<usize>,
+    /// Feedback entries to return, capped at `ERC8004_MAX_FEEDBACK_PAGE`
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+impl ReputationQueryParams {
+    /// Client and tag filters, or why `clients` is not a list of EVM addresses.
+    fn filter(&self) -> Result<GetReputationRequest, String> {
+        let client_addresses = self
+            .clients
+            .split(',')
+            .map(str::trim)
+            .filter(|client| !client.is_empty())
+            .map(|client| {
+                client
+                    .parse::<EvmAddress>()
+                    .map(MixedAddress::Evm)
+                    .map_err(|_| format!("Invalid client address: {}", client))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(GetReputationRequest {
+            client_addresses,
+            tag1: self.tag1.clone(),
+            tag2: self.tag2.clone(),
+        })
+    }
 }
 
 /// `GET /reputation/:network/:agent_id`: Get reputation summary for an agent.
 ///
-/// Returns the aggregated reputation summary from the ERC-8004 Reputation Registry.
-///
-/// # Query Parameters
-/// - `tag1`: Filter by primary tag (optional)
-/// - `tag2`: Filter by secondary tag (optional)
-/// - `includeFeedback`: Include individual feedback entries (optional, default false)
+/// Same as [`get_agent_reputation`] with the network in the path.
 ///
 /// # Example
 /// ```text
@@ -2157,240 +3285,240 @@ where
     A::Error: IntoResponse,
     A::Map: ProviderMap<Value = NetworkProvider>,
 {
-    // Parse network from path
-    let network: crate::network::Network = match params.network.parse() {
-        Ok(n) => n,
-        Err(_) => {
-            return (
-                StatusCode::BAD_REQUEST,
-                Json(json!({
-                    "error": format!("Invalid network: {}", params.network)
-                })),
-            )
-                .into_response();
-        }
-    };
+    reputation(&facilitator, &params.network, params.agent_id, &query).await
+}
 
-    // Check if the network supports ERC-8004
-    if !is_erc8004_supported(&network) {
+/// `GET /reputation/:agent_id`: Get reputation summary for an agent.
+///
+/// Returns the aggregated reputation summary (`getSummary`) from the ERC-8004
+/// Reputation Registry, or 404 if the agent is not in the Identity Registry. Summaries
+/// are cached for `ERC8004_REPUTATION_CACHE_TTL` seconds (default: 60) per filter.
+///
+/// # Query Parameters
+/// - `network`: Network of the agent (required)
+/// - `tag1`: Filter by primary tag (optional)
+/// - `tag2`: Filter by secondary tag (optional)
+/// - `clients`: Comma-separated client addresses to restrict to (optional)
+/// - `includeFeedback`: Include individual feedback entries (optional, default false),
+///   served from the feedback index when it is warm and from `readAllFeedback`
+///   otherwise, as reported in `source`
+/// - `offset`, `limit`: Page of feedback entries to return; `limit` defaults to and
+///   is capped at `ERC8004_MAX_FEEDBACK_PAGE` (default: 100)
+///
+/// # Example
+/// ```text
+/// GET /reputation/42?network=ethereum&tag1=uptime&clients=0xabc...,0xdef...&include_feedback=true
+/// ```
+#[instrument(skip_all)]
+pub async fn get_agent_reputation<A>(
+    State(facilitator): State<A>,
+    Path(agent_id): Path<u64>,
+    Query(query): Query<ReputationQueryParams>,
+) -> impl IntoResponse
+where
+    A: Facilitator + HasProviderMap,
+    A::Error: IntoResponse,
+    A::Map: ProviderMap<Value = NetworkProvider>,
+{
+    let Some(network) = query.network.as_deref() else {
         return (
             StatusCode::BAD_REQUEST,
             Json(json!({
-                "error": format!("ERC-8004 is not supported on network {}", network),
+                "error": "Missing network query parameter",
                 "supportedNetworks": supported_network_names()
             })),
         )
             .into_response();
-    }
+    };
+    reputation(&facilitator, network, agent_id, &query).await
+}
 
-    // Get contracts for this network
-    let contracts = match get_contracts(&network) {
-        Some(c) => c,
-        None => {
-            return (
-                StatusCode::BAD_REQUEST,
-                Json(json!({
-                    "error": format!("No ERC-8004 contracts for network {}", network)
-                })),
-            )
-                .into_response();
-        }
+async fn reputation<A>(
+    facilitator: &A,
+    network: &str,
+    agent_id: u64,
+    query: &ReputationQueryParams,
+) -> Response
+where
+    A: HasProviderMap,
+    A::Map: ProviderMap<Value = NetworkProvider>,
+{
+    let filter = match query.filter() {
+        Ok(filter) => filter,
+        Err(e) => return (StatusCode::BAD_REQUEST, Json(json!({ "error": e }))).into_response(),
+    };
+    let client = match identity_client(facilitator, network) {
+        Ok(client) => client,
+        Err(response) => return response,
     };
 
     info!(
-        network = %network,
-        agent_id = params.agent_id,
+        network = %client.network(),
+        agent_id,
         tag1 = %query.tag1,
         tag2 = %query.tag2,
+        clients = filter.client_addresses.len(),
         "Querying ERC-8004 reputation"
     );
 
-    // Get the provider for this network
-    let provider_map = facilitator.provider_map();
-    let provider = match provider_map.by_network(&network) {
-        Some(NetworkProvider::Evm(p)) => p,
-        _ => {
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({
-                    "error": format!("No EVM provider available for network {}", network)
-                })),
-            )
-                .into_response();
-        }
-    };
-
-    // Create contract instance
-    let reputation_registry =
-        IReputationRegistry::new(contracts.reputation_registry, provider.inner().clone());
-
-    // Call getSummary on the contract
-    let summary_call = reputation_registry.getSummary(
-        alloy::primitives::U256::from(params.agent_id),
-        vec![], // Empty client filter = all clients
-        query.tag1.clone(),
-        query.tag2.clone(),
-    );
-
-    match summary_call.call().await {
-        Ok(result) => {
-            let summary = ReputationSummary {
-                agent_id: params.agent_id,
-                count: result.count,
-                summary_value: result.summaryValue,
-                summary_value_decimals: result.summaryValueDecimals,
-                network: network.clone(),
-            };
-
-            // Optionally fetch individual feedback entries
-            let feedback_entries: Option<Vec<FeedbackEntry>> = if query.include_feedback {
-                // Call readAllFeedback
-                let feedback_call = reputation_registry.readAllFeedback(
-                    alloy::primitives::U256::from(params.agent_id),
-                    vec![], // All clients
-                    query.tag1.clone(),
-                    query.tag2.clone(),
-                    false, // Don't include revoked
-                );
-
-                match feedback_call.call().await {
-                    Ok(fb_result) => {
-                        let entries: Vec<FeedbackEntry> = fb_result.clients
-                            .iter()
-                            .zip(fb_result.feedbackIndexes.iter())
-                            .zip(fb_result.values.iter())
-                            .zip(fb_result.valueDecimals.iter())
-                            .zip(fb_result.tag1s.iter())
-                            .zip(fb_result.tag2s.iter())
-                            .zip(fb_result.revokedStatuses.iter())
-                            .map(|((((((client, idx), val), dec), t1), t2), revoked)| {
-                                FeedbackEntry {
-                                    client: MixedAddress::Evm(crate::types::EvmAddress(*client)),
-                                    feedback_index: *idx,
-                                    value: *val,
-                                    value_decimals: *dec,
-                                    tag1: t1.clone(),
-                                    tag2: t2.clone(),
-                                    is_revoked: *revoked,
-                                }
-                            })
-                            .collect();
-                        Some(entries)
-                    }
-                    Err(e) => {
-                        warn!(error = %e, "Failed to fetch feedback entries, returning summary only");
-                        None
-                    }
-                }
-            } else {
-                None
-            };
+    let page = query
+        .include_feedback
+        .then(|| FeedbackPage::new(query.offset, query.limit, *MAX_FEEDBACK_PAGE));
+    reputation_response(
+        &client,
+        &FEEDBACK_INDEX,
+        &READ_CACHE,
+        agent_id,
+        &filter,
+        page,
+    )
+    .await
+}
 
-            let response = ReputationResponse {
-                agent_id: params.agent_id,
-                summary,
-                feedback: feedback_entries,
-                network,
-            };
+/// Reputation of `agent_id` read through `client`, as returned by `GET /reputation`.
+///
+/// The registration check and the summary go through `cache`. Feedback entries are read
+/// only when `page` is set: from `index` when it is warm for the client's registry,
+/// otherwise from the chain. If the chain read fails, the summary is returned without
+/// them.
+pub async fn reputation_response<P: alloy::providers::Provider>(
+    client: &Erc8004Client<P>,
+    index: &FeedbackIndex,
+    cache: &ReadCache,
+    agent_id: u64,
+    filter: &GetReputationRequest,
+    page: Option<FeedbackPage>,
+) -> Response {
+    match cache.agent_exists(client, agent_id).await {
+        Ok(true) => {}
+        Ok(false) => return identity_error_response(Erc8004Error::AgentNotFound(agent_id)),
+        Err(e) => return identity_error_response(e),
+    }
 
-            (StatusCode::OK, Json(response)).into_response()
-        }
+    let summary = match cache.summary(client, agent_id, filter).await {
+        Ok(summary) => summary,
         Err(e) => {
             error!(
-                network = %network,
-                agent_id = params.agent_id,
+                network = %client.network(),
+                agent_id,
                 error = %e,
                 "Failed to query reputation"
             );
-            (
+            return (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 Json(json!({
                     "error": format!("Failed to query reputation: {}", e)
                 })),
             )
-                .into_response()
+                .into_response();
         }
-    }
+    };
+
+    let (feedback, feedback_total, source) = match page {
+        Some(page) => {
+            let registry = client.contracts().reputation_registry;
+            let read = match index.feedback(client.network(), registry, agent_id, filter) {
+                Some(entries) => Ok((entries, FeedbackSource::Cache)),
+                None => client
+                    .read_all_feedback(agent_id, filter, false)
+                    .await
+                    .map(|entries| (entries, FeedbackSource::Chain)),
+            };
+            match read {
+                Ok((entries, source)) => {
+                    let total = entries.len();
+                    (Some(page.apply(entries)), Some(total), Some(source))
+                }
+                Err(e) => {
+                    warn!(error = %e, "Failed to fetch feedback entries, returning summary only");
+                    (None, None, None)
+                }
+            }
+        }
+        None => (None, None, None),
+    };
+
+    let response = ReputationResponse {
+        agent_id,
+        summary,
+        feedback,
+        feedback_total,
+        source,
+        network: client.network(),
+    };
+    (StatusCode::OK, Json(response)).into_response()
 }
 
-/// Path parameters for identity query
+/// Query parameters for batch reputation query
 #[derive(Debug, Clone, serde::Deserialize)]
-pub struct IdentityPathParams {
+#[serde(rename_all = "camelCase")]
+pub struct ReputationBatchQueryParams {
+    /// Comma-separated agent IDs
+    pub agent_ids: String,
+    /// Network of the agents
     pub network: String,
-    pub agent_id: u64,
 }
 
-/// `GET /identity/:network/:agent_id`: Get agent identity from the ERC-8004 Identity Registry.
+/// `GET /reputation/batch`: Get reputation summaries for several agents at once.
 ///
-/// Returns the agent's identity information including:
-/// - Owner address
-/// - Agent URI (metadata file location)
-/// - Payment wallet (if set)
+/// Reads `getSummary` for every agent concurrently. Summaries are cached for
+/// `ERC8004_REPUTATION_CACHE_TTL` seconds (default: 60); `cachedCount` tells how many
+/// results came from the cache. At most 50 agents per request.
 ///
 /// # Example
 /// ```text
-/// GET /identity/ethereum-mainnet/42
+/// GET /reputation/batch?agentIds=1,2,3&network=ethereum
 /// ```
 #[instrument(skip_all)]
-pub async fn get_identity<A>(
+pub async fn get_reputation_batch<A>(
     State(facilitator): State<A>,
-    Path(params): Path<IdentityPathParams>,
+    Query(query): Query<ReputationBatchQueryParams>,
 ) -> impl IntoResponse
 where
     A: Facilitator + HasProviderMap,
     A::Error: IntoResponse,
     A::Map: ProviderMap<Value = NetworkProvider>,
 {
-    // Parse network from path
-    let network: crate::network::Network = match params.network.parse() {
+    let agent_ids = match parse_agent_ids(&query.agent_ids) {
+        Ok(agent_ids) => agent_ids,
+        Err(e) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(json!({ "error": e.to_string() })),
+            )
+                .into_response();
+        }
+    };
+
+    let network: crate::network::Network = match query.network.parse() {
         Ok(n) => n,
         Err(_) => {
             return (
                 StatusCode::BAD_REQUEST,
                 Json(json!({
-                    "error": format!("Invalid network: {}", params.network)
+                    "error": format!("Invalid network: {}", query.network)
                 })),
             )
                 .into_response();
         }
     };
 
-    // Check if the network supports ERC-8004
-    if !is_erc8004_supported(&network) {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(json!({
-                "error": format!("ERC-8004 is not supported on network {}", network),
-                "supportedNetworks": supported_network_names()
-            })),
-        )
-            .into_response();
-    }
-
-    // Get contracts for this network
     let contracts = match get_contracts(&network) {
         Some(c) => c,
         None => {
             return (
                 StatusCode::BAD_REQUEST,
                 Json(json!({
-                    "error": format!("No ERC-8004 contracts for network {}", network)
+                    "error": format!("ERC-8004 is not supported on network {}", network),
+                    "supportedNetworks": supported_network_names()
                 })),
             )
                 .into_response();
         }
     };
 
-    info!(
-        network = %network,
-        agent_id = params.agent_id,
-        "Querying ERC-8004 agent identity"
-    );
-
-    // Get the provider for this network
-    let provider_map = facilitator.provider_map();
-    let provider = match provider_map.by_network(&network) {
-        Some(NetworkProvider::Evm(p)) => p,
+    let provider = match facilitator.provider_map().by_network(&network) {
+        Some(NetworkProvider::Evm(p)) => p.inner().clone(),
         _ => {
             return (
                 StatusCode::INTERNAL_SERVER_ERROR,
@@ -2402,92 +3530,1127 @@ where
         }
     };
 
-    // Create contract instance
-    let identity_registry =
-        IIdentityRegistry::new(contracts.identity_registry, provider.inner().clone());
-
-    let agent_id_u256 = alloy::primitives::U256::from(params.agent_id);
+    info!(
+        network = %network,
+        agents = agent_ids.len(),
+        "Querying ERC-8004 reputation batch"
+    );
 
-    // Check if agent exists
-    let exists_call = identity_registry.exists(agent_id_u256);
-    match exists_call.call().await {
-        Ok(exists) => {
-            if !exists {
-                return (
-                    StatusCode::NOT_FOUND,
-                    Json(json!({
-                        "error": format!("Agent {} not found in Identity Registry", params.agent_id)
-                    })),
-                )
-                    .into_response();
-            }
-        }
-        Err(e) => {
-            error!(error = %e, "Failed to check if agent exists");
-            return (
+    let reader = EvmReputationReader::new().with_network(network, provider, contracts);
+    match REPUTATION_CACHE
+        .fetch_batch(&reader, network, &agent_ids)
+        .await
+    {
+        Ok(batch) => (StatusCode::OK, Json(batch)).into_response(),
+        Err(e @ BatchError::Read { .. }) => {
+            error!(network = %network, error = %e, "Failed to query reputation batch");
+            (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({
-                    "error": format!("Failed to check agent existence: {}", e)
-                })),
+                Json(json!({ "error": e.to_string() })),
             )
-                .into_response();
+                .into_response()
         }
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            Json(json!({ "error": e.to_string() })),
+        )
+            .into_response(),
     }
+}
 
-    // Get owner, URI, and wallet in parallel
-    let owner_call = identity_registry.ownerOf(agent_id_u256);
-    let uri_call = identity_registry.tokenURI(agent_id_u256);
-    let wallet_call = identity_registry.getAgentWallet(agent_id_u256);
-
-    let (owner_result, uri_result, wallet_result) = tokio::join!(
-        owner_call.call(),
-        uri_call.call(),
-        wallet_call.call()
-    );
-
-    let owner = match owner_result {
-        Ok(o) => MixedAddress::Evm(crate::types::EvmAddress(o)),
+/// `GET /reputation/aggregate/:agent_uri`: Reputation of an agent across networks.
+///
+/// Resolves the registration file at `agent_uri` (URL-encoded, or the rest of the path)
+/// and reads the unfiltered summary of every registration in its `registrations` array
+/// that is on a supported network. Each registration gets its own entry, with an
+/// `error` instead of a `summary` when it could not be read, and `weightedScore`
+/// combines the summaries as described by the `weighting` field. Summaries are cached
+/// like those of [`get_reputation_batch`].
+///
+/// - Returns 400 if `agent_uri` is not a supported URI
+/// - Returns 502 if the registration file could not be fetched or parsed
+///
+/// # Example
+/// ```text
+/// GET /reputation/aggregate/ipfs%3A%2F%2Fbafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi
+/// ```
+#[instrument(skip_all)]
+pub async fn get_aggregated_reputation<A>(
+    State(facilitator): State<A>,
+    Path(agent_uri): Path<String>,
+) -> impl IntoResponse
+where
+    A: Facilitator + HasProviderMap,
+    A::Error: IntoResponse,
+    A::Map: ProviderMap<Value = NetworkProvider>,
+{
+    let registration = match AGENT_URI_RESOLVER.resolve(&agent_uri).await {
+        Ok(file) => file,
         Err(e) => {
-            error!(error = %e, "Failed to get agent owner");
+            warn!(agent_uri = %agent_uri, error = %e, "Failed to resolve agent registration file");
+            let status = match e {
+                ResolverError::EmptyUri
+                | ResolverError::UnsupportedScheme(_)
+                | ResolverError::InvalidUri(_) => StatusCode::BAD_REQUEST,
+                _ => StatusCode::BAD_GATEWAY,
+            };
             return (
-                StatusCode::INTERNAL_SERVER_ERROR,
+                status,
                 Json(json!({
-                    "error": format!("Failed to get agent owner: {}", e)
+                    "error": e.to_string(),
+                    "agentUri": agent_uri
                 })),
             )
                 .into_response();
         }
     };
 
-    let agent_uri = match uri_result {
-        Ok(u) => u,
-        Err(e) => {
-            warn!(error = %e, "Failed to get agent URI, using empty string");
-            String::new()
-        }
-    };
-
-    let agent_wallet = match wallet_result {
-        Ok(w) => {
-            if w == alloy::primitives::Address::ZERO {
-                None
-            } else {
-                Some(MixedAddress::Evm(crate::types::EvmAddress(w)))
+    let provider_map = facilitator.provider_map();
+    let clients: HashMap<_, _> = supported_networks()
+        .into_iter()
+        .filter_map(|network| {
+            let contracts = get_contracts(&network)?;
+            match provider_map.by_network(&network) {
+                Some(NetworkProvider::Evm(provider)) => Some((
+                    network,
+                    Erc8004Client::new(provider.inner().clone(), contracts).with_network(network),
+                )),
+                _ => None,
             }
-        }
-        Err(e) => {
-            warn!(error = %e, "Failed to get agent wallet");
-            None
-        }
-    };
-
-    let identity = AgentIdentity {
-        agent_id: params.agent_id,
-        owner,
-        agent_uri,
-        agent_wallet,
-        network,
-    };
+        })
+        .collect();
 
-    (StatusCode::OK, Json(identity)).into_response()
+    info!(
+        agent_uri = %agent_uri,
+        registrations = registration.registrations.len(),
+        "Querying ERC-8004 reputation across networks"
+    );
+
+    let response = aggregate_reputation(
+        &REPUTATION_CACHE,
+        &agent_uri,
+        &registration.registrations,
+        &clients,
+    )
+    .await;
+    (StatusCode::OK, Json(response)).into_response()
+}
+
+/// Path parameters for identity query
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct IdentityPathParams {
+    pub network: String,
+    pub agent_id: u64,
+}
+
+/// ERC-8004 registry client for the network named in an `/identity` or `/reputation`
+/// request.
+///
+/// Fails with the response to return when the network is unknown, has no ERC-8004
+/// deployment or has no EVM provider.
+fn identity_client<A>(
+    facilitator: &A,
+    network: &str,
+) -> Result<Erc8004Client<InnerProvider>, Response>
+where
+    A: HasProviderMap,
+    A::Map: ProviderMap<Value = NetworkProvider>,
+{
+    let network: crate::network::Network = network.parse().map_err(|_| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(json!({
+                "error": format!("Invalid network: {}", network)
+            })),
+        )
+            .into_response()
+    })?;
+
+    // Check if the network supports ERC-8004
+    if !is_erc8004_supported(&network) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({
+                "error": format!("ERC-8004 is not supported on network {}", network),
+                "supportedNetworks": supported_network_names()
+            })),
+        )
+            .into_response());
+    }
+
+    // Get contracts for this network
+    let contracts = get_contracts(&network).ok_or_else(|| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(json!({
+                "error": format!("No ERC-8004 contracts for network {}", network)
+            })),
+        )
+            .into_response()
+    })?;
+
+    // Get the provider for this network
+    let provider_map = facilitator.provider_map();
+    let provider = match provider_map.by_network(&network) {
+        Some(NetworkProvider::Evm(p)) => p,
+        _ => {
+            return Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({
+                    "error": format!("No EVM provider available for network {}", network)
+                })),
+            )
+                .into_response());
+        }
+    };
+
+    Ok(Erc8004Client::new(provider.inner().clone(), contracts).with_network(network))
+}
+
+/// Response for an [`Erc8004Error`] from an Identity Registry read.
+fn identity_error_response(error: Erc8004Error) -> Response {
+    match error {
+        Erc8004Error::AgentNotFound(agent_id) => (
+            StatusCode::NOT_FOUND,
+            Json(json!({
+                "error": format!("Agent {} not found in Identity Registry", agent_id)
+            })),
+        )
+            .into_response(),
+        e => {
+            error!(error = %e, "Failed to read agent identity");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({
+                    "error": format!("Failed to read agent identity: {}", e)
+                })),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// `GET /identity/:network/:agent_id`: Get agent identity from the ERC-8004 Identity Registry.
+///
+/// Returns the agent's identity information including:
+/// - Owner address
+/// - Agent URI (metadata file location)
+/// - Payment wallet (if set)
+/// - The registration file resolved from the agent URI (`null` with an `errors` entry
+///   if it could not be fetched or parsed)
+///
+/// Registry reads are cached for `ERC8004_IDENTITY_CACHE_TTL` seconds (default: 600);
+/// see [`read_cache`](crate::erc8004::read_cache).
+///
+/// # Example
+/// ```text
+/// GET /identity/ethereum-mainnet/42
+/// ```
+#[instrument(skip_all)]
+pub async fn get_identity<A>(
+    State(facilitator): State<A>,
+    Path(params): Path<IdentityPathParams>,
+) -> impl IntoResponse
+where
+    A: Facilitator + HasProviderMap,
+    A::Error: IntoResponse,
+    A::Map: ProviderMap<Value = NetworkProvider>,
+{
+    let client = match identity_client(&facilitator, &params.network) {
+        Ok(client) => client,
+        Err(response) => return response,
+    };
+
+    info!(
+        network = %client.network(),
+        agent_id = params.agent_id,
+        "Querying ERC-8004 agent identity"
+    );
+
+    let identity = match READ_CACHE.identity(&client, params.agent_id).await {
+        Ok(identity) => identity,
+        Err(e) => return identity_error_response(e),
+    };
+
+    // A missing or broken registration file does not fail the lookup
+    let (registration, errors) = match AGENT_URI_RESOLVER.resolve(&identity.agent_uri).await {
+        Ok(file) => (Some(file), Vec::new()),
+        Err(e) => {
+            warn!(
+                agent_id = identity.agent_id,
+                agent_uri = %identity.agent_uri,
+                error = %e,
+                "Failed to resolve agent registration file"
+            );
+            (None, vec![e.to_string()])
+        }
+    };
+
+    (
+        StatusCode::OK,
+        Json(AgentIdentityResponse {
+            identity,
+            registration,
+            errors,
+        }),
+    )
+        .into_response()
+}
+
+/// `GET /identity/:network/:agent_id/registration`: Get an agent's registration file.
+///
+/// Reads the agent URI from the Identity Registry and returns the resolved
+/// [`AgentRegistrationFile`](crate::erc8004::AgentRegistrationFile). `ipfs://` and `ar://` URIs are fetched through the
+/// configured gateways.
+///
+/// # Errors
+///
+/// - Returns 404 if the agent is not registered or has no agent URI
+/// - Returns 502 if the registration file cannot be fetched or parsed
+///
+/// # Example
+/// ```text
+/// GET /identity/ethereum-mainnet/42/registration
+/// ```
+#[instrument(skip_all)]
+pub async fn get_identity_registration<A>(
+    State(facilitator): State<A>,
+    Path(params): Path<IdentityPathParams>,
+) -> impl IntoResponse
+where
+    A: Facilitator + HasProviderMap,
+    A::Error: IntoResponse,
+    A::Map: ProviderMap<Value = NetworkProvider>,
+{
+    let client = match identity_client(&facilitator, &params.network) {
+        Ok(client) => client,
+        Err(response) => return response,
+    };
+
+    let agent_uri = match READ_CACHE.agent_uri(&client, params.agent_id).await {
+        Ok(uri) => uri,
+        Err(e) => return identity_error_response(e),
+    };
+
+    match AGENT_URI_RESOLVER.resolve(&agent_uri).await {
+        Ok(file) => (StatusCode::OK, Json(file)).into_response(),
+        Err(e) => {
+            warn!(
+                agent_id = params.agent_id,
+                agent_uri = %agent_uri,
+                error = %e,
+                "Failed to resolve agent registration file"
+            );
+            let status = match e {
+                ResolverError::EmptyUri => StatusCode::NOT_FOUND,
+                _ => StatusCode::BAD_GATEWAY,
+            };
+            (
+                status,
+                Json(json!({
+                    "error": e.to_string(),
+                    "agentUri": agent_uri
+                })),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// Query parameters for DELETE /cache/identity/:agent_id
+#[derive(Debug, Default, serde::Deserialize)]
+pub struct CacheInvalidationQuery {
+    /// Only drop the reads made on this network
+    pub network: Option<String>,
+}
+
+/// `DELETE /cache/identity/:agent_id`: Drop the cached identity reads of an agent.
+///
+/// Requires the `ERC8004_CACHE_ADMIN_KEY` in `X-API-Key`. Drops the agent's reads on
+/// every network, or only on `network`, so the next lookup reads the chain again.
+/// Returns how many reads were dropped.
+///
+/// # Example
+/// ```text
+/// DELETE /cache/identity/42?network=ethereum
+/// ```
+#[instrument(skip_all, fields(agent_id = agent_id))]
+pub async fn delete_identity_cache(
+    State(admin_key): State<AdminKey>,
+    headers: HeaderMap,
+    Path(agent_id): Path<u64>,
+    Query(query): Query<CacheInvalidationQuery>,
+) -> impl IntoResponse {
+    if let Err(rejection) = admin_key.require(&headers) {
+        return rejection;
+    }
+
+    let network = match query.network.as_deref().map(str::parse::<crate::network::Network>) {
+        None => None,
+        Some(Ok(network)) => Some(network),
+        Some(Err(_)) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(json!({
+                    "error": format!("Invalid network: {}", query.network.unwrap_or_default())
+                })),
+            )
+                .into_response();
+        }
+    };
+    let invalidated = READ_CACHE.invalidate_identity(agent_id, network);
+    info!(agent_id, network = ?network, invalidated, "Invalidated cached ERC-8004 identity reads");
+    (
+        StatusCode::OK,
+        Json(json!({
+            "agentId": agent_id,
+            "invalidated": invalidated
+        })),
+    )
+        .into_response()
+}
+
+/// `POST /identity/register`: Register an ERC-8004 agent with the facilitator signer.
+///
+/// Calls `register(agentURI, metadata)` on the network's Identity Registry and, when
+/// `owner` is set, transfers the new agent NFT to it in a second transaction. Requires
+/// one of the `IDENTITY_REGISTER_API_KEYS` in the `X-API-Key` header; each key may
+/// register `IDENTITY_REGISTER_DAILY_QUOTA` agents per UTC day.
+///
+/// # Errors
+///
+/// - Returns 401 without a valid API key
+/// - Returns 400 for a malformed request or a network without ERC-8004 contracts
+/// - Returns 429 once the key's daily quota is used up
+/// - Returns 500 if a transaction fails; `agentId` and `transaction` are still set if
+///   the agent was registered but could not be transferred
+///
+/// # Example
+/// ```text
+/// POST /identity/register
+/// X-API-Key: ...
+///
+/// {
+///   "network": "base-sepolia",
+///   "agentUri": "ipfs://bafy.../agent.json",
+///   "metadata": [{ "key": "agentName", "value": "Weather Bot" }],
+///   "owner": "0x..."
+/// }
+/// ```
+#[instrument(skip_all)]
+pub async fn post_identity_register<A>(
+    State(facilitator): State<A>,
+    Extension(registrar): Extension<Arc<IdentityRegistrar>>,
+    headers: HeaderMap,
+    raw_body: Bytes,
+) -> impl IntoResponse
+where
+    A: Facilitator + HasProviderMap,
+    A::Error: IntoResponse,
+    A::Map: ProviderMap<Value = NetworkProvider>,
+{
+    let api_key = headers
+        .get(ADMIN_KEY_HEADER)
+        .and_then(|value| value.to_str().ok());
+    let api_key = match registrar.authenticate(api_key) {
+        Ok(key) => key,
+        Err(e) => {
+            return (
+                StatusCode::UNAUTHORIZED,
+                Json(json!({ "error": e.to_string() })),
+            )
+                .into_response();
+        }
+    };
+
+    let request: RegisterAgentRequest = match serde_json::from_slice(&raw_body) {
+        Ok(req) => req,
+        Err(e) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(json!({ "error": format!("Invalid request format: {}", e) })),
+            )
+                .into_response();
+        }
+    };
+    let network = request.network;
+    let failure = |status: StatusCode, error: String| {
+        (
+            status,
+            Json(RegisterAgentResponse {
+                success: false,
+                agent_id: None,
+                owner: None,
+                transaction: None,
+                transfer_transaction: None,
+                error: Some(error),
+                network,
+            }),
+        )
+            .into_response()
+    };
+
+    let Some(contracts) = get_contracts(&network) else {
+        return failure(
+            StatusCode::BAD_REQUEST,
+            format!("ERC-8004 is not supported on network {}", network),
+        );
+    };
+    let provider_map = facilitator.provider_map();
+    let Some(NetworkProvider::Evm(provider)) = provider_map.by_network(&network) else {
+        return failure(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("No EVM provider available for network {}", network),
+        );
+    };
+
+    if let Err(e) = registrar.acquire(api_key) {
+        warn!(network = %network, "Agent registration quota exhausted");
+        return failure(StatusCode::TOO_MANY_REQUESTS, e.to_string());
+    }
+
+    info!(network = %network, agent_uri = %request.agent_uri, "Registering ERC-8004 agent");
+    let tx_hash = |tx: alloy::primitives::B256| crate::types::TransactionHash::Evm(tx.0);
+    match register_agent(provider.inner(), contracts.identity_registry, &request).await {
+        Ok(registration) => {
+            // An earlier lookup may have cached the agent as not registered
+            READ_CACHE.invalidate_identity(registration.agent_id, Some(network));
+            (
+                StatusCode::OK,
+                Json(RegisterAgentResponse {
+                    success: true,
+                    agent_id: Some(registration.agent_id),
+                    owner: Some(registration.owner),
+                    transaction: Some(tx_hash(registration.transaction)),
+                    transfer_transaction: registration.transfer_transaction.map(tx_hash),
+                    error: None,
+                    network,
+                }),
+            )
+                .into_response()
+        }
+        Err(e) => {
+            error!(network = %network, error = %e, "ERC-8004 agent registration failed");
+            let (agent_id, transaction) = match &e {
+                RegistrationError::Transfer {
+                    agent_id,
+                    transaction,
+                    ..
+                } => (Some(*agent_id), Some(tx_hash(*transaction))),
+                RegistrationError::Reverted(transaction)
+                | RegistrationError::MissingEvent(transaction) => {
+                    (None, Some(tx_hash(*transaction)))
+                }
+                RegistrationError::Register(_) => (None, None),
+            };
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(RegisterAgentResponse {
+                    success: false,
+                    agent_id,
+                    owner: None,
+                    transaction,
+                    transfer_transaction: None,
+                    error: Some(e.to_string()),
+                    network,
+                }),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// `POST /identity/{agent_id}/wallet`: Set an agent's payment wallet.
+///
+/// Submits `setAgentWallet` with the EIP-712 signature of the new wallet, built with
+/// [`build_set_agent_wallet_payload`](crate::erc8004::wallet::build_set_agent_wallet_payload).
+/// The transaction is sent by the facilitator signer, so it only succeeds for agents the
+/// facilitator owns or is approved for. Takes the same API keys and daily quota as
+/// `POST /identity/register`.
+///
+/// # Errors
+///
+/// - Returns 401 without a valid API key
+/// - Returns 400 for a malformed request, a network without ERC-8004 contracts, or an
+///   update the registry rejects (bad signature, expired deadline, agent not held by
+///   the facilitator)
+/// - Returns 429 once the key's daily quota is used up
+/// - Returns 500 if the transaction fails
+///
+/// # Example
+/// ```text
+/// POST /identity/42/wallet
+/// X-API-Key: ...
+///
+/// {
+///   "network": "base-sepolia",
+///   "newWallet": "0x...",
+///   "deadline": 1767225600,
+///   "signature": "0x..."
+/// }
+/// ```
+#[instrument(skip_all)]
+pub async fn post_identity_wallet<A>(
+    State(facilitator): State<A>,
+    Extension(registrar): Extension<Arc<IdentityRegistrar>>,
+    Path(agent_id): Path<u64>,
+    headers: HeaderMap,
+    raw_body: Bytes,
+) -> impl IntoResponse
+where
+    A: Facilitator + HasProviderMap,
+    A::Error: IntoResponse,
+    A::Map: ProviderMap<Value = NetworkProvider>,
+{
+    let api_key = headers
+        .get(ADMIN_KEY_HEADER)
+        .and_then(|value| value.to_str().ok());
+    let api_key = match registrar.authenticate(api_key) {
+        Ok(key) => key,
+        Err(e) => {
+            return (
+                StatusCode::UNAUTHORIZED,
+                Json(json!({ "error": e.to_string() })),
+            )
+                .into_response();
+        }
+    };
+
+    let request: SetAgentWalletRequest = match serde_json::from_slice(&raw_body) {
+        Ok(req) => req,
+        Err(e) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(json!({ "error": format!("Invalid request format: {}", e) })),
+            )
+                .into_response();
+        }
+    };
+    let network = request.network;
+    let response = |status: StatusCode, transaction, error: Option<String>| {
+        (
+            status,
+            Json(SetAgentWalletResponse {
+                success: error.is_none(),
+                agent_id,
+                wallet: request.new_wallet,
+                transaction,
+                error,
+                network,
+            }),
+        )
+            .into_response()
+    };
+
+    let Some(contracts) = get_contracts(&network) else {
+        return response(
+            StatusCode::BAD_REQUEST,
+            None,
+            Some(format!("ERC-8004 is not supported on network {}", network)),
+        );
+    };
+    let provider_map = facilitator.provider_map();
+    let Some(NetworkProvider::Evm(provider)) = provider_map.by_network(&network) else {
+        return response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            None,
+            Some(format!("No EVM provider available for network {}", network)),
+        );
+    };
+
+    let registry = contracts.identity_registry;
+    if let Err(e) = check_agent_wallet(provider.inner(), registry, agent_id, &request).await {
+        warn!(network = %network, agent_id, error = %e, "Agent wallet update rejected");
+        return response(StatusCode::BAD_REQUEST, None, Some(e.to_string()));
+    }
+    if let Err(e) = registrar.acquire(api_key) {
+        warn!(network = %network, "Agent registration quota exhausted");
+        return response(StatusCode::TOO_MANY_REQUESTS, None, Some(e.to_string()));
+    }
+
+    info!(network = %network, agent_id, wallet = %request.new_wallet, "Setting ERC-8004 agent wallet");
+    match set_agent_wallet(provider.inner(), registry, agent_id, &request).await {
+        Ok(tx) => {
+            READ_CACHE.invalidate_identity(agent_id, Some(network));
+            response(
+                StatusCode::OK,
+                Some(crate::types::TransactionHash::Evm(tx.0)),
+                None,
+            )
+        }
+        Err(e) => {
+            error!(network = %network, agent_id, error = %e, "Setting ERC-8004 agent wallet failed");
+            response(StatusCode::INTERNAL_SERVER_ERROR, None, Some(e.to_string()))
+        }
+    }
+}
+
+// ============================================================================
+// ERC-8004 Validation Handlers
+// ============================================================================
+
+/// Validation Registry of `network`.
+///
+/// # Errors
+///
+/// - Returns 400 for a network without ERC-8004 contracts
+/// - Returns 501, with the networks that have one, where no Validation Registry is deployed
+pub fn validation_registry(network: crate::network::Network) -> Result<alloy::primitives::Address, Response> {
+    let Some(contracts) = get_contracts(&network) else {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({
+                "error": format!("ERC-8004 is not supported on network {}", network),
+                "supportedNetworks": supported_network_names()
+            })),
+        )
+            .into_response());
+    };
+    contracts.validation_registry.ok_or_else(|| {
+        (
+            StatusCode::NOT_IMPLEMENTED,
+            Json(json!({
+                "error": format!("No Validation Registry is deployed on network {}", network),
+                "supportedNetworks": validation_network_names()
+            })),
+        )
+            .into_response()
+    })
+}
+
+/// `POST /validation/request`: Ask a validator to validate an agent.
+///
+/// Submits `validationRequest` with the facilitator signer, so it only succeeds for
+/// agents the facilitator owns or is approved for. Without a `requestHash`, the request
+/// is identified by `keccak256(abi.encode(validatorAddress, agentId, requestURI))`.
+/// Takes the same API keys and daily quota as `POST /identity/register`.
+///
+/// # Errors
+///
+/// - Returns 401 without a valid API key
+/// - Returns 400 for a malformed request, a network without ERC-8004 contracts, or a
+///   request the registry rejects
+/// - Returns 501 on networks without a Validation Registry
+/// - Returns 429 once the key's daily quota is used up
+/// - Returns 500 if the transaction fails
+///
+/// # Example
+/// ```text
+/// POST /validation/request
+/// X-API-Key: ...
+///
+/// {
+///   "x402Version": 1,
+///   "network": "ethereum-sepolia",
+///   "validatorAddress": "0x...",
+///   "agentId": 42,
+///   "requestUri": "ipfs://bafy.../request.json"
+/// }
+/// ```
+#[instrument(skip_all)]
+pub async fn post_validation_request<A>(
+    State(facilitator): State<A>,
+    Extension(registrar): Extension<Arc<IdentityRegistrar>>,
+    headers: HeaderMap,
+    raw_body: Bytes,
+) -> impl IntoResponse
+where
+    A: Facilitator + HasProviderMap,
+    A::Error: IntoResponse,
+    A::Map: ProviderMap<Value = NetworkProvider>,
+{
+    let api_key = headers
+        .get(ADMIN_KEY_HEADER)
+        .and_then(|value| value.to_str().ok());
+    let api_key = match registrar.authenticate(api_key) {
+        Ok(key) => key,
+        Err(e) => {
+            return (
+                StatusCode::UNAUTHORIZED,
+                Json(json!({ "error": e.to_string() })),
+            )
+                .into_response();
+        }
+    };
+
+    let request: ValidationRequestParams = match serde_json::from_slice(&raw_body) {
+        Ok(req) => req,
+        Err(e) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(json!({ "error": format!("Invalid request format: {}", e) })),
+            )
+                .into_response();
+        }
+    };
+    let network = request.network;
+    let registry = match validation_registry(network) {
+        Ok(registry) => registry,
+        Err(response) => return response,
+    };
+    let provider_map = facilitator.provider_map();
+    let Some(NetworkProvider::Evm(provider)) = provider_map.by_network(&network) else {
+        return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({
+                "error": format!("No EVM provider available for network {}", network)
+            })),
+        )
+            .into_response();
+    };
+
+    info!(network = %network, agent_id = request.agent_id, validator = %request.validator_address, "Requesting ERC-8004 validation");
+    submit_validation_request(provider.inner(), registry, &request, || {
+        registrar.acquire(api_key)
+    })
+    .await
+}
+
+/// Send `request` to the Validation Registry at `validation_registry` through
+/// `provider`, as answered by `POST /validation/request`.
+///
+/// The request is simulated first; `acquire` counts it against the caller's quota only
+/// once the simulation passed.
+pub async fn submit_validation_request<P: alloy::providers::Provider>(
+    provider: P,
+    validation_registry: alloy::primitives::Address,
+    request: &ValidationRequestParams,
+    acquire: impl FnOnce() -> Result<(), RegistrarError>,
+) -> Response {
+    let network = request.network;
+    let response = |status: StatusCode, request_hash, transaction, error: Option<String>| {
+        (
+            status,
+            Json(ValidationRequestResponse {
+                success: error.is_none(),
+                request_hash,
+                transaction,
+                error,
+                network,
+            }),
+        )
+            .into_response()
+    };
+
+    let request_hash =
+        match check_validation_request(&provider, validation_registry, request).await {
+            Ok(hash) => hash,
+            Err(e) => {
+                warn!(network = %network, agent_id = request.agent_id, error = %e, "Validation request rejected");
+                let hash = validation::request_hash(request).ok();
+                return response(StatusCode::BAD_REQUEST, hash, None, Some(e.to_string()));
+            }
+        };
+    if let Err(e) = acquire() {
+        warn!(network = %network, "Validation request quota exhausted");
+        return response(
+            StatusCode::TOO_MANY_REQUESTS,
+            Some(request_hash),
+            None,
+            Some(e.to_string()),
+        );
+    }
+
+    match request_validation(&provider, validation_registry, request).await {
+        Ok((hash, tx)) => response(
+            StatusCode::OK,
+            Some(hash),
+            Some(crate::types::TransactionHash::Evm(tx.0)),
+            None,
+        ),
+        Err(e) => {
+            error!(network = %network, agent_id = request.agent_id, error = %e, "ERC-8004 validation request failed");
+            response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Some(request_hash),
+                None,
+                Some(e.to_string()),
+            )
+        }
+    }
+}
+
+/// Validation response routes, mounted when `ERC8004_VALIDATOR_PRIVATE_KEY` and
+/// `ERC8004_VALIDATOR_API_KEY` are set.
+///
+/// Handlers read the [`ValidatorKey`] from an [`Extension`] layered by the caller.
+pub fn validator_routes<A>() -> Router<A>
+where
+    A: Facilitator + HasProviderMap + Clone + Send + Sync + 'static,
+    A::Error: IntoResponse,
+    A::Map: ProviderMap<Value = NetworkProvider>,
+{
+    Router::new().route("/validation/response", post(post_validation_response::<A>))
+}
+
+/// `POST /validation/response`: Publish the configured validator's response to a
+/// validation request.
+///
+/// The response is sent by the validator key, so only requests addressed to it can be
+/// answered. `response` ranges from 0 (failed) to 100 (passed).
+///
+/// # Errors
+///
+/// - Returns 401 without the validator API key
+/// - Returns 400 for a malformed request, a network without ERC-8004 contracts, or a
+///   response the registry rejects
+/// - Returns 403 if the request is addressed to another validator
+/// - Returns 404 for an unknown request
+/// - Returns 501 on networks without a Validation Registry
+/// - Returns 500 if the transaction fails
+///
+/// # Example
+/// ```text
+/// POST /validation/response
+/// X-API-Key: ...
+///
+/// {
+///   "x402Version": 1,
+///   "network": "ethereum-sepolia",
+///   "requestHash": "0x...",
+///   "response": 100,
+///   "responseUri": "ipfs://bafy.../response.json",
+///   "tag": "hard-finality"
+/// }
+/// ```
+#[instrument(skip_all)]
+pub async fn post_validation_response<A>(
+    State(facilitator): State<A>,
+    Extension(validator): Extension<Arc<ValidatorKey>>,
+    headers: HeaderMap,
+    raw_body: Bytes,
+) -> impl IntoResponse
+where
+    A: Facilitator + HasProviderMap,
+    A::Error: IntoResponse,
+    A::Map: ProviderMap<Value = NetworkProvider>,
+{
+    let api_key = headers
+        .get(ADMIN_KEY_HEADER)
+        .and_then(|value| value.to_str().ok());
+    if !validator.authenticate(api_key) {
+        return (
+            StatusCode::UNAUTHORIZED,
+            Json(json!({ "error": "Missing or invalid API key" })),
+        )
+            .into_response();
+    }
+
+    let request: ValidationResponseParams = match serde_json::from_slice(&raw_body) {
+        Ok(req) => req,
+        Err(e) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(json!({ "error": format!("Invalid request format: {}", e) })),
+            )
+                .into_response();
+        }
+    };
+    let network = request.network;
+    let registry = match validation_registry(network) {
+        Ok(registry) => registry,
+        Err(response) => return response,
+    };
+    let provider_map = facilitator.provider_map();
+    let Some(NetworkProvider::Evm(provider)) = provider_map.by_network(&network) else {
+        return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({
+                "error": format!("No EVM provider available for network {}", network)
+            })),
+        )
+            .into_response();
+    };
+
+    // Same RPC connection, signing with the validator key instead of the facilitator's
+    let provider = alloy::providers::ProviderBuilder::new()
+        .wallet(validator.wallet())
+        .connect_provider(provider.inner().root().clone());
+    info!(network = %network, request_hash = %request.request_hash, response = request.response, "Publishing ERC-8004 validation response");
+    submit_validation_response(provider, registry, validator.address(), &request).await
+}
+
+/// Send `request` to the Validation Registry at `validation_registry` through
+/// `provider`, which signs as `validator`, as answered by `POST /validation/response`.
+pub async fn submit_validation_response<P: alloy::providers::Provider>(
+    provider: P,
+    validation_registry: alloy::primitives::Address,
+    validator: alloy::primitives::Address,
+    request: &ValidationResponseParams,
+) -> Response {
+    let network = request.network;
+    let response = |status: StatusCode, transaction, error: Option<String>| {
+        (
+            status,
+            Json(ValidationResponseResult {
+                success: error.is_none(),
+                request_hash: request.request_hash,
+                transaction,
+                error,
+                network,
+            }),
+        )
+            .into_response()
+    };
+
+    match validation_status(&provider, validation_registry, request.request_hash).await {
+        Ok(Some(status)) if status.validator_address == MixedAddress::Evm(validator.into()) => {}
+        Ok(Some(status)) => {
+            let error = format!(
+                "Request is addressed to validator {}, not {}",
+                status.validator_address, validator
+            );
+            return response(StatusCode::FORBIDDEN, None, Some(error));
+        }
+        Ok(None) => {
+            let error = format!("Unknown validation request {}", request.request_hash);
+            return response(StatusCode::NOT_FOUND, None, Some(error));
+        }
+        Err(e) => {
+            error!(network = %network, error = %e, "Failed to read validation status");
+            return response(StatusCode::INTERNAL_SERVER_ERROR, None, Some(e.to_string()));
+        }
+    }
+    if let Err(e) = check_validation_response(&provider, validation_registry, validator, request).await {
+        warn!(network = %network, request_hash = %request.request_hash, error = %e, "Validation response rejected");
+        return response(StatusCode::BAD_REQUEST, None, Some(e.to_string()));
+    }
+
+    match respond_to_validation(&provider, validation_registry, request).await {
+        Ok(tx) => response(
+            StatusCode::OK,
+            Some(crate::types::TransactionHash::Evm(tx.0)),
+            None,
+        ),
+        Err(e) => {
+            error!(network = %network, request_hash = %request.request_hash, error = %e, "ERC-8004 validation response failed");
+            response(StatusCode::INTERNAL_SERVER_ERROR, None, Some(e.to_string()))
+        }
+    }
+}
+
+/// Query parameters for `GET /validation/{request_hash}`
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct ValidationStatusQuery {
+    /// Network of the Validation Registry
+    pub network: String,
+}
+
+/// `GET /validation/{request_hash}`: Status of a validation request.
+///
+/// Returns the validator, agent, latest response (0-100, 0 until answered), response
+/// hash, tag and the timestamp of the last update.
+///
+/// # Errors
+///
+/// - Returns 400 for an invalid network or one without ERC-8004 contracts
+/// - Returns 404 for an unknown request
+/// - Returns 501 on networks without a Validation Registry
+///
+/// # Example
+/// ```text
+/// GET /validation/0x...?network=ethereum-sepolia
+/// ```
+#[instrument(skip_all)]
+pub async fn get_validation_status<A>(
+    State(facilitator): State<A>,
+    Path(request_hash): Path<alloy::primitives::B256>,
+    Query(query): Query<ValidationStatusQuery>,
+) -> impl IntoResponse
+where
+    A: HasProviderMap,
+    A::Map: ProviderMap<Value = NetworkProvider>,
+{
+    let network: crate::network::Network = match query.network.parse() {
+        Ok(network) => network,
+        Err(_) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(json!({ "error": format!("Invalid network: {}", query.network) })),
+            )
+                .into_response();
+        }
+    };
+    let registry = match validation_registry(network) {
+        Ok(registry) => registry,
+        Err(response) => return response,
+    };
+    let provider_map = facilitator.provider_map();
+    let Some(NetworkProvider::Evm(provider)) = provider_map.by_network(&network) else {
+        return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({
+                "error": format!("No EVM provider available for network {}", network)
+            })),
+        )
+            .into_response();
+    };
+
+    validation_status_response(provider.inner(), registry, request_hash).await
+}
+
+/// Status of the request `request_hash` in the Validation Registry at
+/// `validation_registry`, as returned by `GET /validation/{request_hash}`.
+pub async fn validation_status_response<P: alloy::providers::Provider>(
+    provider: P,
+    validation_registry: alloy::primitives::Address,
+    request_hash: alloy::primitives::B256,
+) -> Response {
+    match validation_status(provider, validation_registry, request_hash).await {
+        Ok(Some(status)) => (StatusCode::OK, Json(status)).into_response(),
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            Json(json!({
+                "error": format!("Unknown validation request {}", request_hash)
+            })),
+        )
+            .into_response(),
+        Err(e) => {
+            error!(request_hash = %request_hash, error = %e, "Failed to read validation status");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({
+                    "error": format!("Failed to read validation status: {}", e)
+                })),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// `GET /proof-signer`: Addresses that sign ERC-8004 proofs of payment, per network.
+///
+/// Settlement proofs carry a `facilitatorSignature` over their `paymentHash`; resource
+/// servers that trust this facilitator check it offline with
+/// [`ProofOfPayment::verify_signature`](crate::erc8004::ProofOfPayment::verify_signature)
+/// against the address listed for the proof's network.
+///
+/// # Example
+/// ```text
+/// GET /proof-signer
+///
+/// { "signers": [{ "network": "base-sepolia", "address": "0x..." }] }
+/// ```
+#[instrument(skip_all)]
+pub async fn get_proof_signer<A>(State(facilitator): State<A>) -> impl IntoResponse
+where
+    A: HasProviderMap,
+    A::Map: ProviderMap<Value = NetworkProvider>,
+{
+    let mut signers: Vec<_> = facilitator
+        .provider_map()
+        .values()
+        .filter_map(|provider| match provider {
+            NetworkProvider::Evm(p) => p
+                .proof_signer()
+                .map(|signer| (p.chain().network.to_string(), signer.address())),
+            _ => None,
+        })
+        .collect();
+    signers.sort();
+    let signers: Vec<_> = signers
+        .into_iter()
+        .map(|(network, address)| json!({ "network": network, "address": address }))
+        .collect();
+    Json(json!({ "signers": signers }))
 }
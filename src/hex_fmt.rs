@@ -0,0 +1,94 @@
+//! Allocation-free hex encoding for hot paths.
+//!
+//! `hex::encode` allocates a `String` per call, which adds up when the same
+//! 32-byte nonces, group ids and hashes are formatted on every verify and settle.
+//! [`Hex`] formats bytes through a stack buffer directly into any [`fmt::Write`],
+//! and [`Hex32`] holds the encoding of a 32-byte value inline.
+//!
+//! ```
+//! use x402_rs::hex_fmt::{Hex, Hex32};
+//!
+//! assert_eq!(format!("0x{}", Hex(&[0xde, 0xad])), "0xdead");
+//! assert_eq!(Hex32::new(&[0xab; 32]).as_str(), "ab".repeat(32));
+//! ```
+
+use std::fmt;
+use std::ops::Deref;
+
+const ALPHABET: &[u8; 16] = b"0123456789abcdef";
+
+#[inline]
+fn encode_into(bytes: &[u8], out: &mut [u8]) {
+    for (i, byte) in bytes.iter().enumerate() {
+        out[2 * i] = ALPHABET[(byte >> 4) as usize];
+        out[2 * i + 1] = ALPHABET[(byte & 0x0f) as usize];
+    }
+}
+
+/// Lowercase hex [`Display`](fmt::Display) of a byte slice, without a `0x` prefix.
+#[derive(Debug, Clone, Copy)]
+pub struct Hex<'a>(pub &'a [u8]);
+
+impl fmt::Display for Hex<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut buf = [0u8; 128];
+        for chunk in self.0.chunks(buf.len() / 2) {
+            let out = &mut buf[..chunk.len() * 2];
+            encode_into(chunk, out);
+            f.write_str(std::str::from_utf8(out).expect("hex digits are ASCII"))?;
+        }
+        Ok(())
+    }
+}
+
+/// Inline lowercase hex encoding of a 32-byte value.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Hex32([u8; 64]);
+
+impl Hex32 {
+    pub fn new(bytes: &[u8; 32]) -> Self {
+        let mut out = [0u8; 64];
+        encode_into(bytes, &mut out);
+        Self(out)
+    }
+
+    pub fn as_str(&self) -> &str {
+        std::str::from_utf8(&self.0).expect("hex digits are ASCII")
+    }
+}
+
+impl Deref for Hex32 {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl fmt::Display for Hex32 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl fmt::Debug for Hex32 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Hex32({})", self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches_hex_crate() {
+        let long: Vec<u8> = (0..=255u8).cycle().take(200).collect();
+        for bytes in [&[][..], &[0x00, 0x0f, 0xf0, 0xff][..], &long[..]] {
+            assert_eq!(Hex(bytes).to_string(), hex::encode(bytes));
+        }
+        let id = [0x5a; 32];
+        assert_eq!(Hex32::new(&id).as_str(), hex::encode(id));
+        assert_eq!(Hex32::new(&id).to_string(), Hex(&id).to_string());
+    }
+}
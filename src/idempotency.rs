@@ -0,0 +1,468 @@
+//! Idempotency keys for `POST /settle`.
+//!
+//! A client that retries a settlement after a timeout cannot tell whether the first
+//! attempt went through. Sending the same `X-Idempotency-Key: <uuid>` on every attempt
+//! makes retries safe: the first response is stored for 24 hours and replayed for later
+//! requests carrying the key, so the payment is settled at most once.
+//!
+//! # Behavior
+//!
+//! | Request with a known key | Response |
+//! |--------------------------|----------|
+//! | Same body, first request finished | Stored response, with `X-Idempotent-Replayed: true` |
+//! | Same body, first request still running | `409 Conflict` |
+//! | Different body | `409 Conflict` |
+//!
+//! Bodies are compared by the SHA-256 of their JSON re-serialization, so formatting
+//! differences do not count as a mismatch. Keys that are not UUIDs are rejected with
+//! `400 Bad Request`; requests without the header are not affected.
+//!
+//! Only `2xx` and `4xx` responses are stored. A `5xx` (e.g. an RPC node that was briefly
+//! down) releases the key, so a retry with it is settled again instead of replaying the
+//! failure. Request bodies are read within the router's `DefaultBodyLimit`.
+//!
+//! # Storage
+//!
+//! Records live in the [`NonceStore`] under `idempotency:{key}`, so they are shared by
+//! every instance using the same Redis, PostgreSQL, SQLite or DynamoDB backend. A key is
+//! claimed for [`IN_FLIGHT_TTL_SECONDS`] while its request runs, then holds the response
+//! for [`IDEMPOTENCY_TTL_SECONDS`].
+
+use axum::body::{to_bytes, Body, Bytes, HttpBody};
+use axum::extract::{FromRequest, Request, State};
+use axum::http::{header, HeaderValue, Method, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use thiserror::Error;
+use tracing::{debug, warn};
+
+use crate::nonce_store::{NonceStore, NonceStoreError};
+
+/// Header carrying the client's idempotency key.
+pub const IDEMPOTENCY_KEY_HEADER: &str = "X-Idempotency-Key";
+
+/// Header set on responses replayed from the cache.
+pub const REPLAYED_HEADER: &str = "X-Idempotent-Replayed";
+
+/// How long a response is replayed for its key.
+pub const IDEMPOTENCY_TTL_SECONDS: u64 = 24 * 60 * 60;
+
+/// How long a key stays claimed by a request that has not finished.
+pub const IN_FLIGHT_TTL_SECONDS: u64 = 5 * 60;
+
+/// Largest response body stored for replay; larger responses release their key.
+pub const MAX_CACHED_RESPONSE_BYTES: u64 = 64 * 1024;
+
+#[derive(Debug, Error)]
+pub enum IdempotencyError {
+    #[error("X-Idempotency-Key must be a UUID")]
+    InvalidKey,
+    #[error("X-Idempotency-Key was already used with a different request body")]
+    BodyMismatch,
+    #[error("A request with this X-Idempotency-Key is still in progress")]
+    InProgress,
+    #[error("Idempotency store unavailable: {0}")]
+    Store(#[from] NonceStoreError),
+}
+
+impl IdempotencyError {
+    fn status(&self) -> StatusCode {
+        match self {
+            Self::InvalidKey => StatusCode::BAD_REQUEST,
+            Self::BodyMismatch | Self::InProgress => StatusCode::CONFLICT,
+            Self::Store(_) => StatusCode::SERVICE_UNAVAILABLE,
+        }
+    }
+}
+
+impl IntoResponse for IdempotencyError {
+    fn into_response(self) -> Response {
+        (self.status(), Json(json!({ "error": self.to_string() }))).into_response()
+    }
+}
+
+/// Stored state of a key.
+#[derive(Debug, Serialize, Deserialize)]
+struct IdempotencyRecord {
+    fingerprint: String,
+    /// Unset while the first request is running
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    response: Option<CachedResponse>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CachedResponse {
+    pub status: u16,
+    pub body: String,
+}
+
+impl IntoResponse for CachedResponse {
+    fn into_response(self) -> Response {
+        let status = StatusCode::from_u16(self.status).unwrap_or(StatusCode::OK);
+        let mut response = (status, self.body).into_response();
+        let headers = response.headers_mut();
+        headers.insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("application/json"),
+        );
+        headers.insert(REPLAYED_HEADER, HeaderValue::from_static("true"));
+        response
+    }
+}
+
+/// Idempotency records kept in a [`NonceStore`].
+pub struct IdempotencyCache {
+    store: Arc<dyn NonceStore>,
+}
+
+impl IdempotencyCache {
+    pub fn new(store: Arc<dyn NonceStore>) -> Self {
+        Self { store }
+    }
+
+    /// Claim `key` for a request with body `fingerprint`.
+    ///
+    /// Returns the stored response when the key already completed with the same body.
+    pub async fn begin(
+        &self,
+        key: &str,
+        fingerprint: &str,
+    ) -> Result<Option<CachedResponse>, IdempotencyError> {
+        let pending = IdempotencyRecord {
+            fingerprint: fingerprint.to_string(),
+            response: None,
+        };
+        let Some(existing) = self
+            .store
+            .claim_value(&store_key(key), &encode(&pending), IN_FLIGHT_TTL_SECONDS)
+            .await?
+        else {
+            return Ok(None);
+        };
+        let record: IdempotencyRecord =
+            serde_json::from_str(&existing).map_err(|_| IdempotencyError::BodyMismatch)?;
+        if record.fingerprint != fingerprint {
+            return Err(IdempotencyError::BodyMismatch);
+        }
+        record
+            .response
+            .map(Some)
+            .ok_or(IdempotencyError::InProgress)
+    }
+
+    /// Store the response of the request that claimed `key`.
+    pub async fn complete(
+        &self,
+        key: &str,
+        fingerprint: &str,
+        response: CachedResponse,
+    ) -> Result<(), IdempotencyError> {
+        let record = IdempotencyRecord {
+            fingerprint: fingerprint.to_string(),
+            response: Some(response),
+        };
+        self.store
+            .set_value(&store_key(key), &encode(&record), IDEMPOTENCY_TTL_SECONDS)
+            .await?;
+        Ok(())
+    }
+
+    /// Give up the claim on `key` without storing a response, so it can be retried.
+    pub async fn release(&self, key: &str, fingerprint: &str) -> Result<(), IdempotencyError> {
+        let pending = IdempotencyRecord {
+            fingerprint: fingerprint.to_string(),
+            response: None,
+        };
+        // An expired entry is free to claim; stores with a minimum TTL hold it for a second
+        self.store
+            .set_value(&store_key(key), &encode(&pending), 0)
+            .await?;
+        Ok(())
+    }
+}
+
+fn store_key(key: &str) -> String {
+    format!("idempotency:{}", key)
+}
+
+fn encode(record: &IdempotencyRecord) -> String {
+    serde_json::to_string(record).expect("idempotency record serializes")
+}
+
+/// Lowercase hyphenated form of a UUID key, or `None` if `key` is not one.
+pub fn normalize_key(key: &str) -> Option<String> {
+    let bytes = key.as_bytes();
+    if bytes.len() != 36 {
+        return None;
+    }
+    let valid = bytes.iter().enumerate().all(|(i, b)| match i {
+        8 | 13 | 18 | 23 => *b == b'-',
+        _ => b.is_ascii_hexdigit(),
+    });
+    valid.then(|| key.to_ascii_lowercase())
+}
+
+/// SHA-256 of the request body, ignoring JSON formatting.
+pub fn fingerprint(body: &[u8]) -> String {
+    let canonical = serde_json::from_slice::<serde_json::Value>(body)
+        .ok()
+        .and_then(|value| serde_json::to_vec(&value).ok());
+    hex::encode(Sha256::digest(canonical.as_deref().unwrap_or(body)))
+}
+
+/// Replays responses of `POST /settle` requests with a known `X-Idempotency-Key`.
+pub async fn idempotency_middleware(
+    State(cache): State<Arc<IdempotencyCache>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let is_settle = request.method() == Method::POST && request.uri().path().ends_with("/settle");
+    let Some(key) = request
+        .headers()
+        .get(IDEMPOTENCY_KEY_HEADER)
+        .filter(|_| is_settle)
+    else {
+        return next.run(request).await;
+    };
+    let Some(key) = key.to_str().ok().and_then(normalize_key) else {
+        return IdempotencyError::InvalidKey.into_response();
+    };
+
+    // Buffer through the `Bytes` extractor so the router's body limit applies
+    let (parts, body) = request.into_parts();
+    let bytes = match Bytes::from_request(Request::from_parts(parts.clone(), body), &()).await {
+        Ok(bytes) => bytes,
+        Err(rejection) => return rejection.into_response(),
+    };
+    let fingerprint = fingerprint(&bytes);
+    match cache.begin(&key, &fingerprint).await {
+        Ok(None) => {}
+        Ok(Some(cached)) => {
+            debug!(key = %key, "Replaying idempotent settle response");
+            return cached.into_response();
+        }
+        Err(e) => return e.into_response(),
+    }
+
+    let response = next
+        .run(Request::from_parts(parts, Body::from(bytes)))
+        .await;
+    let status = response.status();
+    let cacheable = (status.is_success() || status.is_client_error())
+        && response
+            .body()
+            .size_hint()
+            .upper()
+            .is_some_and(|len| len <= MAX_CACHED_RESPONSE_BYTES);
+    if !cacheable {
+        debug!(key = %key, status = %status, "Not storing idempotent settle response");
+        if let Err(e) = cache.release(&key, &fingerprint).await {
+            warn!(error = %e, key = %key, "Failed to release idempotency key");
+        }
+        return response;
+    }
+    let (parts, body) = response.into_parts();
+    let Ok(bytes) = to_bytes(body, MAX_CACHED_RESPONSE_BYTES as usize).await else {
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    };
+    let cached = CachedResponse {
+        status: parts.status.as_u16(),
+        body: String::from_utf8_lossy(&bytes).into_owned(),
+    };
+    if let Err(e) = cache.complete(&key, &fingerprint, cached).await {
+        warn!(error = %e, key = %key, "Failed to store idempotent settle response");
+    }
+    Response::from_parts(parts, Body::from(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nonce_store::MemoryNonceStore;
+    use axum::http::Request as HttpRequest;
+    use axum::routing::post;
+    use axum::Router;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tower::ServiceExt;
+
+    const KEY: &str = "4F1C2A9E-0B7D-4C3E-9A51-6D2F8E7B1C30";
+
+    fn app(calls: Arc<AtomicUsize>) -> Router {
+        let cache = Arc::new(IdempotencyCache::new(Arc::new(MemoryNonceStore::new())));
+        Router::new()
+            .route(
+                "/settle",
+                post(move || async move {
+                    let call = calls.fetch_add(1, Ordering::SeqCst) + 1;
+                    Json(json!({ "success": true, "transaction": format!("0x{:02x}", call) }))
+                }),
+            )
+            .layer(axum::middleware::from_fn_with_state(
+                cache,
+                idempotency_middleware,
+            ))
+    }
+
+    fn settle(key: Option<&str>, body: &str) -> HttpRequest<Body> {
+        let mut request =
+            HttpRequest::post("/settle").header(header::CONTENT_TYPE, "application/json");
+        if let Some(key) = key {
+            request = request.header(IDEMPOTENCY_KEY_HEADER, key);
+        }
+        request.body(Body::from(body.to_string())).unwrap()
+    }
+
+    async fn body_of(response: Response) -> serde_json::Value {
+        serde_json::from_slice(&to_bytes(response.into_body(), usize::MAX).await.unwrap()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_replayed_request_returns_cached_response() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let app = app(Arc::clone(&calls));
+
+        let first = app
+            .clone()
+            .oneshot(settle(Some(KEY), r#"{"amount":"10"}"#))
+            .await
+            .unwrap();
+        assert_eq!(first.status(), StatusCode::OK);
+        assert!(first.headers().get(REPLAYED_HEADER).is_none());
+        let first = body_of(first).await;
+
+        // Same key in another case and a reformatted body
+        let replay = app
+            .clone()
+            .oneshot(settle(Some(&KEY.to_lowercase()), "{ \"amount\": \"10\" }"))
+            .await
+            .unwrap();
+        assert_eq!(replay.status(), StatusCode::OK);
+        assert_eq!(replay.headers()[REPLAYED_HEADER], "true");
+        assert_eq!(body_of(replay).await, first);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        // Requests without a key are not cached
+        for _ in 0..2 {
+            let response = app
+                .clone()
+                .oneshot(settle(None, r#"{"amount":"10"}"#))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_body_mismatch_is_rejected() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let app = app(Arc::clone(&calls));
+
+        app.clone()
+            .oneshot(settle(Some(KEY), r#"{"amount":"10"}"#))
+            .await
+            .unwrap();
+        let response = app
+            .clone()
+            .oneshot(settle(Some(KEY), r#"{"amount":"20"}"#))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        assert!(body_of(response).await["error"]
+            .as_str()
+            .unwrap()
+            .contains("different request body"));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_in_flight_and_invalid_keys_are_rejected() {
+        let cache = IdempotencyCache::new(Arc::new(MemoryNonceStore::new()));
+        let fingerprint = fingerprint(br#"{"amount":"10"}"#);
+        assert_eq!(cache.begin(KEY, &fingerprint).await.unwrap(), None);
+        assert!(matches!(
+            cache.begin(KEY, &fingerprint).await,
+            Err(IdempotencyError::InProgress)
+        ));
+
+        let response = app(Arc::new(AtomicUsize::new(0)))
+            .oneshot(settle(Some("not-a-uuid"), "{}"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_server_errors_are_not_replayed() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let handler_calls = Arc::clone(&calls);
+        let cache = Arc::new(IdempotencyCache::new(Arc::new(MemoryNonceStore::new())));
+        let app = Router::new()
+            .route(
+                "/settle",
+                post(move || async move {
+                    // The RPC node is down for the first attempt only
+                    if handler_calls.fetch_add(1, Ordering::SeqCst) == 0 {
+                        (StatusCode::BAD_GATEWAY, Json(json!({ "error": "rpc down" })))
+                    } else {
+                        (StatusCode::OK, Json(json!({ "success": true })))
+                    }
+                }),
+            )
+            .layer(axum::middleware::from_fn_with_state(
+                cache,
+                idempotency_middleware,
+            ));
+
+        let failed = app
+            .clone()
+            .oneshot(settle(Some(KEY), r#"{"amount":"10"}"#))
+            .await
+            .unwrap();
+        assert_eq!(failed.status(), StatusCode::BAD_GATEWAY);
+
+        let retried = app
+            .clone()
+            .oneshot(settle(Some(KEY), r#"{"amount":"10"}"#))
+            .await
+            .unwrap();
+        assert_eq!(retried.status(), StatusCode::OK);
+        assert!(retried.headers().get(REPLAYED_HEADER).is_none());
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        let replay = app
+            .oneshot(settle(Some(KEY), r#"{"amount":"10"}"#))
+            .await
+            .unwrap();
+        assert_eq!(replay.headers()[REPLAYED_HEADER], "true");
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_request_body_limit_applies() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let app = app(Arc::clone(&calls)).layer(axum::extract::DefaultBodyLimit::max(16));
+
+        let response = app
+            .oneshot(settle(Some(KEY), &format!(r#"{{"amount":"{}"}}"#, "1".repeat(64))))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn test_normalize_key() {
+        assert_eq!(
+            normalize_key(KEY).as_deref(),
+            Some("4f1c2a9e-0b7d-4c3e-9a51-6d2f8e7b1c30")
+        );
+        assert_eq!(normalize_key("4f1c2a9e0b7d4c3e9a516d2f8e7b1c30"), None);
+        assert_eq!(normalize_key("4f1c2a9e-0b7d-4c3e-9a51-6d2f8e7b1c3g"), None);
+    }
+}
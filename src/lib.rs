@@ -16,27 +16,47 @@
 //! - [`telemetry`] — OpenTelemetry instrumentation setup for tracing and observability.
 //! - [`types`] — all shared x402 protocol structures and payload formats.
 
+pub mod batch;
 pub mod blocklist;
+pub mod canary;
 pub mod caip2;
 pub mod chain;
+pub mod circuit_breaker;
 pub mod erc8004;
+pub mod digest_cache;
 pub mod discovery;
 pub mod discovery_aggregator;
+pub mod discovery_health;
+pub mod discovery_reputation;
 pub mod discovery_store;
+pub mod env_registry;
 pub mod escrow;
 pub mod facilitator;
 pub mod facilitator_local;
 pub mod fhe_proxy;
+pub mod finality;
 pub mod from_env;
 pub mod handlers;
+pub mod hex_fmt;
+pub mod idempotency;
+pub mod metrics;
 pub mod network;
+pub mod paywall;
+pub mod payment_uri;
+pub mod peer_net;
 pub mod nonce_store;
 pub mod provider_cache;
+pub mod rate_limit;
 pub mod sig_down;
+pub mod streaming;
 pub mod telemetry;
+pub mod tenant;
 pub mod timestamp;
+#[cfg(feature = "ts-gen")]
+pub mod ts_bindings;
 pub mod types;
 pub mod types_v2;
+pub mod webhook;
 
 // Hidden re-exports just for macro expansion.
 #[doc(hidden)]
@@ -17,20 +17,29 @@
 //!
 //! Environment:
 //! - `.env` values loaded at startup
-//! - `HOST`, `PORT` control binding address
+//! - `HOST`, `PORT` control binding address (`HOST` accepts a comma-separated list, IPv6 included)
 //! - `OTEL_*` variables enable tracing to systems like Honeycomb
+//! - `METRICS_PORT` serves Prometheus metrics at `/metrics` on a separate port
+//! - `x402-rs config vars` lists every supported variable (see [`env_registry`])
 
 use axum::http::Method;
 use axum::{Extension, Router};
 use dotenvy::dotenv;
+use std::future::IntoFuture;
 use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::Duration;
 use tower_http::cors;
 use url::Url;
 
+use crate::chain::evm::MetaEvmProvider;
+use crate::chain::NetworkProvider;
+use crate::circuit_breaker::{CircuitBreaker, CircuitBreakerConfig};
+use crate::erc8004::saga::{EvmFeedbackChain, FeedbackChain};
 use crate::facilitator::Facilitator;
 use crate::facilitator_local::FacilitatorLocal;
-use crate::provider_cache::ProviderCache;
+use crate::peer_net::BindConfig;
+use crate::provider_cache::{HasProviderMap, ProviderCache, ProviderMap};
 use crate::sig_down::SigDown;
 use crate::telemetry::Telemetry;
 use crate::types_v2::{DiscoveryMetadata, DiscoveryResource};
@@ -38,32 +47,49 @@ use crate::types_v2::{DiscoveryMetadata, DiscoveryResource};
 // Compliance module
 use x402_compliance::ComplianceCheckerBuilder;
 
+mod batch;
 mod blocklist;
+mod canary;
 mod caip2;
 mod chain;
+mod circuit_breaker;
+mod digest_cache;
 mod discovery;
 mod discovery_aggregator;
 mod discovery_crawler;
+mod discovery_health;
+mod discovery_reputation;
 mod discovery_store;
+mod env_registry;
 mod erc8004;
 mod escrow;
 mod facilitator;
 mod facilitator_local;
 mod fhe_proxy;
+mod finality;
 mod from_env;
 mod handlers;
+mod hex_fmt;
+mod idempotency;
+mod metrics;
 mod network;
 mod openapi;
 mod nonce_store;
+mod paywall;
+mod peer_net;
 mod provider_cache;
+mod rate_limit;
 mod sig_down;
+mod streaming;
 mod telemetry;
+mod tenant;
 mod timestamp;
 mod types;
 mod types_v2;
+mod webhook;
 
 use discovery::DiscoveryRegistry;
-use discovery_store::S3Store;
+use discovery_store::{FileSnapshotStore, S3Store};
 #[allow(unused_imports)]
 use discovery_store::DiscoveryStore;
 
@@ -80,12 +106,36 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Load .env variables
     dotenv().ok();
 
+    // `config vars`: print the environment variable registry and exit
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.iter().map(String::as_str).eq(["config", "vars"]) {
+        print!("{}", env_registry::render_table());
+        return Ok(());
+    }
+
     let telemetry = Telemetry::new()
         .with_name(env!("CARGO_PKG_NAME"))
         .with_version(env!("CARGO_PKG_VERSION"))
         .register();
 
-    let provider_cache = ProviderCache::from_env().await;
+    // Resolve ERC-8004 deployments before anything looks them up
+    match erc8004::Erc8004Registry::from_env() {
+        Ok(registry) => {
+            erc8004::install_registry(registry);
+            tracing::info!(
+                networks = ?erc8004::supported_network_names(),
+                "ERC-8004 registry configured"
+            );
+        }
+        Err(e) => {
+            tracing::error!("Invalid ERC-8004 contract configuration: {}", e);
+            std::process::exit(1);
+        }
+    }
+
+    // One nonce store backs Stellar and Algorand replay protection and idempotency records
+    let nonce_store = nonce_store::create_nonce_store().await;
+    let provider_cache = ProviderCache::from_env(Arc::clone(&nonce_store)).await;
     // Abort if we can't initialise Ethereum providers early
     let provider_cache = match provider_cache {
         Ok(provider_cache) => provider_cache,
@@ -115,40 +165,81 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
     };
 
-    let facilitator = FacilitatorLocal::new(provider_cache, compliance_checker);
+    // Fail fast on a network whose RPC node keeps erroring instead of waiting on every call
+    let facilitator = CircuitBreaker::new(
+        FacilitatorLocal::new(provider_cache, compliance_checker),
+        CircuitBreakerConfig::from_env(),
+    );
     let axum_state = Arc::new(facilitator);
 
+    // Resume or compensate feedback sagas interrupted by a previous run
+    let feedback_chain = {
+        let state = Arc::clone(&axum_state);
+        move |network: network::Network| -> Option<Box<dyn FeedbackChain>> {
+            let contracts = erc8004::get_contracts(&network)?;
+            match state.provider_map().by_network(network)? {
+                NetworkProvider::Evm(provider) => Some(Box::new(EvmFeedbackChain::new(
+                    provider.inner().clone(),
+                    contracts.reputation_registry,
+                )) as Box<dyn FeedbackChain>),
+                _ => None,
+            }
+        }
+    };
+    let recovered = erc8004::saga::FEEDBACK_SAGA.recover(&feedback_chain).await;
+    if !recovered.is_empty() {
+        tracing::info!(count = recovered.len(), "Recovered interrupted feedback sagas");
+    }
+    // Keep confirming feedback whose outcome was still unknown, until it expires
+    let retry_interval = env_registry::parse::<u64>("FEEDBACK_SAGA_RETRY_INTERVAL_SECS")
+        .unwrap_or(erc8004::saga::DEFAULT_RETRY_INTERVAL_SECS);
+    let _feedback_retry_handle = erc8004::saga::start_retry_task(
+        erc8004::saga::FEEDBACK_SAGA.clone(),
+        feedback_chain,
+        Duration::from_secs(retry_interval.max(1)),
+    );
+
     // Initialize Bazaar discovery registry with optional S3 persistence
     tracing::info!("Initializing Bazaar discovery registry...");
-    let discovery_registry = if std::env::var("DISCOVERY_S3_BUCKET").is_ok() {
+    let discovery_registry = if env_registry::var("DISCOVERY_S3_BUCKET").is_some() {
         // S3 persistence configured
         match S3Store::from_env().await {
             Ok(store) => {
                 match DiscoveryRegistry::with_store(store).await {
                     Ok(registry) => {
                         tracing::info!("Discovery registry initialized with S3 persistence");
-                        Arc::new(registry)
+                        registry
                     }
                     Err(e) => {
                         tracing::warn!("Failed to initialize S3 store, falling back to in-memory: {}", e);
-                        Arc::new(DiscoveryRegistry::new())
+                        DiscoveryRegistry::new()
                     }
                 }
             }
             Err(e) => {
                 tracing::warn!("Failed to create S3 store, falling back to in-memory: {}", e);
-                Arc::new(DiscoveryRegistry::new())
+                DiscoveryRegistry::new()
             }
         }
     } else {
         // No persistence configured, use in-memory only
         tracing::info!("No DISCOVERY_S3_BUCKET configured, using in-memory registry");
-        Arc::new(DiscoveryRegistry::new())
+        DiscoveryRegistry::new()
+    };
+
+    // Restore the last registry snapshot before aggregation starts
+    let discovery_registry = match FileSnapshotStore::from_env() {
+        Ok(Some(snapshots)) => Arc::new(discovery_registry.with_snapshot_store(snapshots).await),
+        Ok(None) => Arc::new(discovery_registry),
+        Err(e) => {
+            tracing::error!("Invalid discovery snapshot configuration: {}", e);
+            std::process::exit(1);
+        }
     };
 
     // Self-registration: register this facilitator as a discoverable resource
     // Only if FACILITATOR_URL is set (indicates production deployment)
-    if let Ok(facilitator_url) = std::env::var("FACILITATOR_URL") {
+    if let Some(facilitator_url) = env_registry::var("FACILITATOR_URL") {
         match Url::parse(&facilitator_url) {
             Ok(url) => {
                 // Get supported networks to include in description
@@ -174,6 +265,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                         "evm".to_string(),
                         "solana".to_string(),
                     ],
+                    sources: Vec::new(),
+                    liveness: None,
+                    reputation: None,
+                    extra: serde_json::Map::new(),
                 });
 
                 if let Err(e) = discovery_registry.register(facilitator_resource).await {
@@ -196,43 +291,43 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // Start background aggregation task if enabled
     // Fetches resources from external facilitators (Coinbase, etc.) every hour
-    let aggregation_interval_secs = std::env::var("DISCOVERY_AGGREGATION_INTERVAL")
-        .ok()
-        .and_then(|s| s.parse::<u64>().ok())
-        .unwrap_or(3600); // Default: 1 hour
+    let aggregation_interval_secs =
+        env_registry::parse::<u64>("DISCOVERY_AGGREGATION_INTERVAL").unwrap_or(3600); // Default: 1 hour
 
-    let enable_aggregation = std::env::var("DISCOVERY_ENABLE_AGGREGATION")
-        .map(|v| v == "true" || v == "1")
-        .unwrap_or(true); // Enabled by default
+    let enable_aggregation = env_registry::flag("DISCOVERY_ENABLE_AGGREGATION"); // Enabled by default
 
-    if enable_aggregation {
+    let aggregation_report = discovery_aggregator::SharedAggregationReport::default();
+    let source_stats = discovery_aggregator::SharedSourceStats::default();
+    let aggregation_handle = if enable_aggregation {
         tracing::info!(
             interval_secs = aggregation_interval_secs,
             "Starting discovery aggregation background task"
         );
         let registry_for_aggregation = Arc::clone(&discovery_registry);
-        let _aggregation_handle = discovery_aggregator::start_aggregation_task(
-            (*registry_for_aggregation).clone(),
-            aggregation_interval_secs,
-        );
+        Some(
+            discovery_aggregator::start_aggregation_task(
+                (*registry_for_aggregation).clone(),
+                aggregation_interval_secs,
+                Arc::clone(&aggregation_report),
+                Arc::clone(&source_stats),
+            )
+            .await,
+        )
     } else {
         tracing::info!("Discovery aggregation is disabled (DISCOVERY_ENABLE_AGGREGATION=false)");
-    }
+        None
+    };
 
     // Start background crawl task if enabled (Phase 3)
     // Crawls /.well-known/x402 endpoints from configured seed URLs
-    let crawl_interval_secs = std::env::var("DISCOVERY_CRAWL_INTERVAL")
-        .ok()
-        .and_then(|s| s.parse::<u64>().ok())
-        .unwrap_or(86400); // Default: 24 hours
+    let crawl_interval_secs =
+        env_registry::parse::<u64>("DISCOVERY_CRAWL_INTERVAL").unwrap_or(86400); // Default: 24 hours
 
-    let enable_crawler = std::env::var("DISCOVERY_ENABLE_CRAWLER")
-        .map(|v| v == "true" || v == "1")
-        .unwrap_or(false); // Disabled by default (no seed URLs configured)
+    let enable_crawler = env_registry::flag("DISCOVERY_ENABLE_CRAWLER"); // Disabled by default (no seed URLs configured)
 
     if enable_crawler {
         // Parse seed URLs from comma-separated environment variable
-        let seed_urls = std::env::var("DISCOVERY_CRAWL_URLS")
+        let seed_urls = env_registry::var("DISCOVERY_CRAWL_URLS")
             .unwrap_or_default()
             .split(',')
             .filter_map(|s| {
@@ -269,42 +364,361 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         tracing::info!("Discovery crawler is disabled (DISCOVERY_ENABLE_CRAWLER=false)");
     }
 
-    let http_endpoints = Router::new()
-        .merge(handlers::routes().with_state(axum_state))
+    // Start discovery health monitor (hides resources that stop answering)
+    if env_registry::flag("DISCOVERY_ENABLE_HEALTH_CHECKS") {
+        let _health_handle = discovery_health::start_health_monitor_task(
+            (*discovery_registry).clone(),
+            discovery_health::HealthMonitorConfig::from_env(),
+        );
+    } else {
+        tracing::info!("Discovery health checks are disabled (DISCOVERY_ENABLE_HEALTH_CHECKS=false)");
+    }
+
+    // Start discovery reputation refresher (ranks resources by ERC-8004 reputation)
+    if env_registry::flag("DISCOVERY_ENABLE_REPUTATION") {
+        let reader = erc8004::supported_networks().into_iter().fold(
+            discovery_reputation::EvmReputationReader::new(),
+            |reader, network| {
+                let contracts = erc8004::get_contracts(&network);
+                match (contracts, axum_state.provider_map().by_network(network)) {
+                    (Some(contracts), Some(NetworkProvider::Evm(provider))) => {
+                        reader.with_network(network, provider.inner().clone(), contracts)
+                    }
+                    _ => reader,
+                }
+            },
+        );
+        if reader.is_empty() {
+            tracing::warn!(
+                "DISCOVERY_ENABLE_REPUTATION is set but no ERC-8004 network has a provider"
+            );
+        } else {
+            let _reputation_handle = discovery_reputation::start_reputation_refresh_task(
+                (*discovery_registry).clone(),
+                discovery_reputation::ReputationRefresher::new(
+                    Arc::new(reader),
+                    discovery_reputation::ReputationRefresherConfig::from_env(),
+                ),
+            );
+        }
+    }
+
+    // Start ERC-8004 feedback indexer (serves GET /reputation feedback without readAllFeedback)
+    match erc8004::indexer::IndexerConfig::from_env() {
+        Ok(config) if config.is_enabled() => {
+            let indexers: Vec<_> = config
+                .networks
+                .iter()
+                .filter_map(|(&network, &start_block)| {
+                    let contracts = erc8004::get_contracts(&network)?;
+                    match axum_state.provider_map().by_network(network)? {
+                        NetworkProvider::Evm(provider) => Some(
+                            erc8004::indexer::FeedbackIndexer::new(
+                                provider.inner().clone(),
+                                network,
+                                contracts.reputation_registry,
+                                start_block,
+                            )
+                            .with_block_range(config.block_range)
+                            .with_reorg_depth(config.reorg_depth),
+                        ),
+                        _ => None,
+                    }
+                })
+                .collect();
+            if indexers.is_empty() {
+                tracing::warn!("ERC8004_INDEXER_NETWORKS is set but none of its networks has a provider");
+            } else {
+                let _indexer_handle = erc8004::indexer::start_indexer_task(indexers, config);
+            }
+        }
+        Ok(_) => {}
+        Err(e) => {
+            tracing::error!("Failed to load ERC-8004 indexer config: {}", e);
+            std::process::exit(1);
+        }
+    }
+
+    let paywall = match paywall::Paywall::from_env(Arc::clone(&axum_state)) {
+        Ok(paywall) => paywall.map(Arc::new),
+        Err(e) => {
+            tracing::error!("Failed to load paywall config: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let tenants = match tenant::TenantState::from_env(Arc::clone(&discovery_registry)) {
+        Ok(tenants) => tenants.map(Arc::new),
+        Err(e) => {
+            tracing::error!("Failed to load tenant config: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let webhooks = webhook::WebhookDelivery::from_env().map(Arc::new);
+
+    // Finality policies hold settlements as confirmed until their depth and timelock pass
+    let finality = match finality::FinalityPolicies::from_env() {
+        Ok(policies) if !policies.is_empty() => {
+            let heights = network::Network::variants().iter().fold(
+                finality::ChainHeights::new(),
+                |heights, &network| match axum_state.provider_map().by_network(network) {
+                    Some(NetworkProvider::Evm(provider)) => {
+                        heights.with_evm(network, provider.inner().clone())
+                    }
+                    #[cfg(feature = "algorand")]
+                    Some(NetworkProvider::Algorand(provider)) => {
+                        heights.with_algorand(network, provider.clone())
+                    }
+                    _ => heights,
+                },
+            );
+            let ttl = env_registry::parse::<u64>("FINALITY_PENDING_TTL_SECS")
+                .unwrap_or(finality::DEFAULT_PENDING_TTL_SECS);
+            let tracker = Arc::new(finality::FinalityTracker::new(policies).with_pending_ttl(ttl));
+            let interval = env_registry::parse::<u64>("FINALITY_POLL_INTERVAL_SECS")
+                .unwrap_or(finality::DEFAULT_POLL_INTERVAL_SECS);
+            let _finality_handle = finality::start_finality_task(
+                Arc::clone(&tracker),
+                Arc::new(heights),
+                Duration::from_secs(interval.max(1)),
+            );
+            Some(tracker)
+        }
+        Ok(_) => None,
+        Err(e) => {
+            tracing::error!("Failed to load finality policies: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    // Canary self-payments exercise the whole settlement pipeline on a schedule
+    let canary_monitor = match canary::CanaryConfig::from_env() {
+        Ok(config) if config.enabled => {
+            let source = match canary::EvmCanarySource::from_env(config.schedules.keys()) {
+                Ok(source) => source,
+                Err(e) => {
+                    tracing::error!("Failed to load the canary signers: {}", e);
+                    std::process::exit(1);
+                }
+            };
+            let monitor = canary::CanaryMonitor::new(config.failure_threshold);
+            let _canary_handles = canary::start_canary_task(
+                Arc::clone(&axum_state),
+                Arc::new(source),
+                config,
+                monitor.clone(),
+            );
+            Some(monitor)
+        }
+        Ok(_) => {
+            tracing::info!("Canary self-tests are disabled (CANARY_ENABLED=false)");
+            None
+        }
+        Err(e) => {
+            tracing::error!("Failed to load canary config: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let mut routes = Router::new()
+        .merge(handlers::routes().with_state(Arc::clone(&axum_state)))
         .merge(handlers::discovery_routes().with_state(Arc::clone(&discovery_registry)))
-        .merge(openapi::swagger_routes())
+        .merge(openapi::swagger_routes());
+    let aggregation_runner = aggregation_handle.as_ref().and_then(|handle| handle.runner());
+    match (aggregation_runner, handlers::AdminKey::from_env("DISCOVERY_ADMIN_KEY")) {
+        (Some(runner), Some(admin_key)) => {
+            let admin = Arc::new(handlers::DiscoveryAdminState { runner, admin_key });
+            routes = routes.merge(handlers::discovery_admin_routes().with_state(admin));
+        }
+        (None, Some(_)) => {
+            tracing::warn!("DISCOVERY_ADMIN_KEY is set but aggregation is not running, admin routes disabled");
+        }
+        (_, None) => {}
+    }
+    if let Some(tracker) = &finality {
+        routes = routes.merge(handlers::finality_routes().with_state(Arc::clone(tracker)));
+    }
+    if let Some(monitor) = canary_monitor {
+        routes = routes.merge(handlers::canary_routes().with_state(monitor));
+    }
+    // Streams are charged through the provider of their network, without the circuit breaker
+    if env_registry::flag("STREAMING_ENABLED") {
+        let manager = Arc::new(streaming::StreamManager::new(streaming::ProviderCharger::new(
+            Arc::clone(&axum_state),
+        )));
+        let interval = env_registry::parse::<u64>("STREAMING_DRAIN_INTERVAL_SECS")
+            .unwrap_or(streaming::DEFAULT_DRAIN_INTERVAL_SECS);
+        let _drain_handle = streaming::start_drain_task(
+            Arc::clone(&manager),
+            Duration::from_secs(interval.max(1)),
+        );
+        routes = routes.merge(handlers::streaming_routes().with_state(manager));
+    }
+    if let Some(admin_key) = handlers::AdminKey::from_env("CONFIG_ADMIN_KEY") {
+        routes = routes.merge(handlers::config_admin_routes().with_state(admin_key));
+    }
+    if let Some(admin_key) = handlers::AdminKey::from_env("ERC8004_CACHE_ADMIN_KEY") {
+        routes = routes.merge(handlers::cache_admin_routes().with_state(admin_key));
+    }
+    if let Some(registrar) = erc8004::identity::IdentityRegistrar::from_env() {
+        routes = routes.merge(
+            handlers::identity_registration_routes()
+                .with_state(Arc::clone(&axum_state))
+                .layer(Extension(Arc::new(registrar))),
+        );
+    }
+    if let Some(validator) = erc8004::validation::ValidatorKey::from_env() {
+        routes = routes.merge(
+            handlers::validator_routes()
+                .with_state(Arc::clone(&axum_state))
+                .layer(Extension(Arc::new(validator))),
+        );
+    }
+    if let Some(tenants) = &tenants {
+        // Tenants addressed by path prefix get the facilitator API mounted under it
+        for prefix in tenants.path_prefixes() {
+            routes = routes.nest(&prefix, handlers::routes().with_state(Arc::clone(&axum_state)));
+        }
+        routes = routes
+            .merge(tenant::routes().with_state(Arc::clone(&tenants)))
+            .layer(axum::middleware::from_fn_with_state(
+                Arc::clone(tenants),
+                tenant::tenant_payment_middleware,
+            ));
+    }
+    // Settlements held by a finality policy are delivered once final
+    if let (Some(webhooks), Some(tracker)) = (&webhooks, &finality) {
+        let _finality_delivery_handle =
+            webhook::start_finality_delivery(Arc::clone(webhooks), Arc::clone(tracker));
+    }
+    if let Some(webhooks) = webhooks {
+        routes = routes
+            .merge(webhook::routes().with_state(Arc::clone(&webhooks)))
+            .layer(axum::middleware::from_fn_with_state(
+                webhooks,
+                webhook::webhook_middleware,
+            ));
+    }
+    // Attributes the caller for the tenant, webhook and discovery handlers, so it wraps them
+    if let Some(tenants) = tenants {
+        routes = routes.layer(axum::middleware::from_fn_with_state(
+            tenants,
+            tenant::tenant_scope_middleware,
+        ));
+    }
+    // Wraps the tenant and webhook middlewares, so replayed settlements are not delivered again
+    let idempotency = Arc::new(idempotency::IdempotencyCache::new(nonce_store));
+    routes = routes.layer(axum::middleware::from_fn_with_state(
+        idempotency,
+        idempotency::idempotency_middleware,
+    ));
+    if let Some(paywall) = paywall {
+        if let Some(path) = env_registry::var("PAYWALL_CONFIG_FILE") {
+            let interval = env_registry::parse::<u64>("PAYWALL_RELOAD_SECS").unwrap_or(30);
+            let _reload_handle = paywall::start_reload_task(
+                Arc::clone(&paywall),
+                path.into(),
+                Duration::from_secs(interval.max(1)),
+            );
+        }
+        routes = routes.layer(axum::middleware::from_fn_with_state(
+            paywall,
+            paywall::paywall_middleware,
+        ));
+    }
+    // Outermost, so rejected requests cost no payment, idempotency or tenant work
+    let rate_limit_config = rate_limit::RateLimitConfig::from_env();
+    if rate_limit_config.is_enabled() {
+        let limiter = Arc::new(rate_limit::RateLimiter::new(rate_limit_config));
+        let _cleanup_handle = rate_limit::start_cleanup_task(Arc::clone(&limiter));
+        routes = routes.layer(axum::middleware::from_fn_with_state(
+            limiter,
+            rate_limit::rate_limit_middleware,
+        ));
+    }
+
+    // POST /settle hands confirmed settlements to the finality tracker
+    if let Some(tracker) = finality {
+        routes = routes.layer(Extension(tracker));
+    }
+
+    let http_endpoints = routes
         // Share discovery registry with all handlers via Extension for settlement tracking
         .layer(Extension(discovery_registry))
+        .layer(Extension(aggregation_report))
+        .layer(Extension(source_stats))
         .layer(telemetry.http_tracing())
         .layer(
             cors::CorsLayer::new()
                 .allow_origin(cors::Any)
-                .allow_methods([Method::GET, Method::POST])
+                .allow_methods([Method::GET, Method::POST, Method::DELETE])
                 .allow_headers(cors::Any),
         );
 
-    let host = std::env::var("HOST").unwrap_or_else(|_| "0.0.0.0".to_string());
-    let port = std::env::var("PORT")
-        .ok()
-        .and_then(|s| s.parse::<u16>().ok())
-        .unwrap_or(8080);
+    let host = env_registry::var("HOST").unwrap_or_else(|| "0.0.0.0".to_string());
+    let port = env_registry::parse::<u16>("PORT").unwrap_or(8080);
+    let dual_stack = env_registry::flag("LISTEN_DUAL_STACK");
 
-    let addr = SocketAddr::new(host.parse().expect("HOST must be a valid IP address"), port);
-    tracing::info!("Starting server at http://{}", addr);
+    let bind_config = BindConfig::parse(&host, port, dual_stack).unwrap_or_else(|e| {
+        tracing::error!("HOST must be a comma-separated list of IP addresses: {}", e);
+        std::process::exit(1);
+    });
+    let listeners = bind_config.bind().unwrap_or_else(|e| {
+        tracing::error!("Failed to bind to {:?}: {}", bind_config.addrs, e);
+        std::process::exit(1);
+    });
+    for addr in &bind_config.addrs {
+        tracing::info!("Starting server at http://{} (dual_stack={})", addr, dual_stack);
+    }
 
-    let listener = tokio::net::TcpListener::bind(addr)
-        .await
-        .unwrap_or_else(|e| {
-            tracing::error!("Failed to bind to {}: {}", addr, e);
+    let sig_down = SigDown::try_new()?;
+    let mut servers = tokio::task::JoinSet::new();
+    for listener in listeners {
+        let axum_cancellation_token = sig_down.cancellation_token();
+        let axum_graceful_shutdown = async move { axum_cancellation_token.cancelled().await };
+        servers.spawn(
+            axum::serve(
+                listener,
+                http_endpoints
+                    .clone()
+                    .into_make_service_with_connect_info::<SocketAddr>(),
+            )
+                .with_graceful_shutdown(axum_graceful_shutdown)
+                .into_future(),
+        );
+    }
+    // Prometheus metrics on their own port, so scrapes never share the public API
+    if let Some(metrics_port) = env_registry::parse::<u16>("METRICS_PORT") {
+        let handle = metrics::install().unwrap_or_else(|e| {
+            tracing::error!("Failed to install the metrics recorder: {}", e);
             std::process::exit(1);
         });
-
-    let sig_down = SigDown::try_new()?;
-    let axum_cancellation_token = sig_down.cancellation_token();
-    let axum_graceful_shutdown = async move { axum_cancellation_token.cancelled().await };
-    axum::serve(listener, http_endpoints)
-        .with_graceful_shutdown(axum_graceful_shutdown)
-        .await?;
+        let mut metrics_bind = bind_config.clone();
+        for addr in &mut metrics_bind.addrs {
+            addr.set_port(metrics_port);
+        }
+        let metrics_listeners = metrics_bind.bind().unwrap_or_else(|e| {
+            tracing::error!("Failed to bind metrics to {:?}: {}", metrics_bind.addrs, e);
+            std::process::exit(1);
+        });
+        tracing::info!("Serving metrics at /metrics on port {}", metrics_port);
+        for listener in metrics_listeners {
+            let cancellation_token = sig_down.cancellation_token();
+            servers.spawn(
+                axum::serve(listener, metrics::routes(handle.clone()))
+                    .with_graceful_shutdown(async move { cancellation_token.cancelled().await })
+                    .into_future(),
+            );
+        }
+    }
+    while let Some(result) = servers.join_next().await {
+        result??;
+    }
+    // Let an aggregation cycle in progress finish its import
+    if let Some(handle) = aggregation_handle {
+        handle.shutdown().await;
+    }
 
     Ok(())
 }
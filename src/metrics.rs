@@ -0,0 +1,309 @@
+//! Prometheus metrics for verification, settlement and discovery aggregation.
+//!
+//! Every [`Facilitator`](crate::facilitator::Facilitator) served behind an `Arc` records
+//! its calls here, so all chains report the same series:
+//!
+//! - `x402_verify_total{network, scheme, result}` — `result` is `valid`, `invalid` or `error`
+//! - `x402_settle_total{network, scheme, result}` — `result` is `success`, `failure` or `error`
+//! - `x402_verify_duration_seconds{network}` and `x402_settle_duration_seconds{network}`
+//! - `x402_nonce_replay_attempts_total{chain}` — payments rejected by a nonce store
+//!
+//! The discovery aggregator reports each facilitator it contacts and each cycle:
+//!
+//! - `x402_discovery_fetch_duration_seconds{facilitator}`
+//! - `x402_discovery_resources_fetched_total{facilitator}`, `..._converted_total` and
+//!   `..._skipped_total` — listings returned, imported, and dropped during conversion
+//! - `x402_discovery_parse_errors_total{facilitator}` — pages in no known format
+//! - `x402_discovery_import_resources{result}` — `added`, `updated` and `skipped` by the
+//!   latest registry import
+//! - `x402_discovery_last_success_timestamp_seconds` — end of the latest cycle in which
+//!   a facilitator was fetched and the import succeeded
+//!
+//! ERC-8004 registry reads served through the
+//! [`ReadCache`](crate::erc8004::read_cache::ReadCache) are counted in
+//! `x402_erc8004_cache_requests_total{method, result}`, `result` being `hit` or `miss`.
+//!
+//! Canary self-payments report `x402_canary_runs_total{network, result}` (`success`,
+//! `failure` or `skipped`), `x402_canary_duration_seconds{network}` and
+//! `x402_canary_alerting{network}`, 1 while a network's canaries are failing.
+//!
+//! Recording is a no-op until [`install`] sets the global recorder. The binary does so
+//! when `METRICS_PORT` is set and serves the [`PrometheusHandle`] at `/metrics` on that
+//! port, away from the public API.
+
+use ::metrics::{counter, gauge, histogram};
+use axum::routing::get;
+use axum::Router;
+use metrics_exporter_prometheus::{BuildError, Matcher, PrometheusBuilder, PrometheusHandle};
+use std::time::{Duration, Instant};
+
+use crate::canary::CanaryOutcome;
+use crate::discovery_aggregator::SourceResult;
+use crate::network::Network;
+use crate::types::{SettleResponse, VerifyRequest, VerifyResponse};
+
+/// Histogram buckets for operation durations, in seconds. Settlements wait for a
+/// receipt, hence the long tail.
+const DURATION_BUCKETS: &[f64] = &[
+    0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0,
+];
+
+/// How often histograms are compacted and idle series dropped.
+const UPKEEP_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Labels of one verify or settle call, taken from its payment payload.
+#[derive(Debug, Clone)]
+pub struct OperationLabels {
+    network: String,
+    scheme: String,
+    started: Instant,
+}
+
+impl OperationLabels {
+    pub fn start(request: &VerifyRequest) -> Self {
+        Self {
+            network: request.payment_payload.network.to_string(),
+            scheme: request.payment_payload.scheme.to_string(),
+            started: Instant::now(),
+        }
+    }
+
+    pub fn record_verify<E>(self, result: &Result<VerifyResponse, E>) {
+        let result = match result {
+            Ok(VerifyResponse::Valid { .. }) => "valid",
+            Ok(VerifyResponse::Invalid { .. }) => "invalid",
+            Err(_) => "error",
+        };
+        let elapsed = self.started.elapsed().as_secs_f64();
+        counter!("x402_verify_total", "network" => self.network.clone(), "scheme" => self.scheme, "result" => result)
+            .increment(1);
+        histogram!("x402_verify_duration_seconds", "network" => self.network).record(elapsed);
+    }
+
+    pub fn record_settle<E>(self, result: &Result<SettleResponse, E>) {
+        let result = match result {
+            Ok(response) if response.success => "success",
+            Ok(_) => "failure",
+            Err(_) => "error",
+        };
+        let elapsed = self.started.elapsed().as_secs_f64();
+        counter!("x402_settle_total", "network" => self.network.clone(), "scheme" => self.scheme, "result" => result)
+            .increment(1);
+        histogram!("x402_settle_duration_seconds", "network" => self.network).record(elapsed);
+    }
+}
+
+/// Count a payment rejected because its nonce was already used on `chain`.
+pub fn record_nonce_replay(chain: &str) {
+    counter!("x402_nonce_replay_attempts_total", "chain" => chain.to_string()).increment(1);
+}
+
+/// Record a fetch of a discovery source that its circuit breaker did not skip.
+pub fn record_discovery_fetch(result: &SourceResult) {
+    let facilitator = result.facilitator_id.clone();
+    histogram!("x402_discovery_fetch_duration_seconds", "facilitator" => facilitator.clone())
+        .record(result.duration_ms as f64 / 1000.0);
+    counter!("x402_discovery_resources_fetched_total", "facilitator" => facilitator.clone())
+        .increment(result.fetched as u64);
+    counter!("x402_discovery_resources_converted_total", "facilitator" => facilitator.clone())
+        .increment(result.converted as u64);
+    counter!("x402_discovery_resources_skipped_total", "facilitator" => facilitator)
+        .increment(result.skipped as u64);
+}
+
+/// Count a discovery page from `facilitator` that could not be parsed.
+pub fn record_discovery_parse_error(facilitator: &str) {
+    counter!("x402_discovery_parse_errors_total", "facilitator" => facilitator.to_string())
+        .increment(1);
+}
+
+/// Record the outcome of importing aggregated resources into the registry.
+pub fn record_discovery_import(added: usize, updated: usize, skipped: usize) {
+    gauge!("x402_discovery_import_resources", "result" => "added").set(added as f64);
+    gauge!("x402_discovery_import_resources", "result" => "updated").set(updated as f64);
+    gauge!("x402_discovery_import_resources", "result" => "skipped").set(skipped as f64);
+}
+
+/// Count a lookup of the ERC-8004 read cache for the registry `method`.
+pub fn record_erc8004_cache(method: &'static str, hit: bool) {
+    let result = if hit { "hit" } else { "miss" };
+    counter!("x402_erc8004_cache_requests_total", "method" => method, "result" => result)
+        .increment(1);
+}
+
+/// Record a canary run on `network` and whether its canaries are now alerting.
+pub fn record_canary(network: Network, outcome: &CanaryOutcome, latency: Duration, alerting: bool) {
+    let network = network.to_string();
+    let result = match outcome {
+        CanaryOutcome::Success { .. } => "success",
+        CanaryOutcome::Failure { .. } => "failure",
+        CanaryOutcome::Skipped { .. } => "skipped",
+    };
+    counter!("x402_canary_runs_total", "network" => network.clone(), "result" => result)
+        .increment(1);
+    histogram!("x402_canary_duration_seconds", "network" => network.clone())
+        .record(latency.as_secs_f64());
+    gauge!("x402_canary_alerting", "network" => network).set(if alerting { 1.0 } else { 0.0 });
+}
+
+/// Record a successful aggregation cycle that completed at `completed_at` (Unix seconds).
+pub fn record_discovery_cycle_success(completed_at: u64) {
+    gauge!("x402_discovery_last_success_timestamp_seconds").set(completed_at as f64);
+}
+
+pub(crate) fn builder() -> Result<PrometheusBuilder, BuildError> {
+    PrometheusBuilder::new().set_buckets_for_metric(
+        Matcher::Suffix("duration_seconds".to_string()),
+        DURATION_BUCKETS,
+    )
+}
+
+/// Install the Prometheus recorder as the global metrics recorder.
+///
+/// Must be called from within a Tokio runtime: it spawns the exporter's upkeep task.
+pub fn install() -> Result<PrometheusHandle, BuildError> {
+    let handle = builder()?.install_recorder()?;
+    let upkeep = handle.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(UPKEEP_INTERVAL);
+        loop {
+            interval.tick().await;
+            upkeep.run_upkeep();
+        }
+    });
+    Ok(handle)
+}
+
+/// Router serving the scrape endpoint, `GET /metrics`.
+pub fn routes(handle: PrometheusHandle) -> Router {
+    Router::new().route("/metrics", get(move || async move { handle.render() }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::facilitator::Facilitator;
+    use crate::types::{
+        ExactPaymentPayload, MixedAddress, SettleRequest, SupportedPaymentKindsResponse,
+    };
+    use std::sync::Arc;
+
+    /// Settles payments of at least 100 units, reports a failed settlement below that,
+    /// and errors on zero.
+    struct MockFacilitator;
+
+    impl Facilitator for MockFacilitator {
+        type Error = String;
+
+        async fn verify(&self, _request: &VerifyRequest) -> Result<VerifyResponse, String> {
+            unimplemented!()
+        }
+
+        async fn settle(&self, request: &SettleRequest) -> Result<SettleResponse, String> {
+            let amount = request.payment_requirements.max_amount_required.0;
+            if amount == 0u64 {
+                return Err("zero amount".to_string());
+            }
+            let ExactPaymentPayload::Evm(payload) = &request.payment_payload.payload else {
+                return Err("unsupported payload".to_string());
+            };
+            Ok(SettleResponse {
+                success: amount >= 100u64,
+                error_reason: None,
+                payer: MixedAddress::Evm(payload.authorization.from),
+                transaction: None,
+                network: request.payment_payload.network,
+                proof_of_payment: None,
+                confirmation: None,
+            })
+        }
+
+        async fn supported(&self) -> Result<SupportedPaymentKindsResponse, String> {
+            unimplemented!()
+        }
+    }
+
+    fn settle_request(amount: u64) -> SettleRequest {
+        serde_json::from_value(serde_json::json!({
+            "x402Version": 1,
+            "paymentPayload": {
+                "x402Version": 1,
+                "scheme": "exact",
+                "network": "base-sepolia",
+                "payload": {
+                    "signature": format!("0x{}", "11".repeat(65)),
+                    "authorization": {
+                        "from": "0x2222222222222222222222222222222222222222",
+                        "to": "0x1111111111111111111111111111111111111111",
+                        "value": amount.to_string(),
+                        "validAfter": "0",
+                        "validBefore": "9999999999",
+                        "nonce": format!("0x{}", "00".repeat(32))
+                    }
+                }
+            },
+            "paymentRequirements": {
+                "scheme": "exact",
+                "network": "base-sepolia",
+                "maxAmountRequired": amount.to_string(),
+                "resource": "https://api.example.com/data",
+                "description": "",
+                "mimeType": "application/json",
+                "payTo": "0x1111111111111111111111111111111111111111",
+                "maxTimeoutSeconds": 60,
+                "asset": "0x036CbD53842c5426634e7929541eC2318f3dCF7e"
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_settle_calls_are_counted() {
+        let recorder = builder().unwrap().build_recorder();
+        let handle = recorder.handle();
+        let facilitator = Arc::new(MockFacilitator);
+
+        ::metrics::with_local_recorder(&recorder, || {
+            futures::executor::block_on(async {
+                for amount in [100, 500, 1, 0] {
+                    let _ = facilitator.settle(&settle_request(amount)).await;
+                }
+            });
+            record_nonce_replay("stellar");
+        });
+
+        let rendered = handle.render();
+        for line in [
+            r#"x402_settle_total{network="base-sepolia",scheme="exact",result="success"} 2"#,
+            r#"x402_settle_total{network="base-sepolia",scheme="exact",result="failure"} 1"#,
+            r#"x402_settle_total{network="base-sepolia",scheme="exact",result="error"} 1"#,
+            r#"x402_settle_duration_seconds_count{network="base-sepolia"} 4"#,
+            r#"x402_nonce_replay_attempts_total{chain="stellar"} 1"#,
+        ] {
+            assert!(rendered.contains(line), "missing {line} in:\n{rendered}");
+        }
+        assert!(rendered
+            .contains(r#"x402_settle_duration_seconds_bucket{network="base-sepolia",le="0.01"}"#));
+        assert!(!rendered.contains("x402_verify_total"));
+    }
+
+    #[tokio::test]
+    async fn test_metrics_route_renders_handle() {
+        let recorder = builder().unwrap().build_recorder();
+        let handle = recorder.handle();
+        ::metrics::with_local_recorder(&recorder, || record_nonce_replay("algorand"));
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, routes(handle)).await.unwrap();
+        });
+        let body = reqwest::get(format!("http://{addr}/metrics"))
+            .await
+            .unwrap()
+            .text()
+            .await
+            .unwrap();
+        assert!(body.contains(r#"x402_nonce_replay_attempts_total{chain="algorand"} 1"#));
+    }
+}
@@ -17,6 +17,7 @@ use std::str::FromStr;
 ///
 /// Used to differentiate between testnet and mainnet environments for the x402 protocol.
 #[derive(Debug, Hash, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-gen", derive(ts_rs::TS))]
 pub enum Network {
     /// Base Sepolia testnet (chain ID 84532).
     #[serde(rename = "base-sepolia")]
@@ -130,6 +131,14 @@ pub enum Network {
     #[cfg(feature = "sui")]
     #[serde(rename = "sui-testnet")]
     SuiTestnet,
+    /// Hedera mainnet (chain ID 295 on the JSON-RPC Relay).
+    #[cfg(feature = "hedera")]
+    #[serde(rename = "hedera")]
+    Hedera,
+    /// Hedera testnet (chain ID 296 on the JSON-RPC Relay).
+    #[cfg(feature = "hedera")]
+    #[serde(rename = "hedera-testnet")]
+    HederaTestnet,
     /// SKALE Base mainnet (chain ID 1187947933) - L3 on Base with gasless transactions.
     #[serde(rename = "skale-base")]
     SkaleBase,
@@ -139,6 +148,15 @@ pub enum Network {
     /// Scroll mainnet (chain ID 534352) - zkEVM L2 on Ethereum.
     #[serde(rename = "scroll")]
     Scroll,
+    /// Scroll Sepolia testnet (chain ID 534351).
+    #[serde(rename = "scroll-sepolia")]
+    ScrollSepolia,
+    /// zkSync Era mainnet (chain ID 324) - ZK rollup L2 on Ethereum.
+    #[serde(rename = "zksync-era")]
+    ZkSyncEra,
+    /// zkSync Era Sepolia testnet (chain ID 300).
+    #[serde(rename = "zksync-era-sepolia")]
+    ZkSyncEraSepolia,
 }
 
 impl Display for Network {
@@ -184,9 +202,16 @@ impl Display for Network {
             Network::Sui => write!(f, "sui"),
             #[cfg(feature = "sui")]
             Network::SuiTestnet => write!(f, "sui-testnet"),
+            #[cfg(feature = "hedera")]
+            Network::Hedera => write!(f, "hedera"),
+            #[cfg(feature = "hedera")]
+            Network::HederaTestnet => write!(f, "hedera-testnet"),
             Network::SkaleBase => write!(f, "skale-base"),
             Network::SkaleBaseSepolia => write!(f, "skale-base-sepolia"),
             Network::Scroll => write!(f, "scroll"),
+            Network::ScrollSepolia => write!(f, "scroll-sepolia"),
+            Network::ZkSyncEra => write!(f, "zksync-era"),
+            Network::ZkSyncEraSepolia => write!(f, "zksync-era-sepolia"),
         }
     }
 }
@@ -241,9 +266,16 @@ impl FromStr for Network {
             "sui" | "sui-mainnet" => Ok(Network::Sui),
             #[cfg(feature = "sui")]
             "sui-testnet" => Ok(Network::SuiTestnet),
+            #[cfg(feature = "hedera")]
+            "hedera" | "hedera-mainnet" => Ok(Network::Hedera),
+            #[cfg(feature = "hedera")]
+            "hedera-testnet" => Ok(Network::HederaTestnet),
             "skale-base" | "skale" => Ok(Network::SkaleBase),
             "skale-base-sepolia" | "skale-testnet" => Ok(Network::SkaleBaseSepolia),
             "scroll" | "scroll-mainnet" => Ok(Network::Scroll),
+            "scroll-sepolia" => Ok(Network::ScrollSepolia),
+            "zksync-era" | "zksync" | "zksync-mainnet" => Ok(Network::ZkSyncEra),
+            "zksync-era-sepolia" | "zksync-sepolia" => Ok(Network::ZkSyncEraSepolia),
             _ => Err(NetworkParseError(s.to_string())),
         }
     }
@@ -259,6 +291,8 @@ pub enum NetworkFamily {
     Algorand,
     #[cfg(feature = "sui")]
     Sui,
+    #[cfg(feature = "hedera")]
+    Hedera,
 }
 
 impl From<Network> for NetworkFamily {
@@ -304,62 +338,22 @@ impl From<Network> for NetworkFamily {
             Network::Sui => NetworkFamily::Sui,
             #[cfg(feature = "sui")]
             Network::SuiTestnet => NetworkFamily::Sui,
+            #[cfg(feature = "hedera")]
+            Network::Hedera => NetworkFamily::Hedera,
+            #[cfg(feature = "hedera")]
+            Network::HederaTestnet => NetworkFamily::Hedera,
             Network::SkaleBase => NetworkFamily::Evm,
             Network::SkaleBaseSepolia => NetworkFamily::Evm,
             Network::Scroll => NetworkFamily::Evm,
+            Network::ScrollSepolia => NetworkFamily::Evm,
+            Network::ZkSyncEra => NetworkFamily::Evm,
+            Network::ZkSyncEraSepolia => NetworkFamily::Evm,
         }
     }
 }
 
 impl Network {
     /// Return all known [`Network`] variants.
-    #[cfg(all(feature = "algorand", feature = "sui"))]
-    pub fn variants() -> &'static [Network] {
-        &[
-            Network::BaseSepolia,
-            Network::Base,
-            Network::XdcMainnet,
-            Network::AvalancheFuji,
-            Network::Avalanche,
-            Network::XrplEvm,
-            Network::Solana,
-            Network::SolanaDevnet,
-            Network::PolygonAmoy,
-            Network::Polygon,
-            Network::Optimism,
-            Network::OptimismSepolia,
-            Network::Celo,
-            Network::CeloSepolia,
-            Network::HyperEvm,
-            Network::HyperEvmTestnet,
-            Network::Sei,
-            Network::SeiTestnet,
-            Network::Ethereum,
-            Network::EthereumSepolia,
-            Network::Arbitrum,
-            Network::ArbitrumSepolia,
-            Network::Unichain,
-            Network::UnichainSepolia,
-            Network::Monad,
-            Network::Bsc,
-            Network::Near,
-            Network::NearTestnet,
-            Network::Stellar,
-            Network::StellarTestnet,
-            Network::Fogo,
-            Network::FogoTestnet,
-            Network::Algorand,
-            Network::AlgorandTestnet,
-            Network::Sui,
-            Network::SuiTestnet,
-            Network::SkaleBase,
-            Network::SkaleBaseSepolia,
-            Network::Scroll,
-        ]
-    }
-
-    /// Return all known [`Network`] variants.
-    #[cfg(all(feature = "algorand", not(feature = "sui")))]
     pub fn variants() -> &'static [Network] {
         &[
             Network::BaseSepolia,
@@ -394,97 +388,24 @@ impl Network {
             Network::StellarTestnet,
             Network::Fogo,
             Network::FogoTestnet,
+            #[cfg(feature = "algorand")]
             Network::Algorand,
+            #[cfg(feature = "algorand")]
             Network::AlgorandTestnet,
-            Network::SkaleBase,
-            Network::SkaleBaseSepolia,
-            Network::Scroll,
-        ]
-    }
-
-    /// Return all known [`Network`] variants.
-    #[cfg(all(not(feature = "algorand"), feature = "sui"))]
-    pub fn variants() -> &'static [Network] {
-        &[
-            Network::BaseSepolia,
-            Network::Base,
-            Network::XdcMainnet,
-            Network::AvalancheFuji,
-            Network::Avalanche,
-            Network::XrplEvm,
-            Network::Solana,
-            Network::SolanaDevnet,
-            Network::PolygonAmoy,
-            Network::Polygon,
-            Network::Optimism,
-            Network::OptimismSepolia,
-            Network::Celo,
-            Network::CeloSepolia,
-            Network::HyperEvm,
-            Network::HyperEvmTestnet,
-            Network::Sei,
-            Network::SeiTestnet,
-            Network::Ethereum,
-            Network::EthereumSepolia,
-            Network::Arbitrum,
-            Network::ArbitrumSepolia,
-            Network::Unichain,
-            Network::UnichainSepolia,
-            Network::Monad,
-            Network::Bsc,
-            Network::Near,
-            Network::NearTestnet,
-            Network::Stellar,
-            Network::StellarTestnet,
-            Network::Fogo,
-            Network::FogoTestnet,
+            #[cfg(feature = "sui")]
             Network::Sui,
+            #[cfg(feature = "sui")]
             Network::SuiTestnet,
+            #[cfg(feature = "hedera")]
+            Network::Hedera,
+            #[cfg(feature = "hedera")]
+            Network::HederaTestnet,
             Network::SkaleBase,
             Network::SkaleBaseSepolia,
             Network::Scroll,
-        ]
-    }
-
-    /// Return all known [`Network`] variants.
-    #[cfg(all(not(feature = "algorand"), not(feature = "sui")))]
-    pub fn variants() -> &'static [Network] {
-        &[
-            Network::BaseSepolia,
-            Network::Base,
-            Network::XdcMainnet,
-            Network::AvalancheFuji,
-            Network::Avalanche,
-            Network::XrplEvm,
-            Network::Solana,
-            Network::SolanaDevnet,
-            Network::PolygonAmoy,
-            Network::Polygon,
-            Network::Optimism,
-            Network::OptimismSepolia,
-            Network::Celo,
-            Network::CeloSepolia,
-            Network::HyperEvm,
-            Network::HyperEvmTestnet,
-            Network::Sei,
-            Network::SeiTestnet,
-            Network::Ethereum,
-            Network::EthereumSepolia,
-            Network::Arbitrum,
-            Network::ArbitrumSepolia,
-            Network::Unichain,
-            Network::UnichainSepolia,
-            Network::Monad,
-            Network::Bsc,
-            Network::Near,
-            Network::NearTestnet,
-            Network::Stellar,
-            Network::StellarTestnet,
-            Network::Fogo,
-            Network::FogoTestnet,
-            Network::SkaleBase,
-            Network::SkaleBaseSepolia,
-            Network::Scroll,
+            Network::ScrollSepolia,
+            Network::ZkSyncEra,
+            Network::ZkSyncEraSepolia,
         ]
     }
 
@@ -498,6 +419,10 @@ impl Network {
         if matches!(self, Network::SuiTestnet) {
             return true;
         }
+        #[cfg(feature = "hedera")]
+        if matches!(self, Network::HederaTestnet) {
+            return true;
+        }
         matches!(
             self,
             Network::BaseSepolia
@@ -515,6 +440,8 @@ impl Network {
                 | Network::StellarTestnet
                 | Network::FogoTestnet
                 | Network::SkaleBaseSepolia
+                | Network::ScrollSepolia
+                | Network::ZkSyncEraSepolia
         )
     }
 
@@ -531,6 +458,7 @@ impl Network {
     /// - NEAR: `near:{network_name}`
     /// - Stellar: `stellar:{network_name}`
     /// - Fogo: `fogo:{network_name}`
+    /// - Hedera: `hedera:{network_name}`
     pub fn to_caip2(&self) -> String {
         match self {
             // EVM chains - eip155:{chain_id}
@@ -580,11 +508,20 @@ impl Network {
             Network::Sui => "sui:mainnet".to_string(),
             #[cfg(feature = "sui")]
             Network::SuiTestnet => "sui:testnet".to_string(),
+            // Hedera - hedera:{network_name}
+            #[cfg(feature = "hedera")]
+            Network::Hedera => "hedera:mainnet".to_string(),
+            #[cfg(feature = "hedera")]
+            Network::HederaTestnet => "hedera:testnet".to_string(),
             // SKALE - eip155:{chain_id}
             Network::SkaleBase => "eip155:1187947933".to_string(),
             Network::SkaleBaseSepolia => "eip155:324705682".to_string(),
             // Scroll - eip155:{chain_id}
             Network::Scroll => "eip155:534352".to_string(),
+            Network::ScrollSepolia => "eip155:534351".to_string(),
+            // zkSync Era - eip155:{chain_id}
+            Network::ZkSyncEra => "eip155:324".to_string(),
+            Network::ZkSyncEraSepolia => "eip155:300".to_string(),
         }
     }
 
@@ -640,11 +577,20 @@ impl Network {
             "sui:mainnet" => Some(Network::Sui),
             #[cfg(feature = "sui")]
             "sui:testnet" => Some(Network::SuiTestnet),
+            // Hedera
+            #[cfg(feature = "hedera")]
+            "hedera:mainnet" => Some(Network::Hedera),
+            #[cfg(feature = "hedera")]
+            "hedera:testnet" => Some(Network::HederaTestnet),
             // SKALE
             "eip155:1187947933" => Some(Network::SkaleBase),
             "eip155:324705682" => Some(Network::SkaleBaseSepolia),
             // Scroll
             "eip155:534352" => Some(Network::Scroll),
+            "eip155:534351" => Some(Network::ScrollSepolia),
+            // zkSync Era
+            "eip155:324" => Some(Network::ZkSyncEra),
+            "eip155:300" => Some(Network::ZkSyncEraSepolia),
             _ => None,
         }
     }
@@ -1183,6 +1129,36 @@ static USDC_SUI_TESTNET: Lazy<USDCDeployment> = Lazy::new(|| {
     })
 });
 
+/// Lazily initialized known USDC deployment on Hedera mainnet as [`USDCDeployment`].
+/// Note: Hedera tokens are Hedera Token Service (HTS) entities identified as `0.0.<num>`;
+/// the JSON-RPC Relay exposes them as ERC-20s at their long-zero EVM address.
+/// USDC token ID on mainnet: 0.0.456858
+#[cfg(feature = "hedera")]
+static USDC_HEDERA: Lazy<USDCDeployment> = Lazy::new(|| {
+    USDCDeployment(TokenDeployment {
+        asset: TokenAsset {
+            address: MixedAddress::Hedera("0.0.456858".to_string()),
+            network: Network::Hedera,
+        },
+        decimals: 6,
+        eip712: None, // HTS tokens do not implement EIP-3009
+    })
+});
+
+/// Lazily initialized known USDC deployment on Hedera testnet as [`USDCDeployment`].
+/// USDC token ID on testnet: 0.0.429274
+#[cfg(feature = "hedera")]
+static USDC_HEDERA_TESTNET: Lazy<USDCDeployment> = Lazy::new(|| {
+    USDCDeployment(TokenDeployment {
+        asset: TokenAsset {
+            address: MixedAddress::Hedera("0.0.429274".to_string()),
+            network: Network::HederaTestnet,
+        },
+        decimals: 6,
+        eip712: None, // HTS tokens do not implement EIP-3009
+    })
+});
+
 /// Lazily initialized known USDC.e deployment on SKALE Base mainnet as [`USDCDeployment`].
 /// SKALE Base is an L3 on Base with native EIP-3009 support and gasless transactions (sFUEL).
 static USDC_SKALE_BASE: Lazy<USDCDeployment> = Lazy::new(|| {
@@ -1231,6 +1207,53 @@ static USDC_SCROLL: Lazy<USDCDeployment> = Lazy::new(|| {
     })
 });
 
+/// Lazily initialized known USDC deployment on Scroll Sepolia testnet as [`USDCDeployment`].
+/// Bridged from Ethereum Sepolia through the Scroll USDC gateway.
+static USDC_SCROLL_SEPOLIA: Lazy<USDCDeployment> = Lazy::new(|| {
+    USDCDeployment(TokenDeployment {
+        asset: TokenAsset {
+            address: address!("0x2C9678042D52B97D27f2bD2947F7111d93F3dD0D").into(),
+            network: Network::ScrollSepolia,
+        },
+        decimals: 6,
+        eip712: Some(TokenDeploymentEip712 {
+            name: "USD Coin".into(),
+            version: "2".into(),
+        }),
+    })
+});
+
+/// Lazily initialized known USDC deployment on zkSync Era mainnet as [`USDCDeployment`].
+/// Native Circle USDC with EIP-3009 support (not the bridged USDC.e).
+static USDC_ZKSYNC_ERA: Lazy<USDCDeployment> = Lazy::new(|| {
+    USDCDeployment(TokenDeployment {
+        asset: TokenAsset {
+            address: address!("0x1d17CBcF0D6D143135aE902365D2E5e2A16538D4").into(),
+            network: Network::ZkSyncEra,
+        },
+        decimals: 6,
+        eip712: Some(TokenDeploymentEip712 {
+            name: "USDC".into(),
+            version: "2".into(),
+        }),
+    })
+});
+
+/// Lazily initialized known USDC deployment on zkSync Era Sepolia testnet as [`USDCDeployment`].
+static USDC_ZKSYNC_ERA_SEPOLIA: Lazy<USDCDeployment> = Lazy::new(|| {
+    USDCDeployment(TokenDeployment {
+        asset: TokenAsset {
+            address: address!("0xAe045DE5638162fa134807Cb558E15A3F5A7F853").into(),
+            network: Network::ZkSyncEraSepolia,
+        },
+        decimals: 6,
+        eip712: Some(TokenDeploymentEip712 {
+            name: "USDC".into(),
+            version: "2".into(),
+        }),
+    })
+});
+
 /// A known USDC deployment as a wrapper around [`TokenDeployment`].
 #[derive(Clone, Debug)]
 pub struct USDCDeployment(pub TokenDeployment);
@@ -1307,9 +1330,16 @@ impl USDCDeployment {
             Network::Sui => &USDC_SUI,
             #[cfg(feature = "sui")]
             Network::SuiTestnet => &USDC_SUI_TESTNET,
+            #[cfg(feature = "hedera")]
+            Network::Hedera => &USDC_HEDERA,
+            #[cfg(feature = "hedera")]
+            Network::HederaTestnet => &USDC_HEDERA_TESTNET,
             Network::SkaleBase => &USDC_SKALE_BASE,
             Network::SkaleBaseSepolia => &USDC_SKALE_BASE_SEPOLIA,
             Network::Scroll => &USDC_SCROLL,
+            Network::ScrollSepolia => &USDC_SCROLL_SEPOLIA,
+            Network::ZkSyncEra => &USDC_ZKSYNC_ERA,
+            Network::ZkSyncEraSepolia => &USDC_ZKSYNC_ERA_SEPOLIA,
         }
     }
 }
@@ -1776,6 +1806,8 @@ pub fn get_token_deployment(network: Network, token_type: TokenType) -> Option<T
         TokenType::Ausd => AUSDDeployment::by_network(network).map(|d| d.0.clone()),
         TokenType::Pyusd => PYUSDDeployment::by_network(network).map(|d| d.0.clone()),
         TokenType::Usdt => USDTDeployment::by_network(network).map(|d| d.0.clone()),
+        // Native currency, listed by the Algorand provider rather than a deployment
+        TokenType::Algo => None,
     }
 }
 
@@ -1828,9 +1860,26 @@ pub fn supported_networks_for_token(token_type: TokenType) -> Vec<Network> {
         TokenType::Ausd => AUSDDeployment::supported_networks().to_vec(),
         TokenType::Pyusd => PYUSDDeployment::supported_networks().to_vec(),
         TokenType::Usdt => USDTDeployment::supported_networks().to_vec(),
+        TokenType::Algo => vec![Network::Algorand, Network::AlgorandTestnet],
     }
 }
 
+/// Find the known token deployment (USDC, EURC, ...) at `asset` on `network`.
+///
+/// # Example
+/// ```ignore
+/// use x402_rs::network::{find_token_deployment, Network};
+///
+/// let deployment = find_token_deployment(Network::Base, &usdc_address).unwrap();
+/// assert_eq!(deployment.decimals, 6);
+/// ```
+pub fn find_token_deployment(network: Network, asset: &MixedAddress) -> Option<TokenDeployment> {
+    supported_tokens_for_network(network)
+        .into_iter()
+        .filter_map(|token| get_token_deployment(network, token))
+        .find(|deployment| deployment.asset.address == *asset)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -2079,6 +2128,54 @@ mod tests {
         assert!(!tokens.contains(&TokenType::Usdt));
     }
 
+    #[test]
+    fn test_polygon_caip2_round_trip() {
+        for (network, name, caip2, chain_id) in [
+            (Network::Polygon, "polygon", "eip155:137", 137),
+            (Network::PolygonAmoy, "polygon-amoy", "eip155:80002", 80002),
+        ] {
+            assert_eq!(network.to_caip2(), caip2);
+            assert_eq!(Network::from_caip2(caip2), Some(network));
+            let id: crate::caip2::Caip2NetworkId = caip2.parse().unwrap();
+            assert_eq!(id.chain_id(), Some(chain_id));
+            assert_eq!(Network::from_caip2(&id.to_string()), Some(network));
+            assert_eq!(network.to_string(), name);
+            assert_eq!(name.parse::<Network>().unwrap(), network);
+            assert!(matches!(NetworkFamily::from(network), NetworkFamily::Evm));
+        }
+        assert_eq!(
+            USDCDeployment::by_network(Network::Polygon).asset.address,
+            address!("0x3c499c542cEF5E3811e1192ce70d8cC03d5c3359").into()
+        );
+    }
+
+    #[test]
+    fn test_zksync_and_scroll_caip2_round_trip() {
+        for (network, name, caip2, chain_id) in [
+            (Network::ZkSyncEra, "zksync-era", "eip155:324", 324),
+            (Network::ZkSyncEraSepolia, "zksync-era-sepolia", "eip155:300", 300),
+            (Network::Scroll, "scroll", "eip155:534352", 534352),
+            (Network::ScrollSepolia, "scroll-sepolia", "eip155:534351", 534351),
+        ] {
+            assert_eq!(network.to_caip2(), caip2);
+            assert_eq!(Network::from_caip2(caip2), Some(network));
+            let id: crate::caip2::Caip2NetworkId = caip2.parse().unwrap();
+            assert_eq!(id.chain_id(), Some(chain_id));
+            assert_eq!(network.to_string(), name);
+            assert_eq!(name.parse::<Network>().unwrap(), network);
+            assert!(Network::variants().contains(&network));
+            assert_eq!(USDCDeployment::by_network(network).asset.network, network);
+        }
+        assert_eq!("zksync".parse::<Network>().unwrap(), Network::ZkSyncEra);
+        assert!(Network::ZkSyncEraSepolia.is_testnet());
+        assert!(Network::ScrollSepolia.is_testnet());
+        assert!(Network::ZkSyncEra.is_mainnet());
+        assert_eq!(
+            USDCDeployment::by_network(Network::ZkSyncEra).asset.address,
+            address!("0x1d17CBcF0D6D143135aE902365D2E5e2A16538D4").into()
+        );
+    }
+
     #[test]
     fn test_supported_tokens_for_arbitrum() {
         // Arbitrum supports USDC, AUSD, USDT
@@ -10,12 +10,21 @@
 //! StellarProvider / AlgorandProvider
 //!        |
 //!        v
-//! NonceStore (trait) <-- DynamoNonceStore, MemoryNonceStore
-//!        |
+//! NonceStore (trait) <-- RedisNonceStore, PostgresNonceStore, SqliteNonceStore,
+//!        |               DynamoNonceStore, MemoryNonceStore
 //!        v
-//! DynamoDB (production) / HashMap (development)
+//! Redis / PostgreSQL / SQLite / DynamoDB (production) / HashMap (development)
 //! ```
 //!
+//! [`create_nonce_store`] picks Redis when `REDIS_URL` is set (requires the `redis`
+//! feature), then PostgreSQL when `DATABASE_URL` is set (requires the `postgres`
+//! feature), then SQLite when `SQLITE_PATH` is set (requires the `sqlite` feature),
+//! then DynamoDB when `NONCE_STORE_TABLE_NAME` is set, then memory. The server calls it
+//! once at startup and hands the same store to every provider and to [`crate::idempotency`].
+//!
+//! The PostgreSQL table has the same columns as the DynamoDB one; its schema lives in
+//! `migrations/` and is applied on connect.
+//!
 //! # DynamoDB Schema
 //!
 //! Table: `facilitator-nonces` (configurable via NONCE_STORE_TABLE_NAME)
@@ -26,19 +35,37 @@
 //! | chain | S | Chain identifier (stellar, stellar-testnet, algorand, algorand-testnet) |
 //! | created_at | N | Unix timestamp when the nonce was recorded |
 //! | expires_at | N | TTL attribute - Unix timestamp for automatic deletion |
+//! | cached_value | S | Value of entries stored with [`NonceStore::claim_value`] |
+//!
+//! # Values
+//!
+//! Besides used nonces, every store keeps short-lived values under string keys with
+//! [`NonceStore::claim_value`] and [`NonceStore::set_value`], such as the
+//! `idempotency:{key}` records of [`crate::idempotency`]. Their `chain` label is the key
+//! prefix before the first `:` or `#`.
 //!
 //! # TTL Strategy
 //!
 //! - Stellar: TTL = signature_expiration_ledger * 5 seconds + 1 hour buffer
 //! - Algorand: TTL = (last_valid_round - current_round) * 4 seconds + 1 hour buffer
+//!
+//! # Key Representation
+//!
+//! Providers build a [`NonceKey`], a compact binary key that needs no allocation or
+//! hex encoding. The in-memory store indexes by `NonceKey` directly; DynamoDB uses its
+//! string form. Both forms name the same logical key: `NonceKey::parse(&key.to_string())`
+//! round-trips, and the string-based trait methods parse into the binary form first.
 
 use async_trait::async_trait;
 use std::collections::HashMap;
+use std::fmt::{self, Write as _};
 use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
 use tokio::sync::RwLock;
 use tracing::{debug, error, info, warn};
 
+use crate::hex_fmt::Hex32;
+
 // ============================================================================
 // Error Types
 // ============================================================================
@@ -104,6 +131,205 @@ pub trait NonceStore: Send + Sync + std::fmt::Debug {
 
     /// Get the store type name for logging.
     fn store_type(&self) -> &'static str;
+
+    /// [`check_and_mark_used`](Self::check_and_mark_used) for a binary [`NonceKey`].
+    ///
+    /// Stores keyed by strings use the default, which formats the key once.
+    async fn check_and_mark_key(&self, key: &NonceKey, ttl_seconds: u64) -> Result<(), NonceStoreError> {
+        self.check_and_mark_used(&key.to_string(), ttl_seconds).await
+    }
+
+    /// [`is_used`](Self::is_used) for a binary [`NonceKey`].
+    async fn is_key_used(&self, key: &NonceKey) -> Result<bool, NonceStoreError> {
+        self.is_used(&key.to_string()).await
+    }
+
+    /// Atomically store `value` under `key` unless an unexpired entry exists.
+    ///
+    /// The counterpart of [`check_and_mark_used`](Self::check_and_mark_used) for entries
+    /// carrying a value.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(None)` - The key was free and now holds `value`
+    /// * `Ok(Some(existing))` - The key is taken; `existing` is its value, empty if it has none
+    /// * `Err(...)` - Storage error
+    async fn claim_value(
+        &self,
+        key: &str,
+        value: &str,
+        ttl_seconds: u64,
+    ) -> Result<Option<String>, NonceStoreError>;
+
+    /// Store `value` under `key` for `ttl_seconds`, replacing any existing entry.
+    async fn set_value(
+        &self,
+        key: &str,
+        value: &str,
+        ttl_seconds: u64,
+    ) -> Result<(), NonceStoreError>;
+}
+
+/// `chain` label of a value entry: its key prefix, as `idempotency` for `idempotency:{key}`.
+fn value_label(key: &str) -> &str {
+    key.split([':', '#']).next().unwrap_or("unknown")
+}
+
+// ============================================================================
+// Keys
+// ============================================================================
+
+/// Chains with off-chain nonce tracking.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NonceChain {
+    Stellar,
+    StellarTestnet,
+    Algorand,
+    AlgorandTestnet,
+}
+
+impl NonceChain {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NonceChain::Stellar => "stellar",
+            NonceChain::StellarTestnet => "stellar-testnet",
+            NonceChain::Algorand => "algorand",
+            NonceChain::AlgorandTestnet => "algorand-testnet",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "stellar" => Some(NonceChain::Stellar),
+            "stellar-testnet" => Some(NonceChain::StellarTestnet),
+            "algorand" => Some(NonceChain::Algorand),
+            "algorand-testnet" => Some(NonceChain::AlgorandTestnet),
+            _ => None,
+        }
+    }
+}
+
+/// Length of a Stellar StrKey account or contract address.
+const STELLAR_ADDRESS_LEN: usize = 56;
+
+/// Compact binary form of a nonce key.
+///
+/// Known chains and address shapes are stored inline; anything else keeps its
+/// string form in [`NonceKey::Other`], so every string key maps to exactly one `NonceKey`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum NonceKey {
+    /// `{chain}#{address}#{nonce}`
+    Stellar {
+        chain: NonceChain,
+        address: [u8; STELLAR_ADDRESS_LEN],
+        nonce: u64,
+    },
+    /// `{chain}#group#{group_id_hex}`
+    AlgorandGroup { chain: NonceChain, group_id: [u8; 32] },
+    /// Any key without a compact representation
+    Other(Box<str>),
+}
+
+impl NonceKey {
+    /// Key for a Stellar authorization nonce.
+    pub fn stellar(chain: &str, address: &str, nonce: u64) -> Self {
+        match (NonceChain::from_name(chain), <[u8; STELLAR_ADDRESS_LEN]>::try_from(address.as_bytes())) {
+            (Some(chain), Ok(address)) if !address.contains(&b'#') => NonceKey::Stellar { chain, address, nonce },
+            _ => NonceKey::Other(format!("{chain}#{address}#{nonce}").into()),
+        }
+    }
+
+    /// Key for an Algorand atomic group id.
+    pub fn algorand_group(chain: &str, group_id: &[u8; 32]) -> Self {
+        match NonceChain::from_name(chain) {
+            Some(chain) => NonceKey::AlgorandGroup {
+                chain,
+                group_id: *group_id,
+            },
+            None => NonceKey::Other(format!("{chain}#group#{}", Hex32::new(group_id)).into()),
+        }
+    }
+
+    /// Parse the string form produced by [`Display`](fmt::Display).
+    pub fn parse(key: &str) -> Self {
+        Self::parse_compact(key).unwrap_or_else(|| NonceKey::Other(key.into()))
+    }
+
+    fn parse_compact(key: &str) -> Option<Self> {
+        let mut parts = key.split('#');
+        let chain = NonceChain::from_name(parts.next()?)?;
+        let (middle, last) = (parts.next()?, parts.next()?);
+        if parts.next().is_some() {
+            return None;
+        }
+        if middle == "group" {
+            let mut group_id = [0u8; 32];
+            // Only canonical lowercase hex round-trips through Display
+            if last.bytes().any(|b| b.is_ascii_uppercase()) {
+                return None;
+            }
+            hex::decode_to_slice(last, &mut group_id).ok()?;
+            return Some(NonceKey::AlgorandGroup { chain, group_id });
+        }
+        let address = <[u8; STELLAR_ADDRESS_LEN]>::try_from(middle.as_bytes()).ok()?;
+        let nonce: u64 = last.parse().ok()?;
+        // Reject non-canonical numbers such as `007` or `+7`
+        if nonce.to_string() != last {
+            return None;
+        }
+        Some(NonceKey::Stellar { chain, address, nonce })
+    }
+
+    /// Chain label used as the DynamoDB `chain` attribute.
+    pub fn chain_label(&self) -> &str {
+        match self {
+            NonceKey::Stellar { chain, .. } | NonceKey::AlgorandGroup { chain, .. } => chain.as_str(),
+            NonceKey::Other(key) => key.split('#').next().unwrap_or("unknown"),
+        }
+    }
+
+    /// Write the string form into `buf`, reusing its allocation.
+    pub fn write_to(&self, buf: &mut String) {
+        buf.clear();
+        buf.reserve(self.encoded_len());
+        let _ = write!(buf, "{self}");
+    }
+
+    /// Length of the string form.
+    pub fn encoded_len(&self) -> usize {
+        match self {
+            NonceKey::Stellar { chain, nonce, .. } => {
+                let digits = nonce.checked_ilog10().unwrap_or(0) as usize + 1;
+                chain.as_str().len() + 1 + STELLAR_ADDRESS_LEN + 1 + digits
+            }
+            NonceKey::AlgorandGroup { chain, .. } => chain.as_str().len() + "#group#".len() + 64,
+            NonceKey::Other(key) => key.len(),
+        }
+    }
+}
+
+impl fmt::Display for NonceKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NonceKey::Stellar { chain, address, nonce } => {
+                // Addresses only enter this variant from `&str`, so they are valid UTF-8
+                let address = std::str::from_utf8(address).map_err(|_| fmt::Error)?;
+                write!(f, "{}#{}#{}", chain.as_str(), address, nonce)
+            }
+            NonceKey::AlgorandGroup { chain, group_id } => {
+                write!(f, "{}#group#{}", chain.as_str(), Hex32::new(group_id))
+            }
+            NonceKey::Other(key) => f.write_str(key),
+        }
+    }
+}
+
+impl std::str::FromStr for NonceKey {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self::parse(s))
+    }
 }
 
 // ============================================================================
@@ -114,14 +340,14 @@ pub trait NonceStore: Send + Sync + std::fmt::Debug {
 ///
 /// Format: `stellar#{address}#{nonce}` or `stellar-testnet#{address}#{nonce}`
 pub fn stellar_nonce_key(chain: &str, address: &str, nonce: u64) -> String {
-    format!("{}#{}#{}", chain, address, nonce)
+    NonceKey::stellar(chain, address, nonce).to_string()
 }
 
 /// Generate a nonce key for Algorand.
 ///
 /// Format: `algorand#group#{group_id_hex}` or `algorand-testnet#group#{group_id_hex}`
 pub fn algorand_nonce_key(chain: &str, group_id: &[u8; 32]) -> String {
-    format!("{}#group#{}", chain, hex::encode(group_id))
+    NonceKey::algorand_group(chain, group_id).to_string()
 }
 
 /// Calculate TTL for Stellar nonces.
@@ -152,9 +378,12 @@ pub fn algorand_ttl_seconds(current_round: u64, last_valid_round: u64) -> u64 {
 ///
 /// Does not persist data across restarts. Not suitable for production
 /// as it allows replay attacks after facilitator restart.
+///
+/// Entries are indexed by [`NonceKey`]; string keys are parsed into it first.
 #[derive(Debug, Default)]
 pub struct MemoryNonceStore {
-    data: Arc<RwLock<HashMap<String, u64>>>, // key -> expires_at timestamp
+    data: Arc<RwLock<HashMap<NonceKey, u64>>>, // key -> expires_at timestamp
+    values: Arc<RwLock<HashMap<String, (u64, String)>>>, // key -> (expires_at, value)
 }
 
 impl MemoryNonceStore {
@@ -162,6 +391,7 @@ impl MemoryNonceStore {
     pub fn new() -> Self {
         Self {
             data: Arc::new(RwLock::new(HashMap::new())),
+            values: Arc::new(RwLock::new(HashMap::new())),
         }
     }
 
@@ -176,6 +406,14 @@ impl MemoryNonceStore {
 #[async_trait]
 impl NonceStore for MemoryNonceStore {
     async fn check_and_mark_used(&self, key: &str, ttl_seconds: u64) -> Result<(), NonceStoreError> {
+        self.check_and_mark_key(&NonceKey::parse(key), ttl_seconds).await
+    }
+
+    async fn is_used(&self, key: &str) -> Result<bool, NonceStoreError> {
+        self.is_key_used(&NonceKey::parse(key)).await
+    }
+
+    async fn check_and_mark_key(&self, key: &NonceKey, ttl_seconds: u64) -> Result<(), NonceStoreError> {
         let now = Self::current_timestamp();
         let mut data = self.data.write().await;
 
@@ -184,18 +422,16 @@ impl NonceStore for MemoryNonceStore {
             if expires_at > now {
                 return Err(NonceStoreError::NonceAlreadyUsed(key.to_string()));
             }
-            // Expired entry, remove it
-            data.remove(key);
         }
 
-        // Mark as used
+        // Mark as used (replacing any expired entry)
         let expires_at = now + ttl_seconds;
-        data.insert(key.to_string(), expires_at);
+        data.insert(key.clone(), expires_at);
         debug!(key = %key, ttl_seconds = %ttl_seconds, "Marked nonce as used (memory)");
         Ok(())
     }
 
-    async fn is_used(&self, key: &str) -> Result<bool, NonceStoreError> {
+    async fn is_key_used(&self, key: &NonceKey) -> Result<bool, NonceStoreError> {
         let now = Self::current_timestamp();
         let data = self.data.read().await;
 
@@ -205,6 +441,38 @@ impl NonceStore for MemoryNonceStore {
         Ok(false)
     }
 
+    async fn claim_value(
+        &self,
+        key: &str,
+        value: &str,
+        ttl_seconds: u64,
+    ) -> Result<Option<String>, NonceStoreError> {
+        let now = Self::current_timestamp();
+        let mut values = self.values.write().await;
+
+        if let Some((expires_at, existing)) = values.get(key) {
+            if *expires_at > now {
+                return Ok(Some(existing.clone()));
+            }
+        }
+        values.insert(key.to_string(), (now + ttl_seconds, value.to_string()));
+        Ok(None)
+    }
+
+    async fn set_value(
+        &self,
+        key: &str,
+        value: &str,
+        ttl_seconds: u64,
+    ) -> Result<(), NonceStoreError> {
+        let expires_at = Self::current_timestamp() + ttl_seconds;
+        self.values
+            .write()
+            .await
+            .insert(key.to_string(), (expires_at, value.to_string()));
+        Ok(())
+    }
+
     async fn health_check(&self) -> Result<(), NonceStoreError> {
         Ok(())
     }
@@ -243,8 +511,8 @@ impl DynamoNonceStore {
 
     /// Create a new DynamoDB nonce store from environment variables.
     pub async fn from_env() -> Result<Self, NonceStoreError> {
-        let table_name = std::env::var("NONCE_STORE_TABLE_NAME")
-            .unwrap_or_else(|_| "facilitator-nonces".to_string());
+        let table_name = crate::env_registry::var("NONCE_STORE_TABLE_NAME")
+            .unwrap_or_else(|| "facilitator-nonces".to_string());
 
         let config = aws_config::load_defaults(aws_config::BehaviorVersion::latest()).await;
         let client = aws_sdk_dynamodb::Client::new(&config);
@@ -337,6 +605,89 @@ impl NonceStore for DynamoNonceStore {
         Ok(false)
     }
 
+    async fn claim_value(
+        &self,
+        key: &str,
+        value: &str,
+        ttl_seconds: u64,
+    ) -> Result<Option<String>, NonceStoreError> {
+        use aws_sdk_dynamodb::types::AttributeValue;
+
+        let now = Self::current_timestamp();
+        let expires_at = now + ttl_seconds;
+
+        // Same conditional put as check_and_mark_used, carrying the value
+        let result = self
+            .client
+            .put_item()
+            .table_name(&self.table_name)
+            .item("pk", AttributeValue::S(key.to_string()))
+            .item("chain", AttributeValue::S(value_label(key).to_string()))
+            .item("created_at", AttributeValue::N(now.to_string()))
+            .item("expires_at", AttributeValue::N(expires_at.to_string()))
+            .item("cached_value", AttributeValue::S(value.to_string()))
+            .condition_expression("attribute_not_exists(pk) OR expires_at < :now")
+            .expression_attribute_values(":now", AttributeValue::N(now.to_string()))
+            .send()
+            .await;
+
+        match result {
+            Ok(_) => Ok(None),
+            Err(err) => {
+                let service_err = err.into_service_error();
+                if !service_err.is_conditional_check_failed_exception() {
+                    error!(error = %service_err, key = %key, "DynamoDB put_item failed");
+                    return Err(NonceStoreError::WriteError(service_err.to_string()));
+                }
+                let item = self
+                    .client
+                    .get_item()
+                    .table_name(&self.table_name)
+                    .key("pk", AttributeValue::S(key.to_string()))
+                    .projection_expression("cached_value")
+                    .consistent_read(true)
+                    .send()
+                    .await
+                    .map_err(|e| NonceStoreError::ReadError(e.to_string()))?
+                    .item;
+                let existing = match item.as_ref().and_then(|item| item.get("cached_value")) {
+                    Some(AttributeValue::S(existing)) => existing.clone(),
+                    _ => String::new(),
+                };
+                Ok(Some(existing))
+            }
+        }
+    }
+
+    async fn set_value(
+        &self,
+        key: &str,
+        value: &str,
+        ttl_seconds: u64,
+    ) -> Result<(), NonceStoreError> {
+        use aws_sdk_dynamodb::types::AttributeValue;
+
+        let now = Self::current_timestamp();
+        self.client
+            .put_item()
+            .table_name(&self.table_name)
+            .item("pk", AttributeValue::S(key.to_string()))
+            .item("chain", AttributeValue::S(value_label(key).to_string()))
+            .item("created_at", AttributeValue::N(now.to_string()))
+            .item(
+                "expires_at",
+                AttributeValue::N((now + ttl_seconds).to_string()),
+            )
+            .item("cached_value", AttributeValue::S(value.to_string()))
+            .send()
+            .await
+            .map_err(|e| {
+                error!(error = %e, key = %key, "DynamoDB put_item failed");
+                NonceStoreError::WriteError(e.to_string())
+            })?;
+        Ok(())
+    }
+
     async fn health_check(&self) -> Result<(), NonceStoreError> {
         // Try to describe the table to verify connectivity
         self.client
@@ -353,17 +704,741 @@ impl NonceStore for DynamoNonceStore {
     }
 }
 
+// ============================================================================
+// Redis Store
+// ============================================================================
+
+/// Where `REDIS_URL` points: one server, or a cluster given as comma-separated nodes.
+#[cfg(feature = "redis")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum RedisTarget {
+    Standalone(String),
+    Cluster(Vec<String>),
+}
+
+#[cfg(feature = "redis")]
+impl RedisTarget {
+    fn parse(value: &str) -> Option<Self> {
+        let mut nodes: Vec<String> = value
+            .split(',')
+            .map(str::trim)
+            .filter(|n| !n.is_empty())
+            .map(str::to_string)
+            .collect();
+        match nodes.len() {
+            0 => None,
+            1 => nodes.pop().map(Self::Standalone),
+            _ => Some(Self::Cluster(nodes)),
+        }
+    }
+}
+
+#[cfg(feature = "redis")]
+#[derive(Clone)]
+enum RedisConnection {
+    Standalone(redis::aio::ConnectionManager),
+    Cluster(redis::cluster_async::ClusterConnection),
+}
+
+/// Redis-based persistent nonce store for production.
+///
+/// Uses `SET key 1 NX EX ttl` for atomic check-and-mark operations; Redis expires
+/// keys on its own. Keys are `{prefix}{key}` where `key` is the string form built by
+/// [`stellar_nonce_key`] / [`algorand_nonce_key`].
+///
+/// # Configuration
+///
+/// Environment variables:
+/// - `REDIS_URL`: `redis://` URL, or comma-separated node URLs for Redis Cluster
+/// - `NONCE_STORE_PREFIX`: Key prefix (default: "x402:nonce:")
+#[cfg(feature = "redis")]
+pub struct RedisNonceStore {
+    connection: RedisConnection,
+    prefix: String,
+}
+
+#[cfg(feature = "redis")]
+impl fmt::Debug for RedisNonceStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mode = match self.connection {
+            RedisConnection::Standalone(_) => "standalone",
+            RedisConnection::Cluster(_) => "cluster",
+        };
+        f.debug_struct("RedisNonceStore")
+            .field("mode", &mode)
+            .field("prefix", &self.prefix)
+            .finish()
+    }
+}
+
+#[cfg(feature = "redis")]
+impl RedisNonceStore {
+    /// Connect to a standalone server or, given several node URLs, a cluster.
+    pub async fn connect(urls: &str, prefix: String) -> Result<Self, NonceStoreError> {
+        let target = RedisTarget::parse(urls)
+            .ok_or_else(|| NonceStoreError::NotConfigured("REDIS_URL is empty".to_string()))?;
+        let connection = match &target {
+            RedisTarget::Standalone(url) => {
+                let client = redis::Client::open(url.as_str())
+                    .map_err(|e| NonceStoreError::ConnectionFailed(e.to_string()))?;
+                RedisConnection::Standalone(
+                    client
+                        .get_connection_manager()
+                        .await
+                        .map_err(|e| NonceStoreError::ConnectionFailed(e.to_string()))?,
+                )
+            }
+            RedisTarget::Cluster(nodes) => {
+                let client = redis::cluster::ClusterClient::new(nodes.clone())
+                    .map_err(|e| NonceStoreError::ConnectionFailed(e.to_string()))?;
+                RedisConnection::Cluster(
+                    client
+                        .get_async_connection()
+                        .await
+                        .map_err(|e| NonceStoreError::ConnectionFailed(e.to_string()))?,
+                )
+            }
+        };
+        let store = Self { connection, prefix };
+        info!(store = ?store, "Initialized Redis nonce store");
+        Ok(store)
+    }
+
+    /// Create a new Redis nonce store from environment variables.
+    pub async fn from_env() -> Result<Self, NonceStoreError> {
+        let urls = crate::env_registry::var("REDIS_URL")
+            .ok_or_else(|| NonceStoreError::NotConfigured("REDIS_URL not set".to_string()))?;
+        let prefix = crate::env_registry::var("NONCE_STORE_PREFIX").unwrap_or_default();
+        Self::connect(&urls, prefix).await
+    }
+
+    fn redis_key(&self, key: &str) -> String {
+        format!("{}{}", self.prefix, key)
+    }
+
+    async fn query<T: redis::FromRedisValue>(&self, cmd: &redis::Cmd) -> redis::RedisResult<T> {
+        // Both connection kinds are cheap handles over a shared multiplexed connection
+        match self.connection.clone() {
+            RedisConnection::Standalone(mut connection) => cmd.query_async(&mut connection).await,
+            RedisConnection::Cluster(mut connection) => cmd.query_async(&mut connection).await,
+        }
+    }
+}
+
+#[cfg(feature = "redis")]
+#[async_trait]
+impl NonceStore for RedisNonceStore {
+    async fn check_and_mark_used(&self, key: &str, ttl_seconds: u64) -> Result<(), NonceStoreError> {
+        // EX must be positive; an already-expired nonce still needs marking
+        let ttl_seconds = ttl_seconds.max(1);
+        let set: Option<String> = self
+            .query(
+                redis::cmd("SET")
+                    .arg(self.redis_key(key))
+                    .arg(1)
+                    .arg("NX")
+                    .arg("EX")
+                    .arg(ttl_seconds),
+            )
+            .await
+            .map_err(|e| {
+                error!(error = %e, key = %key, "Redis SET NX failed");
+                NonceStoreError::WriteError(e.to_string())
+            })?;
+
+        // Nil reply: the key exists and has not expired
+        if set.is_none() {
+            warn!(key = %key, "Replay attempt detected - nonce already used");
+            return Err(NonceStoreError::NonceAlreadyUsed(key.to_string()));
+        }
+        debug!(key = %key, ttl_seconds = %ttl_seconds, "Marked nonce as used (Redis)");
+        Ok(())
+    }
+
+    async fn is_used(&self, key: &str) -> Result<bool, NonceStoreError> {
+        self.query(redis::cmd("EXISTS").arg(self.redis_key(key)))
+            .await
+            .map_err(|e| NonceStoreError::ReadError(e.to_string()))
+    }
+
+    async fn claim_value(
+        &self,
+        key: &str,
+        value: &str,
+        ttl_seconds: u64,
+    ) -> Result<Option<String>, NonceStoreError> {
+        let set: Option<String> = self
+            .query(
+                redis::cmd("SET")
+                    .arg(self.redis_key(key))
+                    .arg(value)
+                    .arg("NX")
+                    .arg("EX")
+                    .arg(ttl_seconds.max(1)),
+            )
+            .await
+            .map_err(|e| {
+                error!(error = %e, key = %key, "Redis SET NX failed");
+                NonceStoreError::WriteError(e.to_string())
+            })?;
+        if set.is_some() {
+            return Ok(None);
+        }
+
+        // Taken; the key may expire before the read, which leaves no value
+        let existing: Option<String> = self
+            .query(redis::cmd("GET").arg(self.redis_key(key)))
+            .await
+            .map_err(|e| NonceStoreError::ReadError(e.to_string()))?;
+        Ok(Some(existing.unwrap_or_default()))
+    }
+
+    async fn set_value(
+        &self,
+        key: &str,
+        value: &str,
+        ttl_seconds: u64,
+    ) -> Result<(), NonceStoreError> {
+        let _: String = self
+            .query(
+                redis::cmd("SET")
+                    .arg(self.redis_key(key))
+                    .arg(value)
+                    .arg("EX")
+                    .arg(ttl_seconds.max(1)),
+            )
+            .await
+            .map_err(|e| {
+                error!(error = %e, key = %key, "Redis SET failed");
+                NonceStoreError::WriteError(e.to_string())
+            })?;
+        Ok(())
+    }
+
+    async fn health_check(&self) -> Result<(), NonceStoreError> {
+        let _: String = self
+            .query(&redis::cmd("PING"))
+            .await
+            .map_err(|e| NonceStoreError::ConnectionFailed(e.to_string()))?;
+        Ok(())
+    }
+
+    fn store_type(&self) -> &'static str {
+        "redis"
+    }
+}
+
+// ============================================================================
+// PostgreSQL Store
+// ============================================================================
+
+/// PostgreSQL-based persistent nonce store, for deployments already running Postgres.
+///
+/// Each check-and-mark runs in a transaction: `INSERT ... ON CONFLICT DO NOTHING
+/// RETURNING pk` claims a fresh nonce, and a conflicting row is only taken over once
+/// it has expired. Expired rows are otherwise kept until [`purge_expired`] removes
+/// them.
+///
+/// # Configuration
+///
+/// Environment variables:
+/// - `DATABASE_URL`: `postgres://` connection URL
+///
+/// [`purge_expired`]: PostgresNonceStore::purge_expired
+#[cfg(feature = "postgres")]
+#[derive(Debug, Clone)]
+pub struct PostgresNonceStore {
+    pool: sqlx::PgPool,
+}
+
+#[cfg(feature = "postgres")]
+static MIGRATOR: sqlx::migrate::Migrator = sqlx::migrate!("./migrations");
+
+#[cfg(feature = "postgres")]
+impl PostgresNonceStore {
+    /// Use an existing pool, applying pending migrations.
+    pub async fn new(pool: sqlx::PgPool) -> Result<Self, NonceStoreError> {
+        MIGRATOR
+            .run(&pool)
+            .await
+            .map_err(|e| NonceStoreError::ConnectionFailed(format!("migration failed: {}", e)))?;
+        info!("Initialized PostgreSQL nonce store");
+        Ok(Self { pool })
+    }
+
+    /// Connect to `url` and apply pending migrations.
+    pub async fn connect(url: &str) -> Result<Self, NonceStoreError> {
+        let pool = sqlx::PgPool::connect(url)
+            .await
+            .map_err(|e| NonceStoreError::ConnectionFailed(e.to_string()))?;
+        Self::new(pool).await
+    }
+
+    /// Create a new PostgreSQL nonce store from environment variables.
+    pub async fn from_env() -> Result<Self, NonceStoreError> {
+        let url = crate::env_registry::var("DATABASE_URL")
+            .ok_or_else(|| NonceStoreError::NotConfigured("DATABASE_URL not set".to_string()))?;
+        Self::connect(&url).await
+    }
+
+    /// Delete expired nonces, returning how many were removed.
+    pub async fn purge_expired(&self) -> Result<u64, NonceStoreError> {
+        let result = sqlx::query("DELETE FROM nonce_store WHERE expires_at <= $1")
+            .bind(Self::current_timestamp())
+            .execute(&self.pool)
+            .await
+            .map_err(|e| NonceStoreError::WriteError(e.to_string()))?;
+        Ok(result.rows_affected())
+    }
+
+    fn current_timestamp() -> i64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64
+    }
+
+    /// Claim `key` for `ttl_seconds`, with `value` for value entries.
+    async fn try_mark(
+        &self,
+        key: &str,
+        value: Option<&str>,
+        ttl_seconds: u64,
+    ) -> Result<bool, sqlx::Error> {
+        let now = Self::current_timestamp();
+        let expires_at = now.saturating_add(i64::try_from(ttl_seconds).unwrap_or(i64::MAX));
+        let chain = match value {
+            Some(_) => value_label(key).to_string(),
+            None => NonceKey::parse(key).chain_label().to_string(),
+        };
+
+        let mut tx = self.pool.begin().await?;
+        let mut claimed: Option<String> = sqlx::query_scalar(
+            "INSERT INTO nonce_store (pk, chain, created_at, expires_at, cached_value) \
+             VALUES ($1, $2, $3, $4, $5) ON CONFLICT (pk) DO NOTHING RETURNING pk",
+        )
+        .bind(key)
+        .bind(&chain)
+        .bind(now)
+        .bind(expires_at)
+        .bind(value)
+        .fetch_optional(&mut *tx)
+        .await?;
+        if claimed.is_none() {
+            // The row exists; it can be reused once expired, like a DynamoDB TTL deletion
+            claimed = sqlx::query_scalar(
+                "UPDATE nonce_store SET chain = $2, created_at = $3, expires_at = $4, cached_value = $5 \
+                 WHERE pk = $1 AND expires_at <= $3 RETURNING pk",
+            )
+            .bind(key)
+            .bind(&chain)
+            .bind(now)
+            .bind(expires_at)
+            .bind(value)
+            .fetch_optional(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(claimed.is_some())
+    }
+}
+
+#[cfg(feature = "postgres")]
+#[async_trait]
+impl NonceStore for PostgresNonceStore {
+    async fn check_and_mark_used(&self, key: &str, ttl_seconds: u64) -> Result<(), NonceStoreError> {
+        let claimed = self.try_mark(key, None, ttl_seconds).await.map_err(|e| {
+            error!(error = %e, key = %key, "PostgreSQL nonce insert failed");
+            NonceStoreError::WriteError(e.to_string())
+        })?;
+        if !claimed {
+            warn!(key = %key, "Replay attempt detected - nonce already used");
+            return Err(NonceStoreError::NonceAlreadyUsed(key.to_string()));
+        }
+        debug!(key = %key, ttl_seconds = %ttl_seconds, "Marked nonce as used (PostgreSQL)");
+        Ok(())
+    }
+
+    async fn is_used(&self, key: &str) -> Result<bool, NonceStoreError> {
+        sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM nonce_store WHERE pk = $1 AND expires_at > $2)")
+            .bind(key)
+            .bind(Self::current_timestamp())
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| NonceStoreError::ReadError(e.to_string()))
+    }
+
+    async fn claim_value(
+        &self,
+        key: &str,
+        value: &str,
+        ttl_seconds: u64,
+    ) -> Result<Option<String>, NonceStoreError> {
+        let claimed = self
+            .try_mark(key, Some(value), ttl_seconds)
+            .await
+            .map_err(|e| {
+                error!(error = %e, key = %key, "PostgreSQL value insert failed");
+                NonceStoreError::WriteError(e.to_string())
+            })?;
+        if claimed {
+            return Ok(None);
+        }
+        let existing: Option<Option<String>> =
+            sqlx::query_scalar("SELECT cached_value FROM nonce_store WHERE pk = $1")
+                .bind(key)
+                .fetch_optional(&self.pool)
+                .await
+                .map_err(|e| NonceStoreError::ReadError(e.to_string()))?;
+        Ok(Some(existing.flatten().unwrap_or_default()))
+    }
+
+    async fn set_value(
+        &self,
+        key: &str,
+        value: &str,
+        ttl_seconds: u64,
+    ) -> Result<(), NonceStoreError> {
+        let now = Self::current_timestamp();
+        let expires_at = now.saturating_add(i64::try_from(ttl_seconds).unwrap_or(i64::MAX));
+        sqlx::query(
+            "INSERT INTO nonce_store (pk, chain, created_at, expires_at, cached_value) \
+             VALUES ($1, $2, $3, $4, $5) ON CONFLICT (pk) DO UPDATE SET chain = $2, \
+             created_at = $3, expires_at = $4, cached_value = $5",
+        )
+        .bind(key)
+        .bind(value_label(key))
+        .bind(now)
+        .bind(expires_at)
+        .bind(value)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| {
+            error!(error = %e, key = %key, "PostgreSQL value upsert failed");
+            NonceStoreError::WriteError(e.to_string())
+        })?;
+        Ok(())
+    }
+
+    async fn health_check(&self) -> Result<(), NonceStoreError> {
+        sqlx::query("SELECT 1")
+            .execute(&self.pool)
+            .await
+            .map_err(|e| NonceStoreError::ConnectionFailed(e.to_string()))?;
+        Ok(())
+    }
+
+    fn store_type(&self) -> &'static str {
+        "postgres"
+    }
+}
+
+// ============================================================================
+// SQLite Store
+// ============================================================================
+
+/// SQLite-based persistent nonce store, for single-binary deployments without an
+/// external database.
+///
+/// The database runs in WAL mode, so reads are not blocked by the writer. Each
+/// check-and-mark runs in a `BEGIN IMMEDIATE` transaction, which takes the write lock
+/// up front: `INSERT OR IGNORE` claims a fresh nonce, and a conflicting row is only
+/// taken over once it has expired. [`spawn_purge_task`] deletes expired rows
+/// periodically so the database does not grow without bound.
+///
+/// # Configuration
+///
+/// Environment variables:
+/// - `SQLITE_PATH`: database file, created if missing
+///
+/// [`spawn_purge_task`]: SqliteNonceStore::spawn_purge_task
+#[cfg(feature = "sqlite")]
+#[derive(Debug, Clone)]
+pub struct SqliteNonceStore {
+    pool: sqlx::SqlitePool,
+}
+
+/// How often the store built by [`create_nonce_store`] purges expired SQLite nonces.
+#[cfg(feature = "sqlite")]
+pub const SQLITE_PURGE_INTERVAL: std::time::Duration = std::time::Duration::from_secs(600);
+
+#[cfg(feature = "sqlite")]
+impl SqliteNonceStore {
+    /// Use an existing pool, creating the table if needed.
+    pub async fn new(pool: sqlx::SqlitePool) -> Result<Self, NonceStoreError> {
+        let schema_error = |e: sqlx::Error| {
+            NonceStoreError::ConnectionFailed(format!("schema creation failed: {}", e))
+        };
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS nonces \
+             (pk TEXT PRIMARY KEY, chain TEXT, created_at INTEGER, expires_at INTEGER, cached_value TEXT)",
+        )
+        .execute(&pool)
+        .await
+        .map_err(schema_error)?;
+        // Databases created before value entries lack the column
+        let has_value: bool = sqlx::query_scalar(
+            "SELECT COUNT(*) > 0 FROM pragma_table_info('nonces') WHERE name = 'cached_value'",
+        )
+        .fetch_one(&pool)
+        .await
+        .map_err(schema_error)?;
+        if !has_value {
+            sqlx::query("ALTER TABLE nonces ADD COLUMN cached_value TEXT")
+                .execute(&pool)
+                .await
+                .map_err(schema_error)?;
+        }
+        info!("Initialized SQLite nonce store");
+        Ok(Self { pool })
+    }
+
+    /// Open the database at `path` in WAL mode, creating it if missing.
+    pub async fn open(path: impl AsRef<std::path::Path>) -> Result<Self, NonceStoreError> {
+        let options = sqlx::sqlite::SqliteConnectOptions::new()
+            .filename(path)
+            .create_if_missing(true)
+            .journal_mode(sqlx::sqlite::SqliteJournalMode::Wal)
+            .busy_timeout(std::time::Duration::from_secs(5));
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .connect_with(options)
+            .await
+            .map_err(|e| NonceStoreError::ConnectionFailed(e.to_string()))?;
+        Self::new(pool).await
+    }
+
+    /// Create a new SQLite nonce store from environment variables.
+    pub async fn from_env() -> Result<Self, NonceStoreError> {
+        let path = crate::env_registry::var("SQLITE_PATH")
+            .ok_or_else(|| NonceStoreError::NotConfigured("SQLITE_PATH not set".to_string()))?;
+        Self::open(path).await
+    }
+
+    /// Delete expired nonces, returning how many were removed.
+    pub async fn purge_expired(&self) -> Result<u64, NonceStoreError> {
+        let result = sqlx::query("DELETE FROM nonces WHERE expires_at < ?1")
+            .bind(Self::current_timestamp())
+            .execute(&self.pool)
+            .await
+            .map_err(|e| NonceStoreError::WriteError(e.to_string()))?;
+        Ok(result.rows_affected())
+    }
+
+    /// Run [`purge_expired`](Self::purge_expired) every `interval` until the task is aborted.
+    pub fn spawn_purge_task(&self, interval: std::time::Duration) -> tokio::task::JoinHandle<()> {
+        let store = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                ticker.tick().await;
+                match store.purge_expired().await {
+                    Ok(0) => {}
+                    Ok(purged) => debug!(purged, "Purged expired nonces (SQLite)"),
+                    Err(e) => warn!(error = %e, "Failed to purge expired SQLite nonces"),
+                }
+            }
+        })
+    }
+
+    fn current_timestamp() -> i64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64
+    }
+
+    /// Claim `key` for `ttl_seconds`, with `value` for value entries.
+    async fn try_mark(
+        &self,
+        key: &str,
+        value: Option<&str>,
+        ttl_seconds: u64,
+    ) -> Result<bool, sqlx::Error> {
+        let now = Self::current_timestamp();
+        let expires_at = now.saturating_add(i64::try_from(ttl_seconds).unwrap_or(i64::MAX));
+        let chain = match value {
+            Some(_) => value_label(key).to_string(),
+            None => NonceKey::parse(key).chain_label().to_string(),
+        };
+
+        // sqlx begins transactions as DEFERRED; IMMEDIATE takes the write lock before
+        // the read, so concurrent marks of the same key are serialized
+        let mut conn = self.pool.acquire().await?;
+        sqlx::query("BEGIN IMMEDIATE").execute(&mut *conn).await?;
+        let claimed = Self::claim(&mut conn, key, &chain, value, now, expires_at).await;
+        let end = if claimed.is_ok() { "COMMIT" } else { "ROLLBACK" };
+        if let Err(e) = sqlx::query(end).execute(&mut *conn).await {
+            // Don't hand a connection with an open transaction back to the pool
+            conn.detach();
+            return Err(claimed.err().unwrap_or(e));
+        }
+        claimed
+    }
+
+    async fn claim(
+        conn: &mut sqlx::SqliteConnection,
+        key: &str,
+        chain: &str,
+        value: Option<&str>,
+        now: i64,
+        expires_at: i64,
+    ) -> Result<bool, sqlx::Error> {
+        let inserted = sqlx::query(
+            "INSERT OR IGNORE INTO nonces (pk, chain, created_at, expires_at, cached_value) \
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+        )
+        .bind(key)
+        .bind(chain)
+        .bind(now)
+        .bind(expires_at)
+        .bind(value)
+        .execute(&mut *conn)
+        .await?
+        .rows_affected();
+        if inserted > 0 {
+            return Ok(true);
+        }
+
+        // The row exists; it can be reused once expired, like a DynamoDB TTL deletion
+        let live: bool = sqlx::query_scalar("SELECT expires_at > ?2 FROM nonces WHERE pk = ?1")
+            .bind(key)
+            .bind(now)
+            .fetch_one(&mut *conn)
+            .await?;
+        if live {
+            return Ok(false);
+        }
+        sqlx::query(
+            "UPDATE nonces SET chain = ?2, created_at = ?3, expires_at = ?4, cached_value = ?5 WHERE pk = ?1",
+        )
+        .bind(key)
+        .bind(chain)
+        .bind(now)
+        .bind(expires_at)
+        .bind(value)
+        .execute(&mut *conn)
+        .await?;
+        Ok(true)
+    }
+}
+
+#[cfg(feature = "sqlite")]
+#[async_trait]
+impl NonceStore for SqliteNonceStore {
+    async fn check_and_mark_used(&self, key: &str, ttl_seconds: u64) -> Result<(), NonceStoreError> {
+        let claimed = self.try_mark(key, None, ttl_seconds).await.map_err(|e| {
+            error!(error = %e, key = %key, "SQLite nonce insert failed");
+            NonceStoreError::WriteError(e.to_string())
+        })?;
+        if !claimed {
+            warn!(key = %key, "Replay attempt detected - nonce already used");
+            return Err(NonceStoreError::NonceAlreadyUsed(key.to_string()));
+        }
+        debug!(key = %key, ttl_seconds = %ttl_seconds, "Marked nonce as used (SQLite)");
+        Ok(())
+    }
+
+    async fn is_used(&self, key: &str) -> Result<bool, NonceStoreError> {
+        sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM nonces WHERE pk = ?1 AND expires_at > ?2)")
+            .bind(key)
+            .bind(Self::current_timestamp())
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| NonceStoreError::ReadError(e.to_string()))
+    }
+
+    async fn claim_value(
+        &self,
+        key: &str,
+        value: &str,
+        ttl_seconds: u64,
+    ) -> Result<Option<String>, NonceStoreError> {
+        let claimed = self
+            .try_mark(key, Some(value), ttl_seconds)
+            .await
+            .map_err(|e| {
+                error!(error = %e, key = %key, "SQLite value insert failed");
+                NonceStoreError::WriteError(e.to_string())
+            })?;
+        if claimed {
+            return Ok(None);
+        }
+        let existing: Option<Option<String>> =
+            sqlx::query_scalar("SELECT cached_value FROM nonces WHERE pk = ?1")
+                .bind(key)
+                .fetch_optional(&self.pool)
+                .await
+                .map_err(|e| NonceStoreError::ReadError(e.to_string()))?;
+        Ok(Some(existing.flatten().unwrap_or_default()))
+    }
+
+    async fn set_value(
+        &self,
+        key: &str,
+        value: &str,
+        ttl_seconds: u64,
+    ) -> Result<(), NonceStoreError> {
+        let now = Self::current_timestamp();
+        let expires_at = now.saturating_add(i64::try_from(ttl_seconds).unwrap_or(i64::MAX));
+        sqlx::query(
+            "INSERT OR REPLACE INTO nonces (pk, chain, created_at, expires_at, cached_value) \
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+        )
+        .bind(key)
+        .bind(value_label(key))
+        .bind(now)
+        .bind(expires_at)
+        .bind(value)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| {
+            error!(error = %e, key = %key, "SQLite value upsert failed");
+            NonceStoreError::WriteError(e.to_string())
+        })?;
+        Ok(())
+    }
+
+    async fn health_check(&self) -> Result<(), NonceStoreError> {
+        sqlx::query("SELECT 1")
+            .execute(&self.pool)
+            .await
+            .map_err(|e| NonceStoreError::ConnectionFailed(e.to_string()))?;
+        Ok(())
+    }
+
+    fn store_type(&self) -> &'static str {
+        "sqlite"
+    }
+}
+
 // ============================================================================
 // Factory Function
 // ============================================================================
 
 /// Create the appropriate nonce store based on configuration.
 ///
-/// - If `NONCE_STORE_TABLE_NAME` is set, uses DynamoDB
+/// - If `REDIS_URL` is set (and the `redis` feature is enabled), uses Redis
+/// - Else if `DATABASE_URL` is set (and the `postgres` feature is enabled), uses PostgreSQL
+/// - Else if `SQLITE_PATH` is set (and the `sqlite` feature is enabled), uses SQLite, purging
+///   expired nonces every [`SQLITE_PURGE_INTERVAL`]
+/// - Else if `NONCE_STORE_TABLE_NAME` is set, uses DynamoDB
 /// - Otherwise, falls back to in-memory store (with warning)
+///
+/// Each call opens a new connection (or, in memory, a separate store), so call it once
+/// and share the result.
 pub async fn create_nonce_store() -> Arc<dyn NonceStore> {
-    match std::env::var("NONCE_STORE_TABLE_NAME") {
-        Ok(table_name) if !table_name.is_empty() => {
+    if let Some(store) = create_redis_nonce_store().await {
+        return store;
+    }
+    if let Some(store) = create_postgres_nonce_store().await {
+        return store;
+    }
+    if let Some(store) = create_sqlite_nonce_store().await {
+        return store;
+    }
+    match crate::env_registry::var("NONCE_STORE_TABLE_NAME") {
+        Some(table_name) if !table_name.is_empty() => {
             match DynamoNonceStore::from_env().await {
                 Ok(store) => {
                     info!(
@@ -387,6 +1462,82 @@ pub async fn create_nonce_store() -> Arc<dyn NonceStore> {
     }
 }
 
+/// The Redis store when `REDIS_URL` is set and reachable.
+async fn create_redis_nonce_store() -> Option<Arc<dyn NonceStore>> {
+    crate::env_registry::var("REDIS_URL").filter(|url| !url.trim().is_empty())?;
+
+    #[cfg(feature = "redis")]
+    {
+        match RedisNonceStore::from_env().await {
+            Ok(store) => {
+                info!("Using Redis nonce store for replay protection");
+                Some(Arc::new(store))
+            }
+            Err(e) => {
+                error!(error = %e, "Failed to initialize Redis nonce store, falling back");
+                None
+            }
+        }
+    }
+
+    #[cfg(not(feature = "redis"))]
+    {
+        warn!("REDIS_URL is set but this build lacks the `redis` feature - ignoring it");
+        None
+    }
+}
+
+/// The PostgreSQL store when `DATABASE_URL` is set and reachable.
+async fn create_postgres_nonce_store() -> Option<Arc<dyn NonceStore>> {
+    crate::env_registry::var("DATABASE_URL").filter(|url| !url.trim().is_empty())?;
+
+    #[cfg(feature = "postgres")]
+    {
+        match PostgresNonceStore::from_env().await {
+            Ok(store) => {
+                info!("Using PostgreSQL nonce store for replay protection");
+                Some(Arc::new(store))
+            }
+            Err(e) => {
+                error!(error = %e, "Failed to initialize PostgreSQL nonce store, falling back");
+                None
+            }
+        }
+    }
+
+    #[cfg(not(feature = "postgres"))]
+    {
+        warn!("DATABASE_URL is set but this build lacks the `postgres` feature - ignoring it");
+        None
+    }
+}
+
+/// The SQLite store when `SQLITE_PATH` is set and can be opened.
+async fn create_sqlite_nonce_store() -> Option<Arc<dyn NonceStore>> {
+    crate::env_registry::var("SQLITE_PATH").filter(|path| !path.trim().is_empty())?;
+
+    #[cfg(feature = "sqlite")]
+    {
+        match SqliteNonceStore::from_env().await {
+            Ok(store) => {
+                info!("Using SQLite nonce store for replay protection");
+                store.spawn_purge_task(SQLITE_PURGE_INTERVAL);
+                Some(Arc::new(store))
+            }
+            Err(e) => {
+                error!(error = %e, "Failed to initialize SQLite nonce store, falling back");
+                None
+            }
+        }
+    }
+
+    #[cfg(not(feature = "sqlite"))]
+    {
+        warn!("SQLITE_PATH is set but this build lacks the `sqlite` feature - ignoring it");
+        None
+    }
+}
+
 // ============================================================================
 // Tests
 // ============================================================================
@@ -423,6 +1574,32 @@ mod tests {
         assert!(store.is_used(key).await.unwrap());
     }
 
+    #[tokio::test]
+    async fn test_memory_store_claim_and_set_value() {
+        let store = MemoryNonceStore::new();
+        let key = "idempotency:4f1c2a9e-0b7d-4c3e-9a51-6d2f8e7b1c30";
+
+        assert_eq!(store.claim_value(key, "pending", 300).await.unwrap(), None);
+        assert_eq!(
+            store
+                .claim_value(key, "other", 300)
+                .await
+                .unwrap()
+                .as_deref(),
+            Some("pending")
+        );
+
+        store.set_value(key, "done", 3600).await.unwrap();
+        assert_eq!(
+            store
+                .claim_value(key, "other", 300)
+                .await
+                .unwrap()
+                .as_deref(),
+            Some("done")
+        );
+    }
+
     #[test]
     fn test_stellar_nonce_key() {
         let key = stellar_nonce_key("stellar", "GABC123", 12345);
@@ -437,6 +1614,78 @@ mod tests {
         assert!(key.ends_with(&hex::encode([0xab; 32])));
     }
 
+    const STELLAR_ADDRESS: &str = "GBRPYHIL2CI3FNQ4BXLFMNDLFJUNPU2HY3ZMFSHONUCEOASW7QC7OX2H";
+
+    #[test]
+    fn test_binary_and_string_keys_round_trip() {
+        let keys = [
+            NonceKey::stellar("stellar", STELLAR_ADDRESS, 12345),
+            NonceKey::stellar("stellar-testnet", STELLAR_ADDRESS, u64::MAX),
+            NonceKey::algorand_group("algorand", &[0xab; 32]),
+            NonceKey::algorand_group("algorand-testnet", &[0x01; 32]),
+        ];
+        for key in &keys {
+            assert!(!matches!(key, NonceKey::Other(_)), "{key} should be compact");
+            let string = key.to_string();
+            assert_eq!(NonceKey::parse(&string), *key);
+            assert_eq!(string.len(), key.encoded_len());
+        }
+        assert_eq!(
+            keys[0].to_string(),
+            format!("stellar#{STELLAR_ADDRESS}#12345")
+        );
+        assert_eq!(
+            keys[2].to_string(),
+            format!("algorand#group#{}", hex::encode([0xab; 32]))
+        );
+
+        let mut buf = String::from("previous contents");
+        keys[2].write_to(&mut buf);
+        assert_eq!(buf, keys[2].to_string());
+    }
+
+    #[test]
+    fn test_non_canonical_keys_stay_distinct() {
+        let canonical = NonceKey::stellar("stellar", STELLAR_ADDRESS, 7);
+        for key in [
+            format!("stellar#{STELLAR_ADDRESS}#007"),
+            format!("stellar#{STELLAR_ADDRESS}#+7"),
+            format!("algorand#group#{}", hex::encode_upper([0xab; 32])),
+            "stellar-unknown#GABC#1".to_string(),
+            "stellar#GABC123#12345".to_string(),
+        ] {
+            let parsed = NonceKey::parse(&key);
+            assert_eq!(parsed, NonceKey::Other(key.clone().into()));
+            assert_eq!(parsed.to_string(), key);
+            assert_ne!(parsed, canonical);
+        }
+        assert_eq!(NonceKey::stellar("stellar-unknown", "GABC", 1).to_string(), "stellar-unknown#GABC#1");
+    }
+
+    #[tokio::test]
+    async fn test_memory_store_binary_and_string_forms_share_entries() {
+        let store = MemoryNonceStore::new();
+        let stellar = NonceKey::stellar("stellar", STELLAR_ADDRESS, 1);
+        let group = NonceKey::algorand_group("algorand", &[0x42; 32]);
+
+        // Marked via string, replay detected via binary key
+        store.check_and_mark_used(&stellar.to_string(), 3600).await.unwrap();
+        assert!(store.is_key_used(&stellar).await.unwrap());
+        assert!(matches!(
+            store.check_and_mark_key(&stellar, 3600).await,
+            Err(NonceStoreError::NonceAlreadyUsed(k)) if k == stellar.to_string()
+        ));
+
+        // Marked via binary key, replay detected via string
+        store.check_and_mark_key(&group, 3600).await.unwrap();
+        assert!(store.is_used(&algorand_nonce_key("algorand", &[0x42; 32])).await.unwrap());
+        assert!(store.check_and_mark_used(&group.to_string(), 3600).await.is_err());
+
+        // Other chains and networks are unaffected
+        assert!(!store.is_key_used(&NonceKey::algorand_group("algorand-testnet", &[0x42; 32])).await.unwrap());
+        assert!(!store.is_key_used(&NonceKey::stellar("stellar", STELLAR_ADDRESS, 2)).await.unwrap());
+    }
+
     #[test]
     fn test_stellar_ttl_seconds() {
         // 100 ledgers until expiry = 500 seconds + 3600 buffer = 4100
@@ -450,4 +1699,28 @@ mod tests {
         let ttl = algorand_ttl_seconds(1000, 1100);
         assert_eq!(ttl, 4000);
     }
+
+    #[cfg(feature = "redis")]
+    #[test]
+    fn test_redis_target_selects_cluster_for_multiple_nodes() {
+        assert_eq!(
+            RedisTarget::parse("redis://cache:6379/0"),
+            Some(RedisTarget::Standalone("redis://cache:6379/0".to_string()))
+        );
+        assert_eq!(
+            RedisTarget::parse("redis://node-a:6379, redis://node-b:6379,"),
+            Some(RedisTarget::Cluster(vec![
+                "redis://node-a:6379".to_string(),
+                "redis://node-b:6379".to_string(),
+            ]))
+        );
+        assert_eq!(RedisTarget::parse(" , "), None);
+    }
+
+    #[cfg(feature = "redis")]
+    #[tokio::test]
+    async fn test_redis_store_rejects_empty_url() {
+        let result = RedisNonceStore::connect("", "x402:nonce:".to_string()).await;
+        assert!(matches!(result, Err(NonceStoreError::NotConfigured(_))));
+    }
 }
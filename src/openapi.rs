@@ -36,6 +36,7 @@ Ethereum Sepolia, Base Sepolia, Polygon Amoy, Optimism Sepolia, Avalanche Fuji,
 - **Stellar/Soroban**: Mainnet and Testnet
 - **Algorand**: Mainnet and Testnet
 - **Sui**: Mainnet and Testnet
+- **Hedera**: Mainnet and Testnet
 
 ## Core Endpoints
 
@@ -48,10 +49,15 @@ Ethereum Sepolia, Base Sepolia, Polygon Amoy, Optimism Sepolia, Avalanche Fuji,
 The facilitator supports [ERC-8004](https://eips.ethereum.org/EIPS/eip-8004) for AI agent reputation:
 
 - `POST /feedback` - Submit on-chain reputation feedback
+- `GET /feedback-files/{id}` - Get a feedback file stored by the facilitator
 - `POST /feedback/revoke` - Revoke previously submitted feedback
 - `POST /feedback/response` - Append agent response to feedback
 - `GET /reputation/:network/:agentId` - Query agent reputation summary
 - `GET /identity/:network/:agentId` - Get agent identity from registry
+- `DELETE /cache/identity/{agentId}` - Drop the cached identity reads of an agent
+- `POST /validation/request` - Ask a validator to validate an agent
+- `POST /validation/response` - Publish the configured validator's response
+- `GET /validation/:requestHash` - Get the status of a validation request
 
 Supported ERC-8004 networks: `ethereum`, `ethereum-sepolia`
 
@@ -63,6 +69,8 @@ Decentralized resource discovery for x402-enabled services:
 - `POST /bazaar` - Register a new resource
 - `GET /bazaar/:id` - Get specific resource by ID
 - `DELETE /bazaar/:id` - Unregister a resource
+- `POST /discovery/resources` - Register a resource signed by its payTo address
+- `DELETE /discovery/resources` - Remove a signed resource, signed by its owner
 
 ## Protocol Documentation
 
@@ -97,23 +105,35 @@ Decentralized resource discovery for x402-enabled services:
         path_verify_post,
         path_settle_get,
         path_settle_post,
+        path_settle_finality,
         // Discovery endpoints
         path_supported,
         path_version,
         // ERC-8004 endpoints
         path_feedback_get,
         path_feedback_post,
+        path_feedback_file,
         path_feedback_revoke,
         path_feedback_response,
         path_reputation,
         path_identity,
+        path_cache_identity_delete,
+        path_identity_register,
+        path_identity_wallet,
+        path_validation_request,
+        path_validation_response,
+        path_validation_status,
+        path_proof_signer,
         // Bazaar endpoints
         path_bazaar_list,
         path_bazaar_register,
         path_bazaar_get,
         path_bazaar_delete,
+        path_discovery_resource_post,
+        path_discovery_resource_delete,
         // Health
         path_health,
+        path_health_canary,
     )
 )]
 pub struct ApiDoc;
@@ -227,10 +247,17 @@ Submits a verified payment authorization to the blockchain for settlement.
   "success": true,
   "transaction": "0x...",
   "network": "base-mainnet",
-  "payer": "0x..."
+  "payer": "0x...",
+  "confirmation": {
+    "blockNumber": 27439044,
+    "blockTimestamp": 1755427523,
+    "gasUsed": 61873
+  }
 }
 ```
 
+`confirmation` reports the block (the round on Algorand) that confirmed the transaction, and is present on EVM and Algorand networks; `gasUsed` is EVM only.
+
 **Response on failure:**
 ```json
 {
@@ -326,6 +353,10 @@ async fn path_feedback_get() {}
     description = r#"
 Submits on-chain reputation feedback for an AI agent via the ERC-8004 Reputation Registry.
 
+The `feedbackUri` file is fetched (https, http, ipfs, ar; up to 256 KiB) and its raw bytes hashed with keccak256. A given `feedbackHash` must match it (422 otherwise); an omitted one is filled in.
+
+Instead of hosting a file, clients can send it as `feedbackFile` next to `feedback`. The facilitator pins it to IPFS or serves it at `GET /feedback-files/{id}`, and submits its URI and keccak256 as `feedbackUri` and `feedbackHash`.
+
 **Supported networks:** ethereum, ethereum-sepolia
 
 **Request body:**
@@ -370,11 +401,29 @@ Submits on-chain reputation feedback for an AI agent via the ERC-8004 Reputation
     request_body(content = Object, description = "ERC-8004 feedback request"),
     responses(
         (status = 200, description = "Feedback submission result", body = Object),
-        (status = 400, description = "Feedback submission failed", body = Object)
+        (status = 400, description = "Feedback submission failed", body = Object),
+        (status = 422, description = "feedbackHash does not match the feedbackUri content, or the file could not be used", body = Object),
+        (status = 502, description = "feedbackUri could not be fetched, or feedbackFile could not be stored", body = Object)
     )
 )]
 async fn path_feedback_post() {}
 
+#[utoipa::path(
+    get,
+    path = "/feedback-files/{id}",
+    tag = "ERC-8004",
+    summary = "Get a stored feedback file",
+    description = "Serves a feedback file that `POST /feedback` stored on disk, as submitted on-chain.",
+    params(
+        ("id" = String, Path, description = "Hex keccak256 of the file, without 0x")
+    ),
+    responses(
+        (status = 200, description = "Feedback file", body = Object),
+        (status = 404, description = "Unknown feedback file", body = Object)
+    )
+)]
+async fn path_feedback_file() {}
+
 #[utoipa::path(
     post,
     path = "/feedback/revoke",
@@ -480,6 +529,8 @@ async fn path_reputation() {}
     description = r#"
 Retrieves agent identity information from the ERC-8004 Identity Registry.
 
+Registry reads are cached for `ERC8004_IDENTITY_CACHE_TTL` seconds (default: 600).
+
 **Supported networks:** ethereum, ethereum-sepolia
 
 **Response:**
@@ -505,6 +556,291 @@ Retrieves agent identity information from the ERC-8004 Identity Registry.
 )]
 async fn path_identity() {}
 
+#[utoipa::path(
+    delete,
+    path = "/cache/identity/{agent_id}",
+    tag = "ERC-8004",
+    summary = "Drop cached identity reads",
+    description = r#"
+Drops the cached Identity Registry reads of an agent, so the next `GET /identity` reads
+the chain, e.g. after its registration file changed.
+
+Only mounted when `ERC8004_CACHE_ADMIN_KEY` is set, and requires that key in the
+`X-API-Key` header.
+"#,
+    params(
+        ("agent_id" = u64, Path, description = "Agent ID (ERC-721 tokenId)"),
+        ("network" = Option<String>, Query, description = "Only drop reads on this network")
+    ),
+    responses(
+        (status = 200, description = "Number of reads dropped", body = Object),
+        (status = 400, description = "Invalid network", body = Object),
+        (status = 401, description = "Missing or invalid API key", body = Object)
+    )
+)]
+async fn path_cache_identity_delete() {}
+
+#[utoipa::path(
+    post,
+    path = "/identity/register",
+    tag = "ERC-8004",
+    summary = "Register an agent",
+    description = r#"
+Mints an ERC-8004 agent identity with the facilitator signer, which pays the gas.
+
+Calls `register(agentURI, metadata)` on the network's Identity Registry and returns the
+`agentId` from the `Registered` event. When `owner` is set, a second transaction
+transfers the agent NFT to it. Metadata values are stored as their UTF-8 bytes.
+
+Only available when `IDENTITY_REGISTER_API_KEYS` is set. Requires one of those keys in
+the `X-API-Key` header; each key may register `IDENTITY_REGISTER_DAILY_QUOTA` agents
+per UTC day (default: 10).
+
+**Request:**
+```json
+{
+  "network": "base-sepolia",
+  "agentUri": "ipfs://bafy.../agent.json",
+  "metadata": [{ "key": "agentName", "value": "Weather Bot" }],
+  "owner": "0x..."
+}
+```
+
+**Response:**
+```json
+{
+  "success": true,
+  "agentId": 42,
+  "owner": "0x...",
+  "transaction": "0x...",
+  "transferTransaction": "0x...",
+  "network": "base-sepolia"
+}
+```
+"#,
+    request_body(content = Object, description = "Agent registration request"),
+    responses(
+        (status = 200, description = "Agent registered", body = Object),
+        (status = 400, description = "Invalid request or unsupported network", body = Object),
+        (status = 401, description = "Missing or invalid API key", body = Object),
+        (status = 429, description = "Daily registration quota exhausted", body = Object),
+        (status = 500, description = "Registration or transfer failed", body = Object)
+    )
+)]
+async fn path_identity_register() {}
+
+#[utoipa::path(
+    post,
+    path = "/identity/{agent_id}/wallet",
+    tag = "ERC-8004",
+    summary = "Set an agent's payment wallet",
+    description = r#"
+Calls `setAgentWallet` on the network's Identity Registry with the facilitator signer,
+which pays the gas. The facilitator must own the agent or be approved for it, e.g. an
+agent registered through `POST /identity/register` without an `owner`.
+
+`signature` is the new wallet's EIP-712 signature of
+`AgentWalletSet(uint256 agentId,address newWallet,address owner,uint256 deadline)` in
+the domain `{ name: "ERC8004IdentityRegistry", version: "1", chainId, verifyingContract: <Identity Registry> }`,
+where `owner` is the current owner of the agent. The update is simulated first; a
+rejected update spends no gas and does not count against the quota.
+
+Uses the API keys and daily quota of `POST /identity/register`.
+
+**Request:**
+```json
+{
+  "network": "base-sepolia",
+  "newWallet": "0x...",
+  "deadline": 1767225600,
+  "signature": "0x..."
+}
+```
+
+**Response:**
+```json
+{
+  "success": true,
+  "agentId": 42,
+  "wallet": "0x...",
+  "transaction": "0x...",
+  "network": "base-sepolia"
+}
+```
+"#,
+    params(
+        ("agent_id" = u64, Path, description = "Agent ID (ERC-721 tokenId)")
+    ),
+    request_body(content = Object, description = "Signed wallet update"),
+    responses(
+        (status = 200, description = "Wallet set", body = Object),
+        (status = 400, description = "Invalid request, unsupported network or update rejected by the registry", body = Object),
+        (status = 401, description = "Missing or invalid API key", body = Object),
+        (status = 429, description = "Daily registration quota exhausted", body = Object),
+        (status = 500, description = "Transaction failed", body = Object)
+    )
+)]
+async fn path_identity_wallet() {}
+
+#[utoipa::path(
+    post,
+    path = "/validation/request",
+    tag = "ERC-8004",
+    summary = "Request a validation",
+    description = r#"
+Calls `validationRequest(validatorAddress, agentId, requestURI, requestHash)` on the
+network's Validation Registry with the facilitator signer, which pays the gas. The
+facilitator must own the agent or be approved for it.
+
+Without a `requestHash`, the request is identified by
+`keccak256(abi.encode(validatorAddress, agentId, requestURI))`. The request is simulated
+first; a rejected request spends no gas and does not count against the quota.
+
+Uses the API keys and daily quota of `POST /identity/register`. Networks without a
+Validation Registry answer 501 with the networks that have one.
+
+**Request:**
+```json
+{
+  "x402Version": 1,
+  "network": "ethereum-sepolia",
+  "validatorAddress": "0x...",
+  "agentId": 42,
+  "requestUri": "ipfs://bafy.../request.json"
+}
+```
+
+**Response:**
+```json
+{
+  "success": true,
+  "requestHash": "0x...",
+  "transaction": "0x...",
+  "network": "ethereum-sepolia"
+}
+```
+"#,
+    request_body(content = Object, description = "Validation request"),
+    responses(
+        (status = 200, description = "Validation requested", body = Object),
+        (status = 400, description = "Invalid request, unsupported network or request rejected by the registry", body = Object),
+        (status = 401, description = "Missing or invalid API key", body = Object),
+        (status = 429, description = "Daily registration quota exhausted", body = Object),
+        (status = 500, description = "Transaction failed", body = Object),
+        (status = 501, description = "No Validation Registry on this network", body = Object)
+    )
+)]
+async fn path_validation_request() {}
+
+#[utoipa::path(
+    post,
+    path = "/validation/response",
+    tag = "ERC-8004",
+    summary = "Respond to a validation request",
+    description = r#"
+Calls `validationResponse(requestHash, response, responseURI, responseHash, tag)` on the
+network's Validation Registry with the validator key `ERC8004_VALIDATOR_PRIVATE_KEY`,
+which pays the gas. `response` ranges from 0 (failed) to 100 (passed).
+
+Only available when `ERC8004_VALIDATOR_PRIVATE_KEY` and `ERC8004_VALIDATOR_API_KEY` are
+set, and requires the API key in the `X-API-Key` header. Only requests addressed to the
+validator key can be answered.
+
+**Request:**
+```json
+{
+  "x402Version": 1,
+  "network": "ethereum-sepolia",
+  "requestHash": "0x...",
+  "response": 100,
+  "responseUri": "ipfs://bafy.../response.json",
+  "tag": "hard-finality"
+}
+```
+
+**Response:**
+```json
+{
+  "success": true,
+  "requestHash": "0x...",
+  "transaction": "0x...",
+  "network": "ethereum-sepolia"
+}
+```
+"#,
+    request_body(content = Object, description = "Validation response"),
+    responses(
+        (status = 200, description = "Response published", body = Object),
+        (status = 400, description = "Invalid request, unsupported network or response rejected by the registry", body = Object),
+        (status = 401, description = "Missing or invalid API key", body = Object),
+        (status = 403, description = "Request addressed to another validator", body = Object),
+        (status = 404, description = "Unknown validation request", body = Object),
+        (status = 500, description = "Transaction failed", body = Object),
+        (status = 501, description = "No Validation Registry on this network", body = Object)
+    )
+)]
+async fn path_validation_response() {}
+
+#[utoipa::path(
+    get,
+    path = "/validation/{request_hash}",
+    tag = "ERC-8004",
+    summary = "Get validation status",
+    description = r#"
+Reads `getValidationStatus(requestHash)` from the network's Validation Registry.
+
+**Response:**
+```json
+{
+  "validatorAddress": "0x...",
+  "agentId": 42,
+  "response": 100,
+  "responseHash": "0x...",
+  "tag": "hard-finality",
+  "lastUpdate": 1767225600
+}
+```
+"#,
+    params(
+        ("request_hash" = String, Path, description = "Request hash (0x-prefixed, 32 bytes)"),
+        ("network" = String, Query, description = "Network of the Validation Registry, e.g. ethereum-sepolia")
+    ),
+    responses(
+        (status = 200, description = "Validation status", body = Object),
+        (status = 400, description = "Invalid or unsupported network", body = Object),
+        (status = 404, description = "Unknown validation request", body = Object),
+        (status = 501, description = "No Validation Registry on this network", body = Object)
+    )
+)]
+async fn path_validation_status() {}
+
+#[utoipa::path(
+    get,
+    path = "/proof-signer",
+    tag = "ERC-8004",
+    summary = "Get proof of payment signers",
+    description = r#"
+Lists the address that signs ERC-8004 proofs of payment on each network.
+
+Settlement proofs carry a `facilitatorSignature`: an EIP-191 signature of their
+`paymentHash`. Recovering the signer and comparing it with the address listed for the
+proof's network verifies the proof without chain access.
+
+**Response:**
+```json
+{
+  "signers": [
+    { "network": "base-sepolia", "address": "0x..." }
+  ]
+}
+```
+"#,
+    responses(
+        (status = 200, description = "Proof signer per network", body = Object)
+    )
+)]
+async fn path_proof_signer() {}
+
 // ============================================================================
 // Bazaar Endpoints
 // ============================================================================
@@ -626,6 +962,108 @@ async fn path_bazaar_get() {}
 )]
 async fn path_bazaar_delete() {}
 
+#[utoipa::path(
+    post,
+    path = "/discovery/resources",
+    tag = "Bazaar",
+    summary = "Register a signed resource",
+    description = r#"
+Registers a resource on behalf of the address it pays to. Every payment method in
+`accepts` must pay to the same EVM address, which signs (EIP-191) this message:
+
+```text
+x402 discovery registration
+url: <url>
+payTo: <checksummed payTo address>
+bodyHash: <0x-prefixed body hash>
+timestamp: <timestamp>
+```
+
+`bodyHash` is the Keccak-256 of the request body without `timestamp` and `signature`,
+serialized with sorted object keys and no whitespace. The timestamp must be within
+`DISCOVERY_SIGNATURE_MAX_AGE` seconds (default: 300) of the facilitator's clock, and
+each signature is accepted once. The signer becomes the resource's owner and may
+register the URL again, with a new signature, to replace it.
+
+**Request body:** the body of `POST /discovery/register`, plus:
+```json
+{
+  "timestamp": 1767225600,
+  "signature": "0x..."
+}
+```
+"#,
+    request_body(content = Object, description = "Resource registration with its owner's signature"),
+    responses(
+        (status = 200, description = "Resource of the same owner replaced", body = Object),
+        (status = 201, description = "Resource registered", body = Object),
+        (status = 400, description = "Invalid resource, payTo or signature encoding", body = Object),
+        (status = 401, description = "Stale, reused or not made by payTo signature", body = Object),
+        (status = 409, description = "URL owned by another registrant", body = Object)
+    )
+)]
+async fn path_discovery_resource_post() {}
+
+#[utoipa::path(
+    delete,
+    path = "/discovery/resources",
+    tag = "Bazaar",
+    summary = "Remove a signed resource",
+    description = r#"
+Removes a resource registered through `POST /discovery/resources`. The owner signs the
+same message with `x402 discovery deletion` as its first line, hashing this body.
+
+**Request body:**
+```json
+{
+  "url": "https://example.com/api",
+  "timestamp": 1767225600,
+  "signature": "0x..."
+}
+```
+"#,
+    request_body(content = Object, description = "Resource URL with its owner's signature"),
+    responses(
+        (status = 200, description = "Resource removed", body = Object),
+        (status = 401, description = "Stale, reused or not made by the owner signature", body = Object),
+        (status = 404, description = "Resource not found", body = Object),
+        (status = 409, description = "Resource was not registered with a signature", body = Object)
+    )
+)]
+async fn path_discovery_resource_delete() {}
+
+#[utoipa::path(
+    get,
+    path = "/settle/{transaction}/finality",
+    tag = "Core",
+    summary = "Settlement finality",
+    description = r#"
+Finality receipt of a settlement: the finality policy applied to it, when it was
+confirmed and, once the policy's confirmation depth and timelock are met, when it was
+finalized. Settlements no policy matches finalize on first confirmation.
+
+Only mounted when `FINALITY_POLICIES_FILE` is set.
+"#,
+    params(
+        ("transaction" = String, Path, description = "Settlement transaction hash")
+    ),
+    responses(
+        (status = 200, description = "Finality receipt", body = Object,
+            example = json!({
+                "transaction": "0x...",
+                "network": "ethereum",
+                "state": "confirmed",
+                "policyId": "eth-large",
+                "requiredConfirmations": 12,
+                "confirmationBlock": 21000000,
+                "confirmedAt": 1767225600
+            })
+        ),
+        (status = 404, description = "Unknown or expired settlement", body = Object)
+    )
+)]
+async fn path_settle_finality() {}
+
 // ============================================================================
 // Health Endpoints
 // ============================================================================
@@ -646,6 +1084,24 @@ async fn path_bazaar_delete() {}
 )]
 async fn path_health() {}
 
+#[utoipa::path(
+    get,
+    path = "/health/canary",
+    tag = "Health",
+    summary = "Canary health",
+    description = r#"
+Outcome of the latest canary self-payment on each network, and whether its canaries are
+alerting after consecutive failures.
+
+Only mounted when `CANARY_ENABLED` is set.
+"#,
+    responses(
+        (status = 200, description = "No network is alerting", body = Object),
+        (status = 503, description = "At least one network is alerting", body = Object)
+    )
+)]
+async fn path_health_canary() {}
+
 /// Create the Swagger UI router
 pub fn swagger_routes() -> Router {
     Router::new()
@@ -0,0 +1,438 @@
+//! Payment URIs for mobile wallets and QR codes.
+//!
+//! [`generate_payment_uri`] encodes the payment asked by a [`PaymentRequirementsV2`] as a
+//! URI that wallet apps open directly, and [`parse_payment_uri`] reads one back:
+//!
+//! - EVM chains use [EIP-681] token transfers, understood by MetaMask Mobile and Coinbase
+//!   Wallet: `ethereum:<asset>@<chain id>/transfer?address=<pay to>&uint256=<amount>`.
+//!   For known tokens the amount is written in the token's units with its decimals as
+//!   exponent (`0.01e6` for 1 cent of USDC), otherwise in base units.
+//! - Algorand uses [ARC-26] asset transfers (with the `algorand` feature):
+//!   `algorand://<pay to>?amount=<base units>&asset=<ASA id>`. ARC-26 URIs do not name
+//!   the network; parsing recognises the known testnet assets and assumes mainnet otherwise.
+//!
+//! Neither format carries the scheme or timeout, so parsed requirements use
+//! [`Scheme::Exact`] and [`DEFAULT_MAX_TIMEOUT_SECONDS`].
+//!
+//! [EIP-681]: https://eips.ethereum.org/EIPS/eip-681
+//! [ARC-26]: https://arc.algorand.foundation/ARCs/arc-0026
+
+use alloy::primitives::U256;
+use std::str::FromStr;
+
+use crate::caip2::{Caip2NetworkId, Namespace};
+use crate::network::{find_token_deployment, Network};
+use crate::types::{EvmAddress, MixedAddress, Scheme, TokenAmount};
+use crate::types_v2::PaymentRequirementsV2;
+
+/// Timeout of requirements parsed from a payment URI.
+pub const DEFAULT_MAX_TIMEOUT_SECONDS: u64 = 300;
+
+/// Chain of EIP-681 URIs without `@<chain id>`.
+const ETHEREUM_MAINNET_CHAIN_ID: u64 = 1;
+
+/// Payment URI errors.
+#[derive(Debug, thiserror::Error)]
+pub enum PaymentUriError {
+    /// No payment URI format for the network
+    #[error("No payment URI format for network {0}")]
+    UnsupportedNetwork(String),
+
+    /// Not an `ethereum:` or `algorand:` URI
+    #[error("Unsupported payment URI: {0}")]
+    UnsupportedUri(String),
+
+    /// EIP-681 URI calling another function than `transfer`
+    #[error("Unsupported payment URI function: {0}")]
+    UnsupportedFunction(String),
+
+    /// Address of the wrong chain or malformed
+    #[error("Invalid address in payment URI: {0}")]
+    InvalidAddress(String),
+
+    /// Amount that is not a whole number of base units
+    #[error("Invalid amount in payment URI: {0}")]
+    InvalidAmount(String),
+
+    /// Chain id that is not a number
+    #[error("Invalid chain id in payment URI: {0}")]
+    InvalidChainId(String),
+
+    /// A required query parameter is absent
+    #[error("Payment URI is missing `{0}`")]
+    MissingParameter(&'static str),
+}
+
+/// Encode the payment asked by `requirements` as a wallet URI.
+///
+/// Fails with [`PaymentUriError::UnsupportedNetwork`] on networks without a payment URI
+/// format, and [`PaymentUriError::InvalidAddress`] when `asset` or `pay_to` is not an
+/// address of the network.
+pub fn generate_payment_uri(
+    requirements: &PaymentRequirementsV2,
+) -> Result<String, PaymentUriError> {
+    match requirements.network.namespace() {
+        Namespace::Eip155 => generate_eip681(requirements),
+        #[cfg(feature = "algorand")]
+        Namespace::Algorand => generate_arc26(requirements),
+        _ => Err(PaymentUriError::UnsupportedNetwork(
+            requirements.network.to_string(),
+        )),
+    }
+}
+
+/// Read the payment of an EIP-681 or ARC-26 URI back into requirements.
+pub fn parse_payment_uri(uri: &str) -> Result<PaymentRequirementsV2, PaymentUriError> {
+    let uri = uri.trim();
+    if let Some(rest) = uri.strip_prefix("ethereum:") {
+        return parse_eip681(rest);
+    }
+    #[cfg(feature = "algorand")]
+    if let Some(rest) = uri.strip_prefix("algorand:") {
+        return parse_arc26(rest.trim_start_matches("//"));
+    }
+    Err(PaymentUriError::UnsupportedUri(uri.to_string()))
+}
+
+fn generate_eip681(requirements: &PaymentRequirementsV2) -> Result<String, PaymentUriError> {
+    let chain_id = requirements
+        .network
+        .chain_id()
+        .ok_or_else(|| PaymentUriError::InvalidChainId(requirements.network.to_string()))?;
+    let (MixedAddress::Evm(asset), MixedAddress::Evm(pay_to)) =
+        (&requirements.asset, &requirements.pay_to)
+    else {
+        return Err(PaymentUriError::InvalidAddress(format!(
+            "{} / {}",
+            requirements.asset, requirements.pay_to
+        )));
+    };
+
+    let amount = match token_decimals(&requirements.network, &requirements.asset) {
+        Some(decimals) if decimals > 0 => format_scaled(requirements.amount, decimals),
+        _ => requirements.amount.to_string(),
+    };
+    Ok(format!(
+        "ethereum:{asset}@{chain_id}/transfer?address={pay_to}&uint256={amount}"
+    ))
+}
+
+fn parse_eip681(rest: &str) -> Result<PaymentRequirementsV2, PaymentUriError> {
+    let (path, query) = rest.split_once('?').unwrap_or((rest, ""));
+    let (target, function) = path.split_once('/').unwrap_or((path, ""));
+    if function != "transfer" {
+        return Err(PaymentUriError::UnsupportedFunction(function.to_string()));
+    }
+    let target = target.strip_prefix("pay-").unwrap_or(target);
+    let (asset, chain_id) = match target.split_once('@') {
+        Some((asset, chain_id)) => (
+            asset,
+            chain_id
+                .parse::<u64>()
+                .map_err(|_| PaymentUriError::InvalidChainId(chain_id.to_string()))?,
+        ),
+        None => (target, ETHEREUM_MAINNET_CHAIN_ID),
+    };
+
+    let mut pay_to = None;
+    let mut amount = None;
+    for (key, value) in url::form_urlencoded::parse(query.as_bytes()) {
+        match key.as_ref() {
+            "address" => pay_to = Some(evm_address(&value)?),
+            "uint256" => amount = Some(parse_scaled(&value)?),
+            _ => {}
+        }
+    }
+
+    Ok(PaymentRequirementsV2 {
+        scheme: Scheme::Exact,
+        network: Caip2NetworkId::eip155(chain_id),
+        asset: evm_address(asset)?,
+        amount: amount.ok_or(PaymentUriError::MissingParameter("uint256"))?,
+        pay_to: pay_to.ok_or(PaymentUriError::MissingParameter("address"))?,
+        max_timeout_seconds: DEFAULT_MAX_TIMEOUT_SECONDS,
+        extra: None,
+    })
+}
+
+#[cfg(feature = "algorand")]
+fn generate_arc26(requirements: &PaymentRequirementsV2) -> Result<String, PaymentUriError> {
+    let (MixedAddress::Algorand(asset), MixedAddress::Algorand(pay_to)) =
+        (&requirements.asset, &requirements.pay_to)
+    else {
+        return Err(PaymentUriError::InvalidAddress(format!(
+            "{} / {}",
+            requirements.asset, requirements.pay_to
+        )));
+    };
+    // ARC-26 amounts are always in base units
+    Ok(format!(
+        "algorand://{pay_to}?amount={}&asset={asset}",
+        requirements.amount
+    ))
+}
+
+#[cfg(feature = "algorand")]
+fn parse_arc26(rest: &str) -> Result<PaymentRequirementsV2, PaymentUriError> {
+    let (pay_to, query) = rest.split_once('?').unwrap_or((rest, ""));
+    if !is_algorand_address(pay_to) {
+        return Err(PaymentUriError::InvalidAddress(pay_to.to_string()));
+    }
+
+    let mut asset = None;
+    let mut amount = None;
+    for (key, value) in url::form_urlencoded::parse(query.as_bytes()) {
+        match key.as_ref() {
+            "asset" if !value.is_empty() && value.bytes().all(|b| b.is_ascii_digit()) => {
+                asset = Some(MixedAddress::Algorand(value.into_owned()));
+            }
+            "asset" => return Err(PaymentUriError::InvalidAddress(value.into_owned())),
+            "amount" => {
+                let value = U256::from_str_radix(&value, 10)
+                    .map_err(|_| PaymentUriError::InvalidAmount(value.into_owned()))?;
+                amount = Some(TokenAmount(value));
+            }
+            _ => {}
+        }
+    }
+    let asset = asset.ok_or(PaymentUriError::MissingParameter("asset"))?;
+    let network = if find_token_deployment(Network::AlgorandTestnet, &asset).is_some() {
+        Caip2NetworkId::algorand_testnet()
+    } else {
+        Caip2NetworkId::algorand_mainnet()
+    };
+
+    Ok(PaymentRequirementsV2 {
+        scheme: Scheme::Exact,
+        network,
+        asset,
+        amount: amount.ok_or(PaymentUriError::MissingParameter("amount"))?,
+        pay_to: MixedAddress::Algorand(pay_to.to_string()),
+        max_timeout_seconds: DEFAULT_MAX_TIMEOUT_SECONDS,
+        extra: None,
+    })
+}
+
+/// Whether `address` is a 58-character base32 Algorand address.
+#[cfg(feature = "algorand")]
+fn is_algorand_address(address: &str) -> bool {
+    address.len() == 58
+        && address
+            .bytes()
+            .all(|b| b.is_ascii_uppercase() || (b'2'..=b'7').contains(&b))
+}
+
+fn evm_address(address: &str) -> Result<MixedAddress, PaymentUriError> {
+    EvmAddress::from_str(address)
+        .map(MixedAddress::Evm)
+        .map_err(|_| PaymentUriError::InvalidAddress(address.to_string()))
+}
+
+/// Decimals of a known token deployment (USDC, EURC, ...) on `network`.
+fn token_decimals(network: &Caip2NetworkId, asset: &MixedAddress) -> Option<u8> {
+    let network = Network::from_caip2(&network.to_string())?;
+    find_token_deployment(network, asset).map(|deployment| deployment.decimals)
+}
+
+/// Write `amount` base units in token units with `decimals` as exponent, e.g. `0.01e6`.
+fn format_scaled(amount: TokenAmount, decimals: u8) -> String {
+    let unit = U256::from(10u8).pow(U256::from(decimals));
+    let whole = amount.0 / unit;
+    let fraction = (amount.0 % unit).to_string();
+    let fraction = format!("{fraction:0>width$}", width = decimals as usize);
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.is_empty() {
+        format!("{whole}e{decimals}")
+    } else {
+        format!("{whole}.{fraction}e{decimals}")
+    }
+}
+
+/// Parse an EIP-681 number (`10000`, `0.01e6`, `1E4`) that must be a whole number.
+fn parse_scaled(raw: &str) -> Result<TokenAmount, PaymentUriError> {
+    let invalid = || PaymentUriError::InvalidAmount(raw.to_string());
+    let (mantissa, exponent) = match raw.split_once(['e', 'E']) {
+        Some((mantissa, "")) => (mantissa, 0),
+        Some((mantissa, exponent)) => (mantissa, exponent.parse::<u32>().map_err(|_| invalid())?),
+        None => (raw, 0),
+    };
+    let mantissa = mantissa.strip_prefix('+').unwrap_or(mantissa);
+    let (whole, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    let digits = format!("{whole}{fraction}");
+    if whole.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(invalid());
+    }
+
+    // Fractional digits beyond the exponent must be zeros
+    let fraction_len = fraction.len() as u32;
+    let (digits, shift) = if fraction_len > exponent {
+        let keep = digits.len() - (fraction_len - exponent) as usize;
+        if digits[keep..].bytes().any(|b| b != b'0') {
+            return Err(invalid());
+        }
+        (&digits[..keep], 0)
+    } else {
+        (digits.as_str(), exponent - fraction_len)
+    };
+    let value = U256::from_str_radix(digits, 10).map_err(|_| invalid())?;
+    U256::from(10u8)
+        .checked_pow(U256::from(shift))
+        .and_then(|scale| value.checked_mul(scale))
+        .map(TokenAmount)
+        .ok_or_else(invalid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const USDC_BASE: &str = "0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913";
+    const PAY_TO: &str = "0x209693Bc6afc0C5328bA36FaF03C514EF312287C";
+
+    fn usdc_on_base(amount: u64) -> PaymentRequirementsV2 {
+        PaymentRequirementsV2 {
+            scheme: Scheme::Exact,
+            network: Caip2NetworkId::eip155(8453),
+            asset: evm_address(USDC_BASE).unwrap(),
+            amount: TokenAmount::from(amount),
+            pay_to: evm_address(PAY_TO).unwrap(),
+            max_timeout_seconds: DEFAULT_MAX_TIMEOUT_SECONDS,
+            extra: None,
+        }
+    }
+
+    #[test]
+    fn test_usdc_on_base_vectors() {
+        let vectors: &[(u64, &str)] = &[
+            (1, "0.000001e6"),
+            (10_000, "0.01e6"),
+            (250_000, "0.25e6"),
+            (1_000_000, "1e6"),
+            (1_500_000, "1.5e6"),
+            (123_456_789, "123.456789e6"),
+        ];
+        for &(amount, uint256) in vectors {
+            let requirements = usdc_on_base(amount);
+            let uri = generate_payment_uri(&requirements).unwrap();
+            assert_eq!(
+                uri,
+                format!("ethereum:{USDC_BASE}@8453/transfer?address={PAY_TO}&uint256={uint256}")
+            );
+            assert_eq!(parse_payment_uri(&uri).unwrap(), requirements);
+        }
+    }
+
+    #[test]
+    fn test_unknown_token_uses_base_units() {
+        let mut requirements = usdc_on_base(10_000);
+        requirements.asset = evm_address("0x0000000000000000000000000000000000000001").unwrap();
+        let uri = generate_payment_uri(&requirements).unwrap();
+        assert!(uri.ends_with("&uint256=10000"), "{uri}");
+        assert_eq!(parse_payment_uri(&uri).unwrap(), requirements);
+    }
+
+    #[test]
+    fn test_parse_eip681_variants() {
+        let uri = format!(
+            "ethereum:pay-{USDC_BASE}@8453/transfer?uint256=1E4&address={PAY_TO}&gas=60000"
+        );
+        assert_eq!(parse_payment_uri(&uri).unwrap(), usdc_on_base(10_000));
+
+        let uri = format!("ethereum:{USDC_BASE}/transfer?address={PAY_TO}&uint256=2.5e1");
+        let requirements = parse_payment_uri(&uri).unwrap();
+        assert_eq!(requirements.network, Caip2NetworkId::eip155(1));
+        assert_eq!(requirements.amount, TokenAmount::from(25u64));
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid_uris() {
+        let cases = [
+            (format!("ethereum:{PAY_TO}@8453?value=1e18"), "function"),
+            (
+                format!("ethereum:{USDC_BASE}@8453/approve?address={PAY_TO}&uint256=1"),
+                "function",
+            ),
+            (
+                format!("ethereum:{USDC_BASE}@8453/transfer?address={PAY_TO}&uint256=0.0000001e6"),
+                "amount",
+            ),
+            (
+                format!("ethereum:{USDC_BASE}@8453/transfer?address={PAY_TO}&uint256=-1"),
+                "amount",
+            ),
+            (
+                format!("ethereum:{USDC_BASE}@8453/transfer?uint256=1"),
+                "missing",
+            ),
+            (
+                format!("ethereum:{USDC_BASE}@base/transfer?address={PAY_TO}&uint256=1"),
+                "chain",
+            ),
+            (
+                format!("ethereum:0x1234@8453/transfer?address={PAY_TO}&uint256=1"),
+                "address",
+            ),
+            (
+                "bitcoin:bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq?amount=1".to_string(),
+                "uri",
+            ),
+        ];
+        for (uri, expected) in cases {
+            let error = parse_payment_uri(&uri).unwrap_err();
+            let matched = match expected {
+                "function" => matches!(error, PaymentUriError::UnsupportedFunction(_)),
+                "amount" => matches!(error, PaymentUriError::InvalidAmount(_)),
+                "missing" => matches!(error, PaymentUriError::MissingParameter("address")),
+                "chain" => matches!(error, PaymentUriError::InvalidChainId(_)),
+                "address" => matches!(error, PaymentUriError::InvalidAddress(_)),
+                _ => matches!(error, PaymentUriError::UnsupportedUri(_)),
+            };
+            assert!(matched, "{uri}: {error}");
+        }
+    }
+
+    #[test]
+    fn test_unsupported_network() {
+        let mut requirements = usdc_on_base(1);
+        requirements.network = Caip2NetworkId::near_mainnet();
+        assert!(matches!(
+            generate_payment_uri(&requirements),
+            Err(PaymentUriError::UnsupportedNetwork(_))
+        ));
+    }
+
+    #[cfg(feature = "algorand")]
+    #[test]
+    fn test_algorand_round_trip() {
+        let pay_to = "TMTAD6N22HCS2LKH7677L2KFLT3PAQWY6M4JFQFXQS32ECBFC23F57RYX4";
+        let requirements = PaymentRequirementsV2 {
+            scheme: Scheme::Exact,
+            network: Caip2NetworkId::algorand_mainnet(),
+            asset: MixedAddress::Algorand("31566704".to_string()),
+            amount: TokenAmount::from(10_000u64),
+            pay_to: MixedAddress::Algorand(pay_to.to_string()),
+            max_timeout_seconds: DEFAULT_MAX_TIMEOUT_SECONDS,
+            extra: None,
+        };
+        let uri = generate_payment_uri(&requirements).unwrap();
+        assert_eq!(
+            uri,
+            format!("algorand://{pay_to}?amount=10000&asset=31566704")
+        );
+        assert_eq!(parse_payment_uri(&uri).unwrap(), requirements);
+
+        // The testnet USDC asset selects testnet
+        let testnet =
+            parse_payment_uri(&format!("algorand://{pay_to}?amount=1&asset=10458941")).unwrap();
+        assert_eq!(testnet.network, Caip2NetworkId::algorand_testnet());
+
+        assert!(matches!(
+            parse_payment_uri(&format!("algorand://{pay_to}?amount=1")),
+            Err(PaymentUriError::MissingParameter("asset"))
+        ));
+        assert!(matches!(
+            parse_payment_uri("algorand://NOTANADDRESS?amount=1&asset=31566704"),
+            Err(PaymentUriError::InvalidAddress(_))
+        ));
+    }
+}
@@ -0,0 +1,738 @@
+//! x402 payment gating for the facilitator's own premium endpoints.
+//!
+//! Heavy endpoints can be made payable via x402 instead of being gated only by API keys.
+//! A per-route configuration maps selected endpoints to payment requirements paid to the
+//! facilitator's own wallet. Unpaid requests receive the standard `402 Payment Required`
+//! challenge; `X-PAYMENT` payloads are verified and settled through the local
+//! [`Facilitator`] directly, skipping the HTTP hop a remote seller would make.
+//!
+//! # Architecture
+//!
+//! ```text
+//! request ──▶ gated route? ──no──▶ handler
+//!                 │ yes
+//!                 ├─ X-API-Key with route scope ───────────▶ handler
+//!                 ├─ X-Access-Token valid for route scope ─▶ handler
+//!                 ├─ X-PAYMENT ─▶ verify ─▶ settle ─▶ grant token ─▶ handler (+ X-Access-Token)
+//!                 └─ none ─▶ 402 { error, accepts, x402Version }
+//! ```
+//!
+//! A successful payment grants a short-lived access token scoped to the route's `scope`,
+//! so a client can make several related calls without paying for each one.
+//!
+//! # Configuration
+//!
+//! `PAYWALL_CONFIG_FILE` points to a JSON file; it is re-read whenever it changes.
+//!
+//! ```json
+//! {
+//!   "payTo": "0x...",
+//!   "routes": [
+//!     {
+//!       "method": "GET",
+//!       "path": "/discovery/export",
+//!       "scope": "discovery",
+//!       "description": "Full Bazaar export",
+//!       "accessWindowSecs": 600,
+//!       "accepts": [{ "network": "base", "amount": "10000" }]
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! A route with `streaming` terms (`ratePerSecond`, `minDeposit`, `maxDurationSeconds`)
+//! also accepts streaming payments: its challenge carries `X-402-Streaming: true` and, under
+//! `extra.streaming` of every requirement, the streaming requirement to open a stream with
+//! at `POST /streaming/settle` (see [`crate::streaming`]).
+//!
+//! # Environment
+//!
+//! - `PAYWALL_CONFIG_FILE` - Route configuration (gating disabled when unset)
+//! - `PAYWALL_RELOAD_SECS` - How often to check the file for changes (default: 30)
+//! - `PAYWALL_API_KEYS` - `key=scope1|scope2,...` pairs that bypass payment (`*` for all scopes)
+
+use axum::extract::{MatchedPath, Request, State};
+use axum::http::{HeaderMap, HeaderValue, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
+use tokio::sync::RwLock;
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+use url::Url;
+
+use crate::digest_cache;
+use crate::facilitator::Facilitator;
+use crate::network::{Network, USDCDeployment};
+use crate::streaming::{StreamingTerms, STREAMING_HEADER};
+use crate::types::{
+    Base64Bytes, MixedAddress, PaymentPayload, PaymentRequiredResponse, PaymentRequirements,
+    Scheme, SettleResponse, TokenAmount, VerifyRequest, VerifyResponse, X402Version,
+};
+
+/// Request header carrying an x402 payment.
+pub const PAYMENT_HEADER: &str = "X-Payment";
+/// Response header carrying the base64 settlement response.
+pub const PAYMENT_RESPONSE_HEADER: &str = "X-Payment-Response";
+/// Request/response header carrying a paid access token.
+pub const ACCESS_TOKEN_HEADER: &str = "X-Access-Token";
+/// Request header carrying an API key.
+pub const API_KEY_HEADER: &str = "X-API-Key";
+
+/// Errors loading paywall configuration.
+#[derive(Debug, thiserror::Error)]
+pub enum PaywallError {
+    /// Configuration file could not be read
+    #[error("Failed to read paywall config: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// Configuration file is not valid JSON
+    #[error("Failed to parse paywall config: {0}")]
+    Parse(#[from] serde_json::Error),
+}
+
+// ============================================================================
+// Configuration
+// ============================================================================
+
+/// Price of a gated route on one network.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RoutePrice {
+    pub network: Network,
+    /// Token to pay with (the network's USDC when absent)
+    #[serde(default)]
+    pub asset: Option<MixedAddress>,
+    /// Amount in atomic units
+    pub amount: TokenAmount,
+}
+
+fn default_method() -> String {
+    "GET".to_string()
+}
+
+fn default_access_window() -> u64 {
+    300
+}
+
+fn default_timeout() -> u64 {
+    300
+}
+
+/// A facilitator endpoint that requires payment.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GatedRoute {
+    #[serde(default = "default_method")]
+    pub method: String,
+    /// Route template (e.g. `/reputation/{network}/{agent_id}`) or literal path
+    pub path: String,
+    /// Access scope granted by paying; routes sharing a scope share access tokens
+    pub scope: String,
+    #[serde(default)]
+    pub description: String,
+    pub accepts: Vec<RoutePrice>,
+    /// Lifetime of the access token granted after payment
+    #[serde(default = "default_access_window")]
+    pub access_window_secs: u64,
+    #[serde(default = "default_timeout")]
+    pub max_timeout_seconds: u64,
+    /// Per-second pricing for streaming access, advertised alongside `accepts`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub streaming: Option<StreamingTerms>,
+}
+
+impl GatedRoute {
+    fn matches(&self, method: &str, matched_path: Option<&str>, path: &str) -> bool {
+        self.method.eq_ignore_ascii_case(method)
+            && (matched_path == Some(self.path.as_str()) || self.path == path)
+    }
+
+    /// Payment requirements advertised in the 402 challenge.
+    pub fn requirements(&self, pay_to: &MixedAddress, resource: &Url) -> Vec<PaymentRequirements> {
+        self.accepts
+            .iter()
+            .map(|price| {
+                let usdc = USDCDeployment::by_network(price.network);
+                let asset = price.asset.clone().unwrap_or_else(|| usdc.asset.address.clone());
+                let mut extra = (price.asset.is_none())
+                    .then(|| usdc.eip712.as_ref())
+                    .flatten()
+                    .map(|eip712| serde_json::json!({ "name": eip712.name, "version": eip712.version }));
+                if let Some(terms) = &self.streaming {
+                    extra
+                        .get_or_insert_with(|| serde_json::json!({}))
+                        .as_object_mut()
+                        .expect("extra is an object")
+                        .insert(
+                            "streaming".to_string(),
+                            serde_json::json!(terms.requirement(
+                                price.network,
+                                asset.clone(),
+                                pay_to.clone()
+                            )),
+                        );
+                }
+                PaymentRequirements {
+                    scheme: Scheme::Exact,
+                    network: price.network,
+                    max_amount_required: price.amount,
+                    resource: resource.clone(),
+                    description: self.description.clone(),
+                    mime_type: "application/json".to_string(),
+                    output_schema: None,
+                    pay_to: pay_to.clone(),
+                    max_timeout_seconds: self.max_timeout_seconds,
+                    asset,
+                    extra,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Paywall route configuration.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PaywallConfig {
+    /// Facilitator wallet receiving payments
+    pub pay_to: MixedAddress,
+    #[serde(default)]
+    pub routes: Vec<GatedRoute>,
+}
+
+impl PaywallConfig {
+    pub fn from_file(path: &std::path::Path) -> Result<Self, PaywallError> {
+        Ok(serde_json::from_str(&std::fs::read_to_string(path)?)?)
+    }
+
+    fn route(&self, method: &str, matched_path: Option<&str>, path: &str) -> Option<&GatedRoute> {
+        self.routes
+            .iter()
+            .find(|route| route.matches(method, matched_path, path))
+    }
+}
+
+/// Parse `key=scope1|scope2,...` into a key -> scopes map.
+pub fn parse_api_keys(value: &str) -> HashMap<String, Vec<String>> {
+    value
+        .split(',')
+        .filter_map(|entry| {
+            let (key, scopes) = entry.trim().split_once('=')?;
+            let scopes = scopes
+                .split('|')
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect();
+            (!key.trim().is_empty()).then(|| (key.trim().to_string(), scopes))
+        })
+        .collect()
+}
+
+// ============================================================================
+// Paywall State
+// ============================================================================
+
+#[derive(Debug, Clone)]
+struct AccessGrant {
+    scope: String,
+    expires_at: Instant,
+}
+
+/// Shared state of the paywall middleware.
+#[derive(Debug)]
+pub struct Paywall<F> {
+    facilitator: Arc<F>,
+    config: RwLock<Arc<PaywallConfig>>,
+    api_keys: HashMap<String, Vec<String>>,
+    grants: RwLock<HashMap<String, AccessGrant>>,
+}
+
+impl<F> Paywall<F> {
+    pub fn new(facilitator: Arc<F>, config: PaywallConfig, api_keys: HashMap<String, Vec<String>>) -> Self {
+        Self {
+            facilitator,
+            config: RwLock::new(Arc::new(config)),
+            api_keys,
+            grants: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Load from `PAYWALL_CONFIG_FILE` and `PAYWALL_API_KEYS`; `None` when gating is not configured.
+    pub fn from_env(facilitator: Arc<F>) -> Result<Option<Self>, PaywallError> {
+        let Some(path) = crate::env_registry::var("PAYWALL_CONFIG_FILE") else {
+            return Ok(None);
+        };
+        let config = PaywallConfig::from_file(path.as_ref())?;
+        let api_keys = crate::env_registry::var("PAYWALL_API_KEYS")
+            .map(|v| parse_api_keys(&v))
+            .unwrap_or_default();
+        info!(
+            path = %path,
+            routes = config.routes.len(),
+            api_keys = api_keys.len(),
+            "Paywall enabled for facilitator endpoints"
+        );
+        Ok(Some(Self::new(facilitator, config, api_keys)))
+    }
+
+    /// Replace the route configuration.
+    pub async fn set_config(&self, config: PaywallConfig) {
+        *self.config.write().await = Arc::new(config);
+    }
+
+    pub async fn config(&self) -> Arc<PaywallConfig> {
+        self.config.read().await.clone()
+    }
+
+    fn api_key_allows(&self, headers: &HeaderMap, scope: &str) -> bool {
+        headers
+            .get(API_KEY_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|key| self.api_keys.get(key))
+            .is_some_and(|scopes| scopes.iter().any(|s| s == "*" || s == scope))
+    }
+
+    async fn token_allows(&self, headers: &HeaderMap, scope: &str) -> bool {
+        let Some(token) = headers.get(ACCESS_TOKEN_HEADER).and_then(|v| v.to_str().ok()) else {
+            return false;
+        };
+        self.grants
+            .read()
+            .await
+            .get(token)
+            .is_some_and(|grant| grant.scope == scope && grant.expires_at > Instant::now())
+    }
+
+    async fn grant(&self, scope: &str, window: Duration) -> String {
+        let token = hex::encode(rand::random::<[u8; 32]>());
+        let now = Instant::now();
+        let mut grants = self.grants.write().await;
+        grants.retain(|_, grant| grant.expires_at > now);
+        grants.insert(
+            token.clone(),
+            AccessGrant {
+                scope: scope.to_string(),
+                expires_at: now + window,
+            },
+        );
+        token
+    }
+}
+
+/// Poll `path` and reload the paywall configuration when it changes.
+pub fn start_reload_task<F>(paywall: Arc<Paywall<F>>, path: PathBuf, interval: Duration) -> JoinHandle<()>
+where
+    F: Send + Sync + 'static,
+{
+    tokio::spawn(async move {
+        let modified = |path: &PathBuf| std::fs::metadata(path).and_then(|m| m.modified()).ok();
+        let mut last: Option<SystemTime> = modified(&path);
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            let current = modified(&path);
+            if current == last {
+                continue;
+            }
+            last = current;
+            match PaywallConfig::from_file(&path) {
+                Ok(config) => {
+                    info!(routes = config.routes.len(), "Reloaded paywall config");
+                    paywall.set_config(config).await;
+                }
+                Err(e) => warn!(error = %e, "Keeping previous paywall config"),
+            }
+        }
+    })
+}
+
+// ============================================================================
+// Middleware
+// ============================================================================
+
+fn payment_required(
+    route: &GatedRoute,
+    error: impl Into<String>,
+    accepts: Vec<PaymentRequirements>,
+) -> Response {
+    let mut response = (
+        StatusCode::PAYMENT_REQUIRED,
+        Json(PaymentRequiredResponse {
+            error: error.into(),
+            accepts,
+            x402_version: X402Version::V1,
+        }),
+    )
+        .into_response();
+    if route.streaming.is_some() {
+        response
+            .headers_mut()
+            .insert(STREAMING_HEADER, HeaderValue::from_static("true"));
+    }
+    response
+}
+
+fn resource_url(headers: &HeaderMap, path: &str) -> Url {
+    let base = crate::env_registry::var("FACILITATOR_URL").unwrap_or_else(|| {
+        let host = headers
+            .get(axum::http::header::HOST)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or("localhost");
+        format!("http://{host}")
+    });
+    Url::parse(&base)
+        .and_then(|base| base.join(path))
+        .unwrap_or_else(|_| Url::parse("http://localhost/").expect("valid URL"))
+}
+
+/// Verify then settle a payment through the local facilitator.
+async fn verify_and_settle<F: Facilitator>(
+    facilitator: &F,
+    request: &VerifyRequest,
+) -> Result<SettleResponse, String> {
+    match facilitator.verify(request).await {
+        Ok(VerifyResponse::Valid { .. }) => {}
+        Ok(VerifyResponse::Invalid { reason, .. }) => return Err(format!("Verification Failed: {reason}")),
+        Err(e) => return Err(format!("Verification Failed: {e}")),
+    }
+    match facilitator.settle(request).await {
+        Ok(settlement) if settlement.success => Ok(settlement),
+        Ok(settlement) => {
+            let reason = settlement
+                .error_reason
+                .map(|r| r.to_string())
+                .unwrap_or_else(|| "unknown".to_string());
+            Err(format!("Settlement Failed: {reason}"))
+        }
+        Err(e) => Err(format!("Settlement Failed: {e}")),
+    }
+}
+
+/// Axum middleware enforcing the paywall on configured routes.
+pub async fn paywall_middleware<F>(
+    State(paywall): State<Arc<Paywall<F>>>,
+    request: Request,
+    next: Next,
+) -> Response
+where
+    F: Facilitator + Send + Sync + 'static,
+{
+    let config = paywall.config().await;
+    let matched_path = request.extensions().get::<MatchedPath>().map(|p| p.as_str().to_string());
+    let path = request.uri().path().to_string();
+    let Some(route) = config.route(request.method().as_str(), matched_path.as_deref(), &path) else {
+        return next.run(request).await;
+    };
+
+    let headers = request.headers();
+    if paywall.api_key_allows(headers, &route.scope) {
+        debug!(path = %path, scope = %route.scope, "Paywall bypassed by API key");
+        return next.run(request).await;
+    }
+    if paywall.token_allows(headers, &route.scope).await {
+        return next.run(request).await;
+    }
+
+    let accepts = route.requirements(&config.pay_to, &resource_url(headers, &path));
+    let Some(header) = headers.get(PAYMENT_HEADER) else {
+        return payment_required(route, "X-PAYMENT header is required", accepts);
+    };
+    let payload: PaymentPayload = match PaymentPayload::try_from(Base64Bytes::from(header.as_bytes())) {
+        Ok(payload) => payload,
+        Err(_) => return payment_required(route, "Invalid or malformed payment header", accepts),
+    };
+    let Some(requirements) = accepts
+        .iter()
+        .find(|r| r.network == payload.network && r.scheme == payload.scheme)
+        .cloned()
+    else {
+        return payment_required(route, "Unable to find matching payment requirements", accepts);
+    };
+
+    let verify_request = VerifyRequest {
+        x402_version: payload.x402_version,
+        payment_payload: payload,
+        payment_requirements: requirements,
+    };
+    // Verify and settle share one request scope so the payload is hashed once
+    let settlement = match digest_cache::request_scope(verify_and_settle(
+        paywall.facilitator.as_ref(),
+        &verify_request,
+    ))
+    .await
+    {
+        Ok(settlement) => settlement,
+        Err(error) => return payment_required(route, error, accepts),
+    };
+
+    let window = Duration::from_secs(route.access_window_secs);
+    let token = paywall.grant(&route.scope, window).await;
+    info!(
+        path = %path,
+        scope = %route.scope,
+        payer = %settlement.payer,
+        window_secs = route.access_window_secs,
+        "Paid access granted"
+    );
+
+    let mut response = next.run(request).await;
+    let headers = response.headers_mut();
+    if let Ok(value) = HeaderValue::from_str(&token) {
+        headers.insert(ACCESS_TOKEN_HEADER, value);
+    }
+    if let Ok(encoded) = TryInto::<Base64Bytes<'static>>::try_into(settlement) {
+        if let Ok(value) = HeaderValue::from_bytes(encoded.as_ref()) {
+            headers.insert(PAYMENT_RESPONSE_HEADER, value);
+        }
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{
+        EvmSignature, ExactEvmPayload, ExactEvmPayloadAuthorization, ExactPaymentPayload,
+        FacilitatorErrorReason, HexEncodedNonce, SettleRequest, SupportedPaymentKindsResponse,
+        TransactionHash,
+    };
+    use crate::timestamp::UnixTimestamp;
+    use axum::routing::get;
+    use axum::Router;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    const PAY_TO: &str = "0x1111111111111111111111111111111111111111";
+
+    #[derive(Debug, Clone, Default)]
+    struct MockFacilitator {
+        settled: Arc<AtomicUsize>,
+    }
+
+    impl Facilitator for MockFacilitator {
+        type Error = String;
+
+        async fn verify(&self, request: &VerifyRequest) -> Result<VerifyResponse, String> {
+            Ok(VerifyResponse::valid(request.payment_requirements.pay_to.clone()))
+        }
+
+        async fn settle(&self, request: &SettleRequest) -> Result<SettleResponse, String> {
+            self.settled.fetch_add(1, Ordering::SeqCst);
+            Ok(SettleResponse {
+                success: true,
+                error_reason: None::<FacilitatorErrorReason>,
+                payer: request.payment_requirements.pay_to.clone(),
+                transaction: Some(TransactionHash::Evm([1u8; 32])),
+                network: request.network(),
+                proof_of_payment: None,
+                confirmation: None,
+            })
+        }
+
+        async fn supported(&self) -> Result<SupportedPaymentKindsResponse, String> {
+            Err("unused".to_string())
+        }
+    }
+
+    fn config() -> PaywallConfig {
+        serde_json::from_value(serde_json::json!({
+            "payTo": PAY_TO,
+            "routes": [{
+                "path": "/reputation/{network}/{agent_id}",
+                "scope": "reputation",
+                "description": "Reputation history",
+                "accessWindowSecs": 60,
+                "accepts": [{ "network": "base-sepolia", "amount": "1000" }]
+            }]
+        }))
+        .unwrap()
+    }
+
+    fn payment_header() -> String {
+        let payload = PaymentPayload {
+            x402_version: X402Version::V1,
+            scheme: Scheme::Exact,
+            network: Network::BaseSepolia,
+            payload: ExactPaymentPayload::Evm(ExactEvmPayload {
+                signature: EvmSignature(vec![0u8; 65]),
+                authorization: ExactEvmPayloadAuthorization {
+                    from: "0x2222222222222222222222222222222222222222".parse().unwrap(),
+                    to: PAY_TO.parse().unwrap(),
+                    value: TokenAmount::from(1000u64),
+                    valid_after: UnixTimestamp(0),
+                    valid_before: UnixTimestamp(u64::MAX / 2),
+                    nonce: HexEncodedNonce([3u8; 32]),
+                },
+            }),
+        };
+        let encoded = Base64Bytes::encode(serde_json::to_vec(&payload).unwrap());
+        String::from_utf8(encoded.0.into_owned()).unwrap()
+    }
+
+    async fn serve(paywall: Arc<Paywall<MockFacilitator>>) -> String {
+        let app = Router::new()
+            .route("/reputation/{network}/{agent_id}", get(|| async { "history" }))
+            .route("/health", get(|| async { "ok" }))
+            .layer(axum::middleware::from_fn_with_state(
+                paywall,
+                paywall_middleware::<MockFacilitator>,
+            ));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+        format!("http://{addr}")
+    }
+
+    fn paywall(api_keys: &str) -> (Arc<Paywall<MockFacilitator>>, MockFacilitator) {
+        let facilitator = MockFacilitator::default();
+        let paywall = Paywall::new(Arc::new(facilitator.clone()), config(), parse_api_keys(api_keys));
+        (Arc::new(paywall), facilitator)
+    }
+
+    #[tokio::test]
+    async fn test_gated_route_returns_402_challenge() {
+        let (paywall, _) = paywall("");
+        let base = serve(paywall).await;
+        let response = reqwest::get(format!("{base}/reputation/base/42")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::PAYMENT_REQUIRED);
+
+        let body: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(body["x402Version"], 1);
+        assert_eq!(body["error"], "X-PAYMENT header is required");
+        let accepts = &body["accepts"][0];
+        assert_eq!(accepts["scheme"], "exact");
+        assert_eq!(accepts["network"], "base-sepolia");
+        assert_eq!(accepts["maxAmountRequired"], "1000");
+        assert_eq!(accepts["payTo"], PAY_TO);
+        assert!(accepts["resource"].as_str().unwrap().ends_with("/reputation/base/42"));
+        assert_eq!(accepts["description"], "Reputation history");
+        assert!(accepts["extra"].get("streaming").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_streaming_route_advertises_terms() {
+        let mut config = config();
+        config.routes[0].streaming = Some(serde_json::from_value(serde_json::json!({
+            "ratePerSecond": "10",
+            "minDeposit": "6000",
+            "maxDurationSeconds": 600
+        })).unwrap());
+        let streaming = Paywall::new(Arc::new(MockFacilitator::default()), config, HashMap::new());
+        let base = serve(Arc::new(streaming)).await;
+
+        let response = reqwest::get(format!("{base}/reputation/base/42")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::PAYMENT_REQUIRED);
+        assert_eq!(response.headers()[STREAMING_HEADER], "true");
+        let body: serde_json::Value = response.json().await.unwrap();
+        let extra = &body["accepts"][0]["extra"];
+        assert!(extra.get("name").is_some(), "EIP-712 domain kept: {extra}");
+        assert_eq!(extra["streaming"]["ratePerSecond"], "10");
+        assert_eq!(extra["streaming"]["maxDurationSeconds"], 600);
+        assert_eq!(extra["streaming"]["payTo"], PAY_TO);
+        assert_eq!(extra["streaming"]["network"], "base-sepolia");
+
+        let (paywall, _) = paywall("");
+        let base = serve(paywall).await;
+        let response = reqwest::get(format!("{base}/reputation/base/42")).await.unwrap();
+        assert!(!response.headers().contains_key(STREAMING_HEADER));
+    }
+
+    #[tokio::test]
+    async fn test_payment_grants_token_for_window() {
+        let (paywall, facilitator) = paywall("");
+        let base = serve(paywall).await;
+        let client = reqwest::Client::new();
+
+        let paid = client
+            .get(format!("{base}/reputation/base/42"))
+            .header(PAYMENT_HEADER, payment_header())
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(paid.status(), StatusCode::OK);
+        assert!(paid.headers().contains_key(PAYMENT_RESPONSE_HEADER));
+        let token = paid.headers()[ACCESS_TOKEN_HEADER].to_str().unwrap().to_string();
+        assert_eq!(paid.text().await.unwrap(), "history");
+        assert_eq!(facilitator.settled.load(Ordering::SeqCst), 1);
+
+        // Token covers related calls in the same scope without paying again
+        let reused = client
+            .get(format!("{base}/reputation/ethereum/7"))
+            .header(ACCESS_TOKEN_HEADER, &token)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(reused.status(), StatusCode::OK);
+        assert_eq!(facilitator.settled.load(Ordering::SeqCst), 1);
+
+        let bogus = client
+            .get(format!("{base}/reputation/base/42"))
+            .header(ACCESS_TOKEN_HEADER, "not-a-token")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(bogus.status(), StatusCode::PAYMENT_REQUIRED);
+    }
+
+    #[tokio::test]
+    async fn test_token_expires_after_window() {
+        let (paywall, _) = paywall("");
+        let token = paywall.grant("reputation", Duration::from_millis(10)).await;
+        let mut headers = HeaderMap::new();
+        headers.insert(ACCESS_TOKEN_HEADER, HeaderValue::from_str(&token).unwrap());
+        assert!(paywall.token_allows(&headers, "reputation").await);
+        assert!(!paywall.token_allows(&headers, "analytics").await);
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!paywall.token_allows(&headers, "reputation").await);
+    }
+
+    #[tokio::test]
+    async fn test_api_key_bypass_requires_scope() {
+        let (paywall, facilitator) = paywall("ops-key=reputation|discovery,other-key=analytics");
+        let base = serve(paywall).await;
+        let client = reqwest::Client::new();
+
+        let allowed = client
+            .get(format!("{base}/reputation/base/42"))
+            .header(API_KEY_HEADER, "ops-key")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(allowed.status(), StatusCode::OK);
+
+        let wrong_scope = client
+            .get(format!("{base}/reputation/base/42"))
+            .header(API_KEY_HEADER, "other-key")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(wrong_scope.status(), StatusCode::PAYMENT_REQUIRED);
+        assert_eq!(facilitator.settled.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn test_non_gated_routes_unaffected_and_hot_reload() {
+        let (paywall, _) = paywall("");
+        let base = serve(paywall.clone()).await;
+
+        let health = reqwest::get(format!("{base}/health")).await.unwrap();
+        assert_eq!(health.status(), StatusCode::OK);
+
+        // Removing the route takes effect without restarting
+        paywall
+            .set_config(PaywallConfig {
+                pay_to: config().pay_to,
+                routes: Vec::new(),
+            })
+            .await;
+        let ungated = reqwest::get(format!("{base}/reputation/base/42")).await.unwrap();
+        assert_eq!(ungated.status(), StatusCode::OK);
+    }
+}
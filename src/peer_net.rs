@@ -0,0 +1,477 @@
+//! Network addressing for peer facilitators and the local listener.
+//!
+//! Peer facilitators in private deployments may be reachable only over IPv6, or may
+//! advertise their discovery endpoint through DNS SRV records instead of a fixed URL.
+//! This module provides:
+//!
+//! - SRV sources (`srv://_x402._tcp.example.com/discovery/resources`): resolution through
+//!   a pluggable [`SrvResolver`], RFC 2782 priority/weight ordering, and construction of
+//!   the `https://` target URLs tried in order (`srv+http://` yields plain `http://`
+//!   targets for private networks)
+//! - [`ConnectionStats`]: connection failures split into DNS resolution failures and
+//!   connect failures per address family
+//! - [`BindConfig`]: dual-stack listener configuration for the facilitator itself
+//!
+//! # Environment
+//!
+//! - `HOST`: comma-separated bind addresses, IPv6 optionally bracketed (e.g. `0.0.0.0,[::]`)
+//! - `PORT`: bind port
+//! - `LISTEN_DUAL_STACK`: accept IPv4 on IPv6 wildcard sockets (default: true)
+
+use rand::Rng;
+use serde::Serialize;
+use std::error::Error as StdError;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use url::{Host, Url};
+
+/// URL scheme marking a discovery source resolved through DNS SRV (HTTPS targets).
+pub const SRV_SCHEME: &str = "srv";
+
+/// SRV scheme resolving to plain HTTP targets.
+pub const SRV_HTTP_SCHEME: &str = "srv+http";
+
+/// Path used when an SRV source does not specify one.
+const DEFAULT_SRV_PATH: &str = "/discovery/resources";
+
+/// Errors from peer addressing.
+#[derive(Debug, thiserror::Error)]
+pub enum PeerNetError {
+    /// The SRV lookup failed or returned no usable records
+    #[error("SRV resolution failed for {name}: {reason}")]
+    SrvResolution { name: String, reason: String },
+
+    /// A configured URL or address could not be parsed
+    #[error("Invalid address: {0}")]
+    InvalidAddress(String),
+}
+
+// ============================================================================
+// SRV Resolution
+// ============================================================================
+
+/// A single DNS SRV record.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SrvRecord {
+    pub priority: u16,
+    pub weight: u16,
+    pub port: u16,
+    /// Target host name (a trailing dot is allowed)
+    pub target: String,
+}
+
+/// Resolves DNS SRV records.
+#[async_trait::async_trait]
+pub trait SrvResolver: Send + Sync + std::fmt::Debug {
+    async fn resolve_srv(&self, name: &str) -> Result<Vec<SrvRecord>, PeerNetError>;
+}
+
+/// System-configured SRV resolver backed by hickory-resolver.
+#[cfg(feature = "srv")]
+#[derive(Debug)]
+pub struct HickorySrvResolver {
+    resolver: hickory_resolver::TokioAsyncResolver,
+}
+
+#[cfg(feature = "srv")]
+impl HickorySrvResolver {
+    /// Create a resolver from the system DNS configuration (`/etc/resolv.conf`).
+    pub fn from_system_conf() -> Result<Self, PeerNetError> {
+        let resolver = hickory_resolver::TokioAsyncResolver::tokio_from_system_conf().map_err(|e| {
+            PeerNetError::SrvResolution {
+                name: "<system>".to_string(),
+                reason: e.to_string(),
+            }
+        })?;
+        Ok(Self { resolver })
+    }
+}
+
+#[cfg(feature = "srv")]
+#[async_trait::async_trait]
+impl SrvResolver for HickorySrvResolver {
+    async fn resolve_srv(&self, name: &str) -> Result<Vec<SrvRecord>, PeerNetError> {
+        let lookup = self
+            .resolver
+            .srv_lookup(name)
+            .await
+            .map_err(|e| PeerNetError::SrvResolution {
+                name: name.to_string(),
+                reason: e.to_string(),
+            })?;
+        Ok(lookup
+            .iter()
+            .map(|srv| SrvRecord {
+                priority: srv.priority(),
+                weight: srv.weight(),
+                port: srv.port(),
+                target: srv.target().to_utf8(),
+            })
+            .collect())
+    }
+}
+
+/// An SRV-configured discovery source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SrvSource {
+    /// SRV owner name, e.g. `_x402._tcp.example.com`
+    pub name: String,
+    /// Path appended to each resolved target
+    pub path: String,
+    /// Scheme of the resolved target URLs (`https` or `http`)
+    pub target_scheme: &'static str,
+}
+
+impl SrvSource {
+    /// Parse `srv://_x402._tcp.example.com[/path]`. Returns `None` for non-SRV URLs.
+    pub fn parse(raw: &str) -> Option<Result<Self, PeerNetError>> {
+        let url = Url::parse(raw).ok()?;
+        let target_scheme = match url.scheme() {
+            SRV_SCHEME => "https",
+            SRV_HTTP_SCHEME => "http",
+            _ => return None,
+        };
+        let name = match url.host_str() {
+            Some(name) if !name.is_empty() => name.to_string(),
+            _ => return Some(Err(PeerNetError::InvalidAddress(raw.to_string()))),
+        };
+        let path = match url.path() {
+            "" | "/" => DEFAULT_SRV_PATH.to_string(),
+            path => path.to_string(),
+        };
+        Some(Ok(Self {
+            name,
+            path,
+            target_scheme,
+        }))
+    }
+
+    /// Resolve the source into candidate URLs, in the order they should be tried.
+    pub async fn resolve(&self, resolver: &dyn SrvResolver) -> Result<Vec<Url>, PeerNetError> {
+        let records = resolver.resolve_srv(&self.name).await?;
+        let ordered = order_srv_records(records, &mut rand::thread_rng());
+        let urls: Vec<Url> = ordered
+            .iter()
+            .filter_map(|record| srv_target_url(record, self.target_scheme, &self.path).ok())
+            .collect();
+        if urls.is_empty() {
+            return Err(PeerNetError::SrvResolution {
+                name: self.name.clone(),
+                reason: "no usable SRV targets".to_string(),
+            });
+        }
+        Ok(urls)
+    }
+}
+
+/// Order SRV records per RFC 2782.
+///
+/// Lower priority values come first. Within a priority, records are picked by weighted
+/// random selection; zero-weight records have a small chance of being picked first.
+/// A target of `.` means the service is explicitly unavailable and is dropped.
+pub fn order_srv_records<R: Rng + ?Sized>(records: Vec<SrvRecord>, rng: &mut R) -> Vec<SrvRecord> {
+    let mut records: Vec<SrvRecord> = records.into_iter().filter(|r| r.target != ".").collect();
+    records.sort_by_key(|r| r.priority);
+
+    let mut ordered = Vec::with_capacity(records.len());
+    let mut rest = records.as_slice();
+    while let Some(first) = rest.first() {
+        let split = rest.iter().position(|r| r.priority != first.priority).unwrap_or(rest.len());
+        let (group, tail) = rest.split_at(split);
+        let mut group = group.to_vec();
+        // Zero-weight records first, as RFC 2782 recommends
+        group.sort_by_key(|r| r.weight != 0);
+        while !group.is_empty() {
+            let total: u32 = group.iter().map(|r| r.weight as u32).sum();
+            let pick = rng.gen_range(0..=total);
+            let mut running = 0u32;
+            let index = group
+                .iter()
+                .position(|r| {
+                    running += r.weight as u32;
+                    running >= pick
+                })
+                .unwrap_or(0);
+            ordered.push(group.remove(index));
+        }
+        rest = tail;
+    }
+    ordered
+}
+
+/// Build the target URL (`https://host:port/path`) for an SRV record.
+pub fn srv_target_url(record: &SrvRecord, scheme: &str, path: &str) -> Result<Url, PeerNetError> {
+    let target = record.target.trim_end_matches('.');
+    let host = match target.parse::<Ipv6Addr>() {
+        Ok(_) => format!("[{}]", target),
+        Err(_) => target.to_string(),
+    };
+    Url::parse(&format!("{}://{}:{}{}", scheme, host, record.port, path))
+        .map_err(|e| PeerNetError::InvalidAddress(format!("{}: {}", target, e)))
+}
+
+// ============================================================================
+// Connection Failure Classification
+// ============================================================================
+
+/// IP address family of a connection attempt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AddressFamily {
+    Ipv4,
+    Ipv6,
+    /// Host name that may resolve to either family
+    Unknown,
+}
+
+impl AddressFamily {
+    /// Address family of a URL host.
+    pub fn of_url(url: &Url) -> Self {
+        match url.host() {
+            Some(Host::Ipv4(_)) => AddressFamily::Ipv4,
+            Some(Host::Ipv6(_)) => AddressFamily::Ipv6,
+            _ => AddressFamily::Unknown,
+        }
+    }
+}
+
+/// Kind of connection-level failure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectFailure {
+    /// DNS (A/AAAA or SRV) resolution failed
+    Resolution,
+    /// Resolution succeeded but the TCP/TLS connect failed
+    Connect(AddressFamily),
+}
+
+/// Classify a request error as a connection-level failure, if it is one.
+pub fn classify_error(error: &reqwest::Error) -> Option<ConnectFailure> {
+    if !error.is_connect() {
+        return None;
+    }
+    let mut source: Option<&dyn StdError> = error.source();
+    while let Some(err) = source {
+        let message = err.to_string().to_lowercase();
+        if message.contains("dns error") || message.contains("failed to lookup address") {
+            return Some(ConnectFailure::Resolution);
+        }
+        source = err.source();
+    }
+    let family = error.url().map(AddressFamily::of_url).unwrap_or(AddressFamily::Unknown);
+    Some(ConnectFailure::Connect(family))
+}
+
+/// Connection failure counters for a peer.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ConnectionStats {
+    pub resolution_failures: u64,
+    pub connect_failures_ipv4: u64,
+    pub connect_failures_ipv6: u64,
+    pub connect_failures_unknown_family: u64,
+    /// Times a later SRV target was tried after the previous one failed
+    pub srv_fallbacks: u64,
+}
+
+impl ConnectionStats {
+    pub fn record(&mut self, failure: ConnectFailure) {
+        match failure {
+            ConnectFailure::Resolution => self.resolution_failures += 1,
+            ConnectFailure::Connect(AddressFamily::Ipv4) => self.connect_failures_ipv4 += 1,
+            ConnectFailure::Connect(AddressFamily::Ipv6) => self.connect_failures_ipv6 += 1,
+            ConnectFailure::Connect(AddressFamily::Unknown) => {
+                self.connect_failures_unknown_family += 1
+            }
+        }
+    }
+}
+
+// ============================================================================
+// Listener Binding
+// ============================================================================
+
+/// Listener binding configuration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BindConfig {
+    pub addrs: Vec<SocketAddr>,
+    /// Whether IPv6 sockets also accept IPv4 (IPV6_V6ONLY = false)
+    pub dual_stack: bool,
+}
+
+impl BindConfig {
+    /// Parse a comma-separated host list.
+    ///
+    /// IPv6 addresses may be bracketed (`[::]`). With `dual_stack` enabled, a separate
+    /// `0.0.0.0` entry is dropped when `::` is also configured, since the IPv6 wildcard
+    /// socket already accepts IPv4 and binding both would conflict.
+    pub fn parse(hosts: &str, port: u16, dual_stack: bool) -> Result<Self, PeerNetError> {
+        let mut addrs = Vec::new();
+        for host in hosts.split(',').map(str::trim).filter(|h| !h.is_empty()) {
+            let unbracketed = host
+                .strip_prefix('[')
+                .and_then(|h| h.strip_suffix(']'))
+                .unwrap_or(host);
+            let ip: IpAddr = unbracketed
+                .parse()
+                .map_err(|_| PeerNetError::InvalidAddress(host.to_string()))?;
+            let addr = SocketAddr::new(ip, port);
+            if !addrs.contains(&addr) {
+                addrs.push(addr);
+            }
+        }
+        if addrs.is_empty() {
+            return Err(PeerNetError::InvalidAddress(hosts.to_string()));
+        }
+        if dual_stack && addrs.iter().any(|a| a.ip() == IpAddr::V6(Ipv6Addr::UNSPECIFIED)) {
+            addrs.retain(|a| a.ip() != IpAddr::V4(Ipv4Addr::UNSPECIFIED));
+        }
+        Ok(Self { addrs, dual_stack })
+    }
+
+    /// Bind one listener per configured address.
+    pub fn bind(&self) -> std::io::Result<Vec<tokio::net::TcpListener>> {
+        self.addrs
+            .iter()
+            .map(|addr| {
+                let domain = socket2::Domain::for_address(*addr);
+                let socket =
+                    socket2::Socket::new(domain, socket2::Type::STREAM, Some(socket2::Protocol::TCP))?;
+                if addr.is_ipv6() {
+                    socket.set_only_v6(!self.dual_stack)?;
+                }
+                socket.set_reuse_address(true)?;
+                socket.set_nonblocking(true)?;
+                socket.bind(&(*addr).into())?;
+                socket.listen(1024)?;
+                tokio::net::TcpListener::from_std(socket.into())
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn record(priority: u16, weight: u16, target: &str) -> SrvRecord {
+        SrvRecord {
+            priority,
+            weight,
+            port: 8443,
+            target: target.to_string(),
+        }
+    }
+
+    #[derive(Debug)]
+    struct MockResolver(Vec<SrvRecord>);
+
+    #[async_trait::async_trait]
+    impl SrvResolver for MockResolver {
+        async fn resolve_srv(&self, _name: &str) -> Result<Vec<SrvRecord>, PeerNetError> {
+            Ok(self.0.clone())
+        }
+    }
+
+    #[test]
+    fn test_ipv6_literal_urls() {
+        let url = Url::parse("http://[2001:db8::1]:8080/discovery/resources").unwrap();
+        assert_eq!(AddressFamily::of_url(&url), AddressFamily::Ipv6);
+        assert_eq!(url.port(), Some(8080));
+        assert_eq!(url.host_str(), Some("[2001:db8::1]"));
+
+        let record = record(0, 0, "2001:db8::2");
+        let url = srv_target_url(&record, "https", "/discovery/resources").unwrap();
+        assert_eq!(url.as_str(), "https://[2001:db8::2]:8443/discovery/resources");
+
+        let url = srv_target_url(&SrvRecord { target: "peer.example.com.".into(), ..record }, "http", "/x").unwrap();
+        assert_eq!(url.as_str(), "http://peer.example.com:8443/x");
+        assert_eq!(AddressFamily::of_url(&url), AddressFamily::Unknown);
+    }
+
+    #[test]
+    fn test_srv_source_parse() {
+        let source = SrvSource::parse("srv://_x402._tcp.example.com").unwrap().unwrap();
+        assert_eq!(source.name, "_x402._tcp.example.com");
+        assert_eq!(source.path, DEFAULT_SRV_PATH);
+        assert_eq!(source.target_scheme, "https");
+        let source = SrvSource::parse("srv+http://_x402._tcp.internal/api").unwrap().unwrap();
+        assert_eq!(source.target_scheme, "http");
+        assert_eq!(source.path, "/api");
+        assert!(SrvSource::parse("https://example.com/discovery/resources").is_none());
+    }
+
+    #[test]
+    fn test_srv_selection_by_priority_and_weight() {
+        let records = vec![
+            record(20, 100, "backup.example.com"),
+            record(10, 0, "never.example.com"),
+            record(10, 1000, "heavy.example.com"),
+            record(10, 1, "light.example.com"),
+            record(5, 0, "."),
+        ];
+
+        let mut heavy_first = 0;
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..200 {
+            let ordered = order_srv_records(records.clone(), &mut rng);
+            // "." target is dropped, lowest priority value always first group
+            assert_eq!(ordered.len(), 4);
+            assert_eq!(ordered[3].target, "backup.example.com");
+            assert!(ordered[..3].iter().all(|r| r.priority == 10));
+            if ordered[0].target == "heavy.example.com" {
+                heavy_first += 1;
+            }
+        }
+        assert!(heavy_first > 180, "heavy target picked first {heavy_first}/200 times");
+    }
+
+    #[tokio::test]
+    async fn test_srv_fallback_order() {
+        let resolver = MockResolver(vec![
+            record(10, 5, "primary.example.com."),
+            record(20, 5, "secondary.example.com."),
+        ]);
+        let source = SrvSource::parse("srv://_x402._tcp.example.com/api/discovery").unwrap().unwrap();
+        let urls = source.resolve(&resolver).await.unwrap();
+        assert_eq!(urls[0].as_str(), "https://primary.example.com:8443/api/discovery");
+        assert_eq!(urls[1].as_str(), "https://secondary.example.com:8443/api/discovery");
+
+        let empty = MockResolver(vec![record(0, 0, ".")]);
+        assert!(source.resolve(&empty).await.is_err());
+    }
+
+    #[test]
+    fn test_connection_stats_by_family() {
+        let mut stats = ConnectionStats::default();
+        stats.record(ConnectFailure::Resolution);
+        stats.record(ConnectFailure::Connect(AddressFamily::Ipv6));
+        stats.record(ConnectFailure::Connect(AddressFamily::Ipv6));
+        stats.record(ConnectFailure::Connect(AddressFamily::Ipv4));
+        assert_eq!(stats.resolution_failures, 1);
+        assert_eq!(stats.connect_failures_ipv6, 2);
+        assert_eq!(stats.connect_failures_ipv4, 1);
+    }
+
+    #[test]
+    fn test_bind_config_parsing() {
+        let config = BindConfig::parse("0.0.0.0", 8080, true).unwrap();
+        assert_eq!(config.addrs, vec!["0.0.0.0:8080".parse().unwrap()]);
+
+        // Dual-stack: IPv6 wildcard covers IPv4
+        let config = BindConfig::parse("0.0.0.0, [::]", 8080, true).unwrap();
+        assert_eq!(config.addrs, vec!["[::]:8080".parse().unwrap()]);
+
+        // IPv6-only: both sockets are needed
+        let config = BindConfig::parse("0.0.0.0,::", 8080, false).unwrap();
+        assert_eq!(config.addrs.len(), 2);
+
+        let config = BindConfig::parse("[::1],127.0.0.1", 9000, true).unwrap();
+        assert_eq!(
+            config.addrs,
+            vec!["[::1]:9000".parse().unwrap(), "127.0.0.1:9000".parse().unwrap()]
+        );
+
+        assert!(BindConfig::parse("not-an-ip", 8080, true).is_err());
+        assert!(BindConfig::parse("", 8080, true).is_err());
+    }
+}
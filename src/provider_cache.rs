@@ -14,7 +14,7 @@
 //!
 //! Example usage:
 //! ```ignore
-//! let provider_cache = ProviderCache::from_env().await?;
+//! let provider_cache = ProviderCache::from_env(create_nonce_store().await).await?;
 //! let provider = provider_cache.by_network(Network::Base)?;
 //! ```
 
@@ -25,6 +25,7 @@ use std::sync::Arc;
 use crate::chain::FromEnvByNetworkBuild;
 use crate::chain::NetworkProvider;
 use crate::network::Network;
+use crate::nonce_store::NonceStore;
 
 /// A cache of pre-initialized [`EthereumProvider`] instances keyed by network.
 ///
@@ -89,11 +90,16 @@ impl ProviderCache {
     /// - `EVM_PRIVATE_KEY` — comma-separated list of private keys used to sign transactions
     /// - `RPC_URL_BASE`, `RPC_URL_BASE_SEPOLIA` — RPC endpoints per network
     ///
+    /// Providers tracking used nonces off-chain (Stellar, Algorand) all share `nonce_store`.
+    ///
     /// Fails if required env vars are missing or if the provider cannot connect.
-    pub async fn from_env() -> Result<Self, Box<dyn std::error::Error>> {
+    pub async fn from_env(
+        nonce_store: Arc<dyn NonceStore>,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
         let mut providers = HashMap::new();
         for network in Network::variants() {
-            let network_provider = NetworkProvider::from_env(*network).await?;
+            let network_provider =
+                NetworkProvider::from_env(*network, Arc::clone(&nonce_store)).await?;
             if let Some(network_provider) = network_provider {
                 providers.insert(*network, network_provider);
             }
@@ -0,0 +1,431 @@
+//! Per-IP rate limiting of the facilitator's public endpoints.
+//!
+//! Each limited endpoint has its own token bucket per client IP, so a burst of
+//! discovery queries cannot starve settlements and the other way around:
+//!
+//! | Endpoint | Variable | Default |
+//! |----------|----------|---------|
+//! | `POST /verify` | `RATE_LIMIT_VERIFY` | 100 req/s |
+//! | `POST /settle` | `RATE_LIMIT_SETTLE` | 50 req/s |
+//! | `GET /discovery/resources` | `RATE_LIMIT_DISCOVERY` | 20 req/s |
+//!
+//! A bucket holds one second worth of requests and refills continuously; `0` disables
+//! the limit of an endpoint. Requests over the limit get `429 Too Many Requests` with
+//! a `Retry-After` header. Tenant-prefixed routes share the buckets of the plain routes.
+//!
+//! # Client address
+//!
+//! The client is the peer address of the connection. Behind reverse proxies, set
+//! `RATE_LIMIT_TRUSTED_PROXIES` to the number of proxies in front of the facilitator;
+//! the client is then the address that many entries from the end of `X-Forwarded-For`,
+//! which is the one appended by the outermost trusted proxy. Entries further left are
+//! supplied by the client and never trusted. IPv6 clients are limited per /64.
+
+use axum::extract::{ConnectInfo, Request, State};
+use axum::http::{header, HeaderMap, HeaderValue, Method, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use governor::clock::{Clock, DefaultClock};
+use governor::{DefaultKeyedRateLimiter, Quota};
+use serde_json::json;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::num::NonZeroU32;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::debug;
+
+use crate::env_registry;
+
+/// Default `POST /verify` requests per second and client.
+pub const DEFAULT_VERIFY_RPS: u32 = 100;
+
+/// Default `POST /settle` requests per second and client.
+pub const DEFAULT_SETTLE_RPS: u32 = 50;
+
+/// Default `GET /discovery/resources` requests per second and client.
+pub const DEFAULT_DISCOVERY_RPS: u32 = 20;
+
+/// Header listing the client and proxy addresses a request went through.
+pub const FORWARDED_FOR_HEADER: &str = "X-Forwarded-For";
+
+/// How often buckets of clients that went quiet are dropped.
+const CLEANUP_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Endpoints with their own bucket.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LimitedRoute {
+    Verify,
+    Settle,
+    Discovery,
+}
+
+impl LimitedRoute {
+    /// The limited endpoint a request targets, if any.
+    pub fn of(method: &Method, path: &str) -> Option<Self> {
+        let path = path.trim_end_matches('/');
+        match *method {
+            Method::POST if path.ends_with("/verify") => Some(Self::Verify),
+            Method::POST if path.ends_with("/settle") => Some(Self::Settle),
+            Method::GET if path.ends_with("/discovery/resources") => Some(Self::Discovery),
+            _ => None,
+        }
+    }
+}
+
+/// Requests per second of each endpoint; `None` leaves the endpoint unlimited.
+#[derive(Debug, Clone)]
+pub struct RateLimitConfig {
+    pub verify: Option<NonZeroU32>,
+    pub settle: Option<NonZeroU32>,
+    pub discovery: Option<NonZeroU32>,
+    /// Reverse proxies in front of the facilitator; `0` ignores `X-Forwarded-For`
+    pub trusted_proxies: usize,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            verify: NonZeroU32::new(DEFAULT_VERIFY_RPS),
+            settle: NonZeroU32::new(DEFAULT_SETTLE_RPS),
+            discovery: NonZeroU32::new(DEFAULT_DISCOVERY_RPS),
+            trusted_proxies: 0,
+        }
+    }
+}
+
+impl RateLimitConfig {
+    pub fn from_env() -> Self {
+        let rps = |name, default| NonZeroU32::new(env_registry::parse(name).unwrap_or(default));
+        Self {
+            verify: rps("RATE_LIMIT_VERIFY", DEFAULT_VERIFY_RPS),
+            settle: rps("RATE_LIMIT_SETTLE", DEFAULT_SETTLE_RPS),
+            discovery: rps("RATE_LIMIT_DISCOVERY", DEFAULT_DISCOVERY_RPS),
+            trusted_proxies: env_registry::parse("RATE_LIMIT_TRUSTED_PROXIES").unwrap_or(0),
+        }
+    }
+
+    /// Whether any endpoint is limited.
+    pub fn is_enabled(&self) -> bool {
+        self.verify.is_some() || self.settle.is_some() || self.discovery.is_some()
+    }
+}
+
+/// Token buckets of every limited endpoint, keyed by client address.
+#[derive(Debug)]
+pub struct RateLimiter {
+    verify: Option<DefaultKeyedRateLimiter<IpAddr>>,
+    settle: Option<DefaultKeyedRateLimiter<IpAddr>>,
+    discovery: Option<DefaultKeyedRateLimiter<IpAddr>>,
+    trusted_proxies: usize,
+    clock: DefaultClock,
+}
+
+impl RateLimiter {
+    pub fn new(config: RateLimitConfig) -> Self {
+        let keyed = |rps: Option<NonZeroU32>| {
+            rps.map(|rps| governor::RateLimiter::keyed(Quota::per_second(rps)))
+        };
+        Self {
+            verify: keyed(config.verify),
+            settle: keyed(config.settle),
+            discovery: keyed(config.discovery),
+            trusted_proxies: config.trusted_proxies,
+            clock: DefaultClock::default(),
+        }
+    }
+
+    /// Take a token from the bucket of `client` for `route`.
+    ///
+    /// Returns how long to wait before retrying when the bucket is empty.
+    pub fn check(&self, route: LimitedRoute, client: IpAddr) -> Result<(), Duration> {
+        let limiter = match route {
+            LimitedRoute::Verify => &self.verify,
+            LimitedRoute::Settle => &self.settle,
+            LimitedRoute::Discovery => &self.discovery,
+        };
+        let Some(limiter) = limiter else {
+            return Ok(());
+        };
+        limiter
+            .check_key(&bucket_key(client))
+            .map_err(|not_until| not_until.wait_time_from(self.clock.now()))
+    }
+
+    /// Address a request is accounted to: the peer, or the client reported by
+    /// the outermost trusted proxy.
+    pub fn client_ip(&self, headers: &HeaderMap, peer: Option<IpAddr>) -> IpAddr {
+        forwarded_client(headers, self.trusted_proxies)
+            .or(peer)
+            .unwrap_or(IpAddr::V4(Ipv4Addr::UNSPECIFIED))
+    }
+
+    /// Drop buckets that have refilled completely, i.e. of clients that went quiet.
+    pub fn retain_recent(&self) {
+        for limiter in [&self.verify, &self.settle, &self.discovery]
+            .into_iter()
+            .flatten()
+        {
+            limiter.retain_recent();
+        }
+    }
+}
+
+/// Clients in the same IPv6 /64 usually are the same host; IPv4-mapped addresses are IPv4.
+fn bucket_key(ip: IpAddr) -> IpAddr {
+    match ip.to_canonical() {
+        IpAddr::V6(v6) => {
+            let prefix = u128::from(v6) & (u128::MAX << 64);
+            IpAddr::V6(Ipv6Addr::from(prefix))
+        }
+        v4 => v4,
+    }
+}
+
+/// The `X-Forwarded-For` entry added by the outermost of `trusted_proxies` proxies.
+fn forwarded_client(headers: &HeaderMap, trusted_proxies: usize) -> Option<IpAddr> {
+    if trusted_proxies == 0 {
+        return None;
+    }
+    let hops: Vec<&str> = headers
+        .get_all(FORWARDED_FOR_HEADER)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+        .filter(|hop| !hop.is_empty())
+        .collect();
+    // With fewer entries than proxies the request skipped some of them; the leftmost
+    // entry is then the closest thing to the client that a trusted proxy recorded
+    let hop = hops.get(hops.len().saturating_sub(trusted_proxies))?;
+    hop.parse::<IpAddr>()
+        .ok()
+        .or_else(|| hop.parse::<SocketAddr>().ok().map(|addr| addr.ip()))
+}
+
+/// `429 Too Many Requests` asking the client to come back after `wait`.
+fn too_many_requests(wait: Duration) -> Response {
+    // Round up, so a client honouring the header finds a token
+    let retry_after = wait.as_secs() + u64::from(wait.subsec_nanos() > 0);
+    let retry_after = retry_after.max(1);
+    let mut response = (
+        StatusCode::TOO_MANY_REQUESTS,
+        Json(json!({
+            "error": "Rate limit exceeded",
+            "retryAfter": retry_after
+        })),
+    )
+        .into_response();
+    response
+        .headers_mut()
+        .insert(header::RETRY_AFTER, HeaderValue::from(retry_after));
+    response
+}
+
+/// Axum middleware rejecting requests over their endpoint's limit.
+///
+/// The peer address comes from [`ConnectInfo`], so the router must be served with
+/// `into_make_service_with_connect_info::<SocketAddr>()`.
+pub async fn rate_limit_middleware(
+    State(limiter): State<Arc<RateLimiter>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let Some(route) = LimitedRoute::of(request.method(), request.uri().path()) else {
+        return next.run(request).await;
+    };
+    let peer = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip());
+    let client = limiter.client_ip(request.headers(), peer);
+    match limiter.check(route, client) {
+        Ok(()) => next.run(request).await,
+        Err(wait) => {
+            debug!(client = %client, route = ?route, "Rate limit exceeded");
+            too_many_requests(wait)
+        }
+    }
+}
+
+/// Periodically drop the buckets of quiet clients, bounding memory use.
+pub fn start_cleanup_task(limiter: Arc<RateLimiter>) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(CLEANUP_INTERVAL);
+        loop {
+            interval.tick().await;
+            limiter.retain_recent();
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::routing::{get, post};
+    use axum::Router;
+    use tower::ServiceExt;
+
+    const CLIENT: IpAddr = IpAddr::V4(Ipv4Addr::new(203, 0, 113, 7));
+
+    fn config(verify: u32, settle: u32, discovery: u32) -> RateLimitConfig {
+        RateLimitConfig {
+            verify: NonZeroU32::new(verify),
+            settle: NonZeroU32::new(settle),
+            discovery: NonZeroU32::new(discovery),
+            trusted_proxies: 0,
+        }
+    }
+
+    fn app(limiter: RateLimiter) -> Router {
+        Router::new()
+            .route("/verify", post(|| async { "ok" }))
+            .route("/settle", post(|| async { "ok" }))
+            .route("/discovery/resources", get(|| async { "ok" }))
+            .layer(axum::middleware::from_fn_with_state(
+                Arc::new(limiter),
+                rate_limit_middleware,
+            ))
+    }
+
+    async fn send(app: &Router, method: Method, path: &str, peer: IpAddr) -> Response {
+        let mut request = Request::builder()
+            .method(method)
+            .uri(path)
+            .body(Body::empty())
+            .unwrap();
+        request
+            .extensions_mut()
+            .insert(ConnectInfo(SocketAddr::new(peer, 40000)));
+        app.clone().oneshot(request).await.unwrap()
+    }
+
+    #[test]
+    fn test_limited_routes() {
+        assert_eq!(
+            LimitedRoute::of(&Method::POST, "/verify"),
+            Some(LimitedRoute::Verify)
+        );
+        assert_eq!(
+            LimitedRoute::of(&Method::POST, "/t/acme/settle"),
+            Some(LimitedRoute::Settle)
+        );
+        assert_eq!(
+            LimitedRoute::of(&Method::GET, "/discovery/resources/"),
+            Some(LimitedRoute::Discovery)
+        );
+        assert_eq!(LimitedRoute::of(&Method::GET, "/verify"), None);
+        assert_eq!(LimitedRoute::of(&Method::POST, "/verify/batch"), None);
+        assert_eq!(LimitedRoute::of(&Method::GET, "/discovery/search"), None);
+    }
+
+    #[tokio::test]
+    async fn test_limit_fires_with_retry_after() {
+        let app = app(RateLimiter::new(config(2, 1, 1)));
+
+        for _ in 0..2 {
+            let response = send(&app, Method::POST, "/verify", CLIENT).await;
+            assert_eq!(response.status(), StatusCode::OK);
+        }
+        let response = send(&app, Method::POST, "/verify", CLIENT).await;
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers()[header::RETRY_AFTER], "1");
+
+        // Other endpoints and other clients have their own buckets
+        let response = send(&app, Method::POST, "/settle", CLIENT).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let other = IpAddr::V4(Ipv4Addr::new(198, 51, 100, 1));
+        let response = send(&app, Method::POST, "/verify", other).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_bucket_refills() {
+        let app = app(RateLimiter::new(config(1, 1, 10)));
+
+        for _ in 0..10 {
+            let response = send(&app, Method::GET, "/discovery/resources", CLIENT).await;
+            assert_eq!(response.status(), StatusCode::OK);
+        }
+        let response = send(&app, Method::GET, "/discovery/resources", CLIENT).await;
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+
+        // One token per 100ms at 10 req/s
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        let response = send(&app, Method::GET, "/discovery/resources", CLIENT).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = send(&app, Method::GET, "/discovery/resources", CLIENT).await;
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    }
+
+    #[tokio::test]
+    async fn test_zero_disables_limit() {
+        let app = app(RateLimiter::new(config(0, 1, 1)));
+        for _ in 0..50 {
+            let response = send(&app, Method::POST, "/verify", CLIENT).await;
+            assert_eq!(response.status(), StatusCode::OK);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_forwarded_for_needs_trusted_proxies() {
+        let proxy = IpAddr::V4(Ipv4Addr::LOCALHOST);
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            FORWARDED_FOR_HEADER,
+            HeaderValue::from_static("10.0.0.1, 203.0.113.7, 172.16.0.2"),
+        );
+
+        let direct = RateLimiter::new(config(1, 1, 1));
+        assert_eq!(direct.client_ip(&headers, Some(proxy)), proxy);
+
+        let one_proxy = RateLimiter::new(RateLimitConfig {
+            trusted_proxies: 1,
+            ..config(1, 1, 1)
+        });
+        assert_eq!(
+            one_proxy.client_ip(&headers, Some(proxy)),
+            IpAddr::V4(Ipv4Addr::new(172, 16, 0, 2))
+        );
+
+        let two_proxies = RateLimiter::new(RateLimitConfig {
+            trusted_proxies: 2,
+            ..config(1, 1, 1)
+        });
+        assert_eq!(two_proxies.client_ip(&headers, Some(proxy)), CLIENT);
+        assert_eq!(two_proxies.client_ip(&HeaderMap::new(), Some(proxy)), proxy);
+    }
+
+    #[tokio::test]
+    async fn test_clients_behind_proxy_have_own_buckets() {
+        let app = app(RateLimiter::new(RateLimitConfig {
+            trusted_proxies: 1,
+            ..config(1, 1, 1)
+        }));
+        let proxy = IpAddr::V4(Ipv4Addr::LOCALHOST);
+        for client in ["203.0.113.7", "203.0.113.8"] {
+            let request = Request::builder()
+                .method(Method::POST)
+                .uri("/verify")
+                .header(FORWARDED_FOR_HEADER, client)
+                .extension(ConnectInfo(SocketAddr::new(proxy, 40000)))
+                .body(Body::empty())
+                .unwrap();
+            let response = app.clone().oneshot(request).await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK, "{client}");
+        }
+    }
+
+    #[test]
+    fn test_ipv6_clients_share_their_64() {
+        let a: IpAddr = "2001:db8:1:2::1".parse().unwrap();
+        let b: IpAddr = "2001:db8:1:2:ffff::9".parse().unwrap();
+        let c: IpAddr = "2001:db8:1:3::1".parse().unwrap();
+        assert_eq!(bucket_key(a), bucket_key(b));
+        assert_ne!(bucket_key(a), bucket_key(c));
+        let mapped: IpAddr = "::ffff:203.0.113.7".parse().unwrap();
+        assert_eq!(bucket_key(mapped), CLIENT);
+    }
+}
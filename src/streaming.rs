@@ -0,0 +1,796 @@
+//! Streaming payments: per-second micropayments for data streams and real-time feeds.
+//!
+//! Instead of paying per request, a client deposits a signed allowance (an EIP-2612
+//! `permit` for the facilitator as spender) and is charged `ratePerSecond` for as long
+//! as the stream is open. The facilitator pulls what has accrued from the allowance on
+//! every drain, and closes the stream once the deposit is exhausted or
+//! `maxDurationSeconds` has elapsed.
+//!
+//! # Flow
+//!
+//! 1. A streaming route answers `402` with `X-402-Streaming: true` and the terms in the
+//!    `streaming` entry of each requirement's `extra` (see [`crate::paywall`])
+//! 2. The client signs a permit for at least `minDeposit` and sends a [`StreamingPaymentPayload`]
+//! 3. `POST /streaming/verify` ([`StreamingFacilitator::verify_streaming`]) checks the
+//!    terms and the allowance
+//! 4. `POST /streaming/settle` ([`StreamingFacilitator::settle_streaming`]) submits the
+//!    permit and opens the stream, whose access `GET /streaming/{streamId}` reports
+//! 5. [`StreamManager::drain`] (every `STREAMING_DRAIN_INTERVAL_SECS`, see
+//!    [`start_drain_task`]) charges what has accrued and closes finished streams
+//!
+//! Moving funds is chain specific and left to an [`AllowanceCharger`]. The server charges
+//! through a [`ProviderCharger`], which hands each stream to the provider of its network;
+//! only EVM providers implement it.
+
+use alloy::primitives::{keccak256, B256, U256};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::{Debug, Display};
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+use thiserror::Error;
+use tokio::sync::RwLock;
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+use crate::network::Network;
+use crate::provider_cache::{HasProviderMap, ProviderMap};
+use crate::timestamp::UnixTimestamp;
+use crate::types::{
+    EvmAddress, EvmSignature, FacilitatorErrorReason, MixedAddress, TokenAmount, TransactionHash,
+    VerifyResponse,
+};
+
+/// Response header marking a `402` challenge that accepts streaming payments.
+pub const STREAMING_HEADER: &str = "X-402-Streaming";
+
+/// Default seconds between drains of the open streams.
+pub const DEFAULT_DRAIN_INTERVAL_SECS: u64 = 60;
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// Price and limits of a streaming resource, independent of the network it is paid on.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamingTerms {
+    /// Amount charged per second the stream is open, in atomic units
+    pub rate_per_second: TokenAmount,
+    /// Smallest allowance accepted to open a stream
+    pub min_deposit: TokenAmount,
+    /// Longest a stream stays open, whatever the deposit
+    pub max_duration_seconds: u64,
+}
+
+impl StreamingTerms {
+    /// Requirement for paying these terms with `asset` on `network`.
+    pub fn requirement(
+        &self,
+        network: Network,
+        asset: MixedAddress,
+        pay_to: MixedAddress,
+    ) -> StreamingPaymentRequirement {
+        StreamingPaymentRequirement {
+            network,
+            asset,
+            pay_to,
+            rate_per_second: self.rate_per_second,
+            min_deposit: self.min_deposit,
+            max_duration_seconds: self.max_duration_seconds,
+        }
+    }
+}
+
+/// What a seller requires to open a stream.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamingPaymentRequirement {
+    pub network: Network,
+    /// Token the allowance is granted in
+    pub asset: MixedAddress,
+    /// Recipient of every charge
+    pub pay_to: MixedAddress,
+    /// Amount charged per second, in atomic units
+    pub rate_per_second: TokenAmount,
+    /// Smallest allowance accepted to open a stream
+    pub min_deposit: TokenAmount,
+    /// Longest a stream stays open
+    pub max_duration_seconds: u64,
+}
+
+/// EIP-2612 `permit` letting `spender` pull up to `value` from `owner`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Eip2612Permit {
+    pub owner: EvmAddress,
+    /// The facilitator's address
+    pub spender: EvmAddress,
+    /// Allowance, and so the stream's deposit
+    pub value: TokenAmount,
+    /// Token nonce of `owner` the permit was signed with
+    pub nonce: TokenAmount,
+    /// Last second the permit can be submitted
+    pub deadline: UnixTimestamp,
+    pub signature: EvmSignature,
+}
+
+/// Payload opening a stream.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamingPaymentPayload {
+    pub network: Network,
+    pub permit: Eip2612Permit,
+}
+
+impl StreamingPaymentPayload {
+    /// Id of the stream this payload opens: one permit opens at most one stream.
+    pub fn stream_id(&self) -> StreamId {
+        StreamId(keccak256(&self.permit.signature.0))
+    }
+
+    pub fn payer(&self) -> MixedAddress {
+        MixedAddress::Evm(self.permit.owner)
+    }
+}
+
+/// Request to [`StreamingFacilitator::verify_streaming`] or
+/// [`StreamingFacilitator::settle_streaming`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamingVerifyRequest {
+    pub payload: StreamingPaymentPayload,
+    pub requirement: StreamingPaymentRequirement,
+}
+
+/// Settling a stream takes the same request as verifying it.
+pub type StreamingSettleRequest = StreamingVerifyRequest;
+
+/// An opened stream.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamingSettleResponse {
+    pub stream_id: StreamId,
+    pub payer: MixedAddress,
+    pub network: Network,
+    /// When the stream closes at the latest
+    pub expires_at: UnixTimestamp,
+    /// Transaction submitting the permit, if one was needed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transaction: Option<TransactionHash>,
+}
+
+/// Identifier of a stream: the hash of the permit signature that opened it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct StreamId(pub B256);
+
+impl Display for StreamId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Accounting of an open stream.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StreamState {
+    pub payload: StreamingPaymentPayload,
+    pub requirement: StreamingPaymentRequirement,
+    /// Unix timestamp at which the stream was opened
+    pub opened_at: u64,
+    /// Amount already pulled from the allowance
+    pub charged: TokenAmount,
+}
+
+impl StreamState {
+    /// The allowance granted by the permit.
+    pub fn deposit(&self) -> U256 {
+        self.payload.permit.value.0
+    }
+
+    /// Unix timestamp at which the stream closes whatever the deposit.
+    pub fn expires_at(&self) -> u64 {
+        self.opened_at
+            .saturating_add(self.requirement.max_duration_seconds)
+    }
+
+    /// Amount owed for the stream up to `now`, capped by the deposit.
+    pub fn accrued(&self, now: u64) -> U256 {
+        let elapsed = now.min(self.expires_at()).saturating_sub(self.opened_at);
+        self.requirement
+            .rate_per_second
+            .0
+            .saturating_mul(U256::from(elapsed))
+            .min(self.deposit())
+    }
+
+    /// Accrued but not yet charged.
+    pub fn due(&self, now: u64) -> U256 {
+        self.accrued(now).saturating_sub(self.charged.0)
+    }
+
+    /// The deposit has been used up.
+    pub fn is_exhausted(&self, now: u64) -> bool {
+        self.accrued(now) >= self.deposit()
+    }
+
+    /// The stream has reached `maxDurationSeconds`.
+    pub fn is_expired(&self, now: u64) -> bool {
+        now >= self.expires_at()
+    }
+
+    /// The stream no longer grants access.
+    pub fn is_finished(&self, now: u64) -> bool {
+        self.is_exhausted(now) || self.is_expired(now)
+    }
+}
+
+/// Result of charging one stream in [`StreamManager::drain`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DrainOutcome {
+    pub stream_id: StreamId,
+    /// Amount charged in this drain
+    pub charged: TokenAmount,
+    pub transaction: Option<TransactionHash>,
+    /// The stream was finished and fully charged, and has been removed
+    pub closed: bool,
+    /// Why charging failed; the amount is retried on the next drain
+    pub error: Option<String>,
+}
+
+// ============================================================================
+// Errors
+// ============================================================================
+
+/// Errors from streaming payments.
+#[derive(Debug, Error)]
+pub enum StreamingError {
+    /// The allowance is not valid for this stream (bad signature, wrong spender, unfunded)
+    #[error("Invalid allowance: {0}")]
+    InvalidAllowance(String),
+
+    /// A stream with this id is already open
+    #[error("Stream {0} is already open")]
+    StreamExists(StreamId),
+
+    /// The system clock is before the Unix epoch
+    #[error("Clock error: {0}")]
+    Clock(#[from] std::time::SystemTimeError),
+
+    /// Submitting the permit or charging the allowance failed
+    #[error("Charge failed: {0}")]
+    Charge(String),
+}
+
+// ============================================================================
+// Traits
+// ============================================================================
+
+/// Verification and settlement of streaming payments.
+///
+/// The streaming counterpart of [`Facilitator`](crate::facilitator::Facilitator):
+/// settling opens a stream that is charged over time instead of moving funds once.
+pub trait StreamingFacilitator {
+    /// The error type returned by this facilitator.
+    type Error: Debug + Display;
+
+    /// Checks a streaming payload against the requirement, without opening the stream.
+    fn verify_streaming(
+        &self,
+        request: &StreamingVerifyRequest,
+    ) -> impl Future<Output = Result<VerifyResponse, Self::Error>> + Send;
+
+    /// Re-verifies the payload, submits the allowance and opens the stream.
+    fn settle_streaming(
+        &self,
+        request: &StreamingSettleRequest,
+    ) -> impl Future<Output = Result<StreamingSettleResponse, Self::Error>> + Send;
+}
+
+/// Chain side of streaming payments: checks and spends signed allowances.
+pub trait AllowanceCharger {
+    /// Checks that the permit is authentic, names this facilitator as spender, and that
+    /// the owner holds the deposit. Returns [`StreamingError::InvalidAllowance`] otherwise.
+    fn check_allowance(
+        &self,
+        payload: &StreamingPaymentPayload,
+        requirement: &StreamingPaymentRequirement,
+    ) -> impl Future<Output = Result<(), StreamingError>> + Send;
+
+    /// Submits the permit so the allowance can be spent after its deadline.
+    fn activate(
+        &self,
+        payload: &StreamingPaymentPayload,
+        requirement: &StreamingPaymentRequirement,
+    ) -> impl Future<Output = Result<Option<TransactionHash>, StreamingError>> + Send;
+
+    /// Moves `amount` from the permit owner to `requirement.pay_to`.
+    fn charge(
+        &self,
+        payload: &StreamingPaymentPayload,
+        requirement: &StreamingPaymentRequirement,
+        amount: TokenAmount,
+    ) -> impl Future<Output = Result<TransactionHash, StreamingError>> + Send;
+}
+
+/// [`AllowanceCharger`] handing each stream to the provider of its network.
+#[derive(Debug, Clone)]
+pub struct ProviderCharger<F> {
+    facilitator: F,
+}
+
+impl<F> ProviderCharger<F> {
+    pub fn new(facilitator: F) -> Self {
+        Self { facilitator }
+    }
+}
+
+impl<F> ProviderCharger<F>
+where
+    F: HasProviderMap,
+{
+    fn provider(
+        &self,
+        network: Network,
+    ) -> Result<&<F::Map as ProviderMap>::Value, StreamingError> {
+        self.facilitator
+            .provider_map()
+            .by_network(network)
+            .ok_or_else(|| StreamingError::InvalidAllowance("unsupported_network".to_string()))
+    }
+}
+
+impl<F> AllowanceCharger for ProviderCharger<F>
+where
+    F: HasProviderMap + Sync,
+    F::Map: Sync,
+    <F::Map as ProviderMap>::Value: AllowanceCharger + Sync,
+{
+    async fn check_allowance(
+        &self,
+        payload: &StreamingPaymentPayload,
+        requirement: &StreamingPaymentRequirement,
+    ) -> Result<(), StreamingError> {
+        self.provider(payload.network)?
+            .check_allowance(payload, requirement)
+            .await
+    }
+
+    async fn activate(
+        &self,
+        payload: &StreamingPaymentPayload,
+        requirement: &StreamingPaymentRequirement,
+    ) -> Result<Option<TransactionHash>, StreamingError> {
+        self.provider(payload.network)?
+            .activate(payload, requirement)
+            .await
+    }
+
+    async fn charge(
+        &self,
+        payload: &StreamingPaymentPayload,
+        requirement: &StreamingPaymentRequirement,
+        amount: TokenAmount,
+    ) -> Result<TransactionHash, StreamingError> {
+        self.provider(payload.network)?
+            .charge(payload, requirement, amount)
+            .await
+    }
+}
+
+// ============================================================================
+// Stream Manager
+// ============================================================================
+
+/// [`StreamingFacilitator`] keeping open streams in memory and charging them through
+/// an [`AllowanceCharger`].
+#[derive(Debug)]
+pub struct StreamManager<C> {
+    charger: C,
+    streams: Arc<RwLock<HashMap<StreamId, StreamState>>>,
+}
+
+impl<C> StreamManager<C> {
+    pub fn new(charger: C) -> Self {
+        Self {
+            charger,
+            streams: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    /// State of an open stream.
+    pub async fn stream(&self, id: &StreamId) -> Option<StreamState> {
+        self.streams.read().await.get(id).cloned()
+    }
+
+    /// Whether `id` is open and still grants access at `now`.
+    pub async fn is_active(&self, id: &StreamId, now: u64) -> bool {
+        self.streams
+            .read()
+            .await
+            .get(id)
+            .is_some_and(|stream| !stream.is_finished(now))
+    }
+
+    /// Number of open streams, finished but not yet fully charged ones included.
+    pub async fn count(&self) -> usize {
+        self.streams.read().await.len()
+    }
+}
+
+impl<C: AllowanceCharger + Sync> StreamManager<C> {
+    /// Checks a payload against the requirement at `now`.
+    ///
+    /// Term violations are reported as [`VerifyResponse::Invalid`]; only failures to
+    /// check the allowance are errors.
+    async fn verify_at(
+        &self,
+        request: &StreamingVerifyRequest,
+        now: u64,
+    ) -> Result<VerifyResponse, StreamingError> {
+        let StreamingVerifyRequest {
+            payload,
+            requirement,
+        } = request;
+        let payer = payload.payer();
+        let invalid = |reason: &str| {
+            Ok(VerifyResponse::invalid(
+                Some(payer.clone()),
+                FacilitatorErrorReason::FreeForm(reason.to_string()),
+            ))
+        };
+
+        if payload.network != requirement.network {
+            return Ok(VerifyResponse::invalid(
+                Some(payer),
+                FacilitatorErrorReason::InvalidNetwork,
+            ));
+        }
+        if requirement.rate_per_second.0.is_zero() || requirement.max_duration_seconds == 0 {
+            return invalid("invalid_streaming_terms");
+        }
+        if payload.permit.value.0 < requirement.min_deposit.0 {
+            return invalid("streaming_deposit_below_minimum");
+        }
+        if payload.permit.deadline.0 <= now {
+            return invalid("streaming_permit_expired");
+        }
+        if self.streams.read().await.contains_key(&payload.stream_id()) {
+            return invalid("streaming_stream_already_open");
+        }
+        match self.charger.check_allowance(payload, requirement).await {
+            Ok(()) => Ok(VerifyResponse::valid(payer)),
+            Err(StreamingError::InvalidAllowance(reason)) => invalid(&reason),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Opens the stream at `now`.
+    async fn settle_at(
+        &self,
+        request: &StreamingSettleRequest,
+        now: u64,
+    ) -> Result<StreamingSettleResponse, StreamingError> {
+        if let VerifyResponse::Invalid { reason, .. } = self.verify_at(request, now).await? {
+            return Err(StreamingError::InvalidAllowance(reason.to_string()));
+        }
+        let StreamingVerifyRequest {
+            payload,
+            requirement,
+        } = request;
+        let transaction = self.charger.activate(payload, requirement).await?;
+
+        let state = StreamState {
+            payload: payload.clone(),
+            requirement: requirement.clone(),
+            opened_at: now,
+            charged: TokenAmount::from(0u64),
+        };
+        let response = StreamingSettleResponse {
+            stream_id: payload.stream_id(),
+            payer: payload.payer(),
+            network: payload.network,
+            expires_at: UnixTimestamp(state.expires_at()),
+            transaction,
+        };
+        match self.streams.write().await.entry(response.stream_id) {
+            std::collections::hash_map::Entry::Occupied(_) => {
+                return Err(StreamingError::StreamExists(response.stream_id))
+            }
+            std::collections::hash_map::Entry::Vacant(entry) => {
+                entry.insert(state);
+            }
+        }
+        info!(
+            stream_id = %response.stream_id,
+            payer = %response.payer,
+            deposit = %payload.permit.value,
+            expires_at = %response.expires_at,
+            "Stream opened"
+        );
+        Ok(response)
+    }
+
+    /// Charge every stream what has accrued up to `now`, and remove finished streams
+    /// once they are fully charged.
+    ///
+    /// Charges run without holding the lock, so drains must not overlap; the task from
+    /// [`start_drain_task`] runs them one at a time.
+    pub async fn drain(&self, now: u64) -> Vec<DrainOutcome> {
+        let pending: Vec<(StreamId, StreamState, U256)> = self
+            .streams
+            .read()
+            .await
+            .iter()
+            .map(|(id, stream)| (*id, stream.clone(), stream.due(now)))
+            .collect();
+
+        let mut outcomes = Vec::with_capacity(pending.len());
+        for (stream_id, stream, due) in pending {
+            let mut outcome = DrainOutcome {
+                stream_id,
+                charged: TokenAmount::from(0u64),
+                transaction: None,
+                closed: false,
+                error: None,
+            };
+            if !due.is_zero() {
+                match self
+                    .charger
+                    .charge(&stream.payload, &stream.requirement, TokenAmount(due))
+                    .await
+                {
+                    Ok(transaction) => {
+                        outcome.charged = TokenAmount(due);
+                        outcome.transaction = Some(transaction);
+                    }
+                    Err(e) => {
+                        warn!(stream_id = %stream_id, error = %e, "Failed to charge stream");
+                        outcome.error = Some(e.to_string());
+                    }
+                }
+            }
+
+            let mut streams = self.streams.write().await;
+            if let Some(state) = streams.get_mut(&stream_id) {
+                state.charged = TokenAmount(state.charged.0 + outcome.charged.0);
+                if state.is_finished(now) && state.due(now).is_zero() {
+                    outcome.closed = true;
+                    info!(
+                        stream_id = %stream_id,
+                        charged = %state.charged,
+                        exhausted = state.is_exhausted(now),
+                        "Stream closed"
+                    );
+                    streams.remove(&stream_id);
+                }
+            }
+            outcomes.push(outcome);
+        }
+        outcomes
+    }
+}
+
+impl<C: AllowanceCharger + Sync> StreamingFacilitator for StreamManager<C> {
+    type Error = StreamingError;
+
+    async fn verify_streaming(
+        &self,
+        request: &StreamingVerifyRequest,
+    ) -> Result<VerifyResponse, StreamingError> {
+        self.verify_at(request, UnixTimestamp::try_now()?.0).await
+    }
+
+    async fn settle_streaming(
+        &self,
+        request: &StreamingSettleRequest,
+    ) -> Result<StreamingSettleResponse, StreamingError> {
+        self.settle_at(request, UnixTimestamp::try_now()?.0).await
+    }
+}
+
+/// Drain `manager` every `interval`.
+pub fn start_drain_task<C>(manager: Arc<StreamManager<C>>, interval: Duration) -> JoinHandle<()>
+where
+    C: AllowanceCharger + Send + Sync + 'static,
+{
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            let Ok(now) = UnixTimestamp::try_now() else {
+                continue;
+            };
+            let outcomes = manager.drain(now.0).await;
+            for outcome in outcomes.iter().filter(|o| !o.charged.0.is_zero()) {
+                debug!(
+                    stream_id = %outcome.stream_id,
+                    charged = %outcome.charged,
+                    transaction = ?outcome.transaction,
+                    closed = outcome.closed,
+                    "Stream charged"
+                );
+            }
+            let failed = outcomes.iter().filter(|o| o.error.is_some()).count();
+            if failed > 0 {
+                warn!(
+                    streams = outcomes.len(),
+                    failed, "Stream drain had failures"
+                );
+            }
+            debug!(open = manager.count().await, "Streams drained");
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    const NOW: u64 = 1_700_000_000;
+
+    /// Records charges; rejects owners other than `0x22..22`.
+    #[derive(Debug, Default)]
+    struct MockCharger {
+        charges: Mutex<Vec<u64>>,
+    }
+
+    impl AllowanceCharger for MockCharger {
+        async fn check_allowance(
+            &self,
+            payload: &StreamingPaymentPayload,
+            _requirement: &StreamingPaymentRequirement,
+        ) -> Result<(), StreamingError> {
+            if payload.permit.owner.0 != alloy::primitives::Address::repeat_byte(0x22) {
+                return Err(StreamingError::InvalidAllowance(
+                    "invalid_permit_signature".to_string(),
+                ));
+            }
+            Ok(())
+        }
+
+        async fn activate(
+            &self,
+            _payload: &StreamingPaymentPayload,
+            _requirement: &StreamingPaymentRequirement,
+        ) -> Result<Option<TransactionHash>, StreamingError> {
+            Ok(Some(TransactionHash::Evm([1; 32])))
+        }
+
+        async fn charge(
+            &self,
+            _payload: &StreamingPaymentPayload,
+            _requirement: &StreamingPaymentRequirement,
+            amount: TokenAmount,
+        ) -> Result<TransactionHash, StreamingError> {
+            self.charges.lock().unwrap().push(amount.0.to::<u64>());
+            Ok(TransactionHash::Evm([2; 32]))
+        }
+    }
+
+    fn request(rate: u64, deposit: u64, max_duration: u64) -> StreamingVerifyRequest {
+        let address = |byte| EvmAddress(alloy::primitives::Address::repeat_byte(byte));
+        StreamingVerifyRequest {
+            payload: StreamingPaymentPayload {
+                network: Network::BaseSepolia,
+                permit: Eip2612Permit {
+                    owner: address(0x22),
+                    spender: address(0x33),
+                    value: deposit.into(),
+                    nonce: 0u64.into(),
+                    deadline: UnixTimestamp(NOW + 600),
+                    signature: EvmSignature(vec![deposit as u8; 65]),
+                },
+            },
+            requirement: StreamingPaymentRequirement {
+                network: Network::BaseSepolia,
+                asset: MixedAddress::Evm(address(0x44)),
+                pay_to: MixedAddress::Evm(address(0x11)),
+                rate_per_second: rate.into(),
+                min_deposit: 50u64.into(),
+                max_duration_seconds: max_duration,
+            },
+        }
+    }
+
+    fn charged(outcomes: &[DrainOutcome]) -> Vec<(u64, bool)> {
+        outcomes
+            .iter()
+            .map(|o| (o.charged.0.to::<u64>(), o.closed))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_deposit_exhaustion_closes_stream() {
+        let manager = StreamManager::new(MockCharger::default());
+        let request = request(10, 100, 3600);
+        let opened = manager.settle_at(&request, NOW).await.unwrap();
+        assert_eq!(opened.expires_at, UnixTimestamp(NOW + 3600));
+        assert_eq!(opened.transaction, Some(TransactionHash::Evm([1; 32])));
+        let id = opened.stream_id;
+
+        assert_eq!(charged(&manager.drain(NOW + 4).await), vec![(40, false)]);
+        assert!(manager.is_active(&id, NOW + 4).await);
+        // Nothing new has accrued
+        assert_eq!(charged(&manager.drain(NOW + 4).await), vec![(0, false)]);
+
+        // 10/s for 20s is more than the deposit: only the remainder is charged
+        assert!(!manager.is_active(&id, NOW + 20).await);
+        assert_eq!(charged(&manager.drain(NOW + 20).await), vec![(60, true)]);
+        assert_eq!(*manager.charger.charges.lock().unwrap(), vec![40, 60]);
+        assert!(manager.stream(&id).await.is_none());
+        assert!(manager.drain(NOW + 30).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_stream_expires_after_max_duration() {
+        let manager = StreamManager::new(MockCharger::default());
+        let id = manager
+            .settle_at(&request(1, 1000, 30), NOW)
+            .await
+            .unwrap()
+            .stream_id;
+
+        let state = manager.stream(&id).await.unwrap();
+        assert!(!state.is_expired(NOW + 29));
+        assert!(state.is_expired(NOW + 30));
+        assert!(!state.is_exhausted(NOW + 30));
+
+        // Time past expiry is not charged
+        assert_eq!(charged(&manager.drain(NOW + 45).await), vec![(30, true)]);
+        assert!(!manager.is_active(&id, NOW + 45).await);
+        assert_eq!(manager.count().await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_verify_rejects_invalid_streams() {
+        let manager = StreamManager::new(MockCharger::default());
+        let reason = |response: VerifyResponse| match response {
+            VerifyResponse::Invalid { reason, .. } => reason.to_string(),
+            VerifyResponse::Valid { .. } => "valid".to_string(),
+        };
+
+        let valid = request(10, 100, 3600);
+        assert_eq!(
+            reason(manager.verify_at(&valid, NOW).await.unwrap()),
+            "valid"
+        );
+
+        let mut low = valid.clone();
+        low.payload.permit.value = 49u64.into();
+        assert_eq!(
+            reason(manager.verify_at(&low, NOW).await.unwrap()),
+            "streaming_deposit_below_minimum"
+        );
+        assert_eq!(
+            reason(manager.verify_at(&valid, NOW + 600).await.unwrap()),
+            "streaming_permit_expired"
+        );
+        let mut network = valid.clone();
+        network.payload.network = Network::Base;
+        assert_eq!(
+            reason(manager.verify_at(&network, NOW).await.unwrap()),
+            "invalid_network"
+        );
+        let mut owner = valid.clone();
+        owner.payload.permit.owner = EvmAddress(alloy::primitives::Address::repeat_byte(0x99));
+        assert_eq!(
+            reason(manager.verify_at(&owner, NOW).await.unwrap()),
+            "invalid_permit_signature"
+        );
+
+        // One permit opens one stream
+        manager.settle_at(&valid, NOW).await.unwrap();
+        assert_eq!(
+            reason(manager.verify_at(&valid, NOW).await.unwrap()),
+            "streaming_stream_already_open"
+        );
+        assert!(matches!(
+            manager.settle_at(&valid, NOW).await,
+            Err(StreamingError::InvalidAllowance(_))
+        ));
+        assert_eq!(manager.count().await, 1);
+    }
+}
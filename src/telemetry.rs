@@ -1,4 +1,5 @@
 use axum::http::{Request, Response};
+use crate::env_registry;
 use opentelemetry::trace::{Status, TracerProvider};
 use opentelemetry::{global, KeyValue, Value};
 use opentelemetry_sdk::{
@@ -11,7 +12,6 @@ use opentelemetry_semantic_conventions::{
     SCHEMA_URL,
 };
 use serde::{Deserialize, Serialize};
-use std::env;
 use std::time::Duration;
 use tower_http::trace::{MakeSpan, OnResponse, TraceLayer};
 use tracing::Span;
@@ -38,17 +38,17 @@ impl TelemetryProtocol {
     /// Returns `Some(TelemetryProtocol)` if telemetry is enabled, or `None` if
     /// no relevant environment variables are set.
     pub fn from_env() -> Option<Self> {
-        let is_enabled = env::var("OTEL_EXPORTER_OTLP_ENDPOINT").is_ok()
-            || env::var("OTEL_EXPORTER_OTLP_HEADERS").is_ok()
-            || env::var("OTEL_EXPORTER_OTLP_PROTOCOL").is_ok();
+        let is_enabled = env_registry::var("OTEL_EXPORTER_OTLP_ENDPOINT").is_some()
+            || env_registry::var("OTEL_EXPORTER_OTLP_HEADERS").is_some()
+            || env_registry::var("OTEL_EXPORTER_OTLP_PROTOCOL").is_some();
         if is_enabled {
-            let protocol = match env::var("OTEL_EXPORTER_OTLP_PROTOCOL") {
-                Ok(string) => match string.as_str() {
+            let protocol = match env_registry::var("OTEL_EXPORTER_OTLP_PROTOCOL") {
+                Some(string) => match string.as_str() {
                     "http/protobuf" | "http" => TelemetryProtocol::HTTP,
                     "grpc" => TelemetryProtocol::GRPC,
                     _ => TelemetryProtocol::HTTP,
                 },
-                Err(_) => TelemetryProtocol::HTTP,
+                None => TelemetryProtocol::HTTP,
             };
             Some(protocol)
         } else {
@@ -122,8 +122,7 @@ impl Telemetry {
     /// 1. `OTEL_SERVICE_NAME` env variable (if non-empty),
     /// 2. Otherwise, fallback to locally set value in `self.name`.
     pub fn name(&self) -> Option<Value> {
-        env::var("OTEL_SERVICE_NAME")
-            .ok()
+        env_registry::var("OTEL_SERVICE_NAME")
             .filter(|value| !value.trim().is_empty())
             .map(Value::from)
             .or_else(|| self.name.clone())
@@ -135,8 +134,7 @@ impl Telemetry {
     /// 1. `OTEL_SERVICE_VERSION` env variable (if non-empty),
     /// 2. Otherwise, fallback to locally set value in `self.version`.
     pub fn version(&self) -> Option<Value> {
-        env::var("OTEL_SERVICE_VERSION")
-            .ok()
+        env_registry::var("OTEL_SERVICE_VERSION")
             .filter(|value| !value.trim().is_empty())
             .map(Value::from)
             .or_else(|| self.version.clone())
@@ -148,8 +146,7 @@ impl Telemetry {
     /// 1. `OTEL_SERVICE_DEPLOYMENT` env variable (if non-empty),
     /// 2. Otherwise, fallback to locally set value in `self.deployment`.
     pub fn deployment(&self) -> Option<Value> {
-        env::var("OTEL_SERVICE_DEPLOYMENT")
-            .ok()
+        env_registry::var("OTEL_SERVICE_DEPLOYMENT")
             .filter(|value| !value.trim().is_empty())
             .map(Value::from)
             .or_else(|| self.deployment.clone())
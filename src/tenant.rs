@@ -0,0 +1,1573 @@
+//! Multi-tenant facilitator mode.
+//!
+//! One deployment can serve several business units ("tenants"), each with its own
+//! pay_to wallets, API keys, local discovery namespace, spend caps and webhook
+//! subscriptions. Tenants never see each other's data.
+//!
+//! # Architecture
+//!
+//! ```text
+//! request ──▶ attribution (X-API-Key ▸ Host ▸ path prefix) ──▶ TenantScope
+//!                                                                  │
+//!              ┌───────────────┬────────────────┬──────────────────┼──────────────────┐
+//!              ▼               ▼                ▼                  ▼                  ▼
+//!        ReceiptStore    SpendTracker   DiscoveryRegistry   WebhookDelivery    shared aggregated
+//!                                      (tenant namespace,  (tenant webhooks)     discovery
+//!                                       settlement stats)
+//! ```
+//!
+//! Isolation is enforced in the storage layer: every store keys its data by tenant id
+//! and its accessors require a [`TenantScope`], which can only be obtained by attributing
+//! a request to a tenant. [`tenant_scope_middleware`] attaches the caller's [`Access`] to
+//! every request, so the existing discovery, settlement and webhook handlers write to the
+//! caller's partition. Reading a tenant's data additionally requires the scope to be
+//! authenticated by one of the tenant's API keys; host and path attribution only identify
+//! whose payment is being processed. Cross-tenant queries go through
+//! [`Access::SuperAdmin`]. Aggregated public discovery stays shared by all tenants.
+//!
+//! # Configuration
+//!
+//! `TENANTS_CONFIG_FILE` points to a JSON file:
+//!
+//! ```json
+//! {
+//!   "tenants": [
+//!     {
+//!       "id": "payments",
+//!       "apiKeys": ["pk_live_..."],
+//!       "hosts": ["payments.facilitator.example"],
+//!       "pathPrefix": "/payments",
+//!       "allowedNetworks": ["base", "polygon"],
+//!       "payTo": ["0x..."],
+//!       "signer": { "mode": "shared" },
+//!       "quotas": { "dailySpendCaps": { "base": "100000000" }, "maxSubscriptions": 10 }
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! Tenants with `"signer": { "mode": "dedicated", "facilitatorUrl": "..." }` have their
+//! `/verify` and `/settle` calls forwarded to a facilitator holding their own keys.
+//!
+//! # Environment
+//!
+//! - `TENANTS_CONFIG_FILE` - Tenant definitions (single-tenant mode when unset)
+//! - `TENANT_SUPER_ADMIN_KEYS` - API keys allowed to query across tenants
+
+use axum::body::{to_bytes, Body};
+use axum::extract::{FromRequestParts, Query, Request, State};
+use axum::http::request::Parts;
+use axum::http::{HeaderMap, Method, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::RwLock;
+use tracing::{debug, info, warn};
+use url::Url;
+
+use crate::discovery::DiscoveryRegistry;
+use crate::network::Network;
+use crate::types::{MixedAddress, SettleResponse, TokenAmount, TransactionHash, VerifyRequest};
+
+/// Request header carrying a tenant or super-admin API key.
+pub const API_KEY_HEADER: &str = "X-API-Key";
+
+/// Largest `/verify` or `/settle` body buffered for tenant checks.
+const MAX_BODY_BYTES: usize = 1024 * 1024;
+
+/// Errors from tenant attribution and tenant-scoped storage.
+#[derive(Debug, thiserror::Error)]
+pub enum TenantError {
+    #[error("Failed to read tenant config: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Failed to parse tenant config: {0}")]
+    Parse(#[from] serde_json::Error),
+
+    #[error("Invalid tenant config: {0}")]
+    InvalidConfig(String),
+
+    /// Request could not be attributed to a tenant
+    #[error("Request is not attributed to a tenant")]
+    Unattributed,
+
+    /// Reading tenant data requires one of the tenant's API keys
+    #[error("Tenant API key required")]
+    Unauthenticated,
+
+    /// Access to another tenant's data without super-admin scope
+    #[error("Access to tenant {0} requires super-admin scope")]
+    Forbidden(String),
+
+    #[error("Unknown tenant: {0}")]
+    UnknownTenant(String),
+
+    #[error("Network {network} is not enabled for tenant {tenant}")]
+    NetworkNotAllowed { tenant: String, network: Network },
+
+    #[error("Recipient {pay_to} is not a wallet of tenant {tenant}")]
+    PayToNotAllowed { tenant: String, pay_to: String },
+
+    #[error("Tenant {tenant} exceeded its {quota} quota")]
+    QuotaExceeded { tenant: String, quota: String },
+}
+
+impl TenantError {
+    pub(crate) fn status(&self) -> StatusCode {
+        match self {
+            TenantError::Io(_) | TenantError::Parse(_) | TenantError::InvalidConfig(_) => {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+            TenantError::Unattributed | TenantError::Unauthenticated => StatusCode::UNAUTHORIZED,
+            TenantError::Forbidden(_)
+            | TenantError::NetworkNotAllowed { .. }
+            | TenantError::PayToNotAllowed { .. } => StatusCode::FORBIDDEN,
+            TenantError::UnknownTenant(_) => StatusCode::NOT_FOUND,
+            TenantError::QuotaExceeded { .. } => StatusCode::TOO_MANY_REQUESTS,
+        }
+    }
+}
+
+impl IntoResponse for TenantError {
+    fn into_response(self) -> Response {
+        (self.status(), Json(json!({ "error": self.to_string() }))).into_response()
+    }
+}
+
+// ============================================================================
+// Configuration
+// ============================================================================
+
+/// Tenant identifier.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct TenantId(pub String);
+
+impl std::fmt::Display for TenantId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Which keys sign a tenant's settlements.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "mode", rename_all = "camelCase")]
+pub enum TenantSigner {
+    /// The deployment's own signers
+    #[default]
+    Shared,
+    /// A separate facilitator holding the tenant's keys
+    #[serde(rename_all = "camelCase")]
+    Dedicated { facilitator_url: Url },
+}
+
+/// Per-tenant quota overrides.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TenantQuotas {
+    /// Settled volume per network per UTC day, in atomic units
+    #[serde(default)]
+    pub daily_spend_caps: HashMap<Network, TokenAmount>,
+    #[serde(default)]
+    pub max_subscriptions: Option<usize>,
+    #[serde(default)]
+    pub max_local_resources: Option<usize>,
+}
+
+/// A tenant definition.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TenantConfig {
+    pub id: TenantId,
+    #[serde(default)]
+    pub api_keys: Vec<String>,
+    /// Host names attributed to this tenant
+    #[serde(default)]
+    pub hosts: Vec<String>,
+    /// Path prefix under which the facilitator API is mounted for this tenant
+    #[serde(default)]
+    pub path_prefix: Option<String>,
+    /// Networks the tenant may use (all when empty)
+    #[serde(default)]
+    pub allowed_networks: Vec<Network>,
+    /// Recipient wallets the tenant may settle to (any when empty)
+    #[serde(default)]
+    pub pay_to: Vec<MixedAddress>,
+    #[serde(default)]
+    pub signer: TenantSigner,
+    #[serde(default)]
+    pub quotas: TenantQuotas,
+}
+
+impl TenantConfig {
+    fn matches_prefix(&self, path: &str) -> bool {
+        self.path_prefix.as_deref().is_some_and(|prefix| {
+            path.strip_prefix(prefix)
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+        })
+    }
+}
+
+/// All tenant definitions.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TenantsConfig {
+    pub tenants: Vec<TenantConfig>,
+}
+
+impl TenantsConfig {
+    pub fn from_file(path: &Path) -> Result<Self, TenantError> {
+        let config: Self = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Reject ambiguous attribution: duplicate ids, keys, hosts or prefixes.
+    pub fn validate(&self) -> Result<(), TenantError> {
+        let mut ids = HashSet::new();
+        let mut keys = HashSet::new();
+        let mut hosts = HashSet::new();
+        let mut prefixes = HashSet::new();
+        for tenant in &self.tenants {
+            if !ids.insert(&tenant.id) {
+                return Err(TenantError::InvalidConfig(format!(
+                    "duplicate tenant id {}",
+                    tenant.id
+                )));
+            }
+            if tenant.api_keys.iter().any(|k| !keys.insert(k)) {
+                return Err(TenantError::InvalidConfig(format!(
+                    "API key of {} is shared",
+                    tenant.id
+                )));
+            }
+            if tenant
+                .hosts
+                .iter()
+                .any(|h| !hosts.insert(h.to_ascii_lowercase()))
+            {
+                return Err(TenantError::InvalidConfig(format!(
+                    "host of {} is shared",
+                    tenant.id
+                )));
+            }
+            if let Some(prefix) = &tenant.path_prefix {
+                if !prefix.starts_with('/')
+                    || prefix.len() < 2
+                    || prefix.ends_with('/')
+                    || !prefixes.insert(prefix)
+                {
+                    return Err(TenantError::InvalidConfig(format!(
+                        "invalid path prefix {prefix}"
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Scopes
+// ============================================================================
+
+/// Proof that a request was attributed to a tenant.
+///
+/// Only [`TenantState`] hands these out, so storage accessors taking a scope cannot
+/// be pointed at another tenant's partition.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TenantScope {
+    tenant: TenantId,
+    authenticated: bool,
+    quotas: TenantQuotas,
+}
+
+impl TenantScope {
+    pub fn tenant(&self) -> &TenantId {
+        &self.tenant
+    }
+
+    /// Whether the scope came from one of the tenant's API keys.
+    pub fn is_authenticated(&self) -> bool {
+        self.authenticated
+    }
+
+    /// Quota overrides of the scope's tenant.
+    pub fn quotas(&self) -> &TenantQuotas {
+        &self.quotas
+    }
+
+    /// `QuotaExceeded` error for the scope's tenant.
+    pub(crate) fn quota_exceeded(&self, quota: &str) -> TenantError {
+        TenantError::QuotaExceeded {
+            tenant: self.tenant.0.clone(),
+            quota: quota.to_string(),
+        }
+    }
+
+    pub(crate) fn require_authenticated(&self) -> Result<(), TenantError> {
+        if self.authenticated {
+            Ok(())
+        } else {
+            Err(TenantError::Unauthenticated)
+        }
+    }
+}
+
+/// Caller identity for tenant and admin endpoints.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Access {
+    /// May query every tenant
+    SuperAdmin,
+    Tenant(TenantScope),
+}
+
+impl Access {
+    /// Tenants visible to this caller, narrowed by an optional filter.
+    ///
+    /// `None` means every tenant.
+    pub(crate) fn visible(&self, filter: Option<&TenantId>) -> Result<Option<TenantId>, TenantError> {
+        match (self, filter) {
+            (Access::SuperAdmin, filter) => Ok(filter.cloned()),
+            (Access::Tenant(scope), filter) => {
+                scope.require_authenticated()?;
+                match filter {
+                    Some(other) if other != scope.tenant() => {
+                        Err(TenantError::Forbidden(other.0.clone()))
+                    }
+                    _ => Ok(Some(scope.tenant.clone())),
+                }
+            }
+        }
+    }
+}
+
+// ============================================================================
+// Partitioned Storage
+// ============================================================================
+
+/// Storage partitioned by tenant id.
+///
+/// Each tenant owns an independent `V`; there is no accessor returning another
+/// tenant's partition for a given [`TenantScope`].
+pub struct TenantPartition<V> {
+    parts: std::sync::RwLock<HashMap<TenantId, Arc<V>>>,
+    make: fn() -> V,
+}
+
+impl<V> std::fmt::Debug for TenantPartition<V> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TenantPartition")
+            .field("tenants", &self.parts.read().unwrap().len())
+            .finish()
+    }
+}
+
+impl<V: Default> Default for TenantPartition<V> {
+    fn default() -> Self {
+        Self::with_constructor(V::default)
+    }
+}
+
+impl<V> TenantPartition<V> {
+    pub fn with_constructor(make: fn() -> V) -> Self {
+        Self {
+            parts: std::sync::RwLock::new(HashMap::new()),
+            make,
+        }
+    }
+
+    /// The partition of the scope's tenant.
+    pub fn get(&self, scope: &TenantScope) -> Arc<V> {
+        self.partition(&scope.tenant)
+    }
+
+    fn partition(&self, tenant: &TenantId) -> Arc<V> {
+        if let Some(part) = self.parts.read().unwrap().get(tenant) {
+            return Arc::clone(part);
+        }
+        let mut parts = self.parts.write().unwrap();
+        Arc::clone(
+            parts
+                .entry(tenant.clone())
+                .or_insert_with(|| Arc::new((self.make)())),
+        )
+    }
+
+    /// Partitions visible to `access`, optionally narrowed to one tenant.
+    pub fn visible(
+        &self,
+        access: &Access,
+        filter: Option<&TenantId>,
+    ) -> Result<Vec<(TenantId, Arc<V>)>, TenantError> {
+        match access.visible(filter)? {
+            Some(tenant) => Ok(vec![(tenant.clone(), self.partition(&tenant))]),
+            None => {
+                let mut all: Vec<_> = self
+                    .parts
+                    .read()
+                    .unwrap()
+                    .iter()
+                    .map(|(id, part)| (id.clone(), Arc::clone(part)))
+                    .collect();
+                all.sort_by(|a, b| a.0.cmp(&b.0));
+                Ok(all)
+            }
+        }
+    }
+}
+
+/// A settlement processed on behalf of a tenant.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Receipt {
+    pub network: Network,
+    pub payer: MixedAddress,
+    pub pay_to: MixedAddress,
+    pub amount: TokenAmount,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transaction: Option<TransactionHash>,
+    pub settled_at: u64,
+}
+
+/// Settlement receipts per tenant.
+#[derive(Debug, Default)]
+pub struct ReceiptStore {
+    receipts: TenantPartition<RwLock<Vec<Receipt>>>,
+}
+
+impl ReceiptStore {
+    pub async fn record(&self, scope: &TenantScope, receipt: Receipt) {
+        self.receipts.get(scope).write().await.push(receipt);
+    }
+
+    pub async fn list(&self, scope: &TenantScope) -> Result<Vec<Receipt>, TenantError> {
+        scope.require_authenticated()?;
+        Ok(self.receipts.get(scope).read().await.clone())
+    }
+
+    /// Receipts across the tenants visible to `access`.
+    pub async fn list_admin(
+        &self,
+        access: &Access,
+        tenant: Option<&TenantId>,
+    ) -> Result<Vec<(TenantId, Receipt)>, TenantError> {
+        let mut out = Vec::new();
+        for (id, part) in self.receipts.visible(access, tenant)? {
+            out.extend(part.read().await.iter().cloned().map(|r| (id.clone(), r)));
+        }
+        Ok(out)
+    }
+}
+
+#[derive(Debug, Default)]
+struct DailySpend {
+    day: u64,
+    /// Settled volume per network
+    settled: HashMap<Network, TokenAmount>,
+    /// Volume of settlements still in flight per network
+    reserved: HashMap<Network, TokenAmount>,
+}
+
+impl DailySpend {
+    /// Reset the totals when the UTC day has changed.
+    fn roll_over(&mut self, today: u64) {
+        if self.day != today {
+            *self = DailySpend {
+                day: today,
+                ..Default::default()
+            };
+        }
+    }
+}
+
+fn add(totals: &mut HashMap<Network, TokenAmount>, network: Network, amount: TokenAmount) {
+    let total = totals.entry(network).or_insert(TokenAmount::from(0u64));
+    *total = TokenAmount(total.0.saturating_add(amount.0));
+}
+
+fn sub(totals: &mut HashMap<Network, TokenAmount>, network: Network, amount: TokenAmount) {
+    if let Some(total) = totals.get_mut(&network) {
+        *total = TokenAmount(total.0.saturating_sub(amount.0));
+    }
+}
+
+/// Settled volume per tenant, network and UTC day.
+///
+/// A payment reserves its amount before it is settled, so concurrent settlements
+/// cannot together exceed a cap that each fits on its own.
+#[derive(Debug, Default)]
+pub struct SpendTracker {
+    spend: TenantPartition<Mutex<DailySpend>>,
+}
+
+impl SpendTracker {
+    /// Reserve `amount` on `network` if it fits within `cap` today, counting both
+    /// settled and reserved volume.
+    ///
+    /// The reservation is released when dropped unless [committed](SpendReservation::commit).
+    pub fn reserve(
+        &self,
+        scope: &TenantScope,
+        network: Network,
+        amount: TokenAmount,
+        cap: Option<TokenAmount>,
+    ) -> Result<SpendReservation, TenantError> {
+        let part = self.spend.get(scope);
+        let today = now_secs() / 86_400;
+        {
+            let mut spend = part.lock().unwrap();
+            spend.roll_over(today);
+            if let Some(cap) = cap {
+                let zero = TokenAmount::from(0u64);
+                let settled = spend.settled.get(&network).copied().unwrap_or(zero);
+                let reserved = spend.reserved.get(&network).copied().unwrap_or(zero);
+                if settled.0.saturating_add(reserved.0).saturating_add(amount.0) > cap.0 {
+                    return Err(scope.quota_exceeded(&format!("{network} daily spend")));
+                }
+            }
+            add(&mut spend.reserved, network, amount);
+        }
+        Ok(SpendReservation {
+            part,
+            day: today,
+            network,
+            amount,
+            committed: false,
+        })
+    }
+
+    /// Volume settled today on `network`.
+    pub fn spent(&self, scope: &TenantScope, network: Network) -> TokenAmount {
+        self.today(scope)
+            .get(&network)
+            .copied()
+            .unwrap_or(TokenAmount::from(0u64))
+    }
+
+    /// Volume settled today per network.
+    pub fn today(&self, scope: &TenantScope) -> HashMap<Network, TokenAmount> {
+        let part = self.spend.get(scope);
+        let spend = part.lock().unwrap();
+        if spend.day != now_secs() / 86_400 {
+            return HashMap::new();
+        }
+        spend.settled.clone()
+    }
+}
+
+/// Volume held against a tenant's daily cap while its settlement is in flight.
+#[derive(Debug)]
+pub struct SpendReservation {
+    part: Arc<Mutex<DailySpend>>,
+    day: u64,
+    network: Network,
+    amount: TokenAmount,
+    committed: bool,
+}
+
+impl SpendReservation {
+    /// Count the reserved volume as settled.
+    pub fn commit(mut self) {
+        let mut spend = self.part.lock().unwrap();
+        if spend.day == self.day {
+            sub(&mut spend.reserved, self.network, self.amount);
+        }
+        // Settled today, even if reserved before midnight
+        spend.roll_over(now_secs() / 86_400);
+        add(&mut spend.settled, self.network, self.amount);
+        self.committed = true;
+    }
+}
+
+impl Drop for SpendReservation {
+    fn drop(&mut self) {
+        if self.committed {
+            return;
+        }
+        let mut spend = self.part.lock().unwrap();
+        if spend.day == self.day {
+            sub(&mut spend.reserved, self.network, self.amount);
+        }
+    }
+}
+
+// ============================================================================
+// Tenant State
+// ============================================================================
+
+/// Tenant definitions and the tenant-only stores.
+///
+/// Discovery resources, settlement statistics and webhooks live in the shared
+/// [`DiscoveryRegistry`] and [`WebhookDelivery`](crate::webhook::WebhookDelivery),
+/// keyed by tenant there.
+#[derive(Debug)]
+pub struct TenantState {
+    tenants: HashMap<TenantId, TenantConfig>,
+    super_admin_keys: HashSet<String>,
+    pub receipts: ReceiptStore,
+    pub spend: SpendTracker,
+    pub discovery: Arc<DiscoveryRegistry>,
+    client: reqwest::Client,
+}
+
+impl TenantState {
+    pub fn new(
+        config: TenantsConfig,
+        super_admin_keys: HashSet<String>,
+        discovery: Arc<DiscoveryRegistry>,
+    ) -> Self {
+        Self {
+            tenants: config
+                .tenants
+                .into_iter()
+                .map(|t| (t.id.clone(), t))
+                .collect(),
+            super_admin_keys,
+            receipts: ReceiptStore::default(),
+            spend: SpendTracker::default(),
+            discovery,
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(90))
+                .build()
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Load from `TENANTS_CONFIG_FILE`; `None` in single-tenant mode.
+    pub fn from_env(discovery: Arc<DiscoveryRegistry>) -> Result<Option<Self>, TenantError> {
+        let Some(path) = crate::env_registry::var("TENANTS_CONFIG_FILE") else {
+            return Ok(None);
+        };
+        let config = TenantsConfig::from_file(Path::new(&path))?;
+        let super_admin_keys: HashSet<String> = crate::env_registry::var("TENANT_SUPER_ADMIN_KEYS")
+            .map(|v| {
+                v.split(',')
+                    .map(|k| k.trim().to_string())
+                    .filter(|k| !k.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default();
+        info!(
+            path = %path,
+            tenants = config.tenants.len(),
+            super_admins = super_admin_keys.len(),
+            "Multi-tenant mode enabled"
+        );
+        Ok(Some(Self::new(config, super_admin_keys, discovery)))
+    }
+
+    pub fn tenant(&self, id: &TenantId) -> Option<&TenantConfig> {
+        self.tenants.get(id)
+    }
+
+    /// Path prefixes under which the facilitator API is mounted per tenant.
+    pub fn path_prefixes(&self) -> Vec<String> {
+        let mut prefixes: Vec<String> = self
+            .tenants
+            .values()
+            .filter_map(|t| t.path_prefix.clone())
+            .collect();
+        prefixes.sort();
+        prefixes
+    }
+
+    /// Attribute a request: API key first, then Host header, then path prefix.
+    ///
+    /// Returns `Ok(None)` for requests not addressed to any tenant.
+    pub fn attribute(
+        &self,
+        headers: &HeaderMap,
+        path: &str,
+    ) -> Result<Option<Access>, TenantError> {
+        if let Some(key) = headers.get(API_KEY_HEADER).and_then(|v| v.to_str().ok()) {
+            if self.super_admin_keys.contains(key) {
+                return Ok(Some(Access::SuperAdmin));
+            }
+            if let Some(tenant) = self
+                .tenants
+                .values()
+                .find(|t| t.api_keys.iter().any(|k| k == key))
+            {
+                return Ok(Some(Access::Tenant(TenantScope {
+                    tenant: tenant.id.clone(),
+                    authenticated: true,
+                    quotas: tenant.quotas.clone(),
+                })));
+            }
+        }
+        let host = headers
+            .get(axum::http::header::HOST)
+            .and_then(|v| v.to_str().ok())
+            .map(|h| h.split(':').next().unwrap_or(h).to_ascii_lowercase());
+        let tenant = self
+            .tenants
+            .values()
+            .find(|t| {
+                host.as_ref()
+                    .is_some_and(|host| t.hosts.iter().any(|h| h.eq_ignore_ascii_case(host)))
+            })
+            .or_else(|| self.tenants.values().find(|t| t.matches_prefix(path)));
+        Ok(tenant.map(|t| {
+            Access::Tenant(TenantScope {
+                tenant: t.id.clone(),
+                authenticated: false,
+                quotas: t.quotas.clone(),
+            })
+        }))
+    }
+
+    /// Check a payment against the tenant's networks, wallets and spend caps, and
+    /// reserve its amount against the cap.
+    ///
+    /// The amount is the one the payer signed, since that is what settles; the declared
+    /// `maxAmountRequired` may be lower. A payload the amount cannot be read from is
+    /// rejected when the network is capped.
+    ///
+    /// Commit the reservation through [`record_settlement`](Self::record_settlement)
+    /// once settled; dropping it releases the amount.
+    pub fn authorize_payment(
+        &self,
+        scope: &TenantScope,
+        request: &VerifyRequest,
+    ) -> Result<SpendReservation, TenantError> {
+        let tenant = self
+            .tenant(&scope.tenant)
+            .ok_or_else(|| TenantError::UnknownTenant(scope.tenant.0.clone()))?;
+        let requirements = &request.payment_requirements;
+        if !tenant.allowed_networks.is_empty()
+            && !tenant.allowed_networks.contains(&requirements.network)
+        {
+            return Err(TenantError::NetworkNotAllowed {
+                tenant: tenant.id.0.clone(),
+                network: requirements.network,
+            });
+        }
+        if !tenant.pay_to.is_empty() && !tenant.pay_to.contains(&requirements.pay_to) {
+            return Err(TenantError::PayToNotAllowed {
+                tenant: tenant.id.0.clone(),
+                pay_to: requirements.pay_to.to_string(),
+            });
+        }
+        let cap = tenant
+            .quotas
+            .daily_spend_caps
+            .get(&requirements.network)
+            .copied();
+        let amount = match crate::chain::signed_amount(request) {
+            Some(amount) => amount,
+            None if cap.is_some() => {
+                return Err(scope.quota_exceeded(&format!("{} daily spend", requirements.network)))
+            }
+            None => requirements.max_amount_required,
+        };
+        self.spend.reserve(scope, requirements.network, amount, cap)
+    }
+
+    /// Record a successful settlement in the tenant's receipts and commit its spend.
+    ///
+    /// A failed settlement releases the reservation instead.
+    pub async fn record_settlement(
+        &self,
+        scope: &TenantScope,
+        request: &VerifyRequest,
+        response: &SettleResponse,
+        reservation: SpendReservation,
+    ) {
+        if !response.success {
+            return;
+        }
+        let requirements = &request.payment_requirements;
+        let amount = reservation.amount;
+        reservation.commit();
+        self.receipts
+            .record(
+                scope,
+                Receipt {
+                    network: response.network,
+                    payer: response.payer.clone(),
+                    pay_to: requirements.pay_to.clone(),
+                    amount,
+                    transaction: response.transaction.clone(),
+                    settled_at: now_secs(),
+                },
+            )
+            .await;
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+// ============================================================================
+// HTTP
+// ============================================================================
+
+impl FromRequestParts<Arc<TenantState>> for Access {
+    type Rejection = TenantError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &Arc<TenantState>,
+    ) -> Result<Self, Self::Rejection> {
+        state
+            .attribute(&parts.headers, parts.uri.path())?
+            .ok_or(TenantError::Unattributed)
+    }
+}
+
+impl Access {
+    fn scope(self) -> Result<TenantScope, TenantError> {
+        match self {
+            Access::Tenant(scope) => Ok(scope),
+            // Super-admins act on tenants through the admin endpoints
+            Access::SuperAdmin => Err(TenantError::Unattributed),
+        }
+    }
+}
+
+/// Tenant and tenant-admin endpoints.
+pub fn routes() -> Router<Arc<TenantState>> {
+    Router::new()
+        .route("/tenant/receipts", get(get_receipts))
+        .route("/tenant/usage", get(get_usage))
+        .route("/tenant/discovery/resources", get(get_discovery))
+        .route("/admin/tenants/receipts", get(get_admin_receipts))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TenantFilter {
+    pub tenant: Option<TenantId>,
+}
+
+async fn get_receipts(
+    State(state): State<Arc<TenantState>>,
+    access: Access,
+) -> Result<Response, TenantError> {
+    let scope = access.scope()?;
+    Ok(
+        Json(json!({ "tenant": scope.tenant(), "receipts": state.receipts.list(&scope).await? }))
+            .into_response(),
+    )
+}
+
+async fn get_usage(
+    State(state): State<Arc<TenantState>>,
+    access: Access,
+) -> Result<Response, TenantError> {
+    let scope = access.scope()?;
+    scope.require_authenticated()?;
+    let quotas = state
+        .tenant(scope.tenant())
+        .map(|t| t.quotas.clone())
+        .unwrap_or_default();
+    Ok(Json(json!({
+        "tenant": scope.tenant(),
+        "settlements": state.receipts.list(&scope).await?.len(),
+        "spentToday": state.spend.today(&scope),
+        "quotas": quotas,
+    }))
+    .into_response())
+}
+
+async fn get_discovery(
+    State(state): State<Arc<TenantState>>,
+    access: Access,
+) -> Result<Response, TenantError> {
+    let scope = access.scope()?;
+    Ok(Json(json!({ "items": state.discovery.list_scoped(&scope).await? })).into_response())
+}
+
+async fn get_admin_receipts(
+    State(state): State<Arc<TenantState>>,
+    access: Access,
+    Query(filter): Query<TenantFilter>,
+) -> Result<Response, TenantError> {
+    let receipts = state
+        .receipts
+        .list_admin(&access, filter.tenant.as_ref())
+        .await?;
+    let items: Vec<_> = receipts
+        .into_iter()
+        .map(|(tenant, receipt)| json!({ "tenant": tenant, "receipt": receipt }))
+        .collect();
+    Ok(Json(json!({ "items": items })).into_response())
+}
+
+/// Attach the caller's [`Access`] to every request addressed to a tenant.
+///
+/// The discovery, settlement and webhook handlers read it as an extension to key their
+/// data by tenant, so this layer must wrap them and [`tenant_payment_middleware`].
+pub async fn tenant_scope_middleware(
+    State(state): State<Arc<TenantState>>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    match state.attribute(request.headers(), request.uri().path()) {
+        Ok(Some(access)) => {
+            request.extensions_mut().insert(access);
+        }
+        Ok(None) => {}
+        Err(e) => return e.into_response(),
+    }
+    next.run(request).await
+}
+
+/// Enforce the tenant's policy on `/verify` and `/settle` calls.
+///
+/// Unattributed requests pass through unchanged. Attributed ones (see
+/// [`tenant_scope_middleware`]) are checked against the tenant's networks, wallets and
+/// spend caps, forwarded to a dedicated facilitator when configured, and successful
+/// settlements are recorded in the tenant's partition. A settlement holds its amount
+/// against the spend cap until it completes.
+pub async fn tenant_payment_middleware(
+    State(state): State<Arc<TenantState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let path = request.uri().path();
+    let operation = ["/verify", "/settle"]
+        .into_iter()
+        .find(|op| path.ends_with(op));
+    let (Some(operation), true) = (operation, request.method() == Method::POST) else {
+        return next.run(request).await;
+    };
+    let Some(Access::Tenant(scope)) = request.extensions().get::<Access>().cloned() else {
+        return next.run(request).await;
+    };
+
+    let (parts, body) = request.into_parts();
+    let Ok(bytes) = to_bytes(body, MAX_BODY_BYTES).await else {
+        return (
+            StatusCode::PAYLOAD_TOO_LARGE,
+            Json(json!({ "error": "Request body too large" })),
+        )
+            .into_response();
+    };
+    let Ok(payment) = serde_json::from_slice::<VerifyRequest>(&bytes) else {
+        // Let the handler produce its usual validation error
+        return next
+            .run(Request::from_parts(parts, Body::from(bytes)))
+            .await;
+    };
+    let reservation = match state.authorize_payment(&scope, &payment) {
+        Ok(reservation) => reservation,
+        Err(e) => {
+            warn!(tenant = %scope.tenant(), error = %e, "Tenant payment rejected");
+            return e.into_response();
+        }
+    };
+    // A verification only checks the cap
+    let reservation = (operation == "/settle").then_some(reservation);
+
+    let dedicated = state.tenant(scope.tenant()).and_then(|t| match &t.signer {
+        TenantSigner::Dedicated { facilitator_url } => Some(facilitator_url.clone()),
+        TenantSigner::Shared => None,
+    });
+    let (status, body) = match dedicated {
+        Some(base) => match forward(&state.client, &base, operation, bytes).await {
+            Ok(result) => result,
+            Err(e) => {
+                warn!(tenant = %scope.tenant(), error = %e, "Dedicated facilitator unreachable");
+                return (
+                    StatusCode::BAD_GATEWAY,
+                    Json(json!({ "error": "Tenant facilitator unreachable" })),
+                )
+                    .into_response();
+            }
+        },
+        None => {
+            let response = next
+                .run(Request::from_parts(parts, Body::from(bytes)))
+                .await;
+            let (parts, body) = response.into_parts();
+            match to_bytes(body, usize::MAX).await {
+                Ok(bytes) if operation == "/settle" => (parts.status, bytes),
+                Ok(bytes) => return Response::from_parts(parts, Body::from(bytes)),
+                Err(_) => return StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+            }
+        }
+    };
+
+    if let (Some(reservation), true) = (reservation, status.is_success()) {
+        if let Ok(settlement) = serde_json::from_slice::<SettleResponse>(&body) {
+            state
+                .record_settlement(&scope, &payment, &settlement, reservation)
+                .await;
+            debug!(tenant = %scope.tenant(), success = settlement.success, "Recorded tenant settlement");
+        }
+    }
+    (
+        status,
+        [(axum::http::header::CONTENT_TYPE, "application/json")],
+        body,
+    )
+        .into_response()
+}
+
+async fn forward(
+    client: &reqwest::Client,
+    base: &Url,
+    operation: &str,
+    body: axum::body::Bytes,
+) -> Result<(StatusCode, axum::body::Bytes), reqwest::Error> {
+    let url = format!("{}{}", base.as_str().trim_end_matches('/'), operation);
+    let response = client
+        .post(url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .body(body)
+        .send()
+        .await?;
+    let status =
+        StatusCode::from_u16(response.status().as_u16()).unwrap_or(StatusCode::BAD_GATEWAY);
+    Ok((status, response.bytes().await?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::caip2::Caip2NetworkId;
+    use crate::types::{PaymentPayload, PaymentRequirements, Scheme, X402Version};
+    use crate::types_v2::{DiscoveryResource, DiscoverySource, PaymentRequirementsV2};
+    use crate::webhook::{WebhookConfig, WebhookDelivery, WebhookError};
+
+    const WALLET_A: &str = "0x1111111111111111111111111111111111111111";
+    const WALLET_B: &str = "0x2222222222222222222222222222222222222222";
+
+    fn config() -> TenantsConfig {
+        serde_json::from_value(json!({
+            "tenants": [
+                {
+                    "id": "a",
+                    "apiKeys": ["key-a"],
+                    "hosts": ["a.example"],
+                    "allowedNetworks": ["base-sepolia"],
+                    "payTo": [WALLET_A],
+                    "quotas": {
+                        "dailySpendCaps": { "base-sepolia": "1500" },
+                        "maxSubscriptions": 1,
+                        "maxLocalResources": 1
+                    }
+                },
+                { "id": "b", "apiKeys": ["key-b"], "pathPrefix": "/b" }
+            ]
+        }))
+        .unwrap()
+    }
+
+    fn state() -> TenantState {
+        let config = config();
+        config.validate().unwrap();
+        TenantState::new(
+            config,
+            HashSet::from(["root".to_string()]),
+            Arc::new(DiscoveryRegistry::new()),
+        )
+    }
+
+    fn with_key(key: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(API_KEY_HEADER, key.parse().unwrap());
+        headers
+    }
+
+    fn scope(state: &TenantState, key: &str) -> TenantScope {
+        match state.attribute(&with_key(key), "/").unwrap() {
+            Some(Access::Tenant(scope)) => scope,
+            other => panic!("unexpected attribution {other:?}"),
+        }
+    }
+
+    fn resource(url: &str, source: DiscoverySource) -> DiscoveryResource {
+        let accepts = vec![PaymentRequirementsV2 {
+            scheme: Scheme::Exact,
+            network: Caip2NetworkId::eip155(8453),
+            asset: MixedAddress::Evm(
+                "0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913"
+                    .parse()
+                    .unwrap(),
+            ),
+            amount: TokenAmount::from(1000u64),
+            pay_to: MixedAddress::Evm(WALLET_A.parse().unwrap()),
+            max_timeout_seconds: 300,
+            extra: None,
+        }];
+        let mut resource = DiscoveryResource::new(
+            Url::parse(url).unwrap(),
+            "http".into(),
+            "Test".into(),
+            accepts,
+        );
+        resource.source = source;
+        resource
+    }
+
+    fn payment(amount: u64) -> VerifyRequest {
+        let payload: PaymentPayload = serde_json::from_value(json!({
+            "x402Version": 1,
+            "scheme": "exact",
+            "network": "base-sepolia",
+            "payload": {
+                "signature": format!("0x{}", "00".repeat(65)),
+                "authorization": {
+                    "from": WALLET_B, "to": WALLET_A, "value": amount.to_string(),
+                    "validAfter": "0", "validBefore": "9999999999",
+                    "nonce": format!("0x{}", "11".repeat(32))
+                }
+            }
+        }))
+        .unwrap();
+        VerifyRequest {
+            x402_version: X402Version::V1,
+            payment_payload: payload,
+            payment_requirements: PaymentRequirements {
+                scheme: Scheme::Exact,
+                network: Network::BaseSepolia,
+                max_amount_required: TokenAmount::from(amount),
+                resource: Url::parse("https://api.example/data").unwrap(),
+                description: String::new(),
+                mime_type: "application/json".into(),
+                output_schema: None,
+                pay_to: MixedAddress::Evm(WALLET_A.parse().unwrap()),
+                max_timeout_seconds: 60,
+                asset: MixedAddress::Evm(
+                    "0x036CbD53842c5426634e7929541eC2318f3dCF7e"
+                        .parse()
+                        .unwrap(),
+                ),
+                extra: None,
+            },
+        }
+    }
+
+    fn settled() -> SettleResponse {
+        SettleResponse {
+            success: true,
+            error_reason: None,
+            payer: MixedAddress::Evm(WALLET_B.parse().unwrap()),
+            transaction: Some(TransactionHash::Evm([9u8; 32])),
+            network: Network::BaseSepolia,
+            proof_of_payment: None,
+            confirmation: None,
+        }
+    }
+
+    #[test]
+    fn test_attribution_by_key_host_and_prefix() {
+        let state = state();
+        assert_eq!(
+            state.attribute(&with_key("root"), "/").unwrap(),
+            Some(Access::SuperAdmin)
+        );
+        assert!(scope(&state, "key-a").is_authenticated());
+
+        let mut headers = HeaderMap::new();
+        headers.insert(axum::http::header::HOST, "A.example:8080".parse().unwrap());
+        let Some(Access::Tenant(by_host)) = state.attribute(&headers, "/settle").unwrap() else {
+            panic!("host not attributed");
+        };
+        assert_eq!(by_host.tenant().0, "a");
+        assert!(!by_host.is_authenticated());
+
+        let Some(Access::Tenant(by_path)) =
+            state.attribute(&HeaderMap::new(), "/b/settle").unwrap()
+        else {
+            panic!("prefix not attributed");
+        };
+        assert_eq!(by_path.tenant().0, "b");
+        assert_eq!(
+            state.attribute(&HeaderMap::new(), "/bb/settle").unwrap(),
+            None
+        );
+        assert_eq!(
+            state.attribute(&with_key("unknown"), "/settle").unwrap(),
+            None
+        );
+    }
+
+    async fn settle(state: &TenantState, scope: &TenantScope, amount: u64) {
+        let reservation = state.authorize_payment(scope, &payment(amount)).unwrap();
+        state
+            .record_settlement(scope, &payment(amount), &settled(), reservation)
+            .await;
+    }
+
+    fn hook(url: &str) -> WebhookConfig {
+        WebhookConfig {
+            url: url.to_string(),
+            secret: "whsec_test".to_string(),
+            retry_attempts: 0,
+            retry_backoff_ms: 1,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_tenant_data_is_invisible_to_other_tenants() {
+        let state = state();
+        let webhooks = WebhookDelivery::default();
+        let (a, b) = (scope(&state, "key-a"), scope(&state, "key-b"));
+
+        settle(&state, &a, 1000).await;
+        webhooks
+            .register(&Access::Tenant(a.clone()), hook("https://a.example/hook"))
+            .await
+            .unwrap();
+        state
+            .discovery
+            .register_scoped(
+                &a,
+                resource("https://a.example/private", DiscoverySource::SelfRegistered),
+            )
+            .await
+            .unwrap();
+        state
+            .discovery
+            .track_settlement_scoped(
+                &a,
+                resource("https://a.example/paid", DiscoverySource::Settlement),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(state.receipts.list(&a).await.unwrap().len(), 1);
+        let own = Access::Tenant(a.clone());
+        assert_eq!(webhooks.list(&own, None).await.unwrap().len(), 1);
+        assert_eq!(state.discovery.list_scoped(&a).await.unwrap().len(), 2);
+
+        let other = Access::Tenant(b.clone());
+        assert!(state.receipts.list(&b).await.unwrap().is_empty());
+        assert!(webhooks.list(&other, None).await.unwrap().is_empty());
+        assert!(state.discovery.list_scoped(&b).await.unwrap().is_empty());
+        // Nor do they reach the public registry
+        assert_eq!(state.discovery.count().await, 0);
+
+        // Tenant B cannot reach A through the admin filter either
+        let forbidden = state
+            .receipts
+            .list_admin(&other, Some(&TenantId("a".into())))
+            .await;
+        assert!(matches!(forbidden, Err(TenantError::Forbidden(_))));
+        let forbidden = webhooks.list(&other, Some(&TenantId("a".into()))).await;
+        assert!(matches!(
+            forbidden,
+            Err(WebhookError::Tenant(TenantError::Forbidden(_)))
+        ));
+
+        // Host/path attribution alone does not authorize reads
+        let mut headers = HeaderMap::new();
+        headers.insert(axum::http::header::HOST, "a.example".parse().unwrap());
+        let Some(Access::Tenant(unauthenticated)) = state.attribute(&headers, "/").unwrap() else {
+            panic!("host not attributed");
+        };
+        assert!(matches!(
+            state.receipts.list(&unauthenticated).await,
+            Err(TenantError::Unauthenticated)
+        ));
+        assert!(matches!(
+            state.discovery.list_scoped(&unauthenticated).await,
+            Err(TenantError::Unauthenticated)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_quotas_are_isolated_per_tenant() {
+        let state = state();
+        let (a, b) = (scope(&state, "key-a"), scope(&state, "key-b"));
+
+        settle(&state, &a, 1000).await;
+        assert!(matches!(
+            state.authorize_payment(&a, &payment(1000)),
+            Err(TenantError::QuotaExceeded { .. })
+        ));
+        // B has no cap and A's spend does not count against it
+        drop(state.authorize_payment(&b, &payment(1000)).unwrap());
+        assert_eq!(
+            state.spend.spent(&b, Network::BaseSepolia),
+            TokenAmount::from(0u64)
+        );
+
+        let webhooks = WebhookDelivery::default();
+        let (own_a, own_b) = (Access::Tenant(a.clone()), Access::Tenant(b.clone()));
+        webhooks
+            .register(&own_a, hook("https://hooks.example/a"))
+            .await
+            .unwrap();
+        assert!(matches!(
+            webhooks.register(&own_a, hook("https://hooks.example/a")).await,
+            Err(WebhookError::Tenant(TenantError::QuotaExceeded { .. }))
+        ));
+        for _ in 0..2 {
+            webhooks
+                .register(&own_b, hook("https://hooks.example/b"))
+                .await
+                .unwrap();
+        }
+
+        state
+            .discovery
+            .register_scoped(&a, resource("https://a.example/1", DiscoverySource::SelfRegistered))
+            .await
+            .unwrap();
+        assert!(matches!(
+            state
+                .discovery
+                .register_scoped(&a, resource("https://a.example/2", DiscoverySource::SelfRegistered))
+                .await,
+            Err(crate::discovery::DiscoveryError::Tenant(TenantError::QuotaExceeded { .. }))
+        ));
+        for url in ["https://b.example/1", "https://b.example/2"] {
+            state
+                .discovery
+                .register_scoped(&b, resource(url, DiscoverySource::SelfRegistered))
+                .await
+                .unwrap();
+        }
+
+        // Network and wallet restrictions apply to A only
+        let mut other_wallet = payment(1);
+        other_wallet.payment_requirements.pay_to = MixedAddress::Evm(WALLET_B.parse().unwrap());
+        assert!(matches!(
+            state.authorize_payment(&a, &other_wallet),
+            Err(TenantError::PayToNotAllowed { .. })
+        ));
+        drop(state.authorize_payment(&b, &other_wallet).unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_spend_cap_is_reserved_until_settlement_completes() {
+        let state = state();
+        let a = scope(&state, "key-a");
+
+        // Two in-flight settlements cannot together exceed the 1500 cap
+        let first = state.authorize_payment(&a, &payment(1000)).unwrap();
+        assert!(matches!(
+            state.authorize_payment(&a, &payment(1000)),
+            Err(TenantError::QuotaExceeded { .. })
+        ));
+
+        // A failed settlement releases its reservation
+        let failed = SettleResponse {
+            success: false,
+            ..settled()
+        };
+        state
+            .record_settlement(&a, &payment(1000), &failed, first)
+            .await;
+        assert_eq!(
+            state.spend.spent(&a, Network::BaseSepolia),
+            TokenAmount::from(0u64)
+        );
+
+        // So does an abandoned one
+        drop(state.authorize_payment(&a, &payment(1000)).unwrap());
+        settle(&state, &a, 1000).await;
+        assert_eq!(
+            state.spend.spent(&a, Network::BaseSepolia),
+            TokenAmount::from(1000u64)
+        );
+        drop(state.authorize_payment(&a, &payment(500)).unwrap());
+        assert!(state.authorize_payment(&a, &payment(501)).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_spend_cap_counts_the_signed_amount() {
+        let state = state();
+        let a = scope(&state, "key-a");
+        let underdeclared = |declared: u64, signed: u64| {
+            let mut request = payment(signed);
+            request.payment_requirements.max_amount_required = TokenAmount::from(declared);
+            request
+        };
+
+        // Declaring less than the 1500 cap does not hide a larger signed amount
+        assert!(matches!(
+            state.authorize_payment(&a, &underdeclared(100, 2000)),
+            Err(TenantError::QuotaExceeded { .. })
+        ));
+
+        let request = underdeclared(1, 1000);
+        let reservation = state.authorize_payment(&a, &request).unwrap();
+        state
+            .record_settlement(&a, &request, &settled(), reservation)
+            .await;
+        assert_eq!(
+            state.spend.spent(&a, Network::BaseSepolia),
+            TokenAmount::from(1000u64)
+        );
+        assert!(state.authorize_payment(&a, &underdeclared(1, 501)).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_shared_aggregated_resources_visible_to_all() {
+        let shared = Arc::new(DiscoveryRegistry::new());
+        shared
+            .register(resource(
+                "https://aggregated.example/api",
+                DiscoverySource::Aggregated,
+            ))
+            .await
+            .unwrap();
+        // Resources self-registered on the public registry are not tenant-shared
+        shared
+            .register(resource(
+                "https://public.example/api",
+                DiscoverySource::SelfRegistered,
+            ))
+            .await
+            .unwrap();
+        let state = TenantState::new(config(), HashSet::new(), shared);
+        let (a, b) = (scope(&state, "key-a"), scope(&state, "key-b"));
+
+        for scope in [&a, &b] {
+            let urls: Vec<String> = state
+                .discovery
+                .list_scoped(scope)
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|r| r.url.to_string())
+                .collect();
+            assert_eq!(urls, vec!["https://aggregated.example/api"]);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_super_admin_queries_across_tenants() {
+        let state = state();
+        let (a, b) = (scope(&state, "key-a"), scope(&state, "key-b"));
+        settle(&state, &a, 10).await;
+        settle(&state, &b, 20).await;
+        settle(&state, &b, 30).await;
+
+        let all = state
+            .receipts
+            .list_admin(&Access::SuperAdmin, None)
+            .await
+            .unwrap();
+        assert_eq!(all.len(), 3);
+        let only_b = state
+            .receipts
+            .list_admin(&Access::SuperAdmin, Some(&TenantId("b".into())))
+            .await
+            .unwrap();
+        assert_eq!(only_b.len(), 2);
+        assert!(only_b.iter().all(|(tenant, _)| tenant.0 == "b"));
+
+        // A tenant's admin view is its own partition
+        let own = state
+            .receipts
+            .list_admin(&Access::Tenant(a.clone()), None)
+            .await
+            .unwrap();
+        assert_eq!(own.len(), 1);
+
+        let webhooks = WebhookDelivery::default();
+        for scope in [&a, &b] {
+            webhooks
+                .register(&Access::Tenant(scope.clone()), hook("https://hooks.example/x"))
+                .await
+                .unwrap();
+        }
+        assert_eq!(webhooks.list(&Access::SuperAdmin, None).await.unwrap().len(), 2);
+        let only_a = webhooks
+            .list(&Access::SuperAdmin, Some(&TenantId("a".into())))
+            .await
+            .unwrap();
+        assert_eq!(only_a.len(), 1);
+        assert_eq!(only_a[0].tenant, Some(TenantId("a".into())));
+    }
+
+    #[tokio::test]
+    async fn test_http_endpoints_enforce_tenant_scope() {
+        let state = Arc::new(state());
+        let app = routes()
+            .with_state(Arc::clone(&state))
+            .merge(crate::handlers::discovery_routes().with_state(Arc::clone(&state.discovery)))
+            .layer(axum::middleware::from_fn_with_state(
+                Arc::clone(&state),
+                tenant_scope_middleware,
+            ));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+        let client = reqwest::Client::new();
+
+        // The public registration endpoint writes to the caller's namespace
+        let private = resource("https://a.example/private", DiscoverySource::SelfRegistered);
+        let created = client
+            .post(format!("{base}/discovery/register"))
+            .header(API_KEY_HEADER, "key-a")
+            .json(&json!({
+                "url": private.url,
+                "type": "http",
+                "description": "Private",
+                "accepts": private.accepts,
+            }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(created.status(), StatusCode::CREATED);
+        assert_eq!(state.discovery.count().await, 0);
+
+        let listed: serde_json::Value = client
+            .get(format!("{base}/tenant/discovery/resources"))
+            .header(API_KEY_HEADER, "key-b")
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(listed["items"].as_array().unwrap().len(), 0);
+        let listed: serde_json::Value = client
+            .get(format!("{base}/tenant/discovery/resources"))
+            .header(API_KEY_HEADER, "key-a")
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(listed["items"][0]["url"], "https://a.example/private");
+
+        let unattributed = client
+            .get(format!("{base}/tenant/receipts"))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(unattributed.status(), StatusCode::UNAUTHORIZED);
+
+        settle(&state, &scope(&state, "key-a"), 10).await;
+        let cross = client
+            .get(format!("{base}/admin/tenants/receipts?tenant=a"))
+            .header(API_KEY_HEADER, "key-b")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(cross.status(), StatusCode::FORBIDDEN);
+
+        let admin: serde_json::Value = client
+            .get(format!("{base}/admin/tenants/receipts?tenant=a"))
+            .header(API_KEY_HEADER, "root")
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(admin["items"][0]["tenant"], "a");
+    }
+}
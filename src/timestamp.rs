@@ -13,6 +13,7 @@ use std::time::{SystemTime, SystemTimeError};
 /// Serialized as a stringified integer to avoid loss of precision in JSON.
 /// For example, `1699999999` becomes `"1699999999"` in the wire format.
 #[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Ord, Eq)]
+#[cfg_attr(feature = "ts-gen", derive(ts_rs::TS), ts(type = "string"))]
 pub struct UnixTimestamp(pub u64);
 
 impl Serialize for UnixTimestamp {
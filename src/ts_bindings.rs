@@ -0,0 +1,144 @@
+//! TypeScript bindings for the facilitator's wire types.
+//!
+//! Wire-facing serde types derive [`ts_rs::TS`] behind the `ts-gen` feature. This module
+//! exports them (and every type they reference) as `.d.ts`-compatible `.ts` files into the
+//! `bindings/` directory at the crate root, which is committed so that web clients can
+//! consume the types directly and so that any change to the wire format shows up in review.
+//!
+//! # Usage
+//!
+//! ```bash
+//! # Check that committed bindings match the Rust types
+//! cargo test --features ts-gen ts_bindings
+//!
+//! # Regenerate bindings after changing a wire type
+//! UPDATE_BINDINGS=1 cargo test --features ts-gen ts_bindings
+//! ```
+//!
+//! Types with hand-written serde impls are mapped to what they actually serialize to:
+//! addresses, hashes and `U256` amounts are strings, [`crate::types::X402Version`] is
+//! `1 | 2`, and [`crate::types::VerifyResponse`] is a union discriminated by `isValid`.
+//!
+//! Bindings are generated without chain feature flags; `algorand`, `sui` and `hedera` add
+//! variants to `ExactPaymentPayload` and are not part of the committed output.
+
+use std::path::Path;
+use ts_rs::{Config, ExportError, TS};
+
+use crate::erc8004::{FeedbackRequest, FeedbackResponse, ProofOfPayment};
+use crate::types::{ErrorResponse, SettleResponse, VerifyRequest, VerifyResponse};
+use crate::types_v2::{DiscoveryResource, PaymentRequirementsV2};
+
+/// Directory, relative to the crate root, holding the committed bindings.
+pub const BINDINGS_DIR: &str = "bindings";
+
+/// ts-rs configuration matching the JSON produced by `serde_json`.
+///
+/// `u64`/`i128` fields are serialized as JSON numbers, so they map to `number`
+/// rather than ts-rs' default `bigint`.
+pub fn config(out_dir: &Path) -> Config {
+    Config::new()
+        .with_large_int("number")
+        .with_out_dir(out_dir)
+}
+
+/// Export all wire types and their dependencies into `out_dir`.
+///
+/// `SettleRequest` is an alias of [`VerifyRequest`] and shares its binding.
+pub fn export_all(out_dir: &Path) -> Result<(), ExportError> {
+    let cfg = config(out_dir);
+    VerifyRequest::export_all(&cfg)?;
+    VerifyResponse::export_all(&cfg)?;
+    SettleResponse::export_all(&cfg)?;
+    PaymentRequirementsV2::export_all(&cfg)?;
+    DiscoveryResource::export_all(&cfg)?;
+    FeedbackRequest::export_all(&cfg)?;
+    FeedbackResponse::export_all(&cfg)?;
+    ProofOfPayment::export_all(&cfg)?;
+    ErrorResponse::export_all(&cfg)?;
+    Ok(())
+}
+
+#[cfg(all(
+    test,
+    not(feature = "algorand"),
+    not(feature = "sui"),
+    not(feature = "hedera")
+))]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+    use std::path::PathBuf;
+
+    /// Relative path -> contents of every file under `dir`.
+    fn read_tree(dir: &Path) -> BTreeMap<PathBuf, String> {
+        fn walk(root: &Path, dir: &Path, out: &mut BTreeMap<PathBuf, String>) {
+            let Ok(entries) = std::fs::read_dir(dir) else {
+                return;
+            };
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if path.is_dir() {
+                    walk(root, &path, out);
+                } else {
+                    let contents = std::fs::read_to_string(&path).unwrap().replace("\r\n", "\n");
+                    out.insert(path.strip_prefix(root).unwrap().to_path_buf(), contents);
+                }
+            }
+        }
+        let mut out = BTreeMap::new();
+        walk(dir, dir, &mut out);
+        out
+    }
+
+    #[test]
+    fn test_committed_bindings_are_up_to_date() {
+        let committed = Path::new(env!("CARGO_MANIFEST_DIR")).join(BINDINGS_DIR);
+
+        if std::env::var_os("UPDATE_BINDINGS").is_some() {
+            let _ = std::fs::remove_dir_all(&committed);
+            export_all(&committed).unwrap();
+            return;
+        }
+
+        let fresh = std::env::temp_dir().join(format!("x402-bindings-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&fresh);
+        export_all(&fresh).unwrap();
+        let expected = read_tree(&fresh);
+        let _ = std::fs::remove_dir_all(&fresh);
+        let actual = read_tree(&committed);
+
+        let stale: Vec<_> = expected
+            .keys()
+            .chain(actual.keys())
+            .filter(|path| expected.get(*path) != actual.get(*path))
+            .collect();
+        assert!(
+            stale.is_empty(),
+            "TypeScript bindings are out of date: {stale:?}\n\
+             Run `UPDATE_BINDINGS=1 cargo test --features ts-gen ts_bindings` and commit the result."
+        );
+    }
+
+    #[test]
+    fn test_custom_serde_types_map_to_their_json() {
+        let cfg = config(Path::new(BINDINGS_DIR));
+        assert_eq!(crate::types::MixedAddress::inline(&cfg), "string");
+        assert_eq!(crate::types::TransactionHash::inline(&cfg), "string");
+        assert_eq!(crate::types::TokenAmount::inline(&cfg), "string");
+        assert_eq!(crate::timestamp::UnixTimestamp::inline(&cfg), "string");
+        assert_eq!(crate::types::X402Version::inline(&cfg), "1 | 2");
+
+        let amount = serde_json::to_value(crate::types::TokenAmount::from(1_000_000u64)).unwrap();
+        assert!(amount.is_string());
+
+        let verify = VerifyResponse::inline(&cfg);
+        assert!(verify.contains("{ isValid: true, payer: MixedAddress, }"), "{verify}");
+        assert!(verify.contains("{ isValid: false, invalidReason: FacilitatorErrorReason, payer?: MixedAddress, }"), "{verify}");
+
+        // Unit variants of an untagged enum serialize as `null`
+        let reason = serde_json::to_value(crate::types::FacilitatorErrorReason::InsufficientFunds).unwrap();
+        assert!(reason.is_null());
+        assert_eq!(crate::types::FacilitatorErrorReason::inline(&cfg), "null | null | null | null | string");
+    }
+}
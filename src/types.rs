@@ -17,6 +17,7 @@ use rust_decimal::prelude::{FromPrimitive, Zero};
 use rust_decimal::Decimal;
 use serde::ser::SerializeStruct;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use sha2::{Digest, Sha512_256};
 use solana_sdk::bs58;
 use solana_sdk::pubkey::Pubkey;
 use std::borrow::Cow;
@@ -26,7 +27,8 @@ use std::ops::{Add, Div, Mul, Rem, Sub};
 use std::str::FromStr;
 use url::Url;
 
-use crate::network::Network;
+use crate::hex_fmt::{Hex, Hex32};
+use crate::network::{Network, NetworkFamily};
 use crate::timestamp::UnixTimestamp;
 
 /// Represents the protocol version. Supports both v1 and v2 of the x402 protocol.
@@ -35,6 +37,7 @@ use crate::timestamp::UnixTimestamp;
 /// - **V1**: Original x402 protocol with network enum names (e.g., "base", "solana")
 /// - **V2**: Updated protocol using CAIP-2 network identifiers (e.g., "eip155:8453")
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "ts-gen", derive(ts_rs::TS), ts(type = "1 | 2"))]
 pub enum X402Version {
     /// Version `1` - Legacy format with network enum names
     V1,
@@ -97,13 +100,18 @@ impl<'de> Deserialize<'de> for X402Version {
     }
 }
 
-/// Enumerates payment schemes. Only "exact" is supported in this implementation,
-/// meaning the amount to be transferred must match exactly.
+/// Enumerates payment schemes. Only "exact" is settled by this implementation,
+/// meaning the amount to be transferred must match exactly; "upto" is recognized
+/// so that usage-priced resources can be listed in discovery.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-gen", derive(ts_rs::TS))]
 #[serde(rename_all = "lowercase")]
 pub enum Scheme {
     /// Standard exact amount transfer (EIP-3009)
     Exact,
+    /// Usage-based transfer of at most the required amount
+    #[serde(alias = "up-to")]
+    Upto,
     /// Fully Homomorphic Encryption transfer using Zama FHEVM (ERC7984)
     #[serde(rename = "fhe-transfer")]
     FheTransfer,
@@ -113,19 +121,22 @@ impl Display for Scheme {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let s = match self {
             Scheme::Exact => "exact",
+            Scheme::Upto => "upto",
             Scheme::FheTransfer => "fhe-transfer",
         };
         write!(f, "{s}")
     }
 }
 
-/// Enumerates supported stablecoin token types for payment.
+/// Enumerates supported token types for payment.
 ///
 /// The x402 protocol supports multiple EIP-3009 compatible stablecoins.
-/// Each token may have different EIP-712 domain parameters.
+/// Each token may have different EIP-712 domain parameters. [`TokenType::Algo`]
+/// is Algorand's native currency and is only listed by the Algorand provider.
 ///
 /// # Decimal Precision
-/// All supported stablecoins use 6 decimals (1_000_000 = $1.00).
+/// All supported tokens use 6 decimals (1_000_000 = $1.00 for stablecoins,
+/// 1 ALGO for native Algorand payments).
 ///
 /// # Default Behavior
 /// When `token_type` is not specified in a payment request, USDC is used
@@ -149,12 +160,15 @@ pub enum TokenType {
     /// Tether USD (USDT0) by Tether (6 decimals)
     #[serde(rename = "usdt")]
     Usdt,
+    /// ALGO, Algorand's native currency (6 decimals, amounts in microAlgos)
+    #[serde(rename = "algo")]
+    Algo,
 }
 
 impl TokenType {
     /// Returns the number of decimal places for this token.
     ///
-    /// All supported stablecoins use 6 decimals.
+    /// All supported tokens use 6 decimals.
     #[must_use]
     pub const fn decimals(&self) -> u8 {
         match self {
@@ -163,6 +177,7 @@ impl TokenType {
             TokenType::Ausd => 6,
             TokenType::Pyusd => 6,
             TokenType::Usdt => 6,
+            TokenType::Algo => 6,
         }
     }
 
@@ -175,6 +190,7 @@ impl TokenType {
             TokenType::Ausd => "AUSD",
             TokenType::Pyusd => "PYUSD",
             TokenType::Usdt => "USDT",
+            TokenType::Algo => "ALGO",
         }
     }
 
@@ -187,6 +203,7 @@ impl TokenType {
             TokenType::Ausd => "Agora USD",
             TokenType::Pyusd => "PayPal USD",
             TokenType::Usdt => "Tether USD",
+            TokenType::Algo => "Algorand",
         }
     }
 
@@ -199,6 +216,7 @@ impl TokenType {
             TokenType::Ausd => "$",
             TokenType::Pyusd => "$",
             TokenType::Usdt => "$",
+            TokenType::Algo => "ALGO",
         }
     }
 
@@ -211,6 +229,7 @@ impl TokenType {
             TokenType::Ausd => true,
             TokenType::Pyusd => true,
             TokenType::Usdt => true,
+            TokenType::Algo => false,
         }
     }
 
@@ -223,6 +242,7 @@ impl TokenType {
             TokenType::Ausd,
             TokenType::Pyusd,
             TokenType::Usdt,
+            TokenType::Algo,
         ]
     }
 
@@ -240,6 +260,8 @@ impl TokenType {
             // Note: USDT0 uses different names per network - "USD₮0" (Arbitrum/Optimism) or "Tether USD" (Celo)
             // The network-specific name is stored in TokenDeployment.eip712
             TokenType::Usdt => "USD\u{20AE}0", // USD₮0 (Unicode TUGRIK SIGN)
+            // Native currency, transferred without EIP-3009 signatures
+            TokenType::Algo => "",
         }
     }
 
@@ -255,6 +277,7 @@ impl TokenType {
             TokenType::Ausd => "1",
             TokenType::Pyusd => "1",
             TokenType::Usdt => "1",
+            TokenType::Algo => "",
         }
     }
 
@@ -286,6 +309,7 @@ impl FromStr for TokenType {
             "ausd" => Ok(TokenType::Ausd),
             "pyusd" => Ok(TokenType::Pyusd),
             "usdt" => Ok(TokenType::Usdt),
+            "algo" => Ok(TokenType::Algo),
             _ => Err(TokenTypeParseError(s.to_string())),
         }
     }
@@ -293,7 +317,7 @@ impl FromStr for TokenType {
 
 /// Error returned when parsing an invalid token type string.
 #[derive(Debug, Clone, thiserror::Error)]
-#[error("Unknown token type: {0}. Supported: usdc, eurc, ausd, pyusd, usdt")]
+#[error("Unknown token type: {0}. Supported: usdc, eurc, ausd, pyusd, usdt, algo")]
 pub struct TokenTypeParseError(pub String);
 
 /// Represents an EVM signature used in EIP-712 typed data.
@@ -301,6 +325,7 @@ pub struct TokenTypeParseError(pub String);
 /// Used to authorize an ERC-3009 transferWithAuthorization.
 /// Can contain EOA, EIP-1271, and EIP-6492 signatures.
 #[derive(Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "ts-gen", derive(ts_rs::TS), ts(type = "string"))]
 pub struct EvmSignature(pub Vec<u8>);
 
 impl From<[u8; 65]> for EvmSignature {
@@ -323,7 +348,7 @@ impl From<EvmSignature> for Bytes {
 
 impl Debug for EvmSignature {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "EvmSignature(0x{})", hex::encode(self.0.clone()))
+        write!(f, "EvmSignature(0x{})", Hex(&self.0))
     }
 }
 
@@ -345,8 +370,7 @@ impl Serialize for EvmSignature {
     where
         S: Serializer,
     {
-        let hex_string = format!("0x{}", hex::encode(self.0.clone()));
-        serializer.serialize_str(&hex_string)
+        serializer.collect_str(&format_args!("0x{}", Hex(&self.0)))
     }
 }
 
@@ -355,6 +379,7 @@ impl Serialize for EvmSignature {
 /// Wrapper around `alloy::primitives::Address`, providing display/serialization support.
 /// Used throughout the protocol for typed Ethereum address handling.
 #[derive(Debug, Copy, Clone, Eq, Hash, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-gen", derive(ts_rs::TS), ts(type = "string"))]
 pub struct EvmAddress(pub alloy::primitives::Address);
 
 impl Display for EvmAddress {
@@ -405,11 +430,12 @@ impl PartialEq<alloy::primitives::Address> for EvmAddress {
 /// Represents a 32-byte random nonce, hex-encoded with 0x prefix.
 /// Must be exactly 64 hex characters long.
 #[derive(Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "ts-gen", derive(ts_rs::TS), ts(type = "string"))]
 pub struct HexEncodedNonce(pub [u8; 32]);
 
 impl Debug for HexEncodedNonce {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "HexEncodedNonce(0x{})", hex::encode(self.0))
+        write!(f, "HexEncodedNonce(0x{})", Hex32::new(&self.0))
     }
 }
 
@@ -443,14 +469,14 @@ impl Serialize for HexEncodedNonce {
     where
         S: Serializer,
     {
-        let hex_string = format!("0x{}", hex::encode(self.0));
-        serializer.serialize_str(&hex_string)
+        serializer.collect_str(&format_args!("0x{}", Hex32::new(&self.0)))
     }
 }
 
 /// EIP-712 structured data for ERC-3009-based authorization.
 /// Defines who can transfer how much USDC and when.
 #[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-gen", derive(ts_rs::TS))]
 #[serde(rename_all = "camelCase")]
 pub struct ExactEvmPayloadAuthorization {
     pub from: EvmAddress,
@@ -464,6 +490,7 @@ pub struct ExactEvmPayloadAuthorization {
 /// Full payload required to authorize an ERC-3009 transfer:
 /// includes the signature and the EIP-712 struct.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-gen", derive(ts_rs::TS))]
 #[serde(rename_all = "camelCase")]
 pub struct ExactEvmPayload {
     pub signature: EvmSignature,
@@ -471,6 +498,7 @@ pub struct ExactEvmPayload {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-gen", derive(ts_rs::TS))]
 #[serde(rename_all = "camelCase")]
 pub struct ExactSolanaPayload {
     pub transaction: String,
@@ -478,6 +506,7 @@ pub struct ExactSolanaPayload {
 
 /// NEAR payment payload containing a base64-encoded SignedDelegateAction (NEP-366).
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-gen", derive(ts_rs::TS))]
 #[serde(rename_all = "camelCase")]
 pub struct ExactNearPayload {
     /// Base64-encoded borsh-serialized SignedDelegateAction
@@ -486,6 +515,7 @@ pub struct ExactNearPayload {
 
 /// Payload for Stellar/Soroban payments using pre-signed authorization entries.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-gen", derive(ts_rs::TS))]
 #[serde(rename_all = "camelCase")]
 pub struct ExactStellarPayload {
     /// Stellar account paying (G... address)
@@ -519,6 +549,7 @@ pub struct ExactStellarPayload {
 /// - Secure: Facilitator only signs a zero-value fee transaction
 #[cfg(feature = "algorand")]
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-gen", derive(ts_rs::TS))]
 #[serde(rename_all = "camelCase")]
 pub struct ExactAlgorandPayload {
     /// Index of the payment transaction within the atomic group (typically 1).
@@ -546,6 +577,7 @@ pub struct ExactAlgorandPayload {
 /// - Secure: Client signature only authorizes their specific transfer
 #[cfg(feature = "sui")]
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-gen", derive(ts_rs::TS))]
 #[serde(rename_all = "camelCase")]
 pub struct ExactSuiPayload {
     /// BCS-encoded transaction bytes (base64).
@@ -564,7 +596,29 @@ pub struct ExactSuiPayload {
     pub coin_object_id: String,
 }
 
+/// Payload for Hedera payments relayed through the Hedera JSON-RPC Relay.
+///
+/// HTS tokens such as USDC do not implement EIP-3009, so the payer signs the transfer
+/// itself instead of an authorization:
+/// 1. Client signs an EVM transaction calling `transfer(payTo, amount)` on the token's
+///    long-zero address, with the network's chain ID (295 mainnet, 296 testnet)
+/// 2. Facilitator checks the transfer against the requirements and simulates it
+/// 3. Facilitator submits the signed transaction with `eth_sendRawTransaction`
+///
+/// The payer covers the (sub-cent) HBAR fee; the transaction's nonce makes it single-use.
+#[cfg(feature = "hedera")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-gen", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct ExactHederaPayload {
+    /// RLP-encoded signed EVM transaction (0x-prefixed hex).
+    pub signed_transaction: String,
+    /// Payer, as a Hedera account ID (0.0.x) or the EVM address signing the transaction.
+    pub from: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-gen", derive(ts_rs::TS))]
 #[serde(untagged)]
 pub enum ExactPaymentPayload {
     Evm(ExactEvmPayload),
@@ -575,11 +629,14 @@ pub enum ExactPaymentPayload {
     Algorand(ExactAlgorandPayload),
     #[cfg(feature = "sui")]
     Sui(ExactSuiPayload),
+    #[cfg(feature = "hedera")]
+    Hedera(ExactHederaPayload),
 }
 
 /// Describes a signed request to transfer a specific amount of funds on-chain.
 /// Includes the scheme, network, and signed payload contents.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-gen", derive(ts_rs::TS))]
 #[serde(rename_all = "camelCase")]
 pub struct PaymentPayload {
     pub x402_version: X402Version,
@@ -619,6 +676,7 @@ impl TryFrom<Base64Bytes<'_>> for PaymentPayload {
 /// A precise on-chain token amount in base units (e.g., USDC with 6 decimals).
 /// Represented as a stringified `U256` in JSON to prevent precision loss.
 #[derive(Debug, Copy, Clone, PartialEq, Ord, PartialOrd, Eq, Hash)]
+#[cfg_attr(feature = "ts-gen", derive(ts_rs::TS), ts(type = "string"))]
 pub struct TokenAmount(pub U256);
 
 impl TokenAmount {
@@ -927,6 +985,7 @@ impl From<u64> for TokenAmount {
 /// Represents either an EVM address (0x...), or an off-chain address, Solana address, or NEAR account.
 /// The format is used for routing settlement.
 #[derive(Debug, Hash, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "ts-gen", derive(ts_rs::TS), ts(type = "string"))]
 pub enum MixedAddress {
     /// EVM address
     Evm(EvmAddress),
@@ -942,6 +1001,9 @@ pub enum MixedAddress {
     /// Sui address (32-byte hex with 0x prefix) or object type ID (package::module::Type)
     #[cfg(feature = "sui")]
     Sui(String),
+    /// Hedera account or token ID in `shard.realm.num` format (e.g., "0.0.456858")
+    #[cfg(feature = "hedera")]
+    Hedera(String),
 }
 
 #[macro_export]
@@ -985,6 +1047,8 @@ impl TryFrom<MixedAddress> for alloy::primitives::Address {
             MixedAddress::Algorand(_) => Err(MixedAddressError::NotEvmAddress),
             #[cfg(feature = "sui")]
             MixedAddress::Sui(_) => Err(MixedAddressError::NotEvmAddress),
+            #[cfg(feature = "hedera")]
+            MixedAddress::Hedera(_) => Err(MixedAddressError::NotEvmAddress),
         }
     }
 }
@@ -1003,6 +1067,151 @@ pub enum MixedAddressError {
     InvalidAddressFormat,
 }
 
+// NEAR account ID regex: implicit (64 hex chars) or named (.near, .testnet, etc.)
+static NEAR_ACCOUNT_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^([a-f0-9]{64}|([a-z0-9_-]+\.)+([a-z0-9_-]+))$")
+        .expect("Invalid regex for NEAR account")
+});
+
+// Stellar address regex: G... (accounts) or C... (contracts), 56 chars base32
+static STELLAR_ADDRESS_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^[GC][A-Z2-7]{55}$").expect("Invalid regex for Stellar address"));
+
+// Sui address regex: 0x-prefixed 64 hex chars, or object type ID (package::module::Type)
+#[cfg(feature = "sui")]
+static SUI_ADDRESS_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"^(0x[a-fA-F0-9]{64}|0x[a-fA-F0-9]{64}::[a-zA-Z_][a-zA-Z0-9_]*::[a-zA-Z_][a-zA-Z0-9_]*)$",
+    )
+    .expect("Invalid regex for Sui address")
+});
+
+// Hedera entity ID regex: shard.realm.num, each a decimal number
+#[cfg(feature = "hedera")]
+static HEDERA_ENTITY_ID_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^\d{1,19}\.\d{1,19}\.\d{1,19}$").expect("Invalid regex for Hedera entity ID")
+});
+
+/// Error returned when a string cannot be parsed into a [`MixedAddress`].
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum AddressError {
+    /// Not an EVM, Algorand or Solana address
+    #[error("Unrecognized address format: {0}")]
+    Unrecognized(String),
+    /// Not an address on the given network
+    #[error("Invalid {network} address: {address}")]
+    InvalidForNetwork { address: String, network: Network },
+}
+
+impl MixedAddress {
+    /// Parse an address whose chain is not known in advance, judging by its shape:
+    ///
+    /// - EVM: `0x` followed by 40 hex characters
+    /// - Algorand: 58 base32 characters with a valid checksum
+    /// - Solana: 32 to 44 base58 characters decoding to 32 bytes
+    /// - Hedera: a `shard.realm.num` entity ID (with the `hedera` feature)
+    ///
+    /// Addresses of other chains are not detected, as their shapes overlap with these or
+    /// with each other; use [`MixedAddress::parse_for_network`] when the network is known.
+    pub fn detect(s: &str) -> Result<MixedAddress, AddressError> {
+        #[cfg(feature = "hedera")]
+        if HEDERA_ENTITY_ID_REGEX.is_match(s) {
+            return Ok(MixedAddress::Hedera(s.to_string()));
+        }
+        let address = if s.starts_with("0x") && s.len() == 42 {
+            EvmAddress::from_str(s).ok().map(MixedAddress::Evm)
+        } else if s.len() == 58 {
+            is_algorand_address(s).then(|| MixedAddress::Algorand(s.to_string()))
+        } else if (32..=44).contains(&s.len()) {
+            Pubkey::from_str(s).ok().map(MixedAddress::Solana)
+        } else {
+            None
+        };
+        address.ok_or_else(|| AddressError::Unrecognized(s.to_string()))
+    }
+
+    /// Parse `s` as an address in the format of `network`'s chain.
+    ///
+    /// Unlike deserialization, which accepts the first format that matches, only
+    /// `network`'s format is tried, checksums included where the format has one.
+    pub fn parse_for_network(s: &str, network: &Network) -> Result<MixedAddress, AddressError> {
+        let address = match NetworkFamily::from(*network) {
+            NetworkFamily::Evm => (s.starts_with("0x") && s.len() == 42)
+                .then(|| EvmAddress::from_str(s).ok())
+                .flatten()
+                .map(MixedAddress::Evm),
+            NetworkFamily::Solana => Pubkey::from_str(s).ok().map(MixedAddress::Solana),
+            NetworkFamily::Near => ((2..=64).contains(&s.len()) && NEAR_ACCOUNT_REGEX.is_match(s))
+                .then(|| MixedAddress::Near(s.to_string())),
+            NetworkFamily::Stellar => (stellar_strkey::ed25519::PublicKey::from_string(s).is_ok()
+                || stellar_strkey::Contract::from_string(s).is_ok())
+            .then(|| MixedAddress::Stellar(s.to_string())),
+            #[cfg(feature = "algorand")]
+            NetworkFamily::Algorand => {
+                is_algorand_address(s).then(|| MixedAddress::Algorand(s.to_string()))
+            }
+            #[cfg(feature = "sui")]
+            NetworkFamily::Sui => SUI_ADDRESS_REGEX
+                .is_match(s)
+                .then(|| MixedAddress::Sui(s.to_string())),
+            // The JSON-RPC Relay also takes EVM addresses, for accounts with an ECDSA alias
+            #[cfg(feature = "hedera")]
+            NetworkFamily::Hedera => {
+                if HEDERA_ENTITY_ID_REGEX.is_match(s) {
+                    Some(MixedAddress::Hedera(s.to_string()))
+                } else {
+                    (s.starts_with("0x") && s.len() == 42)
+                        .then(|| EvmAddress::from_str(s).ok())
+                        .flatten()
+                        .map(MixedAddress::Evm)
+                }
+            }
+        };
+        address.ok_or_else(|| AddressError::InvalidForNetwork {
+            address: s.to_string(),
+            network: *network,
+        })
+    }
+}
+
+/// Decode unpadded RFC 4648 base32, as used by Algorand addresses and transaction IDs.
+///
+/// Returns `None` for characters outside `A-Z2-7` or non-zero trailing padding bits.
+pub(crate) fn decode_base32(s: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(s.len() * 5 / 8);
+    let (mut buffer, mut bits) = (0u16, 0);
+    for c in s.bytes() {
+        let value = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'2'..=b'7' => c - b'2' + 26,
+            _ => return None,
+        };
+        buffer = (buffer << 5) | u16::from(value);
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+    (buffer == 0).then_some(bytes)
+}
+
+/// Whether `s` is an Algorand address: the base32 encoding (without padding) of a
+/// 32-byte public key followed by the last 4 bytes of its SHA-512/256 hash.
+fn is_algorand_address(s: &str) -> bool {
+    if s.len() != 58 {
+        return false;
+    }
+    // 58 characters carry 290 bits: 36 bytes and 2 padding bits that must be zero
+    let Some(bytes) = decode_base32(s) else {
+        return false;
+    };
+    let (public_key, checksum) = bytes.split_at(32);
+    let hash = Sha512_256::digest(public_key);
+    hash[28..] == *checksum
+}
+
 impl TryInto<EvmAddress> for MixedAddress {
     type Error = MixedAddressError;
 
@@ -1016,6 +1225,8 @@ impl TryInto<EvmAddress> for MixedAddress {
             MixedAddress::Stellar(_) => Err(MixedAddressError::NotEvmAddress),
             #[cfg(feature = "sui")]
             MixedAddress::Sui(_) => Err(MixedAddressError::NotEvmAddress),
+            #[cfg(feature = "hedera")]
+            MixedAddress::Hedera(_) => Err(MixedAddressError::NotEvmAddress),
         }
     }
 }
@@ -1031,6 +1242,8 @@ impl Display for MixedAddress {
             MixedAddress::Algorand(address) => write!(f, "{address}"),
             #[cfg(feature = "sui")]
             MixedAddress::Sui(address) => write!(f, "{address}"),
+            #[cfg(feature = "hedera")]
+            MixedAddress::Hedera(entity_id) => write!(f, "{entity_id}"),
         }
     }
 }
@@ -1045,29 +1258,11 @@ impl<'de> Deserialize<'de> for MixedAddress {
                 .expect("Invalid regex for offchain address")
         });
 
-        // NEAR account ID regex: implicit (64 hex chars) or named (.near, .testnet, etc.)
-        static NEAR_ACCOUNT_REGEX: Lazy<Regex> = Lazy::new(|| {
-            Regex::new(r"^([a-f0-9]{64}|([a-z0-9_-]+\.)+([a-z0-9_-]+))$")
-                .expect("Invalid regex for NEAR account")
-        });
-
-        // Stellar address regex: G... (accounts) or C... (contracts), 56 chars base32
-        static STELLAR_ADDRESS_REGEX: Lazy<Regex> = Lazy::new(|| {
-            Regex::new(r"^[GC][A-Z2-7]{55}$").expect("Invalid regex for Stellar address")
-        });
-
         // Algorand address regex: 58-character base32 (uppercase letters A-Z, digits 2-7)
         static ALGORAND_ADDRESS_REGEX: Lazy<Regex> = Lazy::new(|| {
             Regex::new(r"^[A-Z2-7]{58}$").expect("Invalid regex for Algorand address")
         });
 
-        // Sui address regex: 0x-prefixed 64 hex chars, or object type ID (package::module::Type)
-        #[cfg(feature = "sui")]
-        static SUI_ADDRESS_REGEX: Lazy<Regex> = Lazy::new(|| {
-            Regex::new(r"^(0x[a-fA-F0-9]{64}|0x[a-fA-F0-9]{64}::[a-zA-Z_][a-zA-Z0-9_]*::[a-zA-Z_][a-zA-Z0-9_]*)$")
-                .expect("Invalid regex for Sui address")
-        });
-
         let s = String::deserialize(deserializer)?;
         // 1) EVM address (e.g., 0x... 20 bytes, hex)
         if let Ok(addr) = EvmAddress::from_str(&s) {
@@ -1077,24 +1272,29 @@ impl<'de> Deserialize<'de> for MixedAddress {
         if let Ok(pk) = Pubkey::from_str(&s) {
             return Ok(MixedAddress::Solana(pk));
         }
-        // 3) NEAR account ID (implicit 64 hex or named like "alice.near")
+        // 3) Hedera entity ID (0.0.12345), which the NEAR named account format also matches
+        #[cfg(feature = "hedera")]
+        if HEDERA_ENTITY_ID_REGEX.is_match(&s) {
+            return Ok(MixedAddress::Hedera(s));
+        }
+        // 4) NEAR account ID (implicit 64 hex or named like "alice.near")
         if NEAR_ACCOUNT_REGEX.is_match(&s) {
             return Ok(MixedAddress::Near(s));
         }
-        // 4) Stellar address (G... accounts or C... contracts, 56 chars base32)
+        // 5) Stellar address (G... accounts or C... contracts, 56 chars base32)
         if STELLAR_ADDRESS_REGEX.is_match(&s) {
             return Ok(MixedAddress::Stellar(s));
         }
-        // 5) Algorand address (58 chars base32)
+        // 6) Algorand address (58 chars base32)
         if ALGORAND_ADDRESS_REGEX.is_match(&s) {
             return Ok(MixedAddress::Algorand(s));
         }
-        // 6) Sui address (0x-prefixed 64 hex or object type ID)
+        // 7) Sui address (0x-prefixed 64 hex or object type ID)
         #[cfg(feature = "sui")]
         if SUI_ADDRESS_REGEX.is_match(&s) {
             return Ok(MixedAddress::Sui(s));
         }
-        // 7) Off-chain address by regex
+        // 8) Off-chain address by regex
         if OFFCHAIN_ADDRESS_REGEX.is_match(&s) {
             return Ok(MixedAddress::Offchain(s));
         }
@@ -1116,11 +1316,14 @@ impl Serialize for MixedAddress {
             MixedAddress::Algorand(address) => serializer.serialize_str(address),
             #[cfg(feature = "sui")]
             MixedAddress::Sui(address) => serializer.serialize_str(address),
+            #[cfg(feature = "hedera")]
+            MixedAddress::Hedera(entity_id) => serializer.serialize_str(entity_id),
         }
     }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "ts-gen", derive(ts_rs::TS), ts(type = "string"))]
 pub enum TransactionHash {
     /// A 32-byte EVM transaction hash, encoded as 0x-prefixed hex string.
     Evm([u8; 32]),
@@ -1205,8 +1408,7 @@ impl Serialize for TransactionHash {
     fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
         match self {
             TransactionHash::Evm(bytes) => {
-                let hex_string = format!("0x{}", hex::encode(bytes));
-                serializer.serialize_str(&hex_string)
+                serializer.collect_str(&format_args!("0x{}", Hex32::new(bytes)))
             }
             TransactionHash::Solana(bytes) => {
                 let b58_string = bs58::encode(bytes).into_string();
@@ -1218,7 +1420,7 @@ impl Serialize for TransactionHash {
             }
             TransactionHash::Stellar(bytes) => {
                 // Stellar uses hex encoding without 0x prefix
-                serializer.serialize_str(&hex::encode(bytes))
+                serializer.serialize_str(&Hex32::new(bytes))
             }
             TransactionHash::Algorand(tx_id) => {
                 // Algorand uses base32 string directly
@@ -1237,7 +1439,7 @@ impl Display for TransactionHash {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self {
             TransactionHash::Evm(bytes) => {
-                write!(f, "0x{}", hex::encode(bytes))
+                write!(f, "0x{}", Hex32::new(bytes))
             }
             TransactionHash::Solana(bytes) => {
                 write!(f, "{}", bs58::encode(bytes).into_string())
@@ -1246,7 +1448,7 @@ impl Display for TransactionHash {
                 write!(f, "{}", bs58::encode(bytes).into_string())
             }
             TransactionHash::Stellar(bytes) => {
-                write!(f, "{}", hex::encode(bytes))
+                write!(f, "{}", Hex32::new(bytes))
             }
             TransactionHash::Algorand(tx_id) => {
                 write!(f, "{}", tx_id)
@@ -1262,6 +1464,7 @@ impl Display for TransactionHash {
 /// Requirements set by the payment-gated endpoint for an acceptable payment.
 /// This includes min/max amounts, recipient, asset, network, and metadata.
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-gen", derive(ts_rs::TS))]
 #[serde(rename_all = "camelCase")]
 pub struct PaymentRequirements {
     pub scheme: Scheme,
@@ -1271,6 +1474,7 @@ pub struct PaymentRequirements {
     pub description: String,
     pub mime_type: String,
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "ts-gen", ts(optional))]
     pub output_schema: Option<serde_json::Value>,
     pub pay_to: MixedAddress,
     pub max_timeout_seconds: u64,
@@ -1308,6 +1512,7 @@ impl PaymentRequirements {
 /// Wrapper for a payment payload and requirements sent by the client to a facilitator
 /// to be verified.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-gen", derive(ts_rs::TS))]
 #[serde(rename_all = "camelCase")]
 pub struct VerifyRequest {
     pub x402_version: X402Version,
@@ -1336,6 +1541,7 @@ impl VerifyRequest {
 pub type SettleRequest = VerifyRequest;
 
 #[derive(Debug, Clone, Serialize, Deserialize, thiserror::Error)]
+#[cfg_attr(feature = "ts-gen", derive(ts_rs::TS))]
 #[serde(untagged, rename_all = "camelCase")]
 pub enum FacilitatorErrorReason {
     /// Payer doesn't have sufficient funds.
@@ -1358,6 +1564,25 @@ pub enum FacilitatorErrorReason {
     FreeForm(String),
 }
 
+/// Where and when a settlement transaction was confirmed, for accounting.
+///
+/// Block numbers are rounds on Algorand; `gas_used` is only known on EVM chains.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-gen", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct SettleConfirmation {
+    /// Block (or round) including the transaction
+    pub block_number: u64,
+    /// Unix timestamp of that block, when the block could be fetched
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "ts-gen", ts(optional))]
+    pub block_timestamp: Option<u64>,
+    /// Gas used by the transaction
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "ts-gen", ts(optional))]
+    pub gas_used: Option<u64>,
+}
+
 /// Returned from a facilitator after attempting to settle a payment on-chain.
 /// Indicates success/failure, transaction hash, and payer identity.
 ///
@@ -1365,18 +1590,26 @@ pub enum FacilitatorErrorReason {
 /// the response includes a `proof_of_payment` field containing cryptographic proof
 /// that can be used to submit reputation feedback on-chain.
 #[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-gen", derive(ts_rs::TS))]
 #[serde(rename_all = "camelCase")]
 pub struct SettleResponse {
     pub success: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "ts-gen", ts(optional))]
     pub error_reason: Option<FacilitatorErrorReason>,
     pub payer: MixedAddress,
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "ts-gen", ts(optional))]
     pub transaction: Option<TransactionHash>,
     pub network: Network,
     /// ERC-8004 proof of payment (included when `8004-reputation` extension is active)
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "ts-gen", ts(optional))]
     pub proof_of_payment: Option<crate::erc8004::ProofOfPayment>,
+    /// On-chain confirmation of `transaction`, on chains that report one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "ts-gen", ts(optional))]
+    pub confirmation: Option<SettleConfirmation>,
 }
 
 /// Error returned when encoding a [`SettleResponse`] into base64 fails.
@@ -1410,6 +1643,7 @@ impl TryInto<Base64Bytes<'static>> for SettleResponse {
 /// This response indicates whether the payment authorization is valid and identifies the payer. If invalid,
 /// it includes a reason describing why verification failed (e.g., wrong network, an invalid scheme, insufficient funds).
 #[derive(Debug)]
+#[cfg_attr(feature = "ts-gen", derive(ts_rs::TS), ts(as = "VerifyResponseWire"))]
 pub enum VerifyResponse {
     /// The payload matches the requirements and passes all checks.
     Valid { payer: MixedAddress },
@@ -1503,9 +1737,35 @@ impl<'de> Deserialize<'de> for VerifyResponse {
     }
 }
 
+/// Wire shape of [`VerifyResponse`] for TypeScript generation.
+///
+/// `VerifyResponse` has hand-written serde impls that flatten the enum into an
+/// `isValid` discriminator, so the binding is derived from this mirror instead.
+#[cfg(feature = "ts-gen")]
+#[derive(ts_rs::TS)]
+#[ts(untagged, rename = "VerifyResponse")]
+#[allow(dead_code)]
+enum VerifyResponseWire {
+    #[ts(rename_all = "camelCase")]
+    Valid {
+        #[ts(type = "true")]
+        is_valid: bool,
+        payer: MixedAddress,
+    },
+    #[ts(rename_all = "camelCase")]
+    Invalid {
+        #[ts(type = "false")]
+        is_valid: bool,
+        invalid_reason: FacilitatorErrorReason,
+        #[ts(optional)]
+        payer: Option<MixedAddress>,
+    },
+}
+
 /// A simple error structure returned on unexpected or fatal server errors.
 /// Used when no structured protocol-level response is appropriate.
 #[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-gen", derive(ts_rs::TS))]
 #[serde(rename_all = "camelCase")]
 pub struct ErrorResponse {
     pub error: String,
@@ -1865,6 +2125,9 @@ pub struct SupportedPaymentKindExtra {
     /// List of supported tokens on this network
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tokens: Option<Vec<SupportedTokenInfo>>,
+    /// Payment tokens the facilitator restricts this network to; any token when absent
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub accepted_tokens: Option<Vec<MixedAddress>>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -1931,12 +2194,13 @@ mod tests {
     #[test]
     fn test_token_type_all() {
         let all = TokenType::all();
-        assert_eq!(all.len(), 5);
+        assert_eq!(all.len(), 6);
         assert!(all.contains(&TokenType::Usdc));
         assert!(all.contains(&TokenType::Eurc));
         assert!(all.contains(&TokenType::Ausd));
         assert!(all.contains(&TokenType::Pyusd));
         assert!(all.contains(&TokenType::Usdt));
+        assert!(all.contains(&TokenType::Algo));
     }
 
     #[test]
@@ -2065,6 +2329,9 @@ mod tests {
                     decimals: 6,
                 },
             ]),
+            accepted_tokens: Some(vec![MixedAddress::Evm(
+                alloy::primitives::address!("833589fCD6eDb6E08f4c7C32D4f71b54bdA02913").into(),
+            )]),
         };
 
         let json = serde_json::to_string(&extra).unwrap();
@@ -2074,6 +2341,9 @@ mod tests {
         assert!(json.contains("\"tokens\""));
         assert!(json.contains("\"usdc\""));
         assert!(json.contains("\"eurc\""));
+        assert!(json
+            .to_lowercase()
+            .contains("\"acceptedtokens\":[\"0x833589fcd6edb6e08f4c7c32d4f71b54bda02913\"]"));
     }
 
     #[test]
@@ -2081,10 +2351,240 @@ mod tests {
         let extra = SupportedPaymentKindExtra {
             fee_payer: None,
             tokens: None,
+            accepted_tokens: None,
         };
 
         let json = serde_json::to_string(&extra).unwrap();
-        // Both should be omitted
+        // All should be omitted
         assert_eq!(json, "{}");
     }
+
+    // ============================================================
+    // MixedAddress Tests
+    // ============================================================
+
+    const EVM_ADDRESS: &str = "0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913";
+    const ALGORAND_ADDRESS: &str = "7ZUECA7HFLZTXENRV24SHLU4AVPUTMTTDUFUBNBD64C73F3UHRTHAIOF6Q";
+    const SOLANA_ADDRESS: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+    const STELLAR_ADDRESS: &str = "GBRPYHIL2CI3FNQ4BXLFMNDLFJUNPU2HY3ZMFSHONUCEOASW7QC7OX2H";
+
+    #[test]
+    fn test_mixed_address_detect() {
+        let evm = MixedAddress::detect(EVM_ADDRESS).unwrap();
+        assert!(matches!(evm, MixedAddress::Evm(_)));
+        assert_eq!(evm.to_string(), EVM_ADDRESS);
+
+        let algorand = MixedAddress::detect(ALGORAND_ADDRESS).unwrap();
+        assert_eq!(
+            algorand,
+            MixedAddress::Algorand(ALGORAND_ADDRESS.to_string())
+        );
+        // The all-zero public key, as used in the Algorand provider tests
+        let zero = "AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAY5HFKQ";
+        assert!(matches!(
+            MixedAddress::detect(zero),
+            Ok(MixedAddress::Algorand(_))
+        ));
+
+        let solana = MixedAddress::detect(SOLANA_ADDRESS).unwrap();
+        assert!(matches!(solana, MixedAddress::Solana(_)));
+        assert_eq!(solana.to_string(), SOLANA_ADDRESS);
+    }
+
+    #[test]
+    fn test_mixed_address_detect_invalid() {
+        let bad_checksum = format!("6{}", &ALGORAND_ADDRESS[1..]);
+        let bad_padding = format!("{}A", &ALGORAND_ADDRESS[..57]);
+        for invalid in [
+            "",
+            "invalid",
+            "0x123",
+            "0xZZ3589fCD6eDb6E08f4c7C32D4f71b54bdA02913",
+            &bad_checksum,
+            &bad_padding,
+            &ALGORAND_ADDRESS.to_lowercase(),
+            // Not base58 (contains 0 and l)
+            "0PjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1l",
+            // Stellar addresses are not detected
+            STELLAR_ADDRESS,
+        ] {
+            assert_eq!(
+                MixedAddress::detect(invalid),
+                Err(AddressError::Unrecognized(invalid.to_string())),
+                "detecting {invalid:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_mixed_address_parse_for_network() {
+        assert!(matches!(
+            MixedAddress::parse_for_network(EVM_ADDRESS, &Network::Base),
+            Ok(MixedAddress::Evm(_))
+        ));
+        assert!(matches!(
+            MixedAddress::parse_for_network(SOLANA_ADDRESS, &Network::Solana),
+            Ok(MixedAddress::Solana(_))
+        ));
+        assert_eq!(
+            MixedAddress::parse_for_network("alice.near", &Network::Near),
+            Ok(MixedAddress::Near("alice.near".to_string()))
+        );
+        assert_eq!(
+            MixedAddress::parse_for_network(STELLAR_ADDRESS, &Network::Stellar),
+            Ok(MixedAddress::Stellar(STELLAR_ADDRESS.to_string()))
+        );
+
+        // Well-formed addresses of another chain are rejected
+        assert_eq!(
+            MixedAddress::parse_for_network(SOLANA_ADDRESS, &Network::Base),
+            Err(AddressError::InvalidForNetwork {
+                address: SOLANA_ADDRESS.to_string(),
+                network: Network::Base,
+            })
+        );
+        assert!(MixedAddress::parse_for_network(EVM_ADDRESS, &Network::Solana).is_err());
+        assert!(MixedAddress::parse_for_network(EVM_ADDRESS, &Network::Near).is_err());
+        // Stellar's shape without a valid checksum
+        let bad_stellar = format!("{}A", &STELLAR_ADDRESS[..55]);
+        assert!(MixedAddress::parse_for_network(&bad_stellar, &Network::Stellar).is_err());
+        // EVM addresses need their 0x prefix
+        assert!(MixedAddress::parse_for_network(&EVM_ADDRESS[2..], &Network::Base).is_err());
+    }
+
+    #[cfg(feature = "algorand")]
+    #[test]
+    fn test_mixed_address_parse_for_algorand() {
+        assert_eq!(
+            MixedAddress::parse_for_network(ALGORAND_ADDRESS, &Network::Algorand),
+            Ok(MixedAddress::Algorand(ALGORAND_ADDRESS.to_string()))
+        );
+        let bad_checksum = format!("6{}", &ALGORAND_ADDRESS[1..]);
+        assert!(MixedAddress::parse_for_network(&bad_checksum, &Network::Algorand).is_err());
+        assert!(MixedAddress::parse_for_network(EVM_ADDRESS, &Network::Algorand).is_err());
+    }
+
+    #[cfg(feature = "hedera")]
+    #[test]
+    fn test_mixed_address_hedera_entity_id() {
+        let hedera = MixedAddress::Hedera("0.0.456858".to_string());
+        assert_eq!(MixedAddress::detect("0.0.456858"), Ok(hedera.clone()));
+        assert_eq!(
+            MixedAddress::parse_for_network("0.0.456858", &Network::Hedera),
+            Ok(hedera.clone())
+        );
+        // Accounts with an ECDSA alias are also addressed by their EVM address
+        assert!(matches!(
+            MixedAddress::parse_for_network(EVM_ADDRESS, &Network::HederaTestnet),
+            Ok(MixedAddress::Evm(_))
+        ));
+        assert!(MixedAddress::parse_for_network("0.0", &Network::Hedera).is_err());
+        assert!(MixedAddress::parse_for_network("alice.near", &Network::Hedera).is_err());
+        // Takes precedence over the NEAR named account format it also matches
+        let deserialized: MixedAddress = serde_json::from_str("\"0.0.456858\"").unwrap();
+        assert_eq!(deserialized, hedera);
+        assert_eq!(serde_json::to_string(&hedera).unwrap(), "\"0.0.456858\"");
+    }
+
+    // ============================================================
+    // SettleResponse Tests
+    // ============================================================
+
+    fn settle_response(proof_of_payment: Option<crate::erc8004::ProofOfPayment>) -> SettleResponse {
+        SettleResponse {
+            success: true,
+            error_reason: None,
+            payer: MixedAddress::Evm(alloy::primitives::Address::repeat_byte(0x01).into()),
+            transaction: Some(TransactionHash::Evm([0xab; 32])),
+            network: Network::EthereumSepolia,
+            proof_of_payment,
+            confirmation: None,
+        }
+    }
+
+    #[test]
+    fn test_settle_response_without_proof_omits_field() {
+        let json = serde_json::to_value(settle_response(None)).unwrap();
+        let object = json.as_object().unwrap();
+        assert!(!object.contains_key("proofOfPayment"));
+        // Unchanged wire format: only the pre-8004 fields
+        assert_eq!(object.len(), 4, "{object:?}");
+        for key in ["success", "payer", "transaction", "network"] {
+            assert!(object.contains_key(key), "{key} missing from {object:?}");
+        }
+    }
+
+    #[test]
+    fn test_settle_response_with_proof() {
+        let proof = crate::erc8004::ProofOfPayment::new(
+            TransactionHash::Evm([0xab; 32]),
+            SettleConfirmation {
+                block_number: 27_439_044,
+                block_timestamp: Some(1_755_427_523),
+                gas_used: None,
+            },
+            Network::EthereumSepolia,
+            MixedAddress::Evm(alloy::primitives::Address::repeat_byte(0x01).into()),
+            MixedAddress::Evm(alloy::primitives::Address::repeat_byte(0x02).into()),
+            TokenAmount::from(10_000u64),
+            MixedAddress::Evm(alloy::primitives::Address::repeat_byte(0x03).into()),
+        );
+        let json = serde_json::to_value(settle_response(Some(proof.clone()))).unwrap();
+
+        let encoded = &json["proofOfPayment"];
+        assert_eq!(encoded["blockNumber"], 27_439_044);
+        assert_eq!(encoded["timestamp"], 1_755_427_523);
+        assert_eq!(encoded["amount"], "10000");
+        assert_eq!(encoded["paymentHash"], proof.payment_hash.to_string());
+
+        let decoded: SettleResponse = serde_json::from_value(json).unwrap();
+        let decoded = decoded.proof_of_payment.unwrap();
+        assert_eq!(decoded.payment_hash, proof.payment_hash);
+        assert!(decoded.is_hash_consistent());
+    }
+
+    #[test]
+    fn test_settle_response_with_confirmation() {
+        let mut response = settle_response(None);
+        response.confirmation = Some(SettleConfirmation {
+            block_number: 27_439_044,
+            block_timestamp: Some(1_755_427_523),
+            gas_used: Some(61_873),
+        });
+
+        let json = serde_json::to_value(&response).unwrap();
+        assert_eq!(
+            json["confirmation"],
+            serde_json::json!({
+                "blockNumber": 27_439_044,
+                "blockTimestamp": 1_755_427_523,
+                "gasUsed": 61_873
+            })
+        );
+        let decoded: SettleResponse = serde_json::from_value(json).unwrap();
+        assert_eq!(decoded.confirmation, response.confirmation);
+
+        // Chains without gas, such as Algorand, omit it
+        response.confirmation = Some(SettleConfirmation {
+            block_number: 52_000_000,
+            block_timestamp: Some(1_755_427_523),
+            gas_used: None,
+        });
+        let json = serde_json::to_value(&response).unwrap();
+        assert!(json["confirmation"].get("gasUsed").is_none());
+        let decoded: SettleResponse = serde_json::from_value(json).unwrap();
+        assert_eq!(decoded.confirmation, response.confirmation);
+
+        // A block that could not be fetched leaves only its timestamp out
+        response.confirmation = Some(SettleConfirmation {
+            block_number: 27_439_044,
+            block_timestamp: None,
+            gas_used: Some(61_873),
+        });
+        let json = serde_json::to_value(&response).unwrap();
+        assert_eq!(
+            json["confirmation"],
+            serde_json::json!({ "blockNumber": 27_439_044, "gasUsed": 61_873 })
+        );
+    }
 }
@@ -14,20 +14,39 @@
 //!
 //! During the transition period, both v1 and v2 payloads are supported through
 //! envelope types that auto-detect the version and route appropriately.
+//!
+//! # Building requirements
+//!
+//! [`PaymentRequirementsBuilder`] builds [`PaymentRequirementsV2`] from a CAIP-2 network,
+//! addresses and a human-readable amount, validating each as it goes:
+//!
+//! ```
+//! use x402_rs::types_v2::PaymentRequirementsBuilder;
+//!
+//! let requirements = PaymentRequirementsBuilder::new(
+//!     "eip155:8453",
+//!     "0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913",
+//! )
+//! .amount("0.01", 6)
+//! .pay_to("0x209693Bc6afc0C5328bA36FaF03C514EF312287C")
+//! .build()?;
+//! assert_eq!(requirements.amount.to_string(), "10000");
+//! # Ok::<(), x402_rs::types_v2::BuilderError>(())
+//! ```
 
 use alloy::hex;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use url::Url;
 
-use crate::caip2::{Caip2NetworkId, Namespace};
+use crate::caip2::{Caip2NetworkId, Caip2ParseError, Namespace};
 use crate::network::Network;
 use crate::timestamp::UnixTimestamp;
 use crate::types::{
-    EvmAddress, EvmSignature, ExactEvmPayload, ExactEvmPayloadAuthorization, ExactPaymentPayload,
-    FacilitatorErrorReason, HexEncodedNonce, MixedAddress, PaymentPayload, PaymentRequirements,
-    Scheme, SupportedPaymentKindExtra, SupportedPaymentKindsResponse, TokenAmount, VerifyRequest,
-    X402Version,
+    AddressError, EvmAddress, EvmSignature, ExactEvmPayload, ExactEvmPayloadAuthorization,
+    ExactPaymentPayload, FacilitatorErrorReason, HexEncodedNonce, MixedAddress, MoneyAmount,
+    MoneyAmountParseError, PaymentPayload, PaymentRequirements, Scheme, SupportedPaymentKindExtra,
+    SupportedPaymentKindsResponse, TokenAmount, VerifyRequest, X402Version,
 };
 
 // ============================================================================
@@ -78,6 +97,7 @@ impl ResourceInfo {
 /// - `max_amount_required` renamed to `amount`
 /// - `network` now uses CAIP-2 format
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-gen", derive(ts_rs::TS))]
 #[serde(rename_all = "camelCase")]
 pub struct PaymentRequirementsV2 {
     /// Payment scheme (currently only "exact")
@@ -125,6 +145,165 @@ impl PaymentRequirementsV2 {
     }
 }
 
+/// Timeout of requirements built by [`PaymentRequirementsBuilder`] unless set.
+pub const DEFAULT_MAX_TIMEOUT_SECONDS: u64 = 300;
+
+/// Errors from [`PaymentRequirementsBuilder::build`].
+#[derive(Debug, thiserror::Error)]
+pub enum BuilderError {
+    /// The network is not a CAIP-2 identifier
+    #[error("Invalid network: {0}")]
+    InvalidNetwork(Caip2ParseError),
+
+    /// The asset is not an address of the network
+    #[error("Invalid asset: {0}")]
+    InvalidAsset(AddressError),
+
+    /// The recipient is not an address of the network
+    #[error("Invalid payTo: {0}")]
+    InvalidPayTo(AddressError),
+
+    /// The amount is not a decimal number, or is more precise than the token
+    #[error("Invalid amount {amount:?}: {source}")]
+    InvalidAmount {
+        amount: String,
+        source: MoneyAmountParseError,
+    },
+
+    /// A required field was never set
+    #[error("Missing {0}")]
+    Missing(&'static str),
+}
+
+/// Fluent construction of [`PaymentRequirementsV2`] from user-facing strings.
+///
+/// The network, asset, amount and recipient are parsed as soon as they are given; the
+/// first error is returned by [`build`](Self::build). Addresses are checked against
+/// the network when it is a known [`Network`], and by their shape otherwise. The
+/// scheme is [`Scheme::Exact`] and the timeout [`DEFAULT_MAX_TIMEOUT_SECONDS`] unless
+/// set.
+///
+/// ```
+/// use x402_rs::types_v2::PaymentRequirementsBuilder;
+///
+/// // 1 USDC on Base
+/// let requirements = PaymentRequirementsBuilder::new(
+///     "eip155:8453",
+///     "0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913",
+/// )
+/// .amount("1.00", 6)
+/// .pay_to("0x209693Bc6afc0C5328bA36FaF03C514EF312287C")
+/// .timeout(60)
+/// .build()?;
+/// assert_eq!(requirements.amount.to_string(), "1000000");
+/// # Ok::<(), x402_rs::types_v2::BuilderError>(())
+/// ```
+#[derive(Debug)]
+pub struct PaymentRequirementsBuilder {
+    network: Option<Caip2NetworkId>,
+    asset: Option<MixedAddress>,
+    amount: Option<TokenAmount>,
+    pay_to: Option<MixedAddress>,
+    max_timeout_seconds: u64,
+    extra: Option<serde_json::Value>,
+    /// First parse error, reported by `build`
+    error: Option<BuilderError>,
+}
+
+impl PaymentRequirementsBuilder {
+    /// Start requirements for paying in `asset` on the CAIP-2 `network`.
+    pub fn new(network: &str, asset: &str) -> Self {
+        let mut builder = Self {
+            network: None,
+            asset: None,
+            amount: None,
+            pay_to: None,
+            max_timeout_seconds: DEFAULT_MAX_TIMEOUT_SECONDS,
+            extra: None,
+            error: None,
+        };
+        let network = Caip2NetworkId::parse(network.trim()).map_err(BuilderError::InvalidNetwork);
+        builder.network = builder.record(network);
+        let asset = builder
+            .parse_address(asset)
+            .map_err(BuilderError::InvalidAsset);
+        builder.asset = builder.record(asset);
+        builder
+    }
+
+    /// Amount in token units, such as `"1.00"` or `"$0.01"`, scaled to base units by the
+    /// token's `decimals`.
+    pub fn amount(mut self, human_readable: &str, decimals: u8) -> Self {
+        let amount = MoneyAmount::parse(human_readable)
+            .and_then(|money| money.as_token_amount(u32::from(decimals)))
+            .map_err(|source| BuilderError::InvalidAmount {
+                amount: human_readable.to_string(),
+                source,
+            });
+        self.amount = self.record(amount);
+        self
+    }
+
+    /// Recipient of the payment.
+    pub fn pay_to(mut self, address: &str) -> Self {
+        let pay_to = self
+            .parse_address(address)
+            .map_err(BuilderError::InvalidPayTo);
+        self.pay_to = self.record(pay_to);
+        self
+    }
+
+    /// Seconds the payment stays valid (default: [`DEFAULT_MAX_TIMEOUT_SECONDS`]).
+    pub fn timeout(mut self, secs: u64) -> Self {
+        self.max_timeout_seconds = secs;
+        self
+    }
+
+    /// Chain- or application-specific data, such as the EIP-712 domain of the token.
+    pub fn extra(mut self, value: serde_json::Value) -> Self {
+        self.extra = Some(value);
+        self
+    }
+
+    /// The requirements, or the first error met while building them.
+    pub fn build(self) -> Result<PaymentRequirementsV2, BuilderError> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+        Ok(PaymentRequirementsV2 {
+            scheme: Scheme::Exact,
+            network: self.network.ok_or(BuilderError::Missing("network"))?,
+            asset: self.asset.ok_or(BuilderError::Missing("asset"))?,
+            amount: self.amount.ok_or(BuilderError::Missing("amount"))?,
+            pay_to: self.pay_to.ok_or(BuilderError::Missing("payTo"))?,
+            max_timeout_seconds: self.max_timeout_seconds,
+            extra: self.extra,
+        })
+    }
+
+    /// Keep the first error of `result`.
+    fn record<T>(&mut self, result: Result<T, BuilderError>) -> Option<T> {
+        match result {
+            Ok(value) => Some(value),
+            Err(e) => {
+                self.error.get_or_insert(e);
+                None
+            }
+        }
+    }
+
+    fn parse_address(&self, address: &str) -> Result<MixedAddress, AddressError> {
+        let network = self
+            .network
+            .as_ref()
+            .and_then(|network| Network::from_caip2(&network.to_string()));
+        match network {
+            Some(network) => MixedAddress::parse_for_network(address.trim(), &network),
+            None => MixedAddress::detect(address.trim()),
+        }
+    }
+}
+
 // ============================================================================
 // PaymentRequirements v1 -> v2 conversion
 // ============================================================================
@@ -904,6 +1083,7 @@ pub enum NetworkParseError {
 /// multiple sources: self-registration, settlement tracking, crawling, or
 /// aggregation from other facilitators.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "ts-gen", derive(ts_rs::TS))]
 #[serde(rename_all = "snake_case")]
 pub enum DiscoverySource {
     /// Resource was explicitly registered via POST /discovery/register
@@ -933,19 +1113,41 @@ impl std::fmt::Display for DiscoverySource {
 
 /// Metadata for a discoverable resource in the Bazaar registry.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-gen", derive(ts_rs::TS))]
 #[serde(rename_all = "camelCase")]
 pub struct DiscoveryMetadata {
     /// Category for filtering (e.g., "finance", "ai", "data")
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "ts-gen", ts(optional))]
     pub category: Option<String>,
 
     /// Provider name or organization
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "ts-gen", ts(optional))]
     pub provider: Option<String>,
 
     /// Tags for search and discovery
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub tags: Vec<String>,
+
+    /// Facilitators listing this resource, when aggregated from more than one
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sources: Vec<String>,
+
+    /// Latest liveness checks, recorded by the discovery health monitor
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "ts-gen", ts(optional))]
+    pub liveness: Option<ResourceLiveness>,
+
+    /// ERC-8004 reputation of the resource's agent, recorded by the reputation refresher
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "ts-gen", ts(optional))]
+    pub reputation: Option<ResourceReputation>,
+
+    /// Fields of an aggregated listing that have no counterpart here, kept verbatim
+    #[serde(default, skip_serializing_if = "serde_json::Map::is_empty")]
+    #[cfg_attr(feature = "ts-gen", ts(optional, as = "Option<serde_json::Map<String, serde_json::Value>>"))]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
 impl Default for DiscoveryMetadata {
@@ -954,10 +1156,58 @@ impl Default for DiscoveryMetadata {
             category: None,
             provider: None,
             tags: Vec::new(),
+            sources: Vec::new(),
+            liveness: None,
+            reputation: None,
+            extra: serde_json::Map::new(),
         }
     }
 }
 
+/// Outcome of the latest liveness checks of a resource.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-gen", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceLiveness {
+    /// Whether the resource is listed; false after too many consecutive failed checks
+    pub healthy: bool,
+
+    /// Unix timestamp of the latest check
+    pub last_checked: u64,
+
+    /// Failed checks since the last successful one
+    pub consecutive_failures: u32,
+
+    /// HTTP status of the latest check, absent when no response was received
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "ts-gen", ts(optional))]
+    pub last_status: Option<u16>,
+
+    /// Milliseconds the latest check took
+    pub latency_ms: u64,
+}
+
+/// ERC-8004 reputation of the agent behind a resource.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-gen", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceReputation {
+    /// Agent id in the Identity Registry
+    pub agent_id: u64,
+
+    /// Network of the registries
+    pub network: Caip2NetworkId,
+
+    /// Feedback entries in the Reputation Registry summary
+    pub feedback_count: u64,
+
+    /// Summary value normalized to 0-100; 0 without feedback
+    pub score: u8,
+
+    /// Unix timestamp of the summary read
+    pub last_updated: u64,
+}
+
 /// A discoverable paid resource in the Bazaar registry.
 ///
 /// Represents an API endpoint or service that accepts x402 payments.
@@ -972,6 +1222,7 @@ impl Default for DiscoveryMetadata {
 ///
 /// The `source` and `source_facilitator` fields enable filtering and attribution.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-gen", derive(ts_rs::TS))]
 #[serde(rename_all = "camelCase")]
 pub struct DiscoveryResource {
     /// The URL of the paid resource
@@ -1014,6 +1265,20 @@ pub struct DiscoveryResource {
     /// Number of settlements observed (for Settlement source)
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub settlement_count: Option<u32>,
+
+    /// Whether the resource answered its latest health checks; unhealthy resources are
+    /// hidden from listings until they recover
+    #[serde(default = "default_healthy")]
+    pub is_healthy: bool,
+
+    /// Address that proved ownership of a self-registered resource by signing its
+    /// registration; only it may replace or remove the resource
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner: Option<MixedAddress>,
+}
+
+fn default_healthy() -> bool {
+    true
 }
 
 impl DiscoveryResource {
@@ -1042,6 +1307,8 @@ impl DiscoveryResource {
             source_facilitator: None,
             first_seen: Some(now),
             settlement_count: None,
+            is_healthy: true,
+            owner: None,
         }
     }
 
@@ -1072,6 +1339,8 @@ impl DiscoveryResource {
             source_facilitator: Some(source_facilitator),
             first_seen: Some(now),
             settlement_count: None,
+            is_healthy: true,
+            owner: None,
         }
     }
 
@@ -1100,6 +1369,8 @@ impl DiscoveryResource {
             source_facilitator: None,
             first_seen: Some(now),
             settlement_count: Some(1),
+            is_healthy: true,
+            owner: None,
         }
     }
 
@@ -1115,6 +1386,12 @@ impl DiscoveryResource {
         self
     }
 
+    /// Set the address that signed the registration (for self-registered resources)
+    pub fn with_owner(mut self, owner: MixedAddress) -> Self {
+        self.owner = Some(owner);
+        self
+    }
+
     /// Increment settlement count (for Settlement source)
     pub fn increment_settlement_count(&mut self) {
         self.settlement_count = Some(self.settlement_count.unwrap_or(0) + 1);
@@ -1246,6 +1523,10 @@ pub struct DiscoveryFilters {
     /// Filter by source facilitator (e.g., "coinbase", "ultravioleta")
     #[serde(skip_serializing_if = "Option::is_none")]
     pub source_facilitator: Option<String>,
+
+    /// Also list resources hidden after failing health checks
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub include_unhealthy: bool,
 }
 
 impl DiscoveryFilters {
@@ -1256,6 +1537,136 @@ impl DiscoveryFilters {
             && self.tag.is_none()
             && self.source.is_none()
             && self.source_facilitator.is_none()
+            && !self.include_unhealthy
+    }
+}
+
+/// Filters for [`DiscoveryRegistry::search`](crate::discovery::DiscoveryRegistry::search).
+///
+/// Unlike [`DiscoveryFilters`], values are typed: the network is a parsed CAIP-2 id and
+/// the price ceiling a token amount in base units.
+#[derive(Debug, Clone, Default)]
+pub struct SearchFilters {
+    /// Only resources accepting payment on this network
+    pub network: Option<Caip2NetworkId>,
+
+    /// Only resources with a payment method costing at most this amount (on `network`, if set)
+    pub max_price: Option<TokenAmount>,
+
+    /// Category, compared case-insensitively
+    pub category: Option<String>,
+
+    /// Tags the resource must all carry, compared case-insensitively
+    pub tags: Vec<String>,
+
+    /// Source facilitator (e.g., "coinbase", "ultravioleta")
+    pub source_facilitator: Option<String>,
+}
+
+/// Filters and page of [`DiscoveryRegistry::query`](crate::discovery::DiscoveryRegistry::query).
+///
+/// Every filter that is set must hold. `network`, `asset` and `max_amount` must hold for
+/// the same payment method, and `q` is matched case-insensitively as a plain substring of
+/// the description or URL.
+#[derive(Debug, Clone)]
+pub struct ListQuery {
+    /// Only resources accepting payment on this network
+    pub network: Option<Caip2NetworkId>,
+
+    /// Only resources accepting payment in this token
+    pub asset: Option<MixedAddress>,
+
+    /// Category, compared case-insensitively
+    pub category: Option<String>,
+
+    /// Tag the resource must carry, compared case-insensitively
+    pub tag: Option<String>,
+
+    /// Free text matched against description and URL
+    pub q: Option<String>,
+
+    /// Only resources with a payment method costing at most this amount, in base units
+    pub max_amount: Option<TokenAmount>,
+
+    /// Provider name, compared case-insensitively
+    pub provider: Option<String>,
+
+    /// Discovery source (self_registered, settlement, crawled, aggregated)
+    pub source: Option<String>,
+
+    /// Source facilitator (e.g., "coinbase", "ultravioleta")
+    pub source_facilitator: Option<String>,
+
+    /// Also list resources hidden after failing health checks
+    pub include_unhealthy: bool,
+
+    /// Only resources with an ERC-8004 reputation score of at least this much
+    pub min_reputation: Option<u8>,
+
+    /// Order of the results
+    pub sort: ListSort,
+
+    /// Maximum number of items (capped at 100)
+    pub limit: u32,
+
+    /// Number of matching items to skip
+    pub offset: u32,
+}
+
+/// Order of [`DiscoveryRegistry::query`](crate::discovery::DiscoveryRegistry::query) results.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ListSort {
+    /// Most recently updated first
+    #[default]
+    Newest,
+    /// Highest ERC-8004 reputation score first, then newest; unscored resources last
+    Reputation,
+}
+
+impl Default for ListQuery {
+    fn default() -> Self {
+        Self {
+            network: None,
+            asset: None,
+            category: None,
+            tag: None,
+            q: None,
+            max_amount: None,
+            provider: None,
+            source: None,
+            source_facilitator: None,
+            include_unhealthy: false,
+            min_reputation: None,
+            sort: ListSort::Newest,
+            limit: 10,
+            offset: 0,
+        }
+    }
+}
+
+/// A page of [`DiscoveryRegistry::query`](crate::discovery::DiscoveryRegistry::query) results.
+#[derive(Debug, Clone)]
+pub struct QueryResult {
+    /// Matching resources on the page, in [`ListQuery::sort`] order
+    pub items: Vec<DiscoveryResource>,
+
+    /// Number of matching resources across all pages
+    pub total: u32,
+
+    /// Page size applied, after capping
+    pub limit: u32,
+
+    /// Number of matching resources skipped
+    pub offset: u32,
+}
+
+impl From<QueryResult> for DiscoveryResponse {
+    fn from(result: QueryResult) -> Self {
+        DiscoveryResponse::new(
+            result.items,
+            Pagination::new(result.limit, result.offset, result.total),
+        )
     }
 }
 
@@ -1312,6 +1723,108 @@ mod tests {
         assert_eq!(reqs.max_timeout_seconds, 300);
     }
 
+    const USDC_BASE: &str = "0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913";
+    const PAY_TO: &str = "0x209693Bc6afc0C5328bA36FaF03C514EF312287C";
+
+    #[test]
+    fn test_builder_usdc_on_base() {
+        let requirements = PaymentRequirementsBuilder::new("eip155:8453", USDC_BASE)
+            .amount("1.00", 6)
+            .pay_to(PAY_TO)
+            .extra(serde_json::json!({ "name": "USD Coin", "version": "2" }))
+            .build()
+            .unwrap();
+
+        assert_eq!(requirements.scheme, Scheme::Exact);
+        assert_eq!(requirements.network, Caip2NetworkId::eip155(8453));
+        assert_eq!(
+            requirements.asset,
+            MixedAddress::Evm(USDC_BASE.parse().unwrap())
+        );
+        assert_eq!(requirements.amount, TokenAmount::from(1_000_000u64));
+        assert_eq!(
+            requirements.pay_to,
+            MixedAddress::Evm(PAY_TO.parse().unwrap())
+        );
+        assert_eq!(
+            requirements.max_timeout_seconds,
+            DEFAULT_MAX_TIMEOUT_SECONDS
+        );
+        assert_eq!(requirements.extra.unwrap()["name"], "USD Coin");
+    }
+
+    #[test]
+    fn test_builder_errors() {
+        let build = |network: &str, asset: &str, amount: &str, pay_to: &str| {
+            PaymentRequirementsBuilder::new(network, asset)
+                .amount(amount, 6)
+                .pay_to(pay_to)
+                .timeout(60)
+                .build()
+        };
+
+        assert!(matches!(
+            build("base", USDC_BASE, "1.00", PAY_TO),
+            Err(BuilderError::InvalidNetwork(_))
+        ));
+        assert!(matches!(
+            build("eip155:8453", "0x8335", "1.00", PAY_TO),
+            Err(BuilderError::InvalidAsset(_))
+        ));
+        // A Solana address is not an address on Base
+        assert!(matches!(
+            build(
+                "eip155:8453",
+                USDC_BASE,
+                "1.00",
+                "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v"
+            ),
+            Err(BuilderError::InvalidPayTo(_))
+        ));
+        // Finer than the 6 decimals of USDC
+        assert!(matches!(
+            build("eip155:8453", USDC_BASE, "0.0000001", PAY_TO),
+            Err(BuilderError::InvalidAmount { .. })
+        ));
+        assert!(matches!(
+            PaymentRequirementsBuilder::new("eip155:8453", USDC_BASE)
+                .amount("1", 6)
+                .build(),
+            Err(BuilderError::Missing("payTo"))
+        ));
+        // The first error is the one reported
+        assert!(matches!(
+            build("eip155:8453", "nope", "abc", "nope"),
+            Err(BuilderError::InvalidAsset(_))
+        ));
+    }
+
+    #[test]
+    fn test_payment_requirements_v2_scheme_round_trip() {
+        for (scheme, name) in [(Scheme::Exact, "exact"), (Scheme::Upto, "upto")] {
+            let json = format!(
+                r#"{{
+                    "scheme": "{name}",
+                    "network": "eip155:8453",
+                    "asset": "0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913",
+                    "amount": "1000000",
+                    "payTo": "0x1234567890123456789012345678901234567890",
+                    "maxTimeoutSeconds": 300
+                }}"#
+            );
+            let reqs: PaymentRequirementsV2 = serde_json::from_str(&json).unwrap();
+            assert_eq!(reqs.scheme, scheme);
+            assert_eq!(scheme.to_string(), name);
+
+            let value = serde_json::to_value(&reqs).unwrap();
+            assert_eq!(value["scheme"], name);
+            let parsed: PaymentRequirementsV2 = serde_json::from_value(value).unwrap();
+            assert_eq!(parsed.scheme, scheme);
+        }
+        assert_eq!(serde_json::from_str::<Scheme>(r#""up-to""#).unwrap(), Scheme::Upto);
+        assert!(serde_json::from_str::<Scheme>(r#""stream""#).is_err());
+    }
+
     #[test]
     fn test_payment_payload_envelope_version_detection() {
         // Test that envelope correctly wraps v1 and v2 payloads
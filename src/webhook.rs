@@ -0,0 +1,1001 @@
+//! Webhook delivery of settlement events.
+//!
+//! Operators register an endpoint with `POST /webhooks` and receive a signed JSON event
+//! for every successful settlement, instead of polling for them. Each delivery runs on
+//! its own task, so `/settle` never waits on a slow or unreachable receiver.
+//!
+//! # Payload
+//!
+//! ```json
+//! {
+//!   "id": "3f9c0a17d2e4b865",
+//!   "event": "settlement",
+//!   "timestamp": 1767225600,
+//!   "final": true,
+//!   "settlement": { "success": true, "payer": "0x...", "transaction": "0x...", "network": "base" },
+//!   "finality": { "state": "finalized", "policyId": "base-default", "requiredConfirmations": 2, ... }
+//! }
+//! ```
+//!
+//! `finality` is the settlement's [`FinalityReceipt`] when finality policies are configured
+//! (see [`crate::finality`]).
+//!
+//! # Finality
+//!
+//! Events are only sent once a settlement is final, so `final` is always `true`. A
+//! settlement held by a finality policy is delivered when the tracker finalizes it (see
+//! [`start_finality_delivery`]); one that expires before reaching finality is never
+//! delivered. Without finality policies, settlements are final on first confirmation.
+//!
+//! The body is signed with HMAC-SHA256 under the webhook's secret. The hex digest is sent
+//! in the `X-402-Signature` header; receivers should recompute it over the raw body
+//! (see [`sign`]) and compare in constant time.
+//!
+//! # Retries
+//!
+//! Transport errors and `5xx` responses are retried up to `retryAttempts` times, waiting
+//! `retryBackoffMs` before the first retry and doubling the wait on each one after.
+//! Other non-`2xx` responses are not retried.
+//!
+//! # Tenants
+//!
+//! In multi-tenant mode (see [`crate::tenant`]) a tenant registers webhooks with its own
+//! API key. They receive only that tenant's settlements, count against its
+//! `maxSubscriptions` quota and are invisible to other tenants. Webhooks registered with
+//! the admin key receive every settlement; `GET /webhooks?tenant=<id>` narrows the
+//! admin's listing to one tenant.
+//!
+//! # Environment
+//!
+//! - `WEBHOOK_ADMIN_KEY` - Enables webhooks; required in `X-API-Key` to register or remove one
+
+use axum::body::{to_bytes, Body, Bytes};
+use axum::extract::{Extension, Path, Query, Request, State};
+use axum::http::{HeaderMap, Method, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use axum::routing::{delete, post};
+use axum::{Json, Router};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sha2::Sha256;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use thiserror::Error;
+use tokio::sync::RwLock;
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+use url::Url;
+
+use crate::finality::{FinalityReceipt, FinalityTracker, SettlementState};
+use crate::handlers::AdminKey;
+use crate::tenant::{Access, TenantError, TenantFilter, TenantId, TenantScope};
+use crate::types::SettleResponse;
+
+/// Header carrying the hex HMAC-SHA256 of the request body.
+pub const SIGNATURE_HEADER: &str = "X-402-Signature";
+
+/// Event name of settlement deliveries.
+pub const SETTLEMENT_EVENT: &str = "settlement";
+
+const DEFAULT_RETRY_ATTEMPTS: u32 = 3;
+const DEFAULT_RETRY_BACKOFF_MS: u64 = 500;
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Error)]
+pub enum WebhookError {
+    #[error("Invalid webhook URL: {0}")]
+    InvalidUrl(String),
+    #[error("Webhook secret must not be empty")]
+    EmptySecret,
+    #[error("Webhook {0} not found")]
+    NotFound(String),
+    #[error("Missing or invalid X-API-Key")]
+    Unauthorized,
+    #[error("Webhook request failed: {0}")]
+    Request(#[from] reqwest::Error),
+    #[error("Webhook endpoint responded with {0}")]
+    Status(StatusCode),
+    #[error(transparent)]
+    Tenant(#[from] TenantError),
+}
+
+impl WebhookError {
+    fn status(&self) -> StatusCode {
+        match self {
+            Self::InvalidUrl(_) | Self::EmptySecret => StatusCode::BAD_REQUEST,
+            Self::NotFound(_) => StatusCode::NOT_FOUND,
+            Self::Unauthorized => StatusCode::UNAUTHORIZED,
+            Self::Request(_) | Self::Status(_) => StatusCode::BAD_GATEWAY,
+            Self::Tenant(e) => e.status(),
+        }
+    }
+
+    /// Whether another attempt may succeed.
+    fn is_retryable(&self) -> bool {
+        match self {
+            Self::Request(_) => true,
+            Self::Status(status) => status.is_server_error(),
+            _ => false,
+        }
+    }
+}
+
+impl IntoResponse for WebhookError {
+    fn into_response(self) -> Response {
+        (self.status(), Json(json!({ "error": self.to_string() }))).into_response()
+    }
+}
+
+/// A webhook endpoint and its delivery policy.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookConfig {
+    pub url: String,
+    /// HMAC-SHA256 key for the `X-402-Signature` header
+    pub secret: String,
+    /// Retries after the first attempt
+    #[serde(default = "default_retry_attempts")]
+    pub retry_attempts: u32,
+    /// Wait before the first retry; doubled on each one after
+    #[serde(default = "default_retry_backoff_ms")]
+    pub retry_backoff_ms: u64,
+}
+
+fn default_retry_attempts() -> u32 {
+    DEFAULT_RETRY_ATTEMPTS
+}
+
+fn default_retry_backoff_ms() -> u64 {
+    DEFAULT_RETRY_BACKOFF_MS
+}
+
+impl WebhookConfig {
+    fn validate(&self) -> Result<(), WebhookError> {
+        let url = Url::parse(&self.url).map_err(|e| WebhookError::InvalidUrl(e.to_string()))?;
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err(WebhookError::InvalidUrl(format!(
+                "unsupported scheme {}",
+                url.scheme()
+            )));
+        }
+        if self.secret.is_empty() {
+            return Err(WebhookError::EmptySecret);
+        }
+        Ok(())
+    }
+
+    /// Wait before retry number `retry` (starting at 1).
+    fn backoff(&self, retry: u32) -> Duration {
+        let factor = 1u64 << retry.saturating_sub(1).min(16);
+        Duration::from_millis(self.retry_backoff_ms.saturating_mul(factor))
+    }
+}
+
+/// A registered webhook, as returned by `POST /webhooks`. The secret is never echoed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegisteredWebhook {
+    pub id: String,
+    pub url: String,
+    pub retry_attempts: u32,
+    pub retry_backoff_ms: u64,
+    pub created_at: u64,
+    /// Tenant owning the webhook, `None` for the admin's
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tenant: Option<TenantId>,
+}
+
+/// A stored webhook.
+#[derive(Debug, Clone)]
+struct Registration {
+    config: WebhookConfig,
+    created_at: u64,
+    tenant: Option<TenantId>,
+}
+
+impl Registration {
+    fn registered(&self, id: &str) -> RegisteredWebhook {
+        RegisteredWebhook {
+            id: id.to_string(),
+            url: self.config.url.clone(),
+            retry_attempts: self.config.retry_attempts,
+            retry_backoff_ms: self.config.retry_backoff_ms,
+            created_at: self.created_at,
+            tenant: self.tenant.clone(),
+        }
+    }
+
+    /// Whether `access` may see or remove this webhook.
+    fn is_visible_to(&self, access: &Access) -> bool {
+        match access {
+            Access::SuperAdmin => true,
+            Access::Tenant(scope) => self.tenant.as_ref() == Some(scope.tenant()),
+        }
+    }
+}
+
+/// A settlement event as delivered to webhooks.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookEvent<'a> {
+    /// Unique per event, shared by all retries; receivers can use it to deduplicate
+    pub id: String,
+    pub event: &'static str,
+    pub timestamp: u64,
+    /// Whether the settlement reached its finality bar
+    #[serde(rename = "final")]
+    pub is_final: bool,
+    pub settlement: &'a SettleResponse,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub finality: Option<&'a FinalityReceipt>,
+}
+
+/// A settlement waiting for finality before it is delivered.
+#[derive(Debug)]
+struct DeferredSettlement {
+    tenant: Option<TenantScope>,
+    settlement: SettleResponse,
+}
+
+/// Hex HMAC-SHA256 of `body` under `secret`.
+pub fn sign(secret: &str, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+    mac.update(body);
+    hex::encode(mac.finalize().into_bytes())
+}
+
+/// Registered webhooks and the client delivering to them.
+///
+/// Every webhook is owned by the admin or by one tenant; every accessor takes the
+/// caller's [`Access`], so a tenant only ever reaches its own webhooks.
+#[derive(Debug)]
+pub struct WebhookDelivery {
+    client: reqwest::Client,
+    webhooks: RwLock<HashMap<String, Registration>>,
+    /// Settlements held until finality, by transaction
+    deferred: RwLock<HashMap<String, DeferredSettlement>>,
+    admin_key: Option<AdminKey>,
+}
+
+impl Default for WebhookDelivery {
+    fn default() -> Self {
+        Self::new(None)
+    }
+}
+
+impl WebhookDelivery {
+    /// Registration requires `admin_key` in `X-API-Key` when set.
+    pub fn new(admin_key: Option<AdminKey>) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(DELIVERY_TIMEOUT)
+            .build()
+            .unwrap_or_default();
+        Self {
+            client,
+            webhooks: RwLock::new(HashMap::new()),
+            deferred: RwLock::new(HashMap::new()),
+            admin_key,
+        }
+    }
+
+    /// Webhook delivery from the environment; disabled when `WEBHOOK_ADMIN_KEY` is unset.
+    pub fn from_env() -> Option<Self> {
+        let key = AdminKey::from_env("WEBHOOK_ADMIN_KEY")?;
+        info!("Webhook delivery enabled");
+        Some(Self::new(Some(key)))
+    }
+
+    /// Register a webhook owned by `owner`.
+    ///
+    /// A tenant's webhooks count against its `maxSubscriptions` quota.
+    pub async fn register(
+        &self,
+        owner: &Access,
+        config: WebhookConfig,
+    ) -> Result<RegisteredWebhook, WebhookError> {
+        config.validate()?;
+        let tenant = match owner {
+            Access::SuperAdmin => None,
+            Access::Tenant(scope) => {
+                scope.require_authenticated()?;
+                Some(scope)
+            }
+        };
+        let mut webhooks = self.webhooks.write().await;
+        if let Some(scope) = tenant {
+            let owned = webhooks
+                .values()
+                .filter(|w| w.tenant.as_ref() == Some(scope.tenant()))
+                .count();
+            if scope.quotas().max_subscriptions.is_some_and(|limit| owned >= limit) {
+                return Err(scope.quota_exceeded("subscription").into());
+            }
+        }
+        let id = hex::encode(rand::random::<[u8; 8]>());
+        let registration = Registration {
+            config,
+            created_at: now_secs(),
+            tenant: tenant.map(|scope| scope.tenant().clone()),
+        };
+        let registered = registration.registered(&id);
+        info!(id = %id, url = %registration.config.url, tenant = ?registration.tenant, "Registered webhook");
+        webhooks.insert(id, registration);
+        Ok(registered)
+    }
+
+    /// Remove a webhook visible to `owner`.
+    pub async fn unregister(&self, owner: &Access, id: &str) -> Result<(), WebhookError> {
+        let mut webhooks = self.webhooks.write().await;
+        match webhooks.get(id) {
+            Some(registration) if registration.is_visible_to(owner) => {
+                webhooks.remove(id);
+                info!(id = %id, "Removed webhook");
+                Ok(())
+            }
+            // Another tenant's webhook is reported as missing, not forbidden
+            _ => Err(WebhookError::NotFound(id.to_string())),
+        }
+    }
+
+    /// Webhooks visible to `access`, optionally narrowed to one tenant.
+    pub async fn list(
+        &self,
+        access: &Access,
+        filter: Option<&TenantId>,
+    ) -> Result<Vec<RegisteredWebhook>, WebhookError> {
+        let tenant = match access {
+            Access::SuperAdmin => filter.cloned(),
+            Access::Tenant(_) => access.visible(filter)?,
+        };
+        let mut listed: Vec<RegisteredWebhook> = self
+            .webhooks
+            .read()
+            .await
+            .iter()
+            .filter(|(_, w)| w.is_visible_to(access))
+            .filter(|(_, w)| tenant.is_none() || w.tenant == tenant)
+            .map(|(id, w)| w.registered(id))
+            .collect();
+        listed.sort_by_key(|w| w.created_at);
+        Ok(listed)
+    }
+
+    /// Deliver a final settlement to the admin's webhooks and, for a settlement
+    /// attributed to a tenant, to that tenant's webhooks, in the background.
+    ///
+    /// Failed settlements, and settlements whose `finality` receipt is not final, are
+    /// not delivered. Returns the spawned delivery tasks.
+    pub async fn notify_settlement(
+        &self,
+        tenant: Option<&TenantScope>,
+        settlement: &SettleResponse,
+        finality: Option<&FinalityReceipt>,
+    ) -> Vec<JoinHandle<()>> {
+        if !settlement.success || finality.is_some_and(|r| !r.is_final()) {
+            return Vec::new();
+        }
+        let webhooks = self.webhooks.read().await;
+        let recipients: Vec<(&String, &Registration)> = webhooks
+            .iter()
+            .filter(|(_, w)| match &w.tenant {
+                None => true,
+                Some(owner) => tenant.is_some_and(|scope| scope.tenant() == owner),
+            })
+            .collect();
+        if recipients.is_empty() {
+            return Vec::new();
+        }
+        let event = WebhookEvent {
+            id: hex::encode(rand::random::<[u8; 8]>()),
+            event: SETTLEMENT_EVENT,
+            timestamp: now_secs(),
+            is_final: true,
+            settlement,
+            finality,
+        };
+        let body = match serde_json::to_vec(&event) {
+            Ok(body) => Bytes::from(body),
+            Err(e) => {
+                warn!(error = %e, "Failed to serialize webhook event");
+                return Vec::new();
+            }
+        };
+        recipients
+            .into_iter()
+            .map(|(id, registration)| {
+                let client = self.client.clone();
+                let config = registration.config.clone();
+                let body = body.clone();
+                let id = id.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = deliver(&client, &config, body).await {
+                        warn!(id = %id, url = %config.url, error = %e, "Webhook delivery failed");
+                    }
+                })
+            })
+            .collect()
+    }
+
+    /// Hold a settlement until `tracker` resolves its finality.
+    ///
+    /// Delivers right away if the settlement was resolved in the meantime.
+    pub async fn defer_settlement(
+        &self,
+        tracker: &FinalityTracker,
+        transaction: String,
+        tenant: Option<TenantScope>,
+        settlement: SettleResponse,
+    ) -> Vec<JoinHandle<()>> {
+        self.deferred
+            .write()
+            .await
+            .insert(transaction.clone(), DeferredSettlement { tenant, settlement });
+        // A poll may have resolved it before it was deferred
+        match tracker.receipt(&transaction).await {
+            Some(receipt) if receipt.state == SettlementState::Confirmed => Vec::new(),
+            Some(receipt) => self.on_finality(&receipt).await,
+            None => {
+                self.deferred.write().await.remove(&transaction);
+                Vec::new()
+            }
+        }
+    }
+
+    /// Deliver the deferred settlement `receipt` resolved, if it was finalized.
+    pub async fn on_finality(&self, receipt: &FinalityReceipt) -> Vec<JoinHandle<()>> {
+        let Some(deferred) = self.deferred.write().await.remove(&receipt.transaction) else {
+            return Vec::new();
+        };
+        if !receipt.is_final() {
+            warn!(
+                transaction = %receipt.transaction,
+                "Dropping webhook of a settlement that never reached finality"
+            );
+            return Vec::new();
+        }
+        self.notify_settlement(deferred.tenant.as_ref(), &deferred.settlement, Some(receipt))
+            .await
+    }
+
+    /// Re-check every deferred settlement against `tracker`, after missed events.
+    async fn sweep_deferred(&self, tracker: &FinalityTracker) {
+        let transactions: Vec<String> = self.deferred.read().await.keys().cloned().collect();
+        for transaction in transactions {
+            match tracker.receipt(&transaction).await {
+                Some(receipt) if receipt.state == SettlementState::Confirmed => {}
+                Some(receipt) => {
+                    self.on_finality(&receipt).await;
+                }
+                // Evicted from the tracker; its outcome is unknown
+                None => {
+                    self.deferred.write().await.remove(&transaction);
+                }
+            }
+        }
+    }
+
+    /// The caller: a tenant authenticated by its API key, or the admin.
+    fn authorize(
+        &self,
+        headers: &HeaderMap,
+        access: Option<Extension<Access>>,
+    ) -> Result<Access, WebhookError> {
+        match access {
+            Some(Extension(Access::Tenant(scope))) if scope.is_authenticated() => {
+                return Ok(Access::Tenant(scope));
+            }
+            Some(Extension(Access::SuperAdmin)) => return Ok(Access::SuperAdmin),
+            _ => {}
+        }
+        match &self.admin_key {
+            Some(admin_key) if !admin_key.matches(headers) => Err(WebhookError::Unauthorized),
+            _ => Ok(Access::SuperAdmin),
+        }
+    }
+}
+
+/// POST a signed body, retrying transport errors and `5xx` with exponential backoff.
+pub async fn deliver(
+    client: &reqwest::Client,
+    config: &WebhookConfig,
+    body: Bytes,
+) -> Result<(), WebhookError> {
+    let signature = sign(&config.secret, &body);
+    let mut retry = 0;
+    loop {
+        let result = client
+            .post(&config.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(SIGNATURE_HEADER, &signature)
+            .body(body.clone())
+            .send()
+            .await
+            .map_err(WebhookError::from)
+            .and_then(|response| match response.status() {
+                status if status.is_success() => Ok(()),
+                status => Err(WebhookError::Status(status)),
+            });
+        match result {
+            Ok(()) => {
+                debug!(url = %config.url, attempts = retry + 1, "Delivered webhook");
+                return Ok(());
+            }
+            Err(e) if e.is_retryable() && retry < config.retry_attempts => {
+                retry += 1;
+                let wait = config.backoff(retry);
+                debug!(url = %config.url, error = %e, retry, wait_ms = wait.as_millis() as u64, "Retrying webhook");
+                tokio::time::sleep(wait).await;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Routes for managing webhooks.
+pub fn routes() -> Router<Arc<WebhookDelivery>> {
+    Router::new()
+        .route("/webhooks", post(post_webhook).get(get_webhooks))
+        .route("/webhooks/{id}", delete(delete_webhook))
+}
+
+async fn post_webhook(
+    State(delivery): State<Arc<WebhookDelivery>>,
+    access: Option<Extension<Access>>,
+    headers: HeaderMap,
+    Json(config): Json<WebhookConfig>,
+) -> Result<Response, WebhookError> {
+    let owner = delivery.authorize(&headers, access)?;
+    let registered = delivery.register(&owner, config).await?;
+    Ok((StatusCode::CREATED, Json(registered)).into_response())
+}
+
+async fn get_webhooks(
+    State(delivery): State<Arc<WebhookDelivery>>,
+    access: Option<Extension<Access>>,
+    headers: HeaderMap,
+    Query(filter): Query<TenantFilter>,
+) -> Result<Response, WebhookError> {
+    let caller = delivery.authorize(&headers, access)?;
+    let webhooks = delivery.list(&caller, filter.tenant.as_ref()).await?;
+    Ok(Json(json!({ "webhooks": webhooks })).into_response())
+}
+
+async fn delete_webhook(
+    State(delivery): State<Arc<WebhookDelivery>>,
+    access: Option<Extension<Access>>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> Result<StatusCode, WebhookError> {
+    let owner = delivery.authorize(&headers, access)?;
+    delivery.unregister(&owner, &id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Deliver settlements answered by `POST /settle` to the registered webhooks.
+///
+/// Settlements attributed to a tenant (see [`crate::tenant::tenant_scope_middleware`])
+/// also go to that tenant's webhooks. Settlements the [`FinalityTracker`] extension
+/// holds as confirmed are deferred until it finalizes them.
+pub async fn webhook_middleware(
+    State(delivery): State<Arc<WebhookDelivery>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let is_settle = request.method() == Method::POST && request.uri().path().ends_with("/settle");
+    let tenant = match request.extensions().get::<Access>() {
+        Some(Access::Tenant(scope)) => Some(scope.clone()),
+        _ => None,
+    };
+    let tracker = request.extensions().get::<Arc<FinalityTracker>>().cloned();
+    let response = next.run(request).await;
+    if !is_settle || !response.status().is_success() {
+        return response;
+    }
+
+    let (parts, body) = response.into_parts();
+    let Ok(bytes) = to_bytes(body, usize::MAX).await else {
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    };
+    if let Ok(settlement) = serde_json::from_slice::<SettleResponse>(&bytes) {
+        let transaction = settlement.transaction.as_ref().map(|tx| tx.to_string());
+        let receipt = match (&tracker, &transaction) {
+            (Some(tracker), Some(transaction)) => tracker.receipt(transaction).await,
+            _ => None,
+        };
+        match (tracker, transaction, receipt) {
+            (Some(tracker), Some(transaction), Some(receipt))
+                if settlement.success && !receipt.is_final() =>
+            {
+                debug!(transaction = %transaction, "Deferring webhook until finality");
+                delivery
+                    .defer_settlement(&tracker, transaction, tenant, settlement)
+                    .await;
+            }
+            (_, _, receipt) => {
+                delivery
+                    .notify_settlement(tenant.as_ref(), &settlement, receipt.as_ref())
+                    .await;
+            }
+        }
+    }
+    Response::from_parts(parts, Body::from(bytes))
+}
+
+/// Deliver deferred settlements as `tracker` finalizes them.
+pub fn start_finality_delivery(
+    delivery: Arc<WebhookDelivery>,
+    tracker: Arc<FinalityTracker>,
+) -> JoinHandle<()> {
+    let mut events = tracker.subscribe();
+    tokio::spawn(async move {
+        loop {
+            match events.recv().await {
+                Ok(receipt) => {
+                    delivery.on_finality(&receipt).await;
+                }
+                Err(tokio::sync::broadcast::error::RecvError::Lagged(missed)) => {
+                    warn!(missed, "Missed finality events; re-checking deferred webhooks");
+                    delivery.sweep_deferred(&tracker).await;
+                }
+                Err(tokio::sync::broadcast::error::RecvError::Closed) => return,
+            }
+        }
+    })
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network::Network;
+    use crate::types::{EvmAddress, MixedAddress};
+    use axum::http::Request as HttpRequest;
+    use tower::ServiceExt;
+    use wiremock::matchers::{header_exists, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn settlement(success: bool) -> SettleResponse {
+        SettleResponse {
+            success,
+            error_reason: None,
+            payer: MixedAddress::Evm(EvmAddress(alloy::primitives::Address::repeat_byte(0x11))),
+            transaction: None,
+            network: Network::Base,
+            proof_of_payment: None,
+            confirmation: None,
+        }
+    }
+
+    fn config(url: String) -> WebhookConfig {
+        WebhookConfig {
+            url,
+            secret: "whsec_test".to_string(),
+            retry_attempts: 3,
+            retry_backoff_ms: 1,
+        }
+    }
+
+    #[test]
+    fn test_sign_matches_hmac_sha256_vector() {
+        // RFC 4231 test case 2
+        assert_eq!(
+            sign("Jefe", b"what do ya want for nothing?"),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_delivery_is_signed() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/hook"))
+            .and(header_exists(SIGNATURE_HEADER))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let delivery = WebhookDelivery::default();
+        delivery
+            .register(&Access::SuperAdmin, config(format!("{}/hook", server.uri())))
+            .await
+            .unwrap();
+        for task in delivery.notify_settlement(None, &settlement(true), None).await {
+            task.await.unwrap();
+        }
+
+        let requests = server.received_requests().await.unwrap();
+        let request = &requests[0];
+        let signature = request
+            .headers
+            .get(SIGNATURE_HEADER)
+            .unwrap()
+            .to_str()
+            .unwrap();
+        assert_eq!(signature, sign("whsec_test", &request.body));
+        let event: serde_json::Value = serde_json::from_slice(&request.body).unwrap();
+        assert_eq!(event["event"], "settlement");
+        assert_eq!(event["final"], true);
+        assert_eq!(event["settlement"]["network"], "base");
+        assert!(event.get("finality").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_settlements_are_delivered_after_finality() {
+        use crate::finality::{FinalityPolicies, FinalityPolicy, PaymentContext};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let tracker = FinalityTracker::new(FinalityPolicies::new(vec![FinalityPolicy {
+            id: "base-timelock".into(),
+            network: Some(Network::Base),
+            asset: None,
+            min_amount: None,
+            max_amount: None,
+            min_usd: None,
+            max_usd: None,
+            confirmations: 1,
+            timelock_secs: 30,
+        }]))
+        // Shorter than the timelock, so the earlier settlement expires
+        .with_pending_ttl(20);
+        let payment = PaymentContext {
+            network: Network::Base,
+            asset: "0x1".to_string(),
+            amount: crate::types::TokenAmount::from(1u64),
+            decimals: 6,
+        };
+        let delivery = WebhookDelivery::default();
+        delivery
+            .register(&Access::SuperAdmin, config(server.uri()))
+            .await
+            .unwrap();
+
+        for (tx, confirmed_at) in [("0xexpired", 1_000), ("0xfinal", 1_005)] {
+            let receipt = tracker
+                .on_confirmed(tx.into(), &payment, 10, confirmed_at)
+                .await;
+            // Not delivered while only confirmed
+            assert!(delivery
+                .notify_settlement(None, &settlement(true), Some(&receipt))
+                .await
+                .is_empty());
+            assert!(delivery
+                .defer_settlement(&tracker, tx.into(), None, settlement(true))
+                .await
+                .is_empty());
+        }
+
+        let heights = crate::finality::ChainHeights::new();
+        let expired = tracker.poll(&heights, 1_020).await;
+        assert_eq!(expired[0].state, SettlementState::Expired);
+        assert!(delivery.on_finality(&expired[0]).await.is_empty());
+
+        let finalized = tracker.poll(&heights, 1_035).await;
+        let tasks = delivery.on_finality(&finalized[0]).await;
+        assert_eq!(tasks.len(), 1);
+        for task in tasks {
+            task.await.unwrap();
+        }
+        // Each deferred settlement is delivered at most once
+        assert!(delivery.on_finality(&finalized[0]).await.is_empty());
+
+        let requests = server.received_requests().await.unwrap();
+        let event: serde_json::Value = serde_json::from_slice(&requests[0].body).unwrap();
+        assert_eq!(event["final"], true);
+        assert_eq!(event["finality"]["state"], "finalized");
+        assert_eq!(event["finality"]["transaction"], "0xfinal");
+    }
+
+    #[tokio::test]
+    async fn test_server_errors_are_retried() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(2)
+            .expect(2)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = reqwest::Client::new();
+        deliver(&client, &config(server.uri()), Bytes::from_static(b"{}"))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_retries_give_up_after_retry_attempts() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(500))
+            .expect(4)
+            .mount(&server)
+            .await;
+
+        let client = reqwest::Client::new();
+        let err = deliver(&client, &config(server.uri()), Bytes::from_static(b"{}"))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, WebhookError::Status(s) if s == StatusCode::INTERNAL_SERVER_ERROR));
+    }
+
+    #[tokio::test]
+    async fn test_client_errors_are_not_retried() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(410))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = reqwest::Client::new();
+        assert!(
+            deliver(&client, &config(server.uri()), Bytes::from_static(b"{}"))
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_failed_settlements_are_not_delivered() {
+        let delivery = WebhookDelivery::default();
+        delivery
+            .register(
+                &Access::SuperAdmin,
+                config("https://hooks.example.com/".to_string()),
+            )
+            .await
+            .unwrap();
+        assert!(delivery
+            .notify_settlement(None, &settlement(false), None)
+            .await
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_register_and_delete_routes() {
+        let delivery = Arc::new(WebhookDelivery::new(Some(AdminKey::new("admin"))));
+        let app = routes().with_state(Arc::clone(&delivery));
+        let body = r#"{"url":"https://hooks.example.com/x402","secret":"s"}"#;
+
+        let unauthorized = app
+            .clone()
+            .oneshot(
+                HttpRequest::post("/webhooks")
+                    .header("content-type", "application/json")
+                    .body(Body::from(body))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(unauthorized.status(), StatusCode::UNAUTHORIZED);
+
+        let created = app
+            .clone()
+            .oneshot(
+                HttpRequest::post("/webhooks")
+                    .header("content-type", "application/json")
+                    .header("x-api-key", "admin")
+                    .body(Body::from(body))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(created.status(), StatusCode::CREATED);
+        let created: serde_json::Value =
+            serde_json::from_slice(&to_bytes(created.into_body(), usize::MAX).await.unwrap())
+                .unwrap();
+        assert!(created.get("secret").is_none());
+        assert_eq!(created["retryAttempts"], DEFAULT_RETRY_ATTEMPTS);
+        let id = created["id"].as_str().unwrap();
+
+        let delete = |id: String| {
+            app.clone().oneshot(
+                HttpRequest::delete(format!("/webhooks/{id}"))
+                    .header("x-api-key", "admin")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+        };
+        assert_eq!(
+            delete(id.to_string()).await.unwrap().status(),
+            StatusCode::NO_CONTENT
+        );
+        assert_eq!(
+            delete(id.to_string()).await.unwrap().status(),
+            StatusCode::NOT_FOUND
+        );
+        assert!(delivery
+            .list(&Access::SuperAdmin, None)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_tenant_webhooks_only_receive_their_settlements() {
+        let tenants: crate::tenant::TenantsConfig = serde_json::from_value(serde_json::json!({
+            "tenants": [
+                { "id": "a", "apiKeys": ["key-a"] },
+                { "id": "b", "apiKeys": ["key-b"] }
+            ]
+        }))
+        .unwrap();
+        let tenants = crate::tenant::TenantState::new(
+            tenants,
+            Default::default(),
+            Arc::new(crate::discovery::DiscoveryRegistry::new()),
+        );
+        let scope = |key: &str| {
+            let mut headers = HeaderMap::new();
+            headers.insert("x-api-key", key.parse().unwrap());
+            match tenants.attribute(&headers, "/settle").unwrap() {
+                Some(Access::Tenant(scope)) => scope,
+                other => panic!("unexpected attribution {other:?}"),
+            }
+        };
+        let (a, b) = (scope("key-a"), scope("key-b"));
+
+        let server = MockServer::start().await;
+        for (route, hits) in [("/admin", 2), ("/a", 1), ("/b", 0)] {
+            Mock::given(method("POST"))
+                .and(path(route))
+                .respond_with(ResponseTemplate::new(200))
+                .expect(hits)
+                .mount(&server)
+                .await;
+        }
+
+        let delivery = WebhookDelivery::default();
+        for (owner, route) in [
+            (Access::SuperAdmin, "/admin"),
+            (Access::Tenant(a.clone()), "/a"),
+            (Access::Tenant(b.clone()), "/b"),
+        ] {
+            delivery
+                .register(&owner, config(format!("{}{route}", server.uri())))
+                .await
+                .unwrap();
+        }
+        for tenant in [Some(&a), None] {
+            for task in delivery.notify_settlement(tenant, &settlement(true), None).await {
+                task.await.unwrap();
+            }
+        }
+
+        // Each tenant sees and deletes only its own registrations
+        let own = delivery.list(&Access::Tenant(b.clone()), None).await.unwrap();
+        assert_eq!(own.len(), 1);
+        assert_eq!(own[0].tenant.as_ref(), Some(b.tenant()));
+        let foreign = delivery
+            .list(&Access::SuperAdmin, Some(a.tenant()))
+            .await
+            .unwrap();
+        assert!(matches!(
+            delivery
+                .unregister(&Access::Tenant(b.clone()), &foreign[0].id)
+                .await,
+            Err(WebhookError::NotFound(_))
+        ));
+        assert_eq!(delivery.list(&Access::SuperAdmin, None).await.unwrap().len(), 3);
+    }
+}
@@ -0,0 +1,109 @@
+//! Helpers shared by the integration tests: stand-in contracts assembled from raw EVM
+//! bytecode, Anvil nodes running them, and mocked JSON-RPC nodes answering `eth_call`.
+//!
+//! Each test binary compiles its own copy and uses only some of the helpers.
+
+#![allow(dead_code)]
+
+use alloy::primitives::{Address, Bytes};
+use serde_json::{json, Value};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[cfg(feature = "anvil")]
+pub use self::anvil::*;
+
+/// Runtime code that jumps to `entry` when the call's selector matches, and reverts
+/// with no data on any other selector:
+///
+/// ```text
+/// CALLDATALOAD(0) >> 224
+/// for each route: DUP1 PUSH4 selector EQ PUSH1 entry JUMPI
+/// REVERT(0, 0)
+/// ```
+///
+/// Append each entry point with [`jumpdest`].
+pub fn dispatcher(routes: &[([u8; 4], u8)]) -> Vec<u8> {
+    let mut code = vec![0x60, 0x00, 0x35, 0x60, 0xe0, 0x1c]; // CALLDATALOAD(0) >> 224
+    for (selector, entry) in routes {
+        code.extend_from_slice(&[0x80, 0x63]); // DUP1 PUSH4 selector
+        code.extend_from_slice(selector);
+        code.extend_from_slice(&[0x14, 0x60, *entry, 0x57]); // EQ PUSH1 entry JUMPI
+    }
+    code.extend_from_slice(&[0x60, 0x00, 0x80, 0xfd]); // REVERT(0, 0)
+    code
+}
+
+/// Append the JUMPDEST of `entry`, which must be the next offset in `code`.
+pub fn jumpdest(code: &mut Vec<u8>, entry: u8) {
+    assert_eq!(code.len(), usize::from(entry), "entry point moved");
+    code.push(0x5b);
+}
+
+/// Mocked node answering every `eth_call` with `reply(to, input)`: the returned bytes,
+/// or a revert carrying the error data.
+pub async fn eth_call_node<F>(reply: F) -> MockServer
+where
+    F: Fn(Address, &[u8]) -> Result<Vec<u8>, Vec<u8>> + Send + Sync + 'static,
+{
+    let server = MockServer::start().await;
+    Mock::given(wiremock::matchers::method("POST"))
+        .respond_with(move |request: &wiremock::Request| {
+            let body: Value = request.body_json().unwrap();
+            assert_eq!(body["method"], "eth_call");
+            let call = &body["params"][0];
+            let to: Address = serde_json::from_value(call["to"].clone()).unwrap();
+            let input = call.get("input").or_else(|| call.get("data")).unwrap();
+            let input: Bytes = serde_json::from_value(input.clone()).unwrap();
+            let response = match reply(to, &input) {
+                Ok(output) => {
+                    json!({ "jsonrpc": "2.0", "id": body["id"], "result": Bytes::from(output) })
+                }
+                Err(revert) => json!({
+                    "jsonrpc": "2.0",
+                    "id": body["id"],
+                    "error": { "code": 3, "message": "execution reverted", "data": Bytes::from(revert) }
+                }),
+            };
+            ResponseTemplate::new(200).set_body_json(response)
+        })
+        .mount(&server)
+        .await;
+    server
+}
+
+#[cfg(feature = "anvil")]
+mod anvil {
+    use alloy::network::EthereumWallet;
+    use alloy::node_bindings::{Anvil, AnvilInstance};
+    use alloy::primitives::{Address, Bytes};
+    use alloy::providers::Provider;
+    use alloy::signers::local::PrivateKeySigner;
+    use x402_rs::chain::evm::{EvmChain, EvmProvider, MetaEvmProvider};
+    use x402_rs::network::Network;
+
+    /// Start Anvil with the chain id of `network`. The provider signs with the first
+    /// dev account, `anvil.addresses()[0]`.
+    pub async fn anvil(network: Network) -> (AnvilInstance, EvmProvider) {
+        let chain_id = EvmChain::try_from(network).unwrap().chain_id;
+        let anvil = Anvil::new().chain_id(chain_id).spawn();
+        let signer: PrivateKeySigner = anvil.keys()[0].clone().into();
+        let provider = EvmProvider::try_new(
+            EthereumWallet::from(signer),
+            &anvil.endpoint(),
+            true,
+            network,
+        )
+        .await
+        .unwrap();
+        (anvil, provider)
+    }
+
+    /// Deploy `code` as the runtime code at `address`.
+    pub async fn set_code(provider: &EvmProvider, address: Address, code: Bytes) {
+        provider
+            .inner()
+            .raw_request::<_, ()>("anvil_setCode".into(), (address, code))
+            .await
+            .unwrap();
+    }
+}
@@ -0,0 +1,50 @@
+//! `GET /admin/config/vars` requires the configuration admin key.
+
+use axum::body::{to_bytes, Body};
+use axum::http::{Request, StatusCode};
+use axum::Router;
+use serde_json::Value;
+use tower::ServiceExt;
+
+use x402_rs::handlers::{config_admin_routes, AdminKey, ADMIN_KEY_HEADER};
+
+const ADMIN_KEY: &str = "config-secret";
+
+fn app() -> Router {
+    config_admin_routes().with_state(AdminKey::new(ADMIN_KEY))
+}
+
+async fn config_vars(key: Option<&str>) -> (StatusCode, Value) {
+    let mut request = Request::get("/admin/config/vars");
+    if let Some(key) = key {
+        request = request.header(ADMIN_KEY_HEADER, key);
+    }
+    let response = app().oneshot(request.body(Body::empty()).unwrap()).await.unwrap();
+    let status = response.status();
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    (status, serde_json::from_slice(&body).unwrap())
+}
+
+#[tokio::test]
+async fn config_vars_rejects_missing_or_wrong_key() {
+    let (status, body) = config_vars(None).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert!(body.get("variables").is_none());
+
+    let (status, body) = config_vars(Some("wrong")).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert!(body.get("variables").is_none());
+}
+
+#[tokio::test]
+async fn config_vars_lists_variables_with_key() {
+    let (status, body) = config_vars(Some(ADMIN_KEY)).await;
+    assert_eq!(status, StatusCode::OK);
+    let variables = body["variables"].as_array().unwrap();
+    let admin_key = variables
+        .iter()
+        .find(|var| var["name"] == "CONFIG_ADMIN_KEY")
+        .unwrap();
+    assert_eq!(admin_key["secret"], true);
+    assert!(admin_key.get("value").is_none());
+}
@@ -0,0 +1,121 @@
+//! `POST /admin/discovery/aggregate` against a mocked facilitator.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::body::{to_bytes, Body};
+use axum::http::{Request, StatusCode};
+use axum::Router;
+use serde_json::{json, Value};
+use tower::ServiceExt;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+use x402_rs::discovery::DiscoveryRegistry;
+use x402_rs::discovery_aggregator::{
+    AggregationRunner, DiscoveryAggregator, FacilitatorConfig, SharedAggregationReport,
+};
+use x402_rs::handlers::{discovery_admin_routes, AdminKey, DiscoveryAdminState, ADMIN_KEY_HEADER};
+
+const ADMIN_KEY: &str = "admin-secret";
+
+async fn facilitator(id: &str, delay: Duration) -> (MockServer, FacilitatorConfig) {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/discovery/resources"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!({
+                    "items": [{ "url": format!("https://{id}.example.com/data"), "lastUpdated": 1 }],
+                    "pagination": { "total": 1 }
+                }))
+                .set_delay(delay),
+        )
+        .mount(&server)
+        .await;
+    let config = FacilitatorConfig {
+        id: id.to_string(),
+        name: format!("{id} facilitator"),
+        discovery_url: format!("{}/discovery/resources", server.uri()),
+        enabled: true,
+        timeout_secs: 5,
+        headers: Vec::new(),
+        api_key_env: None,
+        api_key: None,
+        api_key_header: None,
+        min_request_interval_ms: None,
+    };
+    (server, config)
+}
+
+fn app(facilitators: Vec<FacilitatorConfig>, registry: &DiscoveryRegistry) -> Router {
+    let runner = AggregationRunner::new(
+        DiscoveryAggregator::with_facilitators(facilitators),
+        registry.clone(),
+        SharedAggregationReport::default(),
+    );
+    discovery_admin_routes().with_state(Arc::new(DiscoveryAdminState {
+        runner,
+        admin_key: AdminKey::new(ADMIN_KEY),
+    }))
+}
+
+async fn aggregate(app: Router, uri: &str, key: Option<&str>) -> (StatusCode, Value) {
+    let mut request = Request::post(uri);
+    if let Some(key) = key {
+        request = request.header(ADMIN_KEY_HEADER, key);
+    }
+    let response = app.oneshot(request.body(Body::empty()).unwrap()).await.unwrap();
+    let status = response.status();
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    (status, serde_json::from_slice(&body).unwrap())
+}
+
+#[tokio::test]
+async fn aggregate_imports_resources_and_returns_report() {
+    let (_coinbase, coinbase) = facilitator("coinbase", Duration::ZERO).await;
+    let (_payai, payai) = facilitator("payai", Duration::ZERO).await;
+    let registry = DiscoveryRegistry::new();
+    let app = app(vec![coinbase, payai], &registry);
+
+    let (status, _) = aggregate(app.clone(), "/admin/discovery/aggregate", None).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let (status, _) = aggregate(app.clone(), "/admin/discovery/aggregate", Some("wrong")).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(registry.count().await, 0);
+
+    let (status, report) =
+        aggregate(app.clone(), "/admin/discovery/aggregate?source=coinbase", Some(ADMIN_KEY)).await;
+    assert_eq!(status, StatusCode::OK);
+    let sources = report["perSource"].as_array().unwrap();
+    assert_eq!(sources.len(), 1);
+    assert_eq!(sources[0]["facilitatorId"], "coinbase");
+    assert!(registry.get("https://coinbase.example.com/data").await.is_some());
+    assert!(registry.get("https://payai.example.com/data").await.is_none());
+
+    let (status, report) = aggregate(app.clone(), "/admin/discovery/aggregate", Some(ADMIN_KEY)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(report["perSource"].as_array().unwrap().len(), 2);
+    assert!(registry.get("https://payai.example.com/data").await.is_some());
+
+    let (status, body) =
+        aggregate(app, "/admin/discovery/aggregate?source=unknown", Some(ADMIN_KEY)).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert!(body["error"].as_str().unwrap().contains("unknown"));
+}
+
+#[tokio::test]
+async fn aggregate_conflicts_with_cycle_in_progress() {
+    let (_slow, slow) = facilitator("slow", Duration::from_millis(500)).await;
+    let registry = DiscoveryRegistry::new();
+    let app = app(vec![slow], &registry);
+
+    let first = tokio::spawn(aggregate(app.clone(), "/admin/discovery/aggregate", Some(ADMIN_KEY)));
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    let (status, _) = aggregate(app, "/admin/discovery/aggregate", Some(ADMIN_KEY)).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    let (status, _) = first.await.unwrap();
+    assert_eq!(status, StatusCode::OK);
+    assert!(registry.get("https://slow.example.com/data").await.is_some());
+}
@@ -0,0 +1,203 @@
+//! Signed `POST`/`DELETE /discovery/resources` against an in-memory registry.
+
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use alloy::primitives::Address;
+use alloy::signers::local::PrivateKeySigner;
+use alloy::signers::SignerSync;
+use axum::body::{to_bytes, Body};
+use axum::http::{Method, Request, StatusCode};
+use axum::Router;
+use serde_json::{json, Value};
+use tower::ServiceExt;
+use url::Url;
+
+use x402_rs::discovery::ownership::{body_hash, canonical_message, SignedAction};
+use x402_rs::discovery::DiscoveryRegistry;
+use x402_rs::handlers::discovery_routes;
+
+const URL: &str = "https://api.example.com/weather";
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
+
+/// `body` with `signer`'s signature of `action` on [`URL`] for `pay_to`.
+fn sign(
+    signer: &PrivateKeySigner,
+    action: SignedAction,
+    pay_to: Address,
+    timestamp: u64,
+    mut body: Value,
+) -> Value {
+    let message = canonical_message(
+        action,
+        &Url::parse(URL).unwrap(),
+        pay_to,
+        body_hash(&body),
+        timestamp,
+    );
+    body["timestamp"] = json!(timestamp);
+    body["signature"] = json!(signer
+        .sign_message_sync(message.as_bytes())
+        .unwrap()
+        .to_string());
+    body
+}
+
+/// Registration of [`URL`] paying to `owner`, signed by `signer`.
+fn registration(owner: &PrivateKeySigner, signer: &PrivateKeySigner, timestamp: u64) -> Value {
+    let body = json!({
+        "url": URL,
+        "type": "http",
+        "description": "Weather forecasts",
+        "accepts": [{
+            "scheme": "exact",
+            "network": "eip155:8453",
+            "asset": "0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913",
+            "amount": "10000",
+            "payTo": owner.address().to_string(),
+            "maxTimeoutSeconds": 60
+        }]
+    });
+    sign(signer, SignedAction::Register, owner.address(), timestamp, body)
+}
+
+async fn call(app: &Router, method: Method, body: Value) -> (StatusCode, Value) {
+    let request = Request::builder()
+        .method(method)
+        .uri("/discovery/resources")
+        .header("content-type", "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+}
+
+fn app() -> (Router, DiscoveryRegistry) {
+    let registry = DiscoveryRegistry::new();
+    let app = discovery_routes().with_state(Arc::new(registry.clone()));
+    (app, registry)
+}
+
+#[tokio::test]
+async fn test_signed_registration_is_accepted_and_owned() {
+    let (app, registry) = app();
+    let owner = PrivateKeySigner::random();
+
+    let (status, body) = call(&app, Method::POST, registration(&owner, &owner, now())).await;
+    assert_eq!(status, StatusCode::CREATED, "{body}");
+
+    let resource = registry.get(URL).await.unwrap();
+    assert_eq!(
+        resource.owner.unwrap().to_string(),
+        owner.address().to_string()
+    );
+
+    // The owner may register the URL again with a new signature
+    let (status, body) = call(&app, Method::POST, registration(&owner, &owner, now() + 1)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["replaced"], true);
+}
+
+#[tokio::test]
+async fn test_signature_is_accepted_once() {
+    let (app, _registry) = app();
+    let owner = PrivateKeySigner::random();
+    let signed = registration(&owner, &owner, now());
+
+    let (status, _) = call(&app, Method::POST, signed.clone()).await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    let (status, body) = call(&app, Method::POST, signed).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(body["error"], "Signature was already used");
+}
+
+#[tokio::test]
+async fn test_signature_covers_the_resource_body() {
+    let (app, registry) = app();
+    let owner = PrivateKeySigner::random();
+    let mut tampered = registration(&owner, &owner, now());
+    tampered["description"] = json!("Free weather, pay here");
+
+    let (status, _) = call(&app, Method::POST, tampered).await;
+
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert!(registry.get(URL).await.is_none());
+}
+
+#[tokio::test]
+async fn test_signature_by_another_key_is_rejected() {
+    let (app, registry) = app();
+    let owner = PrivateKeySigner::random();
+    let impostor = PrivateKeySigner::random();
+
+    let (status, _) = call(&app, Method::POST, registration(&owner, &impostor, now())).await;
+
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert!(registry.get(URL).await.is_none());
+}
+
+#[tokio::test]
+async fn test_stale_timestamp_is_rejected() {
+    let (app, registry) = app();
+    let owner = PrivateKeySigner::random();
+
+    let (status, _) = call(
+        &app,
+        Method::POST,
+        registration(&owner, &owner, now() - 3600),
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert!(registry.get(URL).await.is_none());
+}
+
+#[tokio::test]
+async fn test_url_owned_by_another_signer_conflicts() {
+    let (app, registry) = app();
+    let owner = PrivateKeySigner::random();
+    let other = PrivateKeySigner::random();
+    call(&app, Method::POST, registration(&owner, &owner, now())).await;
+
+    let (status, _) = call(&app, Method::POST, registration(&other, &other, now())).await;
+
+    assert_eq!(status, StatusCode::CONFLICT);
+    let resource = registry.get(URL).await.unwrap();
+    assert_eq!(
+        resource.owner.unwrap().to_string(),
+        owner.address().to_string()
+    );
+}
+
+#[tokio::test]
+async fn test_only_the_owner_removes_a_signed_resource() {
+    let (app, registry) = app();
+    let owner = PrivateKeySigner::random();
+    let other = PrivateKeySigner::random();
+    call(&app, Method::POST, registration(&owner, &owner, now())).await;
+    let timestamp = now();
+    let deletion = |signer: &PrivateKeySigner, action: SignedAction| {
+        sign(signer, action, owner.address(), timestamp, json!({ "url": URL }))
+    };
+
+    let (status, _) = call(&app, Method::DELETE, deletion(&other, SignedAction::Delete)).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    // A registration signature is not a deletion signature
+    let replayed = deletion(&owner, SignedAction::Register);
+    let (status, _) = call(&app, Method::DELETE, replayed).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert!(registry.get(URL).await.is_some());
+
+    let (status, _) = call(&app, Method::DELETE, deletion(&owner, SignedAction::Delete)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(registry.get(URL).await.is_none());
+}
@@ -0,0 +1,634 @@
+//! Deterministic simulation of discovery aggregation and registry convergence.
+//!
+//! Generates sequences of upstream feed changes and aggregation steps from a seed,
+//! replays them against the real `DiscoveryAggregator` (fetching from in-process mock
+//! facilitators) and `DiscoveryRegistry`, and checks invariants after every step.
+//!
+//! # Events
+//!
+//! - Upstream: resources are published, repriced, mirrored by another facilitator with
+//!   an identical timestamp, delisted or moved between facilitators; facilitators go down
+//!   and come back.
+//! - Aggregation: a cycle is split into `Fetch` and `Import` on one of two slots, so
+//!   cycles can overlap (fetch A, fetch B, import B, import A) or be abandoned.
+//! - Operator: resources are removed from the registry (tombstoned).
+//!
+//! Time is virtual: every event advances a counter used as the upstream `lastUpdated`.
+//!
+//! # Invariants
+//!
+//! - No URL is listed twice.
+//! - The registry matches a reference model: newest `lastUpdated` wins, the first
+//!   listing wins ties, removed resources stay hidden until a newer version appears.
+//! - Tombstoned resources are never listed; a newer upstream version resurrects them.
+//! - A fetch returns exactly the listings of the facilitators that are up.
+//! - Re-importing a cycle identical to the previous one changes nothing.
+//! - Revisions are bounded by the number of distinct upstream versions imported.
+//!
+//! # Reproducing
+//!
+//! Failures print the seed and the minimized event sequence. To replay one seed:
+//!
+//! ```text
+//! DISCOVERY_SIM_SEED=17 cargo test --test discovery_simulation -- --nocapture
+//! ```
+//!
+//! `DISCOVERY_SIM_CASES` raises the number of seeds tried (default 48).
+
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::Deserialize;
+use serde_json::json;
+use std::collections::{BTreeMap, HashSet};
+use std::sync::{Arc, Mutex};
+
+use x402_rs::discovery::{ConflictStrategy, DiscoveryError, DiscoveryRegistry};
+use x402_rs::discovery_aggregator::{DiscoveryAggregator, FacilitatorConfig};
+use x402_rs::types::TokenAmount;
+use x402_rs::types_v2::DiscoveryResource;
+
+const FACILITATORS: [&str; 3] = ["alpha", "beta", "gamma"];
+const RESOURCES: usize = 5;
+const SLOTS: usize = 2;
+const DEFAULT_CASES: u64 = 48;
+
+const USDC_BASE: &str = "0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913";
+const PAY_TO: &str = "0x1234567890123456789012345678901234567890";
+
+fn resource_url(resource: usize) -> String {
+    format!("https://api{resource}.example.com/data")
+}
+
+// ============================================================================
+// Events
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Event {
+    /// List or reprice a resource on a facilitator
+    Publish {
+        facilitator: usize,
+        resource: usize,
+        price: u64,
+    },
+    /// Copy a listing to another facilitator, keeping its timestamp
+    Mirror {
+        from: usize,
+        to: usize,
+        resource: usize,
+    },
+    Delist {
+        facilitator: usize,
+        resource: usize,
+    },
+    /// Delist from one facilitator and list (updated) on another
+    Move {
+        from: usize,
+        to: usize,
+        resource: usize,
+    },
+    Outage {
+        facilitator: usize,
+        down: bool,
+    },
+    Fetch {
+        slot: usize,
+    },
+    Import {
+        slot: usize,
+    },
+    /// Operator removes a resource from the registry
+    Remove {
+        resource: usize,
+    },
+}
+
+fn generate(seed: u64) -> Vec<Event> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let len = rng.gen_range(8..40);
+    let facilitator = |rng: &mut StdRng| rng.gen_range(0..FACILITATORS.len());
+    (0..len)
+        .map(|_| match rng.gen_range(0..100) {
+            0..=24 => Event::Publish {
+                facilitator: facilitator(&mut rng),
+                resource: rng.gen_range(0..RESOURCES),
+                price: rng.gen_range(1..4) * 1000,
+            },
+            25..=31 => Event::Mirror {
+                from: facilitator(&mut rng),
+                to: facilitator(&mut rng),
+                resource: rng.gen_range(0..RESOURCES),
+            },
+            32..=38 => Event::Delist {
+                facilitator: facilitator(&mut rng),
+                resource: rng.gen_range(0..RESOURCES),
+            },
+            39..=44 => Event::Move {
+                from: facilitator(&mut rng),
+                to: facilitator(&mut rng),
+                resource: rng.gen_range(0..RESOURCES),
+            },
+            45..=51 => Event::Outage {
+                facilitator: facilitator(&mut rng),
+                down: rng.gen_bool(0.6),
+            },
+            52..=71 => Event::Fetch {
+                slot: rng.gen_range(0..SLOTS),
+            },
+            72..=91 => Event::Import {
+                slot: rng.gen_range(0..SLOTS),
+            },
+            _ => Event::Remove {
+                resource: rng.gen_range(0..RESOURCES),
+            },
+        })
+        .collect()
+}
+
+// ============================================================================
+// Mock Upstream
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Listing {
+    price: u64,
+    last_updated: u64,
+}
+
+#[derive(Debug, Default)]
+struct Upstream {
+    feeds: [BTreeMap<usize, Listing>; 3],
+    down: [bool; 3],
+}
+
+#[derive(Deserialize)]
+struct PageQuery {
+    limit: usize,
+    offset: usize,
+}
+
+async fn serve_feed(
+    State(upstream): State<Arc<Mutex<Upstream>>>,
+    Path(facilitator): Path<usize>,
+    Query(page): Query<PageQuery>,
+) -> Response {
+    let upstream = upstream.lock().unwrap();
+    if upstream.down[facilitator] {
+        return StatusCode::SERVICE_UNAVAILABLE.into_response();
+    }
+    let feed = &upstream.feeds[facilitator];
+    let items: Vec<_> = feed
+        .iter()
+        .skip(page.offset)
+        .take(page.limit)
+        .map(|(resource, listing)| {
+            json!({
+                "url": resource_url(*resource),
+                "type": "http",
+                "description": format!("Resource {resource}"),
+                "lastUpdated": listing.last_updated,
+                "accepts": [{
+                    "scheme": "exact",
+                    "network": "base",
+                    "asset": USDC_BASE,
+                    "amount": listing.price.to_string(),
+                    "payTo": PAY_TO
+                }]
+            })
+        })
+        .collect();
+    Json(json!({ "items": items, "pagination": { "total": feed.len() } })).into_response()
+}
+
+// ============================================================================
+// Reference Model
+// ============================================================================
+
+/// What the registry should serve for a resource.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Version {
+    facilitator: usize,
+    price: u64,
+    last_updated: u64,
+}
+
+#[derive(Debug, Default)]
+struct Model {
+    served: BTreeMap<usize, Version>,
+    tombstones: BTreeMap<usize, u64>,
+}
+
+impl Model {
+    /// Apply an import, returning the number of revisions (adds + updates).
+    fn import(&mut self, batch: &[(usize, Version)]) -> usize {
+        let mut revisions = 0;
+        for &(resource, version) in batch {
+            if let Some(&removed_at) = self.tombstones.get(&resource) {
+                if version.last_updated <= removed_at {
+                    continue;
+                }
+                self.tombstones.remove(&resource);
+            }
+            match self.served.get(&resource) {
+                Some(current) if version.last_updated <= current.last_updated => {}
+                _ => {
+                    self.served.insert(resource, version);
+                    revisions += 1;
+                }
+            }
+        }
+        revisions
+    }
+}
+
+/// Map an aggregated resource back to model terms.
+fn observe(resource: &DiscoveryResource) -> Result<(usize, Version), String> {
+    let index = (0..RESOURCES)
+        .find(|&i| resource.url.as_str() == resource_url(i))
+        .ok_or_else(|| format!("unexpected url {}", resource.url))?;
+    let facilitator = resource
+        .source_facilitator
+        .as_deref()
+        .and_then(|id| FACILITATORS.iter().position(|f| *f == id))
+        .ok_or_else(|| format!("{} has no known source facilitator", resource.url))?;
+    let price = (1..4)
+        .map(|p| p * 1000)
+        .find(|&p| resource.accepts.first().map(|a| a.amount) == Some(TokenAmount::from(p)))
+        .ok_or_else(|| format!("{} has an unexpected price", resource.url))?;
+    Ok((
+        index,
+        Version {
+            facilitator,
+            price,
+            last_updated: resource.last_updated,
+        },
+    ))
+}
+
+// ============================================================================
+// Simulation
+// ============================================================================
+
+struct Sim {
+    upstream: Arc<Mutex<Upstream>>,
+    aggregator: DiscoveryAggregator,
+    registry: DiscoveryRegistry,
+    model: Model,
+    pending: [Option<Vec<DiscoveryResource>>; SLOTS],
+    clock: u64,
+    last_import: Option<Vec<(usize, Version)>>,
+    revisions: usize,
+    imported_versions: HashSet<(usize, u64)>,
+}
+
+impl Sim {
+    async fn start() -> Self {
+        let upstream = Arc::new(Mutex::new(Upstream::default()));
+        let app = Router::new()
+            .route("/{facilitator}/resources", get(serve_feed))
+            .with_state(Arc::clone(&upstream));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+
+        let facilitators = FACILITATORS
+            .iter()
+            .enumerate()
+            .map(|(i, id)| FacilitatorConfig {
+                id: id.to_string(),
+                name: id.to_string(),
+                discovery_url: format!("{base}/{i}/resources"),
+                enabled: true,
+                timeout_secs: 5,
+                headers: Vec::new(),
+                api_key_env: None,
+                api_key: None,
+                api_key_header: None,
+                min_request_interval_ms: None,
+            })
+            .collect();
+
+        Self {
+            upstream,
+            // Outages are modelled as instantly visible, so no facilitator is skipped
+            aggregator: DiscoveryAggregator::with_facilitators(facilitators)
+                .with_breaker_threshold(0),
+            registry: DiscoveryRegistry::new(),
+            model: Model::default(),
+            pending: Default::default(),
+            clock: 1_000,
+            last_import: None,
+            revisions: 0,
+            imported_versions: HashSet::new(),
+        }
+    }
+
+    async fn step(&mut self, event: Event) -> Result<(), String> {
+        self.clock += 1;
+        let now = self.clock;
+        match event {
+            Event::Publish {
+                facilitator,
+                resource,
+                price,
+            } => {
+                let listing = Listing {
+                    price,
+                    last_updated: now,
+                };
+                self.upstream.lock().unwrap().feeds[facilitator].insert(resource, listing);
+            }
+            Event::Mirror { from, to, resource } => {
+                let mut upstream = self.upstream.lock().unwrap();
+                if let Some(listing) = upstream.feeds[from].get(&resource).copied() {
+                    upstream.feeds[to].insert(resource, listing);
+                }
+            }
+            Event::Delist {
+                facilitator,
+                resource,
+            } => {
+                self.upstream.lock().unwrap().feeds[facilitator].remove(&resource);
+            }
+            Event::Move { from, to, resource } => {
+                let mut upstream = self.upstream.lock().unwrap();
+                if let Some(listing) = upstream.feeds[from].remove(&resource) {
+                    upstream.feeds[to].insert(
+                        resource,
+                        Listing {
+                            price: listing.price,
+                            last_updated: now,
+                        },
+                    );
+                }
+            }
+            Event::Outage { facilitator, down } => {
+                self.upstream.lock().unwrap().down[facilitator] = down;
+            }
+            Event::Fetch { slot } => {
+                let expected = self.reachable_listings();
+                let fetched = self.aggregator.fetch_all().await;
+                let observed = fetched.iter().map(observe).collect::<Result<Vec<_>, _>>()?;
+                if observed != expected {
+                    return Err(format!(
+                        "fetch returned {observed:?}, reachable listings are {expected:?}"
+                    ));
+                }
+                self.pending[slot] = Some(fetched);
+            }
+            Event::Import { slot } => {
+                let Some(batch) = self.pending[slot].take() else {
+                    return Ok(());
+                };
+                self.import(batch).await?;
+            }
+            Event::Remove { resource } => {
+                let url = resource_url(resource);
+                match (
+                    self.registry.unregister(&url).await,
+                    self.model.served.remove(&resource),
+                ) {
+                    (Ok(removed), Some(version)) => {
+                        if removed.last_updated != version.last_updated {
+                            return Err(format!("removed {url} at an unexpected version"));
+                        }
+                        self.model.tombstones.insert(resource, version.last_updated);
+                    }
+                    (Err(DiscoveryError::NotFound(_)), None) => {}
+                    (result, expected) => {
+                        return Err(format!(
+                            "unregister {url} returned {result:?}, model had {expected:?}"
+                        ))
+                    }
+                }
+            }
+        }
+        self.check().await
+    }
+
+    /// Listings of facilitators that are up, merged per resource the way the
+    /// aggregator merges them: first-listed position, newest listing wins, the
+    /// earliest in visiting order on ties.
+    fn reachable_listings(&self) -> Vec<(usize, Version)> {
+        let upstream = self.upstream.lock().unwrap();
+        let mut merged: Vec<(usize, Version)> = Vec::new();
+        for f in (0..FACILITATORS.len()).filter(|&f| !upstream.down[f]) {
+            for (&resource, listing) in &upstream.feeds[f] {
+                let version = Version {
+                    facilitator: f,
+                    price: listing.price,
+                    last_updated: listing.last_updated,
+                };
+                match merged.iter_mut().find(|(r, _)| *r == resource) {
+                    Some((_, kept)) if version.last_updated > kept.last_updated => *kept = version,
+                    Some(_) => {}
+                    None => merged.push((resource, version)),
+                }
+            }
+        }
+        merged
+    }
+
+    async fn import(&mut self, batch: Vec<DiscoveryResource>) -> Result<(), String> {
+        let observed = batch.iter().map(observe).collect::<Result<Vec<_>, _>>()?;
+        let repeated = self.last_import.as_ref() == Some(&observed);
+        let before = self.listing().await?;
+
+        let (added, updated, _) = self
+            .registry
+            .bulk_import(batch, true, &[ConflictStrategy::PreferNewer])
+            .await
+            .map_err(|e| format!("bulk_import failed: {e}"))?;
+        let expected_revisions = self.model.import(&observed);
+        if added + updated != expected_revisions {
+            return Err(format!(
+                "import made {} revisions, model expected {expected_revisions}",
+                added + updated
+            ));
+        }
+
+        // Dedupe winner stability: an identical cycle is a no-op
+        if repeated && (added + updated > 0 || self.listing().await? != before) {
+            return Err("re-importing an identical cycle changed the registry".to_string());
+        }
+
+        // Revision storms: at most one revision per distinct upstream version
+        self.revisions += added + updated;
+        self.imported_versions
+            .extend(observed.iter().map(|(r, v)| (*r, v.last_updated)));
+        if self.revisions > self.imported_versions.len() {
+            return Err(format!(
+                "{} revisions for {} distinct upstream versions",
+                self.revisions,
+                self.imported_versions.len()
+            ));
+        }
+
+        // Resurrect-after-reappear: anything imported and not tombstoned is served at
+        // least at the imported version
+        let after = self.listing().await?;
+        for (resource, version) in &observed {
+            if self.model.tombstones.contains_key(resource) {
+                continue;
+            }
+            if after
+                .get(resource)
+                .is_none_or(|served| served.last_updated < version.last_updated)
+            {
+                return Err(format!(
+                    "{} not served after importing version {}",
+                    resource_url(*resource),
+                    version.last_updated
+                ));
+            }
+        }
+
+        self.last_import = Some(observed);
+        Ok(())
+    }
+
+    /// Public listing as `(resource, version)` pairs, rejecting duplicate URLs.
+    async fn listing(&self) -> Result<BTreeMap<usize, Version>, String> {
+        let response = self.registry.list(100, 0, None).await;
+        let mut listed = BTreeMap::new();
+        for resource in &response.items {
+            let (index, version) = observe(resource)?;
+            if listed.insert(index, version).is_some() {
+                return Err(format!("{} listed more than once", resource.url));
+            }
+        }
+        Ok(listed)
+    }
+
+    async fn check(&self) -> Result<(), String> {
+        let listed = self.listing().await?;
+        for resource in self.model.tombstones.keys() {
+            if listed.contains_key(resource) {
+                return Err(format!("tombstoned {} is served", resource_url(*resource)));
+            }
+        }
+        if listed != self.model.served {
+            return Err(format!(
+                "registry serves {listed:?}, model expects {:?}",
+                self.model.served
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Replay `events`, returning the failing step and violated invariant.
+fn run(events: &[Event]) -> Result<(), (usize, String)> {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    runtime.block_on(async {
+        let mut sim = Sim::start().await;
+        for (i, event) in events.iter().enumerate() {
+            sim.step(*event).await.map_err(|violation| (i, violation))?;
+        }
+        Ok(())
+    })
+}
+
+/// Remove chunks of events while the sequence keeps failing.
+fn shrink(mut events: Vec<Event>, fails: impl Fn(&[Event]) -> bool) -> Vec<Event> {
+    let mut chunk = events.len() / 2;
+    while chunk > 0 {
+        let mut progressed = false;
+        let mut start = 0;
+        while start + chunk <= events.len() {
+            let mut candidate = events[..start].to_vec();
+            candidate.extend_from_slice(&events[start + chunk..]);
+            if fails(&candidate) {
+                events = candidate;
+                progressed = true;
+            } else {
+                start += chunk;
+            }
+        }
+        if !progressed {
+            chunk /= 2;
+        }
+    }
+    events
+}
+
+fn env_u64(name: &str) -> Option<u64> {
+    std::env::var(name).ok().and_then(|v| v.trim().parse().ok())
+}
+
+#[test]
+fn test_aggregation_converges_under_random_interleavings() {
+    let seeds: Vec<u64> = match env_u64("DISCOVERY_SIM_SEED") {
+        Some(seed) => vec![seed],
+        None => (0..env_u64("DISCOVERY_SIM_CASES").unwrap_or(DEFAULT_CASES)).collect(),
+    };
+    for seed in seeds {
+        let events = generate(seed);
+        let Err((step, violation)) = run(&events) else {
+            continue;
+        };
+        let minimized = shrink(events.clone(), |candidate| run(candidate).is_err());
+        let (min_step, min_violation) = run(&minimized).unwrap_err();
+        let trace: String = minimized
+            .iter()
+            .enumerate()
+            .map(|(i, event)| format!("  {i:>3}: {event:?}\n"))
+            .collect();
+        panic!(
+            "discovery simulation failed for seed {seed}: {violation} (step {step} of {})\n\
+             minimized to {} events, failing at step {min_step}: {min_violation}\n{trace}\
+             reproduce with: DISCOVERY_SIM_SEED={seed} cargo test --test discovery_simulation",
+            events.len(),
+            minimized.len(),
+        );
+    }
+}
+
+#[test]
+fn test_cycle_fetched_before_removal_does_not_resurrect() {
+    let events = [
+        Event::Publish {
+            facilitator: 0,
+            resource: 1,
+            price: 1000,
+        },
+        Event::Fetch { slot: 0 },
+        Event::Fetch { slot: 1 },
+        Event::Import { slot: 0 },
+        Event::Remove { resource: 1 },
+        // The overlapping cycle fetched before the removal lands afterwards
+        Event::Import { slot: 1 },
+        // A newer upstream version brings it back
+        Event::Publish {
+            facilitator: 1,
+            resource: 1,
+            price: 2000,
+        },
+        Event::Fetch { slot: 0 },
+        Event::Import { slot: 0 },
+    ];
+    run(&events).unwrap();
+}
+
+#[test]
+fn test_shrink_keeps_a_minimal_failing_sequence() {
+    let events = generate(7);
+    let removes = |events: &[Event]| events.iter().any(|e| matches!(e, Event::Remove { .. }));
+    let events: Vec<Event> = events
+        .into_iter()
+        .chain([Event::Remove { resource: 0 }])
+        .collect();
+    let minimized = shrink(events, removes);
+    assert_eq!(minimized.len(), 1);
+    assert!(removes(&minimized));
+}
@@ -0,0 +1,100 @@
+//! `GET /discovery/sources` after an aggregation run against mocked facilitators.
+
+use axum::body::{to_bytes, Body};
+use axum::http::{Request, StatusCode};
+use axum::routing::get;
+use axum::{Extension, Router};
+use serde_json::{json, Value};
+use tower::ServiceExt;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+use x402_rs::discovery_aggregator::{DiscoveryAggregator, FacilitatorConfig, SharedSourceStats};
+use x402_rs::handlers::get_discovery_sources;
+
+fn facilitator(id: &str, server: &MockServer) -> FacilitatorConfig {
+    FacilitatorConfig {
+        id: id.to_string(),
+        name: format!("{id} facilitator"),
+        discovery_url: format!("{}/discovery/resources", server.uri()),
+        enabled: true,
+        timeout_secs: 5,
+        headers: Vec::new(),
+        api_key_env: None,
+        api_key: None,
+        api_key_header: None,
+        min_request_interval_ms: None,
+    }
+}
+
+async fn get_sources(stats: SharedSourceStats) -> Value {
+    let app = Router::new()
+        .route("/discovery/sources", get(get_discovery_sources))
+        .layer(Extension(stats));
+    let response = app
+        .oneshot(
+            Request::get("/discovery/sources")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    serde_json::from_slice(&to_bytes(response.into_body(), usize::MAX).await.unwrap()).unwrap()
+}
+
+#[tokio::test]
+async fn sources_report_live_aggregation_stats() {
+    let healthy = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/discovery/resources"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "items": [
+                { "url": "https://a.example.com/data", "lastUpdated": 1 },
+                { "url": "https://b.example.com/data", "lastUpdated": 1 }
+            ],
+            "pagination": { "total": 2 }
+        })))
+        .mount(&healthy)
+        .await;
+    let failing = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(503))
+        .mount(&failing)
+        .await;
+
+    let stats = SharedSourceStats::default();
+    let aggregator = DiscoveryAggregator::with_facilitators(vec![
+        facilitator("healthy", &healthy),
+        facilitator("failing", &failing),
+    ])
+    .with_breaker_threshold(1)
+    .with_stats(stats.clone());
+
+    // Configured sources are listed before any fetch
+    let before = get_sources(stats.clone()).await;
+    assert_eq!(before["sources"].as_array().unwrap().len(), 2);
+    assert_eq!(before["sources"][1]["lastFetchAt"], Value::Null);
+
+    aggregator.fetch_all_with_report().await;
+    let body = get_sources(stats).await;
+    let sources = body["sources"].as_array().unwrap();
+
+    let failing = &sources[0];
+    assert_eq!(failing["id"], "failing");
+    assert!(failing["lastFetchAt"].is_u64());
+    assert_eq!(failing["lastSuccessAt"], Value::Null);
+    assert_eq!(failing["resources"], 0);
+    assert!(failing["lastError"].as_str().unwrap().contains("503"));
+    assert_eq!(failing["breaker"]["consecutiveFailures"], 1);
+    assert!(failing["breaker"]["openUntil"].is_u64());
+
+    let healthy = &sources[1];
+    assert_eq!(healthy["id"], "healthy");
+    assert_eq!(healthy["name"], "healthy facilitator");
+    assert_eq!(healthy["enabled"], true);
+    assert!(healthy["lastSuccessAt"].is_u64());
+    assert_eq!(healthy["resources"], 2);
+    assert_eq!(healthy["lastError"], Value::Null);
+    assert_eq!(healthy["breaker"]["consecutiveFailures"], 0);
+}
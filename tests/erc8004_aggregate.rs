@@ -0,0 +1,199 @@
+//! `GET /reputation/aggregate` combines the registries of two mocked networks.
+
+mod common;
+
+use alloy::primitives::{address, Address, Bytes};
+use alloy::providers::{ProviderBuilder, RootProvider};
+use alloy::sol_types::{SolCall, SolValue};
+use std::collections::HashMap;
+use std::time::Duration;
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+use x402_rs::erc8004::aggregate::aggregate_reputation;
+use x402_rs::erc8004::batch::ReputationCache;
+use x402_rs::erc8004::{
+    AgentRegistration, AggregatedReputationResponse, Erc8004Client, Erc8004Contracts,
+    IIdentityRegistry, IReputationRegistry, ETHEREUM_MAINNET_CONTRACTS, ETHEREUM_SEPOLIA_CONTRACTS,
+};
+use x402_rs::network::Network;
+
+const AGENT_URI: &str = "ipfs://bafybeiagent";
+const OWNER: Address = address!("1111111111111111111111111111111111111111");
+
+/// Mocked node where the agent exists and has `count` feedback entries averaging `value`.
+async fn registry_node(count: u64, value: i128) -> MockServer {
+    common::eth_call_node(move |_, input| {
+        Ok(match input[..4].try_into().unwrap() {
+            IIdentityRegistry::ownerOfCall::SELECTOR => OWNER.abi_encode(),
+            IReputationRegistry::getSummaryCall::SELECTOR => {
+                IReputationRegistry::getSummaryCall::abi_encode_returns(
+                    &IReputationRegistry::getSummaryReturn {
+                        count,
+                        summaryValue: value,
+                        summaryValueDecimals: 0,
+                    },
+                )
+            }
+            selector => panic!("unexpected call {}", Bytes::copy_from_slice(&selector)),
+        })
+    })
+    .await
+}
+
+/// Mocked node that is down.
+async fn failing_node() -> MockServer {
+    let server = MockServer::start().await;
+    Mock::given(wiremock::matchers::method("POST"))
+        .respond_with(ResponseTemplate::new(503))
+        .mount(&server)
+        .await;
+    server
+}
+
+fn client(
+    server: &MockServer,
+    contracts: Erc8004Contracts,
+    network: Network,
+) -> Erc8004Client<RootProvider> {
+    let provider = ProviderBuilder::new()
+        .disable_recommended_fillers()
+        .connect_http(server.uri().parse().unwrap());
+    Erc8004Client::new(provider, contracts).with_network(network)
+}
+
+fn registration(network: Network, contracts: Erc8004Contracts, agent_id: u64) -> AgentRegistration {
+    AgentRegistration {
+        agent_id,
+        agent_registry: format!("{}:{}", network.to_caip2(), contracts.identity_registry),
+    }
+}
+
+async fn aggregate(
+    cache: &ReputationCache,
+    mainnet: &MockServer,
+    sepolia: &MockServer,
+) -> AggregatedReputationResponse {
+    let clients = HashMap::from([
+        (
+            Network::Ethereum,
+            client(mainnet, ETHEREUM_MAINNET_CONTRACTS, Network::Ethereum),
+        ),
+        (
+            Network::EthereumSepolia,
+            client(
+                sepolia,
+                ETHEREUM_SEPOLIA_CONTRACTS,
+                Network::EthereumSepolia,
+            ),
+        ),
+    ]);
+    let registrations = [
+        registration(Network::Ethereum, ETHEREUM_MAINNET_CONTRACTS, 7),
+        registration(Network::EthereumSepolia, ETHEREUM_SEPOLIA_CONTRACTS, 42),
+    ];
+    aggregate_reputation(cache, AGENT_URI, &registrations, &clients).await
+}
+
+#[tokio::test]
+async fn test_summaries_are_weighted_by_count() {
+    let cache = ReputationCache::new(Duration::from_secs(60));
+    let mainnet = registry_node(3, 90).await;
+    let sepolia = registry_node(1, 50).await;
+
+    let response = aggregate(&cache, &mainnet, &sepolia).await;
+
+    assert_eq!(response.agent_uri, AGENT_URI);
+    assert_eq!(response.networks.len(), 2);
+    let mainnet_entry = &response.networks[0];
+    assert_eq!(mainnet_entry.network, Some(Network::Ethereum));
+    assert_eq!(mainnet_entry.agent_id, 7);
+    assert_eq!(mainnet_entry.summary.as_ref().unwrap().count, 3);
+    let sepolia_entry = &response.networks[1];
+    assert_eq!(sepolia_entry.network, Some(Network::EthereumSepolia));
+    assert_eq!(sepolia_entry.agent_id, 42);
+    assert_eq!(sepolia_entry.summary.as_ref().unwrap().count, 1);
+    assert_eq!(response.total_count, 4);
+    assert_eq!(response.weighted_score, Some(80.0));
+    assert!(!response.weighting.is_empty());
+
+    let json = serde_json::to_value(&response).unwrap();
+    assert_eq!(json["weightedScore"], 80.0);
+    assert_eq!(
+        json["networks"][0]["agentRegistry"],
+        mainnet_entry.agent_registry
+    );
+    assert!(json["networks"][0].get("error").is_none());
+}
+
+#[tokio::test]
+async fn test_failing_network_is_reported_alone() {
+    let cache = ReputationCache::new(Duration::from_secs(60));
+    let mainnet = failing_node().await;
+    let sepolia = registry_node(2, 70).await;
+
+    let response = aggregate(&cache, &mainnet, &sepolia).await;
+
+    let mainnet_entry = &response.networks[0];
+    assert!(mainnet_entry.summary.is_none());
+    assert!(mainnet_entry.error.is_some());
+    assert_eq!(response.networks[1].summary.as_ref().unwrap().count, 2);
+    assert_eq!(response.total_count, 2);
+    assert_eq!(response.weighted_score, Some(70.0));
+}
+
+#[tokio::test]
+async fn test_summaries_are_cached_per_network() {
+    let cache = ReputationCache::new(Duration::from_secs(60));
+    let mainnet = registry_node(3, 90).await;
+    let sepolia = registry_node(1, 50).await;
+    aggregate(&cache, &mainnet, &sepolia).await;
+    let requests = mainnet.received_requests().await.unwrap().len();
+
+    let response = aggregate(&cache, &mainnet, &sepolia).await;
+
+    assert!(response.networks.iter().all(|network| network.cached));
+    assert_eq!(mainnet.received_requests().await.unwrap().len(), requests);
+    assert_eq!(response.weighted_score, Some(80.0));
+}
+
+#[tokio::test]
+async fn test_unusable_registrations_are_reported() {
+    let cache = ReputationCache::new(Duration::from_secs(60));
+    let sepolia = registry_node(1, 50).await;
+    let clients = HashMap::from([(
+        Network::EthereumSepolia,
+        client(
+            &sepolia,
+            ETHEREUM_SEPOLIA_CONTRACTS,
+            Network::EthereumSepolia,
+        ),
+    )]);
+    let registrations = [
+        // Supported network without a provider
+        registration(Network::Ethereum, ETHEREUM_MAINNET_CONTRACTS, 7),
+        // Network without ERC-8004 registries
+        registration(Network::Base, ETHEREUM_MAINNET_CONTRACTS, 8),
+        // Registry that is not the canonical deployment
+        AgentRegistration {
+            agent_id: 9,
+            agent_registry: format!("eip155:11155111:{}", Address::repeat_byte(0x80)),
+        },
+        AgentRegistration {
+            agent_id: 10,
+            agent_registry: "not a registry".to_string(),
+        },
+    ];
+
+    let response = aggregate_reputation(&cache, AGENT_URI, &registrations, &clients).await;
+
+    assert_eq!(response.networks.len(), 4);
+    assert!(response
+        .networks
+        .iter()
+        .all(|network| network.summary.is_none() && network.error.is_some()));
+    assert_eq!(response.networks[1].network, Some(Network::Base));
+    assert_eq!(response.networks[3].network, None);
+    assert_eq!(response.total_count, 0);
+    assert_eq!(response.weighted_score, None);
+    assert!(sepolia.received_requests().await.unwrap().is_empty());
+}
@@ -0,0 +1,201 @@
+//! ERC-8004 feedback submission against a stand-in Reputation Registry on a local
+//! Anvil node.
+//!
+//! Needs `anvil` (from Foundry) on the PATH and the `anvil` feature:
+//!
+//! ```text
+//! cargo test --features anvil --test erc8004_feedback
+//! ```
+
+#![cfg(feature = "anvil")]
+
+mod common;
+
+use std::sync::Arc;
+
+use alloy::node_bindings::AnvilInstance;
+use alloy::primitives::{keccak256, Address, Bytes, B256, U256};
+use alloy::providers::Provider;
+use alloy::sol_types::SolEvent;
+use x402_rs::chain::evm::{EvmProvider, MetaEvmProvider};
+use x402_rs::erc8004::saga::{
+    EvmFeedbackChain, FeedbackSaga, InMemoryFeedbackJournal, InMemoryProofClaims,
+    InMemorySagaStore, LogNotifier, SagaError,
+};
+use x402_rs::erc8004::{FeedbackParams, FeedbackRequest, IReputationRegistry, ProofOfPayment};
+use x402_rs::network::Network;
+use x402_rs::types::{MixedAddress, SettleConfirmation, TokenAmount, TransactionHash, X402Version};
+
+const REGISTRY: Address = Address::repeat_byte(0x80);
+const AGENT_ID: u64 = 42;
+const FEEDBACK_INDEX: u64 = 7;
+const TAG1: &str = "starred";
+
+/// Runtime code of a stand-in Reputation Registry: any call emits the same
+/// `NewFeedback` log, whose ABI-encoded data is appended to the code and copied into
+/// memory with CODECOPY.
+fn registry_code(client: Address) -> Bytes {
+    let event = IReputationRegistry::NewFeedback {
+        agentId: U256::from(AGENT_ID),
+        clientAddress: client,
+        feedbackIndex: FEEDBACK_INDEX,
+        value: 90,
+        valueDecimals: 0,
+        indexedTag1: keccak256(TAG1),
+        tag1: TAG1.to_string(),
+        tag2: String::new(),
+        endpoint: String::new(),
+        feedbackURI: String::new(),
+        feedbackHash: B256::ZERO,
+    };
+    let data = event.encode_data();
+    let size = u16::try_from(data.len()).unwrap().to_be_bytes();
+    // The prefix below is 148 bytes long; the event data starts right after it
+    let offset = 148u16.to_be_bytes();
+
+    let mut code = vec![0x61, size[0], size[1]]; // PUSH2 size
+    code.extend_from_slice(&[0x61, offset[0], offset[1]]); // PUSH2 offset
+    code.extend_from_slice(&[0x60, 0x00, 0x39]); // CODECOPY(0, offset, size)
+    let topics = [
+        keccak256(TAG1),
+        client.into_word(),
+        B256::from(U256::from(AGENT_ID)),
+        IReputationRegistry::NewFeedback::SIGNATURE_HASH,
+    ];
+    for topic in topics {
+        code.push(0x7f); // PUSH32
+        code.extend_from_slice(topic.as_slice());
+    }
+    code.extend_from_slice(&[0x61, size[0], size[1], 0x60, 0x00, 0xa4]); // LOG4(0, size, ...)
+    code.push(0x00); // STOP
+    assert_eq!(code.len(), 148);
+    code.extend_from_slice(&data);
+    code.into()
+}
+
+/// Start Anvil with the registry runtime code built by `code` from the signer address.
+async fn setup(code: impl FnOnce(Address) -> Bytes) -> (AnvilInstance, EvmProvider, Address) {
+    let (anvil, provider) = common::anvil(Network::BaseSepolia).await;
+    let signer_address = anvil.addresses()[0];
+    common::set_code(&provider, REGISTRY, code(signer_address)).await;
+    (anvil, provider, signer_address)
+}
+
+fn saga() -> FeedbackSaga {
+    FeedbackSaga::new(
+        Arc::new(InMemoryProofClaims::default()),
+        Arc::new(InMemoryFeedbackJournal::default()),
+        Arc::new(LogNotifier),
+        Arc::new(InMemorySagaStore::default()),
+    )
+}
+
+fn request(proof: Option<ProofOfPayment>) -> FeedbackRequest {
+    FeedbackRequest {
+        x402_version: X402Version::V1,
+        network: Network::BaseSepolia,
+        feedback: FeedbackParams {
+            agent_id: AGENT_ID,
+            value: 90,
+            value_decimals: 0,
+            tag1: TAG1.to_string(),
+            tag2: String::new(),
+            endpoint: String::new(),
+            feedback_uri: String::new(),
+            feedback_hash: None,
+            proof,
+        },
+        feedback_file: None,
+    }
+}
+
+fn proof() -> ProofOfPayment {
+    ProofOfPayment::new(
+        TransactionHash::Evm([7u8; 32]),
+        SettleConfirmation {
+            block_number: 100,
+            block_timestamp: Some(1_700_000_000),
+            gas_used: None,
+        },
+        Network::BaseSepolia,
+        MixedAddress::Offchain("payer".into()),
+        MixedAddress::Offchain("payee".into()),
+        TokenAmount::from(1_000u64),
+        MixedAddress::Offchain("usdc".into()),
+    )
+}
+
+#[tokio::test]
+async fn test_feedback_is_submitted_and_indexed() {
+    let (_anvil, provider, _) = setup(registry_code).await;
+    let chain = EvmFeedbackChain::new(provider.inner().clone(), REGISTRY);
+    let saga = saga();
+
+    let receipt = saga.run(&chain, request(Some(proof()))).await.unwrap();
+
+    assert_eq!(receipt.agent_id, AGENT_ID);
+    assert_eq!(receipt.feedback_index, FEEDBACK_INDEX);
+    let mined = provider
+        .inner()
+        .get_transaction_receipt(receipt.transaction)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(mined.status());
+
+    // The proof was consumed by the confirmed feedback
+    let replay = saga.run(&chain, request(Some(proof()))).await;
+    assert!(
+        matches!(replay, Err(SagaError::ProofAlreadyUsed)),
+        "{replay:?}"
+    );
+}
+
+#[tokio::test]
+async fn test_reverting_registry_fails_at_submission() {
+    // REVERT(0, 0)
+    let (_anvil, provider, _) = setup(|_| vec![0x60, 0x00, 0x80, 0xfd].into()).await;
+    let chain = EvmFeedbackChain::new(provider.inner().clone(), REGISTRY);
+    let saga = saga();
+
+    let result = saga.run(&chain, request(Some(proof()))).await;
+    let error = result.unwrap_err();
+    assert!(matches!(error, SagaError::Submission(_)), "{error:?}");
+    assert!(error
+        .to_string()
+        .starts_with("Failed to submit feedback transaction"));
+
+    // Nothing reached the chain, so the proof was released for a retry
+    let retry = saga.run(&chain, request(Some(proof()))).await;
+    assert!(matches!(retry, Err(SagaError::Submission(_))), "{retry:?}");
+}
+
+#[tokio::test]
+async fn test_registry_without_event_is_rejected() {
+    // STOP: the call succeeds but emits no NewFeedback
+    let (_anvil, provider, _) = setup(|_| vec![0x00].into()).await;
+    let chain = EvmFeedbackChain::new(provider.inner().clone(), REGISTRY);
+
+    let error = saga().run(&chain, request(None)).await.unwrap_err();
+    assert!(matches!(error, SagaError::Rejected { .. }), "{error:?}");
+    assert!(error.to_string().contains("did not emit NewFeedback"));
+}
+
+#[tokio::test]
+async fn test_inconsistent_proof_is_rejected_before_submission() {
+    let (_anvil, provider, signer) = setup(|_| vec![0x00].into()).await;
+    let chain = EvmFeedbackChain::new(provider.inner().clone(), REGISTRY);
+    let mut proof = proof();
+    proof.payment_hash = B256::repeat_byte(0x01);
+
+    let error = saga().run(&chain, request(Some(proof))).await.unwrap_err();
+    assert!(matches!(error, SagaError::InvalidProof(_)), "{error:?}");
+    assert_eq!(
+        provider
+            .inner()
+            .get_transaction_count(signer)
+            .await
+            .unwrap(),
+        0
+    );
+}
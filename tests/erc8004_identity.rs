@@ -0,0 +1,146 @@
+//! ERC-8004 agent registration against a stand-in Identity Registry on a local Anvil
+//! node.
+//!
+//! Needs `anvil` (from Foundry) on the PATH and the `anvil` feature:
+//!
+//! ```text
+//! cargo test --features anvil --test erc8004_identity
+//! ```
+
+#![cfg(feature = "anvil")]
+
+mod common;
+
+use alloy::node_bindings::AnvilInstance;
+use alloy::primitives::{Address, Bytes, U256};
+use alloy::sol_types::{SolCall, SolEvent};
+use common::{dispatcher, jumpdest};
+use x402_rs::chain::evm::{EvmProvider, MetaEvmProvider};
+use x402_rs::erc8004::identity::{register_agent, RegistrationError};
+use x402_rs::erc8004::{AgentMetadataParam, IIdentityRegistry, RegisterAgentRequest};
+use x402_rs::network::Network;
+
+const REGISTRY: Address = Address::repeat_byte(0x80);
+const AGENT_URI: &str = "ipfs://bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi";
+
+// Entry points of the stand-in registry code
+const REGISTER: u8 = 0x28;
+const OWNER_OF: u8 = 0x6b;
+const TRANSFER_FROM: u8 = 0x78;
+const TRANSFER_ALLOWED: u8 = 0x86;
+
+/// Runtime code of a stand-in Identity Registry. Agent IDs count up from 1 in slot 0
+/// and the owner of agent `n` is kept in slot `n`:
+///
+/// - `register(agentURI, metadata)` mints the next agent to the caller and emits
+///   `Registered` with an empty `agentURI`
+/// - `ownerOf(agentId)` returns the owner, or the zero address
+/// - `transferFrom(from, to, agentId)` hands the agent to `to` if the caller owns it
+fn registry_code() -> Bytes {
+    let mut code = dispatcher(&[
+        (IIdentityRegistry::register_0Call::SELECTOR, REGISTER),
+        (IIdentityRegistry::ownerOfCall::SELECTOR, OWNER_OF),
+        (IIdentityRegistry::transferFromCall::SELECTOR, TRANSFER_FROM),
+    ]);
+
+    jumpdest(&mut code, REGISTER);
+    code.extend_from_slice(&[0x60, 0x00, 0x54, 0x60, 0x01, 0x01]); // id = SLOAD(0) + 1
+    code.extend_from_slice(&[0x80, 0x60, 0x00, 0x55]); // SSTORE(0, id)
+    code.extend_from_slice(&[0x33, 0x81, 0x55]); // SSTORE(id, CALLER)
+    code.extend_from_slice(&[0x60, 0x20, 0x60, 0x00, 0x52]); // MSTORE(0, 0x20): empty string
+    code.extend_from_slice(&[0x33, 0x81, 0x7f]); // CALLER DUP2 PUSH32 signature
+    code.extend_from_slice(IIdentityRegistry::Registered::SIGNATURE_HASH.as_slice());
+    code.extend_from_slice(&[0x60, 0x40, 0x60, 0x00, 0xa3]); // LOG3(0, 0x40, ...)
+    code.extend_from_slice(&[0x60, 0x00, 0x52, 0x60, 0x20, 0x60, 0x00, 0xf3]); // return id
+
+    jumpdest(&mut code, OWNER_OF);
+    code.extend_from_slice(&[0x60, 0x04, 0x35, 0x54]); // SLOAD(agentId)
+    code.extend_from_slice(&[0x60, 0x00, 0x52, 0x60, 0x20, 0x60, 0x00, 0xf3]); // return it
+
+    jumpdest(&mut code, TRANSFER_FROM);
+    code.extend_from_slice(&[0x60, 0x44, 0x35, 0x54, 0x33, 0x14]); // SLOAD(agentId) == CALLER
+    code.extend_from_slice(&[0x60, TRANSFER_ALLOWED, 0x57, 0x60, 0x00, 0x80, 0xfd]); // else REVERT
+
+    jumpdest(&mut code, TRANSFER_ALLOWED);
+    code.extend_from_slice(&[0x60, 0x24, 0x35, 0x60, 0x44, 0x35, 0x55, 0x00]); // SSTORE(agentId, to)
+    code.into()
+}
+
+/// Start Anvil with the stand-in registry; returns the facilitator signer address.
+async fn setup() -> (AnvilInstance, EvmProvider, Address) {
+    let (anvil, provider) = common::anvil(Network::BaseSepolia).await;
+    common::set_code(&provider, REGISTRY, registry_code()).await;
+    let signer_address = anvil.addresses()[0];
+    (anvil, provider, signer_address)
+}
+
+fn request(owner: Option<Address>) -> RegisterAgentRequest {
+    RegisterAgentRequest {
+        network: Network::BaseSepolia,
+        agent_uri: AGENT_URI.to_string(),
+        metadata: vec![AgentMetadataParam {
+            key: "agentName".to_string(),
+            value: "Weather Bot".to_string(),
+        }],
+        owner,
+    }
+}
+
+async fn owner_of(provider: &EvmProvider, agent_id: u64) -> Address {
+    IIdentityRegistry::new(REGISTRY, provider.inner())
+        .ownerOf(U256::from(agent_id))
+        .call()
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_register_and_transfer_to_owner() {
+    let (anvil, provider, _) = setup().await;
+    let owner = anvil.addresses()[1];
+
+    let registration = register_agent(provider.inner(), REGISTRY, &request(Some(owner)))
+        .await
+        .unwrap();
+
+    assert_eq!(registration.agent_id, 1);
+    assert_eq!(registration.owner, owner);
+    let transfer = registration.transfer_transaction.unwrap();
+    assert_ne!(transfer, registration.transaction);
+    assert_eq!(owner_of(&provider, 1).await, owner);
+}
+
+#[tokio::test]
+async fn test_register_without_owner_keeps_agent() {
+    let (_anvil, provider, signer) = setup().await;
+
+    let first = register_agent(provider.inner(), REGISTRY, &request(None))
+        .await
+        .unwrap();
+    let second = register_agent(provider.inner(), REGISTRY, &request(Some(signer)))
+        .await
+        .unwrap();
+
+    assert_eq!((first.agent_id, second.agent_id), (1, 2));
+    assert_eq!(first.owner, signer);
+    assert_eq!(first.transfer_transaction, None);
+    // Naming the facilitator itself as owner needs no transfer
+    assert_eq!(second.transfer_transaction, None);
+    assert_eq!(owner_of(&provider, 2).await, signer);
+}
+
+#[tokio::test]
+async fn test_register_without_event_fails() {
+    let (_anvil, provider, _) = setup().await;
+    // Any call succeeds without emitting anything
+    common::set_code(&provider, REGISTRY, Bytes::from(vec![0x00])).await;
+
+    let error = register_agent(provider.inner(), REGISTRY, &request(None))
+        .await
+        .unwrap_err();
+
+    assert!(
+        matches!(error, RegistrationError::MissingEvent(_)),
+        "{error}"
+    );
+}
@@ -0,0 +1,219 @@
+//! The feedback indexer replays a mocked node's Reputation Registry logs, and
+//! `GET /reputation` serves feedback from it once it is warm.
+
+use alloy::primitives::{address, keccak256, Address, Bytes, B256, U256};
+use alloy::providers::{ProviderBuilder, RootProvider};
+use alloy::rpc::types::Log;
+use alloy::sol_types::{SolCall, SolEvent, SolValue};
+use axum::body::to_bytes;
+use serde_json::{json, Value};
+use std::num::NonZeroUsize;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+use x402_rs::erc8004::indexer::{FeedbackIndex, FeedbackIndexer};
+use x402_rs::erc8004::read_cache::ReadCache;
+use x402_rs::erc8004::{
+    Erc8004Client, FeedbackPage, GetReputationRequest, IIdentityRegistry, IReputationRegistry,
+    ETHEREUM_SEPOLIA_CONTRACTS,
+};
+use x402_rs::handlers::reputation_response;
+use x402_rs::network::Network;
+
+const AGENT_ID: u64 = 42;
+const HEAD: u64 = 20;
+const OWNER: Address = address!("1111111111111111111111111111111111111111");
+const ALICE: Address = address!("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa");
+const BOB: Address = address!("bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb");
+
+fn log(event: &impl SolEvent, block: u64) -> Log {
+    Log {
+        inner: alloy::primitives::Log {
+            address: ETHEREUM_SEPOLIA_CONTRACTS.reputation_registry,
+            data: event.encode_log_data(),
+        },
+        block_number: Some(block),
+        ..Log::default()
+    }
+}
+
+fn new_feedback(client: Address, feedback_index: u64, value: i128) -> Log {
+    let event = IReputationRegistry::NewFeedback {
+        agentId: U256::from(AGENT_ID),
+        clientAddress: client,
+        feedbackIndex: feedback_index,
+        value,
+        valueDecimals: 0,
+        indexedTag1: keccak256("uptime"),
+        tag1: "uptime".to_string(),
+        tag2: String::new(),
+        endpoint: String::new(),
+        feedbackURI: String::new(),
+        feedbackHash: B256::ZERO,
+    };
+    log(&event, 3 + feedback_index * 4 + u64::from(client == BOB))
+}
+
+fn revocation(client: Address, feedback_index: u64, block: u64) -> Log {
+    let event = IReputationRegistry::FeedbackRevoked {
+        agentId: U256::from(AGENT_ID),
+        clientAddress: client,
+        feedbackIndex: feedback_index,
+    };
+    log(&event, block)
+}
+
+/// Alice and Bob give two entries each; Alice revokes her first one at block 18.
+fn stream() -> Vec<Log> {
+    vec![
+        new_feedback(ALICE, 1, 100),
+        new_feedback(BOB, 1, 90),
+        new_feedback(ALICE, 2, 80),
+        new_feedback(BOB, 2, 70),
+        revocation(ALICE, 1, 18),
+    ]
+}
+
+fn block_param(filter: &Value, field: &str) -> u64 {
+    u64::from_str_radix(filter[field].as_str().unwrap().trim_start_matches("0x"), 16).unwrap()
+}
+
+/// Mocked node at block [`HEAD`] serving `logs` and the registry reads `GET /reputation`
+/// makes besides `readAllFeedback`.
+async fn node(logs: Arc<Mutex<Vec<Log>>>) -> MockServer {
+    let server = MockServer::start().await;
+    Mock::given(wiremock::matchers::method("POST"))
+        .respond_with(move |request: &wiremock::Request| {
+            let body: Value = request.body_json().unwrap();
+            let result = match body["method"].as_str().unwrap() {
+                "eth_blockNumber" => json!(format!("{:#x}", HEAD)),
+                "eth_getLogs" => {
+                    let filter = &body["params"][0];
+                    let (from, to) = (
+                        block_param(filter, "fromBlock"),
+                        block_param(filter, "toBlock"),
+                    );
+                    let logs: Vec<Log> = logs
+                        .lock()
+                        .unwrap()
+                        .iter()
+                        .filter(|log| (from..=to).contains(&log.block_number.unwrap()))
+                        .cloned()
+                        .collect();
+                    json!(logs)
+                }
+                "eth_call" => {
+                    let call = &body["params"][0];
+                    let input = call.get("input").or_else(|| call.get("data")).unwrap();
+                    let input: Bytes = serde_json::from_value(input.clone()).unwrap();
+                    let output = match input[..4].try_into().unwrap() {
+                        IIdentityRegistry::ownerOfCall::SELECTOR => OWNER.abi_encode(),
+                        IReputationRegistry::getSummaryCall::SELECTOR => {
+                            IReputationRegistry::getSummaryCall::abi_encode_returns(
+                                &IReputationRegistry::getSummaryReturn {
+                                    count: 3,
+                                    summaryValue: 80,
+                                    summaryValueDecimals: 0,
+                                },
+                            )
+                        }
+                        selector => panic!("unexpected call {}", Bytes::copy_from_slice(&selector)),
+                    };
+                    json!(Bytes::from(output))
+                }
+                method => panic!("unexpected method {method}"),
+            };
+            ResponseTemplate::new(200)
+                .set_body_json(json!({ "jsonrpc": "2.0", "id": body["id"], "result": result }))
+        })
+        .mount(&server)
+        .await;
+    server
+}
+
+fn provider(server: &MockServer) -> RootProvider {
+    ProviderBuilder::new()
+        .disable_recommended_fillers()
+        .connect_http(server.uri().parse().unwrap())
+}
+
+fn indexer(server: &MockServer) -> FeedbackIndexer<RootProvider> {
+    FeedbackIndexer::new(
+        provider(server),
+        Network::EthereumSepolia,
+        ETHEREUM_SEPOLIA_CONTRACTS.reputation_registry,
+        1,
+    )
+    .with_block_range(4)
+    .with_reorg_depth(4)
+}
+
+/// `(client, feedbackIndex)` of the feedback `GET /reputation` returns.
+async fn feedback(server: &MockServer, index: &FeedbackIndex) -> (Value, Vec<(Address, u64)>) {
+    let client = Erc8004Client::new(provider(server), ETHEREUM_SEPOLIA_CONTRACTS);
+    let page = FeedbackPage::new(None, None, 100);
+    let cache = ReadCache::new(NonZeroUsize::MIN, Duration::ZERO, Duration::ZERO);
+    let response = reputation_response(
+        &client,
+        index,
+        &cache,
+        AGENT_ID,
+        &GetReputationRequest::default(),
+        Some(page),
+    )
+    .await;
+    let body: Value =
+        serde_json::from_slice(&to_bytes(response.into_body(), usize::MAX).await.unwrap()).unwrap();
+    let entries = body["feedback"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|entry| {
+            let client: Address = serde_json::from_value(entry["client"].clone()).unwrap();
+            (client, entry["feedbackIndex"].as_u64().unwrap())
+        })
+        .collect();
+    (body, entries)
+}
+
+#[tokio::test]
+async fn test_warm_index_serves_feedback_without_reading_it_from_the_chain() {
+    let server = node(Arc::new(Mutex::new(stream()))).await;
+    let index = FeedbackIndex::default();
+
+    let head = indexer(&server).sync(&index).await.unwrap();
+    assert_eq!(head, HEAD);
+    assert!(index.is_warm(Network::EthereumSepolia));
+    assert_eq!(index.last_block(Network::EthereumSepolia), Some(HEAD));
+
+    let (body, entries) = feedback(&server, &index).await;
+    assert_eq!(body["source"], "cache");
+    assert_eq!(body["feedbackTotal"], 3);
+    assert_eq!(entries, vec![(ALICE, 2), (BOB, 1), (BOB, 2)]);
+}
+
+#[tokio::test]
+async fn test_sync_rescans_reorg_window() {
+    let logs = Arc::new(Mutex::new(stream()));
+    let server = node(Arc::clone(&logs)).await;
+    let index = FeedbackIndex::default();
+    indexer(&server).sync(&index).await.unwrap();
+    let requests = server.received_requests().await.unwrap().len();
+
+    // The block holding Alice's revocation is reorged out
+    logs.lock().unwrap().pop();
+    indexer(&server).sync(&index).await.unwrap();
+
+    let rescans: Vec<Value> = server.received_requests().await.unwrap()[requests..]
+        .iter()
+        .map(|request| request.body_json::<Value>().unwrap())
+        .filter(|body| body["method"] == "eth_getLogs")
+        .collect();
+    assert_eq!(rescans.len(), 1);
+    assert_eq!(block_param(&rescans[0]["params"][0], "fromBlock"), HEAD - 3);
+    assert_eq!(block_param(&rescans[0]["params"][0], "toBlock"), HEAD);
+
+    let (_, entries) = feedback(&server, &index).await;
+    assert_eq!(entries, vec![(ALICE, 1), (ALICE, 2), (BOB, 1), (BOB, 2)]);
+}
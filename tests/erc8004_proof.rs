@@ -0,0 +1,144 @@
+//! ERC-8004 proofs of payment built from settlement receipts on a local Anvil node.
+//!
+//! Needs `anvil` (from Foundry) on the PATH and the `anvil` feature:
+//!
+//! ```text
+//! cargo test --features anvil --test erc8004_proof
+//! ```
+
+#![cfg(feature = "anvil")]
+
+mod common;
+
+use alloy::node_bindings::AnvilInstance;
+use alloy::primitives::{Address, Bytes, U256};
+use alloy::providers::Provider;
+use alloy::rpc::types::TransactionReceipt;
+use alloy::sol_types::SolEvent;
+use x402_rs::chain::evm::{
+    create_proof_of_payment, settle_confirmation, EvmProvider, MetaEvmProvider, MetaTransaction,
+    USDC,
+};
+use x402_rs::network::Network;
+use x402_rs::types::{MixedAddress, PaymentRequirements, TokenAmount, TransactionHash};
+
+const TOKEN: Address = Address::repeat_byte(0x70);
+const PAYER: Address = Address::repeat_byte(0x11);
+const PAY_TO: Address = Address::repeat_byte(0x22);
+const AMOUNT: u64 = 10_000;
+
+/// Runtime code of a stand-in token: any call emits `Transfer(PAYER, PAY_TO, AMOUNT)`,
+/// as `transferWithAuthorization` does on settlement.
+fn token_code() -> Bytes {
+    let mut code = vec![0x7f]; // PUSH32 amount
+    code.extend_from_slice(&U256::from(AMOUNT).to_be_bytes::<32>());
+    code.extend_from_slice(&[0x60, 0x00, 0x52]); // MSTORE(0, amount)
+    for topic in [
+        PAY_TO.into_word(),
+        PAYER.into_word(),
+        USDC::Transfer::SIGNATURE_HASH,
+    ] {
+        code.push(0x7f); // PUSH32
+        code.extend_from_slice(topic.as_slice());
+    }
+    code.extend_from_slice(&[0x60, 0x20, 0x60, 0x00, 0xa3]); // LOG3(0, 0x20, ...)
+    code.push(0x00); // STOP
+    code.into()
+}
+
+/// Start Anvil with the stand-in token and settle one payment through it.
+async fn settle() -> (AnvilInstance, EvmProvider, TransactionReceipt) {
+    let (anvil, provider) = common::anvil(Network::EthereumSepolia).await;
+    common::set_code(&provider, TOKEN, token_code()).await;
+
+    let receipt = provider
+        .send_transaction(MetaTransaction {
+            to: TOKEN,
+            calldata: Bytes::new(),
+            confirmations: 1,
+        })
+        .await
+        .unwrap();
+    assert!(receipt.status());
+    (anvil, provider, receipt)
+}
+
+fn requirements(extra: Option<serde_json::Value>) -> PaymentRequirements {
+    serde_json::from_value(serde_json::json!({
+        "scheme": "exact",
+        "network": "ethereum-sepolia",
+        "maxAmountRequired": AMOUNT.to_string(),
+        "resource": "https://agent.example/api",
+        "description": "",
+        "mimeType": "application/json",
+        "payTo": PAY_TO,
+        "maxTimeoutSeconds": 60,
+        "asset": TOKEN,
+        "extra": extra,
+    }))
+    .unwrap()
+}
+
+async fn proof_for(
+    provider: &EvmProvider,
+    receipt: &TransactionReceipt,
+    extra: Option<serde_json::Value>,
+) -> Option<x402_rs::erc8004::ProofOfPayment> {
+    let confirmation = settle_confirmation(provider.inner(), receipt)
+        .await
+        .unwrap();
+    create_proof_of_payment(
+        receipt,
+        confirmation,
+        &requirements(extra),
+        Network::EthereumSepolia,
+        MixedAddress::Evm(PAYER.into()),
+        TokenAmount::from(AMOUNT),
+    )
+}
+
+#[tokio::test]
+async fn test_settlement_proof_verifies_on_chain() {
+    let (_anvil, provider, receipt) = settle().await;
+    let extra = serde_json::json!({ "8004-reputation": { "includeProof": true } });
+
+    let proof = proof_for(&provider, &receipt, Some(extra)).await.unwrap();
+
+    assert_eq!(
+        proof.transaction_hash,
+        TransactionHash::Evm(receipt.transaction_hash.0)
+    );
+    assert_eq!(Some(proof.block_number), receipt.block_number);
+    assert!(proof.is_hash_consistent());
+    proof.verify(provider.inner()).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_settlement_confirmation_matches_receipt() {
+    let (_anvil, provider, receipt) = settle().await;
+
+    let confirmation = settle_confirmation(provider.inner(), &receipt)
+        .await
+        .unwrap();
+
+    assert_eq!(Some(confirmation.block_number), receipt.block_number);
+    assert_eq!(confirmation.gas_used, Some(receipt.gas_used));
+    let block = provider
+        .inner()
+        .get_block_by_number(confirmation.block_number.into())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(confirmation.block_timestamp, Some(block.header.timestamp));
+}
+
+#[tokio::test]
+async fn test_no_proof_without_extension() {
+    let (_anvil, provider, receipt) = settle().await;
+
+    assert!(proof_for(&provider, &receipt, None).await.is_none());
+    let opted_out = serde_json::json!({ "8004-reputation": { "includeProof": false } });
+    assert!(proof_for(&provider, &receipt, Some(opted_out))
+        .await
+        .is_none());
+}
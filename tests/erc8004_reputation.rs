@@ -0,0 +1,181 @@
+//! `GET /reputation` reads through an [`Erc8004Client`] backed by a mocked RPC node.
+
+mod common;
+
+use alloy::primitives::{address, Address, Bytes, U256};
+use alloy::providers::ProviderBuilder;
+use alloy::sol_types::{SolCall, SolError, SolValue};
+use axum::body::to_bytes;
+use axum::http::StatusCode;
+use axum::response::Response;
+use serde_json::{json, Value};
+use std::num::NonZeroUsize;
+use std::time::Duration;
+use wiremock::MockServer;
+
+use x402_rs::erc8004::indexer::FeedbackIndex;
+use x402_rs::erc8004::read_cache::ReadCache;
+use x402_rs::erc8004::{
+    Erc8004Client, FeedbackPage, GetReputationRequest, IIdentityRegistry, IReputationRegistry,
+    ETHEREUM_SEPOLIA_CONTRACTS,
+};
+use x402_rs::handlers::reputation_response;
+use x402_rs::types::MixedAddress;
+
+const AGENT_ID: u64 = 42;
+const OWNER: Address = address!("1111111111111111111111111111111111111111");
+const ALICE: Address = address!("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa");
+const BOB: Address = address!("bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb");
+
+/// Reply to a registry `eth_call`: `ownerOf` knows only [`AGENT_ID`], and the
+/// Reputation Registry holds four feedback entries alternating between Alice and Bob.
+fn registry_reply(input: &[u8], filter: &GetReputationRequest) -> Result<Vec<u8>, Vec<u8>> {
+    let clients: Vec<Address> = filter
+        .client_addresses
+        .iter()
+        .map(|client| match client {
+            MixedAddress::Evm(address) => address.0,
+            other => panic!("unexpected client {other}"),
+        })
+        .collect();
+    let selector: [u8; 4] = input[..4].try_into().unwrap();
+    match selector {
+        IIdentityRegistry::ownerOfCall::SELECTOR => {
+            let call = IIdentityRegistry::ownerOfCall::abi_decode(input).unwrap();
+            if call.agentId == U256::from(AGENT_ID) {
+                Ok(OWNER.abi_encode())
+            } else {
+                Err(IIdentityRegistry::ERC721NonexistentToken {
+                    tokenId: call.agentId,
+                }
+                .abi_encode())
+            }
+        }
+        IReputationRegistry::getSummaryCall::SELECTOR => {
+            let call = IReputationRegistry::getSummaryCall::abi_decode(input).unwrap();
+            assert_eq!(call.clientAddresses, clients);
+            assert_eq!(call.tag1, filter.tag1);
+            assert_eq!(call.tag2, filter.tag2);
+            let summary = IReputationRegistry::getSummaryReturn {
+                count: 4,
+                summaryValue: 350,
+                summaryValueDecimals: 0,
+            };
+            Ok(IReputationRegistry::getSummaryCall::abi_encode_returns(
+                &summary,
+            ))
+        }
+        IReputationRegistry::readAllFeedbackCall::SELECTOR => {
+            let call = IReputationRegistry::readAllFeedbackCall::abi_decode(input).unwrap();
+            assert_eq!(call.clientAddresses, clients);
+            assert_eq!(call.tag1, filter.tag1);
+            assert!(!call.includeRevoked);
+            let feedback = IReputationRegistry::readAllFeedbackReturn {
+                clients: vec![ALICE, BOB, ALICE, BOB],
+                feedbackIndexes: vec![1, 1, 2, 2],
+                values: vec![100, 90, 80, 80],
+                valueDecimals: vec![0; 4],
+                tag1s: vec![filter.tag1.clone(); 4],
+                tag2s: vec![String::new(); 4],
+                revokedStatuses: vec![false; 4],
+            };
+            Ok(IReputationRegistry::readAllFeedbackCall::abi_encode_returns(&feedback))
+        }
+        _ => panic!("unexpected call {}", Bytes::copy_from_slice(&selector)),
+    }
+}
+
+/// Mocked node serving both registries, checking reads against `filter`.
+async fn registry_node(filter: GetReputationRequest) -> MockServer {
+    common::eth_call_node(move |to, input| {
+        assert!(
+            to == ETHEREUM_SEPOLIA_CONTRACTS.identity_registry
+                || to == ETHEREUM_SEPOLIA_CONTRACTS.reputation_registry,
+            "unexpected registry {to}"
+        );
+        registry_reply(input, &filter)
+    })
+    .await
+}
+
+fn filter(clients: &[Address], tag1: &str) -> GetReputationRequest {
+    GetReputationRequest {
+        client_addresses: clients
+            .iter()
+            .map(|client| MixedAddress::Evm((*client).into()))
+            .collect(),
+        tag1: tag1.to_string(),
+        tag2: String::new(),
+    }
+}
+
+async fn query(
+    agent_id: u64,
+    filter: GetReputationRequest,
+    page: Option<FeedbackPage>,
+) -> (StatusCode, Value) {
+    let server = registry_node(filter.clone()).await;
+    let provider = ProviderBuilder::new().connect_http(server.uri().parse().unwrap());
+    let client = Erc8004Client::new(provider, ETHEREUM_SEPOLIA_CONTRACTS);
+    let index = FeedbackIndex::default();
+    // Zero TTLs so every query reaches the mock registry.
+    let cache = ReadCache::new(NonZeroUsize::MIN, Duration::ZERO, Duration::ZERO);
+    let response: Response =
+        reputation_response(&client, &index, &cache, agent_id, &filter, page).await;
+    let status = response.status();
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    (status, serde_json::from_slice(&body).unwrap())
+}
+
+#[tokio::test]
+async fn test_summary_with_filters() {
+    let (status, body) = query(AGENT_ID, filter(&[ALICE, BOB], "uptime"), None).await;
+
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body["agentId"], AGENT_ID);
+    assert_eq!(body["network"], "ethereum-sepolia");
+    assert_eq!(body["summary"]["count"], 4);
+    assert_eq!(body["summary"]["summaryValue"], 350);
+    assert!(body.get("feedback").is_none());
+    assert!(body.get("feedbackTotal").is_none());
+    assert!(body.get("source").is_none());
+}
+
+#[tokio::test]
+async fn test_feedback_is_paginated_after_the_read() {
+    let page = FeedbackPage::new(Some(1), Some(2), 100);
+    let (status, body) = query(AGENT_ID, filter(&[], "uptime"), Some(page)).await;
+
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body["feedbackTotal"], 4);
+    assert_eq!(body["source"], "chain");
+    let feedback = body["feedback"].as_array().unwrap();
+    assert_eq!(feedback.len(), 2);
+    assert_eq!(feedback[0]["client"], json!(MixedAddress::Evm(BOB.into())));
+    assert_eq!(feedback[0]["feedbackIndex"], 1);
+    assert_eq!(
+        feedback[1]["client"],
+        json!(MixedAddress::Evm(ALICE.into()))
+    );
+    assert_eq!(feedback[1]["feedbackIndex"], 2);
+    assert_eq!(feedback[1]["tag1"], "uptime");
+}
+
+#[tokio::test]
+async fn test_feedback_page_is_capped() {
+    let page = FeedbackPage::new(None, Some(1_000), 3);
+    let (status, body) = query(AGENT_ID, filter(&[ALICE], ""), Some(page)).await;
+
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body["feedbackTotal"], 4);
+    assert_eq!(body["feedback"].as_array().unwrap().len(), 3);
+}
+
+#[tokio::test]
+async fn test_unknown_agent_is_not_found() {
+    let page = FeedbackPage::new(None, None, 100);
+    let (status, body) = query(7, filter(&[], ""), Some(page)).await;
+
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(body["error"], "Agent 7 not found in Identity Registry");
+}
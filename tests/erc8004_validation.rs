@@ -0,0 +1,231 @@
+//! Validation Registry endpoints against a mocked RPC node.
+
+mod common;
+
+use std::cell::Cell;
+
+use alloy::primitives::{address, b256, Address, Bytes, B256, U256};
+use alloy::providers::ProviderBuilder;
+use alloy::sol_types::{SolCall, SolError};
+use axum::body::to_bytes;
+use axum::http::StatusCode;
+use axum::response::Response;
+use serde_json::{json, Value};
+use wiremock::MockServer;
+
+use x402_rs::erc8004::identity::RegistrarError;
+use x402_rs::erc8004::{
+    IValidationRegistry, ValidationRequestParams, ValidationResponseParams,
+    ETHEREUM_SEPOLIA_CONTRACTS,
+};
+use x402_rs::handlers::{
+    submit_validation_request, submit_validation_response, validation_registry,
+    validation_status_response,
+};
+use x402_rs::network::Network;
+use x402_rs::types::{MixedAddress, X402Version};
+
+const VALIDATOR: Address = address!("70997970C51812dc3A010C7d01b50e0d17dc79C8");
+const OTHER_VALIDATOR: Address = address!("3C44CdDdB6a900fa2b585dd299e03d12FA4293BC");
+const AGENT_ID: u64 = 42;
+/// Agent the facilitator does not hold
+const FOREIGN_AGENT_ID: u64 = 7;
+const KNOWN_REQUEST: B256 =
+    b256!("1111111111111111111111111111111111111111111111111111111111111111");
+const UNKNOWN_REQUEST: B256 =
+    b256!("2222222222222222222222222222222222222222222222222222222222222222");
+
+fn registry() -> Address {
+    ETHEREUM_SEPOLIA_CONTRACTS.validation_registry.unwrap()
+}
+
+/// Reply to a Validation Registry `eth_call`: [`KNOWN_REQUEST`] asks [`VALIDATOR`] to
+/// validate [`AGENT_ID`], and requests for [`FOREIGN_AGENT_ID`] revert.
+fn registry_reply(input: &[u8]) -> Result<Vec<u8>, Vec<u8>> {
+    let selector: [u8; 4] = input[..4].try_into().unwrap();
+    match selector {
+        IValidationRegistry::getValidationStatusCall::SELECTOR => {
+            let call = IValidationRegistry::getValidationStatusCall::abi_decode(input).unwrap();
+            let status = if call.requestHash == KNOWN_REQUEST {
+                IValidationRegistry::getValidationStatusReturn {
+                    validatorAddress: VALIDATOR,
+                    agentId: U256::from(AGENT_ID),
+                    response: 80,
+                    responseHash: B256::repeat_byte(0x33),
+                    tag: "soft-finality".to_string(),
+                    lastUpdate: U256::from(1_767_225_600u64),
+                }
+            } else {
+                IValidationRegistry::getValidationStatusReturn {
+                    validatorAddress: Address::ZERO,
+                    agentId: U256::ZERO,
+                    response: 0,
+                    responseHash: B256::ZERO,
+                    tag: String::new(),
+                    lastUpdate: U256::ZERO,
+                }
+            };
+            Ok(IValidationRegistry::getValidationStatusCall::abi_encode_returns(&status))
+        }
+        IValidationRegistry::validationRequestCall::SELECTOR => {
+            let call = IValidationRegistry::validationRequestCall::abi_decode(input).unwrap();
+            if call.agentId == U256::from(FOREIGN_AGENT_ID) {
+                Err(alloy::sol_types::Revert {
+                    reason: "Not authorized".to_string(),
+                }
+                .abi_encode())
+            } else {
+                Ok(Vec::new())
+            }
+        }
+        _ => panic!("unexpected call {}", Bytes::copy_from_slice(&selector)),
+    }
+}
+
+/// Mocked node serving the Sepolia Validation Registry; only reads are expected.
+async fn registry_node() -> MockServer {
+    common::eth_call_node(|to, input| {
+        assert_eq!(to, registry());
+        registry_reply(input)
+    })
+    .await
+}
+
+async fn into_json(response: Response) -> (StatusCode, Value) {
+    let status = response.status();
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    (status, serde_json::from_slice(&body).unwrap())
+}
+
+fn request(agent_id: u64) -> ValidationRequestParams {
+    ValidationRequestParams {
+        x402_version: X402Version::V1,
+        network: Network::EthereumSepolia,
+        validator_address: MixedAddress::Evm(VALIDATOR.into()),
+        agent_id,
+        request_uri: "ipfs://bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi"
+            .to_string(),
+        request_hash: None,
+    }
+}
+
+fn response(request_hash: B256) -> ValidationResponseParams {
+    ValidationResponseParams {
+        x402_version: X402Version::V1,
+        network: Network::EthereumSepolia,
+        request_hash,
+        response: 100,
+        response_uri: "ipfs://bafkreih5aznjvttude6c3wbvqeebb6rlx5wkbzyppv7garjiubll2ceym4"
+            .to_string(),
+        response_hash: None,
+        tag: "hard-finality".to_string(),
+    }
+}
+
+#[tokio::test]
+async fn test_status_of_known_request() {
+    let server = registry_node().await;
+    let provider = ProviderBuilder::new().connect_http(server.uri().parse().unwrap());
+
+    let (status, body) =
+        into_json(validation_status_response(provider, registry(), KNOWN_REQUEST).await).await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        serde_json::from_value::<MixedAddress>(body["validatorAddress"].clone()).unwrap(),
+        MixedAddress::Evm(VALIDATOR.into())
+    );
+    assert_eq!(body["agentId"], AGENT_ID);
+    assert_eq!(body["response"], 80);
+    assert_eq!(body["tag"], "soft-finality");
+    assert_eq!(body["lastUpdate"], 1_767_225_600u64);
+}
+
+#[tokio::test]
+async fn test_status_of_unknown_request() {
+    let server = registry_node().await;
+    let provider = ProviderBuilder::new().connect_http(server.uri().parse().unwrap());
+
+    let (status, _) =
+        into_json(validation_status_response(provider, registry(), UNKNOWN_REQUEST).await).await;
+
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_network_without_validation_registry() {
+    assert_eq!(
+        validation_registry(Network::EthereumSepolia).unwrap(),
+        registry()
+    );
+
+    let (status, body) = into_json(validation_registry(Network::Ethereum).unwrap_err()).await;
+
+    assert_eq!(status, StatusCode::NOT_IMPLEMENTED);
+    assert_eq!(body["supportedNetworks"], json!(["ethereum-sepolia"]));
+}
+
+#[tokio::test]
+async fn test_rejected_request_spends_no_quota() {
+    let server = registry_node().await;
+    let provider = ProviderBuilder::new().connect_http(server.uri().parse().unwrap());
+    let acquired = Cell::new(false);
+
+    let (status, body) = into_json(
+        submit_validation_request(provider, registry(), &request(FOREIGN_AGENT_ID), || {
+            acquired.set(true);
+            Ok(())
+        })
+        .await,
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["success"], false);
+    assert!(body["requestHash"].is_string());
+    assert!(!acquired.get());
+}
+
+#[tokio::test]
+async fn test_request_beyond_quota_is_not_sent() {
+    let server = registry_node().await;
+    let provider = ProviderBuilder::new().connect_http(server.uri().parse().unwrap());
+
+    // Sending would fail against the mocked node, which only serves eth_call
+    let (status, body) = into_json(
+        submit_validation_request(provider, registry(), &request(AGENT_ID), || {
+            Err(RegistrarError::QuotaExceeded(10))
+        })
+        .await,
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(body["transaction"], Value::Null);
+}
+
+#[tokio::test]
+async fn test_response_only_for_own_requests() {
+    let server = registry_node().await;
+    let provider = ProviderBuilder::new().connect_http(server.uri().parse().unwrap());
+
+    let (status, body) = into_json(
+        submit_validation_response(
+            &provider,
+            registry(),
+            OTHER_VALIDATOR,
+            &response(KNOWN_REQUEST),
+        )
+        .await,
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(body["requestHash"], json!(KNOWN_REQUEST.to_string()));
+
+    let (status, _) = into_json(
+        submit_validation_response(&provider, registry(), VALIDATOR, &response(UNKNOWN_REQUEST))
+            .await,
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
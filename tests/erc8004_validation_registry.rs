@@ -0,0 +1,203 @@
+//! ERC-8004 validation round trip against a stand-in Validation Registry, deployed at
+//! the Sepolia address on a local Anvil node.
+//!
+//! Needs `anvil` (from Foundry) on the PATH and the `anvil` feature:
+//!
+//! ```text
+//! cargo test --features anvil --test erc8004_validation_registry
+//! ```
+
+#![cfg(feature = "anvil")]
+
+mod common;
+
+use alloy::network::EthereumWallet;
+use alloy::node_bindings::AnvilInstance;
+use alloy::primitives::{Address, Bytes, B256};
+use alloy::providers::ProviderBuilder;
+use alloy::signers::local::PrivateKeySigner;
+use alloy::sol_types::SolCall;
+use axum::http::StatusCode;
+use common::{dispatcher, jumpdest};
+use x402_rs::chain::evm::{EvmProvider, MetaEvmProvider};
+use x402_rs::erc8004::validation::{
+    check_validation_response, request_hash, request_validation, validation_status, ValidationError,
+};
+use x402_rs::erc8004::{
+    IValidationRegistry, ValidationRequestParams, ValidationResponseParams,
+    ETHEREUM_SEPOLIA_CONTRACTS,
+};
+use x402_rs::handlers::submit_validation_response;
+use x402_rs::network::Network;
+use x402_rs::types::{MixedAddress, X402Version};
+
+const AGENT_ID: u64 = 42;
+
+// Entry points of the stand-in registry code
+const REQUEST: u8 = 0x28;
+const RESPONSE: u8 = 0x3b;
+const RESPONSE_ALLOWED: u8 = 0x49;
+const STATUS: u8 = 0x67;
+
+fn registry() -> Address {
+    ETHEREUM_SEPOLIA_CONTRACTS.validation_registry.unwrap()
+}
+
+/// Runtime code of a stand-in Validation Registry. Request `h` keeps its validator in
+/// slot `h`, its agent in `h + 1`, its response in `h + 2`, the response hash in `h + 3`
+/// and the time of the response in `h + 4`:
+///
+/// - `validationRequest(validator, agentId, requestURI, requestHash)` records the request
+/// - `validationResponse(requestHash, response, ...)` records the response if the caller
+///   is the request's validator
+/// - `getValidationStatus(requestHash)` returns the request, with an empty tag
+fn registry_code() -> Bytes {
+    let mut code = dispatcher(&[
+        (
+            IValidationRegistry::validationRequestCall::SELECTOR,
+            REQUEST,
+        ),
+        (
+            IValidationRegistry::validationResponseCall::SELECTOR,
+            RESPONSE,
+        ),
+        (
+            IValidationRegistry::getValidationStatusCall::SELECTOR,
+            STATUS,
+        ),
+    ]);
+
+    jumpdest(&mut code, REQUEST);
+    code.extend_from_slice(&[0x60, 0x04, 0x35, 0x60, 0x64, 0x35, 0x55]); // SSTORE(h, validator)
+    code.extend_from_slice(&[0x60, 0x24, 0x35, 0x60, 0x64, 0x35, 0x60, 0x01, 0x01, 0x55]); // SSTORE(h + 1, agentId)
+    code.push(0x00);
+
+    jumpdest(&mut code, RESPONSE);
+    code.extend_from_slice(&[0x60, 0x04, 0x35, 0x54, 0x33, 0x14]); // SLOAD(h) == CALLER
+    code.extend_from_slice(&[0x60, RESPONSE_ALLOWED, 0x57, 0x60, 0x00, 0x80, 0xfd]); // else REVERT
+
+    jumpdest(&mut code, RESPONSE_ALLOWED);
+    for (argument, offset) in [(0x24, 0x02), (0x64, 0x03)] {
+        // SSTORE(h + offset, argument): the response, then its hash
+        code.extend_from_slice(&[
+            0x60, argument, 0x35, 0x60, 0x04, 0x35, 0x60, offset, 0x01, 0x55,
+        ]);
+    }
+    code.extend_from_slice(&[0x42, 0x60, 0x04, 0x35, 0x60, 0x04, 0x01, 0x55]); // SSTORE(h + 4, TIMESTAMP)
+    code.push(0x00);
+
+    jumpdest(&mut code, STATUS);
+    code.extend_from_slice(&[0x60, 0x04, 0x35]); // h
+    for (offset, slot) in [(0x00, 0x00), (0x20, 0x01), (0x40, 0x02), (0x60, 0x03)] {
+        // MSTORE(slot * 32, SLOAD(h + slot))
+        code.extend_from_slice(&[0x80, 0x60, slot, 0x01, 0x54, 0x60, offset, 0x52]);
+    }
+    code.extend_from_slice(&[0x60, 0xc0, 0x60, 0x80, 0x52]); // tag offset
+    code.extend_from_slice(&[0x60, 0x04, 0x01, 0x54, 0x60, 0xa0, 0x52]); // lastUpdate
+    code.extend_from_slice(&[0x60, 0xe0, 0x60, 0x00, 0xf3]); // return 7 words, the tag empty
+    code.into()
+}
+
+/// Start a Sepolia-like Anvil with the stand-in registry. Returns the facilitator
+/// provider and the validator signer.
+async fn setup() -> (AnvilInstance, EvmProvider, PrivateKeySigner) {
+    let (anvil, provider) = common::anvil(Network::EthereumSepolia).await;
+    common::set_code(&provider, registry(), registry_code()).await;
+    let validator: PrivateKeySigner = anvil.keys()[1].clone().into();
+    (anvil, provider, validator)
+}
+
+fn request(validator: Address) -> ValidationRequestParams {
+    ValidationRequestParams {
+        x402_version: X402Version::V1,
+        network: Network::EthereumSepolia,
+        validator_address: MixedAddress::Evm(validator.into()),
+        agent_id: AGENT_ID,
+        request_uri: "ipfs://bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi"
+            .to_string(),
+        request_hash: None,
+    }
+}
+
+fn response(request_hash: B256) -> ValidationResponseParams {
+    ValidationResponseParams {
+        x402_version: X402Version::V1,
+        network: Network::EthereumSepolia,
+        request_hash,
+        response: 100,
+        response_uri: "ipfs://bafkreih5aznjvttude6c3wbvqeebb6rlx5wkbzyppv7garjiubll2ceym4"
+            .to_string(),
+        response_hash: Some(B256::repeat_byte(0x33)),
+        tag: String::new(),
+    }
+}
+
+#[tokio::test]
+async fn test_request_and_response_round_trip() {
+    let (anvil, provider, validator) = setup().await;
+    let request = request(validator.address());
+
+    let (hash, _) = request_validation(provider.inner(), registry(), &request)
+        .await
+        .unwrap();
+    assert_eq!(hash, request_hash(&request).unwrap());
+    let status = validation_status(provider.inner(), registry(), hash)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        status.validator_address,
+        MixedAddress::Evm(validator.address().into())
+    );
+    assert_eq!(status.agent_id, AGENT_ID);
+    assert_eq!((status.response, status.last_update), (0, 0));
+
+    let validator_address = validator.address();
+    let validator_provider = ProviderBuilder::new()
+        .wallet(EthereumWallet::from(validator))
+        .connect_http(anvil.endpoint_url());
+    let answered = submit_validation_response(
+        &validator_provider,
+        registry(),
+        validator_address,
+        &response(hash),
+    )
+    .await;
+    assert_eq!(answered.status(), StatusCode::OK);
+
+    let status = validation_status(provider.inner(), registry(), hash)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(status.response, 100);
+    assert_eq!(status.response_hash, B256::repeat_byte(0x33));
+    assert!(status.last_update > 0);
+}
+
+#[tokio::test]
+async fn test_only_the_requested_validator_responds() {
+    let (anvil, provider, validator) = setup().await;
+    let (hash, _) = request_validation(provider.inner(), registry(), &request(validator.address()))
+        .await
+        .unwrap();
+
+    // The facilitator signer is not the requested validator
+    let facilitator = anvil.addresses()[0];
+    let error =
+        check_validation_response(provider.inner(), registry(), facilitator, &response(hash))
+            .await
+            .unwrap_err();
+
+    assert!(matches!(error, ValidationError::Rejected(_)), "{error}");
+}
+
+#[tokio::test]
+async fn test_unknown_request_has_no_status() {
+    let (_anvil, provider, _) = setup().await;
+
+    let status = validation_status(provider.inner(), registry(), B256::repeat_byte(0x11))
+        .await
+        .unwrap();
+
+    assert!(status.is_none());
+}
@@ -0,0 +1,109 @@
+//! ERC-1271 verification of contract accounts and EIP-7702 delegated EOAs against a
+//! local Anvil node.
+//!
+//! Needs `anvil` (from Foundry) on the PATH and the `anvil` feature:
+//!
+//! ```text
+//! cargo test --features anvil --test evm_eip7702
+//! ```
+
+#![cfg(feature = "anvil")]
+
+use alloy::network::EthereumWallet;
+use alloy::node_bindings::{Anvil, AnvilInstance};
+use alloy::primitives::{Address, Bytes, B256};
+use alloy::providers::Provider;
+use alloy::signers::local::PrivateKeySigner;
+use x402_rs::chain::evm::{verify_erc1271_signature, EvmProvider, MetaEvmProvider};
+use x402_rs::network::Network;
+
+const WALLET: Address = Address::repeat_byte(0x71);
+const DELEGATED_EOA: Address = Address::repeat_byte(0x33);
+const PLAIN_EOA: Address = Address::repeat_byte(0x44);
+
+/// Runtime code of a stand-in ERC-1271 wallet: `isValidSignature(hash, signature)`
+/// returns the magic value when `hash` equals storage slot 0, and reverts otherwise.
+fn wallet_code() -> Bytes {
+    vec![
+        0x60, 0x04, 0x35, // CALLDATALOAD(4): hash
+        0x60, 0x00, 0x54, // SLOAD(0): approved hash
+        0x14, // EQ
+        0x60, 0x0e, 0x57, // JUMPI to the success branch
+        0x60, 0x00, 0x80, 0xfd, // REVERT(0, 0)
+        0x5b, // JUMPDEST
+        0x63, 0x16, 0x26, 0xba, 0x7e, // PUSH4 magic value
+        0x60, 0xe0, 0x1b, 0x60, 0x00, 0x52, // MSTORE(0, magic << 224)
+        0x60, 0x20, 0x60, 0x00, 0xf3, // RETURN(0, 32)
+    ]
+    .into()
+}
+
+async fn setup() -> (AnvilInstance, EvmProvider) {
+    let anvil = Anvil::new().chain_id(84532).spawn();
+    let signer: PrivateKeySigner = anvil.keys()[0].clone().into();
+    let provider = EvmProvider::try_new(
+        EthereumWallet::from(signer),
+        &anvil.endpoint(),
+        true,
+        Network::BaseSepolia,
+    )
+    .await
+    .unwrap();
+
+    provider
+        .inner()
+        .raw_request::<_, ()>("anvil_setCode".into(), (WALLET, wallet_code()))
+        .await
+        .unwrap();
+    (anvil, provider)
+}
+
+/// Approve `hash` in the storage of `account`.
+async fn approve_hash(provider: &EvmProvider, account: Address, hash: B256) {
+    provider
+        .inner()
+        .raw_request::<_, bool>("anvil_setStorageAt".into(), (account, B256::ZERO, hash))
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_contract_wallet_signature() {
+    let (_anvil, provider) = setup().await;
+    let hash = B256::repeat_byte(0xaa);
+    approve_hash(&provider, WALLET, hash).await;
+
+    let signature = Bytes::from(vec![0u8; 65]);
+    assert!(verify_erc1271_signature(provider.inner(), WALLET, hash, signature.clone())
+        .await
+        .unwrap());
+    // The wallet reverts on any other hash
+    assert!(!verify_erc1271_signature(provider.inner(), WALLET, B256::repeat_byte(0xbb), signature)
+        .await
+        .unwrap());
+}
+
+#[tokio::test]
+async fn test_delegated_eoa_signature() {
+    let (_anvil, provider) = setup().await;
+    // EIP-7702 delegation designator: 0xef0100 followed by the delegate
+    let mut designator = vec![0xef, 0x01, 0x00];
+    designator.extend_from_slice(WALLET.as_slice());
+    provider
+        .inner()
+        .raw_request::<_, ()>("anvil_setCode".into(), (DELEGATED_EOA, Bytes::from(designator)))
+        .await
+        .unwrap();
+    // Delegated code runs against the EOA's own storage
+    let hash = B256::repeat_byte(0xcc);
+    approve_hash(&provider, DELEGATED_EOA, hash).await;
+
+    let signature = Bytes::from(vec![0u8; 65]);
+    assert!(verify_erc1271_signature(provider.inner(), DELEGATED_EOA, hash, signature.clone())
+        .await
+        .unwrap());
+    // An EOA without code cannot answer isValidSignature
+    assert!(!verify_erc1271_signature(provider.inner(), PLAIN_EOA, hash, signature)
+        .await
+        .unwrap());
+}
@@ -0,0 +1,149 @@
+//! Honeypot token screening against stand-in tokens on a local Anvil node.
+//!
+//! Needs `anvil` (from Foundry) on the PATH and the `anvil` feature:
+//!
+//! ```text
+//! cargo test --features anvil --test evm_honeypot
+//! ```
+
+#![cfg(feature = "anvil")]
+
+use alloy::network::EthereumWallet;
+use alloy::node_bindings::{Anvil, AnvilInstance};
+use alloy::primitives::{Address, Bytes};
+use alloy::providers::Provider;
+use alloy::signers::local::PrivateKeySigner;
+use std::time::Duration;
+use x402_rs::chain::evm::honeypot::HoneypotDetector;
+use x402_rs::chain::evm::{EvmProvider, MetaEvmProvider};
+use x402_rs::chain::FacilitatorLocalError;
+use x402_rs::network::Network;
+use x402_rs::types::MixedAddress;
+
+const TOKEN: Address = Address::repeat_byte(0x70);
+const HOLDER: Address = Address::repeat_byte(0x11);
+
+/// Runtime code of a token whose every call succeeds, returning `true`.
+fn legitimate_code() -> Bytes {
+    // MSTORE(0, 1) RETURN(0, 0x20)
+    Bytes::from_static(&[0x60, 0x01, 0x60, 0x00, 0x52, 0x60, 0x20, 0x60, 0x00, 0xf3])
+}
+
+/// Runtime code of a honeypot token whose every call reverts.
+fn honeypot_code() -> Bytes {
+    // REVERT(0, 0)
+    Bytes::from_static(&[0x60, 0x00, 0x60, 0x00, 0xfd])
+}
+
+async fn provider(network: Network, chain_id: u64) -> (AnvilInstance, EvmProvider) {
+    let anvil = Anvil::new().chain_id(chain_id).spawn();
+    let signer: PrivateKeySigner = anvil.keys()[0].clone().into();
+    let provider = EvmProvider::try_new(
+        EthereumWallet::from(signer),
+        &anvil.endpoint(),
+        true,
+        network,
+    )
+    .await
+    .unwrap();
+    (anvil, provider)
+}
+
+async fn set_code(provider: &EvmProvider, code: Bytes) {
+    provider
+        .inner()
+        .raw_request::<_, ()>("anvil_setCode".into(), (TOKEN, code))
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_legitimate_token_passes() {
+    let (anvil, provider) = provider(Network::EthereumSepolia, 11155111).await;
+    set_code(&provider, legitimate_code()).await;
+    let detector = HoneypotDetector::new(anvil.addresses()[0]);
+
+    detector
+        .check(provider.inner(), TOKEN, HOLDER)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_reverting_token_is_a_honeypot() {
+    let (anvil, provider) = provider(Network::EthereumSepolia, 11155111).await;
+    set_code(&provider, honeypot_code()).await;
+    let detector = HoneypotDetector::new(anvil.addresses()[0]);
+
+    let error = detector
+        .check(provider.inner(), TOKEN, HOLDER)
+        .await
+        .unwrap_err();
+
+    assert!(matches!(
+        error,
+        FacilitatorLocalError::HoneypotDetected(MixedAddress::Evm(token)) if token.0 == TOKEN
+    ));
+}
+
+#[tokio::test]
+async fn test_reverts_are_not_cached() {
+    let (anvil, provider) = provider(Network::EthereumSepolia, 11155111).await;
+    set_code(&provider, honeypot_code()).await;
+    let detector = HoneypotDetector::new(anvil.addresses()[0]);
+    assert!(detector
+        .check(provider.inner(), TOKEN, HOLDER)
+        .await
+        .is_err());
+
+    // A revert may be the holder's doing, so the token is simulated again next time
+    set_code(&provider, legitimate_code()).await;
+    detector
+        .check(provider.inner(), TOKEN, HOLDER)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_legitimate_verdicts_are_cached_until_expiry() {
+    let (anvil, provider) = provider(Network::EthereumSepolia, 11155111).await;
+    set_code(&provider, legitimate_code()).await;
+    let detector = HoneypotDetector::new(anvil.addresses()[0]);
+    detector
+        .check(provider.inner(), TOKEN, HOLDER)
+        .await
+        .unwrap();
+
+    // The cached verdict stands even though the token now reverts
+    set_code(&provider, honeypot_code()).await;
+    detector
+        .check(provider.inner(), TOKEN, HOLDER)
+        .await
+        .unwrap();
+
+    // An expired verdict does not
+    let detector = HoneypotDetector::new(anvil.addresses()[0]).with_ttl(Duration::ZERO);
+    set_code(&provider, legitimate_code()).await;
+    detector
+        .check(provider.inner(), TOKEN, HOLDER)
+        .await
+        .unwrap();
+    set_code(&provider, honeypot_code()).await;
+    assert!(detector
+        .check(provider.inner(), TOKEN, HOLDER)
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_screening_defaults_by_network() {
+    let (_anvil, testnet) = provider(Network::EthereumSepolia, 11155111).await;
+    assert!(testnet.honeypot_detector().is_none());
+    let testnet = testnet.with_verify_token_legitimacy(true);
+    assert!(testnet.honeypot_detector().is_some());
+
+    let (_anvil, mainnet) = provider(Network::Ethereum, 1).await;
+    assert!(mainnet.honeypot_detector().is_some());
+    let mainnet = mainnet.with_verify_token_legitimacy(false);
+    assert!(mainnet.honeypot_detector().is_none());
+}
@@ -0,0 +1,127 @@
+//! Settlement dry runs of `EvmProvider` against a local Anvil node.
+//!
+//! Needs `anvil` (from Foundry) on the PATH and the `anvil` feature:
+//!
+//! ```text
+//! cargo test --features anvil --test evm_simulation
+//! ```
+
+#![cfg(feature = "anvil")]
+
+mod common;
+
+use alloy::node_bindings::AnvilInstance;
+use alloy::primitives::{Address, Bytes, B256, U256};
+use alloy::providers::Provider;
+use alloy::sol;
+use alloy::sol_types::{SolCall, SolError};
+use x402_rs::chain::evm::{EvmProvider, MetaEvmProvider, MetaTransaction};
+use x402_rs::chain::{FacilitatorLocalError, RevertReason};
+use x402_rs::network::Network;
+
+sol! {
+    function transferWithAuthorization(
+        address from,
+        address to,
+        uint256 value,
+        uint256 validAfter,
+        uint256 validBefore,
+        bytes32 nonce,
+        bytes signature
+    );
+
+    error ERC20InsufficientBalance(address sender, uint256 balance, uint256 needed);
+}
+
+const TOKEN: Address = Address::repeat_byte(0x70);
+const PAYER: Address = Address::repeat_byte(0x11);
+const PAY_TO: Address = Address::repeat_byte(0x22);
+
+/// Runtime code of a stand-in token: `transferWithAuthorization` succeeds (without moving
+/// funds) when storage slot `from` holds at least `value`, and otherwise reverts with
+/// `ERC20InsufficientBalance(from, balance, value)` like an OpenZeppelin v5 token.
+fn token_code() -> Bytes {
+    let mut code = vec![
+        0x60, 0x04, 0x35, // CALLDATALOAD(4): from
+        0x80, 0x54, // SLOAD(from): balance
+        0x60, 0x44, 0x35, // CALLDATALOAD(0x44): value
+        0x80, 0x82, 0x10, // balance < value
+        0x60, 0x0f, 0x57, // JUMPI to the revert branch
+        0x00, // STOP
+        0x5b, // JUMPDEST
+        0x63, // PUSH4 selector
+    ];
+    code.extend_from_slice(&ERC20InsufficientBalance::SELECTOR);
+    code.extend_from_slice(&[
+        0x60, 0xe0, 0x1b, 0x60, 0x00, 0x52, // MSTORE(0, selector << 224)
+        0x60, 0x44, 0x52, // MSTORE(0x44, value)
+        0x60, 0x24, 0x52, // MSTORE(0x24, balance)
+        0x60, 0x04, 0x52, // MSTORE(0x04, from)
+        0x60, 0x64, 0x60, 0x00, 0xfd, // REVERT(0, 0x64)
+    ]);
+    code.into()
+}
+
+async fn setup(simulate: bool) -> (AnvilInstance, EvmProvider, Address) {
+    let (anvil, provider) = common::anvil(Network::BaseSepolia).await;
+    let provider = provider.with_simulate_before_settle(simulate);
+    common::set_code(&provider, TOKEN, token_code()).await;
+    let signer_address = anvil.addresses()[0];
+    (anvil, provider, signer_address)
+}
+
+async fn fund_payer(provider: &EvmProvider, amount: u64) {
+    let slot = B256::left_padding_from(PAYER.as_slice());
+    let value = B256::from(U256::from(amount));
+    provider
+        .inner()
+        .raw_request::<_, bool>("anvil_setStorageAt".into(), (TOKEN, slot, value))
+        .await
+        .unwrap();
+}
+
+fn transfer(value: u64) -> MetaTransaction {
+    let call = transferWithAuthorizationCall {
+        from: PAYER,
+        to: PAY_TO,
+        value: U256::from(value),
+        validAfter: U256::ZERO,
+        validBefore: U256::MAX,
+        nonce: B256::repeat_byte(0x01),
+        signature: Bytes::from(vec![0u8; 65]),
+    };
+    MetaTransaction {
+        to: TOKEN,
+        calldata: call.abi_encode().into(),
+        confirmations: 1,
+    }
+}
+
+#[tokio::test]
+async fn test_underfunded_sender_is_rejected_before_submission() {
+    let (_anvil, provider, signer) = setup(true).await;
+    fund_payer(&provider, 999).await;
+
+    let result = provider.send_transaction(transfer(1_000)).await;
+
+    assert!(
+        matches!(
+            result,
+            Err(FacilitatorLocalError::SimulationFailed(RevertReason::InsufficientBalance))
+        ),
+        "unexpected result: {result:?}"
+    );
+    // Nothing was sent: the facilitator signer has not used a nonce
+    assert_eq!(provider.inner().get_transaction_count(signer).await.unwrap(), 0);
+}
+
+#[tokio::test]
+async fn test_funded_sender_is_submitted() {
+    let (_anvil, provider, signer) = setup(true).await;
+    fund_payer(&provider, 1_000).await;
+
+    let receipt = provider.send_transaction(transfer(1_000)).await.unwrap();
+
+    assert!(receipt.status());
+    assert_eq!(provider.inner().get_transaction_count(signer).await.unwrap(), 1);
+}
@@ -0,0 +1,72 @@
+//! `RedisNonceStore` against a real Redis server.
+//!
+//! Needs the `redis` feature and a server in `REDIS_URL`; the tests pass without
+//! checking anything when it is unset:
+//!
+//! ```text
+//! REDIS_URL=redis://127.0.0.1:6379 cargo test --features redis --test redis_nonce_store
+//! ```
+
+#![cfg(feature = "redis")]
+
+use std::sync::Arc;
+use std::time::Duration;
+use x402_rs::nonce_store::{NonceStore, NonceStoreError, RedisNonceStore};
+
+/// A store under a prefix of its own, so runs never see each other's keys.
+async fn connect() -> Option<RedisNonceStore> {
+    let url = std::env::var("REDIS_URL").ok().filter(|url| !url.trim().is_empty());
+    let Some(url) = url else {
+        eprintln!("REDIS_URL not set, skipping");
+        return None;
+    };
+    let prefix = format!("x402:test:{:x}:", rand::random::<u64>());
+    Some(RedisNonceStore::connect(&url, prefix).await.expect("connect"))
+}
+
+#[tokio::test]
+async fn test_check_and_mark_rejects_replays() {
+    let Some(store) = connect().await else { return };
+    store.health_check().await.unwrap();
+    assert_eq!(store.store_type(), "redis");
+
+    let key = "stellar#GABC123#12345";
+    assert!(!store.is_used(key).await.unwrap());
+    store.check_and_mark_used(key, 60).await.unwrap();
+    assert!(store.is_used(key).await.unwrap());
+    assert!(matches!(
+        store.check_and_mark_used(key, 60).await,
+        Err(NonceStoreError::NonceAlreadyUsed(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_nonce_is_released_after_its_ttl() {
+    let Some(store) = connect().await else { return };
+    let key = "algorand#group#0000000000000000000000000000000000000000000000000000000000000000";
+
+    store.check_and_mark_used(key, 1).await.unwrap();
+    assert!(store.check_and_mark_used(key, 1).await.is_err());
+
+    tokio::time::sleep(Duration::from_millis(1500)).await;
+    assert!(!store.is_used(key).await.unwrap());
+    store.check_and_mark_used(key, 60).await.unwrap();
+    assert!(store.check_and_mark_used(key, 60).await.is_err());
+}
+
+#[tokio::test]
+async fn test_concurrent_marks_admit_exactly_one() {
+    let Some(store) = connect().await else { return };
+    let store = Arc::new(store);
+
+    let attempts = (0..16).map(|_| {
+        let store = Arc::clone(&store);
+        tokio::spawn(async move { store.check_and_mark_used("stellar#GXYZ#7", 60).await })
+    });
+    let results = futures::future::join_all(attempts).await;
+    let admitted = results
+        .into_iter()
+        .filter(|r| matches!(r, Ok(Ok(()))))
+        .count();
+    assert_eq!(admitted, 1);
+}
@@ -0,0 +1,265 @@
+//! `SolanaProvider` against a local validator.
+//!
+//! Needs a running `solana-test-validator` and the `solana-test-validator` feature:
+//!
+//! ```text
+//! solana-test-validator --reset --quiet &
+//! cargo test --features solana-test-validator --test solana_provider
+//! ```
+//!
+//! `SOLANA_TEST_VALIDATOR_URL` overrides the default `http://127.0.0.1:8899`.
+
+#![cfg(feature = "solana-test-validator")]
+
+use base64::Engine;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_commitment_config::CommitmentConfig;
+use solana_sdk::compute_budget::ComputeBudgetInstruction;
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::message::Message;
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use solana_sdk::program_pack::Pack;
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signature};
+use solana_sdk::signer::Signer;
+use solana_sdk::transaction::{Transaction, VersionedTransaction};
+use std::time::Duration;
+use x402_rs::chain::solana::SolanaProvider;
+use x402_rs::facilitator::Facilitator;
+use x402_rs::network::Network;
+use x402_rs::types::{MixedAddress, SettleRequest, TransactionHash, VerifyRequest, VerifyResponse};
+
+const ATA_PROGRAM_ID: Pubkey = pubkey!("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+const DECIMALS: u8 = 6;
+const PRICE: u64 = 10_000;
+
+struct Fixture {
+    rpc: RpcClient,
+    provider: SolanaProvider,
+    facilitator: Keypair,
+    client: Keypair,
+    merchant: Pubkey,
+    mint: Pubkey,
+}
+
+fn validator_url() -> String {
+    std::env::var("SOLANA_TEST_VALIDATOR_URL")
+        .unwrap_or_else(|_| "http://127.0.0.1:8899".to_string())
+}
+
+fn associated_token_address(owner: &Pubkey, mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[owner.as_ref(), spl_token::ID.as_ref(), mint.as_ref()],
+        &ATA_PROGRAM_ID,
+    )
+    .0
+}
+
+/// `CreateIdempotent` of the associated token account program.
+fn create_associated_token_account(payer: &Pubkey, owner: &Pubkey, mint: &Pubkey) -> Instruction {
+    Instruction::new_with_bytes(
+        ATA_PROGRAM_ID,
+        &[1],
+        vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new(associated_token_address(owner, mint), false),
+            AccountMeta::new_readonly(*owner, false),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new_readonly(solana_system_interface::program::ID, false),
+            AccountMeta::new_readonly(spl_token::ID, false),
+        ],
+    )
+}
+
+async fn airdrop(rpc: &RpcClient, to: &Pubkey) {
+    let signature = rpc.request_airdrop(to, LAMPORTS_PER_SOL).await.unwrap();
+    while !rpc.confirm_transaction(&signature).await.unwrap() {
+        tokio::time::sleep(Duration::from_millis(200)).await;
+    }
+}
+
+async fn send(
+    rpc: &RpcClient,
+    instructions: &[Instruction],
+    payer: &Keypair,
+    signers: &[&Keypair],
+) {
+    let blockhash = rpc.get_latest_blockhash().await.unwrap();
+    let tx =
+        Transaction::new_signed_with_payer(instructions, Some(&payer.pubkey()), signers, blockhash);
+    rpc.send_and_confirm_transaction(&tx).await.unwrap();
+}
+
+/// A fresh mint with 1 token in the client's account and an empty merchant account.
+async fn setup() -> Fixture {
+    let rpc = RpcClient::new_with_commitment(validator_url(), CommitmentConfig::confirmed());
+    let facilitator = Keypair::new();
+    let client = Keypair::new();
+    let merchant = Pubkey::new_unique();
+    let mint = Keypair::new();
+    airdrop(&rpc, &facilitator.pubkey()).await;
+    airdrop(&rpc, &client.pubkey()).await;
+
+    let rent = rpc
+        .get_minimum_balance_for_rent_exemption(spl_token::state::Mint::LEN)
+        .await
+        .unwrap();
+    let client_ata = associated_token_address(&client.pubkey(), &mint.pubkey());
+    send(
+        &rpc,
+        &[
+            solana_system_interface::instruction::create_account(
+                &client.pubkey(),
+                &mint.pubkey(),
+                rent,
+                spl_token::state::Mint::LEN as u64,
+                &spl_token::ID,
+            ),
+            spl_token::instruction::initialize_mint2(
+                &spl_token::ID,
+                &mint.pubkey(),
+                &client.pubkey(),
+                None,
+                DECIMALS,
+            )
+            .unwrap(),
+            create_associated_token_account(&client.pubkey(), &client.pubkey(), &mint.pubkey()),
+            create_associated_token_account(&client.pubkey(), &merchant, &mint.pubkey()),
+            spl_token::instruction::mint_to(
+                &spl_token::ID,
+                &mint.pubkey(),
+                &client_ata,
+                &client.pubkey(),
+                &[],
+                1_000_000,
+            )
+            .unwrap(),
+        ],
+        &client,
+        &[&client, &mint],
+    )
+    .await;
+
+    let provider = SolanaProvider::try_new(
+        facilitator.insecure_clone(),
+        validator_url(),
+        Network::SolanaDevnet,
+        200_000,
+        100_000,
+    )
+    .unwrap();
+    Fixture {
+        rpc,
+        provider,
+        facilitator,
+        client,
+        merchant,
+        mint: mint.pubkey(),
+    }
+}
+
+/// The client's half-signed payment, with the facilitator as fee payer.
+async fn payment(fixture: &Fixture, amount: u64) -> VersionedTransaction {
+    let instructions = [
+        ComputeBudgetInstruction::set_compute_unit_limit(50_000),
+        ComputeBudgetInstruction::set_compute_unit_price(1),
+        spl_token::instruction::transfer_checked(
+            &spl_token::ID,
+            &associated_token_address(&fixture.client.pubkey(), &fixture.mint),
+            &fixture.mint,
+            &associated_token_address(&fixture.merchant, &fixture.mint),
+            &fixture.client.pubkey(),
+            &[],
+            amount,
+            DECIMALS,
+        )
+        .unwrap(),
+    ];
+    let blockhash = fixture.rpc.get_latest_blockhash().await.unwrap();
+    let message = Message::new_with_blockhash(
+        &instructions,
+        Some(&fixture.facilitator.pubkey()),
+        &blockhash,
+    );
+    let mut tx = Transaction::new_unsigned(message);
+    tx.partial_sign(&[&fixture.client], blockhash);
+    VersionedTransaction::from(tx)
+}
+
+fn request(fixture: &Fixture, transaction: &VersionedTransaction) -> VerifyRequest {
+    let transaction =
+        base64::engine::general_purpose::STANDARD.encode(bincode::serialize(transaction).unwrap());
+    serde_json::from_value(serde_json::json!({
+        "x402Version": 1,
+        "paymentPayload": {
+            "x402Version": 1,
+            "scheme": "exact",
+            "network": "solana-devnet",
+            "payload": { "transaction": transaction }
+        },
+        "paymentRequirements": {
+            "scheme": "exact",
+            "network": "solana-devnet",
+            "maxAmountRequired": PRICE.to_string(),
+            "resource": "https://api.example.com/data",
+            "description": "",
+            "mimeType": "application/json",
+            "payTo": fixture.merchant.to_string(),
+            "maxTimeoutSeconds": 60,
+            "asset": fixture.mint.to_string()
+        }
+    }))
+    .unwrap()
+}
+
+#[tokio::test]
+async fn test_verify_and_settle_spl_transfer() {
+    let fixture = setup().await;
+    let request = request(&fixture, &payment(&fixture, PRICE).await);
+
+    let verified = fixture.provider.verify(&request).await.unwrap();
+    assert!(matches!(
+        verified,
+        VerifyResponse::Valid { payer: MixedAddress::Solana(payer) } if payer == fixture.client.pubkey()
+    ));
+
+    let settle: SettleRequest = request;
+    let settled = fixture.provider.settle(&settle).await.unwrap();
+    assert!(settled.success);
+    let Some(TransactionHash::Solana(signature)) = settled.transaction else {
+        panic!("expected a Solana signature, got {:?}", settled.transaction);
+    };
+    assert!(fixture
+        .rpc
+        .confirm_transaction(&Signature::from(signature))
+        .await
+        .unwrap());
+
+    let balance = fixture
+        .rpc
+        .get_token_account_balance(&associated_token_address(&fixture.merchant, &fixture.mint))
+        .await
+        .unwrap();
+    assert_eq!(balance.amount, PRICE.to_string());
+}
+
+#[tokio::test]
+async fn test_verify_rejects_bad_payments() {
+    let fixture = setup().await;
+
+    let mut tampered = payment(&fixture, PRICE).await;
+    tampered.signatures[1] = Signature::default();
+    assert!(fixture
+        .provider
+        .verify(&request(&fixture, &tampered))
+        .await
+        .is_err());
+
+    let underpaid = payment(&fixture, PRICE - 1).await;
+    assert!(fixture
+        .provider
+        .verify(&request(&fixture, &underpaid))
+        .await
+        .is_err());
+}
@@ -0,0 +1,97 @@
+//! `SqliteNonceStore` against a database file in a temporary directory.
+//!
+//! ```text
+//! cargo test --features sqlite --test sqlite_nonce_store
+//! ```
+
+#![cfg(feature = "sqlite")]
+
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use x402_rs::nonce_store::{NonceStore, NonceStoreError, SqliteNonceStore};
+
+fn database_path() -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("x402-nonces-{:x}", rand::random::<u64>()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir.join("nonces.db")
+}
+
+#[tokio::test]
+async fn test_check_and_mark_rejects_replays() {
+    let path = database_path();
+    let store = SqliteNonceStore::open(&path).await.expect("open");
+    store.health_check().await.unwrap();
+    assert_eq!(store.store_type(), "sqlite");
+
+    let key = "stellar#GABC123#12345";
+    assert!(!store.is_used(key).await.unwrap());
+    store.check_and_mark_used(key, 3600).await.unwrap();
+    assert!(store.is_used(key).await.unwrap());
+    assert!(matches!(
+        store.check_and_mark_used(key, 3600).await,
+        Err(NonceStoreError::NonceAlreadyUsed(_))
+    ));
+
+    // Marks survive reopening the file, and creating the table again is harmless
+    let reopened = SqliteNonceStore::open(&path).await.unwrap();
+    assert!(reopened.is_used(key).await.unwrap());
+    assert!(reopened.check_and_mark_used(key, 3600).await.is_err());
+}
+
+#[tokio::test]
+async fn test_expired_nonce_can_be_reused_and_purged() {
+    let store = SqliteNonceStore::open(database_path()).await.unwrap();
+    let key = "algorand#group#0000000000000000000000000000000000000000000000000000000000000000";
+
+    store.check_and_mark_used(key, 0).await.unwrap();
+    assert!(!store.is_used(key).await.unwrap());
+    store.check_and_mark_used(key, 3600).await.unwrap();
+    assert!(store.check_and_mark_used(key, 3600).await.is_err());
+
+    store
+        .check_and_mark_used("stellar#GDEF#1", 0)
+        .await
+        .unwrap();
+    // Purging deletes rows that expired before the current second
+    tokio::time::sleep(Duration::from_millis(1100)).await;
+    assert_eq!(store.purge_expired().await.unwrap(), 1);
+    assert!(store.is_used(key).await.unwrap());
+}
+
+#[tokio::test]
+async fn test_purge_task_removes_expired_nonces() {
+    let store = SqliteNonceStore::open(database_path()).await.unwrap();
+    store
+        .check_and_mark_used("stellar#GDEF#2", 0)
+        .await
+        .unwrap();
+    store
+        .check_and_mark_used("stellar#GDEF#3", 3600)
+        .await
+        .unwrap();
+
+    tokio::time::sleep(Duration::from_millis(1100)).await;
+    let task = store.spawn_purge_task(Duration::from_secs(3600));
+    // The first tick fires immediately
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    task.abort();
+    assert_eq!(store.purge_expired().await.unwrap(), 0);
+    assert!(store.is_used("stellar#GDEF#3").await.unwrap());
+}
+
+#[tokio::test]
+async fn test_concurrent_marks_admit_exactly_one() {
+    let store = Arc::new(SqliteNonceStore::open(database_path()).await.unwrap());
+
+    let attempts = (0..16).map(|_| {
+        let store = Arc::clone(&store);
+        tokio::spawn(async move { store.check_and_mark_used("stellar#GXYZ#7", 3600).await })
+    });
+    let results = futures::future::join_all(attempts).await;
+    let admitted = results
+        .into_iter()
+        .filter(|r| matches!(r, Ok(Ok(()))))
+        .count();
+    assert_eq!(admitted, 1);
+}